@@ -0,0 +1,362 @@
+//! Build script support for SQLx, the Rust SQL toolkit.
+//!
+//! The query macros (`sqlx::query!()` and friends) can build without a database by reading the
+//! data saved in `.sqlx` by `cargo sqlx prepare`, but nothing stops that data from going stale
+//! when queries change and the command isn't rerun. Calling [`prepare()`] from the build script
+//! of a crate using the macros does the same as `cargo sqlx prepare` on every build that has a
+//! database available, so the data in `.sqlx` always matches the queries in the crate:
+//!
+//! ```rust,no_run
+//! // build.rs
+//! fn main() -> std::io::Result<()> {
+//!     sqlx_build::prepare()
+//! }
+//! ```
+//!
+//! Cargo only allows build scripts to write to `OUT_DIR`, and `cargo publish` fails its
+//! verification build if the source directory of the package changes, so `.sqlx` is only written
+//! when `SQLX_BUILD_WRITE=true` is set in the environment of the build. Set it where the data
+//! should be updated, e.g. in the shell of a developer, but not in CI or when publishing. Without
+//! it, a warning is printed when the data is out of date.
+//!
+//! With `SQLX_OFFLINE=true`, or without `DATABASE_URL` set, nothing is done and the macros use
+//! the data already in `.sqlx`. To only check that the data is up to date instead, without
+//! changing it (e.g. in CI), use [`check()`], which fails the build if `cargo sqlx prepare` needs
+//! to be rerun.
+//!
+//! This runs a nested `cargo check` of the crate in a separate target directory inside `OUT_DIR`,
+//! with the same features enabled, so the `offline` feature of `sqlx` must be enabled for the
+//! macros to save their data. Use [`Prepare`] to pass extra arguments to it, such as
+//! `--all-targets` to also include the queries in tests.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use crate::query_data::{JsonObject, QueryData, QUERY_DIR};
+
+pub mod query_data;
+
+/// Must be set to write to `.sqlx`, as build scripts may otherwise only write to `OUT_DIR`.
+const WRITE_VAR: &str = "SQLX_BUILD_WRITE";
+
+/// Set for the nested `cargo check`, so the build script doesn't prepare again when it runs there.
+const PREPARING_VAR: &str = "SQLX_BUILD_PREPARING";
+
+/// Save the data of the queries in the crate to `.sqlx`, if a database is available and
+/// `SQLX_BUILD_WRITE=true` is set; see the [crate docs](crate).
+///
+/// Equivalent to `Prepare::new().run()`.
+pub fn prepare() -> io::Result<()> {
+    Prepare::new().run()
+}
+
+/// Check that the data of the queries in the crate saved in `.sqlx` is up to date, if a database
+/// is available.
+///
+/// Equivalent to `Prepare::new().check(true).run()`.
+pub fn check() -> io::Result<()> {
+    Prepare::new().check(true).run()
+}
+
+/// Options for preparing the data of the queries in a crate from its build script.
+#[derive(Debug, Clone, Default)]
+pub struct Prepare {
+    check: bool,
+    cargo_args: Vec<String>,
+}
+
+impl Prepare {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only check that `.sqlx` is up to date, and return an error if it isn't, instead of
+    /// writing to it. The default is `false`.
+    pub fn check(mut self, check: bool) -> Self {
+        self.check = check;
+        self
+    }
+
+    /// Pass an extra argument to the nested `cargo check`, e.g. `--all-targets`.
+    pub fn cargo_arg(mut self, arg: impl Into<String>) -> Self {
+        self.cargo_args.push(arg.into());
+        self
+    }
+
+    /// Run from a build script. Errors are returned for the build script to report.
+    pub fn run(self) -> io::Result<()> {
+        if env::var_os(PREPARING_VAR).is_some() {
+            return Ok(());
+        }
+
+        let manifest_dir = PathBuf::from(var("CARGO_MANIFEST_DIR")?);
+
+        // load `.env` the same way as the query macros do
+        let env_path = manifest_dir.join(".env");
+
+        let env_path = if env_path.exists() {
+            dotenv::from_path(&env_path).map_err(|e| {
+                other(format!(
+                    "failed to load environment from {}: {}",
+                    env_path.display(),
+                    e
+                ))
+            })?;
+
+            Some(env_path)
+        } else {
+            dotenv::dotenv().ok()
+        };
+
+        rerun_if_changed(&manifest_dir, env_path.as_deref());
+
+        let offline = is_enabled("SQLX_OFFLINE");
+
+        let query_dir = manifest_dir.join(QUERY_DIR);
+
+        let database_url = match env::var("DATABASE_URL") {
+            Ok(url) if !offline => url,
+            _ => {
+                if !query_dir.is_dir() {
+                    println!(
+                        "cargo:warning=`DATABASE_URL` is not set and `{}` does not exist; \
+                         the query macros need one or the other",
+                        QUERY_DIR
+                    );
+                }
+
+                return Ok(());
+            }
+        };
+
+        let data = run_prepare_step(&manifest_dir, &database_url, &self.cargo_args)?;
+
+        if data.is_empty() {
+            println!(
+                "cargo:warning=no queries found; please ensure that the `offline` feature is \
+                 enabled in sqlx"
+            );
+        }
+
+        let saved_data = if query_dir.is_dir() {
+            query_data::read(&query_dir)?
+        } else if self.check {
+            return Err(other(format!(
+                "`{}` does not exist; you may need to run `cargo sqlx prepare` first",
+                QUERY_DIR
+            )));
+        } else {
+            QueryData::new()
+        };
+
+        if data == saved_data {
+            return Ok(());
+        }
+
+        if self.check {
+            return Err(other("`cargo sqlx prepare` needs to be rerun"));
+        }
+
+        if !is_enabled(WRITE_VAR) {
+            println!(
+                "cargo:warning=`{}` is out of date; rerun `cargo sqlx prepare`, or set `{}=true` \
+                 to update it from the build script",
+                QUERY_DIR, WRITE_VAR
+            );
+
+            return Ok(());
+        }
+
+        query_data::write(&query_dir, &data)
+    }
+}
+
+/// Tell Cargo to run the build script again when the sources of the crate, or the environment
+/// read by the query macros, change.
+fn rerun_if_changed(manifest_dir: &Path, env_path: Option<&Path>) {
+    for dir in ["src", "tests", "examples", "benches", "migrations"] {
+        let path = manifest_dir.join(dir);
+
+        if path.exists() {
+            println!("cargo:rerun-if-changed={}", path.display());
+        }
+    }
+
+    println!(
+        "cargo:rerun-if-changed={}",
+        manifest_dir.join("Cargo.toml").display()
+    );
+
+    if let Some(env_path) = env_path {
+        println!("cargo:rerun-if-changed={}", env_path.display());
+    }
+
+    println!("cargo:rerun-if-env-changed=DATABASE_URL");
+    println!("cargo:rerun-if-env-changed=SQLX_OFFLINE");
+    println!("cargo:rerun-if-env-changed={}", WRITE_VAR);
+}
+
+fn is_enabled(name: &str) -> bool {
+    env::var(name)
+        .map(|s| s.eq_ignore_ascii_case("true") || s == "1")
+        .unwrap_or(false)
+}
+
+/// Check the crate in a separate target directory with `DATABASE_URL` set, and collect the data
+/// saved by the query macros.
+fn run_prepare_step(
+    manifest_dir: &Path,
+    url: &str,
+    cargo_args: &[String],
+) -> io::Result<QueryData> {
+    let cargo = var("CARGO")?;
+    let package = var("CARGO_PKG_NAME")?;
+    let manifest_path = manifest_dir.join("Cargo.toml");
+
+    // a target directory of our own, as Cargo holds the lock on the one of the outer build
+    let target_dir = PathBuf::from(var("OUT_DIR")?).join("sqlx-prepare");
+
+    let features = enabled_features(&cargo, &manifest_path, &package)?;
+
+    // try removing the target/sqlx directory before running, as stale files
+    // have repeatedly caused issues in the past.
+    let _ = fs::remove_dir_all(target_dir.join("sqlx"));
+
+    // only the crate itself is cleaned, to expand its query macros again
+    run_cargo(
+        Command::new(&cargo)
+            .args(["clean", "-p", &package, "--manifest-path"])
+            .arg(&manifest_path)
+            .env("CARGO_TARGET_DIR", &target_dir),
+        "clean",
+    )?;
+
+    run_cargo(
+        Command::new(&cargo)
+            .args(["check", "-p", &package, "--manifest-path"])
+            .arg(&manifest_path)
+            .args(["--no-default-features", "--features", &features.join(",")])
+            .args(cargo_args)
+            .env("SQLX_OFFLINE", "false")
+            .env("DATABASE_URL", url)
+            .env("CARGO_TARGET_DIR", &target_dir)
+            .env(PREPARING_VAR, "1"),
+        "check",
+    )?;
+
+    let mut data = QueryData::new();
+
+    let save_dir = target_dir.join("sqlx");
+
+    if !save_dir.is_dir() {
+        return Ok(data);
+    }
+
+    for (_, path) in query_data::query_files(&save_dir)? {
+        let query_data: JsonObject = serde_json::from_slice(&fs::read(&path)?)
+            .map_err(|e| other(format!("failed to parse {}: {}", path.display(), e)))?;
+
+        match query_data.get("hash") {
+            Some(serde_json::Value::String(hash)) => {
+                data.insert(hash.clone(), query_data);
+            }
+            hash => {
+                return Err(other(format!(
+                    "expected key `hash` in query data to be string, was {:?} instead; file: {}",
+                    hash,
+                    path.display()
+                )))
+            }
+        }
+    }
+
+    Ok(data)
+}
+
+/// List the features of `package` enabled for the current build, from the `CARGO_FEATURE_*`
+/// variables Cargo sets for build scripts.
+fn enabled_features(cargo: &str, manifest_path: &Path, package: &str) -> io::Result<Vec<String>> {
+    let output = run_cargo(
+        Command::new(cargo)
+            .args([
+                "metadata",
+                "--format-version=1",
+                "--no-deps",
+                "--manifest-path",
+            ])
+            .arg(manifest_path),
+        "metadata",
+    )?;
+
+    let metadata: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| other(format!("invalid `cargo metadata` output: {}", e)))?;
+
+    let package = metadata["packages"]
+        .as_array()
+        .and_then(|packages| {
+            packages.iter().find(|p| {
+                p["name"] == package
+                    && p["manifest_path"].as_str().map(Path::new) == Some(manifest_path)
+            })
+        })
+        .ok_or_else(|| other(format!("package {} not found in `cargo metadata`", package)))?;
+
+    // features implied by optional dependencies aren't always listed under `features`
+    let features = package["features"]
+        .as_object()
+        .into_iter()
+        .flat_map(|features| features.keys().map(String::as_str));
+
+    let dependencies = package["dependencies"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|dep| dep["optional"] == true)
+        .filter_map(|dep| dep["rename"].as_str().or_else(|| dep["name"].as_str()));
+
+    let mut enabled: Vec<String> = features
+        .chain(dependencies)
+        .filter(|feature| {
+            let var = format!("CARGO_FEATURE_{}", feature.to_uppercase().replace('-', "_"));
+
+            env::var_os(var).is_some()
+        })
+        .map(str::to_owned)
+        .collect();
+
+    enabled.sort();
+    enabled.dedup();
+
+    Ok(enabled)
+}
+
+fn run_cargo(command: &mut Command, name: &str) -> io::Result<Output> {
+    let output = command.output()?;
+
+    if !output.status.success() {
+        return Err(other(format!(
+            "`cargo {}` failed with status: {}\n{}",
+            name,
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    Ok(output)
+}
+
+fn var(name: &str) -> io::Result<String> {
+    env::var(name).map_err(|_| {
+        other(format!(
+            "`{}` must be set; `sqlx-build` may only be used from a build script",
+            name
+        ))
+    })
+}
+
+fn other(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, message.into())
+}
@@ -0,0 +1,123 @@
+//! The format of the query data saved in `.sqlx`, shared by `cargo sqlx prepare`, the query
+//! macros and [`prepare()`][crate::prepare()].
+//!
+//! The data of each query is saved in its own file, `query-<hash>.json`, where `<hash>` is the
+//! SHA-256 hash of the query in hex.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+/// Query data keyed by the hash of the query.
+pub type QueryData = BTreeMap<String, JsonObject>;
+pub type JsonObject = serde_json::Map<String, serde_json::Value>;
+
+/// The directory the query data is saved in by default, relative to the manifest directory of
+/// the crate.
+pub const QUERY_DIR: &str = ".sqlx";
+
+/// The path of the file holding the data of the query with the given hash in `dir`.
+pub fn query_file(dir: &Path, hash: &str) -> PathBuf {
+    dir.join(format!("query-{}.json", hash))
+}
+
+/// Save the data of each query to its own file in `dir`, removing the files of queries that
+/// aren't in `data`.
+pub fn write(dir: &Path, data: &QueryData) -> io::Result<()> {
+    fs::create_dir_all(dir).map_err(|e| with_path(e, "failed to create", dir))?;
+
+    for (hash, path) in query_files(dir)? {
+        if !data.contains_key(&hash) {
+            fs::remove_file(&path).map_err(|e| with_path(e, "failed to remove", &path))?;
+        }
+    }
+
+    for (hash, query_data) in data {
+        let path = query_file(dir, hash);
+
+        let file = File::create(&path).map_err(|e| with_path(e, "failed to create", &path))?;
+
+        serde_json::to_writer_pretty(BufWriter::new(file), query_data)
+            .map_err(|e| with_path(e.into(), "failed to write to", &path))?;
+    }
+
+    Ok(())
+}
+
+/// Read the data of all the queries saved in `dir`.
+pub fn read(dir: &Path) -> io::Result<QueryData> {
+    let mut data = QueryData::new();
+
+    for (hash, path) in query_files(dir)? {
+        let file = File::open(&path).map_err(|e| with_path(e, "failed to open", &path))?;
+
+        let query_data = serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| with_path(e.into(), "failed to parse", &path))?;
+
+        data.insert(hash, query_data);
+    }
+
+    Ok(data)
+}
+
+/// List the files of saved query data in `dir` with the hashes of their queries.
+pub fn query_files(dir: &Path) -> io::Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(dir).map_err(|e| with_path(e, "failed to read", dir))? {
+        let path = entry?.path();
+
+        let hash = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("query-"))
+            .and_then(|name| name.strip_suffix(".json"));
+
+        if let Some(hash) = hash {
+            files.push((hash.to_owned(), path));
+        }
+    }
+
+    Ok(files)
+}
+
+fn with_path(e: io::Error, action: &str, path: &Path) -> io::Error {
+    io::Error::new(e.kind(), format!("{} {}: {}", action, path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_data_roundtrips_and_prunes_stale_files() -> io::Result<()> {
+        let dir =
+            std::env::temp_dir().join(format!("sqlx-build-query-data-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let query_data = |query: &str| {
+            let mut data = JsonObject::new();
+            data.insert("query".into(), query.into());
+            data
+        };
+
+        let mut data = QueryData::new();
+        data.insert("a".into(), query_data("SELECT 1"));
+        data.insert("b".into(), query_data("SELECT 2"));
+        write(&dir, &data)?;
+
+        assert_eq!(read(&dir)?, data);
+
+        data.remove("a");
+        data.insert("c".into(), query_data("SELECT 3"));
+        write(&dir, &data)?;
+
+        assert_eq!(read(&dir)?, data);
+        assert!(!dir.join("query-a.json").exists());
+
+        fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+}
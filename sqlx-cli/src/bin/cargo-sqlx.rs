@@ -1,5 +1,4 @@
 use clap::Parser;
-use console::style;
 use dotenv::dotenv;
 use sqlx_cli::Opt;
 use std::process;
@@ -15,10 +14,9 @@ enum Cli {
 #[tokio::main]
 async fn main() {
     dotenv().ok();
-    let Cli::Sqlx(opt) = Cli::parse();
+    let Cli::Sqlx(opt) = Cli::try_parse().unwrap_or_else(|e| sqlx_cli::exit_with_usage(e));
 
     if let Err(error) = sqlx_cli::run(opt).await {
-        println!("{} {}", style("error:").bold().red(), error);
-        process::exit(1);
+        process::exit(sqlx_cli::report(&error));
     }
 }
@@ -1,5 +1,4 @@
 use clap::Parser;
-use console::style;
 use dotenv::dotenv;
 use sqlx_cli::Opt;
 
@@ -7,8 +6,9 @@ use sqlx_cli::Opt;
 async fn main() {
     dotenv().ok();
     // no special handling here
-    if let Err(error) = sqlx_cli::run(Opt::parse()).await {
-        println!("{} {}", style("error:").bold().red(), error);
-        std::process::exit(1);
+    let opt = Opt::try_parse().unwrap_or_else(|e| sqlx_cli::exit_with_usage(e));
+
+    if let Err(error) = sqlx_cli::run(opt).await {
+        std::process::exit(sqlx_cli::report(&error));
     }
 }
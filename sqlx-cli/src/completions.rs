@@ -0,0 +1,122 @@
+use std::io::{self, Write};
+use std::path::Path;
+
+use clap::CommandFactory;
+use clap_complete::Shell;
+use sqlx::migrate::Migrator;
+
+use crate::opt::Opt;
+
+pub fn generate(shell: Shell) -> anyhow::Result<()> {
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut Opt::command(), "sqlx", &mut script);
+
+    let mut script = String::from_utf8(script)?;
+
+    match shell {
+        Shell::Bash => script.push_str(BASH_MIGRATIONS),
+        Shell::Zsh => script = complete_migrations_zsh(&script),
+        Shell::Fish => script.push_str(FISH_MIGRATIONS),
+        _ => {}
+    }
+
+    io::stdout().write_all(script.as_bytes())?;
+
+    Ok(())
+}
+
+// zsh completes the value of an argument with the function named in its spec, so the specs of
+// the arguments taking versions are given `_sqlx_migrations`, defined before completing
+fn complete_migrations_zsh(script: &str) -> String {
+    let mut completed = String::with_capacity(script.len() + ZSH_MIGRATIONS.len());
+
+    for line in script.lines() {
+        let line = match line.rfind(":'") {
+            Some(end) if line.starts_with("':version -- ") => {
+                format!("{}:_sqlx_migrations{}", &line[..end], &line[end + 1..])
+            }
+            _ => line.replace("VERSION: '", "VERSION:_sqlx_migrations'"),
+        };
+
+        if line == "_sqlx \"$@\"" {
+            completed.push_str(ZSH_MIGRATIONS);
+        }
+
+        completed.push_str(&line);
+        completed.push('\n');
+    }
+
+    completed
+}
+
+// printed for shells to complete, so nothing is printed if the migrations can't be read
+pub async fn list_migrations(source: &str) {
+    if let Ok(migrator) = Migrator::new(Path::new(source)).await {
+        for migration in migrator.iter() {
+            if !migration.migration_type.is_down_migration() {
+                println!("{}\t{}", migration.version, migration.description);
+            }
+        }
+    }
+}
+
+const BASH_MIGRATIONS: &str = r#"
+# complete the versions of migrations, read from the migrations (or seeds) directory
+_sqlx_migrations() {
+    local i cur prev source="migrations"
+    cur="${COMP_WORDS[COMP_CWORD]}"
+    prev="${COMP_WORDS[COMP_CWORD-1]}"
+
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${COMP_WORDS[i]}" in
+            seed) source="seeds" ;;
+            --source) source="${COMP_WORDS[i+1]}" ;;
+        esac
+    done
+
+    case "${prev}" in
+        --target-version|--allow-checksum-mismatch|baseline|squash)
+            COMPREPLY=($(compgen -W "$(sqlx complete-migrations --source "${source}" | cut -f1)" -- "${cur}"))
+            ;;
+        *)
+            _sqlx "$@"
+            ;;
+    esac
+}
+
+complete -F _sqlx_migrations -o bashdefault -o default sqlx
+"#;
+
+const ZSH_MIGRATIONS: &str = r#"(( $+functions[_sqlx_migrations] )) ||
+_sqlx_migrations() {
+    local line source=migrations
+    local -a migrations
+
+    (( ${words[(I)seed]} )) && source=seeds
+    (( ${words[(I)--source]} )) && source=${words[${words[(I)--source]} + 1]}
+
+    for line in ${(f)"$(sqlx complete-migrations --source "$source")"}; do
+        migrations+=("${line%%$'\t'*}:${line#*$'\t'}")
+    done
+
+    _describe -t migrations 'migration' migrations
+}
+
+"#;
+
+const FISH_MIGRATIONS: &str = r#"
+# complete the versions of migrations, read from the migrations (or seeds) directory
+function __sqlx_migrations
+    set -l tokens (commandline -opc)
+    set -l source migrations
+
+    contains -- seed $tokens; and set source seeds
+    set -l i (contains -i -- --source $tokens); and set source $tokens[(math $i + 1)]
+
+    sqlx complete-migrations --source $source
+end
+
+complete -c sqlx -n "__fish_seen_subcommand_from run revert" -l target-version -x -a "(__sqlx_migrations)"
+complete -c sqlx -n "__fish_seen_subcommand_from run" -l allow-checksum-mismatch -x -a "(__sqlx_migrations)"
+complete -c sqlx -n "__fish_seen_subcommand_from baseline squash" -x -a "(__sqlx_migrations)"
+"#;
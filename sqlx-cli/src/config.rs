@@ -0,0 +1,130 @@
+//! The configuration of the project, from the closest `sqlx.toml` to the current directory, which
+//! provides the defaults of some arguments, and of the environment selected with `--env` or
+//! `SQLX_ENV`.
+
+use std::env;
+use std::path::Path;
+
+use once_cell::sync::Lazy;
+use sqlx::config::Config;
+use sqlx::migrate::{DEFAULT_SEEDS_TABLE_NAME, DEFAULT_TABLE_NAME};
+
+use crate::{error, opt};
+
+static CONFIG: Lazy<Config> = Lazy::new(|| {
+    let current_dir = env::current_dir().unwrap_or_default();
+
+    Config::find(&current_dir)
+        .map(Option::unwrap_or_default)
+        .unwrap_or_else(|e| exit_with(e))
+});
+
+/// The name of the environment selected with `--env`, or `SQLX_ENV`.
+///
+/// The default of `--database-url` depends on it, so it's read before the arguments are parsed.
+static ENVIRONMENT: Lazy<Option<String>> = Lazy::new(|| {
+    opt::global_arg("--env").or_else(|| env::var("SQLX_ENV").ok().filter(|name| !name.is_empty()))
+});
+
+static DATABASE_URL_VAR: Lazy<&str> = Lazy::new(|| match &*ENVIRONMENT {
+    Some(name) => CONFIG
+        .env_database_url_var(name)
+        .unwrap_or_else(|e| exit_with(e)),
+    None => CONFIG.database_url_var(),
+});
+
+static MIGRATIONS_DIR: Lazy<String> =
+    Lazy::new(|| dir_or(CONFIG.migrate.dir.as_deref(), "migrations"));
+
+static SEEDS_DIR: Lazy<String> = Lazy::new(|| dir_or(CONFIG.seed.dir.as_deref(), "seeds"));
+
+pub fn config() -> &'static Config {
+    &CONFIG
+}
+
+/// The environment variable holding the database URL, of the selected environment if any.
+pub fn database_url_var() -> &'static str {
+    &DATABASE_URL_VAR
+}
+
+/// The host to tunnel connections through with SSH, of the selected environment if any.
+pub fn ssh() -> Option<&'static str> {
+    let env_ssh = ENVIRONMENT.as_deref().and_then(|name| {
+        CONFIG
+            .env(name)
+            .unwrap_or_else(|e| exit_with(e))
+            .ssh
+            .as_deref()
+    });
+
+    env_ssh.or(CONFIG.ssh.as_deref())
+}
+
+/// The name of the selected environment, if it's protected.
+pub fn protected_env() -> Option<&'static str> {
+    let name = ENVIRONMENT.as_deref()?;
+
+    CONFIG
+        .env(name)
+        .unwrap_or_else(|e| exit_with(e))
+        .protected
+        .then(|| name)
+}
+
+/// The default directory of migrations.
+pub fn migrations_dir() -> &'static str {
+    &MIGRATIONS_DIR
+}
+
+/// The default table recording applied migrations.
+pub fn migrations_table() -> &'static str {
+    CONFIG
+        .migrate
+        .table_name
+        .as_deref()
+        .unwrap_or(DEFAULT_TABLE_NAME)
+}
+
+/// The default directory of seed migrations.
+pub fn seeds_dir() -> &'static str {
+    &SEEDS_DIR
+}
+
+/// The default table recording applied seed migrations.
+pub fn seeds_table() -> &'static str {
+    CONFIG
+        .seed
+        .table_name
+        .as_deref()
+        .unwrap_or(DEFAULT_SEEDS_TABLE_NAME)
+}
+
+/// Whether `table` is one SQLx records migrations or seed migrations in, rather than part of the
+/// schema.
+pub fn is_bookkeeping_table(table: &str) -> bool {
+    table.starts_with("_sqlx_")
+        || [migrations_table(), seeds_table()]
+            .iter()
+            .any(|name| name.rsplit('.').next() == Some(table))
+}
+
+/// The directory set in the configuration, relative to the current directory if it's within it,
+/// or `default`.
+fn dir_or(dir: Option<&Path>, default: &str) -> String {
+    let dir = match dir {
+        Some(dir) => CONFIG.resolve(dir),
+        None => return default.to_owned(),
+    };
+
+    let current_dir = env::current_dir().unwrap_or_default();
+
+    dir.strip_prefix(&current_dir)
+        .unwrap_or(&dir)
+        .to_string_lossy()
+        .into_owned()
+}
+
+// the configuration is needed to parse the arguments, so there's no error to return yet
+fn exit_with(error: sqlx::Error) -> ! {
+    std::process::exit(error::report(&error.into()));
+}
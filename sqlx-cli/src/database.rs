@@ -1,11 +1,15 @@
-use crate::migrate;
 use crate::opt::ConnectOpts;
+use crate::{config, migrate};
+use anyhow::bail;
 use console::style;
 use promptly::{prompt, ReadlineError};
 use sqlx::any::Any;
-use sqlx::migrate::MigrateDatabase;
+use sqlx::migrate::{CreateDatabaseOptions, MigrateDatabase, MigrationLock, OutOfOrder};
 
-pub async fn create(connect_opts: &ConnectOpts) -> anyhow::Result<()> {
+pub async fn create(
+    connect_opts: &ConnectOpts,
+    options: &CreateDatabaseOptions,
+) -> anyhow::Result<()> {
     // NOTE: only retry the idempotent action.
     // We're assuming that if this succeeds, then any following operations should also succeed.
     let exists = crate::retry_connect_errors(connect_opts, Any::database_exists).await?;
@@ -17,14 +21,19 @@ pub async fn create(connect_opts: &ConnectOpts) -> anyhow::Result<()> {
             std::sync::atomic::Ordering::Release,
         );
 
-        Any::create_database(&connect_opts.database_url).await?;
+        Any::create_database_with(&connect_opts.database_url, options).await?;
     }
 
     Ok(())
 }
 
 pub async fn drop(connect_opts: &ConnectOpts, confirm: bool) -> anyhow::Result<()> {
-    if confirm && !ask_to_continue(connect_opts) {
+    if confirm
+        && !confirm_destructive(format!(
+            "Drop database at {}?",
+            style(&connect_opts.database_url).cyan()
+        ))?
+    {
         return Ok(());
     }
 
@@ -42,23 +51,71 @@ pub async fn drop(connect_opts: &ConnectOpts, confirm: bool) -> anyhow::Result<(
 pub async fn reset(
     migration_source: &str,
     connect_opts: &ConnectOpts,
+    table_name: &str,
+    options: &CreateDatabaseOptions,
     confirm: bool,
 ) -> anyhow::Result<()> {
     drop(connect_opts, confirm).await?;
-    setup(migration_source, connect_opts).await
+    setup(migration_source, connect_opts, table_name, options).await
 }
 
-pub async fn setup(migration_source: &str, connect_opts: &ConnectOpts) -> anyhow::Result<()> {
-    create(connect_opts).await?;
-    migrate::run(migration_source, connect_opts, false, false).await
+pub async fn setup(
+    migration_source: &str,
+    connect_opts: &ConnectOpts,
+    table_name: &str,
+    options: &CreateDatabaseOptions,
+) -> anyhow::Result<()> {
+    create(connect_opts, options).await?;
+    migrate::run(
+        migration_source,
+        connect_opts,
+        table_name,
+        Some(MigrationLock::default()),
+        false,
+        false,
+        None,
+        &[],
+        OutOfOrder::default(),
+        None,
+    )
+    .await
 }
 
-fn ask_to_continue(connect_opts: &ConnectOpts) -> bool {
+/// Ask `question` before a destructive action. In a protected environment, the question names it,
+/// and there must be a terminal to ask on rather than going on without confirmation.
+pub(crate) fn confirm_destructive(question: String) -> anyhow::Result<bool> {
+    let env = match config::protected_env() {
+        Some(env) => env,
+        None => return Ok(ask_to_continue(question)),
+    };
+
+    if !console::user_attended() {
+        bail!(
+            "the environment `{}` is protected: pass --yes to confirm when not running interactively",
+            env
+        );
+    }
+
+    Ok(ask_to_continue(format!(
+        "{} ({} is a protected environment)",
+        question,
+        style(env).yellow()
+    )))
+}
+
+/// Ask `question` before a destructive action which is only confirmed in a protected environment,
+/// unless `confirm` is false.
+pub(crate) fn confirm_if_protected(question: String, confirm: bool) -> anyhow::Result<bool> {
+    if !confirm || config::protected_env().is_none() {
+        return Ok(true);
+    }
+
+    confirm_destructive(question)
+}
+
+pub(crate) fn ask_to_continue(question: String) -> bool {
     loop {
-        let r: Result<String, ReadlineError> = prompt(format!(
-            "Drop database at {}? (y/n)",
-            style(&connect_opts.database_url).cyan()
-        ));
+        let r: Result<String, ReadlineError> = prompt(format!("{} (y/n)", question));
         match r {
             Ok(response) => {
                 if response == "n" || response == "N" {
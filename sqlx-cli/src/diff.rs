@@ -0,0 +1,778 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{bail, Context};
+use console::style;
+use sqlx::any::{Any, AnyConnectionKind, AnyKind};
+use sqlx::migrate::{MigrateDatabase, Migrator};
+use sqlx::{AnyConnection, Connection};
+use url::Url;
+
+use crate::opt::ConnectOpts;
+use crate::{config, migrate};
+
+/// The schema of a database, as far as it is compared.
+#[derive(Debug, Default)]
+struct Schema {
+    /// The columns of each table, in order.
+    tables: BTreeMap<String, Vec<Column>>,
+    /// The statements creating the tables, with their constraints, if the database keeps them.
+    table_sql: BTreeMap<String, String>,
+    /// Indexes, constraints, views and triggers, keyed by their kind, table and name.
+    objects: BTreeMap<(&'static str, String, String), Object>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Column {
+    name: String,
+    type_name: String,
+    not_null: bool,
+    default: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Object {
+    /// The definition compared, with whitespace collapsed.
+    definition: String,
+    /// The statement creating the object, if it can be created on its own.
+    create: Option<String>,
+    /// The statement dropping the object, if it can be dropped on its own.
+    drop: Option<String>,
+}
+
+impl Object {
+    fn new(definition: &str, create: Option<String>, drop: Option<String>) -> Self {
+        Object {
+            definition: definition.split_whitespace().collect::<Vec<_>>().join(" "),
+            create,
+            drop,
+        }
+    }
+}
+
+/// How to quote identifiers in the statements of a migration.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Quote {
+    Double,
+    #[cfg_attr(not(feature = "mysql"), allow(dead_code))]
+    Backtick,
+}
+
+impl Quote {
+    pub(crate) fn of(kind: AnyKind) -> Self {
+        match kind {
+            #[cfg(feature = "mysql")]
+            AnyKind::MySql => Quote::Backtick,
+            #[allow(unreachable_patterns)]
+            _ => Quote::Double,
+        }
+    }
+
+    pub(crate) fn ident(self, ident: &str) -> String {
+        match self {
+            Quote::Double => format!("\"{}\"", ident.replace('"', "\"\"")),
+            Quote::Backtick => format!("`{}`", ident.replace('`', "``")),
+        }
+    }
+}
+
+impl Column {
+    fn definition(&self, quote: Quote) -> String {
+        let mut definition = format!("{} {}", quote.ident(&self.name), self.type_name);
+
+        if self.not_null {
+            definition += " NOT NULL";
+        }
+
+        if let Some(default) = &self.default {
+            definition += &format!(" DEFAULT {}", default);
+        }
+
+        definition
+    }
+}
+
+/// Compare the schema of `from`, or the one produced by the migrations of `migration_source` if
+/// not given, with that of the database, printing the changes from the former to the latter.
+///
+/// With `migration`, these changes are written to a new migration of that description, e.g. to
+/// capture changes made by hand to a development database; otherwise finding differences is an
+/// error, e.g. for CI to check that the schema of an environment didn't drift.
+pub async fn diff(
+    connect_opts: &ConnectOpts,
+    from: Option<&str>,
+    migration_source: &str,
+    migration: Option<&str>,
+) -> anyhow::Result<()> {
+    let url = &connect_opts.database_url;
+    let kind = AnyKind::from_str(url)?;
+
+    let mut conn = crate::connect(connect_opts).await?;
+    let to_schema = introspect(&mut conn).await?;
+    conn.close().await?;
+
+    let (from_schema, from_label) = match from {
+        Some(from) => {
+            if AnyKind::from_str(from)? != kind {
+                bail!("cannot compare databases of different kinds");
+            }
+
+            let mut conn = AnyConnection::connect(from).await?;
+            let schema = introspect(&mut conn).await?;
+            conn.close().await?;
+
+            (schema, from.to_owned())
+        }
+        None => (
+            migrated_schema(url, kind, migration_source).await?,
+            format!("the migrations in `{}`", migration_source),
+        ),
+    };
+
+    let quote = Quote::of(kind);
+
+    // the statements of the migration, with the order to run them in: objects are dropped before
+    // the tables are changed, and created after
+    let mut statements: Vec<(u8, String)> = Vec::new();
+    let mut differences = 0;
+
+    let mut difference = |line: String, changes: Vec<(u8, String)>| {
+        println!("{}", line);
+        statements.extend(changes);
+        differences += 1;
+    };
+
+    let table_names = from_schema.tables.keys().chain(to_schema.tables.keys());
+
+    for table in table_names.collect::<std::collections::BTreeSet<_>>() {
+        let ident = quote.ident(table);
+
+        match (from_schema.tables.get(table), to_schema.tables.get(table)) {
+            (None, Some(columns)) => {
+                let create = match to_schema.table_sql.get(table) {
+                    Some(sql) => format!("{};\n\n", sql),
+                    None => {
+                        let columns = columns
+                            .iter()
+                            .map(|column| format!("    {}", column.definition(quote)))
+                            .collect::<Vec<_>>()
+                            .join(",\n");
+
+                        format!("CREATE TABLE {} (\n{}\n);\n\n", ident, columns)
+                    }
+                };
+
+                difference(
+                    format!("{} table `{}`", style("+").green(), table),
+                    vec![(1, create)],
+                );
+            }
+
+            (Some(_), None) => difference(
+                format!("{} table `{}`", style("-").red(), table),
+                vec![(1, format!("DROP TABLE {};\n\n", ident))],
+            ),
+
+            (Some(from_columns), Some(to_columns)) => {
+                for column in to_columns {
+                    match from_columns.iter().find(|c| c.name == column.name) {
+                        None => difference(
+                            format!(
+                                "{} column `{}.{}` {}",
+                                style("+").green(),
+                                table,
+                                column.name,
+                                column.definition(quote)
+                            ),
+                            vec![(
+                                1,
+                                format!(
+                                    "ALTER TABLE {} ADD COLUMN {};\n\n",
+                                    ident,
+                                    column.definition(quote)
+                                ),
+                            )],
+                        ),
+
+                        Some(from_column) if from_column != column => difference(
+                            format!(
+                                "{} column `{}.{}`: {} -> {}",
+                                style("~").yellow(),
+                                table,
+                                column.name,
+                                from_column.definition(quote),
+                                column.definition(quote)
+                            ),
+                            vec![(
+                                1,
+                                format!(
+                                    "-- TODO: change column {} of {} from `{}` to `{}`\n\n",
+                                    quote.ident(&column.name),
+                                    ident,
+                                    from_column.definition(quote),
+                                    column.definition(quote)
+                                ),
+                            )],
+                        ),
+
+                        Some(_) => {}
+                    }
+                }
+
+                for column in from_columns {
+                    if !to_columns.iter().any(|c| c.name == column.name) {
+                        difference(
+                            format!("{} column `{}.{}`", style("-").red(), table, column.name),
+                            vec![(
+                                1,
+                                format!(
+                                    "ALTER TABLE {} DROP COLUMN {};\n\n",
+                                    ident,
+                                    quote.ident(&column.name)
+                                ),
+                            )],
+                        );
+                    }
+                }
+            }
+
+            (None, None) => {}
+        }
+    }
+
+    let todo = |action: &str, label: &str, statement: &Option<String>| match statement {
+        Some(statement) => format!("{};\n\n", statement),
+        None => format!("-- TODO: {} {}\n\n", action, label),
+    };
+
+    for (key @ (kind, table, name), from_object) in &from_schema.objects {
+        // dropped along with their table
+        if !table.is_empty() && !to_schema.tables.contains_key(table) {
+            continue;
+        }
+
+        let label = format!("{} `{}`", kind, qualified(table, name));
+
+        match to_schema.objects.get(key) {
+            None => difference(
+                format!("{} {}", style("-").red(), label),
+                vec![(0, todo("drop", &label, &from_object.drop))],
+            ),
+
+            Some(to_object) if to_object.definition != from_object.definition => difference(
+                format!("{} {}", style("~").yellow(), label),
+                vec![
+                    (0, todo("drop", &label, &from_object.drop)),
+                    (2, todo("create", &label, &to_object.create)),
+                ],
+            ),
+
+            Some(_) => {}
+        }
+    }
+
+    for (key @ (kind, table, name), to_object) in &to_schema.objects {
+        // created along with their table, if it's created by its own statement
+        let created_with_table = to_schema.table_sql.contains_key(table)
+            && !from_schema.tables.contains_key(table)
+            && *kind == "constraint";
+
+        if !from_schema.objects.contains_key(key) && !created_with_table {
+            let label = format!("{} `{}`", kind, qualified(table, name));
+
+            difference(
+                format!("{} {}", style("+").green(), label),
+                vec![(2, todo("create", &label, &to_object.create))],
+            );
+        }
+    }
+
+    if differences == 0 {
+        println!("No differences from {}", from_label);
+        return Ok(());
+    }
+
+    match migration {
+        Some(description) => {
+            // the sort is stable, keeping the statements of each step in order
+            statements.sort_by_key(|(order, _)| *order);
+            let sql: String = statements.into_iter().map(|(_, sql)| sql).collect();
+
+            migrate::create_with_sql(migration_source, description, sql.trim_end()).await
+        }
+        None => bail!(
+            "the schema of the database differs from {} in {} place(s)",
+            from_label,
+            differences
+        ),
+    }
+}
+
+fn qualified(table: &str, name: &str) -> String {
+    match table {
+        "" => name.to_owned(),
+        table => format!("{}.{}", table, name),
+    }
+}
+
+/// The schema produced by applying the migrations to a scratch database, next to the database at
+/// `url` for Postgres and MySQL, and in memory for SQLite.
+async fn migrated_schema(
+    url: &str,
+    kind: AnyKind,
+    migration_source: &str,
+) -> anyhow::Result<Schema> {
+    let migrator = Migrator::new(Path::new(migration_source)).await?;
+
+    let scratch_url = match kind {
+        #[cfg(feature = "sqlite")]
+        AnyKind::Sqlite => "sqlite::memory:".to_owned(),
+
+        #[allow(unreachable_patterns)]
+        _ => {
+            let mut scratch_url = Url::parse(url).context("Invalid database URL")?;
+            let database = scratch_url.path().trim_start_matches('/').to_owned();
+            scratch_url.set_path(&format!("{}_sqlx_diff_{}", database, std::process::id()));
+            scratch_url.to_string()
+        }
+    };
+
+    let is_sqlite = scratch_url.starts_with("sqlite:");
+
+    if !is_sqlite {
+        Any::create_database(&scratch_url).await?;
+    }
+
+    let result: anyhow::Result<_> = async {
+        let mut conn = AnyConnection::connect(&scratch_url).await?;
+        migrator.run(&mut conn).await?;
+        let schema = introspect(&mut conn).await;
+        conn.close().await?;
+        schema
+    }
+    .await;
+
+    if !is_sqlite {
+        Any::drop_database(&scratch_url).await?;
+    }
+
+    result
+}
+
+async fn introspect(conn: &mut AnyConnection) -> anyhow::Result<Schema> {
+    let mut schema = match conn.private_get_mut() {
+        #[cfg(feature = "postgres")]
+        AnyConnectionKind::Postgres(conn) => postgres::introspect(conn).await?,
+
+        #[cfg(feature = "mysql")]
+        AnyConnectionKind::MySql(conn) => mysql::introspect(conn).await?,
+
+        #[cfg(feature = "sqlite")]
+        AnyConnectionKind::Sqlite(conn) => sqlite::introspect(conn).await?,
+
+        #[allow(unreachable_patterns)]
+        _ => bail!("comparing the schema of this database is not supported"),
+    };
+
+    let bookkeeping = config::is_bookkeeping_table;
+
+    schema.tables.retain(|table, _| !bookkeeping(table));
+    schema.table_sql.retain(|table, _| !bookkeeping(table));
+    schema
+        .objects
+        .retain(|(_, table, name), _| !bookkeeping(table) && !bookkeeping(name));
+
+    Ok(schema)
+}
+
+#[cfg(feature = "postgres")]
+mod postgres {
+    use sqlx::postgres::PgConnection;
+
+    use super::{Column, Object, Quote, Schema};
+
+    pub(super) async fn introspect(conn: &mut PgConnection) -> anyhow::Result<Schema> {
+        let mut schema = Schema::default();
+        let quote = Quote::Double;
+
+        // language=PostgreSQL
+        let columns: Vec<(String, String, String, bool, Option<String>)> = sqlx::query_as(
+            r#"
+SELECT c.relname::text, a.attname::text, format_type(a.atttypid, a.atttypmod), a.attnotnull,
+       pg_get_expr(d.adbin, d.adrelid)
+FROM pg_class c
+JOIN pg_namespace n ON n.oid = c.relnamespace
+JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped
+LEFT JOIN pg_attrdef d ON d.adrelid = c.oid AND d.adnum = a.attnum
+WHERE n.nspname = current_schema() AND c.relkind IN ('r', 'p')
+ORDER BY c.relname, a.attnum
+            "#,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for (table, name, type_name, not_null, default) in columns {
+            schema.tables.entry(table).or_default().push(Column {
+                name,
+                type_name,
+                not_null,
+                default,
+            });
+        }
+
+        // language=PostgreSQL
+        let constraints: Vec<(String, String, String)> = sqlx::query_as(
+            r#"
+SELECT c.relname::text, con.conname::text, pg_get_constraintdef(con.oid)
+FROM pg_constraint con
+JOIN pg_class c ON c.oid = con.conrelid
+JOIN pg_namespace n ON n.oid = c.relnamespace
+WHERE n.nspname = current_schema()
+            "#,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for (table, name, definition) in constraints {
+            let (table_ident, ident) = (quote.ident(&table), quote.ident(&name));
+
+            let object = Object::new(
+                &definition,
+                Some(format!(
+                    "ALTER TABLE {} ADD CONSTRAINT {} {}",
+                    table_ident, ident, definition
+                )),
+                Some(format!(
+                    "ALTER TABLE {} DROP CONSTRAINT {}",
+                    table_ident, ident
+                )),
+            );
+
+            schema.objects.insert(("constraint", table, name), object);
+        }
+
+        // the indexes of constraints are created with them
+        // language=PostgreSQL
+        let indexes: Vec<(String, String, String)> = sqlx::query_as(
+            r#"
+SELECT t.relname::text, i.relname::text, pg_get_indexdef(i.oid)
+FROM pg_index x
+JOIN pg_class i ON i.oid = x.indexrelid
+JOIN pg_class t ON t.oid = x.indrelid
+JOIN pg_namespace n ON n.oid = t.relnamespace
+WHERE n.nspname = current_schema()
+  AND NOT EXISTS (
+    SELECT 1 FROM pg_constraint con WHERE con.conindid = i.oid AND con.contype IN ('p', 'u', 'x')
+  )
+            "#,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for (table, name, definition) in indexes {
+            let drop = format!("DROP INDEX {}", quote.ident(&name));
+            let object = Object::new(&definition, Some(definition.clone()), Some(drop));
+
+            schema.objects.insert(("index", table, name), object);
+        }
+
+        // language=PostgreSQL
+        let views: Vec<(String, String)> = sqlx::query_as(
+            "SELECT viewname::text, definition FROM pg_views WHERE schemaname = current_schema()",
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for (name, definition) in views {
+            let ident = quote.ident(&name);
+            let definition = definition.trim().trim_end_matches(';');
+
+            let object = Object::new(
+                definition,
+                Some(format!("CREATE VIEW {} AS {}", ident, definition)),
+                Some(format!("DROP VIEW {}", ident)),
+            );
+
+            schema.objects.insert(("view", String::new(), name), object);
+        }
+
+        Ok(schema)
+    }
+}
+
+#[cfg(feature = "mysql")]
+mod mysql {
+    use sqlx::mysql::MySqlConnection;
+
+    use super::{Column, Object, Quote, Schema};
+
+    pub(super) async fn introspect(conn: &mut MySqlConnection) -> anyhow::Result<Schema> {
+        let mut schema = Schema::default();
+        let quote = Quote::Backtick;
+
+        // language=MySQL
+        let columns: Vec<(String, String, String, i64, Option<String>)> = sqlx::query_as(
+            r#"
+SELECT CAST(c.table_name AS CHAR), CAST(c.column_name AS CHAR), CAST(c.column_type AS CHAR),
+       CAST(c.is_nullable = 'NO' AS SIGNED), CAST(c.column_default AS CHAR)
+FROM information_schema.columns c
+JOIN information_schema.tables t
+  ON t.table_schema = c.table_schema AND t.table_name = c.table_name
+WHERE c.table_schema = DATABASE() AND t.table_type = 'BASE TABLE'
+ORDER BY c.table_name, c.ordinal_position
+            "#,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for (table, name, type_name, not_null, default) in columns {
+            schema.tables.entry(table).or_default().push(Column {
+                name,
+                type_name,
+                not_null: not_null != 0,
+                default,
+            });
+        }
+
+        // language=MySQL
+        let indexes: Vec<(String, String, i64, String)> = sqlx::query_as(
+            r#"
+SELECT CAST(table_name AS CHAR), CAST(index_name AS CHAR), CAST(MAX(non_unique) AS SIGNED),
+       CAST(GROUP_CONCAT(CONCAT('`', column_name, '`') ORDER BY seq_in_index SEPARATOR ', ')
+            AS CHAR)
+FROM information_schema.statistics
+WHERE table_schema = DATABASE()
+GROUP BY table_name, index_name
+            "#,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for (table, name, non_unique, columns) in indexes {
+            let (table_ident, ident) = (quote.ident(&table), quote.ident(&name));
+
+            let object = if name == "PRIMARY" {
+                Object::new(
+                    &format!("PRIMARY KEY ({})", columns),
+                    Some(format!(
+                        "ALTER TABLE {} ADD PRIMARY KEY ({})",
+                        table_ident, columns
+                    )),
+                    Some(format!("ALTER TABLE {} DROP PRIMARY KEY", table_ident)),
+                )
+            } else {
+                let unique = if non_unique == 0 { "UNIQUE " } else { "" };
+
+                Object::new(
+                    &format!("{}INDEX ({})", unique, columns),
+                    Some(format!(
+                        "CREATE {}INDEX {} ON {} ({})",
+                        unique, ident, table_ident, columns
+                    )),
+                    Some(format!("DROP INDEX {} ON {}", ident, table_ident)),
+                )
+            };
+
+            schema.objects.insert(("index", table, name), object);
+        }
+
+        // language=MySQL
+        let foreign_keys: Vec<(String, String, String)> = sqlx::query_as(
+            r#"
+SELECT CAST(table_name AS CHAR), CAST(constraint_name AS CHAR),
+       CAST(CONCAT(
+         'FOREIGN KEY (',
+         GROUP_CONCAT(CONCAT('`', column_name, '`') ORDER BY ordinal_position SEPARATOR ', '),
+         ') REFERENCES `', referenced_table_name, '` (',
+         GROUP_CONCAT(CONCAT('`', referenced_column_name, '`')
+                      ORDER BY ordinal_position SEPARATOR ', '),
+         ')'
+       ) AS CHAR)
+FROM information_schema.key_column_usage
+WHERE table_schema = DATABASE() AND referenced_table_name IS NOT NULL
+GROUP BY table_name, constraint_name, referenced_table_name
+            "#,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for (table, name, definition) in foreign_keys {
+            let (table_ident, ident) = (quote.ident(&table), quote.ident(&name));
+
+            let object = Object::new(
+                &definition,
+                Some(format!(
+                    "ALTER TABLE {} ADD CONSTRAINT {} {}",
+                    table_ident, ident, definition
+                )),
+                Some(format!(
+                    "ALTER TABLE {} DROP FOREIGN KEY {}",
+                    table_ident, ident
+                )),
+            );
+
+            schema.objects.insert(("constraint", table, name), object);
+        }
+
+        // the definitions of views qualify tables with the name of the database
+        // language=MySQL
+        let views: Vec<(String, String)> = sqlx::query_as(
+            r#"
+SELECT CAST(table_name AS CHAR),
+       CAST(REPLACE(view_definition, CONCAT('`', DATABASE(), '`.'), '') AS CHAR)
+FROM information_schema.views
+WHERE table_schema = DATABASE()
+            "#,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for (name, definition) in views {
+            let ident = quote.ident(&name);
+
+            let object = Object::new(
+                &definition,
+                Some(format!("CREATE VIEW {} AS {}", ident, definition)),
+                Some(format!("DROP VIEW {}", ident)),
+            );
+
+            schema.objects.insert(("view", String::new(), name), object);
+        }
+
+        Ok(schema)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use std::collections::BTreeMap;
+
+    use sqlx::sqlite::SqliteConnection;
+
+    use super::{Column, Object, Quote, Schema};
+
+    pub(super) async fn introspect(conn: &mut SqliteConnection) -> anyhow::Result<Schema> {
+        let mut schema = Schema::default();
+        let quote = Quote::Double;
+
+        // language=SQLite
+        schema.table_sql = sqlx::query_as(
+            "SELECT name, sql FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+        )
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .collect();
+
+        // language=SQLite
+        let columns: Vec<(String, String, String, bool, Option<String>, i64)> = sqlx::query_as(
+            r#"
+SELECT m.name, c.name, c.type, c."notnull", c.dflt_value, c.pk
+FROM sqlite_master m
+JOIN pragma_table_info(m.name) c
+WHERE m.type = 'table' AND m.name NOT LIKE 'sqlite_%'
+ORDER BY m.name, c.cid
+            "#,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let mut primary_keys: BTreeMap<String, Vec<(i64, String)>> = BTreeMap::new();
+
+        for (table, name, type_name, not_null, default, pk) in columns {
+            if pk > 0 {
+                primary_keys
+                    .entry(table.clone())
+                    .or_default()
+                    .push((pk, quote.ident(&name)));
+            }
+
+            schema.tables.entry(table).or_default().push(Column {
+                name,
+                type_name,
+                not_null,
+                default,
+            });
+        }
+
+        // constraints can only be changed along with the table in SQLite
+        for (table, mut columns) in primary_keys {
+            columns.sort();
+            let columns: Vec<_> = columns.into_iter().map(|(_, column)| column).collect();
+            let definition = format!("PRIMARY KEY ({})", columns.join(", "));
+
+            schema.objects.insert(
+                ("constraint", table, "primary key".into()),
+                Object::new(&definition, None, None),
+            );
+        }
+
+        // language=SQLite
+        let foreign_keys: Vec<(String, i64, String, String, Option<String>)> = sqlx::query_as(
+            r#"
+SELECT m.name, f.id, f."table", f."from", f."to"
+FROM sqlite_master m
+JOIN pragma_foreign_key_list(m.name) f
+WHERE m.type = 'table'
+ORDER BY m.name, f.id, f.seq
+            "#,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let mut definitions: BTreeMap<(String, i64), (String, Vec<String>, Vec<String>)> =
+            BTreeMap::new();
+
+        for (table, id, referenced, from, to) in foreign_keys {
+            let (_, from_columns, to_columns) = definitions
+                .entry((table, id))
+                .or_insert_with(|| (referenced, Vec::new(), Vec::new()));
+
+            from_columns.push(quote.ident(&from));
+            to_columns.extend(to.map(|to| quote.ident(&to)));
+        }
+
+        for ((table, _), (referenced, from_columns, to_columns)) in definitions {
+            let definition = format!(
+                "FOREIGN KEY ({}) REFERENCES {} ({})",
+                from_columns.join(", "),
+                quote.ident(&referenced),
+                to_columns.join(", ")
+            );
+
+            // foreign keys have no name to tell them apart but their definition
+            schema.objects.insert(
+                ("constraint", table, definition.clone()),
+                Object::new(&definition, None, None),
+            );
+        }
+
+        // automatic indexes of constraints have no SQL
+        // language=SQLite
+        let objects: Vec<(String, String, String, String)> = sqlx::query_as(
+            r#"
+SELECT type, tbl_name, name, sql
+FROM sqlite_master
+WHERE type IN ('index', 'view', 'trigger') AND sql IS NOT NULL
+            "#,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for (kind, table, name, sql) in objects {
+            let (kind, table) = match &*kind {
+                "index" => ("index", table),
+                "view" => ("view", String::new()),
+                _ => ("trigger", table),
+            };
+
+            let drop = format!("DROP {} {}", kind.to_uppercase(), quote.ident(&name));
+            let object = Object::new(&sql, Some(sql.clone()), Some(drop));
+
+            schema.objects.insert((kind, table, name), object);
+        }
+
+        Ok(schema)
+    }
+}
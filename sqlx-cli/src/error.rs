@@ -0,0 +1,203 @@
+//! The kinds of failures of the CLI, which exit with their own status for CI to tell them apart,
+//! and how errors are printed, as JSON with `--error-format json`.
+
+use std::fmt::{self, Display, Formatter};
+use std::process;
+
+use console::style;
+use once_cell::sync::Lazy;
+use serde_json::json;
+use sqlx::migrate::MigrateError;
+
+use crate::opt::{self, InfoFormat};
+
+/// The format of errors, from `--error-format` or `SQLX_ERROR_FORMAT`.
+///
+/// Errors parsing the arguments or reading `sqlx.toml` are printed in it, so it's read before
+/// the arguments are parsed.
+static ERROR_FORMAT: Lazy<InfoFormat> = Lazy::new(|| {
+    opt::global_arg("--error-format")
+        .or_else(|| std::env::var("SQLX_ERROR_FORMAT").ok())
+        .and_then(|format| format.parse().ok())
+        .unwrap_or(InfoFormat::Human)
+});
+
+/// The kind of an error returned by [`run()`](crate::run), telling its exit status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Any other failure; exits with 1.
+    Other,
+    /// Invalid arguments or configuration, e.g. a malformed database URL or `sqlx.toml`; exits
+    /// with 2, as argument errors of `clap`.
+    Usage,
+    /// The database couldn't be connected to, or the connection was lost; exits with 3.
+    Connect,
+    /// A migration failed to apply or revert, or the applied migrations don't match the
+    /// migrations directory; exits with 4.
+    Migrate,
+    /// The query data saved by `prepare` is out of date; exits with 5.
+    Stale,
+}
+
+impl ErrorKind {
+    pub fn of(error: &anyhow::Error) -> Self {
+        for cause in error.chain() {
+            if let Some(ConnectError(error)) = cause.downcast_ref() {
+                return match error {
+                    sqlx::Error::Configuration(_) => ErrorKind::Usage,
+                    _ => ErrorKind::Connect,
+                };
+            }
+
+            if cause.is::<StaleError>() {
+                return ErrorKind::Stale;
+            }
+
+            if cause.is::<MigrateError>() {
+                return ErrorKind::Migrate;
+            }
+
+            match cause.downcast_ref::<sqlx::Error>() {
+                Some(sqlx::Error::Migrate(_)) => return ErrorKind::Migrate,
+                Some(sqlx::Error::Configuration(_)) => return ErrorKind::Usage,
+                Some(sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::PoolTimedOut) => {
+                    return ErrorKind::Connect
+                }
+                _ => {}
+            }
+        }
+
+        ErrorKind::Other
+    }
+
+    pub fn exit_status(self) -> i32 {
+        match self {
+            ErrorKind::Other => 1,
+            ErrorKind::Usage => 2,
+            ErrorKind::Connect => 3,
+            ErrorKind::Migrate => 4,
+            ErrorKind::Stale => 5,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::Other => "other",
+            ErrorKind::Usage => "usage",
+            ErrorKind::Connect => "connection",
+            ErrorKind::Migrate => "migration",
+            ErrorKind::Stale => "stale",
+        }
+    }
+}
+
+/// The database couldn't be connected to.
+#[derive(Debug)]
+pub struct ConnectError(pub sqlx::Error);
+
+impl Display for ConnectError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to connect to the database: {}", self.0)
+    }
+}
+
+impl std::error::Error for ConnectError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+/// The query data saved by `prepare` is out of date.
+#[derive(Debug)]
+pub struct StaleError(pub String);
+
+impl Display for StaleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for StaleError {}
+
+/// Print `error`, as a JSON object on stderr with `--error-format json`, and return the exit
+/// status of its kind.
+pub fn report(error: &anyhow::Error) -> i32 {
+    let kind = ErrorKind::of(error);
+    let causes: Vec<_> = error
+        .chain()
+        .skip(1)
+        .map(|cause| cause.to_string())
+        .collect();
+
+    print(kind, &error.to_string(), &causes);
+
+    kind.exit_status()
+}
+
+/// Exit for an error parsing the arguments, printed as JSON with `--error-format json`;
+/// `--help` and `--version` are printed as usual.
+pub fn exit_with_usage(error: clap::Error) -> ! {
+    if *ERROR_FORMAT == InfoFormat::Human || !error.use_stderr() {
+        error.exit();
+    }
+
+    // the first line, without the usage and hints following it
+    let message = error.to_string();
+    let message = message.lines().next().unwrap_or_default();
+
+    print(
+        ErrorKind::Usage,
+        message.strip_prefix("error: ").unwrap_or(message),
+        &[],
+    );
+
+    process::exit(ErrorKind::Usage.exit_status());
+}
+
+fn print(kind: ErrorKind, message: &str, causes: &[String]) {
+    match *ERROR_FORMAT {
+        InfoFormat::Human => println!("{} {}", style("error:").bold().red(), message),
+        InfoFormat::Json => eprintln!(
+            "{}",
+            json!({
+                "kind": kind.as_str(),
+                "exit_status": kind.exit_status(),
+                "message": message,
+                "causes": causes,
+            })
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn it_tells_the_kind_of_errors() {
+        let kind = |error: anyhow::Error| ErrorKind::of(&error);
+
+        assert_eq!(kind(anyhow::anyhow!("oops")), ErrorKind::Other);
+        assert_eq!(
+            kind(ConnectError(sqlx::Error::PoolTimedOut).into()),
+            ErrorKind::Connect
+        );
+        assert_eq!(
+            kind(ConnectError(sqlx::Error::Configuration("bad URL".into())).into()),
+            ErrorKind::Usage
+        );
+        assert_eq!(
+            kind(MigrateError::VersionMissing(1).into()),
+            ErrorKind::Migrate
+        );
+        assert_eq!(
+            kind(
+                Err::<(), _>(StaleError("out of date".into()))
+                    .context("while checking")
+                    .unwrap_err()
+            ),
+            ErrorKind::Stale
+        );
+    }
+}
@@ -0,0 +1,291 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context};
+use console::style;
+use sqlx::any::AnyConnectionKind;
+use sqlx::{AnyConnection, Connection, Executor};
+
+use crate::config;
+use crate::opt::{ConnectOpts, FixtureTransaction};
+
+/// The file of the fixture `name`: a path to a file, or the name of a `.sql` file in `dir`.
+fn resolve(dir: &str, name: &str) -> anyhow::Result<PathBuf> {
+    let path = Path::new(name);
+
+    if path.is_file() {
+        return Ok(path.to_path_buf());
+    }
+
+    let in_dir = Path::new(dir).join(name).with_extension("sql");
+
+    if in_dir.is_file() {
+        return Ok(in_dir);
+    }
+
+    bail!(
+        "fixture `{}` not found: no such file, nor {}",
+        name,
+        in_dir.display()
+    )
+}
+
+/// Apply the SQL of the fixtures, in order, to the database, after deleting the rows of every
+/// table if `truncate_first`.
+pub async fn apply(
+    connect_opts: &ConnectOpts,
+    dir: &str,
+    fixtures: &[String],
+    truncate_first: bool,
+    transaction: FixtureTransaction,
+    confirm: bool,
+) -> anyhow::Result<()> {
+    // read them all first, so a missing fixture doesn't leave the others half applied
+    let fixtures = fixtures
+        .iter()
+        .map(|name| {
+            let path = resolve(dir, name)?;
+            let sql = fs::read_to_string(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+
+            Ok((path, sql))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    if truncate_first
+        && !crate::database::confirm_if_protected(
+            format!(
+                "Delete all rows of the database at {}?",
+                style(&connect_opts.database_url).cyan()
+            ),
+            confirm,
+        )?
+    {
+        return Ok(());
+    }
+
+    let mut conn = crate::connect(connect_opts).await?;
+
+    match transaction {
+        FixtureTransaction::All => {
+            let mut tx = conn.begin().await?;
+
+            if truncate_first {
+                truncate(&mut tx).await?;
+            }
+
+            for (path, sql) in &fixtures {
+                apply_one(&mut tx, path, sql).await?;
+            }
+
+            tx.commit().await?;
+        }
+        FixtureTransaction::File => {
+            if truncate_first {
+                let mut tx = conn.begin().await?;
+                truncate(&mut tx).await?;
+                tx.commit().await?;
+            }
+
+            for (path, sql) in &fixtures {
+                let mut tx = conn.begin().await?;
+                apply_one(&mut tx, path, sql).await?;
+                tx.commit().await?;
+            }
+        }
+        FixtureTransaction::None => {
+            if truncate_first {
+                truncate(&mut conn).await?;
+            }
+
+            for (path, sql) in &fixtures {
+                apply_one(&mut conn, path, sql).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn apply_one(conn: &mut AnyConnection, path: &Path, sql: &str) -> anyhow::Result<()> {
+    let done = conn
+        .execute(sql)
+        .await
+        .map_err(|e| anyhow!("failed to apply {}: {}", path.display(), e))?;
+
+    println!(
+        "Applied {} ({} row(s) affected)",
+        style(path.display()).cyan(),
+        done.rows_affected()
+    );
+
+    Ok(())
+}
+
+/// Delete the rows of every table but those recording migrations, resetting their sequences
+/// where the database allows it.
+async fn truncate(conn: &mut AnyConnection) -> anyhow::Result<()> {
+    let tables: Vec<String> = match conn.private_get_mut() {
+        #[cfg(feature = "postgres")]
+        AnyConnectionKind::Postgres(conn) => postgres::tables(conn).await?,
+
+        #[cfg(feature = "mysql")]
+        AnyConnectionKind::MySql(conn) => mysql::tables(conn).await?,
+
+        #[cfg(feature = "sqlite")]
+        AnyConnectionKind::Sqlite(conn) => sqlite::tables(conn).await?,
+
+        #[allow(unreachable_patterns)]
+        _ => bail!("truncating this database is not supported"),
+    }
+    .into_iter()
+    .filter(|table| !config::is_bookkeeping_table(table))
+    .collect();
+
+    if tables.is_empty() {
+        return Ok(());
+    }
+
+    match conn.private_get_mut() {
+        #[cfg(feature = "postgres")]
+        AnyConnectionKind::Postgres(conn) => postgres::truncate(conn, &tables).await?,
+
+        #[cfg(feature = "mysql")]
+        AnyConnectionKind::MySql(conn) => mysql::truncate(conn, &tables).await?,
+
+        #[cfg(feature = "sqlite")]
+        AnyConnectionKind::Sqlite(conn) => sqlite::truncate(conn, &tables).await?,
+
+        #[allow(unreachable_patterns)]
+        _ => unreachable!(),
+    }
+
+    println!("Deleted the rows of {} table(s)", tables.len());
+
+    Ok(())
+}
+
+#[cfg(feature = "postgres")]
+mod postgres {
+    use sqlx::postgres::PgConnection;
+    use sqlx::Executor;
+
+    pub(super) async fn tables(conn: &mut PgConnection) -> anyhow::Result<Vec<String>> {
+        // language=PostgreSQL
+        let tables = sqlx::query_scalar(
+            r#"
+SELECT c.relname::text
+FROM pg_class c
+JOIN pg_namespace n ON n.oid = c.relnamespace
+WHERE c.relkind IN ('r', 'p') AND n.nspname = current_schema()
+ORDER BY c.relname
+            "#,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(tables)
+    }
+
+    pub(super) async fn truncate(conn: &mut PgConnection, tables: &[String]) -> anyhow::Result<()> {
+        let tables: Vec<_> = tables
+            .iter()
+            .map(|table| format!("\"{}\"", table.replace('"', "\"\"")))
+            .collect();
+
+        conn.execute(&*format!(
+            "TRUNCATE {} RESTART IDENTITY CASCADE",
+            tables.join(", ")
+        ))
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "mysql")]
+mod mysql {
+    use sqlx::mysql::MySqlConnection;
+    use sqlx::Executor;
+
+    pub(super) async fn tables(conn: &mut MySqlConnection) -> anyhow::Result<Vec<String>> {
+        // language=MySQL
+        let tables = sqlx::query_scalar(
+            r#"
+SELECT CAST(table_name AS CHAR)
+FROM information_schema.tables
+WHERE table_schema = DATABASE() AND table_type = 'BASE TABLE'
+ORDER BY table_name
+            "#,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(tables)
+    }
+
+    // `TRUNCATE` (like `ALTER TABLE`) commits the transaction in MySQL, so the rows are deleted
+    // instead, leaving the `AUTO_INCREMENT` counters as they are
+    pub(super) async fn truncate(
+        conn: &mut MySqlConnection,
+        tables: &[String],
+    ) -> anyhow::Result<()> {
+        conn.execute("SET FOREIGN_KEY_CHECKS = 0").await?;
+
+        for table in tables {
+            conn.execute(&*format!("DELETE FROM `{}`", table.replace('`', "``")))
+                .await?;
+        }
+
+        conn.execute("SET FOREIGN_KEY_CHECKS = 1").await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use sqlx::sqlite::SqliteConnection;
+    use sqlx::Executor;
+
+    pub(super) async fn tables(conn: &mut SqliteConnection) -> anyhow::Result<Vec<String>> {
+        // language=SQLite
+        let tables = sqlx::query_scalar(
+            r#"
+SELECT name FROM sqlite_master
+WHERE type = 'table' AND name NOT LIKE 'sqlite_%'
+ORDER BY name
+            "#,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(tables)
+    }
+
+    pub(super) async fn truncate(
+        conn: &mut SqliteConnection,
+        tables: &[String],
+    ) -> anyhow::Result<()> {
+        // the foreign keys are only checked once every row is gone (on commit, in a transaction)
+        conn.execute("PRAGMA defer_foreign_keys = ON").await?;
+
+        for table in tables {
+            conn.execute(&*format!("DELETE FROM \"{}\"", table.replace('"', "\"\"")))
+                .await?;
+        }
+
+        // reset `AUTOINCREMENT` counters, if any table has one
+        let has_sequences: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE name = 'sqlite_sequence')",
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+
+        if has_sequences {
+            conn.execute("DELETE FROM sqlite_sequence").await?;
+        }
+
+        Ok(())
+    }
+}
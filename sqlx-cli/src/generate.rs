@@ -0,0 +1,670 @@
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{bail, Context};
+use console::style;
+use sqlx::any::AnyConnectionKind;
+
+use crate::config;
+use crate::opt::ConnectOpts;
+
+/// A table or view of the schema.
+struct Table {
+    name: String,
+    is_view: bool,
+    columns: Vec<Column>,
+}
+
+struct Column {
+    name: String,
+    /// The name of the type in the database.
+    type_name: String,
+    /// The Rust type of the values, if supported.
+    rust_type: Option<&'static str>,
+    nullable: bool,
+}
+
+/// The features of `sqlx` enabling the types which columns may be mapped to; where several
+/// types fit a column, the first enabled in this order is used, as in the query macros.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct TypeFeatures {
+    chrono: bool,
+    time: bool,
+    bigdecimal: bool,
+    decimal: bool,
+    uuid: bool,
+    json: bool,
+    ipnetwork: bool,
+    mac_address: bool,
+    bit_vec: bool,
+}
+
+impl TypeFeatures {
+    fn from_features<'a>(features: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut types = TypeFeatures::default();
+
+        for feature in features {
+            match feature {
+                "chrono" => types.chrono = true,
+                "time" => types.time = true,
+                "bigdecimal" => types.bigdecimal = true,
+                "decimal" => types.decimal = true,
+                "uuid" => types.uuid = true,
+                "json" => types.json = true,
+                "ipnetwork" => types.ipnetwork = true,
+                "mac_address" => types.mac_address = true,
+                "bit-vec" => types.bit_vec = true,
+                "all" | "all-types" => {
+                    types = TypeFeatures {
+                        chrono: true,
+                        time: true,
+                        bigdecimal: true,
+                        decimal: true,
+                        uuid: true,
+                        json: true,
+                        ipnetwork: true,
+                        mac_address: true,
+                        bit_vec: true,
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        types
+    }
+
+    /// The features enabled on the `sqlx` dependency of the package in the current directory, if
+    /// there is one.
+    fn of_current_package() -> anyhow::Result<Option<Self>> {
+        if !Path::new("Cargo.toml").exists() {
+            return Ok(None);
+        }
+
+        let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".into());
+        let output = Command::new(cargo)
+            .args(&["metadata", "--no-deps", "--format-version=1"])
+            .output()
+            .context("Could not fetch metadata")?;
+
+        if !output.status.success() {
+            bail!(
+                "`cargo metadata` failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        let metadata: cargo_metadata::Metadata = serde_json::from_slice(&output.stdout)?;
+        let manifest_path = Path::new("Cargo.toml").canonicalize()?;
+
+        let sqlx = metadata
+            .packages
+            .iter()
+            .find(|package| package.manifest_path == manifest_path)
+            .and_then(|package| {
+                package
+                    .dependencies
+                    .iter()
+                    .find(|dependency| dependency.name == "sqlx")
+            });
+
+        Ok(sqlx.map(|sqlx| TypeFeatures::from_features(sqlx.features.iter().map(String::as_str))))
+    }
+}
+
+/// Write a struct deriving `FromRow` for each table and view of `schema` (the current schema by
+/// default) to a file of `out`, along with a `mod.rs` declaring them.
+///
+/// The types of the fields depend on `features`, or on those enabled on `sqlx` by the package in
+/// the current directory if not given.
+pub async fn models(
+    connect_opts: &ConnectOpts,
+    schema: Option<&str>,
+    out: &str,
+    features: Option<&[String]>,
+) -> anyhow::Result<()> {
+    let types = match features {
+        Some(features) => TypeFeatures::from_features(features.iter().map(String::as_str)),
+        None => TypeFeatures::of_current_package()?.unwrap_or_else(|| {
+            println!(
+                "{} no `sqlx` dependency in `Cargo.toml` in the current directory; \
+                 pass `--features` to map columns to the types of optional features",
+                style("warning:").yellow()
+            );
+            TypeFeatures::default()
+        }),
+    };
+
+    let mut conn = crate::connect(connect_opts).await?;
+
+    let tables = match conn.private_get_mut() {
+        #[cfg(feature = "postgres")]
+        AnyConnectionKind::Postgres(conn) => postgres::tables(conn, schema, types).await?,
+
+        #[cfg(feature = "mysql")]
+        AnyConnectionKind::MySql(conn) => mysql::tables(conn, schema, types).await?,
+
+        #[cfg(feature = "sqlite")]
+        AnyConnectionKind::Sqlite(conn) => sqlite::tables(conn, schema, types).await?,
+
+        #[allow(unreachable_patterns)]
+        _ => bail!("generating models for this database is not supported"),
+    };
+
+    let tables: Vec<Table> = tables
+        .into_iter()
+        .filter(|table| !config::is_bookkeeping_table(&table.name))
+        .collect();
+
+    if tables.is_empty() {
+        bail!("no tables found in the schema");
+    }
+
+    let out = Path::new(out);
+    fs::create_dir_all(out).with_context(|| format!("Failed to create {}", out.display()))?;
+
+    let mut modules = String::from("// Generated by `sqlx generate models`.\n\n");
+
+    for table in &tables {
+        let module = field_name(&table.name).0;
+        let path = out.join(format!("{}.rs", module.trim_start_matches("r#")));
+
+        println!("Creating {}", style(path.display()).cyan());
+
+        fs::write(&path, model(table))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        writeln!(modules, "mod {};", module)?;
+        writeln!(modules, "pub use {}::{};", module, struct_name(&table.name))?;
+    }
+
+    let path = out.join("mod.rs");
+    println!("Creating {}", style(path.display()).cyan());
+    fs::write(&path, modules).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    for table in &tables {
+        for column in &table.columns {
+            if column.rust_type.is_none() {
+                println!(
+                    "{} no Rust type for column `{}.{}` of type `{}`; it was left out",
+                    style("warning:").yellow(),
+                    table.name,
+                    column.name,
+                    column.type_name
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The source of the struct of a table.
+fn model(table: &Table) -> String {
+    let mut source = String::from("// Generated by `sqlx generate models`.\n\n");
+
+    let kind = if table.is_view { "view" } else { "table" };
+    let _ = writeln!(source, "/// A row of the `{}` {}.", table.name, kind);
+    source.push_str("#[derive(Debug, Clone, sqlx::FromRow)]\n");
+    let _ = writeln!(source, "pub struct {} {{", struct_name(&table.name));
+
+    for column in &table.columns {
+        let (name, renamed) = field_name(&column.name);
+
+        let rust_type = match column.rust_type {
+            Some(rust_type) if column.nullable => format!("Option<{}>", rust_type),
+            Some(rust_type) => rust_type.to_owned(),
+            None => {
+                let _ = writeln!(
+                    source,
+                    "    // TODO: no Rust type for `{}` of type `{}`",
+                    column.name, column.type_name
+                );
+                continue;
+            }
+        };
+
+        if renamed {
+            let _ = writeln!(source, "    #[sqlx(rename = {:?})]", column.name);
+        }
+
+        let _ = writeln!(source, "    pub {}: {},", name, rust_type);
+    }
+
+    source.push_str("}\n");
+    source
+}
+
+/// The name of the struct of a table, e.g. `UserAccount` for `user_accounts`; names are not
+/// singularized.
+fn struct_name(table: &str) -> String {
+    let name: String = table
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect();
+
+    match name.chars().next() {
+        Some(first) if !first.is_numeric() => name,
+        _ => format!("Table{}", name),
+    }
+}
+
+/// The name of the field of a column in snake case, and whether it differs from the name of the
+/// column, which must then be renamed.
+fn field_name(column: &str) -> (String, bool) {
+    let mut name = String::new();
+
+    for c in column.chars() {
+        if c.is_alphanumeric() {
+            name.extend(c.to_lowercase());
+        } else if !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+
+    if name.is_empty() || name.starts_with(|c: char| c.is_numeric()) {
+        name.insert(0, '_');
+    }
+
+    if KEYWORDS.contains(&&*name) {
+        // `self` and the like can't be raw identifiers
+        match &*name {
+            "self" | "super" | "crate" => name.push('_'),
+            _ => name.insert_str(0, "r#"),
+        }
+    }
+
+    // `FromRow` strips the `r#` of raw identifiers
+    let renamed = name.trim_start_matches("r#") != column;
+
+    (name, renamed)
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+    "where", "while", "abstract", "become", "box", "do", "final", "macro", "override", "priv",
+    "try", "typeof", "unsized", "virtual", "yield",
+];
+
+/// Group the columns of each table, read in order of the table and then of the columns.
+fn group_columns(rows: Vec<(String, bool, Column)>) -> Vec<Table> {
+    let mut tables: Vec<Table> = Vec::new();
+
+    for (table, is_view, column) in rows {
+        match tables.last_mut() {
+            Some(last) if last.name == table => last.columns.push(column),
+            _ => tables.push(Table {
+                name: table,
+                is_view,
+                columns: vec![column],
+            }),
+        }
+    }
+
+    tables
+}
+
+#[cfg(feature = "postgres")]
+mod postgres {
+    use sqlx::postgres::PgConnection;
+
+    use super::{group_columns, Column, Table, TypeFeatures};
+
+    pub(super) async fn tables(
+        conn: &mut PgConnection,
+        schema: Option<&str>,
+        types: TypeFeatures,
+    ) -> anyhow::Result<Vec<Table>> {
+        // language=PostgreSQL
+        let rows: Vec<(String, bool, String, String, bool)> = sqlx::query_as(
+            r#"
+SELECT c.table_name::text, t.table_type = 'VIEW', c.column_name::text, c.udt_name::text,
+       c.is_nullable = 'YES'
+FROM information_schema.columns c
+JOIN information_schema.tables t USING (table_schema, table_name)
+WHERE c.table_schema = COALESCE($1, current_schema())
+ORDER BY c.table_name, c.ordinal_position
+            "#,
+        )
+        .bind(schema)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let rows = rows
+            .into_iter()
+            .map(|(table, is_view, name, type_name, nullable)| {
+                let rust_type = match type_name.strip_prefix('_') {
+                    Some(element) => array_type(element, types),
+                    None => rust_type(&type_name, types),
+                };
+
+                let column = Column {
+                    name,
+                    type_name,
+                    rust_type,
+                    nullable,
+                };
+
+                (table, is_view, column)
+            })
+            .collect();
+
+        Ok(group_columns(rows))
+    }
+
+    fn rust_type(type_name: &str, types: TypeFeatures) -> Option<&'static str> {
+        Some(match type_name {
+            "bool" => "bool",
+            "char" => "i8",
+            "int2" => "i16",
+            "int4" => "i32",
+            "int8" => "i64",
+            "float4" => "f32",
+            "float8" => "f64",
+            "text" | "varchar" | "bpchar" | "name" | "citext" => "String",
+            "bytea" => "Vec<u8>",
+            "oid" => "sqlx::postgres::types::Oid",
+            "interval" => "sqlx::postgres::types::PgInterval",
+            "money" => "sqlx::postgres::types::PgMoney",
+            "ltree" => "sqlx::postgres::types::PgLTree",
+            "lquery" => "sqlx::postgres::types::PgLQuery",
+            "uuid" if types.uuid => "sqlx::types::Uuid",
+            "date" if types.chrono => "sqlx::types::chrono::NaiveDate",
+            "date" if types.time => "sqlx::types::time::Date",
+            "time" if types.chrono => "sqlx::types::chrono::NaiveTime",
+            "time" if types.time => "sqlx::types::time::Time",
+            "timestamp" if types.chrono => "sqlx::types::chrono::NaiveDateTime",
+            "timestamp" if types.time => "sqlx::types::time::PrimitiveDateTime",
+            "timestamptz" if types.chrono => {
+                "sqlx::types::chrono::DateTime<sqlx::types::chrono::Utc>"
+            }
+            "timestamptz" if types.time => "sqlx::types::time::OffsetDateTime",
+            "numeric" if types.bigdecimal => "sqlx::types::BigDecimal",
+            "numeric" if types.decimal => "sqlx::types::Decimal",
+            "json" | "jsonb" if types.json => "sqlx::types::JsonValue",
+            "inet" | "cidr" if types.ipnetwork => "sqlx::types::ipnetwork::IpNetwork",
+            "macaddr" if types.mac_address => "sqlx::types::mac_address::MacAddress",
+            "bit" | "varbit" if types.bit_vec => "sqlx::types::BitVec",
+            _ => return None,
+        })
+    }
+
+    /// The type of an array of elements of type `element`, for those supported in arrays.
+    fn array_type(element: &str, types: TypeFeatures) -> Option<&'static str> {
+        Some(match rust_type(element, types)? {
+            "bool" => "Vec<bool>",
+            "i8" => "Vec<i8>",
+            "i16" => "Vec<i16>",
+            "i32" => "Vec<i32>",
+            "i64" => "Vec<i64>",
+            "f32" => "Vec<f32>",
+            "f64" => "Vec<f64>",
+            "String" => "Vec<String>",
+            "Vec<u8>" => "Vec<Vec<u8>>",
+            "sqlx::postgres::types::Oid" => "Vec<sqlx::postgres::types::Oid>",
+            "sqlx::postgres::types::PgMoney" => "Vec<sqlx::postgres::types::PgMoney>",
+            "sqlx::types::Uuid" => "Vec<sqlx::types::Uuid>",
+            "sqlx::types::chrono::NaiveDate" => "Vec<sqlx::types::chrono::NaiveDate>",
+            "sqlx::types::chrono::NaiveTime" => "Vec<sqlx::types::chrono::NaiveTime>",
+            "sqlx::types::chrono::NaiveDateTime" => "Vec<sqlx::types::chrono::NaiveDateTime>",
+            "sqlx::types::chrono::DateTime<sqlx::types::chrono::Utc>" => {
+                "Vec<sqlx::types::chrono::DateTime<sqlx::types::chrono::Utc>>"
+            }
+            "sqlx::types::time::Date" => "Vec<sqlx::types::time::Date>",
+            "sqlx::types::time::Time" => "Vec<sqlx::types::time::Time>",
+            "sqlx::types::time::PrimitiveDateTime" => "Vec<sqlx::types::time::PrimitiveDateTime>",
+            "sqlx::types::time::OffsetDateTime" => "Vec<sqlx::types::time::OffsetDateTime>",
+            "sqlx::types::BigDecimal" => "Vec<sqlx::types::BigDecimal>",
+            "sqlx::types::Decimal" => "Vec<sqlx::types::Decimal>",
+            "sqlx::types::JsonValue" => "Vec<sqlx::types::JsonValue>",
+            "sqlx::types::ipnetwork::IpNetwork" => "Vec<sqlx::types::ipnetwork::IpNetwork>",
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(feature = "mysql")]
+mod mysql {
+    use sqlx::mysql::MySqlConnection;
+
+    use super::{group_columns, Column, Table, TypeFeatures};
+
+    pub(super) async fn tables(
+        conn: &mut MySqlConnection,
+        schema: Option<&str>,
+        types: TypeFeatures,
+    ) -> anyhow::Result<Vec<Table>> {
+        // language=MySQL
+        let rows: Vec<(String, bool, String, String, String, bool)> = sqlx::query_as(
+            r#"
+SELECT CAST(c.table_name AS CHAR), t.table_type = 'VIEW', CAST(c.column_name AS CHAR),
+       CAST(c.data_type AS CHAR), CAST(c.column_type AS CHAR), c.is_nullable = 'YES'
+FROM information_schema.columns c
+JOIN information_schema.tables t
+  ON t.table_schema = c.table_schema AND t.table_name = c.table_name
+WHERE c.table_schema = COALESCE(?, DATABASE())
+ORDER BY c.table_name, c.ordinal_position
+            "#,
+        )
+        .bind(schema)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let rows = rows
+            .into_iter()
+            .map(|(table, is_view, name, data_type, column_type, nullable)| {
+                let column = Column {
+                    name,
+                    rust_type: rust_type(&data_type, &column_type, types),
+                    type_name: column_type,
+                    nullable,
+                };
+
+                (table, is_view, column)
+            })
+            .collect();
+
+        Ok(group_columns(rows))
+    }
+
+    fn rust_type(data_type: &str, column_type: &str, types: TypeFeatures) -> Option<&'static str> {
+        let unsigned = column_type.to_ascii_lowercase().ends_with("unsigned");
+
+        Some(match &*data_type.to_ascii_lowercase() {
+            "tinyint" if column_type.eq_ignore_ascii_case("tinyint(1)") => "bool",
+            "tinyint" if unsigned => "u8",
+            "tinyint" => "i8",
+            "smallint" if unsigned => "u16",
+            "smallint" => "i16",
+            "mediumint" | "int" if unsigned => "u32",
+            "mediumint" | "int" => "i32",
+            "bigint" if unsigned => "u64",
+            "bigint" => "i64",
+            "year" => "u16",
+            "bit" => "u64",
+            "float" => "f32",
+            "double" => "f64",
+            "char" | "varchar" | "tinytext" | "text" | "mediumtext" | "longtext" | "enum"
+            | "set" => "String",
+            "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" => "Vec<u8>",
+            "date" if types.chrono => "sqlx::types::chrono::NaiveDate",
+            "date" if types.time => "sqlx::types::time::Date",
+            "time" if types.chrono => "sqlx::types::chrono::NaiveTime",
+            "time" if types.time => "sqlx::types::time::Time",
+            "datetime" if types.chrono => "sqlx::types::chrono::NaiveDateTime",
+            "datetime" if types.time => "sqlx::types::time::PrimitiveDateTime",
+            "timestamp" if types.chrono => {
+                "sqlx::types::chrono::DateTime<sqlx::types::chrono::Utc>"
+            }
+            "timestamp" if types.time => "sqlx::types::time::OffsetDateTime",
+            "decimal" if types.bigdecimal => "sqlx::types::BigDecimal",
+            "decimal" if types.decimal => "sqlx::types::Decimal",
+            "json" if types.json => "sqlx::types::JsonValue",
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use sqlx::sqlite::SqliteConnection;
+
+    use super::{group_columns, Column, Table, TypeFeatures};
+
+    pub(super) async fn tables(
+        conn: &mut SqliteConnection,
+        schema: Option<&str>,
+        types: TypeFeatures,
+    ) -> anyhow::Result<Vec<Table>> {
+        let schema = schema.unwrap_or("main");
+
+        // language=SQLite
+        let rows: Vec<(String, bool, String, String, bool)> = sqlx::query_as(&format!(
+            r#"
+SELECT m.name, m.type = 'view', c.name, c.type, NOT (c."notnull" OR c.pk AND c.type = 'INTEGER')
+FROM "{}".sqlite_master m
+JOIN pragma_table_info(m.name, ?1) c
+WHERE m.type IN ('table', 'view') AND m.name NOT LIKE 'sqlite_%'
+ORDER BY m.name, c.cid
+            "#,
+            schema.replace('"', "\"\"")
+        ))
+        .bind(schema)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let rows = rows
+            .into_iter()
+            .map(|(table, is_view, name, type_name, nullable)| {
+                let column = Column {
+                    name,
+                    rust_type: rust_type(&type_name, types),
+                    type_name,
+                    nullable,
+                };
+
+                (table, is_view, column)
+            })
+            .collect();
+
+        Ok(group_columns(rows))
+    }
+
+    /// The type of a column declared with `type_name`, by the names SQLite itself recognizes, see
+    /// <https://www.sqlite.org/datatype3.html#determination_of_column_affinity>.
+    fn rust_type(type_name: &str, types: TypeFeatures) -> Option<&'static str> {
+        let type_name = type_name.to_ascii_uppercase();
+
+        Some(match &*type_name {
+            "BOOLEAN" | "BOOL" => "bool",
+            "DATE" if types.chrono => "sqlx::types::chrono::NaiveDate",
+            "DATE" if types.time => "sqlx::types::time::Date",
+            "TIME" if types.chrono => "sqlx::types::chrono::NaiveTime",
+            "TIME" if types.time => "sqlx::types::time::Time",
+            "DATETIME" | "TIMESTAMP" if types.chrono => "sqlx::types::chrono::NaiveDateTime",
+            "DATETIME" | "TIMESTAMP" if types.time => "sqlx::types::time::PrimitiveDateTime",
+            "UUID" if types.uuid => "sqlx::types::Uuid",
+            "JSON" if types.json => "sqlx::types::JsonValue",
+            _ if type_name.contains("INT") => "i64",
+            _ if ["CHAR", "CLOB", "TEXT"]
+                .iter()
+                .any(|t| type_name.contains(t)) =>
+            {
+                "String"
+            }
+            _ if type_name.is_empty() || type_name.contains("BLOB") => "Vec<u8>",
+            _ if ["REAL", "FLOA", "DOUB"]
+                .iter()
+                .any(|t| type_name.contains(t)) =>
+            {
+                "f64"
+            }
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn struct_name_works() {
+        assert_eq!(struct_name("users"), "Users");
+        assert_eq!(struct_name("user_accounts"), "UserAccounts");
+        assert_eq!(struct_name("2fa codes"), "Table2faCodes");
+    }
+
+    #[test]
+    fn field_name_works() {
+        assert_eq!(field_name("email"), ("email".into(), false));
+        assert_eq!(field_name("createdAt"), ("createdat".into(), true));
+        assert_eq!(field_name("first name"), ("first_name".into(), true));
+        assert_eq!(field_name("type"), ("r#type".into(), false));
+        assert_eq!(field_name("self"), ("self_".into(), true));
+    }
+
+    #[test]
+    fn type_features_works() {
+        let types = TypeFeatures::from_features(["postgres", "chrono", "uuid"]);
+
+        assert!(types.chrono && types.uuid);
+        assert!(!types.time && !types.json);
+        assert!(TypeFeatures::from_features(["all-types"]).bit_vec);
+    }
+
+    #[test]
+    fn model_works() {
+        let table = Table {
+            name: "users".into(),
+            is_view: false,
+            columns: vec![
+                Column {
+                    name: "id".into(),
+                    type_name: "int8".into(),
+                    rust_type: Some("i64"),
+                    nullable: false,
+                },
+                Column {
+                    name: "Type".into(),
+                    type_name: "text".into(),
+                    rust_type: Some("String"),
+                    nullable: true,
+                },
+                Column {
+                    name: "location".into(),
+                    type_name: "point".into(),
+                    rust_type: None,
+                    nullable: false,
+                },
+            ],
+        };
+
+        assert_eq!(
+            model(&table),
+            r#"// Generated by `sqlx generate models`.
+
+/// A row of the `users` table.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Users {
+    pub id: i64,
+    #[sqlx(rename = "Type")]
+    pub r#type: Option<String>,
+    // TODO: no Rust type for `location` of type `point`
+}
+"#
+        );
+    }
+}
@@ -4,19 +4,42 @@ use sqlx::{AnyConnection, Connection};
 use std::io;
 use std::time::Duration;
 
-use crate::opt::{Command, ConnectOpts, DatabaseCommand, MigrateCommand};
+use crate::error::ConnectError;
+use crate::opt::{
+    Command, ConnectOpts, DatabaseCommand, FixtureCommand, GenerateCommand, MigrateCommand,
+    SeedCommand,
+};
 
+mod completions;
+mod config;
 mod database;
+mod diff;
+mod dump;
+mod error;
+mod fixture;
+mod generate;
+mod lint;
 mod metadata;
 // mod migration;
 // mod migrator;
 mod migrate;
 mod opt;
 mod prepare;
+mod query;
+mod seed;
+mod ssh;
+mod watch;
 
+pub use crate::error::{exit_with_usage, report};
 pub use crate::opt::Opt;
 
-pub async fn run(opt: Opt) -> Result<()> {
+pub async fn run(mut opt: Opt) -> Result<()> {
+    // kept open until the command is done
+    let _tunnel = match opt.connect_opts_mut() {
+        Some(connect_opts) => ssh::tunnel(connect_opts).await?,
+        None => None,
+    };
+
     match opt.command {
         Command::Migrate(migrate) => match migrate.command {
             MigrateCommand::Add {
@@ -29,75 +52,363 @@ pub async fn run(opt: Opt) -> Result<()> {
                 dry_run,
                 ignore_missing,
                 connect_opts,
+                table_name,
+                lock,
+                target_version,
+                allow_checksum_mismatch,
+                out_of_order,
+                dump_schema,
             } => {
                 migrate::run(
                     source.resolve(&migrate.source),
                     &connect_opts,
+                    &table_name,
+                    lock.lock(),
                     dry_run,
                     *ignore_missing,
+                    target_version,
+                    &allow_checksum_mismatch,
+                    out_of_order,
+                    dump_schema.as_deref(),
                 )
                 .await?
             }
             MigrateCommand::Revert {
                 source,
                 dry_run,
+                confirmation,
                 ignore_missing,
                 connect_opts,
+                table_name,
+                lock,
+                target_version,
             } => {
                 migrate::revert(
                     source.resolve(&migrate.source),
                     &connect_opts,
+                    &table_name,
+                    lock.lock(),
+                    dry_run,
+                    *ignore_missing,
+                    target_version,
+                    !confirmation.yes,
+                )
+                .await?
+            }
+            MigrateCommand::Redo {
+                source,
+                dry_run,
+                confirmation,
+                ignore_missing,
+                connect_opts,
+                table_name,
+                lock,
+            } => {
+                migrate::redo(
+                    source.resolve(&migrate.source),
+                    &connect_opts,
+                    &table_name,
+                    lock.lock(),
                     dry_run,
                     *ignore_missing,
+                    !confirmation.yes,
+                )
+                .await?
+            }
+            MigrateCommand::Repair {
+                source,
+                yes,
+                ignore_missing,
+                connect_opts,
+                table_name,
+                lock,
+            } => {
+                migrate::repair(
+                    source.resolve(&migrate.source),
+                    &connect_opts,
+                    &table_name,
+                    lock.lock(),
+                    !yes,
+                    *ignore_missing,
+                )
+                .await?
+            }
+            MigrateCommand::Baseline {
+                version,
+                source,
+                yes,
+                ignore_missing,
+                connect_opts,
+                table_name,
+                lock,
+            } => {
+                migrate::baseline(
+                    source.resolve(&migrate.source),
+                    &connect_opts,
+                    &table_name,
+                    lock.lock(),
+                    version,
+                    !yes,
+                    *ignore_missing,
+                )
+                .await?
+            }
+            MigrateCommand::Squash {
+                version,
+                source,
+                yes,
+            } => migrate::squash(source.resolve(&migrate.source), version, !yes).await?,
+            MigrateCommand::Renumber {
+                source,
+                yes,
+                connect_opts,
+                table_name,
+                lock,
+            } => {
+                migrate::renumber(
+                    source.resolve(&migrate.source),
+                    &connect_opts,
+                    &table_name,
+                    lock.lock(),
+                    !yes,
                 )
                 .await?
             }
             MigrateCommand::Info {
                 source,
                 connect_opts,
-            } => migrate::info(source.resolve(&migrate.source), &connect_opts).await?,
+                table_name,
+                format,
+            } => {
+                migrate::info(
+                    source.resolve(&migrate.source),
+                    &connect_opts,
+                    &table_name,
+                    format,
+                )
+                .await?
+            }
             MigrateCommand::BuildScript { source, force } => {
                 migrate::build_script(source.resolve(&migrate.source), force)?
             }
         },
 
+        Command::Seed(seed) => match seed.command {
+            SeedCommand::Add {
+                description,
+                source,
+                reversible,
+            } => seed::add(&source, &description, reversible).await?,
+            SeedCommand::Run {
+                source,
+                dry_run,
+                ignore_missing,
+                connect_opts,
+                table_name,
+                lock,
+                target_version,
+            } => {
+                seed::run(
+                    &source,
+                    &connect_opts,
+                    &table_name,
+                    lock.lock(),
+                    dry_run,
+                    *ignore_missing,
+                    target_version,
+                )
+                .await?
+            }
+            SeedCommand::Revert {
+                source,
+                dry_run,
+                confirmation,
+                ignore_missing,
+                connect_opts,
+                table_name,
+                lock,
+                target_version,
+            } => {
+                migrate::revert(
+                    &source,
+                    &connect_opts,
+                    &table_name,
+                    lock.lock(),
+                    dry_run,
+                    *ignore_missing,
+                    target_version,
+                    !confirmation.yes,
+                )
+                .await?
+            }
+            SeedCommand::Info {
+                source,
+                connect_opts,
+                table_name,
+                format,
+            } => migrate::info(&source, &connect_opts, &table_name, format).await?,
+        },
+
+        Command::Generate(generate) => match generate.command {
+            GenerateCommand::Models {
+                schema,
+                out,
+                features,
+                connect_opts,
+            } => {
+                generate::models(&connect_opts, schema.as_deref(), &out, features.as_deref())
+                    .await?
+            }
+        },
+
+        Command::Fixture(fixture) => match fixture.command {
+            FixtureCommand::Apply {
+                fixtures,
+                dir,
+                truncate_first,
+                transaction,
+                confirmation,
+                connect_opts,
+            } => {
+                fixture::apply(
+                    &connect_opts,
+                    &dir,
+                    &fixtures,
+                    truncate_first,
+                    transaction,
+                    !confirmation.yes,
+                )
+                .await?
+            }
+        },
+
         Command::Database(database) => match database.command {
-            DatabaseCommand::Create { connect_opts } => database::create(&connect_opts).await?,
+            DatabaseCommand::Create {
+                create_opts,
+                connect_opts,
+            } => database::create(&connect_opts, &create_opts.options()).await?,
             DatabaseCommand::Drop {
                 confirmation,
                 connect_opts,
             } => database::drop(&connect_opts, !confirmation.yes).await?,
             DatabaseCommand::Reset {
+                create_opts,
                 confirmation,
                 source,
                 connect_opts,
-            } => database::reset(&source, &connect_opts, !confirmation.yes).await?,
+                table_name,
+            } => {
+                database::reset(
+                    &source,
+                    &connect_opts,
+                    &table_name,
+                    &create_opts.options(),
+                    !confirmation.yes,
+                )
+                .await?
+            }
             DatabaseCommand::Setup {
+                create_opts,
+                source,
+                connect_opts,
+                table_name,
+            } => {
+                database::setup(&source, &connect_opts, &table_name, &create_opts.options()).await?
+            }
+            DatabaseCommand::Diff {
+                from,
                 source,
+                migration,
                 connect_opts,
-            } => database::setup(&source, &connect_opts).await?,
+            } => {
+                diff::diff(
+                    &connect_opts,
+                    from.as_deref(),
+                    &source,
+                    migration.as_deref(),
+                )
+                .await?
+            }
+            DatabaseCommand::Lint {
+                schema,
+                sample,
+                format,
+                fail_on,
+                connect_opts,
+            } => lint::lint(&connect_opts, schema.as_deref(), sample, format, fail_on).await?,
+            DatabaseCommand::Dump {
+                output,
+                builtin,
+                connect_opts,
+            } => dump::dump(&connect_opts, output.as_deref(), builtin).await?,
+            DatabaseCommand::Restore {
+                file,
+                builtin,
+                confirmation,
+                connect_opts,
+            } => dump::restore(&connect_opts, &file, builtin, !confirmation.yes).await?,
         },
 
         Command::Prepare {
             check: false,
             merged,
+            workspace,
+            features,
             args,
             connect_opts,
-        } => prepare::run(&connect_opts, merged, args).await?,
+        } => prepare::run(&connect_opts, merged, workspace, &features, args).await?,
 
         Command::Prepare {
             check: true,
             merged,
+            workspace,
+            features,
             args,
             connect_opts,
-        } => prepare::check(&connect_opts, merged, args).await?,
+        } => prepare::check(&connect_opts, merged, workspace, &features, args).await?,
+
+        Command::Query {
+            sql,
+            format,
+            connect_opts,
+        } => query::run(&connect_opts, &sql, format).await?,
+
+        Command::Repl {
+            format,
+            connect_opts,
+        } => query::repl(&connect_opts, format).await?,
+
+        Command::Watch {
+            source,
+            table_name,
+            lock,
+            watch_opts,
+            features,
+            args,
+            connect_opts,
+        } => {
+            watch::run(
+                &source,
+                &connect_opts,
+                &table_name,
+                lock.lock(),
+                watch_opts,
+                &features,
+                args,
+            )
+            .await?
+        }
+
+        Command::Completions { shell } => completions::generate(shell)?,
+
+        Command::CompleteMigrations { source } => completions::list_migrations(&source).await,
     };
 
     Ok(())
 }
 
 /// Attempt to connect to the database server, retrying up to `ops.connect_timeout`.
-async fn connect(opts: &ConnectOpts) -> sqlx::Result<AnyConnection> {
+async fn connect(opts: &ConnectOpts) -> Result<AnyConnection, ConnectError> {
     retry_connect_errors(opts, AnyConnection::connect).await
 }
 
@@ -108,7 +419,7 @@ async fn connect(opts: &ConnectOpts) -> sqlx::Result<AnyConnection> {
 async fn retry_connect_errors<'a, F, Fut, T>(
     opts: &'a ConnectOpts,
     mut connect: F,
-) -> sqlx::Result<T>
+) -> Result<T, ConnectError>
 where
     F: FnMut(&'a str) -> Fut,
     Fut: Future<Output = sqlx::Result<T>> + 'a,
@@ -136,4 +447,5 @@ where
         },
     )
     .await
+    .map_err(ConnectError)
 }
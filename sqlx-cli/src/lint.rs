@@ -0,0 +1,628 @@
+use anyhow::bail;
+use console::style;
+use serde_json::json;
+use sqlx::any::AnyConnectionKind;
+use sqlx::Row;
+
+use crate::config;
+use crate::diff::Quote;
+use crate::opt::{ConnectOpts, InfoFormat, Severity};
+
+/// A table, as far as it is linted.
+#[derive(Debug, Default)]
+struct Table {
+    columns: Vec<Column>,
+    primary_key: Vec<String>,
+    /// The columns of each foreign key, in order.
+    foreign_keys: Vec<Vec<String>>,
+    /// The columns of each index, in order.
+    indexes: Vec<Vec<String>>,
+}
+
+#[derive(Debug)]
+struct Column {
+    name: String,
+    type_name: String,
+    nullable: bool,
+}
+
+#[derive(Debug)]
+struct Issue {
+    severity: Severity,
+    lint: &'static str,
+    table: String,
+    column: Option<String>,
+    message: String,
+}
+
+impl Issue {
+    fn new(severity: Severity, lint: &'static str, table: &str, message: String) -> Self {
+        Issue {
+            severity,
+            lint,
+            table: table.to_owned(),
+            column: None,
+            message,
+        }
+    }
+
+    fn column(mut self, column: &str) -> Self {
+        self.column = Some(column.to_owned());
+        self
+    }
+}
+
+/// Report common issues in the schema of the database, and exit with an error if any is at least
+/// as severe as `fail_on`.
+pub async fn lint(
+    connect_opts: &ConnectOpts,
+    schema: Option<&str>,
+    sample: u64,
+    format: InfoFormat,
+    fail_on: Severity,
+) -> anyhow::Result<()> {
+    let mut conn = crate::connect(connect_opts).await?;
+
+    let mut tables = match conn.private_get_mut() {
+        #[cfg(feature = "postgres")]
+        AnyConnectionKind::Postgres(conn) => postgres::tables(conn, schema).await?,
+
+        #[cfg(feature = "mysql")]
+        AnyConnectionKind::MySql(conn) => mysql::tables(conn, schema).await?,
+
+        #[cfg(feature = "sqlite")]
+        AnyConnectionKind::Sqlite(conn) => sqlite::tables(conn, schema).await?,
+
+        #[allow(unreachable_patterns)]
+        _ => bail!("linting this database is not supported"),
+    };
+
+    tables.retain(|name, _| !config::is_bookkeeping_table(name));
+
+    let quote = Quote::of(conn.kind());
+
+    let mut issues = Vec::new();
+
+    for (name, table) in &tables {
+        issues.extend(check(name, table));
+
+        let nullable: Vec<_> = table.columns.iter().filter(|c| c.nullable).collect();
+
+        if sample == 0 || nullable.is_empty() {
+            continue;
+        }
+
+        let table_ident = match schema {
+            Some(schema) => format!("{}.{}", quote.ident(schema), quote.ident(name)),
+            None => quote.ident(name),
+        };
+
+        let columns: Vec<_> = nullable
+            .iter()
+            .map(|column| quote.ident(&column.name))
+            .collect();
+
+        let sql = format!(
+            "SELECT COUNT(*), {} FROM (SELECT {} FROM {} LIMIT {}) s",
+            columns
+                .iter()
+                .map(|column| format!("COUNT({})", column))
+                .collect::<Vec<_>>()
+                .join(", "),
+            columns.join(", "),
+            table_ident,
+            sample
+        );
+
+        let row = sqlx::query(&sql).fetch_one(&mut conn).await?;
+        let rows: i64 = row.try_get(0)?;
+
+        for (i, column) in nullable.iter().enumerate() {
+            let non_null: i64 = row.try_get(i + 1)?;
+
+            if rows > 0 && non_null == rows {
+                issues.push(
+                    Issue::new(
+                        Severity::Info,
+                        "nullable-never-null",
+                        name,
+                        format!(
+                            "nullable column is never null in {} sampled row(s); consider NOT NULL",
+                            rows
+                        ),
+                    )
+                    .column(&column.name),
+                );
+            }
+        }
+    }
+
+    issues.sort_by(|a, b| b.severity.cmp(&a.severity));
+
+    match format {
+        InfoFormat::Human => print_issues(&issues),
+        InfoFormat::Json => print_issues_json(&issues)?,
+    }
+
+    let failed = issues
+        .iter()
+        .filter(|issue| issue.severity >= fail_on)
+        .count();
+
+    if failed > 0 {
+        bail!(
+            "{} issue(s) of severity `{}` or above",
+            failed,
+            fail_on.as_str()
+        );
+    }
+
+    Ok(())
+}
+
+/// The issues found in the definition of `table`.
+fn check(name: &str, table: &Table) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    if table.primary_key.is_empty() {
+        issues.push(Issue::new(
+            Severity::Error,
+            "missing-primary-key",
+            name,
+            "table has no primary key".into(),
+        ));
+    }
+
+    for foreign_key in &table.foreign_keys {
+        // an index can be used to look up the rows referencing another if the columns of the
+        // foreign key come first in it, in any order
+        let indexed = table
+            .indexes
+            .iter()
+            .chain(Some(&table.primary_key))
+            .any(|index| {
+                index.len() >= foreign_key.len()
+                    && foreign_key
+                        .iter()
+                        .all(|column| index[..foreign_key.len()].contains(column))
+            });
+
+        if !indexed {
+            issues.push(Issue::new(
+                Severity::Warning,
+                "unindexed-foreign-key",
+                name,
+                format!(
+                    "foreign key ({}) has no index, so deleting or updating the rows it \
+                     references scans the table",
+                    foreign_key.join(", ")
+                ),
+            ));
+        }
+    }
+
+    for column in &table.columns {
+        let type_name = column.type_name.to_ascii_lowercase();
+
+        if type_name == "varchar" || type_name == "character varying" {
+            issues.push(
+                Issue::new(
+                    Severity::Warning,
+                    "varchar-without-length",
+                    name,
+                    format!("{} column has no maximum length", column.type_name),
+                )
+                .column(&column.name),
+            );
+        }
+    }
+
+    issues
+}
+
+fn print_issues(issues: &[Issue]) {
+    for issue in issues {
+        let severity = match issue.severity {
+            Severity::Error => style("error:").bold().red(),
+            Severity::Warning => style("warning:").bold().yellow(),
+            Severity::Info => style("info:").bold().cyan(),
+        };
+
+        let location = match &issue.column {
+            Some(column) => format!("{}.{}", issue.table, column),
+            None => issue.table.clone(),
+        };
+
+        println!(
+            "{} {}: {} {}",
+            severity,
+            style(location).cyan(),
+            issue.message,
+            style(format!("[{}]", issue.lint)).dim()
+        );
+    }
+
+    if issues.is_empty() {
+        println!("No issues found");
+    }
+}
+
+// the issues for CI to read
+fn print_issues_json(issues: &[Issue]) -> anyhow::Result<()> {
+    let issues: Vec<_> = issues
+        .iter()
+        .map(|issue| {
+            json!({
+                "severity": issue.severity.as_str(),
+                "lint": issue.lint,
+                "table": issue.table,
+                "column": issue.column,
+                "message": issue.message,
+            })
+        })
+        .collect();
+
+    println!("{}", serde_json::to_string_pretty(&issues)?);
+
+    Ok(())
+}
+
+/// Group rows of a key or index (e.g. its table and name) and one of its columns, ordered by key,
+/// into the columns of each key.
+fn group_keys<K: PartialEq>(rows: impl IntoIterator<Item = (K, String)>) -> Vec<(K, Vec<String>)> {
+    let mut keys: Vec<(K, Vec<String>)> = Vec::new();
+
+    for (key, column) in rows {
+        match keys.last_mut() {
+            Some((k, columns)) if *k == key => columns.push(column),
+            _ => keys.push((key, vec![column])),
+        }
+    }
+
+    keys
+}
+
+#[cfg(feature = "postgres")]
+mod postgres {
+    use std::collections::BTreeMap;
+
+    use sqlx::postgres::PgConnection;
+
+    use super::{group_keys, Column, Table};
+
+    pub(super) async fn tables(
+        conn: &mut PgConnection,
+        schema: Option<&str>,
+    ) -> anyhow::Result<BTreeMap<String, Table>> {
+        let mut tables = BTreeMap::<String, Table>::new();
+
+        // language=PostgreSQL
+        let columns: Vec<(String, String, String, bool)> = sqlx::query_as(
+            r#"
+SELECT c.relname::text, a.attname::text, format_type(a.atttypid, a.atttypmod), NOT a.attnotnull
+FROM pg_class c
+JOIN pg_namespace n ON n.oid = c.relnamespace
+JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped
+WHERE c.relkind IN ('r', 'p') AND n.nspname = COALESCE($1, current_schema())
+ORDER BY c.relname, a.attnum
+            "#,
+        )
+        .bind(schema)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for (table, name, type_name, nullable) in columns {
+            tables.entry(table).or_default().columns.push(Column {
+                name,
+                type_name,
+                nullable,
+            });
+        }
+
+        // language=PostgreSQL
+        let constraints: Vec<(String, bool, String, String)> = sqlx::query_as(
+            r#"
+SELECT c.relname::text, con.contype = 'p', con.conname::text, a.attname::text
+FROM pg_constraint con
+JOIN pg_class c ON c.oid = con.conrelid
+JOIN pg_namespace n ON n.oid = c.relnamespace
+CROSS JOIN LATERAL unnest(con.conkey) WITH ORDINALITY AS k(attnum, i)
+JOIN pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum
+WHERE con.contype IN ('p', 'f') AND n.nspname = COALESCE($1, current_schema())
+ORDER BY c.relname, con.conname, k.i
+            "#,
+        )
+        .bind(schema)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let constraints = constraints
+            .into_iter()
+            .map(|(table, primary, name, column)| ((table, primary, name), column));
+
+        for ((table, primary, _), columns) in group_keys(constraints) {
+            let table = tables.entry(table).or_default();
+
+            if primary {
+                table.primary_key = columns;
+            } else {
+                table.foreign_keys.push(columns);
+            }
+        }
+
+        // language=PostgreSQL
+        let indexes: Vec<(String, String, String)> = sqlx::query_as(
+            r#"
+SELECT t.relname::text, i.relname::text, a.attname::text
+FROM pg_index x
+JOIN pg_class t ON t.oid = x.indrelid
+JOIN pg_class i ON i.oid = x.indexrelid
+JOIN pg_namespace n ON n.oid = t.relnamespace
+CROSS JOIN LATERAL unnest(x.indkey::int2[]) WITH ORDINALITY AS k(attnum, i)
+JOIN pg_attribute a ON a.attrelid = x.indrelid AND a.attnum = k.attnum
+WHERE n.nspname = COALESCE($1, current_schema())
+ORDER BY t.relname, i.relname, k.i
+            "#,
+        )
+        .bind(schema)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let indexes = indexes
+            .into_iter()
+            .map(|(table, name, column)| ((table, name), column));
+
+        for ((table, _), columns) in group_keys(indexes) {
+            tables.entry(table).or_default().indexes.push(columns);
+        }
+
+        Ok(tables)
+    }
+}
+
+#[cfg(feature = "mysql")]
+mod mysql {
+    use std::collections::BTreeMap;
+
+    use sqlx::mysql::MySqlConnection;
+
+    use super::{group_keys, Column, Table};
+
+    pub(super) async fn tables(
+        conn: &mut MySqlConnection,
+        schema: Option<&str>,
+    ) -> anyhow::Result<BTreeMap<String, Table>> {
+        let mut tables = BTreeMap::<String, Table>::new();
+
+        // language=MySQL
+        let columns: Vec<(String, String, String, bool)> = sqlx::query_as(
+            r#"
+SELECT CAST(c.table_name AS CHAR), CAST(c.column_name AS CHAR), CAST(c.column_type AS CHAR),
+       c.is_nullable = 'YES'
+FROM information_schema.columns c
+JOIN information_schema.tables t
+  ON t.table_schema = c.table_schema AND t.table_name = c.table_name
+WHERE t.table_type = 'BASE TABLE' AND c.table_schema = COALESCE(?, DATABASE())
+ORDER BY c.table_name, c.ordinal_position
+            "#,
+        )
+        .bind(schema)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for (table, name, type_name, nullable) in columns {
+            tables.entry(table).or_default().columns.push(Column {
+                name,
+                type_name,
+                nullable,
+            });
+        }
+
+        // language=MySQL
+        let indexes: Vec<(String, String, Option<String>)> = sqlx::query_as(
+            r#"
+SELECT CAST(table_name AS CHAR), CAST(index_name AS CHAR), CAST(column_name AS CHAR)
+FROM information_schema.statistics
+WHERE table_schema = COALESCE(?, DATABASE())
+ORDER BY table_name, index_name, seq_in_index
+            "#,
+        )
+        .bind(schema)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        // functional key parts have no column
+        let indexes = indexes
+            .into_iter()
+            .filter_map(|(table, name, column)| Some(((table, name), column?)));
+
+        for ((table, name), columns) in group_keys(indexes) {
+            let table = tables.entry(table).or_default();
+
+            if name == "PRIMARY" {
+                table.primary_key = columns;
+            } else {
+                table.indexes.push(columns);
+            }
+        }
+
+        // language=MySQL
+        let foreign_keys: Vec<(String, String, String)> = sqlx::query_as(
+            r#"
+SELECT CAST(table_name AS CHAR), CAST(constraint_name AS CHAR), CAST(column_name AS CHAR)
+FROM information_schema.key_column_usage
+WHERE table_schema = COALESCE(?, DATABASE()) AND referenced_table_name IS NOT NULL
+ORDER BY table_name, constraint_name, ordinal_position
+            "#,
+        )
+        .bind(schema)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let foreign_keys = foreign_keys
+            .into_iter()
+            .map(|(table, name, column)| ((table, name), column));
+
+        for ((table, _), columns) in group_keys(foreign_keys) {
+            tables.entry(table).or_default().foreign_keys.push(columns);
+        }
+
+        Ok(tables)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use std::cmp;
+    use std::collections::BTreeMap;
+
+    use sqlx::sqlite::SqliteConnection;
+
+    use super::{group_keys, Column, Table};
+
+    pub(super) async fn tables(
+        conn: &mut SqliteConnection,
+        schema: Option<&str>,
+    ) -> anyhow::Result<BTreeMap<String, Table>> {
+        let schema = schema.unwrap_or("main");
+        let master = format!("\"{}\".sqlite_master", schema.replace('"', "\"\""));
+
+        let mut tables = BTreeMap::<String, Table>::new();
+
+        // language=SQLite
+        let columns: Vec<(String, String, String, bool, i64)> = sqlx::query_as(&format!(
+            r#"
+SELECT m.name, c.name, c.type, NOT (c."notnull" OR c.pk AND c.type = 'INTEGER'), c.pk
+FROM {} m
+JOIN pragma_table_info(m.name, ?1) c
+WHERE m.type = 'table' AND m.name NOT LIKE 'sqlite_%'
+ORDER BY m.name, c.cid
+            "#,
+            master
+        ))
+        .bind(schema)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for (table, name, type_name, nullable, pk) in columns {
+            let table = tables.entry(table).or_default();
+
+            // `pk` is the position of the column in the primary key
+            if pk > 0 {
+                let position = cmp::min(pk as usize - 1, table.primary_key.len());
+                table.primary_key.insert(position, name.clone());
+            }
+
+            table.columns.push(Column {
+                name,
+                type_name,
+                nullable,
+            });
+        }
+
+        // language=SQLite
+        let foreign_keys: Vec<(String, i64, String)> = sqlx::query_as(&format!(
+            r#"
+SELECT m.name, f.id, f."from"
+FROM {} m
+JOIN pragma_foreign_key_list(m.name, ?1) f
+WHERE m.type = 'table' AND m.name NOT LIKE 'sqlite_%'
+ORDER BY m.name, f.id, f.seq
+            "#,
+            master
+        ))
+        .bind(schema)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let foreign_keys = foreign_keys
+            .into_iter()
+            .map(|(table, id, column)| ((table, id), column));
+
+        for ((table, _), columns) in group_keys(foreign_keys) {
+            tables.entry(table).or_default().foreign_keys.push(columns);
+        }
+
+        // language=SQLite
+        let indexes: Vec<(String, String, Option<String>)> = sqlx::query_as(&format!(
+            r#"
+SELECT m.name, il.name, ii.name
+FROM {} m
+JOIN pragma_index_list(m.name, ?1) il
+JOIN pragma_index_info(il.name, ?1) ii
+WHERE m.type = 'table' AND m.name NOT LIKE 'sqlite_%'
+ORDER BY m.name, il.name, ii.seqno
+            "#,
+            master
+        ))
+        .bind(schema)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        // indexes on expressions have no column
+        let indexes = indexes
+            .into_iter()
+            .filter_map(|(table, name, column)| Some(((table, name), column?)));
+
+        for ((table, _), columns) in group_keys(indexes) {
+            tables.entry(table).or_default().indexes.push(columns);
+        }
+
+        Ok(tables)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(primary_key: &[&str], foreign_keys: &[&[&str]], indexes: &[&[&str]]) -> Table {
+        let names = |columns: &[&str]| columns.iter().map(|c| c.to_string()).collect();
+
+        Table {
+            columns: Vec::new(),
+            primary_key: names(primary_key),
+            foreign_keys: foreign_keys.iter().map(|key| names(key)).collect(),
+            indexes: indexes.iter().map(|index| names(index)).collect(),
+        }
+    }
+
+    fn lints(table: &Table) -> Vec<&'static str> {
+        check("t", table).iter().map(|issue| issue.lint).collect()
+    }
+
+    #[test]
+    fn it_reports_missing_primary_keys() {
+        assert_eq!(lints(&table(&[], &[], &[])), ["missing-primary-key"]);
+        assert!(lints(&table(&["id"], &[], &[])).is_empty());
+    }
+
+    #[test]
+    fn it_reports_unindexed_foreign_keys() {
+        assert_eq!(
+            lints(&table(&["id"], &[&["a"]], &[&["b", "a"]])),
+            ["unindexed-foreign-key"]
+        );
+        assert!(lints(&table(&["id"], &[&["a", "b"]], &[&["b", "a", "c"]])).is_empty());
+        assert!(lints(&table(&["a", "b"], &[&["a"]], &[])).is_empty());
+    }
+
+    #[test]
+    fn it_reports_varchar_without_length() {
+        let mut t = table(&["id"], &[], &[]);
+        t.columns = vec![
+            Column {
+                name: "a".into(),
+                type_name: "character varying".into(),
+                nullable: false,
+            },
+            Column {
+                name: "b".into(),
+                type_name: "character varying(255)".into(),
+                nullable: false,
+            },
+        ];
+
+        assert_eq!(lints(&t), ["varchar-without-length"]);
+    }
+}
@@ -3,6 +3,8 @@ use cargo_metadata::{
     Metadata as CargoMetadata, Package as MetadataPackage, PackageId as MetadataId,
 };
 
+use crate::opt::CargoFeatures;
+
 use std::{
     collections::{btree_map, BTreeMap, BTreeSet},
     path::{Path, PathBuf},
@@ -17,6 +19,9 @@ use std::{
 pub struct Package {
     name: String,
     src_paths: Vec<PathBuf>,
+    manifest_dir: PathBuf,
+    /// The features to enable when preparing the package, from `package.metadata.sqlx`
+    prepare_features: CargoFeatures,
 }
 
 impl Package {
@@ -27,6 +32,14 @@ impl Package {
     pub fn src_paths(&self) -> &[PathBuf] {
         &self.src_paths
     }
+
+    pub fn manifest_dir(&self) -> &Path {
+        &self.manifest_dir
+    }
+
+    pub fn prepare_features(&self) -> &CargoFeatures {
+        &self.prepare_features
+    }
 }
 
 impl From<&MetadataPackage> for Package {
@@ -37,8 +50,40 @@ impl From<&MetadataPackage> for Package {
             .iter()
             .map(|target| target.src_path.clone().into_std_path_buf())
             .collect();
-
-        Self { name, src_paths }
+        let manifest_dir = package
+            .manifest_path
+            .parent()
+            .map(|dir| dir.to_owned().into_std_path_buf())
+            .unwrap_or_default();
+        let flag = |key: &str| {
+            package
+                .metadata
+                .pointer(&format!("/sqlx/{}", key))
+                .and_then(|flag| flag.as_bool())
+                .unwrap_or_default()
+        };
+        let prepare_features = CargoFeatures {
+            features: package
+                .metadata
+                .pointer("/sqlx/features")
+                .and_then(|features| features.as_array())
+                .map(|features| {
+                    features
+                        .iter()
+                        .filter_map(|feature| feature.as_str().map(str::to_owned))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            all_features: flag("all-features"),
+            no_default_features: flag("no-default-features"),
+        };
+
+        Self {
+            name,
+            src_paths,
+            manifest_dir,
+            prepare_features,
+        }
     }
 }
 
@@ -56,6 +101,8 @@ pub struct Metadata {
     ///
     /// Typically `target` at the workspace root, but can be overridden
     target_directory: PathBuf,
+    /// The root directory of the workspace
+    workspace_root: PathBuf,
 }
 
 impl Metadata {
@@ -75,6 +122,10 @@ impl Metadata {
         &self.target_directory
     }
 
+    pub fn workspace_root(&self) -> &Path {
+        &self.workspace_root
+    }
+
     /// Gets all dependents (direct and transitive) of `id`
     pub fn all_dependents_of(&self, id: &MetadataId) -> BTreeSet<&MetadataId> {
         let mut dependents = BTreeSet::new();
@@ -106,6 +157,7 @@ impl FromStr for Metadata {
             workspace_members,
             resolve,
             target_directory,
+            workspace_root,
             ..
         } = serde_json::from_str(s)?;
 
@@ -130,12 +182,14 @@ impl FromStr for Metadata {
         }
 
         let target_directory = target_directory.into_std_path_buf();
+        let workspace_root = workspace_root.into_std_path_buf();
 
         Ok(Self {
             packages,
             workspace_members,
             reverse_deps,
             target_directory,
+            workspace_root,
         })
     }
 }
@@ -1,13 +1,20 @@
-use crate::opt::ConnectOpts;
+use crate::opt::{ConnectOpts, InfoFormat};
 use anyhow::{bail, Context};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use console::style;
-use sqlx::migrate::{AppliedMigration, Migrate, MigrateError, MigrationType, Migrator};
+use serde_json::json;
+use sqlx::migrate::{
+    AppliedMigration, Migrate, MigrateError, Migration, MigrationLock, MigrationType, Migrator,
+    OutOfOrder,
+};
+use sqlx::AnyConnection;
 use std::borrow::Cow;
-use std::collections::{HashMap, HashSet};
+use std::cmp;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::Write;
 use std::fs::{self, File};
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 fn create_file(
@@ -15,9 +22,8 @@ fn create_file(
     file_prefix: &str,
     description: &str,
     migration_type: MigrationType,
+    content: &str,
 ) -> anyhow::Result<()> {
-    use std::path::PathBuf;
-
     let mut file_name = file_prefix.to_string();
     file_name.push_str("_");
     file_name.push_str(&description.replace(' ', "_"));
@@ -31,24 +37,52 @@ fn create_file(
 
     let mut file = File::create(&path).context("Failed to create migration file")?;
 
-    std::io::Write::write_all(&mut file, migration_type.file_content().as_bytes())?;
+    std::io::Write::write_all(&mut file, content.as_bytes())?;
 
     Ok(())
 }
 
-pub async fn add(
+/// The content of a new migration file: the template for its type in the migrations directory,
+/// e.g. `.template.up.sql`, with `{version}`, `{description}` and `{timestamp}` replaced, or a
+/// placeholder comment if there is none.
+fn file_content(
+    migration_source: &str,
+    migration_type: MigrationType,
+    version: &str,
+    description: &str,
+    created_at: DateTime<Utc>,
+) -> anyhow::Result<String> {
+    let path = Path::new(migration_source).join(format!(".template{}", migration_type.suffix()));
+
+    let template = match fs::read_to_string(&path) {
+        Ok(template) => template,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Ok(migration_type.file_content().to_owned())
+        }
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read {}", path.display()));
+        }
+    };
+
+    Ok(template
+        .replace("{version}", version)
+        .replace("{description}", description)
+        .replace("{timestamp}", &created_at.to_rfc3339()))
+}
+
+/// Create the file(s) of a new migration, returning whether it is the first one of the source.
+pub(crate) async fn create(
     migration_source: &str,
     description: &str,
     reversible: bool,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<bool> {
     fs::create_dir_all(migration_source).context("Unable to create migrations directory")?;
 
-    // if the migrations directory is empty
-    let has_existing_migrations = fs::read_dir(migration_source)
-        .map(|mut dir| dir.next().is_some())
-        .unwrap_or(false);
-
     let migrator = Migrator::new(Path::new(migration_source)).await?;
+
+    // the directory may only contain templates
+    let has_existing_migrations = migrator.iter().next().is_some();
+
     // This checks if all existing migrations are of the same type as the reverisble flag passed
     for migration in migrator.iter() {
         if migration.migration_type.is_reversible() != reversible {
@@ -56,20 +90,56 @@ pub async fn add(
         }
     }
 
+    let collisions = version_collisions(&migrator);
+
+    for (version, descriptions) in &collisions {
+        println!(
+            "{} migrations {} share the version {}",
+            style("warning:").yellow(),
+            descriptions
+                .iter()
+                .map(|description| format!("`{}`", description))
+                .collect::<Vec<_>>()
+                .join(", "),
+            style(version).cyan()
+        );
+    }
+
+    if !collisions.is_empty() {
+        println!(
+            "{} run `sqlx migrate renumber` to give a new version to those not applied yet",
+            style("hint:").cyan()
+        );
+    }
+
     let dt = Utc::now();
-    let file_prefix = dt.format("%Y%m%d%H%M%S").to_string();
+    let file_prefix = new_version(&migrator, dt)?.to_string();
     if reversible {
         create_file(
             migration_source,
             &file_prefix,
             description,
             MigrationType::ReversibleUp,
+            &file_content(
+                migration_source,
+                MigrationType::ReversibleUp,
+                &file_prefix,
+                description,
+                dt,
+            )?,
         )?;
         create_file(
             migration_source,
             &file_prefix,
             description,
             MigrationType::ReversibleDown,
+            &file_content(
+                migration_source,
+                MigrationType::ReversibleDown,
+                &file_prefix,
+                description,
+                dt,
+            )?,
         )?;
     } else {
         create_file(
@@ -77,11 +147,27 @@ pub async fn add(
             &file_prefix,
             description,
             MigrationType::Simple,
+            &file_content(
+                migration_source,
+                MigrationType::Simple,
+                &file_prefix,
+                description,
+                dt,
+            )?,
         )?;
     }
 
-    if !has_existing_migrations {
-        let quoted_source = if migration_source != "migrations" {
+    Ok(!has_existing_migrations)
+}
+
+pub async fn add(
+    migration_source: &str,
+    description: &str,
+    reversible: bool,
+) -> anyhow::Result<()> {
+    if create(migration_source, description, reversible).await? {
+        // `migrate!()` reads the directory set in `sqlx.toml` as well
+        let quoted_source = if migration_source != crate::config::migrations_dir() {
             format!("{:?}", migration_source)
         } else {
             "".to_string()
@@ -108,6 +194,60 @@ See: https://docs.rs/sqlx/0.5/sqlx/macro.migrate.html
     Ok(())
 }
 
+/// Create a simple migration running `sql`, e.g. generated, failing if the existing migrations are
+/// reversible.
+pub(crate) async fn create_with_sql(
+    migration_source: &str,
+    description: &str,
+    sql: &str,
+) -> anyhow::Result<()> {
+    fs::create_dir_all(migration_source).context("Unable to create migrations directory")?;
+
+    let migrator = Migrator::new(Path::new(migration_source)).await?;
+
+    if migrator.iter().any(|m| m.migration_type.is_reversible()) {
+        bail!(MigrateError::InvalidMixReversibleAndSimple);
+    }
+
+    let version = new_version(&migrator, Utc::now())?;
+
+    create_file(
+        migration_source,
+        &version.to_string(),
+        description,
+        MigrationType::Simple,
+        sql,
+    )
+}
+
+/// The version of a migration created at `created_at`, after those of the migrations created in
+/// the same second, e.g. on another branch.
+fn new_version(migrator: &Migrator, created_at: DateTime<Utc>) -> anyhow::Result<i64> {
+    let mut version: i64 = created_at.format("%Y%m%d%H%M%S").to_string().parse()?;
+
+    while migrator.iter().any(|m| m.version == version) {
+        version += 1;
+    }
+
+    Ok(version)
+}
+
+/// The versions shared by migrations with different descriptions, e.g. added on two branches in
+/// the same second, with the descriptions of those migrations.
+fn version_collisions(migrator: &Migrator) -> BTreeMap<i64, BTreeSet<&str>> {
+    let mut descriptions: BTreeMap<i64, BTreeSet<&str>> = BTreeMap::new();
+
+    for migration in migrator.iter() {
+        descriptions
+            .entry(migration.version)
+            .or_default()
+            .insert(&migration.description);
+    }
+
+    descriptions.retain(|_, descriptions| descriptions.len() > 1);
+    descriptions
+}
+
 fn short_checksum(checksum: &[u8]) -> String {
     let mut s = String::with_capacity(checksum.len() * 2);
     for b in checksum {
@@ -116,19 +256,30 @@ fn short_checksum(checksum: &[u8]) -> String {
     s
 }
 
-pub async fn info(migration_source: &str, connect_opts: &ConnectOpts) -> anyhow::Result<()> {
+pub async fn info(
+    migration_source: &str,
+    connect_opts: &ConnectOpts,
+    table_name: &str,
+    format: InfoFormat,
+) -> anyhow::Result<()> {
     let migrator = Migrator::new(Path::new(migration_source)).await?;
     let mut conn = crate::connect(&connect_opts).await?;
 
-    conn.ensure_migrations_table().await?;
+    conn.ensure_migrations_table(table_name).await?;
 
     let applied_migrations: HashMap<_, _> = conn
-        .list_applied_migrations()
+        .list_applied_migrations(table_name)
         .await?
         .into_iter()
         .map(|m| (m.version, m))
         .collect();
 
+    if format == InfoFormat::Json {
+        let dirty_version = conn.dirty_version(table_name).await?;
+
+        return print_info_json(&migrator, &applied_migrations, dirty_version);
+    }
+
     for migration in migrator.iter() {
         if migration.migration_type.is_down_migration() {
             // Skipping down migrations
@@ -173,6 +324,70 @@ pub async fn info(migration_source: &str, connect_opts: &ConnectOpts) -> anyhow:
     Ok(())
 }
 
+// the migrations and their state, for deployment pipelines to read
+fn print_info_json(
+    migrator: &Migrator,
+    applied_migrations: &HashMap<i64, AppliedMigration>,
+    dirty_version: Option<i64>,
+) -> anyhow::Result<()> {
+    let migrations: Vec<_> = migrator
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .collect();
+
+    let pending: Vec<_> = migrations
+        .iter()
+        .filter(|migration| !applied_migrations.contains_key(&migration.version))
+        .map(|migration| migration.version)
+        .collect();
+
+    let mut missing: Vec<_> = applied_migrations
+        .keys()
+        .filter(|&&version| !migrations.iter().any(|m| m.version == version))
+        .copied()
+        .collect();
+    missing.sort_unstable();
+
+    let migrations: Vec<_> = migrations
+        .iter()
+        .map(|migration| {
+            let applied = applied_migrations.get(&migration.version);
+
+            json!({
+                "version": migration.version,
+                "description": migration.description,
+                "applied": applied.is_some(),
+                "checksum": short_checksum(&migration.checksum),
+                "applied_checksum": applied.map(|applied| short_checksum(&applied.checksum)),
+                "execution_time_ns": applied.map(|applied| applied.execution_time.as_nanos() as u64),
+            })
+        })
+        .collect();
+
+    let info = json!({
+        "dirty_version": dirty_version,
+        "pending": pending,
+        "missing": missing,
+        "migrations": migrations,
+    });
+
+    println!("{}", serde_json::to_string_pretty(&info)?);
+
+    Ok(())
+}
+
+fn is_squash(migration: &Migration) -> bool {
+    !migration.migration_type.is_down_migration()
+        && migration.headers().map_or(false, |headers| headers.squash)
+}
+
+fn is_resumable(migration: &Migration) -> bool {
+    !migration.migration_type.is_down_migration()
+        && migration
+            .headers()
+            .map_or(false, |headers| headers.resumable)
+}
+
 fn validate_applied_migrations(
     applied_migrations: &[AppliedMigration],
     migrator: &Migrator,
@@ -183,9 +398,17 @@ fn validate_applied_migrations(
     }
 
     let migrations: HashSet<_> = migrator.iter().map(|m| m.version).collect();
+    // the files of squashed migrations are expected to be gone
+    let squashed = migrator
+        .iter()
+        .filter(|m| is_squash(m))
+        .map(|m| m.version)
+        .max()
+        .unwrap_or(i64::MIN);
 
     for applied_migration in applied_migrations {
-        if !migrations.contains(&applied_migration.version) {
+        if applied_migration.version > squashed && !migrations.contains(&applied_migration.version)
+        {
             return Err(MigrateError::VersionMissing(applied_migration.version));
         }
     }
@@ -193,47 +416,248 @@ fn validate_applied_migrations(
     Ok(())
 }
 
+// lock the database for exclusive access, unless `--skip-lock` was given
+async fn lock_database(
+    conn: &mut AnyConnection,
+    lock: Option<MigrationLock>,
+) -> anyhow::Result<()> {
+    if let Some(lock) = lock {
+        conn.lock(lock).await?;
+    }
+
+    Ok(())
+}
+
+// release the lock taken by `lock_database` whether what was done while holding it succeeded or
+// not, the result of which takes precedence
+async fn unlock_database<T>(
+    conn: &mut AnyConnection,
+    lock: Option<MigrationLock>,
+    result: anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    let unlocked = match lock {
+        Some(lock) => conn.unlock(lock).await,
+        None => Ok(()),
+    };
+
+    let value = result?;
+    unlocked?;
+
+    Ok(value)
+}
+
 pub async fn run(
     migration_source: &str,
     connect_opts: &ConnectOpts,
+    table_name: &str,
+    lock: Option<MigrationLock>,
     dry_run: bool,
     ignore_missing: bool,
+    target_version: Option<i64>,
+    allow_checksum_mismatch: &[i64],
+    out_of_order: OutOfOrder,
+    dump_schema: Option<&str>,
 ) -> anyhow::Result<()> {
     let migrator = Migrator::new(Path::new(migration_source)).await?;
     let mut conn = crate::connect(connect_opts).await?;
 
-    conn.ensure_migrations_table().await?;
+    lock_database(&mut conn, lock).await?;
 
-    let version = conn.dirty_version().await?;
-    if let Some(version) = version {
-        bail!(MigrateError::Dirty(version));
-    }
+    let result: anyhow::Result<_> = async {
+        conn.ensure_migrations_table(table_name).await?;
 
-    let applied_migrations = conn.list_applied_migrations().await?;
-    validate_applied_migrations(&applied_migrations, &migrator, ignore_missing)?;
+        // a resumable migration which failed is applied again, from the statement that failed
+        let resumed = conn.dirty_version(table_name).await?;
+        if let Some(version) = resumed {
+            if !migrator
+                .iter()
+                .any(|m| m.version == version && is_resumable(m))
+            {
+                bail!(MigrateError::Dirty(version));
+            }
+        }
 
-    let applied_migrations: HashMap<_, _> = applied_migrations
-        .into_iter()
-        .map(|m| (m.version, m))
-        .collect();
+        let applied_migrations = conn.list_applied_migrations(table_name).await?;
+        validate_applied_migrations(&applied_migrations, &migrator, ignore_missing)?;
 
-    for migration in migrator.iter() {
-        if migration.migration_type.is_down_migration() {
-            // Skipping down migrations
-            continue;
+        let applied_migrations: HashMap<_, _> = applied_migrations
+            .into_iter()
+            .filter(|m| Some(m.version) != resumed)
+            .map(|m| (m.version, m))
+            .collect();
+
+        let latest = applied_migrations.keys().copied().max().unwrap_or(i64::MIN);
+
+        if let Some(target) = target_version {
+            if !migrator.iter().any(|m| m.version == target) {
+                bail!(MigrateError::VersionNotPresent(target));
+            }
+
+            // going down is for `revert`
+            if target < latest {
+                bail!(MigrateError::VersionTooOld(target, latest));
+            }
         }
 
-        match applied_migrations.get(&migration.version) {
-            Some(applied_migration) => {
-                if migration.checksum != applied_migration.checksum {
-                    bail!(MigrateError::VersionMismatch(migration.version));
+        for migration in migrator.iter() {
+            if migration.migration_type.is_down_migration() {
+                // Skipping down migrations
+                continue;
+            }
+
+            if Some(migration.version) == resumed && !dry_run {
+                println!(
+                    "Resuming {}/{} {}",
+                    style(migration.version).cyan(),
+                    style(migration.migration_type.label()).green(),
+                    migration.description,
+                );
+            }
+
+            let squash = is_squash(migration);
+
+            match applied_migrations.get(&migration.version) {
+                // either the squash migration itself or the last migration it replaces
+                Some(_) if squash => {}
+                Some(applied_migration) => {
+                    if migration.checksum != applied_migration.checksum {
+                        if !allow_checksum_mismatch.contains(&migration.version) {
+                            bail!(MigrateError::VersionMismatch(migration.version));
+                        }
+
+                        println!(
+                            "{} migration {} was previously applied but has been modified",
+                            style("warning:").yellow(),
+                            style(migration.version).cyan(),
+                        );
+                    }
+                }
+                None if squash && applied_migrations.keys().any(|&v| v < migration.version) => {
+                    bail!(MigrateError::PartiallySquashed(migration.version));
+                }
+                None if target_version.map_or(false, |target| migration.version > target) => {}
+                None if migration.version < latest && out_of_order != OutOfOrder::ApplyMissing => {
+                    if out_of_order == OutOfOrder::Error {
+                        bail!(MigrateError::OutOfOrder(migration.version));
+                    }
+
+                    println!(
+                        "{} migration {} is older than the latest applied migration {}, skipping it",
+                        style("warning:").yellow(),
+                        style(migration.version).cyan(),
+                        style(latest).cyan(),
+                    );
+                }
+                None => {
+                    let elapsed = if dry_run {
+                        Duration::new(0, 0)
+                    } else {
+                        conn.apply(table_name, migration).await?
+                    };
+                    let text = if dry_run { "Can apply" } else { "Applied" };
+
+                    println!(
+                        "{} {}/{} {} {}",
+                        text,
+                        style(migration.version).cyan(),
+                        style(migration.migration_type.label()).green(),
+                        migration.description,
+                        style(format!("({:?})", elapsed)).dim()
+                    );
+
+                    if dry_run {
+                        println!("{}\n", migration.sql.trim_end());
+                    }
                 }
             }
-            None => {
+        }
+
+        if let (Some(path), false) = (dump_schema, dry_run) {
+            let schema = conn.dump_schema(table_name).await?;
+            fs::write(path, schema).with_context(|| format!("Failed to write {}", path))?;
+
+            println!("Wrote schema to {}", style(path).cyan());
+        }
+
+        Ok(())
+    }
+    .await;
+
+    unlock_database(&mut conn, lock, result).await
+}
+
+pub async fn revert(
+    migration_source: &str,
+    connect_opts: &ConnectOpts,
+    table_name: &str,
+    lock: Option<MigrationLock>,
+    dry_run: bool,
+    ignore_missing: bool,
+    target_version: Option<i64>,
+    confirm: bool,
+) -> anyhow::Result<()> {
+    if !crate::database::confirm_if_protected(
+        format!(
+            "Revert migrations of the database at {}?",
+            style(&connect_opts.database_url).cyan()
+        ),
+        confirm && !dry_run,
+    )? {
+        return Ok(());
+    }
+
+    let migrator = Migrator::new(Path::new(migration_source)).await?;
+    let mut conn = crate::connect(&connect_opts).await?;
+
+    lock_database(&mut conn, lock).await?;
+
+    let result: anyhow::Result<_> = async {
+        conn.ensure_migrations_table(table_name).await?;
+
+        let version = conn.dirty_version(table_name).await?;
+        if let Some(version) = version {
+            bail!(MigrateError::Dirty(version));
+        }
+
+        let applied_migrations = conn.list_applied_migrations(table_name).await?;
+        validate_applied_migrations(&applied_migrations, &migrator, ignore_missing)?;
+
+        let applied_migrations: HashMap<_, _> = applied_migrations
+            .into_iter()
+            .map(|m| (m.version, m))
+            .collect();
+
+        if let Some(target) = target_version {
+            let latest = applied_migrations.keys().copied().max().unwrap_or(0);
+
+            // 0 reverts every migration
+            if target != 0 && !migrator.iter().any(|m| m.version == target) {
+                bail!(MigrateError::VersionNotPresent(target));
+            }
+
+            // going up is for `run`
+            if target > latest {
+                bail!(MigrateError::VersionTooNew(target, latest));
+            }
+        }
+
+        let mut is_applied = false;
+        for migration in migrator.iter().rev() {
+            if !migration.migration_type.is_down_migration() {
+                // Skipping non down migration
+                // This will skip any simple or up migration file
+                continue;
+            }
+
+            if target_version.map_or(false, |target| migration.version <= target) {
+                break;
+            }
+
+            if applied_migrations.contains_key(&migration.version) {
                 let elapsed = if dry_run {
                     Duration::new(0, 0)
                 } else {
-                    conn.apply(migration).await?
+                    conn.revert(table_name, migration).await?
                 };
                 let text = if dry_run { "Can apply" } else { "Applied" };
 
@@ -245,50 +669,489 @@ pub async fn run(
                     migration.description,
                     style(format!("({:?})", elapsed)).dim()
                 );
+
+                if dry_run {
+                    println!("{}\n", migration.sql.trim_end());
+                }
+
+                is_applied = true;
+
+                // Without a target version, only a single migration will be reverted at a time
+                if target_version.is_none() {
+                    break;
+                }
+            }
+        }
+        if !is_applied {
+            println!("No migrations available to revert");
+        }
+
+        Ok(())
+    }
+    .await;
+
+    unlock_database(&mut conn, lock, result).await
+}
+
+pub async fn repair(
+    migration_source: &str,
+    connect_opts: &ConnectOpts,
+    table_name: &str,
+    lock: Option<MigrationLock>,
+    confirm: bool,
+    ignore_missing: bool,
+) -> anyhow::Result<()> {
+    let migrator = Migrator::new(Path::new(migration_source)).await?;
+    let mut conn = crate::connect(connect_opts).await?;
+
+    lock_database(&mut conn, lock).await?;
+
+    let result: anyhow::Result<_> = async {
+        conn.ensure_migrations_table(table_name).await?;
+
+        let applied_migrations = conn.list_applied_migrations(table_name).await?;
+        validate_applied_migrations(&applied_migrations, &migrator, ignore_missing)?;
+
+        let applied_migrations: HashMap<_, _> = applied_migrations
+            .into_iter()
+            .map(|m| (m.version, m))
+            .collect();
+
+        let modified: Vec<_> = migrator
+            .iter()
+            .filter(|m| !m.migration_type.is_down_migration() && !is_squash(m))
+            .filter(|m| {
+                applied_migrations
+                    .get(&m.version)
+                    .map_or(false, |applied| applied.checksum != m.checksum)
+            })
+            .collect();
+
+        if modified.is_empty() {
+            println!("No migrations have been modified");
+            return Ok(());
+        }
+
+        for migration in &modified {
+            println!(
+                "Modified {}/{} {}",
+                style(migration.version).cyan(),
+                style(migration.migration_type.label()).green(),
+                migration.description,
+            );
+        }
+
+        if confirm
+            && !crate::database::ask_to_continue(format!(
+                "Update the checksums of {} migration(s) without running them again?",
+                modified.len()
+            ))
+        {
+            return Ok(());
+        }
+
+        for migration in modified {
+            conn.update_checksum(table_name, migration).await?;
+
+            println!(
+                "Repaired {}/{} {}",
+                style(migration.version).cyan(),
+                style(migration.migration_type.label()).green(),
+                migration.description,
+            );
+        }
+
+        Ok(())
+    }
+    .await;
+
+    unlock_database(&mut conn, lock, result).await
+}
+
+/// Give a new version to the migrations sharing their version with another, e.g. after merging
+/// branches which added migrations in the same second, except the one applied, if any.
+pub async fn renumber(
+    migration_source: &str,
+    connect_opts: &ConnectOpts,
+    table_name: &str,
+    lock: Option<MigrationLock>,
+    confirm: bool,
+) -> anyhow::Result<()> {
+    let migrator = Migrator::new(Path::new(migration_source)).await?;
+    let collisions = version_collisions(&migrator);
+
+    if collisions.is_empty() {
+        println!("No migrations share a version");
+        return Ok(());
+    }
+
+    let mut conn = crate::connect(connect_opts).await?;
+
+    lock_database(&mut conn, lock).await?;
+
+    let result: anyhow::Result<_> = async {
+        conn.ensure_migrations_table(table_name).await?;
+
+        let applied_migrations: HashMap<_, _> = conn
+            .list_applied_migrations(table_name)
+            .await?
+            .into_iter()
+            .map(|m| (m.version, m))
+            .collect();
+
+        // the new versions come after every migration, applied or not
+        let latest = migrator
+            .iter()
+            .map(|m| m.version)
+            .chain(applied_migrations.keys().copied())
+            .max()
+            .unwrap_or_default();
+        let now: i64 = Utc::now().format("%Y%m%d%H%M%S").to_string().parse()?;
+        let mut next_version = cmp::max(now, latest + 1);
+
+        let mut renames = Vec::new();
+
+        for (version, descriptions) in collisions {
+            // the migration applied with this version keeps it; if another migration was applied
+            // with it, none of the local ones are applied
+            let kept = match applied_migrations.get(&version) {
+                Some(applied) => migrator
+                    .iter()
+                    .find(|m| {
+                        m.version == version
+                            && !m.migration_type.is_down_migration()
+                            && m.checksum == applied.checksum
+                    })
+                    .map(|m| &*m.description),
+                None => descriptions.iter().next().copied(),
+            };
+
+            for description in descriptions {
+                if Some(description) == kept {
+                    continue;
+                }
+
+                for file in migration_files(migration_source, version, description)? {
+                    let file_name = file.file_name().and_then(|name| name.to_str());
+                    let rest = file_name
+                        .and_then(|name| name.split_once('_'))
+                        .map(|(_, rest)| rest);
+
+                    if let Some(rest) = rest {
+                        let renamed = file.with_file_name(format!("{}_{}", next_version, rest));
+                        renames.push((file, renamed));
+                    }
+                }
+
+                next_version += 1;
             }
         }
+
+        for (file, renamed) in &renames {
+            println!(
+                "Renaming {} to {}",
+                style(file.display()).cyan(),
+                style(renamed.display()).cyan()
+            );
+        }
+
+        if confirm
+            && !crate::database::ask_to_continue(format!(
+                "Rename {} migration file(s)?",
+                renames.len()
+            ))
+        {
+            return Ok(());
+        }
+
+        for (file, renamed) in renames {
+            fs::rename(&file, &renamed)
+                .with_context(|| format!("Failed to rename {}", file.display()))?;
+        }
+
+        Ok(())
+    }
+    .await;
+
+    unlock_database(&mut conn, lock, result).await
+}
+
+/// The files of the migration of `version` and `description`, up and down.
+fn migration_files(
+    migration_source: &str,
+    version: i64,
+    description: &str,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(migration_source)? {
+        let path = entry?.path();
+
+        let matches = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.split_once('_'))
+            .map_or(false, |(file_version, rest)| {
+                let migration_type = MigrationType::from_filename(rest);
+                let file_description = rest.trim_end_matches(migration_type.suffix());
+
+                file_version.parse() == Ok(version)
+                    && file_description.replace('_', " ") == description
+            });
+
+        if matches {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+pub async fn baseline(
+    migration_source: &str,
+    connect_opts: &ConnectOpts,
+    table_name: &str,
+    lock: Option<MigrationLock>,
+    version: i64,
+    confirm: bool,
+    ignore_missing: bool,
+) -> anyhow::Result<()> {
+    let migrator = Migrator::new(Path::new(migration_source)).await?;
+    let mut conn = crate::connect(connect_opts).await?;
+
+    lock_database(&mut conn, lock).await?;
+
+    let result: anyhow::Result<_> = async {
+        conn.ensure_migrations_table(table_name).await?;
+
+        let dirty = conn.dirty_version(table_name).await?;
+        if let Some(dirty) = dirty {
+            bail!(MigrateError::Dirty(dirty));
+        }
+
+        let applied_migrations = conn.list_applied_migrations(table_name).await?;
+        validate_applied_migrations(&applied_migrations, &migrator, ignore_missing)?;
+
+        let applied_migrations: HashSet<_> = applied_migrations.iter().map(|m| m.version).collect();
+
+        let pending: Vec<_> = migrator
+            .iter()
+            .filter(|m| !m.migration_type.is_down_migration())
+            .filter(|m| m.version <= version && !applied_migrations.contains(&m.version))
+            .collect();
+
+        if pending.is_empty() {
+            println!("No migrations available to mark as applied");
+            return Ok(());
+        }
+
+        if confirm
+            && !crate::database::ask_to_continue(format!(
+                "Mark {} migration(s) as applied without running them?",
+                pending.len()
+            ))
+        {
+            return Ok(());
+        }
+
+        for migration in pending {
+            conn.record_applied(table_name, migration).await?;
+
+            println!(
+                "Marked {}/{} {}",
+                style(migration.version).cyan(),
+                style(migration.migration_type.label()).green(),
+                migration.description,
+            );
+        }
+
+        Ok(())
+    }
+    .await;
+
+    unlock_database(&mut conn, lock, result).await
+}
+
+pub async fn squash(migration_source: &str, version: i64, confirm: bool) -> anyhow::Result<()> {
+    let migrator = Migrator::new(Path::new(migration_source)).await?;
+
+    let squashed: Vec<_> = migrator.iter().filter(|m| m.version <= version).collect();
+
+    let last = match squashed.iter().map(|m| m.version).max() {
+        Some(last) => last,
+        None => {
+            println!("No migrations available to squash");
+            return Ok(());
+        }
+    };
+
+    let mut up = String::from("-- sqlx:squash\n");
+    let mut down = String::new();
+
+    for migration in &squashed {
+        let headers = migration.headers()?;
+        if headers.no_transaction {
+            bail!(
+                "migration {} must run outside of a transaction and cannot be squashed",
+                migration.version
+            );
+        }
+
+        // headers only apply at the top of the squashed file
+        let sql: String = migration
+            .sql
+            .lines()
+            .filter(|line| {
+                !line
+                    .trim_start()
+                    .strip_prefix("--")
+                    .map_or(false, |comment| comment.trim_start().starts_with("sqlx:"))
+            })
+            .map(|line| format!("{}\n", line))
+            .collect();
+
+        let section = format!(
+            "\n-- {} {}\n{}",
+            migration.version,
+            migration.description,
+            sql.trim()
+        );
+
+        if migration.migration_type.is_down_migration() {
+            // reverted in the opposite order
+            down.insert_str(0, &format!("{}\n", section));
+        } else {
+            writeln!(up, "{}", section)?;
+        }
+    }
+
+    // the files of the squashed migrations, including down migrations
+    let mut files = Vec::new();
+    for entry in fs::read_dir(migration_source)? {
+        let path = entry?.path();
+        let file_version = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.split_once('_'))
+            .and_then(|(version, _)| version.parse::<i64>().ok());
+
+        if file_version.map_or(false, |v| v <= last) {
+            files.push(path);
+        }
+    }
+    files.sort();
+
+    for file in &files {
+        println!("Removing {}", style(file.display()).cyan());
+    }
+
+    if confirm
+        && !crate::database::ask_to_continue(format!(
+            "Replace {} migration file(s) with a single migration?",
+            files.len()
+        ))
+    {
+        return Ok(());
+    }
+
+    for file in &files {
+        fs::remove_file(file)?;
+    }
+
+    let reversible = squashed.iter().any(|m| m.migration_type.is_reversible());
+    let file_prefix = last.to_string();
+
+    if reversible {
+        create_file(
+            migration_source,
+            &file_prefix,
+            "squashed",
+            MigrationType::ReversibleUp,
+            &up,
+        )?;
+        create_file(
+            migration_source,
+            &file_prefix,
+            "squashed",
+            MigrationType::ReversibleDown,
+            down.trim_start(),
+        )?;
+    } else {
+        create_file(
+            migration_source,
+            &file_prefix,
+            "squashed",
+            MigrationType::Simple,
+            &up,
+        )?;
     }
 
     Ok(())
 }
 
-pub async fn revert(
+pub async fn redo(
     migration_source: &str,
     connect_opts: &ConnectOpts,
+    table_name: &str,
+    lock: Option<MigrationLock>,
     dry_run: bool,
     ignore_missing: bool,
+    confirm: bool,
 ) -> anyhow::Result<()> {
+    if !crate::database::confirm_if_protected(
+        format!(
+            "Redo the latest migration of the database at {}?",
+            style(&connect_opts.database_url).cyan()
+        ),
+        confirm && !dry_run,
+    )? {
+        return Ok(());
+    }
+
     let migrator = Migrator::new(Path::new(migration_source)).await?;
     let mut conn = crate::connect(&connect_opts).await?;
 
-    conn.ensure_migrations_table().await?;
+    lock_database(&mut conn, lock).await?;
 
-    let version = conn.dirty_version().await?;
-    if let Some(version) = version {
-        bail!(MigrateError::Dirty(version));
-    }
+    let result: anyhow::Result<_> = async {
+        conn.ensure_migrations_table(table_name).await?;
 
-    let applied_migrations = conn.list_applied_migrations().await?;
-    validate_applied_migrations(&applied_migrations, &migrator, ignore_missing)?;
+        let version = conn.dirty_version(table_name).await?;
+        if let Some(version) = version {
+            bail!(MigrateError::Dirty(version));
+        }
 
-    let applied_migrations: HashMap<_, _> = applied_migrations
-        .into_iter()
-        .map(|m| (m.version, m))
-        .collect();
+        let applied_migrations = conn.list_applied_migrations(table_name).await?;
+        validate_applied_migrations(&applied_migrations, &migrator, ignore_missing)?;
 
-    let mut is_applied = false;
-    for migration in migrator.iter().rev() {
-        if !migration.migration_type.is_down_migration() {
-            // Skipping non down migration
-            // This will skip any simple or up migration file
-            continue;
-        }
+        let latest = match applied_migrations.iter().map(|m| m.version).max() {
+            Some(latest) => latest,
+            None => {
+                println!("No migrations available to redo");
+                return Ok(());
+            }
+        };
 
-        if applied_migrations.contains_key(&migration.version) {
+        let find = |down: bool| {
+            migrator
+                .iter()
+                .find(|m| m.version == latest && m.migration_type.is_down_migration() == down)
+        };
+
+        let (up, down) = match (find(false), find(true)) {
+            (Some(up), Some(down)) => (up, down),
+            (Some(_), None) => bail!(MigrateError::NotReversible(latest)),
+            (None, _) => bail!(MigrateError::VersionMissing(latest)),
+        };
+
+        for migration in [down, up] {
             let elapsed = if dry_run {
                 Duration::new(0, 0)
+            } else if migration.migration_type.is_down_migration() {
+                conn.revert(table_name, migration).await?
             } else {
-                conn.revert(migration).await?
+                conn.apply(table_name, migration).await?
             };
             let text = if dry_run { "Can apply" } else { "Applied" };
 
@@ -300,17 +1163,13 @@ pub async fn revert(
                 migration.description,
                 style(format!("({:?})", elapsed)).dim()
             );
-
-            is_applied = true;
-            // Only a single migration will be reverted at a time, so we break
-            break;
         }
+
+        Ok(())
     }
-    if !is_applied {
-        println!("No migrations available to revert");
-    }
+    .await;
 
-    Ok(())
+    unlock_database(&mut conn, lock, result).await
 }
 
 pub fn build_script(migration_source: &str, force: bool) -> anyhow::Result<()> {
@@ -1,10 +1,34 @@
 use std::ops::{Deref, Not};
+use std::str::FromStr;
 
 use clap::{Args, Parser};
+use clap_complete::Shell;
+use sqlx::migrate::{CreateDatabaseOptions, MigrationLock, OutOfOrder};
+
+use crate::config;
+use std::env;
+use std::time::Duration;
 
 #[derive(Parser, Debug)]
 #[clap(version, about, author)]
 pub struct Opt {
+    /// The environment of `sqlx.toml` to run against, e.g. `staging`: its database URL is read
+    /// from the variable set by its `database-url-var`, and destructive commands must be
+    /// confirmed if it's `protected`.
+    #[clap(long, global = true, env = "SQLX_ENV", value_name = "NAME")]
+    pub env: Option<String>,
+
+    /// Print errors as `human` readable text, or as a `json` object on stderr with the kind of
+    /// the failure, which also sets the exit status.
+    #[clap(
+        long,
+        global = true,
+        env = "SQLX_ERROR_FORMAT",
+        value_name = "FORMAT",
+        default_value = "human"
+    )]
+    pub error_format: InfoFormat,
+
     #[clap(subcommand)]
     pub command: Command,
 }
@@ -16,8 +40,9 @@ pub enum Command {
 
     /// Generate query metadata to support offline compile-time verification.
     ///
-    /// Saves metadata for all invocations of `query!` and related macros to `sqlx-data.json`
-    /// in the current directory, overwriting if needed.
+    /// Saves metadata for all invocations of `query!` and related macros to the `.sqlx`
+    /// directory in the current directory, one file per query, overwriting if needed and removing
+    /// the files of queries that no longer exist.
     ///
     /// During project compilation, the absence of the `DATABASE_URL` environment variable or
     /// the presence of `SQLX_OFFLINE` (with a value of `true` or `1`) will constrain the
@@ -25,14 +50,26 @@ pub enum Command {
     #[clap(alias = "prep")]
     Prepare {
         /// Run in 'check' mode. Exits with 0 if the query metadata is up-to-date. Exits with
-        /// 1 if the query metadata needs updating.
+        /// 5 if the query metadata needs updating, listing the queries added, removed or changed,
+        /// and with 3 if the database can't be connected to.
         #[clap(long)]
         check: bool,
 
-        /// Generate a single top-level `sqlx-data.json` file when using a cargo workspace.
+        /// Generate a single top-level `.sqlx` directory when using a cargo workspace.
         #[clap(long)]
         merged: bool,
 
+        /// Prepare each member of the workspace using SQLx on its own, saving its queries in a
+        /// `.sqlx` directory next to its `Cargo.toml` (or at the workspace root with `--merged`).
+        ///
+        /// Each member is checked with the features set by the `features`, `all-features` and
+        /// `no-default-features` keys of its `[package.metadata.sqlx]` table, if any.
+        #[clap(long)]
+        workspace: bool,
+
+        #[clap(flatten)]
+        features: CargoFeatures,
+
         /// Arguments to be passed to `cargo rustc ...`.
         #[clap(last = true)]
         args: Vec<String>,
@@ -43,6 +80,212 @@ pub enum Command {
 
     #[clap(alias = "mig")]
     Migrate(MigrateOpt),
+
+    Seed(SeedOpt),
+
+    Fixture(FixtureOpt),
+
+    Generate(GenerateOpt),
+
+    /// Run SQL against the database and print the rows it returns, e.g. where `psql` or `mysql`
+    /// aren't installed.
+    Query {
+        /// The SQL to run; may contain several statements separated by `;`.
+        sql: String,
+
+        /// `table`, `csv` or `json`.
+        #[clap(long, value_name = "FORMAT", default_value = "table")]
+        format: QueryFormat,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+    },
+
+    /// Run SQL statements entered interactively, each ending with `;`, and print the rows they
+    /// return. Enter `\q` to quit.
+    Repl {
+        /// `table`, `csv` or `json`.
+        #[clap(long, value_name = "FORMAT", default_value = "table")]
+        format: QueryFormat,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+    },
+
+    /// Watch the migrations and the Rust sources invoking query macros, running `migrate run` and
+    /// `prepare` when a migration changes, and `prepare` when such a source changes.
+    ///
+    /// Both run once on startup. Errors are printed, and watching goes on until interrupted.
+    Watch {
+        #[clap(flatten)]
+        source: Source,
+
+        #[clap(flatten)]
+        table_name: MigrationsTable,
+
+        #[clap(flatten)]
+        lock: LockOpts,
+
+        #[clap(flatten)]
+        watch_opts: WatchOpts,
+
+        #[clap(flatten)]
+        features: CargoFeatures,
+
+        /// Arguments to be passed to `cargo` by `prepare`.
+        #[clap(last = true)]
+        args: Vec<String>,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+    },
+
+    /// Generate shell completions for the CLI, e.g. for bash:
+    /// `sqlx completions bash > /etc/bash_completion.d/sqlx`.
+    ///
+    /// The completions of bash, zsh and fish also complete the versions of migrations, read from
+    /// the migrations directory.
+    Completions {
+        /// The shell to generate completions for.
+        #[clap(value_enum)]
+        shell: Shell,
+    },
+
+    /// Print the versions and descriptions of the migrations, for the shell completions.
+    #[clap(name = "complete-migrations", hide = true)]
+    CompleteMigrations {
+        #[clap(flatten)]
+        source: Source,
+    },
+}
+
+impl Opt {
+    /// The options to connect to the database of the command, if it connects to one.
+    pub fn connect_opts_mut(&mut self) -> Option<&mut ConnectOpts> {
+        let connect_opts = match &mut self.command {
+            Command::Prepare { connect_opts, .. }
+            | Command::Query { connect_opts, .. }
+            | Command::Repl { connect_opts, .. }
+            | Command::Watch { connect_opts, .. } => connect_opts,
+
+            Command::Database(database) => match &mut database.command {
+                DatabaseCommand::Create { connect_opts, .. }
+                | DatabaseCommand::Drop { connect_opts, .. }
+                | DatabaseCommand::Reset { connect_opts, .. }
+                | DatabaseCommand::Setup { connect_opts, .. }
+                | DatabaseCommand::Diff { connect_opts, .. }
+                | DatabaseCommand::Lint { connect_opts, .. }
+                | DatabaseCommand::Dump { connect_opts, .. }
+                | DatabaseCommand::Restore { connect_opts, .. } => connect_opts,
+            },
+
+            Command::Migrate(migrate) => match &mut migrate.command {
+                MigrateCommand::Run { connect_opts, .. }
+                | MigrateCommand::Revert { connect_opts, .. }
+                | MigrateCommand::Redo { connect_opts, .. }
+                | MigrateCommand::Repair { connect_opts, .. }
+                | MigrateCommand::Baseline { connect_opts, .. }
+                | MigrateCommand::Renumber { connect_opts, .. }
+                | MigrateCommand::Info { connect_opts, .. } => connect_opts,
+                MigrateCommand::Add { .. }
+                | MigrateCommand::Squash { .. }
+                | MigrateCommand::BuildScript { .. } => return None,
+            },
+
+            Command::Seed(seed) => match &mut seed.command {
+                SeedCommand::Run { connect_opts, .. }
+                | SeedCommand::Revert { connect_opts, .. }
+                | SeedCommand::Info { connect_opts, .. } => connect_opts,
+                SeedCommand::Add { .. } => return None,
+            },
+
+            Command::Fixture(fixture) => match &mut fixture.command {
+                FixtureCommand::Apply { connect_opts, .. } => connect_opts,
+            },
+
+            Command::Generate(generate) => match &mut generate.command {
+                GenerateCommand::Models { connect_opts, .. } => connect_opts,
+            },
+
+            Command::Completions { .. } | Command::CompleteMigrations { .. } => return None,
+        };
+
+        Some(connect_opts)
+    }
+}
+
+/// Group of commands for loading fixtures: SQL files of data, e.g. for tests, development
+/// databases or demos, applied as they are rather than recorded like seed migrations.
+#[derive(Parser, Debug)]
+pub struct FixtureOpt {
+    #[clap(subcommand)]
+    pub command: FixtureCommand,
+}
+
+#[derive(Parser, Debug)]
+pub enum FixtureCommand {
+    /// Apply fixtures to the database, in the order given.
+    Apply {
+        /// The fixtures to apply: paths to SQL files, or names of `.sql` files in the fixtures
+        /// directory, e.g. `users` for `fixtures/users.sql`.
+        #[clap(required = true)]
+        fixtures: Vec<String>,
+
+        /// Path to folder containing fixtures.
+        #[clap(long, default_value = "fixtures")]
+        dir: String,
+
+        /// Delete the rows of every table first (except those recording migrations), resetting
+        /// their sequences.
+        #[clap(long)]
+        truncate_first: bool,
+
+        /// `all` to apply the fixtures in a single transaction, `file` for a transaction per
+        /// fixture, or `none`.
+        #[clap(long, value_name = "MODE", default_value = "all")]
+        transaction: FixtureTransaction,
+
+        #[clap(flatten)]
+        confirmation: Confirmation,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+    },
+}
+
+/// Group of commands for generating Rust code from the database.
+#[derive(Parser, Debug)]
+pub struct GenerateOpt {
+    #[clap(subcommand)]
+    pub command: GenerateCommand,
+}
+
+#[derive(Parser, Debug)]
+pub enum GenerateCommand {
+    /// Generate a struct deriving `FromRow` for each table and view of a schema, one file per
+    /// table in the output directory along with a `mod.rs` declaring them.
+    ///
+    /// Columns are mapped to the Rust types used by the query macros, including those of the
+    /// optional features of `sqlx` (e.g. `chrono`, `uuid` or `decimal`) enabled in `Cargo.toml`
+    /// in the current directory. Columns without a supported type are left out with a `TODO`.
+    Models {
+        /// The schema to generate models for, the current schema by default (the current
+        /// database in MySQL, `main` in SQLite).
+        #[clap(long)]
+        schema: Option<String>,
+
+        /// The directory to write the models to; existing files are overwritten.
+        #[clap(long, default_value = "src/models")]
+        out: String,
+
+        /// The features of `sqlx` to use the types of, instead of those enabled in `Cargo.toml`,
+        /// e.g. `--features chrono,uuid`.
+        #[clap(long, use_value_delimiter = true)]
+        features: Option<Vec<String>>,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+    },
 }
 
 /// Group of commands for creating and dropping your database.
@@ -56,6 +299,9 @@ pub struct DatabaseOpt {
 pub enum DatabaseCommand {
     /// Creates the database specified in your DATABASE_URL.
     Create {
+        #[clap(flatten)]
+        create_opts: CreateOpts,
+
         #[clap(flatten)]
         connect_opts: ConnectOpts,
     },
@@ -71,6 +317,9 @@ pub enum DatabaseCommand {
 
     /// Drops the database specified in your DATABASE_URL, re-creates it, and runs any pending migrations.
     Reset {
+        #[clap(flatten)]
+        create_opts: CreateOpts,
+
         #[clap(flatten)]
         confirmation: Confirmation,
 
@@ -79,15 +328,110 @@ pub enum DatabaseCommand {
 
         #[clap(flatten)]
         connect_opts: ConnectOpts,
+
+        #[clap(flatten)]
+        table_name: MigrationsTable,
     },
 
     /// Creates the database specified in your DATABASE_URL and runs any pending migrations.
     Setup {
+        #[clap(flatten)]
+        create_opts: CreateOpts,
+
         #[clap(flatten)]
         source: Source,
 
         #[clap(flatten)]
         connect_opts: ConnectOpts,
+
+        #[clap(flatten)]
+        table_name: MigrationsTable,
+    },
+
+    /// Compares the schema of the database specified in your DATABASE_URL with the one produced
+    /// by the migrations, or with that of another database, and lists the differences.
+    ///
+    /// Exits with 1 if there are any, e.g. for CI to check that the schema of an environment
+    /// didn't drift, unless they are written to a new migration with `--migration`.
+    Diff {
+        /// The database to compare with, instead of applying the migrations to a scratch
+        /// database (created next to the database, and in memory for SQLite).
+        #[clap(long, value_name = "URL")]
+        from: Option<String>,
+
+        #[clap(flatten)]
+        source: Source,
+
+        /// Write the statements changing the schema from the migrations (or `--from`) to that of
+        /// the database to a new migration with this description, e.g. to capture changes made
+        /// to a development database by hand. Changes which can't be written as a statement,
+        /// e.g. to the type of a column, are left as `TODO` comments.
+        #[clap(long, value_name = "DESCRIPTION")]
+        migration: Option<String>,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+    },
+
+    /// Reports common issues in the schema of the database specified in your DATABASE_URL:
+    /// tables without a primary key (`error`), foreign keys without an index and `varchar`
+    /// columns without a length (`warning`), and nullable columns which are never null in a
+    /// sample of their rows (`info`).
+    ///
+    /// Exits with 1 if there are issues of the severity given with `--fail-on` or above, e.g. for
+    /// CI.
+    Lint {
+        /// The schema to lint, the current schema by default (the current database in MySQL,
+        /// `main` in SQLite).
+        #[clap(long)]
+        schema: Option<String>,
+
+        /// How many rows of each table to look for nulls in; 0 skips sampling.
+        #[clap(long, default_value = "1000")]
+        sample: u64,
+
+        /// `human`, or `json` to print the issues as JSON.
+        #[clap(long, value_name = "FORMAT", default_value = "human")]
+        format: InfoFormat,
+
+        /// The least severe issues to exit with an error for: `error`, `warning` or `info`.
+        #[clap(long, value_name = "SEVERITY", default_value = "error")]
+        fail_on: Severity,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+    },
+
+    /// Writes a dump of the schema and data of the database specified in your DATABASE_URL,
+    /// using `pg_dump` or `mysqldump` if installed.
+    Dump {
+        /// File to write the dump to, instead of stdout.
+        #[clap(short, long)]
+        output: Option<String>,
+
+        /// Write the dump with SQLx even if `pg_dump` or `mysqldump` is installed.
+        #[clap(long)]
+        builtin: bool,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+    },
+
+    /// Drops the database specified in your DATABASE_URL, re-creates it, and restores a dump
+    /// written by `sqlx database dump`, using `psql` or `mysql` if installed.
+    Restore {
+        /// The dump to restore.
+        file: String,
+
+        /// Restore the dump with SQLx even if `psql` or `mysql` is installed.
+        #[clap(long)]
+        builtin: bool,
+
+        #[clap(flatten)]
+        confirmation: Confirmation,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
     },
 }
 
@@ -96,7 +440,7 @@ pub enum DatabaseCommand {
 pub struct MigrateOpt {
     /// Path to folder containing migrations.
     /// Warning: deprecated, use <SUBCOMMAND> --source <SOURCE>
-    #[clap(long, default_value = "migrations")]
+    #[clap(long, default_value = config::migrations_dir())]
     pub source: String,
 
     #[clap(subcommand)]
@@ -107,6 +451,10 @@ pub struct MigrateOpt {
 pub enum MigrateCommand {
     /// Create a new migration with the given description,
     /// and the current time as the version.
+    ///
+    /// The files are created from the templates of the migrations directory if there are:
+    /// `.template.sql`, or `.template.up.sql` and `.template.down.sql` for a reversible
+    /// migration, in which `{version}`, `{description}` and `{timestamp}` are replaced.
     Add {
         description: String,
 
@@ -124,7 +472,7 @@ pub enum MigrateCommand {
         #[clap(flatten)]
         source: SourceOverride,
 
-        /// List all the migrations to be run without applying
+        /// List all the migrations to be run, with their SQL, without applying
         #[clap(long)]
         dry_run: bool,
 
@@ -133,6 +481,37 @@ pub enum MigrateCommand {
 
         #[clap(flatten)]
         connect_opts: ConnectOpts,
+
+        #[clap(flatten)]
+        table_name: MigrationsTable,
+
+        #[clap(flatten)]
+        lock: LockOpts,
+
+        /// Apply migrations up to and including this version, instead of all pending migrations.
+        #[clap(long)]
+        target_version: Option<i64>,
+
+        /// Only warn if the checksum of this previously applied migration differs from its file.
+        /// May be given several times.
+        #[clap(long, value_name = "VERSION")]
+        allow_checksum_mismatch: Vec<i64>,
+
+        /// What to do with a pending migration older than the latest applied migration, e.g. one
+        /// merged from another branch: `error`, `warn` (skipping it) or `apply-missing`.
+        #[clap(long, value_name = "POLICY", default_value = "apply-missing")]
+        out_of_order: OutOfOrder,
+
+        /// After migrating, write the schema of the database to this file, `schema.sql` if not
+        /// given, e.g. to commit it next to the migrations. Not written with `--dry-run`.
+        #[clap(
+            long,
+            value_name = "PATH",
+            min_values = 0,
+            require_equals = true,
+            default_missing_value = "schema.sql"
+        )]
+        dump_schema: Option<String>,
     },
 
     /// Revert the latest migration with a down file.
@@ -144,11 +523,145 @@ pub enum MigrateCommand {
         #[clap(long)]
         dry_run: bool,
 
+        #[clap(flatten)]
+        confirmation: Confirmation,
+
+        #[clap(flatten)]
+        ignore_missing: IgnoreMissing,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+
+        #[clap(flatten)]
+        table_name: MigrationsTable,
+
+        #[clap(flatten)]
+        lock: LockOpts,
+
+        /// Revert every applied migration newer than this version, instead of only the latest.
+        /// Use 0 to revert all migrations.
+        #[clap(long)]
+        target_version: Option<i64>,
+    },
+
+    /// Revert the latest migration and apply it again, e.g. after editing it.
+    ///
+    /// The migration must have a down file.
+    Redo {
+        #[clap(flatten)]
+        source: SourceOverride,
+
+        /// List the migration to be redone without applying
+        #[clap(long)]
+        dry_run: bool,
+
+        #[clap(flatten)]
+        confirmation: Confirmation,
+
+        #[clap(flatten)]
+        ignore_missing: IgnoreMissing,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+
+        #[clap(flatten)]
+        table_name: MigrationsTable,
+
+        #[clap(flatten)]
+        lock: LockOpts,
+    },
+
+    /// Update the checksums recorded for applied migrations whose file has been modified since,
+    /// e.g. reformatted, without running them again.
+    Repair {
+        #[clap(flatten)]
+        source: SourceOverride,
+
+        /// Automatic confirmation. Without this option, you will be prompted before the checksums
+        /// are updated.
+        #[clap(short)]
+        yes: bool,
+
         #[clap(flatten)]
         ignore_missing: IgnoreMissing,
 
         #[clap(flatten)]
         connect_opts: ConnectOpts,
+
+        #[clap(flatten)]
+        table_name: MigrationsTable,
+
+        #[clap(flatten)]
+        lock: LockOpts,
+    },
+
+    /// Mark the migrations up to and including a version as applied without running them, e.g.
+    /// to start managing an existing database whose schema already matches them.
+    Baseline {
+        /// The version the database is at.
+        version: i64,
+
+        #[clap(flatten)]
+        source: SourceOverride,
+
+        /// Automatic confirmation. Without this option, you will be prompted before the
+        /// migrations are marked as applied.
+        #[clap(short)]
+        yes: bool,
+
+        #[clap(flatten)]
+        ignore_missing: IgnoreMissing,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+
+        #[clap(flatten)]
+        table_name: MigrationsTable,
+
+        #[clap(flatten)]
+        lock: LockOpts,
+    },
+
+    /// Replace the migrations up to and including a version with a single migration, and remove
+    /// their files.
+    ///
+    /// The new migration takes the version of the last one it replaces, so databases which
+    /// applied them don't run it, and databases created afterwards run it instead of them.
+    Squash {
+        /// The last version to squash.
+        version: i64,
+
+        #[clap(flatten)]
+        source: SourceOverride,
+
+        /// Automatic confirmation. Without this option, you will be prompted before the files are
+        /// replaced.
+        #[clap(short)]
+        yes: bool,
+    },
+
+    /// Give a new version to the migrations sharing their version with another, e.g. after
+    /// merging branches which both added a migration in the same second.
+    ///
+    /// The migration applied to the database with that version keeps it; the others are renamed
+    /// to versions after the latest migration.
+    Renumber {
+        #[clap(flatten)]
+        source: SourceOverride,
+
+        /// Automatic confirmation. Without this option, you will be prompted before the files are
+        /// renamed.
+        #[clap(short)]
+        yes: bool,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+
+        #[clap(flatten)]
+        table_name: MigrationsTable,
+
+        #[clap(flatten)]
+        lock: LockOpts,
     },
 
     /// List all available migrations.
@@ -158,6 +671,14 @@ pub enum MigrateCommand {
 
         #[clap(flatten)]
         connect_opts: ConnectOpts,
+
+        #[clap(flatten)]
+        table_name: MigrationsTable,
+
+        /// `json` to print the migrations as JSON, e.g. for a deployment pipeline to check that
+        /// none are pending, or `human`.
+        #[clap(long, value_name = "FORMAT", default_value = "human")]
+        format: InfoFormat,
     },
 
     /// Generate a `build.rs` to trigger recompilation when a new migration is added.
@@ -173,11 +694,109 @@ pub enum MigrateCommand {
     },
 }
 
+/// Group of commands for creating and running seed migrations: reference or test data versioned
+/// in their own directory and history table, and applied independently of the schema migrations.
+#[derive(Parser, Debug)]
+pub struct SeedOpt {
+    #[clap(subcommand)]
+    pub command: SeedCommand,
+}
+
+#[derive(Parser, Debug)]
+pub enum SeedCommand {
+    /// Create a new seed migration with the given description,
+    /// and the current time as the version.
+    Add {
+        description: String,
+
+        #[clap(flatten)]
+        source: SeedSource,
+
+        /// If true, creates a pair of up and down seed files with same version
+        /// else creates a single sql file
+        #[clap(short)]
+        reversible: bool,
+    },
+
+    /// Run all pending seed migrations, once the schema migrations they depend on are applied.
+    Run {
+        #[clap(flatten)]
+        source: SeedSource,
+
+        /// List all the seed migrations to be run, with their SQL, without applying
+        #[clap(long)]
+        dry_run: bool,
+
+        #[clap(flatten)]
+        ignore_missing: IgnoreMissing,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+
+        #[clap(flatten)]
+        table_name: SeedsTable,
+
+        #[clap(flatten)]
+        lock: LockOpts,
+
+        /// Apply seed migrations up to and including this version, instead of all pending ones.
+        #[clap(long)]
+        target_version: Option<i64>,
+    },
+
+    /// Revert the latest seed migration with a down file.
+    Revert {
+        #[clap(flatten)]
+        source: SeedSource,
+
+        /// List the seed migration to be reverted without applying
+        #[clap(long)]
+        dry_run: bool,
+
+        #[clap(flatten)]
+        confirmation: Confirmation,
+
+        #[clap(flatten)]
+        ignore_missing: IgnoreMissing,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+
+        #[clap(flatten)]
+        table_name: SeedsTable,
+
+        #[clap(flatten)]
+        lock: LockOpts,
+
+        /// Revert every applied seed migration newer than this version, instead of only the
+        /// latest. Use 0 to revert all seed migrations.
+        #[clap(long)]
+        target_version: Option<i64>,
+    },
+
+    /// List all available seed migrations.
+    Info {
+        #[clap(flatten)]
+        source: SeedSource,
+
+        #[clap(flatten)]
+        connect_opts: ConnectOpts,
+
+        #[clap(flatten)]
+        table_name: SeedsTable,
+
+        /// `json` to print the seed migrations as JSON, or `human`.
+        #[clap(long, value_name = "FORMAT", default_value = "human")]
+        format: InfoFormat,
+    },
+}
+
 /// Argument for the migration scripts source.
 #[derive(Args, Debug)]
 pub struct Source {
-    /// Path to folder containing migrations.
-    #[clap(long, default_value = "migrations")]
+    /// Path to folder containing migrations, `dir` in the `[migrate]` table of `sqlx.toml` by
+    /// default.
+    #[clap(long, default_value = config::migrations_dir())]
     source: String,
 }
 
@@ -193,7 +812,8 @@ impl Deref for Source {
 // Note: once `MigrateOpt.source` is removed, usage can be replaced with `Source`.
 #[derive(Args, Debug)]
 pub struct SourceOverride {
-    /// Path to folder containing migrations [default: migrations]
+    /// Path to folder containing migrations [default: migrations, or `dir` in the `[migrate]`
+    /// table of `sqlx.toml`]
     #[clap(long)]
     source: Option<String>,
 }
@@ -210,13 +830,230 @@ impl SourceOverride {
     }
 }
 
+/// Argument for the table recording applied migrations.
+#[derive(Args, Debug)]
+pub struct MigrationsTable {
+    /// Name of the table recording applied migrations, optionally qualified by a schema, e.g. to
+    /// keep the migrations of several services sharing a database apart; `table-name` in the
+    /// `[migrate]` table of `sqlx.toml` by default
+    #[clap(long, env = "SQLX_MIGRATIONS_TABLE", default_value = config::migrations_table())]
+    table_name: String,
+}
+
+impl Deref for MigrationsTable {
+    type Target = String;
+
+    fn deref(&self) -> &Self::Target {
+        &self.table_name
+    }
+}
+
+/// Output format of `migrate info` and `database lint`, and of errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InfoFormat {
+    Human,
+    Json,
+}
+
+impl FromStr for InfoFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(InfoFormat::Human),
+            "json" => Ok(InfoFormat::Json),
+            _ => Err(format!(
+                "unknown format {:?}, expected `human` or `json`",
+                s
+            )),
+        }
+    }
+}
+
+/// How `fixture apply` wraps the fixtures in transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixtureTransaction {
+    All,
+    File,
+    None,
+}
+
+impl FromStr for FixtureTransaction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(FixtureTransaction::All),
+            "file" => Ok(FixtureTransaction::File),
+            "none" => Ok(FixtureTransaction::None),
+            _ => Err(format!(
+                "unknown transaction mode {:?}, expected `all`, `file` or `none`",
+                s
+            )),
+        }
+    }
+}
+
+/// Severity of an issue reported by `database lint`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "info" => Ok(Severity::Info),
+            "warning" => Ok(Severity::Warning),
+            "error" => Ok(Severity::Error),
+            _ => Err(format!(
+                "unknown severity {:?}, expected `error`, `warning` or `info`",
+                s
+            )),
+        }
+    }
+}
+
+/// Output format of `query` and `repl`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+impl FromStr for QueryFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(QueryFormat::Table),
+            "csv" => Ok(QueryFormat::Csv),
+            "json" => Ok(QueryFormat::Json),
+            _ => Err(format!(
+                "unknown format {:?}, expected `table`, `csv` or `json`",
+                s
+            )),
+        }
+    }
+}
+
+/// Argument for the seed migration scripts source.
+#[derive(Args, Debug)]
+pub struct SeedSource {
+    /// Path to folder containing seed migrations, `dir` in the `[seed]` table of `sqlx.toml` by
+    /// default.
+    #[clap(long, default_value = config::seeds_dir())]
+    source: String,
+}
+
+impl Deref for SeedSource {
+    type Target = String;
+
+    fn deref(&self) -> &Self::Target {
+        &self.source
+    }
+}
+
+/// Argument for the table recording applied seed migrations.
+#[derive(Args, Debug)]
+pub struct SeedsTable {
+    /// Name of the table recording applied seed migrations, optionally qualified by a schema;
+    /// `table-name` in the `[seed]` table of `sqlx.toml` by default
+    #[clap(long, env = "SQLX_SEEDS_TABLE", default_value = config::seeds_table())]
+    table_name: String,
+}
+
+impl Deref for SeedsTable {
+    type Target = String;
+
+    fn deref(&self) -> &Self::Target {
+        &self.table_name
+    }
+}
+
+/// Arguments for the lock taken while migrating.
+#[derive(Args, Copy, Clone, Debug)]
+pub struct LockOpts {
+    /// Don't lock the database while migrating, e.g. to get past a SQLite lock left behind by a
+    /// killed migrator. Other migrators may then run concurrently.
+    #[clap(long)]
+    skip_lock: bool,
+
+    /// Key of the lock taken while migrating, derived from the name of the database by default
+    #[clap(long)]
+    lock_key: Option<i64>,
+
+    /// The maximum time, in seconds, to wait for the lock taken while migrating before returning
+    /// an error. Waits as long as it takes by default.
+    #[clap(long)]
+    lock_timeout: Option<u64>,
+}
+
+impl LockOpts {
+    /// The lock to take while migrating, if any.
+    pub fn lock(&self) -> Option<MigrationLock> {
+        if self.skip_lock {
+            return None;
+        }
+
+        Some(MigrationLock {
+            key: self.lock_key,
+            timeout: self.lock_timeout.map(Duration::from_secs),
+        })
+    }
+}
+
+/// Arguments for what `watch` runs, and how often it looks for changes.
+#[derive(Args, Copy, Clone, Debug)]
+pub struct WatchOpts {
+    /// Only apply migrations, without running `prepare`.
+    #[clap(long)]
+    pub no_prepare: bool,
+
+    /// Prepare each member of the workspace, as `prepare --workspace`.
+    #[clap(long)]
+    pub workspace: bool,
+
+    /// How often to look for changes, in milliseconds.
+    #[clap(long, default_value = "500")]
+    interval: u64,
+}
+
+impl WatchOpts {
+    /// The time to wait between looking for changes.
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval)
+    }
+}
+
 /// Argument for the database URL.
 #[derive(Args, Debug)]
 pub struct ConnectOpts {
-    /// Location of the DB, by default will be read from the DATABASE_URL env var
-    #[clap(long, short = 'D', env)]
+    /// Location of the DB, by default will be read from the DATABASE_URL env var, or the one
+    /// named by `database-url-var` in `sqlx.toml`
+    #[clap(long, short = 'D', env = config::database_url_var())]
     pub database_url: String,
 
+    /// Connect through an SSH tunnel to this host, e.g. a bastion, opened for the duration of
+    /// the command with `ssh`. By default, the `ssh` of the environment or of `sqlx.toml`.
+    #[clap(long, value_name = "USER@HOST[:PORT]", env = "SQLX_SSH")]
+    pub ssh: Option<String>,
+
     /// The maximum time, in seconds, to try connecting to the database server before
     /// returning an error.
     #[clap(long, default_value = "10")]
@@ -235,12 +1072,125 @@ pub struct ConnectOpts {
     pub sqlite_create_db_wal: bool,
 }
 
+/// Options of the database created, in addition to those in the `[database]` table of
+/// `sqlx.toml`.
+#[derive(Args, Debug, Default)]
+pub struct CreateOpts {
+    /// The role owning the database (Postgres).
+    #[clap(long)]
+    owner: Option<String>,
+
+    /// The character encoding of the database, e.g. `UTF8` (Postgres).
+    #[clap(long)]
+    encoding: Option<String>,
+
+    /// The locale of the database, setting its collation and character classification
+    /// (Postgres).
+    #[clap(long)]
+    locale: Option<String>,
+
+    /// The collation of the database (Postgres, MySQL).
+    #[clap(long)]
+    collation: Option<String>,
+
+    /// The database to copy, e.g. `template0` for an encoding or locale other than that of
+    /// `template1` (Postgres).
+    #[clap(long)]
+    template: Option<String>,
+
+    /// The default character set of the database, e.g. `utf8mb4` (MySQL).
+    #[clap(long)]
+    charset: Option<String>,
+}
+
+impl CreateOpts {
+    /// The options given, falling back to those of `sqlx.toml`.
+    pub fn options(&self) -> CreateDatabaseOptions {
+        let config = &config::config().database;
+        let mut options = CreateDatabaseOptions::new();
+
+        let or = |flag: &Option<String>, setting: &Option<String>| {
+            flag.as_ref().or(setting.as_ref()).cloned()
+        };
+
+        if let Some(owner) = or(&self.owner, &config.owner) {
+            options = options.owner(owner);
+        }
+        if let Some(encoding) = or(&self.encoding, &config.encoding) {
+            options = options.encoding(encoding);
+        }
+        if let Some(locale) = or(&self.locale, &config.locale) {
+            options = options.locale(locale);
+        }
+        if let Some(collation) = or(&self.collation, &config.collation) {
+            options = options.collation(collation);
+        }
+        if let Some(template) = or(&self.template, &config.template) {
+            options = options.template(template);
+        }
+        if let Some(charset) = or(&self.charset, &config.charset) {
+            options = options.charset(charset);
+        }
+
+        options
+    }
+}
+
+/// The Cargo features to enable when checking the crate, in addition to those of `sqlx.toml`.
+#[derive(Args, Debug, Clone, Default)]
+pub struct CargoFeatures {
+    /// Comma separated list of features to activate.
+    #[clap(long, use_value_delimiter = true)]
+    pub features: Vec<String>,
+
+    /// Activate all available features.
+    #[clap(long)]
+    pub all_features: bool,
+
+    /// Do not activate the `default` feature.
+    #[clap(long)]
+    pub no_default_features: bool,
+}
+
+impl CargoFeatures {
+    /// These features together with those of `other`.
+    pub fn merge(mut self, other: &CargoFeatures) -> Self {
+        for feature in &other.features {
+            if !self.features.contains(feature) {
+                self.features.push(feature.clone());
+            }
+        }
+
+        self.all_features |= other.all_features;
+        self.no_default_features |= other.no_default_features;
+        self
+    }
+
+    /// The arguments of `cargo` enabling these features.
+    pub fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+
+        if !self.features.is_empty() {
+            args.push("--features".to_owned());
+            args.push(self.features.join(","));
+        }
+        if self.all_features {
+            args.push("--all-features".to_owned());
+        }
+        if self.no_default_features {
+            args.push("--no-default-features".to_owned());
+        }
+
+        args
+    }
+}
+
 /// Argument for automatic confirmation.
 #[derive(Args, Copy, Clone, Debug)]
 pub struct Confirmation {
     /// Automatic confirmation. Without this option, you will be prompted before dropping
-    /// your database.
-    #[clap(short)]
+    /// your database, or reverting migrations or deleting rows in a protected environment.
+    #[clap(short, long)]
     pub yes: bool,
 }
 
@@ -267,3 +1217,24 @@ impl Not for IgnoreMissing {
         !self.ignore_missing
     }
 }
+
+/// The value of the global option `flag`, for the settings needed before the arguments are
+/// parsed.
+pub fn global_arg(flag: &str) -> Option<String> {
+    let mut args = env::args().skip(1).take_while(|arg| arg != "--");
+
+    while let Some(arg) = args.next() {
+        if arg == flag {
+            return args.next();
+        }
+
+        if let Some(value) = arg
+            .strip_prefix(flag)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(value.to_owned());
+        }
+    }
+
+    None
+}
@@ -1,33 +1,26 @@
-use crate::opt::ConnectOpts;
+use crate::error::StaleError;
+use crate::opt::{CargoFeatures, ConnectOpts};
 use anyhow::{bail, Context};
 use console::style;
 use remove_dir_all::remove_dir_all;
-use sqlx::any::{AnyConnectOptions, AnyKind};
+use sqlx::any::AnyKind;
+use sqlx::config::Config;
 use sqlx::Connection;
+use sqlx_build::query_data::{self, JsonObject, QueryData, QUERY_DIR};
 use std::collections::{BTreeMap, BTreeSet};
-use std::fs::File;
-use std::io::{BufReader, BufWriter};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::str::FromStr;
 use std::time::SystemTime;
 use std::{env, fs};
 
-use crate::metadata::Metadata;
-
-type QueryData = BTreeMap<String, serde_json::Value>;
-type JsonObject = serde_json::Map<String, serde_json::Value>;
-
-#[derive(serde::Serialize, serde::Deserialize)]
-struct DataFile {
-    db: String,
-    #[serde(flatten)]
-    data: QueryData,
-}
+use crate::metadata::{Metadata, Package};
 
 pub async fn run(
     connect_opts: &ConnectOpts,
     merge: bool,
+    workspace: bool,
+    features: &CargoFeatures,
     cargo_args: Vec<String>,
 ) -> anyhow::Result<()> {
     // Ensure the database server is available.
@@ -35,8 +28,11 @@ pub async fn run(
 
     let url = &connect_opts.database_url;
 
-    let db_kind = get_db_kind(url)?;
-    let data = run_prepare_step(url, merge, cargo_args)?;
+    if workspace {
+        return run_workspace(url, merge, features, cargo_args);
+    }
+
+    let data = run_prepare_step(url, merge, false, features, cargo_args)?;
 
     if data.is_empty() {
         println!(
@@ -45,21 +41,30 @@ pub async fn run(
         );
     }
 
-    serde_json::to_writer_pretty(
-        BufWriter::new(
-            File::create("sqlx-data.json").context("failed to create/open `sqlx-data.json`")?,
-        ),
-        &DataFile {
-            db: db_kind.to_owned(),
-            data,
-        },
-    )
-    .context("failed to write to `sqlx-data.json`")?;
-
-    println!(
-        "query data written to `sqlx-data.json` in the current directory; \
-         please check this into version control"
-    );
+    let query_dir = crate::config::config()
+        .offline_dir()
+        .unwrap_or_else(|| PathBuf::from(QUERY_DIR));
+
+    query_data::write(&query_dir, &data)?;
+
+    if query_dir == Path::new(QUERY_DIR) {
+        println!(
+            "query data written to `.sqlx` in the current directory; \
+             please check this directory into version control"
+        );
+    } else {
+        println!(
+            "query data written to `{}`; please check this directory into version control",
+            query_dir.display()
+        );
+    }
+
+    if Path::new("sqlx-data.json").exists() {
+        println!(
+            "{} `sqlx-data.json` is no longer used and can be deleted",
+            style("note:").cyan()
+        );
+    }
 
     Ok(())
 }
@@ -67,6 +72,8 @@ pub async fn run(
 pub async fn check(
     connect_opts: &ConnectOpts,
     merge: bool,
+    workspace: bool,
+    features: &CargoFeatures,
     cargo_args: Vec<String>,
 ) -> anyhow::Result<()> {
     // Ensure the database server is available.
@@ -74,34 +81,270 @@ pub async fn check(
 
     let url = &connect_opts.database_url;
 
-    let db_kind = get_db_kind(url)?;
-    let data = run_prepare_step(url, merge, cargo_args)?;
+    if workspace {
+        return check_workspace(url, merge, features, cargo_args);
+    }
 
-    let data_file = File::open("sqlx-data.json").context(
-        "failed to open `sqlx-data.json`; you may need to run `cargo sqlx prepare` first",
-    )?;
+    let data = run_prepare_step(url, merge, true, features, cargo_args)?;
 
-    let DataFile {
-        db: expected_db,
-        data: saved_data,
-    } = serde_json::from_reader(BufReader::new(data_file))?;
+    let query_dir = crate::config::config()
+        .offline_dir()
+        .unwrap_or_else(|| PathBuf::from(QUERY_DIR));
 
-    if db_kind != expected_db {
+    if !query_dir.is_dir() {
         bail!(
-            "saved prepare data is for {}, not {} (inferred from `DATABASE_URL`)",
-            expected_db,
-            db_kind
+            "`{}` does not exist; you may need to run `cargo sqlx prepare` first",
+            query_dir.display()
         )
     }
 
+    let saved_data = query_data::read(&query_dir)?;
+    check_db_name(url, &query_dir, &saved_data)?;
+
     if data != saved_data {
-        bail!("`cargo sqlx prepare` needs to be rerun")
+        let locations = read_query_locations(&cargo_metadata(&cargo_path()?)?);
+        print_query_diff(&query_dir, &saved_data, &data, &locations);
+
+        return Err(StaleError("`cargo sqlx prepare` needs to be rerun".into()).into());
     }
 
     Ok(())
 }
 
-fn run_prepare_step(url: &str, merge: bool, cargo_args: Vec<String>) -> anyhow::Result<QueryData> {
+fn run_workspace(
+    url: &str,
+    merge: bool,
+    features: &CargoFeatures,
+    cargo_args: Vec<String>,
+) -> anyhow::Result<()> {
+    let prepared = run_workspace_prepare_step(url, merge, false, features, cargo_args)?;
+
+    if prepared.iter().all(|(_, data)| data.is_empty()) {
+        println!(
+            "{} no queries found; please ensure that the `offline` feature is enabled in sqlx",
+            style("warning:").yellow()
+        );
+    }
+
+    for (query_dir, data) in &prepared {
+        // don't litter crates without queries with empty directories
+        if data.is_empty() && !query_dir.is_dir() {
+            continue;
+        }
+
+        query_data::write(query_dir, data)?;
+
+        println!("query data written to `{}`", query_dir.display());
+    }
+
+    println!("please check these directories into version control");
+
+    Ok(())
+}
+
+fn check_workspace(
+    url: &str,
+    merge: bool,
+    features: &CargoFeatures,
+    cargo_args: Vec<String>,
+) -> anyhow::Result<()> {
+    let prepared = run_workspace_prepare_step(url, merge, true, features, cargo_args)?;
+    let locations = read_query_locations(&cargo_metadata(&cargo_path()?)?);
+
+    let mut stale = Vec::new();
+
+    for (query_dir, data) in prepared {
+        if !query_dir.is_dir() {
+            if data.is_empty() {
+                continue;
+            }
+
+            bail!(
+                "`{}` does not exist; you may need to run `cargo sqlx prepare --workspace` first",
+                query_dir.display()
+            )
+        }
+
+        let saved_data = query_data::read(&query_dir)?;
+        check_db_name(url, &query_dir, &saved_data)?;
+
+        if data != saved_data {
+            print_query_diff(&query_dir, &saved_data, &data, &locations);
+            stale.push(query_dir.display().to_string());
+        }
+    }
+
+    if !stale.is_empty() {
+        return Err(StaleError(format!(
+            "`cargo sqlx prepare --workspace` needs to be rerun; `{}` out of date",
+            stale.join("`, `")
+        ))
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Check that the query data saved in `query_dir` is for the kind of database at `url`.
+fn check_db_name(url: &str, query_dir: &Path, saved_data: &QueryData) -> anyhow::Result<()> {
+    let expected = match db_name(url)? {
+        Some(expected) => expected,
+        None => return Ok(()),
+    };
+
+    let saved = saved_data
+        .values()
+        .filter_map(|query_data| query_data.get("db_name")?.as_str())
+        .find(|&db_name| db_name != expected);
+
+    if let Some(saved) = saved {
+        bail!(
+            "saved prepare data in `{}` is for {}, not {} (inferred from `DATABASE_URL`)",
+            query_dir.display(),
+            saved,
+            expected
+        )
+    }
+
+    Ok(())
+}
+
+/// The name of the database saved by the query macros in the data of each query, see
+/// `DatabaseExt::NAME` in `sqlx-macros`; `None` if the macros don't support the database.
+fn db_name(url: &str) -> anyhow::Result<Option<&'static str>> {
+    Ok(match AnyKind::from_str(url)? {
+        #[cfg(feature = "postgres")]
+        AnyKind::Postgres => Some("PostgreSQL"),
+
+        #[cfg(feature = "mysql")]
+        AnyKind::MySql => Some("MySQL"),
+
+        #[cfg(feature = "sqlite")]
+        AnyKind::Sqlite => Some("SQLite"),
+
+        _ => None,
+    })
+}
+
+/// Print the queries added, removed or changed in `data` compared to the data saved in
+/// `query_dir`.
+fn print_query_diff(
+    query_dir: &Path,
+    saved_data: &QueryData,
+    data: &QueryData,
+    locations: &QueryLocations,
+) {
+    println!("query data in `{}` is out of date:", query_dir.display());
+
+    // the locations are those of the queries just checked, so none are known for removed queries
+    let print = |change, location: Option<&String>, query_data: &JsonObject| {
+        let query = query_data
+            .get("query")
+            .and_then(|query| query.as_str())
+            .unwrap_or_default();
+
+        match location {
+            Some(location) => println!("  {} {} `{}`", change, location, query_snippet(query)),
+            None => println!("  {} `{}`", change, query_snippet(query)),
+        }
+    };
+
+    for (hash, query_data) in data {
+        match saved_data.get(hash) {
+            None => print(style("added").green(), locations.get(hash), query_data),
+            Some(saved) if saved != query_data => {
+                print(style("changed").yellow(), locations.get(hash), query_data)
+            }
+            Some(_) => {}
+        }
+    }
+
+    for (hash, saved) in saved_data {
+        if !data.contains_key(hash) {
+            print(style("removed").red(), None, saved);
+        }
+    }
+}
+
+/// The query on a single line, shortened if it's long.
+fn query_snippet(query: &str) -> String {
+    const MAX_LEN: usize = 60;
+
+    let query = query.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    match query.char_indices().nth(MAX_LEN) {
+        Some((end, _)) => format!("{}...", &query[..end]),
+        None => query,
+    }
+}
+
+/// Where the queries are written, as `<file>:<line>` by hash, read from the query manifest.
+type QueryLocations = BTreeMap<String, String>;
+
+/// Read the locations of the queries from the query manifest written when checking them, see
+/// `sqlx-macros/src/query/manifest.rs`; the files are given relative to the current directory
+/// when possible.
+fn read_query_locations(metadata: &Metadata) -> QueryLocations {
+    let crate_dirs: BTreeMap<_, _> = metadata
+        .workspace_members()
+        .iter()
+        .filter_map(|id| metadata.package(id))
+        .map(|package| (package.name(), package.manifest_dir()))
+        .collect();
+
+    let current_dir = env::current_dir().unwrap_or_default();
+
+    let pattern = metadata
+        .target_directory()
+        .join("sqlx-manifest/query-*.json");
+    let paths = pattern
+        .to_str()
+        .and_then(|pattern| glob::glob(pattern).ok());
+
+    let mut locations = QueryLocations::new();
+
+    for path in paths.into_iter().flatten().flatten() {
+        let manifest: JsonObject = match fs::read(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_slice(&contents).ok())
+        {
+            Some(manifest) => manifest,
+            None => continue,
+        };
+
+        let field = |name: &str| manifest.get(name).and_then(|value| value.as_str());
+
+        let (hash, file) = match (field("hash"), field("file")) {
+            (Some(hash), Some(file)) => (hash, file),
+            _ => continue,
+        };
+
+        let file = match field("crate").and_then(|name| crate_dirs.get(name)) {
+            Some(dir) => dir.join(file),
+            None => PathBuf::from(file),
+        };
+        let file = file.strip_prefix(&current_dir).unwrap_or(&file);
+
+        let location = match manifest.get("line").and_then(|line| line.as_u64()) {
+            Some(line) => format!("{}:{}", file.display(), line),
+            None => file.display().to_string(),
+        };
+
+        locations.insert(hash.to_owned(), location);
+    }
+
+    locations
+}
+
+/// Check the crate to collect the data of its queries; `locate` enables the query manifest, for
+/// [`read_query_locations()`] to tell where the queries are.
+fn run_prepare_step(
+    url: &str,
+    merge: bool,
+    locate: bool,
+    features: &CargoFeatures,
+    cargo_args: Vec<String>,
+) -> anyhow::Result<QueryData> {
     anyhow::ensure!(
         Path::new("Cargo.toml").exists(),
         r#"Failed to read `Cargo.toml`.
@@ -109,17 +352,8 @@ hint: This command only works in the manifest directory of a Cargo package."#
     );
 
     // path to the Cargo executable
-    let cargo = env::var("CARGO")
-        .context("`prepare` subcommand may only be invoked as `cargo sqlx prepare`")?;
-
-    let output = Command::new(&cargo)
-        .args(&["metadata", "--format-version=1"])
-        .output()
-        .context("Could not fetch metadata")?;
-
-    let output_str =
-        std::str::from_utf8(&output.stdout).context("Invalid `cargo metadata` output")?;
-    let metadata: Metadata = output_str.parse()?;
+    let cargo = cargo_path()?;
+    let metadata = cargo_metadata(&cargo)?;
 
     // try removing the target/sqlx directory before running, as stale files
     // have repeatedly caused issues in the past.
@@ -145,9 +379,11 @@ hint: This command only works in the manifest directory of a Cargo package."#
         let mut check_command = Command::new(&cargo);
         check_command
             .arg("check")
+            .args(feature_args(features, crate::config::config(), None))
             .args(cargo_args)
             .env("SQLX_OFFLINE", "false")
-            .env("DATABASE_URL", url);
+            .env("DATABASE_URL", url)
+            .env(crate::config::database_url_var(), url);
 
         // `cargo check` recompiles on changed rust flags which can be set either via the env var
         // or through the `rustflags` field in `$CARGO_HOME/config` when the env var isn't set.
@@ -156,10 +392,16 @@ hint: This command only works in the manifest directory of a Cargo package."#
             check_command.env("RUSTFLAGS", rustflags);
         }
 
+        if locate {
+            check_command.env("SQLX_QUERY_MANIFEST", "true");
+        }
+
         check_command.status()?
     } else {
-        Command::new(&cargo)
+        let mut rustc_command = Command::new(&cargo);
+        rustc_command
             .arg("rustc")
+            .args(feature_args(features, crate::config::config(), None))
             .args(cargo_args)
             .arg("--")
             .arg("--emit")
@@ -172,15 +414,180 @@ hint: This command only works in the manifest directory of a Cargo package."#
             ))
             .env("SQLX_OFFLINE", "false")
             .env("DATABASE_URL", url)
-            .env("CARGO_TARGET_DIR", metadata.target_directory().clone())
-            .status()?
+            .env(crate::config::database_url_var(), url)
+            .env("CARGO_TARGET_DIR", metadata.target_directory().clone());
+
+        if locate {
+            rustc_command.env("SQLX_QUERY_MANIFEST", "true");
+        }
+
+        rustc_command.status()?
     };
 
     if !check_status.success() {
         bail!("`cargo check` failed with status: {}", check_status);
     }
 
-    let pattern = metadata.target_directory().join("sqlx/query-*.json");
+    collect_query_data(metadata.target_directory(), None)
+}
+
+/// Prepare each member of the workspace using `sqlx-macros` on its own, returning the directory
+/// to save its query data in with the data; or that of the workspace root with the data of all of
+/// them, if `merge` is set. Members sharing the `offline-dir` of a `sqlx.toml` have their data
+/// merged.
+fn run_workspace_prepare_step(
+    url: &str,
+    merge: bool,
+    locate: bool,
+    features: &CargoFeatures,
+    cargo_args: Vec<String>,
+) -> anyhow::Result<Vec<(PathBuf, QueryData)>> {
+    let cargo = cargo_path()?;
+    let metadata = cargo_metadata(&cargo)?;
+
+    let members = workspace_sqlx_members(&metadata);
+
+    if members.is_empty() {
+        bail!("no member of the workspace depends on `sqlx-macros`");
+    }
+
+    let mut prepared = Vec::new();
+
+    for id in members {
+        let package = metadata
+            .package(id)
+            .with_context(|| format!("package {} is missing from `cargo metadata`", id))?;
+
+        // the queries of this package saved by the checks of other members, which may enable
+        // other features of it, are not part of its own
+        let _ = remove_dir_all(
+            metadata
+                .target_directory()
+                .join("sqlx")
+                .join(package.name()),
+        );
+
+        for file in package.src_paths() {
+            let now = filetime::FileTime::now();
+            filetime::set_file_times(file, now, now)
+                .with_context(|| format!("Failed to update mtime for {:?}", file))?;
+        }
+
+        let config = Config::find(package.manifest_dir())?.unwrap_or_default();
+
+        let mut check_command = Command::new(&cargo);
+        check_command
+            .args(&["check", "-p", package.name()])
+            .args(feature_args(features, &config, Some(package)))
+            .args(&cargo_args)
+            .env("SQLX_OFFLINE", "false")
+            .env("DATABASE_URL", url)
+            .env(config.database_url_var(), url);
+
+        // see `run_prepare_step()`
+        if let Ok(rustflags) = env::var("RUSTFLAGS") {
+            check_command.env("RUSTFLAGS", rustflags);
+        }
+
+        if locate {
+            check_command.env("SQLX_QUERY_MANIFEST", "true");
+        }
+
+        let check_status = check_command.status()?;
+
+        if !check_status.success() {
+            bail!(
+                "`cargo check -p {}` failed with status: {}",
+                package.name(),
+                check_status
+            );
+        }
+
+        let data = collect_query_data(metadata.target_directory(), Some(package.name()))?;
+        let query_dir = config
+            .offline_dir()
+            .unwrap_or_else(|| package.manifest_dir().join(QUERY_DIR));
+
+        prepared.push((query_dir, data));
+    }
+
+    let _ = remove_dir_all(metadata.target_directory().join("sqlx"));
+
+    if merge {
+        let query_dir = Config::find(metadata.workspace_root())?
+            .and_then(|config| config.offline_dir())
+            .unwrap_or_else(|| metadata.workspace_root().join(QUERY_DIR));
+
+        let data = prepared.into_iter().flat_map(|(_, data)| data).collect();
+        return Ok(vec![(query_dir, data)]);
+    }
+
+    let mut merged: Vec<(PathBuf, QueryData)> = Vec::new();
+
+    for (query_dir, data) in prepared {
+        match merged.iter_mut().find(|(dir, _)| *dir == query_dir) {
+            Some((_, merged_data)) => merged_data.extend(data),
+            None => merged.push((query_dir, data)),
+        }
+    }
+
+    Ok(merged)
+}
+
+/// The arguments enabling the features given on the command line, those set in `config` and, in
+/// a workspace, those set in the metadata of `package`.
+fn feature_args(
+    features: &CargoFeatures,
+    config: &Config,
+    package: Option<&Package>,
+) -> Vec<String> {
+    let mut features = features.clone().merge(&CargoFeatures {
+        features: config.prepare.features.clone(),
+        all_features: config.prepare.all_features,
+        no_default_features: config.prepare.no_default_features,
+    });
+
+    if let Some(package) = package {
+        features = features.merge(package.prepare_features());
+    }
+
+    features.args()
+}
+
+/// The members of the workspace depending on `sqlx-macros`.
+fn workspace_sqlx_members(metadata: &Metadata) -> Vec<&cargo_metadata::PackageId> {
+    let dependents = sqlx_macros_dependents(metadata);
+
+    metadata
+        .workspace_members()
+        .iter()
+        .filter(|id| dependents.contains(id))
+        .collect()
+}
+
+fn cargo_path() -> anyhow::Result<String> {
+    env::var("CARGO").context("`prepare` subcommand may only be invoked as `cargo sqlx prepare`")
+}
+
+fn cargo_metadata(cargo: &str) -> anyhow::Result<Metadata> {
+    let output = Command::new(cargo)
+        .args(&["metadata", "--format-version=1"])
+        .output()
+        .context("Could not fetch metadata")?;
+
+    let output_str =
+        std::str::from_utf8(&output.stdout).context("Invalid `cargo metadata` output")?;
+
+    output_str.parse()
+}
+
+/// Collect the query data saved by the macros in the target directory, removing the files; only
+/// that of the queries of `package` if given.
+fn collect_query_data(target_directory: &Path, package: Option<&str>) -> anyhow::Result<QueryData> {
+    let pattern = match package {
+        Some(package) => target_directory.join(format!("sqlx/{}/query-*.json", package)),
+        None => target_directory.join("sqlx/**/query-*.json"),
+    };
 
     let mut data = BTreeMap::new();
 
@@ -191,15 +598,14 @@ hint: This command only works in the manifest directory of a Cargo package."#
     )? {
         let path = path?;
         let contents = fs::read(&*path)?;
-        let mut query_data: JsonObject = serde_json::from_slice(&contents)?;
+        let query_data: JsonObject = serde_json::from_slice(&contents)?;
 
-        // we lift the `hash` key to the outer map
         let hash = query_data
-            .remove("hash")
+            .get("hash")
             .context("expected key `hash` in query data")?;
 
         if let serde_json::Value::String(hash) = hash {
-            data.insert(hash, serde_json::Value::Object(query_data));
+            data.insert(hash.clone(), query_data);
         } else {
             bail!(
                 "expected key `hash` in query data to be string, was {:?} instead; file: {}",
@@ -253,8 +659,8 @@ fn setup_minimal_project_recompile(cargo: &str, metadata: &Metadata) -> anyhow::
     Ok(())
 }
 
-fn minimal_project_recompile_action(metadata: &Metadata) -> anyhow::Result<ProjectRecompileAction> {
-    // Get all the packages that depend on `sqlx-macros`
+/// Get all the packages that depend on `sqlx-macros`, directly or transitively
+fn sqlx_macros_dependents(metadata: &Metadata) -> BTreeSet<&cargo_metadata::PackageId> {
     let mut sqlx_macros_dependents = BTreeSet::new();
     let sqlx_macros_ids: BTreeSet<_> = metadata
         .entries()
@@ -267,6 +673,12 @@ fn minimal_project_recompile_action(metadata: &Metadata) -> anyhow::Result<Proje
         sqlx_macros_dependents.extend(metadata.all_dependents_of(sqlx_macros_id));
     }
 
+    sqlx_macros_dependents
+}
+
+fn minimal_project_recompile_action(metadata: &Metadata) -> anyhow::Result<ProjectRecompileAction> {
+    let sqlx_macros_dependents = sqlx_macros_dependents(metadata);
+
     // Figure out which `sqlx-macros` dependents are in the workspace vs out
     let mut in_workspace_dependents = Vec::new();
     let mut out_of_workspace_dependents = Vec::new();
@@ -304,77 +716,64 @@ fn minimal_project_recompile_action(metadata: &Metadata) -> anyhow::Result<Proje
     })
 }
 
-fn get_db_kind(url: &str) -> anyhow::Result<&'static str> {
-    let options = AnyConnectOptions::from_str(&url)?;
-
-    // these should match the values of `DatabaseExt::NAME` in `sqlx-macros`
-    match options.kind() {
-        #[cfg(feature = "postgres")]
-        AnyKind::Postgres => Ok("PostgreSQL"),
-
-        #[cfg(feature = "mysql")]
-        AnyKind::MySql => Ok("MySQL"),
-
-        #[cfg(feature = "sqlite")]
-        AnyKind::Sqlite => Ok("SQLite"),
-
-        #[cfg(feature = "mssql")]
-        AnyKind::Mssql => Ok("MSSQL"),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json::json;
     use std::assert_eq;
 
     #[test]
-    fn data_file_serialization_works() {
-        let data_file = DataFile {
-            db: "mysql".to_owned(),
-            data: {
-                let mut data = BTreeMap::new();
-                data.insert("a".to_owned(), json!({"key1": "value1"}));
-                data.insert("z".to_owned(), json!({"key2": "value2"}));
-                data
-            },
+    #[cfg(feature = "postgres")]
+    fn it_checks_the_database_of_saved_data() {
+        let query_data = |db_name: &str| {
+            let mut data = JsonObject::new();
+            data.insert("db_name".into(), db_name.into());
+            data
         };
 
-        let data_file = serde_json::to_string(&data_file).expect("Data file serialized.");
+        let mut data = QueryData::new();
+        data.insert("a".into(), query_data("PostgreSQL"));
 
-        assert_eq!(
-            data_file,
-            "{\"db\":\"mysql\",\"a\":{\"key1\":\"value1\"},\"z\":{\"key2\":\"value2\"}}"
-        );
-    }
+        let url = "postgres://localhost/db";
 
-    #[test]
-    fn data_file_deserialization_works() {
-        let data_file =
-            "{\"db\":\"mysql\",\"a\":{\"key1\":\"value1\"},\"z\":{\"key2\":\"value2\"}}";
+        assert!(check_db_name(url, Path::new(".sqlx"), &data).is_ok());
 
-        let data_file: DataFile = serde_json::from_str(data_file).expect("Data file deserialized.");
-        let DataFile { db, data } = data_file;
+        data.insert("b".into(), query_data("MySQL"));
 
-        assert_eq!(db, "mysql");
-        assert_eq!(data.len(), 2);
-        assert_eq!(data.get("a"), Some(&json!({"key1": "value1"})));
-        assert_eq!(data.get("z"), Some(&json!({"key2": "value2"})));
+        assert_eq!(
+            check_db_name(url, Path::new(".sqlx"), &data)
+                .unwrap_err()
+                .to_string(),
+            "saved prepare data in `.sqlx` is for MySQL, not PostgreSQL \
+             (inferred from `DATABASE_URL`)"
+        );
     }
 
     #[test]
-    fn data_file_deserialization_works_for_ordered_keys() {
-        let data_file =
-            "{\"a\":{\"key1\":\"value1\"},\"db\":\"mysql\",\"z\":{\"key2\":\"value2\"}}";
+    fn it_merges_features() {
+        let features = CargoFeatures {
+            features: vec!["postgres".into(), "uuid".into()],
+            ..Default::default()
+        };
 
-        let data_file: DataFile = serde_json::from_str(data_file).expect("Data file deserialized.");
-        let DataFile { db, data } = data_file;
+        assert_eq!(
+            feature_args(&features, &Config::default(), None),
+            ["--features", "postgres,uuid"]
+        );
 
-        assert_eq!(db, "mysql");
-        assert_eq!(data.len(), 2);
-        assert_eq!(data.get("a"), Some(&json!({"key1": "value1"})));
-        assert_eq!(data.get("z"), Some(&json!({"key2": "value2"})));
+        let merged = features.merge(&CargoFeatures {
+            features: vec!["uuid".into(), "chrono".into()],
+            all_features: false,
+            no_default_features: true,
+        });
+
+        assert_eq!(
+            merged.args(),
+            [
+                "--features",
+                "postgres,uuid,chrono",
+                "--no-default-features"
+            ]
+        );
     }
 
     #[test]
@@ -399,4 +798,22 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn workspace_sqlx_members_works() -> anyhow::Result<()> {
+        let sample_metadata_path = Path::new("tests")
+            .join("assets")
+            .join("sample_metadata.json");
+        let sample_metadata = std::fs::read_to_string(sample_metadata_path)?;
+        let metadata: Metadata = sample_metadata.parse()?;
+
+        let members: Vec<_> = workspace_sqlx_members(&metadata)
+            .into_iter()
+            .filter_map(|id| metadata.package(id))
+            .map(|package| package.name())
+            .collect();
+        assert_eq!(members, ["b_in_workspace_lib", "c_in_workspace_bin"]);
+
+        Ok(())
+    }
 }
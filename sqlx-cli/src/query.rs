@@ -0,0 +1,289 @@
+use std::cmp;
+use std::io::{self, BufRead, Write};
+
+use console::style;
+use futures::TryStreamExt;
+use sqlx::any::AnyRow;
+use sqlx::{AnyConnection, Column, Either, Executor, Row, TypeInfo};
+
+use crate::opt::{ConnectOpts, QueryFormat};
+
+/// The columns and rows returned by a statement, with the values as text.
+struct ResultSet {
+    /// The name and type of each column.
+    columns: Vec<(String, String)>,
+    rows: Vec<Vec<Option<String>>>,
+}
+
+pub async fn run(connect_opts: &ConnectOpts, sql: &str, format: QueryFormat) -> anyhow::Result<()> {
+    let mut conn = crate::connect(connect_opts).await?;
+
+    execute(&mut conn, sql, format).await
+}
+
+/// Run the statements entered on stdin, each ending with a `;` at the end of a line, until
+/// `\q` or the end of the input.
+pub async fn repl(connect_opts: &ConnectOpts, format: QueryFormat) -> anyhow::Result<()> {
+    let mut conn = crate::connect(connect_opts).await?;
+
+    // don't mix prompts with the results when reading statements from a pipe
+    let interactive = console::user_attended();
+
+    if interactive {
+        println!("Connected; enter statements ending with `;`, or `\\q` to quit.");
+    }
+
+    let mut sql = String::new();
+
+    loop {
+        if interactive {
+            print!("{}", if sql.is_empty() { "sqlx> " } else { "   -> " });
+            io::stdout().flush()?;
+        }
+
+        let mut line = String::new();
+
+        if io::stdin().lock().read_line(&mut line)? == 0 {
+            break;
+        }
+
+        if sql.is_empty() {
+            match line.trim() {
+                "" => continue,
+                "\\q" | "quit" | "exit" => return Ok(()),
+                _ => {}
+            }
+        }
+
+        sql.push_str(&line);
+
+        if line.trim_end().ends_with(';') {
+            if let Err(error) = execute(&mut conn, &sql, format).await {
+                println!("{} {}", style("error:").bold().red(), error);
+            }
+
+            sql.clear();
+        }
+    }
+
+    // the last statement may not end with `;`
+    if !sql.trim().is_empty() {
+        execute(&mut conn, &sql, format).await?;
+    }
+
+    Ok(())
+}
+
+/// Run `sql`, which may contain several statements, printing the rows returned by each.
+async fn execute(conn: &mut AnyConnection, sql: &str, format: QueryFormat) -> anyhow::Result<()> {
+    let mut results = conn.fetch_many(sql);
+    let mut result_set: Option<ResultSet> = None;
+
+    while let Some(step) = results.try_next().await? {
+        match step {
+            Either::Left(done) => match result_set.take() {
+                Some(result_set) => print_result_set(&result_set, format)?,
+                None if format == QueryFormat::Table => match done.rows_affected() {
+                    1 => println!("(1 row affected)"),
+                    rows => println!("({} rows affected)", rows),
+                },
+                None => {}
+            },
+
+            Either::Right(row) => result_set
+                .get_or_insert_with(|| ResultSet {
+                    columns: row
+                        .columns()
+                        .iter()
+                        .map(|column| {
+                            (
+                                column.name().to_owned(),
+                                column.type_info().name().to_owned(),
+                            )
+                        })
+                        .collect(),
+                    rows: Vec::new(),
+                })
+                .rows
+                .push(row_values(&row)),
+        }
+    }
+
+    // not every driver reports the end of the rows of the last statement
+    if let Some(result_set) = result_set {
+        print_result_set(&result_set, format)?;
+    }
+
+    Ok(())
+}
+
+/// The values of the row as text; bytes which aren't valid UTF-8 are printed in hex.
+fn row_values(row: &AnyRow) -> Vec<Option<String>> {
+    (0..row.len())
+        .map(|i| match row.try_get_unchecked::<Option<String>, _>(i) {
+            Ok(value) => value,
+            Err(_) => row
+                .try_get_unchecked::<Option<Vec<u8>>, _>(i)
+                .ok()
+                .flatten()
+                .map(|bytes| {
+                    let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+                    format!("\\x{}", hex)
+                }),
+        })
+        .collect()
+}
+
+fn print_result_set(result_set: &ResultSet, format: QueryFormat) -> anyhow::Result<()> {
+    let mut stdout = io::stdout().lock();
+
+    match format {
+        QueryFormat::Table => print_table(&mut stdout, result_set)?,
+        QueryFormat::Csv => print_csv(&mut stdout, result_set)?,
+        QueryFormat::Json => print_json(&mut stdout, result_set)?,
+    }
+
+    Ok(())
+}
+
+fn print_table(out: &mut impl Write, result_set: &ResultSet) -> io::Result<()> {
+    let cell = |value: &Option<String>| value.clone().unwrap_or_else(|| "NULL".into());
+
+    let mut widths: Vec<usize> = result_set
+        .columns
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .collect();
+
+    for row in &result_set.rows {
+        for (width, value) in widths.iter_mut().zip(row) {
+            *width = cmp::max(*width, cell(value).chars().count());
+        }
+    }
+
+    let line = |values: Vec<String>| {
+        values
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| format!(" {:width$} ", value, width = width))
+            .collect::<Vec<_>>()
+            .join("|")
+            .trim_end()
+            .to_owned()
+    };
+
+    writeln!(
+        out,
+        "{}",
+        line(
+            result_set
+                .columns
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect()
+        )
+    )?;
+    writeln!(
+        out,
+        "{}",
+        widths
+            .iter()
+            .map(|width| "-".repeat(width + 2))
+            .collect::<Vec<_>>()
+            .join("+")
+    )?;
+
+    for row in &result_set.rows {
+        writeln!(out, "{}", line(row.iter().map(cell).collect()))?;
+    }
+
+    match result_set.rows.len() {
+        1 => writeln!(out, "(1 row)"),
+        rows => writeln!(out, "({} rows)", rows),
+    }
+}
+
+fn print_csv(out: &mut impl Write, result_set: &ResultSet) -> io::Result<()> {
+    let field = |value: &str| {
+        if value.contains(&[',', '"', '\n', '\r'][..]) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_owned()
+        }
+    };
+
+    let header: Vec<_> = result_set
+        .columns
+        .iter()
+        .map(|(name, _)| field(name))
+        .collect();
+    writeln!(out, "{}", header.join(","))?;
+
+    for row in &result_set.rows {
+        let fields: Vec<_> = row
+            .iter()
+            .map(|value| value.as_deref().map(field).unwrap_or_default())
+            .collect();
+        writeln!(out, "{}", fields.join(","))?;
+    }
+
+    Ok(())
+}
+
+/// Print the rows as a JSON array of objects, with the keys in the order of the columns.
+fn print_json(out: &mut impl Write, result_set: &ResultSet) -> anyhow::Result<()> {
+    let mut rows = Vec::with_capacity(result_set.rows.len());
+
+    for row in &result_set.rows {
+        let mut fields = Vec::with_capacity(row.len());
+
+        for ((name, type_name), value) in result_set.columns.iter().zip(row) {
+            let value = match value {
+                Some(value) => json_value(type_name, value),
+                None => serde_json::Value::Null,
+            };
+
+            fields.push(format!(
+                "{}: {}",
+                serde_json::to_string(name)?,
+                serde_json::to_string(&value)?
+            ));
+        }
+
+        rows.push(format!("  {{{}}}", fields.join(", ")));
+    }
+
+    if rows.is_empty() {
+        writeln!(out, "[]")?;
+    } else {
+        writeln!(out, "[\n{}\n]", rows.join(",\n"))?;
+    }
+
+    Ok(())
+}
+
+/// The value of a column of type `type_name` as JSON: numbers, booleans and JSON documents are
+/// kept as such, other values are strings.
+fn json_value(type_name: &str, value: &str) -> serde_json::Value {
+    let type_name = type_name.to_ascii_uppercase();
+    let type_name = type_name.trim_end_matches(" UNSIGNED");
+
+    let parsed = match type_name {
+        // `NUMERIC` and `DECIMAL` are left as strings, as they may not fit a float
+        "INT2" | "INT4" | "INT8" | "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "INTEGER"
+        | "BIGINT" | "FLOAT4" | "FLOAT8" | "REAL" | "FLOAT" | "DOUBLE" => {
+            serde_json::from_str::<serde_json::Number>(value)
+                .ok()
+                .map(serde_json::Value::Number)
+        }
+        "BOOL" | "BOOLEAN" => match value {
+            "t" | "true" | "1" => Some(true.into()),
+            "f" | "false" | "0" => Some(false.into()),
+            _ => None,
+        },
+        "JSON" | "JSONB" => serde_json::from_str(value).ok(),
+        _ => None,
+    };
+
+    parsed.unwrap_or_else(|| value.into())
+}
@@ -0,0 +1,58 @@
+use crate::migrate;
+use crate::opt::ConnectOpts;
+use sqlx::migrate::{MigrationLock, OutOfOrder};
+
+pub async fn add(seed_source: &str, description: &str, reversible: bool) -> anyhow::Result<()> {
+    if migrate::create(seed_source, description, reversible).await? {
+        let quoted_source = format!("{:?}", seed_source);
+        let table_name = match crate::config::seeds_table() {
+            sqlx::migrate::DEFAULT_SEEDS_TABLE_NAME => {
+                "sqlx::migrate::DEFAULT_SEEDS_TABLE_NAME".to_owned()
+            }
+            table_name => format!("{:?}", table_name),
+        };
+
+        print!(
+            r#"
+Congratulations on creating your first seed migration!
+
+Seed migrations are recorded apart from your schema migrations and only applied by
+`sqlx seed run`, so you can skip them where the data isn't wanted, e.g. in production.
+
+To apply them from your application instead, add:
+
+let mut seeds = sqlx::migrate!({});
+seeds.set_table_name({});
+seeds.run(<&your_pool OR &mut your_connection>).await?;
+"#,
+            quoted_source, table_name
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn run(
+    seed_source: &str,
+    connect_opts: &ConnectOpts,
+    table_name: &str,
+    lock: Option<MigrationLock>,
+    dry_run: bool,
+    ignore_missing: bool,
+    target_version: Option<i64>,
+) -> anyhow::Result<()> {
+    // seed data is expected to be added to on several branches, so apply whatever is missing
+    migrate::run(
+        seed_source,
+        connect_opts,
+        table_name,
+        lock,
+        dry_run,
+        ignore_missing,
+        target_version,
+        &[],
+        OutOfOrder::ApplyMissing,
+        None,
+    )
+    .await
+}
@@ -0,0 +1,143 @@
+use std::cmp;
+use std::net::{Ipv4Addr, SocketAddr, TcpListener};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context};
+use tokio::net::TcpStream;
+use url::Url;
+
+use crate::config;
+use crate::opt::ConnectOpts;
+
+/// An SSH port-forward to the database, closed when dropped.
+pub struct Tunnel {
+    ssh: Child,
+}
+
+impl Drop for Tunnel {
+    fn drop(&mut self) {
+        let _ = self.ssh.kill();
+        let _ = self.ssh.wait();
+    }
+}
+
+/// The `ssh` arguments to connect to `destination`, given as `user@host[:port]`, ending with the
+/// destination after `--` so that it can't be read as an option.
+fn destination_args(destination: &str) -> Vec<String> {
+    // a bracketed IPv6 address, or a host name with no more than one `:`
+    match destination.rsplit_once(':') {
+        Some((host, port))
+            if !port.is_empty()
+                && port.bytes().all(|b| b.is_ascii_digit())
+                && (!host.contains(':') || host.ends_with(']')) =>
+        {
+            let host = match host.rsplit_once('@') {
+                Some((user, host)) => format!("{}@{}", user, host.trim_matches(&['[', ']'][..])),
+                None => host.trim_matches(&['[', ']'][..]).to_owned(),
+            };
+
+            vec!["-p".into(), port.into(), "--".into(), host]
+        }
+        _ => vec!["--".into(), destination.into()],
+    }
+}
+
+/// Open an SSH tunnel to the database of `connect_opts` if one is configured with `--ssh` or in
+/// `sqlx.toml`, and point its URL at the local end of the tunnel.
+pub async fn tunnel(connect_opts: &mut ConnectOpts) -> anyhow::Result<Option<Tunnel>> {
+    let destination = match connect_opts.ssh.clone() {
+        Some(destination) => destination,
+        None => match config::ssh() {
+            Some(destination) => destination.to_owned(),
+            None => return Ok(None),
+        },
+    };
+
+    // e.g. `-oProxyCommand=...` in a checked-in `sqlx.toml` would run any command
+    if destination.starts_with('-') {
+        bail!(
+            "invalid SSH destination `{}`: must not start with `-`",
+            destination
+        );
+    }
+
+    let mut url = Url::parse(&connect_opts.database_url).context("invalid database URL")?;
+
+    let default_port = match url.scheme() {
+        "postgres" | "postgresql" => 5432,
+        "mysql" | "mariadb" => 3306,
+        "mssql" | "sqlserver" => 1433,
+        scheme => bail!("SSH tunnels are not supported for `{}` URLs", scheme),
+    };
+
+    let host = url.host_str().unwrap_or("localhost").to_owned();
+    let port = url.port().unwrap_or(default_port);
+
+    // reserve a free port; `ssh` binds it right after
+    let local_port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?
+        .local_addr()?
+        .port();
+
+    let ssh = Command::new("ssh")
+        .arg("-N")
+        .args(["-o", "ExitOnForwardFailure=yes"])
+        .arg("-L")
+        .arg(format!("127.0.0.1:{}:{}:{}", local_port, host, port))
+        .args(destination_args(&destination))
+        .stdout(Stdio::null())
+        .spawn()
+        .context("failed to run `ssh`; is it installed?")?;
+
+    let mut tunnel = Tunnel { ssh };
+
+    let local = SocketAddr::from((Ipv4Addr::LOCALHOST, local_port));
+    let started = Instant::now();
+
+    // `ssh` may ask for a password or to confirm the host key first
+    loop {
+        let connect = TcpStream::connect(local);
+
+        if let Ok(Ok(_)) = tokio::time::timeout(Duration::from_millis(100), connect).await {
+            break;
+        }
+
+        if let Some(status) = tunnel.ssh.try_wait()? {
+            bail!("`ssh` to {} exited with {}", destination, status);
+        }
+
+        if started.elapsed() > Duration::from_secs(cmp::max(connect_opts.connect_timeout, 30)) {
+            bail!("timed out opening an SSH tunnel to {}", destination);
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    url.set_host(Some("127.0.0.1"))?;
+    url.set_port(Some(local_port))
+        .map_err(|()| anyhow::anyhow!("cannot set the port of the database URL"))?;
+
+    connect_opts.database_url = url.into();
+
+    Ok(Some(tunnel))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_destinations() {
+        assert_eq!(destination_args("bastion"), ["--", "bastion"]);
+        assert_eq!(destination_args("me@bastion"), ["--", "me@bastion"]);
+        assert_eq!(
+            destination_args("me@bastion:2222"),
+            ["-p", "2222", "--", "me@bastion"]
+        );
+        assert_eq!(
+            destination_args("me@[::1]:22"),
+            ["-p", "22", "--", "me@::1"]
+        );
+        assert_eq!(destination_args("::1"), ["--", "::1"]);
+    }
+}
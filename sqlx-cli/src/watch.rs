@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use console::style;
+use sqlx::migrate::{MigrationLock, OutOfOrder};
+
+use crate::opt::{CargoFeatures, ConnectOpts, WatchOpts};
+use crate::{migrate, prepare};
+
+/// The files being watched, with their modification time.
+#[derive(Default, PartialEq)]
+struct Snapshot {
+    migrations: BTreeMap<PathBuf, SystemTime>,
+    /// The Rust sources, with whether they invoke query macros.
+    sources: BTreeMap<PathBuf, (SystemTime, bool)>,
+}
+
+impl Snapshot {
+    fn take(migration_source: &Path, previous: &Snapshot) -> anyhow::Result<Snapshot> {
+        let mut snapshot = Snapshot::default();
+
+        if migration_source.is_dir() {
+            for entry in fs::read_dir(migration_source)? {
+                let entry = entry?;
+
+                if entry.file_type()?.is_file() {
+                    snapshot
+                        .migrations
+                        .insert(entry.path(), entry.metadata()?.modified()?);
+                }
+            }
+        }
+
+        snapshot.add_sources(Path::new("."), previous)?;
+
+        Ok(snapshot)
+    }
+
+    fn add_sources(&mut self, dir: &Path, previous: &Snapshot) -> anyhow::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let file_type = entry.file_type()?;
+
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+
+            if file_type.is_dir() {
+                // skip build output, VCS metadata and the query data written by `prepare`
+                if name != "target" && !name.starts_with('.') {
+                    self.add_sources(&path, previous)?;
+                }
+            } else if file_type.is_file() && name.ends_with(".rs") {
+                let modified = entry.metadata()?.modified()?;
+
+                // only read the files which changed
+                let uses_queries = match previous.sources.get(&path) {
+                    Some(&(previous_modified, uses_queries)) if previous_modified == modified => {
+                        uses_queries
+                    }
+                    _ => uses_query_macros(&fs::read_to_string(&path).unwrap_or_default()),
+                };
+
+                self.sources.insert(path, (modified, uses_queries));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether query macros were invoked in a file which changed since `previous`, or was added
+    /// or removed.
+    fn queries_changed(&self, previous: &Snapshot) -> bool {
+        let changed = |sources: &BTreeMap<_, _>, other: &BTreeMap<_, _>| {
+            sources.iter().any(|(path, source @ &(_, uses_queries))| {
+                uses_queries && other.get(path) != Some(source)
+            })
+        };
+
+        changed(&self.sources, &previous.sources) || changed(&previous.sources, &self.sources)
+    }
+}
+
+/// Whether `source` invokes a macro whose name starts with `query`, e.g. `query!()` or
+/// `sqlx::query_as!()`.
+fn uses_query_macros(source: &str) -> bool {
+    source.match_indices('!').any(|(i, _)| {
+        source[..i]
+            .rsplit(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .next()
+            .map_or(false, |name| name.starts_with("query"))
+    })
+}
+
+/// Run `migrate run` and `prepare`, then again whenever a migration changes, and `prepare`
+/// whenever a Rust source invoking query macros changes, until interrupted.
+pub async fn run(
+    migration_source: &str,
+    connect_opts: &ConnectOpts,
+    table_name: &str,
+    lock: Option<MigrationLock>,
+    watch_opts: WatchOpts,
+    features: &CargoFeatures,
+    cargo_args: Vec<String>,
+) -> anyhow::Result<()> {
+    let source = Path::new(migration_source);
+    let mut snapshot = Snapshot::take(source, &Snapshot::default())?;
+
+    let mut migrations_changed = true;
+    let mut queries_changed = true;
+
+    loop {
+        if migrations_changed {
+            let result = migrate::run(
+                migration_source,
+                connect_opts,
+                table_name,
+                lock,
+                false,
+                false,
+                None,
+                &[],
+                OutOfOrder::ApplyMissing,
+                None,
+            )
+            .await;
+
+            // the queries may not be valid until the migrations are applied
+            if let Err(error) = result {
+                print_error(&error);
+                queries_changed = false;
+            }
+        }
+
+        if (migrations_changed || queries_changed) && !watch_opts.no_prepare {
+            if let Err(error) = prepare::run(
+                connect_opts,
+                false,
+                watch_opts.workspace,
+                features,
+                cargo_args.clone(),
+            )
+            .await
+            {
+                print_error(&error);
+            }
+        }
+
+        println!(
+            "{} watching `{}` and the queries in `*.rs` files for changes",
+            style("info:").cyan(),
+            migration_source
+        );
+
+        // `prepare` updates the modification time of the sources to recompile them, which isn't
+        // a change to react to
+        snapshot = Snapshot::take(source, &snapshot)?;
+
+        loop {
+            tokio::time::sleep(watch_opts.interval()).await;
+
+            let next = Snapshot::take(source, &snapshot)?;
+
+            if next == snapshot {
+                continue;
+            }
+
+            migrations_changed = next.migrations != snapshot.migrations;
+            queries_changed = next.queries_changed(&snapshot);
+            snapshot = next;
+
+            if migrations_changed || queries_changed {
+                break;
+            }
+        }
+    }
+}
+
+fn print_error(error: &anyhow::Error) {
+    println!("{} {}", style("error:").bold().red(), error);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uses_query_macros_works() {
+        assert!(uses_query_macros(
+            r#"let row = sqlx::query!("SELECT 1").fetch_one(&pool).await?;"#
+        ));
+        assert!(uses_query_macros(
+            "query_as!(User, \"SELECT * FROM users\")"
+        ));
+        assert!(!uses_query_macros(
+            r#"println!("{}", query); sqlx::query("SELECT 1");"#
+        ));
+        assert!(!uses_query_macros("if !query.is_empty() {}"));
+    }
+}
@@ -92,7 +92,7 @@ impl<'a, DB: Database> Acquire<'a> for &'_ Pool<DB> {
         let conn = self.acquire();
 
         Box::pin(async move {
-            Transaction::begin(MaybePoolConnection::PoolConnection(conn.await?)).await
+            Transaction::start(MaybePoolConnection::PoolConnection(conn.await?)).await
         })
     }
 }
@@ -120,7 +120,7 @@ macro_rules! impl_acquire {
                 'c,
                 Result<crate::transaction::Transaction<'c, $DB>, crate::error::Error>,
             > {
-                crate::transaction::Transaction::begin(self)
+                crate::transaction::Transaction::start(self)
             }
         }
 
@@ -144,7 +144,7 @@ macro_rules! impl_acquire {
                 'c,
                 Result<crate::transaction::Transaction<'c, $DB>, crate::error::Error>,
             > {
-                crate::transaction::Transaction::begin(&mut **self)
+                crate::transaction::Transaction::start(&mut **self)
             }
         }
 
@@ -170,7 +170,7 @@ macro_rules! impl_acquire {
                 't,
                 Result<crate::transaction::Transaction<'t, $DB>, crate::error::Error>,
             > {
-                crate::transaction::Transaction::begin(&mut **self)
+                crate::transaction::Transaction::begin(self)
             }
         }
     };
@@ -1,4 +1,4 @@
-use crate::any::Any;
+use crate::any::{Any, AnyData};
 use crate::arguments::Arguments;
 use crate::encode::Encode;
 use crate::types::Type;
@@ -41,11 +41,16 @@ pub(crate) enum AnyArgumentBufferKind<'q> {
     #[cfg(feature = "sqlite")]
     Sqlite(crate::sqlite::SqliteArguments<'q>),
 
+    #[cfg(feature = "duckdb")]
+    DuckDb(crate::duckdb::DuckDbArguments<'q>),
+
     #[cfg(feature = "mssql")]
     Mssql(
         crate::mssql::MssqlArguments,
         std::marker::PhantomData<&'q ()>,
     ),
+
+    Driver(Vec<AnyData>, std::marker::PhantomData<&'q ()>),
 }
 
 // control flow inferred type bounds would be fun
@@ -69,6 +74,44 @@ impl<'q> From<AnyArguments<'q>> for crate::sqlite::SqliteArguments<'q> {
     }
 }
 
+#[cfg(feature = "duckdb")]
+#[allow(irrefutable_let_patterns)]
+impl<'q> From<AnyArguments<'q>> for crate::duckdb::DuckDbArguments<'q> {
+    fn from(args: AnyArguments<'q>) -> Self {
+        let mut buf = AnyArgumentBuffer(AnyArgumentBufferKind::DuckDb(Default::default()));
+
+        for value in args.values {
+            let _ = value.encode_by_ref(&mut buf);
+        }
+
+        if let AnyArgumentBufferKind::DuckDb(args) = buf.0 {
+            args
+        } else {
+            unreachable!()
+        }
+    }
+}
+
+#[allow(irrefutable_let_patterns)]
+impl<'q> From<AnyArguments<'q>> for Vec<AnyData> {
+    fn from(args: AnyArguments<'q>) -> Self {
+        let mut buf = AnyArgumentBuffer(AnyArgumentBufferKind::Driver(
+            Vec::with_capacity(args.values.len()),
+            std::marker::PhantomData,
+        ));
+
+        for value in args.values {
+            let _ = value.encode_by_ref(&mut buf);
+        }
+
+        if let AnyArgumentBufferKind::Driver(args, _) = buf.0 {
+            args
+        } else {
+            unreachable!()
+        }
+    }
+}
+
 #[cfg(feature = "mysql")]
 #[allow(irrefutable_let_patterns)]
 impl<'q> From<AnyArguments<'q>> for crate::mysql::MySqlArguments {
@@ -1,5 +1,6 @@
 use crate::any::{Any, AnyTypeInfo};
 use crate::column::{Column, ColumnIndex};
+use crate::ext::ustr::UStr;
 
 #[cfg(feature = "postgres")]
 use crate::postgres::{PgColumn, PgRow, PgStatement};
@@ -10,6 +11,9 @@ use crate::mysql::{MySqlColumn, MySqlRow, MySqlStatement};
 #[cfg(feature = "sqlite")]
 use crate::sqlite::{SqliteColumn, SqliteRow, SqliteStatement};
 
+#[cfg(feature = "duckdb")]
+use crate::duckdb::{DuckDbColumn, DuckDbRow, DuckDbStatement};
+
 #[cfg(feature = "mssql")]
 use crate::mssql::{MssqlColumn, MssqlRow, MssqlStatement};
 
@@ -21,6 +25,17 @@ pub struct AnyColumn {
 
 impl crate::column::private_column::Sealed for AnyColumn {}
 
+impl AnyColumn {
+    /// A column of the rows or statements of an installed driver; see
+    /// [`AnyConnectionBackend`][crate::any::AnyConnectionBackend].
+    pub fn new(ordinal: usize, name: impl Into<String>, type_info: AnyTypeInfo) -> Self {
+        Self {
+            kind: AnyColumnKind::Driver(ordinal, UStr::from(name.into())),
+            type_info,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) enum AnyColumnKind {
     #[cfg(feature = "postgres")]
@@ -32,8 +47,13 @@ pub(crate) enum AnyColumnKind {
     #[cfg(feature = "sqlite")]
     Sqlite(SqliteColumn),
 
+    #[cfg(feature = "duckdb")]
+    DuckDb(DuckDbColumn),
+
     #[cfg(feature = "mssql")]
     Mssql(MssqlColumn),
+
+    Driver(usize, UStr),
 }
 
 impl Column for AnyColumn {
@@ -50,8 +70,13 @@ impl Column for AnyColumn {
             #[cfg(feature = "sqlite")]
             AnyColumnKind::Sqlite(row) => row.ordinal(),
 
+            #[cfg(feature = "duckdb")]
+            AnyColumnKind::DuckDb(row) => row.ordinal(),
+
             #[cfg(feature = "mssql")]
             AnyColumnKind::Mssql(row) => row.ordinal(),
+
+            AnyColumnKind::Driver(ordinal, _) => *ordinal,
         }
     }
 
@@ -66,8 +91,13 @@ impl Column for AnyColumn {
             #[cfg(feature = "sqlite")]
             AnyColumnKind::Sqlite(row) => row.name(),
 
+            #[cfg(feature = "duckdb")]
+            AnyColumnKind::DuckDb(row) => row.name(),
+
             #[cfg(feature = "mssql")]
             AnyColumnKind::Mssql(row) => row.name(),
+
+            AnyColumnKind::Driver(_, name) => name,
         }
     }
 
@@ -79,13 +109,14 @@ impl Column for AnyColumn {
 // FIXME: Find a nice way to auto-generate the below or petition Rust to add support for #[cfg]
 //        to trait bounds
 
-// all 4
+// all 5
 
 #[cfg(all(
     feature = "postgres",
     feature = "mysql",
     feature = "mssql",
-    feature = "sqlite"
+    feature = "sqlite",
+    feature = "duckdb"
 ))]
 pub trait AnyColumnIndex:
     ColumnIndex<PgRow>
@@ -96,6 +127,8 @@ pub trait AnyColumnIndex:
     + for<'q> ColumnIndex<MssqlStatement<'q>>
     + ColumnIndex<SqliteRow>
     + for<'q> ColumnIndex<SqliteStatement<'q>>
+    + ColumnIndex<DuckDbRow>
+    + for<'q> ColumnIndex<DuckDbStatement<'q>>
 {
 }
 
@@ -103,7 +136,8 @@ pub trait AnyColumnIndex:
     feature = "postgres",
     feature = "mysql",
     feature = "mssql",
-    feature = "sqlite"
+    feature = "sqlite",
+    feature = "duckdb"
 ))]
 impl<I: ?Sized> AnyColumnIndex for I where
     I: ColumnIndex<PgRow>
@@ -114,14 +148,105 @@ impl<I: ?Sized> AnyColumnIndex for I where
         + for<'q> ColumnIndex<MssqlStatement<'q>>
         + ColumnIndex<SqliteRow>
         + for<'q> ColumnIndex<SqliteStatement<'q>>
+        + ColumnIndex<DuckDbRow>
+        + for<'q> ColumnIndex<DuckDbStatement<'q>>
+{
+}
+
+// only 4 (5)
+
+#[cfg(all(
+    not(feature = "duckdb"),
+    all(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite"
+    )
+))]
+pub trait AnyColumnIndex:
+    ColumnIndex<PgRow>
+    + for<'q> ColumnIndex<PgStatement<'q>>
+    + ColumnIndex<MySqlRow>
+    + for<'q> ColumnIndex<MySqlStatement<'q>>
+    + ColumnIndex<MssqlRow>
+    + for<'q> ColumnIndex<MssqlStatement<'q>>
+    + ColumnIndex<SqliteRow>
+    + for<'q> ColumnIndex<SqliteStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(feature = "duckdb"),
+    all(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite"
+    )
+))]
+impl<I: ?Sized> AnyColumnIndex for I where
+    I: ColumnIndex<PgRow>
+        + for<'q> ColumnIndex<PgStatement<'q>>
+        + ColumnIndex<MySqlRow>
+        + for<'q> ColumnIndex<MySqlStatement<'q>>
+        + ColumnIndex<MssqlRow>
+        + for<'q> ColumnIndex<MssqlStatement<'q>>
+        + ColumnIndex<SqliteRow>
+        + for<'q> ColumnIndex<SqliteStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(feature = "sqlite"),
+    all(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "duckdb"
+    )
+))]
+pub trait AnyColumnIndex:
+    ColumnIndex<PgRow>
+    + for<'q> ColumnIndex<PgStatement<'q>>
+    + ColumnIndex<MySqlRow>
+    + for<'q> ColumnIndex<MySqlStatement<'q>>
+    + ColumnIndex<MssqlRow>
+    + for<'q> ColumnIndex<MssqlStatement<'q>>
+    + ColumnIndex<DuckDbRow>
+    + for<'q> ColumnIndex<DuckDbStatement<'q>>
 {
 }
 
-// only 3 (4)
+#[cfg(all(
+    not(feature = "sqlite"),
+    all(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "duckdb"
+    )
+))]
+impl<I: ?Sized> AnyColumnIndex for I where
+    I: ColumnIndex<PgRow>
+        + for<'q> ColumnIndex<PgStatement<'q>>
+        + ColumnIndex<MySqlRow>
+        + for<'q> ColumnIndex<MySqlStatement<'q>>
+        + ColumnIndex<MssqlRow>
+        + for<'q> ColumnIndex<MssqlStatement<'q>>
+        + ColumnIndex<DuckDbRow>
+        + for<'q> ColumnIndex<DuckDbStatement<'q>>
+{
+}
 
 #[cfg(all(
     not(feature = "mssql"),
-    all(feature = "postgres", feature = "mysql", feature = "sqlite")
+    all(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )
 ))]
 pub trait AnyColumnIndex:
     ColumnIndex<PgRow>
@@ -130,12 +255,19 @@ pub trait AnyColumnIndex:
     + for<'q> ColumnIndex<MySqlStatement<'q>>
     + ColumnIndex<SqliteRow>
     + for<'q> ColumnIndex<SqliteStatement<'q>>
+    + ColumnIndex<DuckDbRow>
+    + for<'q> ColumnIndex<DuckDbStatement<'q>>
 {
 }
 
 #[cfg(all(
     not(feature = "mssql"),
-    all(feature = "postgres", feature = "mysql", feature = "sqlite")
+    all(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )
 ))]
 impl<I: ?Sized> AnyColumnIndex for I where
     I: ColumnIndex<PgRow>
@@ -144,12 +276,19 @@ impl<I: ?Sized> AnyColumnIndex for I where
         + for<'q> ColumnIndex<MySqlStatement<'q>>
         + ColumnIndex<SqliteRow>
         + for<'q> ColumnIndex<SqliteStatement<'q>>
+        + ColumnIndex<DuckDbRow>
+        + for<'q> ColumnIndex<DuckDbStatement<'q>>
 {
 }
 
 #[cfg(all(
     not(feature = "mysql"),
-    all(feature = "postgres", feature = "mssql", feature = "sqlite")
+    all(
+        feature = "postgres",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )
 ))]
 pub trait AnyColumnIndex:
     ColumnIndex<PgRow>
@@ -158,12 +297,19 @@ pub trait AnyColumnIndex:
     + for<'q> ColumnIndex<MssqlStatement<'q>>
     + ColumnIndex<SqliteRow>
     + for<'q> ColumnIndex<SqliteStatement<'q>>
+    + ColumnIndex<DuckDbRow>
+    + for<'q> ColumnIndex<DuckDbStatement<'q>>
 {
 }
 
 #[cfg(all(
     not(feature = "mysql"),
-    all(feature = "postgres", feature = "mssql", feature = "sqlite")
+    all(
+        feature = "postgres",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )
 ))]
 impl<I: ?Sized> AnyColumnIndex for I where
     I: ColumnIndex<PgRow>
@@ -172,11 +318,57 @@ impl<I: ?Sized> AnyColumnIndex for I where
         + for<'q> ColumnIndex<MssqlStatement<'q>>
         + ColumnIndex<SqliteRow>
         + for<'q> ColumnIndex<SqliteStatement<'q>>
+        + ColumnIndex<DuckDbRow>
+        + for<'q> ColumnIndex<DuckDbStatement<'q>>
 {
 }
 
 #[cfg(all(
-    not(feature = "sqlite"),
+    not(feature = "postgres"),
+    all(
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )
+))]
+pub trait AnyColumnIndex:
+    ColumnIndex<MySqlRow>
+    + for<'q> ColumnIndex<MySqlStatement<'q>>
+    + ColumnIndex<MssqlRow>
+    + for<'q> ColumnIndex<MssqlStatement<'q>>
+    + ColumnIndex<SqliteRow>
+    + for<'q> ColumnIndex<SqliteStatement<'q>>
+    + ColumnIndex<DuckDbRow>
+    + for<'q> ColumnIndex<DuckDbStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(feature = "postgres"),
+    all(
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )
+))]
+impl<I: ?Sized> AnyColumnIndex for I where
+    I: ColumnIndex<MySqlRow>
+        + for<'q> ColumnIndex<MySqlStatement<'q>>
+        + ColumnIndex<MssqlRow>
+        + for<'q> ColumnIndex<MssqlStatement<'q>>
+        + ColumnIndex<SqliteRow>
+        + for<'q> ColumnIndex<SqliteStatement<'q>>
+        + ColumnIndex<DuckDbRow>
+        + for<'q> ColumnIndex<DuckDbStatement<'q>>
+{
+}
+
+// only 3 (10)
+
+#[cfg(all(
+    not(any(feature = "sqlite", feature = "duckdb")),
     all(feature = "postgres", feature = "mysql", feature = "mssql")
 ))]
 pub trait AnyColumnIndex:
@@ -190,7 +382,7 @@ pub trait AnyColumnIndex:
 }
 
 #[cfg(all(
-    not(feature = "sqlite"),
+    not(any(feature = "sqlite", feature = "duckdb")),
     all(feature = "postgres", feature = "mysql", feature = "mssql")
 ))]
 impl<I: ?Sized> AnyColumnIndex for I where
@@ -204,157 +396,381 @@ impl<I: ?Sized> AnyColumnIndex for I where
 }
 
 #[cfg(all(
-    not(feature = "postgres"),
-    all(feature = "sqlite", feature = "mysql", feature = "mssql")
+    not(any(feature = "mssql", feature = "duckdb")),
+    all(feature = "postgres", feature = "mysql", feature = "sqlite")
 ))]
 pub trait AnyColumnIndex:
-    ColumnIndex<SqliteRow>
-    + for<'q> ColumnIndex<SqliteStatement<'q>>
+    ColumnIndex<PgRow>
+    + for<'q> ColumnIndex<PgStatement<'q>>
     + ColumnIndex<MySqlRow>
     + for<'q> ColumnIndex<MySqlStatement<'q>>
-    + ColumnIndex<MssqlRow>
-    + for<'q> ColumnIndex<MssqlStatement<'q>>
+    + ColumnIndex<SqliteRow>
+    + for<'q> ColumnIndex<SqliteStatement<'q>>
 {
 }
 
 #[cfg(all(
-    not(feature = "postgres"),
-    all(feature = "sqlite", feature = "mysql", feature = "mssql")
+    not(any(feature = "mssql", feature = "duckdb")),
+    all(feature = "postgres", feature = "mysql", feature = "sqlite")
 ))]
 impl<I: ?Sized> AnyColumnIndex for I where
-    I: ColumnIndex<SqliteRow>
-        + for<'q> ColumnIndex<SqliteStatement<'q>>
+    I: ColumnIndex<PgRow>
+        + for<'q> ColumnIndex<PgStatement<'q>>
         + ColumnIndex<MySqlRow>
         + for<'q> ColumnIndex<MySqlStatement<'q>>
-        + ColumnIndex<MssqlRow>
-        + for<'q> ColumnIndex<MssqlStatement<'q>>
+        + ColumnIndex<SqliteRow>
+        + for<'q> ColumnIndex<SqliteStatement<'q>>
 {
 }
 
-// only 2 (6)
-
 #[cfg(all(
     not(any(feature = "mssql", feature = "sqlite")),
-    all(feature = "postgres", feature = "mysql")
+    all(feature = "postgres", feature = "mysql", feature = "duckdb")
 ))]
 pub trait AnyColumnIndex:
     ColumnIndex<PgRow>
     + for<'q> ColumnIndex<PgStatement<'q>>
     + ColumnIndex<MySqlRow>
     + for<'q> ColumnIndex<MySqlStatement<'q>>
+    + ColumnIndex<DuckDbRow>
+    + for<'q> ColumnIndex<DuckDbStatement<'q>>
 {
 }
 
 #[cfg(all(
     not(any(feature = "mssql", feature = "sqlite")),
-    all(feature = "postgres", feature = "mysql")
+    all(feature = "postgres", feature = "mysql", feature = "duckdb")
 ))]
 impl<I: ?Sized> AnyColumnIndex for I where
     I: ColumnIndex<PgRow>
         + for<'q> ColumnIndex<PgStatement<'q>>
         + ColumnIndex<MySqlRow>
         + for<'q> ColumnIndex<MySqlStatement<'q>>
+        + ColumnIndex<DuckDbRow>
+        + for<'q> ColumnIndex<DuckDbStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mysql", feature = "duckdb")),
+    all(feature = "postgres", feature = "mssql", feature = "sqlite")
+))]
+pub trait AnyColumnIndex:
+    ColumnIndex<PgRow>
+    + for<'q> ColumnIndex<PgStatement<'q>>
+    + ColumnIndex<MssqlRow>
+    + for<'q> ColumnIndex<MssqlStatement<'q>>
+    + ColumnIndex<SqliteRow>
+    + for<'q> ColumnIndex<SqliteStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mysql", feature = "duckdb")),
+    all(feature = "postgres", feature = "mssql", feature = "sqlite")
+))]
+impl<I: ?Sized> AnyColumnIndex for I where
+    I: ColumnIndex<PgRow>
+        + for<'q> ColumnIndex<PgStatement<'q>>
+        + ColumnIndex<MssqlRow>
+        + for<'q> ColumnIndex<MssqlStatement<'q>>
+        + ColumnIndex<SqliteRow>
+        + for<'q> ColumnIndex<SqliteStatement<'q>>
 {
 }
 
 #[cfg(all(
     not(any(feature = "mysql", feature = "sqlite")),
-    all(feature = "postgres", feature = "mssql")
+    all(feature = "postgres", feature = "mssql", feature = "duckdb")
 ))]
 pub trait AnyColumnIndex:
     ColumnIndex<PgRow>
     + for<'q> ColumnIndex<PgStatement<'q>>
     + ColumnIndex<MssqlRow>
     + for<'q> ColumnIndex<MssqlStatement<'q>>
+    + ColumnIndex<DuckDbRow>
+    + for<'q> ColumnIndex<DuckDbStatement<'q>>
 {
 }
 
 #[cfg(all(
     not(any(feature = "mysql", feature = "sqlite")),
-    all(feature = "postgres", feature = "mssql")
+    all(feature = "postgres", feature = "mssql", feature = "duckdb")
 ))]
 impl<I: ?Sized> AnyColumnIndex for I where
     I: ColumnIndex<PgRow>
         + for<'q> ColumnIndex<PgStatement<'q>>
         + ColumnIndex<MssqlRow>
         + for<'q> ColumnIndex<MssqlStatement<'q>>
+        + ColumnIndex<DuckDbRow>
+        + for<'q> ColumnIndex<DuckDbStatement<'q>>
 {
 }
 
 #[cfg(all(
     not(any(feature = "mysql", feature = "mssql")),
-    all(feature = "postgres", feature = "sqlite")
+    all(feature = "postgres", feature = "sqlite", feature = "duckdb")
 ))]
 pub trait AnyColumnIndex:
     ColumnIndex<PgRow>
     + for<'q> ColumnIndex<PgStatement<'q>>
     + ColumnIndex<SqliteRow>
     + for<'q> ColumnIndex<SqliteStatement<'q>>
+    + ColumnIndex<DuckDbRow>
+    + for<'q> ColumnIndex<DuckDbStatement<'q>>
 {
 }
 
 #[cfg(all(
     not(any(feature = "mysql", feature = "mssql")),
-    all(feature = "postgres", feature = "sqlite")
+    all(feature = "postgres", feature = "sqlite", feature = "duckdb")
 ))]
 impl<I: ?Sized> AnyColumnIndex for I where
     I: ColumnIndex<PgRow>
         + for<'q> ColumnIndex<PgStatement<'q>>
         + ColumnIndex<SqliteRow>
         + for<'q> ColumnIndex<SqliteStatement<'q>>
+        + ColumnIndex<DuckDbRow>
+        + for<'q> ColumnIndex<DuckDbStatement<'q>>
 {
 }
 
 #[cfg(all(
-    not(any(feature = "postgres", feature = "sqlite")),
-    all(feature = "mssql", feature = "mysql")
+    not(any(feature = "postgres", feature = "duckdb")),
+    all(feature = "mysql", feature = "mssql", feature = "sqlite")
 ))]
 pub trait AnyColumnIndex:
-    ColumnIndex<MssqlRow>
+    ColumnIndex<MySqlRow>
+    + for<'q> ColumnIndex<MySqlStatement<'q>>
+    + ColumnIndex<MssqlRow>
     + for<'q> ColumnIndex<MssqlStatement<'q>>
-    + ColumnIndex<MySqlRow>
+    + ColumnIndex<SqliteRow>
+    + for<'q> ColumnIndex<SqliteStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "duckdb")),
+    all(feature = "mysql", feature = "mssql", feature = "sqlite")
+))]
+impl<I: ?Sized> AnyColumnIndex for I where
+    I: ColumnIndex<MySqlRow>
+        + for<'q> ColumnIndex<MySqlStatement<'q>>
+        + ColumnIndex<MssqlRow>
+        + for<'q> ColumnIndex<MssqlStatement<'q>>
+        + ColumnIndex<SqliteRow>
+        + for<'q> ColumnIndex<SqliteStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "sqlite")),
+    all(feature = "mysql", feature = "mssql", feature = "duckdb")
+))]
+pub trait AnyColumnIndex:
+    ColumnIndex<MySqlRow>
     + for<'q> ColumnIndex<MySqlStatement<'q>>
+    + ColumnIndex<MssqlRow>
+    + for<'q> ColumnIndex<MssqlStatement<'q>>
+    + ColumnIndex<DuckDbRow>
+    + for<'q> ColumnIndex<DuckDbStatement<'q>>
 {
 }
 
 #[cfg(all(
     not(any(feature = "postgres", feature = "sqlite")),
-    all(feature = "mssql", feature = "mysql")
+    all(feature = "mysql", feature = "mssql", feature = "duckdb")
 ))]
 impl<I: ?Sized> AnyColumnIndex for I where
-    I: ColumnIndex<MssqlRow>
+    I: ColumnIndex<MySqlRow>
+        + for<'q> ColumnIndex<MySqlStatement<'q>>
+        + ColumnIndex<MssqlRow>
         + for<'q> ColumnIndex<MssqlStatement<'q>>
-        + ColumnIndex<MySqlRow>
+        + ColumnIndex<DuckDbRow>
+        + for<'q> ColumnIndex<DuckDbStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mssql")),
+    all(feature = "mysql", feature = "sqlite", feature = "duckdb")
+))]
+pub trait AnyColumnIndex:
+    ColumnIndex<MySqlRow>
+    + for<'q> ColumnIndex<MySqlStatement<'q>>
+    + ColumnIndex<SqliteRow>
+    + for<'q> ColumnIndex<SqliteStatement<'q>>
+    + ColumnIndex<DuckDbRow>
+    + for<'q> ColumnIndex<DuckDbStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mssql")),
+    all(feature = "mysql", feature = "sqlite", feature = "duckdb")
+))]
+impl<I: ?Sized> AnyColumnIndex for I where
+    I: ColumnIndex<MySqlRow>
         + for<'q> ColumnIndex<MySqlStatement<'q>>
+        + ColumnIndex<SqliteRow>
+        + for<'q> ColumnIndex<SqliteStatement<'q>>
+        + ColumnIndex<DuckDbRow>
+        + for<'q> ColumnIndex<DuckDbStatement<'q>>
 {
 }
 
 #[cfg(all(
     not(any(feature = "postgres", feature = "mysql")),
-    all(feature = "mssql", feature = "sqlite")
+    all(feature = "mssql", feature = "sqlite", feature = "duckdb")
 ))]
 pub trait AnyColumnIndex:
     ColumnIndex<MssqlRow>
     + for<'q> ColumnIndex<MssqlStatement<'q>>
     + ColumnIndex<SqliteRow>
     + for<'q> ColumnIndex<SqliteStatement<'q>>
+    + ColumnIndex<DuckDbRow>
+    + for<'q> ColumnIndex<DuckDbStatement<'q>>
 {
 }
 
 #[cfg(all(
     not(any(feature = "postgres", feature = "mysql")),
-    all(feature = "mssql", feature = "sqlite")
+    all(feature = "mssql", feature = "sqlite", feature = "duckdb")
 ))]
 impl<I: ?Sized> AnyColumnIndex for I where
     I: ColumnIndex<MssqlRow>
         + for<'q> ColumnIndex<MssqlStatement<'q>>
         + ColumnIndex<SqliteRow>
         + for<'q> ColumnIndex<SqliteStatement<'q>>
+        + ColumnIndex<DuckDbRow>
+        + for<'q> ColumnIndex<DuckDbStatement<'q>>
 {
 }
 
+// only 2 (10)
+
 #[cfg(all(
-    not(any(feature = "postgres", feature = "mssql")),
+    not(any(feature = "mssql", feature = "sqlite", feature = "duckdb")),
+    all(feature = "postgres", feature = "mysql")
+))]
+pub trait AnyColumnIndex:
+    ColumnIndex<PgRow>
+    + for<'q> ColumnIndex<PgStatement<'q>>
+    + ColumnIndex<MySqlRow>
+    + for<'q> ColumnIndex<MySqlStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mssql", feature = "sqlite", feature = "duckdb")),
+    all(feature = "postgres", feature = "mysql")
+))]
+impl<I: ?Sized> AnyColumnIndex for I where
+    I: ColumnIndex<PgRow>
+        + for<'q> ColumnIndex<PgStatement<'q>>
+        + ColumnIndex<MySqlRow>
+        + for<'q> ColumnIndex<MySqlStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mysql", feature = "sqlite", feature = "duckdb")),
+    all(feature = "postgres", feature = "mssql")
+))]
+pub trait AnyColumnIndex:
+    ColumnIndex<PgRow>
+    + for<'q> ColumnIndex<PgStatement<'q>>
+    + ColumnIndex<MssqlRow>
+    + for<'q> ColumnIndex<MssqlStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mysql", feature = "sqlite", feature = "duckdb")),
+    all(feature = "postgres", feature = "mssql")
+))]
+impl<I: ?Sized> AnyColumnIndex for I where
+    I: ColumnIndex<PgRow>
+        + for<'q> ColumnIndex<PgStatement<'q>>
+        + ColumnIndex<MssqlRow>
+        + for<'q> ColumnIndex<MssqlStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mysql", feature = "mssql", feature = "duckdb")),
+    all(feature = "postgres", feature = "sqlite")
+))]
+pub trait AnyColumnIndex:
+    ColumnIndex<PgRow>
+    + for<'q> ColumnIndex<PgStatement<'q>>
+    + ColumnIndex<SqliteRow>
+    + for<'q> ColumnIndex<SqliteStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mysql", feature = "mssql", feature = "duckdb")),
+    all(feature = "postgres", feature = "sqlite")
+))]
+impl<I: ?Sized> AnyColumnIndex for I where
+    I: ColumnIndex<PgRow>
+        + for<'q> ColumnIndex<PgStatement<'q>>
+        + ColumnIndex<SqliteRow>
+        + for<'q> ColumnIndex<SqliteStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mysql", feature = "mssql", feature = "sqlite")),
+    all(feature = "postgres", feature = "duckdb")
+))]
+pub trait AnyColumnIndex:
+    ColumnIndex<PgRow>
+    + for<'q> ColumnIndex<PgStatement<'q>>
+    + ColumnIndex<DuckDbRow>
+    + for<'q> ColumnIndex<DuckDbStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mysql", feature = "mssql", feature = "sqlite")),
+    all(feature = "postgres", feature = "duckdb")
+))]
+impl<I: ?Sized> AnyColumnIndex for I where
+    I: ColumnIndex<PgRow>
+        + for<'q> ColumnIndex<PgStatement<'q>>
+        + ColumnIndex<DuckDbRow>
+        + for<'q> ColumnIndex<DuckDbStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "sqlite", feature = "duckdb")),
+    all(feature = "mysql", feature = "mssql")
+))]
+pub trait AnyColumnIndex:
+    ColumnIndex<MySqlRow>
+    + for<'q> ColumnIndex<MySqlStatement<'q>>
+    + ColumnIndex<MssqlRow>
+    + for<'q> ColumnIndex<MssqlStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "sqlite", feature = "duckdb")),
+    all(feature = "mysql", feature = "mssql")
+))]
+impl<I: ?Sized> AnyColumnIndex for I where
+    I: ColumnIndex<MySqlRow>
+        + for<'q> ColumnIndex<MySqlStatement<'q>>
+        + ColumnIndex<MssqlRow>
+        + for<'q> ColumnIndex<MssqlStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mssql", feature = "duckdb")),
     all(feature = "mysql", feature = "sqlite")
 ))]
 pub trait AnyColumnIndex:
@@ -366,7 +782,7 @@ pub trait AnyColumnIndex:
 }
 
 #[cfg(all(
-    not(any(feature = "postgres", feature = "mssql")),
+    not(any(feature = "postgres", feature = "mssql", feature = "duckdb")),
     all(feature = "mysql", feature = "sqlite")
 ))]
 impl<I: ?Sized> AnyColumnIndex for I where
@@ -377,16 +793,122 @@ impl<I: ?Sized> AnyColumnIndex for I where
 {
 }
 
-// only 1 (4)
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mssql", feature = "sqlite")),
+    all(feature = "mysql", feature = "duckdb")
+))]
+pub trait AnyColumnIndex:
+    ColumnIndex<MySqlRow>
+    + for<'q> ColumnIndex<MySqlStatement<'q>>
+    + ColumnIndex<DuckDbRow>
+    + for<'q> ColumnIndex<DuckDbStatement<'q>>
+{
+}
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "mssql", feature = "sqlite")),
+    not(any(feature = "postgres", feature = "mssql", feature = "sqlite")),
+    all(feature = "mysql", feature = "duckdb")
+))]
+impl<I: ?Sized> AnyColumnIndex for I where
+    I: ColumnIndex<MySqlRow>
+        + for<'q> ColumnIndex<MySqlStatement<'q>>
+        + ColumnIndex<DuckDbRow>
+        + for<'q> ColumnIndex<DuckDbStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mysql", feature = "duckdb")),
+    all(feature = "mssql", feature = "sqlite")
+))]
+pub trait AnyColumnIndex:
+    ColumnIndex<MssqlRow>
+    + for<'q> ColumnIndex<MssqlStatement<'q>>
+    + ColumnIndex<SqliteRow>
+    + for<'q> ColumnIndex<SqliteStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mysql", feature = "duckdb")),
+    all(feature = "mssql", feature = "sqlite")
+))]
+impl<I: ?Sized> AnyColumnIndex for I where
+    I: ColumnIndex<MssqlRow>
+        + for<'q> ColumnIndex<MssqlStatement<'q>>
+        + ColumnIndex<SqliteRow>
+        + for<'q> ColumnIndex<SqliteStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mysql", feature = "sqlite")),
+    all(feature = "mssql", feature = "duckdb")
+))]
+pub trait AnyColumnIndex:
+    ColumnIndex<MssqlRow>
+    + for<'q> ColumnIndex<MssqlStatement<'q>>
+    + ColumnIndex<DuckDbRow>
+    + for<'q> ColumnIndex<DuckDbStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mysql", feature = "sqlite")),
+    all(feature = "mssql", feature = "duckdb")
+))]
+impl<I: ?Sized> AnyColumnIndex for I where
+    I: ColumnIndex<MssqlRow>
+        + for<'q> ColumnIndex<MssqlStatement<'q>>
+        + ColumnIndex<DuckDbRow>
+        + for<'q> ColumnIndex<DuckDbStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mysql", feature = "mssql")),
+    all(feature = "sqlite", feature = "duckdb")
+))]
+pub trait AnyColumnIndex:
+    ColumnIndex<SqliteRow>
+    + for<'q> ColumnIndex<SqliteStatement<'q>>
+    + ColumnIndex<DuckDbRow>
+    + for<'q> ColumnIndex<DuckDbStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mysql", feature = "mssql")),
+    all(feature = "sqlite", feature = "duckdb")
+))]
+impl<I: ?Sized> AnyColumnIndex for I where
+    I: ColumnIndex<SqliteRow>
+        + for<'q> ColumnIndex<SqliteStatement<'q>>
+        + ColumnIndex<DuckDbRow>
+        + for<'q> ColumnIndex<DuckDbStatement<'q>>
+{
+}
+
+// only 1 (5)
+
+#[cfg(all(
+    not(any(
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )),
     feature = "postgres"
 ))]
 pub trait AnyColumnIndex: ColumnIndex<PgRow> + for<'q> ColumnIndex<PgStatement<'q>> {}
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "mssql", feature = "sqlite")),
+    not(any(
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )),
     feature = "postgres"
 ))]
 impl<I: ?Sized> AnyColumnIndex for I where
@@ -395,13 +917,23 @@ impl<I: ?Sized> AnyColumnIndex for I where
 }
 
 #[cfg(all(
-    not(any(feature = "postgres", feature = "mssql", feature = "sqlite")),
+    not(any(
+        feature = "postgres",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )),
     feature = "mysql"
 ))]
 pub trait AnyColumnIndex: ColumnIndex<MySqlRow> + for<'q> ColumnIndex<MySqlStatement<'q>> {}
 
 #[cfg(all(
-    not(any(feature = "postgres", feature = "mssql", feature = "sqlite")),
+    not(any(
+        feature = "postgres",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )),
     feature = "mysql"
 ))]
 impl<I: ?Sized> AnyColumnIndex for I where
@@ -410,13 +942,23 @@ impl<I: ?Sized> AnyColumnIndex for I where
 }
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "postgres", feature = "sqlite")),
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )),
     feature = "mssql"
 ))]
 pub trait AnyColumnIndex: ColumnIndex<MssqlRow> + for<'q> ColumnIndex<MssqlStatement<'q>> {}
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "postgres", feature = "sqlite")),
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )),
     feature = "mssql"
 ))]
 impl<I: ?Sized> AnyColumnIndex for I where
@@ -425,7 +967,12 @@ impl<I: ?Sized> AnyColumnIndex for I where
 }
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "mssql", feature = "postgres")),
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "duckdb"
+    )),
     feature = "sqlite"
 ))]
 pub trait AnyColumnIndex:
@@ -434,10 +981,43 @@ pub trait AnyColumnIndex:
 }
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "mssql", feature = "postgres")),
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "duckdb"
+    )),
     feature = "sqlite"
 ))]
 impl<I: ?Sized> AnyColumnIndex for I where
     I: ColumnIndex<SqliteRow> + for<'q> ColumnIndex<SqliteStatement<'q>>
 {
 }
+
+#[cfg(all(
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite"
+    )),
+    feature = "duckdb"
+))]
+pub trait AnyColumnIndex:
+    ColumnIndex<DuckDbRow> + for<'q> ColumnIndex<DuckDbStatement<'q>>
+{
+}
+
+#[cfg(all(
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite"
+    )),
+    feature = "duckdb"
+))]
+impl<I: ?Sized> AnyColumnIndex for I where
+    I: ColumnIndex<DuckDbRow> + for<'q> ColumnIndex<DuckDbStatement<'q>>
+{
+}
@@ -28,12 +28,24 @@ impl AnyConnection {
                     .map(AnyConnectionKind::Sqlite)
             }
 
+            #[cfg(feature = "duckdb")]
+            AnyConnectOptionsKind::DuckDb(options) => {
+                crate::duckdb::DuckDbConnection::connect_with(options)
+                    .await
+                    .map(AnyConnectionKind::DuckDb)
+            }
+
             #[cfg(feature = "mssql")]
             AnyConnectOptionsKind::Mssql(options) => {
                 crate::mssql::MssqlConnection::connect_with(options)
                     .await
                     .map(AnyConnectionKind::Mssql)
             }
+
+            AnyConnectOptionsKind::Driver(options) => options
+                .connect()
+                .await
+                .map(|conn| AnyConnectionKind::Driver(options.driver.name(), conn)),
         }
         .map(AnyConnection)
     }
@@ -1,4 +1,5 @@
 use crate::any::connection::AnyConnectionKind;
+use crate::any::row::AnyColumnsCache;
 use crate::any::{
     Any, AnyColumn, AnyConnection, AnyQueryResult, AnyRow, AnyStatement, AnyTypeInfo,
 };
@@ -25,30 +26,56 @@ impl<'c> Executor<'c> for &'c mut AnyConnection {
         let arguments = query.take_arguments();
         let query = query.sql();
 
+        // the rows of a result set share their columns
+        let mut columns = AnyColumnsCache::default();
+
         match &mut self.0 {
             #[cfg(feature = "postgres")]
             AnyConnectionKind::Postgres(conn) => conn
                 .fetch_many((query, arguments.map(Into::into)))
-                .map_ok(|v| v.map_right(Into::into).map_left(Into::into))
+                .map_ok(move |v| {
+                    v.map_right(|row| row.into_any(&mut columns))
+                        .map_left(Into::into)
+                })
                 .boxed(),
 
             #[cfg(feature = "mysql")]
             AnyConnectionKind::MySql(conn) => conn
                 .fetch_many((query, arguments.map(Into::into)))
-                .map_ok(|v| v.map_right(Into::into).map_left(Into::into))
+                .map_ok(move |v| {
+                    v.map_right(|row| row.into_any(&mut columns))
+                        .map_left(Into::into)
+                })
                 .boxed(),
 
             #[cfg(feature = "sqlite")]
             AnyConnectionKind::Sqlite(conn) => conn
                 .fetch_many((query, arguments.map(Into::into)))
-                .map_ok(|v| v.map_right(Into::into).map_left(Into::into))
+                .map_ok(move |v| {
+                    v.map_right(|row| row.into_any(&mut columns))
+                        .map_left(Into::into)
+                })
+                .boxed(),
+
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(conn) => conn
+                .fetch_many((query, arguments.map(Into::into)))
+                .map_ok(move |v| {
+                    v.map_right(|row| row.into_any(&mut columns))
+                        .map_left(Into::into)
+                })
                 .boxed(),
 
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(conn) => conn
                 .fetch_many((query, arguments.map(Into::into)))
-                .map_ok(|v| v.map_right(Into::into).map_left(Into::into))
+                .map_ok(move |v| {
+                    v.map_right(|row| row.into_any(&mut columns))
+                        .map_left(Into::into)
+                })
                 .boxed(),
+
+            AnyConnectionKind::Driver(_, conn) => conn.fetch_many(query, arguments.map(Into::into)),
         }
     }
 
@@ -83,11 +110,22 @@ impl<'c> Executor<'c> for &'c mut AnyConnection {
                     .await?
                     .map(Into::into),
 
+                #[cfg(feature = "duckdb")]
+                AnyConnectionKind::DuckDb(conn) => conn
+                    .fetch_optional((query, arguments.map(Into::into)))
+                    .await?
+                    .map(Into::into),
+
                 #[cfg(feature = "mssql")]
                 AnyConnectionKind::Mssql(conn) => conn
                     .fetch_optional((query, arguments.map(Into::into)))
                     .await?
                     .map(Into::into),
+
+                AnyConnectionKind::Driver(_, conn) => {
+                    conn.fetch_optional(query, arguments.map(Into::into))
+                        .await?
+                }
             })
         })
     }
@@ -112,8 +150,13 @@ impl<'c> Executor<'c> for &'c mut AnyConnection {
                 #[cfg(feature = "sqlite")]
                 AnyConnectionKind::Sqlite(conn) => conn.prepare(sql).await.map(Into::into)?,
 
+                #[cfg(feature = "duckdb")]
+                AnyConnectionKind::DuckDb(conn) => conn.prepare(sql).await.map(Into::into)?,
+
                 #[cfg(feature = "mssql")]
                 AnyConnectionKind::Mssql(conn) => conn.prepare(sql).await.map(Into::into)?,
+
+                AnyConnectionKind::Driver(_, conn) => conn.prepare(sql).await?,
             })
         })
     }
@@ -136,13 +179,30 @@ impl<'c> Executor<'c> for &'c mut AnyConnection {
                 #[cfg(feature = "sqlite")]
                 AnyConnectionKind::Sqlite(conn) => conn.describe(sql).await.map(map_describe)?,
 
+                #[cfg(feature = "duckdb")]
+                AnyConnectionKind::DuckDb(conn) => conn.describe(sql).await.map(map_describe)?,
+
                 #[cfg(feature = "mssql")]
                 AnyConnectionKind::Mssql(conn) => conn.describe(sql).await.map(map_describe)?,
+
+                AnyConnectionKind::Driver(_, conn) => {
+                    conn.prepare(sql).await.map(describe_statement)?
+                }
             })
         })
     }
 }
 
+// an installed driver can't describe the statements; the columns of a prepared statement are
+// all that is known of them
+fn describe_statement(statement: AnyStatement<'_>) -> Describe<Any> {
+    Describe {
+        nullable: vec![None; statement.columns.len()],
+        parameters: statement.parameters,
+        columns: statement.columns,
+    }
+}
+
 fn map_describe<DB: Database>(info: Describe<DB>) -> Describe<Any>
 where
     AnyTypeInfo: From<DB::TypeInfo>,
@@ -1,7 +1,7 @@
 use futures_core::future::BoxFuture;
 
-use crate::any::{Any, AnyConnectOptions, AnyKind};
-use crate::connection::Connection;
+use crate::any::{Any, AnyConnectOptions, AnyConnectionBackend, AnyKind};
+use crate::connection::{Connection, ServerCapabilities, ServerVersion};
 use crate::error::Error;
 
 #[cfg(feature = "postgres")]
@@ -10,12 +10,15 @@ use crate::postgres;
 #[cfg(feature = "sqlite")]
 use crate::sqlite;
 
+#[cfg(feature = "duckdb")]
+use crate::duckdb;
+
 #[cfg(feature = "mssql")]
 use crate::mssql;
 
 #[cfg(feature = "mysql")]
 use crate::mysql;
-use crate::transaction::Transaction;
+use crate::transaction::{Transaction, TransactionOptions};
 
 mod establish;
 mod executor;
@@ -47,6 +50,11 @@ pub enum AnyConnectionKind {
 
     #[cfg(feature = "sqlite")]
     Sqlite(sqlite::SqliteConnection),
+
+    #[cfg(feature = "duckdb")]
+    DuckDb(duckdb::DuckDbConnection),
+
+    Driver(&'static str, Box<dyn AnyConnectionBackend>),
 }
 
 impl AnyConnectionKind {
@@ -61,8 +69,13 @@ impl AnyConnectionKind {
             #[cfg(feature = "sqlite")]
             AnyConnectionKind::Sqlite(_) => AnyKind::Sqlite,
 
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(_) => AnyKind::DuckDb,
+
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(_) => AnyKind::Mssql,
+
+            AnyConnectionKind::Driver(name, _) => AnyKind::Driver(name),
         }
     }
 }
@@ -91,8 +104,13 @@ macro_rules! delegate_to {
             #[cfg(feature = "sqlite")]
             AnyConnectionKind::Sqlite(conn) => conn.$method($($arg),*),
 
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(conn) => conn.$method($($arg),*),
+
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(conn) => conn.$method($($arg),*),
+
+            AnyConnectionKind::Driver(_, conn) => conn.$method($($arg),*),
         }
     };
 }
@@ -109,8 +127,13 @@ macro_rules! delegate_to_mut {
             #[cfg(feature = "sqlite")]
             AnyConnectionKind::Sqlite(conn) => conn.$method($($arg),*),
 
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(conn) => conn.$method($($arg),*),
+
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(conn) => conn.$method($($arg),*),
+
+            AnyConnectionKind::Driver(_, conn) => conn.$method($($arg),*),
         }
     };
 }
@@ -131,8 +154,13 @@ impl Connection for AnyConnection {
             #[cfg(feature = "sqlite")]
             AnyConnectionKind::Sqlite(conn) => conn.close(),
 
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(conn) => conn.close(),
+
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(conn) => conn.close(),
+
+            AnyConnectionKind::Driver(_, conn) => conn.close(),
         }
     }
 
@@ -147,8 +175,13 @@ impl Connection for AnyConnection {
             #[cfg(feature = "sqlite")]
             AnyConnectionKind::Sqlite(conn) => conn.close_hard(),
 
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(conn) => conn.close_hard(),
+
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(conn) => conn.close_hard(),
+
+            AnyConnectionKind::Driver(_, conn) => conn.close_hard(),
         }
     }
 
@@ -156,11 +189,63 @@ impl Connection for AnyConnection {
         delegate_to_mut!(self.ping())
     }
 
+    fn transaction_depth(&self) -> usize {
+        match &self.0 {
+            #[cfg(feature = "postgres")]
+            AnyConnectionKind::Postgres(conn) => conn.transaction_depth(),
+
+            #[cfg(feature = "mysql")]
+            AnyConnectionKind::MySql(conn) => conn.transaction_depth(),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnectionKind::Sqlite(conn) => conn.transaction_depth(),
+
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(conn) => conn.transaction_depth(),
+
+            #[cfg(feature = "mssql")]
+            AnyConnectionKind::Mssql(conn) => conn.transaction_depth(),
+
+            AnyConnectionKind::Driver(..) => 0,
+        }
+    }
+
+    fn statement_count(&self) -> u64 {
+        match &self.0 {
+            #[cfg(feature = "postgres")]
+            AnyConnectionKind::Postgres(conn) => conn.statement_count(),
+
+            #[cfg(feature = "mysql")]
+            AnyConnectionKind::MySql(conn) => conn.statement_count(),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnectionKind::Sqlite(conn) => conn.statement_count(),
+
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(conn) => conn.statement_count(),
+
+            #[cfg(feature = "mssql")]
+            AnyConnectionKind::Mssql(conn) => conn.statement_count(),
+
+            AnyConnectionKind::Driver(..) => 0,
+        }
+    }
+
     fn begin(&mut self) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
     where
         Self: Sized,
     {
-        Transaction::begin(self)
+        Transaction::start(self)
+    }
+
+    fn begin_with(
+        &mut self,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized,
+    {
+        Transaction::start_with(self, options)
     }
 
     fn cached_statements_size(&self) -> usize {
@@ -174,9 +259,14 @@ impl Connection for AnyConnection {
             #[cfg(feature = "sqlite")]
             AnyConnectionKind::Sqlite(conn) => conn.cached_statements_size(),
 
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(conn) => conn.cached_statements_size(),
+
             // no cache
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(_) => 0,
+
+            AnyConnectionKind::Driver(_, conn) => conn.cached_statements_size(),
         }
     }
 
@@ -191,9 +281,14 @@ impl Connection for AnyConnection {
             #[cfg(feature = "sqlite")]
             AnyConnectionKind::Sqlite(conn) => conn.clear_cached_statements(),
 
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(conn) => conn.clear_cached_statements(),
+
             // no cache
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(_) => Box::pin(futures_util::future::ok(())),
+
+            AnyConnectionKind::Driver(_, conn) => conn.clear_cached_statements(),
         }
     }
 
@@ -202,6 +297,14 @@ impl Connection for AnyConnection {
         delegate_to_mut!(self.flush())
     }
 
+    fn server_version(&self) -> Option<ServerVersion> {
+        delegate_to!(self.server_version())
+    }
+
+    fn server_capabilities(&self) -> ServerCapabilities {
+        delegate_to!(self.server_capabilities())
+    }
+
     #[doc(hidden)]
     fn should_flush(&self) -> bool {
         delegate_to!(self.should_flush())
@@ -235,3 +338,10 @@ impl From<sqlite::SqliteConnection> for AnyConnection {
         AnyConnection(AnyConnectionKind::Sqlite(conn))
     }
 }
+
+#[cfg(feature = "duckdb")]
+impl From<duckdb::DuckDbConnection> for AnyConnection {
+    fn from(conn: duckdb::DuckDbConnection) -> Self {
+        AnyConnection(AnyConnectionKind::DuckDb(conn))
+    }
+}
@@ -0,0 +1,274 @@
+use crate::error::BoxDynError;
+
+/// A value exchanged with a driver installed with [`install_driver`][crate::any::install_driver].
+///
+/// The arguments of a query are converted to these before they are passed to the driver, and
+/// the driver returns the values of a row as these. The types supported by `Any` convert to and
+/// from the variant closest to them; dates and times are exchanged as their ISO 8601 text.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum AnyData {
+    Null,
+    Bool(bool),
+    SmallInt(i16),
+    Integer(i32),
+    BigInt(i64),
+    Real(f32),
+    Double(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl AnyData {
+    pub fn is_null(&self) -> bool {
+        matches!(self, AnyData::Null)
+    }
+
+    fn unexpected<T>(&self) -> BoxDynError {
+        format!(
+            "mismatched types; Rust type `{}` is not compatible with {:?}",
+            std::any::type_name::<T>(),
+            self
+        )
+        .into()
+    }
+}
+
+// the conversions of the types supported by `Any`, used by `impl_any_encode!` and
+// `impl_any_decode!` for an installed driver
+
+pub(crate) trait EncodeAnyData {
+    fn encode_any_data(&self) -> AnyData;
+}
+
+pub(crate) trait DecodeAnyData<'r>: Sized {
+    fn decode_any_data(data: &'r AnyData) -> Result<Self, BoxDynError>;
+}
+
+macro_rules! impl_any_data_for_int {
+    ($ty:ty, $variant:ident) => {
+        impl EncodeAnyData for $ty {
+            fn encode_any_data(&self) -> AnyData {
+                AnyData::$variant(*self)
+            }
+        }
+
+        impl DecodeAnyData<'_> for $ty {
+            fn decode_any_data(data: &AnyData) -> Result<Self, BoxDynError> {
+                match *data {
+                    AnyData::SmallInt(v) => Ok(v.try_into()?),
+                    AnyData::Integer(v) => Ok(v.try_into()?),
+                    AnyData::BigInt(v) => Ok(v.try_into()?),
+                    _ => Err(data.unexpected::<$ty>()),
+                }
+            }
+        }
+    };
+}
+
+impl_any_data_for_int!(i16, SmallInt);
+impl_any_data_for_int!(i32, Integer);
+impl_any_data_for_int!(i64, BigInt);
+
+impl EncodeAnyData for bool {
+    fn encode_any_data(&self) -> AnyData {
+        AnyData::Bool(*self)
+    }
+}
+
+impl DecodeAnyData<'_> for bool {
+    fn decode_any_data(data: &AnyData) -> Result<Self, BoxDynError> {
+        match *data {
+            AnyData::Bool(v) => Ok(v),
+            _ => Err(data.unexpected::<bool>()),
+        }
+    }
+}
+
+impl EncodeAnyData for f32 {
+    fn encode_any_data(&self) -> AnyData {
+        AnyData::Real(*self)
+    }
+}
+
+impl DecodeAnyData<'_> for f32 {
+    fn decode_any_data(data: &AnyData) -> Result<Self, BoxDynError> {
+        match *data {
+            AnyData::Real(v) => Ok(v),
+            _ => Err(data.unexpected::<f32>()),
+        }
+    }
+}
+
+impl EncodeAnyData for f64 {
+    fn encode_any_data(&self) -> AnyData {
+        AnyData::Double(*self)
+    }
+}
+
+impl DecodeAnyData<'_> for f64 {
+    fn decode_any_data(data: &AnyData) -> Result<Self, BoxDynError> {
+        match *data {
+            AnyData::Real(v) => Ok(v.into()),
+            AnyData::Double(v) => Ok(v),
+            _ => Err(data.unexpected::<f64>()),
+        }
+    }
+}
+
+impl EncodeAnyData for &'_ str {
+    fn encode_any_data(&self) -> AnyData {
+        AnyData::Text((*self).to_owned())
+    }
+}
+
+impl<'r> DecodeAnyData<'r> for &'r str {
+    fn decode_any_data(data: &'r AnyData) -> Result<Self, BoxDynError> {
+        match data {
+            AnyData::Text(v) => Ok(v),
+            _ => Err(data.unexpected::<&str>()),
+        }
+    }
+}
+
+impl EncodeAnyData for String {
+    fn encode_any_data(&self) -> AnyData {
+        AnyData::Text(self.clone())
+    }
+}
+
+impl DecodeAnyData<'_> for String {
+    fn decode_any_data(data: &AnyData) -> Result<Self, BoxDynError> {
+        <&str>::decode_any_data(data).map(ToOwned::to_owned)
+    }
+}
+
+impl EncodeAnyData for &'_ [u8] {
+    fn encode_any_data(&self) -> AnyData {
+        AnyData::Blob(self.to_vec())
+    }
+}
+
+impl<'r> DecodeAnyData<'r> for &'r [u8] {
+    fn decode_any_data(data: &'r AnyData) -> Result<Self, BoxDynError> {
+        match data {
+            AnyData::Blob(v) => Ok(v),
+            _ => Err(data.unexpected::<&[u8]>()),
+        }
+    }
+}
+
+impl EncodeAnyData for Vec<u8> {
+    fn encode_any_data(&self) -> AnyData {
+        AnyData::Blob(self.clone())
+    }
+}
+
+impl DecodeAnyData<'_> for Vec<u8> {
+    fn decode_any_data(data: &AnyData) -> Result<Self, BoxDynError> {
+        <&[u8]>::decode_any_data(data).map(ToOwned::to_owned)
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono {
+    use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, Utc};
+
+    use super::{AnyData, DecodeAnyData, EncodeAnyData};
+    use crate::error::BoxDynError;
+
+    impl EncodeAnyData for NaiveDate {
+        fn encode_any_data(&self) -> AnyData {
+            AnyData::Text(self.format("%F").to_string())
+        }
+    }
+
+    impl DecodeAnyData<'_> for NaiveDate {
+        fn decode_any_data(data: &AnyData) -> Result<Self, BoxDynError> {
+            Ok(NaiveDate::parse_from_str(
+                <&str>::decode_any_data(data)?,
+                "%F",
+            )?)
+        }
+    }
+
+    impl EncodeAnyData for NaiveTime {
+        fn encode_any_data(&self) -> AnyData {
+            AnyData::Text(self.format("%T%.f").to_string())
+        }
+    }
+
+    impl DecodeAnyData<'_> for NaiveTime {
+        fn decode_any_data(data: &AnyData) -> Result<Self, BoxDynError> {
+            Ok(NaiveTime::parse_from_str(
+                <&str>::decode_any_data(data)?,
+                "%T%.f",
+            )?)
+        }
+    }
+
+    impl EncodeAnyData for NaiveDateTime {
+        fn encode_any_data(&self) -> AnyData {
+            AnyData::Text(self.format("%F %T%.f").to_string())
+        }
+    }
+
+    impl DecodeAnyData<'_> for NaiveDateTime {
+        fn decode_any_data(data: &AnyData) -> Result<Self, BoxDynError> {
+            let text = <&str>::decode_any_data(data)?;
+
+            // either separator of ISO 8601
+            NaiveDateTime::parse_from_str(text, "%F %T%.f")
+                .or_else(|_| NaiveDateTime::parse_from_str(text, "%FT%T%.f"))
+                .map_err(Into::into)
+        }
+    }
+
+    impl EncodeAnyData for DateTime<Utc> {
+        fn encode_any_data(&self) -> AnyData {
+            AnyData::Text(self.to_rfc3339_opts(SecondsFormat::AutoSi, false))
+        }
+    }
+
+    impl DecodeAnyData<'_> for DateTime<Utc> {
+        fn decode_any_data(data: &AnyData) -> Result<Self, BoxDynError> {
+            Ok(DateTime::parse_from_rfc3339(<&str>::decode_any_data(data)?)?.with_timezone(&Utc))
+        }
+    }
+
+    impl EncodeAnyData for DateTime<Local> {
+        fn encode_any_data(&self) -> AnyData {
+            AnyData::Text(self.to_rfc3339_opts(SecondsFormat::AutoSi, false))
+        }
+    }
+
+    impl DecodeAnyData<'_> for DateTime<Local> {
+        fn decode_any_data(data: &AnyData) -> Result<Self, BoxDynError> {
+            Ok(DateTime::parse_from_rfc3339(<&str>::decode_any_data(data)?)?.with_timezone(&Local))
+        }
+    }
+}
+
+#[test]
+fn test_decode_any_data_int() {
+    assert_eq!(i64::decode_any_data(&AnyData::Integer(7)).unwrap(), 7);
+    assert_eq!(i16::decode_any_data(&AnyData::BigInt(-3)).unwrap(), -3);
+    assert!(i16::decode_any_data(&AnyData::BigInt(1 << 20)).is_err());
+    assert!(i32::decode_any_data(&AnyData::Text("7".into())).is_err());
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_any_data_chrono_round_trip() {
+    use ::chrono::{NaiveDate, NaiveDateTime};
+
+    let dt = NaiveDate::from_ymd(2022, 3, 4).and_hms_micro(5, 6, 7, 890);
+    let data = dt.encode_any_data();
+
+    assert_eq!(data, AnyData::Text("2022-03-04 05:06:07.000890".into()));
+    assert_eq!(NaiveDateTime::decode_any_data(&data).unwrap(), dt);
+    assert_eq!(
+        NaiveDateTime::decode_any_data(&AnyData::Text("2022-03-04T05:06:07".into())).unwrap(),
+        NaiveDate::from_ymd(2022, 3, 4).and_hms(5, 6, 7)
+    );
+}
@@ -13,6 +13,9 @@ use crate::mssql::Mssql;
 #[cfg(feature = "sqlite")]
 use crate::sqlite::Sqlite;
 
+#[cfg(feature = "duckdb")]
+use crate::duckdb::DuckDb;
+
 // Implements Decode for any T where T supports Decode for any database that has support currently
 // compiled into SQLx
 macro_rules! impl_any_decode {
@@ -35,6 +38,11 @@ macro_rules! impl_any_decode {
                         <$ty as crate::decode::Decode<'r, crate::sqlite::Sqlite>>::decode(value)
                     }
 
+                    #[cfg(feature = "duckdb")]
+                    crate::any::value::AnyValueRefKind::DuckDb(value) => {
+                        <$ty as crate::decode::Decode<'r, crate::duckdb::DuckDb>>::decode(value)
+                    }
+
                     #[cfg(feature = "mssql")]
                     crate::any::value::AnyValueRefKind::Mssql(value) => {
                         <$ty as crate::decode::Decode<'r, crate::mssql::Mssql>>::decode(value)
@@ -44,6 +52,10 @@ macro_rules! impl_any_decode {
                     crate::any::value::AnyValueRefKind::Postgres(value) => {
                         <$ty as crate::decode::Decode<'r, crate::postgres::Postgres>>::decode(value)
                     }
+
+                    crate::any::value::AnyValueRefKind::Driver(value) => {
+                        <$ty as crate::any::data::DecodeAnyData<'r>>::decode_any_data(value)
+                    }
                 }
             }
         }
@@ -53,13 +65,14 @@ macro_rules! impl_any_decode {
 // FIXME: Find a nice way to auto-generate the below or petition Rust to add support for #[cfg]
 //        to trait bounds
 
-// all 4
+// all 5
 
 #[cfg(all(
     feature = "postgres",
     feature = "mysql",
     feature = "mssql",
-    feature = "sqlite"
+    feature = "sqlite",
+    feature = "duckdb"
 ))]
 pub trait AnyDecode<'r>:
     Decode<'r, Postgres>
@@ -70,6 +83,8 @@ pub trait AnyDecode<'r>:
     + Type<Mssql>
     + Decode<'r, Sqlite>
     + Type<Sqlite>
+    + Decode<'r, DuckDb>
+    + Type<DuckDb>
 {
 }
 
@@ -77,7 +92,8 @@ pub trait AnyDecode<'r>:
     feature = "postgres",
     feature = "mysql",
     feature = "mssql",
-    feature = "sqlite"
+    feature = "sqlite",
+    feature = "duckdb"
 ))]
 impl<'r, T> AnyDecode<'r> for T where
     T: Decode<'r, Postgres>
@@ -88,14 +104,105 @@ impl<'r, T> AnyDecode<'r> for T where
         + Type<Mssql>
         + Decode<'r, Sqlite>
         + Type<Sqlite>
+        + Decode<'r, DuckDb>
+        + Type<DuckDb>
 {
 }
 
-// only 3 (4)
+// only 4 (5)
+
+#[cfg(all(
+    not(feature = "duckdb"),
+    all(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite"
+    )
+))]
+pub trait AnyDecode<'r>:
+    Decode<'r, Postgres>
+    + Type<Postgres>
+    + Decode<'r, MySql>
+    + Type<MySql>
+    + Decode<'r, Mssql>
+    + Type<Mssql>
+    + Decode<'r, Sqlite>
+    + Type<Sqlite>
+{
+}
+
+#[cfg(all(
+    not(feature = "duckdb"),
+    all(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite"
+    )
+))]
+impl<'r, T> AnyDecode<'r> for T where
+    T: Decode<'r, Postgres>
+        + Type<Postgres>
+        + Decode<'r, MySql>
+        + Type<MySql>
+        + Decode<'r, Mssql>
+        + Type<Mssql>
+        + Decode<'r, Sqlite>
+        + Type<Sqlite>
+{
+}
+
+#[cfg(all(
+    not(feature = "sqlite"),
+    all(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "duckdb"
+    )
+))]
+pub trait AnyDecode<'r>:
+    Decode<'r, Postgres>
+    + Type<Postgres>
+    + Decode<'r, MySql>
+    + Type<MySql>
+    + Decode<'r, Mssql>
+    + Type<Mssql>
+    + Decode<'r, DuckDb>
+    + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(feature = "sqlite"),
+    all(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "duckdb"
+    )
+))]
+impl<'r, T> AnyDecode<'r> for T where
+    T: Decode<'r, Postgres>
+        + Type<Postgres>
+        + Decode<'r, MySql>
+        + Type<MySql>
+        + Decode<'r, Mssql>
+        + Type<Mssql>
+        + Decode<'r, DuckDb>
+        + Type<DuckDb>
+{
+}
 
 #[cfg(all(
     not(feature = "mssql"),
-    all(feature = "postgres", feature = "mysql", feature = "sqlite")
+    all(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )
 ))]
 pub trait AnyDecode<'r>:
     Decode<'r, Postgres>
@@ -104,12 +211,19 @@ pub trait AnyDecode<'r>:
     + Type<MySql>
     + Decode<'r, Sqlite>
     + Type<Sqlite>
+    + Decode<'r, DuckDb>
+    + Type<DuckDb>
 {
 }
 
 #[cfg(all(
     not(feature = "mssql"),
-    all(feature = "postgres", feature = "mysql", feature = "sqlite")
+    all(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )
 ))]
 impl<'r, T> AnyDecode<'r> for T where
     T: Decode<'r, Postgres>
@@ -118,12 +232,19 @@ impl<'r, T> AnyDecode<'r> for T where
         + Type<MySql>
         + Decode<'r, Sqlite>
         + Type<Sqlite>
+        + Decode<'r, DuckDb>
+        + Type<DuckDb>
 {
 }
 
 #[cfg(all(
     not(feature = "mysql"),
-    all(feature = "postgres", feature = "mssql", feature = "sqlite")
+    all(
+        feature = "postgres",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )
 ))]
 pub trait AnyDecode<'r>:
     Decode<'r, Postgres>
@@ -132,12 +253,19 @@ pub trait AnyDecode<'r>:
     + Type<Mssql>
     + Decode<'r, Sqlite>
     + Type<Sqlite>
+    + Decode<'r, DuckDb>
+    + Type<DuckDb>
 {
 }
 
 #[cfg(all(
     not(feature = "mysql"),
-    all(feature = "postgres", feature = "mssql", feature = "sqlite")
+    all(
+        feature = "postgres",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )
 ))]
 impl<'r, T> AnyDecode<'r> for T where
     T: Decode<'r, Postgres>
@@ -146,11 +274,57 @@ impl<'r, T> AnyDecode<'r> for T where
         + Type<Mssql>
         + Decode<'r, Sqlite>
         + Type<Sqlite>
+        + Decode<'r, DuckDb>
+        + Type<DuckDb>
 {
 }
 
 #[cfg(all(
-    not(feature = "sqlite"),
+    not(feature = "postgres"),
+    all(
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )
+))]
+pub trait AnyDecode<'r>:
+    Decode<'r, MySql>
+    + Type<MySql>
+    + Decode<'r, Mssql>
+    + Type<Mssql>
+    + Decode<'r, Sqlite>
+    + Type<Sqlite>
+    + Decode<'r, DuckDb>
+    + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(feature = "postgres"),
+    all(
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )
+))]
+impl<'r, T> AnyDecode<'r> for T where
+    T: Decode<'r, MySql>
+        + Type<MySql>
+        + Decode<'r, Mssql>
+        + Type<Mssql>
+        + Decode<'r, Sqlite>
+        + Type<Sqlite>
+        + Decode<'r, DuckDb>
+        + Type<DuckDb>
+{
+}
+
+// only 3 (10)
+
+#[cfg(all(
+    not(any(feature = "sqlite", feature = "duckdb")),
     all(feature = "postgres", feature = "mysql", feature = "mssql")
 ))]
 pub trait AnyDecode<'r>:
@@ -164,7 +338,7 @@ pub trait AnyDecode<'r>:
 }
 
 #[cfg(all(
-    not(feature = "sqlite"),
+    not(any(feature = "sqlite", feature = "duckdb")),
     all(feature = "postgres", feature = "mysql", feature = "mssql")
 ))]
 impl<'r, T> AnyDecode<'r> for T where
@@ -178,37 +352,261 @@ impl<'r, T> AnyDecode<'r> for T where
 }
 
 #[cfg(all(
-    not(feature = "postgres"),
-    all(feature = "sqlite", feature = "mysql", feature = "mssql")
+    not(any(feature = "mssql", feature = "duckdb")),
+    all(feature = "postgres", feature = "mysql", feature = "sqlite")
 ))]
 pub trait AnyDecode<'r>:
-    Decode<'r, Sqlite>
+    Decode<'r, Postgres>
+    + Type<Postgres>
+    + Decode<'r, MySql>
+    + Type<MySql>
+    + Decode<'r, Sqlite>
     + Type<Sqlite>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mssql", feature = "duckdb")),
+    all(feature = "postgres", feature = "mysql", feature = "sqlite")
+))]
+impl<'r, T> AnyDecode<'r> for T where
+    T: Decode<'r, Postgres>
+        + Type<Postgres>
+        + Decode<'r, MySql>
+        + Type<MySql>
+        + Decode<'r, Sqlite>
+        + Type<Sqlite>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mssql", feature = "sqlite")),
+    all(feature = "postgres", feature = "mysql", feature = "duckdb")
+))]
+pub trait AnyDecode<'r>:
+    Decode<'r, Postgres>
+    + Type<Postgres>
     + Decode<'r, MySql>
     + Type<MySql>
+    + Decode<'r, DuckDb>
+    + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mssql", feature = "sqlite")),
+    all(feature = "postgres", feature = "mysql", feature = "duckdb")
+))]
+impl<'r, T> AnyDecode<'r> for T where
+    T: Decode<'r, Postgres>
+        + Type<Postgres>
+        + Decode<'r, MySql>
+        + Type<MySql>
+        + Decode<'r, DuckDb>
+        + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mysql", feature = "duckdb")),
+    all(feature = "postgres", feature = "mssql", feature = "sqlite")
+))]
+pub trait AnyDecode<'r>:
+    Decode<'r, Postgres>
+    + Type<Postgres>
     + Decode<'r, Mssql>
     + Type<Mssql>
+    + Decode<'r, Sqlite>
+    + Type<Sqlite>
 {
 }
 
 #[cfg(all(
-    not(feature = "postgres"),
-    all(feature = "sqlite", feature = "mysql", feature = "mssql")
+    not(any(feature = "mysql", feature = "duckdb")),
+    all(feature = "postgres", feature = "mssql", feature = "sqlite")
 ))]
 impl<'r, T> AnyDecode<'r> for T where
-    T: Decode<'r, Sqlite>
+    T: Decode<'r, Postgres>
+        + Type<Postgres>
+        + Decode<'r, Mssql>
+        + Type<Mssql>
+        + Decode<'r, Sqlite>
         + Type<Sqlite>
-        + Decode<'r, MySql>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mysql", feature = "sqlite")),
+    all(feature = "postgres", feature = "mssql", feature = "duckdb")
+))]
+pub trait AnyDecode<'r>:
+    Decode<'r, Postgres>
+    + Type<Postgres>
+    + Decode<'r, Mssql>
+    + Type<Mssql>
+    + Decode<'r, DuckDb>
+    + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mysql", feature = "sqlite")),
+    all(feature = "postgres", feature = "mssql", feature = "duckdb")
+))]
+impl<'r, T> AnyDecode<'r> for T where
+    T: Decode<'r, Postgres>
+        + Type<Postgres>
+        + Decode<'r, Mssql>
+        + Type<Mssql>
+        + Decode<'r, DuckDb>
+        + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mysql", feature = "mssql")),
+    all(feature = "postgres", feature = "sqlite", feature = "duckdb")
+))]
+pub trait AnyDecode<'r>:
+    Decode<'r, Postgres>
+    + Type<Postgres>
+    + Decode<'r, Sqlite>
+    + Type<Sqlite>
+    + Decode<'r, DuckDb>
+    + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mysql", feature = "mssql")),
+    all(feature = "postgres", feature = "sqlite", feature = "duckdb")
+))]
+impl<'r, T> AnyDecode<'r> for T where
+    T: Decode<'r, Postgres>
+        + Type<Postgres>
+        + Decode<'r, Sqlite>
+        + Type<Sqlite>
+        + Decode<'r, DuckDb>
+        + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "duckdb")),
+    all(feature = "mysql", feature = "mssql", feature = "sqlite")
+))]
+pub trait AnyDecode<'r>:
+    Decode<'r, MySql>
+    + Type<MySql>
+    + Decode<'r, Mssql>
+    + Type<Mssql>
+    + Decode<'r, Sqlite>
+    + Type<Sqlite>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "duckdb")),
+    all(feature = "mysql", feature = "mssql", feature = "sqlite")
+))]
+impl<'r, T> AnyDecode<'r> for T where
+    T: Decode<'r, MySql>
         + Type<MySql>
         + Decode<'r, Mssql>
         + Type<Mssql>
+        + Decode<'r, Sqlite>
+        + Type<Sqlite>
 {
 }
 
-// only 2 (6)
+#[cfg(all(
+    not(any(feature = "postgres", feature = "sqlite")),
+    all(feature = "mysql", feature = "mssql", feature = "duckdb")
+))]
+pub trait AnyDecode<'r>:
+    Decode<'r, MySql>
+    + Type<MySql>
+    + Decode<'r, Mssql>
+    + Type<Mssql>
+    + Decode<'r, DuckDb>
+    + Type<DuckDb>
+{
+}
 
 #[cfg(all(
-    not(any(feature = "mssql", feature = "sqlite")),
+    not(any(feature = "postgres", feature = "sqlite")),
+    all(feature = "mysql", feature = "mssql", feature = "duckdb")
+))]
+impl<'r, T> AnyDecode<'r> for T where
+    T: Decode<'r, MySql>
+        + Type<MySql>
+        + Decode<'r, Mssql>
+        + Type<Mssql>
+        + Decode<'r, DuckDb>
+        + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mssql")),
+    all(feature = "mysql", feature = "sqlite", feature = "duckdb")
+))]
+pub trait AnyDecode<'r>:
+    Decode<'r, MySql>
+    + Type<MySql>
+    + Decode<'r, Sqlite>
+    + Type<Sqlite>
+    + Decode<'r, DuckDb>
+    + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mssql")),
+    all(feature = "mysql", feature = "sqlite", feature = "duckdb")
+))]
+impl<'r, T> AnyDecode<'r> for T where
+    T: Decode<'r, MySql>
+        + Type<MySql>
+        + Decode<'r, Sqlite>
+        + Type<Sqlite>
+        + Decode<'r, DuckDb>
+        + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mysql")),
+    all(feature = "mssql", feature = "sqlite", feature = "duckdb")
+))]
+pub trait AnyDecode<'r>:
+    Decode<'r, Mssql>
+    + Type<Mssql>
+    + Decode<'r, Sqlite>
+    + Type<Sqlite>
+    + Decode<'r, DuckDb>
+    + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mysql")),
+    all(feature = "mssql", feature = "sqlite", feature = "duckdb")
+))]
+impl<'r, T> AnyDecode<'r> for T where
+    T: Decode<'r, Mssql>
+        + Type<Mssql>
+        + Decode<'r, Sqlite>
+        + Type<Sqlite>
+        + Decode<'r, DuckDb>
+        + Type<DuckDb>
+{
+}
+
+// only 2 (10)
+
+#[cfg(all(
+    not(any(feature = "mssql", feature = "sqlite", feature = "duckdb")),
     all(feature = "postgres", feature = "mysql")
 ))]
 pub trait AnyDecode<'r>:
@@ -217,7 +615,7 @@ pub trait AnyDecode<'r>:
 }
 
 #[cfg(all(
-    not(any(feature = "mssql", feature = "sqlite")),
+    not(any(feature = "mssql", feature = "sqlite", feature = "duckdb")),
     all(feature = "postgres", feature = "mysql")
 ))]
 impl<'r, T> AnyDecode<'r> for T where
@@ -226,7 +624,7 @@ impl<'r, T> AnyDecode<'r> for T where
 }
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "sqlite")),
+    not(any(feature = "mysql", feature = "sqlite", feature = "duckdb")),
     all(feature = "postgres", feature = "mssql")
 ))]
 pub trait AnyDecode<'r>:
@@ -235,7 +633,7 @@ pub trait AnyDecode<'r>:
 }
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "sqlite")),
+    not(any(feature = "mysql", feature = "sqlite", feature = "duckdb")),
     all(feature = "postgres", feature = "mssql")
 ))]
 impl<'r, T> AnyDecode<'r> for T where
@@ -244,7 +642,7 @@ impl<'r, T> AnyDecode<'r> for T where
 }
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "mssql")),
+    not(any(feature = "mysql", feature = "mssql", feature = "duckdb")),
     all(feature = "postgres", feature = "sqlite")
 ))]
 pub trait AnyDecode<'r>:
@@ -253,7 +651,7 @@ pub trait AnyDecode<'r>:
 }
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "mssql")),
+    not(any(feature = "mysql", feature = "mssql", feature = "duckdb")),
     all(feature = "postgres", feature = "sqlite")
 ))]
 impl<'r, T> AnyDecode<'r> for T where
@@ -262,22 +660,76 @@ impl<'r, T> AnyDecode<'r> for T where
 }
 
 #[cfg(all(
-    not(any(feature = "postgres", feature = "sqlite")),
-    all(feature = "mssql", feature = "mysql")
+    not(any(feature = "mysql", feature = "mssql", feature = "sqlite")),
+    all(feature = "postgres", feature = "duckdb")
 ))]
-pub trait AnyDecode<'r>: Decode<'r, Mssql> + Type<Mssql> + Decode<'r, MySql> + Type<MySql> {}
+pub trait AnyDecode<'r>:
+    Decode<'r, Postgres> + Type<Postgres> + Decode<'r, DuckDb> + Type<DuckDb>
+{
+}
 
 #[cfg(all(
-    not(any(feature = "postgres", feature = "sqlite")),
-    all(feature = "mssql", feature = "mysql")
+    not(any(feature = "mysql", feature = "mssql", feature = "sqlite")),
+    all(feature = "postgres", feature = "duckdb")
 ))]
 impl<'r, T> AnyDecode<'r> for T where
-    T: Decode<'r, Mssql> + Type<Mssql> + Decode<'r, MySql> + Type<MySql>
+    T: Decode<'r, Postgres> + Type<Postgres> + Decode<'r, DuckDb> + Type<DuckDb>
 {
 }
 
 #[cfg(all(
-    not(any(feature = "postgres", feature = "mysql")),
+    not(any(feature = "postgres", feature = "sqlite", feature = "duckdb")),
+    all(feature = "mysql", feature = "mssql")
+))]
+pub trait AnyDecode<'r>: Decode<'r, MySql> + Type<MySql> + Decode<'r, Mssql> + Type<Mssql> {}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "sqlite", feature = "duckdb")),
+    all(feature = "mysql", feature = "mssql")
+))]
+impl<'r, T> AnyDecode<'r> for T where
+    T: Decode<'r, MySql> + Type<MySql> + Decode<'r, Mssql> + Type<Mssql>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mssql", feature = "duckdb")),
+    all(feature = "mysql", feature = "sqlite")
+))]
+pub trait AnyDecode<'r>:
+    Decode<'r, MySql> + Type<MySql> + Decode<'r, Sqlite> + Type<Sqlite>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mssql", feature = "duckdb")),
+    all(feature = "mysql", feature = "sqlite")
+))]
+impl<'r, T> AnyDecode<'r> for T where
+    T: Decode<'r, MySql> + Type<MySql> + Decode<'r, Sqlite> + Type<Sqlite>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mssql", feature = "sqlite")),
+    all(feature = "mysql", feature = "duckdb")
+))]
+pub trait AnyDecode<'r>:
+    Decode<'r, MySql> + Type<MySql> + Decode<'r, DuckDb> + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mssql", feature = "sqlite")),
+    all(feature = "mysql", feature = "duckdb")
+))]
+impl<'r, T> AnyDecode<'r> for T where
+    T: Decode<'r, MySql> + Type<MySql> + Decode<'r, DuckDb> + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mysql", feature = "duckdb")),
     all(feature = "mssql", feature = "sqlite")
 ))]
 pub trait AnyDecode<'r>:
@@ -286,7 +738,7 @@ pub trait AnyDecode<'r>:
 }
 
 #[cfg(all(
-    not(any(feature = "postgres", feature = "mysql")),
+    not(any(feature = "postgres", feature = "mysql", feature = "duckdb")),
     all(feature = "mssql", feature = "sqlite")
 ))]
 impl<'r, T> AnyDecode<'r> for T where
@@ -295,69 +747,149 @@ impl<'r, T> AnyDecode<'r> for T where
 }
 
 #[cfg(all(
-    not(any(feature = "postgres", feature = "mssql")),
-    all(feature = "mysql", feature = "sqlite")
+    not(any(feature = "postgres", feature = "mysql", feature = "sqlite")),
+    all(feature = "mssql", feature = "duckdb")
 ))]
 pub trait AnyDecode<'r>:
-    Decode<'r, MySql> + Type<MySql> + Decode<'r, Sqlite> + Type<Sqlite>
+    Decode<'r, Mssql> + Type<Mssql> + Decode<'r, DuckDb> + Type<DuckDb>
 {
 }
 
 #[cfg(all(
-    not(any(feature = "postgres", feature = "mssql")),
-    all(feature = "mysql", feature = "sqlite")
+    not(any(feature = "postgres", feature = "mysql", feature = "sqlite")),
+    all(feature = "mssql", feature = "duckdb")
 ))]
 impl<'r, T> AnyDecode<'r> for T where
-    T: Decode<'r, MySql> + Type<MySql> + Decode<'r, Sqlite> + Type<Sqlite>
+    T: Decode<'r, Mssql> + Type<Mssql> + Decode<'r, DuckDb> + Type<DuckDb>
 {
 }
 
-// only 1 (4)
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mysql", feature = "mssql")),
+    all(feature = "sqlite", feature = "duckdb")
+))]
+pub trait AnyDecode<'r>:
+    Decode<'r, Sqlite> + Type<Sqlite> + Decode<'r, DuckDb> + Type<DuckDb>
+{
+}
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "mssql", feature = "sqlite")),
+    not(any(feature = "postgres", feature = "mysql", feature = "mssql")),
+    all(feature = "sqlite", feature = "duckdb")
+))]
+impl<'r, T> AnyDecode<'r> for T where
+    T: Decode<'r, Sqlite> + Type<Sqlite> + Decode<'r, DuckDb> + Type<DuckDb>
+{
+}
+
+// only 1 (5)
+
+#[cfg(all(
+    not(any(
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )),
     feature = "postgres"
 ))]
 pub trait AnyDecode<'r>: Decode<'r, Postgres> + Type<Postgres> {}
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "mssql", feature = "sqlite")),
+    not(any(
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )),
     feature = "postgres"
 ))]
 impl<'r, T> AnyDecode<'r> for T where T: Decode<'r, Postgres> + Type<Postgres> {}
 
 #[cfg(all(
-    not(any(feature = "postgres", feature = "mssql", feature = "sqlite")),
+    not(any(
+        feature = "postgres",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )),
     feature = "mysql"
 ))]
 pub trait AnyDecode<'r>: Decode<'r, MySql> + Type<MySql> {}
 
 #[cfg(all(
-    not(any(feature = "postgres", feature = "mssql", feature = "sqlite")),
+    not(any(
+        feature = "postgres",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )),
     feature = "mysql"
 ))]
 impl<'r, T> AnyDecode<'r> for T where T: Decode<'r, MySql> + Type<MySql> {}
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "postgres", feature = "sqlite")),
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )),
     feature = "mssql"
 ))]
 pub trait AnyDecode<'r>: Decode<'r, Mssql> + Type<Mssql> {}
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "postgres", feature = "sqlite")),
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )),
     feature = "mssql"
 ))]
 impl<'r, T> AnyDecode<'r> for T where T: Decode<'r, Mssql> + Type<Mssql> {}
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "mssql", feature = "postgres")),
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "duckdb"
+    )),
     feature = "sqlite"
 ))]
 pub trait AnyDecode<'r>: Decode<'r, Sqlite> + Type<Sqlite> {}
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "mssql", feature = "postgres")),
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "duckdb"
+    )),
     feature = "sqlite"
 ))]
 impl<'r, T> AnyDecode<'r> for T where T: Decode<'r, Sqlite> + Type<Sqlite> {}
+
+#[cfg(all(
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite"
+    )),
+    feature = "duckdb"
+))]
+pub trait AnyDecode<'r>: Decode<'r, DuckDb> + Type<DuckDb> {}
+
+#[cfg(all(
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite"
+    )),
+    feature = "duckdb"
+))]
+impl<'r, T> AnyDecode<'r> for T where T: Decode<'r, DuckDb> + Type<DuckDb> {}
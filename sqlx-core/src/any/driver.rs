@@ -0,0 +1,307 @@
+use std::fmt::{self, Debug, Formatter};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use either::Either;
+use futures_core::future::BoxFuture;
+use futures_core::stream::BoxStream;
+use log::LevelFilter;
+
+use crate::any::{AnyData, AnyKind, AnyQueryResult, AnyRow, AnyStatement};
+use crate::connection::{LogSettings, ServerCapabilities, ServerVersion};
+use crate::error::Error;
+use crate::query_log::QueryLog;
+
+/// A database driver that can be installed for [`Any`][crate::any::Any] at runtime, to
+/// connect to the URLs with its schemes.
+///
+/// ```rust,ignore
+/// static DRIVER: AnyDriver = AnyDriver::new("clickhouse", &["clickhouse"], |options| {
+///     Box::pin(async move {
+///         let conn = ClickHouseConnection::connect(options.url()).await?;
+///         Ok(Box::new(conn) as Box<dyn AnyConnectionBackend>)
+///     })
+/// });
+///
+/// sqlx::any::install_driver(&DRIVER)?;
+///
+/// let conn = AnyConnection::connect("clickhouse://localhost/default").await?;
+/// ```
+pub struct AnyDriver {
+    name: &'static str,
+    url_schemes: &'static [&'static str],
+    connect: ConnectFn,
+}
+
+type ConnectFn = for<'a> fn(
+    &'a AnyDriverConnectOptions,
+) -> BoxFuture<'a, Result<Box<dyn AnyConnectionBackend>, Error>>;
+
+impl AnyDriver {
+    /// The driver `name`, connecting to the URLs which start with one of `url_schemes` (given
+    /// without the `:`) with `connect`.
+    pub const fn new(
+        name: &'static str,
+        url_schemes: &'static [&'static str],
+        connect: ConnectFn,
+    ) -> Self {
+        Self {
+            name,
+            url_schemes,
+            connect,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn url_schemes(&self) -> &'static [&'static str] {
+        self.url_schemes
+    }
+
+    fn matches(&self, url: &str) -> bool {
+        self.url_schemes.iter().any(|scheme| {
+            url.strip_prefix(scheme)
+                .map_or(false, |rest| rest.starts_with(':'))
+        })
+    }
+}
+
+impl Debug for AnyDriver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AnyDriver")
+            .field("name", &self.name)
+            .field("url_schemes", &self.url_schemes)
+            .finish()
+    }
+}
+
+static DRIVERS: RwLock<Vec<&'static AnyDriver>> = RwLock::new(Vec::new());
+
+/// Installs `driver`, so that [`AnyConnection`][crate::any::AnyConnection] and
+/// [`AnyPool`][crate::any::AnyPool] connect to the URLs with its schemes with it.
+///
+/// This is meant to be called once at startup, before connecting. A driver can't take the
+/// scheme of a built-in driver that is enabled, or of another installed driver; the schemes of
+/// the built-in drivers whose Cargo feature isn't enabled, such as `mssql:`, are free to take.
+pub fn install_driver(driver: &'static AnyDriver) -> Result<(), Error> {
+    let taken = |by: &dyn Debug, scheme: &str| {
+        Error::Configuration(
+            format!(
+                "cannot install the `{}` driver: the `{}:` scheme is taken by {:?}",
+                driver.name, scheme, by
+            )
+            .into(),
+        )
+    };
+
+    for scheme in driver.url_schemes {
+        if let Ok(kind) = AnyKind::from_builtin_url(&format!("{}:", scheme)) {
+            return Err(taken(&kind, scheme));
+        }
+    }
+
+    let mut drivers = DRIVERS.write().unwrap_or_else(|e| e.into_inner());
+
+    for installed in drivers.iter() {
+        if installed.name == driver.name {
+            return Err(Error::Configuration(
+                format!("a driver named `{}` is already installed", driver.name).into(),
+            ));
+        }
+
+        if let Some(scheme) = driver
+            .url_schemes
+            .iter()
+            .find(|scheme| installed.url_schemes.contains(scheme))
+        {
+            return Err(taken(&installed.name, scheme));
+        }
+    }
+
+    drivers.push(driver);
+
+    Ok(())
+}
+
+pub(crate) fn find_driver(url: &str) -> Option<&'static AnyDriver> {
+    DRIVERS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .copied()
+        .find(|driver| driver.matches(url))
+}
+
+/// The options passed to an installed driver to connect; see [`AnyDriver`].
+#[derive(Clone)]
+pub struct AnyDriverConnectOptions {
+    pub(crate) driver: &'static AnyDriver,
+    pub(crate) url: String,
+    pub(crate) log_settings: LogSettings,
+    pub(crate) application_name: Option<String>,
+}
+
+impl AnyDriverConnectOptions {
+    pub(crate) fn new(driver: &'static AnyDriver, url: &str) -> Self {
+        Self {
+            driver,
+            url: url.to_owned(),
+            log_settings: Default::default(),
+            application_name: None,
+        }
+    }
+
+    pub(crate) async fn connect(&self) -> Result<Box<dyn AnyConnectionBackend>, Error> {
+        (self.driver.connect)(self).await
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The level to log the statements at, from
+    /// [`ConnectOptions::log_statements()`][crate::connection::ConnectOptions::log_statements].
+    pub fn log_statements_level(&self) -> LevelFilter {
+        self.log_settings.statements_level
+    }
+
+    /// The level and the duration over which to log the slow statements at, from
+    /// [`ConnectOptions::log_slow_statements()`][crate::connection::ConnectOptions::log_slow_statements].
+    pub fn log_slow_statements(&self) -> (LevelFilter, Duration) {
+        (
+            self.log_settings.slow_statements_level,
+            self.log_settings.slow_statements_duration,
+        )
+    }
+
+    /// The destination of the executed statements, if one was set with
+    /// [`ConnectOptions::query_log()`][crate::connection::ConnectOptions::query_log].
+    pub fn query_log(&self) -> Option<&Arc<dyn QueryLog>> {
+        self.log_settings.query_log.as_ref()
+    }
+
+    /// The name of the application, if one was set with
+    /// [`ConnectOptions::application_name()`][crate::connection::ConnectOptions::application_name].
+    pub fn application_name(&self) -> Option<&str> {
+        self.application_name.as_deref()
+    }
+}
+
+impl Debug for AnyDriverConnectOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // the URL may have a password in it
+        f.debug_struct("AnyDriverConnectOptions")
+            .field("driver", &self.driver.name)
+            .field("application_name", &self.application_name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A connection made by an installed driver; see [`AnyDriver`].
+///
+/// The arguments of the queries are given as [`AnyData`], and the driver returns rows made
+/// with [`AnyRow::new()`], with the columns made with
+/// [`AnyColumn::new()`][crate::any::AnyColumn::new].
+pub trait AnyConnectionBackend: Debug + Send + 'static {
+    fn close(self: Box<Self>) -> BoxFuture<'static, Result<(), Error>>;
+
+    /// Closes the connection without notifying the server.
+    fn close_hard(self: Box<Self>) -> BoxFuture<'static, Result<(), Error>> {
+        self.close()
+    }
+
+    fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>>;
+
+    /// Begins a transaction, or a savepoint within the current transaction.
+    fn begin(&mut self) -> BoxFuture<'_, Result<(), Error>>;
+
+    fn commit(&mut self) -> BoxFuture<'_, Result<(), Error>>;
+
+    fn rollback(&mut self) -> BoxFuture<'_, Result<(), Error>>;
+
+    /// Queues a rollback of the current transaction, to be sent with the next query; called
+    /// when a transaction is dropped without being committed or rolled back.
+    fn start_rollback(&mut self);
+
+    /// Queues `sql`, a statement managing the current transaction, to be sent with the next
+    /// query; called e.g. when a savepoint is dropped without being released.
+    fn start_statement(&mut self, sql: &str);
+
+    fn fetch_many<'q>(
+        &'q mut self,
+        sql: &'q str,
+        arguments: Option<Vec<AnyData>>,
+    ) -> BoxStream<'q, Result<Either<AnyQueryResult, AnyRow>, Error>>;
+
+    fn fetch_optional<'q>(
+        &'q mut self,
+        sql: &'q str,
+        arguments: Option<Vec<AnyData>>,
+    ) -> BoxFuture<'q, Result<Option<AnyRow>, Error>>;
+
+    fn prepare<'e, 'q: 'e>(
+        &'e mut self,
+        sql: &'q str,
+    ) -> BoxFuture<'e, Result<AnyStatement<'q>, Error>>;
+
+    fn cached_statements_size(&self) -> usize {
+        0
+    }
+
+    fn clear_cached_statements(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(futures_util::future::ok(()))
+    }
+
+    fn server_version(&self) -> Option<ServerVersion> {
+        None
+    }
+
+    fn server_capabilities(&self) -> ServerCapabilities {
+        ServerCapabilities::default()
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(futures_util::future::ok(()))
+    }
+
+    fn should_flush(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+fn connect_unreachable(
+    _options: &AnyDriverConnectOptions,
+) -> BoxFuture<'_, Result<Box<dyn AnyConnectionBackend>, Error>> {
+    unreachable!()
+}
+
+#[test]
+fn test_install_driver() {
+    static DRIVER: AnyDriver = AnyDriver::new("test", &["test-any"], connect_unreachable);
+    static OTHER: AnyDriver = AnyDriver::new("test", &["test-other"], connect_unreachable);
+
+    assert!(find_driver("test-any://localhost").is_none());
+
+    install_driver(&DRIVER).unwrap();
+
+    assert_eq!(find_driver("test-any://localhost").unwrap().name(), "test");
+    assert!(find_driver("test-any-2://localhost").is_none());
+    assert_eq!(
+        "test-any://localhost".parse::<AnyKind>().unwrap(),
+        AnyKind::Driver("test")
+    );
+
+    // the name is taken
+    assert!(install_driver(&OTHER).is_err());
+}
+
+#[cfg(feature = "postgres")]
+#[test]
+fn test_install_driver_builtin_scheme() {
+    static DRIVER: AnyDriver = AnyDriver::new("pg", &["postgres"], connect_unreachable);
+
+    assert!(install_driver(&DRIVER).is_err());
+}
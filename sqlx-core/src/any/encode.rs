@@ -13,6 +13,9 @@ use crate::mssql::Mssql;
 #[cfg(feature = "sqlite")]
 use crate::sqlite::Sqlite;
 
+#[cfg(feature = "duckdb")]
+use crate::duckdb::DuckDb;
+
 // Implements Encode for any T where T supports Encode for any database that has support currently
 // compiled into SQLx
 macro_rules! impl_any_encode {
@@ -39,6 +42,13 @@ macro_rules! impl_any_encode {
 
                     #[cfg(feature = "sqlite")]
                     crate::any::arguments::AnyArgumentBufferKind::Sqlite(args) => args.add(self),
+
+                    #[cfg(feature = "duckdb")]
+                    crate::any::arguments::AnyArgumentBufferKind::DuckDb(args) => args.add(self),
+
+                    crate::any::arguments::AnyArgumentBufferKind::Driver(args, _) => {
+                        args.push(crate::any::data::EncodeAnyData::encode_any_data(self))
+                    }
                 }
 
                 // unused
@@ -51,13 +61,14 @@ macro_rules! impl_any_encode {
 // FIXME: Find a nice way to auto-generate the below or petition Rust to add support for #[cfg]
 //        to trait bounds
 
-// all 4
+// all 5
 
 #[cfg(all(
     feature = "postgres",
     feature = "mysql",
     feature = "mssql",
-    feature = "sqlite"
+    feature = "sqlite",
+    feature = "duckdb"
 ))]
 pub trait AnyEncode<'q>:
     Encode<'q, Postgres>
@@ -68,6 +79,8 @@ pub trait AnyEncode<'q>:
     + Type<Mssql>
     + Encode<'q, Sqlite>
     + Type<Sqlite>
+    + Encode<'q, DuckDb>
+    + Type<DuckDb>
 {
 }
 
@@ -75,7 +88,8 @@ pub trait AnyEncode<'q>:
     feature = "postgres",
     feature = "mysql",
     feature = "mssql",
-    feature = "sqlite"
+    feature = "sqlite",
+    feature = "duckdb"
 ))]
 impl<'q, T> AnyEncode<'q> for T where
     T: Encode<'q, Postgres>
@@ -86,14 +100,105 @@ impl<'q, T> AnyEncode<'q> for T where
         + Type<Mssql>
         + Encode<'q, Sqlite>
         + Type<Sqlite>
+        + Encode<'q, DuckDb>
+        + Type<DuckDb>
 {
 }
 
-// only 3 (4)
+// only 4 (5)
+
+#[cfg(all(
+    not(feature = "duckdb"),
+    all(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite"
+    )
+))]
+pub trait AnyEncode<'q>:
+    Encode<'q, Postgres>
+    + Type<Postgres>
+    + Encode<'q, MySql>
+    + Type<MySql>
+    + Encode<'q, Mssql>
+    + Type<Mssql>
+    + Encode<'q, Sqlite>
+    + Type<Sqlite>
+{
+}
+
+#[cfg(all(
+    not(feature = "duckdb"),
+    all(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite"
+    )
+))]
+impl<'q, T> AnyEncode<'q> for T where
+    T: Encode<'q, Postgres>
+        + Type<Postgres>
+        + Encode<'q, MySql>
+        + Type<MySql>
+        + Encode<'q, Mssql>
+        + Type<Mssql>
+        + Encode<'q, Sqlite>
+        + Type<Sqlite>
+{
+}
+
+#[cfg(all(
+    not(feature = "sqlite"),
+    all(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "duckdb"
+    )
+))]
+pub trait AnyEncode<'q>:
+    Encode<'q, Postgres>
+    + Type<Postgres>
+    + Encode<'q, MySql>
+    + Type<MySql>
+    + Encode<'q, Mssql>
+    + Type<Mssql>
+    + Encode<'q, DuckDb>
+    + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(feature = "sqlite"),
+    all(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "duckdb"
+    )
+))]
+impl<'q, T> AnyEncode<'q> for T where
+    T: Encode<'q, Postgres>
+        + Type<Postgres>
+        + Encode<'q, MySql>
+        + Type<MySql>
+        + Encode<'q, Mssql>
+        + Type<Mssql>
+        + Encode<'q, DuckDb>
+        + Type<DuckDb>
+{
+}
 
 #[cfg(all(
     not(feature = "mssql"),
-    all(feature = "postgres", feature = "mysql", feature = "sqlite")
+    all(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )
 ))]
 pub trait AnyEncode<'q>:
     Encode<'q, Postgres>
@@ -102,12 +207,19 @@ pub trait AnyEncode<'q>:
     + Type<MySql>
     + Encode<'q, Sqlite>
     + Type<Sqlite>
+    + Encode<'q, DuckDb>
+    + Type<DuckDb>
 {
 }
 
 #[cfg(all(
     not(feature = "mssql"),
-    all(feature = "postgres", feature = "mysql", feature = "sqlite")
+    all(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )
 ))]
 impl<'q, T> AnyEncode<'q> for T where
     T: Encode<'q, Postgres>
@@ -116,12 +228,19 @@ impl<'q, T> AnyEncode<'q> for T where
         + Type<MySql>
         + Encode<'q, Sqlite>
         + Type<Sqlite>
+        + Encode<'q, DuckDb>
+        + Type<DuckDb>
 {
 }
 
 #[cfg(all(
     not(feature = "mysql"),
-    all(feature = "postgres", feature = "mssql", feature = "sqlite")
+    all(
+        feature = "postgres",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )
 ))]
 pub trait AnyEncode<'q>:
     Encode<'q, Postgres>
@@ -130,12 +249,19 @@ pub trait AnyEncode<'q>:
     + Type<Mssql>
     + Encode<'q, Sqlite>
     + Type<Sqlite>
+    + Encode<'q, DuckDb>
+    + Type<DuckDb>
 {
 }
 
 #[cfg(all(
     not(feature = "mysql"),
-    all(feature = "postgres", feature = "mssql", feature = "sqlite")
+    all(
+        feature = "postgres",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )
 ))]
 impl<'q, T> AnyEncode<'q> for T where
     T: Encode<'q, Postgres>
@@ -144,11 +270,57 @@ impl<'q, T> AnyEncode<'q> for T where
         + Type<Mssql>
         + Encode<'q, Sqlite>
         + Type<Sqlite>
+        + Encode<'q, DuckDb>
+        + Type<DuckDb>
 {
 }
 
 #[cfg(all(
-    not(feature = "sqlite"),
+    not(feature = "postgres"),
+    all(
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )
+))]
+pub trait AnyEncode<'q>:
+    Encode<'q, MySql>
+    + Type<MySql>
+    + Encode<'q, Mssql>
+    + Type<Mssql>
+    + Encode<'q, Sqlite>
+    + Type<Sqlite>
+    + Encode<'q, DuckDb>
+    + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(feature = "postgres"),
+    all(
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )
+))]
+impl<'q, T> AnyEncode<'q> for T where
+    T: Encode<'q, MySql>
+        + Type<MySql>
+        + Encode<'q, Mssql>
+        + Type<Mssql>
+        + Encode<'q, Sqlite>
+        + Type<Sqlite>
+        + Encode<'q, DuckDb>
+        + Type<DuckDb>
+{
+}
+
+// only 3 (10)
+
+#[cfg(all(
+    not(any(feature = "sqlite", feature = "duckdb")),
     all(feature = "postgres", feature = "mysql", feature = "mssql")
 ))]
 pub trait AnyEncode<'q>:
@@ -162,7 +334,7 @@ pub trait AnyEncode<'q>:
 }
 
 #[cfg(all(
-    not(feature = "sqlite"),
+    not(any(feature = "sqlite", feature = "duckdb")),
     all(feature = "postgres", feature = "mysql", feature = "mssql")
 ))]
 impl<'q, T> AnyEncode<'q> for T where
@@ -176,37 +348,261 @@ impl<'q, T> AnyEncode<'q> for T where
 }
 
 #[cfg(all(
-    not(feature = "postgres"),
-    all(feature = "sqlite", feature = "mysql", feature = "mssql")
+    not(any(feature = "mssql", feature = "duckdb")),
+    all(feature = "postgres", feature = "mysql", feature = "sqlite")
 ))]
 pub trait AnyEncode<'q>:
-    Encode<'q, Sqlite>
+    Encode<'q, Postgres>
+    + Type<Postgres>
+    + Encode<'q, MySql>
+    + Type<MySql>
+    + Encode<'q, Sqlite>
     + Type<Sqlite>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mssql", feature = "duckdb")),
+    all(feature = "postgres", feature = "mysql", feature = "sqlite")
+))]
+impl<'q, T> AnyEncode<'q> for T where
+    T: Encode<'q, Postgres>
+        + Type<Postgres>
+        + Encode<'q, MySql>
+        + Type<MySql>
+        + Encode<'q, Sqlite>
+        + Type<Sqlite>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mssql", feature = "sqlite")),
+    all(feature = "postgres", feature = "mysql", feature = "duckdb")
+))]
+pub trait AnyEncode<'q>:
+    Encode<'q, Postgres>
+    + Type<Postgres>
     + Encode<'q, MySql>
     + Type<MySql>
+    + Encode<'q, DuckDb>
+    + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mssql", feature = "sqlite")),
+    all(feature = "postgres", feature = "mysql", feature = "duckdb")
+))]
+impl<'q, T> AnyEncode<'q> for T where
+    T: Encode<'q, Postgres>
+        + Type<Postgres>
+        + Encode<'q, MySql>
+        + Type<MySql>
+        + Encode<'q, DuckDb>
+        + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mysql", feature = "duckdb")),
+    all(feature = "postgres", feature = "mssql", feature = "sqlite")
+))]
+pub trait AnyEncode<'q>:
+    Encode<'q, Postgres>
+    + Type<Postgres>
     + Encode<'q, Mssql>
     + Type<Mssql>
+    + Encode<'q, Sqlite>
+    + Type<Sqlite>
 {
 }
 
 #[cfg(all(
-    not(feature = "postgres"),
-    all(feature = "sqlite", feature = "mysql", feature = "mssql")
+    not(any(feature = "mysql", feature = "duckdb")),
+    all(feature = "postgres", feature = "mssql", feature = "sqlite")
 ))]
 impl<'q, T> AnyEncode<'q> for T where
-    T: Encode<'q, Sqlite>
+    T: Encode<'q, Postgres>
+        + Type<Postgres>
+        + Encode<'q, Mssql>
+        + Type<Mssql>
+        + Encode<'q, Sqlite>
         + Type<Sqlite>
-        + Encode<'q, MySql>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mysql", feature = "sqlite")),
+    all(feature = "postgres", feature = "mssql", feature = "duckdb")
+))]
+pub trait AnyEncode<'q>:
+    Encode<'q, Postgres>
+    + Type<Postgres>
+    + Encode<'q, Mssql>
+    + Type<Mssql>
+    + Encode<'q, DuckDb>
+    + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mysql", feature = "sqlite")),
+    all(feature = "postgres", feature = "mssql", feature = "duckdb")
+))]
+impl<'q, T> AnyEncode<'q> for T where
+    T: Encode<'q, Postgres>
+        + Type<Postgres>
+        + Encode<'q, Mssql>
+        + Type<Mssql>
+        + Encode<'q, DuckDb>
+        + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mysql", feature = "mssql")),
+    all(feature = "postgres", feature = "sqlite", feature = "duckdb")
+))]
+pub trait AnyEncode<'q>:
+    Encode<'q, Postgres>
+    + Type<Postgres>
+    + Encode<'q, Sqlite>
+    + Type<Sqlite>
+    + Encode<'q, DuckDb>
+    + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "mysql", feature = "mssql")),
+    all(feature = "postgres", feature = "sqlite", feature = "duckdb")
+))]
+impl<'q, T> AnyEncode<'q> for T where
+    T: Encode<'q, Postgres>
+        + Type<Postgres>
+        + Encode<'q, Sqlite>
+        + Type<Sqlite>
+        + Encode<'q, DuckDb>
+        + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "duckdb")),
+    all(feature = "mysql", feature = "mssql", feature = "sqlite")
+))]
+pub trait AnyEncode<'q>:
+    Encode<'q, MySql>
+    + Type<MySql>
+    + Encode<'q, Mssql>
+    + Type<Mssql>
+    + Encode<'q, Sqlite>
+    + Type<Sqlite>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "duckdb")),
+    all(feature = "mysql", feature = "mssql", feature = "sqlite")
+))]
+impl<'q, T> AnyEncode<'q> for T where
+    T: Encode<'q, MySql>
         + Type<MySql>
         + Encode<'q, Mssql>
         + Type<Mssql>
+        + Encode<'q, Sqlite>
+        + Type<Sqlite>
 {
 }
 
-// only 2 (6)
+#[cfg(all(
+    not(any(feature = "postgres", feature = "sqlite")),
+    all(feature = "mysql", feature = "mssql", feature = "duckdb")
+))]
+pub trait AnyEncode<'q>:
+    Encode<'q, MySql>
+    + Type<MySql>
+    + Encode<'q, Mssql>
+    + Type<Mssql>
+    + Encode<'q, DuckDb>
+    + Type<DuckDb>
+{
+}
 
 #[cfg(all(
-    not(any(feature = "mssql", feature = "sqlite")),
+    not(any(feature = "postgres", feature = "sqlite")),
+    all(feature = "mysql", feature = "mssql", feature = "duckdb")
+))]
+impl<'q, T> AnyEncode<'q> for T where
+    T: Encode<'q, MySql>
+        + Type<MySql>
+        + Encode<'q, Mssql>
+        + Type<Mssql>
+        + Encode<'q, DuckDb>
+        + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mssql")),
+    all(feature = "mysql", feature = "sqlite", feature = "duckdb")
+))]
+pub trait AnyEncode<'q>:
+    Encode<'q, MySql>
+    + Type<MySql>
+    + Encode<'q, Sqlite>
+    + Type<Sqlite>
+    + Encode<'q, DuckDb>
+    + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mssql")),
+    all(feature = "mysql", feature = "sqlite", feature = "duckdb")
+))]
+impl<'q, T> AnyEncode<'q> for T where
+    T: Encode<'q, MySql>
+        + Type<MySql>
+        + Encode<'q, Sqlite>
+        + Type<Sqlite>
+        + Encode<'q, DuckDb>
+        + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mysql")),
+    all(feature = "mssql", feature = "sqlite", feature = "duckdb")
+))]
+pub trait AnyEncode<'q>:
+    Encode<'q, Mssql>
+    + Type<Mssql>
+    + Encode<'q, Sqlite>
+    + Type<Sqlite>
+    + Encode<'q, DuckDb>
+    + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mysql")),
+    all(feature = "mssql", feature = "sqlite", feature = "duckdb")
+))]
+impl<'q, T> AnyEncode<'q> for T where
+    T: Encode<'q, Mssql>
+        + Type<Mssql>
+        + Encode<'q, Sqlite>
+        + Type<Sqlite>
+        + Encode<'q, DuckDb>
+        + Type<DuckDb>
+{
+}
+
+// only 2 (10)
+
+#[cfg(all(
+    not(any(feature = "mssql", feature = "sqlite", feature = "duckdb")),
     all(feature = "postgres", feature = "mysql")
 ))]
 pub trait AnyEncode<'q>:
@@ -215,7 +611,7 @@ pub trait AnyEncode<'q>:
 }
 
 #[cfg(all(
-    not(any(feature = "mssql", feature = "sqlite")),
+    not(any(feature = "mssql", feature = "sqlite", feature = "duckdb")),
     all(feature = "postgres", feature = "mysql")
 ))]
 impl<'q, T> AnyEncode<'q> for T where
@@ -224,7 +620,7 @@ impl<'q, T> AnyEncode<'q> for T where
 }
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "sqlite")),
+    not(any(feature = "mysql", feature = "sqlite", feature = "duckdb")),
     all(feature = "postgres", feature = "mssql")
 ))]
 pub trait AnyEncode<'q>:
@@ -233,7 +629,7 @@ pub trait AnyEncode<'q>:
 }
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "sqlite")),
+    not(any(feature = "mysql", feature = "sqlite", feature = "duckdb")),
     all(feature = "postgres", feature = "mssql")
 ))]
 impl<'q, T> AnyEncode<'q> for T where
@@ -242,7 +638,7 @@ impl<'q, T> AnyEncode<'q> for T where
 }
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "mssql")),
+    not(any(feature = "mysql", feature = "mssql", feature = "duckdb")),
     all(feature = "postgres", feature = "sqlite")
 ))]
 pub trait AnyEncode<'q>:
@@ -251,7 +647,7 @@ pub trait AnyEncode<'q>:
 }
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "mssql")),
+    not(any(feature = "mysql", feature = "mssql", feature = "duckdb")),
     all(feature = "postgres", feature = "sqlite")
 ))]
 impl<'q, T> AnyEncode<'q> for T where
@@ -260,22 +656,76 @@ impl<'q, T> AnyEncode<'q> for T where
 }
 
 #[cfg(all(
-    not(any(feature = "postgres", feature = "sqlite")),
-    all(feature = "mssql", feature = "mysql")
+    not(any(feature = "mysql", feature = "mssql", feature = "sqlite")),
+    all(feature = "postgres", feature = "duckdb")
 ))]
-pub trait AnyEncode<'q>: Encode<'q, Mssql> + Type<Mssql> + Encode<'q, MySql> + Type<MySql> {}
+pub trait AnyEncode<'q>:
+    Encode<'q, Postgres> + Type<Postgres> + Encode<'q, DuckDb> + Type<DuckDb>
+{
+}
 
 #[cfg(all(
-    not(any(feature = "postgres", feature = "sqlite")),
-    all(feature = "mssql", feature = "mysql")
+    not(any(feature = "mysql", feature = "mssql", feature = "sqlite")),
+    all(feature = "postgres", feature = "duckdb")
 ))]
 impl<'q, T> AnyEncode<'q> for T where
-    T: Encode<'q, Mssql> + Type<Mssql> + Encode<'q, MySql> + Type<MySql>
+    T: Encode<'q, Postgres> + Type<Postgres> + Encode<'q, DuckDb> + Type<DuckDb>
 {
 }
 
 #[cfg(all(
-    not(any(feature = "postgres", feature = "mysql")),
+    not(any(feature = "postgres", feature = "sqlite", feature = "duckdb")),
+    all(feature = "mysql", feature = "mssql")
+))]
+pub trait AnyEncode<'q>: Encode<'q, MySql> + Type<MySql> + Encode<'q, Mssql> + Type<Mssql> {}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "sqlite", feature = "duckdb")),
+    all(feature = "mysql", feature = "mssql")
+))]
+impl<'q, T> AnyEncode<'q> for T where
+    T: Encode<'q, MySql> + Type<MySql> + Encode<'q, Mssql> + Type<Mssql>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mssql", feature = "duckdb")),
+    all(feature = "mysql", feature = "sqlite")
+))]
+pub trait AnyEncode<'q>:
+    Encode<'q, MySql> + Type<MySql> + Encode<'q, Sqlite> + Type<Sqlite>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mssql", feature = "duckdb")),
+    all(feature = "mysql", feature = "sqlite")
+))]
+impl<'q, T> AnyEncode<'q> for T where
+    T: Encode<'q, MySql> + Type<MySql> + Encode<'q, Sqlite> + Type<Sqlite>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mssql", feature = "sqlite")),
+    all(feature = "mysql", feature = "duckdb")
+))]
+pub trait AnyEncode<'q>:
+    Encode<'q, MySql> + Type<MySql> + Encode<'q, DuckDb> + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mssql", feature = "sqlite")),
+    all(feature = "mysql", feature = "duckdb")
+))]
+impl<'q, T> AnyEncode<'q> for T where
+    T: Encode<'q, MySql> + Type<MySql> + Encode<'q, DuckDb> + Type<DuckDb>
+{
+}
+
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mysql", feature = "duckdb")),
     all(feature = "mssql", feature = "sqlite")
 ))]
 pub trait AnyEncode<'q>:
@@ -284,7 +734,7 @@ pub trait AnyEncode<'q>:
 }
 
 #[cfg(all(
-    not(any(feature = "postgres", feature = "mysql")),
+    not(any(feature = "postgres", feature = "mysql", feature = "duckdb")),
     all(feature = "mssql", feature = "sqlite")
 ))]
 impl<'q, T> AnyEncode<'q> for T where
@@ -293,69 +743,149 @@ impl<'q, T> AnyEncode<'q> for T where
 }
 
 #[cfg(all(
-    not(any(feature = "postgres", feature = "mssql")),
-    all(feature = "mysql", feature = "sqlite")
+    not(any(feature = "postgres", feature = "mysql", feature = "sqlite")),
+    all(feature = "mssql", feature = "duckdb")
 ))]
 pub trait AnyEncode<'q>:
-    Encode<'q, MySql> + Type<MySql> + Encode<'q, Sqlite> + Type<Sqlite>
+    Encode<'q, Mssql> + Type<Mssql> + Encode<'q, DuckDb> + Type<DuckDb>
 {
 }
 
 #[cfg(all(
-    not(any(feature = "postgres", feature = "mssql")),
-    all(feature = "mysql", feature = "sqlite")
+    not(any(feature = "postgres", feature = "mysql", feature = "sqlite")),
+    all(feature = "mssql", feature = "duckdb")
 ))]
 impl<'q, T> AnyEncode<'q> for T where
-    T: Encode<'q, MySql> + Type<MySql> + Encode<'q, Sqlite> + Type<Sqlite>
+    T: Encode<'q, Mssql> + Type<Mssql> + Encode<'q, DuckDb> + Type<DuckDb>
 {
 }
 
-// only 1 (4)
+#[cfg(all(
+    not(any(feature = "postgres", feature = "mysql", feature = "mssql")),
+    all(feature = "sqlite", feature = "duckdb")
+))]
+pub trait AnyEncode<'q>:
+    Encode<'q, Sqlite> + Type<Sqlite> + Encode<'q, DuckDb> + Type<DuckDb>
+{
+}
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "mssql", feature = "sqlite")),
+    not(any(feature = "postgres", feature = "mysql", feature = "mssql")),
+    all(feature = "sqlite", feature = "duckdb")
+))]
+impl<'q, T> AnyEncode<'q> for T where
+    T: Encode<'q, Sqlite> + Type<Sqlite> + Encode<'q, DuckDb> + Type<DuckDb>
+{
+}
+
+// only 1 (5)
+
+#[cfg(all(
+    not(any(
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )),
     feature = "postgres"
 ))]
 pub trait AnyEncode<'q>: Encode<'q, Postgres> + Type<Postgres> {}
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "mssql", feature = "sqlite")),
+    not(any(
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )),
     feature = "postgres"
 ))]
 impl<'q, T> AnyEncode<'q> for T where T: Encode<'q, Postgres> + Type<Postgres> {}
 
 #[cfg(all(
-    not(any(feature = "postgres", feature = "mssql", feature = "sqlite")),
+    not(any(
+        feature = "postgres",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )),
     feature = "mysql"
 ))]
 pub trait AnyEncode<'q>: Encode<'q, MySql> + Type<MySql> {}
 
 #[cfg(all(
-    not(any(feature = "postgres", feature = "mssql", feature = "sqlite")),
+    not(any(
+        feature = "postgres",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )),
     feature = "mysql"
 ))]
 impl<'q, T> AnyEncode<'q> for T where T: Encode<'q, MySql> + Type<MySql> {}
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "postgres", feature = "sqlite")),
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )),
     feature = "mssql"
 ))]
 pub trait AnyEncode<'q>: Encode<'q, Mssql> + Type<Mssql> {}
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "postgres", feature = "sqlite")),
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "duckdb"
+    )),
     feature = "mssql"
 ))]
 impl<'q, T> AnyEncode<'q> for T where T: Encode<'q, Mssql> + Type<Mssql> {}
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "mssql", feature = "postgres")),
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "duckdb"
+    )),
     feature = "sqlite"
 ))]
 pub trait AnyEncode<'q>: Encode<'q, Sqlite> + Type<Sqlite> {}
 
 #[cfg(all(
-    not(any(feature = "mysql", feature = "mssql", feature = "postgres")),
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "duckdb"
+    )),
     feature = "sqlite"
 ))]
 impl<'q, T> AnyEncode<'q> for T where T: Encode<'q, Sqlite> + Type<Sqlite> {}
+
+#[cfg(all(
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite"
+    )),
+    feature = "duckdb"
+))]
+pub trait AnyEncode<'q>: Encode<'q, DuckDb> + Type<DuckDb> {}
+
+#[cfg(all(
+    not(any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite"
+    )),
+    feature = "duckdb"
+))]
+impl<'q, T> AnyEncode<'q> for T where T: Encode<'q, DuckDb> + Type<DuckDb> {}
@@ -1,3 +1,4 @@
+use crate::any::driver::find_driver;
 use crate::error::Error;
 use std::str::FromStr;
 
@@ -14,12 +15,27 @@ pub enum AnyKind {
 
     #[cfg(feature = "mssql")]
     Mssql,
+
+    #[cfg(feature = "duckdb")]
+    DuckDb,
+
+    /// A driver installed with [`install_driver`][crate::any::install_driver], by its name.
+    Driver(&'static str),
 }
 
 impl FromStr for AnyKind {
     type Err = Error;
 
     fn from_str(url: &str) -> Result<Self, Self::Err> {
+        match find_driver(url) {
+            Some(driver) => Ok(AnyKind::Driver(driver.name())),
+            None => AnyKind::from_builtin_url(url),
+        }
+    }
+}
+
+impl AnyKind {
+    pub(crate) fn from_builtin_url(url: &str) -> Result<Self, Error> {
         match url {
             #[cfg(feature = "postgres")]
             _ if url.starts_with("postgres:") || url.starts_with("postgresql:") => {
@@ -61,6 +77,16 @@ impl FromStr for AnyKind {
                 Err(Error::Configuration("database URL has the scheme of a MSSQL database but the `mssql` feature is not enabled".into()))
             }
 
+            #[cfg(feature = "duckdb")]
+            _ if url.starts_with("duckdb:") => {
+                Ok(AnyKind::DuckDb)
+            }
+
+            #[cfg(not(feature = "duckdb"))]
+            _ if url.starts_with("duckdb:") => {
+                Err(Error::Configuration("database URL has the scheme of a DuckDB database but the `duckdb` feature is not enabled".into()))
+            }
+
             _ => Err(Error::Configuration(format!("unrecognized database url: {:?}", url).into()))
         }
     }
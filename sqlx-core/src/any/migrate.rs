@@ -2,7 +2,10 @@ use crate::any::connection::AnyConnectionKind;
 use crate::any::kind::AnyKind;
 use crate::any::{Any, AnyConnection};
 use crate::error::Error;
-use crate::migrate::{AppliedMigration, Migrate, MigrateDatabase, MigrateError, Migration};
+use crate::migrate::{
+    AppliedMigration, CreateDatabaseOptions, Migrate, MigrateDatabase, MigrateError, Migration,
+    MigrationLock,
+};
 use futures_core::future::BoxFuture;
 use std::str::FromStr;
 use std::time::Duration;
@@ -22,6 +25,39 @@ impl MigrateDatabase for Any {
 
                 #[cfg(feature = "mssql")]
                 AnyKind::Mssql => unimplemented!(),
+
+                #[cfg(feature = "duckdb")]
+                AnyKind::DuckDb => Err(migrations_unsupported("duckdb")),
+
+                AnyKind::Driver(name) => Err(migrations_unsupported(name)),
+            }
+        })
+    }
+
+    fn create_database_with<'a>(
+        url: &'a str,
+        options: &'a CreateDatabaseOptions,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            match AnyKind::from_str(url)? {
+                #[cfg(feature = "postgres")]
+                AnyKind::Postgres => {
+                    crate::postgres::Postgres::create_database_with(url, options).await
+                }
+
+                #[cfg(feature = "sqlite")]
+                AnyKind::Sqlite => crate::sqlite::Sqlite::create_database_with(url, options).await,
+
+                #[cfg(feature = "mysql")]
+                AnyKind::MySql => crate::mysql::MySql::create_database_with(url, options).await,
+
+                #[cfg(feature = "mssql")]
+                AnyKind::Mssql => unimplemented!(),
+
+                #[cfg(feature = "duckdb")]
+                AnyKind::DuckDb => Err(migrations_unsupported("duckdb")),
+
+                AnyKind::Driver(name) => Err(migrations_unsupported(name)),
             }
         })
     }
@@ -40,6 +76,11 @@ impl MigrateDatabase for Any {
 
                 #[cfg(feature = "mssql")]
                 AnyKind::Mssql => unimplemented!(),
+
+                #[cfg(feature = "duckdb")]
+                AnyKind::DuckDb => Err(migrations_unsupported("duckdb")),
+
+                AnyKind::Driver(name) => Err(migrations_unsupported(name)),
             }
         })
     }
@@ -58,25 +99,48 @@ impl MigrateDatabase for Any {
 
                 #[cfg(feature = "mssql")]
                 AnyKind::Mssql => unimplemented!(),
+
+                #[cfg(feature = "duckdb")]
+                AnyKind::DuckDb => Err(migrations_unsupported("duckdb")),
+
+                AnyKind::Driver(name) => Err(migrations_unsupported(name)),
             }
         })
     }
 }
 
 impl Migrate for AnyConnection {
-    fn ensure_migrations_table(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn ensure_migrations_table<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<(), MigrateError>> {
         match &mut self.0 {
             #[cfg(feature = "postgres")]
-            AnyConnectionKind::Postgres(conn) => conn.ensure_migrations_table(),
+            AnyConnectionKind::Postgres(conn) => conn.ensure_migrations_table(table_name),
 
             #[cfg(feature = "sqlite")]
-            AnyConnectionKind::Sqlite(conn) => conn.ensure_migrations_table(),
+            AnyConnectionKind::Sqlite(conn) => conn.ensure_migrations_table(table_name),
 
             #[cfg(feature = "mysql")]
-            AnyConnectionKind::MySql(conn) => conn.ensure_migrations_table(),
+            AnyConnectionKind::MySql(conn) => conn.ensure_migrations_table(table_name),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+            AnyConnectionKind::Mssql(_conn) => {
+                let _ = table_name;
+                unimplemented!()
+            }
+
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(_) => {
+                let _ = table_name;
+                Box::pin(futures_util::future::err(
+                    migrations_unsupported("duckdb").into(),
+                ))
+            }
+
+            AnyConnectionKind::Driver(name, _) => Box::pin(futures_util::future::err(
+                migrations_unsupported(name).into(),
+            )),
         }
     }
 
@@ -94,22 +158,49 @@ impl Migrate for AnyConnection {
 
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(_) => Box::pin(futures_util::future::err(
+                migrations_unsupported("duckdb").into(),
+            )),
+
+            AnyConnectionKind::Driver(name, _) => Box::pin(futures_util::future::err(
+                migrations_unsupported(name).into(),
+            )),
         }
     }
 
-    fn dirty_version(&mut self) -> BoxFuture<'_, Result<Option<i64>, MigrateError>> {
+    fn dirty_version<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Option<i64>, MigrateError>> {
         match &mut self.0 {
             #[cfg(feature = "postgres")]
-            AnyConnectionKind::Postgres(conn) => conn.dirty_version(),
+            AnyConnectionKind::Postgres(conn) => conn.dirty_version(table_name),
 
             #[cfg(feature = "sqlite")]
-            AnyConnectionKind::Sqlite(conn) => conn.dirty_version(),
+            AnyConnectionKind::Sqlite(conn) => conn.dirty_version(table_name),
 
             #[cfg(feature = "mysql")]
-            AnyConnectionKind::MySql(conn) => conn.dirty_version(),
+            AnyConnectionKind::MySql(conn) => conn.dirty_version(table_name),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+            AnyConnectionKind::Mssql(_conn) => {
+                let _ = table_name;
+                unimplemented!()
+            }
+
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(_) => {
+                let _ = table_name;
+                Box::pin(futures_util::future::err(
+                    migrations_unsupported("duckdb").into(),
+                ))
+            }
+
+            AnyConnectionKind::Driver(name, _) => Box::pin(futures_util::future::err(
+                migrations_unsupported(name).into(),
+            )),
         }
     }
 
@@ -133,100 +224,292 @@ impl Migrate for AnyConnection {
                 let _ = migration;
                 unimplemented!()
             }
+
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(_) => {
+                let _ = migration;
+                Box::pin(futures_util::future::err(
+                    migrations_unsupported("duckdb").into(),
+                ))
+            }
+
+            AnyConnectionKind::Driver(name, _) => Box::pin(futures_util::future::err(
+                migrations_unsupported(name).into(),
+            )),
         }
     }
 
-    fn list_applied_migrations(
-        &mut self,
-    ) -> BoxFuture<'_, Result<Vec<AppliedMigration>, MigrateError>> {
+    fn list_applied_migrations<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Vec<AppliedMigration>, MigrateError>> {
         match &mut self.0 {
             #[cfg(feature = "postgres")]
-            AnyConnectionKind::Postgres(conn) => conn.list_applied_migrations(),
+            AnyConnectionKind::Postgres(conn) => conn.list_applied_migrations(table_name),
 
             #[cfg(feature = "sqlite")]
-            AnyConnectionKind::Sqlite(conn) => conn.list_applied_migrations(),
+            AnyConnectionKind::Sqlite(conn) => conn.list_applied_migrations(table_name),
 
             #[cfg(feature = "mysql")]
-            AnyConnectionKind::MySql(conn) => conn.list_applied_migrations(),
+            AnyConnectionKind::MySql(conn) => conn.list_applied_migrations(table_name),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+            AnyConnectionKind::Mssql(_conn) => {
+                let _ = table_name;
+                unimplemented!()
+            }
+
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(_) => {
+                let _ = table_name;
+                Box::pin(futures_util::future::err(
+                    migrations_unsupported("duckdb").into(),
+                ))
+            }
+
+            AnyConnectionKind::Driver(name, _) => Box::pin(futures_util::future::err(
+                migrations_unsupported(name).into(),
+            )),
         }
     }
 
-    fn lock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn lock(&mut self, lock: MigrationLock) -> BoxFuture<'_, Result<(), MigrateError>> {
         match &mut self.0 {
             #[cfg(feature = "postgres")]
-            AnyConnectionKind::Postgres(conn) => conn.lock(),
+            AnyConnectionKind::Postgres(conn) => conn.lock(lock),
 
             #[cfg(feature = "sqlite")]
-            AnyConnectionKind::Sqlite(conn) => conn.lock(),
+            AnyConnectionKind::Sqlite(conn) => conn.lock(lock),
 
             #[cfg(feature = "mysql")]
-            AnyConnectionKind::MySql(conn) => conn.lock(),
+            AnyConnectionKind::MySql(conn) => conn.lock(lock),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+            AnyConnectionKind::Mssql(_conn) => {
+                let _ = lock;
+                unimplemented!()
+            }
+
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(_) => {
+                let _ = lock;
+                Box::pin(futures_util::future::err(
+                    migrations_unsupported("duckdb").into(),
+                ))
+            }
+
+            AnyConnectionKind::Driver(name, _) => Box::pin(futures_util::future::err(
+                migrations_unsupported(name).into(),
+            )),
         }
     }
 
-    fn unlock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn unlock(&mut self, lock: MigrationLock) -> BoxFuture<'_, Result<(), MigrateError>> {
         match &mut self.0 {
             #[cfg(feature = "postgres")]
-            AnyConnectionKind::Postgres(conn) => conn.unlock(),
+            AnyConnectionKind::Postgres(conn) => conn.unlock(lock),
 
             #[cfg(feature = "sqlite")]
-            AnyConnectionKind::Sqlite(conn) => conn.unlock(),
+            AnyConnectionKind::Sqlite(conn) => conn.unlock(lock),
 
             #[cfg(feature = "mysql")]
-            AnyConnectionKind::MySql(conn) => conn.unlock(),
+            AnyConnectionKind::MySql(conn) => conn.unlock(lock),
 
             #[cfg(feature = "mssql")]
-            AnyConnectionKind::Mssql(_conn) => unimplemented!(),
+            AnyConnectionKind::Mssql(_conn) => {
+                let _ = lock;
+                unimplemented!()
+            }
+
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(_) => {
+                let _ = lock;
+                Box::pin(futures_util::future::err(
+                    migrations_unsupported("duckdb").into(),
+                ))
+            }
+
+            AnyConnectionKind::Driver(name, _) => Box::pin(futures_util::future::err(
+                migrations_unsupported(name).into(),
+            )),
         }
     }
 
     fn apply<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         match &mut self.0 {
             #[cfg(feature = "postgres")]
-            AnyConnectionKind::Postgres(conn) => conn.apply(migration),
+            AnyConnectionKind::Postgres(conn) => conn.apply(table_name, migration),
 
             #[cfg(feature = "sqlite")]
-            AnyConnectionKind::Sqlite(conn) => conn.apply(migration),
+            AnyConnectionKind::Sqlite(conn) => conn.apply(table_name, migration),
 
             #[cfg(feature = "mysql")]
-            AnyConnectionKind::MySql(conn) => conn.apply(migration),
+            AnyConnectionKind::MySql(conn) => conn.apply(table_name, migration),
 
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(_conn) => {
-                let _ = migration;
+                let _ = (table_name, migration);
                 unimplemented!()
             }
+
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(_) => {
+                let _ = (table_name, migration);
+                Box::pin(futures_util::future::err(
+                    migrations_unsupported("duckdb").into(),
+                ))
+            }
+
+            AnyConnectionKind::Driver(name, _) => Box::pin(futures_util::future::err(
+                migrations_unsupported(name).into(),
+            )),
+        }
+    }
+
+    fn update_checksum<'e: 'm, 'm>(
+        &'e mut self,
+        table_name: &'m str,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        match &mut self.0 {
+            #[cfg(feature = "postgres")]
+            AnyConnectionKind::Postgres(conn) => conn.update_checksum(table_name, migration),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnectionKind::Sqlite(conn) => conn.update_checksum(table_name, migration),
+
+            #[cfg(feature = "mysql")]
+            AnyConnectionKind::MySql(conn) => conn.update_checksum(table_name, migration),
+
+            #[cfg(feature = "mssql")]
+            AnyConnectionKind::Mssql(_conn) => {
+                let _ = (table_name, migration);
+                unimplemented!()
+            }
+
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(_) => {
+                let _ = (table_name, migration);
+                Box::pin(futures_util::future::err(
+                    migrations_unsupported("duckdb").into(),
+                ))
+            }
+
+            AnyConnectionKind::Driver(name, _) => Box::pin(futures_util::future::err(
+                migrations_unsupported(name).into(),
+            )),
+        }
+    }
+
+    fn record_applied<'e: 'm, 'm>(
+        &'e mut self,
+        table_name: &'m str,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        match &mut self.0 {
+            #[cfg(feature = "postgres")]
+            AnyConnectionKind::Postgres(conn) => conn.record_applied(table_name, migration),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnectionKind::Sqlite(conn) => conn.record_applied(table_name, migration),
+
+            #[cfg(feature = "mysql")]
+            AnyConnectionKind::MySql(conn) => conn.record_applied(table_name, migration),
+
+            #[cfg(feature = "mssql")]
+            AnyConnectionKind::Mssql(_conn) => {
+                let _ = (table_name, migration);
+                unimplemented!()
+            }
+
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(_) => {
+                let _ = (table_name, migration);
+                Box::pin(futures_util::future::err(
+                    migrations_unsupported("duckdb").into(),
+                ))
+            }
+
+            AnyConnectionKind::Driver(name, _) => Box::pin(futures_util::future::err(
+                migrations_unsupported(name).into(),
+            )),
+        }
+    }
+
+    fn dump_schema<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<String, MigrateError>> {
+        match &mut self.0 {
+            #[cfg(feature = "postgres")]
+            AnyConnectionKind::Postgres(conn) => conn.dump_schema(table_name),
+
+            #[cfg(feature = "sqlite")]
+            AnyConnectionKind::Sqlite(conn) => conn.dump_schema(table_name),
+
+            #[cfg(feature = "mysql")]
+            AnyConnectionKind::MySql(conn) => conn.dump_schema(table_name),
+
+            #[cfg(feature = "mssql")]
+            AnyConnectionKind::Mssql(_conn) => {
+                let _ = table_name;
+                unimplemented!()
+            }
+
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(_) => {
+                let _ = table_name;
+                Box::pin(futures_util::future::err(
+                    migrations_unsupported("duckdb").into(),
+                ))
+            }
+
+            AnyConnectionKind::Driver(name, _) => Box::pin(futures_util::future::err(
+                migrations_unsupported(name).into(),
+            )),
         }
     }
 
     fn revert<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         match &mut self.0 {
             #[cfg(feature = "postgres")]
-            AnyConnectionKind::Postgres(conn) => conn.revert(migration),
+            AnyConnectionKind::Postgres(conn) => conn.revert(table_name, migration),
 
             #[cfg(feature = "sqlite")]
-            AnyConnectionKind::Sqlite(conn) => conn.revert(migration),
+            AnyConnectionKind::Sqlite(conn) => conn.revert(table_name, migration),
 
             #[cfg(feature = "mysql")]
-            AnyConnectionKind::MySql(conn) => conn.revert(migration),
+            AnyConnectionKind::MySql(conn) => conn.revert(table_name, migration),
 
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(_conn) => {
-                let _ = migration;
+                let _ = (table_name, migration);
                 unimplemented!()
             }
+
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(_) => {
+                let _ = (table_name, migration);
+                Box::pin(futures_util::future::err(
+                    migrations_unsupported("duckdb").into(),
+                ))
+            }
+
+            AnyConnectionKind::Driver(name, _) => Box::pin(futures_util::future::err(
+                migrations_unsupported(name).into(),
+            )),
         }
     }
 }
+
+fn migrations_unsupported(driver: &str) -> Error {
+    Error::Configuration(format!("the `{}` driver doesn't support migrations", driver).into())
+}
@@ -14,7 +14,9 @@ mod r#type;
 mod arguments;
 pub(crate) mod column;
 mod connection;
+pub(crate) mod data;
 mod database;
+mod driver;
 mod error;
 mod kind;
 mod options;
@@ -35,8 +37,10 @@ pub use connection::AnyConnection;
 // Used internally in `sqlx-macros`
 #[doc(hidden)]
 pub use connection::AnyConnectionKind;
+pub use data::AnyData;
 pub use database::Any;
 pub use decode::AnyDecode;
+pub use driver::{install_driver, AnyConnectionBackend, AnyDriver, AnyDriverConnectOptions};
 pub use encode::AnyEncode;
 pub use kind::AnyKind;
 pub use options::AnyConnectOptions;
@@ -59,6 +63,7 @@ impl<'c, T: Executor<'c, Database = Any>> AnyExecutor<'c> for T {}
 impl_into_arguments_for_arguments!(AnyArguments<'q>);
 impl_executor_for_pool_connection!(Any, AnyConnection, AnyRow);
 impl_executor_for_transaction!(Any, AnyRow);
+impl_executor_for_reconnecting_connection!(Any, AnyRow);
 impl_acquire!(Any, AnyConnection);
 impl_column_index_for_row!(AnyRow);
 impl_column_index_for_statement!(AnyStatement);
@@ -67,7 +72,8 @@ impl_into_maybe_pool!(Any, AnyConnection);
 // required because some databases have a different handling of NULL
 impl<'q, T> crate::encode::Encode<'q, Any> for Option<T>
 where
-    T: AnyEncode<'q> + 'q,
+    // `Encode<Any>` to encode the value for an installed driver
+    T: AnyEncode<'q> + crate::encode::Encode<'q, Any> + 'q,
 {
     fn encode_by_ref(&self, buf: &mut AnyArgumentBuffer<'q>) -> crate::encode::IsNull {
         match &mut buf.0 {
@@ -82,6 +88,16 @@ where
 
             #[cfg(feature = "sqlite")]
             arguments::AnyArgumentBufferKind::Sqlite(args) => args.add(self),
+
+            #[cfg(feature = "duckdb")]
+            arguments::AnyArgumentBufferKind::DuckDb(args) => args.add(self),
+
+            arguments::AnyArgumentBufferKind::Driver(args, _) => match self {
+                Some(value) => {
+                    return <T as crate::encode::Encode<'q, Any>>::encode_by_ref(value, buf)
+                }
+                None => args.push(AnyData::Null),
+            },
         }
 
         // unused
@@ -1,9 +1,12 @@
+use crate::any::driver::{find_driver, AnyDriverConnectOptions};
 use crate::any::AnyConnection;
 use crate::connection::ConnectOptions;
 use crate::error::Error;
+use crate::query_log::QueryLog;
 use futures_core::future::BoxFuture;
 use log::LevelFilter;
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 
 #[cfg(feature = "postgres")]
@@ -15,6 +18,9 @@ use crate::mysql::MySqlConnectOptions;
 #[cfg(feature = "sqlite")]
 use crate::sqlite::SqliteConnectOptions;
 
+#[cfg(feature = "duckdb")]
+use crate::duckdb::DuckDbConnectOptions;
+
 use crate::any::kind::AnyKind;
 #[cfg(feature = "mssql")]
 use crate::mssql::MssqlConnectOptions;
@@ -41,8 +47,13 @@ impl AnyConnectOptions {
             #[cfg(feature = "sqlite")]
             AnyConnectOptionsKind::Sqlite(_) => AnyKind::Sqlite,
 
+            #[cfg(feature = "duckdb")]
+            AnyConnectOptionsKind::DuckDb(_) => AnyKind::DuckDb,
+
             #[cfg(feature = "mssql")]
             AnyConnectOptionsKind::Mssql(_) => AnyKind::Mssql,
+
+            AnyConnectOptionsKind::Driver(o) => AnyKind::Driver(o.driver.name()),
         }
     }
 }
@@ -105,6 +116,13 @@ try_from_any_connect_options_to!(
     "sqlite"
 );
 
+#[cfg(feature = "duckdb")]
+try_from_any_connect_options_to!(
+    DuckDbConnectOptions,
+    AnyConnectOptionsKind::DuckDb,
+    "duckdb"
+);
+
 #[cfg(feature = "mssql")]
 try_from_any_connect_options_to!(MssqlConnectOptions, AnyConnectOptionsKind::Mssql, "mssql");
 
@@ -119,8 +137,13 @@ pub(crate) enum AnyConnectOptionsKind {
     #[cfg(feature = "sqlite")]
     Sqlite(SqliteConnectOptions),
 
+    #[cfg(feature = "duckdb")]
+    DuckDb(DuckDbConnectOptions),
+
     #[cfg(feature = "mssql")]
     Mssql(MssqlConnectOptions),
+
+    Driver(AnyDriverConnectOptions),
 }
 
 #[cfg(feature = "postgres")]
@@ -144,6 +167,13 @@ impl From<SqliteConnectOptions> for AnyConnectOptions {
     }
 }
 
+#[cfg(feature = "duckdb")]
+impl From<DuckDbConnectOptions> for AnyConnectOptions {
+    fn from(options: DuckDbConnectOptions) -> Self {
+        Self(AnyConnectOptionsKind::DuckDb(options))
+    }
+}
+
 #[cfg(feature = "mssql")]
 impl From<MssqlConnectOptions> for AnyConnectOptions {
     fn from(options: MssqlConnectOptions) -> Self {
@@ -169,8 +199,22 @@ impl FromStr for AnyConnectOptions {
                 SqliteConnectOptions::from_str(url).map(AnyConnectOptionsKind::Sqlite)
             }
 
+            #[cfg(feature = "duckdb")]
+            AnyKind::DuckDb => {
+                DuckDbConnectOptions::from_str(url).map(AnyConnectOptionsKind::DuckDb)
+            }
+
             #[cfg(feature = "mssql")]
             AnyKind::Mssql => MssqlConnectOptions::from_str(url).map(AnyConnectOptionsKind::Mssql),
+
+            AnyKind::Driver(_) => {
+                // an installed driver is never removed
+                let driver = find_driver(url).expect("BUG: the driver of the URL is not installed");
+
+                Ok(AnyConnectOptionsKind::Driver(AnyDriverConnectOptions::new(
+                    driver, url,
+                )))
+            }
         }
         .map(AnyConnectOptions)
     }
@@ -201,10 +245,19 @@ impl ConnectOptions for AnyConnectOptions {
                 o.log_statements(level);
             }
 
+            #[cfg(feature = "duckdb")]
+            AnyConnectOptionsKind::DuckDb(o) => {
+                o.log_statements(level);
+            }
+
             #[cfg(feature = "mssql")]
             AnyConnectOptionsKind::Mssql(o) => {
                 o.log_statements(level);
             }
+
+            AnyConnectOptionsKind::Driver(o) => {
+                o.log_settings.log_statements(level);
+            }
         };
         self
     }
@@ -226,10 +279,88 @@ impl ConnectOptions for AnyConnectOptions {
                 o.log_slow_statements(level, duration);
             }
 
+            #[cfg(feature = "duckdb")]
+            AnyConnectOptionsKind::DuckDb(o) => {
+                o.log_slow_statements(level, duration);
+            }
+
             #[cfg(feature = "mssql")]
             AnyConnectOptionsKind::Mssql(o) => {
                 o.log_slow_statements(level, duration);
             }
+
+            AnyConnectOptionsKind::Driver(o) => {
+                o.log_settings.log_slow_statements(level, duration);
+            }
+        };
+        self
+    }
+
+    fn query_log(&mut self, log: Arc<dyn QueryLog>) -> &mut Self {
+        match &mut self.0 {
+            #[cfg(feature = "postgres")]
+            AnyConnectOptionsKind::Postgres(o) => {
+                o.query_log(log);
+            }
+
+            #[cfg(feature = "mysql")]
+            AnyConnectOptionsKind::MySql(o) => {
+                o.query_log(log);
+            }
+
+            #[cfg(feature = "sqlite")]
+            AnyConnectOptionsKind::Sqlite(o) => {
+                o.query_log(log);
+            }
+
+            #[cfg(feature = "duckdb")]
+            AnyConnectOptionsKind::DuckDb(o) => {
+                o.query_log(log);
+            }
+
+            #[cfg(feature = "mssql")]
+            AnyConnectOptionsKind::Mssql(o) => {
+                o.query_log(log);
+            }
+
+            AnyConnectOptionsKind::Driver(o) => {
+                o.log_settings.query_log(log);
+            }
+        };
+        self
+    }
+
+    fn application_name(&mut self, name: &str) -> &mut Self {
+        // the inherent builders of the same name take the options by value
+        match &mut self.0 {
+            #[cfg(feature = "postgres")]
+            AnyConnectOptionsKind::Postgres(o) => {
+                ConnectOptions::application_name(o, name);
+            }
+
+            #[cfg(feature = "mysql")]
+            AnyConnectOptionsKind::MySql(o) => {
+                ConnectOptions::application_name(o, name);
+            }
+
+            #[cfg(feature = "sqlite")]
+            AnyConnectOptionsKind::Sqlite(o) => {
+                ConnectOptions::application_name(o, name);
+            }
+
+            #[cfg(feature = "duckdb")]
+            AnyConnectOptionsKind::DuckDb(o) => {
+                ConnectOptions::application_name(o, name);
+            }
+
+            #[cfg(feature = "mssql")]
+            AnyConnectOptionsKind::Mssql(o) => {
+                ConnectOptions::application_name(o, name);
+            }
+
+            AnyConnectOptionsKind::Driver(o) => {
+                o.application_name = Some(name.to_owned());
+            }
         };
         self
     }
@@ -7,6 +7,15 @@ pub struct AnyQueryResult {
 }
 
 impl AnyQueryResult {
+    /// The result of a statement run by an installed driver; see
+    /// [`AnyConnectionBackend`][crate::any::AnyConnectionBackend].
+    pub fn new(rows_affected: u64, last_insert_id: Option<i64>) -> Self {
+        Self {
+            rows_affected,
+            last_insert_id,
+        }
+    }
+
     pub fn rows_affected(&self) -> u64 {
         self.rows_affected
     }
@@ -1,13 +1,17 @@
 use crate::any::error::mismatched_types;
-use crate::any::{Any, AnyColumn, AnyColumnIndex};
-use crate::column::ColumnIndex;
+use crate::any::value::AnyValueRefKind;
+use crate::any::{Any, AnyColumn, AnyColumnIndex, AnyData, AnyValueRef};
+use crate::column::{Column, ColumnIndex};
 use crate::database::HasValueRef;
 use crate::decode::Decode;
 use crate::error::Error;
+use crate::ext::ustr::UStr;
 use crate::row::Row;
 use crate::type_info::TypeInfo;
 use crate::types::Type;
 use crate::value::ValueRef;
+use crate::HashMap;
+use std::sync::Arc;
 
 #[cfg(feature = "postgres")]
 use crate::postgres::PgRow;
@@ -18,16 +22,90 @@ use crate::mysql::MySqlRow;
 #[cfg(feature = "sqlite")]
 use crate::sqlite::SqliteRow;
 
+#[cfg(feature = "duckdb")]
+use crate::duckdb::DuckDbRow;
+
 #[cfg(feature = "mssql")]
 use crate::mssql::MssqlRow;
 
 pub struct AnyRow {
     pub(crate) kind: AnyRowKind,
-    pub(crate) columns: Vec<AnyColumn>,
+    pub(crate) columns: Arc<AnyColumns>,
+}
+
+// the columns of the rows of a result set, with an index of their names; shared by its rows
+pub(crate) struct AnyColumns {
+    columns: Vec<AnyColumn>,
+    column_names: HashMap<UStr, usize>,
+}
+
+impl AnyColumns {
+    fn new(columns: Vec<AnyColumn>) -> Self {
+        let column_names = columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| (UStr::new(column.name()), index))
+            .collect();
+
+        Self {
+            columns,
+            column_names,
+        }
+    }
+}
+
+// converts the columns of the rows of a result set once, as long as the driver's rows share
+// the same columns
+#[derive(Default)]
+pub(crate) struct AnyColumnsCache {
+    last: Option<(Arc<dyn std::any::Any + Send + Sync>, Arc<AnyColumns>)>,
+}
+
+impl AnyColumnsCache {
+    pub(crate) fn get<C>(
+        &mut self,
+        columns: &Arc<C>,
+        convert: impl FnOnce(&C) -> Vec<AnyColumn>,
+    ) -> Arc<AnyColumns>
+    where
+        C: Send + Sync + 'static,
+    {
+        if let Some((last, converted)) = &self.last {
+            // holding on to the last columns ensures their address isn't reused
+            if Arc::as_ptr(last) as *const u8 == Arc::as_ptr(columns) as *const u8 {
+                return Arc::clone(converted);
+            }
+        }
+
+        let converted = Arc::new(AnyColumns::new(convert(columns)));
+        self.last = Some((Arc::clone(columns) as _, Arc::clone(&converted)));
+
+        converted
+    }
 }
 
 impl crate::row::private_row::Sealed for AnyRow {}
 
+impl AnyRow {
+    /// A row returned by an installed driver; see
+    /// [`AnyConnectionBackend`][crate::any::AnyConnectionBackend].
+    ///
+    /// # Panics
+    /// If there isn't one value for each column.
+    pub fn new(columns: Vec<AnyColumn>, values: Vec<AnyData>) -> Self {
+        assert_eq!(
+            columns.len(),
+            values.len(),
+            "a row must have one value for each column"
+        );
+
+        Self {
+            kind: AnyRowKind::Driver(values),
+            columns: Arc::new(AnyColumns::new(columns)),
+        }
+    }
+}
+
 pub(crate) enum AnyRowKind {
     #[cfg(feature = "postgres")]
     Postgres(PgRow),
@@ -38,15 +116,20 @@ pub(crate) enum AnyRowKind {
     #[cfg(feature = "sqlite")]
     Sqlite(SqliteRow),
 
+    #[cfg(feature = "duckdb")]
+    DuckDb(DuckDbRow),
+
     #[cfg(feature = "mssql")]
     Mssql(MssqlRow),
+
+    Driver(Vec<AnyData>),
 }
 
 impl Row for AnyRow {
     type Database = Any;
 
     fn columns(&self) -> &[AnyColumn] {
-        &self.columns
+        &self.columns.columns
     }
 
     fn try_get_raw<I>(
@@ -68,8 +151,16 @@ impl Row for AnyRow {
             #[cfg(feature = "sqlite")]
             AnyRowKind::Sqlite(row) => row.try_get_raw(index).map(Into::into),
 
+            #[cfg(feature = "duckdb")]
+            AnyRowKind::DuckDb(row) => row.try_get_raw(index).map(Into::into),
+
             #[cfg(feature = "mssql")]
             AnyRowKind::Mssql(row) => row.try_get_raw(index).map(Into::into),
+
+            AnyRowKind::Driver(values) => Ok(AnyValueRef {
+                kind: AnyValueRefKind::Driver(&values[index]),
+                type_info: self.columns.columns[index].type_info.clone(),
+            }),
         }
     }
 
@@ -108,8 +199,94 @@ where
             #[cfg(feature = "sqlite")]
             AnyRowKind::Sqlite(row) => self.index(row),
 
+            #[cfg(feature = "duckdb")]
+            AnyRowKind::DuckDb(row) => self.index(row),
+
             #[cfg(feature = "mssql")]
             AnyRowKind::Mssql(row) => self.index(row),
+
+            AnyRowKind::Driver(_) => row
+                .columns
+                .column_names
+                .get(*self)
+                .copied()
+                .ok_or_else(|| Error::ColumnNotFound((*self).into())),
+        }
+    }
+}
+
+/// Serializes the row as the row of its database does; the rows of MSSQL and DuckDB fail to
+/// serialize. The values of a row returned by an installed driver are serialized as
+/// themselves.
+#[cfg(feature = "json")]
+impl serde::Serialize for AnyRow {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match &self.kind {
+            #[cfg(feature = "postgres")]
+            AnyRowKind::Postgres(row) => row.serialize(serializer),
+
+            #[cfg(feature = "mysql")]
+            AnyRowKind::MySql(row) => row.serialize(serializer),
+
+            #[cfg(feature = "sqlite")]
+            AnyRowKind::Sqlite(row) => row.serialize(serializer),
+
+            #[cfg(feature = "duckdb")]
+            AnyRowKind::DuckDb(_) => Err(serde::ser::Error::custom(
+                "the rows of DuckDB cannot be serialized",
+            )),
+
+            #[cfg(feature = "mssql")]
+            AnyRowKind::Mssql(_) => Err(serde::ser::Error::custom(
+                "the rows of MSSQL cannot be serialized",
+            )),
+
+            AnyRowKind::Driver(_) => crate::row::serialize_row(self, serializer, |_, value| {
+                let value = match value.kind {
+                    AnyValueRefKind::Driver(value) => value,
+                    #[allow(unreachable_patterns)]
+                    _ => unreachable!(),
+                };
+
+                Ok(match value {
+                    AnyData::Null => serde_json::Value::Null,
+                    AnyData::Bool(v) => (*v).into(),
+                    AnyData::SmallInt(v) => (*v).into(),
+                    AnyData::Integer(v) => (*v).into(),
+                    AnyData::BigInt(v) => (*v).into(),
+                    AnyData::Real(v) => f64::from(*v).into(),
+                    AnyData::Double(v) => (*v).into(),
+                    AnyData::Text(v) => v.as_str().into(),
+                    AnyData::Blob(v) => v.as_slice().into(),
+                })
+            }),
+        }
+    }
+}
+
+/// The type of a column is the type of its database; the columns of an installed driver are
+/// written as text.
+#[cfg(feature = "parquet")]
+impl crate::export::parquet::ParquetDatabase for Any {
+    fn parquet_type(ty: &crate::any::AnyTypeInfo) -> crate::export::parquet::ParquetType {
+        use crate::any::type_info::AnyTypeInfoKind;
+        use crate::export::parquet::ParquetType;
+
+        match &ty.0 {
+            #[cfg(feature = "postgres")]
+            AnyTypeInfoKind::Postgres(ty) => crate::postgres::Postgres::parquet_type(ty),
+
+            #[cfg(feature = "mysql")]
+            AnyTypeInfoKind::MySql(ty) => crate::mysql::MySql::parquet_type(ty),
+
+            #[cfg(feature = "sqlite")]
+            AnyTypeInfoKind::Sqlite(ty) => crate::sqlite::Sqlite::parquet_type(ty),
+
+            #[allow(unreachable_patterns)]
+            _ => ParquetType::String,
         }
     }
 }
@@ -1,5 +1,5 @@
 use crate::any::{Any, AnyArguments, AnyColumn, AnyColumnIndex, AnyTypeInfo};
-use crate::column::ColumnIndex;
+use crate::column::{Column, ColumnIndex};
 use crate::error::Error;
 use crate::ext::ustr::UStr;
 use crate::statement::Statement;
@@ -15,6 +15,29 @@ pub struct AnyStatement<'q> {
     pub(crate) columns: Vec<AnyColumn>,
 }
 
+impl<'q> AnyStatement<'q> {
+    /// A statement prepared by an installed driver, with the number of its parameters if it's
+    /// known; see [`AnyConnectionBackend`][crate::any::AnyConnectionBackend].
+    pub fn new(
+        sql: impl Into<Cow<'q, str>>,
+        parameters: Option<usize>,
+        columns: Vec<AnyColumn>,
+    ) -> Self {
+        let column_names = columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| (UStr::new(column.name()), i))
+            .collect();
+
+        Self {
+            sql: sql.into(),
+            parameters: parameters.map(Either::Right),
+            column_names: Arc::new(column_names),
+            columns,
+        }
+    }
+}
+
 impl<'q> Statement<'q> for AnyStatement<'q> {
     type Database = Any;
 
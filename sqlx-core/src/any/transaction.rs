@@ -4,7 +4,9 @@ use crate::any::connection::AnyConnectionKind;
 use crate::any::{Any, AnyConnection};
 use crate::database::Database;
 use crate::error::Error;
-use crate::transaction::TransactionManager;
+use crate::transaction::{
+    savepoint_ansi_sql, SavepointStatement, TransactionManager, TransactionOptions,
+};
 
 pub struct AnyTransactionManager;
 
@@ -28,10 +30,57 @@ impl TransactionManager for AnyTransactionManager {
                 <crate::sqlite::Sqlite as Database>::TransactionManager::begin(conn)
             }
 
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(conn) => {
+                <crate::duckdb::DuckDb as Database>::TransactionManager::begin(conn)
+            }
+
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(conn) => {
                 <crate::mssql::Mssql as Database>::TransactionManager::begin(conn)
             }
+
+            AnyConnectionKind::Driver(_, conn) => conn.begin(),
+        }
+    }
+
+    fn begin_with(
+        conn: &mut AnyConnection,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        match &mut conn.0 {
+            #[cfg(feature = "postgres")]
+            AnyConnectionKind::Postgres(conn) => {
+                <crate::postgres::Postgres as Database>::TransactionManager::begin_with(
+                    conn, options,
+                )
+            }
+
+            #[cfg(feature = "mysql")]
+            AnyConnectionKind::MySql(conn) => {
+                <crate::mysql::MySql as Database>::TransactionManager::begin_with(conn, options)
+            }
+
+            #[cfg(feature = "sqlite")]
+            AnyConnectionKind::Sqlite(conn) => {
+                <crate::sqlite::Sqlite as Database>::TransactionManager::begin_with(conn, options)
+            }
+
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(conn) => {
+                <crate::duckdb::DuckDb as Database>::TransactionManager::begin_with(conn, options)
+            }
+
+            #[cfg(feature = "mssql")]
+            AnyConnectionKind::Mssql(conn) => {
+                <crate::mssql::Mssql as Database>::TransactionManager::begin_with(conn, options)
+            }
+
+            AnyConnectionKind::Driver(..) => Box::pin(async move {
+                Err(Error::Configuration(
+                    "transaction options are not supported by this driver".into(),
+                ))
+            }),
         }
     }
 
@@ -52,10 +101,17 @@ impl TransactionManager for AnyTransactionManager {
                 <crate::sqlite::Sqlite as Database>::TransactionManager::commit(conn)
             }
 
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(conn) => {
+                <crate::duckdb::DuckDb as Database>::TransactionManager::commit(conn)
+            }
+
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(conn) => {
                 <crate::mssql::Mssql as Database>::TransactionManager::commit(conn)
             }
+
+            AnyConnectionKind::Driver(_, conn) => conn.commit(),
         }
     }
 
@@ -76,10 +132,17 @@ impl TransactionManager for AnyTransactionManager {
                 <crate::sqlite::Sqlite as Database>::TransactionManager::rollback(conn)
             }
 
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(conn) => {
+                <crate::duckdb::DuckDb as Database>::TransactionManager::rollback(conn)
+            }
+
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(conn) => {
                 <crate::mssql::Mssql as Database>::TransactionManager::rollback(conn)
             }
+
+            AnyConnectionKind::Driver(_, conn) => conn.rollback(),
         }
     }
 
@@ -100,10 +163,68 @@ impl TransactionManager for AnyTransactionManager {
                 <crate::sqlite::Sqlite as Database>::TransactionManager::start_rollback(conn)
             }
 
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(conn) => {
+                <crate::duckdb::DuckDb as Database>::TransactionManager::start_rollback(conn)
+            }
+
             #[cfg(feature = "mssql")]
             AnyConnectionKind::Mssql(conn) => {
                 <crate::mssql::Mssql as Database>::TransactionManager::start_rollback(conn)
             }
+
+            AnyConnectionKind::Driver(_, conn) => conn.start_rollback(),
+        }
+    }
+
+    fn start_statement(conn: &mut AnyConnection, sql: &str) {
+        match &mut conn.0 {
+            #[cfg(feature = "postgres")]
+            AnyConnectionKind::Postgres(conn) => {
+                <crate::postgres::Postgres as Database>::TransactionManager::start_statement(
+                    conn, sql,
+                )
+            }
+
+            #[cfg(feature = "mysql")]
+            AnyConnectionKind::MySql(conn) => {
+                <crate::mysql::MySql as Database>::TransactionManager::start_statement(conn, sql)
+            }
+
+            #[cfg(feature = "sqlite")]
+            AnyConnectionKind::Sqlite(conn) => {
+                <crate::sqlite::Sqlite as Database>::TransactionManager::start_statement(conn, sql)
+            }
+
+            #[cfg(feature = "duckdb")]
+            AnyConnectionKind::DuckDb(conn) => {
+                <crate::duckdb::DuckDb as Database>::TransactionManager::start_statement(conn, sql)
+            }
+
+            #[cfg(feature = "mssql")]
+            AnyConnectionKind::Mssql(conn) => {
+                <crate::mssql::Mssql as Database>::TransactionManager::start_statement(conn, sql)
+            }
+
+            AnyConnectionKind::Driver(_, conn) => conn.start_statement(sql),
+        }
+    }
+
+    fn savepoint_sql(
+        conn: &AnyConnection,
+        statement: SavepointStatement,
+        name: &str,
+    ) -> Option<String> {
+        match &conn.0 {
+            #[cfg(feature = "mssql")]
+            AnyConnectionKind::Mssql(conn) => {
+                <crate::mssql::Mssql as Database>::TransactionManager::savepoint_sql(
+                    conn, statement, name,
+                )
+            }
+
+            // the other databases have the savepoints of the SQL standard
+            _ => Some(savepoint_ansi_sql(statement, name)),
         }
     }
 }
@@ -29,10 +29,18 @@ macro_rules! impl_any_type {
                         <$ty as crate::types::Type<crate::sqlite::Sqlite>>::compatible(&ty)
                     }
 
+                    #[cfg(feature = "duckdb")]
+                    crate::any::type_info::AnyTypeInfoKind::DuckDb(ty) => {
+                        <$ty as crate::types::Type<crate::duckdb::DuckDb>>::compatible(&ty)
+                    }
+
                     #[cfg(feature = "mssql")]
                     crate::any::type_info::AnyTypeInfoKind::Mssql(ty) => {
                         <$ty as crate::types::Type<crate::mssql::Mssql>>::compatible(&ty)
                     }
+
+                    // checked when decoding the value instead
+                    crate::any::type_info::AnyTypeInfoKind::Driver(_) => true,
                 }
             }
         }
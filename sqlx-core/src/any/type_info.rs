@@ -1,5 +1,6 @@
 use std::fmt::{self, Display, Formatter};
 
+use crate::ext::ustr::UStr;
 use crate::type_info::TypeInfo;
 
 #[cfg(feature = "postgres")]
@@ -11,12 +12,23 @@ use crate::mysql::MySqlTypeInfo;
 #[cfg(feature = "sqlite")]
 use crate::sqlite::SqliteTypeInfo;
 
+#[cfg(feature = "duckdb")]
+use crate::duckdb::DuckDbTypeInfo;
+
 #[cfg(feature = "mssql")]
 use crate::mssql::MssqlTypeInfo;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct AnyTypeInfo(pub(crate) AnyTypeInfoKind);
 
+impl AnyTypeInfo {
+    /// The type `name` of an installed driver; see
+    /// [`AnyConnectionBackend`][crate::any::AnyConnectionBackend].
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(AnyTypeInfoKind::Driver(UStr::from(name.into())))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) enum AnyTypeInfoKind {
     #[cfg(feature = "postgres")]
@@ -28,8 +40,13 @@ pub(crate) enum AnyTypeInfoKind {
     #[cfg(feature = "sqlite")]
     Sqlite(SqliteTypeInfo),
 
+    #[cfg(feature = "duckdb")]
+    DuckDb(DuckDbTypeInfo),
+
     #[cfg(feature = "mssql")]
     Mssql(MssqlTypeInfo),
+
+    Driver(UStr),
 }
 
 impl TypeInfo for AnyTypeInfo {
@@ -44,8 +61,13 @@ impl TypeInfo for AnyTypeInfo {
             #[cfg(feature = "sqlite")]
             AnyTypeInfoKind::Sqlite(ty) => ty.is_null(),
 
+            #[cfg(feature = "duckdb")]
+            AnyTypeInfoKind::DuckDb(ty) => ty.is_null(),
+
             #[cfg(feature = "mssql")]
             AnyTypeInfoKind::Mssql(ty) => ty.is_null(),
+
+            AnyTypeInfoKind::Driver(_) => false,
         }
     }
 
@@ -60,8 +82,13 @@ impl TypeInfo for AnyTypeInfo {
             #[cfg(feature = "sqlite")]
             AnyTypeInfoKind::Sqlite(ty) => ty.name(),
 
+            #[cfg(feature = "duckdb")]
+            AnyTypeInfoKind::DuckDb(ty) => ty.name(),
+
             #[cfg(feature = "mssql")]
             AnyTypeInfoKind::Mssql(ty) => ty.name(),
+
+            AnyTypeInfoKind::Driver(name) => name,
         }
     }
 }
@@ -78,8 +105,13 @@ impl Display for AnyTypeInfo {
             #[cfg(feature = "sqlite")]
             AnyTypeInfoKind::Sqlite(ty) => ty.fmt(f),
 
+            #[cfg(feature = "duckdb")]
+            AnyTypeInfoKind::DuckDb(ty) => ty.fmt(f),
+
             #[cfg(feature = "mssql")]
             AnyTypeInfoKind::Mssql(ty) => ty.fmt(f),
+
+            AnyTypeInfoKind::Driver(name) => f.pad(name),
         }
     }
 }
@@ -63,36 +63,66 @@ impl_any_decode!(String);
 // Conversions for Blob SQL types
 // Type
 #[cfg(all(
-    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    any(
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_type!([u8]);
 #[cfg(all(
-    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    any(
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_type!(Vec<u8>);
 
 // Encode
 #[cfg(all(
-    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    any(
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_encode!(&'q [u8]);
 #[cfg(all(
-    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    any(
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_encode!(Vec<u8>);
 
 // Decode
 #[cfg(all(
-    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    any(
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_decode!(&'r [u8]);
 #[cfg(all(
-    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    any(
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_decode!(Vec<u8>);
@@ -101,31 +131,56 @@ impl_any_decode!(Vec<u8>);
 // Type
 #[cfg(all(
     feature = "chrono",
-    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    any(
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_type!(chrono::NaiveDate);
 #[cfg(all(
     feature = "chrono",
-    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    any(
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_type!(chrono::NaiveTime);
 #[cfg(all(
     feature = "chrono",
-    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    any(
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_type!(chrono::NaiveDateTime);
 #[cfg(all(
     feature = "chrono",
-    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    any(
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_type!(chrono::DateTime<chrono::offset::Utc>);
 #[cfg(all(
     feature = "chrono",
-    any(feature = "sqlite", feature = "postgres", feature = "mysql"),
+    any(
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "mysql",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_type!(chrono::DateTime<chrono::offset::Local>);
@@ -133,31 +188,56 @@ impl_any_type!(chrono::DateTime<chrono::offset::Local>);
 // Encode
 #[cfg(all(
     feature = "chrono",
-    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    any(
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_encode!(chrono::NaiveDate);
 #[cfg(all(
     feature = "chrono",
-    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    any(
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_encode!(chrono::NaiveTime);
 #[cfg(all(
     feature = "chrono",
-    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    any(
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_encode!(chrono::NaiveDateTime);
 #[cfg(all(
     feature = "chrono",
-    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    any(
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_encode!(chrono::DateTime<chrono::offset::Utc>);
 #[cfg(all(
     feature = "chrono",
-    any(feature = "sqlite", feature = "postgres", feature = "mysql"),
+    any(
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "mysql",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_encode!(chrono::DateTime<chrono::offset::Local>);
@@ -165,31 +245,56 @@ impl_any_encode!(chrono::DateTime<chrono::offset::Local>);
 // Decode
 #[cfg(all(
     feature = "chrono",
-    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    any(
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_decode!(chrono::NaiveDate);
 #[cfg(all(
     feature = "chrono",
-    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    any(
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_decode!(chrono::NaiveTime);
 #[cfg(all(
     feature = "chrono",
-    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    any(
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_decode!(chrono::NaiveDateTime);
 #[cfg(all(
     feature = "chrono",
-    any(feature = "mysql", feature = "sqlite", feature = "postgres"),
+    any(
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_decode!(chrono::DateTime<chrono::offset::Utc>);
 #[cfg(all(
     feature = "chrono",
-    any(feature = "sqlite", feature = "postgres", feature = "mysql"),
+    any(
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "mysql",
+        feature = "duckdb"
+    ),
     not(feature = "mssql")
 ))]
 impl_any_decode!(chrono::DateTime<chrono::offset::Local>);
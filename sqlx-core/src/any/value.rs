@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
 use crate::any::error::mismatched_types;
-use crate::any::{Any, AnyTypeInfo};
+use crate::any::{Any, AnyData, AnyTypeInfo};
 use crate::database::HasValueRef;
 use crate::decode::Decode;
 use crate::error::Error;
@@ -18,6 +18,9 @@ use crate::mysql::{MySqlValue, MySqlValueRef};
 #[cfg(feature = "sqlite")]
 use crate::sqlite::{SqliteValue, SqliteValueRef};
 
+#[cfg(feature = "duckdb")]
+use crate::duckdb::{DuckDbValue, DuckDbValueRef};
+
 #[cfg(feature = "mssql")]
 use crate::mssql::{MssqlValue, MssqlValueRef};
 
@@ -36,8 +39,13 @@ pub(crate) enum AnyValueKind {
     #[cfg(feature = "sqlite")]
     Sqlite(SqliteValue),
 
+    #[cfg(feature = "duckdb")]
+    DuckDb(DuckDbValue),
+
     #[cfg(feature = "mssql")]
     Mssql(MssqlValue),
+
+    Driver(AnyData),
 }
 
 pub struct AnyValueRef<'r> {
@@ -55,8 +63,13 @@ pub(crate) enum AnyValueRefKind<'r> {
     #[cfg(feature = "sqlite")]
     Sqlite(SqliteValueRef<'r>),
 
+    #[cfg(feature = "duckdb")]
+    DuckDb(DuckDbValueRef<'r>),
+
     #[cfg(feature = "mssql")]
     Mssql(MssqlValueRef<'r>),
+
+    Driver(&'r AnyData),
 }
 
 impl Value for AnyValue {
@@ -73,8 +86,16 @@ impl Value for AnyValue {
             #[cfg(feature = "sqlite")]
             AnyValueKind::Sqlite(value) => value.as_ref().into(),
 
+            #[cfg(feature = "duckdb")]
+            AnyValueKind::DuckDb(value) => value.as_ref().into(),
+
             #[cfg(feature = "mssql")]
             AnyValueKind::Mssql(value) => value.as_ref().into(),
+
+            AnyValueKind::Driver(value) => AnyValueRef {
+                kind: AnyValueRefKind::Driver(value),
+                type_info: self.type_info.clone(),
+            },
         }
     }
 
@@ -93,8 +114,13 @@ impl Value for AnyValue {
             #[cfg(feature = "sqlite")]
             AnyValueKind::Sqlite(value) => value.is_null(),
 
+            #[cfg(feature = "duckdb")]
+            AnyValueKind::DuckDb(value) => value.is_null(),
+
             #[cfg(feature = "mssql")]
             AnyValueKind::Mssql(value) => value.is_null(),
+
+            AnyValueKind::Driver(value) => value.is_null(),
         }
     }
 
@@ -128,8 +154,16 @@ impl<'r> ValueRef<'r> for AnyValueRef<'r> {
             #[cfg(feature = "sqlite")]
             AnyValueRefKind::Sqlite(value) => ValueRef::to_owned(value).into(),
 
+            #[cfg(feature = "duckdb")]
+            AnyValueRefKind::DuckDb(value) => ValueRef::to_owned(value).into(),
+
             #[cfg(feature = "mssql")]
             AnyValueRefKind::Mssql(value) => ValueRef::to_owned(value).into(),
+
+            AnyValueRefKind::Driver(value) => AnyValue {
+                kind: AnyValueKind::Driver((*value).clone()),
+                type_info: self.type_info.clone(),
+            },
         }
     }
 
@@ -148,8 +182,13 @@ impl<'r> ValueRef<'r> for AnyValueRef<'r> {
             #[cfg(feature = "sqlite")]
             AnyValueRefKind::Sqlite(value) => value.is_null(),
 
+            #[cfg(feature = "duckdb")]
+            AnyValueRefKind::DuckDb(value) => value.is_null(),
+
             #[cfg(feature = "mssql")]
             AnyValueRefKind::Mssql(value) => value.is_null(),
+
+            AnyValueRefKind::Driver(value) => value.is_null(),
         }
     }
 }
@@ -0,0 +1,212 @@
+//! A process-wide pool of the byte buffers the network drivers use to read and write the
+//! protocol messages and to encode the arguments of queries.
+//!
+//! A connection takes its read and write buffers from the pool when it's opened and returns
+//! them when it's closed, and the arguments of a query return their buffer when they're dropped,
+//! after the query is sent, so that the following query reuses it instead of allocating a new
+//! one.
+//!
+//! The pool is sized with [`BufferPoolOptions`]:
+//!
+//! ```rust,no_run
+//! # use sqlx_core::buffer_pool::BufferPoolOptions;
+//! BufferPoolOptions::new()
+//!     .max_idle_buffers(256)
+//!     .max_retained_capacity(256 * 1024)
+//!     .install();
+//! ```
+
+use std::sync::{Mutex, RwLock};
+
+use bytes::BytesMut;
+
+/// Configuration options for the pool of buffers; see the [module documentation][self].
+#[derive(Debug, Clone)]
+pub struct BufferPoolOptions {
+    max_idle_buffers: usize,
+    read_buffer_capacity: usize,
+    write_buffer_capacity: usize,
+    max_retained_capacity: usize,
+}
+
+impl BufferPoolOptions {
+    /// The default options: up to 128 idle buffers of each kind are kept, read buffers start
+    /// at 4 KiB and write buffers at 512 bytes, and buffers which grew over 64 KiB are released.
+    pub const fn new() -> Self {
+        Self {
+            max_idle_buffers: 128,
+            read_buffer_capacity: 4096,
+            write_buffer_capacity: 512,
+            max_retained_capacity: 64 * 1024,
+        }
+    }
+
+    /// Set the number of idle buffers of each kind (read and write) kept in the pool.
+    ///
+    /// `0` disables the pool: every buffer is allocated when it's needed and released when
+    /// it's no longer used.
+    pub fn max_idle_buffers(mut self, max: usize) -> Self {
+        self.max_idle_buffers = max;
+        self
+    }
+
+    pub fn get_max_idle_buffers(&self) -> usize {
+        self.max_idle_buffers
+    }
+
+    /// Set the capacity a new read buffer is allocated with.
+    pub fn read_buffer_capacity(mut self, capacity: usize) -> Self {
+        self.read_buffer_capacity = capacity;
+        self
+    }
+
+    pub fn get_read_buffer_capacity(&self) -> usize {
+        self.read_buffer_capacity
+    }
+
+    /// Set the capacity a new write buffer is allocated with.
+    pub fn write_buffer_capacity(mut self, capacity: usize) -> Self {
+        self.write_buffer_capacity = capacity;
+        self
+    }
+
+    pub fn get_write_buffer_capacity(&self) -> usize {
+        self.write_buffer_capacity
+    }
+
+    /// Set the capacity over which a buffer isn't returned to the pool.
+    ///
+    /// A buffer grows to fit the largest message or arguments it held, such as a large
+    /// `BYTEA` argument. Such a buffer is released instead of being kept: a connection's write
+    /// buffer is swapped for one from the pool before its next write, and other buffers are
+    /// released when they're returned.
+    pub fn max_retained_capacity(mut self, capacity: usize) -> Self {
+        self.max_retained_capacity = capacity;
+        self
+    }
+
+    pub fn get_max_retained_capacity(&self) -> usize {
+        self.max_retained_capacity
+    }
+
+    /// Use these options from now on, releasing the idle buffers over the new limits.
+    pub fn install(self) {
+        let mut pool = lock();
+
+        pool.read
+            .retain(|buf| buf.capacity() <= self.max_retained_capacity);
+        pool.read.truncate(self.max_idle_buffers);
+        pool.write
+            .retain(|buf| buf.capacity() <= self.max_retained_capacity);
+        pool.write.truncate(self.max_idle_buffers);
+
+        *OPTIONS.write().unwrap_or_else(|e| e.into_inner()) = self;
+    }
+
+    /// The options in use.
+    pub fn current() -> Self {
+        options()
+    }
+}
+
+impl Default for BufferPoolOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static OPTIONS: RwLock<BufferPoolOptions> = RwLock::new(BufferPoolOptions::new());
+
+static POOL: Mutex<BufferPool> = Mutex::new(BufferPool {
+    read: Vec::new(),
+    write: Vec::new(),
+});
+
+struct BufferPool {
+    read: Vec<BytesMut>,
+    write: Vec<Vec<u8>>,
+}
+
+fn options() -> BufferPoolOptions {
+    OPTIONS.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn lock() -> std::sync::MutexGuard<'static, BufferPool> {
+    // the buffers are valid even if a thread panicked while holding the lock
+    POOL.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Takes an empty read buffer from the pool, or allocates one.
+pub(crate) fn take_read_buf() -> BytesMut {
+    lock()
+        .read
+        .pop()
+        .unwrap_or_else(|| BytesMut::with_capacity(options().read_buffer_capacity))
+}
+
+/// Takes an empty write buffer from the pool, or allocates one.
+pub(crate) fn take_write_buf() -> Vec<u8> {
+    lock()
+        .write
+        .pop()
+        .unwrap_or_else(|| Vec::with_capacity(options().write_buffer_capacity))
+}
+
+/// Returns a read buffer to the pool, unless the pool is full or the buffer is too large.
+pub(crate) fn recycle_read_buf(mut buf: BytesMut) {
+    let options = options();
+
+    // a buffer with no capacity left had all of its storage split off into messages
+    if buf.capacity() == 0 || buf.capacity() > options.max_retained_capacity {
+        return;
+    }
+
+    buf.clear();
+
+    let mut pool = lock();
+
+    if pool.read.len() < options.max_idle_buffers {
+        pool.read.push(buf);
+    }
+}
+
+/// Returns a write buffer to the pool, unless the pool is full or the buffer is too large.
+pub(crate) fn recycle_write_buf(mut buf: Vec<u8>) {
+    let options = options();
+
+    if buf.capacity() == 0 || buf.capacity() > options.max_retained_capacity {
+        return;
+    }
+
+    buf.clear();
+
+    let mut pool = lock();
+
+    if pool.write.len() < options.max_idle_buffers {
+        pool.write.push(buf);
+    }
+}
+
+/// Whether a buffer of `capacity` grew too large to be kept, and should be swapped for a
+/// pooled buffer.
+pub(crate) fn is_oversized(capacity: usize) -> bool {
+    capacity > options().max_retained_capacity
+}
+
+#[test]
+fn test_recycle_write_buf() {
+    let mut buf = take_write_buf();
+    buf.extend_from_slice(b"message");
+    recycle_write_buf(buf);
+
+    // the pool is shared with the other tests, but only holds empty buffers
+    assert!(take_write_buf().is_empty());
+
+    let mut large = Vec::with_capacity(options().max_retained_capacity + 1);
+    large.push(0);
+    assert!(is_oversized(large.capacity()));
+
+    let ptr = large.as_ptr();
+    recycle_write_buf(large);
+    assert!(lock().write.iter().all(|buf| buf.as_ptr() != ptr));
+}
@@ -0,0 +1,330 @@
+//! The configuration of a project, read from a `sqlx.toml` file by `sqlx-cli` and the macros.
+//!
+//! The file is looked for in the directory of the crate (the current directory for `sqlx-cli`)
+//! and then in its ancestors, so a single file at the root of a repository applies to all of its
+//! crates. Every setting is optional, and relative paths are resolved against the directory of
+//! the file:
+//!
+//! ```toml
+//! # the environment variable holding the database URL, instead of `DATABASE_URL`
+//! database-url-var = "APP_DATABASE_URL"
+//!
+//! # where `cargo sqlx prepare` saves the data of queries, and the macros read it from in offline
+//! # mode, instead of `.sqlx` in the directory of each crate
+//! offline-dir = "query-data"
+//!
+//! # connect to the database through an SSH tunnel to this host, with `sqlx-cli`
+//! ssh = "deploy@bastion.example.com:2222"
+//!
+//! [database]
+//! # the options of databases created by `sqlx database create`, instead of the server defaults:
+//! # `owner`, `encoding`, `locale`, `collation` and `template` in Postgres, `charset` and
+//! # `collation` in MySQL
+//! encoding = "UTF8"
+//! locale = "en_US.UTF-8"
+//! template = "template0"
+//!
+//! [migrate]
+//! # the migrations of `sqlx migrate` and `migrate!()`, instead of `migrations`
+//! dir = "db/migrations"
+//! # the table recording applied migrations, instead of `_sqlx_migrations`
+//! table-name = "_app_migrations"
+//!
+//! [seed]
+//! # the seed migrations of `sqlx seed`, instead of `seeds`
+//! dir = "db/seeds"
+//! # the table recording applied seed migrations, instead of `_sqlx_seeds`
+//! table-name = "_app_seeds"
+//!
+//! [prepare]
+//! # the Cargo features enabled when `cargo sqlx prepare` checks the crate
+//! features = ["postgres"]
+//! # check it with `--no-default-features` or `--all-features`
+//! no-default-features = false
+//! all-features = false
+//!
+//! # an environment selected with `sqlx --env staging`
+//! [env.staging]
+//! # the environment variable holding its database URL, instead of `database-url-var`
+//! database-url-var = "STAGING_DATABASE_URL"
+//! # the SSH tunnel to connect through, instead of `ssh`
+//! ssh = "deploy@staging-bastion.example.com"
+//! # require confirmation before dropping the database or reverting migrations
+//! protected = true
+//! ```
+//!
+//! Command-line flags and environment variables such as `SQLX_MIGRATIONS_TABLE` take precedence
+//! over the file.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::Error;
+
+/// The name of the configuration file.
+pub const FILE_NAME: &str = "sqlx.toml";
+
+/// The configuration read from a `sqlx.toml` file, see the [module documentation](self).
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+#[non_exhaustive]
+pub struct Config {
+    /// The environment variable holding the URL of the database.
+    pub database_url_var: Option<String>,
+
+    /// The directory query data is saved in by `cargo sqlx prepare`, and read from by the macros
+    /// in offline mode.
+    pub offline_dir: Option<PathBuf>,
+
+    /// The host `sqlx-cli` connects to the database through with an SSH tunnel, as
+    /// `user@host[:port]`.
+    pub ssh: Option<String>,
+
+    /// The options of databases created by `sqlx-cli`.
+    pub database: DatabaseConfig,
+
+    /// The settings of migrations.
+    pub migrate: MigrateConfig,
+
+    /// The settings of seed migrations.
+    pub seed: MigrateConfig,
+
+    /// The settings of `cargo sqlx prepare`.
+    pub prepare: PrepareConfig,
+
+    /// The environments `sqlx-cli` can be run against, by name.
+    pub env: BTreeMap<String, EnvConfig>,
+
+    /// The directory of the file, which relative paths are resolved against.
+    #[serde(skip)]
+    root: PathBuf,
+}
+
+/// The options of databases created by `sqlx-cli`, instead of the defaults of the server.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+#[non_exhaustive]
+pub struct DatabaseConfig {
+    /// The role owning the database (Postgres).
+    pub owner: Option<String>,
+
+    /// The character encoding (Postgres).
+    pub encoding: Option<String>,
+
+    /// The locale, setting the collation and character classification (Postgres).
+    pub locale: Option<String>,
+
+    /// The collation (Postgres, MySQL).
+    pub collation: Option<String>,
+
+    /// The database to copy (Postgres).
+    pub template: Option<String>,
+
+    /// The default character set (MySQL).
+    pub charset: Option<String>,
+}
+
+/// The settings of migrations, or seed migrations.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+#[non_exhaustive]
+pub struct MigrateConfig {
+    /// The directory of the migrations.
+    pub dir: Option<PathBuf>,
+
+    /// The name of the table recording applied migrations.
+    pub table_name: Option<String>,
+}
+
+/// The settings of `cargo sqlx prepare`.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+#[non_exhaustive]
+pub struct PrepareConfig {
+    /// The Cargo features to enable when checking the crate.
+    pub features: Vec<String>,
+
+    /// Whether to check the crate without its default features.
+    pub no_default_features: bool,
+
+    /// Whether to check the crate with all of its features.
+    pub all_features: bool,
+}
+
+/// The settings of an environment.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+#[non_exhaustive]
+pub struct EnvConfig {
+    /// The environment variable holding the URL of its database.
+    pub database_url_var: Option<String>,
+
+    /// The host to connect to its database through with an SSH tunnel.
+    pub ssh: Option<String>,
+
+    /// Whether destructive commands must be confirmed, e.g. for production.
+    pub protected: bool,
+}
+
+impl Config {
+    /// Read the configuration from the `sqlx.toml` file in `dir` or the closest of its ancestors,
+    /// if there is one.
+    pub fn find(dir: &Path) -> Result<Option<Config>, Error> {
+        for dir in dir.ancestors() {
+            let path = dir.join(FILE_NAME);
+
+            if path.is_file() {
+                return Config::read(&path).map(Some);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Read the configuration from the file at `path`.
+    pub fn read(path: &Path) -> Result<Config, Error> {
+        let contents = fs::read_to_string(path).map_err(|e| {
+            Error::Configuration(format!("failed to read {}: {}", path.display(), e).into())
+        })?;
+
+        let mut config = Config::parse(&contents).map_err(|e| {
+            Error::Configuration(format!("failed to parse {}: {}", path.display(), e).into())
+        })?;
+
+        config.root = path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+        Ok(config)
+    }
+
+    fn parse(contents: &str) -> Result<Config, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    /// The directory of the file, which relative paths are resolved against.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Resolve `path` against the directory of the file.
+    pub fn resolve(&self, path: &Path) -> PathBuf {
+        self.root.join(path)
+    }
+
+    /// The environment variable holding the URL of the database, `DATABASE_URL` by default.
+    pub fn database_url_var(&self) -> &str {
+        self.database_url_var.as_deref().unwrap_or("DATABASE_URL")
+    }
+
+    /// The environment variable holding the URL of the database of the environment `name`,
+    /// falling back to [`database_url_var()`](Self::database_url_var).
+    pub fn env_database_url_var(&self, name: &str) -> Result<&str, Error> {
+        let env = self.env(name)?;
+
+        Ok(env
+            .database_url_var
+            .as_deref()
+            .unwrap_or_else(|| self.database_url_var()))
+    }
+
+    /// The settings of the environment `name`.
+    pub fn env(&self, name: &str) -> Result<&EnvConfig, Error> {
+        self.env.get(name).ok_or_else(|| {
+            Error::Configuration(format!("unknown environment `{}` in {}", name, FILE_NAME).into())
+        })
+    }
+
+    /// The directory query data is saved in and read from, if set.
+    pub fn offline_dir(&self) -> Option<PathBuf> {
+        self.offline_dir.as_deref().map(|dir| self.resolve(dir))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_config() {
+        let config = Config::parse(
+            r#"
+database-url-var = "APP_DATABASE_URL"
+offline-dir = "query-data"
+
+[migrate]
+dir = "db/migrations"
+table-name = "_app_migrations"
+
+[database]
+encoding = "UTF8"
+template = "template0"
+
+[prepare]
+features = ["postgres"]
+no-default-features = true
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.database_url_var(), "APP_DATABASE_URL");
+        assert_eq!(config.offline_dir(), Some(PathBuf::from("query-data")));
+        assert_eq!(config.migrate.dir, Some(PathBuf::from("db/migrations")));
+        assert_eq!(
+            config.migrate.table_name.as_deref(),
+            Some("_app_migrations")
+        );
+        assert_eq!(config.seed.dir, None);
+        assert_eq!(config.database.encoding.as_deref(), Some("UTF8"));
+        assert_eq!(config.database.owner, None);
+        assert_eq!(config.prepare.features, ["postgres"]);
+        assert!(config.prepare.no_default_features);
+        assert!(!config.prepare.all_features);
+    }
+
+    #[test]
+    fn it_defaults_to_database_url() {
+        let config = Config::parse("").unwrap();
+
+        assert_eq!(config.database_url_var(), "DATABASE_URL");
+        assert_eq!(config.offline_dir(), None);
+    }
+
+    #[test]
+    fn it_parses_environments() {
+        let config = Config::parse(
+            r#"
+database-url-var = "APP_DATABASE_URL"
+
+[env.staging]
+database-url-var = "STAGING_DATABASE_URL"
+
+[env.production]
+ssh = "deploy@bastion:2222"
+protected = true
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.env_database_url_var("staging").unwrap(),
+            "STAGING_DATABASE_URL"
+        );
+        assert!(!config.env("staging").unwrap().protected);
+        assert_eq!(
+            config.env_database_url_var("production").unwrap(),
+            "APP_DATABASE_URL"
+        );
+        assert!(config.env("production").unwrap().protected);
+        assert_eq!(
+            config.env("production").unwrap().ssh.as_deref(),
+            Some("deploy@bastion:2222")
+        );
+        assert!(config.env("dev").is_err());
+    }
+
+    #[test]
+    fn it_rejects_unknown_settings() {
+        assert!(Config::parse("database-url = \"postgres://\"").is_err());
+        assert!(Config::parse("[migrate]\ndirs = []").is_err());
+    }
+}
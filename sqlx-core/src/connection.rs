@@ -1,10 +1,12 @@
 use crate::database::{Database, HasStatementCache};
 use crate::error::Error;
-use crate::transaction::Transaction;
+use crate::query_log::QueryLog;
+use crate::transaction::{Transaction, TransactionOptions};
 use futures_core::future::BoxFuture;
 use log::LevelFilter;
-use std::fmt::Debug;
+use std::fmt::{self, Debug, Display, Formatter};
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 
 /// Represents a single database connection.
@@ -29,6 +31,19 @@ pub trait Connection: Send {
     /// Checks if a connection to the database is still valid.
     fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>>;
 
+    /// The depth of the transaction the connection is in: `0` outside of a transaction, `1` in
+    /// a transaction, `2` in a savepoint of it, and so on.
+    #[doc(hidden)]
+    fn transaction_depth(&self) -> usize {
+        0
+    }
+
+    /// The number of statements the connection executed since it was opened.
+    #[doc(hidden)]
+    fn statement_count(&self) -> u64 {
+        0
+    }
+
     /// Begin a new transaction or establish a savepoint within the active transaction.
     ///
     /// Returns a [`Transaction`] for controlling and tracking the new transaction.
@@ -36,6 +51,17 @@ pub trait Connection: Send {
     where
         Self: Sized;
 
+    /// Begin a new transaction with `options`, e.g. its isolation level, rather than setting
+    /// them with a statement once it began.
+    ///
+    /// Fails within an active transaction, since a savepoint has the options of its transaction.
+    fn begin_with(
+        &mut self,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized;
+
     /// Execute the function inside a transaction.
     ///
     /// If the function returns an error, the transaction will be rolled back. If it does not
@@ -102,6 +128,30 @@ pub trait Connection: Send {
         Box::pin(async move { Ok(()) })
     }
 
+    /// The version of the server, as reported when connecting; `None` if it couldn't be parsed.
+    ///
+    /// For SQLite, this is the version of the library.
+    fn server_version(&self) -> Option<ServerVersion> {
+        None
+    }
+
+    /// The features supported by the server, derived from its [version](Self::server_version),
+    /// so that a library can adapt its queries without parsing `SELECT version()` itself.
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::connection::Connection;
+    /// # fn f(conn: &impl Connection) {
+    /// let sql = if conn.server_capabilities().returning() {
+    ///     "INSERT INTO users (name) VALUES (?) RETURNING id"
+    /// } else {
+    ///     "INSERT INTO users (name) VALUES (?)"
+    /// };
+    /// # }
+    /// ```
+    fn server_capabilities(&self) -> ServerCapabilities {
+        ServerCapabilities::default()
+    }
+
     #[doc(hidden)]
     fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>>;
 
@@ -131,11 +181,98 @@ pub trait Connection: Send {
     }
 }
 
+/// The version of a database server, e.g. `8.0.28` for MySQL 8.0.28, or `14.5.0` for Postgres 14.5.
+///
+/// Versions are ordered, so they can be compared to the version introducing a feature:
+///
+/// ```rust
+/// # use sqlx_core::connection::ServerVersion;
+/// let version = ServerVersion::new(14, 5, 0);
+///
+/// assert!(version >= ServerVersion::new(14, 0, 0));
+/// assert_eq!(version.to_string(), "14.5.0");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ServerVersion {
+    major: u32,
+    minor: u32,
+    patch: u32,
+}
+
+impl ServerVersion {
+    pub fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    pub fn major(&self) -> u32 {
+        self.major
+    }
+
+    pub fn minor(&self) -> u32 {
+        self.minor
+    }
+
+    pub fn patch(&self) -> u32 {
+        self.patch
+    }
+}
+
+impl Display for ServerVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// The features supported by a database server; see [`Connection::server_capabilities()`].
+///
+/// A feature is reported as unsupported when the version of the server is unknown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ServerCapabilities {
+    pub(crate) returning: bool,
+    pub(crate) json: bool,
+    pub(crate) common_table_expressions: bool,
+    pub(crate) multiranges: bool,
+}
+
+impl ServerCapabilities {
+    /// Whether `INSERT`, `UPDATE` and `DELETE` statements can return rows with a `RETURNING`
+    /// clause: Postgres, MariaDB 10.5 and SQLite 3.35.0 or later.
+    ///
+    /// MySQL doesn't support it; MSSQL has a different `OUTPUT` clause.
+    pub fn returning(&self) -> bool {
+        self.returning
+    }
+
+    /// Whether the server has a JSON type or JSON functions: Postgres 9.4 (`jsonb`), MySQL
+    /// 5.7.8, MariaDB 10.2.7, SQLite 3.38.0 and MSSQL 2016 or later.
+    pub fn json(&self) -> bool {
+        self.json
+    }
+
+    /// Whether queries can use common table expressions (`WITH`): Postgres, MySQL 8.0,
+    /// MariaDB 10.2.1, SQLite 3.8.3 and MSSQL.
+    pub fn common_table_expressions(&self) -> bool {
+        self.common_table_expressions
+    }
+
+    /// Whether the server has multirange types: Postgres 14 or later.
+    pub fn multiranges(&self) -> bool {
+        self.multiranges
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct LogSettings {
     pub(crate) statements_level: LevelFilter,
     pub(crate) slow_statements_level: LevelFilter,
     pub(crate) slow_statements_duration: Duration,
+    pub(crate) query_log: Option<Arc<dyn QueryLog>>,
+    // the identifier of the connection on the server, set once it's established
+    pub(crate) connection_id: Option<u64>,
 }
 
 impl Default for LogSettings {
@@ -144,6 +281,8 @@ impl Default for LogSettings {
             statements_level: LevelFilter::Info,
             slow_statements_level: LevelFilter::Warn,
             slow_statements_duration: Duration::from_secs(1),
+            query_log: None,
+            connection_id: None,
         }
     }
 }
@@ -156,6 +295,15 @@ impl LogSettings {
         self.slow_statements_level = level;
         self.slow_statements_duration = duration;
     }
+    pub(crate) fn query_log(&mut self, log: Arc<dyn QueryLog>) {
+        self.query_log = Some(log);
+    }
+    pub(crate) fn for_connection(&self, id: u64) -> Self {
+        LogSettings {
+            connection_id: Some(id),
+            ..self.clone()
+        }
+    }
 }
 
 pub trait ConnectOptions: 'static + Send + Sync + FromStr<Err = Error> + Debug + Clone {
@@ -173,9 +321,27 @@ pub trait ConnectOptions: 'static + Send + Sync + FromStr<Err = Error> + Debug +
     /// at the specified `level`.
     fn log_slow_statements(&mut self, level: LevelFilter, duration: Duration) -> &mut Self;
 
+    /// Sends the executed statements to `log` as records, instead of logging them as text;
+    /// see [`crate::query_log`].
+    fn query_log(&mut self, log: Arc<dyn QueryLog>) -> &mut Self;
+
     /// Entirely disables statement logging (both slow and regular).
     fn disable_statement_logging(&mut self) -> &mut Self {
         self.log_statements(LevelFilter::Off)
             .log_slow_statements(LevelFilter::Off, Duration::default())
     }
+
+    /// Sets the name of the application, sent to the server when connecting so that the
+    /// connections (and their queries) can be attributed to it in server-side monitoring.
+    ///
+    /// | Driver | Reported as |
+    /// |--------|-------------|
+    /// | Postgres | the `application_name` parameter, e.g. in `pg_stat_activity` |
+    /// | MySQL | the `program_name` connection attribute, e.g. in `performance_schema.session_connect_attrs` |
+    /// | MSSQL | the application name of the login, e.g. `program_name` in `sys.dm_exec_sessions` |
+    /// | SQLite | ignored |
+    fn application_name(&mut self, name: &str) -> &mut Self {
+        let _ = name;
+        self
+    }
 }
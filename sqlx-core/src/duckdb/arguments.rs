@@ -0,0 +1,146 @@
+use std::borrow::Cow;
+
+use crate::arguments::Arguments;
+use crate::duckdb::connection::StatementHandle;
+use crate::duckdb::DuckDb;
+use crate::encode::{Encode, IsNull};
+use crate::error::Error;
+
+#[derive(Debug, Clone)]
+pub enum DuckDbArgumentValue<'q> {
+    Null,
+    Boolean(bool),
+    TinyInt(i8),
+    SmallInt(i16),
+    Integer(i32),
+    BigInt(i64),
+    UTinyInt(u8),
+    USmallInt(u16),
+    UInteger(u32),
+    UBigInt(u64),
+    Float(f32),
+    Double(f64),
+    Text(Cow<'q, str>),
+    Blob(Cow<'q, [u8]>),
+    /// Days since 1970-01-01.
+    Date(i32),
+    /// Microseconds since midnight.
+    Time(i64),
+    /// Microseconds since 1970-01-01 00:00:00.
+    Timestamp(i64),
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct DuckDbArguments<'q> {
+    pub(crate) values: Vec<DuckDbArgumentValue<'q>>,
+}
+
+impl<'q> DuckDbArguments<'q> {
+    pub(crate) fn add<T>(&mut self, value: T)
+    where
+        T: Encode<'q, DuckDb>,
+    {
+        if let IsNull::Yes = value.encode(&mut self.values) {
+            self.values.push(DuckDbArgumentValue::Null);
+        }
+    }
+
+    pub(crate) fn into_static(self) -> DuckDbArguments<'static> {
+        DuckDbArguments {
+            values: self
+                .values
+                .into_iter()
+                .map(DuckDbArgumentValue::into_static)
+                .collect(),
+        }
+    }
+}
+
+impl<'q> Arguments<'q> for DuckDbArguments<'q> {
+    type Database = DuckDb;
+
+    fn reserve(&mut self, len: usize, _size_hint: usize) {
+        self.values.reserve(len);
+    }
+
+    fn add<T>(&mut self, value: T)
+    where
+        T: Encode<'q, Self::Database>,
+    {
+        self.add(value)
+    }
+}
+
+impl DuckDbArguments<'_> {
+    /// Binds the arguments from `offset` to the parameters of the statement, and returns the
+    /// number of parameters.
+    ///
+    /// DuckDB numbers the parameters of a statement from 1, whether they're written `?` or `$N`,
+    /// so the `N`th parameter takes the `N`th argument after the ones taken by the previous
+    /// statements of the query.
+    pub(super) fn bind(&self, handle: &mut StatementHandle, offset: usize) -> Result<usize, Error> {
+        let cnt = handle.parameter_count();
+
+        for param_i in 1..=cnt {
+            // unbound parameters are an error when the statement is executed
+            let value = match self.values.get(offset + param_i - 1) {
+                Some(value) => value,
+                None => break,
+            };
+
+            value.bind(handle, param_i)?;
+        }
+
+        Ok(cnt)
+    }
+}
+
+impl DuckDbArgumentValue<'_> {
+    fn into_static(self) -> DuckDbArgumentValue<'static> {
+        use DuckDbArgumentValue::*;
+
+        match self {
+            Null => Null,
+            Boolean(v) => Boolean(v),
+            TinyInt(v) => TinyInt(v),
+            SmallInt(v) => SmallInt(v),
+            Integer(v) => Integer(v),
+            BigInt(v) => BigInt(v),
+            UTinyInt(v) => UTinyInt(v),
+            USmallInt(v) => USmallInt(v),
+            UInteger(v) => UInteger(v),
+            UBigInt(v) => UBigInt(v),
+            Float(v) => Float(v),
+            Double(v) => Double(v),
+            Text(text) => Text(text.into_owned().into()),
+            Blob(blob) => Blob(blob.into_owned().into()),
+            Date(v) => Date(v),
+            Time(v) => Time(v),
+            Timestamp(v) => Timestamp(v),
+        }
+    }
+
+    fn bind(&self, handle: &mut StatementHandle, i: usize) -> Result<(), Error> {
+        use DuckDbArgumentValue::*;
+
+        match self {
+            Null => handle.bind_null(i),
+            Boolean(v) => handle.bind_boolean(i, *v),
+            TinyInt(v) => handle.bind_int8(i, *v),
+            SmallInt(v) => handle.bind_int16(i, *v),
+            Integer(v) => handle.bind_int32(i, *v),
+            BigInt(v) => handle.bind_int64(i, *v),
+            UTinyInt(v) => handle.bind_uint8(i, *v),
+            USmallInt(v) => handle.bind_uint16(i, *v),
+            UInteger(v) => handle.bind_uint32(i, *v),
+            UBigInt(v) => handle.bind_uint64(i, *v),
+            Float(v) => handle.bind_float(i, *v),
+            Double(v) => handle.bind_double(i, *v),
+            Text(v) => handle.bind_text(i, v),
+            Blob(v) => handle.bind_blob(i, v),
+            Date(v) => handle.bind_date(i, *v),
+            Time(v) => handle.bind_time(i, *v),
+            Timestamp(v) => handle.bind_timestamp(i, *v),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use crate::column::Column;
+use crate::duckdb::{DuckDb, DuckDbTypeInfo};
+use crate::ext::ustr::UStr;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "offline", derive(serde::Serialize, serde::Deserialize))]
+pub struct DuckDbColumn {
+    pub(crate) name: UStr,
+    pub(crate) ordinal: usize,
+    pub(crate) type_info: DuckDbTypeInfo,
+}
+
+impl crate::column::private_column::Sealed for DuckDbColumn {}
+
+impl Column for DuckDbColumn {
+    type Database = DuckDb;
+
+    fn ordinal(&self) -> usize {
+        self.ordinal
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn type_info(&self) -> &DuckDbTypeInfo {
+        &self.type_info
+    }
+}
+
+#[cfg(feature = "any")]
+impl From<DuckDbColumn> for crate::any::AnyColumn {
+    #[inline]
+    fn from(column: DuckDbColumn) -> Self {
+        crate::any::AnyColumn {
+            type_info: column.type_info.clone().into(),
+            kind: crate::any::column::AnyColumnKind::DuckDb(column),
+        }
+    }
+}
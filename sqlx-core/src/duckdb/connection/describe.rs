@@ -0,0 +1,84 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use either::Either;
+
+use crate::describe::Describe;
+use crate::duckdb::connection::handle::StatementHandle;
+use crate::duckdb::connection::ConnectionState;
+use crate::duckdb::type_info::DataType;
+use crate::duckdb::{DuckDb, DuckDbColumn, DuckDbStatement, DuckDbTypeInfo};
+use crate::error::Error;
+use crate::ext::ustr::UStr;
+use crate::HashMap;
+
+pub(crate) fn describe(conn: &mut ConnectionState, query: &str) -> Result<Describe<DuckDb>, Error> {
+    let statement = prepare(conn, query)?;
+
+    Ok(Describe {
+        // DuckDB doesn't tell whether the columns of a result are nullable
+        nullable: vec![None; statement.columns.len()],
+        columns: statement.columns.to_vec(),
+        parameters: Some(Either::Right(statement.parameters)),
+    })
+}
+
+pub(crate) fn prepare(
+    conn: &mut ConnectionState,
+    query: &str,
+) -> Result<DuckDbStatement<'static>, Error> {
+    let extracted = conn.handle.extract(query)?;
+
+    let mut parameters = 0;
+    let mut columns = None;
+
+    // note: a statement which depends on a previous one of the query (e.g. on a table it
+    // creates) can't be prepared before the previous one is executed
+    for index in 0..extracted.count {
+        let statement = conn.handle.prepare_extracted(&extracted, index)?;
+        parameters += statement.parameter_count();
+
+        // the first statement with columns is chosen as the statement we pull columns from
+        if columns.is_none() {
+            columns = statement_columns(&statement);
+        }
+    }
+
+    let columns = columns.unwrap_or_default();
+    let column_names = columns
+        .iter()
+        .map(|column| (column.name.clone(), column.ordinal))
+        .collect::<HashMap<UStr, usize>>();
+
+    Ok(DuckDbStatement {
+        sql: Cow::Owned(query.to_string()),
+        columns: Arc::new(columns),
+        column_names: Arc::new(column_names),
+        parameters,
+    })
+}
+
+fn statement_columns(statement: &StatementHandle) -> Option<Vec<DuckDbColumn>> {
+    let count = statement.column_count();
+
+    // the statements which don't return rows have a single `Count` column, for the number of
+    // changed rows
+    if count == 0
+        || (!statement.is_select()
+            && count == 1
+            && statement.column_name(0) == "Count"
+            && DataType::from_code(statement.column_type(0)) == DataType::BigInt)
+    {
+        return None;
+    }
+
+    Some(
+        (0..count)
+            .map(|ordinal| DuckDbColumn {
+                name: UStr::from(statement.column_name(ordinal)),
+                ordinal,
+                type_info: DuckDbTypeInfo(DataType::from_code(statement.column_type(ordinal))),
+            })
+            .collect(),
+    )
+}
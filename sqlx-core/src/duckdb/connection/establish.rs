@@ -0,0 +1,52 @@
+use std::ffi::CString;
+use std::sync::{Arc, Mutex};
+
+use crate::duckdb::connection::handle::{ConnectionHandle, DatabaseHandle};
+use crate::duckdb::DuckDbConnectOptions;
+use crate::error::Error;
+
+/// The database instance shared by the clones of the options which opened it, e.g. by a pool,
+/// as a database file can only be opened once in a process.
+#[derive(Default)]
+pub(crate) struct SharedDatabase(Mutex<Option<Arc<DatabaseHandle>>>);
+
+impl SharedDatabase {
+    /// Connects to the database, opening it if it isn't already open.
+    pub(crate) fn connect(
+        &self,
+        options: &DuckDbConnectOptions,
+    ) -> Result<ConnectionHandle, Error> {
+        // a panic can't poison the lock while it holds the database
+        let mut database = self.0.lock().unwrap_or_else(|e| e.into_inner());
+
+        let database = match &*database {
+            Some(database) => Arc::clone(database),
+            None => database.insert(Arc::new(open(options)?)).clone(),
+        };
+
+        ConnectionHandle::connect(database)
+    }
+}
+
+fn open(options: &DuckDbConnectOptions) -> Result<DatabaseHandle, Error> {
+    let filename = options
+        .filename
+        .as_ref()
+        .map(|filename| {
+            CString::new(filename.to_string_lossy().as_bytes()).map_err(|_| {
+                Error::Configuration("filename passed to DuckDB must not contain nul bytes".into())
+            })
+        })
+        .transpose()?;
+
+    DatabaseHandle::open(
+        filename.as_deref(),
+        options.config.iter().map(|(key, value)| (&**key, &**value)),
+    )
+}
+
+impl std::fmt::Debug for SharedDatabase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedDatabase").finish_non_exhaustive()
+    }
+}
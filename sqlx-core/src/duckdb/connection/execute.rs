@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use either::Either;
+
+use crate::duckdb::connection::handle::{ResultHandle, StatementHandle};
+use crate::duckdb::connection::ConnectionState;
+use crate::duckdb::ffi::idx_t;
+use crate::duckdb::type_info::DataType;
+use crate::duckdb::{
+    DuckDbArguments, DuckDbColumn, DuckDbQueryResult, DuckDbRow, DuckDbTypeInfo, DuckDbValue,
+};
+use crate::error::Error;
+use crate::ext::ustr::UStr;
+use crate::logger::QueryLogger;
+use crate::HashMap;
+
+type ResultSender = flume::Sender<Result<Either<DuckDbQueryResult, DuckDbRow>, Error>>;
+
+/// Executes the statements of a query one after the other, sending their rows and results.
+///
+/// The statements are prepared one at a time, as they may depend on the previous ones (e.g. an
+/// `INSERT` into a table created by the previous statement). Only the queries with a single
+/// statement are cached.
+pub(crate) fn execute(
+    conn: &mut ConnectionState,
+    query: &str,
+    arguments: Option<DuckDbArguments<'_>>,
+    persistent: bool,
+    tx: &ResultSender,
+) -> Result<(), Error> {
+    let mut logger = QueryLogger::new(query, conn.log_settings.clone());
+
+    let result = execute_statements(conn, query, arguments, persistent, &mut logger, tx);
+
+    if let Err(error) = &result {
+        logger.fail(error);
+    }
+
+    result
+}
+
+fn execute_statements(
+    conn: &mut ConnectionState,
+    query: &str,
+    arguments: Option<DuckDbArguments<'_>>,
+    persistent: bool,
+    logger: &mut QueryLogger<'_>,
+    tx: &ResultSender,
+) -> Result<(), Error> {
+    let persistent = persistent && conn.statements.is_enabled();
+
+    if persistent {
+        if let Some(statement) = conn.statements.get_mut(query) {
+            // as this statement has been executed before, we clear its arguments first
+            statement.clear_bindings()?;
+            run(statement, arguments.as_ref(), 0, logger, tx)?;
+
+            return Ok(());
+        }
+    }
+
+    let extracted = conn.handle.extract(query)?;
+    let mut offset = 0;
+
+    for index in 0..extracted.count {
+        let mut statement = conn.handle.prepare_extracted(&extracted, index)?;
+
+        offset = match run(&mut statement, arguments.as_ref(), offset, logger, tx)? {
+            Some(parameters) => offset + parameters,
+            // the receiver hung up
+            None => return Ok(()),
+        };
+
+        if persistent && extracted.count == 1 {
+            conn.statements.insert(query, statement);
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs one statement, and returns the number of its parameters, or `None` if the receiver
+/// hung up.
+fn run(
+    statement: &mut StatementHandle,
+    arguments: Option<&DuckDbArguments<'_>>,
+    offset: usize,
+    logger: &mut QueryLogger<'_>,
+    tx: &ResultSender,
+) -> Result<Option<usize>, Error> {
+    let parameters = match arguments {
+        Some(arguments) => arguments.bind(statement, offset)?,
+        None => statement.parameter_count(),
+    };
+
+    let mut result = statement.execute()?;
+
+    if result.is_query_result() {
+        let (columns, column_names) = result_columns(&mut result);
+
+        for row in 0..result.row_count() {
+            let values = columns
+                .iter()
+                .map(|column| unsafe {
+                    // https://duckdb.org/docs/api/c/value
+                    DuckDbValue::read(
+                        result.as_mut_ptr(),
+                        column.ordinal as idx_t,
+                        row as idx_t,
+                        column.type_info.clone(),
+                    )
+                })
+                .collect();
+
+            logger.increment_rows_returned();
+
+            let row = DuckDbRow {
+                values,
+                columns: Arc::clone(&columns),
+                column_names: Arc::clone(&column_names),
+            };
+
+            if tx.send(Ok(Either::Right(row))).is_err() {
+                return Ok(None);
+            }
+        }
+    }
+
+    let rows_affected = if result.is_changed_rows() {
+        result.rows_changed()
+    } else {
+        0
+    };
+
+    logger.increase_rows_affected(rows_affected);
+
+    if tx
+        .send(Ok(Either::Left(DuckDbQueryResult { rows_affected })))
+        .is_err()
+    {
+        return Ok(None);
+    }
+
+    Ok(Some(parameters))
+}
+
+#[allow(clippy::rc_buffer)]
+fn result_columns(
+    result: &mut ResultHandle,
+) -> (Arc<Vec<DuckDbColumn>>, Arc<HashMap<UStr, usize>>) {
+    let count = result.column_count();
+    let mut columns = Vec::with_capacity(count);
+    let mut column_names = HashMap::with_capacity(count);
+
+    for ordinal in 0..count {
+        let name = UStr::from(result.column_name(ordinal));
+
+        columns.push(DuckDbColumn {
+            name: name.clone(),
+            ordinal,
+            type_info: DuckDbTypeInfo(DataType::from_code(result.column_type(ordinal))),
+        });
+
+        column_names.insert(name, ordinal);
+    }
+
+    (Arc::new(columns), Arc::new(column_names))
+}
@@ -0,0 +1,415 @@
+use std::ffi::{CStr, CString};
+use std::os::raw::c_void;
+use std::ptr;
+use std::sync::Arc;
+
+use crate::duckdb::ffi::*;
+use crate::duckdb::DuckDbError;
+use crate::error::Error;
+
+/// An open `duckdb_database`, shared by the connections to it.
+#[derive(Debug)]
+pub(crate) struct DatabaseHandle(duckdb_database);
+
+// A DuckDB database instance can be used from multiple threads.
+unsafe impl Send for DatabaseHandle {}
+unsafe impl Sync for DatabaseHandle {}
+
+impl DatabaseHandle {
+    /// Opens the database at `path`, or an in-memory database, with the configuration options.
+    pub(crate) fn open<'a>(
+        path: Option<&CStr>,
+        config: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<Self, Error> {
+        let mut raw_config: duckdb_config = ptr::null_mut();
+
+        // https://duckdb.org/docs/api/c/config
+        if unsafe { duckdb_create_config(&mut raw_config) } != DUCKDB_SUCCESS {
+            return Err(Error::Database(Box::new(DuckDbError::new(
+                "failed to create the database configuration",
+            ))));
+        }
+
+        // destroys the configuration when returning, including on an error
+        let config_guard = ConfigGuard(raw_config);
+
+        for (key, value) in config {
+            let c_key = CString::new(key).map_err(Error::config)?;
+            let c_value = CString::new(value).map_err(Error::config)?;
+
+            let status =
+                unsafe { duckdb_set_config(config_guard.0, c_key.as_ptr(), c_value.as_ptr()) };
+
+            if status != DUCKDB_SUCCESS {
+                return Err(Error::Configuration(
+                    format!("invalid configuration option `{}={}`", key, value).into(),
+                ));
+            }
+        }
+
+        let mut handle: duckdb_database = ptr::null_mut();
+        let mut error = ptr::null_mut();
+
+        // https://duckdb.org/docs/api/c/api#duckdb_open_ext
+        let status = unsafe {
+            duckdb_open_ext(
+                path.map_or(ptr::null(), CStr::as_ptr),
+                &mut handle,
+                config_guard.0,
+                &mut error,
+            )
+        };
+
+        if status != DUCKDB_SUCCESS {
+            let err = unsafe { DuckDbError::from_ptr(error, "failed to open the database") };
+            unsafe { duckdb_free(error.cast()) };
+
+            return Err(Error::Database(Box::new(err)));
+        }
+
+        Ok(Self(handle))
+    }
+}
+
+impl Drop for DatabaseHandle {
+    fn drop(&mut self) {
+        // the connections have their own reference to the database instance, so this only
+        // closes the database once they're all disconnected
+        unsafe { duckdb_close(&mut self.0) }
+    }
+}
+
+struct ConfigGuard(duckdb_config);
+
+impl Drop for ConfigGuard {
+    fn drop(&mut self) {
+        unsafe { duckdb_destroy_config(&mut self.0) }
+    }
+}
+
+/// A `duckdb_connection`, used by the worker thread only.
+pub(crate) struct ConnectionHandle {
+    raw: duckdb_connection,
+    // dropped after the connection is closed
+    _database: Arc<DatabaseHandle>,
+}
+
+// A connection is only used by one thread at a time; it's moved to the worker thread.
+unsafe impl Send for ConnectionHandle {}
+
+impl ConnectionHandle {
+    pub(crate) fn connect(database: Arc<DatabaseHandle>) -> Result<Self, Error> {
+        let mut raw: duckdb_connection = ptr::null_mut();
+
+        if unsafe { duckdb_connect(database.0, &mut raw) } != DUCKDB_SUCCESS {
+            return Err(Error::Database(Box::new(DuckDbError::new(
+                "failed to connect to the database",
+            ))));
+        }
+
+        Ok(Self {
+            raw,
+            _database: database,
+        })
+    }
+
+    /// Executes a query without arguments and ignores its result.
+    pub(crate) fn exec(&mut self, query: impl Into<String>) -> Result<(), Error> {
+        let query =
+            CString::new(query.into()).map_err(|_| err_protocol!("query contains nul bytes"))?;
+        let mut result = ResultHandle(duckdb_result::empty());
+
+        // https://duckdb.org/docs/api/c/query#duckdb_query
+        let status = unsafe { duckdb_query(self.raw, query.as_ptr(), &mut result.0) };
+
+        if status != DUCKDB_SUCCESS {
+            return Err(result.error());
+        }
+
+        Ok(())
+    }
+
+    /// Splits a query into its statements, which can then be prepared one by one.
+    pub(crate) fn extract(&mut self, query: &str) -> Result<ExtractedStatements, Error> {
+        let query = CString::new(query).map_err(|_| err_protocol!("query contains nul bytes"))?;
+        let mut raw: duckdb_extracted_statements = ptr::null_mut();
+
+        // https://duckdb.org/docs/api/c/prepared#duckdb_extract_statements
+        let count = unsafe { duckdb_extract_statements(self.raw, query.as_ptr(), &mut raw) };
+        let extracted = ExtractedStatements {
+            raw,
+            count: count as usize,
+        };
+
+        if count == 0 {
+            // an empty query has no statements, which isn't an error
+            let message = unsafe { duckdb_extract_statements_error(raw) };
+
+            if !message.is_null() {
+                return Err(Error::Database(Box::new(unsafe {
+                    DuckDbError::from_ptr(message, "")
+                })));
+            }
+        }
+
+        Ok(extracted)
+    }
+
+    pub(crate) fn prepare_extracted(
+        &mut self,
+        extracted: &ExtractedStatements,
+        index: usize,
+    ) -> Result<StatementHandle, Error> {
+        let mut handle = StatementHandle(ptr::null_mut());
+
+        let status = unsafe {
+            duckdb_prepare_extracted_statement(
+                self.raw,
+                extracted.raw,
+                index as idx_t,
+                &mut handle.0,
+            )
+        };
+
+        if status != DUCKDB_SUCCESS {
+            return Err(handle.error());
+        }
+
+        Ok(handle)
+    }
+}
+
+impl Drop for ConnectionHandle {
+    fn drop(&mut self) {
+        unsafe { duckdb_disconnect(&mut self.raw) }
+    }
+}
+
+pub(crate) struct ExtractedStatements {
+    raw: duckdb_extracted_statements,
+    pub(crate) count: usize,
+}
+
+impl Drop for ExtractedStatements {
+    fn drop(&mut self) {
+        unsafe { duckdb_destroy_extracted(&mut self.raw) }
+    }
+}
+
+/// A `duckdb_prepared_statement`.
+pub(crate) struct StatementHandle(duckdb_prepared_statement);
+
+// The statements are only used by the worker thread which prepared them.
+unsafe impl Send for StatementHandle {}
+
+impl StatementHandle {
+    fn error(&self) -> Error {
+        // https://duckdb.org/docs/api/c/prepared#duckdb_prepare_error
+        let message = unsafe { duckdb_prepare_error(self.0) };
+
+        Error::Database(Box::new(unsafe {
+            DuckDbError::from_ptr(message, "failed to prepare the statement")
+        }))
+    }
+
+    pub(crate) fn parameter_count(&self) -> usize {
+        unsafe { duckdb_nparams(self.0) as usize }
+    }
+
+    pub(crate) fn is_select(&self) -> bool {
+        unsafe { duckdb_prepared_statement_type(self.0) == DUCKDB_STATEMENT_TYPE_SELECT }
+    }
+
+    pub(crate) fn column_count(&self) -> usize {
+        unsafe { duckdb_prepared_statement_column_count(self.0) as usize }
+    }
+
+    pub(crate) fn column_name(&self, index: usize) -> String {
+        unsafe {
+            let name = duckdb_prepared_statement_column_name(self.0, index as idx_t);
+
+            if name.is_null() {
+                return String::new();
+            }
+
+            let owned = CStr::from_ptr(name).to_string_lossy().into_owned();
+            duckdb_free(name as *mut c_void);
+
+            owned
+        }
+    }
+
+    pub(crate) fn column_type(&self, index: usize) -> duckdb_type {
+        unsafe { duckdb_prepared_statement_column_type(self.0, index as idx_t) }
+    }
+
+    pub(crate) fn clear_bindings(&mut self) -> Result<(), Error> {
+        self.check(unsafe { duckdb_clear_bindings(self.0) })
+    }
+
+    fn check(&self, status: duckdb_state) -> Result<(), Error> {
+        if status != DUCKDB_SUCCESS {
+            return Err(self.error());
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn bind_null(&mut self, i: usize) -> Result<(), Error> {
+        self.check(unsafe { duckdb_bind_null(self.0, i as idx_t) })
+    }
+
+    pub(crate) fn bind_boolean(&mut self, i: usize, v: bool) -> Result<(), Error> {
+        self.check(unsafe { duckdb_bind_boolean(self.0, i as idx_t, v) })
+    }
+
+    pub(crate) fn bind_int8(&mut self, i: usize, v: i8) -> Result<(), Error> {
+        self.check(unsafe { duckdb_bind_int8(self.0, i as idx_t, v) })
+    }
+
+    pub(crate) fn bind_int16(&mut self, i: usize, v: i16) -> Result<(), Error> {
+        self.check(unsafe { duckdb_bind_int16(self.0, i as idx_t, v) })
+    }
+
+    pub(crate) fn bind_int32(&mut self, i: usize, v: i32) -> Result<(), Error> {
+        self.check(unsafe { duckdb_bind_int32(self.0, i as idx_t, v) })
+    }
+
+    pub(crate) fn bind_int64(&mut self, i: usize, v: i64) -> Result<(), Error> {
+        self.check(unsafe { duckdb_bind_int64(self.0, i as idx_t, v) })
+    }
+
+    pub(crate) fn bind_uint8(&mut self, i: usize, v: u8) -> Result<(), Error> {
+        self.check(unsafe { duckdb_bind_uint8(self.0, i as idx_t, v) })
+    }
+
+    pub(crate) fn bind_uint16(&mut self, i: usize, v: u16) -> Result<(), Error> {
+        self.check(unsafe { duckdb_bind_uint16(self.0, i as idx_t, v) })
+    }
+
+    pub(crate) fn bind_uint32(&mut self, i: usize, v: u32) -> Result<(), Error> {
+        self.check(unsafe { duckdb_bind_uint32(self.0, i as idx_t, v) })
+    }
+
+    pub(crate) fn bind_uint64(&mut self, i: usize, v: u64) -> Result<(), Error> {
+        self.check(unsafe { duckdb_bind_uint64(self.0, i as idx_t, v) })
+    }
+
+    pub(crate) fn bind_float(&mut self, i: usize, v: f32) -> Result<(), Error> {
+        self.check(unsafe { duckdb_bind_float(self.0, i as idx_t, v) })
+    }
+
+    pub(crate) fn bind_double(&mut self, i: usize, v: f64) -> Result<(), Error> {
+        self.check(unsafe { duckdb_bind_double(self.0, i as idx_t, v) })
+    }
+
+    pub(crate) fn bind_text(&mut self, i: usize, v: &str) -> Result<(), Error> {
+        self.check(unsafe {
+            duckdb_bind_varchar_length(self.0, i as idx_t, v.as_ptr().cast(), v.len() as idx_t)
+        })
+    }
+
+    pub(crate) fn bind_blob(&mut self, i: usize, v: &[u8]) -> Result<(), Error> {
+        self.check(unsafe {
+            duckdb_bind_blob(self.0, i as idx_t, v.as_ptr().cast(), v.len() as idx_t)
+        })
+    }
+
+    pub(crate) fn bind_date(&mut self, i: usize, days: i32) -> Result<(), Error> {
+        self.check(unsafe { duckdb_bind_date(self.0, i as idx_t, duckdb_date { days }) })
+    }
+
+    pub(crate) fn bind_time(&mut self, i: usize, micros: i64) -> Result<(), Error> {
+        self.check(unsafe { duckdb_bind_time(self.0, i as idx_t, duckdb_time { micros }) })
+    }
+
+    pub(crate) fn bind_timestamp(&mut self, i: usize, micros: i64) -> Result<(), Error> {
+        self.check(unsafe {
+            duckdb_bind_timestamp(self.0, i as idx_t, duckdb_timestamp { micros })
+        })
+    }
+
+    /// Executes the statement with the bound arguments, and materializes its result.
+    pub(crate) fn execute(&mut self) -> Result<ResultHandle, Error> {
+        let mut result = ResultHandle(duckdb_result::empty());
+
+        // https://duckdb.org/docs/api/c/prepared#duckdb_execute_prepared
+        let status = unsafe { duckdb_execute_prepared(self.0, &mut result.0) };
+
+        if status != DUCKDB_SUCCESS {
+            return Err(result.error());
+        }
+
+        Ok(result)
+    }
+}
+
+impl Drop for StatementHandle {
+    fn drop(&mut self) {
+        unsafe { duckdb_destroy_prepare(&mut self.0) }
+    }
+}
+
+/// A materialized `duckdb_result`.
+pub(crate) struct ResultHandle(duckdb_result);
+
+impl ResultHandle {
+    fn error(&mut self) -> Error {
+        let message = unsafe { duckdb_result_error(&mut self.0) };
+
+        Error::Database(Box::new(unsafe {
+            DuckDbError::from_ptr(message, "failed to execute the statement")
+        }))
+    }
+
+    pub(crate) fn as_mut_ptr(&mut self) -> *mut duckdb_result {
+        &mut self.0
+    }
+
+    /// Whether the result has rows, rather than the number of changed rows or nothing.
+    pub(crate) fn is_query_result(&self) -> bool {
+        unsafe { duckdb_result_return_type(self.0) == DUCKDB_RESULT_TYPE_QUERY_RESULT }
+    }
+
+    pub(crate) fn is_changed_rows(&self) -> bool {
+        unsafe { duckdb_result_return_type(self.0) == DUCKDB_RESULT_TYPE_CHANGED_ROWS }
+    }
+
+    pub(crate) fn rows_changed(&mut self) -> u64 {
+        unsafe { duckdb_rows_changed(&mut self.0) }
+    }
+
+    pub(crate) fn column_count(&mut self) -> usize {
+        unsafe { duckdb_column_count(&mut self.0) as usize }
+    }
+
+    pub(crate) fn row_count(&mut self) -> usize {
+        unsafe { duckdb_row_count(&mut self.0) as usize }
+    }
+
+    pub(crate) fn column_name(&mut self, index: usize) -> String {
+        unsafe {
+            // owned by the result
+            let name = duckdb_column_name(&mut self.0, index as idx_t);
+
+            if name.is_null() {
+                return String::new();
+            }
+
+            CStr::from_ptr(name).to_string_lossy().into_owned()
+        }
+    }
+
+    pub(crate) fn column_type(&mut self, index: usize) -> duckdb_type {
+        unsafe { duckdb_column_type(&mut self.0, index as idx_t) }
+    }
+}
+
+// The result is copied out and dropped by the worker thread which executed the statement.
+unsafe impl Send for ResultHandle {}
+
+impl Drop for ResultHandle {
+    fn drop(&mut self) {
+        unsafe { duckdb_destroy_result(&mut self.0) }
+    }
+}
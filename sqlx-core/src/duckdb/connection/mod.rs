@@ -0,0 +1,186 @@
+use std::ffi::CStr;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::atomic::Ordering;
+
+use futures_core::future::BoxFuture;
+use futures_util::future;
+
+pub(crate) use establish::SharedDatabase;
+pub(crate) use handle::StatementHandle;
+
+use crate::common::StatementCache;
+use crate::connection::{Connection, LogSettings, ServerCapabilities, ServerVersion};
+use crate::duckdb::connection::handle::ConnectionHandle;
+use crate::duckdb::connection::worker::ConnectionWorker;
+use crate::duckdb::ffi::duckdb_library_version;
+use crate::duckdb::{DuckDb, DuckDbConnectOptions};
+use crate::error::Error;
+use crate::transaction::{Transaction, TransactionOptions};
+
+mod describe;
+mod establish;
+mod execute;
+mod executor;
+mod handle;
+mod worker;
+
+/// A connection to an open [DuckDb] database.
+///
+/// Because DuckDB is an in-process database accessed by blocking API calls, SQLx uses a background
+/// thread and communicates with it via channels to allow non-blocking access to the database.
+///
+/// Dropping this struct will signal the worker thread to quit and disconnect, though if an error
+/// occurs there is no way to pass it back to the user this way.
+///
+/// You can explicitly call [`.close()`][Self::close] to ensure the connection is closed
+/// successfully or get an error otherwise.
+pub struct DuckDbConnection {
+    pub(crate) worker: ConnectionWorker,
+    pub(crate) row_channel_size: usize,
+}
+
+pub(crate) struct ConnectionState {
+    // dropped before the connection handle
+    pub(crate) statements: StatementCache<StatementHandle>,
+
+    pub(crate) handle: ConnectionHandle,
+
+    // transaction status
+    pub(crate) transaction_depth: usize,
+
+    pub(crate) log_settings: LogSettings,
+}
+
+impl DuckDbConnection {
+    pub(crate) async fn establish(options: &DuckDbConnectOptions) -> Result<Self, Error> {
+        let worker = ConnectionWorker::establish(options).await?;
+
+        Ok(Self {
+            worker,
+            row_channel_size: options.row_channel_size,
+        })
+    }
+}
+
+impl Debug for DuckDbConnection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DuckDbConnection")
+            .field("row_channel_size", &self.row_channel_size)
+            .field("cached_statements_size", &self.cached_statements_size())
+            .finish()
+    }
+}
+
+impl Connection for DuckDbConnection {
+    type Database = DuckDb;
+
+    type Options = DuckDbConnectOptions;
+
+    fn close(mut self) -> BoxFuture<'static, Result<(), Error>> {
+        Box::pin(async move {
+            let shutdown = self.worker.shutdown();
+            drop(self);
+            // Ensure the worker thread has terminated
+            shutdown.await
+        })
+    }
+
+    fn close_hard(self) -> BoxFuture<'static, Result<(), Error>> {
+        Box::pin(async move {
+            drop(self);
+            Ok(())
+        })
+    }
+
+    /// Ensure the background worker thread is alive and accepting commands.
+    fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(self.worker.ping())
+    }
+
+    fn begin(&mut self) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized,
+    {
+        Transaction::start(self)
+    }
+
+    fn begin_with(
+        &mut self,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized,
+    {
+        Transaction::start_with(self, options)
+    }
+
+    fn cached_statements_size(&self) -> usize {
+        self.worker.cached_statements_size.load(Ordering::Acquire)
+    }
+
+    fn transaction_depth(&self) -> usize {
+        self.worker.transaction_depth.load(Ordering::Acquire)
+    }
+
+    fn statement_count(&self) -> u64 {
+        self.worker.statement_count.load(Ordering::Acquire)
+    }
+
+    fn clear_cached_statements(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(self.worker.clear_cache())
+    }
+
+    fn server_version(&self) -> Option<ServerVersion> {
+        // e.g. `v1.1.3`
+        let version = unsafe { CStr::from_ptr(duckdb_library_version()) };
+
+        parse_library_version(version.to_str().ok()?)
+    }
+
+    fn server_capabilities(&self) -> ServerCapabilities {
+        ServerCapabilities {
+            returning: true,
+            json: true,
+            common_table_expressions: true,
+            multiranges: false,
+        }
+    }
+
+    #[doc(hidden)]
+    fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        // as for SQLite, there's nothing to flush
+        Box::pin(future::ok(()))
+    }
+
+    #[doc(hidden)]
+    fn should_flush(&self) -> bool {
+        false
+    }
+}
+
+fn parse_library_version(s: &str) -> Option<ServerVersion> {
+    // ignore the suffix of development builds, e.g. `v1.2.0-dev123`
+    let version = s.strip_prefix('v').unwrap_or(s).split('-').next()?;
+    let mut parts = version.split('.').map(str::parse::<u32>);
+
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().and_then(Result::ok).unwrap_or(0);
+    let patch = parts.next().and_then(Result::ok).unwrap_or(0);
+
+    Some(ServerVersion::new(major, minor, patch))
+}
+
+#[test]
+fn test_parse_library_version() {
+    assert_eq!(
+        parse_library_version("v1.1.3"),
+        Some(ServerVersion::new(1, 1, 3))
+    );
+
+    assert_eq!(
+        parse_library_version("v1.2.0-dev123"),
+        Some(ServerVersion::new(1, 2, 0))
+    );
+
+    assert_eq!(parse_library_version("unknown"), None);
+}
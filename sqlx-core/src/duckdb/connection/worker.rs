@@ -0,0 +1,327 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use either::Either;
+use futures_channel::oneshot;
+
+use crate::common::StatementCache;
+use crate::describe::Describe;
+use crate::duckdb::connection::{describe, execute, ConnectionState};
+use crate::duckdb::{
+    DuckDb, DuckDbArguments, DuckDbConnectOptions, DuckDbQueryResult, DuckDbRow, DuckDbStatement,
+};
+use crate::error::Error;
+use crate::observer;
+use crate::transaction::{
+    begin_ansi_transaction_sql, commit_ansi_transaction_sql, rollback_ansi_transaction_sql,
+};
+
+// Each DuckDB connection has a dedicated thread, running the blocking calls into DuckDB.
+
+static THREAD_ID: AtomicU64 = AtomicU64::new(0);
+
+pub(crate) struct ConnectionWorker {
+    command_tx: flume::Sender<Command>,
+    pub(crate) cached_statements_size: Arc<AtomicUsize>,
+    pub(crate) transaction_depth: Arc<AtomicUsize>,
+    pub(crate) statement_count: Arc<AtomicU64>,
+}
+
+enum Command {
+    Prepare {
+        query: Box<str>,
+        tx: oneshot::Sender<Result<DuckDbStatement<'static>, Error>>,
+    },
+    Describe {
+        query: Box<str>,
+        tx: oneshot::Sender<Result<Describe<DuckDb>, Error>>,
+    },
+    Execute {
+        query: Box<str>,
+        arguments: Option<DuckDbArguments<'static>>,
+        persistent: bool,
+        // the tags of the task executing the statement, for the observer
+        tags: Option<observer::Tags>,
+        tx: flume::Sender<Result<Either<DuckDbQueryResult, DuckDbRow>, Error>>,
+    },
+    Begin {
+        tx: oneshot::Sender<Result<(), Error>>,
+    },
+    Commit {
+        tx: oneshot::Sender<Result<(), Error>>,
+    },
+    Rollback {
+        tx: Option<oneshot::Sender<Result<(), Error>>>,
+    },
+    // a statement managing the transaction, e.g. rolling back to a dropped savepoint
+    StartStatement {
+        query: Box<str>,
+    },
+    ClearCache {
+        tx: oneshot::Sender<()>,
+    },
+    Ping {
+        tx: oneshot::Sender<()>,
+    },
+    Shutdown {
+        tx: oneshot::Sender<()>,
+    },
+}
+
+impl ConnectionWorker {
+    pub(crate) async fn establish(options: &DuckDbConnectOptions) -> Result<Self, Error> {
+        let (establish_tx, establish_rx) = oneshot::channel();
+        let options = options.clone();
+
+        thread::Builder::new()
+            .name(format!(
+                "sqlx-duckdb-worker-{}",
+                THREAD_ID.fetch_add(1, Ordering::AcqRel)
+            ))
+            .spawn(move || {
+                let (command_tx, command_rx) = flume::bounded(options.command_channel_size);
+
+                let mut conn = match options.database.connect(&options) {
+                    Ok(handle) => ConnectionState {
+                        statements: StatementCache::new(options.statement_cache_capacity),
+                        handle,
+                        transaction_depth: 0,
+                        log_settings: options.log_settings.clone(),
+                    },
+                    Err(e) => {
+                        establish_tx.send(Err(e)).ok();
+                        return;
+                    }
+                };
+
+                let cached_statements_size = Arc::new(AtomicUsize::new(0));
+                let transaction_depth = Arc::new(AtomicUsize::new(0));
+                let statement_count = Arc::new(AtomicU64::new(0));
+
+                if establish_tx
+                    .send(Ok(Self {
+                        command_tx,
+                        cached_statements_size: Arc::clone(&cached_statements_size),
+                        transaction_depth: Arc::clone(&transaction_depth),
+                        statement_count: Arc::clone(&statement_count),
+                    }))
+                    .is_err()
+                {
+                    return;
+                }
+
+                for cmd in command_rx {
+                    match cmd {
+                        Command::Prepare { query, tx } => {
+                            tx.send(describe::prepare(&mut conn, &query)).ok();
+                        }
+                        Command::Describe { query, tx } => {
+                            tx.send(describe::describe(&mut conn, &query)).ok();
+                        }
+                        Command::Execute {
+                            query,
+                            arguments,
+                            persistent,
+                            tags,
+                            tx,
+                        } => {
+                            let _tags = observer::enter(tags);
+
+                            statement_count.fetch_add(1, Ordering::Release);
+
+                            if let Err(e) =
+                                execute::execute(&mut conn, &query, arguments, persistent, &tx)
+                            {
+                                tx.send(Err(e)).ok();
+                            }
+
+                            cached_statements_size.store(conn.statements.len(), Ordering::Release);
+                        }
+                        Command::Begin { tx } => {
+                            let depth = conn.transaction_depth;
+                            let res =
+                                conn.handle
+                                    .exec(begin_ansi_transaction_sql(depth))
+                                    .map(|_| {
+                                        conn.transaction_depth += 1;
+                                    });
+
+                            transaction_depth.store(conn.transaction_depth, Ordering::Release);
+
+                            tx.send(res).ok();
+                        }
+                        Command::Commit { tx } => {
+                            let depth = conn.transaction_depth;
+
+                            let res = if depth > 0 {
+                                conn.handle
+                                    .exec(commit_ansi_transaction_sql(depth))
+                                    .map(|_| {
+                                        conn.transaction_depth -= 1;
+                                    })
+                            } else {
+                                Ok(())
+                            };
+
+                            transaction_depth.store(conn.transaction_depth, Ordering::Release);
+
+                            tx.send(res).ok();
+                        }
+                        Command::Rollback { tx } => {
+                            let depth = conn.transaction_depth;
+
+                            let res = if depth > 0 {
+                                conn.handle
+                                    .exec(rollback_ansi_transaction_sql(depth))
+                                    .map(|_| {
+                                        conn.transaction_depth -= 1;
+                                    })
+                            } else {
+                                Ok(())
+                            };
+
+                            transaction_depth.store(conn.transaction_depth, Ordering::Release);
+
+                            if let Some(tx) = tx {
+                                tx.send(res).ok();
+                            }
+                        }
+                        Command::StartStatement { query } => {
+                            if let Err(e) = conn.handle.exec(&*query) {
+                                log::warn!(
+                                    "error executing {:?} in background worker: {}",
+                                    query,
+                                    e
+                                );
+                            }
+                        }
+                        Command::ClearCache { tx } => {
+                            conn.statements.clear();
+                            cached_statements_size.store(0, Ordering::Release);
+                            tx.send(()).ok();
+                        }
+                        Command::Ping { tx } => {
+                            tx.send(()).ok();
+                        }
+                        Command::Shutdown { tx } => {
+                            // disconnect before sending confirmation and ending the command loop
+                            drop(conn);
+                            let _ = tx.send(());
+                            return;
+                        }
+                    }
+                }
+            })?;
+
+        establish_rx.await.map_err(|_| Error::WorkerCrashed)?
+    }
+
+    pub(crate) async fn prepare(&mut self, query: &str) -> Result<DuckDbStatement<'static>, Error> {
+        self.oneshot_cmd(|tx| Command::Prepare {
+            query: query.into(),
+            tx,
+        })
+        .await?
+    }
+
+    pub(crate) async fn describe(&mut self, query: &str) -> Result<Describe<DuckDb>, Error> {
+        self.oneshot_cmd(|tx| Command::Describe {
+            query: query.into(),
+            tx,
+        })
+        .await?
+    }
+
+    pub(crate) async fn execute(
+        &mut self,
+        query: &str,
+        args: Option<DuckDbArguments<'_>>,
+        chan_size: usize,
+        persistent: bool,
+    ) -> Result<flume::Receiver<Result<Either<DuckDbQueryResult, DuckDbRow>, Error>>, Error> {
+        let (tx, rx) = flume::bounded(chan_size);
+
+        self.command_tx
+            .send_async(Command::Execute {
+                query: query.into(),
+                arguments: args.map(DuckDbArguments::into_static),
+                persistent,
+                tags: observer::current_tags(),
+                tx,
+            })
+            .await
+            .map_err(|_| Error::WorkerCrashed)?;
+
+        Ok(rx)
+    }
+
+    pub(crate) async fn begin(&mut self) -> Result<(), Error> {
+        self.oneshot_cmd(|tx| Command::Begin { tx }).await?
+    }
+
+    pub(crate) async fn commit(&mut self) -> Result<(), Error> {
+        self.oneshot_cmd(|tx| Command::Commit { tx }).await?
+    }
+
+    pub(crate) async fn rollback(&mut self) -> Result<(), Error> {
+        self.oneshot_cmd(|tx| Command::Rollback { tx: Some(tx) })
+            .await?
+    }
+
+    pub(crate) fn start_rollback(&mut self) -> Result<(), Error> {
+        self.command_tx
+            .send(Command::Rollback { tx: None })
+            .map_err(|_| Error::WorkerCrashed)
+    }
+
+    pub(crate) fn start_statement(&mut self, query: &str) -> Result<(), Error> {
+        self.command_tx
+            .send(Command::StartStatement {
+                query: query.into(),
+            })
+            .map_err(|_| Error::WorkerCrashed)
+    }
+
+    pub(crate) async fn ping(&mut self) -> Result<(), Error> {
+        self.oneshot_cmd(|tx| Command::Ping { tx }).await
+    }
+
+    pub(crate) async fn clear_cache(&mut self) -> Result<(), Error> {
+        self.oneshot_cmd(|tx| Command::ClearCache { tx }).await
+    }
+
+    async fn oneshot_cmd<F, T>(&mut self, command: F) -> Result<T, Error>
+    where
+        F: FnOnce(oneshot::Sender<T>) -> Command,
+    {
+        let (tx, rx) = oneshot::channel();
+
+        self.command_tx
+            .send_async(command(tx))
+            .await
+            .map_err(|_| Error::WorkerCrashed)?;
+
+        rx.await.map_err(|_| Error::WorkerCrashed)
+    }
+
+    /// Send a command to the worker to shut down the processing thread.
+    ///
+    /// A `WorkerCrashed` error may be returned if the thread has already stopped.
+    pub(crate) fn shutdown(&mut self) -> impl Future<Output = Result<(), Error>> {
+        let (tx, rx) = oneshot::channel();
+
+        let send_res = self
+            .command_tx
+            .send(Command::Shutdown { tx })
+            .map_err(|_| Error::WorkerCrashed);
+
+        async move {
+            send_res?;
+
+            // wait for the response
+            rx.await.map_err(|_| Error::WorkerCrashed)
+        }
+    }
+}
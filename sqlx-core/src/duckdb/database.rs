@@ -0,0 +1,48 @@
+use crate::database::{Database, HasArguments, HasStatement, HasStatementCache, HasValueRef};
+use crate::duckdb::{
+    DuckDbArgumentValue, DuckDbArguments, DuckDbColumn, DuckDbConnection, DuckDbQueryResult,
+    DuckDbRow, DuckDbStatement, DuckDbTransactionManager, DuckDbTypeInfo, DuckDbValue,
+    DuckDbValueRef,
+};
+
+/// DuckDB database driver.
+#[derive(Debug)]
+pub struct DuckDb;
+
+impl Database for DuckDb {
+    type Connection = DuckDbConnection;
+
+    type TransactionManager = DuckDbTransactionManager;
+
+    type Row = DuckDbRow;
+
+    type QueryResult = DuckDbQueryResult;
+
+    type Column = DuckDbColumn;
+
+    type TypeInfo = DuckDbTypeInfo;
+
+    type Value = DuckDbValue;
+}
+
+impl<'r> HasValueRef<'r> for DuckDb {
+    type Database = DuckDb;
+
+    type ValueRef = DuckDbValueRef<'r>;
+}
+
+impl<'q> HasArguments<'q> for DuckDb {
+    type Database = DuckDb;
+
+    type Arguments = DuckDbArguments<'q>;
+
+    type ArgumentBuffer = Vec<DuckDbArgumentValue<'q>>;
+}
+
+impl<'q> HasStatement<'q> for DuckDb {
+    type Database = DuckDb;
+
+    type Statement = DuckDbStatement<'q>;
+}
+
+impl HasStatementCache for DuckDb {}
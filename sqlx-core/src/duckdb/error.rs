@@ -0,0 +1,61 @@
+use std::error::Error as StdError;
+use std::ffi::CStr;
+use std::fmt::{self, Display, Formatter};
+use std::os::raw::c_char;
+
+use crate::error::DatabaseError;
+
+// DuckDB reports errors with a message only, e.g.
+// `Constraint Error: Duplicate key "id: 1" violates primary key constraint.`
+
+#[derive(Debug)]
+pub struct DuckDbError {
+    message: String,
+}
+
+impl DuckDbError {
+    pub(crate) fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+
+    /// From the error message returned by a DuckDB function, which may be NULL.
+    pub(crate) unsafe fn from_ptr(message: *const c_char, fallback: &str) -> Self {
+        if message.is_null() {
+            return Self::new(fallback);
+        }
+
+        Self::new(CStr::from_ptr(message).to_string_lossy())
+    }
+}
+
+impl Display for DuckDbError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl StdError for DuckDbError {}
+
+impl DatabaseError for DuckDbError {
+    #[inline]
+    fn message(&self) -> &str {
+        &self.message
+    }
+
+    #[doc(hidden)]
+    fn as_error(&self) -> &(dyn StdError + Send + Sync + 'static) {
+        self
+    }
+
+    #[doc(hidden)]
+    fn as_error_mut(&mut self) -> &mut (dyn StdError + Send + Sync + 'static) {
+        self
+    }
+
+    #[doc(hidden)]
+    fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
+        self
+    }
+}
@@ -0,0 +1,337 @@
+//! Bindings to the parts of the [DuckDB C API](https://duckdb.org/docs/api/c/api) used by the
+//! driver, linked against the `duckdb` library.
+//!
+//! The functions reading values by row and column (`duckdb_value_*`) work on materialized results,
+//! which is what `duckdb_execute_prepared()` returns.
+#![allow(non_camel_case_types)]
+
+use std::os::raw::{c_char, c_int, c_void};
+
+pub(crate) type idx_t = u64;
+
+pub(crate) type duckdb_state = c_int;
+pub(crate) const DUCKDB_SUCCESS: duckdb_state = 0;
+
+pub(crate) type duckdb_type = c_int;
+pub(crate) const DUCKDB_TYPE_INVALID: duckdb_type = 0;
+pub(crate) const DUCKDB_TYPE_BOOLEAN: duckdb_type = 1;
+pub(crate) const DUCKDB_TYPE_TINYINT: duckdb_type = 2;
+pub(crate) const DUCKDB_TYPE_SMALLINT: duckdb_type = 3;
+pub(crate) const DUCKDB_TYPE_INTEGER: duckdb_type = 4;
+pub(crate) const DUCKDB_TYPE_BIGINT: duckdb_type = 5;
+pub(crate) const DUCKDB_TYPE_UTINYINT: duckdb_type = 6;
+pub(crate) const DUCKDB_TYPE_USMALLINT: duckdb_type = 7;
+pub(crate) const DUCKDB_TYPE_UINTEGER: duckdb_type = 8;
+pub(crate) const DUCKDB_TYPE_UBIGINT: duckdb_type = 9;
+pub(crate) const DUCKDB_TYPE_FLOAT: duckdb_type = 10;
+pub(crate) const DUCKDB_TYPE_DOUBLE: duckdb_type = 11;
+pub(crate) const DUCKDB_TYPE_TIMESTAMP: duckdb_type = 12;
+pub(crate) const DUCKDB_TYPE_DATE: duckdb_type = 13;
+pub(crate) const DUCKDB_TYPE_TIME: duckdb_type = 14;
+pub(crate) const DUCKDB_TYPE_INTERVAL: duckdb_type = 15;
+pub(crate) const DUCKDB_TYPE_HUGEINT: duckdb_type = 16;
+pub(crate) const DUCKDB_TYPE_VARCHAR: duckdb_type = 17;
+pub(crate) const DUCKDB_TYPE_BLOB: duckdb_type = 18;
+pub(crate) const DUCKDB_TYPE_DECIMAL: duckdb_type = 19;
+pub(crate) const DUCKDB_TYPE_TIMESTAMP_S: duckdb_type = 20;
+pub(crate) const DUCKDB_TYPE_TIMESTAMP_MS: duckdb_type = 21;
+pub(crate) const DUCKDB_TYPE_TIMESTAMP_NS: duckdb_type = 22;
+pub(crate) const DUCKDB_TYPE_ENUM: duckdb_type = 23;
+pub(crate) const DUCKDB_TYPE_LIST: duckdb_type = 24;
+pub(crate) const DUCKDB_TYPE_STRUCT: duckdb_type = 25;
+pub(crate) const DUCKDB_TYPE_MAP: duckdb_type = 26;
+pub(crate) const DUCKDB_TYPE_UUID: duckdb_type = 27;
+pub(crate) const DUCKDB_TYPE_UNION: duckdb_type = 28;
+pub(crate) const DUCKDB_TYPE_BIT: duckdb_type = 29;
+pub(crate) const DUCKDB_TYPE_TIME_TZ: duckdb_type = 30;
+pub(crate) const DUCKDB_TYPE_TIMESTAMP_TZ: duckdb_type = 31;
+pub(crate) const DUCKDB_TYPE_UHUGEINT: duckdb_type = 32;
+pub(crate) const DUCKDB_TYPE_ARRAY: duckdb_type = 33;
+pub(crate) const DUCKDB_TYPE_SQLNULL: duckdb_type = 36;
+
+pub(crate) type duckdb_result_type = c_int;
+pub(crate) const DUCKDB_RESULT_TYPE_CHANGED_ROWS: duckdb_result_type = 1;
+pub(crate) const DUCKDB_RESULT_TYPE_QUERY_RESULT: duckdb_result_type = 3;
+
+pub(crate) type duckdb_statement_type = c_int;
+pub(crate) const DUCKDB_STATEMENT_TYPE_SELECT: duckdb_statement_type = 1;
+
+// the handles are pointers to structs holding a single pointer, only ever passed around
+pub(crate) type duckdb_database = *mut c_void;
+pub(crate) type duckdb_connection = *mut c_void;
+pub(crate) type duckdb_prepared_statement = *mut c_void;
+pub(crate) type duckdb_extracted_statements = *mut c_void;
+pub(crate) type duckdb_config = *mut c_void;
+
+/// A materialized result; the fields are deprecated in favor of the accessor functions.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct duckdb_result {
+    deprecated_column_count: idx_t,
+    deprecated_row_count: idx_t,
+    deprecated_rows_changed: idx_t,
+    deprecated_columns: *mut c_void,
+    deprecated_error_message: *mut c_char,
+    internal_data: *mut c_void,
+}
+
+impl duckdb_result {
+    pub(crate) fn empty() -> Self {
+        Self {
+            deprecated_column_count: 0,
+            deprecated_row_count: 0,
+            deprecated_rows_changed: 0,
+            deprecated_columns: std::ptr::null_mut(),
+            deprecated_error_message: std::ptr::null_mut(),
+            internal_data: std::ptr::null_mut(),
+        }
+    }
+}
+
+/// Days since 1970-01-01.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct duckdb_date {
+    pub(crate) days: i32,
+}
+
+/// Microseconds since 00:00:00.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct duckdb_time {
+    pub(crate) micros: i64,
+}
+
+/// Microseconds since 1970-01-01 00:00:00 (UTC for `TIMESTAMPTZ`).
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct duckdb_timestamp {
+    pub(crate) micros: i64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(crate) struct duckdb_hugeint {
+    pub(crate) lower: u64,
+    pub(crate) upper: i64,
+}
+
+#[repr(C)]
+pub(crate) struct duckdb_blob {
+    pub(crate) data: *mut c_void,
+    pub(crate) size: idx_t,
+}
+
+#[link(name = "duckdb")]
+extern "C" {
+    pub(crate) fn duckdb_library_version() -> *const c_char;
+    pub(crate) fn duckdb_free(ptr: *mut c_void);
+
+    pub(crate) fn duckdb_create_config(out_config: *mut duckdb_config) -> duckdb_state;
+    pub(crate) fn duckdb_set_config(
+        config: duckdb_config,
+        name: *const c_char,
+        option: *const c_char,
+    ) -> duckdb_state;
+    pub(crate) fn duckdb_destroy_config(config: *mut duckdb_config);
+
+    pub(crate) fn duckdb_open_ext(
+        path: *const c_char,
+        out_database: *mut duckdb_database,
+        config: duckdb_config,
+        out_error: *mut *mut c_char,
+    ) -> duckdb_state;
+    pub(crate) fn duckdb_close(database: *mut duckdb_database);
+
+    pub(crate) fn duckdb_connect(
+        database: duckdb_database,
+        out_connection: *mut duckdb_connection,
+    ) -> duckdb_state;
+    pub(crate) fn duckdb_disconnect(connection: *mut duckdb_connection);
+
+    pub(crate) fn duckdb_query(
+        connection: duckdb_connection,
+        query: *const c_char,
+        out_result: *mut duckdb_result,
+    ) -> duckdb_state;
+
+    pub(crate) fn duckdb_extract_statements(
+        connection: duckdb_connection,
+        query: *const c_char,
+        out_extracted_statements: *mut duckdb_extracted_statements,
+    ) -> idx_t;
+    pub(crate) fn duckdb_prepare_extracted_statement(
+        connection: duckdb_connection,
+        extracted_statements: duckdb_extracted_statements,
+        index: idx_t,
+        out_prepared_statement: *mut duckdb_prepared_statement,
+    ) -> duckdb_state;
+    pub(crate) fn duckdb_extract_statements_error(
+        extracted_statements: duckdb_extracted_statements,
+    ) -> *const c_char;
+    pub(crate) fn duckdb_destroy_extracted(extracted_statements: *mut duckdb_extracted_statements);
+
+    pub(crate) fn duckdb_destroy_prepare(prepared_statement: *mut duckdb_prepared_statement);
+    pub(crate) fn duckdb_prepare_error(
+        prepared_statement: duckdb_prepared_statement,
+    ) -> *const c_char;
+    pub(crate) fn duckdb_nparams(prepared_statement: duckdb_prepared_statement) -> idx_t;
+    pub(crate) fn duckdb_prepared_statement_type(
+        prepared_statement: duckdb_prepared_statement,
+    ) -> duckdb_statement_type;
+    pub(crate) fn duckdb_prepared_statement_column_count(
+        prepared_statement: duckdb_prepared_statement,
+    ) -> idx_t;
+    // the returned name must be freed with `duckdb_free()`
+    pub(crate) fn duckdb_prepared_statement_column_name(
+        prepared_statement: duckdb_prepared_statement,
+        col_idx: idx_t,
+    ) -> *const c_char;
+    pub(crate) fn duckdb_prepared_statement_column_type(
+        prepared_statement: duckdb_prepared_statement,
+        col_idx: idx_t,
+    ) -> duckdb_type;
+    pub(crate) fn duckdb_clear_bindings(
+        prepared_statement: duckdb_prepared_statement,
+    ) -> duckdb_state;
+
+    // the parameter indices start at 1
+    pub(crate) fn duckdb_bind_boolean(
+        prepared_statement: duckdb_prepared_statement,
+        param_idx: idx_t,
+        val: bool,
+    ) -> duckdb_state;
+    pub(crate) fn duckdb_bind_int8(
+        prepared_statement: duckdb_prepared_statement,
+        param_idx: idx_t,
+        val: i8,
+    ) -> duckdb_state;
+    pub(crate) fn duckdb_bind_int16(
+        prepared_statement: duckdb_prepared_statement,
+        param_idx: idx_t,
+        val: i16,
+    ) -> duckdb_state;
+    pub(crate) fn duckdb_bind_int32(
+        prepared_statement: duckdb_prepared_statement,
+        param_idx: idx_t,
+        val: i32,
+    ) -> duckdb_state;
+    pub(crate) fn duckdb_bind_int64(
+        prepared_statement: duckdb_prepared_statement,
+        param_idx: idx_t,
+        val: i64,
+    ) -> duckdb_state;
+    pub(crate) fn duckdb_bind_uint8(
+        prepared_statement: duckdb_prepared_statement,
+        param_idx: idx_t,
+        val: u8,
+    ) -> duckdb_state;
+    pub(crate) fn duckdb_bind_uint16(
+        prepared_statement: duckdb_prepared_statement,
+        param_idx: idx_t,
+        val: u16,
+    ) -> duckdb_state;
+    pub(crate) fn duckdb_bind_uint32(
+        prepared_statement: duckdb_prepared_statement,
+        param_idx: idx_t,
+        val: u32,
+    ) -> duckdb_state;
+    pub(crate) fn duckdb_bind_uint64(
+        prepared_statement: duckdb_prepared_statement,
+        param_idx: idx_t,
+        val: u64,
+    ) -> duckdb_state;
+    pub(crate) fn duckdb_bind_float(
+        prepared_statement: duckdb_prepared_statement,
+        param_idx: idx_t,
+        val: f32,
+    ) -> duckdb_state;
+    pub(crate) fn duckdb_bind_double(
+        prepared_statement: duckdb_prepared_statement,
+        param_idx: idx_t,
+        val: f64,
+    ) -> duckdb_state;
+    pub(crate) fn duckdb_bind_date(
+        prepared_statement: duckdb_prepared_statement,
+        param_idx: idx_t,
+        val: duckdb_date,
+    ) -> duckdb_state;
+    pub(crate) fn duckdb_bind_time(
+        prepared_statement: duckdb_prepared_statement,
+        param_idx: idx_t,
+        val: duckdb_time,
+    ) -> duckdb_state;
+    pub(crate) fn duckdb_bind_timestamp(
+        prepared_statement: duckdb_prepared_statement,
+        param_idx: idx_t,
+        val: duckdb_timestamp,
+    ) -> duckdb_state;
+    pub(crate) fn duckdb_bind_varchar_length(
+        prepared_statement: duckdb_prepared_statement,
+        param_idx: idx_t,
+        val: *const c_char,
+        length: idx_t,
+    ) -> duckdb_state;
+    pub(crate) fn duckdb_bind_blob(
+        prepared_statement: duckdb_prepared_statement,
+        param_idx: idx_t,
+        data: *const c_void,
+        length: idx_t,
+    ) -> duckdb_state;
+    pub(crate) fn duckdb_bind_null(
+        prepared_statement: duckdb_prepared_statement,
+        param_idx: idx_t,
+    ) -> duckdb_state;
+
+    pub(crate) fn duckdb_execute_prepared(
+        prepared_statement: duckdb_prepared_statement,
+        out_result: *mut duckdb_result,
+    ) -> duckdb_state;
+
+    pub(crate) fn duckdb_destroy_result(result: *mut duckdb_result);
+    pub(crate) fn duckdb_result_error(result: *mut duckdb_result) -> *const c_char;
+    pub(crate) fn duckdb_result_return_type(result: duckdb_result) -> duckdb_result_type;
+    pub(crate) fn duckdb_column_count(result: *mut duckdb_result) -> idx_t;
+    pub(crate) fn duckdb_column_name(result: *mut duckdb_result, col: idx_t) -> *const c_char;
+    pub(crate) fn duckdb_column_type(result: *mut duckdb_result, col: idx_t) -> duckdb_type;
+    pub(crate) fn duckdb_row_count(result: *mut duckdb_result) -> idx_t;
+    pub(crate) fn duckdb_rows_changed(result: *mut duckdb_result) -> idx_t;
+
+    pub(crate) fn duckdb_value_is_null(result: *mut duckdb_result, col: idx_t, row: idx_t) -> bool;
+    pub(crate) fn duckdb_value_boolean(result: *mut duckdb_result, col: idx_t, row: idx_t) -> bool;
+    pub(crate) fn duckdb_value_int64(result: *mut duckdb_result, col: idx_t, row: idx_t) -> i64;
+    pub(crate) fn duckdb_value_uint64(result: *mut duckdb_result, col: idx_t, row: idx_t) -> u64;
+    pub(crate) fn duckdb_value_hugeint(
+        result: *mut duckdb_result,
+        col: idx_t,
+        row: idx_t,
+    ) -> duckdb_hugeint;
+    pub(crate) fn duckdb_value_double(result: *mut duckdb_result, col: idx_t, row: idx_t) -> f64;
+    pub(crate) fn duckdb_value_date(
+        result: *mut duckdb_result,
+        col: idx_t,
+        row: idx_t,
+    ) -> duckdb_date;
+    pub(crate) fn duckdb_value_time(
+        result: *mut duckdb_result,
+        col: idx_t,
+        row: idx_t,
+    ) -> duckdb_time;
+    pub(crate) fn duckdb_value_timestamp(
+        result: *mut duckdb_result,
+        col: idx_t,
+        row: idx_t,
+    ) -> duckdb_timestamp;
+    // the returned string must be freed with `duckdb_free()`
+    pub(crate) fn duckdb_value_varchar(
+        result: *mut duckdb_result,
+        col: idx_t,
+        row: idx_t,
+    ) -> *mut c_char;
+    // the returned data must be freed with `duckdb_free()`
+    pub(crate) fn duckdb_value_blob(
+        result: *mut duckdb_result,
+        col: idx_t,
+        row: idx_t,
+    ) -> duckdb_blob;
+}
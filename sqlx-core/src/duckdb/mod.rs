@@ -0,0 +1,78 @@
+//! **DuckDB** database driver.
+//!
+//! [DuckDB](https://duckdb.org/) is an in-process analytical database. Like SQLite, it's accessed
+//! by blocking calls to a C library, so each connection runs on a dedicated worker thread. The
+//! driver links against the `duckdb` shared library (version 1.1 or newer), which has to be
+//! installed on the system.
+//!
+//! A database file can only be opened once in a process, so the connections opened with the
+//! same [`DuckDbConnectOptions`] (and their clones, e.g. held by a pool) share one instance of
+//! the database, which stays open while the options are alive. This is also what makes the
+//! connections of a pool to `duckdb::memory:` see the same in-memory database.
+//!
+//! ### Transactions
+//!
+//! DuckDB doesn't support savepoints, so beginning a transaction inside another one fails.
+//!
+//! ### Parameters
+//!
+//! Both `?` and `$N` parameters are supported. When a query has several statements, the
+//! parameters of each statement take the next arguments in order.
+
+// DuckDB is a C library. All interactions require FFI which is unsafe.
+// All unsafe blocks should have comments pointing to DuckDB docs and ensuring that we maintain
+// invariants.
+#![allow(unsafe_code)]
+
+pub use arguments::{DuckDbArgumentValue, DuckDbArguments};
+pub use column::DuckDbColumn;
+pub use connection::DuckDbConnection;
+pub use database::DuckDb;
+pub use error::DuckDbError;
+pub use options::DuckDbConnectOptions;
+pub use query_result::DuckDbQueryResult;
+pub use row::DuckDbRow;
+pub use statement::DuckDbStatement;
+pub use transaction::DuckDbTransactionManager;
+pub use type_info::DuckDbTypeInfo;
+pub use value::{DuckDbValue, DuckDbValueRef};
+
+use crate::executor::Executor;
+
+mod arguments;
+mod column;
+mod connection;
+mod database;
+mod error;
+mod ffi;
+mod options;
+mod query_result;
+mod row;
+mod statement;
+mod transaction;
+mod type_info;
+pub mod types;
+mod value;
+
+/// An alias for [`Pool`][crate::pool::Pool], specialized for DuckDB.
+pub type DuckDbPool = crate::pool::Pool<DuckDb>;
+
+/// An alias for [`PoolOptions`][crate::pool::PoolOptions], specialized for DuckDB.
+pub type DuckDbPoolOptions = crate::pool::PoolOptions<DuckDb>;
+
+/// An alias for [`Executor<'_, Database = DuckDb>`][Executor].
+pub trait DuckDbExecutor<'c>: Executor<'c, Database = DuckDb> {}
+impl<'c, T: Executor<'c, Database = DuckDb>> DuckDbExecutor<'c> for T {}
+
+// NOTE: required due to the lack of lazy normalization
+impl_into_arguments_for_arguments!(DuckDbArguments<'q>);
+impl_executor_for_pool_connection!(DuckDb, DuckDbConnection, DuckDbRow);
+impl_executor_for_transaction!(DuckDb, DuckDbRow);
+impl_executor_for_reconnecting_connection!(DuckDb, DuckDbRow);
+impl_column_index_for_row!(DuckDbRow);
+impl_column_index_for_statement!(DuckDbStatement);
+impl_acquire!(DuckDb, DuckDbConnection);
+impl_into_maybe_pool!(DuckDb, DuckDbConnection);
+
+// required because some databases have a different handling of NULL
+impl_encode_for_option!(DuckDb);
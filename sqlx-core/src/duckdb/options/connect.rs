@@ -0,0 +1,36 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_core::future::BoxFuture;
+use log::LevelFilter;
+
+use crate::connection::ConnectOptions;
+use crate::duckdb::{DuckDbConnectOptions, DuckDbConnection};
+use crate::error::Error;
+use crate::query_log::QueryLog;
+
+impl ConnectOptions for DuckDbConnectOptions {
+    type Connection = DuckDbConnection;
+
+    fn connect(&self) -> BoxFuture<'_, Result<Self::Connection, Error>>
+    where
+        Self::Connection: Sized,
+    {
+        Box::pin(DuckDbConnection::establish(self))
+    }
+
+    fn log_statements(&mut self, level: LevelFilter) -> &mut Self {
+        self.log_settings.log_statements(level);
+        self
+    }
+
+    fn log_slow_statements(&mut self, level: LevelFilter, duration: Duration) -> &mut Self {
+        self.log_settings.log_slow_statements(level, duration);
+        self
+    }
+
+    fn query_log(&mut self, log: Arc<dyn QueryLog>) -> &mut Self {
+        self.log_settings.query_log(log);
+        self
+    }
+}
@@ -0,0 +1,170 @@
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+
+use crate::connection::LogSettings;
+use crate::duckdb::connection::SharedDatabase;
+
+mod connect;
+mod parse;
+
+/// Options and flags which can be used to configure a DuckDB connection.
+///
+/// A value of `DuckDbConnectOptions` can be parsed from a connection URL:
+///
+/// | URL | Description |
+/// | -- | -- |
+/// `duckdb::memory:` | Open an in-memory database. |
+/// `duckdb:data.duckdb` | Open the file `data.duckdb` in the current directory. |
+/// `duckdb://data.duckdb` | Open the file `data.duckdb` in the current directory. |
+/// `duckdb:///data.duckdb` | Open the file `data.duckdb` from the root (`/`) directory. |
+/// `duckdb://data.duckdb?mode=ro` | Open the file `data.duckdb` for read-only access. |
+/// `duckdb://data.duckdb?threads=4` | Set a [configuration option](https://duckdb.org/docs/configuration/overview). |
+///
+/// A database file is created if it doesn't exist, unless it's opened read-only.
+///
+/// The connections opened with the options (and their clones, e.g. held by a pool) share one
+/// instance of the database, which stays open until the options are dropped.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use sqlx_core::connection::ConnectOptions;
+/// # use sqlx_core::error::Error;
+/// use sqlx::duckdb::DuckDbConnectOptions;
+/// use std::str::FromStr;
+///
+/// # fn main() {
+/// # #[cfg(feature = "_rt-async-std")]
+/// # sqlx_rt::async_std::task::block_on::<_, Result<(), Error>>(async move {
+/// let conn = DuckDbConnectOptions::from_str("duckdb://analytics.duckdb")?
+///     .threads(4)
+///     .memory_limit("2GB")
+///     .connect().await?;
+/// # Ok(())
+/// # }).unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct DuckDbConnectOptions {
+    // `None` for an in-memory database
+    pub(crate) filename: Option<PathBuf>,
+    pub(crate) config: IndexMap<Cow<'static, str>, Cow<'static, str>>,
+    pub(crate) database: Arc<SharedDatabase>,
+    pub(crate) statement_cache_capacity: usize,
+    pub(crate) log_settings: LogSettings,
+
+    pub(crate) command_channel_size: usize,
+    pub(crate) row_channel_size: usize,
+}
+
+impl Default for DuckDbConnectOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DuckDbConnectOptions {
+    /// Construct `Self` with default options, for an in-memory database.
+    ///
+    /// See the source of this method for the current defaults.
+    pub fn new() -> Self {
+        Self {
+            filename: None,
+            config: IndexMap::new(),
+            database: Default::default(),
+            statement_cache_capacity: 100,
+            log_settings: Default::default(),
+            command_channel_size: 50,
+            row_channel_size: 50,
+        }
+    }
+
+    /// Sets the name of the database file.
+    ///
+    /// `:memory:` opens an in-memory database, which is the default.
+    pub fn filename(mut self, filename: impl AsRef<Path>) -> Self {
+        let filename = filename.as_ref();
+
+        self.filename = if filename == Path::new(":memory:") {
+            None
+        } else {
+            Some(filename.to_owned())
+        };
+
+        self.reopen()
+    }
+
+    /// Opens the database for read-only access.
+    ///
+    /// A read-only database can be opened by several processes at once, while a database opened
+    /// for writing is locked by its process.
+    pub fn read_only(self, read_only: bool) -> Self {
+        self.config(
+            "access_mode",
+            if read_only { "READ_ONLY" } else { "AUTOMATIC" },
+        )
+    }
+
+    /// Sets the number of threads used by DuckDB to run the queries.
+    ///
+    /// By default, this is the number of cores of the machine.
+    pub fn threads(self, threads: usize) -> Self {
+        self.config("threads", threads.to_string())
+    }
+
+    /// Sets the maximum memory used by DuckDB, e.g. `"2GB"`.
+    ///
+    /// By default, this is 80% of the memory of the machine.
+    pub fn memory_limit(self, limit: impl Into<Cow<'static, str>>) -> Self {
+        self.config("memory_limit", limit)
+    }
+
+    /// Sets a [configuration option](https://duckdb.org/docs/configuration/overview) of the
+    /// database, applied when it's opened.
+    ///
+    /// An unknown option, or an invalid value, is an error when connecting.
+    pub fn config(
+        mut self,
+        key: impl Into<Cow<'static, str>>,
+        value: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.config.insert(key.into(), value.into());
+        self.reopen()
+    }
+
+    /// Sets the capacity of the connection's statement cache in a number of stored
+    /// distinct statements. Caching is handled using LRU, meaning when the
+    /// amount of queries hits the defined limit, the oldest statement will get
+    /// dropped.
+    ///
+    /// The default cache capacity is 100 statements.
+    pub fn statement_cache_capacity(mut self, capacity: usize) -> Self {
+        self.statement_cache_capacity = capacity;
+        self
+    }
+
+    /// Set the maximum number of commands to buffer for the worker thread before backpressure is
+    /// applied.
+    pub fn command_buffer_size(mut self, size: usize) -> Self {
+        self.command_channel_size = size;
+        self
+    }
+
+    /// Set the maximum number of rows to buffer back to the calling task when a query is executed.
+    ///
+    /// If the calling task cannot keep up, backpressure will be applied to the worker thread
+    /// in order to limit CPU and memory usage.
+    pub fn row_buffer_size(mut self, size: usize) -> Self {
+        self.row_channel_size = size;
+        self
+    }
+
+    // a different database, or opened differently, from the one the options might have opened
+    fn reopen(mut self) -> Self {
+        self.database = Default::default();
+        self
+    }
+}
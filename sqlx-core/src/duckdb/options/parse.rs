@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use percent_encoding::percent_decode_str;
+
+use crate::duckdb::DuckDbConnectOptions;
+use crate::error::Error;
+
+impl FromStr for DuckDbConnectOptions {
+    type Err = Error;
+
+    fn from_str(mut url: &str) -> Result<Self, Self::Err> {
+        let mut options = Self::new();
+
+        // remove scheme from the URL
+        url = url
+            .trim_start_matches("duckdb://")
+            .trim_start_matches("duckdb:");
+
+        let mut database_and_params = url.splitn(2, '?');
+
+        let database = database_and_params.next().unwrap_or_default();
+
+        if !database.is_empty() && database != ":memory:" {
+            // % decode to allow for `?` or `#` in the filename
+            options.filename = Some(PathBuf::from(
+                &*percent_decode_str(database)
+                    .decode_utf8()
+                    .map_err(Error::config)?,
+            ));
+        }
+
+        if let Some(params) = database_and_params.next() {
+            for (key, value) in url::form_urlencoded::parse(params.as_bytes()) {
+                match &*key {
+                    "mode" => match &*value {
+                        "ro" => {
+                            options = options.read_only(true);
+                        }
+
+                        // default
+                        "rw" | "rwc" => {}
+
+                        _ => {
+                            return Err(Error::Configuration(
+                                format!("unknown value {:?} for `mode`", value).into(),
+                            ));
+                        }
+                    },
+
+                    // any other parameter is a configuration option of the database
+                    _ => {
+                        options = options.config(key.into_owned(), value.into_owned());
+                    }
+                }
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+#[test]
+fn test_parse_in_memory() -> Result<(), Error> {
+    let options: DuckDbConnectOptions = "duckdb::memory:".parse()?;
+    assert_eq!(options.filename, None);
+
+    let options: DuckDbConnectOptions = "duckdb://".parse()?;
+    assert_eq!(options.filename, None);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_filename() -> Result<(), Error> {
+    let options: DuckDbConnectOptions = "duckdb:data.duckdb".parse()?;
+    assert_eq!(options.filename, Some(PathBuf::from("data.duckdb")));
+
+    let options: DuckDbConnectOptions = "duckdb:///var/data%3F.duckdb".parse()?;
+    assert_eq!(options.filename, Some(PathBuf::from("/var/data?.duckdb")));
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_params() -> Result<(), Error> {
+    let options: DuckDbConnectOptions =
+        "duckdb://data.duckdb?mode=ro&threads=4&memory_limit=2GB".parse()?;
+
+    assert_eq!(options.config["access_mode"], "READ_ONLY");
+    assert_eq!(options.config["threads"], "4");
+    assert_eq!(options.config["memory_limit"], "2GB");
+
+    assert!("duckdb://data.duckdb?mode=memory"
+        .parse::<DuckDbConnectOptions>()
+        .is_err());
+
+    Ok(())
+}
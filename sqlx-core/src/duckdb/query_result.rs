@@ -0,0 +1,30 @@
+use std::iter::{Extend, IntoIterator};
+
+#[derive(Debug, Default)]
+pub struct DuckDbQueryResult {
+    pub(super) rows_affected: u64,
+}
+
+impl DuckDbQueryResult {
+    pub fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+}
+
+impl Extend<DuckDbQueryResult> for DuckDbQueryResult {
+    fn extend<T: IntoIterator<Item = DuckDbQueryResult>>(&mut self, iter: T) {
+        for elem in iter {
+            self.rows_affected += elem.rows_affected;
+        }
+    }
+}
+
+#[cfg(feature = "any")]
+impl From<DuckDbQueryResult> for crate::any::AnyQueryResult {
+    fn from(done: DuckDbQueryResult) -> Self {
+        crate::any::AnyQueryResult {
+            rows_affected: done.rows_affected,
+            last_insert_id: None,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+#![allow(clippy::rc_buffer)]
+
+use std::sync::Arc;
+
+use crate::HashMap;
+
+use crate::column::ColumnIndex;
+use crate::duckdb::{DuckDb, DuckDbColumn, DuckDbValue, DuckDbValueRef};
+use crate::error::Error;
+use crate::ext::ustr::UStr;
+use crate::row::Row;
+
+/// Implementation of [`Row`] for DuckDB.
+pub struct DuckDbRow {
+    pub(crate) values: Box<[DuckDbValue]>,
+    pub(crate) columns: Arc<Vec<DuckDbColumn>>,
+    pub(crate) column_names: Arc<HashMap<UStr, usize>>,
+}
+
+impl crate::row::private_row::Sealed for DuckDbRow {}
+
+impl Row for DuckDbRow {
+    type Database = DuckDb;
+
+    fn columns(&self) -> &[DuckDbColumn] {
+        &self.columns
+    }
+
+    fn try_get_raw<I>(&self, index: I) -> Result<DuckDbValueRef<'_>, Error>
+    where
+        I: ColumnIndex<Self>,
+    {
+        let index = index.index(self)?;
+        Ok(DuckDbValueRef::value(&self.values[index]))
+    }
+}
+
+impl ColumnIndex<DuckDbRow> for &'_ str {
+    fn index(&self, row: &DuckDbRow) -> Result<usize, Error> {
+        row.column_names
+            .get(*self)
+            .ok_or_else(|| Error::ColumnNotFound((*self).into()))
+            .map(|v| *v)
+    }
+}
+
+#[cfg(feature = "any")]
+impl DuckDbRow {
+    // the converted columns are shared with the other rows of the result set
+    pub(crate) fn into_any(
+        self,
+        columns: &mut crate::any::row::AnyColumnsCache,
+    ) -> crate::any::AnyRow {
+        crate::any::AnyRow {
+            columns: columns.get(&self.columns, |columns| {
+                columns.iter().map(|col| col.clone().into()).collect()
+            }),
+            kind: crate::any::row::AnyRowKind::DuckDb(self),
+        }
+    }
+}
+
+#[cfg(feature = "any")]
+impl From<DuckDbRow> for crate::any::AnyRow {
+    #[inline]
+    fn from(row: DuckDbRow) -> Self {
+        row.into_any(&mut Default::default())
+    }
+}
@@ -0,0 +1,72 @@
+use crate::column::ColumnIndex;
+use crate::duckdb::{DuckDb, DuckDbArguments, DuckDbColumn, DuckDbTypeInfo};
+use crate::error::Error;
+use crate::ext::ustr::UStr;
+use crate::statement::Statement;
+use crate::HashMap;
+use either::Either;
+use std::borrow::Cow;
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+#[allow(clippy::rc_buffer)]
+pub struct DuckDbStatement<'q> {
+    pub(crate) sql: Cow<'q, str>,
+    pub(crate) parameters: usize,
+    pub(crate) columns: Arc<Vec<DuckDbColumn>>,
+    pub(crate) column_names: Arc<HashMap<UStr, usize>>,
+}
+
+impl<'q> Statement<'q> for DuckDbStatement<'q> {
+    type Database = DuckDb;
+
+    fn to_owned(&self) -> DuckDbStatement<'static> {
+        DuckDbStatement::<'static> {
+            sql: Cow::Owned(self.sql.clone().into_owned()),
+            parameters: self.parameters,
+            columns: Arc::clone(&self.columns),
+            column_names: Arc::clone(&self.column_names),
+        }
+    }
+
+    fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    fn parameters(&self) -> Option<Either<&[DuckDbTypeInfo], usize>> {
+        Some(Either::Right(self.parameters))
+    }
+
+    fn columns(&self) -> &[DuckDbColumn] {
+        &self.columns
+    }
+
+    impl_statement_query!(DuckDbArguments<'_>);
+}
+
+impl ColumnIndex<DuckDbStatement<'_>> for &'_ str {
+    fn index(&self, statement: &DuckDbStatement<'_>) -> Result<usize, Error> {
+        statement
+            .column_names
+            .get(*self)
+            .ok_or_else(|| Error::ColumnNotFound((*self).into()))
+            .map(|v| *v)
+    }
+}
+
+#[cfg(feature = "any")]
+impl<'q> From<DuckDbStatement<'q>> for crate::any::AnyStatement<'q> {
+    #[inline]
+    fn from(statement: DuckDbStatement<'q>) -> Self {
+        crate::any::AnyStatement::<'q> {
+            columns: statement
+                .columns
+                .iter()
+                .map(|col| col.clone().into())
+                .collect(),
+            column_names: statement.column_names,
+            parameters: Some(Either::Right(statement.parameters)),
+            sql: statement.sql,
+        }
+    }
+}
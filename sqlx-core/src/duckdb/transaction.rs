@@ -0,0 +1,32 @@
+use futures_core::future::BoxFuture;
+
+use crate::duckdb::{DuckDb, DuckDbConnection};
+use crate::error::Error;
+use crate::transaction::TransactionManager;
+
+/// Implementation of [`TransactionManager`] for DuckDB.
+pub struct DuckDbTransactionManager;
+
+impl TransactionManager for DuckDbTransactionManager {
+    type Database = DuckDb;
+
+    fn begin(conn: &mut DuckDbConnection) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(conn.worker.begin())
+    }
+
+    fn commit(conn: &mut DuckDbConnection) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(conn.worker.commit())
+    }
+
+    fn rollback(conn: &mut DuckDbConnection) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(conn.worker.rollback())
+    }
+
+    fn start_rollback(conn: &mut DuckDbConnection) {
+        conn.worker.start_rollback().ok();
+    }
+
+    fn start_statement(conn: &mut DuckDbConnection, sql: &str) {
+        conn.worker.start_statement(sql).ok();
+    }
+}
@@ -0,0 +1,202 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::duckdb::ffi::*;
+use crate::type_info::TypeInfo;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "offline", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) enum DataType {
+    Null,
+    Boolean,
+    TinyInt,
+    SmallInt,
+    Integer,
+    BigInt,
+    HugeInt,
+    UTinyInt,
+    USmallInt,
+    UInteger,
+    UBigInt,
+    UHugeInt,
+    Float,
+    Double,
+    Decimal,
+    Varchar,
+    Blob,
+    Date,
+    Time,
+    TimeTz,
+    Timestamp,
+    TimestampS,
+    TimestampMs,
+    TimestampNs,
+    TimestampTz,
+    Interval,
+    Uuid,
+    Enum,
+    List,
+    Array,
+    Struct,
+    Map,
+    Union,
+    Bit,
+
+    // a type added by a newer version of DuckDB
+    Unknown,
+}
+
+/// Type information for a DuckDB type.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "offline", derive(serde::Serialize, serde::Deserialize))]
+pub struct DuckDbTypeInfo(pub(crate) DataType);
+
+impl Display for DuckDbTypeInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+impl TypeInfo for DuckDbTypeInfo {
+    fn is_null(&self) -> bool {
+        matches!(self.0, DataType::Null)
+    }
+
+    fn name(&self) -> &str {
+        match self.0 {
+            DataType::Null => "NULL",
+            DataType::Boolean => "BOOLEAN",
+            DataType::TinyInt => "TINYINT",
+            DataType::SmallInt => "SMALLINT",
+            DataType::Integer => "INTEGER",
+            DataType::BigInt => "BIGINT",
+            DataType::HugeInt => "HUGEINT",
+            DataType::UTinyInt => "UTINYINT",
+            DataType::USmallInt => "USMALLINT",
+            DataType::UInteger => "UINTEGER",
+            DataType::UBigInt => "UBIGINT",
+            DataType::UHugeInt => "UHUGEINT",
+            DataType::Float => "FLOAT",
+            DataType::Double => "DOUBLE",
+            DataType::Decimal => "DECIMAL",
+            DataType::Varchar => "VARCHAR",
+            DataType::Blob => "BLOB",
+            DataType::Date => "DATE",
+            DataType::Time => "TIME",
+            DataType::TimeTz => "TIMETZ",
+            DataType::Timestamp => "TIMESTAMP",
+            DataType::TimestampS => "TIMESTAMP_S",
+            DataType::TimestampMs => "TIMESTAMP_MS",
+            DataType::TimestampNs => "TIMESTAMP_NS",
+            DataType::TimestampTz => "TIMESTAMPTZ",
+            DataType::Interval => "INTERVAL",
+            DataType::Uuid => "UUID",
+            DataType::Enum => "ENUM",
+            DataType::List => "LIST",
+            DataType::Array => "ARRAY",
+            DataType::Struct => "STRUCT",
+            DataType::Map => "MAP",
+            DataType::Union => "UNION",
+            DataType::Bit => "BIT",
+            DataType::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+impl DataType {
+    // https://duckdb.org/docs/api/c/types
+    pub(crate) fn from_code(code: duckdb_type) -> Self {
+        match code {
+            // the type of parameters, and of columns which depend on them, before binding
+            DUCKDB_TYPE_INVALID | DUCKDB_TYPE_SQLNULL => DataType::Null,
+            DUCKDB_TYPE_BOOLEAN => DataType::Boolean,
+            DUCKDB_TYPE_TINYINT => DataType::TinyInt,
+            DUCKDB_TYPE_SMALLINT => DataType::SmallInt,
+            DUCKDB_TYPE_INTEGER => DataType::Integer,
+            DUCKDB_TYPE_BIGINT => DataType::BigInt,
+            DUCKDB_TYPE_HUGEINT => DataType::HugeInt,
+            DUCKDB_TYPE_UTINYINT => DataType::UTinyInt,
+            DUCKDB_TYPE_USMALLINT => DataType::USmallInt,
+            DUCKDB_TYPE_UINTEGER => DataType::UInteger,
+            DUCKDB_TYPE_UBIGINT => DataType::UBigInt,
+            DUCKDB_TYPE_UHUGEINT => DataType::UHugeInt,
+            DUCKDB_TYPE_FLOAT => DataType::Float,
+            DUCKDB_TYPE_DOUBLE => DataType::Double,
+            DUCKDB_TYPE_DECIMAL => DataType::Decimal,
+            DUCKDB_TYPE_VARCHAR => DataType::Varchar,
+            DUCKDB_TYPE_BLOB => DataType::Blob,
+            DUCKDB_TYPE_DATE => DataType::Date,
+            DUCKDB_TYPE_TIME => DataType::Time,
+            DUCKDB_TYPE_TIME_TZ => DataType::TimeTz,
+            DUCKDB_TYPE_TIMESTAMP => DataType::Timestamp,
+            DUCKDB_TYPE_TIMESTAMP_S => DataType::TimestampS,
+            DUCKDB_TYPE_TIMESTAMP_MS => DataType::TimestampMs,
+            DUCKDB_TYPE_TIMESTAMP_NS => DataType::TimestampNs,
+            DUCKDB_TYPE_TIMESTAMP_TZ => DataType::TimestampTz,
+            DUCKDB_TYPE_INTERVAL => DataType::Interval,
+            DUCKDB_TYPE_UUID => DataType::Uuid,
+            DUCKDB_TYPE_ENUM => DataType::Enum,
+            DUCKDB_TYPE_LIST => DataType::List,
+            DUCKDB_TYPE_ARRAY => DataType::Array,
+            DUCKDB_TYPE_STRUCT => DataType::Struct,
+            DUCKDB_TYPE_MAP => DataType::Map,
+            DUCKDB_TYPE_UNION => DataType::Union,
+            DUCKDB_TYPE_BIT => DataType::Bit,
+
+            _ => DataType::Unknown,
+        }
+    }
+
+    /// The types read as their text representation, as they have no Rust equivalent.
+    pub(crate) fn is_text(self) -> bool {
+        matches!(
+            self,
+            DataType::Varchar
+                | DataType::UHugeInt
+                | DataType::TimeTz
+                | DataType::Interval
+                | DataType::Uuid
+                | DataType::Enum
+                | DataType::List
+                | DataType::Array
+                | DataType::Struct
+                | DataType::Map
+                | DataType::Union
+                | DataType::Bit
+                | DataType::Unknown
+        )
+    }
+
+    pub(crate) fn is_timestamp(self) -> bool {
+        matches!(
+            self,
+            DataType::Timestamp
+                | DataType::TimestampS
+                | DataType::TimestampMs
+                | DataType::TimestampNs
+                | DataType::TimestampTz
+        )
+    }
+}
+
+#[cfg(feature = "any")]
+impl From<DuckDbTypeInfo> for crate::any::AnyTypeInfo {
+    #[inline]
+    fn from(ty: DuckDbTypeInfo) -> Self {
+        crate::any::AnyTypeInfo(crate::any::type_info::AnyTypeInfoKind::DuckDb(ty))
+    }
+}
+
+#[test]
+fn test_data_type_from_code() {
+    assert_eq!(DataType::from_code(DUCKDB_TYPE_INVALID), DataType::Null);
+    assert_eq!(DataType::from_code(DUCKDB_TYPE_BIGINT), DataType::BigInt);
+    assert_eq!(
+        DataType::from_code(DUCKDB_TYPE_UHUGEINT),
+        DataType::UHugeInt
+    );
+    assert_eq!(
+        DataType::from_code(DUCKDB_TYPE_TIMESTAMP_TZ),
+        DataType::TimestampTz
+    );
+    assert_eq!(DataType::from_code(1000), DataType::Unknown);
+}
@@ -0,0 +1,26 @@
+use crate::decode::Decode;
+use crate::duckdb::type_info::DataType;
+use crate::duckdb::{DuckDb, DuckDbArgumentValue, DuckDbTypeInfo, DuckDbValueRef};
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::types::Type;
+
+impl Type<DuckDb> for bool {
+    fn type_info() -> DuckDbTypeInfo {
+        DuckDbTypeInfo(DataType::Boolean)
+    }
+}
+
+impl<'q> Encode<'q, DuckDb> for bool {
+    fn encode_by_ref(&self, args: &mut Vec<DuckDbArgumentValue<'q>>) -> IsNull {
+        args.push(DuckDbArgumentValue::Boolean(*self));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for bool {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<bool, BoxDynError> {
+        value.bool()
+    }
+}
@@ -0,0 +1,62 @@
+use std::borrow::Cow;
+
+use crate::decode::Decode;
+use crate::duckdb::type_info::DataType;
+use crate::duckdb::{DuckDb, DuckDbArgumentValue, DuckDbTypeInfo, DuckDbValueRef};
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::types::Type;
+
+impl Type<DuckDb> for [u8] {
+    fn type_info() -> DuckDbTypeInfo {
+        DuckDbTypeInfo(DataType::Blob)
+    }
+
+    fn compatible(ty: &DuckDbTypeInfo) -> bool {
+        matches!(ty.0, DataType::Blob)
+    }
+}
+
+impl<'q> Encode<'q, DuckDb> for &'q [u8] {
+    fn encode_by_ref(&self, args: &mut Vec<DuckDbArgumentValue<'q>>) -> IsNull {
+        args.push(DuckDbArgumentValue::Blob(Cow::Borrowed(self)));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for &'r [u8] {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<Self, BoxDynError> {
+        value.blob()
+    }
+}
+
+impl Type<DuckDb> for Vec<u8> {
+    fn type_info() -> DuckDbTypeInfo {
+        <&[u8] as Type<DuckDb>>::type_info()
+    }
+
+    fn compatible(ty: &DuckDbTypeInfo) -> bool {
+        <&[u8] as Type<DuckDb>>::compatible(ty)
+    }
+}
+
+impl<'q> Encode<'q, DuckDb> for Vec<u8> {
+    fn encode(self, args: &mut Vec<DuckDbArgumentValue<'q>>) -> IsNull {
+        args.push(DuckDbArgumentValue::Blob(Cow::Owned(self)));
+
+        IsNull::No
+    }
+
+    fn encode_by_ref(&self, args: &mut Vec<DuckDbArgumentValue<'q>>) -> IsNull {
+        args.push(DuckDbArgumentValue::Blob(Cow::Owned(self.clone())));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for Vec<u8> {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<Self, BoxDynError> {
+        value.blob().map(ToOwned::to_owned)
+    }
+}
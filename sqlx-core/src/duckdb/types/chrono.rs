@@ -0,0 +1,147 @@
+use std::fmt::Display;
+
+use chrono::{
+    DateTime, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, Offset,
+    SecondsFormat, TimeZone, Utc,
+};
+
+use crate::decode::Decode;
+use crate::duckdb::type_info::DataType;
+use crate::duckdb::{DuckDb, DuckDbArgumentValue, DuckDbTypeInfo, DuckDbValueRef};
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::types::Type;
+
+// DuckDB stores dates as the days since the epoch, and times and timestamps as microseconds
+
+fn epoch() -> NaiveDateTime {
+    NaiveDate::from_ymd(1970, 1, 1).and_hms(0, 0, 0)
+}
+
+impl Type<DuckDb> for NaiveDate {
+    fn type_info() -> DuckDbTypeInfo {
+        DuckDbTypeInfo(DataType::Date)
+    }
+}
+
+impl Type<DuckDb> for NaiveTime {
+    fn type_info() -> DuckDbTypeInfo {
+        DuckDbTypeInfo(DataType::Time)
+    }
+}
+
+impl Type<DuckDb> for NaiveDateTime {
+    fn type_info() -> DuckDbTypeInfo {
+        DuckDbTypeInfo(DataType::Timestamp)
+    }
+
+    fn compatible(ty: &DuckDbTypeInfo) -> bool {
+        ty.0.is_timestamp()
+    }
+}
+
+impl<Tz: TimeZone> Type<DuckDb> for DateTime<Tz> {
+    fn type_info() -> DuckDbTypeInfo {
+        DuckDbTypeInfo(DataType::TimestampTz)
+    }
+
+    fn compatible(ty: &DuckDbTypeInfo) -> bool {
+        ty.0.is_timestamp()
+    }
+}
+
+impl Encode<'_, DuckDb> for NaiveDate {
+    fn encode_by_ref(&self, buf: &mut Vec<DuckDbArgumentValue<'_>>) -> IsNull {
+        let days = (*self - epoch().date()).num_days() as i32;
+        buf.push(DuckDbArgumentValue::Date(days));
+
+        IsNull::No
+    }
+}
+
+impl Encode<'_, DuckDb> for NaiveTime {
+    fn encode_by_ref(&self, buf: &mut Vec<DuckDbArgumentValue<'_>>) -> IsNull {
+        // a `NaiveTime` is less than a day, which is in range
+        let micros = (*self - NaiveTime::from_hms(0, 0, 0))
+            .num_microseconds()
+            .unwrap();
+
+        buf.push(DuckDbArgumentValue::Time(micros));
+
+        IsNull::No
+    }
+}
+
+impl Encode<'_, DuckDb> for NaiveDateTime {
+    fn encode_by_ref(&self, buf: &mut Vec<DuckDbArgumentValue<'_>>) -> IsNull {
+        // FIXME: We should *really* be returning an error, Encode needs to be fallible
+        let micros = (*self - epoch())
+            .num_microseconds()
+            .unwrap_or_else(|| panic!("NaiveDateTime out of range for DuckDB: {:?}", self));
+
+        buf.push(DuckDbArgumentValue::Timestamp(micros));
+
+        IsNull::No
+    }
+}
+
+impl<Tz: TimeZone> Encode<'_, DuckDb> for DateTime<Tz>
+where
+    Tz::Offset: Display,
+{
+    fn encode_by_ref(&self, buf: &mut Vec<DuckDbArgumentValue<'_>>) -> IsNull {
+        // with its offset, so that it's converted to `TIMESTAMPTZ` regardless of the time zone
+        // of the session
+        Encode::<DuckDb>::encode(self.to_rfc3339_opts(SecondsFormat::AutoSi, false), buf)
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for NaiveDate {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<Self, BoxDynError> {
+        let days = value.date()?;
+
+        epoch()
+            .date()
+            .checked_add_signed(Duration::days(days.into()))
+            .ok_or_else(|| format!("date out of range: {} days", days).into())
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for NaiveTime {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(NaiveTime::from_hms(0, 0, 0) + Duration::microseconds(value.time()?))
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for NaiveDateTime {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<Self, BoxDynError> {
+        let micros = value.timestamp()?;
+
+        // e.g. `'infinity'::TIMESTAMP`
+        epoch()
+            .checked_add_signed(Duration::microseconds(micros))
+            .ok_or_else(|| format!("timestamp out of range: {} microseconds", micros).into())
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for DateTime<Utc> {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<Self, BoxDynError> {
+        // `TIMESTAMPTZ` is stored in UTC
+        let naive = <NaiveDateTime as Decode<DuckDb>>::decode(value)?;
+        Ok(Utc.from_utc_datetime(&naive))
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for DateTime<Local> {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<Self, BoxDynError> {
+        let naive = <NaiveDateTime as Decode<DuckDb>>::decode(value)?;
+        Ok(Local.from_utc_datetime(&naive))
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for DateTime<FixedOffset> {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<Self, BoxDynError> {
+        let naive = <NaiveDateTime as Decode<DuckDb>>::decode(value)?;
+        Ok(Utc.fix().from_utc_datetime(&naive))
+    }
+}
@@ -0,0 +1,51 @@
+use crate::decode::Decode;
+use crate::duckdb::type_info::DataType;
+use crate::duckdb::{DuckDb, DuckDbArgumentValue, DuckDbTypeInfo, DuckDbValueRef};
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::types::Type;
+
+impl Type<DuckDb> for f32 {
+    fn type_info() -> DuckDbTypeInfo {
+        DuckDbTypeInfo(DataType::Float)
+    }
+}
+
+impl<'q> Encode<'q, DuckDb> for f32 {
+    fn encode_by_ref(&self, args: &mut Vec<DuckDbArgumentValue<'q>>) -> IsNull {
+        args.push(DuckDbArgumentValue::Float(*self));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for f32 {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<f32, BoxDynError> {
+        Ok(value.double()? as f32)
+    }
+}
+
+impl Type<DuckDb> for f64 {
+    fn type_info() -> DuckDbTypeInfo {
+        DuckDbTypeInfo(DataType::Double)
+    }
+
+    fn compatible(ty: &DuckDbTypeInfo) -> bool {
+        // `DECIMAL` is decoded as the nearest `f64`
+        matches!(ty.0, DataType::Float | DataType::Double | DataType::Decimal)
+    }
+}
+
+impl<'q> Encode<'q, DuckDb> for f64 {
+    fn encode_by_ref(&self, args: &mut Vec<DuckDbArgumentValue<'q>>) -> IsNull {
+        args.push(DuckDbArgumentValue::Double(*self));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for f64 {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<f64, BoxDynError> {
+        value.double()
+    }
+}
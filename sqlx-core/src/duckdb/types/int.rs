@@ -0,0 +1,122 @@
+use crate::decode::Decode;
+use crate::duckdb::type_info::DataType;
+use crate::duckdb::{DuckDb, DuckDbArgumentValue, DuckDbTypeInfo, DuckDbValueRef};
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::types::Type;
+
+impl Type<DuckDb> for i8 {
+    fn type_info() -> DuckDbTypeInfo {
+        DuckDbTypeInfo(DataType::TinyInt)
+    }
+
+    fn compatible(ty: &DuckDbTypeInfo) -> bool {
+        matches!(ty.0, DataType::TinyInt)
+    }
+}
+
+impl<'q> Encode<'q, DuckDb> for i8 {
+    fn encode_by_ref(&self, args: &mut Vec<DuckDbArgumentValue<'q>>) -> IsNull {
+        args.push(DuckDbArgumentValue::TinyInt(*self));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for i8 {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(value.int64()?.try_into()?)
+    }
+}
+
+impl Type<DuckDb> for i16 {
+    fn type_info() -> DuckDbTypeInfo {
+        DuckDbTypeInfo(DataType::SmallInt)
+    }
+
+    fn compatible(ty: &DuckDbTypeInfo) -> bool {
+        matches!(
+            ty.0,
+            DataType::TinyInt | DataType::SmallInt | DataType::UTinyInt
+        )
+    }
+}
+
+impl<'q> Encode<'q, DuckDb> for i16 {
+    fn encode_by_ref(&self, args: &mut Vec<DuckDbArgumentValue<'q>>) -> IsNull {
+        args.push(DuckDbArgumentValue::SmallInt(*self));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for i16 {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(value.int64()?.try_into()?)
+    }
+}
+
+impl Type<DuckDb> for i32 {
+    fn type_info() -> DuckDbTypeInfo {
+        DuckDbTypeInfo(DataType::Integer)
+    }
+
+    fn compatible(ty: &DuckDbTypeInfo) -> bool {
+        matches!(
+            ty.0,
+            DataType::TinyInt
+                | DataType::SmallInt
+                | DataType::Integer
+                | DataType::UTinyInt
+                | DataType::USmallInt
+        )
+    }
+}
+
+impl<'q> Encode<'q, DuckDb> for i32 {
+    fn encode_by_ref(&self, args: &mut Vec<DuckDbArgumentValue<'q>>) -> IsNull {
+        args.push(DuckDbArgumentValue::Integer(*self));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for i32 {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(value.int64()?.try_into()?)
+    }
+}
+
+impl Type<DuckDb> for i64 {
+    fn type_info() -> DuckDbTypeInfo {
+        DuckDbTypeInfo(DataType::BigInt)
+    }
+
+    fn compatible(ty: &DuckDbTypeInfo) -> bool {
+        matches!(
+            ty.0,
+            DataType::TinyInt
+                | DataType::SmallInt
+                | DataType::Integer
+                | DataType::BigInt
+                | DataType::HugeInt
+                | DataType::UTinyInt
+                | DataType::USmallInt
+                | DataType::UInteger
+        )
+    }
+}
+
+impl<'q> Encode<'q, DuckDb> for i64 {
+    fn encode_by_ref(&self, args: &mut Vec<DuckDbArgumentValue<'q>>) -> IsNull {
+        args.push(DuckDbArgumentValue::BigInt(*self));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for i64 {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<Self, BoxDynError> {
+        value.int64()
+    }
+}
@@ -0,0 +1,68 @@
+//! Conversions between Rust and **DuckDB** types.
+//!
+//! # Types
+//!
+//! | Rust type                             | DuckDB type(s)                                       |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `bool`                                | BOOLEAN                                              |
+//! | `i8`                                  | TINYINT                                              |
+//! | `i16`                                 | SMALLINT                                             |
+//! | `i32`                                 | INTEGER                                              |
+//! | `i64`                                 | BIGINT, HUGEINT                                      |
+//! | `u8`                                  | UTINYINT                                             |
+//! | `u16`                                 | USMALLINT                                            |
+//! | `u32`                                 | UINTEGER                                             |
+//! | `u64`                                 | UBIGINT                                              |
+//! | `f32`                                 | FLOAT                                                |
+//! | `f64`                                 | DOUBLE, DECIMAL                                      |
+//! | `&str`, [`String`]                    | VARCHAR                                              |
+//! | `&[u8]`, `Vec<u8>`                    | BLOB                                                 |
+//!
+//! #### Note: Integers
+//! The integer types also decode the narrower integer types. `i64` decodes `HUGEINT`, which is
+//! e.g. the type of `SUM()` of integers, and fails when the value doesn't fit.
+//!
+//! #### Note: Decimals
+//! `DECIMAL` is decoded as the nearest `f64`; cast it to `VARCHAR` in the query to decode its
+//! exact value as text.
+//!
+//! #### Note: Other types
+//! The types without a Rust equivalent, such as `INTERVAL`, `UHUGEINT`, `LIST`, `STRUCT` or
+//! `MAP`, decode to their text representation as [`String`].
+//!
+//! ### [`chrono`](https://crates.io/crates/chrono)
+//!
+//! Requires the `chrono` Cargo feature flag.
+//!
+//! | Rust type                             | DuckDB type(s)                                       |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `chrono::NaiveDateTime`               | TIMESTAMP                                            |
+//! | `chrono::DateTime<Utc>`               | TIMESTAMPTZ                                          |
+//! | `chrono::DateTime<Local>`             | TIMESTAMPTZ                                          |
+//! | `chrono::NaiveDate`                   | DATE                                                 |
+//! | `chrono::NaiveTime`                   | TIME                                                 |
+//!
+//! ### [`uuid`](https://crates.io/crates/uuid)
+//!
+//! Requires the `uuid` Cargo feature flag.
+//!
+//! | Rust type                             | DuckDB type(s)                                       |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `uuid::Uuid`                          | UUID                                                 |
+//!
+//! # Nullable
+//!
+//! In addition, `Option<T>` is supported where `T` implements `Type`. An `Option<T>` represents
+//! a potentially `NULL` value from DuckDB.
+//!
+
+mod bool;
+mod bytes;
+#[cfg(feature = "chrono")]
+mod chrono;
+mod float;
+mod int;
+mod str;
+mod uint;
+#[cfg(feature = "uuid")]
+mod uuid;
@@ -0,0 +1,88 @@
+use std::borrow::Cow;
+
+use crate::decode::Decode;
+use crate::duckdb::type_info::DataType;
+use crate::duckdb::{DuckDb, DuckDbArgumentValue, DuckDbTypeInfo, DuckDbValueRef};
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::types::Type;
+
+impl Type<DuckDb> for str {
+    fn type_info() -> DuckDbTypeInfo {
+        DuckDbTypeInfo(DataType::Varchar)
+    }
+
+    fn compatible(ty: &DuckDbTypeInfo) -> bool {
+        ty.0.is_text()
+    }
+}
+
+impl<'q> Encode<'q, DuckDb> for &'q str {
+    fn encode_by_ref(&self, args: &mut Vec<DuckDbArgumentValue<'q>>) -> IsNull {
+        args.push(DuckDbArgumentValue::Text(Cow::Borrowed(*self)));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for &'r str {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<Self, BoxDynError> {
+        value.text()
+    }
+}
+
+impl Type<DuckDb> for String {
+    fn type_info() -> DuckDbTypeInfo {
+        <&str as Type<DuckDb>>::type_info()
+    }
+}
+
+impl<'q> Encode<'q, DuckDb> for String {
+    fn encode(self, args: &mut Vec<DuckDbArgumentValue<'q>>) -> IsNull {
+        args.push(DuckDbArgumentValue::Text(Cow::Owned(self)));
+
+        IsNull::No
+    }
+
+    fn encode_by_ref(&self, args: &mut Vec<DuckDbArgumentValue<'q>>) -> IsNull {
+        args.push(DuckDbArgumentValue::Text(Cow::Owned(self.clone())));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for String {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<Self, BoxDynError> {
+        value.text().map(ToOwned::to_owned)
+    }
+}
+
+impl Type<DuckDb> for Cow<'_, str> {
+    fn type_info() -> DuckDbTypeInfo {
+        <&str as Type<DuckDb>>::type_info()
+    }
+
+    fn compatible(ty: &DuckDbTypeInfo) -> bool {
+        <&str as Type<DuckDb>>::compatible(ty)
+    }
+}
+
+impl<'q> Encode<'q, DuckDb> for Cow<'q, str> {
+    fn encode(self, args: &mut Vec<DuckDbArgumentValue<'q>>) -> IsNull {
+        args.push(DuckDbArgumentValue::Text(self));
+
+        IsNull::No
+    }
+
+    fn encode_by_ref(&self, args: &mut Vec<DuckDbArgumentValue<'q>>) -> IsNull {
+        args.push(DuckDbArgumentValue::Text(self.clone()));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for Cow<'r, str> {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<Self, BoxDynError> {
+        value.text().map(Cow::Borrowed)
+    }
+}
@@ -0,0 +1,108 @@
+use crate::decode::Decode;
+use crate::duckdb::type_info::DataType;
+use crate::duckdb::{DuckDb, DuckDbArgumentValue, DuckDbTypeInfo, DuckDbValueRef};
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::types::Type;
+
+impl Type<DuckDb> for u8 {
+    fn type_info() -> DuckDbTypeInfo {
+        DuckDbTypeInfo(DataType::UTinyInt)
+    }
+
+    fn compatible(ty: &DuckDbTypeInfo) -> bool {
+        matches!(ty.0, DataType::UTinyInt)
+    }
+}
+
+impl<'q> Encode<'q, DuckDb> for u8 {
+    fn encode_by_ref(&self, args: &mut Vec<DuckDbArgumentValue<'q>>) -> IsNull {
+        args.push(DuckDbArgumentValue::UTinyInt(*self));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for u8 {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(value.uint64()?.try_into()?)
+    }
+}
+
+impl Type<DuckDb> for u16 {
+    fn type_info() -> DuckDbTypeInfo {
+        DuckDbTypeInfo(DataType::USmallInt)
+    }
+
+    fn compatible(ty: &DuckDbTypeInfo) -> bool {
+        matches!(ty.0, DataType::UTinyInt | DataType::USmallInt)
+    }
+}
+
+impl<'q> Encode<'q, DuckDb> for u16 {
+    fn encode_by_ref(&self, args: &mut Vec<DuckDbArgumentValue<'q>>) -> IsNull {
+        args.push(DuckDbArgumentValue::USmallInt(*self));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for u16 {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(value.uint64()?.try_into()?)
+    }
+}
+
+impl Type<DuckDb> for u32 {
+    fn type_info() -> DuckDbTypeInfo {
+        DuckDbTypeInfo(DataType::UInteger)
+    }
+
+    fn compatible(ty: &DuckDbTypeInfo) -> bool {
+        matches!(
+            ty.0,
+            DataType::UTinyInt | DataType::USmallInt | DataType::UInteger
+        )
+    }
+}
+
+impl<'q> Encode<'q, DuckDb> for u32 {
+    fn encode_by_ref(&self, args: &mut Vec<DuckDbArgumentValue<'q>>) -> IsNull {
+        args.push(DuckDbArgumentValue::UInteger(*self));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for u32 {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(value.uint64()?.try_into()?)
+    }
+}
+
+impl Type<DuckDb> for u64 {
+    fn type_info() -> DuckDbTypeInfo {
+        DuckDbTypeInfo(DataType::UBigInt)
+    }
+
+    fn compatible(ty: &DuckDbTypeInfo) -> bool {
+        matches!(
+            ty.0,
+            DataType::UTinyInt | DataType::USmallInt | DataType::UInteger | DataType::UBigInt
+        )
+    }
+}
+
+impl<'q> Encode<'q, DuckDb> for u64 {
+    fn encode_by_ref(&self, args: &mut Vec<DuckDbArgumentValue<'q>>) -> IsNull {
+        args.push(DuckDbArgumentValue::UBigInt(*self));
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, DuckDb> for u64 {
+    fn decode(value: DuckDbValueRef<'r>) -> Result<Self, BoxDynError> {
+        value.uint64()
+    }
+}
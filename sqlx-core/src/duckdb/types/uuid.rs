@@ -0,0 +1,38 @@
+use std::borrow::Cow;
+
+use uuid::Uuid;
+
+use crate::decode::Decode;
+use crate::duckdb::type_info::DataType;
+use crate::duckdb::{DuckDb, DuckDbArgumentValue, DuckDbTypeInfo, DuckDbValueRef};
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::types::Type;
+
+impl Type<DuckDb> for Uuid {
+    fn type_info() -> DuckDbTypeInfo {
+        DuckDbTypeInfo(DataType::Uuid)
+    }
+
+    fn compatible(ty: &DuckDbTypeInfo) -> bool {
+        matches!(ty.0, DataType::Uuid | DataType::Varchar)
+    }
+}
+
+impl<'q> Encode<'q, DuckDb> for Uuid {
+    fn encode_by_ref(&self, args: &mut Vec<DuckDbArgumentValue<'q>>) -> IsNull {
+        // converted from its text representation by DuckDB
+        args.push(DuckDbArgumentValue::Text(Cow::Owned(
+            self.hyphenated().to_string(),
+        )));
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, DuckDb> for Uuid {
+    fn decode(value: DuckDbValueRef<'_>) -> Result<Self, BoxDynError> {
+        // the `UUID` values are read as their text representation
+        Ok(Uuid::parse_str(value.text()?)?)
+    }
+}
@@ -0,0 +1,252 @@
+use std::borrow::Cow;
+use std::ffi::CStr;
+use std::slice::from_raw_parts;
+
+use crate::duckdb::ffi::*;
+use crate::duckdb::type_info::DataType;
+use crate::duckdb::{DuckDb, DuckDbTypeInfo};
+use crate::error::BoxDynError;
+use crate::value::{Value, ValueRef};
+
+/// A value copied out of a DuckDB result.
+#[derive(Debug, Clone)]
+pub(crate) enum ValueData {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    HugeInt(i128),
+    Double(f64),
+    Text(String),
+    Blob(Vec<u8>),
+    // days since 1970-01-01
+    Date(i32),
+    // microseconds since midnight
+    Time(i64),
+    // microseconds since 1970-01-01 00:00:00
+    Timestamp(i64),
+}
+
+pub struct DuckDbValueRef<'r>(&'r DuckDbValue);
+
+impl<'r> DuckDbValueRef<'r> {
+    pub(crate) fn value(value: &'r DuckDbValue) -> Self {
+        Self(value)
+    }
+
+    pub(super) fn int64(&self) -> Result<i64, BoxDynError> {
+        match self.0.data {
+            ValueData::Int(v) => Ok(v),
+            ValueData::UInt(v) => Ok(v.try_into()?),
+            ValueData::HugeInt(v) => Ok(v.try_into()?),
+            _ => Err(self.0.unexpected()),
+        }
+    }
+
+    pub(super) fn uint64(&self) -> Result<u64, BoxDynError> {
+        match self.0.data {
+            ValueData::UInt(v) => Ok(v),
+            ValueData::Int(v) => Ok(v.try_into()?),
+            ValueData::HugeInt(v) => Ok(v.try_into()?),
+            _ => Err(self.0.unexpected()),
+        }
+    }
+
+    pub(super) fn bool(&self) -> Result<bool, BoxDynError> {
+        match self.0.data {
+            ValueData::Bool(v) => Ok(v),
+            _ => Err(self.0.unexpected()),
+        }
+    }
+
+    pub(super) fn double(&self) -> Result<f64, BoxDynError> {
+        match self.0.data {
+            ValueData::Double(v) => Ok(v),
+            _ => Err(self.0.unexpected()),
+        }
+    }
+
+    pub(super) fn text(&self) -> Result<&'r str, BoxDynError> {
+        match &self.0.data {
+            ValueData::Text(v) => Ok(v),
+            _ => Err(self.0.unexpected()),
+        }
+    }
+
+    pub(super) fn blob(&self) -> Result<&'r [u8], BoxDynError> {
+        match &self.0.data {
+            ValueData::Blob(v) => Ok(v),
+            _ => Err(self.0.unexpected()),
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    pub(super) fn date(&self) -> Result<i32, BoxDynError> {
+        match self.0.data {
+            ValueData::Date(v) => Ok(v),
+            _ => Err(self.0.unexpected()),
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    pub(super) fn time(&self) -> Result<i64, BoxDynError> {
+        match self.0.data {
+            ValueData::Time(v) => Ok(v),
+            _ => Err(self.0.unexpected()),
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    pub(super) fn timestamp(&self) -> Result<i64, BoxDynError> {
+        match self.0.data {
+            ValueData::Timestamp(v) => Ok(v),
+            _ => Err(self.0.unexpected()),
+        }
+    }
+}
+
+impl<'r> ValueRef<'r> for DuckDbValueRef<'r> {
+    type Database = DuckDb;
+
+    fn to_owned(&self) -> DuckDbValue {
+        self.0.clone()
+    }
+
+    fn type_info(&self) -> Cow<'_, DuckDbTypeInfo> {
+        self.0.type_info()
+    }
+
+    fn is_null(&self) -> bool {
+        self.0.is_null()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DuckDbValue {
+    pub(crate) data: ValueData,
+    pub(crate) type_info: DuckDbTypeInfo,
+}
+
+impl DuckDbValue {
+    /// Copies the value at `col` and `row` out of a materialized result.
+    ///
+    /// The `duckdb_value_*()` functions cast the value to the requested type, so the fixed-size
+    /// types are read in their widest form, and the types without a Rust equivalent (e.g.
+    /// `INTERVAL` or `LIST`) are read as their text representation.
+    pub(crate) unsafe fn read(
+        result: *mut duckdb_result,
+        col: idx_t,
+        row: idx_t,
+        type_info: DuckDbTypeInfo,
+    ) -> Self {
+        let data = if duckdb_value_is_null(result, col, row) {
+            ValueData::Null
+        } else {
+            match type_info.0 {
+                DataType::Boolean => ValueData::Bool(duckdb_value_boolean(result, col, row)),
+
+                DataType::TinyInt | DataType::SmallInt | DataType::Integer | DataType::BigInt => {
+                    ValueData::Int(duckdb_value_int64(result, col, row))
+                }
+
+                DataType::UTinyInt
+                | DataType::USmallInt
+                | DataType::UInteger
+                | DataType::UBigInt => ValueData::UInt(duckdb_value_uint64(result, col, row)),
+
+                DataType::HugeInt => {
+                    let v = duckdb_value_hugeint(result, col, row);
+                    ValueData::HugeInt(((v.upper as i128) << 64) | v.lower as i128)
+                }
+
+                // `DECIMAL` is converted to `DOUBLE`, which may lose precision
+                DataType::Float | DataType::Double | DataType::Decimal => {
+                    ValueData::Double(duckdb_value_double(result, col, row))
+                }
+
+                DataType::Blob => {
+                    let blob = duckdb_value_blob(result, col, row);
+                    let data = if blob.data.is_null() {
+                        Vec::new()
+                    } else {
+                        from_raw_parts(blob.data as *const u8, blob.size as usize).to_vec()
+                    };
+
+                    duckdb_free(blob.data);
+                    ValueData::Blob(data)
+                }
+
+                DataType::Date => ValueData::Date(duckdb_value_date(result, col, row).days),
+                DataType::Time => ValueData::Time(duckdb_value_time(result, col, row).micros),
+
+                ty if ty.is_timestamp() => {
+                    ValueData::Timestamp(duckdb_value_timestamp(result, col, row).micros)
+                }
+
+                _ => {
+                    let text = duckdb_value_varchar(result, col, row);
+
+                    if text.is_null() {
+                        ValueData::Null
+                    } else {
+                        let s = CStr::from_ptr(text).to_string_lossy().into_owned();
+                        duckdb_free(text.cast());
+                        ValueData::Text(s)
+                    }
+                }
+            }
+        };
+
+        Self { data, type_info }
+    }
+
+    fn unexpected(&self) -> BoxDynError {
+        format!(
+            "unexpected value {:?} for type {}",
+            self.data, self.type_info
+        )
+        .into()
+    }
+}
+
+impl Value for DuckDbValue {
+    type Database = DuckDb;
+
+    fn as_ref(&self) -> DuckDbValueRef<'_> {
+        DuckDbValueRef::value(self)
+    }
+
+    fn type_info(&self) -> Cow<'_, DuckDbTypeInfo> {
+        if self.is_null() {
+            Cow::Owned(DuckDbTypeInfo(DataType::Null))
+        } else {
+            Cow::Borrowed(&self.type_info)
+        }
+    }
+
+    fn is_null(&self) -> bool {
+        matches!(self.data, ValueData::Null)
+    }
+}
+
+#[cfg(feature = "any")]
+impl<'r> From<DuckDbValueRef<'r>> for crate::any::AnyValueRef<'r> {
+    #[inline]
+    fn from(value: DuckDbValueRef<'r>) -> Self {
+        crate::any::AnyValueRef {
+            type_info: value.type_info().clone().into_owned().into(),
+            kind: crate::any::value::AnyValueRefKind::DuckDb(value),
+        }
+    }
+}
+
+#[cfg(feature = "any")]
+impl From<DuckDbValue> for crate::any::AnyValue {
+    #[inline]
+    fn from(value: DuckDbValue) -> Self {
+        crate::any::AnyValue {
+            type_info: value.type_info().clone().into_owned().into(),
+            kind: crate::any::value::AnyValueKind::DuckDb(value),
+        }
+    }
+}
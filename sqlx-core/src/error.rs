@@ -96,6 +96,18 @@ pub enum Error {
     #[error("attempted to acquire a connection on a closed pool")]
     PoolClosed,
 
+    /// A transaction was rolled back once its [timeout] passed.
+    ///
+    /// [timeout]: crate::transaction::TransactionOptions::timeout
+    #[error("transaction timed out and was rolled back")]
+    TransactionTimedOut,
+
+    /// A message from the database was larger than the maximum size set on the connect options.
+    ///
+    /// The message was skipped without being buffered, and the connection remains usable.
+    #[error("message of {size} bytes from the database exceeds the maximum size of {max} bytes")]
+    MessageTooLarge { size: usize, max: usize },
+
     /// A background worker has crashed.
     #[error("attempted to communicate with a crashed background worker")]
     WorkerCrashed,
@@ -170,14 +182,107 @@ pub trait DatabaseError: 'static + Send + Sync + StdError {
         false
     }
 
+    /// The kind of the error, mapped from the codes of each database, to handle e.g. a
+    /// duplicate key the same way with any of them.
+    ///
+    /// ```rust,ignore
+    /// match sqlx::query("INSERT INTO users (email) VALUES ($1)").bind(email).execute(&pool).await {
+    ///     Err(Error::Database(e)) if e.is_unique_violation() => Err(SignupError::EmailTaken),
+    ///     result => result.map(drop).map_err(Into::into),
+    /// }
+    /// ```
+    ///
+    /// ### Note
+    /// Populated by the Postgres, MySQL and SQLite drivers; [`ErrorKind::Other`] for the others.
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+
     /// Returns the name of the constraint that triggered the error, if applicable.
     /// If the error was caused by a conflict of a unique index, this will be the index name.
     ///
     /// ### Note
-    /// Currently only populated by the Postgres driver.
+    /// Populated by the Postgres and MySQL drivers, and by the SQLite driver for the violations
+    /// of a `CHECK` constraint, which is the only one SQLite names.
     fn constraint(&self) -> Option<&str> {
         None
     }
+
+    /// Returns the name of the table the error is about, if applicable.
+    ///
+    /// ### Note
+    /// Populated by the Postgres, MySQL and SQLite drivers, for the violations of a constraint.
+    fn table(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns the name of the column the error is about, if applicable, e.g. the column which
+    /// can't be `NULL`; `None` if the constraint is on several columns.
+    ///
+    /// ### Note
+    /// Populated by the Postgres, MySQL and SQLite drivers, for the violations of a constraint.
+    fn column(&self) -> Option<&str> {
+        None
+    }
+
+    /// Whether the error is the violation of a unique constraint or index.
+    fn is_unique_violation(&self) -> bool {
+        matches!(self.kind(), ErrorKind::UniqueViolation)
+    }
+
+    /// Whether the error is the violation of a foreign key.
+    fn is_foreign_key_violation(&self) -> bool {
+        matches!(self.kind(), ErrorKind::ForeignKeyViolation)
+    }
+
+    /// Whether the error is the violation of a `CHECK` constraint.
+    fn is_check_violation(&self) -> bool {
+        matches!(self.kind(), ErrorKind::CheckViolation)
+    }
+
+    /// Whether the error is the violation of a `NOT NULL` constraint.
+    fn is_not_null_violation(&self) -> bool {
+        matches!(self.kind(), ErrorKind::NotNullViolation)
+    }
+
+    /// Whether the transaction failed because it conflicted with another one, so that it
+    /// should be retried: a serialization failure or a deadlock.
+    fn is_transaction_conflict(&self) -> bool {
+        matches!(
+            self.kind(),
+            ErrorKind::SerializationFailure | ErrorKind::Deadlock
+        )
+    }
+}
+
+/// The kind of a [`DatabaseError`], the same for every database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A row has the same value as another one in a unique constraint or index, e.g. the same
+    /// primary key.
+    UniqueViolation,
+
+    /// A row references a row which doesn't exist, or a row which is still referenced was
+    /// deleted.
+    ForeignKeyViolation,
+
+    /// A row doesn't satisfy a `CHECK` constraint.
+    CheckViolation,
+
+    /// A column which is `NOT NULL` was set to `NULL`.
+    NotNullViolation,
+
+    /// The transaction couldn't be serialized with a concurrent one, e.g. under the
+    /// `SERIALIZABLE` isolation level, and should be retried.
+    SerializationFailure,
+
+    /// The transaction was aborted to break a deadlock with a concurrent one, and should be
+    /// retried.
+    Deadlock,
+
+    /// Any other error.
+    Other,
 }
 
 impl dyn DatabaseError {
@@ -238,6 +343,9 @@ where
 {
     #[inline]
     fn from(error: E) -> Self {
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_error(&error);
+
         Error::Database(Box::new(error))
     }
 }
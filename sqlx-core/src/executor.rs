@@ -22,6 +22,11 @@ use std::fmt::Debug;
 ///  * [`&mut PoolConnection`](super::pool::PoolConnection)
 ///  * [`&mut Connection`](super::connection::Connection)
 ///
+/// The futures and streams of a query can be dropped at any point, e.g. by a timeout: the
+/// connection receives what's left of the response before its next query. The statement keeps
+/// running on the server meanwhile; the PostgreSQL and MySQL connections have a cancel token to
+/// stop it.
+///
 pub trait Executor<'c>: Send + Debug + Sized {
     type Database: Database;
 
@@ -57,6 +62,12 @@ pub trait Executor<'c>: Send + Debug + Sized {
     }
 
     /// Execute the query and return the generated results as a stream.
+    ///
+    /// The rows are read as the stream is polled: once a few rows are buffered ahead of the
+    /// consumer, the connection stops reading the response until the consumer takes one, so a
+    /// slow consumer doesn't make the rows pile up in memory. The size of this buffer is set
+    /// on the connect options, e.g. `row_buffer_size` and `row_buffer_bytes` for MySQL and
+    /// PostgreSQL, or `row_buffer_size` for SQLite.
     fn fetch<'e, 'q: 'e, E: 'q>(
         self,
         query: E,
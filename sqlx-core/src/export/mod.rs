@@ -0,0 +1,340 @@
+//! Writing the rows of a query as CSV or newline-delimited JSON, e.g. to the body of an export
+//! endpoint or to a file:
+//!
+//! ```rust,ignore
+//! let rows = sqlx::query("SELECT * FROM orders WHERE created_at > $1")
+//!     .bind(since)
+//!     .fetch(&pool);
+//!
+//! let mut file = tokio::fs::File::create("orders.csv").await?;
+//!
+//! sqlx::export::write_csv(rows, &mut file).await?;
+//! ```
+//!
+//! The rows are written as they're received, as anything implementing `Serialize` as a map or a
+//! struct: the rows of the databases which [implement it](crate::row::Row), serializing their
+//! values by their type, or the structs of the application deriving it, e.g. with
+//! [`query_as()`](crate::query_as::query_as).
+//!
+//! With the `parquet` feature, the [`parquet`] module writes them as a Parquet file.
+
+use futures_core::Stream;
+use futures_util::{pin_mut, TryStreamExt};
+use serde::ser::{self, Impossible, Serialize, SerializeMap, SerializeStruct, Serializer};
+use serde_json::Value;
+use sqlx_rt::{AsyncWrite, AsyncWriteExt};
+
+use crate::error::Error;
+
+#[cfg(feature = "parquet")]
+pub mod parquet;
+
+/// Writes each row as a JSON object on its own line, and returns the number of rows written.
+pub async fn write_ndjson<S, R, W>(rows: S, writer: &mut W) -> Result<u64, Error>
+where
+    S: Stream<Item = Result<R, Error>>,
+    R: Serialize,
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    pin_mut!(rows);
+
+    let mut line = Vec::new();
+    let mut count = 0;
+
+    while let Some(row) = rows.try_next().await? {
+        line.clear();
+
+        serde_json::to_writer(&mut line, &row).map_err(|e| Error::Decode(e.into()))?;
+        line.push(b'\n');
+
+        writer.write_all(&line).await?;
+        count += 1;
+    }
+
+    writer.flush().await?;
+
+    Ok(count)
+}
+
+/// Writes the rows as CSV, as described by [RFC 4180], after a header with the names of the
+/// columns of the first row, and returns the number of rows written; nothing is written if there
+/// are no rows.
+///
+/// A `NULL` is written as an empty field, and an empty string as `""`; the booleans as `true`
+/// and `false`, and the arrays and objects as JSON. The fields are quoted when they contain a
+/// comma, a quote, a line break, or begin or end with a space.
+///
+/// [RFC 4180]: https://www.rfc-editor.org/rfc/rfc4180
+pub async fn write_csv<S, R, W>(rows: S, writer: &mut W) -> Result<u64, Error>
+where
+    S: Stream<Item = Result<R, Error>>,
+    R: Serialize,
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    pin_mut!(rows);
+
+    let mut record = String::new();
+    let mut count = 0;
+
+    while let Some(row) = rows.try_next().await? {
+        let fields = row
+            .serialize(FieldsSerializer)
+            .map_err(|e| Error::Decode(e.into()))?;
+
+        record.clear();
+
+        if count == 0 {
+            write_record(
+                &mut record,
+                fields.iter().map(|(name, _)| Some(name.clone())),
+            );
+        }
+
+        write_record(
+            &mut record,
+            fields.into_iter().map(|(_, value)| field(value)),
+        );
+
+        writer.write_all(record.as_bytes()).await?;
+        count += 1;
+    }
+
+    writer.flush().await?;
+
+    Ok(count)
+}
+
+// the text of a field, or `None` for `NULL`
+fn field(value: Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        value @ (Value::Array(_) | Value::Object(_)) => Some(value.to_string()),
+    }
+}
+
+fn write_record(record: &mut String, fields: impl Iterator<Item = Option<String>>) {
+    for (i, field) in fields.enumerate() {
+        if i > 0 {
+            record.push(',');
+        }
+
+        let field = match field {
+            Some(field) => field,
+            None => continue,
+        };
+
+        let quoted = field.is_empty()
+            || field.contains([',', '"', '\r', '\n'])
+            || field.starts_with(' ')
+            || field.ends_with(' ');
+
+        if quoted {
+            record.push('"');
+            record.push_str(&field.replace('"', "\"\""));
+            record.push('"');
+        } else {
+            record.push_str(&field);
+        }
+    }
+
+    record.push_str("\r\n");
+}
+
+type Fields = Vec<(String, Value)>;
+
+// captures the entries of a row serialized as a map or a struct, in order
+struct FieldsSerializer;
+
+struct FieldsBuilder {
+    fields: Fields,
+    key: Option<String>,
+}
+
+fn not_a_row() -> serde_json::Error {
+    ser::Error::custom("a row must be serialized as a map or a struct")
+}
+
+macro_rules! not_a_row {
+    ($($method:ident($($arg:ty),*) -> $ret:ty;)*) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<$ret, Self::Error> {
+                Err(not_a_row())
+            }
+        )*
+    };
+}
+
+impl Serializer for FieldsSerializer {
+    type Ok = Fields;
+    type Error = serde_json::Error;
+
+    type SerializeSeq = Impossible<Fields, serde_json::Error>;
+    type SerializeTuple = Impossible<Fields, serde_json::Error>;
+    type SerializeTupleStruct = Impossible<Fields, serde_json::Error>;
+    type SerializeTupleVariant = Impossible<Fields, serde_json::Error>;
+    type SerializeMap = FieldsBuilder;
+    type SerializeStruct = FieldsBuilder;
+    type SerializeStructVariant = Impossible<Fields, serde_json::Error>;
+
+    not_a_row! {
+        serialize_bool(bool) -> Fields;
+        serialize_i8(i8) -> Fields;
+        serialize_i16(i16) -> Fields;
+        serialize_i32(i32) -> Fields;
+        serialize_i64(i64) -> Fields;
+        serialize_u8(u8) -> Fields;
+        serialize_u16(u16) -> Fields;
+        serialize_u32(u32) -> Fields;
+        serialize_u64(u64) -> Fields;
+        serialize_f32(f32) -> Fields;
+        serialize_f64(f64) -> Fields;
+        serialize_char(char) -> Fields;
+        serialize_str(&str) -> Fields;
+        serialize_bytes(&[u8]) -> Fields;
+        serialize_none() -> Fields;
+        serialize_unit() -> Fields;
+        serialize_unit_struct(&'static str) -> Fields;
+        serialize_unit_variant(&'static str, u32, &'static str) -> Fields;
+        serialize_seq(Option<usize>) -> Self::SerializeSeq;
+        serialize_tuple(usize) -> Self::SerializeTuple;
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Fields, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Fields, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Fields, Self::Error> {
+        Err(not_a_row())
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<FieldsBuilder, Self::Error> {
+        Ok(FieldsBuilder {
+            fields: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<FieldsBuilder, Self::Error> {
+        self.serialize_map(Some(len))
+    }
+}
+
+impl SerializeMap for FieldsBuilder {
+    type Ok = Fields;
+    type Error = serde_json::Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.key = Some(match serde_json::to_value(key)? {
+            Value::String(key) => key,
+            key => key.to_string(),
+        });
+
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let key = self.key.take().unwrap_or_default();
+
+        self.fields.push((key, serde_json::to_value(value)?));
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<Fields, Self::Error> {
+        Ok(self.fields)
+    }
+}
+
+impl SerializeStruct for FieldsBuilder {
+    type Ok = Fields;
+    type Error = serde_json::Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.fields
+            .push((key.to_owned(), serde_json::to_value(value)?));
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<Fields, Self::Error> {
+        Ok(self.fields)
+    }
+}
+
+#[test]
+fn test_write_csv() {
+    use std::collections::BTreeMap;
+
+    #[derive(serde::Serialize)]
+    struct User {
+        id: i64,
+        name: Option<&'static str>,
+        tags: Vec<&'static str>,
+    }
+
+    let users = vec![
+        Ok(User {
+            id: 1,
+            name: Some("Doe, \"Jane\""),
+            tags: vec!["a"],
+        }),
+        Ok(User {
+            id: 2,
+            name: None,
+            tags: vec![],
+        }),
+        Ok(User {
+            id: 3,
+            name: Some(""),
+            tags: vec![],
+        }),
+    ];
+
+    let mut out = Vec::new();
+
+    let count = sqlx_rt::block_on(write_csv(futures_util::stream::iter(users), &mut out)).unwrap();
+
+    assert_eq!(count, 3);
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "id,name,tags\r\n\
+         1,\"Doe, \"\"Jane\"\"\",\"[\"\"a\"\"]\"\r\n\
+         2,,[]\r\n\
+         3,\"\",[]\r\n"
+    );
+
+    let mut out = Vec::new();
+    let rows = futures_util::stream::iter(vec![Ok::<_, Error>(BTreeMap::from([("a", 1)]))]);
+
+    sqlx_rt::block_on(write_ndjson(rows, &mut out)).unwrap();
+
+    assert_eq!(out, b"{\"a\":1}\n");
+}
@@ -0,0 +1,637 @@
+//! Writing the rows of a query as a Parquet file, e.g. to archive them to object storage:
+//!
+//! ```rust,ignore
+//! let rows = sqlx::query("SELECT * FROM events WHERE day = $1")
+//!     .bind(day)
+//!     .fetch(&pool);
+//!
+//! let mut file = tokio::fs::File::create("events.parquet").await?;
+//!
+//! ParquetWriter::new()
+//!     .row_group_size(100_000)
+//!     .write(rows, &mut file)
+//!     .await?;
+//! ```
+//!
+//! The schema of the file is derived from the type of the columns of the first row, as
+//! [`ParquetDatabase::parquet_type()`] maps them. Every column is optional, `NULL` being a
+//! missing value. The pages are written uncompressed, with the plain encoding.
+
+use futures_core::Stream;
+use futures_util::{pin_mut, TryStreamExt};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx_rt::{AsyncWrite, AsyncWriteExt};
+
+use crate::column::Column;
+use crate::error::Error;
+use crate::row::Row;
+
+use super::FieldsSerializer;
+
+const MAGIC: &[u8] = b"PAR1";
+
+/// The type of a column of a Parquet file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParquetType {
+    Boolean,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    Float,
+    Double,
+
+    /// UTF-8 text; the values which aren't strings are written as JSON.
+    String,
+
+    /// JSON text.
+    Json,
+
+    Bytes,
+}
+
+/// A database whose rows can be written as Parquet.
+pub trait ParquetDatabase: crate::database::Database {
+    /// The type of the Parquet column of the values of type `ty`.
+    fn parquet_type(ty: &Self::TypeInfo) -> ParquetType;
+}
+
+/// Writes a stream of rows as a Parquet file; see the [module documentation][self].
+#[derive(Debug, Clone)]
+pub struct ParquetWriter {
+    row_group_size: usize,
+}
+
+impl Default for ParquetWriter {
+    fn default() -> Self {
+        Self {
+            row_group_size: 65_536,
+        }
+    }
+}
+
+impl ParquetWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of rows of a row group, which are kept in memory until the group is
+    /// written. Defaults to 65,536.
+    pub fn row_group_size(mut self, rows: usize) -> Self {
+        self.row_group_size = std::cmp::max(rows, 1);
+        self
+    }
+
+    /// Writes `rows` to `writer`, and returns the number of rows written.
+    ///
+    /// A file without rows has no columns, as their types are unknown.
+    pub async fn write<S, R, W>(&self, rows: S, writer: &mut W) -> Result<u64, Error>
+    where
+        S: Stream<Item = Result<R, Error>>,
+        R: Row + Serialize,
+        R::Database: ParquetDatabase,
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        pin_mut!(rows);
+
+        writer.write_all(MAGIC).await?;
+
+        let mut file = FileState {
+            offset: MAGIC.len() as u64,
+            num_rows: 0,
+            row_groups: Vec::new(),
+        };
+
+        let mut columns = Vec::new();
+        let mut group_rows = 0;
+
+        while let Some(row) = rows.try_next().await? {
+            if file.num_rows == 0 && group_rows == 0 {
+                columns = row
+                    .columns()
+                    .iter()
+                    .map(|column| ColumnBuffer {
+                        name: column.name().to_owned(),
+                        ty: R::Database::parquet_type(column.type_info()),
+                        levels: Vec::new(),
+                        values: Vec::new(),
+                        booleans: Vec::new(),
+                    })
+                    .collect();
+            }
+
+            let fields = row
+                .serialize(FieldsSerializer)
+                .map_err(|e| Error::Decode(e.into()))?;
+
+            for (column, (_, value)) in columns.iter_mut().zip(fields) {
+                column.push(value)?;
+            }
+
+            group_rows += 1;
+
+            if group_rows == self.row_group_size {
+                file.write_row_group(&mut columns, group_rows, writer)
+                    .await?;
+
+                group_rows = 0;
+            }
+        }
+
+        if group_rows > 0 {
+            file.write_row_group(&mut columns, group_rows, writer)
+                .await?;
+        }
+
+        let metadata = file.metadata(&columns);
+
+        writer.write_all(&metadata).await?;
+        writer
+            .write_all(&(metadata.len() as u32).to_le_bytes())
+            .await?;
+        writer.write_all(MAGIC).await?;
+        writer.flush().await?;
+
+        Ok(file.num_rows)
+    }
+}
+
+// the values of a column in the current row group
+struct ColumnBuffer {
+    name: String,
+    ty: ParquetType,
+
+    // whether each value is defined, i.e. not `NULL`
+    levels: Vec<bool>,
+
+    // the defined values, in the plain encoding, but for the booleans
+    values: Vec<u8>,
+    booleans: Vec<bool>,
+}
+
+impl ColumnBuffer {
+    fn push(&mut self, value: Value) -> Result<(), Error> {
+        if value.is_null() {
+            self.levels.push(false);
+
+            return Ok(());
+        }
+
+        let pushed = match (self.ty, &value) {
+            (ParquetType::Boolean, Value::Bool(value)) => {
+                self.booleans.push(*value);
+                true
+            }
+
+            (ParquetType::Int8, _) => self.push_int32(&value, i8::MIN.into(), i8::MAX.into()),
+            (ParquetType::Int16, _) => self.push_int32(&value, i16::MIN.into(), i16::MAX.into()),
+            (ParquetType::Int32, _) => self.push_int32(&value, i32::MIN, i32::MAX),
+
+            (ParquetType::Int64, Value::Number(n)) => match n.as_i64() {
+                Some(n) => {
+                    self.values.extend_from_slice(&n.to_le_bytes());
+                    true
+                }
+
+                None => false,
+            },
+
+            (ParquetType::Float, Value::Number(n)) => match n.as_f64() {
+                Some(n) => {
+                    self.values.extend_from_slice(&(n as f32).to_le_bytes());
+                    true
+                }
+
+                None => false,
+            },
+
+            (ParquetType::Double, Value::Number(n)) => match n.as_f64() {
+                Some(n) => {
+                    self.values.extend_from_slice(&n.to_le_bytes());
+                    true
+                }
+
+                None => false,
+            },
+
+            (ParquetType::String | ParquetType::Bytes, Value::String(s)) => {
+                self.push_bytes(s.as_bytes());
+                true
+            }
+
+            (ParquetType::String | ParquetType::Json, _) => {
+                self.push_bytes(value.to_string().as_bytes());
+                true
+            }
+
+            (ParquetType::Bytes, Value::Array(array)) => {
+                let bytes: Option<Vec<u8>> = array
+                    .iter()
+                    .map(|byte| byte.as_u64().and_then(|byte| u8::try_from(byte).ok()))
+                    .collect();
+
+                match bytes {
+                    Some(bytes) => {
+                        self.push_bytes(&bytes);
+                        true
+                    }
+
+                    None => false,
+                }
+            }
+
+            _ => false,
+        };
+
+        if !pushed {
+            return Err(Error::Decode(
+                format!(
+                    "cannot write the value {} of column `{}` as {:?}",
+                    value, self.name, self.ty
+                )
+                .into(),
+            ));
+        }
+
+        self.levels.push(true);
+
+        Ok(())
+    }
+
+    fn push_int32(&mut self, value: &Value, min: i32, max: i32) -> bool {
+        match value.as_i64().and_then(|n| i32::try_from(n).ok()) {
+            Some(n) if (min..=max).contains(&n) => {
+                self.values.extend_from_slice(&n.to_le_bytes());
+                true
+            }
+
+            _ => false,
+        }
+    }
+
+    fn push_bytes(&mut self, bytes: &[u8]) {
+        self.values
+            .extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        self.values.extend_from_slice(bytes);
+    }
+
+    // the type and the converted type of the column in the schema
+    fn schema_types(&self) -> (i32, Option<i32>) {
+        match self.ty {
+            ParquetType::Boolean => (TYPE_BOOLEAN, None),
+            ParquetType::Int8 => (TYPE_INT32, Some(CONVERTED_INT_8)),
+            ParquetType::Int16 => (TYPE_INT32, Some(CONVERTED_INT_16)),
+            ParquetType::Int32 => (TYPE_INT32, None),
+            ParquetType::Int64 => (TYPE_INT64, None),
+            ParquetType::Float => (TYPE_FLOAT, None),
+            ParquetType::Double => (TYPE_DOUBLE, None),
+            ParquetType::String => (TYPE_BYTE_ARRAY, Some(CONVERTED_UTF8)),
+            ParquetType::Json => (TYPE_BYTE_ARRAY, Some(CONVERTED_JSON)),
+            ParquetType::Bytes => (TYPE_BYTE_ARRAY, None),
+        }
+    }
+
+    // the data page of the values of the row group, emptying the buffer
+    fn take_page(&mut self) -> Vec<u8> {
+        let mut levels = Vec::new();
+        encode_levels(&self.levels, &mut levels);
+
+        let mut page = Vec::with_capacity(4 + levels.len() + self.values.len());
+
+        page.extend_from_slice(&(levels.len() as u32).to_le_bytes());
+        page.extend_from_slice(&levels);
+
+        if self.ty == ParquetType::Boolean {
+            for booleans in self.booleans.chunks(8) {
+                page.push(
+                    booleans
+                        .iter()
+                        .enumerate()
+                        .fold(0, |byte, (i, value)| byte | (u8::from(*value) << i)),
+                );
+            }
+        } else {
+            page.extend_from_slice(&self.values);
+        }
+
+        self.levels.clear();
+        self.values.clear();
+        self.booleans.clear();
+
+        page
+    }
+}
+
+// the definition levels of a column, in the RLE encoding, as runs of a bit width of 1
+fn encode_levels(levels: &[bool], out: &mut Vec<u8>) {
+    let mut i = 0;
+
+    while i < levels.len() {
+        let level = levels[i];
+        let run = levels[i..].iter().take_while(|l| **l == level).count();
+
+        write_varint(out, (run as u64) << 1);
+        out.push(level.into());
+
+        i += run;
+    }
+}
+
+struct FileState {
+    offset: u64,
+    num_rows: u64,
+    row_groups: Vec<RowGroup>,
+}
+
+struct RowGroup {
+    num_rows: u64,
+    chunks: Vec<ColumnChunk>,
+}
+
+struct ColumnChunk {
+    offset: u64,
+    size: u64,
+}
+
+impl FileState {
+    async fn write_row_group<W>(
+        &mut self,
+        columns: &mut [ColumnBuffer],
+        num_rows: usize,
+        writer: &mut W,
+    ) -> Result<(), Error>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let mut chunks = Vec::with_capacity(columns.len());
+
+        for column in columns {
+            let page = column.take_page();
+
+            let mut header = Thrift::new();
+
+            header.i32(1, PAGE_DATA);
+            header.i32(2, page.len() as i32);
+            header.i32(3, page.len() as i32);
+            header.begin_struct(5);
+            header.i32(1, num_rows as i32);
+            header.i32(2, ENCODING_PLAIN);
+            header.i32(3, ENCODING_RLE);
+            header.i32(4, ENCODING_RLE);
+            header.end();
+
+            let header = header.finish();
+
+            writer.write_all(&header).await?;
+            writer.write_all(&page).await?;
+
+            let size = (header.len() + page.len()) as u64;
+
+            chunks.push(ColumnChunk {
+                offset: self.offset,
+                size,
+            });
+
+            self.offset += size;
+        }
+
+        self.num_rows += num_rows as u64;
+        self.row_groups.push(RowGroup {
+            num_rows: num_rows as u64,
+            chunks,
+        });
+
+        Ok(())
+    }
+
+    // the `FileMetaData` of the footer
+    fn metadata(&self, columns: &[ColumnBuffer]) -> Vec<u8> {
+        let mut metadata = Thrift::new();
+
+        metadata.i32(1, 1);
+
+        metadata.begin_list(2, THRIFT_STRUCT, columns.len() + 1);
+        metadata.begin_element();
+        metadata.binary(4, b"schema");
+        metadata.i32(5, columns.len() as i32);
+        metadata.end();
+
+        for column in columns {
+            let (ty, converted_type) = column.schema_types();
+
+            metadata.begin_element();
+            metadata.i32(1, ty);
+            metadata.i32(3, REPETITION_OPTIONAL);
+            metadata.binary(4, column.name.as_bytes());
+
+            if let Some(converted_type) = converted_type {
+                metadata.i32(6, converted_type);
+            }
+
+            metadata.end();
+        }
+
+        metadata.i64(3, self.num_rows as i64);
+
+        metadata.begin_list(4, THRIFT_STRUCT, self.row_groups.len());
+
+        for row_group in &self.row_groups {
+            metadata.begin_element();
+            metadata.begin_list(1, THRIFT_STRUCT, row_group.chunks.len());
+
+            for (column, chunk) in columns.iter().zip(&row_group.chunks) {
+                metadata.begin_element();
+                metadata.i64(2, chunk.offset as i64);
+
+                metadata.begin_struct(3);
+                metadata.i32(1, column.schema_types().0);
+                metadata.begin_list(2, THRIFT_I32, 2);
+                metadata.i32_element(ENCODING_PLAIN);
+                metadata.i32_element(ENCODING_RLE);
+                metadata.begin_list(3, THRIFT_BINARY, 1);
+                metadata.binary_element(column.name.as_bytes());
+                metadata.i32(4, CODEC_UNCOMPRESSED);
+                metadata.i64(5, row_group.num_rows as i64);
+                metadata.i64(6, chunk.size as i64);
+                metadata.i64(7, chunk.size as i64);
+                metadata.i64(9, chunk.offset as i64);
+                metadata.end();
+
+                metadata.end();
+            }
+
+            let size: u64 = row_group.chunks.iter().map(|chunk| chunk.size).sum();
+
+            metadata.i64(2, size as i64);
+            metadata.i64(3, row_group.num_rows as i64);
+            metadata.end();
+        }
+
+        metadata.binary(
+            6,
+            concat!("sqlx version ", env!("CARGO_PKG_VERSION")).as_bytes(),
+        );
+
+        metadata.finish()
+    }
+}
+
+// https://github.com/apache/parquet-format/blob/master/src/main/thrift/parquet.thrift
+const TYPE_BOOLEAN: i32 = 0;
+const TYPE_INT32: i32 = 1;
+const TYPE_INT64: i32 = 2;
+const TYPE_FLOAT: i32 = 4;
+const TYPE_DOUBLE: i32 = 5;
+const TYPE_BYTE_ARRAY: i32 = 6;
+
+const CONVERTED_UTF8: i32 = 0;
+const CONVERTED_INT_8: i32 = 15;
+const CONVERTED_INT_16: i32 = 16;
+const CONVERTED_JSON: i32 = 19;
+
+const REPETITION_OPTIONAL: i32 = 1;
+
+const ENCODING_PLAIN: i32 = 0;
+const ENCODING_RLE: i32 = 3;
+
+const CODEC_UNCOMPRESSED: i32 = 0;
+
+const PAGE_DATA: i32 = 0;
+
+// https://github.com/apache/thrift/blob/master/doc/specs/thrift-compact-protocol.md
+const THRIFT_I32: u8 = 5;
+const THRIFT_I64: u8 = 6;
+const THRIFT_BINARY: u8 = 8;
+const THRIFT_LIST: u8 = 9;
+const THRIFT_STRUCT: u8 = 12;
+
+// a struct in the Thrift compact protocol
+struct Thrift {
+    buf: Vec<u8>,
+    last_field: i16,
+    outer_fields: Vec<i16>,
+}
+
+impl Thrift {
+    fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+            last_field: 0,
+            outer_fields: Vec::new(),
+        }
+    }
+
+    fn field(&mut self, id: i16, ty: u8) {
+        let delta = id - self.last_field;
+
+        if (1..=15).contains(&delta) {
+            self.buf.push((delta as u8) << 4 | ty);
+        } else {
+            self.buf.push(ty);
+            write_varint(&mut self.buf, zigzag(id.into()));
+        }
+
+        self.last_field = id;
+    }
+
+    fn i32(&mut self, id: i16, value: i32) {
+        self.field(id, THRIFT_I32);
+        self.i32_element(value);
+    }
+
+    fn i64(&mut self, id: i16, value: i64) {
+        self.field(id, THRIFT_I64);
+        write_varint(&mut self.buf, zigzag(value));
+    }
+
+    fn binary(&mut self, id: i16, value: &[u8]) {
+        self.field(id, THRIFT_BINARY);
+        self.binary_element(value);
+    }
+
+    fn begin_struct(&mut self, id: i16) {
+        self.field(id, THRIFT_STRUCT);
+        self.begin_element();
+    }
+
+    fn begin_list(&mut self, id: i16, element_type: u8, len: usize) {
+        self.field(id, THRIFT_LIST);
+
+        if len < 15 {
+            self.buf.push((len as u8) << 4 | element_type);
+        } else {
+            self.buf.push(0xf0 | element_type);
+            write_varint(&mut self.buf, len as u64);
+        }
+    }
+
+    fn i32_element(&mut self, value: i32) {
+        write_varint(&mut self.buf, zigzag(value.into()));
+    }
+
+    fn binary_element(&mut self, value: &[u8]) {
+        write_varint(&mut self.buf, value.len() as u64);
+        self.buf.extend_from_slice(value);
+    }
+
+    // begins a struct element of a list
+    fn begin_element(&mut self) {
+        self.outer_fields.push(self.last_field);
+        self.last_field = 0;
+    }
+
+    fn end(&mut self) {
+        self.buf.push(0);
+        self.last_field = self.outer_fields.pop().unwrap_or(0);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.buf.push(0);
+        self.buf
+    }
+}
+
+fn zigzag(n: i64) -> u64 {
+    ((n << 1) ^ (n >> 63)) as u64
+}
+
+fn write_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+
+    out.push(n as u8);
+}
+
+#[test]
+fn test_encode_levels() {
+    let mut out = Vec::new();
+
+    encode_levels(&[true, true, true, false, true], &mut out);
+
+    assert_eq!(out, [0x06, 0x01, 0x02, 0x00, 0x02, 0x01]);
+}
+
+#[test]
+fn test_thrift() {
+    let mut thrift = Thrift::new();
+
+    thrift.i32(1, 1);
+    thrift.i64(3, -2);
+    thrift.begin_list(4, THRIFT_STRUCT, 1);
+    thrift.begin_element();
+    thrift.binary(20, b"a");
+    thrift.end();
+    thrift.i32(5, 300);
+
+    assert_eq!(
+        thrift.finish(),
+        [
+            0x15, 0x02, 0x26, 0x03, 0x19, 0x1c, 0x08, 0x28, 0x01, b'a', 0x00, 0x15, 0xd8, 0x04,
+            0x00
+        ]
+    );
+}
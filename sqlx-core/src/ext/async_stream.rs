@@ -1,40 +1,144 @@
+use std::collections::VecDeque;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
 use std::task::{Context, Poll};
 
-use futures_channel::mpsc;
 use futures_core::future::BoxFuture;
 use futures_core::stream::Stream;
-use futures_util::{pin_mut, FutureExt, SinkExt};
+use futures_util::future::{Fuse, FusedFuture};
+use futures_util::FutureExt;
 
 use crate::error::Error;
 
+/// The limits of the items a stream produces ahead of its consumer.
+///
+/// The future producing the items is only polled when the stream is, and pauses once the buffer
+/// is full, e.g. not reading from the socket anymore, until the consumer takes an item.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BufferLimits {
+    // the number of items buffered, at least one
+    pub(crate) items: usize,
+
+    // the total size of the items buffered; an item larger than this is still buffered alone
+    pub(crate) bytes: Option<usize>,
+}
+
+impl Default for BufferLimits {
+    fn default() -> Self {
+        Self {
+            items: 1,
+            bytes: None,
+        }
+    }
+}
+
+struct Buffer<T> {
+    items: VecDeque<(Result<T, Error>, usize)>,
+    bytes: usize,
+    limits: BufferLimits,
+    size_of: fn(&T) -> usize,
+}
+
+impl<T> Buffer<T> {
+    fn has_room_for(&self, size: usize) -> bool {
+        self.items.is_empty()
+            || (self.items.len() < self.limits.items
+                && self
+                    .limits
+                    .bytes
+                    .map_or(true, |max| self.bytes + size <= max))
+    }
+
+    fn push(&mut self, item: Result<T, Error>, size: usize) {
+        self.bytes += size;
+        self.items.push_back((item, size));
+    }
+
+    fn pop(&mut self) -> Option<Result<T, Error>> {
+        let (item, size) = self.items.pop_front()?;
+        self.bytes -= size;
+
+        Some(item)
+    }
+}
+
+fn lock<T>(buffer: &Mutex<Buffer<T>>) -> MutexGuard<'_, Buffer<T>> {
+    // the items are valid even if a thread panicked while holding the lock
+    buffer.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+pub struct Sender<T> {
+    buffer: Arc<Mutex<Buffer<T>>>,
+}
+
+impl<T> Sender<T> {
+    // waits until the buffer has room for the item
+    pub async fn send(&mut self, item: T) {
+        let mut item = Some(item);
+
+        futures_util::future::poll_fn(|_| {
+            let mut buffer = lock(&self.buffer);
+            let size = item.as_ref().map_or(0, buffer.size_of);
+
+            if !buffer.has_room_for(size) {
+                // the stream polls this future again once it took an item
+                return Poll::Pending;
+            }
+
+            if let Some(item) = item.take() {
+                buffer.push(Ok(item), size);
+            }
+
+            Poll::Ready(())
+        })
+        .await
+    }
+}
+
 pub struct TryAsyncStream<'a, T> {
-    receiver: mpsc::Receiver<Result<T, Error>>,
-    future: BoxFuture<'a, Result<(), Error>>,
+    buffer: Arc<Mutex<Buffer<T>>>,
+    future: Fuse<BoxFuture<'a, ()>>,
 }
 
 impl<'a, T> TryAsyncStream<'a, T> {
     pub fn new<F, Fut>(f: F) -> Self
     where
-        F: FnOnce(mpsc::Sender<Result<T, Error>>) -> Fut + Send,
+        F: FnOnce(Sender<T>) -> Fut + Send,
+        Fut: 'a + Future<Output = Result<(), Error>> + Send,
+        T: 'a + Send,
+    {
+        Self::with_buffer(BufferLimits::default(), |_| 0, f)
+    }
+
+    pub(crate) fn with_buffer<F, Fut>(limits: BufferLimits, size_of: fn(&T) -> usize, f: F) -> Self
+    where
+        F: FnOnce(Sender<T>) -> Fut + Send,
         Fut: 'a + Future<Output = Result<(), Error>> + Send,
         T: 'a + Send,
     {
-        let (mut sender, receiver) = mpsc::channel(0);
+        let buffer = Arc::new(Mutex::new(Buffer {
+            items: VecDeque::new(),
+            bytes: 0,
+            limits,
+            size_of,
+        }));
+
+        let future = f(Sender {
+            buffer: Arc::clone(&buffer),
+        });
 
-        let future = f(sender.clone());
+        let errors = Arc::clone(&buffer);
         let future = async move {
             if let Err(error) = future.await {
-                let _ = sender.send(Err(error)).await;
+                // the error is the last item, regardless of the limits
+                lock(&errors).push(Err(error), 0);
             }
-
-            Ok(())
         }
-        .fuse()
-        .boxed();
+        .boxed()
+        .fuse();
 
-        Self { future, receiver }
+        Self { buffer, future }
     }
 }
 
@@ -42,32 +146,83 @@ impl<'a, T> Stream for TryAsyncStream<'a, T> {
     type Item = Result<T, Error>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let future = &mut self.future;
-        pin_mut!(future);
-
         // the future is fused so its safe to call forever
-        // the future advances our "stream"
-        // the future should be polled in tandem with the stream receiver
-        let _ = future.poll(cx);
-
-        let receiver = &mut self.receiver;
-        pin_mut!(receiver);
+        // the future advances our "stream", until it waits for more data or the buffer is full
+        if !self.future.is_terminated() {
+            let _ = self.future.poll_unpin(cx);
+        }
 
         // then we check to see if we have anything to return
-        receiver.poll_next(cx)
+        if let Some(item) = lock(&self.buffer).pop() {
+            return Poll::Ready(Some(item));
+        }
+
+        if self.future.is_terminated() {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
     }
 }
 
 macro_rules! try_stream {
+    (@buffer($limits:expr, $size_of:expr) $($block:tt)*) => {
+        crate::ext::async_stream::TryAsyncStream::with_buffer($limits, $size_of, move |mut sender| async move {
+            macro_rules! r#yield {
+                ($v:expr) => {{
+                    sender.send($v).await;
+                }}
+            }
+
+            $($block)*
+        })
+    };
+
     ($($block:tt)*) => {
         crate::ext::async_stream::TryAsyncStream::new(move |mut sender| async move {
             macro_rules! r#yield {
                 ($v:expr) => {{
-                    let _ = futures_util::sink::SinkExt::send(&mut sender, Ok($v)).await;
+                    sender.send($v).await;
                 }}
             }
 
             $($block)*
         })
-    }
+    };
+}
+
+#[test]
+fn test_buffer_limits() {
+    use futures_util::StreamExt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let produced = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&produced);
+
+    let limits = BufferLimits {
+        items: 3,
+        bytes: Some(10),
+    };
+
+    let mut stream = TryAsyncStream::with_buffer(
+        limits,
+        |item: &usize| *item,
+        move |mut sender| async move {
+            for _ in 0..10 {
+                counter.fetch_add(1, Ordering::SeqCst);
+                sender.send(4).await;
+            }
+
+            Ok(())
+        },
+    );
+
+    // the third item waits for room, as the buffer would hold 12 bytes
+    assert!(matches!(stream.next().now_or_never(), Some(Some(Ok(4)))));
+    assert_eq!(produced.load(Ordering::SeqCst), 3);
+
+    assert!(matches!(stream.next().now_or_never(), Some(Some(Ok(4)))));
+    assert_eq!(produced.load(Ordering::SeqCst), 4);
+
+    assert_eq!(stream.count().now_or_never(), Some(8));
 }
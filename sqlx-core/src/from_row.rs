@@ -92,6 +92,9 @@ use crate::row::Row;
 /// will set the value of the field `location` to the default value of `Option<String>`,
 /// which is `None`.
 ///
+/// To use another value, or for a type that doesn't implement [`Default`], give the path of a
+/// function returning the value, as in `#[sqlx(default = "default_location")]`.
+///
 /// ### `flatten`
 ///
 /// If you want to handle a field that implements [`FromRow`],
@@ -123,6 +126,137 @@ use crate::row::Row;
 ///
 /// This field is compatible with the `default` attribute.
 ///
+/// #### `prefix`
+///
+/// When a flattened struct is read from a join, its columns usually need aliases to avoid
+/// clashing with the columns of the outer struct. The `prefix` attribute, which can only be
+/// used together with `flatten`, reads each column of the nested struct from a column of
+/// the same name with the given prefix:
+///
+/// ```rust,ignore
+/// #[derive(sqlx::FromRow)]
+/// struct Author {
+///     id: i32,
+///     name: String,
+/// }
+///
+/// #[derive(sqlx::FromRow)]
+/// struct Post {
+///     id: i32,
+///     title: String,
+///     #[sqlx(flatten, prefix = "author_")]
+///     author: Author,
+/// }
+/// ```
+///
+/// Given a query such as:
+///
+/// ```sql
+/// SELECT p.id, p.title, a.id AS author_id, a.name AS author_name
+/// FROM posts p JOIN authors a ON a.id = p.author_id;
+/// ```
+///
+/// Prefixes are applied after `rename` and `rename_all` of the nested struct, and they
+/// combine: a struct flattened with `prefix = "b_"` inside one flattened with
+/// `prefix = "a_"` reads its columns from `a_b_*`.
+///
+/// The nested struct must implement [`FromRowPrefixed`], which `#[derive(FromRow)]`
+/// does for structs with named fields.
+///
+/// #### `json`
+///
+/// If a column holds JSON (`json`/`jsonb` in Postgres, `JSON` in MySQL or `TEXT` in SQLite),
+/// you can use the `json` attribute to deserialize it directly into any type implementing
+/// [`serde::Deserialize`] instead of declaring the field as [`Json<T>`][crate::types::Json].
+/// This requires the `json` feature.
+/// For example:
+///
+/// ```rust,ignore
+/// #[derive(serde::Deserialize, serde::Serialize)]
+/// struct Settings {
+///     theme: String,
+///     notifications: bool,
+/// }
+///
+/// #[derive(sqlx::FromRow)]
+/// struct User {
+///     id: i32,
+///     #[sqlx(json)]
+///     settings: Settings,
+/// }
+/// ```
+///
+/// To bind such a value, wrap a reference to it in [`Json`][crate::types::Json], e.g.
+/// `.bind(Json(&user.settings))`, which serializes it without taking ownership.
+///
+/// This attribute is compatible with `rename` and `default`, but not with `flatten`.
+///
+/// #### `decode_with`
+///
+/// To transform the value of a column while reading it, e.g. to decrypt it or to parse a legacy
+/// format, give the path of a function taking the value as decoded from the database and
+/// returning a `Result` with the value of the field. The type the column is decoded as, which
+/// the function takes, is given with `decode_as`:
+///
+/// ```rust,ignore
+/// struct Version(u32, u32);
+///
+/// fn parse_version(s: String) -> Result<Version, std::num::ParseIntError> {
+///     let (major, minor) = s.split_once('.').unwrap_or((&s, "0"));
+///
+///     Ok(Version(major.parse()?, minor.parse()?))
+/// }
+///
+/// #[derive(sqlx::FromRow)]
+/// struct Package {
+///     name: String,
+///     #[sqlx(decode_with = "parse_version", decode_as = "String")]
+///     version: Version,
+/// }
+/// ```
+///
+/// The error type of the function may be anything that converts into
+/// [`BoxDynError`][crate::error::BoxDynError], such as a `String` or any [`std::error::Error`],
+/// and is returned as [`Error::ColumnDecode`] for the column.
+///
+/// This attribute is compatible with `rename` and `default`, but not with `json` or `flatten`.
+///
+/// ### Generic structs
+///
+/// Structs may have type parameters. The derived implementation requires each field's type to
+/// be decodable (or [`FromRow`] for `flatten`, [`Default`] for `default`, etc.), so it applies
+/// to whichever instantiations of the struct satisfy that:
+///
+/// ```rust,ignore
+/// #[derive(sqlx::FromRow)]
+/// struct Tagged<T> {
+///     tag: String,
+///     #[sqlx(flatten)]
+///     item: T,
+/// }
+///
+/// let users: Vec<Tagged<User>> = sqlx::query_as("SELECT 'admin' AS tag, id, name FROM users")
+///     .fetch_all(&mut conn)
+///     .await?;
+/// ```
+///
+/// ### Tuple structs
+///
+/// For tuple structs, each field is read from the column at the same position, so the names of
+/// the columns don't matter:
+///
+/// ```rust,ignore
+/// #[derive(sqlx::FromRow)]
+/// struct Count(String, i64, #[sqlx(default)] Option<i64>);
+///
+/// let counts: Vec<Count> = sqlx::query_as("SELECT kind, count(*) FROM items GROUP BY kind")
+///     .fetch_all(&mut conn)
+///     .await?;
+/// ```
+///
+/// The `default` and `json` attributes may be used on their fields, where `default` applies if
+/// the row has fewer columns than the struct has fields. `rename` and `flatten` are not supported.
+///
 /// ## Manual implementation
 ///
 /// You can also implement the [`FromRow`] trait by hand. This can be useful if you
@@ -153,6 +287,15 @@ pub trait FromRow<'r, R: Row>: Sized {
     fn from_row(row: &'r R) -> Result<Self, Error>;
 }
 
+/// A record that can be built from a row whose column names have a common prefix.
+///
+/// This is used for fields marked `#[sqlx(flatten, prefix = "...")]` when deriving
+/// [`FromRow`], and is derived along with [`FromRow`] for structs with named fields.
+pub trait FromRowPrefixed<'r, R: Row>: Sized {
+    /// Build `Self` from `row`, reading each column `name` from `{prefix}{name}` instead.
+    fn from_row_prefixed(row: &'r R, prefix: &str) -> Result<Self, Error>;
+}
+
 // implement FromRow for tuples of types that implement Decode
 // up to tuples of 9 values
 
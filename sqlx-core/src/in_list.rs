@@ -0,0 +1,118 @@
+//! Runtime support for `{in: ...}` list arguments in the query macros on databases that need
+//! one bind parameter per element of the list (MySQL and SQLite).
+//!
+//! Not intended to be used directly.
+
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+use crate::HashMap;
+
+/// The SQL of a single `query!()` invocation, split at the placeholders of its list arguments.
+///
+/// Expanded SQL strings are generated on first use and cached for the lifetime of the program.
+/// Since a query has to borrow its SQL, each cached string is leaked. To keep this bounded, list
+/// lengths are rounded up to the next power of two, see [`padded()`].
+pub struct InListSql {
+    parts: &'static [&'static str],
+    cache: Lazy<Mutex<HashMap<Vec<usize>, &'static str>>>,
+}
+
+/// Substituted for the placeholder of an empty list.
+const EMPTY_LIST: &str = "SELECT NULL FROM (SELECT 1) AS t WHERE 1=0";
+
+impl InListSql {
+    /// `parts` must have one more element than there are list arguments.
+    pub const fn new(parts: &'static [&'static str]) -> Self {
+        InListSql {
+            parts,
+            cache: Lazy::new(|| Mutex::new(HashMap::default())),
+        }
+    }
+
+    /// Get the SQL with each list placeholder expanded for a list of the given length.
+    pub fn sql(&'static self, lens: &[usize]) -> &'static str {
+        assert_eq!(
+            lens.len() + 1,
+            self.parts.len(),
+            "expected one length per list argument"
+        );
+
+        let padded_lens: Vec<usize> = lens.iter().copied().map(padded_len).collect();
+
+        let mut cache = self
+            .cache
+            .lock()
+            .unwrap_or_else(|poison_err| poison_err.into_inner());
+
+        if let Some(sql) = cache.get(&padded_lens) {
+            return sql;
+        }
+
+        let mut sql = String::from(self.parts[0]);
+
+        for (&len, part) in padded_lens.iter().zip(&self.parts[1..]) {
+            if len == 0 {
+                // `IN ()` is a syntax error in MySQL and `IN (NULL)` is never true, not even for
+                // `NOT IN`; an empty subquery makes `IN` always false and `NOT IN` always true
+                sql.push_str(EMPTY_LIST);
+            } else {
+                sql.push('?');
+
+                for _ in 1..len {
+                    sql.push_str(", ?");
+                }
+            }
+
+            sql.push_str(part);
+        }
+
+        let sql: &'static str = Box::leak(sql.into_boxed_str());
+        cache.insert(padded_lens, sql);
+
+        sql
+    }
+}
+
+/// The number of bind parameters used for a list of `len` elements.
+fn padded_len(len: usize) -> usize {
+    if len == 0 {
+        0
+    } else {
+        len.next_power_of_two()
+    }
+}
+
+/// Iterate over the elements of `list`, repeating the last element to fill out the padding
+/// added by [`InListSql::sql()`]. Duplicates do not change the result of `[NOT] IN (...)`.
+pub fn padded<T>(list: &[T]) -> impl Iterator<Item = &T> {
+    let padding = padded_len(list.len()) - list.len();
+
+    list.iter()
+        .chain(list.last().into_iter().cycle().take(padding))
+}
+
+#[test]
+fn test_in_list_sql() {
+    static SQL: InListSql =
+        InListSql::new(&["SELECT * FROM foo WHERE a IN (", ") AND b IN (", ")"]);
+
+    assert_eq!(
+        SQL.sql(&[1, 3]),
+        "SELECT * FROM foo WHERE a IN (?) AND b IN (?, ?, ?, ?)"
+    );
+    assert_eq!(
+        SQL.sql(&[0, 4]),
+        "SELECT * FROM foo WHERE a IN (SELECT NULL FROM (SELECT 1) AS t WHERE 1=0) \
+         AND b IN (?, ?, ?, ?)"
+    );
+    assert!(std::ptr::eq(SQL.sql(&[1, 3]), SQL.sql(&[1, 4])));
+}
+
+#[test]
+fn test_padded() {
+    assert_eq!(padded(&[1, 2, 3]).collect::<Vec<_>>(), [&1, &2, &3, &3]);
+    assert_eq!(padded(&[1, 2]).collect::<Vec<_>>(), [&1, &2]);
+    assert_eq!(padded::<i32>(&[]).count(), 0);
+}
@@ -1,16 +1,21 @@
 #![allow(dead_code)]
 
+use std::cmp;
 use std::io;
 use std::ops::{Deref, DerefMut};
 
-use bytes::BytesMut;
+use bytes::{Buf, BytesMut};
 use sqlx_rt::{AsyncRead, AsyncReadExt, AsyncWrite};
 
+use crate::buffer_pool;
 use crate::error::Error;
 use crate::io::write_and_flush::WriteAndFlush;
 use crate::io::{decode::Decode, encode::Encode};
 use std::io::Cursor;
 
+// the size of the chunks a skipped message is read in
+const SKIP_CHUNK_SIZE: usize = 64 * 1024;
+
 pub struct BufStream<S>
 where
     S: AsyncRead + AsyncWrite + Unpin,
@@ -23,6 +28,9 @@ where
 
     // we read into the read buffer using 100% safe code
     rbuf: BytesMut,
+
+    // the number of bytes to discard before the next read, left by `skip`
+    skipping: usize,
 }
 
 impl<S> BufStream<S>
@@ -32,8 +40,9 @@ where
     pub fn new(stream: S) -> Self {
         Self {
             stream,
-            wbuf: Vec::with_capacity(512),
-            rbuf: BytesMut::with_capacity(4096),
+            wbuf: buffer_pool::take_write_buf(),
+            rbuf: buffer_pool::take_read_buf(),
+            skipping: 0,
         }
     }
 
@@ -48,6 +57,12 @@ where
     where
         T: Encode<'en, C>,
     {
+        // a buffer which grew to fit a large message is swapped for a smaller one once
+        // it's flushed
+        if self.wbuf.is_empty() && buffer_pool::is_oversized(self.wbuf.capacity()) {
+            self.wbuf = buffer_pool::take_write_buf();
+        }
+
         value.encode_with(&mut self.wbuf, context);
     }
 
@@ -72,16 +87,68 @@ where
         T::decode_with(self.read_raw(cnt).await?.freeze(), context)
     }
 
+    // the bytes read into the buffer are kept until they're all there, so a read that's
+    // cancelled is resumed by the next one
     pub async fn read_raw(&mut self, cnt: usize) -> Result<BytesMut, Error> {
-        read_raw_into(&mut self.stream, &mut self.rbuf, cnt).await?;
+        self.fill(cnt).await?;
         let buf = self.rbuf.split_to(cnt);
 
         Ok(buf)
     }
 
+    // the next `cnt` bytes, without consuming them
+    pub async fn peek(&mut self, cnt: usize) -> Result<&[u8], Error> {
+        self.fill(cnt).await?;
+
+        Ok(&self.rbuf[..cnt])
+    }
+
+    async fn fill(&mut self, cnt: usize) -> Result<(), Error> {
+        self.skip_pending().await?;
+
+        if self.rbuf.len() < cnt {
+            let missing = cnt - self.rbuf.len();
+            read_raw_into(&mut self.stream, &mut self.rbuf, missing).await?;
+        }
+
+        Ok(())
+    }
+
     pub async fn read_raw_into(&mut self, buf: &mut BytesMut, cnt: usize) -> Result<(), Error> {
         read_raw_into(&mut self.stream, buf, cnt).await
     }
+
+    // discard the next `cnt` bytes before the next read, which reads them in chunks instead of
+    // buffering them all
+    pub fn skip(&mut self, cnt: usize) {
+        self.skipping += cnt;
+    }
+
+    async fn skip_pending(&mut self) -> Result<(), Error> {
+        while self.skipping > 0 {
+            if self.rbuf.is_empty() {
+                let chunk = cmp::min(self.skipping, SKIP_CHUNK_SIZE);
+                read_raw_into(&mut self.stream, &mut self.rbuf, chunk).await?;
+            }
+
+            let cnt = cmp::min(self.skipping, self.rbuf.len());
+
+            self.rbuf.advance(cnt);
+            self.skipping -= cnt;
+        }
+
+        Ok(())
+    }
+}
+
+impl<S> Drop for BufStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn drop(&mut self) {
+        buffer_pool::recycle_write_buf(std::mem::take(&mut self.wbuf));
+        buffer_pool::recycle_read_buf(std::mem::take(&mut self.rbuf));
+    }
 }
 
 impl<S> Deref for BufStream<S>
@@ -8,6 +8,10 @@ use std::task::{Context, Poll};
 
 // Atomic operation that writes the full buffer to the stream, flushes the stream, and then
 // clears the buffer (even if either of the two previous operations failed).
+//
+// If it's dropped before completing, only the bytes written are removed from the buffer: the
+// rest of the messages are sent by the next flush, so that the server doesn't receive half of
+// a message followed by the next one.
 pub struct WriteAndFlush<'a, S> {
     pub(super) stream: &'a mut S,
     pub(super) buf: Cursor<&'a mut Vec<u8>>,
@@ -26,7 +30,15 @@ impl<S: AsyncWrite + Unpin> Future for WriteAndFlush<'_, S> {
             let read = buf.fill_buf()?;
 
             if !read.is_empty() {
-                let written = ready!(Pin::new(&mut *stream).poll_write(cx, read)?);
+                let written = match ready!(Pin::new(&mut *stream).poll_write(cx, read)) {
+                    Ok(written) => written,
+                    Err(error) => {
+                        // the connection is broken, nothing else is sent
+                        buf.get_mut().clear();
+                        return Poll::Ready(Err(error.into()));
+                    }
+                };
+
                 buf.consume(written);
             } else {
                 break;
@@ -39,7 +51,8 @@ impl<S: AsyncWrite + Unpin> Future for WriteAndFlush<'_, S> {
 
 impl<'a, S> Drop for WriteAndFlush<'a, S> {
     fn drop(&mut self) {
-        // clear the buffer regardless of whether the flush succeeded or not
-        self.buf.get_mut().clear();
+        // remove the bytes written, whether the flush succeeded or not
+        let written = self.buf.position() as usize;
+        self.buf.get_mut().drain(..written);
     }
 }
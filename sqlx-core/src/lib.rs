@@ -10,11 +10,17 @@
 // <https://doc.rust-lang.org/unstable-book/language-features/doc-cfg.html>
 #![cfg_attr(docsrs, feature(doc_cfg))]
 //
-// When compiling with support for SQLite we must allow some unsafe code in order to
-// interface with the inherently unsafe C module. This unsafe code is contained
-// to the sqlite module.
-#![cfg_attr(feature = "sqlite", deny(unsafe_code))]
-#![cfg_attr(not(feature = "sqlite"), forbid(unsafe_code))]
+// When compiling with support for SQLite, DuckDB or GSSAPI we must allow some unsafe code in
+// order to interface with the inherently unsafe C libraries. This unsafe code is contained
+// to the sqlite and duckdb modules and the postgres gss module.
+#![cfg_attr(
+    any(feature = "sqlite", feature = "duckdb", feature = "postgres-gssapi"),
+    deny(unsafe_code)
+)]
+#![cfg_attr(
+    not(any(feature = "sqlite", feature = "duckdb", feature = "postgres-gssapi")),
+    forbid(unsafe_code)
+)]
 
 #[cfg(feature = "bigdecimal")]
 extern crate bigdecimal_ as bigdecimal;
@@ -36,6 +42,9 @@ pub mod connection;
 #[macro_use]
 pub mod transaction;
 
+#[macro_use]
+pub mod reconnect;
+
 #[macro_use]
 pub mod encode;
 
@@ -57,19 +66,36 @@ pub mod column;
 #[macro_use]
 pub mod statement;
 
+#[cfg(feature = "config")]
+pub mod config;
+
 mod common;
 pub use either::Either;
+pub mod buffer_pool;
 pub mod database;
 pub mod describe;
 pub mod executor;
+#[cfg(feature = "json")]
+pub mod export;
 pub mod from_row;
+#[doc(hidden)]
+pub mod in_list;
 mod io;
 mod logger;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 mod net;
+pub mod observer;
 pub mod query_as;
+pub mod query_batch;
 pub mod query_builder;
+pub mod query_log;
 pub mod query_scalar;
 pub mod row;
+pub mod runtime;
+pub mod service;
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+pub mod sqlcommenter;
 pub mod type_info;
 pub mod value;
 
@@ -81,12 +107,25 @@ pub mod migrate;
         feature = "postgres",
         feature = "mysql",
         feature = "mssql",
-        feature = "sqlite"
+        feature = "sqlite",
+        feature = "duckdb"
     ),
     feature = "any"
 ))]
 pub mod any;
 
+#[cfg(all(
+    any(
+        feature = "postgres",
+        feature = "mysql",
+        feature = "mssql",
+        feature = "sqlite",
+        feature = "duckdb"
+    ),
+    feature = "mock"
+))]
+pub mod mock;
+
 #[cfg(feature = "postgres")]
 #[cfg_attr(docsrs, doc(cfg(feature = "postgres")))]
 pub mod postgres;
@@ -103,6 +142,10 @@ pub mod mysql;
 #[cfg_attr(docsrs, doc(cfg(feature = "mssql")))]
 pub mod mssql;
 
+#[cfg(feature = "duckdb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "duckdb")))]
+pub mod duckdb;
+
 /// sqlx uses ahash for increased performance, at the cost of reduced DoS resistance.
 use ahash::AHashMap as HashMap;
 //type HashMap<K, V> = std::collections::HashMap<K, V, ahash::RandomState>;
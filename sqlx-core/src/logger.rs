@@ -1,4 +1,7 @@
 use crate::connection::LogSettings;
+use crate::error::Error;
+use crate::observer::{self, StatementError, StatementEvent, Tags};
+use crate::query_log::QueryRecord;
 #[cfg(feature = "sqlite")]
 use std::collections::HashSet;
 #[cfg(feature = "sqlite")]
@@ -13,6 +16,9 @@ pub(crate) struct QueryLogger<'q> {
     rows_affected: u64,
     start: Instant,
     settings: LogSettings,
+    // the tags of the scope the statement was sent in, and the error it failed with
+    tags: Option<Tags>,
+    error: Option<StatementError>,
 }
 
 impl<'q> QueryLogger<'q> {
@@ -23,6 +29,14 @@ impl<'q> QueryLogger<'q> {
             rows_affected: 0,
             start: Instant::now(),
             settings,
+            tags: observer::current_tags(),
+            error: None,
+        }
+    }
+
+    pub(crate) fn fail(&mut self, error: &Error) {
+        if self.error.is_none() {
+            self.error = Some(StatementError::new(error));
         }
     }
 
@@ -37,12 +51,44 @@ impl<'q> QueryLogger<'q> {
     pub(crate) fn finish(&self) {
         let elapsed = self.start.elapsed();
 
-        let lvl = if elapsed >= self.settings.slow_statements_duration {
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_query(self.sql, elapsed);
+
+        if observer::is_observed() {
+            observer::notify(&StatementEvent {
+                sql: self.sql,
+                elapsed,
+                rows_affected: self.rows_affected,
+                rows_returned: self.rows_returned,
+                error: self.error.as_ref(),
+                tags: self.tags.as_deref().unwrap_or_default(),
+            });
+        }
+
+        let slow = elapsed >= self.settings.slow_statements_duration;
+
+        let lvl = if slow {
             self.settings.slow_statements_level
         } else {
             self.settings.statements_level
         };
 
+        if let Some(query_log) = &self.settings.query_log {
+            if let Some(level) = lvl.to_level() {
+                query_log.log(&QueryRecord {
+                    sql: self.sql,
+                    level,
+                    slow,
+                    elapsed,
+                    rows_affected: self.rows_affected,
+                    rows_returned: self.rows_returned,
+                    connection_id: self.settings.connection_id,
+                });
+            }
+
+            return;
+        }
+
         if let Some(lvl) = lvl
             .to_level()
             .filter(|lvl| log::log_enabled!(target: "sqlx::query", *lvl))
@@ -0,0 +1,404 @@
+//! Metrics of the pools, queries and transactions of the process, exported in the Prometheus text
+//! format.
+//!
+//! They're collected once the `metrics` feature is enabled, and [rendered](render) for a
+//! scrape, e.g. by the `/metrics` endpoint of a service:
+//!
+//! | Metric | Type | Labels |
+//! |---|---|---|
+//! | `sqlx_pool_connections`: the connections opened by the pool | gauge | `pool` |
+//! | `sqlx_pool_idle_connections`: the connections waiting in the pool | gauge | `pool` |
+//! | `sqlx_pool_max_connections`: the maximum size of the pool | gauge | `pool` |
+//! | `sqlx_pool_acquire_duration_seconds`: the time to acquire a connection | histogram | `pool` |
+//! | `sqlx_pool_acquire_timeouts_total`: the acquires which timed out | counter | `pool` |
+//! | `sqlx_query_duration_seconds`: the time of the queries, until their last row | histogram | `family` |
+//! | `sqlx_query_errors_total`: the errors returned by the database | counter | `code` |
+//! | `sqlx_transaction_duration_seconds`: the time the transactions were open | histogram | `outcome` |
+//! | `sqlx_transaction_statements_total`: the statements executed in transactions | counter | `outcome` |
+//!
+//! The pools are numbered in the order they're created, from `1`. The family of a query is its
+//! first keyword, among `select`, `insert`, `update`, `delete`, `merge`, `with`, `call`,
+//! `begin`, `commit`, `rollback`, `savepoint`, `copy` and `ddl` for the statements creating,
+//! altering or dropping objects; it's `other` for the rest. The code of an error is its SQLSTATE,
+//! or the code of the database if it has no SQLSTATE. The outcome of a transaction is `commit`,
+//! `rollback`, or `dropped` if it was dropped while open; its savepoints aren't counted apart.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, Weak};
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+
+use crate::error::DatabaseError;
+use crate::observer::TransactionEventKind;
+
+// the upper bounds of the buckets of the histograms, in seconds
+const BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+const FAMILIES: [&str; 14] = [
+    "select",
+    "insert",
+    "update",
+    "delete",
+    "merge",
+    "with",
+    "call",
+    "begin",
+    "commit",
+    "rollback",
+    "savepoint",
+    "copy",
+    "ddl",
+    "other",
+];
+
+static QUERIES: [Histogram; FAMILIES.len()] = [Histogram::NEW; FAMILIES.len()];
+
+const OUTCOMES: [&str; 3] = ["commit", "rollback", "dropped"];
+
+static TRANSACTIONS: [Histogram; OUTCOMES.len()] = [Histogram::NEW; OUTCOMES.len()];
+
+static TRANSACTION_STATEMENTS: [AtomicU64; OUTCOMES.len()] = [ZERO; OUTCOMES.len()];
+
+static ERRORS: Lazy<Mutex<BTreeMap<String, u64>>> = Lazy::new(Default::default);
+
+static POOLS: Lazy<Mutex<Vec<Weak<dyn PoolGauges>>>> = Lazy::new(Default::default);
+
+static NEXT_POOL_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Render the metrics collected so far, in the Prometheus text format.
+pub fn render() -> String {
+    let mut out = String::new();
+
+    let pools: Vec<_> = {
+        let mut pools = lock(&POOLS);
+
+        // the pools dropped since the last scrape aren't reported anymore
+        pools.retain(|pool| pool.strong_count() > 0);
+        pools.iter().filter_map(Weak::upgrade).collect()
+    };
+
+    let gauges: [(&str, &str, fn(&dyn PoolGauges) -> u64); 3] = [
+        (
+            "sqlx_pool_connections",
+            "The connections opened by the pool.",
+            |pool| pool.size().into(),
+        ),
+        (
+            "sqlx_pool_idle_connections",
+            "The connections waiting in the pool.",
+            |pool| pool.num_idle() as u64,
+        ),
+        (
+            "sqlx_pool_max_connections",
+            "The maximum number of connections of the pool.",
+            |pool| pool.max_connections().into(),
+        ),
+    ];
+
+    for (name, help, value) in gauges {
+        header(&mut out, name, help, "gauge");
+
+        for pool in &pools {
+            let _ = writeln!(
+                out,
+                "{}{{pool=\"{}\"}} {}",
+                name,
+                pool.metrics().id,
+                value(&**pool)
+            );
+        }
+    }
+
+    header(
+        &mut out,
+        "sqlx_pool_acquire_duration_seconds",
+        "The time to acquire a connection from the pool.",
+        "histogram",
+    );
+
+    for pool in &pools {
+        let labels = format!("pool=\"{}\"", pool.metrics().id);
+
+        pool.metrics()
+            .acquire
+            .render(&mut out, "sqlx_pool_acquire_duration_seconds", &labels);
+    }
+
+    header(
+        &mut out,
+        "sqlx_pool_acquire_timeouts_total",
+        "The acquires of a connection from the pool which timed out.",
+        "counter",
+    );
+
+    for pool in &pools {
+        let metrics = pool.metrics();
+
+        let _ = writeln!(
+            out,
+            "sqlx_pool_acquire_timeouts_total{{pool=\"{}\"}} {}",
+            metrics.id,
+            metrics.acquire_timeouts.load(Ordering::Relaxed)
+        );
+    }
+
+    header(
+        &mut out,
+        "sqlx_query_duration_seconds",
+        "The time of the queries, until their last row.",
+        "histogram",
+    );
+
+    for (family, histogram) in FAMILIES.iter().zip(&QUERIES) {
+        let labels = format!("family=\"{}\"", family);
+
+        histogram.render(&mut out, "sqlx_query_duration_seconds", &labels);
+    }
+
+    header(
+        &mut out,
+        "sqlx_query_errors_total",
+        "The errors returned by the database, by SQLSTATE.",
+        "counter",
+    );
+
+    for (code, count) in lock(&ERRORS).iter() {
+        let _ = writeln!(
+            out,
+            "sqlx_query_errors_total{{code=\"{}\"}} {}",
+            escape(code),
+            count
+        );
+    }
+
+    header(
+        &mut out,
+        "sqlx_transaction_duration_seconds",
+        "The time the transactions were open, from begin to commit or rollback.",
+        "histogram",
+    );
+
+    for (outcome, histogram) in OUTCOMES.iter().zip(&TRANSACTIONS) {
+        let labels = format!("outcome=\"{}\"", outcome);
+
+        histogram.render(&mut out, "sqlx_transaction_duration_seconds", &labels);
+    }
+
+    header(
+        &mut out,
+        "sqlx_transaction_statements_total",
+        "The statements executed in the transactions.",
+        "counter",
+    );
+
+    for (outcome, count) in OUTCOMES.iter().zip(&TRANSACTION_STATEMENTS) {
+        let _ = writeln!(
+            out,
+            "sqlx_transaction_statements_total{{outcome=\"{}\"}} {}",
+            outcome,
+            count.load(Ordering::Relaxed)
+        );
+    }
+
+    out
+}
+
+/// The metrics of a pool, besides its gauges.
+#[derive(Debug)]
+pub(crate) struct PoolMetrics {
+    id: u64,
+    acquire: Histogram,
+    acquire_timeouts: AtomicU64,
+}
+
+impl PoolMetrics {
+    pub(crate) fn new() -> Self {
+        Self {
+            id: NEXT_POOL_ID.fetch_add(1, Ordering::Relaxed),
+            acquire: Histogram::NEW,
+            acquire_timeouts: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn record_acquire(&self, elapsed: Duration, timed_out: bool) {
+        if timed_out {
+            self.acquire_timeouts.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.acquire.observe(elapsed);
+        }
+    }
+}
+
+/// The state of a pool, read when the metrics are rendered.
+pub(crate) trait PoolGauges: Send + Sync {
+    fn metrics(&self) -> &PoolMetrics;
+
+    fn size(&self) -> u32;
+
+    fn num_idle(&self) -> usize;
+
+    fn max_connections(&self) -> u32;
+}
+
+/// Report the metrics of `pool`, until it's dropped.
+pub(crate) fn register_pool(pool: Weak<dyn PoolGauges>) {
+    lock(&POOLS).push(pool);
+}
+
+pub(crate) fn record_query(sql: &str, elapsed: Duration) {
+    QUERIES[family(sql)].observe(elapsed);
+}
+
+pub(crate) fn record_error(error: &dyn DatabaseError) {
+    let code = error
+        .code()
+        .map_or_else(|| "unknown".to_owned(), |code| code.into_owned());
+
+    *lock(&ERRORS).entry(code).or_insert(0) += 1;
+}
+
+pub(crate) fn record_transaction(
+    outcome: TransactionEventKind,
+    elapsed: Duration,
+    statements: u64,
+) {
+    let outcome = match outcome {
+        TransactionEventKind::Commit => 0,
+        TransactionEventKind::Rollback => 1,
+        TransactionEventKind::Dropped => 2,
+        TransactionEventKind::Begin => return,
+    };
+
+    TRANSACTIONS[outcome].observe(elapsed);
+    TRANSACTION_STATEMENTS[outcome].fetch_add(statements, Ordering::Relaxed);
+}
+
+// the index of the family of the statement in `FAMILIES`
+fn family(sql: &str) -> usize {
+    let keyword = sql
+        .trim_start_matches(|c: char| c.is_whitespace() || c == '(')
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    let family = match &*keyword {
+        "create" | "alter" | "drop" | "truncate" | "comment" => "ddl",
+        "start" => "begin",
+        "release" => "savepoint",
+        keyword => keyword,
+    };
+
+    FAMILIES
+        .iter()
+        .position(|f| *f == family)
+        .unwrap_or(FAMILIES.len() - 1)
+}
+
+#[derive(Debug)]
+struct Histogram {
+    // the observations in each bucket, not cumulated; the last one is `+Inf`
+    buckets: [AtomicU64; BUCKETS.len() + 1],
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const NEW: Histogram = Histogram {
+        buckets: [ZERO; BUCKETS.len() + 1],
+        sum_micros: ZERO,
+    };
+
+    fn observe(&self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        let bucket = BUCKETS
+            .iter()
+            .position(|le| seconds <= *le)
+            .unwrap_or(BUCKETS.len());
+
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let mut count = 0;
+
+        for (i, bucket) in self.buckets.iter().enumerate() {
+            count += bucket.load(Ordering::Relaxed);
+
+            let le = BUCKETS.get(i).map_or("+Inf".to_owned(), f64::to_string);
+
+            let _ = writeln!(out, "{}_bucket{{{},le=\"{}\"}} {}", name, labels, le, count);
+        }
+
+        let sum = self.sum_micros.load(Ordering::Relaxed) as f64 / 1e6;
+
+        let _ = writeln!(out, "{}_sum{{{}}} {}", name, labels, sum);
+        let _ = writeln!(out, "{}_count{{{}}} {}", name, labels, count);
+    }
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const ZERO: AtomicU64 = AtomicU64::new(0);
+
+fn header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // the metrics are valid even if a thread panicked while holding the lock
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[test]
+fn test_family() {
+    assert_eq!(FAMILIES[family("SELECT 1")], "select");
+    assert_eq!(FAMILIES[family("  (select 1) union (select 2)")], "select");
+    assert_eq!(FAMILIES[family("insert into t values (1)")], "insert");
+    assert_eq!(
+        FAMILIES[family("WITH x AS (SELECT 1) SELECT * FROM x")],
+        "with"
+    );
+    assert_eq!(FAMILIES[family("CREATE TABLE t (id INT)")], "ddl");
+    assert_eq!(FAMILIES[family("START TRANSACTION")], "begin");
+    assert_eq!(
+        FAMILIES[family("RELEASE SAVEPOINT _sqlx_savepoint_1")],
+        "savepoint"
+    );
+    assert_eq!(FAMILIES[family("VACUUM")], "other");
+    assert_eq!(FAMILIES[family("")], "other");
+}
+
+#[test]
+fn test_histogram() {
+    let histogram = Histogram::NEW;
+
+    histogram.observe(Duration::from_millis(3));
+    histogram.observe(Duration::from_millis(30));
+    histogram.observe(Duration::from_secs(60));
+
+    let mut out = String::new();
+    histogram.render(&mut out, "query", "family=\"select\"");
+
+    assert!(out.contains("query_bucket{family=\"select\",le=\"0.005\"} 1\n"));
+    assert!(out.contains("query_bucket{family=\"select\",le=\"0.025\"} 1\n"));
+    assert!(out.contains("query_bucket{family=\"select\",le=\"0.05\"} 2\n"));
+    assert!(out.contains("query_bucket{family=\"select\",le=\"10\"} 2\n"));
+    assert!(out.contains("query_bucket{family=\"select\",le=\"+Inf\"} 3\n"));
+    assert!(out.contains("query_sum{family=\"select\"} 60.033\n"));
+    assert!(out.contains("query_count{family=\"select\"} 3\n"));
+}
@@ -0,0 +1,172 @@
+use std::fmt::{self, Debug, Formatter};
+use std::io::Read;
+use std::path::PathBuf;
+
+use futures_core::future::BoxFuture;
+use sqlx_rt::fs;
+
+use crate::error::BoxDynError;
+use crate::migrate::source::{resolve_files, MigrationFileName};
+use crate::migrate::{Migration, MigrationSource};
+
+/// The contents of an archive, or the path to read them from.
+#[derive(Clone)]
+enum Archive {
+    Path(PathBuf),
+    Bytes(Vec<u8>),
+}
+
+impl Archive {
+    async fn read(self) -> Result<Vec<u8>, BoxDynError> {
+        match self {
+            Archive::Path(path) => Ok(fs::read(path).await?),
+            Archive::Bytes(bytes) => Ok(bytes),
+        }
+    }
+}
+
+impl Debug for Archive {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Archive::Path(path) => f.debug_tuple("Path").field(path).finish(),
+            Archive::Bytes(bytes) => write!(f, "Bytes({} bytes)", bytes.len()),
+        }
+    }
+}
+
+/// Migrations read from the files of a tar archive, which may be compressed with gzip; e.g. a
+/// migrations directory packaged as a release artifact.
+///
+/// The files are named as those of a migrations directory (see the implementation of
+/// [`MigrationSource`] for [`Path`][std::path::Path]), in any directory of the archive. Other
+/// files are ignored.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use sqlx_core::migrate::MigrateError;
+/// # fn main() -> Result<(), MigrateError> {
+/// # sqlx_rt::block_on(async move {
+/// # use sqlx_core::migrate::{Migrator, TarArchive};
+/// let m = Migrator::new(TarArchive::open("./migrations.tar.gz")).await?;
+/// # Ok(())
+/// # })
+/// # }
+/// ```
+#[cfg(feature = "migrate-tar")]
+#[derive(Debug, Clone)]
+pub struct TarArchive(Archive);
+
+#[cfg(feature = "migrate-tar")]
+impl TarArchive {
+    /// The archive at the given path, read when the migrations are resolved.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        TarArchive(Archive::Path(path.into()))
+    }
+
+    /// The archive with the given contents, e.g. downloaded.
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>) -> Self {
+        TarArchive(Archive::Bytes(bytes.into()))
+    }
+}
+
+#[cfg(feature = "migrate-tar")]
+impl MigrationSource<'static> for TarArchive {
+    fn resolve(self) -> BoxFuture<'static, Result<Vec<Migration>, BoxDynError>> {
+        Box::pin(async move {
+            let bytes = self.0.read().await?;
+
+            // the magic number of gzip
+            let reader: Box<dyn Read> = if bytes.starts_with(&[0x1f, 0x8b]) {
+                Box::new(flate2::read::GzDecoder::new(&*bytes))
+            } else {
+                Box::new(&*bytes)
+            };
+
+            let mut archive = tar::Archive::new(reader);
+            let mut files = Vec::new();
+
+            for entry in archive.entries()? {
+                let mut entry = entry?;
+
+                if !entry.header().entry_type().is_file() {
+                    continue;
+                }
+
+                let path = entry.path()?.to_string_lossy().into_owned();
+
+                if MigrationFileName::parse(&path)?.is_some() {
+                    let mut sql = String::new();
+                    entry.read_to_string(&mut sql)?;
+
+                    files.push((path, sql));
+                }
+            }
+
+            resolve_files(files)
+        })
+    }
+}
+
+/// Migrations read from the files of a zip archive.
+///
+/// The files are named as those of a migrations directory (see the implementation of
+/// [`MigrationSource`] for [`Path`][std::path::Path]), in any directory of the archive. Other
+/// files are ignored.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use sqlx_core::migrate::MigrateError;
+/// # fn main() -> Result<(), MigrateError> {
+/// # sqlx_rt::block_on(async move {
+/// # use sqlx_core::migrate::{Migrator, ZipArchive};
+/// let m = Migrator::new(ZipArchive::open("./migrations.zip")).await?;
+/// # Ok(())
+/// # })
+/// # }
+/// ```
+#[cfg(feature = "migrate-zip")]
+#[derive(Debug, Clone)]
+pub struct ZipArchive(Archive);
+
+#[cfg(feature = "migrate-zip")]
+impl ZipArchive {
+    /// The archive at the given path, read when the migrations are resolved.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        ZipArchive(Archive::Path(path.into()))
+    }
+
+    /// The archive with the given contents, e.g. downloaded.
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>) -> Self {
+        ZipArchive(Archive::Bytes(bytes.into()))
+    }
+}
+
+#[cfg(feature = "migrate-zip")]
+impl MigrationSource<'static> for ZipArchive {
+    fn resolve(self) -> BoxFuture<'static, Result<Vec<Migration>, BoxDynError>> {
+        Box::pin(async move {
+            let bytes = self.0.read().await?;
+
+            let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))?;
+            let mut files = Vec::new();
+
+            for i in 0..archive.len() {
+                let mut file = archive.by_index(i)?;
+
+                if !file.is_file() || MigrationFileName::parse(file.name())?.is_none() {
+                    continue;
+                }
+
+                let path = file.name().to_owned();
+                let mut sql = String::new();
+                file.read_to_string(&mut sql)?;
+
+                files.push((path, sql));
+            }
+
+            resolve_files(files)
+        })
+    }
+}
@@ -0,0 +1,200 @@
+use std::cmp;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::time::Duration;
+
+use crate::database::Database;
+use crate::decode::Decode;
+use crate::error::Error;
+use crate::executor::Executor;
+use crate::row::Row;
+use crate::types::Type;
+
+/// The progress of a [`BatchedQuery`], reported after each batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchProgress<K> {
+    /// The number of batches run so far.
+    pub batches: u64,
+
+    /// The number of rows in the batches run so far.
+    pub rows: u64,
+
+    /// The last key of the latest batch.
+    pub key: K,
+
+    /// The largest key of the table when the query started, for the progress to be estimated.
+    pub max_key: K,
+}
+
+type ProgressFn<'q, K> = Box<dyn FnMut(&BatchProgress<K>) + Send + 'q>;
+
+/// An `UPDATE` or `DELETE` over a large table, run in batches of rows in order of an integer key
+/// rather than as a single statement, e.g. in a migration rewriting a table, so that each batch
+/// only holds locks on its own rows and has its changes written out before the next one.
+///
+/// The statement is run for each batch with `{start}` and `{end}` replaced by the first and last
+/// key of the batch, between which it should restrict the rows it changes. The batches are
+/// found by paginating over the key in order, which should be indexed, e.g. the primary key.
+///
+/// When the connection isn't in a transaction, each batch is committed on its own; a migration
+/// implemented as a Rust function should then be run outside of a transaction, see
+/// [`Migration::no_transaction`][super::Migration::no_transaction].
+///
+/// Supported by Postgres, MySQL and SQLite.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use sqlx::migrate::{BatchProgress, BatchedQuery};
+/// use std::time::Duration;
+///
+/// let rows = BatchedQuery::new(
+///     "users",
+///     "id",
+///     "UPDATE users SET email = lower(email) WHERE id BETWEEN {start} AND {end}",
+/// )
+/// .batch_size(10_000)
+/// .pause(Duration::from_millis(100))
+/// .on_progress(|progress: &BatchProgress<i64>| {
+///     println!("{} rows, up to {} of {}", progress.rows, progress.key, progress.max_key)
+/// })
+/// .execute(&mut conn)
+/// .await?;
+/// ```
+pub struct BatchedQuery<'q, K> {
+    table: &'q str,
+    key: &'q str,
+    sql: &'q str,
+    batch_size: u64,
+    pause: Duration,
+    progress: Option<ProgressFn<'q, K>>,
+}
+
+impl<'q, K> BatchedQuery<'q, K>
+where
+    K: Display + Copy,
+{
+    /// Run `sql` over the rows of `table` in batches of the `key` column, which are included in
+    /// the SQL as is, so they must be valid identifiers and must not come from untrusted input.
+    pub fn new(table: &'q str, key: &'q str, sql: &'q str) -> Self {
+        BatchedQuery {
+            table,
+            key,
+            sql,
+            batch_size: 1000,
+            pause: Duration::ZERO,
+            progress: None,
+        }
+    }
+
+    /// Set the number of rows of a batch, 1000 by default.
+    pub fn batch_size(mut self, batch_size: u64) -> Self {
+        self.batch_size = cmp::max(batch_size, 1);
+        self
+    }
+
+    /// Wait between batches, none by default, e.g. to leave room for other queries or for
+    /// replicas to catch up.
+    pub fn pause(mut self, pause: Duration) -> Self {
+        self.pause = pause;
+        self
+    }
+
+    /// Invoke `progress` after each batch, e.g. to log the progress of a migration.
+    pub fn on_progress(mut self, progress: impl FnMut(&BatchProgress<K>) + Send + 'q) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Run the batches, returning the number of rows in them.
+    pub async fn execute<DB, C>(mut self, conn: &mut C) -> Result<u64, Error>
+    where
+        DB: Database,
+        C: ?Sized,
+        for<'c> &'c mut C: Executor<'c, Database = DB>,
+        K: Type<DB> + for<'r> Decode<'r, DB>,
+        i64: Type<DB> + for<'r> Decode<'r, DB>,
+        usize: crate::column::ColumnIndex<DB::Row>,
+    {
+        // language=SQL
+        let max_key: Option<K> = conn
+            .fetch_one(&*format!("SELECT MAX({}) FROM {}", self.key, self.table))
+            .await?
+            .try_get(0)?;
+
+        let max_key = match max_key {
+            Some(max_key) => max_key,
+            None => return Ok(0),
+        };
+
+        let mut progress = BatchProgress {
+            batches: 0,
+            rows: 0,
+            key: max_key,
+            max_key,
+        };
+
+        loop {
+            let after = match progress.batches {
+                0 => String::new(),
+                _ => format!("WHERE {} > {}", self.key, progress.key),
+            };
+
+            // language=SQL
+            let row = conn
+                .fetch_one(&*format!(
+                    "SELECT COUNT(*), MIN({key}), MAX({key}) FROM \
+                     (SELECT {key} FROM {table} {after} ORDER BY {key} LIMIT {limit}) AS batch",
+                    key = self.key,
+                    table = self.table,
+                    after = after,
+                    limit = self.batch_size,
+                ))
+                .await?;
+
+            let rows: i64 = row.try_get(0)?;
+            let (start, end): (Option<K>, Option<K>) = (row.try_get(1)?, row.try_get(2)?);
+
+            let (start, end) = match (start, end) {
+                (Some(start), Some(end)) => (start, end),
+                _ => break,
+            };
+
+            let sql = self
+                .sql
+                .replace("{start}", &start.to_string())
+                .replace("{end}", &end.to_string());
+
+            conn.execute(&*sql).await?;
+
+            progress.batches += 1;
+            progress.rows += rows as u64;
+            progress.key = end;
+
+            if let Some(f) = &mut self.progress {
+                f(&progress);
+            }
+
+            if (rows as u64) < self.batch_size {
+                break;
+            }
+
+            if !self.pause.is_zero() {
+                crate::runtime::sleep(self.pause).await;
+            }
+        }
+
+        Ok(progress.rows)
+    }
+}
+
+impl<K> Debug for BatchedQuery<'_, K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchedQuery")
+            .field("table", &self.table)
+            .field("key", &self.key)
+            .field("sql", &self.sql)
+            .field("batch_size", &self.batch_size)
+            .field("pause", &self.pause)
+            .finish_non_exhaustive()
+    }
+}
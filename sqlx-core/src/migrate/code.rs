@@ -0,0 +1,67 @@
+use std::any::Any;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use futures_core::future::BoxFuture;
+
+use crate::database::Database;
+use crate::error::BoxDynError;
+use crate::migrate::MigrateError;
+
+type MigrationFn<C> =
+    Box<dyn for<'c> Fn(&'c mut C) -> BoxFuture<'c, Result<(), BoxDynError>> + Send + Sync>;
+
+/// A migration implemented as a Rust function, see [`Migration::from_fn`][super::Migration::from_fn].
+#[derive(Clone)]
+pub struct MigrationCode {
+    db_name: &'static str,
+    // a `MigrationFn<DB::Connection>`, to keep `Migration` independent of the database
+    f: Arc<dyn Any + Send + Sync>,
+}
+
+impl MigrationCode {
+    pub(crate) fn new<DB, F>(f: F) -> Self
+    where
+        DB: Database,
+        F: for<'c> Fn(&'c mut DB::Connection) -> BoxFuture<'c, Result<(), BoxDynError>>
+            + 'static
+            + Send
+            + Sync,
+    {
+        let f: MigrationFn<DB::Connection> = Box::new(f);
+
+        // e.g. `Postgres`
+        let db_name = std::any::type_name::<DB>();
+        let db_name = db_name.rsplit("::").next().unwrap_or(db_name);
+
+        MigrationCode {
+            db_name,
+            f: Arc::new(f),
+        }
+    }
+
+    /// Run the function of the migration on the connection, which must be of the database it was
+    /// written for.
+    pub(crate) async fn run<C: 'static>(
+        &self,
+        version: i64,
+        conn: &mut C,
+    ) -> Result<(), MigrateError> {
+        let f = self
+            .f
+            .downcast_ref::<MigrationFn<C>>()
+            .ok_or_else(|| MigrateError::RequiresDatabase(version, self.db_name.to_owned()))?;
+
+        f(conn)
+            .await
+            .map_err(|source| MigrateError::Code(version, source))
+    }
+}
+
+impl Debug for MigrationCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MigrationCode")
+            .field("db_name", &self.db_name)
+            .finish_non_exhaustive()
+    }
+}
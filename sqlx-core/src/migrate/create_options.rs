@@ -0,0 +1,89 @@
+use crate::error::Error;
+
+/// Options of a database created with
+/// [`MigrateDatabase::create_database_with`](super::MigrateDatabase::create_database_with),
+/// instead of the defaults of the server.
+///
+/// ```rust
+/// # use sqlx_core::migrate::CreateDatabaseOptions;
+/// let options = CreateDatabaseOptions::new()
+///     .owner("app")
+///     .encoding("UTF8")
+///     .locale("en_US.UTF-8")
+///     .template("template0");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CreateDatabaseOptions {
+    pub(crate) owner: Option<String>,
+    pub(crate) encoding: Option<String>,
+    pub(crate) locale: Option<String>,
+    pub(crate) collation: Option<String>,
+    pub(crate) template: Option<String>,
+    pub(crate) charset: Option<String>,
+}
+
+impl CreateDatabaseOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The role owning the database (Postgres).
+    pub fn owner(mut self, owner: impl Into<String>) -> Self {
+        self.owner = Some(owner.into());
+        self
+    }
+
+    /// The character encoding of the database, e.g. `UTF8` (Postgres).
+    pub fn encoding(mut self, encoding: impl Into<String>) -> Self {
+        self.encoding = Some(encoding.into());
+        self
+    }
+
+    /// The locale of the database, setting both its collation and character classification
+    /// (Postgres).
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// The collation of the database, taking precedence over the locale (Postgres, MySQL).
+    pub fn collation(mut self, collation: impl Into<String>) -> Self {
+        self.collation = Some(collation.into());
+        self
+    }
+
+    /// The database to copy, e.g. `template0` to use an encoding or locale other than that of
+    /// `template1` (Postgres).
+    pub fn template(mut self, template: impl Into<String>) -> Self {
+        self.template = Some(template.into());
+        self
+    }
+
+    /// The default character set of the database, e.g. `utf8mb4` (MySQL).
+    pub fn charset(mut self, charset: impl Into<String>) -> Self {
+        self.charset = Some(charset.into());
+        self
+    }
+
+    /// Returns an error naming the first option which is set but isn't one of `supported`.
+    pub(crate) fn check_supported(&self, supported: &[&str]) -> Result<(), Error> {
+        let options = [
+            ("owner", &self.owner),
+            ("encoding", &self.encoding),
+            ("locale", &self.locale),
+            ("collation", &self.collation),
+            ("template", &self.template),
+            ("charset", &self.charset),
+        ];
+
+        match options
+            .iter()
+            .find(|(name, value)| value.is_some() && !supported.contains(name))
+        {
+            Some((name, _)) => Err(Error::Configuration(
+                format!("the `{}` option is not supported by this database", name).into(),
+            )),
+            None => Ok(()),
+        }
+    }
+}
@@ -18,6 +18,48 @@ pub enum MigrateError {
     #[error("cannot mix reversible migrations with simple migrations. All migrations should be reversible or simple migrations")]
     InvalidMixReversibleAndSimple,
 
+    #[error("migration {0} cannot be reverted because it has no down migration")]
+    NotReversible(i64),
+
+    #[error("migration {0} is defined more than once")]
+    VersionCollision(i64),
+
+    #[error(
+        "migration {0} squashes migrations that were only partially applied; \
+         apply the rest of them before removing their files"
+    )]
+    PartiallySquashed(i64),
+
+    #[error("migration {0} is older than the latest applied migration")]
+    OutOfOrder(i64),
+
+    #[error("target version {0} is not present in the resolved migrations")]
+    VersionNotPresent(i64),
+
+    #[error("target version {0} is older than the latest applied migration {1}")]
+    VersionTooOld(i64, i64),
+
+    #[error("target version {0} is newer than the latest applied migration {1}")]
+    VersionTooNew(i64, i64),
+
+    #[error("migration {0} has an invalid header: {1}")]
+    InvalidHeader(i64, String),
+
+    #[error("migration {0} can only be run against {1}")]
+    RequiresDatabase(i64, String),
+
+    #[error("in a hook of migration {0}: {1}")]
+    Hook(i64, #[source] BoxDynError),
+
+    #[error("while running migration {0}: {1}")]
+    Code(i64, #[source] BoxDynError),
+
+    #[error("while writing the schema dump: {0}")]
+    SchemaDump(#[source] std::io::Error),
+
+    #[error("timed out waiting for the migration lock held by another migrator")]
+    LockTimeout,
+
     // NOTE: this will only happen with a database that does not have transactional DDL (.e.g, MySQL or Oracle)
     #[error(
         "migration {0} is partially applied; fix and remove row from `_sqlx_migrations` table"
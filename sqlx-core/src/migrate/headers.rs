@@ -0,0 +1,185 @@
+use std::time::Duration;
+
+use crate::migrate::MigrateError;
+
+/// Configuration of a migration, given by comment headers at the top of its file:
+///
+/// ```sql
+/// -- sqlx:no-transaction
+/// -- sqlx:lock-timeout=5s
+/// -- sqlx:require-db=postgres
+/// CREATE INDEX CONCURRENTLY users_email ON users (email);
+/// ```
+///
+/// Headers are read from the comment lines before the first statement; an unknown `sqlx:` header
+/// is an error.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationHeaders {
+    /// `-- sqlx:no-transaction`: run the migration outside of a transaction, e.g. for
+    /// `CREATE INDEX CONCURRENTLY` in Postgres, or `VACUUM` in SQLite.
+    ///
+    /// Postgres runs several statements sent at once in one implicit transaction, so such a
+    /// migration should contain a single statement. Migrations never run in a transaction in
+    /// MySQL.
+    pub no_transaction: bool,
+
+    /// `-- sqlx:lock-timeout=<duration>`, with a duration like `500ms`, `5s` or `1m`: the longest
+    /// time to wait for a lock while running the migration, setting `lock_timeout` in Postgres,
+    /// `lock_wait_timeout` in MySQL (rounded up to seconds) or `busy_timeout` in SQLite.
+    pub lock_timeout: Option<Duration>,
+
+    /// `-- sqlx:require-db=<postgres|mysql|sqlite>`: fail instead of running the migration against
+    /// another database.
+    pub require_db: Option<String>,
+
+    /// `-- sqlx:squash`: the migration replaces all the migrations up to and including its
+    /// version, whose files can then be removed, e.g. as generated by `sqlx migrate squash`.
+    ///
+    /// It is only applied to a database where none of them were applied; where they all were,
+    /// it is considered applied instead.
+    pub squash: bool,
+
+    /// `-- sqlx:resumable`: run the statements of the migration one at a time outside of a
+    /// transaction, implying `no-transaction`, and record how many were applied in its row of the
+    /// migrations table. If one fails, the migration stays dirty and the next run resumes it from
+    /// that statement, e.g. once the statement is fixed; the statements before it must be left
+    /// as they are.
+    ///
+    /// The statements are split as by [`split_statements`](crate::query_batch::split_statements).
+    pub resumable: bool,
+}
+
+const PREFIX: &str = "sqlx:";
+
+impl MigrationHeaders {
+    /// Parse the headers of a migration from its SQL.
+    pub fn parse(sql: &str) -> Result<Self, String> {
+        let mut headers = MigrationHeaders::default();
+
+        for line in sql.lines().map(str::trim) {
+            if line.is_empty() {
+                continue;
+            }
+
+            let comment = match line.strip_prefix("--") {
+                Some(comment) => comment.trim(),
+                // the first statement
+                None => break,
+            };
+
+            let header = match comment.strip_prefix(PREFIX) {
+                Some(header) => header.trim(),
+                None => continue,
+            };
+
+            let (key, value) = match header.split_once('=') {
+                Some((key, value)) => (key.trim(), Some(value.trim())),
+                None => (header, None),
+            };
+
+            match (key, value) {
+                ("no-transaction", None) => headers.no_transaction = true,
+                ("squash", None) => headers.squash = true,
+                ("resumable", None) => {
+                    headers.resumable = true;
+                    headers.no_transaction = true;
+                }
+                ("lock-timeout", Some(value)) => {
+                    headers.lock_timeout = Some(parse_duration(value).ok_or_else(|| {
+                        format!(
+                            "invalid duration {:?} for `{}lock-timeout`, expected e.g. `5s`",
+                            value, PREFIX
+                        )
+                    })?);
+                }
+                ("require-db", Some(value)) => {
+                    let db = value.to_ascii_lowercase();
+
+                    if !["postgres", "mysql", "sqlite"].contains(&&*db) {
+                        return Err(format!(
+                            "unknown database {:?} for `{}require-db`, expected `postgres`, \
+                             `mysql` or `sqlite`",
+                            value, PREFIX
+                        ));
+                    }
+
+                    headers.require_db = Some(db);
+                }
+                _ => return Err(format!("unknown migration header `{}{}`", PREFIX, header)),
+            }
+        }
+
+        Ok(headers)
+    }
+
+    /// Returns an error if the migration requires a database other than `db`.
+    pub(crate) fn check_database(&self, version: i64, db: &str) -> Result<(), MigrateError> {
+        match &self.require_db {
+            Some(required) if required != db => {
+                Err(MigrateError::RequiresDatabase(version, required.clone()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn parse_duration(s: &str) -> Option<Duration> {
+    let split = s.find(|c: char| !c.is_ascii_digit())?;
+    let (value, unit) = s.split_at(split);
+    let value: u64 = value.parse().ok()?;
+
+    match unit.trim() {
+        "ms" => Some(Duration::from_millis(value)),
+        "s" => Some(Duration::from_secs(value)),
+        "m" | "min" => Some(Duration::from_secs(value * 60)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_headers() {
+        assert_eq!(
+            MigrationHeaders::parse("CREATE TABLE foo (id INT);"),
+            Ok(MigrationHeaders::default())
+        );
+
+        assert_eq!(
+            MigrationHeaders::parse(
+                "-- Add an index\n\
+                 -- sqlx:no-transaction\n\
+                 --sqlx:lock-timeout = 500ms\n\
+                 \n\
+                 -- sqlx: require-db=Postgres\n\
+                 CREATE INDEX CONCURRENTLY foo_bar ON foo (bar);\n\
+                 -- sqlx:not-a-header"
+            ),
+            Ok(MigrationHeaders {
+                no_transaction: true,
+                lock_timeout: Some(Duration::from_millis(500)),
+                require_db: Some("postgres".into()),
+                squash: false,
+                resumable: false,
+            })
+        );
+
+        assert_eq!(
+            MigrationHeaders::parse("-- sqlx:lock-timeout=2m").map(|h| h.lock_timeout),
+            Ok(Some(Duration::from_secs(120)))
+        );
+
+        assert!(MigrationHeaders::parse("-- sqlx:squash").unwrap().squash);
+
+        assert_eq!(
+            MigrationHeaders::parse("-- sqlx:resumable").map(|h| (h.resumable, h.no_transaction)),
+            Ok((true, true))
+        );
+
+        assert!(MigrationHeaders::parse("-- sqlx:no-transactions").is_err());
+        assert!(MigrationHeaders::parse("-- sqlx:lock-timeout=5").is_err());
+        assert!(MigrationHeaders::parse("-- sqlx:require-db=oracle").is_err());
+    }
+}
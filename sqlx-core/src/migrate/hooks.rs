@@ -0,0 +1,87 @@
+use std::borrow::Cow;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_core::future::BoxFuture;
+
+use crate::error::BoxDynError;
+use crate::migrate::{MigrateError, Migration, MigrationType};
+
+type Hook = Arc<
+    dyn Fn(MigrationInfo) -> BoxFuture<'static, Result<(), BoxDynError>> + 'static + Send + Sync,
+>;
+
+/// The migration a hook registered with [`Migrator::before_each`][super::Migrator::before_each]
+/// or [`Migrator::after_each`][super::Migrator::after_each] is invoked for.
+#[derive(Debug, Clone)]
+pub struct MigrationInfo {
+    pub version: i64,
+    pub description: Cow<'static, str>,
+    /// Whether the migration is applied or, for a down migration, reverted.
+    pub migration_type: MigrationType,
+    /// How long the migration took to run, for hooks invoked after it.
+    pub elapsed: Option<Duration>,
+}
+
+/// The hooks of a [`Migrator`][super::Migrator].
+#[derive(Clone, Default)]
+pub struct MigrationHooks {
+    pub(crate) before: Vec<Hook>,
+    pub(crate) after: Vec<Hook>,
+}
+
+impl MigrationHooks {
+    /// No hooks.
+    pub const fn new() -> Self {
+        MigrationHooks {
+            before: Vec::new(),
+            after: Vec::new(),
+        }
+    }
+
+    pub(crate) fn extend(&mut self, other: &MigrationHooks) {
+        self.before.extend(other.before.iter().cloned());
+        self.after.extend(other.after.iter().cloned());
+    }
+
+    pub(crate) async fn run_before(&self, migration: &Migration) -> Result<(), MigrateError> {
+        run(&self.before, migration, None).await
+    }
+
+    pub(crate) async fn run_after(
+        &self,
+        migration: &Migration,
+        elapsed: Duration,
+    ) -> Result<(), MigrateError> {
+        run(&self.after, migration, Some(elapsed)).await
+    }
+}
+
+async fn run(
+    hooks: &[Hook],
+    migration: &Migration,
+    elapsed: Option<Duration>,
+) -> Result<(), MigrateError> {
+    for hook in hooks {
+        hook(MigrationInfo {
+            version: migration.version,
+            description: migration.description.clone(),
+            migration_type: migration.migration_type,
+            elapsed,
+        })
+        .await
+        .map_err(|source| MigrateError::Hook(migration.version, source))?;
+    }
+
+    Ok(())
+}
+
+impl Debug for MigrationHooks {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MigrationHooks")
+            .field("before", &self.before.len())
+            .field("after", &self.after.len())
+            .finish()
+    }
+}
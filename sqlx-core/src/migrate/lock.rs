@@ -0,0 +1,75 @@
+use crate::migrate::MigrateError;
+use std::cmp;
+use std::time::{Duration, Instant};
+
+/// The lock taken by [`Migrator`][super::Migrator] while it applies or reverts migrations, so that
+/// several migrators running against the same database, e.g. in concurrent deploy jobs, run one
+/// at a time.
+///
+/// The lock is an advisory lock in Postgres (`pg_advisory_lock`), a named lock in MySQL
+/// (`GET_LOCK`), and a row of the `_sqlx_migrations_lock` table in SQLite. The SQLite lock is
+/// released by the migrator rather than by closing the connection, so if a migrator is killed
+/// while holding it, the row must be deleted before migrations can run again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MigrationLock {
+    /// The key identifying the lock, for migrators which should run concurrently to use different
+    /// keys. By default, it is derived from the name of the database.
+    pub key: Option<i64>,
+
+    /// The longest time to wait for the lock before returning
+    /// [`MigrateError::LockTimeout`][super::MigrateError::LockTimeout]; by default, wait as long
+    /// as it takes.
+    pub timeout: Option<Duration>,
+}
+
+impl MigrationLock {
+    pub const fn new() -> Self {
+        MigrationLock {
+            key: None,
+            timeout: None,
+        }
+    }
+}
+
+impl Default for MigrationLock {
+    fn default() -> Self {
+        MigrationLock::new()
+    }
+}
+
+// how long to wait between attempts to take a lock which can't be waited for
+const RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+// the attempts of a driver to take a lock without blocking, until `MigrationLock::timeout`
+pub(crate) struct LockAttempts {
+    deadline: Option<Instant>,
+}
+
+impl LockAttempts {
+    pub(crate) fn new(lock: MigrationLock) -> Self {
+        LockAttempts {
+            deadline: lock.timeout.map(|timeout| Instant::now() + timeout),
+        }
+    }
+
+    // wait before the next attempt, or fail if the timeout would elapse in the meantime
+    pub(crate) async fn retry(&self) -> Result<(), MigrateError> {
+        let interval = match self.deadline {
+            Some(deadline) => {
+                let left = deadline.saturating_duration_since(Instant::now());
+
+                if left.is_zero() {
+                    return Err(MigrateError::LockTimeout);
+                }
+
+                cmp::min(left, RETRY_INTERVAL)
+            }
+
+            None => RETRY_INTERVAL,
+        };
+
+        crate::runtime::sleep(interval).await;
+
+        Ok(())
+    }
+}
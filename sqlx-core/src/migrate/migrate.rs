@@ -1,5 +1,7 @@
 use crate::error::Error;
-use crate::migrate::{AppliedMigration, MigrateError, Migration};
+use crate::migrate::{
+    AppliedMigration, CreateDatabaseOptions, MigrateError, Migration, MigrationLock,
+};
 use futures_core::future::BoxFuture;
 use std::time::Duration;
 
@@ -8,6 +10,18 @@ pub trait MigrateDatabase {
     // uses a maintenance database depending on driver
     fn create_database(url: &str) -> BoxFuture<'_, Result<(), Error>>;
 
+    // create database in url with options such as its owner or encoding
+    // returns an error if an option is not supported by the driver
+    fn create_database_with<'a>(
+        url: &'a str,
+        options: &'a CreateDatabaseOptions,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            options.check_supported(&[])?;
+            Self::create_database(url).await
+        })
+    }
+
     // check if the database in url exists
     // uses a maintenance database depending on driver
     fn database_exists(url: &str) -> BoxFuture<'_, Result<bool, Error>>;
@@ -18,22 +32,32 @@ pub trait MigrateDatabase {
 }
 
 // 'e = Executor
+// `table_name` is the table recording applied migrations, `_sqlx_migrations` by default, optionally
+// qualified by a schema; it is included in the SQL as is
 pub trait Migrate {
     // ensure migrations table exists
     // will create or migrate it if needed
-    fn ensure_migrations_table(&mut self) -> BoxFuture<'_, Result<(), MigrateError>>;
+    fn ensure_migrations_table<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<(), MigrateError>>;
 
     // Return the version on which the database is dirty or None otherwise.
     // "dirty" means there is a partially applied migration that failed.
-    fn dirty_version(&mut self) -> BoxFuture<'_, Result<Option<i64>, MigrateError>>;
+    fn dirty_version<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Option<i64>, MigrateError>>;
 
     // Return the current version and if the database is "dirty".
     // "dirty" means there is a partially applied migration that failed.
+    // Only reads the default table.
     #[deprecated]
     fn version(&mut self) -> BoxFuture<'_, Result<Option<(i64, bool)>, MigrateError>>;
 
     // validate the migration
     // checks that it does exist on the database and that the checksum matches
+    // Only reads the default table.
     #[deprecated]
     fn validate<'e: 'm, 'm>(
         &'e mut self,
@@ -41,32 +65,59 @@ pub trait Migrate {
     ) -> BoxFuture<'m, Result<(), MigrateError>>;
 
     // Return the ordered list of applied migrations
-    fn list_applied_migrations(
-        &mut self,
-    ) -> BoxFuture<'_, Result<Vec<AppliedMigration>, MigrateError>>;
+    fn list_applied_migrations<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Vec<AppliedMigration>, MigrateError>>;
 
     // Should acquire a database lock so that only one migration process
     // can run at a time. [`Migrate`] will call this function before applying
     // any migrations.
-    fn lock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>>;
+    // Waits at most `lock.timeout`, returning `MigrateError::LockTimeout` past it.
+    fn lock(&mut self, lock: MigrationLock) -> BoxFuture<'_, Result<(), MigrateError>>;
 
     // Should release the lock. [`Migrate`] will call this function after all
     // migrations have been run.
-    fn unlock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>>;
+    fn unlock(&mut self, lock: MigrationLock) -> BoxFuture<'_, Result<(), MigrateError>>;
 
     // run SQL from migration in a DDL transaction
     // insert new row to [_migrations] table on completion (success or failure)
     // returns the time taking to run the migration SQL
     fn apply<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>>;
 
+    // replace the checksum stored for the applied migration with its current checksum,
+    // e.g. after its file was reformatted
+    fn update_checksum<'e: 'm, 'm>(
+        &'e mut self,
+        table_name: &'m str,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(), MigrateError>>;
+
+    // insert a row in [_migrations] table for the migration without running it, e.g. because the
+    // schema it creates already exists
+    fn record_applied<'e: 'm, 'm>(
+        &'e mut self,
+        table_name: &'m str,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(), MigrateError>>;
+
+    // return the DDL creating the schema of the database, without the migrations table, in a
+    // stable order so that it can be committed and diffed
+    fn dump_schema<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<String, MigrateError>>;
+
     // run a revert SQL from migration in a DDL transaction
     // deletes the row in [_migrations] table with specified migration version on completion (success or failure)
     // returns the time taking to run the migration SQL
     fn revert<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>>;
 }
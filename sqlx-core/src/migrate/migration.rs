@@ -1,8 +1,12 @@
 use std::borrow::Cow;
+use std::time::Duration;
 
+use futures_core::future::BoxFuture;
 use sha2::{Digest, Sha384};
 
-use super::MigrationType;
+use super::{MigrateError, MigrationCode, MigrationHeaders, MigrationType};
+use crate::database::Database;
+use crate::error::BoxDynError;
 
 #[derive(Debug, Clone)]
 pub struct Migration {
@@ -11,6 +15,8 @@ pub struct Migration {
     pub migration_type: MigrationType,
     pub sql: Cow<'static, str>,
     pub checksum: Cow<'static, [u8]>,
+    /// The function run instead of `sql` for a migration implemented in Rust.
+    pub code: Option<MigrationCode>,
 }
 
 impl Migration {
@@ -28,12 +34,96 @@ impl Migration {
             migration_type,
             sql,
             checksum,
+            code: None,
         }
     }
+
+    /// Create a migration implemented as a Rust function, e.g. for a data transformation that
+    /// needs application logic, to add to a [`Migrator`][super::Migrator] with
+    /// [`add_migration`][super::Migrator::add_migration]. It is applied in order of version along
+    /// with the SQL migrations, in a transaction, and can only be run against a connection to
+    /// `DB`.
+    ///
+    /// Since there is no file to compare, the checksum recorded for it never changes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use sqlx::migrate::{Migration, MigrationType};
+    /// use sqlx::Postgres;
+    ///
+    /// let migration = Migration::from_fn::<Postgres, _>(
+    ///     20220801000000,
+    ///     "hash api keys".into(),
+    ///     MigrationType::Simple,
+    ///     |conn| Box::pin(async move {
+    ///         let keys: Vec<(i64, String)> = sqlx::query_as("SELECT id, api_key FROM users")
+    ///             .fetch_all(&mut *conn)
+    ///             .await?;
+    ///
+    ///         for (id, key) in keys {
+    ///             sqlx::query("UPDATE users SET api_key = $1 WHERE id = $2")
+    ///                 .bind(hash(&key))
+    ///                 .bind(id)
+    ///                 .execute(&mut *conn)
+    ///                 .await?;
+    ///         }
+    ///
+    ///         Ok(())
+    ///     }),
+    /// );
+    /// ```
+    pub fn from_fn<DB, F>(
+        version: i64,
+        description: Cow<'static, str>,
+        migration_type: MigrationType,
+        f: F,
+    ) -> Self
+    where
+        DB: Database,
+        F: for<'c> Fn(&'c mut DB::Connection) -> BoxFuture<'c, Result<(), BoxDynError>>
+            + 'static
+            + Send
+            + Sync,
+    {
+        Migration {
+            code: Some(MigrationCode::new::<DB, F>(f)),
+            ..Migration::new(version, description, migration_type, Cow::Borrowed(""))
+        }
+    }
+
+    /// Run the migration outside of a transaction, as the `-- sqlx:no-transaction` header does for
+    /// a migration file, e.g. for a migration implemented as a Rust function to commit the
+    /// batches of a [`BatchedQuery`][super::BatchedQuery] as it goes.
+    pub fn no_transaction(mut self) -> Self {
+        // the checksum is left as is, to keep comparing it with the one of the file
+        self.sql = Cow::Owned(format!("-- sqlx:no-transaction\n{}", self.sql));
+        self
+    }
+
+    /// Parse the configuration given by the comment headers of the migration.
+    pub fn headers(&self) -> Result<MigrationHeaders, MigrateError> {
+        MigrationHeaders::parse(&self.sql).map_err(|e| MigrateError::InvalidHeader(self.version, e))
+    }
+
+    /// Whether the migration replaces the migrations before it, see
+    /// [`MigrationHeaders::squash`]; invalid headers are reported when the migration is resolved.
+    pub(crate) fn is_squash(&self) -> bool {
+        self.headers().map_or(false, |headers| headers.squash)
+    }
+
+    /// Whether a failed run of the migration can be resumed, see [`MigrationHeaders::resumable`].
+    pub(crate) fn is_resumable(&self) -> bool {
+        !self.migration_type.is_down_migration()
+            && self.code.is_none()
+            && self.headers().map_or(false, |headers| headers.resumable)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct AppliedMigration {
     pub version: i64,
     pub checksum: Cow<'static, [u8]>,
+    /// How long running the migration took.
+    pub execution_time: Duration,
 }
@@ -1,14 +1,46 @@
 use crate::acquire::Acquire;
-use crate::migrate::{AppliedMigration, Migrate, MigrateError, Migration, MigrationSource};
+use crate::error::BoxDynError;
+use crate::migrate::{
+    AppliedMigration, Migrate, MigrateError, Migration, MigrationHooks, MigrationInfo,
+    MigrationLock, MigrationSource, OutOfOrder,
+};
+use futures_core::future::BoxFuture;
+use sqlx_rt::fs;
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::ops::Deref;
+use std::path::PathBuf;
 use std::slice;
+use std::sync::Arc;
+use std::time::Duration;
 
-#[derive(Debug)]
+/// The table recording applied migrations unless [`Migrator::set_table_name`] is used.
+pub const DEFAULT_TABLE_NAME: &str = "_sqlx_migrations";
+
+/// The table recording applied seed migrations, i.e. reference or test data kept in a separate
+/// directory (`seeds` for `sqlx seed`) and applied independently of the schema migrations.
+pub const DEFAULT_SEEDS_TABLE_NAME: &str = "_sqlx_seeds";
+
+#[derive(Debug, Clone)]
 pub struct Migrator {
     pub migrations: Cow<'static, [Migration]>,
     pub ignore_missing: bool,
+    /// Versions whose checksum may differ from the one recorded when they were applied, logging
+    /// a warning instead of returning [`MigrateError::VersionMismatch`].
+    pub allow_checksum_mismatch: Cow<'static, [i64]>,
+    /// What to do with pending migrations older than the latest applied migration.
+    pub out_of_order: OutOfOrder,
+    /// The table recording applied migrations, see [`set_table_name`][Self::set_table_name].
+    pub table_name: Cow<'static, str>,
+    /// The file the schema is written to after running migrations, see
+    /// [`set_schema_dump`][Self::set_schema_dump].
+    pub schema_dump: Option<PathBuf>,
+    /// Whether to lock the database while migrating, see [`set_locking`][Self::set_locking].
+    pub locking: bool,
+    /// The lock taken while migrating, see [`MigrationLock`].
+    pub lock: MigrationLock,
+    /// Callbacks invoked around each migration, see [`before_each`][Self::before_each].
+    pub hooks: MigrationHooks,
 }
 
 fn validate_applied_migrations(
@@ -20,9 +52,12 @@ fn validate_applied_migrations(
     }
 
     let migrations: HashSet<_> = migrator.iter().map(|m| m.version).collect();
+    // the files of squashed migrations are expected to be gone
+    let squashed = migrator.squashed_version().unwrap_or(i64::MIN);
 
     for applied_migration in applied_migrations {
-        if !migrations.contains(&applied_migration.version) {
+        if applied_migration.version > squashed && !migrations.contains(&applied_migration.version)
+        {
             return Err(MigrateError::VersionMissing(applied_migration.version));
         }
     }
@@ -56,6 +91,79 @@ impl Migrator {
         Ok(Self {
             migrations: Cow::Owned(source.resolve().await.map_err(MigrateError::Source)?),
             ignore_missing: false,
+            allow_checksum_mismatch: Cow::Borrowed(&[]),
+            out_of_order: OutOfOrder::ApplyMissing,
+            table_name: Cow::Borrowed(DEFAULT_TABLE_NAME),
+            schema_dump: None,
+            locking: true,
+            lock: MigrationLock::new(),
+            hooks: MigrationHooks::new(),
+        })
+    }
+
+    /// Merges the migrations of several migrators, e.g. those of the crates of a workspace, into one
+    /// migrator applying them as a single history, in order of version.
+    ///
+    /// Returns an error if a version is defined by more than one of the migrators. Missing applied
+    /// migrations are ignored if any of the migrators ignores them, and the most permissive
+    /// [`OutOfOrder`] policy of the migrators is used. The migrations are recorded in the table of
+    /// the first migrator and under its lock, and the schema is dumped to the file of the first
+    /// migrator setting one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use sqlx::migrate::Migrator;
+    ///
+    /// static USERS: Migrator = sqlx::migrate!("users/migrations");
+    /// static BILLING: Migrator = sqlx::migrate!("billing/migrations");
+    ///
+    /// let migrator = Migrator::merge(&[&USERS, &BILLING])?;
+    /// migrator.run(&pool).await?;
+    /// ```
+    pub fn merge(migrators: &[&Migrator]) -> Result<Self, MigrateError> {
+        let mut versions = HashMap::new();
+        let mut migrations = Vec::new();
+
+        for (i, migrator) in migrators.iter().enumerate() {
+            for migration in migrator.iter() {
+                // the up and down migrations of a version come from the same migrator
+                if *versions.entry(migration.version).or_insert(i) != i {
+                    return Err(MigrateError::VersionCollision(migration.version));
+                }
+
+                migrations.push(migration.clone());
+            }
+        }
+
+        // ensure that we are sorted by `VERSION ASC`, keeping the order of each version's files
+        migrations.sort_by_key(|m| m.version);
+
+        let mut hooks = MigrationHooks::new();
+
+        for migrator in migrators {
+            hooks.extend(&migrator.hooks);
+        }
+
+        Ok(Self {
+            migrations: Cow::Owned(migrations),
+            ignore_missing: migrators.iter().any(|m| m.ignore_missing),
+            allow_checksum_mismatch: migrators
+                .iter()
+                .flat_map(|m| m.allow_checksum_mismatch.iter().copied())
+                .collect(),
+            out_of_order: migrators
+                .iter()
+                .map(|m| m.out_of_order)
+                .max()
+                .unwrap_or_default(),
+            table_name: migrators
+                .first()
+                .map_or(Cow::Borrowed(DEFAULT_TABLE_NAME), |m| m.table_name.clone()),
+            schema_dump: migrators.iter().find_map(|m| m.schema_dump.clone()),
+            locking: migrators.first().map_or(true, |m| m.locking),
+            lock: migrators.first().map_or(MigrationLock::new(), |m| m.lock),
+            hooks,
         })
     }
 
@@ -65,6 +173,168 @@ impl Migrator {
         self
     }
 
+    /// Specify versions whose checksum may differ from the one recorded when they were applied,
+    /// e.g. because their file was reformatted, logging a warning instead of returning an error.
+    ///
+    /// To update the recorded checksums instead, see [`repair`][Self::repair].
+    pub fn set_allow_checksum_mismatch(
+        &mut self,
+        versions: impl IntoIterator<Item = i64>,
+    ) -> &Self {
+        self.allow_checksum_mismatch = versions.into_iter().collect();
+        self
+    }
+
+    /// Specify what to do with a pending migration whose version is lower than that of the latest
+    /// applied migration, e.g. one added on a branch merged after a newer migration was applied.
+    ///
+    /// Defaults to [`OutOfOrder::ApplyMissing`].
+    pub fn set_out_of_order(&mut self, out_of_order: OutOfOrder) -> &Self {
+        self.out_of_order = out_of_order;
+        self
+    }
+
+    /// Specify the table recording applied migrations, `_sqlx_migrations` by default, e.g. so that
+    /// several services sharing a database keep separate histories.
+    ///
+    /// The name may be qualified by a schema, as in `ops._my_service_migrations`, which must
+    /// exist. It is included in the SQL as is, so it must be a valid identifier and must not come
+    /// from untrusted input.
+    ///
+    /// Reference data versioned apart from the schema, e.g. in a `seeds` directory, can be applied
+    /// by a second migrator recording it in [`DEFAULT_SEEDS_TABLE_NAME`], and skipped where it
+    /// isn't wanted:
+    ///
+    /// ```rust,ignore
+    /// use sqlx::migrate::DEFAULT_SEEDS_TABLE_NAME;
+    ///
+    /// sqlx::migrate!().run(&pool).await?;
+    ///
+    /// if seed {
+    ///     let mut seeds = sqlx::migrate!("./seeds");
+    ///     seeds.set_table_name(DEFAULT_SEEDS_TABLE_NAME);
+    ///     seeds.run(&pool).await?;
+    /// }
+    /// ```
+    pub fn set_table_name(&mut self, table_name: impl Into<Cow<'static, str>>) -> &Self {
+        self.table_name = table_name.into();
+        self
+    }
+
+    /// Specify whether to lock the database while migrating, `true` by default, so that migrators
+    /// running concurrently against it, e.g. in several deploy jobs, don't apply the same
+    /// migrations. See [`MigrationLock`].
+    ///
+    /// Disabling the lock is an escape hatch, e.g. if a killed migrator left a SQLite lock behind,
+    /// or for databases where only one migrator can ever run.
+    pub fn set_locking(&mut self, locking: bool) -> &Self {
+        self.locking = locking;
+        self
+    }
+
+    /// Specify the key of the lock taken while migrating, derived from the name of the database by
+    /// default, e.g. so that migrators of unrelated histories in the same database don't wait for
+    /// each other.
+    pub fn set_lock_key(&mut self, key: i64) -> &Self {
+        self.lock.key = Some(key);
+        self
+    }
+
+    /// Specify the longest time to wait for the lock taken while migrating, after which
+    /// [`MigrateError::LockTimeout`] is returned; by default, wait as long as it takes.
+    ///
+    /// MySQL rounds the timeout up to whole seconds.
+    pub fn set_lock_timeout(&mut self, timeout: Duration) -> &Self {
+        self.lock.timeout = Some(timeout);
+        self
+    }
+
+    /// Write the schema of the database to a file, e.g. `schema.sql`, each time migrations are
+    /// run successfully, so that it can be committed to give code review a view of the schema and
+    /// to detect differences between environments. See [`dump_schema`][Self::dump_schema].
+    pub fn set_schema_dump(&mut self, path: impl Into<PathBuf>) -> &Self {
+        self.schema_dump = Some(path.into());
+        self
+    }
+
+    /// Register a callback invoked before each migration is applied or reverted, e.g. to emit an
+    /// audit event or pause background workers. If it returns an error, the migration is not run
+    /// and [`MigrateError::Hook`] is returned.
+    ///
+    /// Callbacks are invoked in the order they were registered.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::migrate::MigrateError;
+    /// # fn main() -> Result<(), MigrateError> {
+    /// # sqlx_rt::block_on(async move {
+    /// # use sqlx_core::migrate::Migrator;
+    /// let mut m = Migrator::new(std::path::Path::new("./migrations")).await?;
+    ///
+    /// m.before_each(|migration| {
+    ///     Box::pin(async move {
+    ///         println!("running {} {}", migration.version, migration.description);
+    ///         Ok(())
+    ///     })
+    /// });
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub fn before_each<F>(&mut self, hook: F) -> &Self
+    where
+        F: Fn(MigrationInfo) -> BoxFuture<'static, Result<(), BoxDynError>> + 'static + Send + Sync,
+    {
+        self.hooks.before.push(Arc::new(hook));
+        self
+    }
+
+    /// Register a callback invoked after each migration is applied or reverted, with the time it
+    /// took in [`MigrationInfo::elapsed`]. If it returns an error, [`MigrateError::Hook`] is
+    /// returned without running the remaining migrations.
+    ///
+    /// See [`before_each`][Self::before_each] for an example.
+    pub fn after_each<F>(&mut self, hook: F) -> &Self
+    where
+        F: Fn(MigrationInfo) -> BoxFuture<'static, Result<(), BoxDynError>> + 'static + Send + Sync,
+    {
+        self.hooks.after.push(Arc::new(hook));
+        self
+    }
+
+    /// Add a migration, e.g. one implemented in Rust with [`Migration::from_fn`], to be applied in
+    /// order of version with the others.
+    ///
+    /// Returns an error if there already is a migration of the same version and direction.
+    ///
+    /// To add migrations to a `static` migrator embedded with `migrate!()`, clone it first:
+    ///
+    /// ```rust,ignore
+    /// static MIGRATOR: Migrator = sqlx::migrate!();
+    ///
+    /// let mut migrator = MIGRATOR.clone();
+    /// migrator.add_migration(hash_api_keys())?;
+    /// migrator.run(&pool).await?;
+    /// ```
+    pub fn add_migration(&mut self, migration: Migration) -> Result<(), MigrateError> {
+        let is_down = migration.migration_type.is_down_migration();
+
+        if self.iter().any(|m| {
+            m.version == migration.version && m.migration_type.is_down_migration() == is_down
+        }) {
+            return Err(MigrateError::VersionCollision(migration.version));
+        }
+
+        let migrations = self.migrations.to_mut();
+        migrations.push(migration);
+
+        // ensure that we are sorted by `VERSION ASC`
+        migrations.sort_by_key(|m| m.version);
+
+        Ok(())
+    }
+
     /// Get an iterator over all known migrations.
     pub fn iter(&self) -> slice::Iter<'_, Migration> {
         self.migrations.iter()
@@ -73,6 +343,9 @@ impl Migrator {
     /// Run any pending migrations against the database; and, validate previously applied migrations
     /// against the current migration source to detect accidental changes in previously-applied migrations.
     ///
+    /// A [resumable](super::MigrationHeaders::resumable) migration which failed in a previous run
+    /// is resumed first, from the statement that failed.
+    ///
     /// # Examples
     ///
     /// ```rust,no_run
@@ -88,6 +361,31 @@ impl Migrator {
     /// # }
     /// ```
     pub async fn run<'a, A>(&self, migrator: A) -> Result<(), MigrateError>
+    where
+        A: Acquire<'a>,
+        <A::Connection as Deref>::Target: Migrate,
+    {
+        self.run_to(migrator, i64::MAX).await
+    }
+
+    /// Run pending migrations against the database up to and including a specific version, and
+    /// validate previously applied migrations as [`run`][Self::run] does.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::migrate::MigrateError;
+    /// # #[cfg(feature = "sqlite")]
+    /// # fn main() -> Result<(), MigrateError> {
+    /// #     sqlx_rt::block_on(async move {
+    /// # use sqlx_core::migrate::Migrator;
+    /// let m = Migrator::new(std::path::Path::new("./migrations")).await?;
+    /// let pool = sqlx_core::sqlite::SqlitePoolOptions::new().connect("sqlite::memory:").await?;
+    /// m.run_to(&pool, 4).await
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn run_to<'a, A>(&self, migrator: A, target: i64) -> Result<(), MigrateError>
     where
         A: Acquire<'a>,
         <A::Connection as Deref>::Target: Migrate,
@@ -95,18 +393,128 @@ impl Migrator {
         let mut conn = migrator.acquire().await?;
 
         // lock the database for exclusive access by the migrator
-        conn.lock().await?;
+        self.lock(&mut *conn).await?;
 
-        // creates [_migrations] table only if needed
-        // eventually this will likely migrate previous versions of the table
-        conn.ensure_migrations_table().await?;
+        let result: Result<_, MigrateError> = async {
+            // creates [_migrations] table only if needed
+            // eventually this will likely migrate previous versions of the table
+            conn.ensure_migrations_table(&self.table_name).await?;
 
-        let version = conn.dirty_version().await?;
-        if let Some(version) = version {
-            return Err(MigrateError::Dirty(version));
+            // a resumable migration which failed is resumed from the statement that failed
+            let version = conn.dirty_version(&self.table_name).await?;
+            if let Some(version) = version {
+                let migration = self.resumable(version)?;
+                self.execute(&mut *conn, migration).await?;
+            }
+
+            let applied_migrations = conn.list_applied_migrations(&self.table_name).await?;
+
+            for migration in self.pending(applied_migrations, target)? {
+                self.execute(&mut *conn, migration).await?;
+            }
+
+            if let Some(path) = &self.schema_dump {
+                let schema = conn.dump_schema(&self.table_name).await?;
+
+                fs::write(path, schema)
+                    .await
+                    .map_err(MigrateError::SchemaDump)?;
+            }
+
+            Ok(())
         }
+        .await;
+
+        self.unlock(&mut *conn, result).await
+    }
 
-        let applied_migrations = conn.list_applied_migrations().await?;
+    /// List the migrations that [`run`][Self::run] would apply, in order, without applying them;
+    /// e.g. to review the SQL a deployment will run. Previously applied migrations are validated
+    /// as they are by `run`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::migrate::MigrateError;
+    /// # #[cfg(feature = "sqlite")]
+    /// # fn main() -> Result<(), MigrateError> {
+    /// #     sqlx_rt::block_on(async move {
+    /// # use sqlx_core::migrate::Migrator;
+    /// let m = Migrator::new(std::path::Path::new("./migrations")).await?;
+    /// let pool = sqlx_core::sqlite::SqlitePoolOptions::new().connect("sqlite::memory:").await?;
+    ///
+    /// for migration in m.plan(&pool).await? {
+    ///     println!("-- {} {}\n{}", migration.version, migration.description, migration.sql);
+    /// }
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn plan<'a, A>(&self, migrator: A) -> Result<Vec<&Migration>, MigrateError>
+    where
+        A: Acquire<'a>,
+        <A::Connection as Deref>::Target: Migrate,
+    {
+        let mut conn = migrator.acquire().await?;
+
+        conn.ensure_migrations_table(&self.table_name).await?;
+
+        // a resumable migration which failed is listed as pending
+        let resumed = match conn.dirty_version(&self.table_name).await? {
+            Some(version) => Some(self.resumable(version)?.version),
+            None => None,
+        };
+
+        let applied_migrations = conn
+            .list_applied_migrations(&self.table_name)
+            .await?
+            .into_iter()
+            .filter(|m| Some(m.version) != resumed)
+            .collect();
+
+        self.pending(applied_migrations, i64::MAX)
+    }
+
+    /// Return the DDL creating the schema of the database, without the migrations table.
+    ///
+    /// The statements are generated from the catalog of the database, in a stable order: for
+    /// Postgres, the enum types, tables, indexes and views of the current schema; for MySQL, the
+    /// output of `SHOW CREATE TABLE` and `SHOW CREATE VIEW`; for SQLite, the statements recorded
+    /// in `sqlite_master`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::migrate::MigrateError;
+    /// # #[cfg(feature = "sqlite")]
+    /// # fn main() -> Result<(), MigrateError> {
+    /// #     sqlx_rt::block_on(async move {
+    /// # use sqlx_core::migrate::Migrator;
+    /// let m = Migrator::new(std::path::Path::new("./migrations")).await?;
+    /// let pool = sqlx_core::sqlite::SqlitePoolOptions::new().connect("sqlite::memory:").await?;
+    /// m.run(&pool).await?;
+    ///
+    /// println!("{}", m.dump_schema(&pool).await?);
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn dump_schema<'a, A>(&self, migrator: A) -> Result<String, MigrateError>
+    where
+        A: Acquire<'a>,
+        <A::Connection as Deref>::Target: Migrate,
+    {
+        let mut conn = migrator.acquire().await?;
+
+        conn.dump_schema(&self.table_name).await
+    }
+
+    /// Validate the applied migrations and find the pending ones up to and including `target`.
+    fn pending(
+        &self,
+        applied_migrations: Vec<AppliedMigration>,
+        target: i64,
+    ) -> Result<Vec<&Migration>, MigrateError> {
         validate_applied_migrations(&applied_migrations, self)?;
 
         let applied_migrations: HashMap<_, _> = applied_migrations
@@ -114,32 +522,74 @@ impl Migrator {
             .map(|m| (m.version, m))
             .collect();
 
+        let latest = applied_migrations.keys().copied().max().unwrap_or(i64::MIN);
+
+        let mut pending = Vec::new();
+
         for migration in self.iter() {
             if migration.migration_type.is_down_migration() {
                 continue;
             }
 
+            let squash = migration.is_squash();
+
             match applied_migrations.get(&migration.version) {
+                // either the squash migration itself or the last migration it replaces
+                Some(_) if squash => {}
                 Some(applied_migration) => {
                     if migration.checksum != applied_migration.checksum {
-                        return Err(MigrateError::VersionMismatch(migration.version));
+                        if !self.allow_checksum_mismatch.contains(&migration.version) {
+                            return Err(MigrateError::VersionMismatch(migration.version));
+                        }
+
+                        log::warn!(
+                            "migration {} was previously applied but has been modified",
+                            migration.version
+                        );
                     }
                 }
-                None => {
-                    conn.apply(migration).await?;
+                None if squash && applied_migrations.keys().any(|&v| v < migration.version) => {
+                    return Err(MigrateError::PartiallySquashed(migration.version));
                 }
+                None if migration.version > target => {}
+                None if migration.version < latest => match self.out_of_order {
+                    OutOfOrder::Error => return Err(MigrateError::OutOfOrder(migration.version)),
+                    OutOfOrder::Warn => log::warn!(
+                        "migration {} is older than the latest applied migration {}, skipping it",
+                        migration.version,
+                        latest
+                    ),
+                    OutOfOrder::ApplyMissing => pending.push(migration),
+                },
+                None => pending.push(migration),
             }
         }
 
-        // unlock the migrator to allow other migrators to run
-        // but do nothing as we already migrated
-        conn.unlock().await?;
+        Ok(pending)
+    }
 
-        Ok(())
+    /// The version up to which migrations have been squashed, if any.
+    fn squashed_version(&self) -> Option<i64> {
+        self.iter()
+            .filter(|m| !m.migration_type.is_down_migration() && m.is_squash())
+            .map(|m| m.version)
+            .max()
     }
 
     /// Run down migrations against the database until a specific version.
     ///
+    /// This is the same as [`undo_to`][Self::undo_to].
+    pub async fn undo<'a, A>(&self, migrator: A, target: i64) -> Result<(), MigrateError>
+    where
+        A: Acquire<'a>,
+        <A::Connection as Deref>::Target: Migrate,
+    {
+        self.undo_to(migrator, target).await
+    }
+
+    /// Run down migrations against the database until a specific version, reverting every applied
+    /// migration newer than `target`.
+    ///
     /// # Examples
     ///
     /// ```rust,no_run
@@ -150,11 +600,11 @@ impl Migrator {
     /// # use sqlx_core::migrate::Migrator;
     /// let m = Migrator::new(std::path::Path::new("./migrations")).await?;
     /// let pool = sqlx_core::sqlite::SqlitePoolOptions::new().connect("sqlite::memory:").await?;
-    /// m.undo(&pool, 4).await
+    /// m.undo_to(&pool, 4).await
     /// #     })
     /// # }
     /// ```
-    pub async fn undo<'a, A>(&self, migrator: A, target: i64) -> Result<(), MigrateError>
+    pub async fn undo_to<'a, A>(&self, migrator: A, target: i64) -> Result<(), MigrateError>
     where
         A: Acquire<'a>,
         <A::Connection as Deref>::Target: Migrate,
@@ -162,39 +612,291 @@ impl Migrator {
         let mut conn = migrator.acquire().await?;
 
         // lock the database for exclusive access by the migrator
-        conn.lock().await?;
+        self.lock(&mut *conn).await?;
+
+        let result: Result<_, MigrateError> = async {
+            // creates [_migrations] table only if needed
+            // eventually this will likely migrate previous versions of the table
+            conn.ensure_migrations_table(&self.table_name).await?;
+
+            let version = conn.dirty_version(&self.table_name).await?;
+            if let Some(version) = version {
+                return Err(MigrateError::Dirty(version));
+            }
+
+            let applied_migrations = conn.list_applied_migrations(&self.table_name).await?;
+            validate_applied_migrations(&applied_migrations, self)?;
+
+            let applied_migrations: HashMap<_, _> = applied_migrations
+                .into_iter()
+                .map(|m| (m.version, m))
+                .collect();
 
-        // creates [_migrations] table only if needed
-        // eventually this will likely migrate previous versions of the table
-        conn.ensure_migrations_table().await?;
+            for migration in self
+                .iter()
+                .rev()
+                .filter(|m| m.migration_type.is_down_migration())
+                .filter(|m| applied_migrations.contains_key(&m.version))
+                .filter(|m| m.version > target)
+            {
+                self.execute(&mut *conn, migration).await?;
+            }
 
-        let version = conn.dirty_version().await?;
-        if let Some(version) = version {
-            return Err(MigrateError::Dirty(version));
+            Ok(())
         }
+        .await;
 
-        let applied_migrations = conn.list_applied_migrations().await?;
-        validate_applied_migrations(&applied_migrations, self)?;
+        self.unlock(&mut *conn, result).await
+    }
 
-        let applied_migrations: HashMap<_, _> = applied_migrations
-            .into_iter()
-            .map(|m| (m.version, m))
-            .collect();
+    /// Update the checksums recorded for applied migrations whose file has been modified since,
+    /// e.g. reformatted, so that they no longer cause [`MigrateError::VersionMismatch`]. Returns
+    /// the versions that were repaired.
+    ///
+    /// The migrations are not run again, so this should only be used after checking that the
+    /// changes don't affect the schema.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::migrate::MigrateError;
+    /// # #[cfg(feature = "sqlite")]
+    /// # fn main() -> Result<(), MigrateError> {
+    /// #     sqlx_rt::block_on(async move {
+    /// # use sqlx_core::migrate::Migrator;
+    /// let m = Migrator::new(std::path::Path::new("./migrations")).await?;
+    /// let pool = sqlx_core::sqlite::SqlitePoolOptions::new().connect("sqlite::memory:").await?;
+    /// let repaired = m.repair(&pool).await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn repair<'a, A>(&self, migrator: A) -> Result<Vec<i64>, MigrateError>
+    where
+        A: Acquire<'a>,
+        <A::Connection as Deref>::Target: Migrate,
+    {
+        let mut conn = migrator.acquire().await?;
 
-        for migration in self
-            .iter()
-            .rev()
-            .filter(|m| m.migration_type.is_down_migration())
-            .filter(|m| applied_migrations.contains_key(&m.version))
-            .filter(|m| m.version > target)
-        {
-            conn.revert(migration).await?;
+        // lock the database for exclusive access by the migrator
+        self.lock(&mut *conn).await?;
+
+        let result: Result<_, MigrateError> = async {
+            conn.ensure_migrations_table(&self.table_name).await?;
+
+            let applied_migrations = conn.list_applied_migrations(&self.table_name).await?;
+            validate_applied_migrations(&applied_migrations, self)?;
+
+            let applied_migrations: HashMap<_, _> = applied_migrations
+                .into_iter()
+                .map(|m| (m.version, m))
+                .collect();
+
+            let mut repaired = Vec::new();
+
+            for migration in self.iter() {
+                if migration.migration_type.is_down_migration() {
+                    continue;
+                }
+
+                match applied_migrations.get(&migration.version) {
+                    // may have been recorded for the last of the migrations it replaces
+                    _ if migration.is_squash() => {}
+                    Some(applied) if applied.checksum != migration.checksum => {
+                        conn.update_checksum(&self.table_name, migration).await?;
+                        repaired.push(migration.version);
+                    }
+                    _ => {}
+                }
+            }
+
+            Ok(repaired)
+        }
+        .await;
+
+        self.unlock(&mut *conn, result).await
+    }
+
+    /// Mark the migrations up to and including `version` as applied without running them, e.g.
+    /// to start managing an existing database whose schema already matches them. Returns the
+    /// versions that were marked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::migrate::MigrateError;
+    /// # #[cfg(feature = "sqlite")]
+    /// # fn main() -> Result<(), MigrateError> {
+    /// #     sqlx_rt::block_on(async move {
+    /// # use sqlx_core::migrate::Migrator;
+    /// let m = Migrator::new(std::path::Path::new("./migrations")).await?;
+    /// let pool = sqlx_core::sqlite::SqlitePoolOptions::new().connect("sqlite::memory:").await?;
+    /// let marked = m.baseline(&pool, 20220801000000).await?;
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn baseline<'a, A>(&self, migrator: A, version: i64) -> Result<Vec<i64>, MigrateError>
+    where
+        A: Acquire<'a>,
+        <A::Connection as Deref>::Target: Migrate,
+    {
+        let mut conn = migrator.acquire().await?;
+
+        // lock the database for exclusive access by the migrator
+        self.lock(&mut *conn).await?;
+
+        let result: Result<_, MigrateError> = async {
+            conn.ensure_migrations_table(&self.table_name).await?;
+
+            let dirty = conn.dirty_version(&self.table_name).await?;
+            if let Some(dirty) = dirty {
+                return Err(MigrateError::Dirty(dirty));
+            }
+
+            let applied_migrations = conn.list_applied_migrations(&self.table_name).await?;
+
+            let mut marked = Vec::new();
+
+            for migration in self.pending(applied_migrations, version)? {
+                conn.record_applied(&self.table_name, migration).await?;
+                marked.push(migration.version);
+            }
+
+            Ok(marked)
+        }
+        .await;
+
+        self.unlock(&mut *conn, result).await
+    }
+
+    /// Revert the latest applied migration and apply it again, e.g. after editing it during
+    /// development.
+    ///
+    /// The migration is applied from the current source, so its checksum is allowed to differ
+    /// from the one recorded when it was first applied. Returns an error if it has no down
+    /// migration; does nothing if no migration is applied.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::migrate::MigrateError;
+    /// # #[cfg(feature = "sqlite")]
+    /// # fn main() -> Result<(), MigrateError> {
+    /// #     sqlx_rt::block_on(async move {
+    /// # use sqlx_core::migrate::Migrator;
+    /// let m = Migrator::new(std::path::Path::new("./migrations")).await?;
+    /// let pool = sqlx_core::sqlite::SqlitePoolOptions::new().connect("sqlite::memory:").await?;
+    /// m.redo(&pool).await
+    /// #     })
+    /// # }
+    /// ```
+    pub async fn redo<'a, A>(&self, migrator: A) -> Result<(), MigrateError>
+    where
+        A: Acquire<'a>,
+        <A::Connection as Deref>::Target: Migrate,
+    {
+        let mut conn = migrator.acquire().await?;
+
+        // lock the database for exclusive access by the migrator
+        self.lock(&mut *conn).await?;
+
+        let result: Result<_, MigrateError> = async {
+            conn.ensure_migrations_table(&self.table_name).await?;
+
+            let version = conn.dirty_version(&self.table_name).await?;
+            if let Some(version) = version {
+                return Err(MigrateError::Dirty(version));
+            }
+
+            let applied_migrations = conn.list_applied_migrations(&self.table_name).await?;
+            validate_applied_migrations(&applied_migrations, self)?;
+
+            if let Some(latest) = applied_migrations.iter().map(|m| m.version).max() {
+                let (up, down) = self.find_reversible(latest)?;
+
+                self.execute(&mut *conn, down).await?;
+                self.execute(&mut *conn, up).await?;
+            }
+
+            Ok(())
         }
+        .await;
+
+        self.unlock(&mut *conn, result).await
+    }
 
-        // unlock the migrator to allow other migrators to run
-        // but do nothing as we already migrated
-        conn.unlock().await?;
+    /// Lock the database for exclusive access by the migrator, unless locking is disabled.
+    async fn lock<C>(&self, conn: &mut C) -> Result<(), MigrateError>
+    where
+        C: Migrate + ?Sized,
+    {
+        if self.locking {
+            conn.lock(self.lock).await?;
+        }
 
         Ok(())
     }
+
+    /// Release the lock taken by [`lock`][Self::lock] to allow other migrators to run, whether
+    /// what was done while holding it succeeded or not, and return its result.
+    async fn unlock<C, T>(
+        &self,
+        conn: &mut C,
+        result: Result<T, MigrateError>,
+    ) -> Result<T, MigrateError>
+    where
+        C: Migrate + ?Sized,
+    {
+        if !self.locking {
+            return result;
+        }
+
+        let unlocked = conn.unlock(self.lock).await;
+
+        // an error while holding the lock takes precedence
+        let value = result?;
+        unlocked?;
+
+        Ok(value)
+    }
+
+    /// Apply or, for a down migration, revert the migration, invoking the hooks around it.
+    async fn execute<C>(&self, conn: &mut C, migration: &Migration) -> Result<(), MigrateError>
+    where
+        C: Migrate + ?Sized,
+    {
+        self.hooks.run_before(migration).await?;
+
+        let elapsed = if migration.migration_type.is_down_migration() {
+            conn.revert(&self.table_name, migration).await?
+        } else {
+            conn.apply(&self.table_name, migration).await?
+        };
+
+        self.hooks.run_after(migration, elapsed).await
+    }
+
+    /// Find the resumable migration of a dirty version, see [`MigrationHeaders::resumable`].
+    ///
+    /// [`MigrationHeaders::resumable`]: super::MigrationHeaders::resumable
+    fn resumable(&self, version: i64) -> Result<&Migration, MigrateError> {
+        self.iter()
+            .find(|m| m.version == version && m.is_resumable())
+            .ok_or(MigrateError::Dirty(version))
+    }
+
+    /// Find the up and down migrations of a version.
+    fn find_reversible(&self, version: i64) -> Result<(&Migration, &Migration), MigrateError> {
+        let find = |down: bool| {
+            self.iter()
+                .find(|m| m.version == version && m.migration_type.is_down_migration() == down)
+        };
+
+        match (find(false), find(true)) {
+            (Some(up), Some(down)) => Ok((up, down)),
+            (Some(_), None) => Err(MigrateError::NotReversible(version)),
+            (None, _) => Err(MigrateError::VersionMissing(version)),
+        }
+    }
 }
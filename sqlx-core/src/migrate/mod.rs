@@ -1,14 +1,35 @@
+#[cfg(any(feature = "migrate-tar", feature = "migrate-zip"))]
+mod archive;
+mod batch;
+mod code;
+mod create_options;
 mod error;
+mod headers;
+mod hooks;
+mod lock;
 #[allow(clippy::module_inception)]
 mod migrate;
 mod migration;
 mod migration_type;
 mod migrator;
+mod out_of_order;
 mod source;
 
+#[cfg(feature = "migrate-tar")]
+pub use archive::TarArchive;
+#[cfg(feature = "migrate-zip")]
+pub use archive::ZipArchive;
+pub use batch::{BatchProgress, BatchedQuery};
+pub use code::MigrationCode;
+pub use create_options::CreateDatabaseOptions;
 pub use error::MigrateError;
+pub use headers::MigrationHeaders;
+pub use hooks::{MigrationHooks, MigrationInfo};
+pub(crate) use lock::LockAttempts;
+pub use lock::MigrationLock;
 pub use migrate::{Migrate, MigrateDatabase};
 pub use migration::{AppliedMigration, Migration};
 pub use migration_type::MigrationType;
-pub use migrator::Migrator;
-pub use source::MigrationSource;
+pub use migrator::{Migrator, DEFAULT_SEEDS_TABLE_NAME, DEFAULT_TABLE_NAME};
+pub use out_of_order::OutOfOrder;
+pub use source::{MigrationFiles, MigrationSource};
@@ -0,0 +1,40 @@
+use std::str::FromStr;
+
+/// What [`Migrator::run`][super::Migrator::run] does with a pending migration whose version is
+/// lower than that of the latest applied migration, e.g. one added on a branch that was merged
+/// after a newer migration was applied.
+///
+/// Policies are ordered from the strictest to the most permissive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OutOfOrder {
+    /// Return [`MigrateError::OutOfOrder`][super::MigrateError::OutOfOrder].
+    Error,
+
+    /// Log a warning and leave the migration pending.
+    Warn,
+
+    /// Apply the migration, as if it were newer than the applied migrations.
+    ApplyMissing,
+}
+
+impl Default for OutOfOrder {
+    fn default() -> Self {
+        OutOfOrder::ApplyMissing
+    }
+}
+
+impl FromStr for OutOfOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(OutOfOrder::Error),
+            "warn" => Ok(OutOfOrder::Warn),
+            "apply-missing" => Ok(OutOfOrder::ApplyMissing),
+            _ => Err(format!(
+                "unknown out-of-order policy {:?}, expected `error`, `warn` or `apply-missing`",
+                s
+            )),
+        }
+    }
+}
@@ -7,6 +7,35 @@ use std::borrow::Cow;
 use std::fmt::Debug;
 use std::path::{Path, PathBuf};
 
+/// A source of migrations, see [`Migrator::new`][crate::migrate::Migrator::new].
+///
+/// It is implemented for a directory of migration files ([`Path`] and [`PathBuf`]), for files
+/// given in memory ([`MigrationFiles`]), and for tar and zip archives with the `migrate-tar` and
+/// `migrate-zip` features ([`TarArchive`][crate::migrate::TarArchive] and
+/// [`ZipArchive`][crate::migrate::ZipArchive]).
+///
+/// It can be implemented to load migrations from elsewhere, e.g. object storage or a
+/// configuration service:
+///
+/// ```rust,ignore
+/// use sqlx::migrate::{MigrationFiles, MigrationSource, Migrator};
+///
+/// #[derive(Debug)]
+/// struct Bucket(String);
+///
+/// impl MigrationSource<'static> for Bucket {
+///     fn resolve(self) -> BoxFuture<'static, Result<Vec<Migration>, BoxDynError>> {
+///         Box::pin(async move {
+///             // the names and contents of the migration files
+///             let files: Vec<(String, String)> = download(&self.0).await?;
+///
+///             MigrationFiles::new(files).resolve().await
+///         })
+///     }
+/// }
+///
+/// let migrator = Migrator::new(Bucket("s3://my-bucket/migrations".into())).await?;
+/// ```
 pub trait MigrationSource<'s>: Debug {
     fn resolve(self) -> BoxFuture<'s, Result<Vec<Migration>, BoxDynError>>;
 }
@@ -16,7 +45,8 @@ pub trait MigrationSource<'s>: Debug {
 /// The path has to point to a directory, which contains the migration SQL scripts. All these
 /// scripts must be stored in files with names using the format `<VERSION>_<DESCRIPTION>.sql`,
 /// where `<VERSION>` is a string that can be parsed into `i64` and its value is greater than zero,
-/// and `<DESCRIPTION>` is a string.
+/// and `<DESCRIPTION>` is a string. Each script may start with comment headers configuring how it
+/// is run, see [`MigrationHeaders`][crate::migrate::MigrationHeaders].
 impl<'s> MigrationSource<'s> for &'s Path {
     fn resolve(self) -> BoxFuture<'s, Result<Vec<Migration>, BoxDynError>> {
         Box::pin(async move {
@@ -34,32 +64,11 @@ impl<'s> MigrationSource<'s> for &'s Path {
                 }
 
                 let file_name = entry.file_name();
-                let file_name = file_name.to_string_lossy();
-
-                let parts = file_name.splitn(2, '_').collect::<Vec<_>>();
 
-                if parts.len() != 2 || !parts[1].ends_with(".sql") {
-                    // not of the format: <VERSION>_<DESCRIPTION>.sql; ignore
-                    continue;
+                if let Some(name) = MigrationFileName::parse(&file_name.to_string_lossy())? {
+                    let sql = fs::read_to_string(&entry.path()).await?;
+                    migrations.push(name.into_migration(sql)?);
                 }
-
-                let version: i64 = parts[0].parse()?;
-
-                let migration_type = MigrationType::from_filename(parts[1]);
-                // remove the `.sql` and replace `_` with ` `
-                let description = parts[1]
-                    .trim_end_matches(migration_type.suffix())
-                    .replace('_', " ")
-                    .to_owned();
-
-                let sql = fs::read_to_string(&entry.path()).await?;
-
-                migrations.push(Migration::new(
-                    version,
-                    Cow::Owned(description),
-                    migration_type,
-                    Cow::Owned(sql),
-                ));
             }
 
             // ensure that we are sorted by `VERSION ASC`
@@ -75,3 +84,121 @@ impl MigrationSource<'static> for PathBuf {
         Box::pin(async move { self.as_path().resolve().await })
     }
 }
+
+/// Migrations given as the names and contents of their files, e.g. embedded with
+/// [`include_str!`] or downloaded, named as the files of a migrations directory (see the
+/// implementation of [`MigrationSource`] for [`Path`]). Files with other names are ignored, as are
+/// the directories in the names.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use sqlx_core::migrate::MigrateError;
+/// # fn main() -> Result<(), MigrateError> {
+/// # sqlx_rt::block_on(async move {
+/// # use sqlx_core::migrate::{MigrationFiles, Migrator};
+/// let m = Migrator::new(MigrationFiles::new([
+///     ("20220801000000_users.sql", "CREATE TABLE users (id BIGINT PRIMARY KEY);"),
+///     ("20220802000000_posts.sql", "CREATE TABLE posts (id BIGINT PRIMARY KEY);"),
+/// ]))
+/// .await?;
+/// # Ok(())
+/// # })
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MigrationFiles {
+    files: Vec<(String, String)>,
+}
+
+impl MigrationFiles {
+    pub fn new<I, N, S>(files: I) -> Self
+    where
+        I: IntoIterator<Item = (N, S)>,
+        N: Into<String>,
+        S: Into<String>,
+    {
+        MigrationFiles {
+            files: files
+                .into_iter()
+                .map(|(name, sql)| (name.into(), sql.into()))
+                .collect(),
+        }
+    }
+}
+
+impl MigrationSource<'static> for MigrationFiles {
+    fn resolve(self) -> BoxFuture<'static, Result<Vec<Migration>, BoxDynError>> {
+        Box::pin(async move { resolve_files(self.files) })
+    }
+}
+
+/// Parse the migrations among the files with the given names and contents, sorted by version.
+pub(crate) fn resolve_files(
+    files: impl IntoIterator<Item = (String, String)>,
+) -> Result<Vec<Migration>, BoxDynError> {
+    let mut migrations = Vec::new();
+
+    for (name, sql) in files {
+        if let Some(name) = MigrationFileName::parse(&name)? {
+            migrations.push(name.into_migration(sql)?);
+        }
+    }
+
+    // ensure that we are sorted by `VERSION ASC`
+    migrations.sort_by_key(|m| m.version);
+
+    Ok(migrations)
+}
+
+/// The parts of the name of a migration file.
+pub(crate) struct MigrationFileName {
+    version: i64,
+    description: String,
+    migration_type: MigrationType,
+}
+
+impl MigrationFileName {
+    /// Parse the name of a file, ignoring its directories, if it is a migration.
+    pub(crate) fn parse(path: &str) -> Result<Option<Self>, BoxDynError> {
+        let file_name = path
+            .rsplit(|c| c == '/' || c == '\\')
+            .next()
+            .unwrap_or(path);
+
+        let parts = file_name.splitn(2, '_').collect::<Vec<_>>();
+
+        if parts.len() != 2 || !parts[1].ends_with(".sql") {
+            // not of the format: <VERSION>_<DESCRIPTION>.sql; ignore
+            return Ok(None);
+        }
+
+        let version: i64 = parts[0].parse()?;
+
+        let migration_type = MigrationType::from_filename(parts[1]);
+        // remove the `.sql` and replace `_` with ` `
+        let description = parts[1]
+            .trim_end_matches(migration_type.suffix())
+            .replace('_', " ");
+
+        Ok(Some(MigrationFileName {
+            version,
+            description,
+            migration_type,
+        }))
+    }
+
+    pub(crate) fn into_migration(self, sql: String) -> Result<Migration, BoxDynError> {
+        let migration = Migration::new(
+            self.version,
+            Cow::Owned(self.description),
+            self.migration_type,
+            Cow::Owned(sql),
+        );
+
+        // report invalid headers before running any migration
+        migration.headers()?;
+
+        Ok(migration)
+    }
+}
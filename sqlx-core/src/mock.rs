@@ -0,0 +1,612 @@
+//! An in-memory database to run the unit tests of code using [`Any`] with, without a database
+//! server or network.
+//!
+//! A [`MockDatabase`] is given the statements it expects, in order, with the arguments they're
+//! expected to be bound with and the rows they return. Its connections are opened at its
+//! [URL](MockDatabase::url) like those of any other database:
+//!
+//! ```rust,ignore
+//! use sqlx::mock::{MockDatabase, MockStatement};
+//!
+//! let db = MockDatabase::new();
+//!
+//! db.expect(
+//!     MockStatement::new("SELECT id, name FROM users WHERE id = ?")
+//!         .bind(1_i64)
+//!         .columns(["id", "name"])
+//!         .row((1_i64, "Alice")),
+//! );
+//!
+//! db.expect(
+//!     MockStatement::new("UPDATE users SET name = ? WHERE id = ?")
+//!         .bind("Bob")
+//!         .bind(1_i64)
+//!         .rows_affected(1),
+//! );
+//!
+//! let pool = AnyPool::connect(&db.url()).await?;
+//!
+//! // the code under test
+//! rename_user(&pool, 1, "Bob").await?;
+//!
+//! // panics if a statement was unexpected, or wasn't executed
+//! db.verify();
+//! ```
+//!
+//! The SQL of a statement is compared with the expected SQL with their runs of whitespace
+//! collapsed; its arguments are only compared if the expected statement was given any with
+//! [`bind()`](MockStatement::bind), or with [`no_binds()`](MockStatement::no_binds).
+//! Transactions are executed as the `BEGIN`, `COMMIT` and `ROLLBACK` statements, which are
+//! expected as any other.
+//!
+//! A statement which isn't the next one expected fails with [`Error::Protocol`], and fails the
+//! [verification](MockDatabase::verify) of the database even if the error is handled by the code
+//! under test.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Display, Formatter};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, Once, Weak};
+
+use either::Either;
+use futures_core::future::BoxFuture;
+use futures_core::stream::BoxStream;
+use futures_util::{future, stream};
+use once_cell::sync::Lazy;
+
+use crate::any::{
+    install_driver, Any, AnyArguments, AnyColumn, AnyConnection, AnyConnectionBackend, AnyData,
+    AnyDriver, AnyDriverConnectOptions, AnyQueryResult, AnyRow, AnyStatement, AnyTypeInfo,
+};
+use crate::arguments::Arguments;
+use crate::connection::Connection;
+use crate::encode::Encode;
+use crate::error::{DatabaseError, Error};
+use crate::types::Type;
+
+static DRIVER: AnyDriver = AnyDriver::new("mock", &["mock"], connect);
+
+static INSTALL: Once = Once::new();
+
+static DATABASES: Lazy<Mutex<HashMap<u64, Weak<Shared>>>> = Lazy::new(Default::default);
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A scripted in-memory database; see the [module documentation][self].
+///
+/// The clones of a database share its script, as do all the connections opened to it.
+#[derive(Debug, Clone)]
+pub struct MockDatabase {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    id: u64,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    expected: VecDeque<MockStatement>,
+    failures: Vec<String>,
+}
+
+impl MockDatabase {
+    /// A database expecting no statement yet.
+    ///
+    /// The `mock:` scheme is taken by the first database created, which panics if it's taken by
+    /// another installed driver.
+    pub fn new() -> Self {
+        INSTALL.call_once(|| install_driver(&DRIVER).expect("failed to install the mock driver"));
+
+        let shared = Arc::new(Shared {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            state: Mutex::default(),
+        });
+
+        lock(&DATABASES).insert(shared.id, Arc::downgrade(&shared));
+
+        Self { shared }
+    }
+
+    /// The URL to connect to the database at, with [`AnyConnection`] or
+    /// [`AnyPool`][crate::any::AnyPool].
+    pub fn url(&self) -> String {
+        format!("mock://{}", self.shared.id)
+    }
+
+    /// Opens a connection to the database.
+    pub async fn connect(&self) -> Result<AnyConnection, Error> {
+        AnyConnection::connect(&self.url()).await
+    }
+
+    /// Expects `statement` after the statements expected so far.
+    pub fn expect(&self, statement: MockStatement) -> &Self {
+        self.shared.lock().expected.push_back(statement);
+        self
+    }
+
+    /// Panics if a statement executed wasn't the one expected, or if an expected statement
+    /// wasn't executed.
+    pub fn verify(&self) {
+        let state = self.shared.lock();
+
+        let problems: Vec<String> = state
+            .failures
+            .iter()
+            .cloned()
+            .chain(
+                state
+                    .expected
+                    .iter()
+                    .map(|statement| format!("`{}` was expected, but not executed", statement.sql)),
+            )
+            .collect();
+
+        if !problems.is_empty() {
+            panic!("mock database: {}", problems.join("; "));
+        }
+    }
+}
+
+impl Default for MockDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        lock(&self.state)
+    }
+
+    // matches `sql` with the next expected statement, and returns its rows and result
+    fn execute(
+        &self,
+        sql: &str,
+        arguments: Option<Vec<AnyData>>,
+    ) -> Result<(Vec<AnyRow>, AnyQueryResult), Error> {
+        let sql = normalize(sql);
+        let arguments = arguments.unwrap_or_default();
+
+        let mut state = self.lock();
+
+        let failure = match state.expected.front_mut() {
+            None => format!("`{}` was executed, but no statement was expected", sql),
+
+            Some(expected) if expected.sql != sql => {
+                format!(
+                    "`{}` was executed, but `{}` was expected",
+                    sql, expected.sql
+                )
+            }
+
+            Some(MockStatement {
+                binds: Some(binds), ..
+            }) if *binds != arguments => {
+                format!(
+                    "`{}` was executed with the arguments {:?}, but {:?} were expected",
+                    sql, arguments, binds
+                )
+            }
+
+            Some(expected) => {
+                let response = expected.response();
+
+                expected.times -= 1;
+
+                if expected.times == 0 {
+                    state.expected.pop_front();
+                }
+
+                return response;
+            }
+        };
+
+        state.failures.push(failure.clone());
+
+        Err(Error::Protocol(format!("mock database: {}", failure)))
+    }
+}
+
+impl Drop for Shared {
+    fn drop(&mut self) {
+        lock(&DATABASES).remove(&self.id);
+    }
+}
+
+/// A statement expected by a [`MockDatabase`], and its response.
+///
+/// By default, the statement is expected once, with any arguments, and returns no rows.
+#[derive(Debug, Clone)]
+pub struct MockStatement {
+    sql: String,
+    binds: Option<Vec<AnyData>>,
+    columns: Vec<String>,
+    rows: Vec<Vec<AnyData>>,
+    rows_affected: u64,
+    last_insert_id: Option<i64>,
+    error: Option<MockDatabaseError>,
+    times: usize,
+}
+
+impl MockStatement {
+    pub fn new(sql: &str) -> Self {
+        Self {
+            sql: normalize(sql),
+            binds: None,
+            columns: Vec::new(),
+            rows: Vec::new(),
+            rows_affected: 0,
+            last_insert_id: None,
+            error: None,
+            times: 1,
+        }
+    }
+
+    /// Expects `value` as the next argument of the statement.
+    pub fn bind<T>(mut self, value: T) -> Self
+    where
+        T: 'static + Send + Encode<'static, Any> + Type<Any>,
+    {
+        self.binds.get_or_insert_with(Vec::new).push(encode(value));
+        self
+    }
+
+    /// Expects the statement to have no arguments.
+    pub fn no_binds(mut self) -> Self {
+        self.binds = Some(Vec::new());
+        self
+    }
+
+    /// Sets the names of the columns of the rows returned.
+    pub fn columns<I>(mut self, names: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.columns = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Adds a row to the rows returned, with a value for each of the [columns](Self::columns),
+    /// e.g. `(1_i64, "Alice")`.
+    ///
+    /// # Panics
+    ///
+    /// If it doesn't have a value for each column.
+    pub fn row(mut self, values: impl MockValues) -> Self {
+        let values = values.into_values();
+
+        assert_eq!(
+            values.len(),
+            self.columns.len(),
+            "a row of `{}` must have one value for each of its columns, {:?}",
+            self.sql,
+            self.columns
+        );
+
+        self.rows.push(values);
+        self
+    }
+
+    pub fn rows_affected(mut self, rows_affected: u64) -> Self {
+        self.rows_affected = rows_affected;
+        self
+    }
+
+    pub fn last_insert_id(mut self, id: i64) -> Self {
+        self.last_insert_id = Some(id);
+        self
+    }
+
+    /// Fails the statement with a database error, with the `code`, e.g. the SQLSTATE `23505` of
+    /// a unique violation, and the `message`.
+    pub fn fails(mut self, code: &str, message: &str) -> Self {
+        self.error = Some(MockDatabaseError {
+            code: code.to_owned(),
+            message: message.to_owned(),
+        });
+        self
+    }
+
+    /// Expects the statement `times` times in a row, instead of once.
+    ///
+    /// # Panics
+    ///
+    /// If `times` is `0`.
+    pub fn times(mut self, times: usize) -> Self {
+        assert!(times > 0, "a statement must be expected at least once");
+
+        self.times = times;
+        self
+    }
+
+    fn response(&self) -> Result<(Vec<AnyRow>, AnyQueryResult), Error> {
+        if let Some(error) = &self.error {
+            return Err(Error::Database(Box::new(error.clone())));
+        }
+
+        let columns: Vec<AnyColumn> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(ordinal, name)| {
+                // the type of a column is that of its first value which isn't `NULL`
+                let type_name = self
+                    .rows
+                    .iter()
+                    .map(|row| &row[ordinal])
+                    .find(|value| !value.is_null())
+                    .map_or("NULL", type_name);
+
+                AnyColumn::new(ordinal, name.clone(), AnyTypeInfo::new(type_name))
+            })
+            .collect();
+
+        let rows = self
+            .rows
+            .iter()
+            .map(|values| AnyRow::new(columns.clone(), values.clone()))
+            .collect();
+
+        Ok((
+            rows,
+            AnyQueryResult::new(self.rows_affected, self.last_insert_id),
+        ))
+    }
+}
+
+/// The values of a row returned by a [`MockStatement`]: a tuple of values which can be bound to
+/// a query of [`Any`], or the values themselves.
+pub trait MockValues {
+    fn into_values(self) -> Vec<AnyData>;
+}
+
+impl MockValues for Vec<AnyData> {
+    fn into_values(self) -> Vec<AnyData> {
+        self
+    }
+}
+
+macro_rules! impl_mock_values_for_tuple {
+    ($($T:ident),+) => {
+        impl<$($T),+> MockValues for ($($T,)+)
+        where
+            $($T: 'static + Send + Encode<'static, Any> + Type<Any>,)+
+        {
+            #[allow(non_snake_case)]
+            fn into_values(self) -> Vec<AnyData> {
+                let ($($T,)+) = self;
+
+                vec![$(encode($T)),+]
+            }
+        }
+    };
+}
+
+impl_mock_values_for_tuple!(T1);
+impl_mock_values_for_tuple!(T1, T2);
+impl_mock_values_for_tuple!(T1, T2, T3);
+impl_mock_values_for_tuple!(T1, T2, T3, T4);
+impl_mock_values_for_tuple!(T1, T2, T3, T4, T5);
+impl_mock_values_for_tuple!(T1, T2, T3, T4, T5, T6);
+impl_mock_values_for_tuple!(T1, T2, T3, T4, T5, T6, T7);
+impl_mock_values_for_tuple!(T1, T2, T3, T4, T5, T6, T7, T8);
+
+/// The error of a statement expected to [fail](MockStatement::fails).
+#[derive(Debug, Clone)]
+pub struct MockDatabaseError {
+    code: String,
+    message: String,
+}
+
+impl Display for MockDatabaseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.message, self.code)
+    }
+}
+
+impl std::error::Error for MockDatabaseError {}
+
+impl DatabaseError for MockDatabaseError {
+    fn message(&self) -> &str {
+        &self.message
+    }
+
+    fn code(&self) -> Option<std::borrow::Cow<'_, str>> {
+        Some(self.code.as_str().into())
+    }
+
+    fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+        self
+    }
+
+    fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+        self
+    }
+
+    fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+        self
+    }
+}
+
+#[derive(Debug)]
+struct MockConnection {
+    shared: Arc<Shared>,
+}
+
+impl MockConnection {
+    fn run(&self, sql: &str) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(future::ready(self.shared.execute(sql, None).map(|_| ())))
+    }
+}
+
+impl AnyConnectionBackend for MockConnection {
+    fn close(self: Box<Self>) -> BoxFuture<'static, Result<(), Error>> {
+        Box::pin(future::ok(()))
+    }
+
+    fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(future::ok(()))
+    }
+
+    fn begin(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        self.run("BEGIN")
+    }
+
+    fn commit(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        self.run("COMMIT")
+    }
+
+    fn rollback(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        self.run("ROLLBACK")
+    }
+
+    fn start_rollback(&mut self) {
+        // an unexpected rollback is recorded as a failure
+        let _ = self.shared.execute("ROLLBACK", None);
+    }
+
+    fn start_statement(&mut self, sql: &str) {
+        let _ = self.shared.execute(sql, None);
+    }
+
+    fn fetch_many<'q>(
+        &'q mut self,
+        sql: &'q str,
+        arguments: Option<Vec<AnyData>>,
+    ) -> BoxStream<'q, Result<Either<AnyQueryResult, AnyRow>, Error>> {
+        let results = match self.shared.execute(sql, arguments) {
+            Ok((rows, result)) => rows
+                .into_iter()
+                .map(Either::Right)
+                .chain(Some(Either::Left(result)))
+                .map(Ok)
+                .collect(),
+
+            Err(error) => vec![Err(error)],
+        };
+
+        Box::pin(stream::iter(results))
+    }
+
+    fn fetch_optional<'q>(
+        &'q mut self,
+        sql: &'q str,
+        arguments: Option<Vec<AnyData>>,
+    ) -> BoxFuture<'q, Result<Option<AnyRow>, Error>> {
+        let row = self
+            .shared
+            .execute(sql, arguments)
+            .map(|(rows, _)| rows.into_iter().next());
+
+        Box::pin(future::ready(row))
+    }
+
+    fn prepare<'e, 'q: 'e>(
+        &'e mut self,
+        sql: &'q str,
+    ) -> BoxFuture<'e, Result<AnyStatement<'q>, Error>> {
+        // preparing a statement doesn't execute it, so it isn't matched with the script
+        Box::pin(future::ok(AnyStatement::new(sql, None, Vec::new())))
+    }
+}
+
+fn connect(
+    options: &AnyDriverConnectOptions,
+) -> BoxFuture<'_, Result<Box<dyn AnyConnectionBackend>, Error>> {
+    let shared = options
+        .url()
+        .strip_prefix("mock://")
+        .and_then(|id| id.parse::<u64>().ok())
+        .and_then(|id| lock(&DATABASES).get(&id).and_then(Weak::upgrade));
+
+    let conn = match shared {
+        Some(shared) => Ok(Box::new(MockConnection { shared }) as Box<dyn AnyConnectionBackend>),
+        None => Err(Error::Configuration(
+            format!("no mock database at `{}`", options.url()).into(),
+        )),
+    };
+
+    Box::pin(future::ready(conn))
+}
+
+fn encode<T>(value: T) -> AnyData
+where
+    T: 'static + Send + Encode<'static, Any> + Type<Any>,
+{
+    let mut arguments = AnyArguments::default();
+    arguments.add(value);
+
+    Vec::<AnyData>::from(arguments)
+        .pop()
+        .unwrap_or(AnyData::Null)
+}
+
+fn type_name(value: &AnyData) -> &'static str {
+    match value {
+        AnyData::Null => "NULL",
+        AnyData::Bool(_) => "BOOLEAN",
+        AnyData::SmallInt(_) => "SMALLINT",
+        AnyData::Integer(_) => "INTEGER",
+        AnyData::BigInt(_) => "BIGINT",
+        AnyData::Real(_) => "REAL",
+        AnyData::Double(_) => "DOUBLE",
+        AnyData::Text(_) => "TEXT",
+        AnyData::Blob(_) => "BLOB",
+    }
+}
+
+fn normalize(sql: &str) -> String {
+    sql.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // the script is valid even if a test panicked while holding the lock
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[test]
+fn test_mock_database() {
+    use crate::executor::Executor;
+    use crate::row::Row;
+
+    let db = MockDatabase::new();
+
+    db.expect(
+        MockStatement::new("SELECT id, name\n  FROM users WHERE id = ?")
+            .bind(1_i64)
+            .columns(["id", "name"])
+            .row((1_i64, "Alice")),
+    )
+    .expect(MockStatement::new("DELETE FROM users").rows_affected(2));
+
+    sqlx_rt::block_on(async {
+        let mut conn = db.connect().await?;
+
+        let row = crate::query::query("SELECT id, name FROM users WHERE id = ?")
+            .bind(1_i64)
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(row.try_get::<i64, _>("id")?, 1);
+        assert_eq!(row.try_get::<String, _>("name")?, "Alice");
+
+        // out of order
+        assert!(conn.execute("SELECT 1").await.is_err());
+
+        assert_eq!(conn.execute("DELETE FROM users").await?.rows_affected(), 2);
+
+        Ok::<_, Error>(())
+    })
+    .unwrap();
+
+    let failure = std::panic::catch_unwind(|| db.verify()).unwrap_err();
+
+    assert_eq!(
+        failure.downcast_ref::<String>().map(|s| &**s),
+        Some("mock database: `SELECT 1` was executed, but `DELETE FROM users` was expected")
+    );
+}
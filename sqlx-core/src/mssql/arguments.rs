@@ -1,11 +1,12 @@
 use crate::arguments::Arguments;
+use crate::buffer_pool;
 use crate::encode::Encode;
 use crate::mssql::database::Mssql;
 use crate::mssql::io::MssqlBufMutExt;
 use crate::mssql::protocol::rpc::StatusFlags;
 use crate::types::Type;
 
-#[derive(Default, Clone)]
+#[derive(Clone)]
 pub struct MssqlArguments {
     // next ordinal to be used when formatting a positional parameter name
     pub(crate) ordinal: usize,
@@ -102,6 +103,23 @@ impl MssqlArguments {
     }
 }
 
+impl Default for MssqlArguments {
+    fn default() -> Self {
+        Self {
+            ordinal: 0,
+            name: String::new(),
+            data: buffer_pool::take_write_buf(),
+            declarations: String::new(),
+        }
+    }
+}
+
+impl Drop for MssqlArguments {
+    fn drop(&mut self) {
+        buffer_pool::recycle_write_buf(std::mem::take(&mut self.data));
+    }
+}
+
 impl<'q> Arguments<'q> for MssqlArguments {
     type Database = Mssql;
 
@@ -1,11 +1,13 @@
 use crate::common::StatementCache;
+use crate::connection::ServerVersion;
 use crate::error::Error;
 use crate::io::Decode;
 use crate::mssql::connection::stream::MssqlStream;
+use crate::mssql::connection::tls;
 use crate::mssql::protocol::login::Login7;
 use crate::mssql::protocol::message::Message;
 use crate::mssql::protocol::packet::PacketType;
-use crate::mssql::protocol::pre_login::{Encrypt, PreLogin, Version};
+use crate::mssql::protocol::pre_login::{PreLogin, Version};
 use crate::mssql::{MssqlConnectOptions, MssqlConnection};
 
 impl MssqlConnection {
@@ -15,14 +17,17 @@ impl MssqlConnection {
         // Send PRELOGIN to set up the context for login. The server should immediately
         // respond with a PRELOGIN message of its own.
 
-        // TODO: Encryption
         // TODO: Send the version of SQLx over
 
         stream.write_packet(
             PacketType::PreLogin,
             PreLogin {
                 version: Version::default(),
-                encryption: Encrypt::NOT_SUPPORTED,
+                encryption: tls::requested_encryption(options),
+
+                // every query of a connection runs to completion before the next one, as the
+                // executor borrows the connection mutably
+                multiple_active_result_sets: Some(false),
 
                 ..Default::default()
             },
@@ -31,7 +36,10 @@ impl MssqlConnection {
         stream.flush().await?;
 
         let (_, packet) = stream.recv_packet().await?;
-        let _ = PreLogin::decode(packet)?;
+        let pre_login = PreLogin::decode(packet)?;
+
+        // the TLS handshake follows the PRELOGIN messages, if encryption was negotiated
+        let login_only = tls::maybe_upgrade(&mut stream, options, pre_login.encryption).await?;
 
         // LOGIN7 defines the authentication rules for use between client and server
 
@@ -41,14 +49,14 @@ impl MssqlConnection {
                 // FIXME: use a version constant
                 version: 0x74000004, // SQL Server 2012 - SQL Server 2019
                 client_program_version: 0,
-                client_pid: 0,
+                client_pid: std::process::id(),
                 packet_size: 4096,
                 hostname: "",
                 username: &options.username,
                 password: options.password.as_deref().unwrap_or_default(),
-                app_name: "",
+                app_name: &options.application_name,
                 server_name: "",
-                client_interface_name: "",
+                client_interface_name: "sqlx",
                 language: "",
                 database: &*options.database,
                 client_id: [0; 6],
@@ -57,14 +65,29 @@ impl MssqlConnection {
 
         stream.flush().await?;
 
+        if login_only {
+            // only the login packet is encrypted
+            tls::downgrade(&mut stream);
+        }
+
+        let mut server_version = None;
+
         loop {
             // NOTE: we should receive an [Error] message if something goes wrong, otherwise,
             //       all messages are mostly informational (ENVCHANGE, INFO, LOGINACK)
 
             match stream.recv_message().await? {
-                Message::LoginAck(_) => {
+                Message::LoginAck(ack) => {
                     // indicates that the login was successful
-                    // no action is needed, we are just going to keep waiting till we hit <Done>
+                    // we keep waiting till we hit <Done>
+
+                    // e.g. 15.0.2000 for SQL Server 2019
+                    let version = ack.program_version;
+                    server_version = Some(ServerVersion::new(
+                        version.major.into(),
+                        version.minor.into(),
+                        version.build.into(),
+                    ));
                 }
 
                 Message::Done(_) => {
@@ -83,6 +106,8 @@ impl MssqlConnection {
             stream,
             cache_statement: StatementCache::new(1024),
             log_settings: options.log_settings.clone(),
+            server_version,
+            statement_count: 0,
         })
     }
 }
@@ -80,12 +80,22 @@ impl<'c> Executor<'c> for &'c mut MssqlConnection {
         let sql = query.sql();
         let arguments = query.take_arguments();
         let mut logger = QueryLogger::new(sql, self.log_settings.clone());
+        self.statement_count += 1;
 
         Box::pin(try_stream! {
-            self.run(sql, arguments).await?;
+            if let Err(error) = self.run(sql, arguments).await {
+                logger.fail(&error);
+                return Err(error);
+            }
 
             loop {
-                let message = self.stream.recv_message().await?;
+                let message = match self.stream.recv_message().await {
+                    Ok(message) => message,
+                    Err(error) => {
+                        logger.fail(&error);
+                        return Err(error);
+                    }
+                };
 
                 match message {
                     Message::Row(row) => {
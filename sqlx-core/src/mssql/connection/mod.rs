@@ -1,11 +1,11 @@
 use crate::common::StatementCache;
-use crate::connection::{Connection, LogSettings};
+use crate::connection::{Connection, LogSettings, ServerCapabilities, ServerVersion};
 use crate::error::Error;
 use crate::executor::Executor;
 use crate::mssql::connection::stream::MssqlStream;
 use crate::mssql::statement::MssqlStatementMetadata;
 use crate::mssql::{Mssql, MssqlConnectOptions};
-use crate::transaction::Transaction;
+use crate::transaction::{Transaction, TransactionOptions};
 use futures_core::future::BoxFuture;
 use futures_util::{FutureExt, TryFutureExt};
 use std::fmt::{self, Debug, Formatter};
@@ -15,11 +15,15 @@ mod establish;
 mod executor;
 mod prepare;
 mod stream;
+mod tls;
 
 pub struct MssqlConnection {
     pub(crate) stream: MssqlStream,
     pub(crate) cache_statement: StatementCache<Arc<MssqlStatementMetadata>>,
     log_settings: LogSettings,
+    server_version: Option<ServerVersion>,
+    // the statements executed so far
+    pub(crate) statement_count: u64,
 }
 
 impl Debug for MssqlConnection {
@@ -59,6 +63,14 @@ impl Connection for MssqlConnection {
         self.close()
     }
 
+    fn transaction_depth(&self) -> usize {
+        self.stream.transaction_depth
+    }
+
+    fn statement_count(&self) -> u64 {
+        self.statement_count
+    }
+
     fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         // NOTE: we do not use `SELECT 1` as that *could* interact with any ongoing transactions
         self.execute("/* SQLx ping */").map_ok(|_| ()).boxed()
@@ -68,7 +80,36 @@ impl Connection for MssqlConnection {
     where
         Self: Sized,
     {
-        Transaction::begin(self)
+        Transaction::start(self)
+    }
+
+    fn begin_with(
+        &mut self,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized,
+    {
+        Transaction::start_with(self, options)
+    }
+
+    fn server_version(&self) -> Option<ServerVersion> {
+        self.server_version
+    }
+
+    fn server_capabilities(&self) -> ServerCapabilities {
+        let version = match self.server_version {
+            Some(version) => version,
+            None => return ServerCapabilities::default(),
+        };
+
+        ServerCapabilities {
+            returning: false,
+            // SQL Server 2016
+            json: version.major() >= 13,
+            common_table_expressions: true,
+            multiranges: false,
+        }
     }
 
     #[doc(hidden)]
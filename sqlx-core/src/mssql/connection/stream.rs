@@ -6,6 +6,7 @@ use sqlx_rt::TcpStream;
 use crate::error::Error;
 use crate::ext::ustr::UStr;
 use crate::io::{BufStream, Encode};
+use crate::mssql::connection::tls::TlsPreloginWrapper;
 use crate::mssql::protocol::col_meta_data::ColMetaData;
 use crate::mssql::protocol::done::{Done, Status as DoneStatus};
 use crate::mssql::protocol::env_change::EnvChange;
@@ -24,7 +25,7 @@ use crate::HashMap;
 use std::sync::Arc;
 
 pub(crate) struct MssqlStream {
-    inner: BufStream<MaybeTlsStream<TcpStream>>,
+    inner: BufStream<MaybeTlsStream<TlsPreloginWrapper<TcpStream>>>,
 
     // how many Done (or Error) we are currently waiting for
     pub(crate) pending_done_count: usize,
@@ -46,7 +47,7 @@ pub(crate) struct MssqlStream {
 impl MssqlStream {
     pub(super) async fn connect(options: &MssqlConnectOptions) -> Result<Self, Error> {
         let inner = BufStream::new(MaybeTlsStream::Raw(
-            TcpStream::connect((&*options.host, options.port)).await?,
+            TlsPreloginWrapper::connect(options).await?,
         ));
 
         Ok(Self {
@@ -222,7 +223,7 @@ impl MssqlStream {
 }
 
 impl Deref for MssqlStream {
-    type Target = BufStream<MaybeTlsStream<TcpStream>>;
+    type Target = BufStream<MaybeTlsStream<TlsPreloginWrapper<TcpStream>>>;
 
     fn deref(&self) -> &Self::Target {
         &self.inner
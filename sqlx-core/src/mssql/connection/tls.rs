@@ -0,0 +1,344 @@
+use std::cmp;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::ready;
+use sqlx_rt::{AsyncRead, AsyncWrite, TcpStream};
+
+use crate::error::Error;
+use crate::mssql::connection::stream::MssqlStream;
+use crate::mssql::protocol::packet::{PacketType, Status};
+use crate::mssql::protocol::pre_login::Encrypt;
+use crate::mssql::{MssqlConnectOptions, MssqlSslMode};
+use crate::net::{connect_tcp, MaybeTlsStream, PollReadBuf, PollReadOut, TlsConfig};
+
+// https://docs.microsoft.com/en-us/openspecs/windows_protocols/ms-tds/60f56408-0188-4cd5-8b90-25c6f2423868
+
+/// The encryption requested in the PRELOGIN message of the client.
+pub(super) fn requested_encryption(options: &MssqlConnectOptions) -> Encrypt {
+    match options.ssl_mode {
+        MssqlSslMode::Disabled => Encrypt::NOT_SUPPORTED,
+
+        // the login packet is encrypted, unless the server doesn't support it
+        MssqlSslMode::Preferred => Encrypt::OFF,
+
+        MssqlSslMode::Required | MssqlSslMode::VerifyCa | MssqlSslMode::VerifyIdentity => {
+            Encrypt::ON
+        }
+    }
+}
+
+/// Upgrade to TLS as negotiated with the PRELOGIN message of the server, returning `true` if
+/// only the login packet is encrypted.
+pub(super) async fn maybe_upgrade(
+    stream: &mut MssqlStream,
+    options: &MssqlConnectOptions,
+    server_encryption: Encrypt,
+) -> Result<bool, Error> {
+    let server_encryption = server_encryption - Encrypt::CLIENT_CERT;
+
+    let login_only = match options.ssl_mode {
+        MssqlSslMode::Disabled if server_encryption == Encrypt::REQUIRED => {
+            return Err(Error::Tls("server requires TLS".into()));
+        }
+
+        MssqlSslMode::Disabled => return Ok(false),
+
+        _ if server_encryption == Encrypt::NOT_SUPPORTED => {
+            if matches!(options.ssl_mode, MssqlSslMode::Preferred) {
+                return Ok(false);
+            }
+
+            return Err(Error::Tls("server does not support TLS".into()));
+        }
+
+        // both sides sent ENCRYPT_OFF
+        MssqlSslMode::Preferred => server_encryption == Encrypt::OFF,
+
+        _ => false,
+    };
+
+    let accept_invalid_certs = !matches!(
+        options.ssl_mode,
+        MssqlSslMode::VerifyCa | MssqlSslMode::VerifyIdentity
+    );
+    let accept_invalid_hostnames = !matches!(options.ssl_mode, MssqlSslMode::VerifyIdentity);
+
+    // the TLS records of the handshake are wrapped in PRELOGIN packets
+    stream.stream.handshake = true;
+
+    stream
+        .upgrade(TlsConfig {
+            accept_invalid_certs,
+            accept_invalid_hostnames,
+            hostname: &options.host,
+            root_cert_path: options.ssl_ca.as_ref(),
+            client_cert_path: None,
+            client_key_path: None,
+            custom: None,
+        })
+        .await?;
+
+    stream.stream.handshake = false;
+
+    Ok(login_only)
+}
+
+/// Go back to the unencrypted connection, after the login packet of a connection which only
+/// encrypts the login.
+pub(super) fn downgrade(stream: &mut MssqlStream) {
+    if let MaybeTlsStream::Tls(_) = &stream.stream {
+        // the TLS stream is dropped without a close_notify, as the server doesn't expect one
+        let socket = stream.stream.socket.take();
+
+        stream.stream = MaybeTlsStream::Raw(TlsPreloginWrapper::from_option(socket));
+    }
+}
+
+/// The TCP stream of the connection. TDS wraps the TLS handshake in PRELOGIN packets; the
+/// TLS records are exchanged directly once the handshake is complete, and the rest of the
+/// connection passes through unchanged.
+pub(crate) struct TlsPreloginWrapper<S> {
+    // taken when the TLS stream is dropped, after a login-only encryption
+    socket: Option<S>,
+    handshake: bool,
+
+    // the PRELOGIN packet being written, starting with room for its header
+    wbuf: Vec<u8>,
+    wpos: usize,
+
+    // the header of the PRELOGIN packet being read, then the length of its unread payload
+    header: [u8; 8],
+    header_pos: usize,
+    read_remaining: usize,
+}
+
+impl<S> TlsPreloginWrapper<S> {
+    fn from_option(socket: Option<S>) -> Self {
+        Self {
+            socket,
+            handshake: false,
+            wbuf: Vec::new(),
+            wpos: 0,
+            header: [0; 8],
+            header_pos: 0,
+            read_remaining: 0,
+        }
+    }
+
+    fn socket(&mut self) -> io::Result<Pin<&mut S>>
+    where
+        S: Unpin,
+    {
+        pin_socket(&mut self.socket)
+    }
+}
+
+impl TlsPreloginWrapper<TcpStream> {
+    pub(super) async fn connect(options: &MssqlConnectOptions) -> io::Result<Self> {
+        connect_tcp(&options.host, options.port, &options.tcp)
+            .await
+            .map(|socket| Self::from_option(Some(socket)))
+    }
+
+    #[cfg(feature = "_rt-async-std")]
+    pub(super) fn shutdown(&self, how: std::net::Shutdown) -> io::Result<()> {
+        match &self.socket {
+            Some(socket) => socket.shutdown(how),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> TlsPreloginWrapper<S> {
+    // read the header of the next PRELOGIN packet, skipping empty packets
+    fn poll_header(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.read_remaining == 0 {
+            while self.header_pos < self.header.len() {
+                let socket = pin_socket(&mut self.socket)?;
+                let header = &mut self.header[self.header_pos..];
+                let n = ready!(poll_read_slice(socket, cx, header))?;
+
+                if n == 0 {
+                    return Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+                }
+
+                self.header_pos += n;
+            }
+
+            let length = u16::from_be_bytes([self.header[2], self.header[3]]) as usize;
+
+            self.header_pos = 0;
+            self.read_remaining = length.saturating_sub(self.header.len());
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> AsyncRead for TlsPreloginWrapper<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut PollReadBuf<'_>,
+    ) -> Poll<io::Result<PollReadOut>> {
+        if !self.handshake {
+            return self.socket()?.poll_read(cx, buf);
+        }
+
+        ready!(self.poll_header(cx))?;
+
+        #[cfg(feature = "_rt-async-std")]
+        {
+            let len = cmp::min(self.read_remaining, buf.len());
+            let n = ready!(poll_read_slice(self.socket()?, cx, &mut buf[..len]))?;
+
+            self.read_remaining -= n;
+
+            Poll::Ready(Ok(n))
+        }
+
+        #[cfg(any(feature = "_rt-actix", feature = "_rt-tokio"))]
+        {
+            let mut data = vec![0; cmp::min(self.read_remaining, buf.remaining())];
+            let n = ready!(poll_read_slice(self.socket()?, cx, &mut data))?;
+
+            buf.put_slice(&data[..n]);
+            self.read_remaining -= n;
+
+            Poll::Ready(Ok(()))
+        }
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> AsyncWrite for TlsPreloginWrapper<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        if !self.handshake {
+            return self.socket()?.poll_write(cx, buf);
+        }
+
+        // the records are buffered until the flush, then written in one packet
+        if self.wbuf.is_empty() {
+            self.wbuf.resize(8, 0);
+        }
+
+        self.wbuf.extend_from_slice(buf);
+
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+
+        if !this.wbuf.is_empty() {
+            if this.wpos == 0 {
+                let length = this.wbuf.len() as u16;
+
+                this.wbuf[0] = PacketType::PreLogin as u8;
+                this.wbuf[1] = Status::END_OF_MESSAGE.bits();
+                this.wbuf[2..4].copy_from_slice(&length.to_be_bytes());
+                this.wbuf[6] = 1; // packet id
+            }
+
+            while this.wpos < this.wbuf.len() {
+                let socket = pin_socket(&mut this.socket)?;
+                let n = ready!(socket.poll_write(cx, &this.wbuf[this.wpos..]))?;
+
+                if n == 0 {
+                    return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+                }
+
+                this.wpos += n;
+            }
+
+            this.wbuf.clear();
+            this.wpos = 0;
+        }
+
+        this.socket()?.poll_flush(cx)
+    }
+
+    #[cfg(any(feature = "_rt-actix", feature = "_rt-tokio"))]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.socket()?.poll_shutdown(cx)
+    }
+
+    #[cfg(feature = "_rt-async-std")]
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.socket()?.poll_close(cx)
+    }
+}
+
+fn pin_socket<S: Unpin>(socket: &mut Option<S>) -> io::Result<Pin<&mut S>> {
+    socket
+        .as_mut()
+        .map(Pin::new)
+        .ok_or_else(|| io::ErrorKind::NotConnected.into())
+}
+
+fn poll_read_slice<S: AsyncRead>(
+    socket: Pin<&mut S>,
+    cx: &mut Context<'_>,
+    buf: &mut [u8],
+) -> Poll<io::Result<usize>> {
+    #[cfg(feature = "_rt-async-std")]
+    return socket.poll_read(cx, buf);
+
+    #[cfg(any(feature = "_rt-actix", feature = "_rt-tokio"))]
+    {
+        let mut buf = sqlx_rt::ReadBuf::new(buf);
+        ready!(socket.poll_read(cx, &mut buf))?;
+
+        Poll::Ready(Ok(buf.filled().len()))
+    }
+}
+
+#[test]
+fn it_wraps_the_tls_handshake_in_prelogin_packets() {
+    use sqlx_rt::{AsyncReadExt, AsyncWriteExt};
+    use std::io::{Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let server = std::thread::spawn(move || {
+        let (mut socket, _) = listener.accept().unwrap();
+
+        let mut packet = [0; 11];
+        socket.read_exact(&mut packet).unwrap();
+
+        // the response is split across two packets
+        socket
+            .write_all(b"\x12\x00\x00\x0a\x00\x00\x01\x00de")
+            .unwrap();
+        socket
+            .write_all(b"\x12\x01\x00\x09\x00\x00\x02\x00f")
+            .unwrap();
+
+        packet
+    });
+
+    sqlx_rt::block_on(async {
+        let socket = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        let mut wrapper = TlsPreloginWrapper::from_option(Some(socket));
+        wrapper.handshake = true;
+
+        wrapper.write_all(b"abc").await.unwrap();
+        wrapper.flush().await.unwrap();
+
+        let mut response = [0; 3];
+        wrapper.read_exact(&mut response).await.unwrap();
+
+        assert_eq!(&response, b"def");
+    });
+
+    assert_eq!(
+        &server.join().unwrap(),
+        b"\x12\x01\x00\x0b\x00\x00\x01\x00abc"
+    );
+}
@@ -23,7 +23,7 @@ pub use column::MssqlColumn;
 pub use connection::MssqlConnection;
 pub use database::Mssql;
 pub use error::MssqlDatabaseError;
-pub use options::MssqlConnectOptions;
+pub use options::{MssqlConnectOptions, MssqlSslMode};
 pub use query_result::MssqlQueryResult;
 pub use row::MssqlRow;
 pub use statement::MssqlStatement;
@@ -45,6 +45,7 @@ impl<'c, T: Executor<'c, Database = Mssql>> MssqlExecutor<'c> for T {}
 impl_into_arguments_for_arguments!(MssqlArguments);
 impl_executor_for_pool_connection!(Mssql, MssqlConnection, MssqlRow);
 impl_executor_for_transaction!(Mssql, MssqlRow);
+impl_executor_for_reconnecting_connection!(Mssql, MssqlRow);
 impl_acquire!(Mssql, MssqlConnection);
 impl_column_index_for_row!(MssqlRow);
 impl_column_index_for_statement!(MssqlStatement);
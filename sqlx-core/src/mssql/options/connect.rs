@@ -1,8 +1,10 @@
 use crate::connection::ConnectOptions;
 use crate::error::Error;
 use crate::mssql::{MssqlConnectOptions, MssqlConnection};
+use crate::query_log::QueryLog;
 use futures_core::future::BoxFuture;
 use log::LevelFilter;
+use std::sync::Arc;
 use std::time::Duration;
 
 impl ConnectOptions for MssqlConnectOptions {
@@ -24,4 +26,14 @@ impl ConnectOptions for MssqlConnectOptions {
         self.log_settings.log_slow_statements(level, duration);
         self
     }
+
+    fn query_log(&mut self, log: Arc<dyn QueryLog>) -> &mut Self {
+        self.log_settings.query_log(log);
+        self
+    }
+
+    fn application_name(&mut self, name: &str) -> &mut Self {
+        self.application_name = name.to_owned();
+        self
+    }
 }
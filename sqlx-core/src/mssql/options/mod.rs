@@ -1,7 +1,14 @@
+use std::path::Path;
+use std::time::Duration;
+
 use crate::connection::LogSettings;
+use crate::net::{CertificateInput, TcpOptions};
 
 mod connect;
 mod parse;
+mod ssl_mode;
+
+pub use ssl_mode::MssqlSslMode;
 
 #[derive(Debug, Clone)]
 pub struct MssqlConnectOptions {
@@ -10,6 +17,10 @@ pub struct MssqlConnectOptions {
     pub(crate) username: String,
     pub(crate) database: String,
     pub(crate) password: Option<String>,
+    pub(crate) ssl_mode: MssqlSslMode,
+    pub(crate) ssl_ca: Option<CertificateInput>,
+    pub(crate) application_name: String,
+    pub(crate) tcp: TcpOptions,
     pub(crate) log_settings: LogSettings,
 }
 
@@ -27,6 +38,10 @@ impl MssqlConnectOptions {
             database: String::from("master"),
             username: String::from("sa"),
             password: None,
+            ssl_mode: MssqlSslMode::Preferred,
+            ssl_ca: None,
+            application_name: String::new(),
+            tcp: TcpOptions::default(),
             log_settings: Default::default(),
         }
     }
@@ -55,4 +70,133 @@ impl MssqlConnectOptions {
         self.database = database.to_owned();
         self
     }
+
+    /// Sets the name of the application, sent with the login, e.g. shown as `program_name` in
+    /// `sys.dm_exec_sessions` and returned by `APP_NAME()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mssql::MssqlConnectOptions;
+    /// let options = MssqlConnectOptions::new()
+    ///     .application_name("my-app");
+    /// ```
+    pub fn application_name(mut self, application_name: &str) -> Self {
+        self.application_name = application_name.to_owned();
+        self
+    }
+
+    /// Sets whether or with what priority the connection will be encrypted with TLS.
+    ///
+    /// By default, the SSL mode is [`Preferred`](MssqlSslMode::Preferred): the login packet is
+    /// encrypted if the server supports it, and the whole connection if the server requires it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mssql::{MssqlSslMode, MssqlConnectOptions};
+    /// let options = MssqlConnectOptions::new()
+    ///     .ssl_mode(MssqlSslMode::VerifyIdentity);
+    /// ```
+    pub fn ssl_mode(mut self, mode: MssqlSslMode) -> Self {
+        self.ssl_mode = mode;
+        self
+    }
+
+    /// Sets the name of a file containing a list of trusted SSL Certificate Authorities.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mssql::{MssqlSslMode, MssqlConnectOptions};
+    /// let options = MssqlConnectOptions::new()
+    ///     .ssl_mode(MssqlSslMode::VerifyCa)
+    ///     .ssl_ca("path/to/ca.crt");
+    /// ```
+    pub fn ssl_ca(mut self, file_name: impl AsRef<Path>) -> Self {
+        self.ssl_ca = Some(CertificateInput::File(file_name.as_ref().to_owned()));
+        self
+    }
+
+    /// Enables TCP keepalive on the connection, sending the first probe after it has been idle
+    /// for `time`, so that a connection silently dropped by the network (e.g. by a NAT gateway or
+    /// a firewall) is detected, instead of hanging until the next query times out.
+    ///
+    /// Without TCP keepalive settings, the OS defaults are used; keepalive is usually disabled.
+    /// Ignored for Unix domain sockets.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use std::time::Duration;
+    /// # use sqlx_core::mssql::MssqlConnectOptions;
+    /// let options = MssqlConnectOptions::new()
+    ///     .tcp_keepalive(Duration::from_secs(60))
+    ///     .tcp_keepalive_interval(Duration::from_secs(10))
+    ///     .tcp_keepalive_retries(3);
+    /// ```
+    pub fn tcp_keepalive(mut self, time: Duration) -> Self {
+        self.tcp.keepalive_time = Some(time);
+        self
+    }
+
+    /// Enables TCP keepalive on the connection, with `interval` between unanswered probes.
+    ///
+    /// Only supported on Linux, the BSDs, Apple platforms and Windows.
+    pub fn tcp_keepalive_interval(mut self, interval: Duration) -> Self {
+        self.tcp.keepalive_interval = Some(interval);
+        self
+    }
+
+    /// Enables TCP keepalive on the connection, dropping it after `retries` unanswered probes.
+    ///
+    /// Only supported on Linux, the BSDs and Apple platforms.
+    pub fn tcp_keepalive_retries(mut self, retries: u32) -> Self {
+        self.tcp.keepalive_retries = Some(retries);
+        self
+    }
+
+    /// Sets `TCP_NODELAY` on the connection, to send its packets without waiting to coalesce
+    /// them.
+    ///
+    /// By default, the OS default is used; it's usually disabled.
+    pub fn tcp_nodelay(mut self, nodelay: bool) -> Self {
+        self.tcp.nodelay = Some(nodelay);
+        self
+    }
+
+    /// Sets the maximum time to wait for the TCP connection to each of the addresses the host
+    /// resolves to; when it elapses, the next address is tried.
+    ///
+    /// By default, the OS timeout is used, which can be minutes for an unreachable address.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.tcp.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Tunnels the connection through a proxy, for a server only reachable through it.
+    ///
+    /// The proxy is given by a URL, which may include credentials:
+    ///
+    /// * `socks5://[user:password@]host[:port]`: a SOCKS5 proxy, with the host of the server
+    ///   resolved locally; the port defaults to 1080;
+    /// * `socks5h://…`: a SOCKS5 proxy, which resolves the host of the server;
+    /// * `http://[user:password@]host[:port]`: an HTTP proxy, asked to tunnel the connection with
+    ///   a `CONNECT` request; the port defaults to 80.
+    ///
+    /// An invalid URL makes connecting fail. The TCP settings, e.g. the
+    /// [`connect_timeout`](Self::connect_timeout), apply to the connection to the proxy.
+    /// Ignored for Unix domain sockets.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mssql::MssqlConnectOptions;
+    /// let options = MssqlConnectOptions::new()
+    ///     .proxy("socks5h://bastion.example.com:1080");
+    /// ```
+    pub fn proxy(mut self, url: &str) -> Self {
+        self.tcp.proxy = Some(url.to_owned());
+        self
+    }
 }
@@ -24,12 +24,12 @@ impl<'de> Decode<'de> for PreLogin<'de> {
     fn decode_with(buf: Bytes, _: ()) -> Result<Self, Error> {
         let mut version = None;
         let mut encryption = None;
+        let mut multiple_active_result_sets = None;
 
         // TODO: Decode the remainder of the structure
         // let mut instance = None;
         // let mut thread_id = None;
         // let mut trace_id = None;
-        // let mut multiple_active_result_sets = None;
 
         let mut offsets = buf.clone();
 
@@ -61,7 +61,15 @@ impl<'de> Decode<'de> for PreLogin<'de> {
                             encryption = Some(Encrypt::from_bits_truncate(data.get_u8()));
                         }
 
-                        tok => todo!("{:?}", tok),
+                        PreLoginOptionToken::MultipleActiveResultSets => {
+                            multiple_active_result_sets = Some(data.get_u8() != 0);
+                        }
+
+                        // the server echoes the instance, which we don't send, and leaves
+                        // the thread and trace IDs empty
+                        PreLoginOptionToken::Instance
+                        | PreLoginOptionToken::ThreadId
+                        | PreLoginOptionToken::TraceId => {}
                     }
                 }
 
@@ -89,6 +97,7 @@ impl<'de> Decode<'de> for PreLogin<'de> {
         Ok(Self {
             version,
             encryption,
+            multiple_active_result_sets,
 
             ..Default::default()
         })
@@ -243,7 +252,10 @@ bitflags! {
     /// wire encryption to be used.
     #[derive(Default)]
     pub(crate) struct Encrypt: u8 {
-        /// Encryption is available but on.
+        /// Encryption is available but off; only the login packet is encrypted.
+        const OFF = 0x00;
+
+        /// Encryption is available and on.
         const ON = 0x01;
 
         /// Encryption is not available.
@@ -309,3 +321,18 @@ fn test_decode_pre_login() {
     // ENCRYPT_OFF
     assert_eq!(pre_login.encryption.bits(), 0);
 }
+
+#[test]
+fn test_decode_pre_login_with_mars() {
+    #[rustfmt::skip]
+    let buffer = Bytes::from_static(&[
+        0, 0, 21, 0, 6, 1, 0, 27, 0, 1, 2, 0, 28, 0, 1, 4, 0, 29, 0, 1, 255,
+        15, 0, 7, 208, 0, 0, 2, 0, 0,
+    ]);
+
+    let pre_login = PreLogin::decode(buffer).unwrap();
+
+    assert_eq!(pre_login.version.major, 15);
+    assert_eq!(pre_login.encryption, Encrypt::NOT_SUPPORTED);
+    assert_eq!(pre_login.multiple_active_result_sets, Some(false));
+}
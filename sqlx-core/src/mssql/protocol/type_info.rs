@@ -4,6 +4,7 @@ use encoding_rs::Encoding;
 
 use crate::encode::{Encode, IsNull};
 use crate::error::Error;
+use crate::mssql::io::MssqlBufMutExt;
 use crate::mssql::Mssql;
 
 bitflags! {
@@ -84,6 +85,9 @@ pub(crate) enum DataType {
     Image = 0x22,
     NText = 0x63,
     Variant = 0x62,
+
+    // table-valued parameters
+    Table = 0xf3,
 }
 
 // http://msdn.microsoft.com/en-us/library/dd358284.aspx
@@ -95,6 +99,9 @@ pub(crate) struct TypeInfo {
     pub(crate) scale: u8,
     pub(crate) precision: u8,
     pub(crate) collation: Option<Collation>,
+
+    // the name of the user-defined table type of a table-valued parameter
+    pub(crate) type_name: Option<String>,
 }
 
 impl TypeInfo {
@@ -105,6 +112,7 @@ impl TypeInfo {
             scale: 0,
             precision: 0,
             collation: None,
+            type_name: None,
         }
     }
 
@@ -185,6 +193,7 @@ impl TypeInfo {
                     ty,
                     precision: 0,
                     collation: None,
+                    type_name: None,
                 }
             }
 
@@ -210,6 +219,7 @@ impl TypeInfo {
                     scale,
                     ty,
                     collation: None,
+                    type_name: None,
                 }
             }
 
@@ -225,6 +235,7 @@ impl TypeInfo {
                     collation: Some(collation),
                     scale: 0,
                     precision: 0,
+                    type_name: None,
                 }
             }
 
@@ -293,6 +304,16 @@ impl TypeInfo {
                 }
             }
 
+            DataType::Table => {
+                // TVP_TYPENAME = DbName OwningSchema TypeName
+                let name = self.type_name.as_deref().unwrap_or_default();
+                let (schema, name) = name.rsplit_once('.').unwrap_or(("", name));
+
+                buf.put_b_varchar("");
+                buf.put_b_varchar(schema);
+                buf.put_b_varchar(name);
+            }
+
             _ => {
                 unimplemented!("unsupported data type {:?}", self.ty);
             }
@@ -377,6 +398,8 @@ impl TypeInfo {
                     Some(buf.split_to(size as usize))
                 }
             }
+
+            DataType::Table => unreachable!("table-valued parameters are never returned"),
         }
     }
 
@@ -432,6 +455,11 @@ impl TypeInfo {
             DataType::Text | DataType::Image | DataType::NText | DataType::Variant => {
                 self.put_long_len_value(buf, value);
             }
+
+            // the column metadata and rows of the table, written by `MssqlTableValue`
+            DataType::Table => {
+                let _ = value.encode(buf);
+            }
         }
     }
 
@@ -514,6 +542,7 @@ impl TypeInfo {
             DataType::Char => "CHAR",
             DataType::BigChar => "BIGCHAR",
             DataType::NChar => "NCHAR",
+            DataType::Table => "TABLE",
 
             _ => unimplemented!("name: unsupported data type {:?}", self.ty),
         }
@@ -578,6 +607,11 @@ impl TypeInfo {
                 s.push_str("bit");
             }
 
+            DataType::Table => {
+                s.push_str(self.type_name.as_deref().unwrap_or_default());
+                s.push_str(" READONLY");
+            }
+
             _ => unimplemented!("fmt: unsupported data type {:?}", self.ty),
         }
     }
@@ -628,6 +662,7 @@ impl DataType {
             0x22 => DataType::Image,
             0x63 => DataType::NText,
             0x62 => DataType::Variant,
+            0xf3 => DataType::Table,
 
             ty => {
                 return Err(err_protocol!("unknown data type 0x{:02x}", ty));
@@ -45,13 +45,26 @@ impl ColumnIndex<MssqlRow> for &'_ str {
     }
 }
 
+#[cfg(feature = "any")]
+impl MssqlRow {
+    // the converted columns are shared with the other rows of the result set
+    pub(crate) fn into_any(
+        self,
+        columns: &mut crate::any::row::AnyColumnsCache,
+    ) -> crate::any::AnyRow {
+        crate::any::AnyRow {
+            columns: columns.get(&self.columns, |columns| {
+                columns.iter().map(|col| col.clone().into()).collect()
+            }),
+            kind: crate::any::row::AnyRowKind::Mssql(self),
+        }
+    }
+}
+
 #[cfg(feature = "any")]
 impl From<MssqlRow> for crate::any::AnyRow {
     #[inline]
     fn from(row: MssqlRow) -> Self {
-        crate::any::AnyRow {
-            columns: row.columns.iter().map(|col| col.clone().into()).collect(),
-            kind: crate::any::row::AnyRowKind::Mssql(row),
-        }
+        row.into_any(&mut Default::default())
     }
 }
@@ -7,7 +7,7 @@ use crate::executor::Executor;
 use crate::mssql::protocol::packet::PacketType;
 use crate::mssql::protocol::sql_batch::SqlBatch;
 use crate::mssql::{Mssql, MssqlConnection};
-use crate::transaction::TransactionManager;
+use crate::transaction::{SavepointStatement, TransactionManager};
 
 /// Implementation of [`TransactionManager`] for MSSQL.
 pub struct MssqlTransactionManager;
@@ -91,4 +91,29 @@ impl TransactionManager for MssqlTransactionManager {
             conn.stream.transaction_depth = depth - 1;
         }
     }
+
+    fn start_statement(conn: &mut MssqlConnection, sql: &str) {
+        conn.stream.pending_done_count += 1;
+
+        conn.stream.write_packet(
+            PacketType::SqlBatch,
+            SqlBatch {
+                transaction_descriptor: conn.stream.transaction_descriptor,
+                sql,
+            },
+        );
+    }
+
+    fn savepoint_sql(
+        _conn: &MssqlConnection,
+        statement: SavepointStatement,
+        name: &str,
+    ) -> Option<String> {
+        // savepoints are not released in MSSQL
+        match statement {
+            SavepointStatement::Create => Some(format!("SAVE TRAN {}", name)),
+            SavepointStatement::RollbackTo => Some(format!("ROLLBACK TRAN {}", name)),
+            SavepointStatement::Release => None,
+        }
+    }
 }
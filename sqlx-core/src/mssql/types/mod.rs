@@ -6,6 +6,9 @@ mod bool;
 mod float;
 mod int;
 mod str;
+mod table;
+
+pub use table::{MssqlTableRow, MssqlTableRowEncoder, MssqlTableValue};
 
 impl<'q, T: 'q + Encode<'q, Mssql>> Encode<'q, Mssql> for Option<T> {
     fn encode(self, buf: &mut Vec<u8>) -> IsNull {
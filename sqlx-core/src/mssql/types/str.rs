@@ -51,6 +51,7 @@ impl Encode<'_, Mssql> for &'_ str {
                 sort: 52,
                 version: 0,
             }),
+            type_name: None,
         }))
     }
 
@@ -0,0 +1,252 @@
+use crate::encode::{Encode, IsNull};
+use crate::mssql::protocol::type_info::{DataType, TypeInfo};
+use crate::mssql::{Mssql, MssqlTypeInfo};
+use crate::types::Type;
+
+// https://docs.microsoft.com/en-us/openspecs/windows_protocols/ms-tds/c264db71-c1ec-4fe8-b5ef-19d54b1e6566
+
+const TVP_ROW_TOKEN: u8 = 0x01;
+const TVP_END_TOKEN: u8 = 0x00;
+const TVP_NULL_TOKEN: u16 = 0xffff;
+
+/// A table-valued parameter, binding rows to a user-defined table type.
+///
+/// The type of each column is taken from the values of the first row; the table type must
+/// already exist in the database.
+///
+/// ```rust,ignore
+/// // CREATE TYPE dbo.Ids AS TABLE (id INT, name NVARCHAR(100))
+/// let ids = MssqlTableValue::from_rows("dbo.Ids", vec![(1_i32, "one"), (2, "two")]);
+///
+/// sqlx::query("SELECT id FROM @p1 ORDER BY id")
+///     .bind(ids)
+///     .fetch_all(&mut conn)
+///     .await?;
+/// ```
+///
+/// A table without any rows is sent as the default (empty) table.
+#[derive(Debug, Clone)]
+pub struct MssqlTableValue {
+    type_name: String,
+    columns: Vec<TypeInfo>,
+    rows: Vec<u8>,
+    num_rows: usize,
+}
+
+/// A row of a [`MssqlTableValue`], implemented for tuples of the column values.
+pub trait MssqlTableRow {
+    fn encode_row(self, encoder: &mut MssqlTableRowEncoder<'_>);
+}
+
+/// Encodes the columns of a row of a [`MssqlTableValue`], in order.
+pub struct MssqlTableRowEncoder<'a> {
+    table: &'a mut MssqlTableValue,
+    column: usize,
+}
+
+impl MssqlTableValue {
+    /// Creates an empty table of the user-defined table type `type_name`, optionally
+    /// qualified with its schema.
+    pub fn new(type_name: impl Into<String>) -> Self {
+        Self {
+            type_name: type_name.into(),
+            columns: Vec::new(),
+            rows: Vec::new(),
+            num_rows: 0,
+        }
+    }
+
+    /// Creates a table of the user-defined table type `type_name` from an iterator of rows.
+    pub fn from_rows<I>(type_name: impl Into<String>, rows: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: MssqlTableRow,
+    {
+        let mut table = Self::new(type_name);
+
+        for row in rows {
+            table.push_row(row);
+        }
+
+        table
+    }
+
+    /// Appends a row to the table.
+    ///
+    /// # Panics
+    ///
+    /// If the row doesn't have as many columns as the first row.
+    pub fn push_row<R: MssqlTableRow>(&mut self, row: R) -> &mut Self {
+        self.rows.push(TVP_ROW_TOKEN);
+
+        let mut encoder = MssqlTableRowEncoder {
+            table: self,
+            column: 0,
+        };
+
+        row.encode_row(&mut encoder);
+
+        let columns = encoder.column;
+
+        assert_eq!(
+            columns,
+            self.columns.len(),
+            "every row of a table-valued parameter must have the same number of columns"
+        );
+
+        self.num_rows += 1;
+        self
+    }
+
+    /// The number of rows of the table.
+    pub fn len(&self) -> usize {
+        self.num_rows
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.num_rows == 0
+    }
+}
+
+impl MssqlTableRowEncoder<'_> {
+    pub fn encode<'q, T>(&mut self, value: T) -> &mut Self
+    where
+        T: Encode<'q, Mssql> + Type<Mssql>,
+    {
+        let table = &mut *self.table;
+
+        if table.num_rows == 0 {
+            table.columns.push(column_type(&value));
+        }
+
+        let ty = table.columns.get(self.column).unwrap_or_else(|| {
+            panic!("every row of a table-valued parameter must have the same number of columns")
+        });
+
+        ty.put_value(&mut table.rows, value);
+        self.column += 1;
+
+        self
+    }
+}
+
+// the type of a column, from its value in the first row
+fn column_type<'q, T: Encode<'q, Mssql> + Type<Mssql>>(value: &T) -> TypeInfo {
+    let mut ty = value
+        .produces()
+        .filter(|ty| !ty.0.is_null())
+        .unwrap_or_else(T::type_info)
+        .0;
+
+    // the size of a string is that of the first row; widen it to fit the rows that follow
+    if let DataType::NVarChar | DataType::BigVarChar | DataType::BigVarBinary = ty.ty {
+        ty.size = 8000;
+    }
+
+    ty
+}
+
+impl Type<Mssql> for MssqlTableValue {
+    fn type_info() -> MssqlTypeInfo {
+        MssqlTypeInfo(TypeInfo::new(DataType::Table, 0))
+    }
+}
+
+impl Encode<'_, Mssql> for MssqlTableValue {
+    fn produces(&self) -> Option<MssqlTypeInfo> {
+        let mut ty = TypeInfo::new(DataType::Table, 0);
+        ty.type_name = Some(self.type_name.clone());
+
+        Some(MssqlTypeInfo(ty))
+    }
+
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        // TVP_COLMETADATA
+        if self.columns.is_empty() {
+            buf.extend(&TVP_NULL_TOKEN.to_le_bytes());
+        } else {
+            buf.extend(&(self.columns.len() as u16).to_le_bytes());
+
+            for column in &self.columns {
+                buf.extend(&0_u32.to_le_bytes()); // [UserType]
+                buf.extend(&0x0001_u16.to_le_bytes()); // [Flags] nullable
+                column.put(buf); // [TYPE_INFO]
+                buf.push(0); // [ColName] always empty
+            }
+        }
+
+        buf.push(TVP_END_TOKEN);
+
+        // TVP_ROW*
+        buf.extend_from_slice(&self.rows);
+        buf.push(TVP_END_TOKEN);
+
+        IsNull::No
+    }
+
+    fn size_hint(&self) -> usize {
+        self.rows.len() + self.columns.len() * 16 + 4
+    }
+}
+
+macro_rules! impl_table_row_for_tuple {
+    ($( $idx:ident : $T:ident ),*) => {
+        impl<'q, $($T,)*> MssqlTableRow for ($($T,)*)
+        where
+            $($T: Encode<'q, Mssql> + Type<Mssql>,)*
+        {
+            fn encode_row(self, encoder: &mut MssqlTableRowEncoder<'_>) {
+                let ($($idx,)*) = self;
+
+                $(encoder.encode($idx);)*
+            }
+        }
+    };
+}
+
+impl_table_row_for_tuple!(_1: T1);
+impl_table_row_for_tuple!(_1: T1, _2: T2);
+impl_table_row_for_tuple!(_1: T1, _2: T2, _3: T3);
+impl_table_row_for_tuple!(_1: T1, _2: T2, _3: T3, _4: T4);
+impl_table_row_for_tuple!(_1: T1, _2: T2, _3: T3, _4: T4, _5: T5);
+impl_table_row_for_tuple!(_1: T1, _2: T2, _3: T3, _4: T4, _5: T5, _6: T6);
+impl_table_row_for_tuple!(_1: T1, _2: T2, _3: T3, _4: T4, _5: T5, _6: T6, _7: T7);
+impl_table_row_for_tuple!(_1: T1, _2: T2, _3: T3, _4: T4, _5: T5, _6: T6, _7: T7, _8: T8);
+
+#[test]
+fn it_encodes_a_table_valued_parameter() {
+    let table = MssqlTableValue::from_rows("dbo.Ids", vec![(1_i32, true), (2, false)]);
+
+    let mut buf = Vec::new();
+    table.produces().unwrap().0.put(&mut buf);
+    let _ = table.encode_by_ref(&mut buf);
+
+    #[rustfmt::skip]
+    assert_eq!(buf, [
+        // TVP_TYPE_INFO, TVP_TYPENAME
+        0xf3, 0, 3, b'd', 0, b'b', 0, b'o', 0, 3, b'I', 0, b'd', 0, b's', 0,
+        // TVP_COLMETADATA
+        2, 0,
+        0, 0, 0, 0, 1, 0, 0x26, 4, 0,
+        0, 0, 0, 0, 1, 0, 0x68, 1, 0,
+        0,
+        // TVP_ROW
+        1, 4, 1, 0, 0, 0, 1, 1,
+        1, 4, 2, 0, 0, 0, 1, 0,
+        0,
+    ][..]);
+}
+
+#[test]
+fn it_encodes_an_empty_table_valued_parameter() {
+    let table = MssqlTableValue::new("Ids");
+
+    let mut ty = String::new();
+    table.produces().unwrap().0.fmt(&mut ty);
+    assert_eq!(ty, "Ids READONLY");
+
+    let mut buf = Vec::new();
+    let _ = table.encode_by_ref(&mut buf);
+
+    assert_eq!(buf, [0xff, 0xff, 0, 0]);
+}
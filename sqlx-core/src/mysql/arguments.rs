@@ -1,10 +1,11 @@
 use crate::arguments::Arguments;
+use crate::buffer_pool;
 use crate::encode::{Encode, IsNull};
 use crate::mysql::{MySql, MySqlTypeInfo};
 use crate::types::Type;
 
 /// Implementation of [`Arguments`] for MySQL.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Clone)]
 pub struct MySqlArguments {
     pub(crate) values: Vec<u8>,
     pub(crate) types: Vec<MySqlTypeInfo>,
@@ -33,6 +34,22 @@ impl MySqlArguments {
     }
 }
 
+impl Default for MySqlArguments {
+    fn default() -> Self {
+        Self {
+            values: buffer_pool::take_write_buf(),
+            types: Vec::new(),
+            null_bitmap: Vec::new(),
+        }
+    }
+}
+
+impl Drop for MySqlArguments {
+    fn drop(&mut self) {
+        buffer_pool::recycle_write_buf(std::mem::take(&mut self.values));
+    }
+}
+
 impl<'q> Arguments<'q> for MySqlArguments {
     type Database = MySql;
 
@@ -0,0 +1,83 @@
+use curve25519_dalek::edwards::EdwardsPoint;
+use curve25519_dalek::scalar::Scalar;
+use sha2::{Digest, Sha512};
+
+use crate::error::Error;
+use crate::mysql::MySqlAuthPlugin;
+
+// https://mariadb.com/kb/en/authentication-plugin-ed25519/
+
+/// `client_ed25519` of MariaDB, which signs the nonce of the server with a key derived from the
+/// password.
+#[derive(Default)]
+pub(crate) struct Ed25519 {
+    password: String,
+}
+
+impl MySqlAuthPlugin for Ed25519 {
+    fn start(&mut self, _username: &str, password: &str, data: &[u8]) -> Result<Vec<u8>, Error> {
+        self.password = password.to_owned();
+
+        Ok(sign(password.as_bytes(), data).to_vec())
+    }
+
+    // when the plugin is the default of the server, the nonce of the handshake is ignored and
+    // the server sends its 32-byte nonce after the handshake response
+    fn next(&mut self, data: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        Ok(Some(sign(self.password.as_bytes(), data).to_vec()))
+    }
+}
+
+// The signature of Ed25519 (RFC 8032), expanding the password of any length with SHA-512
+// rather than a 32-byte secret key, like `crypto_sign()` of MariaDB
+fn sign(password: &[u8], message: &[u8]) -> [u8; 64] {
+    let expanded = Sha512::digest(password);
+
+    let mut key = [0; 32];
+    key.copy_from_slice(&expanded[..32]);
+    key[0] &= 248;
+    key[31] &= 63;
+    key[31] |= 64;
+
+    let key = Scalar::from_bytes_mod_order(key);
+    let public_key = EdwardsPoint::mul_base(&key).compress();
+
+    let nonce = hash_to_scalar(&[&expanded[32..], message]);
+    let r = EdwardsPoint::mul_base(&nonce).compress();
+
+    let challenge = hash_to_scalar(&[r.as_bytes(), public_key.as_bytes(), message]);
+    let s = challenge * key + nonce;
+
+    let mut signature = [0; 64];
+    signature[..32].copy_from_slice(r.as_bytes());
+    signature[32..].copy_from_slice(s.as_bytes());
+
+    signature
+}
+
+fn hash_to_scalar(parts: &[&[u8]]) -> Scalar {
+    let mut hasher = Sha512::new();
+
+    for part in parts {
+        hasher.update(part);
+    }
+
+    let mut hash = [0; 64];
+    hash.copy_from_slice(&hasher.finalize());
+
+    Scalar::from_bytes_mod_order_wide(&hash)
+}
+
+#[test]
+fn it_signs_like_ed25519() {
+    // https://www.rfc-editor.org/rfc/rfc8032#section-7.1, as the password of a 32-byte key is
+    // expanded like the key
+    let key =
+        hex::decode("4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb").unwrap();
+
+    assert_eq!(
+        hex::encode(sign(&key, b"\x72")),
+        "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da\
+         085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00"
+    );
+}
@@ -0,0 +1,285 @@
+use digest::core_api::BlockSizeUser;
+use digest::Digest;
+use hmac::{Mac, SimpleHmac};
+use rand::Rng;
+use sha1::Sha1;
+use sha2::Sha256;
+use stringprep::saslprep;
+
+use crate::error::Error;
+use crate::mysql::MySqlAuthPlugin;
+
+// https://dev.mysql.com/doc/refman/8.0/en/ldap-pluggable-authentication.html
+// https://www.rfc-editor.org/rfc/rfc5802
+
+/// `authentication_ldap_sasl_client` of MySQL Enterprise, with the SCRAM mechanisms.
+///
+/// The server names the SASL mechanism when it asks for the plugin; the client then sends its
+/// first SCRAM message, answers the challenge of the server with its proof, and verifies the
+/// signature of the server.
+#[derive(Default)]
+pub(crate) struct LdapSasl {
+    exchange: Option<Exchange>,
+}
+
+struct Exchange {
+    mechanism: Mechanism,
+    password: String,
+    client_first_bare: String,
+    nonce: String,
+    server_signature: Option<Vec<u8>>,
+}
+
+#[derive(Clone, Copy)]
+enum Mechanism {
+    ScramSha1,
+    ScramSha256,
+}
+
+impl MySqlAuthPlugin for LdapSasl {
+    fn start(&mut self, username: &str, password: &str, data: &[u8]) -> Result<Vec<u8>, Error> {
+        let nonce: String = std::iter::repeat_with(|| rand::thread_rng().gen_range(0x21..0x7F))
+            .filter(|&c: &u8| c != b',')
+            .take(24)
+            .map(char::from)
+            .collect();
+
+        self.start_with_nonce(username, password, data, nonce)
+    }
+
+    fn next(&mut self, data: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        let exchange = self.exchange.as_mut().ok_or_else(|| {
+            err_protocol!("unexpected data from the server before the SASL exchange")
+        })?;
+
+        let message = std::str::from_utf8(data).map_err(Error::protocol)?;
+
+        match exchange.server_signature.take() {
+            None => exchange.prove(message).map(Some),
+
+            // server-final-message = (server-error / verifier)
+            Some(server_signature) => {
+                if let Some(error) = attribute(message, 'e') {
+                    return Err(err_protocol!("SASL authentication failed: {}", error));
+                }
+
+                let verifier = attribute(message, 'v')
+                    .and_then(|v| base64::decode(v).ok())
+                    .ok_or_else(|| err_protocol!("invalid SASL final message: {:?}", message))?;
+
+                if verifier != server_signature {
+                    return Err(err_protocol!(
+                        "the signature of the server doesn't match; it may not know the password"
+                    ));
+                }
+
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl LdapSasl {
+    fn start_with_nonce(
+        &mut self,
+        username: &str,
+        password: &str,
+        data: &[u8],
+        nonce: String,
+    ) -> Result<Vec<u8>, Error> {
+        let mechanism = std::str::from_utf8(data)
+            .map_err(Error::protocol)?
+            .trim_end_matches('\0');
+
+        let mechanism = match mechanism {
+            "SCRAM-SHA-1" => Mechanism::ScramSha1,
+            "SCRAM-SHA-256" => Mechanism::ScramSha256,
+
+            _ => {
+                return Err(err_protocol!(
+                    "unsupported SASL mechanism for authentication_ldap_sasl_client: {:?}",
+                    mechanism
+                ));
+            }
+        };
+
+        let username = username.replace('=', "=3D").replace(',', "=2C");
+        let client_first_bare = format!("n={},r={}", username, nonce);
+
+        // no channel binding
+        let client_first = format!("n,,{}", client_first_bare);
+
+        self.exchange = Some(Exchange {
+            mechanism,
+            password: saslprep(password)
+                .map(|password| password.into_owned())
+                .unwrap_or_else(|_| password.to_owned()),
+            client_first_bare,
+            nonce,
+            server_signature: None,
+        });
+
+        Ok(client_first.into_bytes())
+    }
+}
+
+impl Exchange {
+    // answer the server-first-message with the client-final-message
+    fn prove(&mut self, server_first: &str) -> Result<Vec<u8>, Error> {
+        let invalid = || err_protocol!("invalid SASL challenge: {:?}", server_first);
+
+        let nonce = attribute(server_first, 'r').ok_or_else(invalid)?;
+        let salt = attribute(server_first, 's')
+            .and_then(|salt| base64::decode(salt).ok())
+            .ok_or_else(invalid)?;
+        let iterations: u32 = attribute(server_first, 'i')
+            .and_then(|i| i.parse().ok())
+            .ok_or_else(invalid)?;
+
+        if !nonce.starts_with(&self.nonce) {
+            return Err(err_protocol!(
+                "the SASL nonce of the server doesn't extend ours"
+            ));
+        }
+
+        let mechanism = self.mechanism;
+        let salted_password = mechanism.hi(self.password.as_bytes(), &salt, iterations);
+
+        let client_key = mechanism.hmac(&salted_password, b"Client Key");
+        let stored_key = mechanism.hash(&client_key);
+
+        // "biws" is the GS2 header "n,," in base64
+        let client_final_without_proof = format!("c=biws,r={}", nonce);
+
+        let auth_message = format!(
+            "{},{},{}",
+            self.client_first_bare, server_first, client_final_without_proof
+        );
+
+        let client_signature = mechanism.hmac(&stored_key, auth_message.as_bytes());
+
+        let proof: Vec<u8> = client_key
+            .iter()
+            .zip(&client_signature)
+            .map(|(key, signature)| key ^ signature)
+            .collect();
+
+        let server_key = mechanism.hmac(&salted_password, b"Server Key");
+        self.server_signature = Some(mechanism.hmac(&server_key, auth_message.as_bytes()));
+
+        Ok(format!("{},p={}", client_final_without_proof, base64::encode(proof)).into_bytes())
+    }
+}
+
+impl Mechanism {
+    fn hash(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Mechanism::ScramSha1 => Sha1::digest(data).to_vec(),
+            Mechanism::ScramSha256 => Sha256::digest(data).to_vec(),
+        }
+    }
+
+    fn hmac(self, key: &[u8], data: &[u8]) -> Vec<u8> {
+        match self {
+            Mechanism::ScramSha1 => hmac::<Sha1>(key, data),
+            Mechanism::ScramSha256 => hmac::<Sha256>(key, data),
+        }
+    }
+
+    // Hi(str, salt, i), i.e. PBKDF2 with the HMAC of the mechanism
+    fn hi(self, password: &[u8], salt: &[u8], iterations: u32) -> Vec<u8> {
+        let mut u = self.hmac(password, &[salt, &1_u32.to_be_bytes()].concat());
+        let mut hi = u.clone();
+
+        for _ in 1..iterations {
+            u = self.hmac(password, &u);
+
+            for (hi, u) in hi.iter_mut().zip(&u) {
+                *hi ^= u;
+            }
+        }
+
+        hi
+    }
+}
+
+fn hmac<D: Digest + BlockSizeUser>(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac =
+        <SimpleHmac<D> as Mac>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(data);
+
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn attribute(message: &str, name: char) -> Option<&str> {
+    message.split(',').find_map(|attribute| {
+        attribute
+            .strip_prefix(name)
+            .and_then(|value| value.strip_prefix('='))
+    })
+}
+
+#[cfg(test)]
+fn exchange(mechanism: &str, nonce: &str, server_first: &str, server_final: &str) -> String {
+    let mut plugin = LdapSasl::default();
+
+    let client_first = plugin
+        .start_with_nonce("user", "pencil", mechanism.as_bytes(), nonce.to_owned())
+        .unwrap();
+
+    assert_eq!(client_first, format!("n,,n=user,r={}", nonce).as_bytes());
+
+    let client_final = plugin.next(server_first.as_bytes()).unwrap().unwrap();
+
+    assert!(plugin.next(server_final.as_bytes()).unwrap().is_none());
+
+    String::from_utf8(client_final).unwrap()
+}
+
+#[test]
+fn it_authenticates_with_scram_sha_1() {
+    // https://www.rfc-editor.org/rfc/rfc5802#section-5
+    let client_final = exchange(
+        "SCRAM-SHA-1",
+        "fyko+d2lbbFgONRv9qkxdawL",
+        "r=fyko+d2lbbFgONRv9qkxdawL3rfcNHYJY1ZVvWVs7j,s=QSXCR+Q6sek8bf92,i=4096",
+        "v=rmF9pqV8S7suAoZWja4dJRkFsKQ=",
+    );
+
+    assert_eq!(
+        client_final,
+        "c=biws,r=fyko+d2lbbFgONRv9qkxdawL3rfcNHYJY1ZVvWVs7j,p=v0X8v3Bz2T0CJGbJQyF0X+HI4Ts="
+    );
+}
+
+#[test]
+fn it_authenticates_with_scram_sha_256() {
+    // https://www.rfc-editor.org/rfc/rfc7677#section-3
+    let client_final = exchange(
+        "SCRAM-SHA-256\0",
+        "rOprNGfwEbeRWgbNEkqO",
+        "r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096",
+        "v=6rriTRBi23WpRR/wtup+mMhUZUn/dB5nLTJRsjl95G4=",
+    );
+
+    assert_eq!(
+        client_final,
+        "c=biws,r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,\
+         p=dHzbZapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ="
+    );
+}
+
+#[test]
+fn it_rejects_a_wrong_server_signature() {
+    let mut plugin = LdapSasl::default();
+
+    plugin
+        .start_with_nonce("user", "pencil", b"SCRAM-SHA-1", "fyko".to_owned())
+        .unwrap();
+
+    plugin
+        .next(b"r=fyko3rfcNHYJY1ZVvWVs7j,s=QSXCR+Q6sek8bf92,i=4096")
+        .unwrap();
+
+    assert!(plugin.next(b"v=rmF9pqV8S7suAoZWja4dJRkFsKQ=").is_err());
+}
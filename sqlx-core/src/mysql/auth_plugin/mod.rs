@@ -0,0 +1,69 @@
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use crate::error::Error;
+
+mod ed25519;
+mod ldap_sasl;
+
+pub(crate) use ed25519::Ed25519;
+pub(crate) use ldap_sasl::LdapSasl;
+
+/// A client authentication plugin, answering the server authentication plugin of an account.
+///
+/// The plugins `mysql_native_password`, `caching_sha2_password` and `sha256_password` are
+/// supported, as well as `client_ed25519` of MariaDB and `authentication_ldap_sasl_client`
+/// of MySQL Enterprise, with the `SCRAM-SHA-1` and `SCRAM-SHA-256` mechanisms. Other plugins
+/// can be implemented with this trait, and registered by name with
+/// [`MySqlConnectOptions::auth_plugin`](crate::mysql::MySqlConnectOptions::auth_plugin).
+///
+/// A plugin is created for each connection, so it can keep the state of a multi-step exchange.
+///
+/// # Example
+///
+/// ```rust
+/// # use sqlx_core::error::Error;
+/// # use sqlx_core::mysql::{MySqlAuthPlugin, MySqlConnectOptions};
+/// // sends the password in clear text, as for PAM or LDAP simple authentication;
+/// // it should only be used over TLS
+/// struct ClearPassword;
+///
+/// impl MySqlAuthPlugin for ClearPassword {
+///     fn start(&mut self, _username: &str, password: &str, _data: &[u8]) -> Result<Vec<u8>, Error> {
+///         let mut response = password.as_bytes().to_vec();
+///         response.push(0);
+///
+///         Ok(response)
+///     }
+/// }
+///
+/// let options = MySqlConnectOptions::new()
+///     .auth_plugin("mysql_clear_password", || ClearPassword);
+/// ```
+pub trait MySqlAuthPlugin: Send {
+    /// Respond to the data sent by the server when it asks for this plugin, usually a nonce.
+    fn start(&mut self, username: &str, password: &str, data: &[u8]) -> Result<Vec<u8>, Error>;
+
+    /// Respond to more data sent by the server, or return `None` to wait for the result of the
+    /// authentication.
+    fn next(&mut self, data: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        let _ = data;
+
+        Err(err_protocol!(
+            "unexpected data from the server during authentication"
+        ))
+    }
+}
+
+/// A plugin registered with [`auth_plugin`](crate::mysql::MySqlConnectOptions::auth_plugin).
+#[derive(Clone)]
+pub(crate) struct AuthPluginFactory {
+    pub(crate) name: String,
+    pub(crate) create: Arc<dyn Fn() -> Box<dyn MySqlAuthPlugin> + Send + Sync>,
+}
+
+impl Debug for AuthPluginFactory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "AuthPluginFactory({:?})", self.name)
+    }
+}
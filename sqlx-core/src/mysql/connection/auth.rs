@@ -13,25 +13,44 @@ use crate::mysql::protocol::auth::AuthPlugin;
 use crate::mysql::protocol::Packet;
 
 impl AuthPlugin {
+    /// The response to the data of the server asking for the plugin, or `None` if there is no
+    /// password to send.
     pub(super) async fn scramble(
-        self,
+        &mut self,
         stream: &mut MySqlStream,
-        password: &str,
+        username: &str,
+        password: Option<&str>,
         nonce: &Chain<Bytes, Bytes>,
-    ) -> Result<Vec<u8>, Error> {
-        match self {
+    ) -> Result<Option<Vec<u8>>, Error> {
+        match (self, password) {
+            (AuthPlugin::Other(_, plugin), password) => {
+                let data = [nonce.first_ref().as_ref(), nonce.last_ref().as_ref()].concat();
+
+                plugin
+                    .start(username, password.unwrap_or_default(), &data)
+                    .map(Some)
+            }
+
+            (_, None) => Ok(None),
+
             // https://mariadb.com/kb/en/caching_sha2_password-authentication-plugin/
-            AuthPlugin::CachingSha2Password => Ok(scramble_sha256(password, nonce).to_vec()),
+            (AuthPlugin::CachingSha2Password, Some(password)) => {
+                Ok(Some(scramble_sha256(password, nonce).to_vec()))
+            }
 
-            AuthPlugin::MySqlNativePassword => Ok(scramble_sha1(password, nonce).to_vec()),
+            (AuthPlugin::MySqlNativePassword, Some(password)) => {
+                Ok(Some(scramble_sha1(password, nonce).to_vec()))
+            }
 
             // https://mariadb.com/kb/en/sha256_password-plugin/
-            AuthPlugin::Sha256Password => encrypt_rsa(stream, 0x01, password, nonce).await,
+            (AuthPlugin::Sha256Password, Some(password)) => {
+                encrypt_rsa(stream, 0x01, password, nonce).await.map(Some)
+            }
         }
     }
 
     pub(super) async fn handle(
-        self,
+        &mut self,
         stream: &mut MySqlStream,
         packet: Packet<Bytes>,
         password: &str,
@@ -59,6 +78,16 @@ impl AuthPlugin {
                 }
             }
 
+            // AUTH_MORE_DATA
+            AuthPlugin::Other(_, plugin) if packet[0] == 0x01 => {
+                if let Some(response) = plugin.next(&packet[1..])? {
+                    stream.write_packet(&*response);
+                    stream.flush().await?;
+                }
+
+                Ok(false)
+            }
+
             _ => Err(err_protocol!(
                 "unexpected packet 0x{:02x} for auth plugin '{}' during authentication",
                 packet[0],
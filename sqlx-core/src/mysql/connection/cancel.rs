@@ -0,0 +1,61 @@
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use crate::connection::{ConnectOptions, Connection};
+use crate::error::Error;
+use crate::executor::Executor;
+use crate::mysql::MySqlConnectOptions;
+
+/// A handle to cancel the statement running on a [`MySqlConnection`][super::MySqlConnection],
+/// from another task; returned by
+/// [`MySqlConnection::cancel_token()`][super::MySqlConnection::cancel_token].
+///
+/// [`cancel()`][Self::cancel] opens a new connection with the options of the connection, to run
+/// [`KILL QUERY`](https://dev.mysql.com/doc/refman/8.0/en/kill.html) with its connection ID. The
+/// server then interrupts the statement which is running on the connection, if any, and frees
+/// its resources; the statement fails with an `ER_QUERY_INTERRUPTED` (`1317`) error. The user of
+/// the connection needs the `CONNECTION_ADMIN` privilege to cancel the statements of the
+/// connections of other users.
+///
+/// The statement may complete before it is cancelled; as the connection ID is only known by the
+/// server the token was created from, cancelling through a proxy may fail or cancel a statement
+/// on another connection.
+#[derive(Clone)]
+pub struct MySqlCancelToken {
+    options: Arc<MySqlConnectOptions>,
+    connection_id: u32,
+}
+
+impl MySqlCancelToken {
+    pub(super) fn new(options: Arc<MySqlConnectOptions>, connection_id: u32) -> Self {
+        Self {
+            options,
+            connection_id,
+        }
+    }
+
+    /// The ID of the connection, i.e. the value of `CONNECTION_ID()` in its session.
+    pub fn connection_id(&self) -> u32 {
+        self.connection_id
+    }
+
+    /// Asks the server to cancel the statement running on the connection, if any.
+    pub async fn cancel(&self) -> Result<(), Error> {
+        let mut conn = self.options.connect().await?;
+
+        conn.execute(&*format!("KILL QUERY {}", self.connection_id))
+            .await?;
+
+        conn.close().await
+    }
+}
+
+impl Debug for MySqlCancelToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MySqlCancelToken")
+            .field("host", &self.options.host)
+            .field("port", &self.options.port)
+            .field("connection_id", &self.connection_id)
+            .finish()
+    }
+}
@@ -0,0 +1,182 @@
+use std::cmp;
+use std::io::{Read, Write};
+
+use bytes::{Buf, Bytes, BytesMut};
+
+use crate::error::Error;
+use crate::mysql::MySqlCompression;
+
+// https://dev.mysql.com/doc/dev/mysql-server/latest/page_protocol_basic_compression.html
+// https://mariadb.com/kb/en/0-packet/#compressed-packet
+
+// payloads shorter than this are sent uncompressed, as by the MySQL client
+const MIN_COMPRESS_LENGTH: usize = 50;
+
+const MAX_PAYLOAD_LENGTH: usize = 0xFF_FF_FF;
+
+/// The compressed packets wrap a stream of regular packets, which may be split across them.
+pub(super) struct Compression {
+    algorithm: MySqlCompression,
+
+    // decompressed data which wasn't read yet
+    pub(super) rbuf: BytesMut,
+}
+
+impl Compression {
+    pub(super) fn new(algorithm: MySqlCompression) -> Self {
+        Self {
+            algorithm,
+            rbuf: BytesMut::new(),
+        }
+    }
+
+    /// Wrap the regular packets of a command in compressed packets.
+    pub(super) fn compress(&self, mut packets: &[u8], buf: &mut Vec<u8>) -> Result<(), Error> {
+        // the sequence of the compressed packets restarts with each command, like the one of
+        // the regular packets
+        let mut sequence_id = 0_u8;
+
+        while !packets.is_empty() {
+            let len = cmp::min(packets.len(), MAX_PAYLOAD_LENGTH);
+            let (payload, rest) = packets.split_at(len);
+
+            // 0 as the uncompressed length marks an uncompressed payload
+            let (payload, uncompressed_len) = if len < MIN_COMPRESS_LENGTH {
+                (payload.to_vec(), 0)
+            } else {
+                (self.compress_payload(payload)?, len)
+            };
+
+            buf.extend(&(payload.len() as u32).to_le_bytes()[..3]);
+            buf.push(sequence_id);
+            buf.extend(&(uncompressed_len as u32).to_le_bytes()[..3]);
+            buf.extend(payload);
+
+            sequence_id = sequence_id.wrapping_add(1);
+            packets = rest;
+        }
+
+        Ok(())
+    }
+
+    /// Decompress the payload of a compressed packet into the read buffer, given its header.
+    pub(super) fn decompress(&mut self, mut header: Bytes, payload: Bytes) -> Result<(), Error> {
+        let _compressed_len = header.get_uint_le(3);
+        let _sequence_id = header.get_u8();
+        let uncompressed_len = header.get_uint_le(3) as usize;
+
+        if uncompressed_len == 0 {
+            self.rbuf.extend_from_slice(&payload);
+
+            return Ok(());
+        }
+
+        let data = self.decompress_payload(&payload, uncompressed_len)?;
+
+        if data.len() != uncompressed_len {
+            return Err(err_protocol!(
+                "compressed packet decompressed to {} bytes, expected {}",
+                data.len(),
+                uncompressed_len
+            ));
+        }
+
+        self.rbuf.extend_from_slice(&data);
+
+        Ok(())
+    }
+
+    fn compress_payload(&self, payload: &[u8]) -> Result<Vec<u8>, Error> {
+        match self.algorithm {
+            MySqlCompression::Disabled => Ok(payload.to_vec()),
+
+            MySqlCompression::Zlib => {
+                let mut encoder =
+                    flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+
+                encoder.write_all(payload)?;
+
+                Ok(encoder.finish()?)
+            }
+
+            #[cfg(feature = "mysql-zstd")]
+            MySqlCompression::Zstd { level } => Ok(zstd::bulk::compress(payload, level.into())?),
+        }
+    }
+
+    fn decompress_payload(&self, payload: &[u8], len: usize) -> Result<Vec<u8>, Error> {
+        let data = match self.algorithm {
+            MySqlCompression::Disabled => Ok(payload.to_vec()),
+
+            MySqlCompression::Zlib => {
+                let mut data = Vec::with_capacity(len);
+
+                flate2::read::ZlibDecoder::new(payload)
+                    .read_to_end(&mut data)
+                    .map(|_| data)
+            }
+
+            #[cfg(feature = "mysql-zstd")]
+            MySqlCompression::Zstd { .. } => zstd::bulk::decompress(payload, len),
+        };
+
+        data.map_err(|e| err_protocol!("failed to decompress packet: {}", e))
+    }
+}
+
+#[cfg(test)]
+fn round_trip(algorithm: MySqlCompression, packets: &[u8]) -> Vec<u8> {
+    let compression = Compression::new(algorithm);
+    let mut buf = Vec::new();
+
+    compression.compress(packets, &mut buf).unwrap();
+
+    let mut buf = Bytes::from(buf);
+    let mut decompression = Compression::new(algorithm);
+
+    while !buf.is_empty() {
+        let header = buf.split_to(7);
+        let len = (&header[..]).get_uint_le(3) as usize;
+        let payload = buf.split_to(len);
+
+        decompression.decompress(header, payload).unwrap();
+    }
+
+    decompression.rbuf.to_vec()
+}
+
+#[test]
+fn it_sends_short_payloads_uncompressed() {
+    let compression = Compression::new(MySqlCompression::Zlib);
+    let mut buf = Vec::new();
+
+    // COM_PING
+    compression
+        .compress(b"\x01\x00\x00\x00\x0e", &mut buf)
+        .unwrap();
+
+    assert_eq!(buf, b"\x05\x00\x00\x00\x00\x00\x00\x01\x00\x00\x00\x0e");
+}
+
+#[test]
+fn it_compresses_with_zlib() {
+    let packets = b"SELECT * FROM users WHERE name = 'sqlx' ".repeat(100);
+
+    let compression = Compression::new(MySqlCompression::Zlib);
+    let mut buf = Vec::new();
+    compression.compress(&packets, &mut buf).unwrap();
+
+    assert!(buf.len() < packets.len());
+    assert_eq!(&buf[4..7], &(packets.len() as u32).to_le_bytes()[..3]);
+
+    assert_eq!(round_trip(MySqlCompression::Zlib, &packets), packets);
+}
+
+#[cfg(feature = "mysql-zstd")]
+#[test]
+fn it_compresses_with_zstd() {
+    let packets = b"SELECT * FROM users WHERE name = 'sqlx' ".repeat(100);
+    let algorithm = MySqlCompression::Zstd { level: 3 };
+
+    assert_eq!(round_trip(algorithm, &packets), packets);
+}
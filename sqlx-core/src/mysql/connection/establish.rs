@@ -1,9 +1,13 @@
 use bytes::buf::Buf;
 use bytes::Bytes;
+use std::sync::Arc;
 
 use crate::common::StatementCache;
+use crate::connection::ServerVersion;
 use crate::error::Error;
-use crate::mysql::connection::{tls, MySqlStream, MAX_PACKET_SIZE};
+use crate::ext::async_stream::BufferLimits;
+use crate::mysql::connection::{tls, MySqlCancelToken, MySqlStream, MAX_PACKET_SIZE};
+use crate::mysql::protocol::auth::AuthPlugin;
 use crate::mysql::protocol::connect::{
     AuthSwitchRequest, AuthSwitchResponse, Handshake, HandshakeResponse,
 };
@@ -19,37 +23,16 @@ impl MySqlConnection {
 
         let handshake: Handshake = stream.recv_packet().await?.decode()?;
 
-        let mut plugin = handshake.auth_plugin;
+        let mut plugin = handshake
+            .auth_plugin
+            .as_deref()
+            .map(|name| AuthPlugin::new(name, options))
+            .transpose()?;
         let mut nonce = handshake.auth_plugin_data;
 
-        // FIXME: server version parse is a bit ugly
-        // expecting MAJOR.MINOR.PATCH
-
-        let mut server_version = handshake.server_version.split('.');
-
-        let server_version_major: u16 = server_version
-            .next()
-            .unwrap_or_default()
-            .parse()
-            .unwrap_or(0);
-
-        let server_version_minor: u16 = server_version
-            .next()
-            .unwrap_or_default()
-            .parse()
-            .unwrap_or(0);
-
-        let server_version_patch: u16 = server_version
-            .next()
-            .unwrap_or_default()
-            .parse()
-            .unwrap_or(0);
-
-        stream.server_version = (
-            server_version_major,
-            server_version_minor,
-            server_version_patch,
-        );
+        let (server_version, is_mariadb) = parse_server_version(&handshake.server_version);
+        stream.server_version = server_version;
+        stream.is_mariadb = is_mariadb;
 
         stream.capabilities &= handshake.server_capabilities;
         stream.capabilities |= Capabilities::PROTOCOL_41;
@@ -62,19 +45,48 @@ impl MySqlConnection {
         // Upgrade to TLS if we were asked to and the server supports it
         tls::maybe_upgrade(&mut stream, options).await?;
 
-        let auth_response = if let (Some(plugin), Some(password)) = (plugin, &options.password) {
-            Some(plugin.scramble(&mut stream, password, &nonce).await?)
+        let auth_response = if let Some(plugin) = &mut plugin {
+            plugin
+                .scramble(
+                    &mut stream,
+                    &options.username,
+                    options.password.as_deref(),
+                    &nonce,
+                )
+                .await?
         } else {
             None
         };
 
+        let pid = std::process::id().to_string();
+
+        // the attributes of the official clients, which are documented as the `_`-prefixed ones
+        let mut connect_attrs = vec![
+            ("_client_name", "sqlx"),
+            ("_client_version", env!("CARGO_PKG_VERSION")),
+            ("_os", std::env::consts::OS),
+            ("_platform", std::env::consts::ARCH),
+            ("_pid", &*pid),
+        ];
+
+        // the options may replace them
+        connect_attrs.retain(|(key, _)| !options.connect_attrs.iter().any(|(k, _)| k == key));
+        connect_attrs.extend(
+            options
+                .connect_attrs
+                .iter()
+                .map(|(key, value)| (&**key, &**value)),
+        );
+
         stream.write_packet(HandshakeResponse {
             collation: stream.collation as u8,
             max_packet_size: MAX_PACKET_SIZE,
             username: &options.username,
             database: options.database.as_deref(),
-            auth_plugin: plugin,
+            auth_plugin: plugin.as_ref().map(AuthPlugin::name),
             auth_response: auth_response.as_deref(),
+            connect_attrs: &connect_attrs,
+            zstd_compression_level: options.compression.zstd_level(),
         });
 
         stream.flush().await?;
@@ -91,24 +103,28 @@ impl MySqlConnection {
                 0xfe => {
                     let switch: AuthSwitchRequest = packet.decode()?;
 
-                    plugin = Some(switch.plugin);
+                    let mut switched = AuthPlugin::new(&switch.plugin, options)?;
                     nonce = switch.data.chain(Bytes::new());
 
-                    let response = switch
-                        .plugin
+                    let response = switched
                         .scramble(
                             &mut stream,
-                            options.password.as_deref().unwrap_or_default(),
+                            &options.username,
+                            Some(options.password.as_deref().unwrap_or_default()),
                             &nonce,
                         )
                         .await?;
 
-                    stream.write_packet(AuthSwitchResponse(response));
+                    plugin = Some(switched);
+
+                    stream.write_packet(AuthSwitchResponse(response.unwrap_or_default()));
                     stream.flush().await?;
                 }
 
                 id => {
-                    if let (Some(plugin), Some(password)) = (plugin, &options.password) {
+                    if let Some(plugin) = &mut plugin {
+                        let password = options.password.as_deref().unwrap_or_default();
+
                         if plugin.handle(&mut stream, packet, password, &nonce).await? {
                             // plugin signaled authentication is ok
                             break;
@@ -125,11 +141,71 @@ impl MySqlConnection {
             }
         }
 
+        // the packets following the authentication are compressed, if negotiated
+        stream.start_compression(options.compression);
+
         Ok(Self {
             stream,
             transaction_depth: 0,
+            statement_count: 0,
             cache_statement: StatementCache::new(options.statement_cache_capacity),
-            log_settings: options.log_settings.clone(),
+            uncached_statements: Vec::new(),
+            max_prepared_statements: options.max_prepared_statements,
+            log_settings: options
+                .log_settings
+                .for_connection(handshake.connection_id.into()),
+            sql_commenter: options.sql_commenter.clone(),
+            row_buffer: BufferLimits {
+                items: options.row_buffer_size,
+                bytes: options.row_buffer_bytes,
+            },
+            cancel_token: MySqlCancelToken::new(Arc::new(options.clone()), handshake.connection_id),
         })
     }
 }
+
+// parses a version like `8.0.28-0ubuntu0.20.04.3`, telling whether the server is MariaDB
+fn parse_server_version(s: &str) -> (Option<ServerVersion>, bool) {
+    let is_mariadb = s.contains("MariaDB");
+
+    // MariaDB prefixes its version with `5.5.5-`, for clients expecting MySQL 5
+    let s = match s.strip_prefix("5.5.5-") {
+        Some(rest) if is_mariadb => rest,
+        _ => s,
+    };
+
+    let mut parts = s.splitn(3, '.').map(|part| {
+        let digits = part
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(part.len());
+        part[..digits].parse::<u32>().ok()
+    });
+
+    let version = match (parts.next(), parts.next(), parts.next()) {
+        (Some(Some(major)), Some(Some(minor)), patch) => Some(ServerVersion::new(
+            major,
+            minor,
+            patch.flatten().unwrap_or(0),
+        )),
+        _ => None,
+    };
+
+    (version, is_mariadb)
+}
+
+#[test]
+fn test_parse_server_version() {
+    assert_eq!(
+        parse_server_version("8.0.28-0ubuntu0.20.04.3"),
+        (Some(ServerVersion::new(8, 0, 28)), false)
+    );
+    assert_eq!(
+        parse_server_version("5.5.5-10.4.7-MariaDB-1:10.4.7+maria~bionic"),
+        (Some(ServerVersion::new(10, 4, 7)), true)
+    );
+    assert_eq!(
+        parse_server_version("5.7.31-log"),
+        (Some(ServerVersion::new(5, 7, 31)), false)
+    );
+    assert_eq!(parse_server_version("unknown"), (None, false));
+}
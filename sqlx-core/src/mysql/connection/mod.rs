@@ -1,21 +1,27 @@
 use crate::common::StatementCache;
-use crate::connection::{Connection, LogSettings};
+use crate::connection::{Connection, LogSettings, ServerCapabilities, ServerVersion};
 use crate::error::Error;
+use crate::ext::async_stream::BufferLimits;
 use crate::mysql::protocol::statement::StmtClose;
 use crate::mysql::protocol::text::{Ping, Quit};
 use crate::mysql::statement::MySqlStatementMetadata;
 use crate::mysql::{MySql, MySqlConnectOptions};
-use crate::transaction::Transaction;
+use crate::sqlcommenter::SqlCommenter;
+use crate::transaction::{Transaction, TransactionOptions};
 use futures_core::future::BoxFuture;
 use futures_util::FutureExt;
 use std::fmt::{self, Debug, Formatter};
+use std::mem;
 
 mod auth;
+mod cancel;
+mod compression;
 mod establish;
 mod executor;
 mod stream;
 mod tls;
 
+pub use cancel::MySqlCancelToken;
 pub(crate) use stream::{MySqlStream, Waiting};
 
 const MAX_PACKET_SIZE: u32 = 1024;
@@ -30,10 +36,61 @@ pub struct MySqlConnection {
     // transaction status
     pub(crate) transaction_depth: usize,
 
+    // the statements executed so far
+    pub(crate) statement_count: u64,
+
     // cache by query string to the statement id and metadata
     cache_statement: StatementCache<(u32, MySqlStatementMetadata)>,
 
+    // statements prepared for queries which aren't persistent, left open on the server
+    uncached_statements: Vec<u32>,
+    max_prepared_statements: Option<usize>,
+
     log_settings: LogSettings,
+
+    // the tags of the comment appended to the queries
+    sql_commenter: Option<SqlCommenter>,
+
+    // the rows of a streamed query read ahead of its consumer
+    row_buffer: BufferLimits,
+
+    // connection id of this session, with the options to connect to its server,
+    // used to kill its running statement
+    cancel_token: MySqlCancelToken,
+}
+
+impl MySqlConnection {
+    /// A handle to cancel the statement running on this connection, from another task.
+    ///
+    /// This is the way to stop a long-running statement on the server, e.g. when it exceeds a
+    /// timeout: dropping the future of a query doesn't stop the statement, which keeps running
+    /// on the server.
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::error::Error;
+    /// # use sqlx_core::executor::Executor;
+    /// # use sqlx_core::mysql::MySqlConnection;
+    /// # async fn f(conn: &mut MySqlConnection) -> Result<(), Error> {
+    /// let token = conn.cancel_token();
+    ///
+    /// sqlx_rt::spawn(async move {
+    ///     sqlx_rt::sleep(std::time::Duration::from_secs(5)).await;
+    ///     let _ = token.cancel().await;
+    /// });
+    ///
+    /// // interrupted after 5 seconds
+    /// conn.execute("SELECT SLEEP(60)").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cancel_token(&self) -> MySqlCancelToken {
+        self.cancel_token.clone()
+    }
+
+    /// Whether the server is MariaDB rather than MySQL, as told by its version.
+    pub fn is_mariadb(&self) -> bool {
+        self.stream.is_mariadb
+    }
 }
 
 impl Debug for MySqlConnection {
@@ -63,16 +120,56 @@ impl Connection for MySqlConnection {
         })
     }
 
+    fn transaction_depth(&self) -> usize {
+        self.transaction_depth
+    }
+
+    fn statement_count(&self) -> u64 {
+        self.statement_count
+    }
+
     fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
             self.stream.wait_until_ready().await?;
+
+            self.stream.waiting.push_back(Waiting::Result);
             self.stream.send_packet(Ping).await?;
             self.stream.recv_ok().await?;
+            self.stream.waiting.pop_front();
 
             Ok(())
         })
     }
 
+    fn server_version(&self) -> Option<ServerVersion> {
+        self.stream.server_version
+    }
+
+    fn server_capabilities(&self) -> ServerCapabilities {
+        let version = match self.stream.server_version {
+            Some(version) => version,
+            None => return ServerCapabilities::default(),
+        };
+
+        let at_least = |major, minor, patch| version >= ServerVersion::new(major, minor, patch);
+
+        if self.stream.is_mariadb {
+            ServerCapabilities {
+                returning: at_least(10, 5, 0),
+                json: at_least(10, 2, 7),
+                common_table_expressions: at_least(10, 2, 1),
+                multiranges: false,
+            }
+        } else {
+            ServerCapabilities {
+                returning: false,
+                json: at_least(5, 7, 8),
+                common_table_expressions: at_least(8, 0, 0),
+                multiranges: false,
+            }
+        }
+    }
+
     #[doc(hidden)]
     fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         self.stream.wait_until_ready().boxed()
@@ -84,6 +181,15 @@ impl Connection for MySqlConnection {
 
     fn clear_cached_statements(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
+            // the statements which aren't cached are closed as well
+            for statement_id in mem::take(&mut self.uncached_statements) {
+                self.stream
+                    .send_packet(StmtClose {
+                        statement: statement_id,
+                    })
+                    .await?;
+            }
+
             while let Some((statement_id, _)) = self.cache_statement.remove_lru() {
                 self.stream
                     .send_packet(StmtClose {
@@ -105,6 +211,16 @@ impl Connection for MySqlConnection {
     where
         Self: Sized,
     {
-        Transaction::begin(self)
+        Transaction::start(self)
+    }
+
+    fn begin_with(
+        &mut self,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized,
+    {
+        Transaction::start_with(self, options)
     }
 }
@@ -1,34 +1,79 @@
+use std::cmp;
 use std::collections::VecDeque;
 use std::ops::{Deref, DerefMut};
 
-use bytes::{Buf, Bytes};
+use bytes::{Buf, Bytes, BytesMut};
 
+use crate::buffer_pool;
+use crate::connection::ServerVersion;
 use crate::error::Error;
 use crate::io::{BufStream, Decode, Encode};
 use crate::mysql::collation::{CharSet, Collation};
+use crate::mysql::connection::compression::Compression;
 use crate::mysql::io::MySqlBufExt;
 use crate::mysql::protocol::response::{EofPacket, ErrPacket, OkPacket, Status};
+use crate::mysql::protocol::statement::PrepareOk;
 use crate::mysql::protocol::{Capabilities, Packet};
-use crate::mysql::{MySqlConnectOptions, MySqlDatabaseError};
+use crate::mysql::{MySqlCompression, MySqlConnectOptions, MySqlDatabaseError};
 use crate::net::{MaybeTlsStream, Socket};
 
 pub struct MySqlStream {
     stream: BufStream<MaybeTlsStream<Socket>>,
-    pub(crate) server_version: (u16, u16, u16),
+    pub(crate) server_version: Option<ServerVersion>,
+    pub(crate) is_mariadb: bool,
     pub(super) capabilities: Capabilities,
     pub(crate) sequence_id: u8,
     pub(crate) waiting: VecDeque<Waiting>,
     pub(crate) charset: CharSet,
     pub(crate) collation: Collation,
+    compression: Option<Compression>,
+    max_message_size: Option<usize>,
+
+    // the payload being received, kept so that a receive which is cancelled is resumed by the
+    // next one
+    partial: PartialPayload,
+
+    // the number of decompressed bytes to discard before the next read
+    skipping: usize,
+
+    // the length of the compressed packets at the start of the write buffer, and whether they
+    // are being flushed; a flush which is cancelled leaves the rest of them in the buffer
+    compressed: usize,
+    flushing: bool,
+}
+
+#[derive(Default)]
+struct PartialPayload {
+    // the size of the packet whose header was received, but not its payload yet
+    packet_size: Option<usize>,
+
+    // the size of the payload, across the packets received so far
+    size: usize,
+
+    // the payload of the packets received so far, when it's split across packets
+    joined: BytesMut,
+
+    // whether the payload is over the maximum size, so that its packets are skipped
+    oversized: bool,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+// the largest payload of a packet; a larger payload is split into packets of this size,
+// followed by a shorter packet
+const MAX_PACKET_PAYLOAD: usize = 0xFF_FF_FF;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum Waiting {
     // waiting for a result set
     Result,
 
     // waiting for a row within a result set
     Row,
+
+    // waiting for the response to the preparation of a statement
+    Prepare,
+
+    // waiting for this number of column or parameter definitions, and their EOF packets
+    Definitions(usize),
 }
 
 impl MySqlStream {
@@ -42,8 +87,13 @@ impl MySqlStream {
             .unwrap_or_else(|| charset.default_collation());
 
         let socket = match options.socket {
+            #[cfg(windows)]
+            Some(ref path) => Socket::connect_named_pipe(named_pipe_path(path)).await?,
+
+            #[cfg(not(windows))]
             Some(ref path) => Socket::connect_uds(path).await?,
-            None => Socket::connect_tcp(&options.host, options.port).await?,
+
+            None => Socket::connect_tcp(&options.host, options.port, &options.tcp).await?,
         };
 
         let mut capabilities = Capabilities::PROTOCOL_41
@@ -53,66 +103,134 @@ impl MySqlStream {
             | Capabilities::TRANSACTIONS
             | Capabilities::SECURE_CONNECTION
             | Capabilities::PLUGIN_AUTH_LENENC_DATA
-            | Capabilities::MULTI_STATEMENTS
             | Capabilities::MULTI_RESULTS
             | Capabilities::PLUGIN_AUTH
+            | Capabilities::CONNECT_ATTRS
             | Capabilities::PS_MULTI_RESULTS
-            | Capabilities::SSL;
+            | Capabilities::SSL
+            | options.compression.capability();
 
         if options.database.is_some() {
             capabilities |= Capabilities::CONNECT_WITH_DB;
         }
 
+        if options.multi_statements {
+            capabilities |= Capabilities::MULTI_STATEMENTS;
+        }
+
         Ok(Self {
             waiting: VecDeque::new(),
             capabilities,
-            server_version: (0, 0, 0),
+            server_version: None,
+            is_mariadb: false,
             sequence_id: 0,
             collation,
             charset,
+            compression: None,
+            max_message_size: options.max_message_size,
+            partial: PartialPayload::default(),
+            skipping: 0,
+            compressed: 0,
+            flushing: false,
             stream: BufStream::new(MaybeTlsStream::Raw(socket)),
         })
     }
 
+    /// Compress the packets from now on, if the server accepted the algorithm.
+    pub(super) fn start_compression(&mut self, algorithm: MySqlCompression) {
+        if self.capabilities.intersects(algorithm.capability()) {
+            self.compression = Some(Compression::new(algorithm));
+        }
+    }
+
+    // the responses left by the queries that were dropped before completing are discarded here,
+    // along with their errors: these belong to no one anymore
     pub(crate) async fn wait_until_ready(&mut self) -> Result<(), Error> {
         if !self.stream.wbuf.is_empty() {
-            self.stream.flush().await?;
+            self.flush().await?;
         }
 
-        while !self.waiting.is_empty() {
-            while self.waiting.front() == Some(&Waiting::Row) {
-                let packet = self.recv_packet().await?;
+        while let Some(&waiting) = self.waiting.front() {
+            let mut packet = match self.recv_packet().await {
+                Ok(packet) => packet,
+                Err(Error::Database(_)) | Err(Error::MessageTooLarge { .. }) => continue,
+                Err(error) => return Err(error),
+            };
+
+            match waiting {
+                Waiting::Row => {
+                    if !packet.is_empty() && packet[0] == 0xfe && packet.len() < 9 {
+                        let eof = packet.eof(self.capabilities)?;
+
+                        if eof.status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
+                            *self.waiting.front_mut().unwrap() = Waiting::Result;
+                        } else {
+                            self.waiting.pop_front();
+                        };
+                    }
+                }
 
-                if !packet.is_empty() && packet[0] == 0xfe && packet.len() < 9 {
-                    let eof = packet.eof(self.capabilities)?;
+                Waiting::Result => {
+                    if !packet.is_empty() && (packet[0] == 0x00 || packet[0] == 0xff) {
+                        let ok = packet.ok()?;
 
-                    if eof.status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
-                        *self.waiting.front_mut().unwrap() = Waiting::Result;
+                        if !ok.status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
+                            self.waiting.pop_front();
+                        }
                     } else {
-                        self.waiting.pop_front();
-                    };
-                }
-            }
+                        // the result set metadata is skipped before its rows
+                        let num_columns = packet.get_uint_lenenc() as usize; // column count
 
-            while self.waiting.front() == Some(&Waiting::Result) {
-                let packet = self.recv_packet().await?;
+                        *self.waiting.front_mut().unwrap() = Waiting::Row;
+                        self.expect_definitions(self.definitions(num_columns));
+                    }
+                }
 
-                if !packet.is_empty() && (packet[0] == 0x00 || packet[0] == 0xff) {
-                    let ok = packet.ok()?;
+                Waiting::Prepare => {
+                    let ok: PrepareOk = packet.decode_with(self.capabilities)?;
 
-                    if !ok.status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
-                        self.waiting.pop_front();
-                    }
-                } else {
-                    *self.waiting.front_mut().unwrap() = Waiting::Row;
-                    self.skip_result_metadata(packet).await?;
+                    self.waiting.pop_front();
+                    self.expect_definitions(
+                        self.definitions(ok.params.into()) + self.definitions(ok.columns.into()),
+                    );
                 }
+
+                // counted down as they're received
+                Waiting::Definitions(_) => {}
             }
         }
 
         Ok(())
     }
 
+    // the number of packets following a result set header or a prepared statement, for
+    // `count` column or parameter definitions
+    pub(crate) fn definitions(&self, count: usize) -> usize {
+        if count > 0 && !self.capabilities.contains(Capabilities::DEPRECATE_EOF) {
+            count + 1
+        } else {
+            count
+        }
+    }
+
+    // the next `count` packets are definitions, to be received before what's waited for next
+    pub(crate) fn expect_definitions(&mut self, count: usize) {
+        if count > 0 {
+            self.waiting.push_front(Waiting::Definitions(count));
+        }
+    }
+
+    // count down the definitions waited for, once one of them is received
+    fn received_packet(&mut self) {
+        if let Some(Waiting::Definitions(count)) = self.waiting.front_mut() {
+            *count -= 1;
+
+            if *count == 0 {
+                self.waiting.pop_front();
+            }
+        }
+    }
+
     pub(crate) async fn send_packet<'en, T>(&mut self, payload: T) -> Result<(), Error>
     where
         T: Encode<'en, Capabilities>,
@@ -126,27 +244,187 @@ impl MySqlStream {
     where
         T: Encode<'en, Capabilities>,
     {
+        self.compressed_len();
+
         self.stream
             .write_with(Packet(payload), (self.capabilities, &mut self.sequence_id));
     }
 
-    // receive the next packet from the database server
-    // may block (async) on more data from the server
-    pub(crate) async fn recv_packet(&mut self) -> Result<Packet<Bytes>, Error> {
-        // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_basic_packets.html
-        // https://mariadb.com/kb/en/library/0-packet/#standard-packet
+    pub(crate) async fn flush(&mut self) -> Result<(), Error> {
+        let compressed = self.compressed_len();
+
+        if let Some(compression) = &self.compression {
+            // the packets written since the last flush are compressed, after the compressed
+            // packets a cancelled flush left
+            if self.stream.wbuf.len() > compressed {
+                let mut packets = buffer_pool::take_write_buf();
+                packets.extend_from_slice(&self.stream.wbuf[compressed..]);
+                self.stream.wbuf.truncate(compressed);
+
+                let compressed = compression.compress(&packets, &mut self.stream.wbuf);
+                buffer_pool::recycle_write_buf(packets);
+                compressed?;
+            }
+
+            self.compressed = self.stream.wbuf.len();
+            self.flushing = true;
+        }
+
+        let flushed = self.stream.flush().await;
+
+        // the buffer is empty, unless the flush was cancelled
+        self.compressed = 0;
+        self.flushing = false;
+
+        flushed
+    }
+
+    // the length of the compressed packets at the start of the write buffer: the rest of them,
+    // if a flush was cancelled
+    fn compressed_len(&mut self) -> usize {
+        if self.flushing {
+            self.compressed = self.stream.wbuf.len();
+            self.flushing = false;
+        }
+
+        self.compressed
+    }
+
+    // read from the compressed packets, if the connection is compressed
+    async fn read_bytes(&mut self, cnt: usize) -> Result<Bytes, Error> {
+        if self.compression.is_none() {
+            return self.stream.read(cnt).await;
+        }
+
+        while self.skipping > 0 {
+            self.decompress(1).await?;
 
-        let mut header: Bytes = self.stream.read(4).await?;
+            let compression = self.compression.as_mut().unwrap();
+            let skipped = cmp::min(self.skipping, compression.rbuf.len());
+
+            compression.rbuf.advance(skipped);
+            self.skipping -= skipped;
+        }
+
+        self.decompress(cnt).await?;
+
+        let compression = self.compression.as_mut().unwrap();
+
+        Ok(compression.rbuf.split_to(cnt).freeze())
+    }
+
+    // decompress packets until at least `cnt` bytes are decompressed
+    async fn decompress(&mut self, cnt: usize) -> Result<(), Error> {
+        let compression = self.compression.as_mut().unwrap();
+
+        while compression.rbuf.len() < cnt {
+            // the header is only consumed along with the payload, so that a read which is
+            // cancelled in between is resumed by the next one
+            let compressed_len = (&self.stream.peek(7).await?[..]).get_uint_le(3) as usize;
+
+            let mut header: Bytes = self.stream.read(7 + compressed_len).await?;
+            let payload = header.split_off(7);
+
+            compression.decompress(header, payload)?;
+        }
+
+        Ok(())
+    }
+
+    // discard the next bytes of the packets, from the compressed packets if the connection
+    // is compressed, before the next read
+    fn skip_bytes(&mut self, cnt: usize) {
+        if self.compression.is_none() {
+            return self.stream.skip(cnt);
+        }
+
+        self.skipping += cnt;
+    }
+
+    // read the header of the next packet, returning the size of its payload
+    async fn recv_packet_header(&mut self) -> Result<usize, Error> {
+        let mut header: Bytes = self.read_bytes(4).await?;
 
         let packet_size = header.get_uint_le(3) as usize;
         let sequence_id = header.get_u8();
 
         self.sequence_id = sequence_id.wrapping_add(1);
 
-        let payload: Bytes = self.stream.read(packet_size).await?;
+        Ok(packet_size)
+    }
+
+    // receive the payload of the next packets, joined if it's split across packets
+    async fn recv_payload(&mut self) -> Result<Bytes, Error> {
+        // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_basic_packets.html
+        // https://mariadb.com/kb/en/library/0-packet/#standard-packet
+
+        loop {
+            let packet_size = match self.partial.packet_size {
+                Some(packet_size) => packet_size,
+                None => {
+                    let packet_size = self.recv_packet_header().await?;
+
+                    self.partial.packet_size = Some(packet_size);
+                    self.partial.size += packet_size;
+
+                    packet_size
+                }
+            };
+
+            if let Some(max) = self.max_message_size {
+                if self.partial.size > max {
+                    // the following packets are still valid once these are skipped
+                    self.partial.oversized = true;
+                    self.partial.joined.clear();
+                }
+            }
 
-        // TODO: packet compression
-        // TODO: packet joining
+            let payload = if self.partial.oversized {
+                self.skip_bytes(packet_size);
+                None
+            } else {
+                Some(self.read_bytes(packet_size).await?)
+            };
+
+            self.partial.packet_size = None;
+
+            // a payload of 16 MiB or more is split into several packets, joined back here
+            // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_basic_packets.html#sect_protocol_basic_packets_sending_mt_16mb
+            if packet_size == MAX_PACKET_PAYLOAD {
+                if let Some(payload) = payload {
+                    self.partial.joined.extend_from_slice(&payload);
+                }
+
+                continue;
+            }
+
+            let partial = std::mem::take(&mut self.partial);
+
+            return match payload {
+                None => Err(Error::MessageTooLarge {
+                    size: partial.size,
+                    max: self.max_message_size.unwrap_or_default(),
+                }),
+
+                Some(payload) if partial.joined.is_empty() => Ok(payload),
+
+                Some(payload) => {
+                    let mut joined = partial.joined;
+                    joined.extend_from_slice(&payload);
+
+                    Ok(joined.freeze())
+                }
+            };
+        }
+    }
+
+    // receive the next packet from the database server
+    // may block (async) on more data from the server
+    pub(crate) async fn recv_packet(&mut self) -> Result<Packet<Bytes>, Error> {
+        let payload = self.recv_payload().await;
+        self.received_packet();
+
+        let payload = payload?;
 
         if payload
             .get(0)
@@ -183,18 +461,6 @@ impl MySqlStream {
             self.recv().await.map(Some)
         }
     }
-
-    async fn skip_result_metadata(&mut self, mut packet: Packet<Bytes>) -> Result<(), Error> {
-        let num_columns: u64 = packet.get_uint_lenenc(); // column count
-
-        for _ in 0..num_columns {
-            let _ = self.recv_packet().await?;
-        }
-
-        self.maybe_recv_eof().await?;
-
-        Ok(())
-    }
 }
 
 impl Deref for MySqlStream {
@@ -210,3 +476,15 @@ impl DerefMut for MySqlStream {
         &mut self.stream
     }
 }
+
+// like the `mysql` client, a bare name is a pipe on the local machine, e.g. `MySQL`
+#[cfg(windows)]
+fn named_pipe_path(path: &std::path::Path) -> std::path::PathBuf {
+    let path = path.to_string_lossy();
+
+    if path.starts_with(r"\\") {
+        path.into_owned().into()
+    } else {
+        format!(r"\\.\pipe\{}", path).into()
+    }
+}
@@ -3,6 +3,7 @@ use crate::mysql::connection::MySqlStream;
 use crate::mysql::protocol::connect::SslRequest;
 use crate::mysql::protocol::Capabilities;
 use crate::mysql::{MySqlConnectOptions, MySqlSslMode};
+use crate::net::TlsConfig;
 
 pub(super) async fn maybe_upgrade(
     stream: &mut MySqlStream,
@@ -48,12 +49,15 @@ async fn upgrade(stream: &mut MySqlStream, options: &MySqlConnectOptions) -> Res
     let accept_invalid_host_names = !matches!(options.ssl_mode, MySqlSslMode::VerifyIdentity);
 
     stream
-        .upgrade(
-            &options.host,
+        .upgrade(TlsConfig {
             accept_invalid_certs,
-            accept_invalid_host_names,
-            options.ssl_ca.as_ref(),
-        )
+            accept_invalid_hostnames: accept_invalid_host_names,
+            hostname: &options.host,
+            root_cert_path: options.ssl_ca.as_ref(),
+            client_cert_path: options.ssl_client_cert.as_ref(),
+            client_key_path: options.ssl_client_key.as_ref(),
+            custom: options.custom_tls.as_ref(),
+        })
         .await?;
 
     Ok(true)
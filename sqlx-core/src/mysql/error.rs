@@ -1,7 +1,7 @@
 use std::error::Error;
 use std::fmt::{self, Debug, Display, Formatter};
 
-use crate::error::DatabaseError;
+use crate::error::{DatabaseError, ErrorKind};
 use crate::mysql::protocol::response::ErrPacket;
 use smallvec::alloc::borrow::Cow;
 
@@ -76,4 +76,142 @@ impl DatabaseError for MySqlDatabaseError {
     fn into_error(self: Box<Self>) -> Box<dyn Error + Send + Sync + 'static> {
         self
     }
+
+    fn kind(&self) -> ErrorKind {
+        // https://dev.mysql.com/doc/mysql-errors/8.0/en/server-error-reference.html
+        // https://mariadb.com/kb/en/mariadb-error-codes/
+        match self.number() {
+            // ER_DUP_KEY, ER_DUP_ENTRY, ER_DUP_UNIQUE, ER_DUP_ENTRY_WITH_KEY_NAME
+            1022 | 1062 | 1169 | 1586 => ErrorKind::UniqueViolation,
+            // ER_NO_REFERENCED_ROW, ER_ROW_IS_REFERENCED, ER_ROW_IS_REFERENCED_2,
+            // ER_NO_REFERENCED_ROW_2
+            1216 | 1217 | 1451 | 1452 => ErrorKind::ForeignKeyViolation,
+            // ER_CHECK_CONSTRAINT_VIOLATED, and ER_CONSTRAINT_FAILED of MariaDB
+            3819 | 4025 => ErrorKind::CheckViolation,
+            // ER_BAD_NULL_ERROR, ER_NO_DEFAULT_FOR_FIELD
+            1048 | 1364 => ErrorKind::NotNullViolation,
+            // ER_LOCK_DEADLOCK
+            1213 => ErrorKind::Deadlock,
+            _ => ErrorKind::Other,
+        }
+    }
+
+    // MySQL doesn't report the objects of an error apart from its message, so they're parsed out
+    // of the messages of the constraint violations, e.g.:
+    //
+    //   Duplicate entry 'a@b.c' for key 'users.email'
+    //   Cannot add or update a child row: a foreign key constraint fails (`db`.`posts`,
+    //     CONSTRAINT `posts_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`))
+    //   Check constraint 'users_chk_1' is violated.
+    //   CONSTRAINT `users.age` failed for `db`.`users`
+    //   Column 'name' cannot be null
+    //   Field 'name' doesn't have a default value
+
+    fn constraint(&self) -> Option<&str> {
+        let message = self.message();
+
+        match self.kind() {
+            ErrorKind::UniqueViolation => {
+                let key = between(message.rsplit_once("for key '")?.1, "", "'")?;
+
+                // the key is qualified by its table since MySQL 8.0.19
+                Some(key.rsplit_once('.').map_or(key, |(_, key)| key))
+            }
+            ErrorKind::ForeignKeyViolation => between(message, "CONSTRAINT `", "`"),
+            ErrorKind::CheckViolation if self.number() == 4025 => {
+                between(message, "CONSTRAINT `", "`")
+            }
+            ErrorKind::CheckViolation => between(message, "constraint '", "'"),
+            _ => None,
+        }
+    }
+
+    fn table(&self) -> Option<&str> {
+        let message = self.message();
+
+        let table = match self.kind() {
+            ErrorKind::UniqueViolation => {
+                let key = between(message.rsplit_once("for key '")?.1, "", "'")?;
+
+                key.rsplit_once('.')?.0
+            }
+            ErrorKind::ForeignKeyViolation => between(message, "fails (", ", CONSTRAINT")?,
+            ErrorKind::CheckViolation if self.number() == 4025 => {
+                message.rsplit_once(" failed for ")?.1
+            }
+            _ => return None,
+        };
+
+        // the last part of a `db`.`table` name
+        Some(table.rsplit('.').next().unwrap_or(table).trim_matches('`'))
+    }
+
+    fn column(&self) -> Option<&str> {
+        let message = self.message();
+
+        let column = match self.number() {
+            1048 => between(message, "Column '", "'")?,
+            1364 => between(message, "Field '", "'")?,
+            1216 | 1217 | 1451 | 1452 => between(message, "FOREIGN KEY (", ")")?,
+            _ => return None,
+        };
+
+        if column.contains(',') {
+            return None;
+        }
+
+        Some(column.trim_matches('`'))
+    }
+}
+
+// the text between the first `start` of `s` and the following `end`
+fn between<'a>(s: &'a str, start: &str, end: &str) -> Option<&'a str> {
+    let s = &s[s.find(start)? + start.len()..];
+
+    Some(&s[..s.find(end)?])
+}
+
+#[test]
+fn test_constraint_violations() {
+    fn error(error_code: u16, error_message: &str) -> MySqlDatabaseError {
+        MySqlDatabaseError(ErrPacket {
+            error_code,
+            sql_state: None,
+            error_message: error_message.to_owned(),
+        })
+    }
+
+    let e = error(1062, "Duplicate entry 'for key 'x'' for key 'users.email'");
+    assert_eq!(e.kind(), ErrorKind::UniqueViolation);
+    assert_eq!(DatabaseError::constraint(&e), Some("email"));
+    assert_eq!(DatabaseError::table(&e), Some("users"));
+
+    let e = error(1062, "Duplicate entry '1' for key 'PRIMARY'");
+    assert_eq!(DatabaseError::constraint(&e), Some("PRIMARY"));
+    assert_eq!(DatabaseError::table(&e), None);
+
+    let e = error(
+        1452,
+        "Cannot add or update a child row: a foreign key constraint fails (`db`.`posts`, \
+         CONSTRAINT `posts_ibfk_1` FOREIGN KEY (`user_id`) REFERENCES `users` (`id`))",
+    );
+    assert_eq!(e.kind(), ErrorKind::ForeignKeyViolation);
+    assert_eq!(DatabaseError::constraint(&e), Some("posts_ibfk_1"));
+    assert_eq!(DatabaseError::table(&e), Some("posts"));
+    assert_eq!(DatabaseError::column(&e), Some("user_id"));
+
+    let e = error(3819, "Check constraint 'users_chk_1' is violated.");
+    assert_eq!(e.kind(), ErrorKind::CheckViolation);
+    assert_eq!(DatabaseError::constraint(&e), Some("users_chk_1"));
+
+    let e = error(4025, "CONSTRAINT `age` failed for `db`.`users`");
+    assert_eq!(e.kind(), ErrorKind::CheckViolation);
+    assert_eq!(DatabaseError::constraint(&e), Some("age"));
+    assert_eq!(DatabaseError::table(&e), Some("users"));
+
+    let e = error(1048, "Column 'name' cannot be null");
+    assert_eq!(e.kind(), ErrorKind::NotNullViolation);
+    assert_eq!(DatabaseError::column(&e), Some("name"));
+
+    assert!(error(1213, "Deadlock found when trying to get lock").is_transaction_conflict());
 }
@@ -2,13 +2,16 @@ use crate::connection::ConnectOptions;
 use crate::error::Error;
 use crate::executor::Executor;
 use crate::migrate::MigrateError;
-use crate::migrate::{AppliedMigration, Migration};
+use crate::migrate::{AppliedMigration, CreateDatabaseOptions, Migration, MigrationLock};
 use crate::migrate::{Migrate, MigrateDatabase};
 use crate::mysql::{MySql, MySqlConnectOptions, MySqlConnection};
 use crate::query::query;
 use crate::query_as::query_as;
+use crate::query_batch::split_statements;
 use crate::query_scalar::query_scalar;
+use crate::row::Row;
 use futures_core::future::BoxFuture;
+use std::cmp;
 use std::str::FromStr;
 use std::time::Duration;
 use std::time::Instant;
@@ -32,12 +35,42 @@ fn parse_for_maintenance(url: &str) -> Result<(MySqlConnectOptions, String), Err
 
 impl MigrateDatabase for MySql {
     fn create_database(url: &str) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move { Self::create_database_with(url, &Default::default()).await })
+    }
+
+    fn create_database_with<'a>(
+        url: &'a str,
+        create_options: &'a CreateDatabaseOptions,
+    ) -> BoxFuture<'a, Result<(), Error>> {
         Box::pin(async move {
+            create_options.check_supported(&["charset", "collation"])?;
+
+            // names of character sets and collations can't be quoted as strings
+            let name = |kind: &str, name: &str| {
+                if name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                    Ok(name.to_owned())
+                } else {
+                    Err(Error::Configuration(
+                        format!("invalid {} name: {:?}", kind, name).into(),
+                    ))
+                }
+            };
+
+            let mut sql = String::new();
+
+            if let Some(charset) = &create_options.charset {
+                sql += &format!(" CHARACTER SET {}", name("character set", charset)?);
+            }
+
+            if let Some(collation) = &create_options.collation {
+                sql += &format!(" COLLATE {}", name("collation", collation)?);
+            }
+
             let (options, database) = parse_for_maintenance(url)?;
             let mut conn = options.connect().await?;
 
             let _ = conn
-                .execute(&*format!("CREATE DATABASE `{}`", database))
+                .execute(&*format!("CREATE DATABASE `{}`{}", database, sql))
                 .await?;
 
             Ok(())
@@ -75,12 +108,15 @@ impl MigrateDatabase for MySql {
 }
 
 impl Migrate for MySqlConnection {
-    fn ensure_migrations_table(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn ensure_migrations_table<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<(), MigrateError>> {
         Box::pin(async move {
             // language=MySQL
-            self.execute(
+            self.execute(&*format!(
                 r#"
-CREATE TABLE IF NOT EXISTS _sqlx_migrations (
+CREATE TABLE IF NOT EXISTS {} (
     version BIGINT PRIMARY KEY,
     description TEXT NOT NULL,
     installed_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
@@ -89,7 +125,8 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
     execution_time BIGINT NOT NULL
 );
                 "#,
-            )
+                table_name
+            ))
             .await?;
 
             Ok(())
@@ -109,12 +146,16 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         })
     }
 
-    fn dirty_version(&mut self) -> BoxFuture<'_, Result<Option<i64>, MigrateError>> {
+    fn dirty_version<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Option<i64>, MigrateError>> {
         Box::pin(async move {
             // language=SQL
-            let row: Option<(i64,)> = query_as(
-                "SELECT version FROM _sqlx_migrations WHERE success = false ORDER BY version LIMIT 1",
-            )
+            let row: Option<(i64,)> = query_as(&format!(
+                "SELECT version FROM {} WHERE success = false ORDER BY version LIMIT 1",
+                table_name
+            ))
             .fetch_optional(self)
             .await?;
 
@@ -122,21 +163,25 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         })
     }
 
-    fn list_applied_migrations(
-        &mut self,
-    ) -> BoxFuture<'_, Result<Vec<AppliedMigration>, MigrateError>> {
+    fn list_applied_migrations<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Vec<AppliedMigration>, MigrateError>> {
         Box::pin(async move {
             // language=SQL
-            let rows: Vec<(i64, Vec<u8>)> =
-                query_as("SELECT version, checksum FROM _sqlx_migrations ORDER BY version")
-                    .fetch_all(self)
-                    .await?;
+            let rows: Vec<(i64, Vec<u8>, i64)> = query_as(&format!(
+                "SELECT version, checksum, execution_time FROM {} ORDER BY version",
+                table_name
+            ))
+            .fetch_all(self)
+            .await?;
 
             let migrations = rows
                 .into_iter()
-                .map(|(version, checksum)| AppliedMigration {
+                .map(|(version, checksum, execution_time)| AppliedMigration {
                     version,
                     checksum: checksum.into(),
+                    execution_time: Duration::from_nanos(execution_time as u64),
                 })
                 .collect();
 
@@ -144,31 +189,38 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         })
     }
 
-    fn lock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn lock(&mut self, lock: MigrationLock) -> BoxFuture<'_, Result<(), MigrateError>> {
         Box::pin(async move {
-            let database_name = current_database(self).await?;
-            let lock_id = generate_lock_id(&database_name);
+            let lock_id = lock_name(self, lock).await?;
 
             // create an application lock over the database
-            // this function will not return until the lock is acquired
+            // without a timeout, this function will not return until the lock is acquired
 
-            // https://www.postgresql.org/docs/current/explicit-locking.html#ADVISORY-LOCKS
-            // https://www.postgresql.org/docs/current/functions-admin.html#FUNCTIONS-ADVISORY-LOCKS-TABLE
+            // https://dev.mysql.com/doc/refman/8.0/en/locking-functions.html#function_get-lock
+
+            // the timeout is in whole seconds, and a negative timeout means forever
+            let timeout = lock
+                .timeout
+                .map_or(-1, |timeout| ((timeout.as_millis() + 999) / 1000) as i64);
 
             // language=MySQL
-            let _ = query("SELECT GET_LOCK(?, -1)")
+            let acquired: Option<i64> = query_scalar("SELECT GET_LOCK(?, ?)")
                 .bind(lock_id)
-                .execute(self)
+                .bind(timeout)
+                .fetch_one(self)
                 .await?;
 
+            if acquired != Some(1) {
+                return Err(MigrateError::LockTimeout);
+            }
+
             Ok(())
         })
     }
 
-    fn unlock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn unlock(&mut self, lock: MigrationLock) -> BoxFuture<'_, Result<(), MigrateError>> {
         Box::pin(async move {
-            let database_name = current_database(self).await?;
-            let lock_id = generate_lock_id(&database_name);
+            let lock_id = lock_name(self, lock).await?;
 
             // language=MySQL
             let _ = query("SELECT RELEASE_LOCK(?)")
@@ -206,22 +258,46 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
 
     fn apply<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         Box::pin(async move {
             let start = Instant::now();
 
-            let res = self.execute(&*migration.sql).await;
+            let res = execute_migration(self, table_name, migration).await;
 
             let elapsed = start.elapsed();
 
+            // a resumable migration records its progress in its own row, left dirty if it failed
+            if migration.is_resumable() {
+                res?;
+
+                // language=MySQL
+                let _ = query(&format!(
+                    r#"
+    UPDATE {} SET description = ?, success = TRUE, checksum = ?, execution_time = ?
+    WHERE version = ?
+                    "#,
+                    table_name
+                ))
+                .bind(&*migration.description)
+                .bind(&*migration.checksum)
+                .bind(elapsed.as_nanos() as i64)
+                .bind(migration.version)
+                .execute(self)
+                .await?;
+
+                return Ok(elapsed);
+            }
+
             // language=MySQL
-            let _ = query(
+            let _ = query(&format!(
                 r#"
-    INSERT INTO _sqlx_migrations ( version, description, success, checksum, execution_time )
+    INSERT INTO {} ( version, description, success, checksum, execution_time )
     VALUES ( ?, ?, ?, ?, ? )
                 "#,
-            )
+                table_name
+            ))
             .bind(migration.version)
             .bind(&*migration.description)
             .bind(res.is_ok())
@@ -236,19 +312,108 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         })
     }
 
+    fn update_checksum<'e: 'm, 'm>(
+        &'e mut self,
+        table_name: &'m str,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        Box::pin(async move {
+            // language=MySQL
+            let _ = query(&format!(
+                r#"UPDATE {} SET checksum = ? WHERE version = ?"#,
+                table_name
+            ))
+            .bind(&*migration.checksum)
+            .bind(migration.version)
+            .execute(self)
+            .await?;
+
+            Ok(())
+        })
+    }
+
+    fn record_applied<'e: 'm, 'm>(
+        &'e mut self,
+        table_name: &'m str,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        Box::pin(async move {
+            // language=MySQL
+            let _ = query(&format!(
+                r#"
+    INSERT INTO {} ( version, description, success, checksum, execution_time )
+    VALUES ( ?, ?, TRUE, ?, 0 )
+                "#,
+                table_name
+            ))
+            .bind(migration.version)
+            .bind(&*migration.description)
+            .bind(&*migration.checksum)
+            .execute(self)
+            .await?;
+
+            Ok(())
+        })
+    }
+
+    fn dump_schema<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<String, MigrateError>> {
+        Box::pin(async move {
+            // the migrations table may be qualified by a database
+            let table_name = table_name.rsplit('.').next().unwrap_or(table_name);
+
+            // language=MySQL
+            let tables: Vec<(String, String)> = query_as(
+                r#"
+SELECT CAST(table_name AS CHAR), CAST(table_type AS CHAR)
+FROM information_schema.tables
+WHERE table_schema = DATABASE() AND table_name <> ?
+ORDER BY table_type = 'VIEW', table_name
+                "#,
+            )
+            .bind(table_name)
+            .fetch_all(&mut *self)
+            .await?;
+
+            let mut statements = Vec::new();
+
+            for (name, table_type) in tables {
+                let kind = if table_type == "VIEW" {
+                    "VIEW"
+                } else {
+                    "TABLE"
+                };
+                let name = name.replace('`', "``");
+
+                let row = query(&format!("SHOW CREATE {} `{}`", kind, name))
+                    .fetch_one(&mut *self)
+                    .await?;
+                let statement: String = row.try_get(1)?;
+
+                // the next value of the counter is not part of the schema
+                statements.push(format!("{};\n", strip_auto_increment(&statement)));
+            }
+
+            Ok(statements.join("\n"))
+        })
+    }
+
     fn revert<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         Box::pin(async move {
             let start = Instant::now();
 
-            self.execute(&*migration.sql).await?;
+            execute_migration(self, table_name, migration).await?;
 
             let elapsed = start.elapsed();
 
             // language=SQL
-            let _ = query(r#"DELETE FROM _sqlx_migrations WHERE version = ?"#)
+            let _ = query(&format!(r#"DELETE FROM {} WHERE version = ?"#, table_name))
                 .bind(migration.version)
                 .execute(self)
                 .await?;
@@ -258,17 +423,162 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
     }
 }
 
+// run the SQL of the migration as configured by its headers; migrations never run in a
+// transaction, as MySQL does not have transactional DDL
+async fn execute_migration(
+    conn: &mut MySqlConnection,
+    table_name: &str,
+    migration: &Migration,
+) -> Result<(), MigrateError> {
+    let headers = migration.headers()?;
+    headers.check_database(migration.version, "mysql")?;
+
+    if let Some(timeout) = headers.lock_timeout {
+        // `lock_wait_timeout` is in whole seconds
+        let seconds = (timeout.as_millis() + 999) / 1000;
+
+        conn.execute(&*format!(
+            "SET SESSION lock_wait_timeout = {}",
+            cmp::max(seconds, 1)
+        ))
+        .await?;
+    }
+
+    let res = match &migration.code {
+        Some(code) => code.run(migration.version, &mut *conn).await,
+        None if migration.is_resumable() => {
+            execute_statements(&mut *conn, table_name, migration).await
+        }
+        None => conn
+            .execute(&*migration.sql)
+            .await
+            .map(|_| ())
+            .map_err(Into::into),
+    };
+
+    if headers.lock_timeout.is_some() {
+        conn.execute("SET SESSION lock_wait_timeout = DEFAULT")
+            .await?;
+    }
+
+    res?;
+
+    Ok(())
+}
+
+// run the statements of a resumable migration one at a time, counting those applied in its row
+// of the migrations table, which stays dirty until the migration is recorded as applied
+async fn execute_statements(
+    conn: &mut MySqlConnection,
+    table_name: &str,
+    migration: &Migration,
+) -> Result<(), MigrateError> {
+    // the column isn't created with the table, which may predate it
+    // language=MySQL
+    let has_column = conn
+        .execute(&*format!(
+            "SELECT statements_applied FROM {} LIMIT 0",
+            table_name
+        ))
+        .await
+        .is_ok();
+
+    if !has_column {
+        // language=MySQL
+        conn.execute(&*format!(
+            "ALTER TABLE {} ADD COLUMN statements_applied BIGINT",
+            table_name
+        ))
+        .await?;
+    }
+
+    // language=MySQL
+    let applied: Option<Option<i64>> = query_scalar(&format!(
+        "SELECT statements_applied FROM {} WHERE version = ?",
+        table_name
+    ))
+    .bind(migration.version)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let applied = match applied {
+        Some(applied) => applied.unwrap_or(0),
+        None => {
+            // language=MySQL
+            let _ = query(&format!(
+                r#"
+    INSERT INTO {} ( version, description, success, checksum, execution_time, statements_applied )
+    VALUES ( ?, ?, FALSE, ?, 0, 0 )
+                "#,
+                table_name
+            ))
+            .bind(migration.version)
+            .bind(&*migration.description)
+            .bind(&*migration.checksum)
+            .execute(&mut *conn)
+            .await?;
+
+            0
+        }
+    };
+
+    let statements = split_statements(&migration.sql);
+
+    for (i, statement) in statements.into_iter().enumerate().skip(applied as usize) {
+        let _ = conn.execute(statement).await?;
+
+        // language=MySQL
+        let _ = query(&format!(
+            "UPDATE {} SET statements_applied = ? WHERE version = ?",
+            table_name
+        ))
+        .bind(i as i64 + 1)
+        .bind(migration.version)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
 async fn current_database(conn: &mut MySqlConnection) -> Result<String, MigrateError> {
     // language=MySQL
     Ok(query_scalar("SELECT DATABASE()").fetch_one(conn).await?)
 }
 
 // inspired from rails: https://github.com/rails/rails/blob/6e49cc77ab3d16c06e12f93158eaf3e507d4120e/activerecord/lib/active_record/migration.rb#L1308
-fn generate_lock_id(database_name: &str) -> String {
+fn generate_lock_id(database_name: &str) -> i64 {
     const CRC_IEEE: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
     // 0x3d32ad9e chosen by fair dice roll
-    format!(
-        "{:x}",
-        0x3d32ad9e * (CRC_IEEE.checksum(database_name.as_bytes()) as i64)
-    )
+    0x3d32ad9e * (CRC_IEEE.checksum(database_name.as_bytes()) as i64)
+}
+
+// MySQL locks are named rather than numbered
+async fn lock_name(
+    conn: &mut MySqlConnection,
+    lock: MigrationLock,
+) -> Result<String, MigrateError> {
+    let lock_id = match lock.key {
+        Some(key) => key,
+        None => generate_lock_id(&current_database(conn).await?),
+    };
+
+    Ok(format!("{:x}", lock_id))
+}
+
+// remove the `AUTO_INCREMENT=<n>` table option from `SHOW CREATE TABLE`
+fn strip_auto_increment(statement: &str) -> String {
+    const OPTION: &str = " AUTO_INCREMENT=";
+
+    match statement.find(OPTION) {
+        Some(start) => {
+            let value = &statement[start + OPTION.len()..];
+            let end = value
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(value.len());
+
+            format!("{}{}", &statement[..start], &value[end..])
+        }
+        None => statement.to_owned(),
+    }
 }
@@ -3,6 +3,7 @@
 use crate::executor::Executor;
 
 mod arguments;
+mod auth_plugin;
 mod collation;
 mod column;
 mod connection;
@@ -23,12 +24,13 @@ mod value;
 mod migrate;
 
 pub use arguments::MySqlArguments;
+pub use auth_plugin::MySqlAuthPlugin;
 pub use column::MySqlColumn;
-pub use connection::MySqlConnection;
+pub use connection::{MySqlCancelToken, MySqlConnection};
 pub use database::MySql;
 pub use error::MySqlDatabaseError;
-pub use options::{MySqlConnectOptions, MySqlSslMode};
-pub use query_result::MySqlQueryResult;
+pub use options::{MySqlCompression, MySqlConnectOptions, MySqlSslMode};
+pub use query_result::{MySqlBatchResult, MySqlQueryResult};
 pub use row::MySqlRow;
 pub use statement::MySqlStatement;
 pub use transaction::MySqlTransactionManager;
@@ -49,6 +51,7 @@ impl<'c, T: Executor<'c, Database = MySql>> MySqlExecutor<'c> for T {}
 impl_into_arguments_for_arguments!(MySqlArguments);
 impl_executor_for_pool_connection!(MySql, MySqlConnection, MySqlRow);
 impl_executor_for_transaction!(MySql, MySqlRow);
+impl_executor_for_reconnecting_connection!(MySql, MySqlRow);
 impl_acquire!(MySql, MySqlConnection);
 impl_column_index_for_row!(MySqlRow);
 impl_column_index_for_statement!(MySqlStatement);
@@ -0,0 +1,70 @@
+use crate::error::Error;
+use crate::mysql::protocol::Capabilities;
+use std::str::FromStr;
+
+/// Options for compressing the packets exchanged with the MySQL server, which reduces the
+/// transfer time of large result sets over slow links, at the cost of CPU time on both ends.
+///
+/// It is used by the [`compression`](super::MySqlConnectOptions::compression) method.
+/// If the server doesn't support the algorithm, the connection is not compressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MySqlCompression {
+    /// Don't compress the connection.
+    ///
+    /// This is the default if `compression` is not specified.
+    Disabled,
+
+    /// Compress with zlib, supported by MySQL and MariaDB.
+    Zlib,
+
+    /// Compress with zstd at the given level, from 1 to 22. Supported from MySQL 8.0.18.
+    ///
+    /// The default level of the server is `3`.
+    #[cfg(feature = "mysql-zstd")]
+    Zstd { level: u8 },
+}
+
+impl MySqlCompression {
+    // the capability requesting the algorithm during the handshake
+    pub(crate) fn capability(self) -> Capabilities {
+        match self {
+            MySqlCompression::Disabled => Capabilities::empty(),
+            MySqlCompression::Zlib => Capabilities::COMPRESS,
+            #[cfg(feature = "mysql-zstd")]
+            MySqlCompression::Zstd { .. } => Capabilities::ZSTD_COMPRESSION_ALGORITHM,
+        }
+    }
+
+    pub(crate) fn zstd_level(self) -> Option<u8> {
+        match self {
+            #[cfg(feature = "mysql-zstd")]
+            MySqlCompression::Zstd { level } => Some(level),
+            _ => None,
+        }
+    }
+}
+
+impl Default for MySqlCompression {
+    fn default() -> Self {
+        MySqlCompression::Disabled
+    }
+}
+
+impl FromStr for MySqlCompression {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match &*s.to_ascii_lowercase() {
+            "disabled" | "uncompressed" => MySqlCompression::Disabled,
+            "zlib" => MySqlCompression::Zlib,
+            #[cfg(feature = "mysql-zstd")]
+            "zstd" => MySqlCompression::Zstd { level: 3 },
+
+            _ => {
+                return Err(Error::Configuration(
+                    format!("unknown value {:?} for `compression`", s).into(),
+                ));
+            }
+        })
+    }
+}
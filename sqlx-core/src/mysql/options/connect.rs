@@ -2,8 +2,10 @@ use crate::connection::ConnectOptions;
 use crate::error::Error;
 use crate::executor::Executor;
 use crate::mysql::{MySqlConnectOptions, MySqlConnection};
+use crate::query_log::QueryLog;
 use futures_core::future::BoxFuture;
 use log::LevelFilter;
+use std::sync::Arc;
 use std::time::Duration;
 
 impl ConnectOptions for MySqlConnectOptions {
@@ -65,4 +67,14 @@ impl ConnectOptions for MySqlConnectOptions {
         self.log_settings.log_slow_statements(level, duration);
         self
     }
+
+    fn query_log(&mut self, log: Arc<dyn QueryLog>) -> &mut Self {
+        self.log_settings.query_log(log);
+        self
+    }
+
+    fn application_name(&mut self, name: &str) -> &mut Self {
+        self.set_connect_attr("program_name", name);
+        self
+    }
 }
@@ -1,34 +1,59 @@
-use std::str::FromStr;
+use std::fmt::{self, Debug, Formatter};
 
 use crate::error::Error;
+use crate::mysql::auth_plugin::{Ed25519, LdapSasl};
+use crate::mysql::{MySqlAuthPlugin, MySqlConnectOptions};
 
-#[derive(Debug, Copy, Clone)]
 pub enum AuthPlugin {
     MySqlNativePassword,
     CachingSha2Password,
     Sha256Password,
+    Other(String, Box<dyn MySqlAuthPlugin>),
 }
 
 impl AuthPlugin {
-    pub(crate) fn name(self) -> &'static str {
+    /// Create the client plugin for the authentication plugin named by the server, preferring
+    /// the plugins registered in the options.
+    pub(crate) fn new(name: &str, options: &MySqlConnectOptions) -> Result<Self, Error> {
+        if let Some(factory) = options.auth_plugins.iter().find(|p| p.name == name) {
+            return Ok(AuthPlugin::Other(name.to_owned(), (factory.create)()));
+        }
+
+        let plugin: Box<dyn MySqlAuthPlugin> = match name {
+            "mysql_native_password" => return Ok(AuthPlugin::MySqlNativePassword),
+            "caching_sha2_password" => return Ok(AuthPlugin::CachingSha2Password),
+            "sha256_password" => return Ok(AuthPlugin::Sha256Password),
+
+            "client_ed25519" => Box::<Ed25519>::default(),
+            "authentication_ldap_sasl_client" => Box::<LdapSasl>::default(),
+
+            _ => {
+                return Err(Error::Configuration(
+                    format!(
+                        "unsupported authentication plugin {:?}; \
+                         it can be implemented with `MySqlConnectOptions::auth_plugin()`",
+                        name
+                    )
+                    .into(),
+                ));
+            }
+        };
+
+        Ok(AuthPlugin::Other(name.to_owned(), plugin))
+    }
+
+    pub(crate) fn name(&self) -> &str {
         match self {
             AuthPlugin::MySqlNativePassword => "mysql_native_password",
             AuthPlugin::CachingSha2Password => "caching_sha2_password",
             AuthPlugin::Sha256Password => "sha256_password",
+            AuthPlugin::Other(name, _) => name,
         }
     }
 }
 
-impl FromStr for AuthPlugin {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "mysql_native_password" => Ok(AuthPlugin::MySqlNativePassword),
-            "caching_sha2_password" => Ok(AuthPlugin::CachingSha2Password),
-            "sha256_password" => Ok(AuthPlugin::Sha256Password),
-
-            _ => Err(err_protocol!("unknown authentication plugin: {}", s)),
-        }
+impl Debug for AuthPlugin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "AuthPlugin({:?})", self.name())
     }
 }
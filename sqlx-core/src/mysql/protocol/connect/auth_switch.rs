@@ -3,14 +3,13 @@ use bytes::{Buf, Bytes};
 use crate::error::Error;
 use crate::io::Encode;
 use crate::io::{BufExt, Decode};
-use crate::mysql::protocol::auth::AuthPlugin;
 use crate::mysql::protocol::Capabilities;
 
 // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_connection_phase_packets_protocol_auth_switch_request.html
 
 #[derive(Debug)]
 pub struct AuthSwitchRequest {
-    pub plugin: AuthPlugin,
+    pub plugin: String,
     pub data: Bytes,
 }
 
@@ -24,7 +23,15 @@ impl Decode<'_> for AuthSwitchRequest {
             ));
         }
 
-        let plugin = buf.get_str_nul()?.parse()?;
+        let plugin = buf.get_str_nul()?;
+
+        if !matches!(
+            &*plugin,
+            "mysql_native_password" | "caching_sha2_password" | "sha256_password"
+        ) {
+            // the data of other plugins is theirs to interpret
+            return Ok(Self { plugin, data: buf });
+        }
 
         // See: https://github.com/mysql/mysql-server/blob/ea7d2e2d16ac03afdd9cb72a972a95981107bf51/sql/auth/sha2_password.cc#L942
         if buf.len() != 21 {
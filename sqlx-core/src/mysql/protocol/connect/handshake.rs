@@ -3,7 +3,6 @@ use bytes::{Buf, Bytes};
 
 use crate::error::Error;
 use crate::io::{BufExt, Decode};
-use crate::mysql::protocol::auth::AuthPlugin;
 use crate::mysql::protocol::response::Status;
 use crate::mysql::protocol::Capabilities;
 
@@ -15,14 +14,13 @@ pub(crate) struct Handshake {
     #[allow(unused)]
     pub(crate) protocol_version: u8,
     pub(crate) server_version: String,
-    #[allow(unused)]
     pub(crate) connection_id: u32,
     pub(crate) server_capabilities: Capabilities,
     #[allow(unused)]
     pub(crate) server_default_collation: u8,
     #[allow(unused)]
     pub(crate) status: Status,
-    pub(crate) auth_plugin: Option<AuthPlugin>,
+    pub(crate) auth_plugin: Option<String>,
     pub(crate) auth_plugin_data: Chain<Bytes, Bytes>,
 }
 
@@ -71,7 +69,7 @@ impl Decode<'_> for Handshake {
         };
 
         let auth_plugin = if capabilities.contains(Capabilities::PLUGIN_AUTH) {
-            Some(buf.get_str_nul()?.parse()?)
+            Some(buf.get_str_nul()?)
         } else {
             None
         };
@@ -133,8 +131,8 @@ fn test_decode_handshake_mysql_8_0_18() {
     assert!(p.status.contains(Status::SERVER_STATUS_AUTOCOMMIT));
 
     assert!(matches!(
-        p.auth_plugin,
-        Some(AuthPlugin::CachingSha2Password)
+        p.auth_plugin.as_deref(),
+        Some("caching_sha2_password")
     ));
 
     assert_eq!(
@@ -186,8 +184,8 @@ fn test_decode_handshake_mariadb_10_4_7() {
     assert_eq!(p.server_default_collation, 8);
     assert!(p.status.contains(Status::SERVER_STATUS_AUTOCOMMIT));
     assert!(matches!(
-        p.auth_plugin,
-        Some(AuthPlugin::MySqlNativePassword)
+        p.auth_plugin.as_deref(),
+        Some("mysql_native_password")
     ));
 
     assert_eq!(
@@ -1,6 +1,5 @@
 use crate::io::{BufMutExt, Encode};
 use crate::mysql::io::MySqlBufMutExt;
-use crate::mysql::protocol::auth::AuthPlugin;
 use crate::mysql::protocol::connect::ssl_request::SslRequest;
 use crate::mysql::protocol::Capabilities;
 
@@ -21,10 +20,16 @@ pub struct HandshakeResponse<'a> {
     pub username: &'a str,
 
     /// Authentication method used by the client
-    pub auth_plugin: Option<AuthPlugin>,
+    pub auth_plugin: Option<&'a str>,
 
     /// Opaque authentication response
     pub auth_response: Option<&'a [u8]>,
+
+    /// Key-value attributes describing the client, e.g. the name of the program
+    pub connect_attrs: &'a [(&'a str, &'a str)],
+
+    /// Compression level for zstd
+    pub zstd_compression_level: Option<u8>,
 }
 
 impl Encode<'_, Capabilities> for HandshakeResponse<'_> {
@@ -64,10 +69,56 @@ impl Encode<'_, Capabilities> for HandshakeResponse<'_> {
 
         if capabilities.contains(Capabilities::PLUGIN_AUTH) {
             if let Some(plugin) = &self.auth_plugin {
-                buf.put_str_nul(plugin.name());
+                buf.put_str_nul(plugin);
             } else {
                 buf.push(0);
             }
         }
+
+        if capabilities.contains(Capabilities::CONNECT_ATTRS) {
+            let mut attrs = Vec::new();
+
+            for (key, value) in self.connect_attrs {
+                attrs.put_str_lenenc(key);
+                attrs.put_str_lenenc(value);
+            }
+
+            buf.put_bytes_lenenc(&attrs);
+        }
+
+        if capabilities.contains(Capabilities::ZSTD_COMPRESSION_ALGORITHM) {
+            buf.push(self.zstd_compression_level.unwrap_or(3));
+        }
     }
 }
+
+#[test]
+fn test_encode_handshake_response_with_connect_attrs() {
+    let response = HandshakeResponse {
+        database: None,
+        max_packet_size: 1024,
+        collation: 45,
+        username: "root",
+        auth_plugin: None,
+        auth_response: None,
+        connect_attrs: &[("_client_name", "sqlx"), ("program_name", "app")],
+        zstd_compression_level: None,
+    };
+
+    let mut buf = Vec::new();
+    response.encode_with(
+        &mut buf,
+        Capabilities::PROTOCOL_41 | Capabilities::CONNECT_ATTRS,
+    );
+
+    // the fixed part is 32 bytes, followed by the username and an empty auth response
+    let attrs = b"\x0c_client_name\x04sqlx\x0cprogram_name\x03app";
+    assert_eq!(buf[38] as usize, attrs.len());
+    assert_eq!(&buf[39..], &attrs[..]);
+
+    // without the capability, the attributes aren't sent
+    let mut buf = Vec::new();
+    response.encode_with(&mut buf, Capabilities::PROTOCOL_41);
+
+    assert_eq!(buf.len(), 38);
+}
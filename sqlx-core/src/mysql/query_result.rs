@@ -1,5 +1,7 @@
 use std::iter::{Extend, IntoIterator};
 
+use crate::mysql::MySqlRow;
+
 #[derive(Debug, Default)]
 pub struct MySqlQueryResult {
     pub(super) rows_affected: u64,
@@ -25,6 +27,33 @@ impl Extend<MySqlQueryResult> for MySqlQueryResult {
     }
 }
 
+/// The result of one statement of a batch run by
+/// [`MySqlConnection::execute_batch`](crate::mysql::MySqlConnection::execute_batch).
+#[derive(Debug)]
+pub struct MySqlBatchResult {
+    pub(super) rows: Vec<MySqlRow>,
+    pub(super) result: MySqlQueryResult,
+}
+
+impl MySqlBatchResult {
+    /// The rows returned by the statement, if any.
+    pub fn rows(&self) -> &[MySqlRow] {
+        &self.rows
+    }
+
+    pub fn into_rows(self) -> Vec<MySqlRow> {
+        self.rows
+    }
+
+    pub fn last_insert_id(&self) -> u64 {
+        self.result.last_insert_id
+    }
+
+    pub fn rows_affected(&self) -> u64 {
+        self.result.rows_affected
+    }
+}
+
 #[cfg(feature = "any")]
 impl From<MySqlQueryResult> for crate::any::AnyQueryResult {
     fn from(done: MySqlQueryResult) -> Self {
@@ -50,14 +50,201 @@ impl ColumnIndex<MySqlRow> for &'_ str {
     }
 }
 
+#[cfg(feature = "any")]
+impl MySqlRow {
+    // the converted columns are shared with the other rows of the result set
+    pub(crate) fn into_any(
+        self,
+        columns: &mut crate::any::row::AnyColumnsCache,
+    ) -> crate::any::AnyRow {
+        crate::any::AnyRow {
+            columns: columns.get(&self.columns, |columns| {
+                columns.iter().map(|col| col.clone().into()).collect()
+            }),
+            kind: crate::any::row::AnyRowKind::MySql(self),
+        }
+    }
+}
+
 #[cfg(feature = "any")]
 impl From<MySqlRow> for crate::any::AnyRow {
     #[inline]
     fn from(row: MySqlRow) -> Self {
-        crate::any::AnyRow {
-            columns: row.columns.iter().map(|col| col.clone().into()).collect(),
+        row.into_any(&mut Default::default())
+    }
+}
+
+/// Serializes the row as a map of its values by the names of their columns, e.g. as a JSON
+/// object.
+///
+/// The numbers, `BOOLEAN`s, strings, JSON and binary strings are serialized as themselves,
+/// `BIT`s as their bytes; `DECIMAL`s and the dates and times as strings, which requires the
+/// `chrono` feature for the dates and times. The other types fail to serialize.
+#[cfg(feature = "json")]
+impl serde::Serialize for MySqlRow {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        crate::row::serialize_row(self, serializer, |_, value| to_json(value))
+    }
+}
+
+#[cfg(feature = "json")]
+fn to_json(value: MySqlValueRef<'_>) -> Result<serde_json::Value, crate::error::BoxDynError> {
+    use crate::decode::Decode;
+    use crate::mysql::protocol::text::{ColumnFlags, ColumnType};
+    use crate::type_info::TypeInfo;
+    use crate::types::JsonValue;
+
+    macro_rules! decode {
+        ($T:ty) => {
+            <$T as Decode<'_, MySql>>::decode(value.clone())?
+        };
+    }
+
+    let ty = &value.type_info;
+    let is_binary = ty.char_set == 63;
+
+    let json = match ty.r#type {
+        ColumnType::Tiny if ty.name() == "BOOLEAN" => decode!(bool).into(),
+
+        ColumnType::Tiny
+        | ColumnType::Short
+        | ColumnType::Long
+        | ColumnType::Int24
+        | ColumnType::LongLong
+            if ty.flags.contains(ColumnFlags::UNSIGNED) =>
+        {
+            decode!(u64).into()
+        }
+
+        ColumnType::Tiny
+        | ColumnType::Short
+        | ColumnType::Long
+        | ColumnType::Int24
+        | ColumnType::LongLong => decode!(i64).into(),
+
+        ColumnType::Year => decode!(u16).into(),
+
+        ColumnType::Float => f64::from(decode!(f32)).into(),
+        ColumnType::Double => decode!(f64).into(),
+
+        // sent as text in both formats
+        ColumnType::Decimal | ColumnType::NewDecimal => value.as_str()?.into(),
+
+        ColumnType::Json => decode!(JsonValue),
+
+        ColumnType::Bit => value.as_bytes()?.into(),
+
+        ColumnType::VarChar
+        | ColumnType::VarString
+        | ColumnType::String
+        | ColumnType::TinyBlob
+        | ColumnType::Blob
+        | ColumnType::MediumBlob
+        | ColumnType::LongBlob
+            if is_binary =>
+        {
+            value.as_bytes()?.into()
+        }
+
+        ColumnType::VarChar
+        | ColumnType::VarString
+        | ColumnType::String
+        | ColumnType::TinyBlob
+        | ColumnType::Blob
+        | ColumnType::MediumBlob
+        | ColumnType::LongBlob
+        | ColumnType::Enum
+        | ColumnType::Set => value.as_str()?.into(),
+
+        ColumnType::Timestamp | ColumnType::Datetime | ColumnType::Date | ColumnType::Time => {
+            datetime_json(value)?
+        }
+
+        _ => return Err(format!("values of type {} are not supported", ty).into()),
+    };
+
+    Ok(json)
+}
+
+// the dates and times, as strings in the ISO 8601 format
+#[cfg(feature = "json")]
+fn datetime_json(value: MySqlValueRef<'_>) -> Result<serde_json::Value, crate::error::BoxDynError> {
+    #[cfg(feature = "chrono")]
+    {
+        use crate::decode::Decode;
+        use crate::mysql::protocol::text::ColumnType;
+        use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+
+        let s = match value.type_info.r#type {
+            ColumnType::Timestamp => {
+                <DateTime<Utc> as Decode<'_, MySql>>::decode(value)?.to_rfc3339()
+            }
+
+            ColumnType::Datetime => <NaiveDateTime as Decode<'_, MySql>>::decode(value)?
+                .format("%Y-%m-%dT%H:%M:%S%.f")
+                .to_string(),
+
+            ColumnType::Date => <NaiveDate as Decode<'_, MySql>>::decode(value)?.to_string(),
+
+            _ => <NaiveTime as Decode<'_, MySql>>::decode(value)?.to_string(),
+        };
+
+        Ok(s.into())
+    }
+
+    #[cfg(not(feature = "chrono"))]
+    {
+        let _ = value;
+
+        Err("the `chrono` feature is required to serialize dates and times".into())
+    }
+}
+
+/// The unsigned integers are written as the next larger signed integers, but `BIGINT UNSIGNED`,
+/// written as text like `DECIMAL`.
+#[cfg(feature = "parquet")]
+impl crate::export::parquet::ParquetDatabase for MySql {
+    fn parquet_type(ty: &crate::mysql::MySqlTypeInfo) -> crate::export::parquet::ParquetType {
+        use crate::export::parquet::ParquetType;
+        use crate::mysql::protocol::text::{ColumnFlags, ColumnType};
+        use crate::type_info::TypeInfo;
+
+        let unsigned = ty.flags.contains(ColumnFlags::UNSIGNED);
+
+        match ty.r#type {
+            ColumnType::Tiny if ty.name() == "BOOLEAN" => ParquetType::Boolean,
+
+            ColumnType::Tiny if unsigned => ParquetType::Int16,
+            ColumnType::Tiny => ParquetType::Int8,
+            ColumnType::Short if unsigned => ParquetType::Int32,
+            ColumnType::Short | ColumnType::Year => ParquetType::Int16,
+            ColumnType::Long | ColumnType::Int24 if unsigned => ParquetType::Int64,
+            ColumnType::Long | ColumnType::Int24 => ParquetType::Int32,
+            ColumnType::LongLong if !unsigned => ParquetType::Int64,
+
+            ColumnType::Float => ParquetType::Float,
+            ColumnType::Double => ParquetType::Double,
+
+            ColumnType::Json => ParquetType::Json,
+
+            ColumnType::Bit => ParquetType::Bytes,
+
+            ColumnType::VarChar
+            | ColumnType::VarString
+            | ColumnType::String
+            | ColumnType::TinyBlob
+            | ColumnType::Blob
+            | ColumnType::MediumBlob
+            | ColumnType::LongBlob
+                if ty.char_set == 63 =>
+            {
+                ParquetType::Bytes
+            }
 
-            kind: crate::any::row::AnyRowKind::MySql(row),
+            _ => ParquetType::String,
         }
     }
 }
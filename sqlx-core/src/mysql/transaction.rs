@@ -7,7 +7,7 @@ use crate::mysql::protocol::text::Query;
 use crate::mysql::{MySql, MySqlConnection};
 use crate::transaction::{
     begin_ansi_transaction_sql, commit_ansi_transaction_sql, rollback_ansi_transaction_sql,
-    TransactionManager,
+    TransactionManager, TransactionOptions,
 };
 
 /// Implementation of [`TransactionManager`] for MySQL.
@@ -27,6 +27,51 @@ impl TransactionManager for MySqlTransactionManager {
         })
     }
 
+    fn begin_with(
+        conn: &mut MySqlConnection,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            // there are no deferrable transactions: every snapshot is taken without waiting
+            if options.deferrable == Some(true) {
+                return Err(Error::Configuration(
+                    "MySQL does not support deferrable transactions".into(),
+                ));
+            }
+
+            // https://dev.mysql.com/doc/refman/8.0/en/set-transaction.html
+            // without `SESSION`, the level is the one of the next transaction only
+            if let Some(isolation) = options.isolation {
+                conn.execute(&*format!(
+                    "SET TRANSACTION ISOLATION LEVEL {}",
+                    isolation.as_sql()
+                ))
+                .await?;
+            }
+
+            // https://dev.mysql.com/doc/refman/8.0/en/commit.html
+            let sql = match options.read_only {
+                Some(true) => "START TRANSACTION READ ONLY",
+                Some(false) => "START TRANSACTION READ WRITE",
+                None => "START TRANSACTION",
+            };
+
+            if let Err(e) = conn.execute(sql).await {
+                // the level would otherwise be the one of the next transaction begun on the
+                // connection; ending a transaction, even when there is none, resets it
+                if options.isolation.is_some() {
+                    let _ = conn.execute("ROLLBACK").await;
+                }
+
+                return Err(e);
+            }
+
+            conn.transaction_depth = 1;
+
+            Ok(())
+        })
+    }
+
     fn commit(conn: &mut MySqlConnection) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
             let depth = conn.transaction_depth;
@@ -65,4 +110,10 @@ impl TransactionManager for MySqlTransactionManager {
             conn.transaction_depth = depth - 1;
         }
     }
+
+    fn start_statement(conn: &mut MySqlConnection, sql: &str) {
+        conn.stream.waiting.push_back(Waiting::Result);
+        conn.stream.sequence_id = 0;
+        conn.stream.write_packet(Query(sql));
+    }
 }
@@ -7,37 +7,112 @@ use std::task::{Context, Poll};
 
 use sqlx_rt::{AsyncRead, AsyncWrite, TcpStream};
 
+use super::TcpOptions;
+use crate::runtime::{self, RuntimeSocket};
+
 #[derive(Debug)]
 pub enum Socket {
     Tcp(TcpStream),
 
     #[cfg(unix)]
     Unix(sqlx_rt::UnixStream),
+
+    #[cfg(all(windows, any(feature = "_rt-actix", feature = "_rt-tokio")))]
+    NamedPipe(sqlx_rt::NamedPipeClient),
+
+    // a socket opened by the installed runtime
+    Custom(Box<dyn RuntimeSocket>),
 }
 
 impl Socket {
-    pub async fn connect_tcp(host: &str, port: u16) -> io::Result<Self> {
-        // Trim square brackets from host if it's an IPv6 address as the `url` crate doesn't do that.
-        TcpStream::connect((host.trim_matches(|c| c == '[' || c == ']'), port))
+    pub(crate) async fn connect_tcp(
+        host: &str,
+        port: u16,
+        options: &TcpOptions,
+    ) -> io::Result<Self> {
+        if let Some(runtime) = runtime::installed() {
+            if options.proxy.is_some() {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "connections through a proxy are not supported by the installed runtime",
+                ));
+            }
+
+            // Trim square brackets from host if it's an IPv6 address as the `url` crate doesn't do that.
+            let host = host.trim_matches(|c| c == '[' || c == ']');
+            let connect = runtime.connect_tcp(host, port);
+
+            let socket = match options.connect_timeout {
+                None => connect.await?,
+                Some(timeout) => runtime::timeout(timeout, connect).await.map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        format!(
+                            "connecting to {}:{} timed out after {:?}",
+                            host, port, timeout
+                        ),
+                    )
+                })??,
+            };
+
+            return Ok(Socket::Custom(socket));
+        }
+
+        super::connect_tcp(host, port, options)
             .await
             .map(Socket::Tcp)
     }
 
     #[cfg(unix)]
     pub async fn connect_uds(path: impl AsRef<Path>) -> io::Result<Self> {
+        if let Some(runtime) = runtime::installed() {
+            return runtime.connect_uds(path.as_ref()).await.map(Socket::Custom);
+        }
+
         sqlx_rt::UnixStream::connect(path.as_ref())
             .await
             .map(Socket::Unix)
     }
 
     #[cfg(not(unix))]
-    pub async fn connect_uds(_: impl AsRef<Path>) -> io::Result<Self> {
+    pub async fn connect_uds(path: impl AsRef<Path>) -> io::Result<Self> {
+        if let Some(runtime) = runtime::installed() {
+            return runtime.connect_uds(path.as_ref()).await.map(Socket::Custom);
+        }
+
         Err(io::Error::new(
             io::ErrorKind::Other,
             "Unix domain sockets are not supported outside Unix platforms.",
         ))
     }
 
+    #[cfg(all(windows, any(feature = "_rt-actix", feature = "_rt-tokio")))]
+    pub async fn connect_named_pipe(path: impl AsRef<Path>) -> io::Result<Self> {
+        // https://docs.microsoft.com/en-us/windows/win32/debug/system-error-codes--0-499-
+        const ERROR_PIPE_BUSY: i32 = 231;
+
+        loop {
+            match sqlx_rt::NamedPipeClientOptions::new().open(path.as_ref()) {
+                Ok(client) => return Ok(Socket::NamedPipe(client)),
+
+                // every instance of the pipe is in use; wait for the server to create another
+                Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY) => {
+                    sqlx_rt::sleep(std::time::Duration::from_millis(50)).await;
+                }
+
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    #[cfg(not(all(windows, any(feature = "_rt-actix", feature = "_rt-tokio"))))]
+    pub async fn connect_named_pipe(_: impl AsRef<Path>) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            "Named pipes are only supported on Windows, with the Tokio or Actix runtimes.",
+        ))
+    }
+
     pub async fn shutdown(&mut self) -> io::Result<()> {
         #[cfg(feature = "_rt-async-std")]
         {
@@ -48,6 +123,12 @@ impl Socket {
 
                 #[cfg(unix)]
                 Socket::Unix(s) => s.shutdown(Shutdown::Both),
+
+                Socket::Custom(s) => {
+                    use futures_util::AsyncWriteExt;
+
+                    s.close().await
+                }
             }
         }
 
@@ -60,6 +141,11 @@ impl Socket {
 
                 #[cfg(unix)]
                 Socket::Unix(s) => s.shutdown().await,
+
+                #[cfg(windows)]
+                Socket::NamedPipe(s) => s.shutdown().await,
+
+                Socket::Custom(s) => s.shutdown().await,
             }
         }
     }
@@ -76,6 +162,11 @@ impl AsyncRead for Socket {
 
             #[cfg(unix)]
             Socket::Unix(s) => Pin::new(s).poll_read(cx, buf),
+
+            #[cfg(all(windows, any(feature = "_rt-actix", feature = "_rt-tokio")))]
+            Socket::NamedPipe(s) => Pin::new(s).poll_read(cx, buf),
+
+            Socket::Custom(s) => Pin::new(&mut **s).poll_read(cx, buf),
         }
     }
 }
@@ -91,6 +182,11 @@ impl AsyncWrite for Socket {
 
             #[cfg(unix)]
             Socket::Unix(s) => Pin::new(s).poll_write(cx, buf),
+
+            #[cfg(all(windows, any(feature = "_rt-actix", feature = "_rt-tokio")))]
+            Socket::NamedPipe(s) => Pin::new(s).poll_write(cx, buf),
+
+            Socket::Custom(s) => Pin::new(&mut **s).poll_write(cx, buf),
         }
     }
 
@@ -100,6 +196,11 @@ impl AsyncWrite for Socket {
 
             #[cfg(unix)]
             Socket::Unix(s) => Pin::new(s).poll_flush(cx),
+
+            #[cfg(all(windows, any(feature = "_rt-actix", feature = "_rt-tokio")))]
+            Socket::NamedPipe(s) => Pin::new(s).poll_flush(cx),
+
+            Socket::Custom(s) => Pin::new(&mut **s).poll_flush(cx),
         }
     }
 
@@ -110,6 +211,11 @@ impl AsyncWrite for Socket {
 
             #[cfg(unix)]
             Socket::Unix(s) => Pin::new(s).poll_shutdown(cx),
+
+            #[cfg(windows)]
+            Socket::NamedPipe(s) => Pin::new(s).poll_shutdown(cx),
+
+            Socket::Custom(s) => Pin::new(&mut **s).poll_shutdown(cx),
         }
     }
 
@@ -120,6 +226,8 @@ impl AsyncWrite for Socket {
 
             #[cfg(unix)]
             Socket::Unix(s) => Pin::new(s).poll_close(cx),
+
+            Socket::Custom(s) => Pin::new(&mut **s).poll_close(cx),
         }
     }
 }
@@ -0,0 +1,176 @@
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::time::Duration;
+
+use socket2::{SockRef, TcpKeepalive};
+use sqlx_rt::TcpStream;
+
+use super::proxy::Proxy;
+
+/// The settings of the TCP connections of a driver, set by the `tcp_*` and `connect_timeout`
+/// builders of its connect options.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TcpOptions {
+    // `None` leaves the OS defaults
+    pub(crate) keepalive_time: Option<Duration>,
+    pub(crate) keepalive_interval: Option<Duration>,
+    pub(crate) keepalive_retries: Option<u32>,
+    pub(crate) nodelay: Option<bool>,
+    pub(crate) connect_timeout: Option<Duration>,
+    // the URL of the proxy to tunnel the connection through, parsed when connecting
+    pub(crate) proxy: Option<String>,
+}
+
+impl TcpOptions {
+    fn keepalive(&self) -> Option<TcpKeepalive> {
+        if self.keepalive_time.is_none()
+            && self.keepalive_interval.is_none()
+            && self.keepalive_retries.is_none()
+        {
+            return None;
+        }
+
+        let mut keepalive = TcpKeepalive::new();
+
+        if let Some(time) = self.keepalive_time {
+            keepalive = keepalive.with_time(time);
+        }
+
+        // the probes are only tunable on some platforms; elsewhere, the OS defaults are used
+        #[cfg(any(
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "fuchsia",
+            target_os = "linux",
+            target_os = "netbsd",
+            target_vendor = "apple",
+            windows,
+        ))]
+        if let Some(interval) = self.keepalive_interval {
+            keepalive = keepalive.with_interval(interval);
+        }
+
+        #[cfg(any(
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "fuchsia",
+            target_os = "linux",
+            target_os = "netbsd",
+            target_vendor = "apple",
+        ))]
+        if let Some(retries) = self.keepalive_retries {
+            keepalive = keepalive.with_retries(retries);
+        }
+
+        Some(keepalive)
+    }
+}
+
+/// Connects to `host`, through the proxy of `options` if it has one, and applies `options` to
+/// the stream.
+pub(crate) async fn connect_tcp(
+    host: &str,
+    port: u16,
+    options: &TcpOptions,
+) -> io::Result<TcpStream> {
+    // Trim square brackets from host if it's an IPv6 address as the `url` crate doesn't do that.
+    let host = host.trim_matches(|c| c == '[' || c == ']');
+
+    let proxy: Option<Proxy> = options.proxy.as_deref().map(str::parse).transpose()?;
+
+    // the options apply to the connection to the proxy, if any
+    let (connect_host, connect_port) = match &proxy {
+        Some(proxy) => (&*proxy.host, proxy.port),
+        None => (host, port),
+    };
+
+    let mut stream = match options.connect_timeout {
+        None => TcpStream::connect((connect_host, connect_port)).await?,
+        Some(timeout) => connect_with_timeout(connect_host, connect_port, timeout).await?,
+    };
+
+    let socket = SockRef::from(&stream);
+
+    if let Some(nodelay) = options.nodelay {
+        socket.set_nodelay(nodelay)?;
+    }
+
+    if let Some(keepalive) = options.keepalive() {
+        socket.set_tcp_keepalive(&keepalive)?;
+    }
+
+    if let Some(proxy) = &proxy {
+        proxy.tunnel(&mut stream, host, port).await?;
+    }
+
+    Ok(stream)
+}
+
+// like libpq, the timeout applies to each address, so an unreachable address doesn't use up
+// the time of the next ones
+async fn connect_with_timeout(host: &str, port: u16, timeout: Duration) -> io::Result<TcpStream> {
+    let addrs = resolve(host, port).await?;
+    let mut last_error = None;
+
+    for addr in addrs {
+        match sqlx_rt::timeout(timeout, TcpStream::connect(addr)).await {
+            Ok(Ok(stream)) => return Ok(stream),
+            Ok(Err(error)) => last_error = Some(error),
+            Err(_) => {
+                last_error = Some(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("connecting to {} timed out after {:?}", addr, timeout),
+                ));
+            }
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} did not resolve to any address", host),
+        )
+    }))
+}
+
+async fn resolve(host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+    let host = host.to_owned();
+
+    // the resolution of the runtimes also runs `getaddrinfo` on a blocking thread
+    sqlx_rt::blocking!((host.as_str(), port)
+        .to_socket_addrs()
+        .map(Iterator::collect))
+}
+
+#[test]
+fn test_connect_tcp_applies_options() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let options = TcpOptions {
+        keepalive_time: Some(Duration::from_secs(30)),
+        nodelay: Some(true),
+        connect_timeout: Some(Duration::from_secs(5)),
+        ..TcpOptions::default()
+    };
+
+    sqlx_rt::block_on(async {
+        let stream = connect_tcp("localhost", port, &options).await.unwrap();
+        let socket = SockRef::from(&stream);
+
+        assert!(socket.nodelay().unwrap());
+        assert!(socket.keepalive().unwrap());
+
+        #[cfg(target_os = "linux")]
+        assert_eq!(socket.keepalive_time().unwrap(), Duration::from_secs(30));
+    });
+
+    // the OS defaults are left alone
+    sqlx_rt::block_on(async {
+        let stream = connect_tcp("127.0.0.1", port, &TcpOptions::default())
+            .await
+            .unwrap();
+
+        assert!(!SockRef::from(&stream).keepalive().unwrap());
+    });
+}
@@ -1,7 +1,8 @@
-use crate::net::CertificateInput;
+use crate::net::tls::{CustomTlsConfig, TlsConfig};
 use rustls::{
     client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier},
-    ClientConfig, Error as TlsError, OwnedTrustAnchor, RootCertStore, ServerName,
+    Certificate, ClientConfig, Error as TlsError, OwnedTrustAnchor, PrivateKey, RootCertStore,
+    ServerName,
 };
 use std::io::Cursor;
 use std::sync::Arc;
@@ -10,16 +11,15 @@ use std::time::SystemTime;
 use crate::error::Error;
 
 pub async fn configure_tls_connector(
-    accept_invalid_certs: bool,
-    accept_invalid_hostnames: bool,
-    root_cert_path: Option<&CertificateInput>,
+    tls_config: &TlsConfig<'_>,
 ) -> Result<sqlx_rt::TlsConnector, Error> {
-    let config = ClientConfig::builder().with_safe_defaults();
+    if let Some(CustomTlsConfig::Rustls(config)) = tls_config.custom {
+        return Ok(Arc::clone(config).into());
+    }
 
-    let config = if accept_invalid_certs {
-        config
-            .with_custom_certificate_verifier(Arc::new(DummyTlsVerifier))
-            .with_no_client_auth()
+    // the same verifier as `with_root_certificates()`, unless the checks are relaxed
+    let verifier: Arc<dyn ServerCertVerifier> = if tls_config.accept_invalid_certs {
+        Arc::new(DummyTlsVerifier)
     } else {
         let mut cert_store = RootCertStore::empty();
         cert_store.add_server_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.0.iter().map(|ta| {
@@ -30,7 +30,7 @@ pub async fn configure_tls_connector(
             )
         }));
 
-        if let Some(ca) = root_cert_path {
+        if let Some(ca) = tls_config.root_cert_path {
             let data = ca.data().await?;
             let mut cursor = Cursor::new(data);
 
@@ -43,22 +43,51 @@ pub async fn configure_tls_connector(
             }
         }
 
-        if accept_invalid_hostnames {
-            let verifier = WebPkiVerifier::new(cert_store, None);
+        let verifier = WebPkiVerifier::new(cert_store, None);
 
-            config
-                .with_custom_certificate_verifier(Arc::new(NoHostnameTlsVerifier { verifier }))
-                .with_no_client_auth()
+        if tls_config.accept_invalid_hostnames {
+            Arc::new(NoHostnameTlsVerifier { verifier })
         } else {
-            config
-                .with_root_certificates(cert_store)
-                .with_no_client_auth()
+            Arc::new(verifier)
         }
     };
 
+    let config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(verifier);
+
+    let config = match tls_config.client_auth().await? {
+        Some((cert, key)) => config
+            .with_single_cert(certs_from_pem(cert)?, private_key_from_pem(key)?)
+            .map_err(|err| Error::Tls(err.into()))?,
+        None => config.with_no_client_auth(),
+    };
+
     Ok(Arc::new(config).into())
 }
 
+fn certs_from_pem(pem: Vec<u8>) -> Result<Vec<Certificate>, Error> {
+    let certs = rustls_pemfile::certs(&mut Cursor::new(pem))
+        .map_err(|_| Error::Tls("invalid client certificate".into()))?;
+
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+fn private_key_from_pem(pem: Vec<u8>) -> Result<PrivateKey, Error> {
+    let items = rustls_pemfile::read_all(&mut Cursor::new(pem))
+        .map_err(|_| Error::Tls("invalid client key".into()))?;
+
+    items
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .ok_or_else(|| Error::Tls("no private key found in the client key".into()))
+}
+
 struct DummyTlsVerifier;
 
 impl ServerCertVerifier for DummyTlsVerifier {
@@ -0,0 +1,326 @@
+//! A hook observing every statement executed by the process, e.g. to write an audit log or to
+//! account for the usage of each tenant, without wrapping each call to an executor.
+//!
+//! ```rust,ignore
+//! sqlx::observer::set_statement_observer(|event| {
+//!     usage::record(event.tag("tenant"), event.elapsed(), event.error().is_some());
+//! });
+//!
+//! // the statements executed by the request are tagged with its tenant
+//! sqlx::observer::with_tags([("tenant", "acme")], handle(request)).await;
+//! ```
+//!
+//! The observer is called once a statement executed with `execute()` or `fetch*()` completed,
+//! failed, or was dropped, by the task or thread executing it, so it shouldn't block. The tags
+//! are those of the [scope](with_tags) the statement was sent in.
+//!
+//! Transactions are observed the same way, e.g. to find the requests holding one open while
+//! they wait on something else:
+//!
+//! ```rust,ignore
+//! sqlx::observer::set_transaction_observer(|event| {
+//!     if event.kind() != TransactionEventKind::Begin && event.elapsed() > Duration::from_secs(1) {
+//!         log::warn!("transaction of {:?} open for {:?}", event.tag("route"), event.elapsed());
+//!     }
+//! });
+//! ```
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::task::Context;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+
+use crate::error::Error;
+
+type Observer = Arc<dyn Fn(&StatementEvent<'_>) + Send + Sync>;
+
+type TransactionObserver = Arc<dyn Fn(&TransactionEvent<'_>) + Send + Sync>;
+
+pub(crate) type Tags = Arc<[(String, String)]>;
+
+// whether an observer is set, checked before taking the lock
+static OBSERVED: AtomicBool = AtomicBool::new(false);
+
+static OBSERVER: Lazy<RwLock<Option<Observer>>> = Lazy::new(Default::default);
+
+static TRANSACTIONS_OBSERVED: AtomicBool = AtomicBool::new(false);
+
+static TRANSACTION_OBSERVER: Lazy<RwLock<Option<TransactionObserver>>> =
+    Lazy::new(Default::default);
+
+thread_local! {
+    static TAGS: RefCell<Option<Tags>> = const { RefCell::new(None) };
+}
+
+/// Calls `observer` with each statement executed from now on, in place of the previous
+/// observer, if any.
+pub fn set_statement_observer(observer: impl Fn(&StatementEvent<'_>) + Send + Sync + 'static) {
+    *OBSERVER.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(observer));
+    OBSERVED.store(true, Ordering::Release);
+}
+
+/// Stops observing the statements.
+pub fn clear_statement_observer() {
+    OBSERVED.store(false, Ordering::Release);
+    *OBSERVER.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Calls `observer` when a transaction or a savepoint begins, and when it's committed, rolled
+/// back, or dropped, in place of the previous observer, if any.
+pub fn set_transaction_observer(observer: impl Fn(&TransactionEvent<'_>) + Send + Sync + 'static) {
+    *TRANSACTION_OBSERVER
+        .write()
+        .unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(observer));
+    TRANSACTIONS_OBSERVED.store(true, Ordering::Release);
+}
+
+/// Stops observing the transactions.
+pub fn clear_transaction_observer() {
+    TRANSACTIONS_OBSERVED.store(false, Ordering::Release);
+    *TRANSACTION_OBSERVER
+        .write()
+        .unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Runs `future`, tagging the statements it executes with `tags`, along with the tags of the
+/// scope it runs in; a tag of `tags` replaces a tag of the same key of the outer scope.
+pub async fn with_tags<I, K, V, F>(tags: I, future: F) -> F::Output
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<String>,
+    V: Into<String>,
+    F: Future,
+{
+    let mut merged: Vec<(String, String)> = current_tags().map_or_else(Vec::new, |t| t.to_vec());
+
+    for (key, value) in tags {
+        let key = key.into();
+
+        merged.retain(|(k, _)| *k != key);
+        merged.push((key, value.into()));
+    }
+
+    let tags: Tags = merged.into();
+
+    futures_util::pin_mut!(future);
+
+    futures_util::future::poll_fn(|cx: &mut Context<'_>| {
+        let _scope = enter(Some(Arc::clone(&tags)));
+
+        future.as_mut().poll(cx)
+    })
+    .await
+}
+
+/// A statement executed by a connection, given to the observer.
+#[derive(Debug)]
+pub struct StatementEvent<'a> {
+    pub(crate) sql: &'a str,
+    pub(crate) elapsed: Duration,
+    pub(crate) rows_affected: u64,
+    pub(crate) rows_returned: u64,
+    pub(crate) error: Option<&'a StatementError>,
+    pub(crate) tags: &'a [(String, String)],
+}
+
+impl<'a> StatementEvent<'a> {
+    pub fn sql(&self) -> &'a str {
+        self.sql
+    }
+
+    /// The time the statement took, from when it was sent until its last row was received,
+    /// or until it failed or was dropped.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+
+    pub fn rows_returned(&self) -> u64 {
+        self.rows_returned
+    }
+
+    /// The message of the error the statement failed with, or `None` if it didn't fail.
+    pub fn error(&self) -> Option<&'a str> {
+        self.error.map(|error| &*error.message)
+    }
+
+    /// The code of the database error the statement failed with, e.g. its SQLSTATE, if any.
+    pub fn error_code(&self) -> Option<&'a str> {
+        self.error.and_then(|error| error.code.as_deref())
+    }
+
+    /// The tags of the scope the statement was sent in; see [`with_tags()`].
+    pub fn tags(&self) -> &'a [(String, String)] {
+        self.tags
+    }
+
+    /// The value of the tag `key`, if any.
+    pub fn tag(&self, key: &str) -> Option<&'a str> {
+        self.tags
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| &**value)
+    }
+}
+
+/// What happened to a transaction, in a [`TransactionEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TransactionEventKind {
+    Begin,
+    Commit,
+    Rollback,
+
+    /// The transaction was dropped while still open, so it's rolled back by the connection in
+    /// the background.
+    Dropped,
+}
+
+/// A transaction or a savepoint which began or ended, given to the transaction observer.
+#[derive(Debug)]
+pub struct TransactionEvent<'a> {
+    pub(crate) kind: TransactionEventKind,
+    pub(crate) depth: usize,
+    pub(crate) elapsed: Duration,
+    pub(crate) statements: u64,
+    pub(crate) tags: &'a [(String, String)],
+}
+
+impl<'a> TransactionEvent<'a> {
+    pub fn kind(&self) -> TransactionEventKind {
+        self.kind
+    }
+
+    /// `1` for a transaction, and more for a savepoint within it, e.g. `2` for a savepoint of a
+    /// transaction.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// For [`Begin`](TransactionEventKind::Begin), the time the transaction took to begin;
+    /// otherwise, the time it was open, from when it began until it ended.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// The statements executed in the transaction, including those of its savepoints; `0` for
+    /// [`Begin`](TransactionEventKind::Begin).
+    pub fn statements(&self) -> u64 {
+        self.statements
+    }
+
+    /// The tags of the scope the transaction began or ended in; see [`with_tags()`].
+    pub fn tags(&self) -> &'a [(String, String)] {
+        self.tags
+    }
+
+    /// The value of the tag `key`, if any.
+    pub fn tag(&self, key: &str) -> Option<&'a str> {
+        self.tags
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| &**value)
+    }
+}
+
+/// The error a statement failed with, kept until it's observed.
+#[derive(Debug)]
+pub(crate) struct StatementError {
+    code: Option<String>,
+    message: String,
+}
+
+impl StatementError {
+    pub(crate) fn new(error: &Error) -> Self {
+        Self {
+            code: error
+                .as_database_error()
+                .and_then(|error| error.code())
+                .map(|code| code.into_owned()),
+            message: error.to_string(),
+        }
+    }
+}
+
+pub(crate) fn is_observed() -> bool {
+    OBSERVED.load(Ordering::Acquire)
+}
+
+pub(crate) fn notify(event: &StatementEvent<'_>) {
+    let observer = OBSERVER
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(Arc::clone);
+
+    if let Some(observer) = observer {
+        observer(event);
+    }
+}
+
+pub(crate) fn is_transaction_observed() -> bool {
+    TRANSACTIONS_OBSERVED.load(Ordering::Acquire)
+}
+
+pub(crate) fn notify_transaction(event: &TransactionEvent<'_>) {
+    let observer = TRANSACTION_OBSERVER
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(Arc::clone);
+
+    if let Some(observer) = observer {
+        observer(event);
+    }
+}
+
+/// The tags of the current scope.
+pub(crate) fn current_tags() -> Option<Tags> {
+    TAGS.with(|tags| tags.borrow().clone())
+}
+
+/// Makes `tags` the tags of the current thread, e.g. of the worker thread executing the
+/// statements of a task, until the guard is dropped.
+pub(crate) fn enter(tags: Option<Tags>) -> TagsGuard {
+    TagsGuard(TAGS.with(|current| current.replace(tags)))
+}
+
+pub(crate) struct TagsGuard(Option<Tags>);
+
+impl Drop for TagsGuard {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+
+        TAGS.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+#[test]
+fn test_with_tags() {
+    use futures_util::FutureExt;
+
+    let tags = with_tags([("tenant", "acme"), ("route", "/")], async {
+        with_tags([("route", "/users")], async { current_tags() }).await
+    })
+    .now_or_never()
+    .unwrap();
+
+    assert_eq!(
+        tags.as_deref(),
+        Some(
+            &[
+                ("tenant".to_owned(), "acme".to_owned()),
+                ("route".to_owned(), "/users".to_owned())
+            ][..]
+        )
+    );
+
+    assert!(current_tags().is_none());
+}
@@ -11,6 +11,7 @@ use crate::error::Error;
 
 use super::inner::{DecrementSizeGuard, PoolInner};
 use crate::pool::options::PoolConnectionMetadata;
+use crate::pool::watchdog::{TransactionWatchdog, WatchedTransaction};
 use std::future::Future;
 
 /// A connection managed by a [`Pool`][crate::pool::Pool].
@@ -39,6 +40,13 @@ pub(super) struct Floating<DB: Database, C> {
 
 const EXPECT_MSG: &str = "BUG: inner connection already taken!";
 
+impl<DB: Database> PoolConnection<DB> {
+    // watches a transaction begun on the connection, if the pool watches them
+    pub(crate) fn watch_transaction(&self) -> Option<WatchedTransaction> {
+        self.pool.watchdog.as_ref().map(TransactionWatchdog::watch)
+    }
+}
+
 impl<DB: Database> Debug for PoolConnection<DB> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         // TODO: Show the type name of the connection ?
@@ -134,13 +142,7 @@ impl<DB: Database> Drop for PoolConnection<DB> {
     fn drop(&mut self) {
         // We still need to spawn a task to maintain `min_connections`.
         if self.live.is_some() || self.pool.options.min_connections > 0 {
-            #[cfg(not(feature = "_rt-async-std"))]
-            if let Ok(handle) = sqlx_rt::Handle::try_current() {
-                handle.spawn(self.return_to_pool());
-            }
-
-            #[cfg(feature = "_rt-async-std")]
-            sqlx_rt::spawn(self.return_to_pool());
+            crate::runtime::spawn_from_drop(self.return_to_pool());
         }
     }
 }
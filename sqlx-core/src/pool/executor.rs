@@ -15,6 +15,15 @@ where
 {
     type Database = DB;
 
+    fn execute<'e, 'q: 'e, E: 'q>(self, query: E) -> BoxFuture<'e, Result<DB::QueryResult, Error>>
+    where
+        E: Execute<'q, Self::Database>,
+    {
+        let pool = self.clone();
+
+        Box::pin(async move { pool.acquire().await?.execute(query).await })
+    }
+
     fn fetch_many<'e, 'q: 'e, E: 'q>(
         self,
         query: E,
@@ -36,6 +45,24 @@ where
         })
     }
 
+    fn fetch_all<'e, 'q: 'e, E: 'q>(self, query: E) -> BoxFuture<'e, Result<Vec<DB::Row>, Error>>
+    where
+        E: Execute<'q, Self::Database>,
+    {
+        let pool = self.clone();
+
+        Box::pin(async move { pool.acquire().await?.fetch_all(query).await })
+    }
+
+    fn fetch_one<'e, 'q: 'e, E: 'q>(self, query: E) -> BoxFuture<'e, Result<DB::Row, Error>>
+    where
+        E: Execute<'q, Self::Database>,
+    {
+        let pool = self.clone();
+
+        Box::pin(async move { pool.acquire().await?.fetch_one(query).await })
+    }
+
     fn fetch_optional<'e, 'q: 'e, E: 'q>(
         self,
         query: E,
@@ -76,6 +103,21 @@ macro_rules! impl_executor_for_pool_connection {
         impl<'c> crate::executor::Executor<'c> for &'c mut crate::pool::PoolConnection<$DB> {
             type Database = $DB;
 
+            #[inline]
+            fn execute<'e, 'q: 'e, E: 'q>(
+                self,
+                query: E,
+            ) -> futures_core::future::BoxFuture<
+                'e,
+                Result<<$DB as crate::database::Database>::QueryResult, crate::error::Error>,
+            >
+            where
+                'c: 'e,
+                E: crate::executor::Execute<'q, $DB>,
+            {
+                (**self).execute(query)
+            }
+
             #[inline]
             fn fetch_many<'e, 'q: 'e, E: 'q>(
                 self,
@@ -94,6 +136,42 @@ macro_rules! impl_executor_for_pool_connection {
                 (**self).fetch_many(query)
             }
 
+            #[inline]
+            fn fetch<'e, 'q: 'e, E: 'q>(
+                self,
+                query: E,
+            ) -> futures_core::stream::BoxStream<'e, Result<$R, crate::error::Error>>
+            where
+                'c: 'e,
+                E: crate::executor::Execute<'q, $DB>,
+            {
+                (**self).fetch(query)
+            }
+
+            #[inline]
+            fn fetch_all<'e, 'q: 'e, E: 'q>(
+                self,
+                query: E,
+            ) -> futures_core::future::BoxFuture<'e, Result<Vec<$R>, crate::error::Error>>
+            where
+                'c: 'e,
+                E: crate::executor::Execute<'q, $DB>,
+            {
+                (**self).fetch_all(query)
+            }
+
+            #[inline]
+            fn fetch_one<'e, 'q: 'e, E: 'q>(
+                self,
+                query: E,
+            ) -> futures_core::future::BoxFuture<'e, Result<$R, crate::error::Error>>
+            where
+                'c: 'e,
+                E: crate::executor::Execute<'q, $DB>,
+            {
+                (**self).fetch_one(query)
+            }
+
             #[inline]
             fn fetch_optional<'e, 'q: 'e, E: 'q>(
                 self,
@@ -0,0 +1,146 @@
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::connection::Connection;
+use crate::database::Database;
+use crate::executor::Executor;
+use crate::pool::Pool;
+
+/// The health of a pool, reported by [`Pool::health_check()`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct PoolHealth {
+    /// Whether a connection was acquired and checked within the timeout of the check.
+    pub healthy: bool,
+
+    /// The time the check took, including acquiring the connection.
+    pub latency: Duration,
+
+    /// The number of connections of the pool, idle or not, once the check is done.
+    pub size: u32,
+
+    /// The number of idle connections of the pool, once the check is done.
+    pub num_idle: usize,
+
+    /// The [maximum number of connections][crate::pool::PoolOptions::max_connections] of the
+    /// pool.
+    pub max_connections: u32,
+
+    /// The error the check failed with, if it failed.
+    pub error: Option<String>,
+
+    /// The error of the last check which failed, this one or a previous one.
+    pub last_error: Option<HealthCheckError>,
+}
+
+impl PoolHealth {
+    /// The fraction of the maximum number of connections in use, from 0 to 1.
+    pub fn utilization(&self) -> f64 {
+        if self.max_connections == 0 {
+            return 0.0;
+        }
+
+        let in_use = (self.size as usize).saturating_sub(self.num_idle);
+
+        in_use as f64 / f64::from(self.max_connections)
+    }
+}
+
+/// The error a [health check][Pool::health_check] failed with.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct HealthCheckError {
+    pub message: String,
+
+    /// When the check failed.
+    pub at: SystemTime,
+}
+
+impl<DB: Database> Pool<DB> {
+    /// Checks the health of the pool, e.g. for the readiness probe of a service: acquires a
+    /// connection and executes the [health check query][crate::pool::PoolOptions::health_check_query]
+    /// on it, or pings it, within `timeout`.
+    ///
+    /// A failed check is reported with the state of the pool rather than returned as an error.
+    ///
+    /// ```rust,ignore
+    /// let health = pool.health_check(Duration::from_secs(1)).await;
+    ///
+    /// if !health.healthy {
+    ///     return (StatusCode::SERVICE_UNAVAILABLE, health.error.unwrap_or_default());
+    /// }
+    /// ```
+    pub async fn health_check(&self, timeout: Duration) -> PoolHealth
+    where
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    {
+        let start = Instant::now();
+
+        let check = async {
+            let mut conn = self.acquire().await?;
+
+            match &self.0.options.health_check_query {
+                Some(sql) => conn.execute(&**sql).await.map(drop),
+                None => conn.ping().await,
+            }
+        };
+
+        let error = match crate::runtime::timeout(timeout, check).await {
+            Ok(Ok(())) => None,
+            Ok(Err(error)) => Some(error.to_string()),
+            Err(_) => Some(format!("timed out after {:?}", timeout)),
+        };
+
+        let latency = start.elapsed();
+
+        let last_error = {
+            let mut last_error = self
+                .0
+                .last_health_error
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+
+            if let Some(message) = &error {
+                *last_error = Some(HealthCheckError {
+                    message: message.clone(),
+                    at: SystemTime::now(),
+                });
+            }
+
+            last_error.clone()
+        };
+
+        PoolHealth {
+            healthy: error.is_none(),
+            latency,
+            size: self.size(),
+            num_idle: self.num_idle(),
+            max_connections: self.0.options.max_connections,
+            error,
+            last_error,
+        }
+    }
+}
+
+#[test]
+fn test_utilization() {
+    let health = PoolHealth {
+        healthy: true,
+        latency: Duration::ZERO,
+        size: 6,
+        num_idle: 2,
+        max_connections: 10,
+        error: None,
+        last_error: None,
+    };
+
+    assert_eq!(health.utilization(), 0.4);
+
+    assert_eq!(
+        PoolHealth {
+            max_connections: 0,
+            ..health
+        }
+        .utilization(),
+        0.0
+    );
+}
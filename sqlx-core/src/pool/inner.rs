@@ -3,7 +3,7 @@ use crate::connection::ConnectOptions;
 use crate::connection::Connection;
 use crate::database::Database;
 use crate::error::Error;
-use crate::pool::{deadline_as_timeout, CloseEvent, PoolOptions};
+use crate::pool::{deadline_as_timeout, CloseEvent, HealthCheckError, PoolOptions};
 use crossbeam_queue::ArrayQueue;
 
 use futures_intrusive::sync::{Semaphore, SemaphoreReleaser};
@@ -11,9 +11,10 @@ use futures_intrusive::sync::{Semaphore, SemaphoreReleaser};
 use std::cmp;
 use std::future::Future;
 use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use crate::pool::options::PoolConnectionMetadata;
+use crate::pool::watchdog::TransactionWatchdog;
 use std::time::{Duration, Instant};
 
 /// Ihe number of permits to release to wake all waiters, such as on `PoolInner::close()`.
@@ -31,6 +32,10 @@ pub(crate) struct PoolInner<DB: Database> {
     is_closed: AtomicBool,
     pub(super) on_closed: event_listener::Event,
     pub(super) options: PoolOptions<DB>,
+    pub(super) last_health_error: Mutex<Option<HealthCheckError>>,
+    pub(super) watchdog: Option<Arc<TransactionWatchdog>>,
+    #[cfg(feature = "metrics")]
+    pub(super) metrics: crate::metrics::PoolMetrics,
 }
 
 impl<DB: Database> PoolInner<DB> {
@@ -46,6 +51,10 @@ impl<DB: Database> PoolInner<DB> {
             .checked_add(WAKE_ALL_PERMITS)
             .expect("max_connections exceeds max capacity of the pool");
 
+        let watchdog = options
+            .long_transaction_threshold
+            .map(|threshold| Arc::new(TransactionWatchdog::new(threshold)));
+
         let pool = Self {
             connect_options,
             idle_conns: ArrayQueue::new(capacity),
@@ -55,10 +64,17 @@ impl<DB: Database> PoolInner<DB> {
             is_closed: AtomicBool::new(false),
             on_closed: event_listener::Event::new(),
             options,
+            last_health_error: Mutex::new(None),
+            watchdog,
+            #[cfg(feature = "metrics")]
+            metrics: crate::metrics::PoolMetrics::new(),
         };
 
         let pool = Arc::new(pool);
 
+        #[cfg(feature = "metrics")]
+        crate::metrics::register_pool(Arc::downgrade(&pool) as _);
+
         spawn_maintenance_tasks(&pool);
 
         pool
@@ -178,20 +194,34 @@ impl<DB: Database> PoolInner<DB> {
             return Err(Error::PoolClosed);
         }
 
+        // Fast path: an idle connection that doesn't need to be checked is returned right away.
+        let mut idle = match self.try_acquire() {
+            Some(conn) if is_trusted(&conn, &self.options) => return Ok(conn.into_live()),
+            idle => idle,
+        };
+
         let deadline = Instant::now() + self.options.acquire_timeout;
 
-        sqlx_rt::timeout(
+        crate::runtime::timeout(
             self.options.acquire_timeout,
             async {
                 loop {
-                    let permit = self.semaphore.acquire(1).await;
+                    // First attempt to pop a connection from the idle queue.
+                    let popped = match idle.take() {
+                        // ...unless the fast path already did
+                        Some(conn) => Ok(conn),
+                        None => {
+                            let permit = self.semaphore.acquire(1).await;
 
-                    if self.is_closed() {
-                        return Err(Error::PoolClosed);
-                    }
+                            if self.is_closed() {
+                                return Err(Error::PoolClosed);
+                            }
 
-                    // First attempt to pop a connection from the idle queue.
-                    let guard = match self.pop_idle(permit) {
+                            self.pop_idle(permit)
+                        }
+                    };
+
+                    let guard = match popped {
 
                         // Then, check that we can use it...
                         Ok(conn) => match check_idle_conn(conn, &self.options).await {
@@ -238,7 +268,7 @@ impl<DB: Database> PoolInner<DB> {
 
             // result here is `Result<Result<C, Error>, TimeoutError>`
             // if this block does not return, sleep for the backoff timeout and try again
-            match sqlx_rt::timeout(timeout, self.connect_options.connect()).await {
+            match crate::runtime::timeout(timeout, self.connect_options.connect()).await {
                 // successfully established connection
                 Ok(Ok(mut raw)) => {
                     // See comment on `PoolOptions::after_connect`
@@ -282,7 +312,7 @@ impl<DB: Database> PoolInner<DB> {
             // If the connection is refused, wait in exponentially
             // increasing steps for the server to come up,
             // capped by a factor of the remaining time until the deadline
-            sqlx_rt::sleep(backoff).await;
+            crate::runtime::sleep(backoff).await;
             backoff = cmp::min(backoff * 2, max_backoff);
         }
     }
@@ -334,6 +364,25 @@ impl<DB: Database> PoolInner<DB> {
     }
 }
 
+#[cfg(feature = "metrics")]
+impl<DB: Database> crate::metrics::PoolGauges for PoolInner<DB> {
+    fn metrics(&self) -> &crate::metrics::PoolMetrics {
+        &self.metrics
+    }
+
+    fn size(&self) -> u32 {
+        self.size()
+    }
+
+    fn num_idle(&self) -> usize {
+        self.num_idle()
+    }
+
+    fn max_connections(&self) -> u32 {
+        self.options.max_connections
+    }
+}
+
 /// Returns `true` if the connection has exceeded `options.max_lifetime` if set, `false` otherwise.
 fn is_beyond_max_lifetime<DB: Database>(live: &Live<DB>, options: &PoolOptions<DB>) -> bool {
     options
@@ -348,6 +397,25 @@ fn is_beyond_idle_timeout<DB: Database>(idle: &Idle<DB>, options: &PoolOptions<D
         .map_or(false, |timeout| idle.idle_since.elapsed() > timeout)
 }
 
+/// Returns `true` if the connection should be pinged before it's acquired, unless it was released
+/// within `options.trust_window`.
+fn needs_test<DB: Database>(idle: &Idle<DB>, options: &PoolOptions<DB>) -> bool {
+    options.test_before_acquire
+        && options
+            .trust_window
+            .map_or(true, |window| idle.idle_since.elapsed() >= window)
+}
+
+/// Returns `true` if the connection was released within `options.trust_window`, if set, and can
+/// be used without being checked by `check_idle_conn()`.
+fn is_trusted<DB: Database>(idle: &Idle<DB>, options: &PoolOptions<DB>) -> bool {
+    options
+        .trust_window
+        .map_or(false, |window| idle.idle_since.elapsed() < window)
+        && options.before_acquire.is_none()
+        && !is_beyond_max_lifetime(idle, options)
+}
+
 async fn check_idle_conn<DB: Database>(
     mut conn: Floating<DB, Idle<DB>>,
     options: &PoolOptions<DB>,
@@ -358,7 +426,7 @@ async fn check_idle_conn<DB: Database>(
         return Err(conn.close().await);
     }
 
-    if options.test_before_acquire {
+    if needs_test(&conn, options) {
         // Check that the connection is still live
         if let Err(e) = conn.ping().await {
             // an error here means the other end has hung up or we lost connectivity
@@ -393,6 +461,23 @@ async fn check_idle_conn<DB: Database>(
 }
 
 fn spawn_maintenance_tasks<DB: Database>(pool: &Arc<PoolInner<DB>>) {
+    if let Some(watchdog) = &pool.watchdog {
+        let watchdog = Arc::clone(watchdog);
+        let pool = Arc::clone(pool);
+
+        crate::runtime::spawn(async move {
+            let _ = pool
+                .close_event()
+                .do_until(async {
+                    while !pool.is_closed() {
+                        crate::runtime::sleep(watchdog.period()).await;
+                        watchdog.check();
+                    }
+                })
+                .await;
+        });
+    }
+
     let pool = Arc::clone(&pool);
 
     let period = match (pool.options.max_lifetime, pool.options.idle_timeout) {
@@ -402,7 +487,7 @@ fn spawn_maintenance_tasks<DB: Database>(pool: &Arc<PoolInner<DB>>) {
 
         (None, None) => {
             if pool.options.min_connections > 0 {
-                sqlx_rt::spawn(async move {
+                crate::runtime::spawn(async move {
                     pool.min_connections_maintenance(None).await;
                 });
             }
@@ -411,7 +496,7 @@ fn spawn_maintenance_tasks<DB: Database>(pool: &Arc<PoolInner<DB>>) {
         }
     };
 
-    sqlx_rt::spawn(async move {
+    crate::runtime::spawn(async move {
         // Immediately cancel this task if the pool is closed.
         let _ = pool
             .close_event()
@@ -423,9 +508,9 @@ fn spawn_maintenance_tasks<DB: Database>(pool: &Arc<PoolInner<DB>>) {
 
                     if let Some(duration) = next_run.checked_duration_since(Instant::now()) {
                         // `async-std` doesn't have a `sleep_until()`
-                        sqlx_rt::sleep(duration).await;
+                        crate::runtime::sleep(duration).await;
                     } else {
-                        sqlx_rt::yield_now().await;
+                        crate::runtime::yield_now().await;
                     }
 
                     // Don't run the reaper right away.
@@ -68,7 +68,7 @@ use crate::any::{Any, AnyKind};
 use crate::connection::Connection;
 use crate::database::Database;
 use crate::error::Error;
-use crate::transaction::Transaction;
+use crate::transaction::{Transaction, TransactionOptions};
 use event_listener::EventListener;
 use futures_core::FusedFuture;
 use futures_util::FutureExt;
@@ -86,12 +86,18 @@ mod executor;
 mod maybe;
 
 mod connection;
+mod health;
 mod inner;
 mod options;
+pub(crate) mod retry;
+mod watchdog;
 
 pub use self::connection::PoolConnection;
+pub use self::health::{HealthCheckError, PoolHealth};
 pub(crate) use self::maybe::MaybePoolConnection;
 pub use self::options::{PoolConnectionMetadata, PoolOptions};
+pub use self::retry::RetryPolicy;
+pub(crate) use self::watchdog::WatchedTransaction;
 
 /// An asynchronous pool of SQLx database connections.
 ///
@@ -340,7 +346,20 @@ impl<DB: Database> Pool<DB> {
     /// returning it.
     pub fn acquire(&self) -> impl Future<Output = Result<PoolConnection<DB>, Error>> + 'static {
         let shared = self.0.clone();
-        async move { shared.acquire().await.map(|conn| conn.reattach()) }
+
+        async move {
+            #[cfg(feature = "metrics")]
+            let start = Instant::now();
+
+            let conn = shared.acquire().await;
+
+            #[cfg(feature = "metrics")]
+            shared
+                .metrics
+                .record_acquire(start.elapsed(), matches!(conn, Err(Error::PoolTimedOut)));
+
+            conn.map(|conn| conn.reattach())
+        }
     }
 
     /// Attempts to retrieve a connection from the pool if there is one available.
@@ -353,13 +372,25 @@ impl<DB: Database> Pool<DB> {
 
     /// Retrieves a connection and immediately begins a new transaction.
     pub async fn begin(&self) -> Result<Transaction<'static, DB>, Error> {
-        Ok(Transaction::begin(MaybePoolConnection::PoolConnection(self.acquire().await?)).await?)
+        Ok(Transaction::start(MaybePoolConnection::PoolConnection(self.acquire().await?)).await?)
+    }
+
+    /// Retrieves a connection and immediately begins a new transaction with `options`.
+    pub async fn begin_with(
+        &self,
+        options: TransactionOptions,
+    ) -> Result<Transaction<'static, DB>, Error> {
+        Transaction::start_with(
+            MaybePoolConnection::PoolConnection(self.acquire().await?),
+            options,
+        )
+        .await
     }
 
     /// Attempts to retrieve a connection and immediately begins a new transaction if successful.
     pub async fn try_begin(&self) -> Result<Option<Transaction<'static, DB>>, Error> {
         match self.try_acquire() {
-            Some(conn) => Transaction::begin(MaybePoolConnection::PoolConnection(conn))
+            Some(conn) => Transaction::start(MaybePoolConnection::PoolConnection(conn))
                 .await
                 .map(Some),
 
@@ -44,6 +44,7 @@ use std::time::{Duration, Instant};
 #[derive(Clone)]
 pub struct PoolOptions<DB: Database> {
     pub(crate) test_before_acquire: bool,
+    pub(crate) trust_window: Option<Duration>,
     pub(crate) after_connect: Option<
         Arc<
             dyn Fn(&mut DB::Connection, PoolConnectionMetadata) -> BoxFuture<'_, Result<(), Error>>
@@ -80,6 +81,8 @@ pub struct PoolOptions<DB: Database> {
     pub(crate) max_lifetime: Option<Duration>,
     pub(crate) idle_timeout: Option<Duration>,
     pub(crate) fair: bool,
+    pub(crate) health_check_query: Option<String>,
+    pub(crate) long_transaction_threshold: Option<Duration>,
 }
 
 /// Metadata for the connection being processed by a [`PoolOptions`] callback.
@@ -118,6 +121,7 @@ impl<DB: Database> PoolOptions<DB> {
             before_acquire: None,
             after_release: None,
             test_before_acquire: true,
+            trust_window: None,
             // A production application will want to set a higher limit than this.
             max_connections: 10,
             min_connections: 0,
@@ -125,6 +129,8 @@ impl<DB: Database> PoolOptions<DB> {
             idle_timeout: Some(Duration::from_secs(10 * 60)),
             max_lifetime: Some(Duration::from_secs(30 * 60)),
             fair: true,
+            health_check_query: None,
+            long_transaction_threshold: None,
         }
     }
 
@@ -221,6 +227,44 @@ impl<DB: Database> PoolOptions<DB> {
         self
     }
 
+    /// Set how long a connection is trusted after it was returned to the pool: a connection
+    /// idle for less than this isn't tested before it's returned by `acquire()`, even with
+    /// [`test_before_acquire`][Self::test_before_acquire].
+    ///
+    /// Such a connection is also handed out as soon as `acquire()` is polled, without waiting in
+    /// the queue of tasks acquiring a connection, as long as none are waiting and no
+    /// [`before_acquire`][Self::before_acquire] callback is set, whether or not
+    /// `test_before_acquire` is enabled. A connection dropped by the server in the meantime fails
+    /// the first query run on it instead.
+    ///
+    /// Defaults to `None`: connections are always tested, and always acquired through the queue.
+    pub fn test_before_acquire_trust_window(mut self, window: impl Into<Option<Duration>>) -> Self {
+        self.trust_window = window.into();
+        self
+    }
+
+    /// Set the statement executed by [`Pool::health_check()`] on a connection of the pool, e.g.
+    /// a query of a table the application can't run without.
+    ///
+    /// Defaults to `None`: the connection is checked with [`Connection::ping`].
+    pub fn health_check_query(mut self, sql: impl Into<Option<String>>) -> Self {
+        self.health_check_query = sql.into();
+        self
+    }
+
+    /// Set the time a transaction begun by the pool, e.g. with [`Pool::begin()`], can be open
+    /// for before a warning is logged, with the statements it executed, so that a transaction
+    /// which was forgotten, and holds its locks, is found.
+    ///
+    /// The warning includes where the transaction was begun if backtraces are enabled, with
+    /// `RUST_LIB_BACKTRACE=1`, and the debug info needed to resolve them.
+    ///
+    /// Defaults to `None`: the transactions aren't watched.
+    pub fn long_transaction_threshold(mut self, threshold: impl Into<Option<Duration>>) -> Self {
+        self.long_transaction_threshold = threshold.into();
+        self
+    }
+
     /// If set to `true`, calls to `acquire()` are fair and connections  are issued
     /// in first-come-first-serve order. If `false`, "drive-by" tasks may steal idle connections
     /// ahead of tasks that have been waiting.
@@ -478,6 +522,12 @@ impl<DB: Database> Debug for PoolOptions<DB> {
             .field("max_lifetime", &self.max_lifetime)
             .field("idle_timeout", &self.idle_timeout)
             .field("test_before_acquire", &self.test_before_acquire)
+            .field("trust_window", &self.trust_window)
+            .field("health_check_query", &self.health_check_query)
+            .field(
+                "long_transaction_threshold",
+                &self.long_transaction_threshold,
+            )
             .finish()
     }
 }
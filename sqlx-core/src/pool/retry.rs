@@ -0,0 +1,200 @@
+use std::cmp;
+use std::time::Duration;
+
+use futures_core::future::BoxFuture;
+
+use crate::database::Database;
+use crate::error::Error;
+use crate::pool::Pool;
+use crate::transaction::{Transaction, TransactionOptions};
+
+/// How [`Pool::transaction_with_retry()`] retries the transactions which conflict with others,
+/// as does `PgConnection::transaction_with_retry()`.
+///
+/// By default, a transaction is attempted up to 5 times, and the retries are delayed by an
+/// exponential backoff, from 10ms up to 1s.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    options: TransactionOptions,
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(10),
+            max_delay: Duration::from_secs(1),
+            options: TransactionOptions::default(),
+        }
+    }
+
+    /// The number of times the transaction is attempted, including the first one; at least 1.
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = cmp::max(attempts, 1);
+        self
+    }
+
+    /// The delay before the first retry, doubled for each next one.
+    pub fn base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    /// The longest delay before a retry.
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// The options the transaction begins with, e.g. the `SERIALIZABLE` isolation level.
+    pub fn transaction_options(mut self, options: TransactionOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    // the delay before retrying after `err` failed the attempt `attempt`, from 1, if it's retried
+    fn retry_delay(&self, attempt: u32, err: &Error) -> Option<Duration> {
+        let conflict = matches!(err, Error::Database(err) if err.is_transaction_conflict());
+
+        if attempt < self.max_attempts && conflict {
+            Some(self.delay(attempt))
+        } else {
+            None
+        }
+    }
+
+    // the delay before the retry following the failed attempt `attempt`, from 1
+    fn delay(&self, attempt: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt - 1));
+
+        cmp::min(delay, self.max_delay)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<DB: Database> Pool<DB> {
+    /// Execute the function inside a transaction, and commit it, retrying the whole transaction
+    /// when it conflicts with another one, i.e. when the function or the commit fails with a
+    /// [serialization failure or a deadlock][crate::error::DatabaseError::is_transaction_conflict].
+    ///
+    /// The function is called again for each attempt, so it shouldn't have side effects outside
+    /// of the transaction. The other errors, and the conflict of the last attempt, are returned
+    /// as is, after rolling back the transaction.
+    ///
+    /// ```rust,ignore
+    /// let policy = RetryPolicy::new()
+    ///     .transaction_options(TransactionOptions::new().isolation(IsolationLevel::Serializable));
+    ///
+    /// pool.transaction_with_retry(&policy, |tx| {
+    ///     Box::pin(async move {
+    ///         tx.execute("UPDATE accounts SET balance = balance - 10 WHERE id = 1").await?;
+    ///         tx.execute("UPDATE accounts SET balance = balance + 10 WHERE id = 2").await?;
+    ///
+    ///         Ok(())
+    ///     })
+    /// })
+    /// .await?;
+    /// ```
+    pub async fn transaction_with_retry<F, R>(
+        &self,
+        policy: &RetryPolicy,
+        callback: F,
+    ) -> Result<R, Error>
+    where
+        for<'c> F: FnMut(&'c mut Transaction<'_, DB>) -> BoxFuture<'c, Result<R, Error>> + Send,
+        R: Send,
+    {
+        fn begin<'b, DB: Database>(
+            pool: &'b mut Pool<DB>,
+            options: TransactionOptions,
+        ) -> BoxFuture<'b, Result<Transaction<'b, DB>, Error>> {
+            Box::pin(async move {
+                let transaction: Transaction<'b, DB> = pool.begin_with(options).await?;
+                Ok(transaction)
+            })
+        }
+
+        transaction_with_retry(&mut self.clone(), begin, policy, callback).await
+    }
+}
+
+/// Execute `callback` inside a transaction begun on `conn` by `begin`, and commit it, retrying
+/// the whole transaction as `policy` says.
+pub(crate) async fn transaction_with_retry<C, DB, F, R>(
+    conn: &mut C,
+    begin: for<'b> fn(
+        &'b mut C,
+        TransactionOptions,
+    ) -> BoxFuture<'b, Result<Transaction<'b, DB>, Error>>,
+    policy: &RetryPolicy,
+    mut callback: F,
+) -> Result<R, Error>
+where
+    DB: Database,
+    for<'c> F: FnMut(&'c mut Transaction<'_, DB>) -> BoxFuture<'c, Result<R, Error>>,
+{
+    let mut attempt = 1;
+
+    loop {
+        let mut transaction = begin(&mut *conn, policy.options).await?;
+
+        let ret = match callback(&mut transaction).await {
+            Ok(ret) => transaction.commit().await.map(|_| ret),
+            Err(err) => {
+                // the error of the callback is the one to report
+                if let Err(rollback_err) = transaction.rollback().await {
+                    log::warn!(
+                        "failed to roll back transaction attempt {}: {}",
+                        attempt,
+                        rollback_err
+                    );
+                }
+
+                Err(err)
+            }
+        };
+
+        let err = match ret {
+            Err(err) => err,
+            ret => return ret,
+        };
+
+        match policy.retry_delay(attempt, &err) {
+            Some(delay) => {
+                log::debug!(
+                    "transaction attempt {} conflicted, retrying in {:?}: {}",
+                    attempt,
+                    delay,
+                    err
+                );
+
+                crate::runtime::sleep(delay).await;
+
+                attempt += 1;
+            }
+
+            None => return Err(err),
+        }
+    }
+}
+
+#[test]
+fn test_delay() {
+    let policy = RetryPolicy::new();
+
+    assert_eq!(policy.delay(1), Duration::from_millis(10));
+    assert_eq!(policy.delay(2), Duration::from_millis(20));
+    assert_eq!(policy.delay(4), Duration::from_millis(80));
+    assert_eq!(policy.delay(8), Duration::from_secs(1));
+    assert_eq!(policy.delay(100), Duration::from_secs(1));
+}
@@ -0,0 +1,138 @@
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::cmp;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::HashMap;
+
+/// The open transactions of a pool, watched for those open for longer than
+/// [`PoolOptions::long_transaction_threshold`][crate::pool::PoolOptions::long_transaction_threshold].
+pub(crate) struct TransactionWatchdog {
+    threshold: Duration,
+    next_id: AtomicU64,
+    transactions: Mutex<HashMap<u64, OpenTransaction>>,
+}
+
+struct OpenTransaction {
+    started: Instant,
+    statements: Arc<AtomicU64>,
+    // where the transaction was begun, captured if `RUST_LIB_BACKTRACE` or `RUST_BACKTRACE` is set
+    backtrace: Backtrace,
+    warned: bool,
+}
+
+/// A transaction watched by a [`TransactionWatchdog`] until it's dropped.
+pub(crate) struct WatchedTransaction {
+    watchdog: Arc<TransactionWatchdog>,
+    id: u64,
+    statements: Arc<AtomicU64>,
+}
+
+impl TransactionWatchdog {
+    pub(crate) fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            next_id: AtomicU64::new(0),
+            transactions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn watch(self: &Arc<Self>) -> WatchedTransaction {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let statements = Arc::new(AtomicU64::new(0));
+
+        self.lock().insert(
+            id,
+            OpenTransaction {
+                started: Instant::now(),
+                statements: Arc::clone(&statements),
+                backtrace: Backtrace::capture(),
+                warned: false,
+            },
+        );
+
+        WatchedTransaction {
+            watchdog: Arc::clone(self),
+            id,
+            statements,
+        }
+    }
+
+    // the period of the checks, so that a transaction is reported soon after the threshold
+    pub(crate) fn period(&self) -> Duration {
+        cmp::max(self.threshold / 4, Duration::from_millis(10))
+    }
+
+    /// Warns of the transactions open for longer than the threshold, once each.
+    pub(crate) fn check(&self) {
+        for warning in self.overdue(Instant::now()) {
+            log::warn!("{}", warning);
+        }
+    }
+
+    fn overdue(&self, now: Instant) -> Vec<String> {
+        let mut transactions = self.lock();
+
+        transactions
+            .values_mut()
+            .filter(|tx| !tx.warned && now.saturating_duration_since(tx.started) > self.threshold)
+            .map(|tx| {
+                tx.warned = true;
+
+                let mut warning = format!(
+                    "transaction open for {:?}, longer than {:?}, with {} statements executed",
+                    now.saturating_duration_since(tx.started),
+                    self.threshold,
+                    tx.statements.load(Ordering::Relaxed)
+                );
+
+                if tx.backtrace.status() == BacktraceStatus::Captured {
+                    warning += &format!("; begun at:\n{}", tx.backtrace);
+                }
+
+                warning
+            })
+            .collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<u64, OpenTransaction>> {
+        self.transactions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl WatchedTransaction {
+    pub(crate) fn set_statements(&self, statements: u64) {
+        self.statements.store(statements, Ordering::Relaxed);
+    }
+}
+
+impl Drop for WatchedTransaction {
+    fn drop(&mut self) {
+        self.watchdog.lock().remove(&self.id);
+    }
+}
+
+#[test]
+fn test_overdue() {
+    let watchdog = Arc::new(TransactionWatchdog::new(Duration::from_secs(60)));
+
+    let first = watchdog.watch();
+    let second = watchdog.watch();
+    first.set_statements(3);
+
+    let now = Instant::now();
+    assert!(watchdog.overdue(now).is_empty());
+
+    let warnings = watchdog.overdue(now + Duration::from_secs(61));
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings
+        .iter()
+        .any(|warning| warning.contains("with 3 statements executed")));
+
+    // each transaction is reported once
+    assert!(watchdog.overdue(now + Duration::from_secs(120)).is_empty());
+
+    drop((first, second));
+    assert!(watchdog.lock().is_empty());
+}
@@ -1,5 +1,7 @@
 use crate::error::Result;
-use crate::postgres::PgConnection;
+use crate::pool::PoolConnection;
+use crate::postgres::{PgConnection, PgPool, Postgres};
+use crate::transaction::Transaction;
 use crate::Either;
 use hkdf::Hkdf;
 use once_cell::sync::OnceCell;
@@ -16,13 +18,13 @@ use std::ops::{Deref, DerefMut};
 /// advisory locks use, as well as RAII guards for releasing advisory locks when they fall out
 /// of scope.
 ///
-/// This API only handles session-scoped advisory locks (explicitly locked and unlocked, or
-/// automatically released when a connection is closed).
+/// Session-scoped advisory locks are explicitly locked and unlocked, or automatically released
+/// when a connection is closed. [`acquire_from_pool()`][Self::acquire_from_pool] holds one on a
+/// connection of a pool dedicated to it, e.g. so that only one worker of a service does a job.
 ///
-/// It is also possible to use transaction-scoped locks but those can be used by beginning a
-/// transaction and calling the appropriate lock functions (e.g. `SELECT pg_advisory_xact_lock()`)
-/// manually, and cannot be explicitly released, but are automatically released when a transaction
-/// ends (is committed or rolled back).
+/// Transaction-scoped locks, taken by [`acquire_xact()`][Self::acquire_xact], cannot be
+/// explicitly released, but are automatically released when the transaction ends (is committed
+/// or rolled back).
 ///
 /// Session-level locks can be acquired either inside or outside a transaction and are not
 /// tied to transaction semantics; a lock acquired inside a transaction is still held when that
@@ -269,6 +271,94 @@ impl PgAdvisoryLock {
         }
     }
 
+    /// Acquires an exclusive lock on a connection of `pool` dedicated to it, waiting until the
+    /// lock is acquired, as [`Self::acquire()`] does.
+    ///
+    /// The connection is returned to the pool once the guard is dropped, which releases the lock.
+    ///
+    /// ```rust,ignore
+    /// let lock = PgAdvisoryLock::new("send-newsletter");
+    ///
+    /// // only one worker sends the newsletter at a time
+    /// let mut guard = lock.acquire_from_pool(&pool).await?;
+    ///
+    /// send_newsletter(&mut *guard).await?;
+    ///
+    /// guard.release_now().await?;
+    /// ```
+    pub async fn acquire_from_pool(
+        &self,
+        pool: &PgPool,
+    ) -> Result<PgAdvisoryLockGuard<'_, PoolConnection<Postgres>>> {
+        self.acquire(pool.acquire().await?).await
+    }
+
+    /// Acquires an exclusive lock on a connection of `pool` dedicated to it, returning `None`
+    /// immediately if the lock could not be acquired, as [`Self::try_acquire()`] does.
+    ///
+    /// The connection is returned to the pool if the lock could not be acquired, or once the guard
+    /// is dropped, which releases the lock.
+    pub async fn try_acquire_from_pool(
+        &self,
+        pool: &PgPool,
+    ) -> Result<Option<PgAdvisoryLockGuard<'_, PoolConnection<Postgres>>>> {
+        Ok(self.try_acquire(pool.acquire().await?).await?.left())
+    }
+
+    /// Acquires an exclusive, transaction-scoped lock using `pg_advisory_xact_lock()`, waiting
+    /// until the lock is acquired.
+    ///
+    /// The lock is held until the transaction is committed or rolled back; taken in a savepoint,
+    /// it's held even if the savepoint is rolled back.
+    ///
+    /// See [Postgres' documentation for the Advisory Lock Functions][advisory-funcs] for details.
+    ///
+    /// [advisory-funcs]: https://www.postgresql.org/docs/current/functions-admin.html#FUNCTIONS-ADVISORY-LOCKS
+    pub async fn acquire_xact(&self, tx: &mut Transaction<'_, Postgres>) -> Result<()> {
+        match &self.key {
+            PgAdvisoryLockKey::BigInt(key) => {
+                crate::query::query("SELECT pg_advisory_xact_lock($1)")
+                    .bind(key)
+                    .execute(&mut **tx)
+                    .await?;
+            }
+            PgAdvisoryLockKey::IntPair(key1, key2) => {
+                crate::query::query("SELECT pg_advisory_xact_lock($1, $2)")
+                    .bind(key1)
+                    .bind(key2)
+                    .execute(&mut **tx)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Acquires an exclusive, transaction-scoped lock using `pg_try_advisory_xact_lock()`,
+    /// returning `false` immediately if the lock could not be acquired.
+    ///
+    /// The lock is held until the transaction is committed or rolled back, as with
+    /// [`Self::acquire_xact()`].
+    pub async fn try_acquire_xact(&self, tx: &mut Transaction<'_, Postgres>) -> Result<bool> {
+        let locked = match &self.key {
+            PgAdvisoryLockKey::BigInt(key) => {
+                crate::query_scalar::query_scalar("SELECT pg_try_advisory_xact_lock($1)")
+                    .bind(key)
+                    .fetch_one(&mut **tx)
+                    .await?
+            }
+            PgAdvisoryLockKey::IntPair(key1, key2) => {
+                crate::query_scalar::query_scalar("SELECT pg_try_advisory_xact_lock($1, $2)")
+                    .bind(key1)
+                    .bind(key2)
+                    .fetch_one(&mut **tx)
+                    .await?
+            }
+        };
+
+        Ok(locked)
+    }
+
     /// Execute `pg_advisory_unlock()` for this lock's key on the given connection.
     ///
     /// This is used by [`PgAdvisoryLockGuard::release_now()`] and is also provided for manually
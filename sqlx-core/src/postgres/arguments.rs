@@ -2,6 +2,7 @@ use std::fmt::{self, Write};
 use std::ops::{Deref, DerefMut};
 
 use crate::arguments::Arguments;
+use crate::buffer_pool;
 use crate::encode::{Encode, IsNull};
 use crate::error::Error;
 use crate::ext::ustr::UStr;
@@ -18,7 +19,6 @@ use crate::types::Type;
 //            that has a patch, we then apply the patch which should write to &mut Vec<u8>,
 //            backtrack and update the prefixed-len, then write until the next patch offset
 
-#[derive(Default)]
 pub struct PgArgumentBuffer {
     buffer: Vec<u8>,
 
@@ -73,6 +73,11 @@ impl PgArguments {
         self.buffer.count += 1;
     }
 
+    // Whether some arguments can only be encoded once the server described the parameters
+    pub(crate) fn has_patches(&self) -> bool {
+        !self.buffer.patches.is_empty()
+    }
+
     // Apply patches
     // This should only go out and ask postgres if we have not seen the type name yet
     pub(crate) async fn apply_patches(
@@ -168,6 +173,23 @@ impl PgArgumentBuffer {
     }
 }
 
+impl Default for PgArgumentBuffer {
+    fn default() -> Self {
+        Self {
+            buffer: buffer_pool::take_write_buf(),
+            count: 0,
+            patches: Vec::new(),
+            type_holes: Vec::new(),
+        }
+    }
+}
+
+impl Drop for PgArgumentBuffer {
+    fn drop(&mut self) {
+        buffer_pool::recycle_write_buf(std::mem::take(&mut self.buffer));
+    }
+}
+
 impl Deref for PgArgumentBuffer {
     type Target = Vec<u8>;
 
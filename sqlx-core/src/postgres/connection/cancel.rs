@@ -0,0 +1,74 @@
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use crate::error::Error;
+use crate::postgres::connection::{stream::PgStream, tls};
+use crate::postgres::message::CancelRequest;
+use crate::postgres::PgConnectOptions;
+
+// https://www.postgresql.org/docs/current/protocol-flow.html#id-1.10.5.7.9
+
+/// A handle to cancel the statement running on a [`PgConnection`][super::PgConnection], from
+/// another task; returned by [`PgConnection::cancel_token()`][super::PgConnection::cancel_token].
+///
+/// [`cancel()`][Self::cancel] opens a new connection to the server of the connection, to send
+/// it the process ID and secret key of the backend. The server then interrupts the statement
+/// which is running on that backend, if any, and frees its resources; the statement fails with
+/// a `query_canceled` (`57014`) error.
+///
+/// Cancelling is best-effort: the server doesn't reply to the request, so it can't be known if a
+/// statement was cancelled, and the statement may complete before the request is processed.
+#[derive(Clone)]
+pub struct PgCancelToken {
+    options: Arc<PgConnectOptions>,
+    process_id: u32,
+    secret_key: u32,
+}
+
+impl PgCancelToken {
+    pub(super) fn new(options: Arc<PgConnectOptions>, process_id: u32, secret_key: u32) -> Self {
+        Self {
+            options,
+            process_id,
+            secret_key,
+        }
+    }
+
+    /// The process ID of the backend of the connection, i.e. the value of
+    /// `pg_backend_pid()` in its session.
+    pub fn process_id(&self) -> u32 {
+        self.process_id
+    }
+
+    /// Asks the server to cancel the statement running on the connection, if any.
+    pub async fn cancel(&self) -> Result<(), Error> {
+        let mut stream = PgStream::connect(&self.options).await?;
+
+        // the request is sent over TLS, like the connection, if it was asked for
+        tls::maybe_upgrade(&mut stream, &self.options).await?;
+
+        stream
+            .send(CancelRequest {
+                process_id: self.process_id,
+                secret_key: self.secret_key,
+            })
+            .await?;
+
+        // the server closes the connection once it has processed the request, without a reply;
+        // like libpq, wait for it, so the statement has been interrupted when this returns
+        let _ = stream.read_raw(1).await;
+
+        Ok(())
+    }
+}
+
+impl Debug for PgCancelToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // the secret key is a credential
+        f.debug_struct("PgCancelToken")
+            .field("host", &self.options.host)
+            .field("port", &self.options.port)
+            .field("process_id", &self.process_id)
+            .finish()
+    }
+}
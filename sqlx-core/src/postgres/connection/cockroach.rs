@@ -0,0 +1,122 @@
+use futures_core::future::BoxFuture;
+
+use crate::connection::{Connection, ServerVersion};
+use crate::error::Error;
+use crate::pool::retry::transaction_with_retry;
+use crate::pool::RetryPolicy;
+use crate::postgres::{PgConnection, Postgres};
+use crate::transaction::Transaction;
+
+impl PgConnection {
+    /// Whether the server is a [CockroachDB](https://www.cockroachlabs.com/) node rather
+    /// than PostgreSQL.
+    ///
+    /// CockroachDB reports itself with the `crdb_version` parameter on startup. For its
+    /// connections, SQLx skips the `EXPLAIN` queries it uses to infer the nullability of
+    /// the columns in `describe()` (and so in the `query!()` macros), as CockroachDB doesn't
+    /// support `EXPLAIN (VERBOSE, FORMAT JSON)`.
+    ///
+    /// See the [module documentation][crate::postgres#cockroachdb] for the other differences.
+    pub fn is_cockroachdb(&self) -> bool {
+        self.stream.parameter_statuses.contains_key("crdb_version")
+    }
+
+    /// The version of CockroachDB, if the server is a CockroachDB node.
+    ///
+    /// [`server_version()`][Connection::server_version] is the version of PostgreSQL that
+    /// CockroachDB claims to be compatible with instead, e.g. `13.0.0`.
+    pub fn cockroachdb_version(&self) -> Option<ServerVersion> {
+        self.stream
+            .parameter_statuses
+            .get("crdb_version")
+            .and_then(|version| parse_cockroachdb_version(version))
+    }
+
+    /// Execute the function inside a transaction, retrying the whole transaction when it fails
+    /// with a `serialization_failure` (SQLSTATE `40001`) or a deadlock, up to `max_retries`
+    /// times.
+    ///
+    /// This is the client-side retry protocol of CockroachDB, which runs every transaction at
+    /// the `SERIALIZABLE` isolation level and asks the client to retry the transactions that
+    /// conflict with others with a "restart transaction" error, including at `COMMIT`. It's
+    /// as useful for `SERIALIZABLE` transactions in PostgreSQL.
+    ///
+    /// The function is called again for each attempt, so it shouldn't have side effects outside
+    /// of the transaction. The retries are delayed by the exponential backoff of the default
+    /// [`RetryPolicy`]. The other errors, and the last retryable one, are returned as is, after
+    /// rolling back the transaction; see [`Pool::transaction_with_retry()`] for other policies.
+    ///
+    /// [`Pool::transaction_with_retry()`]: crate::pool::Pool::transaction_with_retry
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::error::Error;
+    /// # use sqlx_core::executor::Executor;
+    /// # use sqlx_core::postgres::PgConnection;
+    /// # async fn f(conn: &mut PgConnection) -> Result<(), Error> {
+    /// conn.transaction_with_retry(5, |tx| {
+    ///     Box::pin(async move {
+    ///         tx.execute("UPDATE accounts SET balance = balance - 10 WHERE id = 1")
+    ///             .await?;
+    ///         tx.execute("UPDATE accounts SET balance = balance + 10 WHERE id = 2")
+    ///             .await?;
+    ///
+    ///         Ok(())
+    ///     })
+    /// })
+    /// .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn transaction_with_retry<'a, F, R>(
+        &'a mut self,
+        max_retries: u32,
+        callback: F,
+    ) -> BoxFuture<'a, Result<R, Error>>
+    where
+        for<'c> F: FnMut(&'c mut Transaction<'_, Postgres>) -> BoxFuture<'c, Result<R, Error>>
+            + 'a
+            + Send
+            + Sync,
+        R: Send,
+    {
+        let policy = RetryPolicy::new().max_attempts(max_retries.saturating_add(1));
+
+        Box::pin(async move {
+            transaction_with_retry(self, PgConnection::begin_with, &policy, callback).await
+        })
+    }
+}
+
+// e.g. `CockroachDB CCL v22.1.6 (x86_64-pc-linux-gnu, built 2022/08/23 17:05:04, go1.17.11)`
+fn parse_cockroachdb_version(s: &str) -> Option<ServerVersion> {
+    let version = s
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix('v'))?;
+
+    // ignore the pre-release suffix of e.g. `v22.2.0-beta.1`
+    let version = version.split('-').next()?;
+    let mut parts = version.split('.').map(str::parse::<u32>);
+
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().and_then(Result::ok).unwrap_or(0);
+    let patch = parts.next().and_then(Result::ok).unwrap_or(0);
+
+    Some(ServerVersion::new(major, minor, patch))
+}
+
+#[test]
+fn test_parse_cockroachdb_version() {
+    assert_eq!(
+        parse_cockroachdb_version(
+            "CockroachDB CCL v22.1.6 (x86_64-pc-linux-gnu, built 2022/08/23 17:05:04, go1.17.11)"
+        ),
+        Some(ServerVersion::new(22, 1, 6))
+    );
+
+    assert_eq!(
+        parse_cockroachdb_version("CockroachDB OSS v22.2.0-beta.1 (aarch64-apple-darwin21.2)"),
+        Some(ServerVersion::new(22, 2, 0))
+    );
+
+    assert_eq!(parse_cockroachdb_version("CockroachDB"), None);
+}
@@ -1,5 +1,6 @@
 use crate::error::Error;
 use crate::ext::ustr::UStr;
+use crate::postgres::connection::nullability::{Explain, Nullability, PlanInfo};
 use crate::postgres::message::{ParameterDescription, RowDescription};
 use crate::postgres::statement::PgStatementMetadata;
 use crate::postgres::type_info::{PgCustomType, PgType, PgTypeKind};
@@ -145,6 +146,11 @@ impl PgConnection {
         Ok(params)
     }
 
+    // whether the type info of `oid` is known without asking the database
+    pub(super) fn is_type_info_known(&self, oid: Oid) -> bool {
+        PgTypeInfo::try_from_oid(oid).is_some() || self.cache_type_info.contains_key(&oid)
+    }
+
     async fn maybe_fetch_type_info_by_oid(
         &mut self,
         oid: Oid,
@@ -404,7 +410,7 @@ SELECT oid FROM pg_catalog.pg_type WHERE typname ILIKE $1
             .await?;
 
         // if it's cockroachdb skip this step #1248
-        if !self.stream.parameter_statuses.contains_key("crdb_version") {
+        if !self.is_cockroachdb() {
             // patch up our null inference with data from EXPLAIN
             let nullable_patch = self
                 .nullables_from_explain(stmt_id, meta.parameters.len())
@@ -420,8 +426,9 @@ SELECT oid FROM pg_catalog.pg_type WHERE typname ILIKE $1
 
     /// Infer nullability for columns of this statement using EXPLAIN VERBOSE.
     ///
-    /// This currently only marks columns that are on the inner half of an outer join
-    /// and returns `None` for all others.
+    /// This marks columns from relations null-extended by an outer join as nullable, and infers
+    /// the nullability of expressions from that of their operands (see [`super::nullability`]).
+    /// Returns `None` for the columns it can't infer the nullability of.
     async fn nullables_from_explain(
         &mut self,
         stmt_id: Oid,
@@ -449,58 +456,50 @@ SELECT oid FROM pg_catalog.pg_type WHERE typname ILIKE $1
             explain += ")";
         }
 
-        let (Json([explain]),): (Json<[Explain; 1]>,) = query_as(&explain).fetch_one(self).await?;
+        let (Json([explain]),): (Json<[Explain; 1]>,) =
+            query_as(&explain).fetch_one(&mut *self).await?;
 
-        let mut nullables = Vec::new();
+        let outputs = match &explain.plan.output {
+            Some(outputs) => outputs,
+            None => return Ok(Vec::new()),
+        };
 
-        if let Some(outputs) = &explain.plan.output {
-            nullables.resize(outputs.len(), None);
-            visit_plan(&explain.plan, outputs, &mut nullables);
-        }
+        let info = PlanInfo::from_plan(&explain.plan);
 
-        Ok(nullables)
-    }
-}
+        // whether the columns of the tables in the plan are `NOT NULL`
+        let mut not_nulls: HashMap<&(String, String), HashMap<String, bool>> = HashMap::new();
 
-fn visit_plan(plan: &Plan, outputs: &[String], nullables: &mut Vec<Option<bool>>) {
-    if let Some(plan_outputs) = &plan.output {
-        // all outputs of a Full Join must be marked nullable
-        // otherwise, all outputs of the inner half of an outer join must be marked nullable
-        if plan.join_type.as_deref() == Some("Full")
-            || plan.parent_relation.as_deref() == Some("Inner")
-        {
-            for output in plan_outputs {
-                if let Some(i) = outputs.iter().position(|o| o == output) {
-                    // N.B. this may produce false positives but those don't cause runtime errors
-                    nullables[i] = Some(true);
-                }
+        for table in info.tables.values() {
+            if not_nulls.contains_key(table) {
+                continue;
             }
-        }
-    }
 
-    if let Some(plans) = &plan.plans {
-        if let Some("Left") | Some("Right") = plan.join_type.as_deref() {
-            for plan in plans {
-                visit_plan(plan, outputs, nullables);
-            }
+            let columns: Vec<(String, bool)> = query_as(
+                "SELECT attname::text, attnotnull FROM pg_catalog.pg_attribute \
+                 WHERE attrelid = format('%I.%I', $1::text, $2::text)::regclass \
+                   AND attnum > 0 AND NOT attisdropped",
+            )
+            .bind(&table.0)
+            .bind(&table.1)
+            .fetch_all(&mut *self)
+            .await?;
+
+            not_nulls.insert(table, columns.into_iter().collect());
         }
-    }
-}
 
-#[derive(serde::Deserialize)]
-struct Explain {
-    #[serde(rename = "Plan")]
-    plan: Plan,
-}
+        let column = |alias: &str, column: &str| {
+            let not_null = *not_nulls.get(info.tables.get(alias)?)?.get(column)?;
 
-#[derive(serde::Deserialize)]
-struct Plan {
-    #[serde(rename = "Join Type")]
-    join_type: Option<String>,
-    #[serde(rename = "Parent Relationship")]
-    parent_relation: Option<String>,
-    #[serde(rename = "Output")]
-    output: Option<Vec<String>>,
-    #[serde(rename = "Plans")]
-    plans: Option<Vec<Plan>>,
+            Some(if not_null {
+                Nullability::NotNull
+            } else {
+                Nullability::Nullable
+            })
+        };
+
+        Ok(outputs
+            .iter()
+            .map(|output| info.infer(output, &column).to_option())
+            .collect())
+    }
 }
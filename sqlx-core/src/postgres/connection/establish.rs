@@ -1,20 +1,135 @@
 use crate::HashMap;
+use std::borrow::Cow;
+use std::io;
+use std::iter;
+use std::sync::Arc;
 
 use crate::common::StatementCache;
+use crate::connection::Connection;
 use crate::error::Error;
+use crate::ext::async_stream::BufferLimits;
 use crate::io::Decode;
-use crate::postgres::connection::{sasl, stream::PgStream, tls};
+use crate::postgres::connection::{sasl, stream::PgStream, tls, PgCancelToken};
 use crate::postgres::message::{
     Authentication, BackendKeyData, MessageFormat, Password, ReadyForQuery, Startup,
 };
 use crate::postgres::types::Oid;
-use crate::postgres::{PgConnectOptions, PgConnection};
+use crate::postgres::{PgConnectOptions, PgConnection, PgTargetSessionAttrs};
+use crate::query_scalar::query_scalar;
 
 // https://www.postgresql.org/docs/current/protocol-flow.html#id-1.10.5.7.3
 // https://www.postgresql.org/docs/current/protocol-flow.html#id-1.10.5.7.11
 
 impl PgConnection {
     pub(crate) async fn establish(options: &PgConnectOptions) -> Result<Self, Error> {
+        if options.fallback_hosts.is_empty()
+            && options.target_session_attrs == PgTargetSessionAttrs::Any
+        {
+            return Self::establish_host(options).await;
+        }
+
+        // like libpq, try the hosts in order until one accepts the connection and its session
+        // has the requested attributes
+        // https://www.postgresql.org/docs/current/libpq-connect.html#LIBPQ-MULTIPLE-HOSTS
+
+        let hosts = iter::once(Cow::Borrowed(options)).chain(options.fallback_hosts.iter().map(
+            |(host, port)| {
+                let mut options = options.clone();
+                options.host = host.clone();
+                options.port = *port;
+                options.socket = None;
+                options.fallback_hosts = Vec::new();
+
+                Cow::Owned(options)
+            },
+        ));
+        let hosts: Vec<_> = hosts.collect();
+
+        let mut attrs = options.target_session_attrs;
+        let mut failures = Vec::new();
+
+        loop {
+            for host in &hosts {
+                let mut conn = match Self::establish_host(host).await {
+                    Ok(conn) => conn,
+                    Err(error) if hosts.len() == 1 => return Err(error),
+                    Err(error) => {
+                        failures.push(format!("{}:{}: {}", host.host, host.port, error));
+                        continue;
+                    }
+                };
+
+                match conn.mismatched_session_attrs(attrs).await {
+                    Ok(None) => return Ok(conn),
+                    Ok(Some(mismatch)) => {
+                        let _ = conn.close().await;
+                        failures.push(format!("{}:{}: {}", host.host, host.port, mismatch));
+                    }
+                    Err(error) if hosts.len() == 1 => return Err(error),
+                    Err(error) => {
+                        failures.push(format!("{}:{}: {}", host.host, host.port, error));
+                    }
+                }
+            }
+
+            if attrs != PgTargetSessionAttrs::PreferStandby {
+                break;
+            }
+
+            // no standby; any host will do
+            attrs = PgTargetSessionAttrs::Any;
+        }
+
+        Err(Error::Io(io::Error::new(
+            io::ErrorKind::Other,
+            format!(
+                "no host accepted the connection with target_session_attrs={}: {}",
+                options.target_session_attrs,
+                failures.join("; ")
+            ),
+        )))
+    }
+
+    /// Tell why the session doesn't have the attributes `attrs`, if it doesn't.
+    async fn mismatched_session_attrs(
+        &mut self,
+        attrs: PgTargetSessionAttrs,
+    ) -> Result<Option<&'static str>, Error> {
+        Ok(match attrs {
+            PgTargetSessionAttrs::Any => None,
+
+            PgTargetSessionAttrs::ReadWrite | PgTargetSessionAttrs::ReadOnly => {
+                let read_only: String = query_scalar("SHOW transaction_read_only")
+                    .fetch_one(&mut *self)
+                    .await?;
+
+                match (attrs, &*read_only) {
+                    (PgTargetSessionAttrs::ReadWrite, "on") => Some("session is read-only"),
+                    (PgTargetSessionAttrs::ReadOnly, "off") => Some("session is not read-only"),
+                    _ => None,
+                }
+            }
+
+            PgTargetSessionAttrs::Primary
+            | PgTargetSessionAttrs::Standby
+            | PgTargetSessionAttrs::PreferStandby => {
+                let in_recovery: bool = query_scalar("SELECT pg_is_in_recovery()")
+                    .fetch_one(&mut *self)
+                    .await?;
+
+                match (attrs, in_recovery) {
+                    (PgTargetSessionAttrs::Primary, true) => Some("server is in hot standby mode"),
+                    (
+                        PgTargetSessionAttrs::Standby | PgTargetSessionAttrs::PreferStandby,
+                        false,
+                    ) => Some("server is not in hot standby mode"),
+                    _ => None,
+                }
+            }
+        })
+    }
+
+    async fn establish_host(options: &PgConnectOptions) -> Result<Self, Error> {
         let mut stream = PgStream::connect(options).await?;
 
         // Upgrade to TLS if we were asked to and the server supports it
@@ -46,6 +161,25 @@ impl PgConnection {
             params.push(("options", options));
         }
 
+        for (key, value) in &options.startup_params {
+            // the names of parameters are case-insensitive
+            match params.iter_mut().find(|(k, _)| k.eq_ignore_ascii_case(key)) {
+                // the values are decoded as ISO dates and UTF-8 text
+                Some(("DateStyle" | "client_encoding", _)) => {
+                    log::warn!("ignoring startup parameter {}={}", key, value);
+                }
+                Some((_, v)) => *v = value,
+                None => params.push((key.as_str(), value.as_str())),
+            }
+        }
+
+        // Starts a walsender in logical replication mode, which accepts replication commands
+        // as well as SQL (only through simple queries).
+        #[cfg(feature = "postgres-replication")]
+        if options.replication {
+            params.push(("replication", "database"));
+        }
+
         stream
             .send(Startup {
                 username: Some(&options.username),
@@ -102,6 +236,20 @@ impl PgConnection {
                         sasl::authenticate(&mut stream, options, body).await?;
                     }
 
+                    #[cfg(feature = "postgres-gssapi")]
+                    Authentication::Gss => {
+                        super::gss::authenticate(&mut stream, options).await?;
+                    }
+
+                    #[cfg(not(feature = "postgres-gssapi"))]
+                    Authentication::Gss => {
+                        return Err(Error::Configuration(
+                            "the server requested GSSAPI authentication, \
+                             which requires the `postgres-gssapi` feature"
+                                .into(),
+                        ));
+                    }
+
                     method => {
                         return Err(err_protocol!(
                             "unsupported authentication method: {:?}",
@@ -139,16 +287,26 @@ impl PgConnection {
 
         Ok(PgConnection {
             stream,
-            process_id,
-            secret_key,
+            cancel_token: PgCancelToken::new(Arc::new(options.clone()), process_id, secret_key),
             transaction_status,
             transaction_depth: 0,
+            statement_count: 0,
             pending_ready_for_query_count: 0,
             next_statement_id: Oid(1),
             cache_statement: StatementCache::new(options.statement_cache_capacity),
+            shared_statement_cache: options.shared_statement_cache.clone(),
+            pending_close: Vec::new(),
+            uncached_statements: Vec::new(),
+            max_prepared_statements: options.max_prepared_statements,
+            flush_policy: options.flush_policy,
             cache_type_oid: HashMap::new(),
             cache_type_info: HashMap::new(),
-            log_settings: options.log_settings.clone(),
+            log_settings: options.log_settings.for_connection(process_id.into()),
+            sql_commenter: options.sql_commenter.clone(),
+            row_buffer: BufferLimits {
+                items: options.row_buffer_size,
+                bytes: options.row_buffer_bytes,
+            },
         })
     }
 }
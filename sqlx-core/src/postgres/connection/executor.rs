@@ -3,21 +3,20 @@ use crate::error::Error;
 use crate::executor::{Execute, Executor};
 use crate::logger::QueryLogger;
 use crate::postgres::message::{
-    self, Bind, Close, CommandComplete, DataRow, MessageFormat, ParameterDescription, Parse, Query,
-    RowDescription,
+    self, Bind, Close, CommandComplete, DataRow, Message, MessageFormat, ParameterDescription,
+    Parse, Query, RowDescription,
 };
 use crate::postgres::statement::PgStatementMetadata;
 use crate::postgres::type_info::PgType;
 use crate::postgres::types::Oid;
 use crate::postgres::{
-    statement::PgStatement, PgArguments, PgConnection, PgQueryResult, PgRow, PgTypeInfo,
-    PgValueFormat, Postgres,
+    statement::PgStatement, PgArguments, PgConnection, PgFlushPolicy, PgQueryResult, PgRow,
+    PgTypeInfo, PgValueFormat, Postgres,
 };
 use either::Either;
 use futures_core::future::BoxFuture;
 use futures_core::stream::BoxStream;
-use futures_core::Stream;
-use futures_util::{pin_mut, TryStreamExt};
+use std::collections::VecDeque;
 use std::{borrow::Cow, sync::Arc};
 
 async fn prepare(
@@ -29,23 +28,15 @@ async fn prepare(
     let id = conn.next_statement_id;
     conn.next_statement_id.incr_one();
 
-    // build a list of type OIDs to send to the database in the PARSE command
-    // we have not yet started the query sequence, so we are *safe* to cleanly make
-    // additional queries here to get any missing OIDs
-
-    let mut param_types = Vec::with_capacity(parameters.len());
-
-    for ty in parameters {
-        param_types.push(if let PgType::DeclareWithName(name) = &ty.0 {
-            conn.fetch_type_id_by_name(name).await?
-        } else {
-            ty.0.oid()
-        });
-    }
+    let param_types = resolve_param_types(conn, parameters).await?;
 
     // flush and wait until we are re-ready
     conn.wait_until_ready().await?;
 
+    // close the statements evicted from the cache on the way
+    conn.evict_prepared_statements();
+    let closing = conn.write_pending_close();
+
     // next we send the PARSE command to the server
     conn.stream.write(Parse {
         param_types: &*param_types,
@@ -62,8 +53,10 @@ async fn prepare(
     conn.write_sync();
     conn.stream.flush().await?;
 
+    conn.wait_for_close_complete(closing).await?;
+
     // indicates that the SQL query string is now successfully parsed and has semantic validity
-    let _ = conn
+    let _: () = conn
         .stream
         .recv_expect(MessageFormat::ParseComplete)
         .await?;
@@ -100,6 +93,26 @@ async fn prepare(
     Ok((id, metadata))
 }
 
+// build a list of type OIDs to send to the database in the PARSE command
+// we have not yet started the query sequence, so we are *safe* to cleanly make
+// additional queries here to get any missing OIDs
+async fn resolve_param_types(
+    conn: &mut PgConnection,
+    parameters: &[PgTypeInfo],
+) -> Result<Vec<Oid>, Error> {
+    let mut param_types = Vec::with_capacity(parameters.len());
+
+    for ty in parameters {
+        param_types.push(if let PgType::DeclareWithName(name) = &ty.0 {
+            conn.fetch_type_id_by_name(name).await?
+        } else {
+            ty.0.oid()
+        });
+    }
+
+    Ok(param_types)
+}
+
 async fn recv_desc_params(conn: &mut PgConnection) -> Result<ParameterDescription, Error> {
     conn.stream
         .recv_expect(MessageFormat::ParameterDescription)
@@ -154,6 +167,18 @@ impl PgConnection {
         Ok(())
     }
 
+    // write a CLOSE for each of the statements evicted from the cache since the last query,
+    // returning how many CloseComplete to wait for once they're flushed
+    pub(super) fn write_pending_close(&mut self) -> usize {
+        let count = self.pending_close.len();
+
+        for id in self.pending_close.drain(..) {
+            self.stream.write(Close::Statement(id));
+        }
+
+        count
+    }
+
     pub(crate) fn write_sync(&mut self) {
         self.stream.write(message::Sync);
 
@@ -164,6 +189,8 @@ impl PgConnection {
     async fn get_or_prepare<'a>(
         &mut self,
         sql: &str,
+        // the text sent for the statement, `sql` with its comment if any
+        text: &str,
         parameters: &[PgTypeInfo],
         // should we store the result of this prepare to the cache
         store_to_cache: bool,
@@ -171,47 +198,318 @@ impl PgConnection {
         // a statement object
         metadata: Option<Arc<PgStatementMetadata>>,
     ) -> Result<(Oid, Arc<PgStatementMetadata>), Error> {
-        if let Some(statement) = self.cache_statement.get_mut(sql) {
+        if let Some(statement) = self.cache_statement.get_mut(text) {
             return Ok((*statement).clone());
         }
 
-        let statement = prepare(self, sql, parameters, metadata).await?;
+        let described = metadata.is_none();
+        let metadata = metadata.or_else(|| self.shared_metadata(sql));
+
+        let statement = prepare(self, text, parameters, metadata).await?;
+
+        if described {
+            self.share_metadata(sql, &statement.1);
+        }
 
-        if store_to_cache && self.cache_statement.is_enabled() {
-            if let Some((id, _)) = self.cache_statement.insert(sql, statement.clone()) {
-                self.stream.write(Close::Statement(id));
-                self.write_sync();
+        self.store_to_cache(text, &statement, store_to_cache)
+            .await?;
 
-                self.stream.flush().await?;
+        Ok(statement)
+    }
 
-                self.wait_for_close_complete(1).await?;
-                self.recv_ready_for_query().await?;
+    // the metadata of a statement described by another connection opened with the same options
+    fn shared_metadata(&self, sql: &str) -> Option<Arc<PgStatementMetadata>> {
+        self.shared_statement_cache.as_ref()?.get(sql)
+    }
+
+    fn share_metadata(&self, sql: &str, metadata: &Arc<PgStatementMetadata>) {
+        if let Some(cache) = &self.shared_statement_cache {
+            cache.insert(sql, Arc::clone(metadata));
+        }
+    }
+
+    // remember a statement prepared by the connection; it's only cached if it's persistent
+    async fn store_to_cache(
+        &mut self,
+        sql: &str,
+        statement: &(Oid, Arc<PgStatementMetadata>),
+        persistent: bool,
+    ) -> Result<(), Error> {
+        if !persistent || !self.cache_statement.is_enabled() {
+            // left open until the connection holds too many statements
+            self.uncached_statements.push(statement.0);
+
+            return Ok(());
+        }
+
+        if let Some((id, _)) = self.cache_statement.insert(sql, statement.clone()) {
+            if self.flush_policy == PgFlushPolicy::Coalesce {
+                // closed along with the next prepared query
+                self.pending_close.push(id);
+
+                return Ok(());
             }
+
+            self.stream.write(Close::Statement(id));
+            self.write_sync();
+
+            self.stream.flush().await?;
+
+            self.wait_for_close_complete(1).await?;
+            self.recv_ready_for_query().await?;
         }
 
-        Ok(statement)
+        Ok(())
     }
 
-    async fn run<'e, 'c: 'e, 'q: 'e>(
-        &'c mut self,
+    // once the connection holds as many prepared statements as allowed, queue the statements
+    // which aren't cached to be closed, then the least recently used cached statements
+    fn evict_prepared_statements(&mut self) {
+        let max = match self.max_prepared_statements {
+            Some(max) => max,
+            None => return,
+        };
+
+        if self.cache_statement.len() + self.uncached_statements.len() < max {
+            return;
+        }
+
+        self.pending_close.append(&mut self.uncached_statements);
+
+        while self.cache_statement.len() >= max {
+            match self.cache_statement.remove_lru() {
+                Some((id, _)) => self.pending_close.push(id),
+                None => break,
+            }
+        }
+    }
+
+    // parse, describe and execute a statement in a single round-trip, the first time it's
+    // executed; returns its metadata, and the whole response if it was received ahead of
+    // time to fetch the types of the description from the database; the statement isn't
+    // described if its metadata is already known
+    async fn pipeline(
+        &mut self,
+        sql: &str,
+        text: &str,
+        mut arguments: PgArguments,
+        limit: u8,
+        persistent: bool,
+        metadata: Option<Arc<PgStatementMetadata>>,
+    ) -> Result<(Arc<PgStatementMetadata>, Option<VecDeque<Message>>), Error> {
+        let id = self.next_statement_id;
+        self.next_statement_id.incr_one();
+
+        let param_types = resolve_param_types(self, &arguments.types).await?;
+
+        // without metadata, there are no patches to apply, only type holes to fill
+        let parameters = metadata.as_ref().map_or(&[][..], |m| &*m.parameters);
+        arguments.apply_patches(self, parameters).await?;
+
+        self.wait_until_ready().await?;
+
+        self.evict_prepared_statements();
+        let closing = self.write_pending_close();
+
+        self.stream.write(Parse {
+            param_types: &*param_types,
+            query: text,
+            statement: id,
+        });
+
+        if metadata.is_none() {
+            self.stream.write(message::Describe::Statement(id));
+        }
+
+        self.stream.write(Bind {
+            portal: None,
+            statement: id,
+            formats: &[PgValueFormat::Binary],
+            num_params: arguments.types.len() as i16,
+            params: &*arguments.buffer,
+            result_formats: &[PgValueFormat::Binary],
+        });
+
+        self.stream.write(message::Execute {
+            portal: None,
+            limit: limit.into(),
+        });
+
+        self.write_sync();
+        self.stream.flush().await?;
+
+        self.wait_for_close_complete(closing).await?;
+
+        let _: () = self
+            .stream
+            .recv_expect(MessageFormat::ParseComplete)
+            .await?;
+
+        if let Some(metadata) = metadata {
+            self.store_to_cache(text, &(id, Arc::clone(&metadata)), persistent)
+                .await?;
+
+            return Ok((metadata, None));
+        }
+
+        let parameters = recv_desc_params(self).await?;
+        let rows = recv_desc_rows(self).await?;
+
+        let known = parameters
+            .types
+            .iter()
+            .chain(
+                rows.iter()
+                    .flat_map(|rows| rows.fields.iter().map(|f| &f.data_type_id)),
+            )
+            .all(|oid| self.is_type_info_known(*oid));
+
+        let read_ahead = if known {
+            None
+        } else {
+            // the types are fetched with queries of their own, which can only be sent once
+            // this one is complete: receive its results ahead of time
+            let mut messages = VecDeque::new();
+
+            loop {
+                let message = self.stream.recv().await?;
+
+                if let MessageFormat::ReadyForQuery = message.format {
+                    self.handle_ready_for_query(message)?;
+                    break;
+                }
+
+                messages.push_back(message);
+            }
+
+            Some(messages)
+        };
+
+        let parameters = self.handle_parameter_description(parameters).await?;
+        let (columns, column_names) = self
+            .handle_row_description(rows, read_ahead.is_some())
+            .await?;
+
+        if read_ahead.is_some() {
+            // wait until the queries fetching the types are complete
+            self.wait_until_ready().await?;
+        }
+
+        let metadata = Arc::new(PgStatementMetadata {
+            parameters,
+            columns,
+            column_names,
+        });
+
+        self.share_metadata(sql, &metadata);
+
+        self.store_to_cache(text, &(id, Arc::clone(&metadata)), persistent)
+            .await?;
+
+        Ok((metadata, read_ahead))
+    }
+
+    // send a query, returning the state to receive its response with
+    async fn run<'q>(
+        &mut self,
         query: &'q str,
         arguments: Option<PgArguments>,
         limit: u8,
         persistent: bool,
         metadata_opt: Option<Arc<PgStatementMetadata>>,
-    ) -> Result<impl Stream<Item = Result<Either<PgQueryResult, PgRow>, Error>> + 'e, Error> {
+    ) -> Result<PgResponse<'q>, Error> {
         let mut logger = QueryLogger::new(query, self.log_settings.clone());
+        self.statement_count += 1;
+
+        match self
+            .send_query(query, arguments, limit, persistent, metadata_opt)
+            .await
+        {
+            Ok((format, metadata, read_ahead)) => Ok(PgResponse {
+                logger,
+                format,
+                metadata,
+                read_ahead,
+            }),
+
+            Err(error) => {
+                logger.fail(&error);
+                Err(error)
+            }
+        }
+    }
+
+    // send a query, returning the format and metadata of its rows, and its response if it was
+    // received ahead of time
+    async fn send_query(
+        &mut self,
+        query: &str,
+        arguments: Option<PgArguments>,
+        limit: u8,
+        persistent: bool,
+        metadata_opt: Option<Arc<PgStatementMetadata>>,
+    ) -> Result<
+        (
+            PgValueFormat,
+            Arc<PgStatementMetadata>,
+            Option<VecDeque<Message>>,
+        ),
+        Error,
+    > {
+        // a statement with a trace context in its comment is unique, so it isn't cached
+        let (text, traced) = match &self.sql_commenter {
+            Some(commenter) => commenter.apply(query),
+            None => (Cow::Borrowed(query), false),
+        };
+
+        let persistent = persistent && !traced;
 
         // before we continue, wait until we are "ready" to accept more queries
         self.wait_until_ready().await?;
 
-        let mut metadata: Arc<PgStatementMetadata>;
+        let metadata: Arc<PgStatementMetadata>;
+
+        // the response of the query, if it was received before its rows are asked for
+        let mut read_ahead = None;
+
+        // a statement executed for the first time is parsed along with its execution, unless
+        // its arguments can only be encoded once the server described its parameters
+        let (pipelined, arguments) = match arguments {
+            Some(arguments)
+                if self.flush_policy == PgFlushPolicy::Coalesce
+                    && !self.cache_statement.contains_key(&text) =>
+            {
+                let metadata = metadata_opt.clone().or_else(|| self.shared_metadata(query));
+
+                // the response is read ahead whole if the types of its columns are unknown,
+                // which a limit on the size of the rows read ahead rules out
+                if metadata.is_some()
+                    || (!arguments.has_patches() && self.row_buffer.bytes.is_none())
+                {
+                    (Some((arguments, metadata)), None)
+                } else {
+                    (None, Some(arguments))
+                }
+            }
+
+            arguments => (None, arguments),
+        };
+
+        let format = if let Some((arguments, metadata_opt)) = pipelined {
+            let (metadata_, read_ahead_) = self
+                .pipeline(query, &text, arguments, limit, persistent, metadata_opt)
+                .await?;
+
+            metadata = metadata_;
+            read_ahead = read_ahead_;
 
-        let format = if let Some(mut arguments) = arguments {
+            // prepared statements are binary
+            PgValueFormat::Binary
+        } else if let Some(mut arguments) = arguments {
             // prepare the statement if this our first time executing it
             // always return the statement ID here
             let (statement, metadata_) = self
-                .get_or_prepare(query, &arguments.types, persistent, metadata_opt)
+                .get_or_prepare(query, &text, &arguments.types, persistent, metadata_opt)
                 .await?;
 
             metadata = metadata_;
@@ -251,7 +549,7 @@ impl PgConnection {
             PgValueFormat::Binary
         } else {
             // Query will trigger a ReadyForQuery
-            self.stream.write(Query(query));
+            self.stream.write(Query(&text));
             self.pending_ready_for_query_count += 1;
 
             // metadata starts out as "nothing"
@@ -263,83 +561,161 @@ impl PgConnection {
 
         self.stream.flush().await?;
 
-        Ok(try_stream! {
-            loop {
-                let message = self.stream.recv().await?;
+        Ok((format, metadata, read_ahead))
+    }
 
-                match message.format {
-                    MessageFormat::BindComplete
-                    | MessageFormat::ParseComplete
-                    | MessageFormat::ParameterDescription
-                    | MessageFormat::NoData => {
-                        // harmless messages to ignore
-                    }
-
-                    MessageFormat::CommandComplete => {
-                        // a SQL command completed normally
-                        let cc: CommandComplete = message.decode()?;
-
-                        let rows_affected = cc.rows_affected();
-                        logger.increase_rows_affected(rows_affected);
-                        r#yield!(Either::Left(PgQueryResult {
-                            rows_affected,
-                        }));
-                    }
-
-                    MessageFormat::EmptyQueryResponse => {
-                        // empty query string passed to an unprepared execute
-                    }
-
-                    MessageFormat::RowDescription => {
-                        // indicates that a *new* set of rows are about to be returned
-                        let (columns, column_names) = self
-                            .handle_row_description(Some(message.decode()?), false)
-                            .await?;
-
-                        metadata = Arc::new(PgStatementMetadata {
-                            column_names,
-                            columns,
-                            parameters: Vec::default(),
-                        });
-                    }
-
-                    MessageFormat::DataRow => {
-                        logger.increment_rows_returned();
-
-                        // one of the set of rows returned by a SELECT, FETCH, etc query
-                        let data: DataRow = message.decode()?;
-                        let row = PgRow {
-                            data,
-                            format,
-                            metadata: Arc::clone(&metadata),
-                        };
-
-                        r#yield!(Either::Right(row));
-                    }
-
-                    MessageFormat::ReadyForQuery => {
-                        // processing of the query string is complete
-                        self.handle_ready_for_query(message)?;
-                        break;
-                    }
-
-                    _ => {
-                        return Err(err_protocol!(
-                            "execute: unexpected message: {:?}",
-                            message.format
-                        ));
-                    }
+    // receive the next result or row of the response to a query,
+    // or `None` once the query is complete
+    async fn recv_response(
+        &mut self,
+        response: &mut PgResponse<'_>,
+    ) -> Result<Option<Either<PgQueryResult, PgRow>>, Error> {
+        let step = self.recv_step(response).await;
+
+        if let Err(error) = &step {
+            response.logger.fail(error);
+        }
+
+        step
+    }
+
+    async fn recv_step(
+        &mut self,
+        response: &mut PgResponse<'_>,
+    ) -> Result<Option<Either<PgQueryResult, PgRow>>, Error> {
+        loop {
+            let message = match response.read_ahead.as_mut().map(VecDeque::pop_front) {
+                Some(Some(message)) => message,
+                // its ReadyForQuery was handled when it was received
+                Some(None) => return Ok(None),
+                None => self.stream.recv().await?,
+            };
+
+            match message.format {
+                MessageFormat::BindComplete
+                | MessageFormat::ParseComplete
+                | MessageFormat::ParameterDescription
+                | MessageFormat::NoData
+                | MessageFormat::PortalSuspended => {
+                    // harmless messages to ignore
+                }
+
+                MessageFormat::CommandComplete => {
+                    // a SQL command completed normally
+                    let cc: CommandComplete = message.decode()?;
+
+                    let rows_affected = cc.rows_affected();
+                    response.logger.increase_rows_affected(rows_affected);
+
+                    return Ok(Some(Either::Left(PgQueryResult { rows_affected })));
+                }
+
+                MessageFormat::EmptyQueryResponse => {
+                    // empty query string passed to an unprepared execute
+                }
+
+                MessageFormat::RowDescription => {
+                    // indicates that a *new* set of rows are about to be returned
+                    let (columns, column_names) = self
+                        .handle_row_description(Some(message.decode()?), false)
+                        .await?;
+
+                    response.metadata = Arc::new(PgStatementMetadata {
+                        column_names,
+                        columns,
+                        parameters: Vec::default(),
+                    });
                 }
+
+                MessageFormat::DataRow => {
+                    response.logger.increment_rows_returned();
+
+                    // one of the set of rows returned by a SELECT, FETCH, etc query
+                    let data: DataRow = message.decode()?;
+                    let row = PgRow {
+                        data,
+                        format: response.format,
+                        metadata: Arc::clone(&response.metadata),
+                    };
+
+                    return Ok(Some(Either::Right(row)));
+                }
+
+                MessageFormat::ReadyForQuery => {
+                    // processing of the query string is complete
+                    self.handle_ready_for_query(message)?;
+
+                    return Ok(None);
+                }
+
+                _ => {
+                    return Err(err_protocol!(
+                        "execute: unexpected message: {:?}",
+                        message.format
+                    ));
+                }
+            }
+        }
+    }
+
+    async fn fetch_optional_row<'q, E>(&mut self, mut query: E) -> Result<Option<PgRow>, Error>
+    where
+        E: Execute<'q, Postgres>,
+    {
+        let sql = query.sql();
+        let metadata = query.statement().map(|s| Arc::clone(&s.metadata));
+        let arguments = query.take_arguments();
+        let persistent = query.persistent();
+
+        let mut response = self.run(sql, arguments, 1, persistent, metadata).await?;
+
+        while let Some(step) = self.recv_response(&mut response).await? {
+            if let Either::Right(row) = step {
+                return Ok(Some(row));
             }
+        }
 
-            Ok(())
-        })
+        Ok(None)
     }
 }
 
+// the state of a query whose response is being received
+struct PgResponse<'q> {
+    logger: QueryLogger<'q>,
+    format: PgValueFormat,
+    metadata: Arc<PgStatementMetadata>,
+    // the whole response, if it was received before its rows were asked for
+    read_ahead: Option<VecDeque<Message>>,
+}
+
+// the hot paths are implemented without a stream, so that each call allocates a single future
 impl<'c> Executor<'c> for &'c mut PgConnection {
     type Database = Postgres;
 
+    fn execute<'e, 'q: 'e, E: 'q>(self, mut query: E) -> BoxFuture<'e, Result<PgQueryResult, Error>>
+    where
+        'c: 'e,
+        E: Execute<'q, Self::Database>,
+    {
+        let sql = query.sql();
+        let metadata = query.statement().map(|s| Arc::clone(&s.metadata));
+        let arguments = query.take_arguments();
+        let persistent = query.persistent();
+
+        Box::pin(async move {
+            let mut response = self.run(sql, arguments, 0, persistent, metadata).await?;
+            let mut done = PgQueryResult::default();
+
+            while let Some(step) = self.recv_response(&mut response).await? {
+                if let Either::Left(result) = step {
+                    done.extend(Some(result));
+                }
+            }
+
+            Ok(done)
+        })
+    }
+
     fn fetch_many<'e, 'q: 'e, E: 'q>(
         self,
         mut query: E,
@@ -353,22 +729,27 @@ impl<'c> Executor<'c> for &'c mut PgConnection {
         let arguments = query.take_arguments();
         let persistent = query.persistent();
 
+        let buffer = self.row_buffer;
+        let size_of = |step: &Either<PgQueryResult, PgRow>| {
+            step.as_ref()
+                .right()
+                .map_or(0, |row| row.data.storage.len())
+        };
+
         Box::pin(try_stream! {
-            let s = self.run(sql, arguments, 0, persistent, metadata).await?;
-            pin_mut!(s);
+            @buffer(buffer, size_of)
+
+            let mut response = self.run(sql, arguments, 0, persistent, metadata).await?;
 
-            while let Some(v) = s.try_next().await? {
-                r#yield!(v);
+            while let Some(step) = self.recv_response(&mut response).await? {
+                r#yield!(step);
             }
 
             Ok(())
         })
     }
 
-    fn fetch_optional<'e, 'q: 'e, E: 'q>(
-        self,
-        mut query: E,
-    ) -> BoxFuture<'e, Result<Option<PgRow>, Error>>
+    fn fetch_all<'e, 'q: 'e, E: 'q>(self, mut query: E) -> BoxFuture<'e, Result<Vec<PgRow>, Error>>
     where
         'c: 'e,
         E: Execute<'q, Self::Database>,
@@ -379,19 +760,42 @@ impl<'c> Executor<'c> for &'c mut PgConnection {
         let persistent = query.persistent();
 
         Box::pin(async move {
-            let s = self.run(sql, arguments, 1, persistent, metadata).await?;
-            pin_mut!(s);
+            let mut response = self.run(sql, arguments, 0, persistent, metadata).await?;
+            let mut rows = Vec::new();
 
-            while let Some(s) = s.try_next().await? {
-                if let Either::Right(r) = s {
-                    return Ok(Some(r));
+            while let Some(step) = self.recv_response(&mut response).await? {
+                if let Either::Right(row) = step {
+                    rows.push(row);
                 }
             }
 
-            Ok(None)
+            Ok(rows)
+        })
+    }
+
+    fn fetch_one<'e, 'q: 'e, E: 'q>(self, query: E) -> BoxFuture<'e, Result<PgRow, Error>>
+    where
+        'c: 'e,
+        E: Execute<'q, Self::Database>,
+    {
+        Box::pin(async move {
+            self.fetch_optional_row(query)
+                .await?
+                .ok_or(Error::RowNotFound)
         })
     }
 
+    fn fetch_optional<'e, 'q: 'e, E: 'q>(
+        self,
+        query: E,
+    ) -> BoxFuture<'e, Result<Option<PgRow>, Error>>
+    where
+        'c: 'e,
+        E: Execute<'q, Self::Database>,
+    {
+        Box::pin(self.fetch_optional_row(query))
+    }
+
     fn prepare_with<'e, 'q: 'e>(
         self,
         sql: &'q str,
@@ -403,7 +807,9 @@ impl<'c> Executor<'c> for &'c mut PgConnection {
         Box::pin(async move {
             self.wait_until_ready().await?;
 
-            let (_, metadata) = self.get_or_prepare(sql, parameters, true, None).await?;
+            let (_, metadata) = self
+                .get_or_prepare(sql, sql, parameters, true, None)
+                .await?;
 
             Ok(PgStatement {
                 sql: Cow::Borrowed(sql),
@@ -422,7 +828,7 @@ impl<'c> Executor<'c> for &'c mut PgConnection {
         Box::pin(async move {
             self.wait_until_ready().await?;
 
-            let (stmt_id, metadata) = self.get_or_prepare(sql, &[], true, None).await?;
+            let (stmt_id, metadata) = self.get_or_prepare(sql, sql, &[], true, None).await?;
 
             let nullable = self.get_nullable_for_columns(stmt_id, &metadata).await?;
 
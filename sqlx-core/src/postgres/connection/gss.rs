@@ -0,0 +1,318 @@
+//! GSSAPI (Kerberos) authentication, through the system GSSAPI library: MIT Kerberos or
+//! Heimdal (`libgssapi_krb5`), or the GSS framework on macOS.
+
+// GSSAPI is a C library. All interactions require FFI which is unsafe.
+#![allow(unsafe_code)]
+
+use std::os::raw::{c_int, c_void};
+use std::ptr;
+
+use crate::error::Error;
+use crate::postgres::connection::stream::PgStream;
+use crate::postgres::message::{Authentication, GssResponse, MessageFormat};
+use crate::postgres::PgConnectOptions;
+
+// https://www.postgresql.org/docs/current/protocol-flow.html#id-1.10.5.7.3
+// https://www.rfc-editor.org/rfc/rfc2744
+
+#[allow(non_camel_case_types)]
+type OM_uint32 = u32;
+
+#[repr(C)]
+struct gss_buffer_desc {
+    length: usize,
+    value: *mut c_void,
+}
+
+// the structures of the GSS framework of macOS are packed to 2 bytes
+#[cfg_attr(target_os = "macos", repr(C, packed(2)))]
+#[cfg_attr(not(target_os = "macos"), repr(C))]
+struct gss_OID_desc {
+    length: OM_uint32,
+    elements: *mut c_void,
+}
+
+#[allow(non_camel_case_types)]
+type gss_name_t = *mut c_void;
+#[allow(non_camel_case_types)]
+type gss_ctx_id_t = *mut c_void;
+
+const GSS_S_COMPLETE: OM_uint32 = 0;
+const GSS_S_CONTINUE_NEEDED: OM_uint32 = 1;
+const GSS_C_GSS_CODE: c_int = 1;
+const GSS_C_MECH_CODE: c_int = 2;
+const GSS_C_MUTUAL_FLAG: OM_uint32 = 2;
+
+// the calling and routine errors of a major status
+const GSS_ERROR_MASK: OM_uint32 = 0xffff_0000;
+
+// GSS_C_NT_HOSTBASED_SERVICE, 1.2.840.113554.1.2.1.4, which Heimdal only defines as a macro
+static NT_HOSTBASED_SERVICE: [u8; 10] =
+    [0x2a, 0x86, 0x48, 0x86, 0xf7, 0x12, 0x01, 0x02, 0x01, 0x04];
+
+#[cfg_attr(target_os = "macos", link(name = "GSS", kind = "framework"))]
+#[cfg_attr(not(target_os = "macos"), link(name = "gssapi_krb5"))]
+extern "C" {
+    fn gss_import_name(
+        minor_status: *mut OM_uint32,
+        input_name_buffer: *mut gss_buffer_desc,
+        input_name_type: *mut gss_OID_desc,
+        output_name: *mut gss_name_t,
+    ) -> OM_uint32;
+
+    fn gss_init_sec_context(
+        minor_status: *mut OM_uint32,
+        initiator_cred_handle: *mut c_void,
+        context_handle: *mut gss_ctx_id_t,
+        target_name: gss_name_t,
+        mech_type: *mut gss_OID_desc,
+        req_flags: OM_uint32,
+        time_req: OM_uint32,
+        input_chan_bindings: *mut c_void,
+        input_token: *mut gss_buffer_desc,
+        actual_mech_type: *mut *mut gss_OID_desc,
+        output_token: *mut gss_buffer_desc,
+        ret_flags: *mut OM_uint32,
+        time_rec: *mut OM_uint32,
+    ) -> OM_uint32;
+
+    fn gss_display_status(
+        minor_status: *mut OM_uint32,
+        status_value: OM_uint32,
+        status_type: c_int,
+        mech_type: *mut gss_OID_desc,
+        message_context: *mut OM_uint32,
+        status_string: *mut gss_buffer_desc,
+    ) -> OM_uint32;
+
+    fn gss_release_buffer(minor_status: *mut OM_uint32, buffer: *mut gss_buffer_desc) -> OM_uint32;
+
+    fn gss_release_name(minor_status: *mut OM_uint32, name: *mut gss_name_t) -> OM_uint32;
+
+    fn gss_delete_sec_context(
+        minor_status: *mut OM_uint32,
+        context_handle: *mut gss_ctx_id_t,
+        output_token: *mut gss_buffer_desc,
+    ) -> OM_uint32;
+}
+
+/// Authenticate with the ticket of the Kerberos credentials cache, after the server asked for
+/// GSSAPI authentication with `AuthenticationGSS`.
+pub(crate) async fn authenticate(
+    stream: &mut PgStream,
+    options: &PgConnectOptions,
+) -> Result<(), Error> {
+    let service = options.krb_service_name.as_deref().unwrap_or("postgres");
+
+    // the host of a Unix socket is its directory
+    let host = if options.fetch_socket().is_some() {
+        "localhost"
+    } else {
+        &*options.host
+    };
+
+    let mut context = SecurityContext::new(&format!("{}@{}", service, host))?;
+    let mut input = Vec::new();
+
+    loop {
+        // this may ask the KDC for a service ticket
+        let (returned, step) = sqlx_rt::blocking!(
+            let step = context.step(&input);
+            (context, step)
+        );
+
+        context = returned;
+
+        let (token, complete) = step?;
+
+        if !token.is_empty() {
+            stream.send(GssResponse(&token)).await?;
+        }
+
+        if complete {
+            // the server then sends `AuthenticationOk`
+            return Ok(());
+        }
+
+        input = match stream.recv_expect(MessageFormat::Authentication).await? {
+            Authentication::GssContinue(data) => data.to_vec(),
+
+            message => {
+                return Err(err_protocol!(
+                    "expected AuthenticationGSSContinue but received {:?}",
+                    message
+                ));
+            }
+        };
+    }
+}
+
+/// A GSSAPI security context being established with a service.
+struct SecurityContext {
+    target: gss_name_t,
+    context: gss_ctx_id_t,
+}
+
+// the handles of GSSAPI may be used by another thread, as long as it's one at a time
+unsafe impl Send for SecurityContext {}
+
+impl SecurityContext {
+    fn new(service: &str) -> Result<Self, Error> {
+        let mut minor = 0;
+        let mut target = ptr::null_mut();
+
+        let mut name = gss_buffer_desc {
+            length: service.len(),
+            value: service.as_ptr() as *mut c_void,
+        };
+
+        let mut name_type = gss_OID_desc {
+            length: NT_HOSTBASED_SERVICE.len() as OM_uint32,
+            elements: NT_HOSTBASED_SERVICE.as_ptr() as *mut c_void,
+        };
+
+        // SAFETY: the buffers are only read, and outlive the call
+        let major = unsafe { gss_import_name(&mut minor, &mut name, &mut name_type, &mut target) };
+
+        if major & GSS_ERROR_MASK != 0 {
+            return Err(gss_error(
+                "failed to import the name of the service",
+                major,
+                minor,
+            ));
+        }
+
+        Ok(SecurityContext {
+            target,
+            context: ptr::null_mut(),
+        })
+    }
+
+    /// Process the token of the server, if any; return the token to send to it, and whether
+    /// the context is established.
+    fn step(&mut self, input: &[u8]) -> Result<(Vec<u8>, bool), Error> {
+        let mut minor = 0;
+
+        let mut input = gss_buffer_desc {
+            length: input.len(),
+            value: input.as_ptr() as *mut c_void,
+        };
+
+        let mut output = gss_buffer_desc {
+            length: 0,
+            value: ptr::null_mut(),
+        };
+
+        // SAFETY: the input is only read; the output is allocated by GSSAPI and released below
+        let major = unsafe {
+            gss_init_sec_context(
+                &mut minor,
+                ptr::null_mut(),
+                &mut self.context,
+                self.target,
+                ptr::null_mut(),
+                GSS_C_MUTUAL_FLAG,
+                0,
+                ptr::null_mut(),
+                &mut input,
+                ptr::null_mut(),
+                &mut output,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            )
+        };
+
+        let token = take_buffer(&mut output);
+
+        match major {
+            GSS_S_COMPLETE => Ok((token, true)),
+            GSS_S_CONTINUE_NEEDED => Ok((token, false)),
+            _ => Err(gss_error("GSSAPI authentication failed", major, minor)),
+        }
+    }
+}
+
+impl Drop for SecurityContext {
+    fn drop(&mut self) {
+        let mut minor = 0;
+
+        // SAFETY: the handles were created by GSSAPI, or are null
+        unsafe {
+            if !self.context.is_null() {
+                gss_delete_sec_context(&mut minor, &mut self.context, ptr::null_mut());
+            }
+
+            gss_release_name(&mut minor, &mut self.target);
+        }
+    }
+}
+
+/// Copy and release a buffer allocated by GSSAPI.
+fn take_buffer(buffer: &mut gss_buffer_desc) -> Vec<u8> {
+    if buffer.value.is_null() {
+        return Vec::new();
+    }
+
+    // SAFETY: GSSAPI allocated `length` bytes at `value`
+    let data =
+        unsafe { std::slice::from_raw_parts(buffer.value as *const u8, buffer.length) }.to_vec();
+
+    let mut minor = 0;
+
+    // SAFETY: the buffer was allocated by GSSAPI, and is released once
+    unsafe {
+        gss_release_buffer(&mut minor, buffer);
+    }
+
+    data
+}
+
+/// An error with the messages of the GSSAPI and mechanism (e.g. Kerberos) statuses, like
+/// "No Kerberos credentials available".
+fn gss_error(context: &str, major: OM_uint32, minor: OM_uint32) -> Error {
+    let mut messages = status_messages(major, GSS_C_GSS_CODE);
+    messages.extend(status_messages(minor, GSS_C_MECH_CODE));
+
+    Error::Protocol(format!("{}: {}", context, messages.join(": ")))
+}
+
+fn status_messages(status: OM_uint32, status_type: c_int) -> Vec<String> {
+    let mut messages = Vec::new();
+    let mut message_context = 0;
+
+    loop {
+        let mut minor = 0;
+        let mut message = gss_buffer_desc {
+            length: 0,
+            value: ptr::null_mut(),
+        };
+
+        // SAFETY: the message is allocated by GSSAPI and released by `take_buffer`
+        let major = unsafe {
+            gss_display_status(
+                &mut minor,
+                status,
+                status_type,
+                ptr::null_mut(),
+                &mut message_context,
+                &mut message,
+            )
+        };
+
+        if major & GSS_ERROR_MASK != 0 {
+            break;
+        }
+
+        let message = String::from_utf8_lossy(&take_buffer(&mut message)).into_owned();
+
+        if !message.is_empty() {
+            messages.push(message);
+        }
+
+        if message_context == 0 {
+            break;
+        }
+    }
+
+    messages
+}
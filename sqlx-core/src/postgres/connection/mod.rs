@@ -6,26 +6,36 @@ use futures_core::future::BoxFuture;
 use futures_util::FutureExt;
 
 use crate::common::StatementCache;
-use crate::connection::{Connection, LogSettings};
+use crate::connection::{Connection, LogSettings, ServerCapabilities, ServerVersion};
 use crate::error::Error;
+use crate::ext::async_stream::BufferLimits;
 use crate::ext::ustr::UStr;
 use crate::io::Decode;
 use crate::postgres::message::{
     Close, Message, MessageFormat, Query, ReadyForQuery, Terminate, TransactionStatus,
 };
-use crate::postgres::statement::PgStatementMetadata;
+use crate::postgres::statement::{PgSharedStatementCache, PgStatementMetadata};
 use crate::postgres::types::Oid;
-use crate::postgres::{PgConnectOptions, PgTypeInfo, Postgres};
-use crate::transaction::Transaction;
+use crate::postgres::{PgConnectOptions, PgFlushPolicy, PgNotice, PgTypeInfo, Postgres};
+use crate::sqlcommenter::SqlCommenter;
+use crate::transaction::{Transaction, TransactionOptions};
 
+pub use self::cancel::PgCancelToken;
 pub use self::stream::PgStream;
+pub use self::two_phase::PgPreparedTransaction;
 
+mod cancel;
+mod cockroach;
 pub(crate) mod describe;
 mod establish;
 mod executor;
+#[cfg(feature = "postgres-gssapi")]
+mod gss;
+mod nullability;
 mod sasl;
 mod stream;
 mod tls;
+mod two_phase;
 
 /// A connection to a PostgreSQL database.
 pub struct PgConnection {
@@ -34,15 +44,10 @@ pub struct PgConnection {
     // wrapped in a buffered stream
     pub(crate) stream: PgStream,
 
-    // process id of this backend
+    // process id and secret key of this backend,
+    // with the options to connect to its server,
     // used to send cancel requests
-    #[allow(dead_code)]
-    process_id: u32,
-
-    // secret key of this backend
-    // used to send cancel requests
-    #[allow(dead_code)]
-    secret_key: u32,
+    cancel_token: PgCancelToken,
 
     // sequence of statement IDs for use in preparing statements
     // in PostgreSQL, the statement is prepared to a user-supplied identifier
@@ -51,6 +56,19 @@ pub struct PgConnection {
     // cache statement by query string to the id and columns
     cache_statement: StatementCache<(Oid, Arc<PgStatementMetadata>)>,
 
+    // metadata of the statements prepared by the connections opened with the same options
+    shared_statement_cache: Option<PgSharedStatementCache>,
+
+    // statements evicted from the cache, closed along with the next prepared query
+    pending_close: Vec<Oid>,
+
+    // statements prepared for queries which aren't persistent, left open on the server
+    uncached_statements: Vec<Oid>,
+    max_prepared_statements: Option<usize>,
+
+    // whether the messages of a query are coalesced into a single write
+    flush_policy: PgFlushPolicy,
+
     // cache user-defined types by id <-> info
     cache_type_info: HashMap<Oid, PgTypeInfo>,
     cache_type_oid: HashMap<UStr, Oid>,
@@ -62,7 +80,16 @@ pub struct PgConnection {
     transaction_status: TransactionStatus,
     pub(crate) transaction_depth: usize,
 
+    // the statements executed so far
+    pub(crate) statement_count: u64,
+
     log_settings: LogSettings,
+
+    // the tags of the comment appended to the queries
+    sql_commenter: Option<SqlCommenter>,
+
+    // the rows of a streamed query read ahead of its consumer
+    row_buffer: BufferLimits,
 }
 
 impl PgConnection {
@@ -71,14 +98,70 @@ impl PgConnection {
         self.stream.server_version_num
     }
 
+    /// The current value of a parameter reported by the server, such as `server_version`,
+    /// `application_name` or `TimeZone`; it's updated when the parameter is changed, e.g.
+    /// by `SET`.
+    ///
+    /// See [the list of reported parameters](https://www.postgresql.org/docs/current/protocol-flow.html#PROTOCOL-ASYNC).
+    pub fn parameter_status(&self, name: &str) -> Option<&str> {
+        self.stream.parameter_statuses.get(name).map(String::as_str)
+    }
+
+    /// A handle to cancel the statement running on this connection, from another task.
+    ///
+    /// This is the way to stop a long-running statement on the server, e.g. when it exceeds a
+    /// timeout: dropping the future of a query doesn't stop the statement, which keeps running
+    /// on the server.
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::error::Error;
+    /// # use sqlx_core::executor::Executor;
+    /// # use sqlx_core::postgres::PgConnection;
+    /// # async fn f(conn: &mut PgConnection) -> Result<(), Error> {
+    /// let token = conn.cancel_token();
+    ///
+    /// sqlx_rt::spawn(async move {
+    ///     sqlx_rt::sleep(std::time::Duration::from_secs(5)).await;
+    ///     let _ = token.cancel().await;
+    /// });
+    ///
+    /// // fails with a `query_canceled` error after 5 seconds
+    /// conn.execute("SELECT pg_sleep(60)").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cancel_token(&self) -> PgCancelToken {
+        self.cancel_token.clone()
+    }
+
+    /// Take the notices and warnings sent by the server since the last call, such as the output
+    /// of `RAISE NOTICE` in the functions called by a query.
+    ///
+    /// They're only kept with [`PgConnectOptions::collect_notices()`]; otherwise this returns
+    /// nothing.
+    pub fn take_notices(&mut self) -> Vec<PgNotice> {
+        self.stream
+            .notices
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
     // will return when the connection is ready for another query
+    //
+    // the responses left by the queries that were dropped before completing are discarded here,
+    // along with their errors: these belong to no one anymore
     pub(in crate::postgres) async fn wait_until_ready(&mut self) -> Result<(), Error> {
         if !self.stream.wbuf.is_empty() {
             self.stream.flush().await?;
         }
 
         while self.pending_ready_for_query_count > 0 {
-            let message = self.stream.recv().await?;
+            let message = match self.stream.recv().await {
+                Ok(message) => message,
+                Err(Error::Database(_)) | Err(Error::MessageTooLarge { .. }) => continue,
+                Err(error) => return Err(error),
+            };
 
             if let MessageFormat::ReadyForQuery = message.format {
                 self.handle_ready_for_query(message)?;
@@ -150,6 +233,14 @@ impl Connection for PgConnection {
         })
     }
 
+    fn transaction_depth(&self) -> usize {
+        self.transaction_depth
+    }
+
+    fn statement_count(&self) -> u64 {
+        self.statement_count
+    }
+
     fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         // Users were complaining about this showing up in query statistics on the server.
         // By sending a comment we avoid an error if the connection was in the middle of a rowset
@@ -166,7 +257,17 @@ impl Connection for PgConnection {
     where
         Self: Sized,
     {
-        Transaction::begin(self)
+        Transaction::start(self)
+    }
+
+    fn begin_with(
+        &mut self,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized,
+    {
+        Transaction::start_with(self, options)
     }
 
     fn cached_statements_size(&self) -> usize {
@@ -175,10 +276,13 @@ impl Connection for PgConnection {
 
     fn clear_cached_statements(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
-            let mut cleared = 0_usize;
-
             self.wait_until_ready().await?;
 
+            // the statements which aren't cached are closed as well
+            self.pending_close.append(&mut self.uncached_statements);
+
+            let mut cleared = self.write_pending_close();
+
             while let Some((id, _)) = self.cache_statement.remove_lru() {
                 self.stream.write(Close::Statement(id));
                 cleared += 1;
@@ -196,6 +300,28 @@ impl Connection for PgConnection {
         })
     }
 
+    fn server_version(&self) -> Option<ServerVersion> {
+        // e.g. 140005 for 14.5, or 90601 for 9.6.1
+        self.stream.server_version_num.map(|num| match num {
+            100000.. => ServerVersion::new(num / 10000, num % 10000, 0),
+            _ => ServerVersion::new(num / 10000, num / 100 % 100, num % 100),
+        })
+    }
+
+    fn server_capabilities(&self) -> ServerCapabilities {
+        let num = match self.stream.server_version_num {
+            Some(num) => num,
+            None => return ServerCapabilities::default(),
+        };
+
+        ServerCapabilities {
+            returning: true,
+            json: num >= 90400,
+            common_table_expressions: true,
+            multiranges: num >= 140000 && !self.is_cockroachdb(),
+        }
+    }
+
     #[doc(hidden)]
     fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         self.wait_until_ready().boxed()
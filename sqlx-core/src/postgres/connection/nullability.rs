@@ -0,0 +1,907 @@
+//! Inference of the nullability of the output columns of a query, from its plan as given by
+//! `EXPLAIN (VERBOSE, FORMAT JSON)`.
+//!
+//! The plan is walked to find the relations on the null-extended side of outer joins, wherever
+//! the joins are in the plan. Then each output expression, as deparsed by Postgres, is parsed
+//! just enough to tell whether it can be null: column references are nullable if their column
+//! is, or if their relation is null-extended, literals are never null, `COALESCE()` is not null
+//! if one of its arguments isn't, strict operators and functions are null if one of their
+//! arguments is, and so on. Anything not understood is left unknown.
+
+use std::collections::{HashMap, HashSet};
+
+#[derive(serde::Deserialize)]
+pub(super) struct Explain {
+    #[serde(rename = "Plan")]
+    pub(super) plan: Plan,
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct Plan {
+    #[serde(rename = "Join Type")]
+    join_type: Option<String>,
+    #[serde(rename = "Parent Relationship")]
+    parent_relation: Option<String>,
+    #[serde(rename = "Relation Name")]
+    relation_name: Option<String>,
+    #[serde(rename = "Schema")]
+    schema: Option<String>,
+    #[serde(rename = "Alias")]
+    alias: Option<String>,
+    #[serde(rename = "Output")]
+    pub(super) output: Option<Vec<String>>,
+    #[serde(rename = "Plans")]
+    plans: Option<Vec<Plan>>,
+}
+
+/// Whether an expression can be null.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(super) enum Nullability {
+    NotNull,
+    Nullable,
+    Unknown,
+}
+
+impl Nullability {
+    pub(super) fn to_option(self) -> Option<bool> {
+        match self {
+            Nullability::NotNull => Some(false),
+            Nullability::Nullable => Some(true),
+            Nullability::Unknown => None,
+        }
+    }
+
+    /// The nullability of a strict expression, which is null if any of its arguments is.
+    fn strict(args: impl IntoIterator<Item = Nullability>) -> Nullability {
+        args.into_iter()
+            .fold(Nullability::NotNull, |acc, arg| match (acc, arg) {
+                (Nullability::Nullable, _) | (_, Nullability::Nullable) => Nullability::Nullable,
+                (Nullability::Unknown, _) | (_, Nullability::Unknown) => Nullability::Unknown,
+                _ => Nullability::NotNull,
+            })
+    }
+
+    /// The nullability of an expression like `COALESCE()`, which is only null if all of its
+    /// arguments are.
+    fn any_not_null(args: impl IntoIterator<Item = Nullability>) -> Nullability {
+        args.into_iter()
+            .fold(Nullability::Nullable, |acc, arg| match (acc, arg) {
+                (Nullability::NotNull, _) | (_, Nullability::NotNull) => Nullability::NotNull,
+                (Nullability::Unknown, _) | (_, Nullability::Unknown) => Nullability::Unknown,
+                _ => Nullability::Nullable,
+            })
+    }
+}
+
+/// What is known about the relations in a plan.
+#[derive(Default)]
+pub(super) struct PlanInfo {
+    /// The aliases of the relations (or subqueries) null-extended by an outer join.
+    nullable_aliases: HashSet<String>,
+    /// The outputs of the plan nodes on the null-extended side of an outer join.
+    nullable_outputs: HashSet<String>,
+    /// The tables scanned by the plan, as `(schema, name)` by alias.
+    pub(super) tables: HashMap<String, (String, String)>,
+}
+
+impl PlanInfo {
+    pub(super) fn from_plan(plan: &Plan) -> Self {
+        let mut info = PlanInfo::default();
+        info.visit(plan, false);
+        info
+    }
+
+    /// Returns whether the plan has a null-extended relation.
+    fn visit(&mut self, plan: &Plan, nullable: bool) -> bool {
+        let mut has_nullable = nullable;
+
+        for child in plan.plans.iter().flatten() {
+            // `Left` joins null-extend the inner relation, `Right` joins the outer one
+            let child_nullable = nullable
+                || match (plan.join_type.as_deref(), child.parent_relation.as_deref()) {
+                    (Some("Full"), _) => true,
+                    (Some("Left"), Some("Inner")) => true,
+                    (Some("Right"), Some("Outer")) => true,
+                    _ => false,
+                };
+
+            has_nullable |= self.visit(child, child_nullable);
+        }
+
+        if nullable {
+            self.nullable_outputs
+                .extend(plan.output.iter().flatten().cloned());
+        }
+
+        if let Some(alias) = &plan.alias {
+            // the columns of a subquery may come from a null-extended relation inside it
+            if nullable || (plan.relation_name.is_none() && has_nullable) {
+                self.nullable_aliases.insert(alias.clone());
+            }
+
+            if let (Some(schema), Some(name)) = (&plan.schema, &plan.relation_name) {
+                self.tables
+                    .insert(alias.clone(), (schema.clone(), name.clone()));
+            }
+        }
+
+        has_nullable
+    }
+
+    /// Infer the nullability of an output expression of the plan, where `column` gives the
+    /// nullability of a column of a table by its alias and name, if known.
+    pub(super) fn infer(
+        &self,
+        output: &str,
+        column: &dyn Fn(&str, &str) -> Option<Nullability>,
+    ) -> Nullability {
+        let mut parser = Parser {
+            src: output,
+            tokens: tokenize(output),
+            pos: 0,
+            info: self,
+            column,
+        };
+
+        match parser.expr() {
+            Some(nullability) if parser.pos == parser.tokens.len() => {
+                parser.check_nullable_output(output, nullability)
+            }
+            _ => Nullability::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token<'a> {
+    /// An identifier or keyword, and whether it was quoted.
+    Ident(&'a str, bool),
+    Str,
+    Number,
+    Param,
+    Cast,
+    Op(&'a str),
+    Punct(char),
+}
+
+/// Split a deparsed expression into tokens, with the byte offsets where they start and end.
+fn tokenize(src: &str) -> Vec<(Token<'_>, usize, usize)> {
+    let bytes = src.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    let is_op_char = |b: u8| b"+-*/<>=~!@#%^&|`?".contains(&b);
+    let is_ident_char = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || b >= 0x80;
+
+    // the end of a quoted string or identifier starting at `start`, where doubled quotes are
+    // escapes
+    let quoted_end = |start: usize, quote: u8| {
+        let mut j = start + 1;
+
+        while j < bytes.len() {
+            if bytes[j] == quote {
+                if bytes.get(j + 1) == Some(&quote) {
+                    j += 2;
+                    continue;
+                }
+
+                return j + 1;
+            }
+
+            j += 1;
+        }
+
+        bytes.len()
+    };
+
+    while i < bytes.len() {
+        let start = i;
+
+        let token = match bytes[i] {
+            b if b.is_ascii_whitespace() => {
+                i += 1;
+                continue;
+            }
+
+            b'\'' => {
+                i = quoted_end(i, b'\'');
+                Token::Str
+            }
+
+            // escape string, e.g. `E'\n'`
+            b'E' if bytes.get(i + 1) == Some(&b'\'') => {
+                i = quoted_end(i + 1, b'\'');
+                Token::Str
+            }
+
+            b'"' => {
+                i = quoted_end(i, b'"');
+                Token::Ident(&src[start + 1..i - 1], true)
+            }
+
+            b'$' if bytes.get(i + 1).map_or(false, u8::is_ascii_digit) => {
+                i += 1;
+
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+
+                Token::Param
+            }
+
+            b if b.is_ascii_digit() => {
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'.') {
+                    i += 1;
+                }
+
+                Token::Number
+            }
+
+            b if is_ident_char(b) => {
+                while i < bytes.len() && is_ident_char(bytes[i]) {
+                    i += 1;
+                }
+
+                Token::Ident(&src[start..i], false)
+            }
+
+            b':' if bytes.get(i + 1) == Some(&b':') => {
+                i += 2;
+                Token::Cast
+            }
+
+            b if is_op_char(b) => {
+                while i < bytes.len() && is_op_char(bytes[i]) {
+                    i += 1;
+                }
+
+                Token::Op(&src[start..i])
+            }
+
+            b => {
+                i += 1;
+                Token::Punct(b as char)
+            }
+        };
+
+        tokens.push((token, start, i));
+    }
+
+    tokens
+}
+
+/// Functions that are never null.
+const NOT_NULL_FUNCTIONS: &[&str] = &[
+    "count",
+    "row_number",
+    "rank",
+    "dense_rank",
+    "percent_rank",
+    "cume_dist",
+    "concat",
+    "now",
+    "statement_timestamp",
+    "clock_timestamp",
+    "transaction_timestamp",
+    "random",
+    "gen_random_uuid",
+    "json_build_object",
+    "jsonb_build_object",
+    "json_build_array",
+    "jsonb_build_array",
+];
+
+/// Functions that are only null if all of their arguments are.
+const COALESCING_FUNCTIONS: &[&str] = &["coalesce", "greatest", "least"];
+
+/// Strict functions, which are null if and only if one of their arguments is.
+const STRICT_FUNCTIONS: &[&str] = &[
+    "lower",
+    "upper",
+    "initcap",
+    "length",
+    "char_length",
+    "character_length",
+    "octet_length",
+    "abs",
+    "round",
+    "trunc",
+    "floor",
+    "ceil",
+    "ceiling",
+    "sqrt",
+    "power",
+    "mod",
+    "btrim",
+    "ltrim",
+    "rtrim",
+    "lpad",
+    "rpad",
+    "replace",
+    "reverse",
+    "repeat",
+    "md5",
+    "left",
+    "right",
+    "substr",
+    "substring",
+    "strpos",
+    "split_part",
+    "date_trunc",
+    "date_part",
+    "to_char",
+    "to_date",
+    "to_timestamp",
+    "to_json",
+    "to_jsonb",
+    "row_to_json",
+    "cardinality",
+];
+
+/// Strict operators, which are null if and only if one of their operands is. Operators like
+/// `->>` may also be null for operands that aren't, and so are not included.
+const STRICT_OPERATORS: &[&str] = &[
+    "+", "-", "*", "/", "%", "^", "||", "=", "<>", "!=", "<", ">", "<=", ">=", "~~", "!~~", "~~*",
+    "!~~*", "~", "~*", "!~", "!~*", "&&", "@>", "<@", "&", "|", "#", "<<", ">>",
+];
+
+/// Keywords that Postgres deparses some functions of the current time or user to.
+const NOT_NULL_KEYWORDS: &[&str] = &[
+    "CURRENT_DATE",
+    "CURRENT_TIME",
+    "CURRENT_TIMESTAMP",
+    "LOCALTIME",
+    "LOCALTIMESTAMP",
+    "CURRENT_USER",
+    "CURRENT_ROLE",
+    "SESSION_USER",
+];
+
+/// The words that may follow the first word of the name of a type, as in `double precision`.
+const TYPE_NAME_WORDS: &[&str] = &["precision", "varying", "with", "without", "time", "zone"];
+
+/// A recursive descent parser of deparsed expressions, returning `None` for anything it doesn't
+/// understand.
+struct Parser<'a> {
+    src: &'a str,
+    tokens: Vec<(Token<'a>, usize, usize)>,
+    pos: usize,
+    info: &'a PlanInfo,
+    column: &'a dyn Fn(&str, &str) -> Option<Nullability>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.pos).map(|(token, ..)| token)
+    }
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let token = self.peek().cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, token: Token<'_>) -> bool {
+        if self.peek() == Some(&token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consume the next token if it is the (unquoted) keyword `keyword`.
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Ident(word, false)) if word.eq_ignore_ascii_case(keyword) => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, token: Token<'_>) -> Option<()> {
+        self.eat(token).then(|| ())
+    }
+
+    /// Skip to after the parenthesis or bracket closing the one just consumed.
+    fn skip_group(&mut self) -> Option<()> {
+        let mut depth = 1;
+
+        while depth > 0 {
+            match self.next()? {
+                Token::Punct('(') | Token::Punct('[') => depth += 1,
+                Token::Punct(')') | Token::Punct(']') => depth -= 1,
+                _ => {}
+            }
+        }
+
+        Some(())
+    }
+
+    /// An expression computed on the null-extended side of an outer join is nullable, whatever
+    /// it is.
+    fn check_nullable_output(&self, text: &str, nullability: Nullability) -> Nullability {
+        if self.info.nullable_outputs.contains(text.trim()) {
+            Nullability::Nullable
+        } else {
+            nullability
+        }
+    }
+
+    fn expr(&mut self) -> Option<Nullability> {
+        let mut operands = vec![self.operand()?];
+
+        loop {
+            match self.peek() {
+                Some(Token::Op(op)) => {
+                    let op = *op;
+                    self.pos += 1;
+
+                    let operand = self.operand()?;
+
+                    if STRICT_OPERATORS.contains(&op) {
+                        operands.push(operand);
+                    } else {
+                        operands.push(Nullability::Unknown);
+                    }
+                }
+
+                Some(Token::Ident(word, false)) => match word.to_ascii_uppercase().as_str() {
+                    "AND" | "OR" => {
+                        self.pos += 1;
+                        operands.push(self.operand()?);
+                    }
+
+                    "AT" => {
+                        // `AT TIME ZONE`
+                        self.pos += 1;
+
+                        if !(self.eat_keyword("TIME") && self.eat_keyword("ZONE")) {
+                            return None;
+                        }
+
+                        operands.push(self.operand()?);
+                    }
+
+                    "IS" => {
+                        // `IS [NOT] NULL`, `IS [NOT] DISTINCT FROM ...`, `IS [NOT] TRUE`, etc.
+                        // are never null
+                        self.pos += 1;
+                        self.eat_keyword("NOT");
+
+                        if self.eat_keyword("DISTINCT") {
+                            if !self.eat_keyword("FROM") {
+                                return None;
+                            }
+
+                            self.operand()?;
+                        } else {
+                            match self.next()? {
+                                Token::Ident(_, false) => {}
+                                _ => return None,
+                            }
+                        }
+
+                        operands = vec![Nullability::NotNull];
+                    }
+
+                    "COLLATE" => {
+                        self.pos += 1;
+                        self.name()?;
+                    }
+
+                    _ => break,
+                },
+
+                _ => break,
+            }
+        }
+
+        Some(Nullability::strict(operands))
+    }
+
+    /// An operand with any prefix operators, casts and subscripts.
+    fn operand(&mut self) -> Option<Nullability> {
+        if self.eat_keyword("NOT") {
+            return self.operand();
+        }
+
+        if let Some(Token::Op("-" | "+")) = self.peek() {
+            self.pos += 1;
+            return self.operand();
+        }
+
+        let mut nullability = self.primary()?;
+
+        loop {
+            if self.eat(Token::Cast) {
+                self.type_name()?;
+            } else if self.eat(Token::Punct('[')) {
+                // subscripts out of range are null
+                self.skip_group()?;
+                nullability = Nullability::Nullable;
+            } else {
+                break;
+            }
+        }
+
+        Some(nullability)
+    }
+
+    fn primary(&mut self) -> Option<Nullability> {
+        let start = self.pos;
+
+        match self.next()? {
+            Token::Str | Token::Number => Some(Nullability::NotNull),
+
+            Token::Param => Some(Nullability::Unknown),
+
+            Token::Punct('(') => {
+                let inner = self.expr();
+
+                // a row constructor, or something else not understood
+                if inner.is_none() || !self.eat(Token::Punct(')')) {
+                    self.pos = start + 1;
+                    self.skip_group()?;
+                    return Some(Nullability::Unknown);
+                }
+
+                let text = &self.src[self.tokens[start].2..self.tokens[self.pos - 1].1];
+
+                Some(self.check_nullable_output(text, inner?))
+            }
+
+            Token::Ident(word, false) if word.eq_ignore_ascii_case("NULL") => {
+                Some(Nullability::Nullable)
+            }
+
+            Token::Ident(word, false)
+                if word.eq_ignore_ascii_case("true") || word.eq_ignore_ascii_case("false") =>
+            {
+                Some(Nullability::NotNull)
+            }
+
+            Token::Ident(word, false)
+                if NOT_NULL_KEYWORDS
+                    .iter()
+                    .any(|keyword| word.eq_ignore_ascii_case(keyword)) =>
+            {
+                Some(Nullability::NotNull)
+            }
+
+            Token::Ident(word, false) if word.eq_ignore_ascii_case("CASE") => self.case(),
+
+            Token::Ident(word, false)
+                if word.eq_ignore_ascii_case("ARRAY") && self.eat(Token::Punct('[')) =>
+            {
+                self.skip_group()?;
+                Some(Nullability::NotNull)
+            }
+
+            Token::Ident(first, _) => {
+                let mut path = vec![first];
+
+                while self.eat(Token::Punct('.')) {
+                    match self.next()? {
+                        Token::Ident(name, _) => path.push(name),
+                        // `alias.*`
+                        Token::Op("*") => return Some(Nullability::Unknown),
+                        _ => return None,
+                    }
+                }
+
+                if self.eat(Token::Punct('(')) {
+                    return self.function(&path);
+                }
+
+                match path[..] {
+                    [alias, column] => Some(self.column_ref(alias, column)),
+                    // columns aren't qualified when the query has a single relation
+                    [column] if self.info.tables.len() == 1 => {
+                        let alias = self.info.tables.keys().next().expect("one table");
+
+                        Some(self.column_ref(alias, column))
+                    }
+                    _ => Some(Nullability::Unknown),
+                }
+            }
+
+            _ => None,
+        }
+    }
+
+    fn column_ref(&self, alias: &str, column: &str) -> Nullability {
+        if self.info.nullable_aliases.contains(alias) {
+            return Nullability::Nullable;
+        }
+
+        (self.column)(alias, column).unwrap_or(Nullability::Unknown)
+    }
+
+    /// A function call, after the opening parenthesis.
+    fn function(&mut self, path: &[&str]) -> Option<Nullability> {
+        let args_start = self.pos;
+        let mut args = Vec::new();
+
+        let parsed = (|| {
+            if self.eat(Token::Punct(')')) {
+                return Some(());
+            }
+
+            // `count(*)`, `count(DISTINCT ...)`
+            if !self.eat(Token::Op("*")) {
+                self.eat_keyword("DISTINCT");
+                args.push(self.expr()?);
+
+                while self.eat(Token::Punct(',')) {
+                    args.push(self.expr()?);
+                }
+            }
+
+            self.expect(Token::Punct(')'))
+        })();
+
+        if parsed.is_none() {
+            // e.g. `EXTRACT(year FROM ...)` or an aggregate with `ORDER BY`
+            self.pos = args_start;
+            self.skip_group()?;
+            args = vec![Nullability::Unknown];
+        }
+
+        // `agg(...) FILTER (WHERE ...)`, `func(...) OVER (...)`
+        loop {
+            if self.eat_keyword("FILTER") {
+                self.expect(Token::Punct('('))?;
+                self.skip_group()?;
+            } else if self.eat_keyword("OVER") {
+                if self.eat(Token::Punct('(')) {
+                    self.skip_group()?;
+                } else {
+                    self.name()?;
+                }
+            } else {
+                break;
+            }
+        }
+
+        let name = match path {
+            [name] | ["pg_catalog", name] => name.to_ascii_lowercase(),
+            _ => return Some(Nullability::Unknown),
+        };
+
+        let name = name.as_str();
+
+        Some(if NOT_NULL_FUNCTIONS.contains(&name) {
+            Nullability::NotNull
+        } else if COALESCING_FUNCTIONS.contains(&name) {
+            Nullability::any_not_null(args)
+        } else if STRICT_FUNCTIONS.contains(&name) {
+            Nullability::strict(args)
+        } else if name == "row" {
+            Nullability::NotNull
+        } else {
+            Nullability::Unknown
+        })
+    }
+
+    /// `CASE [<expr>] WHEN <expr> THEN <expr> ... [ELSE <expr>] END`, after `CASE`, which is null
+    /// if any of its results is.
+    fn case(&mut self) -> Option<Nullability> {
+        let mut results = Vec::new();
+
+        if !self.eat_keyword("WHEN") {
+            self.expr()?;
+
+            if !self.eat_keyword("WHEN") {
+                return None;
+            }
+        }
+
+        loop {
+            self.expr()?;
+
+            if !self.eat_keyword("THEN") {
+                return None;
+            }
+
+            results.push(self.expr()?);
+
+            if !self.eat_keyword("WHEN") {
+                break;
+            }
+        }
+
+        if self.eat_keyword("ELSE") {
+            results.push(self.expr()?);
+        } else {
+            results.push(Nullability::Nullable);
+        }
+
+        if !self.eat_keyword("END") {
+            return None;
+        }
+
+        Some(Nullability::strict(results))
+    }
+
+    /// The name of a type in a cast, e.g. `integer`, `public.status`, `double precision`,
+    /// `character varying(10)` or `text[]`.
+    fn type_name(&mut self) -> Option<()> {
+        self.name()?;
+
+        while let Some(Token::Ident(word, false)) = self.peek() {
+            if !TYPE_NAME_WORDS
+                .iter()
+                .any(|type_word| word.eq_ignore_ascii_case(type_word))
+            {
+                break;
+            }
+
+            self.pos += 1;
+        }
+
+        if self.eat(Token::Punct('(')) {
+            self.skip_group()?;
+        }
+
+        while self.eat(Token::Punct('[')) {
+            self.skip_group()?;
+        }
+
+        Some(())
+    }
+
+    /// A possibly qualified name.
+    fn name(&mut self) -> Option<()> {
+        match self.next()? {
+            Token::Ident(..) => {}
+            _ => return None,
+        }
+
+        while self.eat(Token::Punct('.')) {
+            match self.next()? {
+                Token::Ident(..) => {}
+                _ => return None,
+            }
+        }
+
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn infer(info: &PlanInfo, output: &str) -> Nullability {
+        info.infer(output, &|alias, column| match (alias, column) {
+            ("t", "id") | ("t", "text") | ("t2", "id") => Some(Nullability::NotNull),
+            ("t", "owner_id") => Some(Nullability::Nullable),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn it_infers_nullability_of_expressions() {
+        use Nullability::*;
+
+        let info = PlanInfo::default();
+
+        assert_eq!(infer(&info, "t.id"), NotNull);
+        assert_eq!(infer(&info, "id"), Unknown);
+        assert_eq!(infer(&info, "t.owner_id"), Nullable);
+        assert_eq!(infer(&info, "t.unknown"), Unknown);
+        assert_eq!(infer(&info, "'11'::bigint"), NotNull);
+        assert_eq!(infer(&info, "NULL::integer"), Nullable);
+        assert_eq!(infer(&info, "$1"), Unknown);
+        assert_eq!(infer(&info, "COALESCE(t.owner_id, '0'::bigint)"), NotNull);
+        assert_eq!(infer(&info, "COALESCE(t.owner_id, $1)"), Unknown);
+        assert_eq!(infer(&info, "count(*)"), NotNull);
+        assert_eq!(infer(&info, "(count(*))"), NotNull);
+        assert_eq!(infer(&info, "count(DISTINCT t.owner_id)"), NotNull);
+        assert_eq!(infer(&info, "max(t.text)"), Unknown);
+        assert_eq!(infer(&info, "lower(t.text)"), NotNull);
+        assert_eq!(infer(&info, "(t.text || 'x'::text)"), NotNull);
+        assert_eq!(infer(&info, "(t.owner_id + 1)"), Nullable);
+        assert_eq!(
+            infer(&info, "((t.id)::text)::character varying(10)"),
+            NotNull
+        );
+        assert_eq!(
+            infer(&info, "(t.created_at)::timestamp with time zone"),
+            Unknown
+        );
+        assert_eq!(infer(&info, "(t.owner_id IS NULL)"), NotNull);
+        assert_eq!(infer(&info, "(t.data ->> 'key'::text)"), Unknown);
+        assert_eq!(infer(&info, "CURRENT_TIMESTAMP"), NotNull);
+        assert_eq!(infer(&info, "now()"), NotNull);
+        assert_eq!(infer(&info, "row_number() OVER (?)"), NotNull);
+        assert_eq!(
+            infer(&info, "count(*) FILTER (WHERE (t.owner_id IS NULL))"),
+            NotNull
+        );
+        assert_eq!(infer(&info, "EXTRACT(year FROM t.created_at)"), Unknown);
+        assert_eq!(infer(&info, "ARRAY[t.id, t.owner_id]"), NotNull);
+        assert_eq!(infer(&info, "'{1,2}'::integer[]"), NotNull);
+        assert_eq!(infer(&info, "(ARRAY[t.id])[1]"), Nullable);
+        assert_eq!(
+            infer(&info, "CASE WHEN (t.id > 1) THEN 1 ELSE NULL::integer END"),
+            Nullable
+        );
+        assert_eq!(
+            infer(&info, "CASE t.id WHEN 1 THEN 'a'::text ELSE t.text END"),
+            NotNull
+        );
+        assert_eq!(infer(&info, "(SubPlan 1)"), Unknown);
+        assert_eq!(infer(&info, "my_function(t.id)"), Unknown);
+
+        let mut info = PlanInfo::default();
+        info.tables
+            .insert("t".into(), ("public".into(), "tweet".into()));
+
+        assert_eq!(infer(&info, "id"), NotNull);
+        assert_eq!(infer(&info, "(owner_id + 1)"), Nullable);
+    }
+
+    #[test]
+    fn it_finds_null_extended_relations() {
+        use Nullability::*;
+
+        // `SELECT ... FROM tweet t RIGHT JOIN (SELECT 1 AS c, t3.id + 1 AS d FROM tweet t3) x ...`,
+        // under a sort
+        let explain: Explain = serde_json::from_str(
+            r#"{"Plan": {
+                "Node Type": "Sort",
+                "Output": ["t.id", "(1)", "((t3.id + 1))"],
+                "Plans": [{
+                    "Node Type": "Hash Join",
+                    "Parent Relationship": "Outer",
+                    "Join Type": "Right",
+                    "Output": ["t.id", "(1)", "(t3.id + 1)"],
+                    "Plans": [
+                        {
+                            "Node Type": "Seq Scan",
+                            "Parent Relationship": "Outer",
+                            "Relation Name": "tweet",
+                            "Schema": "public",
+                            "Alias": "t3",
+                            "Output": ["t3.id", "1"]
+                        },
+                        {
+                            "Node Type": "Hash",
+                            "Parent Relationship": "Inner",
+                            "Output": ["t.id"],
+                            "Plans": [{
+                                "Node Type": "Seq Scan",
+                                "Parent Relationship": "Outer",
+                                "Relation Name": "tweet",
+                                "Schema": "public",
+                                "Alias": "t",
+                                "Output": ["t.id"]
+                            }]
+                        }
+                    ]
+                }]
+            }}"#,
+        )
+        .unwrap();
+
+        let info = PlanInfo::from_plan(&explain.plan);
+
+        let outputs: Vec<_> = explain
+            .plan
+            .output
+            .iter()
+            .flatten()
+            .map(|output| infer(&info, output))
+            .collect();
+
+        assert_eq!(outputs, [NotNull, Nullable, Nullable]);
+        assert_eq!(
+            info.tables.get("t3"),
+            Some(&("public".to_owned(), "tweet".to_owned()))
+        );
+    }
+}
@@ -6,10 +6,15 @@ use crate::postgres::message::{
 use crate::postgres::PgConnectOptions;
 use hmac::{Hmac, Mac};
 use rand::Rng;
-use sha2::{Digest, Sha256};
+use sha2::{Digest, Sha256, Sha384, Sha512};
 use stringprep::saslprep;
 
+// the client doesn't support channel binding, e.g. without TLS
 const GS2_HEADER: &str = "n,,";
+// the client supports channel binding, but the server doesn't
+const GS2_HEADER_NO_BINDING: &str = "y,,";
+// the client binds to the certificate of the server
+const GS2_HEADER_PLUS: &str = "p=tls-server-end-point,,";
 const CHANNEL_ATTR: &str = "c";
 const USERNAME_ATTR: &str = "n";
 const CLIENT_PROOF_ATTR: &str = "p";
@@ -47,8 +52,31 @@ pub(crate) async fn authenticate(
         ));
     }
 
+    // bind to the TLS connection, unless the server can't (or the client can't, without TLS)
+    let end_point = stream
+        .peer_certificate()
+        .map(|cert| tls_server_end_point(&cert));
+
+    let (gs2_header, plus) = match end_point {
+        Some(_) if has_sasl_plus => (GS2_HEADER_PLUS, true),
+        Some(_) => (GS2_HEADER_NO_BINDING, false),
+        None => (GS2_HEADER, false),
+    };
+
+    if !plus && !has_sasl {
+        return Err(err_protocol!(
+            "the server requires channel binding (SCRAM-SHA-256-PLUS), which needs TLS"
+        ));
+    }
+
     // channel-binding = "c=" base64
-    let channel_binding = format!("{}={}", CHANNEL_ATTR, base64::encode(GS2_HEADER));
+    let mut cbind_input = gs2_header.as_bytes().to_vec();
+
+    if plus {
+        cbind_input.extend(end_point.unwrap_or_default());
+    }
+
+    let channel_binding = format!("{}={}", CHANNEL_ATTR, base64::encode(&cbind_input));
 
     // "n=" saslname ;; Usernames are prepared using SASLprep.
     let username = format!("{}={}", USERNAME_ATTR, options.username);
@@ -67,14 +95,14 @@ pub(crate) async fn authenticate(
 
     let client_first_message = format!(
         "{gs2_header}{client_first_message_bare}",
-        gs2_header = GS2_HEADER,
+        gs2_header = gs2_header,
         client_first_message_bare = client_first_message_bare
     );
 
     stream
         .send(SaslInitialResponse {
             response: &client_first_message,
-            plus: false,
+            plus,
         })
         .await?;
 
@@ -167,6 +195,68 @@ pub(crate) async fn authenticate(
     Ok(())
 }
 
+/// The `tls-server-end-point` channel binding data of RFC 5929: the hash of the certificate of
+/// the server, with the hash function of its signature, or SHA-256 if that's MD5 or SHA-1.
+fn tls_server_end_point(cert: &[u8]) -> Vec<u8> {
+    // sha384WithRSAEncryption, ecdsa-with-SHA384
+    const SHA384: &[&[u8]] = &[
+        &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x0C],
+        &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x03],
+    ];
+    // sha512WithRSAEncryption, ecdsa-with-SHA512
+    const SHA512: &[&[u8]] = &[
+        &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x0D],
+        &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x04],
+    ];
+
+    match signature_algorithm(cert) {
+        Some(oid) if SHA384.contains(&oid) => Sha384::digest(cert).to_vec(),
+        Some(oid) if SHA512.contains(&oid) => Sha512::digest(cert).to_vec(),
+        _ => Sha256::digest(cert).to_vec(),
+    }
+}
+
+/// The object identifier of the signature algorithm of a DER encoded X.509 certificate.
+fn signature_algorithm(cert: &[u8]) -> Option<&[u8]> {
+    // Certificate ::= SEQUENCE { tbsCertificate, signatureAlgorithm, signatureValue }
+    let (_, cert, _) = der_element(cert)?;
+    let (_, _, cert) = der_element(cert)?;
+    // AlgorithmIdentifier ::= SEQUENCE { algorithm OBJECT IDENTIFIER, parameters }
+    let (_, algorithm, _) = der_element(cert)?;
+    let (tag, oid, _) = der_element(algorithm)?;
+
+    (tag == 0x06).then(|| oid)
+}
+
+/// Split the first DER element off `der`: its tag, contents, and the elements following it.
+fn der_element(der: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, der) = der.split_first()?;
+    let (&len, mut der) = der.split_first()?;
+
+    let len = if len < 0x80 {
+        len as usize
+    } else {
+        // the long form: the number of bytes of the length, then the length
+        let size = (len & 0x7F) as usize;
+
+        if size == 0 || size > 4 || der.len() < size {
+            return None;
+        }
+
+        let (len, rest) = der.split_at(size);
+        der = rest;
+
+        len.iter().fold(0, |len, &byte| len << 8 | byte as usize)
+    };
+
+    if der.len() < len {
+        return None;
+    }
+
+    let (contents, rest) = der.split_at(len);
+    Some((tag, contents, rest))
+}
+
 // nonce is a sequence of random printable bytes
 fn gen_nonce() -> String {
     let mut rng = rand::thread_rng();
@@ -213,3 +303,51 @@ fn hi<'a>(s: &'a str, salt: &'a [u8], iter_count: u32) -> Result<[u8; 32], Error
 
     Ok(hi.into())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_hashes_the_certificate_with_its_signature_hash() {
+        // SEQUENCE { tbsCertificate, SEQUENCE { OID }, BIT STRING }
+        fn cert(tbs: &[u8], oid: &[u8]) -> Vec<u8> {
+            let mut algorithm = vec![0x30, oid.len() as u8 + 2, 0x06, oid.len() as u8];
+            algorithm.extend(oid);
+
+            let mut contents = tbs.to_vec();
+            contents.extend(algorithm);
+            contents.extend([0x03, 0x01, 0x00]);
+
+            let mut cert = vec![0x30, 0x81, contents.len() as u8];
+            cert.extend(contents);
+            cert
+        }
+
+        // a `tbsCertificate` with a long form length
+        let mut tbs = vec![0x30, 0x81, 0x80];
+        tbs.extend([0; 0x80]);
+
+        let ecdsa_sha384 = [0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x03];
+        let rsa_sha512 = [0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x0D];
+        let rsa_sha1 = [0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x05];
+
+        let sha384 = cert(&tbs, &ecdsa_sha384);
+        assert_eq!(signature_algorithm(&sha384), Some(&ecdsa_sha384[..]));
+        assert_eq!(
+            tls_server_end_point(&sha384),
+            Sha384::digest(&sha384).to_vec()
+        );
+
+        let sha512 = cert(&[0x30, 0x00], &rsa_sha512);
+        assert_eq!(
+            tls_server_end_point(&sha512),
+            Sha512::digest(&sha512).to_vec()
+        );
+
+        let sha1 = cert(&[0x30, 0x00], &rsa_sha1);
+        assert_eq!(tls_server_end_point(&sha1), Sha256::digest(&sha1).to_vec());
+
+        assert_eq!(signature_algorithm(&[0x30, 0x05, 0x30]), None);
+    }
+}
@@ -11,7 +11,8 @@ use crate::error::Error;
 use crate::io::{BufStream, Decode, Encode};
 use crate::net::{MaybeTlsStream, Socket};
 use crate::postgres::message::{Message, MessageFormat, Notice, Notification, ParameterStatus};
-use crate::postgres::{PgConnectOptions, PgDatabaseError, PgSeverity};
+use crate::postgres::notice::PgNoticeHandler;
+use crate::postgres::{PgConnectOptions, PgDatabaseError, PgNotice, PgSeverity};
 
 // the stream is a separate type from the connection to uphold the invariant where an instantiated
 // [PgConnection] is a **valid** connection to postgres
@@ -32,14 +33,21 @@ pub struct PgStream {
 
     pub(crate) parameter_statuses: BTreeMap<String, String>,
 
+    pub(crate) notice_handler: Option<PgNoticeHandler>,
+
+    // notices kept until taken, if the options ask to collect them
+    pub(crate) notices: Option<Vec<PgNotice>>,
+
     pub(crate) server_version_num: Option<u32>,
+
+    max_message_size: Option<usize>,
 }
 
 impl PgStream {
     pub(super) async fn connect(options: &PgConnectOptions) -> Result<Self, Error> {
         let socket = match options.fetch_socket() {
             Some(ref path) => Socket::connect_uds(path).await?,
-            None => Socket::connect_tcp(&options.host, options.port).await?,
+            None => Socket::connect_tcp(&options.host, options.port, &options.tcp).await?,
         };
 
         let inner = BufStream::new(MaybeTlsStream::Raw(socket));
@@ -48,7 +56,10 @@ impl PgStream {
             inner,
             notifications: None,
             parameter_statuses: BTreeMap::default(),
+            notice_handler: options.notice_handler.clone(),
+            notices: options.collect_notices.then(Vec::new),
             server_version_num: None,
+            max_message_size: options.max_message_size,
         })
     }
 
@@ -81,12 +92,22 @@ impl PgStream {
     pub(crate) async fn recv_unchecked(&mut self) -> Result<Message, Error> {
         // all packets in postgres start with a 5-byte header
         // this header contains the message type and the total length of the message
-        let mut header: Bytes = self.inner.read(5).await?;
+        let mut header = self.inner.peek(5).await?;
 
         let format = MessageFormat::try_from_u8(header.get_u8())?;
         let size = (header.get_u32() - 4) as usize;
 
-        let contents = self.inner.read(size).await?;
+        if let Some(max) = self.max_message_size.filter(|max| size > *max) {
+            // the following messages are still valid once this one is skipped
+            self.inner.skip(5 + size);
+
+            return Err(Error::MessageTooLarge { size, max });
+        }
+
+        // the header is only consumed along with the contents, so that a receive which is
+        // cancelled in between is resumed by the next one
+        let mut contents: Bytes = self.inner.read(5 + size).await?;
+        contents.advance(5);
 
         Ok(Message { format, contents })
     }
@@ -119,15 +140,12 @@ impl PgStream {
                     let ParameterStatus { name, value } = message.decode()?;
                     // TODO: handle `client_encoding`, `DateStyle` change
 
-                    match name.as_str() {
-                        "server_version" => {
-                            self.server_version_num = parse_server_version(&value);
-                        }
-                        _ => {
-                            self.parameter_statuses.insert(name, value);
-                        }
+                    if name == "server_version" {
+                        self.server_version_num = parse_server_version(&value);
                     }
 
+                    self.parameter_statuses.insert(name, value);
+
                     continue;
                 }
 
@@ -159,6 +177,16 @@ impl PgStream {
                         );
                     }
 
+                    let notice = PgNotice(notice);
+
+                    if let Some(handler) = &self.notice_handler {
+                        (handler.0)(&notice);
+                    }
+
+                    if let Some(notices) = &mut self.notices {
+                        notices.push(notice);
+                    }
+
                     continue;
                 }
 
@@ -1,6 +1,7 @@
 use bytes::Bytes;
 
 use crate::error::Error;
+use crate::net::TlsConfig;
 use crate::postgres::connection::stream::PgStream;
 use crate::postgres::message::SslRequest;
 use crate::postgres::{PgConnectOptions, PgSslMode};
@@ -66,12 +67,15 @@ async fn upgrade(stream: &mut PgStream, options: &PgConnectOptions) -> Result<bo
     let accept_invalid_hostnames = !matches!(options.ssl_mode, PgSslMode::VerifyFull);
 
     stream
-        .upgrade(
-            &options.host,
+        .upgrade(TlsConfig {
             accept_invalid_certs,
             accept_invalid_hostnames,
-            options.ssl_root_cert.as_ref(),
-        )
+            hostname: &options.host,
+            root_cert_path: options.ssl_root_cert.as_ref(),
+            client_cert_path: options.ssl_client_cert.as_ref(),
+            client_key_path: options.ssl_client_key.as_ref(),
+            custom: options.custom_tls.as_ref(),
+        })
         .await?;
 
     Ok(true)
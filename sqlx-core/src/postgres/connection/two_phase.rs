@@ -0,0 +1,126 @@
+use std::time::{Duration, SystemTime};
+
+use crate::error::Error;
+use crate::executor::Executor;
+use crate::postgres::{PgConnection, Postgres};
+use crate::query_as::query_as;
+use crate::transaction::Transaction;
+
+/// A transaction prepared for a two-phase commit by [`Transaction::prepare_transaction()`], which isn't
+/// committed or rolled back yet, listed by [`PgConnection::prepared_transactions()`].
+#[derive(Debug, Clone)]
+pub struct PgPreparedTransaction {
+    /// The identifier the transaction was prepared with.
+    pub id: String,
+    /// When the transaction was prepared.
+    pub prepared_at: SystemTime,
+    /// The role which prepared the transaction.
+    pub owner: String,
+}
+
+impl<'c> Transaction<'c, Postgres> {
+    /// Prepares this transaction for a two-phase commit with `PREPARE TRANSACTION`, under the
+    /// identifier `id`, and ends it.
+    ///
+    /// The prepared transaction survives the connection, and a restart of the server, until it's
+    /// committed or rolled back by [`PgConnection::commit_prepared()`] or
+    /// [`PgConnection::rollback_prepared()`], from any connection to the database. Its
+    /// [`on_commit`](Self::on_commit) and [`on_rollback`](Self::on_rollback) callbacks are
+    /// discarded, as it's neither committed nor rolled back yet.
+    ///
+    /// The server must allow prepared transactions, with `max_prepared_transactions` above 0.
+    ///
+    /// ```rust,ignore
+    /// let mut orders = orders_pool.begin().await?;
+    /// let mut payments = payments_pool.begin().await?;
+    ///
+    /// // ...
+    ///
+    /// orders.prepare_transaction("checkout-42").await?;
+    ///
+    /// if let Err(e) = payments.prepare_transaction("checkout-42").await {
+    ///     orders_pool.acquire().await?.rollback_prepared("checkout-42").await?;
+    ///     return Err(e);
+    /// }
+    ///
+    /// orders_pool.acquire().await?.commit_prepared("checkout-42").await?;
+    /// payments_pool.acquire().await?.commit_prepared("checkout-42").await?;
+    /// ```
+    pub async fn prepare_transaction(mut self, id: &str) -> Result<(), Error> {
+        let conn: &mut PgConnection = &mut self;
+
+        if conn.transaction_depth != 1 {
+            return Err(Error::Configuration(
+                "only a transaction, rather than a savepoint, can be prepared".into(),
+            ));
+        }
+
+        conn.execute(&*format!("PREPARE TRANSACTION {}", literal(id)))
+            .await?;
+
+        // the session is no longer in a transaction once it's prepared
+        conn.transaction_depth = 0;
+
+        self.detach();
+
+        Ok(())
+    }
+}
+
+impl PgConnection {
+    /// Commits the transaction prepared with the identifier `id` by [`Transaction::prepare_transaction()`],
+    /// with `COMMIT PREPARED`.
+    ///
+    /// It can't be run in a transaction.
+    pub async fn commit_prepared(&mut self, id: &str) -> Result<(), Error> {
+        self.execute(&*format!("COMMIT PREPARED {}", literal(id)))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Rolls back the transaction prepared with the identifier `id` by
+    /// [`Transaction::prepare_transaction()`], with `ROLLBACK PREPARED`.
+    ///
+    /// It can't be run in a transaction.
+    pub async fn rollback_prepared(&mut self, id: &str) -> Result<(), Error> {
+        self.execute(&*format!("ROLLBACK PREPARED {}", literal(id)))
+            .await?;
+
+        Ok(())
+    }
+
+    /// The transactions prepared in the database of the connection which are neither committed
+    /// nor rolled back, e.g. to resolve those left in doubt by a coordinator which failed
+    /// between the two phases of a commit, oldest first.
+    pub async fn prepared_transactions(&mut self) -> Result<Vec<PgPreparedTransaction>, Error> {
+        let rows: Vec<(String, f64, String)> = query_as(
+            "SELECT gid, EXTRACT(EPOCH FROM prepared)::FLOAT8, owner::TEXT \
+             FROM pg_catalog.pg_prepared_xacts \
+             WHERE database = current_database() \
+             ORDER BY prepared",
+        )
+        .fetch_all(self)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, prepared_at, owner)| PgPreparedTransaction {
+                id,
+                prepared_at: SystemTime::UNIX_EPOCH + Duration::from_secs_f64(prepared_at),
+                owner,
+            })
+            .collect())
+    }
+}
+
+// the identifier of a prepared transaction is a string literal, which can't be bound
+fn literal(id: &str) -> String {
+    format!("'{}'", id.replace('\'', "''"))
+}
+
+#[test]
+fn test_literal() {
+    assert_eq!(literal("checkout-42"), "'checkout-42'");
+    assert_eq!(literal("it's"), "'it''s'");
+}
@@ -4,7 +4,7 @@ use std::fmt::{self, Debug, Display, Formatter};
 use atoi::atoi;
 use smallvec::alloc::borrow::Cow;
 
-use crate::error::DatabaseError;
+use crate::error::{DatabaseError, ErrorKind};
 use crate::postgres::message::{Notice, PgSeverity};
 
 /// An error returned from the PostgreSQL database.
@@ -199,7 +199,28 @@ impl DatabaseError for PgDatabaseError {
         .contains(&self.code())
     }
 
+    fn kind(&self) -> ErrorKind {
+        // https://www.postgresql.org/docs/current/errcodes-appendix.html
+        match self.code() {
+            "23505" => ErrorKind::UniqueViolation,
+            "23503" => ErrorKind::ForeignKeyViolation,
+            "23514" => ErrorKind::CheckViolation,
+            "23502" => ErrorKind::NotNullViolation,
+            "40001" => ErrorKind::SerializationFailure,
+            "40P01" => ErrorKind::Deadlock,
+            _ => ErrorKind::Other,
+        }
+    }
+
     fn constraint(&self) -> Option<&str> {
         self.constraint()
     }
+
+    fn table(&self) -> Option<&str> {
+        self.table()
+    }
+
+    fn column(&self) -> Option<&str> {
+        self.column()
+    }
 }
@@ -34,6 +34,20 @@ pub enum Authentication {
     /// again using the 4-byte random salt.
     Md5Password(AuthenticationMd5Password),
 
+    /// The frontend must now initiate a GSSAPI negotiation, by sending a
+    /// [GssResponse] with the first token of the security context.
+    ///
+    /// If further messages are needed, the server will
+    /// respond with [Authentication::GssContinue].
+    Gss,
+
+    /// This message contains the response data from the previous step of GSSAPI negotiation.
+    ///
+    /// The frontend must respond with a [GssResponse] message, unless the
+    /// security context is established.
+    #[cfg(feature = "postgres-gssapi")]
+    GssContinue(Bytes),
+
     /// The frontend must now initiate a SASL negotiation,
     /// using one of the SASL mechanisms listed in the message.
     ///
@@ -72,6 +86,10 @@ impl Decode<'_> for Authentication {
                 Authentication::Md5Password(AuthenticationMd5Password { salt })
             }
 
+            7 => Authentication::Gss,
+            #[cfg(feature = "postgres-gssapi")]
+            8 => Authentication::GssContinue(buf),
+
             10 => Authentication::Sasl(AuthenticationSasl(buf)),
             11 => Authentication::SaslContinue(AuthenticationSaslContinue::decode(buf)?),
             12 => Authentication::SaslFinal(AuthenticationSaslFinal::decode(buf)?),
@@ -0,0 +1,32 @@
+use crate::io::Encode;
+
+/// Asks the server to cancel the statement running on the backend identified by the key data
+/// of its [`BackendKeyData`][super::BackendKeyData]; sent on a new connection, instead of a
+/// `StartupMessage`.
+pub struct CancelRequest {
+    pub process_id: u32,
+    pub secret_key: u32,
+}
+
+impl Encode<'_> for CancelRequest {
+    fn encode_with(&self, buf: &mut Vec<u8>, _: ()) {
+        buf.extend(&16_u32.to_be_bytes());
+        buf.extend(&(((1234 << 16) | 5678) as u32).to_be_bytes());
+        buf.extend(&self.process_id.to_be_bytes());
+        buf.extend(&self.secret_key.to_be_bytes());
+    }
+}
+
+#[test]
+fn test_encode_cancel_request() {
+    const EXPECTED: &[u8] = b"\x00\x00\x00\x10\x04\xd2\x16.\0\0'\xc6\x89R\xc5+";
+
+    let mut buf = Vec::new();
+    CancelRequest {
+        process_id: 10182,
+        secret_key: 2303903019,
+    }
+    .encode(&mut buf);
+
+    assert_eq!(buf, EXPECTED);
+}
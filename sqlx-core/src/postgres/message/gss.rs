@@ -0,0 +1,13 @@
+use crate::io::Encode;
+use crate::postgres::io::PgBufMutExt;
+
+pub struct GssResponse<'a>(pub &'a [u8]);
+
+impl Encode<'_> for GssResponse<'_> {
+    fn encode_with(&self, buf: &mut Vec<u8>, _: ()) {
+        buf.push(b'p');
+        buf.put_length_prefixed(|buf| {
+            buf.extend(self.0);
+        });
+    }
+}
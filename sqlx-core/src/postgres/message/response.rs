@@ -49,7 +49,7 @@ impl TryFrom<&str> for PgSeverity {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Notice {
     storage: Bytes,
     severity: PgSeverity,
@@ -2,11 +2,14 @@ use crate::connection::{ConnectOptions, Connection};
 use crate::error::Error;
 use crate::executor::Executor;
 use crate::migrate::MigrateError;
-use crate::migrate::{AppliedMigration, Migration};
+use crate::migrate::{
+    AppliedMigration, CreateDatabaseOptions, LockAttempts, Migration, MigrationLock,
+};
 use crate::migrate::{Migrate, MigrateDatabase};
 use crate::postgres::{PgConnectOptions, PgConnection, Postgres};
 use crate::query::query;
 use crate::query_as::query_as;
+use crate::query_batch::split_statements;
 use crate::query_scalar::query_scalar;
 use futures_core::future::BoxFuture;
 use std::str::FromStr;
@@ -37,16 +40,55 @@ fn parse_for_maintenance(url: &str) -> Result<(PgConnectOptions, String), Error>
 
 impl MigrateDatabase for Postgres {
     fn create_database(url: &str) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move { Self::create_database_with(url, &Default::default()).await })
+    }
+
+    fn create_database_with<'a>(
+        url: &'a str,
+        create_options: &'a CreateDatabaseOptions,
+    ) -> BoxFuture<'a, Result<(), Error>> {
         Box::pin(async move {
+            create_options.check_supported(&[
+                "owner",
+                "encoding",
+                "locale",
+                "collation",
+                "template",
+            ])?;
+
             let (options, database) = parse_for_maintenance(url)?;
             let mut conn = options.connect().await?;
 
-            let _ = conn
-                .execute(&*format!(
-                    "CREATE DATABASE \"{}\"",
-                    database.replace('"', "\"\"")
-                ))
-                .await?;
+            let ident = |ident: &str| format!("\"{}\"", ident.replace('"', "\"\""));
+            let literal = |literal: &str| format!("'{}'", literal.replace('\'', "''"));
+
+            let mut sql = format!("CREATE DATABASE {}", ident(&database));
+
+            if let Some(owner) = &create_options.owner {
+                sql += &format!(" OWNER {}", ident(owner));
+            }
+
+            if let Some(template) = &create_options.template {
+                sql += &format!(" TEMPLATE {}", ident(template));
+            }
+
+            if let Some(encoding) = &create_options.encoding {
+                sql += &format!(" ENCODING {}", literal(encoding));
+            }
+
+            if let Some(collation) = create_options
+                .collation
+                .as_ref()
+                .or(create_options.locale.as_ref())
+            {
+                sql += &format!(" LC_COLLATE {}", literal(collation));
+            }
+
+            if let Some(locale) = &create_options.locale {
+                sql += &format!(" LC_CTYPE {}", literal(locale));
+            }
+
+            let _ = conn.execute(&*sql).await?;
 
             Ok(())
         })
@@ -85,12 +127,15 @@ impl MigrateDatabase for Postgres {
 }
 
 impl Migrate for PgConnection {
-    fn ensure_migrations_table(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn ensure_migrations_table<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<(), MigrateError>> {
         Box::pin(async move {
             // language=SQL
-            self.execute(
+            self.execute(&*format!(
                 r#"
-CREATE TABLE IF NOT EXISTS _sqlx_migrations (
+CREATE TABLE IF NOT EXISTS {} (
     version BIGINT PRIMARY KEY,
     description TEXT NOT NULL,
     installed_on TIMESTAMPTZ NOT NULL DEFAULT now(),
@@ -99,7 +144,8 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
     execution_time BIGINT NOT NULL
 );
                 "#,
-            )
+                table_name
+            ))
             .await?;
 
             Ok(())
@@ -119,12 +165,16 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         })
     }
 
-    fn dirty_version(&mut self) -> BoxFuture<'_, Result<Option<i64>, MigrateError>> {
+    fn dirty_version<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Option<i64>, MigrateError>> {
         Box::pin(async move {
             // language=SQL
-            let row: Option<(i64,)> = query_as(
-                "SELECT version FROM _sqlx_migrations WHERE success = false ORDER BY version LIMIT 1",
-            )
+            let row: Option<(i64,)> = query_as(&format!(
+                "SELECT version FROM {} WHERE success = false ORDER BY version LIMIT 1",
+                table_name
+            ))
             .fetch_optional(self)
             .await?;
 
@@ -132,21 +182,25 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         })
     }
 
-    fn list_applied_migrations(
-        &mut self,
-    ) -> BoxFuture<'_, Result<Vec<AppliedMigration>, MigrateError>> {
+    fn list_applied_migrations<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Vec<AppliedMigration>, MigrateError>> {
         Box::pin(async move {
             // language=SQL
-            let rows: Vec<(i64, Vec<u8>)> =
-                query_as("SELECT version, checksum FROM _sqlx_migrations ORDER BY version")
-                    .fetch_all(self)
-                    .await?;
+            let rows: Vec<(i64, Vec<u8>, i64)> = query_as(&format!(
+                "SELECT version, checksum, execution_time FROM {} ORDER BY version",
+                table_name
+            ))
+            .fetch_all(self)
+            .await?;
 
             let migrations = rows
                 .into_iter()
-                .map(|(version, checksum)| AppliedMigration {
+                .map(|(version, checksum, execution_time)| AppliedMigration {
                     version,
                     checksum: checksum.into(),
+                    execution_time: Duration::from_nanos(execution_time as u64),
                 })
                 .collect();
 
@@ -154,31 +208,44 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         })
     }
 
-    fn lock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn lock(&mut self, lock: MigrationLock) -> BoxFuture<'_, Result<(), MigrateError>> {
         Box::pin(async move {
-            let database_name = current_database(self).await?;
-            let lock_id = generate_lock_id(&database_name);
+            let lock_id = lock_id(self, lock).await?;
 
             // create an application lock over the database
-            // this function will not return until the lock is acquired
+            // without a timeout, this function will not return until the lock is acquired
 
             // https://www.postgresql.org/docs/current/explicit-locking.html#ADVISORY-LOCKS
             // https://www.postgresql.org/docs/current/functions-admin.html#FUNCTIONS-ADVISORY-LOCKS-TABLE
 
+            if lock.timeout.is_none() {
+                // language=SQL
+                let _ = query("SELECT pg_advisory_lock($1)")
+                    .bind(lock_id)
+                    .execute(self)
+                    .await?;
+
+                return Ok(());
+            }
+
+            let attempts = LockAttempts::new(lock);
+
             // language=SQL
-            let _ = query("SELECT pg_advisory_lock($1)")
+            while !query_scalar("SELECT pg_try_advisory_lock($1)")
                 .bind(lock_id)
-                .execute(self)
-                .await?;
+                .fetch_one(&mut *self)
+                .await?
+            {
+                attempts.retry().await?;
+            }
 
             Ok(())
         })
     }
 
-    fn unlock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn unlock(&mut self, lock: MigrationLock) -> BoxFuture<'_, Result<(), MigrateError>> {
         Box::pin(async move {
-            let database_name = current_database(self).await?;
-            let lock_id = generate_lock_id(&database_name);
+            let lock_id = lock_id(self, lock).await?;
 
             // language=SQL
             let _ = query("SELECT pg_advisory_unlock($1)")
@@ -216,52 +283,210 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
 
     fn apply<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         Box::pin(async move {
-            let mut tx = self.begin().await?;
             let start = Instant::now();
 
-            let _ = tx.execute(&*migration.sql).await?;
-
-            tx.commit().await?;
+            execute_migration(self, table_name, migration).await?;
 
             let elapsed = start.elapsed();
 
+            // the row recording the progress of a resumable migration already exists
             // language=SQL
-            let _ = query(
-                r#"
-    INSERT INTO _sqlx_migrations ( version, description, success, checksum, execution_time )
+            let sql = if migration.is_resumable() {
+                format!(
+                    r#"
+    UPDATE {} SET description = $2, success = TRUE, checksum = $3, execution_time = $4
+    WHERE version = $1
+                    "#,
+                    table_name
+                )
+            } else {
+                format!(
+                    r#"
+    INSERT INTO {} ( version, description, success, checksum, execution_time )
     VALUES ( $1, $2, TRUE, $3, $4 )
+                    "#,
+                    table_name
+                )
+            };
+
+            let _ = query(&sql)
+                .bind(migration.version)
+                .bind(&*migration.description)
+                .bind(&*migration.checksum)
+                .bind(elapsed.as_nanos() as i64)
+                .execute(self)
+                .await?;
+
+            Ok(elapsed)
+        })
+    }
+
+    fn update_checksum<'e: 'm, 'm>(
+        &'e mut self,
+        table_name: &'m str,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        Box::pin(async move {
+            // language=SQL
+            let _ = query(&format!(
+                r#"UPDATE {} SET checksum = $1 WHERE version = $2"#,
+                table_name
+            ))
+            .bind(&*migration.checksum)
+            .bind(migration.version)
+            .execute(self)
+            .await?;
+
+            Ok(())
+        })
+    }
+
+    fn record_applied<'e: 'm, 'm>(
+        &'e mut self,
+        table_name: &'m str,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        Box::pin(async move {
+            // language=SQL
+            let _ = query(&format!(
+                r#"
+    INSERT INTO {} ( version, description, success, checksum, execution_time )
+    VALUES ( $1, $2, TRUE, $3, 0 )
                 "#,
-            )
+                table_name
+            ))
             .bind(migration.version)
             .bind(&*migration.description)
             .bind(&*migration.checksum)
-            .bind(elapsed.as_nanos() as i64)
             .execute(self)
             .await?;
 
-            Ok(elapsed)
+            Ok(())
+        })
+    }
+
+    fn dump_schema<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<String, MigrateError>> {
+        Box::pin(async move {
+            // the migrations table may be qualified by a schema
+            let table_name = table_name.rsplit('.').next().unwrap_or(table_name);
+
+            let mut statements: Vec<String> = Vec::new();
+
+            // language=PostgreSQL
+            statements.extend(
+                query_scalar::<_, String>(
+                    r#"
+SELECT format('CREATE TYPE %I AS ENUM (%s);', t.typname,
+              string_agg(quote_literal(e.enumlabel), ', ' ORDER BY e.enumsortorder))
+FROM pg_type t
+JOIN pg_enum e ON e.enumtypid = t.oid
+JOIN pg_namespace n ON n.oid = t.typnamespace
+WHERE n.nspname = current_schema()
+GROUP BY t.typname
+ORDER BY t.typname
+                    "#,
+                )
+                .fetch_all(&mut *self)
+                .await?,
+            );
+
+            // language=PostgreSQL
+            statements.extend(
+                query_scalar::<_, String>(
+                    r#"
+SELECT format(E'CREATE TABLE %I (\n%s\n);', c.relname, array_to_string(
+    array(
+        SELECT format('    %I %s%s%s', a.attname, format_type(a.atttypid, a.atttypmod),
+                      CASE WHEN a.attnotnull THEN ' NOT NULL' ELSE '' END,
+                      COALESCE(' DEFAULT ' || pg_get_expr(d.adbin, d.adrelid), ''))
+        FROM pg_attribute a
+        LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+        WHERE a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped
+        ORDER BY a.attnum
+    ) || array(
+        SELECT format('    CONSTRAINT %I %s', co.conname, pg_get_constraintdef(co.oid))
+        FROM pg_constraint co
+        WHERE co.conrelid = c.oid
+        ORDER BY co.conname
+    ), E',\n'))
+FROM pg_class c
+JOIN pg_namespace n ON n.oid = c.relnamespace
+WHERE n.nspname = current_schema() AND c.relkind IN ('r', 'p') AND c.relname <> $1
+ORDER BY c.relname
+                    "#,
+                )
+                .bind(table_name)
+                .fetch_all(&mut *self)
+                .await?,
+            );
+
+            // indexes other than those of primary key, unique and exclusion constraints
+            // language=PostgreSQL
+            statements.extend(
+                query_scalar::<_, String>(
+                    r#"
+SELECT pg_get_indexdef(i.indexrelid) || ';'
+FROM pg_index i
+JOIN pg_class c ON c.oid = i.indexrelid
+JOIN pg_class t ON t.oid = i.indrelid
+JOIN pg_namespace n ON n.oid = c.relnamespace
+WHERE n.nspname = current_schema() AND t.relname <> $1
+  AND NOT EXISTS (
+    SELECT 1 FROM pg_constraint co
+    WHERE co.conindid = i.indexrelid AND co.contype IN ('p', 'u', 'x')
+  )
+ORDER BY c.relname
+                    "#,
+                )
+                .bind(table_name)
+                .fetch_all(&mut *self)
+                .await?,
+            );
+
+            // language=PostgreSQL
+            statements.extend(
+                query_scalar::<_, String>(
+                    r#"
+SELECT format(E'CREATE VIEW %I AS\n%s', c.relname, pg_get_viewdef(c.oid))
+FROM pg_class c
+JOIN pg_namespace n ON n.oid = c.relnamespace
+WHERE n.nspname = current_schema() AND c.relkind = 'v'
+ORDER BY c.relname
+                    "#,
+                )
+                .fetch_all(&mut *self)
+                .await?,
+            );
+
+            Ok(statements
+                .iter()
+                .map(|statement| format!("{}\n", statement))
+                .collect::<Vec<_>>()
+                .join("\n"))
         })
     }
 
     fn revert<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         Box::pin(async move {
-            let mut tx = self.begin().await?;
             let start = Instant::now();
 
-            let _ = tx.execute(&*migration.sql).await?;
-
-            tx.commit().await?;
+            execute_migration(self, table_name, migration).await?;
 
             let elapsed = start.elapsed();
 
             // language=SQL
-            let _ = query(r#"DELETE FROM _sqlx_migrations WHERE version = $1"#)
+            let _ = query(&format!(r#"DELETE FROM {} WHERE version = $1"#, table_name))
                 .bind(migration.version)
                 .execute(self)
                 .await?;
@@ -271,6 +496,127 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
     }
 }
 
+// run the SQL of the migration as configured by its headers
+async fn execute_migration(
+    conn: &mut PgConnection,
+    table_name: &str,
+    migration: &Migration,
+) -> Result<(), MigrateError> {
+    let headers = migration.headers()?;
+    headers.check_database(migration.version, "postgres")?;
+
+    // `lock_timeout` is in milliseconds
+    let lock_timeout = headers.lock_timeout.map(|timeout| timeout.as_millis());
+
+    if headers.no_transaction {
+        if let Some(lock_timeout) = lock_timeout {
+            conn.execute(&*format!("SET lock_timeout = {}", lock_timeout))
+                .await?;
+        }
+
+        let res = if migration.is_resumable() {
+            execute_statements(&mut *conn, table_name, migration).await
+        } else {
+            execute_body(&mut *conn, migration).await
+        };
+
+        if lock_timeout.is_some() {
+            conn.execute("RESET lock_timeout").await?;
+        }
+
+        res?;
+    } else {
+        let mut tx = conn.begin().await?;
+
+        if let Some(lock_timeout) = lock_timeout {
+            tx.execute(&*format!("SET LOCAL lock_timeout = {}", lock_timeout))
+                .await?;
+        }
+
+        execute_body(&mut *tx, migration).await?;
+
+        tx.commit().await?;
+    }
+
+    Ok(())
+}
+
+// run the SQL of the migration, or its function if implemented in Rust
+async fn execute_body(conn: &mut PgConnection, migration: &Migration) -> Result<(), MigrateError> {
+    match &migration.code {
+        Some(code) => code.run(migration.version, conn).await,
+        None => {
+            let _ = conn.execute(&*migration.sql).await?;
+
+            Ok(())
+        }
+    }
+}
+
+// run the statements of a resumable migration one at a time, counting those applied in its row
+// of the migrations table, which stays dirty until the migration is recorded as applied
+async fn execute_statements(
+    conn: &mut PgConnection,
+    table_name: &str,
+    migration: &Migration,
+) -> Result<(), MigrateError> {
+    // the column isn't created with the table, which may predate it
+    // language=SQL
+    conn.execute(&*format!(
+        "ALTER TABLE {} ADD COLUMN IF NOT EXISTS statements_applied BIGINT",
+        table_name
+    ))
+    .await?;
+
+    // language=SQL
+    let applied: Option<Option<i64>> = query_scalar(&format!(
+        "SELECT statements_applied FROM {} WHERE version = $1",
+        table_name
+    ))
+    .bind(migration.version)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let applied = match applied {
+        Some(applied) => applied.unwrap_or(0),
+        None => {
+            // language=SQL
+            let _ = query(&format!(
+                r#"
+    INSERT INTO {} ( version, description, success, checksum, execution_time, statements_applied )
+    VALUES ( $1, $2, FALSE, $3, 0, 0 )
+                "#,
+                table_name
+            ))
+            .bind(migration.version)
+            .bind(&*migration.description)
+            .bind(&*migration.checksum)
+            .execute(&mut *conn)
+            .await?;
+
+            0
+        }
+    };
+
+    let statements = split_statements(&migration.sql);
+
+    for (i, statement) in statements.into_iter().enumerate().skip(applied as usize) {
+        let _ = conn.execute(statement).await?;
+
+        // language=SQL
+        let _ = query(&format!(
+            "UPDATE {} SET statements_applied = $1 WHERE version = $2",
+            table_name
+        ))
+        .bind(i as i64 + 1)
+        .bind(migration.version)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
 async fn current_database(conn: &mut PgConnection) -> Result<String, MigrateError> {
     // language=SQL
     Ok(query_scalar("SELECT current_database()")
@@ -278,6 +624,13 @@ async fn current_database(conn: &mut PgConnection) -> Result<String, MigrateErro
         .await?)
 }
 
+async fn lock_id(conn: &mut PgConnection, lock: MigrationLock) -> Result<i64, MigrateError> {
+    match lock.key {
+        Some(key) => Ok(key),
+        None => Ok(generate_lock_id(&current_database(conn).await?)),
+    }
+}
+
 // inspired from rails: https://github.com/rails/rails/blob/6e49cc77ab3d16c06e12f93158eaf3e507d4120e/activerecord/lib/active_record/migration.rb#L1308
 fn generate_lock_id(database_name: &str) -> i64 {
     const CRC_IEEE: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
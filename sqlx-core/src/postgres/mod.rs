@@ -1,4 +1,24 @@
 //! **PostgreSQL** database driver.
+//!
+//! # CockroachDB
+//!
+//! This driver also connects to [CockroachDB](https://www.cockroachlabs.com/), which speaks
+//! the PostgreSQL protocol. [`PgConnection::is_cockroachdb()`] tells whether a connection is to
+//! CockroachDB, which differs from PostgreSQL in a few ways:
+//!
+//! * It runs every transaction at the `SERIALIZABLE` isolation level, and fails the transactions
+//!   that conflict with others with a retryable `40001` error, which the client is expected to
+//!   retry. [`PgConnection::transaction_with_retry()`] does that.
+//! * `INT` is an alias of `INT8`, and integer literals are `INT8`, so they decode to `i64`
+//!   rather than `i32`; use `INT4` for the columns to decode to `i32`.
+//! * The nullability of the columns of queries is only inferred from the `NOT NULL` constraints
+//!   of the tables, as CockroachDB doesn't support the `EXPLAIN` queries used for the rest; use
+//!   the overrides of `query!()` for the columns that are wrongly inferred.
+//! * It reports a PostgreSQL version that it's compatible with as its
+//!   [`server_version()`][crate::connection::Connection::server_version];
+//!   [`PgConnection::cockroachdb_version()`] is its own version.
+//! * `LISTEN`/`NOTIFY` ([`PgListener`]), advisory locks ([`PgAdvisoryLock`]), the replication
+//!   protocol, and range and multirange types aren't supported.
 
 use crate::executor::Executor;
 
@@ -12,6 +32,7 @@ mod error;
 mod io;
 mod listener;
 mod message;
+mod notice;
 mod options;
 mod query_result;
 mod row;
@@ -24,17 +45,27 @@ mod value;
 #[cfg(feature = "migrate")]
 mod migrate;
 
+#[cfg(feature = "postgres-replication")]
+mod replication;
+
 pub use advisory_lock::{PgAdvisoryLock, PgAdvisoryLockGuard, PgAdvisoryLockKey};
 pub use arguments::{PgArgumentBuffer, PgArguments};
 pub use column::PgColumn;
-pub use connection::PgConnection;
+pub use connection::{PgCancelToken, PgConnection, PgPreparedTransaction};
 pub use copy::PgCopyIn;
 pub use database::Postgres;
 pub use error::{PgDatabaseError, PgErrorPosition};
 pub use listener::{PgListener, PgNotification};
 pub use message::PgSeverity;
-pub use options::{PgConnectOptions, PgSslMode};
+pub use notice::PgNotice;
+pub use options::{PgConnectOptions, PgFlushPolicy, PgSslMode, PgTargetSessionAttrs};
 pub use query_result::PgQueryResult;
+#[cfg(feature = "postgres-replication")]
+pub use replication::{
+    PgLsn, PgRelation, PgRelationColumn, PgReplicaIdentity, PgReplicationConnection,
+    PgReplicationEvent, PgReplicationSlot, PgReplicationStream, PgSystemIdentity, PgTuple,
+    PgTupleValue,
+};
 pub use row::PgRow;
 pub use statement::PgStatement;
 pub use transaction::PgTransactionManager;
@@ -55,6 +86,7 @@ impl<'c, T: Executor<'c, Database = Postgres>> PgExecutor<'c> for T {}
 impl_into_arguments_for_arguments!(PgArguments);
 impl_executor_for_pool_connection!(Postgres, PgConnection, PgRow);
 impl_executor_for_transaction!(Postgres, PgRow);
+impl_executor_for_reconnecting_connection!(Postgres, PgRow);
 impl_acquire!(Postgres, PgConnection);
 impl_column_index_for_row!(PgRow);
 impl_column_index_for_statement!(PgStatement);
@@ -0,0 +1,83 @@
+use std::fmt::{self, Debug, Display, Formatter};
+use std::sync::Arc;
+
+use crate::postgres::message::{Notice, PgSeverity};
+
+/// A notice or warning sent by the PostgreSQL server, such as the output of `RAISE NOTICE` in
+/// a PL/pgSQL function.
+///
+/// Received notices are passed to the
+/// [`notice_handler`](super::PgConnectOptions::notice_handler) and kept by connections
+/// [collecting them](super::PgConnectOptions::collect_notices).
+#[derive(Clone)]
+pub struct PgNotice(pub(crate) Notice);
+
+// The fields are those of errors:
+// https://www.postgresql.org/docs/current/protocol-error-fields.html
+
+impl PgNotice {
+    #[inline]
+    pub fn severity(&self) -> PgSeverity {
+        self.0.severity()
+    }
+
+    /// The [SQLSTATE](https://www.postgresql.org/docs/current/errcodes-appendix.html) code for
+    /// this notice, `00000` for `RAISE NOTICE` without one.
+    #[inline]
+    pub fn code(&self) -> &str {
+        self.0.code()
+    }
+
+    /// The primary human-readable message.
+    #[inline]
+    pub fn message(&self) -> &str {
+        self.0.message()
+    }
+
+    /// An optional secondary message carrying more detail. Might run to multiple lines.
+    #[inline]
+    pub fn detail(&self) -> Option<&str> {
+        self.0.get(b'D')
+    }
+
+    /// An optional suggestion what to do. Might run to multiple lines.
+    #[inline]
+    pub fn hint(&self) -> Option<&str> {
+        self.0.get(b'H')
+    }
+
+    /// An indication of the context in which the notice was raised, as a call stack traceback
+    /// of active procedural language functions, one entry per line, most recent first.
+    pub fn r#where(&self) -> Option<&str> {
+        self.0.get(b'W')
+    }
+}
+
+impl Debug for PgNotice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PgNotice")
+            .field("severity", &self.severity())
+            .field("code", &self.code())
+            .field("message", &self.message())
+            .field("detail", &self.detail())
+            .field("hint", &self.hint())
+            .field("where", &self.r#where())
+            .finish()
+    }
+}
+
+impl Display for PgNotice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+/// The function set by [`notice_handler`](super::PgConnectOptions::notice_handler).
+#[derive(Clone)]
+pub(crate) struct PgNoticeHandler(pub(crate) Arc<dyn Fn(&PgNotice) + Send + Sync>);
+
+impl Debug for PgNoticeHandler {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("PgNoticeHandler(..)")
+    }
+}
@@ -0,0 +1,51 @@
+use crate::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// Options for when a PostgreSQL connection sends the messages it queued to the server.
+///
+/// It is used by the [`flush_policy`](super::PgConnectOptions::flush_policy) method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PgFlushPolicy {
+    /// The messages of a query are sent in a single write: a statement executed for the first
+    /// time is parsed, described and executed in one round-trip, and the statements evicted
+    /// from the statement cache are closed along with the next query.
+    Coalesce,
+
+    /// The messages are sent as soon as each step of a query needs them: a new statement is
+    /// parsed and described before it's executed, and an evicted statement is closed right
+    /// away, each in their own round-trip.
+    Eager,
+}
+
+impl Default for PgFlushPolicy {
+    fn default() -> Self {
+        PgFlushPolicy::Coalesce
+    }
+}
+
+impl Display for PgFlushPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PgFlushPolicy::Coalesce => "coalesce",
+            PgFlushPolicy::Eager => "eager",
+        })
+    }
+}
+
+impl FromStr for PgFlushPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match &*s.to_ascii_lowercase() {
+            "coalesce" => PgFlushPolicy::Coalesce,
+            "eager" => PgFlushPolicy::Eager,
+
+            _ => {
+                return Err(Error::Configuration(
+                    format!("unknown value {:?} for `flush_policy`", s).into(),
+                ));
+            }
+        })
+    }
+}
@@ -0,0 +1,547 @@
+use std::cmp;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bytes::{Buf, Bytes};
+
+use crate::decode::Decode;
+use crate::error::{mismatched_types, Error};
+use crate::io::BufExt;
+use crate::postgres::replication::PgLsn;
+use crate::postgres::types::Oid;
+use crate::postgres::{PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+use crate::type_info::TypeInfo;
+use crate::types::Type;
+use crate::value::ValueRef;
+use crate::HashMap;
+
+// https://www.postgresql.org/docs/current/protocol-logicalrep-message-formats.html
+
+/// A message of logical replication, decoded from the output of the `pgoutput` plugin.
+///
+/// The changes of a transaction are sent after it commits, between its `Begin` and `Commit`.
+/// A `Relation` is sent before the first change to a table, and again after its definition
+/// changes.
+#[derive(Debug, Clone)]
+pub enum PgReplicationEvent {
+    /// The beginning of a transaction.
+    Begin {
+        /// The position of the commit of the transaction.
+        final_lsn: PgLsn,
+        commit_time: SystemTime,
+        xid: u32,
+    },
+
+    /// The end of a transaction.
+    Commit {
+        /// The position of the commit of the transaction.
+        commit_lsn: PgLsn,
+        /// The position following the commit, to [`ack`](super::PgReplicationStream::ack)
+        /// once the transaction has been processed.
+        end_lsn: PgLsn,
+        commit_time: SystemTime,
+    },
+
+    /// The origin of the transaction, when it was itself replicated from another server.
+    Origin { commit_lsn: PgLsn, name: String },
+
+    /// The definition of a table whose changes follow.
+    Relation(Arc<PgRelation>),
+
+    /// The definition of a user-defined type of a column of a relation.
+    Type {
+        oid: Oid,
+        namespace: String,
+        name: String,
+    },
+
+    /// A row was inserted.
+    Insert {
+        relation: Arc<PgRelation>,
+        new: PgTuple,
+    },
+
+    /// A row was updated.
+    Update {
+        relation: Arc<PgRelation>,
+        /// The previous values of the row: all of them if the replica identity of the table is
+        /// `FULL`, otherwise only the columns of its key, and only if they changed.
+        old: Option<PgTuple>,
+        new: PgTuple,
+    },
+
+    /// A row was deleted.
+    Delete {
+        relation: Arc<PgRelation>,
+        /// The values of the row: all of them if the replica identity of the table is `FULL`,
+        /// otherwise only the columns of its key, the others being null.
+        old: PgTuple,
+    },
+
+    /// Tables were truncated.
+    Truncate {
+        relations: Vec<Arc<PgRelation>>,
+        cascade: bool,
+        restart_identity: bool,
+    },
+}
+
+/// A table, as described by logical replication.
+#[derive(Debug, Clone)]
+pub struct PgRelation {
+    pub oid: Oid,
+    pub namespace: String,
+    pub name: String,
+    pub replica_identity: PgReplicaIdentity,
+    pub columns: Vec<PgRelationColumn>,
+}
+
+/// A column of a [`PgRelation`].
+#[derive(Debug, Clone)]
+pub struct PgRelationColumn {
+    pub name: String,
+    pub type_info: PgTypeInfo,
+    pub type_modifier: i32,
+    /// Whether the column is part of the key of the replica identity of the table.
+    pub is_key: bool,
+}
+
+/// The columns of a table identifying its rows in logical replication, sent as the old values
+/// of updates and deletes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PgReplicaIdentity {
+    /// The columns of the primary key.
+    Default,
+    /// No columns.
+    Nothing,
+    /// All the columns.
+    Full,
+    /// The columns of a unique index.
+    Index,
+}
+
+/// The values of a row, sent by logical replication.
+#[derive(Debug, Clone)]
+pub struct PgTuple {
+    relation: Arc<PgRelation>,
+    values: Vec<PgTupleValue>,
+}
+
+/// A value of a [`PgTuple`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PgTupleValue {
+    Null,
+    /// A TOASTed value which didn't change, so isn't sent.
+    Unchanged,
+    Text(Bytes),
+    Binary(Bytes),
+}
+
+impl PgTuple {
+    /// The table of the row.
+    pub fn relation(&self) -> &PgRelation {
+        &self.relation
+    }
+
+    /// The values of the row, in the order of the columns of the table.
+    pub fn values(&self) -> &[PgTupleValue] {
+        &self.values
+    }
+
+    /// Decode the value of a column of the row.
+    ///
+    /// Fails for an [`Unchanged`](PgTupleValue::Unchanged) value, as it wasn't sent.
+    pub fn try_get<'r, T>(&'r self, column: &str) -> Result<T, Error>
+    where
+        T: Decode<'r, Postgres> + Type<Postgres>,
+    {
+        let index = self
+            .relation
+            .columns
+            .iter()
+            .position(|c| c.name == column)
+            .ok_or_else(|| Error::ColumnNotFound(column.to_owned()))?;
+
+        let column_error = |source| Error::ColumnDecode {
+            index: format!("{:?}", column),
+            source,
+        };
+
+        let type_info = self.relation.columns[index].type_info.clone();
+        let (value, format) = match &self.values[index] {
+            PgTupleValue::Null => (None, PgValueFormat::Text),
+            PgTupleValue::Text(value) => (Some(&**value), PgValueFormat::Text),
+            PgTupleValue::Binary(value) => (Some(&**value), PgValueFormat::Binary),
+            PgTupleValue::Unchanged => {
+                return Err(column_error(
+                    "the unchanged TOASTed value wasn't sent".into(),
+                ));
+            }
+        };
+
+        let value = PgValueRef {
+            value,
+            row: None,
+            type_info,
+            format,
+        };
+
+        if !value.is_null() {
+            let ty = value.type_info();
+
+            if !ty.is_null() && !T::compatible(&ty) {
+                return Err(column_error(mismatched_types::<Postgres, T>(&ty)));
+            }
+        }
+
+        T::decode(value).map_err(column_error)
+    }
+}
+
+/// Decode a message of `pgoutput`, keeping the relations it defines in `relations` to resolve
+/// the ones of the following changes.
+pub(super) fn decode(
+    mut buf: Bytes,
+    relations: &mut HashMap<Oid, Arc<PgRelation>>,
+) -> Result<PgReplicationEvent, Error> {
+    let relation = |buf: &mut Bytes, relations: &HashMap<Oid, Arc<PgRelation>>| {
+        let oid = Oid(buf.get_u32());
+
+        relations
+            .get(&oid)
+            .cloned()
+            .ok_or_else(|| err_protocol!("change to relation {} before its definition", oid.0))
+    };
+
+    Ok(match buf.get_u8() {
+        b'B' => PgReplicationEvent::Begin {
+            final_lsn: PgLsn(buf.get_u64()),
+            commit_time: time(buf.get_i64()),
+            xid: buf.get_u32(),
+        },
+
+        b'C' => {
+            let _flags = buf.get_u8();
+
+            PgReplicationEvent::Commit {
+                commit_lsn: PgLsn(buf.get_u64()),
+                end_lsn: PgLsn(buf.get_u64()),
+                commit_time: time(buf.get_i64()),
+            }
+        }
+
+        b'O' => PgReplicationEvent::Origin {
+            commit_lsn: PgLsn(buf.get_u64()),
+            name: buf.get_str_nul()?,
+        },
+
+        b'R' => {
+            let oid = Oid(buf.get_u32());
+            let namespace = namespace(buf.get_str_nul()?);
+            let name = buf.get_str_nul()?;
+
+            let replica_identity = match buf.get_u8() {
+                b'd' => PgReplicaIdentity::Default,
+                b'n' => PgReplicaIdentity::Nothing,
+                b'f' => PgReplicaIdentity::Full,
+                b'i' => PgReplicaIdentity::Index,
+                identity => {
+                    return Err(err_protocol!(
+                        "unknown replica identity {:?}",
+                        identity as char
+                    ));
+                }
+            };
+
+            let num_columns = buf.get_i16();
+            let mut columns = Vec::with_capacity(cmp::max(num_columns, 0) as usize);
+
+            for _ in 0..num_columns {
+                let flags = buf.get_u8();
+                let name = buf.get_str_nul()?;
+                let type_oid = Oid(buf.get_u32());
+
+                columns.push(PgRelationColumn {
+                    name,
+                    type_info: PgTypeInfo::try_from_oid(type_oid)
+                        .unwrap_or_else(|| PgTypeInfo::with_oid(type_oid)),
+                    type_modifier: buf.get_i32(),
+                    is_key: flags & 1 != 0,
+                });
+            }
+
+            let relation = Arc::new(PgRelation {
+                oid,
+                namespace,
+                name,
+                replica_identity,
+                columns,
+            });
+
+            relations.insert(oid, relation.clone());
+
+            PgReplicationEvent::Relation(relation)
+        }
+
+        b'Y' => PgReplicationEvent::Type {
+            oid: Oid(buf.get_u32()),
+            namespace: namespace(buf.get_str_nul()?),
+            name: buf.get_str_nul()?,
+        },
+
+        b'I' => {
+            let relation = relation(&mut buf, relations)?;
+            expect_tag(&mut buf, b'N')?;
+
+            PgReplicationEvent::Insert {
+                new: tuple(&mut buf, &relation)?,
+                relation,
+            }
+        }
+
+        b'U' => {
+            let relation = relation(&mut buf, relations)?;
+
+            let old = match buf.first() {
+                Some(b'K' | b'O') => {
+                    buf.advance(1);
+                    Some(tuple(&mut buf, &relation)?)
+                }
+                _ => None,
+            };
+
+            expect_tag(&mut buf, b'N')?;
+
+            PgReplicationEvent::Update {
+                old,
+                new: tuple(&mut buf, &relation)?,
+                relation,
+            }
+        }
+
+        b'D' => {
+            let relation = relation(&mut buf, relations)?;
+
+            match buf.get_u8() {
+                b'K' | b'O' => {}
+                tag => return Err(err_protocol!("unexpected tuple type {:?}", tag as char)),
+            }
+
+            PgReplicationEvent::Delete {
+                old: tuple(&mut buf, &relation)?,
+                relation,
+            }
+        }
+
+        b'T' => {
+            let num_relations = buf.get_u32();
+            let options = buf.get_u8();
+
+            let relations = (0..num_relations)
+                .map(|_| relation(&mut buf, relations))
+                .collect::<Result<_, _>>()?;
+
+            PgReplicationEvent::Truncate {
+                relations,
+                cascade: options & 1 != 0,
+                restart_identity: options & 2 != 0,
+            }
+        }
+
+        tag => {
+            return Err(err_protocol!(
+                "unknown logical replication message {:?}",
+                tag as char
+            ));
+        }
+    })
+}
+
+fn expect_tag(buf: &mut Bytes, expected: u8) -> Result<(), Error> {
+    match buf.get_u8() {
+        tag if tag == expected => Ok(()),
+        tag => Err(err_protocol!(
+            "expected tuple type {:?}, got {:?}",
+            expected as char,
+            tag as char
+        )),
+    }
+}
+
+fn tuple(buf: &mut Bytes, relation: &Arc<PgRelation>) -> Result<PgTuple, Error> {
+    let num_columns = buf.get_i16();
+    let mut values = Vec::with_capacity(cmp::max(num_columns, 0) as usize);
+
+    for _ in 0..num_columns {
+        values.push(match buf.get_u8() {
+            b'n' => PgTupleValue::Null,
+            b'u' => PgTupleValue::Unchanged,
+            b't' => {
+                let len = buf.get_i32() as usize;
+                PgTupleValue::Text(buf.split_to(len))
+            }
+            b'b' => {
+                let len = buf.get_i32() as usize;
+                PgTupleValue::Binary(buf.split_to(len))
+            }
+            kind => return Err(err_protocol!("unknown tuple value kind {:?}", kind as char)),
+        });
+    }
+
+    if values.len() != relation.columns.len() {
+        return Err(err_protocol!(
+            "tuple of {} values for relation {:?} of {} columns",
+            values.len(),
+            relation.name,
+            relation.columns.len()
+        ));
+    }
+
+    Ok(PgTuple {
+        relation: relation.clone(),
+        values,
+    })
+}
+
+// an empty namespace is `pg_catalog`
+fn namespace(namespace: String) -> String {
+    if namespace.is_empty() {
+        "pg_catalog".to_owned()
+    } else {
+        namespace
+    }
+}
+
+/// Microseconds since 2000-01-01, the epoch of Postgres timestamps, from `SystemTime`.
+pub(super) fn pg_timestamp(time: SystemTime) -> i64 {
+    let since_epoch = time
+        .duration_since(UNIX_EPOCH + POSTGRES_EPOCH)
+        .unwrap_or_default();
+
+    since_epoch.as_micros() as i64
+}
+
+fn time(micros: i64) -> SystemTime {
+    let postgres_epoch = UNIX_EPOCH + POSTGRES_EPOCH;
+    let offset = Duration::from_micros(micros.unsigned_abs());
+
+    if micros < 0 {
+        postgres_epoch - offset
+    } else {
+        postgres_epoch + offset
+    }
+}
+
+// 2000-01-01 since 1970-01-01
+const POSTGRES_EPOCH: Duration = Duration::from_secs(946_684_800);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BufMut;
+
+    fn relation_message() -> Bytes {
+        let mut buf = Vec::new();
+        buf.push(b'R');
+        buf.put_u32(16385);
+        buf.extend_from_slice(b"public\0users\0");
+        buf.push(b'd');
+        buf.put_i16(2);
+        buf.push(1);
+        buf.extend_from_slice(b"id\0");
+        buf.put_u32(23);
+        buf.put_i32(-1);
+        buf.push(0);
+        buf.extend_from_slice(b"name\0");
+        buf.put_u32(25);
+        buf.put_i32(-1);
+
+        buf.into()
+    }
+
+    #[test]
+    fn it_decodes_changes() -> Result<(), Error> {
+        let mut relations = HashMap::new();
+
+        let relation = match decode(relation_message(), &mut relations)? {
+            PgReplicationEvent::Relation(relation) => relation,
+            event => panic!("unexpected event {:?}", event),
+        };
+
+        assert_eq!("public", relation.namespace);
+        assert_eq!("users", relation.name);
+        assert_eq!(PgReplicaIdentity::Default, relation.replica_identity);
+        assert_eq!(2, relation.columns.len());
+        assert!(relation.columns[0].is_key);
+        assert!(!relation.columns[1].is_key);
+
+        let mut buf = Vec::new();
+        buf.push(b'U');
+        buf.put_u32(16385);
+        buf.push(b'K');
+        buf.put_i16(2);
+        buf.push(b't');
+        buf.put_i32(1);
+        buf.push(b'1');
+        buf.push(b'n');
+        buf.push(b'N');
+        buf.put_i16(2);
+        buf.push(b't');
+        buf.put_i32(2);
+        buf.extend_from_slice(b"42");
+        buf.push(b't');
+        buf.put_i32(5);
+        buf.extend_from_slice(b"alice");
+
+        let (old, new) = match decode(buf.into(), &mut relations)? {
+            PgReplicationEvent::Update {
+                old: Some(old),
+                new,
+                ..
+            } => (old, new),
+            event => panic!("unexpected event {:?}", event),
+        };
+
+        assert_eq!(1, old.try_get::<i32>("id")?);
+        assert_eq!(None, old.try_get::<Option<String>>("name")?);
+        assert_eq!(42, new.try_get::<i32>("id")?);
+        assert_eq!("alice", new.try_get::<String>("name")?);
+        assert!(new.try_get::<i32>("email").is_err());
+        assert!(new.try_get::<String>("id").is_err());
+
+        let mut buf = Vec::new();
+        buf.push(b'D');
+        buf.put_u32(16386);
+        assert!(decode(buf.into(), &mut relations).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_decodes_transactions() -> Result<(), Error> {
+        let mut relations = HashMap::new();
+
+        let mut buf = Vec::new();
+        buf.push(b'C');
+        buf.push(0);
+        buf.put_u64(0x16_B374_D848);
+        buf.put_u64(0x16_B374_D878);
+        buf.put_i64(1_000_000);
+
+        match decode(buf.into(), &mut relations)? {
+            PgReplicationEvent::Commit {
+                commit_lsn,
+                end_lsn,
+                commit_time,
+            } => {
+                assert_eq!("16/B374D848", commit_lsn.to_string());
+                assert_eq!("16/B374D878", end_lsn.to_string());
+                assert_eq!(UNIX_EPOCH + Duration::from_secs(946_684_801), commit_time);
+                assert_eq!(1_000_000, pg_timestamp(commit_time));
+            }
+            event => panic!("unexpected event {:?}", event),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use crate::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// A position in the write-ahead log, a "log sequence number".
+///
+/// It's written as two hexadecimal numbers of up to 8 digits each, separated by a slash,
+/// as in `16/B374D848`, like the `pg_lsn` type of Postgres.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PgLsn(pub u64);
+
+impl Display for PgLsn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:X}/{:X}", self.0 >> 32, self.0 as u32)
+    }
+}
+
+impl FromStr for PgLsn {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let invalid =
+            || Error::Configuration(format!("invalid log sequence number {:?}", s).into());
+
+        let (high, low) = s.split_once('/').ok_or_else(invalid)?;
+        let high = u32::from_str_radix(high, 16).map_err(|_| invalid())?;
+        let low = u32::from_str_radix(low, 16).map_err(|_| invalid())?;
+
+        Ok(PgLsn((u64::from(high) << 32) | u64::from(low)))
+    }
+}
+
+#[test]
+fn it_formats_and_parses_lsns() {
+    let lsn: PgLsn = "16/B374D848".parse().unwrap();
+
+    assert_eq!(PgLsn(0x16_B374_D848), lsn);
+    assert_eq!("16/B374D848", lsn.to_string());
+    assert_eq!("0/0", PgLsn::default().to_string());
+
+    assert!("16B374D848".parse::<PgLsn>().is_err());
+    assert!("16/B374D848G".parse::<PgLsn>().is_err());
+}
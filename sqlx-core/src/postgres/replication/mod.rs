@@ -0,0 +1,349 @@
+//! Streaming logical replication, to consume the changes to tables as they are committed.
+//!
+//! Requires `wal_level = logical` on the server, a role with the `REPLICATION` attribute,
+//! and a publication of the tables to replicate:
+//!
+//! ```sql
+//! CREATE PUBLICATION my_publication FOR TABLE users, orders;
+//! ```
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! # use sqlx_core::error::Error;
+//! # use sqlx_core::postgres::{PgLsn, PgReplicationConnection, PgReplicationEvent};
+//! #
+//! # fn main() {
+//! # #[cfg(feature = "_rt-async-std")]
+//! # sqlx_rt::async_std::task::block_on::<_, Result<(), Error>>(async move {
+//! let mut conn = PgReplicationConnection::connect("postgres://localhost/mydb").await?;
+//! conn.create_replication_slot("my_slot", false).await?;
+//!
+//! let mut stream = conn
+//!     .start_replication("my_slot", &["my_publication"], PgLsn::default())
+//!     .await?;
+//!
+//! while let Some(event) = stream.recv().await? {
+//!     match event {
+//!         PgReplicationEvent::Insert { relation, new } => {
+//!             println!("insert into {}: {:?}", relation.name, new.try_get::<i64>("id")?);
+//!         }
+//!
+//!         // the transaction was processed; the server may discard its WAL
+//!         PgReplicationEvent::Commit { end_lsn, .. } => stream.ack(end_lsn).await?,
+//!
+//!         _ => {}
+//!     }
+//! }
+//! # Ok(())
+//! # }).unwrap();
+//! # }
+//! ```
+
+use std::cmp;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use bytes::{Buf, BufMut, Bytes};
+
+use crate::connection::{ConnectOptions, Connection};
+use crate::error::Error;
+use crate::executor::Executor;
+use crate::postgres::message::{CopyData, CopyDone, CopyResponse, MessageFormat, Query};
+use crate::postgres::types::Oid;
+use crate::postgres::{PgConnectOptions, PgConnection, PgQueryResult};
+use crate::row::Row;
+use crate::HashMap;
+
+mod event;
+mod lsn;
+
+pub use event::{
+    PgRelation, PgRelationColumn, PgReplicaIdentity, PgReplicationEvent, PgTuple, PgTupleValue,
+};
+pub use lsn::PgLsn;
+
+/// A connection in logical replication mode, to manage replication slots and start streaming
+/// changes from them.
+///
+/// Only simple statements, without arguments, can be executed on it.
+pub struct PgReplicationConnection {
+    conn: PgConnection,
+}
+
+/// The identity of the server, returned by
+/// [`identify_system`](PgReplicationConnection::identify_system).
+#[derive(Debug, Clone)]
+pub struct PgSystemIdentity {
+    /// The unique identifier of the cluster.
+    pub system_id: String,
+    pub timeline: u32,
+    /// The current position of the write-ahead log.
+    pub xlog_pos: PgLsn,
+    pub database: Option<String>,
+}
+
+/// A slot created by [`create_replication_slot`](PgReplicationConnection::create_replication_slot).
+#[derive(Debug, Clone)]
+pub struct PgReplicationSlot {
+    pub name: String,
+    /// The position from which the slot streams changes.
+    pub consistent_point: PgLsn,
+    /// The snapshot of the database at `consistent_point`, which can be imported with
+    /// `SET TRANSACTION SNAPSHOT` to read the initial data of the tables until the next
+    /// command on the replication connection.
+    pub snapshot_name: Option<String>,
+}
+
+impl PgReplicationConnection {
+    pub async fn connect(url: &str) -> Result<Self, Error> {
+        Self::connect_with(&PgConnectOptions::from_str(url)?).await
+    }
+
+    pub async fn connect_with(options: &PgConnectOptions) -> Result<Self, Error> {
+        let mut options = options.clone();
+        options.replication = true;
+
+        Ok(Self {
+            conn: options.connect().await?,
+        })
+    }
+
+    /// Execute a SQL statement, e.g. to create a publication.
+    pub async fn execute(&mut self, sql: &str) -> Result<PgQueryResult, Error> {
+        self.conn.execute(sql).await
+    }
+
+    /// Identify the cluster and the current position of its write-ahead log.
+    pub async fn identify_system(&mut self) -> Result<PgSystemIdentity, Error> {
+        let row = self.conn.fetch_one("IDENTIFY_SYSTEM").await?;
+
+        Ok(PgSystemIdentity {
+            system_id: row.try_get("systemid")?,
+            timeline: row.try_get::<i32, _>("timeline")? as u32,
+            xlog_pos: row.try_get::<String, _>("xlogpos")?.parse()?,
+            database: row.try_get("dbname")?,
+        })
+    }
+
+    /// Create a logical replication slot for the `pgoutput` plugin, which keeps the changes
+    /// from now on until they are acknowledged by a stream.
+    ///
+    /// A temporary slot is dropped when the connection is closed.
+    pub async fn create_replication_slot(
+        &mut self,
+        name: &str,
+        temporary: bool,
+    ) -> Result<PgReplicationSlot, Error> {
+        let sql = format!(
+            "CREATE_REPLICATION_SLOT {}{} LOGICAL pgoutput",
+            ident(name),
+            if temporary { " TEMPORARY" } else { "" }
+        );
+
+        let row = self.conn.fetch_one(&*sql).await?;
+
+        Ok(PgReplicationSlot {
+            name: row.try_get("slot_name")?,
+            consistent_point: row.try_get::<String, _>("consistent_point")?.parse()?,
+            snapshot_name: row.try_get("snapshot_name")?,
+        })
+    }
+
+    /// Drop a replication slot, releasing the changes it keeps.
+    pub async fn drop_replication_slot(&mut self, name: &str) -> Result<(), Error> {
+        self.conn
+            .execute(&*format!("DROP_REPLICATION_SLOT {}", ident(name)))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Start streaming the changes to the tables of `publications` from the slot, from the
+    /// position `start` or from the last acknowledged one, whichever is later.
+    pub async fn start_replication(
+        mut self,
+        slot: &str,
+        publications: &[&str],
+        start: PgLsn,
+    ) -> Result<PgReplicationStream, Error> {
+        let publications = publications
+            .iter()
+            .map(|publication| ident(publication))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let sql = format!(
+            "START_REPLICATION SLOT {} LOGICAL {} (proto_version '1', publication_names {})",
+            ident(slot),
+            start,
+            literal(&publications)
+        );
+
+        self.conn.wait_until_ready().await?;
+        self.conn.stream.send(Query(&sql)).await?;
+
+        let _: CopyResponse = self
+            .conn
+            .stream
+            .recv_expect(MessageFormat::CopyBothResponse)
+            .await?;
+
+        Ok(PgReplicationStream {
+            conn: self.conn,
+            relations: HashMap::new(),
+            received_lsn: start,
+            flushed_lsn: start,
+            done: false,
+        })
+    }
+
+    /// Explicitly close this connection.
+    pub async fn close(self) -> Result<(), Error> {
+        self.conn.close().await
+    }
+}
+
+/// A stream of the changes of a logical replication slot.
+///
+/// The changes of a transaction stay in the slot, and are sent again by the next stream from
+/// it, until they are acknowledged with [`ack`](Self::ack).
+pub struct PgReplicationStream {
+    conn: PgConnection,
+    relations: HashMap<Oid, Arc<PgRelation>>,
+    received_lsn: PgLsn,
+    flushed_lsn: PgLsn,
+    done: bool,
+}
+
+impl PgReplicationStream {
+    /// Receive the next message, or `None` if the server ended the replication.
+    ///
+    /// The keepalive messages of the server are answered while waiting.
+    pub async fn recv(&mut self) -> Result<Option<PgReplicationEvent>, Error> {
+        while !self.done {
+            let message = self.conn.stream.recv().await?;
+
+            match message.format {
+                MessageFormat::CopyData => {
+                    let CopyData(mut data) = message.decode::<CopyData<Bytes>>()?;
+
+                    match data.get_u8() {
+                        // XLogData
+                        b'w' => {
+                            let wal_start = PgLsn(data.get_u64());
+                            let _wal_end = data.get_u64();
+                            let _send_time = data.get_i64();
+
+                            self.received_lsn = cmp::max(self.received_lsn, wal_start);
+
+                            return event::decode(data, &mut self.relations).map(Some);
+                        }
+
+                        // Primary keepalive message
+                        b'k' => {
+                            let _wal_end = data.get_u64();
+                            let _send_time = data.get_i64();
+
+                            if data.get_u8() == 1 {
+                                self.send_status().await?;
+                            }
+                        }
+
+                        tag => {
+                            return Err(err_protocol!(
+                                "unknown replication message {:?}",
+                                tag as char
+                            ));
+                        }
+                    }
+                }
+
+                // the server ended the replication, e.g. as it's shutting down
+                MessageFormat::CopyDone => {
+                    self.conn.stream.send(CopyDone).await?;
+                    self.end().await?;
+                }
+
+                format => {
+                    return Err(err_protocol!(
+                        "unexpected message format during replication: {:?}",
+                        format
+                    ));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Acknowledge that the changes up to `lsn`, usually the `end_lsn` of a
+    /// [`Commit`](PgReplicationEvent::Commit), have been processed, so the server can
+    /// discard them.
+    pub async fn ack(&mut self, lsn: PgLsn) -> Result<(), Error> {
+        self.flushed_lsn = cmp::max(self.flushed_lsn, lsn);
+        self.received_lsn = cmp::max(self.received_lsn, lsn);
+
+        self.send_status().await
+    }
+
+    /// Stop streaming, returning the connection to manage the slots again.
+    pub async fn finish(mut self) -> Result<PgReplicationConnection, Error> {
+        if !self.done {
+            self.send_status().await?;
+            self.conn.stream.send(CopyDone).await?;
+            self.end().await?;
+        }
+
+        Ok(PgReplicationConnection { conn: self.conn })
+    }
+
+    // Standby status update
+    async fn send_status(&mut self) -> Result<(), Error> {
+        let mut data = Vec::with_capacity(34);
+        data.push(b'r');
+        data.put_u64(self.received_lsn.0);
+        data.put_u64(self.flushed_lsn.0);
+        data.put_u64(self.flushed_lsn.0);
+        data.put_i64(event::pg_timestamp(SystemTime::now()));
+        // no reply requested
+        data.put_u8(0);
+
+        self.conn.stream.send(CopyData(data)).await
+    }
+
+    // after both sides sent `CopyDone`, discard what remains until the end of the command
+    async fn end(&mut self) -> Result<(), Error> {
+        loop {
+            let message = self.conn.stream.recv().await?;
+
+            match message.format {
+                MessageFormat::CopyData
+                | MessageFormat::CopyDone
+                | MessageFormat::CommandComplete => {}
+
+                MessageFormat::ReadyForQuery => break,
+
+                format => {
+                    return Err(err_protocol!(
+                        "unexpected message format ending replication: {:?}",
+                        format
+                    ));
+                }
+            }
+        }
+
+        self.done = true;
+
+        Ok(())
+    }
+}
+
+fn ident(name: &str) -> String {
+    format!(r#""{}""#, name.replace('"', r#""""#))
+}
+
+fn literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
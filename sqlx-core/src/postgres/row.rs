@@ -50,19 +50,237 @@ impl ColumnIndex<PgRow> for &'_ str {
     }
 }
 
+#[cfg(feature = "any")]
+impl PgRow {
+    // the converted columns are shared with the other rows of the result set
+    pub(crate) fn into_any(
+        self,
+        columns: &mut crate::any::row::AnyColumnsCache,
+    ) -> crate::any::AnyRow {
+        crate::any::AnyRow {
+            columns: columns.get(&self.metadata, |metadata| {
+                metadata
+                    .columns
+                    .iter()
+                    .map(|col| col.clone().into())
+                    .collect()
+            }),
+            kind: crate::any::row::AnyRowKind::Postgres(self),
+        }
+    }
+}
+
 #[cfg(feature = "any")]
 impl From<PgRow> for crate::any::AnyRow {
     #[inline]
     fn from(row: PgRow) -> Self {
-        crate::any::AnyRow {
-            columns: row
-                .metadata
-                .columns
-                .iter()
-                .map(|col| col.clone().into())
-                .collect(),
-
-            kind: crate::any::row::AnyRowKind::Postgres(row),
+        row.into_any(&mut Default::default())
+    }
+}
+
+/// Serializes the row as a map of its values by the names of their columns, e.g. as a JSON
+/// object.
+///
+/// The numbers, booleans, strings and enums, JSON, byte arrays and arrays of these are
+/// serialized as themselves; `NUMERIC`, `UUID` and the dates and times as strings, which
+/// requires the `bigdecimal` or `decimal` feature for `NUMERIC`, and `chrono` for the dates and
+/// times. The other types fail to serialize.
+#[cfg(feature = "json")]
+impl serde::Serialize for PgRow {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        crate::row::serialize_row(self, serializer, |_, value| to_json(value))
+    }
+}
+
+#[cfg(feature = "json")]
+fn to_json(value: PgValueRef<'_>) -> Result<serde_json::Value, crate::error::BoxDynError> {
+    use crate::decode::Decode;
+    use crate::postgres::{PgTypeInfo, PgTypeKind};
+    use crate::types::{JsonValue, Type};
+
+    let ty = value.type_info.clone();
+
+    macro_rules! decode {
+        ($T:ty) => {
+            <$T as Decode<'_, Postgres>>::decode(value.clone())?
+        };
+    }
+
+    macro_rules! compatible {
+        ($T:ty) => {
+            <$T as Type<Postgres>>::compatible(&ty)
+        };
+    }
+
+    let json = if compatible!(bool) {
+        decode!(bool).into()
+    } else if compatible!(i8) {
+        decode!(i8).into()
+    } else if compatible!(i16) {
+        decode!(i16).into()
+    } else if compatible!(i32) {
+        decode!(i32).into()
+    } else if compatible!(i64) {
+        decode!(i64).into()
+    } else if ty == PgTypeInfo::OID {
+        decode!(crate::postgres::types::Oid).0.into()
+    } else if compatible!(f32) {
+        f64::from(decode!(f32)).into()
+    } else if compatible!(f64) {
+        decode!(f64).into()
+    } else if compatible!(String) || matches!(ty.kind(), PgTypeKind::Enum(_)) {
+        value.as_str()?.into()
+    } else if compatible!(Vec<u8>) {
+        value.as_bytes()?.into()
+    } else if compatible!(JsonValue) {
+        decode!(JsonValue)
+    } else if ty == PgTypeInfo::UUID {
+        uuid_string(value.as_bytes()?)?.into()
+    } else if ty == PgTypeInfo::NUMERIC {
+        numeric_string(value)?.into()
+    } else if let Some(json) = datetime_json(&value)? {
+        json
+    } else if compatible!(Vec<bool>) {
+        decode!(Vec<bool>).into()
+    } else if compatible!(Vec<i16>) {
+        decode!(Vec<i16>).into()
+    } else if compatible!(Vec<i32>) {
+        decode!(Vec<i32>).into()
+    } else if compatible!(Vec<i64>) {
+        decode!(Vec<i64>).into()
+    } else if compatible!(Vec<f32>) {
+        decode!(Vec<f32>).into()
+    } else if compatible!(Vec<f64>) {
+        decode!(Vec<f64>).into()
+    } else if compatible!(Vec<String>) {
+        decode!(Vec<String>).into()
+    } else {
+        return Err(format!("values of type {} are not supported", ty).into());
+    };
+
+    Ok(json)
+}
+
+#[cfg(feature = "json")]
+fn uuid_string(bytes: &[u8]) -> Result<String, crate::error::BoxDynError> {
+    if bytes.len() != 16 {
+        return Err(format!("expected 16 bytes for a UUID, got {}", bytes.len()).into());
+    }
+
+    let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+
+    Ok(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    ))
+}
+
+#[cfg(feature = "json")]
+fn numeric_string(value: PgValueRef<'_>) -> Result<String, crate::error::BoxDynError> {
+    #[cfg(feature = "bigdecimal")]
+    return Ok(
+        <bigdecimal::BigDecimal as crate::decode::Decode<'_, Postgres>>::decode(value)?.to_string(),
+    );
+
+    #[cfg(all(feature = "decimal", not(feature = "bigdecimal")))]
+    return Ok(
+        <rust_decimal::Decimal as crate::decode::Decode<'_, Postgres>>::decode(value)?.to_string(),
+    );
+
+    #[cfg(not(any(feature = "bigdecimal", feature = "decimal")))]
+    {
+        let _ = value;
+
+        Err("the `bigdecimal` or `decimal` feature is required to serialize NUMERIC".into())
+    }
+}
+
+// the dates and times, as strings in the ISO 8601 format, or `None` if `value` isn't one
+#[cfg(feature = "json")]
+fn datetime_json(
+    value: &PgValueRef<'_>,
+) -> Result<Option<serde_json::Value>, crate::error::BoxDynError> {
+    use crate::postgres::PgTypeInfo;
+
+    let ty = &value.type_info;
+
+    if ![
+        PgTypeInfo::TIMESTAMPTZ,
+        PgTypeInfo::TIMESTAMP,
+        PgTypeInfo::DATE,
+        PgTypeInfo::TIME,
+    ]
+    .contains(ty)
+    {
+        return Ok(None);
+    }
+
+    #[cfg(feature = "chrono")]
+    {
+        use crate::decode::Decode;
+        use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+
+        let value = value.clone();
+
+        let s = if *ty == PgTypeInfo::TIMESTAMPTZ {
+            <DateTime<Utc> as Decode<'_, Postgres>>::decode(value)?.to_rfc3339()
+        } else if *ty == PgTypeInfo::TIMESTAMP {
+            <NaiveDateTime as Decode<'_, Postgres>>::decode(value)?
+                .format("%Y-%m-%dT%H:%M:%S%.f")
+                .to_string()
+        } else if *ty == PgTypeInfo::DATE {
+            <NaiveDate as Decode<'_, Postgres>>::decode(value)?.to_string()
+        } else {
+            <NaiveTime as Decode<'_, Postgres>>::decode(value)?.to_string()
+        };
+
+        Ok(Some(s.into()))
+    }
+
+    #[cfg(not(feature = "chrono"))]
+    Err("the `chrono` feature is required to serialize dates and times".into())
+}
+
+#[cfg(feature = "parquet")]
+impl crate::export::parquet::ParquetDatabase for Postgres {
+    fn parquet_type(ty: &crate::postgres::PgTypeInfo) -> crate::export::parquet::ParquetType {
+        use crate::export::parquet::ParquetType;
+        use crate::postgres::{PgTypeInfo, PgTypeKind};
+        use crate::types::{JsonValue, Type};
+
+        macro_rules! compatible {
+            ($T:ty) => {
+                <$T as Type<Postgres>>::compatible(ty)
+            };
+        }
+
+        if compatible!(bool) {
+            ParquetType::Boolean
+        } else if compatible!(i8) {
+            ParquetType::Int8
+        } else if compatible!(i16) {
+            ParquetType::Int16
+        } else if compatible!(i32) {
+            ParquetType::Int32
+        } else if compatible!(i64) || *ty == PgTypeInfo::OID {
+            ParquetType::Int64
+        } else if compatible!(f32) {
+            ParquetType::Float
+        } else if compatible!(f64) {
+            ParquetType::Double
+        } else if compatible!(Vec<u8>) {
+            ParquetType::Bytes
+        } else if compatible!(JsonValue) || matches!(ty.kind(), PgTypeKind::Array(_)) {
+            ParquetType::Json
+        } else {
+            ParquetType::String
         }
     }
 }
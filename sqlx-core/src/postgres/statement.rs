@@ -1,5 +1,6 @@
 use super::{PgColumn, PgTypeInfo};
 use crate::column::ColumnIndex;
+use crate::common::StatementCache;
 use crate::error::Error;
 use crate::ext::ustr::UStr;
 use crate::postgres::{PgArguments, Postgres};
@@ -7,7 +8,7 @@ use crate::statement::Statement;
 use crate::HashMap;
 use either::Either;
 use std::borrow::Cow;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 #[derive(Debug, Clone)]
 pub struct PgStatement<'q> {
@@ -22,6 +23,30 @@ pub(crate) struct PgStatementMetadata {
     pub(crate) parameters: Vec<PgTypeInfo>,
 }
 
+// the metadata of statements, shared by the connections opened with the same options
+// (e.g. by the connections of a pool) so that each doesn't describe the statements again
+#[derive(Debug, Clone)]
+pub(crate) struct PgSharedStatementCache(Arc<Mutex<StatementCache<Arc<PgStatementMetadata>>>>);
+
+impl PgSharedStatementCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self(Arc::new(Mutex::new(StatementCache::new(capacity))))
+    }
+
+    pub(crate) fn get(&self, sql: &str) -> Option<Arc<PgStatementMetadata>> {
+        self.lock().get_mut(sql).cloned()
+    }
+
+    pub(crate) fn insert(&self, sql: &str, metadata: Arc<PgStatementMetadata>) {
+        self.lock().insert(sql, metadata);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, StatementCache<Arc<PgStatementMetadata>>> {
+        // the cache is valid even if a thread panicked while holding the lock
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
 impl<'q> Statement<'q> for PgStatement<'q> {
     type Database = Postgres;
 
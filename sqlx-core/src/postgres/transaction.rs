@@ -5,7 +5,7 @@ use crate::executor::Executor;
 use crate::postgres::{PgConnection, Postgres};
 use crate::transaction::{
     begin_ansi_transaction_sql, commit_ansi_transaction_sql, rollback_ansi_transaction_sql,
-    TransactionManager,
+    TransactionManager, TransactionOptions,
 };
 
 /// Implementation of [`TransactionManager`] for PostgreSQL.
@@ -25,6 +25,20 @@ impl TransactionManager for PgTransactionManager {
         })
     }
 
+    fn begin_with(
+        conn: &mut PgConnection,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            // the timeout is enforced by the `Transaction`
+            conn.execute(&*begin_sql(options)).await?;
+
+            conn.transaction_depth += 1;
+
+            Ok(())
+        })
+    }
+
     fn commit(conn: &mut PgConnection) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
             if conn.transaction_depth > 0 {
@@ -58,4 +72,55 @@ impl TransactionManager for PgTransactionManager {
             conn.transaction_depth -= 1;
         }
     }
+
+    fn start_statement(conn: &mut PgConnection, sql: &str) {
+        conn.queue_simple_query(sql);
+    }
+}
+
+// https://www.postgresql.org/docs/current/sql-begin.html
+fn begin_sql(options: TransactionOptions) -> String {
+    let mut modes = Vec::new();
+
+    if let Some(isolation) = options.isolation {
+        modes.push(format!("ISOLATION LEVEL {}", isolation.as_sql()));
+    }
+
+    match options.read_only {
+        Some(true) => modes.push("READ ONLY".into()),
+        Some(false) => modes.push("READ WRITE".into()),
+        None => {}
+    }
+
+    match options.deferrable {
+        Some(true) => modes.push("DEFERRABLE".into()),
+        Some(false) => modes.push("NOT DEFERRABLE".into()),
+        None => {}
+    }
+
+    if modes.is_empty() {
+        "BEGIN".into()
+    } else {
+        format!("BEGIN {}", modes.join(", "))
+    }
+}
+
+#[test]
+fn test_begin_sql() {
+    use crate::transaction::IsolationLevel;
+
+    assert_eq!(begin_sql(TransactionOptions::new()), "BEGIN");
+    assert_eq!(
+        begin_sql(
+            TransactionOptions::new()
+                .isolation(IsolationLevel::Serializable)
+                .read_only(true)
+                .deferrable(true)
+        ),
+        "BEGIN ISOLATION LEVEL SERIALIZABLE, READ ONLY, DEFERRABLE"
+    );
+    assert_eq!(
+        begin_sql(TransactionOptions::new().read_only(false)),
+        "BEGIN READ WRITE"
+    );
 }
@@ -193,6 +193,39 @@ where
         executor.fetch_many(self)
     }
 
+    /// Execute the query and call `f` with each generated row, in order, stopping at the first
+    /// error.
+    ///
+    /// The row is only borrowed by `f`, so its values can be read with
+    /// [`Row::get_borrowed()`][crate::row::Row::get_borrowed] instead of being copied out of
+    /// the row; it is dropped once `f` returns.
+    ///
+    /// ```rust,ignore
+    /// let mut len = 0;
+    ///
+    /// sqlx::query("SELECT body FROM articles")
+    ///     .fetch_for_each(&mut conn, |row| {
+    ///         len += row.try_get_borrowed::<str, _>("body")?.len();
+    ///         Ok(())
+    ///     })
+    ///     .await?;
+    /// ```
+    pub async fn fetch_for_each<'e, 'c: 'e, E, F>(self, executor: E, mut f: F) -> Result<(), Error>
+    where
+        'q: 'e,
+        A: 'e,
+        E: Executor<'c, Database = DB>,
+        F: FnMut(&DB::Row) -> Result<(), Error> + Send,
+    {
+        let mut rows = executor.fetch(self);
+
+        while let Some(row) = rows.try_next().await? {
+            f(&row)?;
+        }
+
+        Ok(())
+    }
+
     /// Execute the query and return all the generated results, collected into a [`Vec`].
     #[inline]
     pub async fn fetch_all<'e, 'c: 'e, E>(self, executor: E) -> Result<Vec<DB::Row>, Error>
@@ -0,0 +1,268 @@
+use std::marker::PhantomData;
+
+use futures_core::stream::BoxStream;
+
+use crate::database::{Database, HasArguments, HasStatement};
+use crate::error::Error;
+use crate::executor::{Execute, Executor};
+
+/// A batch of SQL statements separated by semicolons, without bind parameters.
+///
+/// Returned by [`query_batch`] and `query_batch!()`.
+///
+/// The batch is sent using the "simple" query protocol of the database, if it has one, so all
+/// statements are executed in a single round-trip and none of them are prepared or cached.
+#[must_use = "query must be executed to affect database"]
+pub struct QueryBatch<'q, DB: Database> {
+    sql: &'q str,
+    database: PhantomData<DB>,
+}
+
+impl<'q, DB: Database> Execute<'q, DB> for QueryBatch<'q, DB> {
+    #[inline]
+    fn sql(&self) -> &'q str {
+        self.sql
+    }
+
+    #[inline]
+    fn statement(&self) -> Option<&<DB as HasStatement<'q>>::Statement> {
+        None
+    }
+
+    #[inline]
+    fn take_arguments(&mut self) -> Option<<DB as HasArguments<'q>>::Arguments> {
+        None
+    }
+
+    #[inline]
+    fn persistent(&self) -> bool {
+        false
+    }
+}
+
+impl<'q, DB: Database> QueryBatch<'q, DB> {
+    /// Execute the batch and return the total number of rows affected by all statements.
+    #[inline]
+    pub async fn execute<'e, 'c: 'e, E>(self, executor: E) -> Result<DB::QueryResult, Error>
+    where
+        'q: 'e,
+        E: Executor<'c, Database = DB>,
+    {
+        executor.execute(self).await
+    }
+
+    /// Execute the batch and return the rows affected by each statement, in a stream.
+    #[inline]
+    pub fn execute_many<'e, 'c: 'e, E>(
+        self,
+        executor: E,
+    ) -> BoxStream<'e, Result<DB::QueryResult, Error>>
+    where
+        'q: 'e,
+        E: Executor<'c, Database = DB>,
+    {
+        executor.execute_many(self)
+    }
+}
+
+/// Make a batch of SQL statements separated by semicolons, to be executed at once.
+///
+/// No bind parameters can be used; any rows returned by the statements are discarded.
+pub fn query_batch<DB>(sql: &str) -> QueryBatch<'_, DB>
+where
+    DB: Database,
+{
+    QueryBatch {
+        sql,
+        database: PhantomData,
+    }
+}
+
+/// Split a script at the semicolons between statements, skipping over quoted strings, quoted
+/// identifiers, dollar-quoted strings and comments; e.g. for `query_batch!()` to check each
+/// statement on its own. Statements that are empty or only comments are dropped.
+///
+/// Within a `CREATE` statement, semicolons between `BEGIN` (or `CASE`) and `END` don't separate
+/// statements either, as in the body of a trigger.
+pub fn split_statements(sql: &str) -> Vec<&str> {
+    let bytes = sql.as_bytes();
+
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+
+    // whether the current statement has anything besides whitespace and comments
+    let mut has_code = false;
+    let mut is_create = false;
+    let mut depth = 0_usize;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                i = find(sql, i, "\n").map_or(bytes.len(), |end| end + 1);
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = find(sql, i + 2, "*/").map_or(bytes.len(), |end| end + 2);
+                continue;
+            }
+            quote @ (b'\'' | b'"' | b'`') => {
+                has_code = true;
+                i = skip_quoted(bytes, i, quote);
+                continue;
+            }
+            b'$' if !bytes[..i].last().map_or(false, |&c| is_word(c)) => {
+                has_code = true;
+
+                if let Some(tag_len) = dollar_tag(&bytes[i..]) {
+                    let tag = &sql[i..i + tag_len];
+                    i = find(sql, i + tag_len, tag).map_or(bytes.len(), |end| end + tag_len);
+                    continue;
+                }
+            }
+            b';' if depth == 0 => {
+                if has_code {
+                    statements.push(sql[start..i].trim());
+                }
+
+                start = i + 1;
+                has_code = false;
+                is_create = false;
+            }
+            c if is_word(c) => {
+                let end = bytes[i..]
+                    .iter()
+                    .position(|&c| !is_word(c))
+                    .map_or(bytes.len(), |len| i + len);
+                let word = &sql[i..end];
+
+                if !has_code {
+                    is_create = word.eq_ignore_ascii_case("create");
+                } else if is_create {
+                    if word.eq_ignore_ascii_case("begin") || word.eq_ignore_ascii_case("case") {
+                        depth += 1;
+                    } else if word.eq_ignore_ascii_case("end") {
+                        depth = depth.saturating_sub(1);
+                    }
+                }
+
+                has_code = true;
+                i = end;
+                continue;
+            }
+            c if !c.is_ascii_whitespace() => has_code = true,
+            _ => {}
+        }
+
+        i += 1;
+    }
+
+    if has_code {
+        statements.push(sql[start..].trim());
+    }
+
+    statements
+}
+
+fn is_word(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_' || c >= 0x80
+}
+
+fn find(sql: &str, from: usize, pattern: &str) -> Option<usize> {
+    sql[from..].find(pattern).map(|pos| from + pos)
+}
+
+// the end of a string or identifier starting at `start`, where a doubled quote is an escaped one
+fn skip_quoted(bytes: &[u8], start: usize, quote: u8) -> usize {
+    let mut i = start + 1;
+
+    while i < bytes.len() {
+        if bytes[i] == quote {
+            if bytes.get(i + 1) == Some(&quote) {
+                i += 2;
+                continue;
+            }
+
+            return i + 1;
+        }
+
+        i += 1;
+    }
+
+    bytes.len()
+}
+
+// the length of the tag starting a dollar-quoted string, e.g. `$$` or `$body$`
+fn dollar_tag(bytes: &[u8]) -> Option<usize> {
+    let len = bytes[1..].iter().position(|&c| !is_word(c))? + 1;
+
+    match bytes.get(len) {
+        // a positional parameter, e.g. `$1`, isn't a tag
+        Some(b'$') if !bytes.get(1).map_or(false, u8::is_ascii_digit) => Some(len + 1),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_splits_statements() {
+        assert_eq!(
+            split_statements("INSERT INTO foo VALUES (1);\n  DELETE FROM bar WHERE id = 2;\n"),
+            ["INSERT INTO foo VALUES (1)", "DELETE FROM bar WHERE id = 2"]
+        );
+
+        assert_eq!(
+            split_statements("SELECT ';', \"a;b\" -- c;d\nFROM foo; /* ; */ SELECT 1"),
+            ["SELECT ';', \"a;b\" -- c;d\nFROM foo", "/* ; */ SELECT 1"]
+        );
+
+        assert_eq!(
+            split_statements("SELECT 'a;''b', `c;d`; SELECT 2"),
+            ["SELECT 'a;''b', `c;d`", "SELECT 2"]
+        );
+
+        assert_eq!(
+            split_statements(
+                "CREATE FUNCTION f() RETURNS int AS $body$ SELECT 1; $body$ LANGUAGE sql; \
+                 SELECT $1, $$;$$"
+            ),
+            [
+                "CREATE FUNCTION f() RETURNS int AS $body$ SELECT 1; $body$ LANGUAGE sql",
+                "SELECT $1, $$;$$"
+            ]
+        );
+    }
+
+    #[test]
+    fn it_splits_trigger_bodies() {
+        assert_eq!(
+            split_statements(
+                "CREATE TRIGGER t AFTER INSERT ON foo BEGIN \
+                 UPDATE foo SET name = CASE WHEN id > 0 THEN 'a' ELSE 'b' END; \
+                 DELETE FROM bar; \
+                 END;\n\
+                 BEGIN; DROP TABLE bar;"
+            ),
+            [
+                "CREATE TRIGGER t AFTER INSERT ON foo BEGIN \
+                 UPDATE foo SET name = CASE WHEN id > 0 THEN 'a' ELSE 'b' END; \
+                 DELETE FROM bar; \
+                 END",
+                "BEGIN",
+                "DROP TABLE bar",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_drops_empty_statements() {
+        assert_eq!(
+            split_statements(";; SELECT 1;  ; -- the end\n"),
+            ["SELECT 1"]
+        );
+        assert!(split_statements("  -- nothing\n /* to see */ ").is_empty());
+    }
+}
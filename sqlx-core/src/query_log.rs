@@ -0,0 +1,281 @@
+//! Structured logging of the executed statements.
+//!
+//! By default, the statements are logged as text to the `sqlx::query` target of the `log`
+//! crate. A [`QueryLog`] set with [`ConnectOptions::query_log()`] receives them as
+//! [records](QueryRecord) instead, at the levels set with
+//! [`ConnectOptions::log_statements()`] and [`ConnectOptions::log_slow_statements()`], e.g. to
+//! log them as JSON with [`JsonQueryLog`]:
+//!
+//! ```rust,ignore
+//! let mut options: PgConnectOptions = url.parse()?;
+//! options.query_log(Arc::new(JsonQueryLog::new().pool("primary")));
+//!
+//! let pool = PgPoolOptions::new().connect_with(options).await?;
+//! ```
+//!
+//! [`ConnectOptions::query_log()`]: crate::connection::ConnectOptions::query_log
+//! [`ConnectOptions::log_statements()`]: crate::connection::ConnectOptions::log_statements
+//! [`ConnectOptions::log_slow_statements()`]: crate::connection::ConnectOptions::log_slow_statements
+
+use std::fmt::{self, Debug, Formatter, Write};
+use std::time::Duration;
+
+use log::Level;
+
+/// A destination for the records of the executed statements; see the
+/// [module documentation][self].
+pub trait QueryLog: Send + Sync + 'static {
+    /// Called once a statement completed, or its execution was dropped.
+    ///
+    /// It's called by the task executing the statement, so it shouldn't block.
+    fn log(&self, record: &QueryRecord<'_>);
+}
+
+impl Debug for dyn QueryLog {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("QueryLog(..)")
+    }
+}
+
+/// A statement executed by a connection, given to a [`QueryLog`].
+#[derive(Debug)]
+pub struct QueryRecord<'a> {
+    pub(crate) sql: &'a str,
+    pub(crate) level: Level,
+    pub(crate) slow: bool,
+    pub(crate) elapsed: Duration,
+    pub(crate) rows_affected: u64,
+    pub(crate) rows_returned: u64,
+    pub(crate) connection_id: Option<u64>,
+}
+
+impl<'a> QueryRecord<'a> {
+    /// The SQL of the statement.
+    pub fn sql(&self) -> &'a str {
+        self.sql
+    }
+
+    /// A hash of the SQL of the statement, the same in every process, to group the executions
+    /// of a statement with.
+    pub fn hash(&self) -> u64 {
+        // 64-bit FNV-1a
+        self.sql.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        })
+    }
+
+    /// The level to log the statement at.
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// Whether the statement took longer than the duration set with
+    /// [`ConnectOptions::log_slow_statements()`][crate::connection::ConnectOptions::log_slow_statements].
+    pub fn is_slow(&self) -> bool {
+        self.slow
+    }
+
+    /// The time the statement took, from when it was sent until its last row was received.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn rows_affected(&self) -> u64 {
+        self.rows_affected
+    }
+
+    pub fn rows_returned(&self) -> u64 {
+        self.rows_returned
+    }
+
+    /// The identifier of the connection on the server: the process ID of the backend for
+    /// Postgres, or the connection ID for MySQL; `None` for the other databases.
+    pub fn connection_id(&self) -> Option<u64> {
+        self.connection_id
+    }
+}
+
+/// A [`QueryLog`] logging each statement as a JSON object, to the `sqlx::query` target of the
+/// `log` crate:
+///
+/// ```json
+/// {"statement_hash":"8d3f0d5aa1f3c1b2","sql":"SELECT * FROM users WHERE id = $1","duration_ms":1.234,"rows_affected":0,"rows_returned":1,"slow":false,"connection_id":4242,"pool":"primary"}
+/// ```
+///
+/// `connection_id` and `pool` are `null` if unknown. The SQL is truncated to 1024 bytes by
+/// default, ending with `…` if it was.
+#[derive(Debug, Clone)]
+pub struct JsonQueryLog {
+    pool: Option<String>,
+    max_sql_length: usize,
+}
+
+impl Default for JsonQueryLog {
+    fn default() -> Self {
+        Self {
+            pool: None,
+            max_sql_length: 1024,
+        }
+    }
+}
+
+impl JsonQueryLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name of the pool the statements are executed by, the pool of the connect
+    /// options this log is set on.
+    pub fn pool(mut self, name: &str) -> Self {
+        self.pool = Some(name.to_owned());
+        self
+    }
+
+    /// Sets the length, in bytes, the SQL is truncated to. Defaults to 1024.
+    pub fn max_sql_length(mut self, length: usize) -> Self {
+        self.max_sql_length = length;
+        self
+    }
+
+    /// The JSON object logged for `record`.
+    pub fn to_json(&self, record: &QueryRecord<'_>) -> String {
+        let mut sql = record.sql;
+        let truncated = sql.len() > self.max_sql_length;
+
+        if truncated {
+            let mut end = self.max_sql_length;
+
+            while !sql.is_char_boundary(end) {
+                end -= 1;
+            }
+
+            sql = &sql[..end];
+        }
+
+        let mut json = String::new();
+
+        let _ = write!(
+            json,
+            "{{\"statement_hash\":\"{:016x}\",\"sql\":",
+            record.hash()
+        );
+        write_str(&mut json, sql, if truncated { "…" } else { "" });
+
+        let _ = write!(
+            json,
+            ",\"duration_ms\":{:.3},\"rows_affected\":{},\"rows_returned\":{},\"slow\":{},\
+             \"connection_id\":",
+            record.elapsed.as_secs_f64() * 1000.0,
+            record.rows_affected,
+            record.rows_returned,
+            record.slow,
+        );
+
+        match record.connection_id {
+            Some(id) => {
+                let _ = write!(json, "{}", id);
+            }
+
+            None => json.push_str("null"),
+        }
+
+        json.push_str(",\"pool\":");
+
+        match &self.pool {
+            Some(pool) => write_str(&mut json, pool, ""),
+            None => json.push_str("null"),
+        }
+
+        json.push('}');
+        json
+    }
+}
+
+impl QueryLog for JsonQueryLog {
+    fn log(&self, record: &QueryRecord<'_>) {
+        if !log::log_enabled!(target: "sqlx::query", record.level) {
+            return;
+        }
+
+        log::logger().log(
+            &log::Record::builder()
+                .args(format_args!("{}", self.to_json(record)))
+                .level(record.level)
+                .module_path_static(Some("sqlx::query"))
+                .target("sqlx::query")
+                .build(),
+        );
+    }
+}
+
+// writes `s` and `suffix` as a JSON string
+fn write_str(json: &mut String, s: &str, suffix: &str) {
+    json.push('"');
+
+    for c in s.chars().chain(suffix.chars()) {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+}
+
+#[test]
+fn test_json_query_log() {
+    let record = QueryRecord {
+        sql: "SELECT \"name\"\nFROM users WHERE name = 'é'",
+        level: Level::Info,
+        slow: false,
+        elapsed: Duration::from_micros(1500),
+        rows_affected: 0,
+        rows_returned: 2,
+        connection_id: Some(42),
+    };
+
+    assert_eq!(
+        JsonQueryLog::new().pool("primary").to_json(&record),
+        format!(
+            "{{\"statement_hash\":\"{:016x}\",\"sql\":\"SELECT \\\"name\\\"\\nFROM users WHERE name = 'é'\",\
+             \"duration_ms\":1.500,\"rows_affected\":0,\"rows_returned\":2,\"slow\":false,\
+             \"connection_id\":42,\"pool\":\"primary\"}}",
+            record.hash()
+        )
+    );
+
+    // truncated on a character boundary
+    let json = JsonQueryLog::new()
+        .max_sql_length(40)
+        .to_json(&QueryRecord {
+            connection_id: None,
+            ..record
+        });
+
+    assert!(json.contains("\"sql\":\"SELECT \\\"name\\\"\\nFROM users WHERE name = '…\""));
+    assert!(json.ends_with("\"connection_id\":null,\"pool\":null}"));
+}
+
+#[test]
+fn test_hash() {
+    let record = |sql| QueryRecord {
+        sql,
+        level: Level::Info,
+        slow: false,
+        elapsed: Duration::ZERO,
+        rows_affected: 0,
+        rows_returned: 0,
+        connection_id: None,
+    };
+
+    assert_eq!(record("").hash(), 0xcbf2_9ce4_8422_2325);
+    assert_eq!(record("a").hash(), 0xaf63_dc4c_8601_ec8c);
+    assert_ne!(record("SELECT 1").hash(), record("SELECT 2").hash());
+}
@@ -0,0 +1,279 @@
+//! A single connection which reconnects transparently after it is lost.
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures_core::future::BoxFuture;
+
+use crate::connection::{ConnectOptions, Connection};
+use crate::database::Database;
+use crate::error::Error;
+use crate::transaction::Transaction;
+
+type Options<DB> = <<DB as Database>::Connection as Connection>::Options;
+
+type AfterConnect<DB> = dyn for<'c> Fn(&'c mut <DB as Database>::Connection) -> BoxFuture<'c, Result<(), Error>>
+    + 'static
+    + Send
+    + Sync;
+
+/// A database connection which is re-established, along with its session setup, when it is lost.
+///
+/// This is meant for programs holding a single long-lived connection, e.g. CLI tools and
+/// daemons, which would otherwise have to handle reconnecting around every query. Applications
+/// running queries concurrently should use a [`Pool`][crate::pool::Pool] instead.
+///
+/// A lost connection is detected:
+///
+/// * by a query failing with an I/O, TLS or protocol error: the query returns the error, and
+///   the next one runs on a new connection. The failed query is **not** retried, as it may have
+///   been executed before the connection was lost;
+/// * by a [ping][Connection::ping] before a query, if the connection was idle for longer than
+///   [`ping_after_idle()`][Self::ping_after_idle], e.g. because the server was restarted; the
+///   query then runs on a new connection.
+///
+/// The state of the session is lost with the connection, so any setup of the session (e.g.
+/// `SET search_path`, session variables, or temporary tables) should be done in
+/// [`after_connect()`][Self::after_connect], which is run on every new connection. An open
+/// transaction is lost too; its next query fails, as it runs on the lost connection.
+///
+/// ```rust,no_run
+/// # use sqlx_core::error::Error;
+/// # use sqlx_core::executor::Executor;
+/// # use sqlx_core::postgres::{PgConnectOptions, Postgres};
+/// # use sqlx_core::reconnect::ReconnectingConnection;
+/// # async fn f() -> Result<(), Error> {
+/// let options: PgConnectOptions = "postgres:// …".parse()?;
+///
+/// let mut conn = ReconnectingConnection::<Postgres>::new(options).after_connect(|conn| {
+///     Box::pin(async move {
+///         conn.execute("SET search_path = 'my_schema'").await?;
+///         Ok(())
+///     })
+/// });
+///
+/// loop {
+///     // reconnects if the connection was lost during the previous iteration
+///     conn.execute("SELECT do_some_work()").await?;
+///
+///     sqlx_rt::sleep(std::time::Duration::from_secs(60)).await;
+/// }
+/// # }
+/// ```
+pub struct ReconnectingConnection<DB: Database> {
+    options: Options<DB>,
+    after_connect: Option<Arc<AfterConnect<DB>>>,
+    ping_after_idle: Option<Duration>,
+    connection: Option<DB::Connection>,
+    last_used: Instant,
+}
+
+impl<DB: Database> ReconnectingConnection<DB> {
+    /// Creates the connection, which connects with `options` on its first use.
+    pub fn new(options: Options<DB>) -> Self {
+        Self {
+            options,
+            after_connect: None,
+            ping_after_idle: Some(Duration::from_secs(10)),
+            connection: None,
+            last_used: Instant::now(),
+        }
+    }
+
+    /// Sets up the session of every new connection, e.g. with `SET` statements.
+    ///
+    /// If it fails, the connection is discarded and the error is returned to the query which
+    /// needed the connection.
+    pub fn after_connect<F>(mut self, callback: F) -> Self
+    where
+        for<'c> F:
+            Fn(&'c mut DB::Connection) -> BoxFuture<'c, Result<(), Error>> + 'static + Send + Sync,
+    {
+        self.after_connect = Some(Arc::new(callback));
+        self
+    }
+
+    /// Pings the connection before a query if it was idle for longer than `idle`, to reconnect
+    /// if it was lost. `None` disables the ping.
+    ///
+    /// Defaults to 10 seconds.
+    pub fn ping_after_idle(mut self, idle: impl Into<Option<Duration>>) -> Self {
+        self.ping_after_idle = idle.into();
+        self
+    }
+
+    /// Returns the connection, connecting first if it isn't connected or was lost.
+    pub async fn acquire(&mut self) -> Result<&mut DB::Connection, Error> {
+        if let (Some(conn), Some(idle)) = (&mut self.connection, self.ping_after_idle) {
+            if self.last_used.elapsed() >= idle && conn.ping().await.is_err() {
+                self.connection = None;
+            }
+        }
+
+        if self.connection.is_none() {
+            let mut conn = self.options.connect().await?;
+
+            if let Some(after_connect) = &self.after_connect {
+                after_connect(&mut conn).await?;
+            }
+
+            self.connection = Some(conn);
+        }
+
+        self.last_used = Instant::now();
+
+        // unwrap: connected just above
+        Ok(self.connection.as_mut().unwrap())
+    }
+
+    /// Begins a transaction, connecting first if needed.
+    pub async fn begin(&mut self) -> Result<Transaction<'_, DB>, Error> {
+        self.acquire().await?.begin().await
+    }
+
+    /// `true` if the connection is established, i.e. not lost as far as is known.
+    pub fn is_connected(&self) -> bool {
+        self.connection.is_some()
+    }
+
+    /// Discards the connection, so the next query runs on a new one.
+    pub fn reset(&mut self) {
+        self.connection = None;
+    }
+
+    /// Closes the connection, if it is established.
+    pub async fn close(mut self) -> Result<(), Error> {
+        match self.connection.take() {
+            Some(conn) => conn.close().await,
+            None => Ok(()),
+        }
+    }
+
+    // discards the connection if the error means it was lost
+    pub(crate) fn check<T>(&mut self, res: Result<T, Error>) -> Result<T, Error> {
+        if let Err(e) = &res {
+            if is_connection_lost(e) {
+                self.connection = None;
+            }
+        }
+
+        res
+    }
+}
+
+fn is_connection_lost(error: &Error) -> bool {
+    // a protocol error leaves the connection in an unknown state
+    matches!(
+        error,
+        Error::Io(_) | Error::Tls(_) | Error::Protocol(_) | Error::WorkerCrashed
+    )
+}
+
+impl<DB: Database> Debug for ReconnectingConnection<DB> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReconnectingConnection")
+            .field("options", &self.options)
+            .field("ping_after_idle", &self.ping_after_idle)
+            .field("connected", &self.connection.is_some())
+            .finish()
+    }
+}
+
+// NOTE: implemented for each database rather than for any, as a bound on the `Executor` of the
+// inner connection overflows the evaluation of the `Executor` bounds of the other types
+#[allow(unused_macros)]
+macro_rules! impl_executor_for_reconnecting_connection {
+    ($DB:ident, $Row:ident) => {
+        impl<'c> crate::executor::Executor<'c>
+            for &'c mut crate::reconnect::ReconnectingConnection<$DB>
+        {
+            type Database = $DB;
+
+            fn fetch_many<'e, 'q: 'e, E: 'q>(
+                self,
+                query: E,
+            ) -> futures_core::stream::BoxStream<
+                'e,
+                Result<
+                    either::Either<<$DB as crate::database::Database>::QueryResult, $Row>,
+                    crate::error::Error,
+                >,
+            >
+            where
+                'c: 'e,
+                E: crate::executor::Execute<'q, Self::Database>,
+            {
+                Box::pin(try_stream! {
+                    let res = async {
+                        let mut s = self.acquire().await?.fetch_many(query);
+
+                        while let Some(v) = futures_util::TryStreamExt::try_next(&mut s).await? {
+                            r#yield!(v);
+                        }
+
+                        Ok(())
+                    }
+                    .await;
+
+                    self.check(res)
+                })
+            }
+
+            fn fetch_optional<'e, 'q: 'e, E: 'q>(
+                self,
+                query: E,
+            ) -> futures_core::future::BoxFuture<'e, Result<Option<$Row>, crate::error::Error>>
+            where
+                'c: 'e,
+                E: crate::executor::Execute<'q, Self::Database>,
+            {
+                Box::pin(async move {
+                    let res = async { self.acquire().await?.fetch_optional(query).await }.await;
+
+                    self.check(res)
+                })
+            }
+
+            fn prepare_with<'e, 'q: 'e>(
+                self,
+                sql: &'q str,
+                parameters: &'e [<Self::Database as crate::database::Database>::TypeInfo],
+            ) -> futures_core::future::BoxFuture<
+                'e,
+                Result<
+                    <Self::Database as crate::database::HasStatement<'q>>::Statement,
+                    crate::error::Error,
+                >,
+            >
+            where
+                'c: 'e,
+            {
+                Box::pin(async move {
+                    let res =
+                        async { self.acquire().await?.prepare_with(sql, parameters).await }.await;
+
+                    self.check(res)
+                })
+            }
+
+            #[doc(hidden)]
+            fn describe<'e, 'q: 'e>(
+                self,
+                sql: &'q str,
+            ) -> futures_core::future::BoxFuture<
+                'e,
+                Result<crate::describe::Describe<Self::Database>, crate::error::Error>,
+            >
+            where
+                'c: 'e,
+            {
+                Box::pin(async move {
+                    let res = async { self.acquire().await?.describe(sql).await }.await;
+
+                    self.check(res)
+                })
+            }
+        }
+    };
+}
@@ -162,6 +162,56 @@ pub trait Row: private_row::Sealed + Unpin + Send + Sync + 'static {
         })
     }
 
+    /// Index into the database row and borrow a single value, without copying it.
+    ///
+    /// This is meant for `str` and `[u8]`: the value is a slice into the buffer the row was
+    /// received in, so no `String` or `Vec<u8>` is allocated for it, and it lives as long as
+    /// the row.
+    ///
+    /// ```rust,ignore
+    /// let name: &str = row.get_borrowed("name");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the column does not exist or its value cannot be borrowed as the requested type.
+    /// See [`try_get_borrowed`](Self::try_get_borrowed) for a non-panicking version.
+    ///
+    #[inline]
+    fn get_borrowed<'r, T, I>(&'r self, index: I) -> &'r T
+    where
+        I: ColumnIndex<Self>,
+        T: ?Sized,
+        &'r T: Decode<'r, Self::Database> + Type<Self::Database>,
+    {
+        self.try_get_borrowed::<T, I>(index).unwrap()
+    }
+
+    /// Index into the database row and borrow a single value, without copying it.
+    ///
+    /// See [`get_borrowed`](Self::get_borrowed). PostgreSQL can only borrow a `[u8]` from a
+    /// row in the binary format, that is, from a prepared query.
+    ///
+    /// # Errors
+    ///
+    ///  * [`ColumnNotFound`] if the column by the given name was not found.
+    ///  * [`ColumnIndexOutOfBounds`] if the `usize` index was greater than the number of columns in the row.
+    ///  * [`ColumnDecode`] if the value could not be borrowed as the requested type.
+    ///
+    /// [`ColumnDecode`]: Error::ColumnDecode
+    /// [`ColumnNotFound`]: Error::ColumnNotFound
+    /// [`ColumnIndexOutOfBounds`]: Error::ColumnIndexOutOfBounds
+    ///
+    #[inline]
+    fn try_get_borrowed<'r, T, I>(&'r self, index: I) -> Result<&'r T, Error>
+    where
+        I: ColumnIndex<Self>,
+        T: ?Sized,
+        &'r T: Decode<'r, Self::Database> + Type<Self::Database>,
+    {
+        self.try_get::<&'r T, I>(index)
+    }
+
     /// Index into the database row and decode a single value.
     ///
     /// # Errors
@@ -184,3 +234,47 @@ pub trait Row: private_row::Sealed + Unpin + Send + Sync + 'static {
 pub(crate) mod private_row {
     pub trait Sealed {}
 }
+
+/// Serializes `row` as a map of its values by the names of their columns, converted by
+/// `to_json`; a `NULL` is serialized as `null`.
+#[cfg(feature = "json")]
+pub(crate) fn serialize_row<R, S>(
+    row: &R,
+    serializer: S,
+    to_json: impl for<'r> Fn(
+        &<R::Database as Database>::Column,
+        <R::Database as HasValueRef<'r>>::ValueRef,
+    ) -> Result<serde_json::Value, crate::error::BoxDynError>,
+) -> Result<S::Ok, S::Error>
+where
+    R: Row,
+    S: serde::Serializer,
+    usize: ColumnIndex<R>,
+{
+    use crate::column::Column;
+    use serde::ser::{Error as _, SerializeMap};
+
+    let mut map = serializer.serialize_map(Some(row.len()))?;
+
+    for column in row.columns() {
+        let value = row
+            .try_get_raw(column.ordinal())
+            .map_err(S::Error::custom)?;
+
+        let value = if value.is_null() {
+            serde_json::Value::Null
+        } else {
+            to_json(column, value).map_err(|e| {
+                S::Error::custom(format!(
+                    "cannot serialize column `{}`: {}",
+                    column.name(),
+                    e
+                ))
+            })?
+        };
+
+        map.serialize_entry(column.name(), &value)?;
+    }
+
+    map.end()
+}
@@ -0,0 +1,168 @@
+//! The runtime SQLx spawns its background tasks on, waits with, and opens its connections with.
+//!
+//! By default, it's the runtime chosen with the `runtime-*` feature of the crate. Another
+//! runtime takes its place once it's [installed](install), e.g. to run SQLx on a custom
+//! executor:
+//!
+//! ```rust,ignore
+//! sqlx::runtime::install(MyRuntime)?;
+//! ```
+//!
+//! The sockets of an installed runtime implement the I/O traits of the runtime chosen with the
+//! feature, [`AsyncRead`] and [`AsyncWrite`]: those of Tokio for the `runtime-tokio-*` and
+//! `runtime-actix-*` features, or of `futures-io` for the `runtime-async-std-*` features, which
+//! most runtimes provide adapters to. TLS is negotiated over these sockets by SQLx, as it is over
+//! its own, and configured with the `tls_config()` or `tls_connector_hook()` methods of the
+//! connect options.
+//!
+//! The TCP settings of the connect options, besides the connect timeout, only apply to the
+//! sockets SQLx opens itself: an installed runtime configures its sockets, and connections
+//! through a proxy are not supported with it.
+
+use std::fmt::Debug;
+use std::future::Future;
+use std::io;
+use std::path::Path;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_core::future::BoxFuture;
+use futures_util::future::{self, Either};
+use once_cell::sync::OnceCell;
+
+use crate::error::Error;
+
+pub use sqlx_rt::{AsyncRead, AsyncWrite};
+
+/// A runtime SQLx can run on; see the [module documentation][self].
+pub trait Runtime: Send + Sync + 'static {
+    /// Runs `task` in the background, until it completes.
+    fn spawn(&self, task: BoxFuture<'static, ()>);
+
+    /// Completes once `duration` elapsed.
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+
+    /// Opens a TCP connection to `port` on `host`, a name to resolve or an IP address.
+    fn connect_tcp<'a>(
+        &'a self,
+        host: &'a str,
+        port: u16,
+    ) -> BoxFuture<'a, io::Result<Box<dyn RuntimeSocket>>>;
+
+    /// Opens a connection to the Unix domain socket at `path`.
+    ///
+    /// By default, Unix domain sockets are not supported.
+    fn connect_uds<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxFuture<'a, io::Result<Box<dyn RuntimeSocket>>> {
+        let _ = path;
+
+        Box::pin(async {
+            Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Unix domain sockets are not supported by the installed runtime",
+            ))
+        })
+    }
+}
+
+/// A socket opened by a [`Runtime`].
+pub trait RuntimeSocket: AsyncRead + AsyncWrite + Debug + Send + Sync + Unpin + 'static {}
+
+impl<S> RuntimeSocket for S where S: AsyncRead + AsyncWrite + Debug + Send + Sync + Unpin + 'static {}
+
+static RUNTIME: OnceCell<Box<dyn Runtime>> = OnceCell::new();
+
+/// Runs SQLx on `runtime` from now on, instead of the runtime chosen with the `runtime-*`
+/// feature.
+///
+/// A runtime can only be installed once, before the first connection is opened; the
+/// connections and pools opened before keep running on the previous runtime.
+pub fn install(runtime: impl Runtime) -> Result<(), Error> {
+    RUNTIME
+        .set(Box::new(runtime))
+        .map_err(|_| Error::Configuration("a runtime is already installed".into()))
+}
+
+/// The installed runtime, if any.
+pub(crate) fn installed() -> Option<&'static dyn Runtime> {
+    RUNTIME.get().map(|runtime| &**runtime)
+}
+
+pub(crate) fn spawn<F>(future: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    match installed() {
+        Some(runtime) => runtime.spawn(Box::pin(future)),
+        None => {
+            sqlx_rt::spawn(future);
+        }
+    }
+}
+
+// spawns a task from a destructor, which may run outside of a Tokio runtime; the task is
+// dropped in that case
+pub(crate) fn spawn_from_drop<F>(future: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    if let Some(runtime) = installed() {
+        return runtime.spawn(Box::pin(future));
+    }
+
+    #[cfg(not(feature = "_rt-async-std"))]
+    if let Ok(handle) = sqlx_rt::Handle::try_current() {
+        handle.spawn(future);
+    }
+
+    #[cfg(feature = "_rt-async-std")]
+    sqlx_rt::spawn(future);
+}
+
+pub(crate) async fn sleep(duration: Duration) {
+    match installed() {
+        Some(runtime) => runtime.sleep(duration).await,
+        None => sqlx_rt::sleep(duration).await,
+    }
+}
+
+/// The error of a future which didn't complete within its timeout.
+#[derive(Debug)]
+pub(crate) struct Elapsed;
+
+pub(crate) async fn timeout<F: Future>(duration: Duration, f: F) -> Result<F::Output, Elapsed> {
+    let runtime = match installed() {
+        Some(runtime) => runtime,
+        None => return sqlx_rt::timeout(duration, f).await.map_err(|_| Elapsed),
+    };
+
+    futures_util::pin_mut!(f);
+
+    match future::select(f, runtime.sleep(duration)).await {
+        Either::Left((output, _)) => Ok(output),
+        Either::Right(_) => Err(Elapsed),
+    }
+}
+
+pub(crate) async fn yield_now() {
+    if installed().is_none() {
+        return sqlx_rt::yield_now().await;
+    }
+
+    // lets the executor poll the other tasks before this one is polled again
+    let mut yielded = false;
+
+    future::poll_fn(|cx: &mut Context<'_>| {
+        if yielded {
+            return Poll::Ready(());
+        }
+
+        yielded = true;
+        cx.waker().wake_by_ref();
+
+        Poll::Pending
+    })
+    .await
+}
@@ -0,0 +1,212 @@
+//! Query execution as a service: a [`QueryService`] takes a [`QueryRequest`] and returns a
+//! future of its [`QueryResponse`], which owns everything it needs, so that the middleware of
+//! `tower` (timeouts, rate limits, concurrency limits, load shedding) wraps the queries sent to a
+//! pool as it wraps the requests sent by an HTTP client.
+//!
+//! With the `tower` feature, [`QueryService`] implements `tower::Service<QueryRequest<DB>>`:
+//!
+//! ```rust,ignore
+//! use sqlx::service::{QueryRequest, QueryService};
+//! use tower::{Service, ServiceBuilder, ServiceExt};
+//!
+//! let mut service = ServiceBuilder::new()
+//!     .load_shed()
+//!     .concurrency_limit(16)
+//!     .timeout(Duration::from_secs(5))
+//!     .service(QueryService::new(pool));
+//!
+//! let response = service
+//!     .ready()
+//!     .await?
+//!     .call(QueryRequest::new("SELECT name FROM users WHERE id = $1").bind(1_i64))
+//!     .await?;
+//!
+//! let name: String = response.rows()[0].try_get("name")?;
+//! ```
+//!
+//! The service is always ready: the connections are waited for by the queries, within the
+//! acquire timeout of the pool, unless a middleware limits how many are sent at once.
+
+use std::fmt::{self, Debug, Formatter};
+#[cfg(feature = "tower")]
+use std::task::{Context, Poll};
+
+use either::Either;
+use futures_core::future::BoxFuture;
+use futures_util::TryStreamExt;
+
+use crate::arguments::{Arguments, IntoArguments};
+use crate::database::{Database, HasArguments};
+use crate::encode::Encode;
+use crate::error::Error;
+use crate::executor::Executor;
+use crate::pool::Pool;
+use crate::types::Type;
+
+/// Executes the [requests](QueryRequest) on the connections of a pool; see the
+/// [module documentation][self].
+pub struct QueryService<DB: Database> {
+    pool: Pool<DB>,
+}
+
+impl<DB: Database> QueryService<DB> {
+    pub fn new(pool: Pool<DB>) -> Self {
+        Self { pool }
+    }
+
+    pub fn pool(&self) -> &Pool<DB> {
+        &self.pool
+    }
+}
+
+impl<DB> QueryService<DB>
+where
+    DB: Database,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+{
+    /// Executes `request` on a connection of the pool, and returns all its rows.
+    pub fn call(
+        &self,
+        request: QueryRequest<DB>,
+    ) -> BoxFuture<'static, Result<QueryResponse<DB>, Error>> {
+        let pool = self.pool.clone();
+
+        Box::pin(async move {
+            let mut arguments = <DB as HasArguments<'_>>::Arguments::default();
+
+            for bind in request.binds {
+                bind(&mut arguments);
+            }
+
+            let mut results = pool.fetch_many((&*request.sql, Some(arguments)));
+
+            let mut response = QueryResponse::<DB> {
+                result: DB::QueryResult::default(),
+                rows: Vec::new(),
+            };
+
+            while let Some(result) = results.try_next().await? {
+                match result {
+                    Either::Left(result) => response.result.extend(Some(result)),
+                    Either::Right(row) => response.rows.push(row),
+                }
+            }
+
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(feature = "tower")]
+impl<DB> tower_service::Service<QueryRequest<DB>> for QueryService<DB>
+where
+    DB: Database,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+{
+    type Response = QueryResponse<DB>;
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<QueryResponse<DB>, Error>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: QueryRequest<DB>) -> Self::Future {
+        QueryService::call(self, request)
+    }
+}
+
+impl<DB: Database> Clone for QueryService<DB> {
+    fn clone(&self) -> Self {
+        Self {
+            pool: self.pool.clone(),
+        }
+    }
+}
+
+impl<DB: Database> Debug for QueryService<DB> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryService")
+            .field("pool", &self.pool)
+            .finish()
+    }
+}
+
+type Bind<DB> = Box<dyn for<'q> FnOnce(&mut <DB as HasArguments<'q>>::Arguments) + Send>;
+
+/// A query sent to a [`QueryService`], with the values bound to its parameters.
+///
+/// Unlike a [`Query`][crate::query::Query], it owns its SQL and its values, so that it can be
+/// sent through the middleware of a service, e.g. queued by a concurrency limit.
+pub struct QueryRequest<DB: Database> {
+    sql: String,
+    binds: Vec<Bind<DB>>,
+}
+
+impl<DB: Database> QueryRequest<DB> {
+    pub fn new(sql: impl Into<String>) -> Self {
+        Self {
+            sql: sql.into(),
+            binds: Vec::new(),
+        }
+    }
+
+    /// Binds `value` to the next parameter of the query, as
+    /// [`Query::bind()`][crate::query::Query::bind] does.
+    pub fn bind<T>(mut self, value: T) -> Self
+    where
+        T: 'static + Send + for<'q> Encode<'q, DB> + Type<DB>,
+    {
+        self.binds
+            .push(Box::new(move |arguments| arguments.add(value)));
+        self
+    }
+
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+}
+
+impl<DB: Database> Debug for QueryRequest<DB> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryRequest")
+            .field("sql", &self.sql)
+            .field("binds", &self.binds.len())
+            .finish()
+    }
+}
+
+/// The rows returned by a [`QueryRequest`], and the result of its statements.
+pub struct QueryResponse<DB: Database> {
+    result: DB::QueryResult,
+    rows: Vec<DB::Row>,
+}
+
+impl<DB: Database> QueryResponse<DB> {
+    /// The result of the statements of the query, e.g. the rows they affected.
+    pub fn result(&self) -> &DB::QueryResult {
+        &self.result
+    }
+
+    pub fn rows(&self) -> &[DB::Row] {
+        &self.rows
+    }
+
+    pub fn into_rows(self) -> Vec<DB::Row> {
+        self.rows
+    }
+}
+
+impl<DB: Database> Debug for QueryResponse<DB>
+where
+    DB::QueryResult: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryResponse")
+            .field("result", &self.result)
+            .field("rows", &self.rows.len())
+            .finish()
+    }
+}
@@ -0,0 +1,190 @@
+//! Comments appended to the statements sent to the database, in the [sqlcommenter] format, so
+//! that its logs, e.g. of slow queries, can be correlated with the traces of the application.
+//!
+//! A [`SqlCommenter`] is set with the `sql_commenter()` method of the connect options of Postgres
+//! and MySQL:
+//!
+//! ```rust,ignore
+//! let options = PgConnectOptions::new().sql_commenter(
+//!     SqlCommenter::new()
+//!         .tag("application", "my-app")
+//!         .traceparent(current_traceparent),
+//! );
+//! ```
+//!
+//! `SELECT * FROM users WHERE id = $1` is then sent as:
+//!
+//! ```sql
+//! SELECT * FROM users WHERE id = $1 /*application='my-app',traceparent='00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01'*/
+//! ```
+//!
+//! The `traceparent` is returned by a function called as each query is sent, in the task
+//! executing it, which reads the current context of the tracing library of the application;
+//! with OpenTelemetry, and the `tracing-opentelemetry` crate for the spans of `tracing`:
+//!
+//! ```rust,ignore
+//! use opentelemetry::trace::TraceContextExt;
+//! use tracing_opentelemetry::OpenTelemetrySpanExt;
+//!
+//! fn current_traceparent() -> Option<String> {
+//!     let context = tracing::Span::current().context();
+//!     let span = context.span();
+//!     let span = span.span_context();
+//!
+//!     span.is_valid().then(|| {
+//!         format!(
+//!             "00-{}-{}-{:02x}",
+//!             span.trace_id(),
+//!             span.span_id(),
+//!             span.trace_flags().to_u8()
+//!         )
+//!     })
+//! }
+//! ```
+//!
+//! The text of a statement executed within a trace differs on each execution, so it's prepared
+//! each time, as with `persistent(false)`, instead of being cached; the statements executed
+//! outside of a trace are cached as usual. A statement which already has a comment is sent as
+//! it is.
+//!
+//! [sqlcommenter]: https://google.github.io/sqlcommenter/spec/
+
+use std::borrow::Cow;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+/// The tags of the comments appended to the statements; see the [module documentation][self].
+#[derive(Clone, Default)]
+pub struct SqlCommenter {
+    tags: Vec<(String, String)>,
+    traceparent: Option<Arc<dyn Fn() -> Option<String> + Send + Sync>>,
+}
+
+impl SqlCommenter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a tag with the same value to every statement, e.g. `application` or `controller`.
+    pub fn tag(mut self, key: &str, value: &str) -> Self {
+        self.tags.retain(|(k, _)| k != key);
+        self.tags.push((key.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Sets the function returning the `traceparent` of the current span, in the
+    /// [W3C Trace Context] format, or `None` outside of a trace.
+    ///
+    /// [W3C Trace Context]: https://www.w3.org/TR/trace-context/#traceparent-header
+    pub fn traceparent(mut self, f: impl Fn() -> Option<String> + Send + Sync + 'static) -> Self {
+        self.traceparent = Some(Arc::new(f));
+        self
+    }
+
+    /// The text to send for `sql`, and whether it has a trace context, which makes it unique.
+    pub(crate) fn apply<'q>(&self, sql: &'q str) -> (Cow<'q, str>, bool) {
+        // a comment, or a line comment the tags would end up in, is left as it is
+        if sql.contains("/*") || sql.contains("--") {
+            return (Cow::Borrowed(sql), false);
+        }
+
+        let traceparent = self.traceparent.as_ref().and_then(|f| f());
+        let traced = traceparent.is_some();
+
+        let mut tags: Vec<(&str, &str)> = self
+            .tags
+            .iter()
+            .map(|(key, value)| (&**key, &**value))
+            .chain(traceparent.as_deref().map(|value| ("traceparent", value)))
+            .collect();
+
+        if tags.is_empty() {
+            return (Cow::Borrowed(sql), false);
+        }
+
+        tags.sort_unstable();
+
+        let comment = tags
+            .iter()
+            .map(|(key, value)| format!("{}='{}'", encode(key), encode(value)))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        // the comment goes before the semicolon which ends the statement, if any
+        let sql = sql.trim_end();
+        let (statement, end) = match sql.strip_suffix(';') {
+            Some(statement) => (statement.trim_end(), ";"),
+            None => (sql, ""),
+        };
+
+        (
+            Cow::Owned(format!("{} /*{}*/{}", statement, comment, end)),
+            traced,
+        )
+    }
+}
+
+impl Debug for SqlCommenter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqlCommenter")
+            .field("tags", &self.tags)
+            .field("traceparent", &self.traceparent.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+// percent-encodes all but the unreserved characters, which also rules out the quotes and the end
+// of the comment
+fn encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    encoded
+}
+
+#[test]
+fn test_apply() {
+    let commenter = SqlCommenter::new()
+        .tag("application", "my app")
+        .tag("route", "/users/{id}")
+        .traceparent(|| Some("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01".into()));
+
+    assert_eq!(
+        commenter.apply("SELECT 1;"),
+        (
+            Cow::Borrowed(
+                "SELECT 1 /*application='my%20app',route='%2Fusers%2F%7Bid%7D',\
+                 traceparent='00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01'*/;"
+            ),
+            true
+        )
+    );
+
+    assert_eq!(
+        commenter.apply("SELECT 1 -- one"),
+        (Cow::Borrowed("SELECT 1 -- one"), false)
+    );
+
+    let untraced = SqlCommenter::new()
+        .tag("application", "app")
+        .traceparent(|| None);
+
+    assert_eq!(
+        untraced.apply("SELECT 1"),
+        (Cow::Borrowed("SELECT 1 /*application='app'*/"), false)
+    );
+
+    assert_eq!(
+        SqlCommenter::new().apply("SELECT 1"),
+        (Cow::Borrowed("SELECT 1"), false)
+    );
+}
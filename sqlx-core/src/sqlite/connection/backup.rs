@@ -0,0 +1,238 @@
+use std::path::{Path, PathBuf};
+use std::ptr::NonNull;
+use std::time::Duration;
+
+use libsqlite3_sys::{
+    sqlite3_backup, sqlite3_backup_finish, sqlite3_backup_init, sqlite3_backup_pagecount,
+    sqlite3_backup_remaining, sqlite3_backup_step, SQLITE_BUSY, SQLITE_DONE, SQLITE_LOCKED,
+    SQLITE_OK,
+};
+
+use crate::error::Error;
+use crate::sqlite::connection::establish::EstablishParams;
+use crate::sqlite::connection::{ConnectionHandle, ConnectionState, LockedSqliteHandle};
+use crate::sqlite::{SqliteConnectOptions, SqliteConnection, SqliteError};
+
+// https://www.sqlite.org/backup.html
+
+/// The destination of a backup: a database file, created if it doesn't exist, or a connection.
+///
+/// The destination database is replaced by the backup.
+#[derive(Debug)]
+pub enum SqliteBackupDestination<'a> {
+    Path(PathBuf),
+    Connection(&'a mut SqliteConnection),
+}
+
+impl From<&'_ Path> for SqliteBackupDestination<'_> {
+    fn from(path: &Path) -> Self {
+        Self::Path(path.to_owned())
+    }
+}
+
+impl From<PathBuf> for SqliteBackupDestination<'_> {
+    fn from(path: PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+impl From<&'_ str> for SqliteBackupDestination<'_> {
+    fn from(path: &str) -> Self {
+        Self::Path(path.into())
+    }
+}
+
+impl<'a> From<&'a mut SqliteConnection> for SqliteBackupDestination<'a> {
+    fn from(conn: &'a mut SqliteConnection) -> Self {
+        Self::Connection(conn)
+    }
+}
+
+/// The progress of a backup, as of its last step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqliteBackupProgress {
+    remaining: u32,
+    page_count: u32,
+}
+
+impl SqliteBackupProgress {
+    /// The number of pages still to be copied.
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+
+    /// The number of pages of the source database.
+    pub fn page_count(&self) -> u32 {
+        self.page_count
+    }
+}
+
+/// An online backup of the `main` database of a connection, copied in steps of pages.
+///
+/// Both connections are locked until the backup is dropped, but other connections to the source
+/// database (e.g. of the same pool) can still read and write it. If they write to it between
+/// steps, the backup restarts on the next step.
+///
+/// Each step blocks the current thread while its pages are copied; use
+/// [`run()`][Self::run] to copy a few pages at a time.
+///
+/// See [`SqliteConnection::backup_to()`].
+pub struct SqliteBackup<'a> {
+    // released by `finish()` or on drop
+    handle: Option<NonNull<sqlite3_backup>>,
+    progress: SqliteBackupProgress,
+
+    // both connections outlive the backup, which is released first on drop
+    _source: LockedSqliteHandle<'a>,
+    destination: Destination<'a>,
+}
+
+enum Destination<'a> {
+    Owned(ConnectionState),
+    Locked(LockedSqliteHandle<'a>),
+}
+
+impl Destination<'_> {
+    fn handle(&self) -> &ConnectionHandle {
+        match self {
+            Destination::Owned(state) => &state.handle,
+            Destination::Locked(locked) => &locked.guard.handle,
+        }
+    }
+}
+
+// SAFE: the backup object is only used by its owner, while both connections are locked
+unsafe impl Send for SqliteBackup<'_> {}
+
+impl<'a> SqliteBackup<'a> {
+    pub(crate) async fn new(
+        source: &'a mut SqliteConnection,
+        destination: SqliteBackupDestination<'a>,
+    ) -> Result<SqliteBackup<'a>, Error> {
+        let destination = match destination {
+            SqliteBackupDestination::Path(path) => {
+                let options = SqliteConnectOptions::new()
+                    .filename(path)
+                    .create_if_missing(true);
+
+                Destination::Owned(EstablishParams::from_options(&options)?.establish()?)
+            }
+
+            SqliteBackupDestination::Connection(conn) => {
+                Destination::Locked(conn.lock_handle().await?)
+            }
+        };
+
+        let source = source.lock_handle().await?;
+
+        let dest_handle = destination.handle().as_ptr();
+
+        let handle = unsafe {
+            sqlite3_backup_init(
+                dest_handle,
+                b"main\0".as_ptr() as _,
+                source.guard.handle.as_ptr(),
+                b"main\0".as_ptr() as _,
+            )
+        };
+
+        let handle = NonNull::new(handle)
+            .ok_or_else(|| Error::Database(Box::new(SqliteError::new(dest_handle))))?;
+
+        Ok(Self {
+            handle: Some(handle),
+            // unknown until the first step
+            progress: SqliteBackupProgress {
+                remaining: 0,
+                page_count: 0,
+            },
+            _source: source,
+            destination,
+        })
+    }
+
+    /// Copies up to `pages` pages, or all the remaining ones if negative, returning `true` once
+    /// the backup is complete.
+    ///
+    /// Returns `false` without copying if a database is busy or locked; retry the step later.
+    pub fn step(&mut self, pages: i32) -> Result<bool, Error> {
+        let handle = self.as_ptr();
+        let status = unsafe { sqlite3_backup_step(handle, pages) };
+
+        self.progress = unsafe {
+            SqliteBackupProgress {
+                remaining: sqlite3_backup_remaining(handle) as u32,
+                page_count: sqlite3_backup_pagecount(handle) as u32,
+            }
+        };
+
+        match status & 0xFF {
+            SQLITE_DONE => Ok(true),
+            SQLITE_OK | SQLITE_BUSY | SQLITE_LOCKED => Ok(false),
+
+            _ => Err(Error::Database(Box::new(SqliteError::new(
+                self.destination.handle().as_ptr(),
+            )))),
+        }
+    }
+
+    /// The progress of the backup, as of the last step.
+    pub fn progress(&self) -> SqliteBackupProgress {
+        self.progress
+    }
+
+    /// Copies the database in steps of `pages_per_step` pages, pausing for `pause` between
+    /// steps so other connections can use the source database, and calling `on_progress` after
+    /// every step.
+    pub async fn run(
+        mut self,
+        pages_per_step: i32,
+        pause: Duration,
+        mut on_progress: impl FnMut(SqliteBackupProgress),
+    ) -> Result<(), Error> {
+        loop {
+            let done = self.step(pages_per_step)?;
+
+            on_progress(self.progress);
+
+            if done {
+                return self.finish();
+            }
+
+            crate::runtime::sleep(pause).await;
+        }
+    }
+
+    /// Releases the backup, returning an error if a step failed.
+    ///
+    /// A backup which isn't complete leaves the destination database unchanged.
+    pub fn finish(mut self) -> Result<(), Error> {
+        let status = match self.handle.take() {
+            Some(handle) => unsafe { sqlite3_backup_finish(handle.as_ptr()) },
+            None => SQLITE_OK,
+        };
+
+        if status == SQLITE_OK {
+            Ok(())
+        } else {
+            Err(Error::Database(Box::new(SqliteError::new(
+                self.destination.handle().as_ptr(),
+            ))))
+        }
+    }
+
+    fn as_ptr(&self) -> *mut sqlite3_backup {
+        // unwrap: only taken by `finish()`, which consumes the backup
+        self.handle.unwrap().as_ptr()
+    }
+}
+
+impl Drop for SqliteBackup<'_> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            unsafe {
+                sqlite3_backup_finish(handle.as_ptr());
+            }
+        }
+    }
+}
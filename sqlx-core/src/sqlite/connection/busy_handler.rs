@@ -0,0 +1,44 @@
+use std::os::raw::{c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+
+use libsqlite3_sys::{sqlite3_busy_handler, SQLITE_OK};
+
+use crate::common::DebugFn;
+use crate::error::Error;
+use crate::sqlite::connection::handle::ConnectionHandle;
+use crate::sqlite::SqliteError;
+
+// https://www.sqlite.org/c3ref/busy_handler.html
+
+pub(crate) type BusyHandlerFn = DebugFn<dyn Fn(u32) -> bool + Send + Sync + 'static>;
+
+/// A busy handler registered with a connection, freed after the connection is closed.
+pub(crate) struct BusyHandler(Arc<BusyHandlerFn>);
+
+impl BusyHandler {
+    pub(crate) fn register(
+        handle: &ConnectionHandle,
+        f: Arc<BusyHandlerFn>,
+    ) -> Result<Box<Self>, Error> {
+        let handler = Box::new(BusyHandler(f));
+
+        let arg = &*handler as *const BusyHandler as *mut c_void;
+
+        // SAFETY: the connection state owns the handler, and drops it after the connection
+        let status = unsafe { sqlite3_busy_handler(handle.as_ptr(), Some(call), arg) };
+
+        if status != SQLITE_OK {
+            return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
+        }
+
+        Ok(handler)
+    }
+}
+
+unsafe extern "C" fn call(arg: *mut c_void, count: c_int) -> c_int {
+    let handler = &*(arg as *const BusyHandler);
+
+    // a panic must not unwind into SQLite; give up on the lock instead
+    catch_unwind(AssertUnwindSafe(|| (handler.0)(count as u32))).unwrap_or(false) as c_int
+}
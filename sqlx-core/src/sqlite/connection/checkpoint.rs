@@ -0,0 +1,51 @@
+use crate::error::Error;
+use crate::sqlite::{SqliteCheckpointMode, SqliteConnection};
+
+// https://www.sqlite.org/pragma.html#pragma_wal_checkpoint
+
+/// The result of a [WAL checkpoint](https://www.sqlite.org/wal.html#checkpointing).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqliteCheckpoint {
+    busy: bool,
+    log_frames: Option<u64>,
+    checkpointed_frames: Option<u64>,
+}
+
+impl SqliteCheckpoint {
+    pub(crate) async fn run(
+        conn: &mut SqliteConnection,
+        mode: SqliteCheckpointMode,
+    ) -> Result<Self, Error> {
+        let (busy, log_frames, checkpointed_frames): (i64, i64, i64) =
+            crate::query_as::query_as(&format!("PRAGMA wal_checkpoint({})", mode.as_str()))
+                .fetch_one(conn)
+                .await?;
+
+        // both counts are -1 if the checkpoint couldn't run
+        Ok(Self {
+            busy: busy != 0,
+            log_frames: u64::try_from(log_frames).ok(),
+            checkpointed_frames: u64::try_from(checkpointed_frames).ok(),
+        })
+    }
+
+    /// `true` if the checkpoint couldn't complete because of other connections, i.e. a `FULL`,
+    /// `RESTART` or `TRUNCATE` checkpoint timed out waiting for them.
+    pub fn is_busy(&self) -> bool {
+        self.busy
+    }
+
+    /// The number of frames in the WAL, or `None` if the checkpoint couldn't run, e.g. because
+    /// the database isn't in WAL mode.
+    ///
+    /// `Some(0)` after a successful `TRUNCATE` checkpoint.
+    pub fn log_frames(&self) -> Option<u64> {
+        self.log_frames
+    }
+
+    /// The number of frames of the WAL copied into the database, or `None` if the checkpoint
+    /// couldn't run.
+    pub fn checkpointed_frames(&self) -> Option<u64> {
+        self.checkpointed_frames
+    }
+}
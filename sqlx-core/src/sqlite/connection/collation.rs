@@ -1,12 +1,14 @@
 use std::cmp::Ordering;
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::fmt::{self, Debug, Formatter};
-use std::os::raw::{c_int, c_void};
+use std::os::raw::{c_char, c_int, c_void};
 use std::slice;
 use std::str::from_utf8_unchecked;
 use std::sync::Arc;
 
-use libsqlite3_sys::{sqlite3_create_collation_v2, SQLITE_OK, SQLITE_UTF8};
+use libsqlite3_sys::{
+    sqlite3, sqlite3_collation_needed, sqlite3_create_collation_v2, SQLITE_OK, SQLITE_UTF8,
+};
 
 use crate::error::Error;
 use crate::sqlite::connection::handle::ConnectionHandle;
@@ -115,6 +117,60 @@ where
     }
 }
 
+/// Compare strings bytewise for any collation which isn't defined on the connection.
+///
+/// The collations of the application are unknown when describing queries for the macros; the
+/// statements are only prepared there, so the order doesn't matter.
+pub(crate) fn stand_in_collations(handle: &mut ConnectionHandle) -> Result<(), Error> {
+    unsafe extern "C" fn create_stand_in(
+        _: *mut c_void,
+        db: *mut sqlite3,
+        _: c_int,
+        name: *const c_char,
+    ) {
+        log::debug!(
+            "using a bytewise stand-in for the collation {:?}",
+            CStr::from_ptr(name)
+        );
+
+        sqlite3_create_collation_v2(
+            db,
+            name,
+            SQLITE_UTF8,
+            std::ptr::null_mut(),
+            Some(compare_bytes),
+            None,
+        );
+    }
+
+    let r = unsafe {
+        sqlite3_collation_needed(handle.as_ptr(), std::ptr::null_mut(), Some(create_stand_in))
+    };
+
+    if r == SQLITE_OK {
+        Ok(())
+    } else {
+        Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))))
+    }
+}
+
+unsafe extern "C" fn compare_bytes(
+    _: *mut c_void,
+    left_len: c_int,
+    left_ptr: *const c_void,
+    right_len: c_int,
+    right_ptr: *const c_void,
+) -> c_int {
+    let left = slice::from_raw_parts(left_ptr as *const u8, left_len as usize);
+    let right = slice::from_raw_parts(right_ptr as *const u8, right_len as usize);
+
+    match left.cmp(right) {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    }
+}
+
 unsafe extern "C" fn call_boxed_closure<C>(
     data: *mut c_void,
     left_len: c_int,
@@ -1,17 +1,23 @@
 use crate::connection::LogSettings;
 use crate::error::Error;
+use crate::sqlite::connection::busy_handler::{BusyHandler, BusyHandlerFn};
 use crate::sqlite::connection::handle::ConnectionHandle;
+use crate::sqlite::connection::hooks::ChangeHooks;
 use crate::sqlite::connection::{ConnectionState, Statements};
-use crate::sqlite::{SqliteConnectOptions, SqliteError};
+use crate::sqlite::{SqliteChange, SqliteConnectOptions, SqliteError};
+use futures_channel::mpsc::UnboundedSender;
 use libsqlite3_sys::{
-    sqlite3_busy_timeout, sqlite3_extended_result_codes, sqlite3_open_v2, SQLITE_OK,
+    sqlite3_busy_timeout, sqlite3_db_config, sqlite3_extended_result_codes, sqlite3_free,
+    sqlite3_load_extension, sqlite3_open_v2, SQLITE_DBCONFIG_ENABLE_LOAD_EXTENSION, SQLITE_OK,
     SQLITE_OPEN_CREATE, SQLITE_OPEN_FULLMUTEX, SQLITE_OPEN_MEMORY, SQLITE_OPEN_NOMUTEX,
     SQLITE_OPEN_PRIVATECACHE, SQLITE_OPEN_READONLY, SQLITE_OPEN_READWRITE, SQLITE_OPEN_SHAREDCACHE,
 };
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::io;
+use std::os::raw::c_int;
 use std::ptr::{null, null_mut};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 static THREAD_ID: AtomicU64 = AtomicU64::new(0);
@@ -20,8 +26,11 @@ pub struct EstablishParams {
     filename: CString,
     open_flags: i32,
     busy_timeout: Duration,
+    busy_handler: Option<Arc<BusyHandlerFn>>,
     statement_cache_capacity: usize,
     log_settings: LogSettings,
+    extensions: Vec<(CString, Option<CString>)>,
+    change_listener: Option<UnboundedSender<SqliteChange>>,
     pub(crate) thread_name: String,
     pub(crate) command_channel_size: usize,
 }
@@ -79,17 +88,37 @@ impl EstablishParams {
             )
         })?;
 
+        let extensions = options
+            .extensions
+            .iter()
+            .map(|(name, entry_point)| {
+                Ok((
+                    extension_cstring(name)?,
+                    entry_point.as_deref().map(extension_cstring).transpose()?,
+                ))
+            })
+            .collect::<Result<_, Error>>()?;
+
         Ok(Self {
             filename,
             open_flags: flags,
             busy_timeout: options.busy_timeout,
+            busy_handler: options.busy_handler.clone(),
             statement_cache_capacity: options.statement_cache_capacity,
             log_settings: options.log_settings.clone(),
+            extensions,
+            change_listener: options.change_listener.clone(),
             thread_name: (options.thread_name)(THREAD_ID.fetch_add(1, Ordering::AcqRel)),
             command_channel_size: options.command_channel_size,
         })
     }
 
+    /// `true` if the connection is to a named in-memory database, which exists only while a
+    /// connection to it is open.
+    pub(crate) fn is_shared_memory(&self) -> bool {
+        self.open_flags & SQLITE_OPEN_MEMORY != 0 && self.open_flags & SQLITE_OPEN_SHAREDCACHE != 0
+    }
+
     pub(crate) fn establish(&self) -> Result<ConnectionState, Error> {
         let mut handle = null_mut();
 
@@ -121,25 +150,133 @@ impl EstablishParams {
             sqlite3_extended_result_codes(handle.as_ptr(), 1);
         }
 
-        // Configure a busy timeout
+        // Configure a busy handler, which replaces the busy timeout, or else a busy timeout
         // This causes SQLite to automatically sleep in increasing intervals until the time
         // when there is something locked during [sqlite3_step].
-        //
-        // We also need to convert the u128 value to i32, checking we're not overflowing.
-        let ms = i32::try_from(self.busy_timeout.as_millis())
-            .expect("Given busy timeout value is too big.");
+        let busy_handler = match &self.busy_handler {
+            Some(f) => Some(BusyHandler::register(&handle, Arc::clone(f))?),
 
-        status = unsafe { sqlite3_busy_timeout(handle.as_ptr(), ms) };
+            None => {
+                // We also need to convert the u128 value to i32, checking we're not overflowing.
+                let ms = i32::try_from(self.busy_timeout.as_millis())
+                    .expect("Given busy timeout value is too big.");
 
-        if status != SQLITE_OK {
-            return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
+                status = unsafe { sqlite3_busy_timeout(handle.as_ptr(), ms) };
+
+                if status != SQLITE_OK {
+                    return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
+                }
+
+                None
+            }
+        };
+
+        if !self.extensions.is_empty() {
+            self.load_extensions(&handle)?;
         }
 
+        let change_hooks = self
+            .change_listener
+            .clone()
+            .map(|tx| ChangeHooks::register(&handle, tx));
+
         Ok(ConnectionState {
             handle,
             statements: Statements::new(self.statement_cache_capacity),
             transaction_depth: 0,
             log_settings: self.log_settings.clone(),
+            _busy_handler: busy_handler,
+            _change_hooks: change_hooks,
         })
     }
+
+    // <https://www.sqlite.org/c3ref/load_extension.html>
+    fn load_extensions(&self, handle: &ConnectionHandle) -> Result<(), Error> {
+        // enable `sqlite3_load_extension()`, but not the SQL function `load_extension()`
+        set_load_extension(handle, 1)?;
+
+        for (name, entry_point) in &self.extensions {
+            let mut message = null_mut();
+
+            let status = unsafe {
+                sqlite3_load_extension(
+                    handle.as_ptr(),
+                    name.as_ptr(),
+                    entry_point.as_ref().map_or(null(), |e| e.as_ptr()),
+                    &mut message,
+                )
+            };
+
+            if status != SQLITE_OK {
+                // SAFE: the message is ours to free
+                let message = if message.is_null() {
+                    format!("failed to load extension {:?}", name)
+                } else {
+                    unsafe {
+                        let m = CStr::from_ptr(message).to_string_lossy().into_owned();
+                        sqlite3_free(message.cast());
+                        m
+                    }
+                };
+
+                return Err(Error::Database(Box::new(SqliteError::with_message(
+                    handle.as_ptr(),
+                    &message,
+                ))));
+            }
+        }
+
+        // disable the loading of extensions again, as it's a risk if SQL can be injected
+        set_load_extension(handle, 0)
+    }
+}
+
+/// A connection which keeps a shared in-memory database alive, shared by the clones of the
+/// options which opened it, e.g. by a pool while its connections are closed and reopened.
+#[derive(Default)]
+pub(crate) struct InMemoryAnchor(Mutex<Option<ConnectionState>>);
+
+impl InMemoryAnchor {
+    /// Opens the anchor connection, if it isn't already open.
+    pub(crate) fn open(&self, params: &EstablishParams) -> Result<(), Error> {
+        // a panic can't poison the lock while it holds the state
+        let mut anchor = self.0.lock().unwrap_or_else(|e| e.into_inner());
+
+        if anchor.is_none() {
+            *anchor = Some(params.establish()?);
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for InMemoryAnchor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InMemoryAnchor").finish_non_exhaustive()
+    }
+}
+
+fn set_load_extension(handle: &ConnectionHandle, on: c_int) -> Result<(), Error> {
+    let status = unsafe {
+        sqlite3_db_config(
+            handle.as_ptr(),
+            SQLITE_DBCONFIG_ENABLE_LOAD_EXTENSION,
+            on,
+            null::<c_int>(),
+        )
+    };
+
+    if status != SQLITE_OK {
+        return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
+    }
+
+    Ok(())
+}
+
+fn extension_cstring(name: &str) -> Result<CString, Error> {
+    CString::new(name).map_err(|_| {
+        Error::Configuration(
+            format!("SQLite extension name contains a nul byte: {:?}", name).into(),
+        )
+    })
 }
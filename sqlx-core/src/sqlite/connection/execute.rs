@@ -24,10 +24,16 @@ pub(crate) fn iter<'a>(
     args: Option<SqliteArguments<'a>>,
     persistent: bool,
 ) -> Result<ExecuteIter<'a>, Error> {
-    // fetch the cached statement or allocate a new one
-    let statement = conn.statements.get(query, persistent)?;
+    let mut logger = QueryLogger::new(query, conn.log_settings.clone());
 
-    let logger = QueryLogger::new(query, conn.log_settings.clone());
+    // fetch the cached statement or allocate a new one
+    let statement = match conn.statements.get(query, persistent) {
+        Ok(statement) => statement,
+        Err(error) => {
+            logger.fail(&error);
+            return Err(error);
+        }
+    };
 
     Ok(ExecuteIter {
         handle: &mut conn.handle,
@@ -67,6 +73,18 @@ impl Iterator for ExecuteIter<'_> {
     type Item = Result<Either<SqliteQueryResult, SqliteRow>, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        let item = self.step();
+
+        if let Some(Err(error)) = &item {
+            self.logger.fail(error);
+        }
+
+        item
+    }
+}
+
+impl ExecuteIter<'_> {
+    fn step(&mut self) -> Option<Result<Either<SqliteQueryResult, SqliteRow>, Error>> {
         let statement = if self.goto_next {
             let mut statement = match self.statement.prepare_next(self.handle) {
                 Ok(Some(statement)) => statement,
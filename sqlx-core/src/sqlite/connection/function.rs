@@ -0,0 +1,308 @@
+use std::ffi::CString;
+use std::fmt::{self, Debug, Formatter};
+use std::mem;
+use std::os::raw::{c_char, c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+use std::sync::Arc;
+
+use libsqlite3_sys::{
+    sqlite3_aggregate_context, sqlite3_context, sqlite3_create_function_v2, sqlite3_result_blob64,
+    sqlite3_result_double, sqlite3_result_error, sqlite3_result_error_nomem, sqlite3_result_int,
+    sqlite3_result_int64, sqlite3_result_null, sqlite3_result_text64, sqlite3_user_data,
+    sqlite3_value, sqlite3_value_type, SQLITE_OK, SQLITE_TRANSIENT, SQLITE_UTF8,
+};
+
+use crate::encode::{Encode, IsNull};
+use crate::error::{BoxDynError, Error};
+use crate::sqlite::connection::handle::ConnectionHandle;
+use crate::sqlite::type_info::DataType;
+use crate::sqlite::{Sqlite, SqliteArgumentValue, SqliteError, SqliteTypeInfo, SqliteValue};
+
+/// An aggregate SQL function, e.g. `SUM()`, implemented in Rust.
+///
+/// A value is created for every group of rows; [`step()`][Self::step] is called with the
+/// arguments for each row of the group, then [`finish()`][Self::finish] returns the result.
+///
+/// ```rust,no_run
+/// # use sqlx_core::error::BoxDynError;
+/// # use sqlx_core::sqlite::{SqliteAggregate, SqliteValue};
+/// use sqlx::Value;
+///
+/// // product(x)
+/// #[derive(Default)]
+/// struct Product(f64);
+///
+/// impl SqliteAggregate for Product {
+///     type Output = f64;
+///
+///     fn step(&mut self, args: &[SqliteValue]) -> Result<(), BoxDynError> {
+///         self.0 *= args[0].try_decode::<f64>()?;
+///         Ok(())
+///     }
+///
+///     fn finish(self) -> Result<f64, BoxDynError> {
+///         Ok(self.0)
+///     }
+/// }
+/// ```
+pub trait SqliteAggregate: Send + 'static {
+    type Output: Encode<'static, Sqlite>;
+
+    /// Adds the arguments for a row of the group.
+    fn step(&mut self, args: &[SqliteValue]) -> Result<(), BoxDynError>;
+
+    /// Returns the result for the group.
+    fn finish(self) -> Result<Self::Output, BoxDynError>;
+}
+
+type ScalarFn = dyn Fn(&[SqliteValue]) -> Result<SqliteArgumentValue<'static>, BoxDynError>
+    + Send
+    + Sync
+    + 'static;
+
+type AggregateFactory = dyn Fn() -> Box<dyn ErasedAggregate> + Send + Sync + 'static;
+
+// `SqliteAggregate` without the associated type, to be boxed
+trait ErasedAggregate: Send {
+    fn step(&mut self, args: &[SqliteValue]) -> Result<(), BoxDynError>;
+
+    fn finish(self: Box<Self>) -> Result<SqliteArgumentValue<'static>, BoxDynError>;
+}
+
+impl<A: SqliteAggregate> ErasedAggregate for A {
+    fn step(&mut self, args: &[SqliteValue]) -> Result<(), BoxDynError> {
+        SqliteAggregate::step(self, args)
+    }
+
+    fn finish(self: Box<Self>) -> Result<SqliteArgumentValue<'static>, BoxDynError> {
+        SqliteAggregate::finish(*self).map(encode_result)
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct Function {
+    name: Arc<str>,
+    num_args: c_int,
+    kind: FunctionKind,
+}
+
+#[derive(Clone)]
+enum FunctionKind {
+    Scalar(Arc<ScalarFn>),
+    Aggregate(Arc<AggregateFactory>),
+}
+
+impl Function {
+    pub(crate) fn scalar<N, F, R>(name: N, num_args: c_int, f: F) -> Self
+    where
+        N: Into<Arc<str>>,
+        F: Fn(&[SqliteValue]) -> Result<R, BoxDynError> + Send + Sync + 'static,
+        R: Encode<'static, Sqlite>,
+    {
+        Function {
+            name: name.into(),
+            num_args,
+            kind: FunctionKind::Scalar(Arc::new(move |args| f(args).map(encode_result))),
+        }
+    }
+
+    pub(crate) fn aggregate<N, F, A>(name: N, num_args: c_int, init: F) -> Self
+    where
+        N: Into<Arc<str>>,
+        F: Fn() -> A + Send + Sync + 'static,
+        A: SqliteAggregate,
+    {
+        Function {
+            name: name.into(),
+            num_args,
+            kind: FunctionKind::Aggregate(Arc::new(move || Box::new(init()))),
+        }
+    }
+
+    pub(crate) fn create(&self, handle: &mut ConnectionHandle) -> Result<(), Error> {
+        let c_name = CString::new(&*self.name)
+            .map_err(|_| err_protocol!("invalid function name: {:?}", self.name))?;
+
+        // SAFETY: the user data must match the callbacks
+        let r = unsafe {
+            match &self.kind {
+                FunctionKind::Scalar(f) => sqlite3_create_function_v2(
+                    handle.as_ptr(),
+                    c_name.as_ptr(),
+                    self.num_args,
+                    SQLITE_UTF8,
+                    Box::into_raw(Box::new(Arc::clone(f))) as *mut c_void,
+                    Some(call_scalar),
+                    None,
+                    None,
+                    Some(drop_boxed_value::<Arc<ScalarFn>>),
+                ),
+
+                FunctionKind::Aggregate(init) => sqlite3_create_function_v2(
+                    handle.as_ptr(),
+                    c_name.as_ptr(),
+                    self.num_args,
+                    SQLITE_UTF8,
+                    Box::into_raw(Box::new(Arc::clone(init))) as *mut c_void,
+                    None,
+                    Some(call_step),
+                    Some(call_final),
+                    Some(drop_boxed_value::<Arc<AggregateFactory>>),
+                ),
+            }
+        };
+
+        // unlike with collations, the destructor is called if sqlite3_create_function_v2() fails
+        if r == SQLITE_OK {
+            Ok(())
+        } else {
+            Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))))
+        }
+    }
+}
+
+impl Debug for Function {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Function")
+            .field("name", &self.name)
+            .field("num_args", &self.num_args)
+            .finish_non_exhaustive()
+    }
+}
+
+fn encode_result<R: Encode<'static, Sqlite>>(value: R) -> SqliteArgumentValue<'static> {
+    let mut values = Vec::with_capacity(1);
+
+    match value.encode(&mut values) {
+        IsNull::Yes => SqliteArgumentValue::Null,
+        IsNull::No => values.pop().unwrap_or(SqliteArgumentValue::Null),
+    }
+}
+
+unsafe extern "C" fn drop_boxed_value<T>(p: *mut c_void) {
+    drop(Box::from_raw(p as *mut T));
+}
+
+unsafe fn args(argc: c_int, argv: *mut *mut sqlite3_value) -> Vec<SqliteValue> {
+    if argc == 0 {
+        return Vec::new();
+    }
+
+    slice::from_raw_parts(argv, argc as usize)
+        .iter()
+        .map(|&value| {
+            let ty = SqliteTypeInfo(DataType::from_code(sqlite3_value_type(value)));
+
+            SqliteValue::new(value, ty)
+        })
+        .collect()
+}
+
+unsafe fn set_result(
+    ctx: *mut sqlite3_context,
+    result: Result<SqliteArgumentValue<'_>, BoxDynError>,
+) {
+    match result {
+        Ok(SqliteArgumentValue::Null) => sqlite3_result_null(ctx),
+
+        Ok(SqliteArgumentValue::Text(v)) => sqlite3_result_text64(
+            ctx,
+            v.as_ptr() as *const c_char,
+            v.len() as u64,
+            SQLITE_TRANSIENT(),
+            SQLITE_UTF8 as u8,
+        ),
+
+        Ok(SqliteArgumentValue::Blob(v)) => sqlite3_result_blob64(
+            ctx,
+            v.as_ptr() as *const c_void,
+            v.len() as u64,
+            SQLITE_TRANSIENT(),
+        ),
+
+        Ok(SqliteArgumentValue::Double(v)) => sqlite3_result_double(ctx, v),
+        Ok(SqliteArgumentValue::Int(v)) => sqlite3_result_int(ctx, v),
+        Ok(SqliteArgumentValue::Int64(v)) => sqlite3_result_int64(ctx, v),
+
+        Err(e) => {
+            let message = e.to_string();
+
+            sqlite3_result_error(
+                ctx,
+                message.as_ptr() as *const c_char,
+                message.len() as c_int,
+            );
+        }
+    }
+}
+
+// a panic must not unwind into SQLite
+fn catch<T>(f: impl FnOnce() -> Result<T, BoxDynError>) -> Result<T, BoxDynError> {
+    catch_unwind(AssertUnwindSafe(f))
+        .unwrap_or_else(|_| Err("panic in a user-defined SQL function".into()))
+}
+
+unsafe extern "C" fn call_scalar(
+    ctx: *mut sqlite3_context,
+    argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    let f = &*(sqlite3_user_data(ctx) as *const Arc<ScalarFn>);
+    let args = args(argc, argv);
+
+    set_result(ctx, catch(|| f(&args)));
+}
+
+// the aggregate context holds a pointer to the state of the aggregate, created on the first row
+type AggregateState = *mut Box<dyn ErasedAggregate>;
+
+unsafe extern "C" fn call_step(
+    ctx: *mut sqlite3_context,
+    argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    let slot = sqlite3_aggregate_context(ctx, mem::size_of::<AggregateState>() as c_int)
+        as *mut AggregateState;
+
+    if slot.is_null() {
+        sqlite3_result_error_nomem(ctx);
+        return;
+    }
+
+    let init = &*(sqlite3_user_data(ctx) as *const Arc<AggregateFactory>);
+    let args = args(argc, argv);
+
+    let res = catch(|| {
+        if (*slot).is_null() {
+            *slot = Box::into_raw(Box::new(init()));
+        }
+
+        (**slot).step(&args)
+    });
+
+    if let Err(e) = res {
+        set_result(ctx, Err(e));
+    }
+}
+
+unsafe extern "C" fn call_final(ctx: *mut sqlite3_context) {
+    // NULL if no row was stepped, i.e. an empty group
+    let slot = sqlite3_aggregate_context(ctx, 0) as *mut AggregateState;
+    let init = &*(sqlite3_user_data(ctx) as *const Arc<AggregateFactory>);
+
+    let state = if slot.is_null() || (*slot).is_null() {
+        None
+    } else {
+        Some(Box::from_raw(mem::replace(&mut *slot, ptr::null_mut())))
+    };
+
+    set_result(
+        ctx,
+        catch(|| match state {
+            Some(state) => (*state).finish(),
+            None => init().finish(),
+        }),
+    );
+}
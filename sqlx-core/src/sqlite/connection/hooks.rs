@@ -0,0 +1,220 @@
+use std::ffi::CStr;
+use std::fmt::{self, Debug, Formatter};
+use std::os::raw::{c_char, c_int, c_void};
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+
+use futures_channel::mpsc;
+use futures_core::stream::Stream;
+use futures_util::StreamExt;
+use libsqlite3_sys::{
+    sqlite3_commit_hook, sqlite3_rollback_hook, sqlite3_update_hook, SQLITE_DELETE, SQLITE_INSERT,
+    SQLITE_UPDATE,
+};
+
+use crate::sqlite::connection::handle::ConnectionHandle;
+
+// https://www.sqlite.org/c3ref/update_hook.html
+// https://www.sqlite.org/c3ref/commit_hook.html
+
+/// A stream of the changes to rows committed by the connections opened with
+/// [`SqliteConnectOptions::change_listener()`][crate::sqlite::SqliteConnectOptions::change_listener].
+///
+/// Changes are sent once their transaction commits, in the order they were made; the changes of a
+/// transaction which is rolled back are discarded. A statement which fails inside a transaction
+/// undoes its changes without a rollback, so the changes it made before failing are still sent
+/// if the transaction commits.
+///
+/// The changes are those reported by
+/// [`sqlite3_update_hook()`](https://www.sqlite.org/c3ref/update_hook.html): changes to
+/// `WITHOUT ROWID` tables, to internal tables, and deletes by the truncate optimization (a
+/// `DELETE` without a `WHERE` clause, on a table without triggers) are not reported.
+///
+/// Changes are buffered until they are received, so the listener should be polled continuously.
+///
+/// ```rust,no_run
+/// # use sqlx_core::error::Error;
+/// # use sqlx_core::sqlite::{SqliteChangeListener, SqliteConnectOptions};
+/// # use sqlx_core::pool::PoolOptions;
+/// # async fn listen() -> Result<(), Error> {
+/// let mut listener = SqliteChangeListener::new();
+///
+/// let pool = PoolOptions::new()
+///     .connect_with(SqliteConnectOptions::new().filename("data.db").change_listener(&listener))
+///     .await?;
+/// # let _: sqlx_core::sqlite::SqlitePool = pool;
+///
+/// loop {
+///     let change = listener.recv().await;
+///
+///     println!("{:?} row {} of {}", change.operation(), change.rowid(), change.table());
+/// }
+/// # }
+/// ```
+pub struct SqliteChangeListener {
+    tx: mpsc::UnboundedSender<SqliteChange>,
+    rx: mpsc::UnboundedReceiver<SqliteChange>,
+}
+
+/// A change to a row, sent by a [`SqliteChangeListener`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqliteChange {
+    operation: SqliteOperation,
+    database: String,
+    table: String,
+    rowid: i64,
+}
+
+/// The kind of change to a row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqliteOperation {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl SqliteChangeListener {
+    pub fn new() -> Self {
+        let (tx, rx) = mpsc::unbounded();
+
+        Self { tx, rx }
+    }
+
+    /// Receives the next change.
+    pub async fn recv(&mut self) -> SqliteChange {
+        // the listener keeps a sender, so the channel never closes
+        self.rx
+            .next()
+            .await
+            .expect("the listener holds a sender of its channel")
+    }
+
+    /// Receives the next change if one was already sent.
+    pub fn try_recv(&mut self) -> Option<SqliteChange> {
+        self.rx.try_next().ok().flatten()
+    }
+
+    pub(crate) fn sender(&self) -> mpsc::UnboundedSender<SqliteChange> {
+        self.tx.clone()
+    }
+}
+
+impl Default for SqliteChangeListener {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for SqliteChangeListener {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqliteChangeListener").finish()
+    }
+}
+
+impl Stream for SqliteChangeListener {
+    type Item = SqliteChange;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.rx).poll_next(cx)
+    }
+}
+
+impl SqliteChange {
+    pub fn operation(&self) -> SqliteOperation {
+        self.operation
+    }
+
+    /// The name of the database of the table, e.g. `main` or `temp`.
+    pub fn database(&self) -> &str {
+        &self.database
+    }
+
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// The rowid of the row; that of the row before an update, if the rowid was changed.
+    pub fn rowid(&self) -> i64 {
+        self.rowid
+    }
+}
+
+/// The state of the hooks of a connection, freed after the connection is closed.
+pub(crate) struct ChangeHooks {
+    tx: mpsc::UnboundedSender<SqliteChange>,
+
+    // the changes of the current transaction
+    pending: Mutex<Vec<SqliteChange>>,
+}
+
+impl ChangeHooks {
+    pub(crate) fn register(
+        handle: &ConnectionHandle,
+        tx: mpsc::UnboundedSender<SqliteChange>,
+    ) -> Box<Self> {
+        let hooks = Box::new(ChangeHooks {
+            tx,
+            pending: Mutex::new(Vec::new()),
+        });
+
+        let arg = &*hooks as *const ChangeHooks as *mut c_void;
+
+        // SAFETY: the connection state owns the hooks, and drops them after the connection
+        unsafe {
+            sqlite3_update_hook(handle.as_ptr(), Some(on_update), arg);
+            sqlite3_commit_hook(handle.as_ptr(), Some(on_commit), arg);
+            sqlite3_rollback_hook(handle.as_ptr(), Some(on_rollback), arg);
+        }
+
+        hooks
+    }
+
+    fn pending(&self) -> std::sync::MutexGuard<'_, Vec<SqliteChange>> {
+        // a panic can't poison the lock: the hooks don't call back into user code
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+unsafe extern "C" fn on_update(
+    arg: *mut c_void,
+    operation: c_int,
+    database: *const c_char,
+    table: *const c_char,
+    rowid: i64,
+) {
+    let hooks = &*(arg as *const ChangeHooks);
+
+    let operation = match operation {
+        SQLITE_INSERT => SqliteOperation::Insert,
+        SQLITE_UPDATE => SqliteOperation::Update,
+        SQLITE_DELETE => SqliteOperation::Delete,
+
+        _ => return,
+    };
+
+    hooks.pending().push(SqliteChange {
+        operation,
+        database: CStr::from_ptr(database).to_string_lossy().into_owned(),
+        table: CStr::from_ptr(table).to_string_lossy().into_owned(),
+        rowid,
+    });
+}
+
+unsafe extern "C" fn on_commit(arg: *mut c_void) -> c_int {
+    let hooks = &*(arg as *const ChangeHooks);
+
+    for change in hooks.pending().drain(..) {
+        // the listener may have been dropped
+        let _ = hooks.tx.unbounded_send(change);
+    }
+
+    // zero lets the commit proceed
+    0
+}
+
+unsafe extern "C" fn on_rollback(arg: *mut c_void) {
+    let hooks = &*(arg as *const ChangeHooks);
+
+    hooks.pending().clear();
+}
@@ -5,26 +5,39 @@ use std::ptr::NonNull;
 use futures_core::future::BoxFuture;
 use futures_intrusive::sync::MutexGuard;
 use futures_util::future;
-use libsqlite3_sys::sqlite3;
+use libsqlite3_sys::{sqlite3, sqlite3_libversion_number};
 
+pub use backup::{SqliteBackup, SqliteBackupDestination, SqliteBackupProgress};
+pub use checkpoint::SqliteCheckpoint;
+pub use function::SqliteAggregate;
 pub(crate) use handle::{ConnectionHandle, ConnectionHandleRaw};
+pub use hooks::{SqliteChange, SqliteChangeListener, SqliteOperation};
 
 use crate::common::StatementCache;
-use crate::connection::{Connection, LogSettings};
-use crate::error::Error;
+use crate::connection::{Connection, LogSettings, ServerCapabilities, ServerVersion};
+use crate::encode::Encode;
+use crate::error::{BoxDynError, Error};
+use crate::sqlite::connection::busy_handler::BusyHandler;
 use crate::sqlite::connection::establish::EstablishParams;
+use crate::sqlite::connection::function::Function;
+use crate::sqlite::connection::hooks::ChangeHooks;
 use crate::sqlite::connection::worker::ConnectionWorker;
 use crate::sqlite::statement::VirtualStatement;
-use crate::sqlite::{Sqlite, SqliteConnectOptions};
-use crate::transaction::Transaction;
+use crate::sqlite::{Sqlite, SqliteCheckpointMode, SqliteConnectOptions, SqliteValue};
+use crate::transaction::{Transaction, TransactionOptions};
 
+mod backup;
+pub(crate) mod busy_handler;
+mod checkpoint;
 pub(crate) mod collation;
 pub(crate) mod describe;
 pub(crate) mod establish;
 pub(crate) mod execute;
 mod executor;
 mod explain;
+pub(crate) mod function;
 mod handle;
+mod hooks;
 
 mod worker;
 
@@ -56,6 +69,10 @@ pub(crate) struct ConnectionState {
     pub(crate) statements: Statements,
 
     log_settings: LogSettings,
+
+    // registered with the handle, so dropped after it
+    _busy_handler: Option<Box<BusyHandler>>,
+    _change_hooks: Option<Box<ChangeHooks>>,
 }
 
 pub(crate) struct Statements {
@@ -68,6 +85,12 @@ pub(crate) struct Statements {
 impl SqliteConnection {
     pub(crate) async fn establish(options: &SqliteConnectOptions) -> Result<Self, Error> {
         let params = EstablishParams::from_options(options)?;
+
+        // keep the database alive while the options are, even if every connection is closed
+        if params.is_shared_memory() {
+            options.in_memory_anchor.open(&params)?;
+        }
+
         let worker = ConnectionWorker::establish(params).await?;
         Ok(Self {
             worker,
@@ -119,6 +142,104 @@ impl SqliteConnection {
         self.worker.create_collation(name, compare)
     }
 
+    /// Register a scalar SQL function implemented in Rust.
+    ///
+    /// See [`SqliteConnectOptions::scalar_function()`] for details, and to register the
+    /// function on every connection of a pool.
+    pub async fn create_scalar_function<F, R>(
+        &mut self,
+        name: &str,
+        num_args: i32,
+        f: F,
+    ) -> Result<(), Error>
+    where
+        F: Fn(&[SqliteValue]) -> Result<R, BoxDynError> + Send + Sync + 'static,
+        R: Encode<'static, Sqlite>,
+    {
+        self.lock_handle()
+            .await?
+            .create_scalar_function(name, num_args, f)
+    }
+
+    /// Register an aggregate SQL function implemented in Rust.
+    ///
+    /// See [`SqliteConnectOptions::aggregate_function()`] for details, and to register the
+    /// function on every connection of a pool.
+    pub async fn create_aggregate_function<F, A>(
+        &mut self,
+        name: &str,
+        num_args: i32,
+        init: F,
+    ) -> Result<(), Error>
+    where
+        F: Fn() -> A + Send + Sync + 'static,
+        A: SqliteAggregate,
+    {
+        self.lock_handle()
+            .await?
+            .create_aggregate_function(name, num_args, init)
+    }
+
+    /// Start an [online backup](https://www.sqlite.org/backup.html) of the database to a file
+    /// or another connection, which is copied as the backup is stepped.
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::error::Error;
+    /// # use sqlx_core::sqlite::SqliteConnection;
+    /// # use std::time::Duration;
+    /// # async fn backup(conn: &mut SqliteConnection) -> Result<(), Error> {
+    /// conn.backup_to("backup.db")
+    ///     .await?
+    ///     .run(100, Duration::from_millis(10), |progress| {
+    ///         println!("{} of {} pages left", progress.remaining(), progress.page_count());
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// See [`SqliteBackup`] for details.
+    pub async fn backup_to<'a>(
+        &'a mut self,
+        destination: impl Into<SqliteBackupDestination<'a>>,
+    ) -> Result<SqliteBackup<'a>, Error> {
+        SqliteBackup::new(self, destination.into()).await
+    }
+
+    /// Run a [WAL checkpoint](https://www.sqlite.org/wal.html#checkpointing), copying the pages
+    /// of the WAL into the database.
+    ///
+    /// A `TRUNCATE` checkpoint waits for readers and writers, up to the busy timeout, then
+    /// truncates the WAL file, so it is the one to run periodically to keep the WAL from growing
+    /// on a busy database.
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::error::Error;
+    /// # use sqlx_core::sqlite::{SqliteCheckpointMode, SqlitePool};
+    /// # use std::time::Duration;
+    /// # async fn checkpoint(pool: SqlitePool) -> Result<(), Error> {
+    /// loop {
+    ///     sqlx_rt::sleep(Duration::from_secs(60)).await;
+    ///
+    ///     let checkpoint = pool
+    ///         .acquire()
+    ///         .await?
+    ///         .wal_checkpoint(SqliteCheckpointMode::Truncate)
+    ///         .await?;
+    ///
+    ///     if checkpoint.is_busy() {
+    ///         println!("{:?} frames left in the WAL", checkpoint.log_frames());
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub async fn wal_checkpoint(
+        &mut self,
+        mode: SqliteCheckpointMode,
+    ) -> Result<SqliteCheckpoint, Error> {
+        SqliteCheckpoint::run(self, mode).await
+    }
+
     /// Lock the SQLite database handle out from the worker thread so direct SQLite API calls can
     /// be made safely.
     ///
@@ -171,7 +292,17 @@ impl Connection for SqliteConnection {
     where
         Self: Sized,
     {
-        Transaction::begin(self)
+        Transaction::start(self)
+    }
+
+    fn begin_with(
+        &mut self,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized,
+    {
+        Transaction::start_with(self, options)
     }
 
     fn cached_statements_size(&self) -> usize {
@@ -181,6 +312,20 @@ impl Connection for SqliteConnection {
             .load(std::sync::atomic::Ordering::Acquire)
     }
 
+    fn transaction_depth(&self) -> usize {
+        self.worker
+            .shared
+            .transaction_depth
+            .load(std::sync::atomic::Ordering::Acquire)
+    }
+
+    fn statement_count(&self) -> u64 {
+        self.worker
+            .shared
+            .statement_count
+            .load(std::sync::atomic::Ordering::Acquire)
+    }
+
     fn clear_cached_statements(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
             self.worker.clear_cache().await?;
@@ -188,6 +333,29 @@ impl Connection for SqliteConnection {
         })
     }
 
+    fn server_version(&self) -> Option<ServerVersion> {
+        // e.g. 3039002 for 3.39.2
+        let num = unsafe { sqlite3_libversion_number() } as u32;
+
+        Some(ServerVersion::new(
+            num / 1_000_000,
+            num / 1000 % 1000,
+            num % 1000,
+        ))
+    }
+
+    fn server_capabilities(&self) -> ServerCapabilities {
+        let num = unsafe { sqlite3_libversion_number() };
+
+        ServerCapabilities {
+            returning: num >= 3_035_000,
+            // the JSON functions are built in by default since 3.38.0
+            json: num >= 3_038_000,
+            common_table_expressions: num >= 3_008_003,
+            multiranges: false,
+        }
+    }
+
     #[doc(hidden)]
     fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         // For SQLite, FLUSH does effectively nothing...
@@ -222,6 +390,38 @@ impl LockedSqliteHandle<'_> {
     ) -> Result<(), Error> {
         collation::create_collation(&mut self.guard.handle, name, compare)
     }
+
+    /// Register a scalar SQL function implemented in Rust.
+    ///
+    /// See [`SqliteConnectOptions::scalar_function()`] for details.
+    pub fn create_scalar_function<F, R>(
+        &mut self,
+        name: &str,
+        num_args: i32,
+        f: F,
+    ) -> Result<(), Error>
+    where
+        F: Fn(&[SqliteValue]) -> Result<R, BoxDynError> + Send + Sync + 'static,
+        R: Encode<'static, Sqlite>,
+    {
+        Function::scalar(name, num_args, f).create(&mut self.guard.handle)
+    }
+
+    /// Register an aggregate SQL function implemented in Rust.
+    ///
+    /// See [`SqliteConnectOptions::aggregate_function()`] for details.
+    pub fn create_aggregate_function<F, A>(
+        &mut self,
+        name: &str,
+        num_args: i32,
+        init: F,
+    ) -> Result<(), Error>
+    where
+        F: Fn() -> A + Send + Sync + 'static,
+        A: SqliteAggregate,
+    {
+        Function::aggregate(name, num_args, init).create(&mut self.guard.handle)
+    }
 }
 
 impl Drop for ConnectionState {
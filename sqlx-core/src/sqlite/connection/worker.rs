@@ -1,6 +1,6 @@
 use std::borrow::Cow;
 use std::future::Future;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::thread;
 
@@ -10,6 +10,7 @@ use futures_intrusive::sync::{Mutex, MutexGuard};
 
 use crate::describe::Describe;
 use crate::error::Error;
+use crate::observer;
 use crate::sqlite::connection::collation::create_collation;
 use crate::sqlite::connection::describe::describe;
 use crate::sqlite::connection::establish::EstablishParams;
@@ -36,6 +37,9 @@ pub(crate) struct ConnectionWorker {
 
 pub(crate) struct WorkerSharedState {
     pub(crate) cached_statements_size: AtomicUsize,
+    // mirrors of the state of the connection, readable without locking it
+    pub(crate) transaction_depth: AtomicUsize,
+    pub(crate) statement_count: AtomicU64,
     pub(crate) conn: Mutex<ConnectionState>,
 }
 
@@ -52,9 +56,13 @@ enum Command {
         query: Box<str>,
         arguments: Option<SqliteArguments<'static>>,
         persistent: bool,
+        // the tags of the task executing the statement, for the observer
+        tags: Option<observer::Tags>,
         tx: flume::Sender<Result<Either<SqliteQueryResult, SqliteRow>, Error>>,
     },
     Begin {
+        // `BEGIN IMMEDIATE`, taking the write lock when the transaction begins
+        immediate: bool,
         tx: oneshot::Sender<Result<(), Error>>,
     },
     Commit {
@@ -63,6 +71,10 @@ enum Command {
     Rollback {
         tx: Option<oneshot::Sender<Result<(), Error>>>,
     },
+    // a statement managing the transaction, e.g. rolling back to a dropped savepoint
+    StartStatement {
+        query: Box<str>,
+    },
     CreateCollation {
         create_collation:
             Box<dyn FnOnce(&mut ConnectionState) -> Result<(), Error> + Send + Sync + 'static>,
@@ -98,6 +110,8 @@ impl ConnectionWorker {
 
                 let shared = Arc::new(WorkerSharedState {
                     cached_statements_size: AtomicUsize::new(0),
+                    transaction_depth: AtomicUsize::new(0),
+                    statement_count: AtomicU64::new(0),
                     // note: must be fair because in `Command::UnlockDb` we unlock the mutex
                     // and then immediately try to relock it; an unfair mutex would immediately
                     // grant us the lock even if another task is waiting.
@@ -135,8 +149,13 @@ impl ConnectionWorker {
                             query,
                             arguments,
                             persistent,
+                            tags,
                             tx,
                         } => {
+                            let _tags = observer::enter(tags);
+
+                            shared.statement_count.fetch_add(1, Ordering::Release);
+
                             let iter = match execute::iter(&mut conn, &query, arguments, persistent)
                             {
                                 Ok(iter) => iter,
@@ -154,14 +173,21 @@ impl ConnectionWorker {
 
                             update_cached_statements_size(&conn, &shared.cached_statements_size);
                         }
-                        Command::Begin { tx } => {
+                        Command::Begin { immediate, tx } => {
                             let depth = conn.transaction_depth;
-                            let res =
-                                conn.handle
-                                    .exec(begin_ansi_transaction_sql(depth))
-                                    .map(|_| {
-                                        conn.transaction_depth += 1;
-                                    });
+                            let sql = if immediate && depth == 0 {
+                                "BEGIN IMMEDIATE".into()
+                            } else {
+                                begin_ansi_transaction_sql(depth)
+                            };
+
+                            let res = conn.handle.exec(sql).map(|_| {
+                                conn.transaction_depth += 1;
+                            });
+
+                            shared
+                                .transaction_depth
+                                .store(conn.transaction_depth, Ordering::Release);
 
                             tx.send(res).ok();
                         }
@@ -178,6 +204,10 @@ impl ConnectionWorker {
                                 Ok(())
                             };
 
+                            shared
+                                .transaction_depth
+                                .store(conn.transaction_depth, Ordering::Release);
+
                             tx.send(res).ok();
                         }
                         Command::Rollback { tx } => {
@@ -193,10 +223,23 @@ impl ConnectionWorker {
                                 Ok(())
                             };
 
+                            shared
+                                .transaction_depth
+                                .store(conn.transaction_depth, Ordering::Release);
+
                             if let Some(tx) = tx {
                                 tx.send(res).ok();
                             }
                         }
+                        Command::StartStatement { query } => {
+                            if let Err(e) = conn.handle.exec(&*query) {
+                                log::warn!(
+                                    "error executing {:?} in background worker: {}",
+                                    query,
+                                    e
+                                );
+                            }
+                        }
                         Command::CreateCollation { create_collation } => {
                             if let Err(e) = (create_collation)(&mut conn) {
                                 log::warn!("error applying collation in background worker: {}", e);
@@ -259,6 +302,7 @@ impl ConnectionWorker {
                 query: query.into(),
                 arguments: args.map(SqliteArguments::into_static),
                 persistent,
+                tags: observer::current_tags(),
                 tx,
             })
             .await
@@ -268,7 +312,19 @@ impl ConnectionWorker {
     }
 
     pub(crate) async fn begin(&mut self) -> Result<(), Error> {
-        self.oneshot_cmd(|tx| Command::Begin { tx }).await?
+        self.oneshot_cmd(|tx| Command::Begin {
+            immediate: false,
+            tx,
+        })
+        .await?
+    }
+
+    pub(crate) async fn begin_immediate(&mut self) -> Result<(), Error> {
+        self.oneshot_cmd(|tx| Command::Begin {
+            immediate: true,
+            tx,
+        })
+        .await?
     }
 
     pub(crate) async fn commit(&mut self) -> Result<(), Error> {
@@ -286,6 +342,14 @@ impl ConnectionWorker {
             .map_err(|_| Error::WorkerCrashed)
     }
 
+    pub(crate) fn start_statement(&mut self, query: &str) -> Result<(), Error> {
+        self.command_tx
+            .send(Command::StartStatement {
+                query: query.into(),
+            })
+            .map_err(|_| Error::WorkerCrashed)
+    }
+
     pub(crate) async fn ping(&mut self) -> Result<(), Error> {
         self.oneshot_cmd(|tx| Command::Ping { tx }).await
     }
@@ -4,9 +4,13 @@ use std::fmt::{self, Display, Formatter};
 use std::os::raw::c_int;
 use std::{borrow::Cow, str::from_utf8_unchecked};
 
-use libsqlite3_sys::{sqlite3, sqlite3_errmsg, sqlite3_extended_errcode};
+use libsqlite3_sys::{
+    sqlite3, sqlite3_errmsg, sqlite3_extended_errcode, SQLITE_BUSY_SNAPSHOT,
+    SQLITE_CONSTRAINT_CHECK, SQLITE_CONSTRAINT_FOREIGNKEY, SQLITE_CONSTRAINT_NOTNULL,
+    SQLITE_CONSTRAINT_PRIMARYKEY, SQLITE_CONSTRAINT_UNIQUE,
+};
 
-use crate::error::DatabaseError;
+use crate::error::{DatabaseError, ErrorKind};
 
 // Error Codes And Messages
 // https://www.sqlite.org/c3ref/errcode.html
@@ -35,6 +39,15 @@ impl SqliteError {
             message: message.to_owned(),
         }
     }
+
+    /// For errors which don't set the error message of the connection, such as
+    /// `sqlite3_load_extension()`.
+    pub(crate) fn with_message(handle: *mut sqlite3, message: &str) -> Self {
+        Self {
+            message: message.to_owned(),
+            ..Self::new(handle)
+        }
+    }
 }
 
 impl Display for SqliteError {
@@ -75,4 +88,58 @@ impl DatabaseError for SqliteError {
     fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
         self
     }
+
+    fn kind(&self) -> ErrorKind {
+        match self.code {
+            SQLITE_CONSTRAINT_UNIQUE | SQLITE_CONSTRAINT_PRIMARYKEY => ErrorKind::UniqueViolation,
+            SQLITE_CONSTRAINT_FOREIGNKEY => ErrorKind::ForeignKeyViolation,
+            SQLITE_CONSTRAINT_CHECK => ErrorKind::CheckViolation,
+            SQLITE_CONSTRAINT_NOTNULL => ErrorKind::NotNullViolation,
+            // a write in a WAL read transaction, whose snapshot is no longer the latest
+            SQLITE_BUSY_SNAPSHOT => ErrorKind::SerializationFailure,
+            _ => ErrorKind::Other,
+        }
+    }
+
+    // SQLite reports the objects of a constraint violation in its message, e.g.:
+    //
+    //   UNIQUE constraint failed: users.email
+    //   NOT NULL constraint failed: users.name
+    //   CHECK constraint failed: age_positive
+    //
+    // while a foreign key violation names nothing.
+
+    fn constraint(&self) -> Option<&str> {
+        match self.kind() {
+            ErrorKind::CheckViolation => self.message.split_once("failed: ").map(|(_, c)| c),
+            _ => None,
+        }
+    }
+
+    fn table(&self) -> Option<&str> {
+        self.columns()?.split_once('.').map(|(table, _)| table)
+    }
+
+    fn column(&self) -> Option<&str> {
+        let columns = self.columns()?;
+
+        if columns.contains(", ") {
+            return None;
+        }
+
+        columns.split_once('.').map(|(_, column)| column)
+    }
+}
+
+impl SqliteError {
+    // the `table.column, ...` a unique or not-null violation is about
+    fn columns(&self) -> Option<&str> {
+        match self.kind() {
+            ErrorKind::UniqueViolation | ErrorKind::NotNullViolation => self
+                .message
+                .split_once("failed: ")
+                .map(|(_, columns)| columns),
+            _ => None,
+        }
+    }
 }
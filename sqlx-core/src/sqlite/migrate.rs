@@ -2,10 +2,11 @@ use crate::connection::{ConnectOptions, Connection};
 use crate::error::Error;
 use crate::executor::Executor;
 use crate::migrate::MigrateError;
-use crate::migrate::{AppliedMigration, Migration};
+use crate::migrate::{AppliedMigration, LockAttempts, Migration, MigrationLock};
 use crate::migrate::{Migrate, MigrateDatabase};
 use crate::query::query;
 use crate::query_as::query_as;
+use crate::query_batch::split_statements;
 use crate::query_scalar::query_scalar;
 use crate::sqlite::{Sqlite, SqliteConnectOptions, SqliteConnection, SqliteJournalMode};
 use futures_core::future::BoxFuture;
@@ -65,12 +66,15 @@ impl MigrateDatabase for Sqlite {
 }
 
 impl Migrate for SqliteConnection {
-    fn ensure_migrations_table(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+    fn ensure_migrations_table<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<(), MigrateError>> {
         Box::pin(async move {
             // language=SQLite
-            self.execute(
+            self.execute(&*format!(
                 r#"
-CREATE TABLE IF NOT EXISTS _sqlx_migrations (
+CREATE TABLE IF NOT EXISTS {} (
     version BIGINT PRIMARY KEY,
     description TEXT NOT NULL,
     installed_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
@@ -79,7 +83,8 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
     execution_time BIGINT NOT NULL
 );
                 "#,
-            )
+                table_name
+            ))
             .await?;
 
             Ok(())
@@ -99,12 +104,16 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         })
     }
 
-    fn dirty_version(&mut self) -> BoxFuture<'_, Result<Option<i64>, MigrateError>> {
+    fn dirty_version<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Option<i64>, MigrateError>> {
         Box::pin(async move {
             // language=SQLite
-            let row: Option<(i64,)> = query_as(
-                "SELECT version FROM _sqlx_migrations WHERE success = false ORDER BY version LIMIT 1",
-            )
+            let row: Option<(i64,)> = query_as(&format!(
+                "SELECT version FROM {} WHERE success = false ORDER BY version LIMIT 1",
+                table_name
+            ))
             .fetch_optional(self)
             .await?;
 
@@ -112,21 +121,25 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         })
     }
 
-    fn list_applied_migrations(
-        &mut self,
-    ) -> BoxFuture<'_, Result<Vec<AppliedMigration>, MigrateError>> {
+    fn list_applied_migrations<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<Vec<AppliedMigration>, MigrateError>> {
         Box::pin(async move {
             // language=SQLite
-            let rows: Vec<(i64, Vec<u8>)> =
-                query_as("SELECT version, checksum FROM _sqlx_migrations ORDER BY version")
-                    .fetch_all(self)
-                    .await?;
+            let rows: Vec<(i64, Vec<u8>, i64)> = query_as(&format!(
+                "SELECT version, checksum, execution_time FROM {} ORDER BY version",
+                table_name
+            ))
+            .fetch_all(self)
+            .await?;
 
             let migrations = rows
                 .into_iter()
-                .map(|(version, checksum)| AppliedMigration {
+                .map(|(version, checksum, execution_time)| AppliedMigration {
                     version,
                     checksum: checksum.into(),
+                    execution_time: Duration::from_nanos(execution_time as u64),
                 })
                 .collect();
 
@@ -134,12 +147,49 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         })
     }
 
-    fn lock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
-        Box::pin(async move { Ok(()) })
+    fn lock(&mut self, lock: MigrationLock) -> BoxFuture<'_, Result<(), MigrateError>> {
+        Box::pin(async move {
+            // SQLite has no application locks, so the lock is a row of a table, which other
+            // migrators can't insert until it is deleted
+
+            // language=SQLite
+            self.execute(
+                r#"
+CREATE TABLE IF NOT EXISTS _sqlx_migrations_lock (
+    key BIGINT PRIMARY KEY,
+    locked_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+                "#,
+            )
+            .await?;
+
+            let attempts = LockAttempts::new(lock);
+
+            // language=SQLite
+            while query("INSERT OR IGNORE INTO _sqlx_migrations_lock (key) VALUES (?1)")
+                .bind(lock.key.unwrap_or(0))
+                .execute(&mut *self)
+                .await?
+                .rows_affected()
+                == 0
+            {
+                attempts.retry().await?;
+            }
+
+            Ok(())
+        })
     }
 
-    fn unlock(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
-        Box::pin(async move { Ok(()) })
+    fn unlock(&mut self, lock: MigrationLock) -> BoxFuture<'_, Result<(), MigrateError>> {
+        Box::pin(async move {
+            // language=SQLite
+            let _ = query("DELETE FROM _sqlx_migrations_lock WHERE key = ?1")
+                .bind(lock.key.unwrap_or(0))
+                .execute(self)
+                .await?;
+
+            Ok(())
+        })
     }
 
     fn validate<'e: 'm, 'm>(
@@ -168,52 +218,135 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
 
     fn apply<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         Box::pin(async move {
-            let mut tx = self.begin().await?;
             let start = Instant::now();
 
-            let _ = tx.execute(&*migration.sql).await?;
-
-            tx.commit().await?;
+            execute_migration(self, table_name, migration).await?;
 
             let elapsed = start.elapsed();
 
+            // the row recording the progress of a resumable migration already exists
             // language=SQL
-            let _ = query(
-                r#"
-    INSERT INTO _sqlx_migrations ( version, description, success, checksum, execution_time )
+            let sql = if migration.is_resumable() {
+                format!(
+                    r#"
+    UPDATE {} SET description = ?2, success = TRUE, checksum = ?3, execution_time = ?4
+    WHERE version = ?1
+                    "#,
+                    table_name
+                )
+            } else {
+                format!(
+                    r#"
+    INSERT INTO {} ( version, description, success, checksum, execution_time )
     VALUES ( ?1, ?2, TRUE, ?3, ?4 )
+                    "#,
+                    table_name
+                )
+            };
+
+            let _ = query(&sql)
+                .bind(migration.version)
+                .bind(&*migration.description)
+                .bind(&*migration.checksum)
+                .bind(elapsed.as_nanos() as i64)
+                .execute(self)
+                .await?;
+
+            Ok(elapsed)
+        })
+    }
+
+    fn update_checksum<'e: 'm, 'm>(
+        &'e mut self,
+        table_name: &'m str,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        Box::pin(async move {
+            // language=SQL
+            let _ = query(&format!(
+                r#"UPDATE {} SET checksum = ?1 WHERE version = ?2"#,
+                table_name
+            ))
+            .bind(&*migration.checksum)
+            .bind(migration.version)
+            .execute(self)
+            .await?;
+
+            Ok(())
+        })
+    }
+
+    fn record_applied<'e: 'm, 'm>(
+        &'e mut self,
+        table_name: &'m str,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        Box::pin(async move {
+            // language=SQL
+            let _ = query(&format!(
+                r#"
+    INSERT INTO {} ( version, description, success, checksum, execution_time )
+    VALUES ( ?1, ?2, TRUE, ?3, 0 )
                 "#,
-            )
+                table_name
+            ))
             .bind(migration.version)
             .bind(&*migration.description)
             .bind(&*migration.checksum)
-            .bind(elapsed.as_nanos() as i64)
             .execute(self)
             .await?;
 
-            Ok(elapsed)
+            Ok(())
+        })
+    }
+
+    fn dump_schema<'e>(
+        &'e mut self,
+        table_name: &'e str,
+    ) -> BoxFuture<'e, Result<String, MigrateError>> {
+        Box::pin(async move {
+            // the migrations table may be qualified by the name of an attached database
+            let table_name = table_name.rsplit('.').next().unwrap_or(table_name);
+
+            // language=SQLite
+            let statements: Vec<String> = query_scalar(
+                r#"
+SELECT sql FROM sqlite_master
+WHERE sql IS NOT NULL AND name NOT LIKE 'sqlite_%'
+    AND tbl_name NOT IN (?1, '_sqlx_migrations_lock')
+ORDER BY CASE type WHEN 'table' THEN 0 WHEN 'index' THEN 1 WHEN 'view' THEN 2 ELSE 3 END, name
+                "#,
+            )
+            .bind(table_name)
+            .fetch_all(self)
+            .await?;
+
+            Ok(statements
+                .iter()
+                .map(|sql| format!("{};\n", sql))
+                .collect::<Vec<_>>()
+                .join("\n"))
         })
     }
 
     fn revert<'e: 'm, 'm>(
         &'e mut self,
+        table_name: &'m str,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         Box::pin(async move {
-            let mut tx = self.begin().await?;
             let start = Instant::now();
 
-            let _ = tx.execute(&*migration.sql).await?;
-
-            tx.commit().await?;
+            execute_migration(self, table_name, migration).await?;
 
             let elapsed = start.elapsed();
 
             // language=SQL
-            let _ = query(r#"DELETE FROM _sqlx_migrations WHERE version = ?1"#)
+            let _ = query(&format!(r#"DELETE FROM {} WHERE version = ?1"#, table_name))
                 .bind(migration.version)
                 .execute(self)
                 .await?;
@@ -222,3 +355,138 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         })
     }
 }
+
+// run the SQL of the migration as configured by its headers
+async fn execute_migration(
+    conn: &mut SqliteConnection,
+    table_name: &str,
+    migration: &Migration,
+) -> Result<(), MigrateError> {
+    let headers = migration.headers()?;
+    headers.check_database(migration.version, "sqlite")?;
+
+    // `busy_timeout` is in milliseconds, restored after the migration
+    let busy_timeout = match headers.lock_timeout {
+        Some(timeout) => {
+            let previous: i64 = query_scalar("PRAGMA busy_timeout")
+                .fetch_one(&mut *conn)
+                .await?;
+
+            conn.execute(&*format!("PRAGMA busy_timeout = {}", timeout.as_millis()))
+                .await?;
+
+            Some(previous)
+        }
+        None => None,
+    };
+
+    let res = if migration.is_resumable() {
+        execute_statements(&mut *conn, table_name, migration).await
+    } else if headers.no_transaction {
+        execute_body(&mut *conn, migration).await
+    } else {
+        async {
+            let mut tx = conn.begin().await?;
+            execute_body(&mut *tx, migration).await?;
+            Ok(tx.commit().await?)
+        }
+        .await
+    };
+
+    if let Some(previous) = busy_timeout {
+        conn.execute(&*format!("PRAGMA busy_timeout = {}", previous))
+            .await?;
+    }
+
+    res
+}
+
+// run the statements of a resumable migration one at a time, counting those applied in its row
+// of the migrations table, which stays dirty until the migration is recorded as applied
+async fn execute_statements(
+    conn: &mut SqliteConnection,
+    table_name: &str,
+    migration: &Migration,
+) -> Result<(), MigrateError> {
+    // the column isn't created with the table, which may predate it
+    // language=SQLite
+    let has_column = conn
+        .execute(&*format!(
+            "SELECT statements_applied FROM {} LIMIT 0",
+            table_name
+        ))
+        .await
+        .is_ok();
+
+    if !has_column {
+        // language=SQLite
+        conn.execute(&*format!(
+            "ALTER TABLE {} ADD COLUMN statements_applied BIGINT",
+            table_name
+        ))
+        .await?;
+    }
+
+    // language=SQLite
+    let applied: Option<Option<i64>> = query_scalar(&format!(
+        "SELECT statements_applied FROM {} WHERE version = ?1",
+        table_name
+    ))
+    .bind(migration.version)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let applied = match applied {
+        Some(applied) => applied.unwrap_or(0),
+        None => {
+            // language=SQLite
+            let _ = query(&format!(
+                r#"
+    INSERT INTO {} ( version, description, success, checksum, execution_time, statements_applied )
+    VALUES ( ?1, ?2, FALSE, ?3, 0, 0 )
+                "#,
+                table_name
+            ))
+            .bind(migration.version)
+            .bind(&*migration.description)
+            .bind(&*migration.checksum)
+            .execute(&mut *conn)
+            .await?;
+
+            0
+        }
+    };
+
+    let statements = split_statements(&migration.sql);
+
+    for (i, statement) in statements.into_iter().enumerate().skip(applied as usize) {
+        let _ = conn.execute(statement).await?;
+
+        // language=SQLite
+        let _ = query(&format!(
+            "UPDATE {} SET statements_applied = ?1 WHERE version = ?2",
+            table_name
+        ))
+        .bind(i as i64 + 1)
+        .bind(migration.version)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+// run the SQL of the migration, or its function if implemented in Rust
+async fn execute_body(
+    conn: &mut SqliteConnection,
+    migration: &Migration,
+) -> Result<(), MigrateError> {
+    match &migration.code {
+        Some(code) => code.run(migration.version, conn).await,
+        None => {
+            let _ = conn.execute(&*migration.sql).await?;
+
+            Ok(())
+        }
+    }
+}
@@ -7,11 +7,16 @@
 
 pub use arguments::{SqliteArgumentValue, SqliteArguments};
 pub use column::SqliteColumn;
-pub use connection::{LockedSqliteHandle, SqliteConnection};
+pub use connection::{
+    LockedSqliteHandle, SqliteAggregate, SqliteBackup, SqliteBackupDestination,
+    SqliteBackupProgress, SqliteChange, SqliteChangeListener, SqliteCheckpoint, SqliteConnection,
+    SqliteOperation,
+};
 pub use database::Sqlite;
 pub use error::SqliteError;
 pub use options::{
-    SqliteAutoVacuum, SqliteConnectOptions, SqliteJournalMode, SqliteLockingMode, SqliteSynchronous,
+    SqliteAutoVacuum, SqliteCheckpointMode, SqliteConnectOptions, SqliteJournalMode,
+    SqliteLockingMode, SqliteSynchronous,
 };
 pub use query_result::SqliteQueryResult;
 pub use row::SqliteRow;
@@ -57,6 +62,7 @@ impl<'c, T: Executor<'c, Database = Sqlite>> SqliteExecutor<'c> for T {}
 impl_into_arguments_for_arguments!(SqliteArguments<'q>);
 impl_executor_for_pool_connection!(Sqlite, SqliteConnection, SqliteRow);
 impl_executor_for_transaction!(Sqlite, SqliteRow);
+impl_executor_for_reconnecting_connection!(Sqlite, SqliteRow);
 impl_column_index_for_row!(SqliteRow);
 impl_column_index_for_statement!(SqliteStatement);
 impl_acquire!(Sqlite, SqliteConnection);
@@ -81,6 +87,17 @@ pub fn describe_blocking(
     // Execute any ancillary `PRAGMA`s
     connection::execute::iter(&mut conn, &opts.pragma_string(), None, false)?.finish()?;
 
+    for collation in &opts.collations {
+        collation.create(&mut conn.handle)?;
+    }
+
+    for function in &opts.functions {
+        function.create(&mut conn.handle)?;
+    }
+
+    // the application may define collations used by the schema on its own connections
+    connection::collation::stand_in_collations(&mut conn.handle)?;
+
     connection::describe::describe(&mut conn, query)
 
     // SQLite database is closed immediately when `conn` is dropped
@@ -0,0 +1,49 @@
+use crate::error::Error;
+use std::str::FromStr;
+
+/// Refer to [SQLite documentation] for the meaning of the WAL checkpoint modes.
+///
+/// [SQLite documentation]: https://www.sqlite.org/pragma.html#pragma_wal_checkpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqliteCheckpointMode {
+    Passive,
+    Full,
+    Restart,
+    Truncate,
+}
+
+impl SqliteCheckpointMode {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            SqliteCheckpointMode::Passive => "PASSIVE",
+            SqliteCheckpointMode::Full => "FULL",
+            SqliteCheckpointMode::Restart => "RESTART",
+            SqliteCheckpointMode::Truncate => "TRUNCATE",
+        }
+    }
+}
+
+impl Default for SqliteCheckpointMode {
+    fn default() -> Self {
+        SqliteCheckpointMode::Passive
+    }
+}
+
+impl FromStr for SqliteCheckpointMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match &*s.to_ascii_lowercase() {
+            "passive" => SqliteCheckpointMode::Passive,
+            "full" => SqliteCheckpointMode::Full,
+            "restart" => SqliteCheckpointMode::Restart,
+            "truncate" => SqliteCheckpointMode::Truncate,
+
+            _ => {
+                return Err(Error::Configuration(
+                    format!("unknown value {:?} for `wal_checkpoint`", s).into(),
+                ));
+            }
+        })
+    }
+}
@@ -1,10 +1,12 @@
 use crate::connection::ConnectOptions;
 use crate::error::Error;
 use crate::executor::Executor;
+use crate::query_log::QueryLog;
 use crate::sqlite::{SqliteConnectOptions, SqliteConnection};
 use futures_core::future::BoxFuture;
 use log::LevelFilter;
 use std::fmt::Write;
+use std::sync::Arc;
 use std::time::Duration;
 
 impl ConnectOptions for SqliteConnectOptions {
@@ -20,12 +22,16 @@ impl ConnectOptions for SqliteConnectOptions {
             // Execute PRAGMAs
             conn.execute(&*self.pragma_string()).await?;
 
-            if !self.collations.is_empty() {
+            if !self.collations.is_empty() || !self.functions.is_empty() {
                 let mut locked = conn.lock_handle().await?;
 
                 for collation in &self.collations {
                     collation.create(&mut locked.guard.handle)?;
                 }
+
+                for function in &self.functions {
+                    function.create(&mut locked.guard.handle)?;
+                }
             }
 
             Ok(conn)
@@ -41,6 +47,11 @@ impl ConnectOptions for SqliteConnectOptions {
         self.log_settings.log_slow_statements(level, duration);
         self
     }
+
+    fn query_log(&mut self, log: Arc<dyn QueryLog>) -> &mut Self {
+        self.log_settings.query_log(log);
+        self
+    }
 }
 
 impl SqliteConnectOptions {
@@ -1,6 +1,7 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 mod auto_vacuum;
+mod checkpoint_mode;
 mod connect;
 mod journal_mode;
 mod locking_mode;
@@ -9,15 +10,24 @@ mod synchronous;
 
 use crate::connection::LogSettings;
 pub use auto_vacuum::SqliteAutoVacuum;
+pub use checkpoint_mode::SqliteCheckpointMode;
 pub use journal_mode::SqliteJournalMode;
 pub use locking_mode::SqliteLockingMode;
 use std::cmp::Ordering;
+use std::sync::atomic::{self, AtomicUsize};
 use std::sync::Arc;
 use std::{borrow::Cow, time::Duration};
 pub use synchronous::SqliteSynchronous;
 
 use crate::common::DebugFn;
+use crate::encode::Encode;
+use crate::error::BoxDynError;
+use crate::sqlite::connection::busy_handler::BusyHandlerFn;
 use crate::sqlite::connection::collation::Collation;
+use crate::sqlite::connection::establish::InMemoryAnchor;
+use crate::sqlite::connection::function::Function;
+use crate::sqlite::{Sqlite, SqliteAggregate, SqliteChange, SqliteChangeListener, SqliteValue};
+use futures_channel::mpsc::UnboundedSender;
 use indexmap::IndexMap;
 
 /// Options and flags which can be used to configure a SQLite connection.
@@ -59,8 +69,10 @@ pub struct SqliteConnectOptions {
     pub(crate) read_only: bool,
     pub(crate) create_if_missing: bool,
     pub(crate) shared_cache: bool,
+    pub(crate) in_memory_anchor: Arc<InMemoryAnchor>,
     pub(crate) statement_cache_capacity: usize,
     pub(crate) busy_timeout: Duration,
+    pub(crate) busy_handler: Option<Arc<BusyHandlerFn>>,
     pub(crate) log_settings: LogSettings,
     pub(crate) immutable: bool,
 
@@ -70,11 +82,24 @@ pub struct SqliteConnectOptions {
     pub(crate) row_channel_size: usize,
 
     pub(crate) collations: Vec<Collation>,
+    pub(crate) functions: Vec<Function>,
+
+    // extensions to load, with their entry point
+    pub(crate) extensions: IndexMap<Cow<'static, str>, Option<Cow<'static, str>>>,
+
+    pub(crate) change_listener: Option<UnboundedSender<SqliteChange>>,
 
     pub(crate) serialized: bool,
     pub(crate) thread_name: Arc<DebugFn<dyn Fn(u64) -> String + Send + Sync + 'static>>,
 }
 
+static IN_MEMORY_DB_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+pub(crate) fn unique_in_memory_name() -> String {
+    let seqno = IN_MEMORY_DB_SEQ.fetch_add(1, atomic::Ordering::Relaxed);
+    format!("sqlx-in-memory-{}", seqno)
+}
+
 impl Default for SqliteConnectOptions {
     fn default() -> Self {
         Self::new()
@@ -125,18 +150,27 @@ impl SqliteConnectOptions {
 
         pragmas.insert("auto_vacuum".into(), None);
 
+        // Both only apply in WAL mode, and default to 1000 pages and no limit respectively.
+        pragmas.insert("wal_autocheckpoint".into(), None);
+        pragmas.insert("journal_size_limit".into(), None);
+
         Self {
             filename: Cow::Borrowed(Path::new(":memory:")),
             in_memory: false,
             read_only: false,
             create_if_missing: false,
             shared_cache: false,
+            in_memory_anchor: Default::default(),
             statement_cache_capacity: 100,
             busy_timeout: Duration::from_secs(5),
+            busy_handler: None,
             log_settings: Default::default(),
             immutable: false,
             pragmas,
             collations: Default::default(),
+            functions: Default::default(),
+            extensions: Default::default(),
+            change_listener: None,
             serialized: false,
             thread_name: Arc::new(DebugFn(|id| format!("sqlx-sqlite-worker-{}", id))),
             command_channel_size: 50,
@@ -145,11 +179,48 @@ impl SqliteConnectOptions {
     }
 
     /// Sets the name of the database file.
+    ///
+    /// `:memory:` opens a new in-memory database with a unique name, shared by the connections
+    /// opened with these options (and their clones); see [`shared_memory()`][Self::shared_memory].
     pub fn filename(mut self, filename: impl AsRef<Path>) -> Self {
+        if filename.as_ref() == Path::new(":memory:") {
+            return self.shared_memory(unique_in_memory_name());
+        }
+
         self.filename = Cow::Owned(filename.as_ref().to_owned());
         self
     }
 
+    /// Opens the named in-memory database, shared by every connection of the process which opens
+    /// it by the same name, in place of a database file.
+    ///
+    /// This is what `sqlite::memory:` means in a URL, with a unique name, so the connections of
+    /// a pool all see the same database.
+    ///
+    /// An in-memory database is normally deleted when its last connection closes; instead, the
+    /// options (and their clones, e.g. held by a pool) keep a connection to it once they have
+    /// opened one, so it lasts until they are dropped.
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::error::Error;
+    /// # use sqlx_core::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    /// # async fn pool() -> Result<(), Error> {
+    /// let pool = SqlitePoolOptions::new()
+    ///     .min_connections(4)
+    ///     .connect_with(SqliteConnectOptions::new().shared_memory("cache"))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn shared_memory(mut self, name: impl AsRef<str>) -> Self {
+        self.filename = Cow::Owned(PathBuf::from(format!("file:{}", name.as_ref())));
+        self.in_memory = true;
+        self.shared_cache = true;
+        // a different database from the one the options might have opened before
+        self.in_memory_anchor = Default::default();
+        self
+    }
+
     /// Set the enforcement of [foreign key constraints](https://www.sqlite.org/pragma.html#pragma_foreign_keys).
     ///
     /// SQLx chooses to enable this by default so that foreign keys function as expected,
@@ -229,11 +300,44 @@ impl SqliteConnectOptions {
     /// returning a busy timeout error.
     ///
     /// The default busy timeout is 5 seconds.
+    ///
+    /// Has no effect if a [`busy_handler()`][Self::busy_handler] is set.
     pub fn busy_timeout(mut self, timeout: Duration) -> Self {
         self.busy_timeout = timeout;
         self
     }
 
+    /// Sets a [busy handler](https://www.sqlite.org/c3ref/busy_handler.html), called when the
+    /// database is locked, in place of the [`busy_timeout()`][Self::busy_timeout].
+    ///
+    /// The handler is called with the number of times it was already called for the same lock,
+    /// and returns `true` to try again, or `false` to give up with a busy error. It runs on the
+    /// worker thread of the connection, which it blocks, so it should sleep between attempts.
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::sqlite::SqliteConnectOptions;
+    /// use std::time::Duration;
+    ///
+    /// // retry 10 times, backing off exponentially
+    /// let options = SqliteConnectOptions::new()
+    ///     .filename("data.db")
+    ///     .busy_handler(|count| {
+    ///         if count >= 10 {
+    ///             return false;
+    ///         }
+    ///
+    ///         std::thread::sleep(Duration::from_millis(1 << count));
+    ///         true
+    ///     });
+    /// ```
+    pub fn busy_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(u32) -> bool + Send + Sync + 'static,
+    {
+        self.busy_handler = Some(Arc::new(DebugFn(handler)));
+        self
+    }
+
     /// Sets the [synchronous](https://www.sqlite.org/pragma.html#pragma_synchronous) setting for the database connection.
     ///
     /// The default synchronous settings is FULL. However, if durability is not a concern,
@@ -263,6 +367,29 @@ impl SqliteConnectOptions {
         self.pragma("page_size", page_size.to_string())
     }
 
+    /// Sets the [wal_autocheckpoint](https://www.sqlite.org/pragma.html#pragma_wal_autocheckpoint)
+    /// setting for the database connection.
+    ///
+    /// In WAL mode, a passive checkpoint is run on commit when the WAL has at least this many
+    /// pages; `0` disables automatic checkpoints. The default is 1000 pages.
+    ///
+    /// A passive checkpoint doesn't wait for readers, so under constant load it may never reach
+    /// the end of the WAL. See
+    /// [`SqliteConnection::wal_checkpoint()`][crate::sqlite::SqliteConnection::wal_checkpoint]
+    /// to run checkpoints which do.
+    pub fn wal_autocheckpoint(self, pages: u32) -> Self {
+        self.pragma("wal_autocheckpoint", pages.to_string())
+    }
+
+    /// Sets the [journal_size_limit](https://www.sqlite.org/pragma.html#pragma_journal_size_limit)
+    /// setting for the database connection, in bytes.
+    ///
+    /// In WAL mode, the WAL file is truncated to this size when it is reset after a checkpoint,
+    /// instead of being left at the largest size it reached. By default there is no limit.
+    pub fn journal_size_limit(self, bytes: u64) -> Self {
+        self.pragma("journal_size_limit", bytes.to_string())
+    }
+
     /// Sets custom initial pragma for the database connection.
     pub fn pragma<K, V>(mut self, key: K, value: V) -> Self
     where
@@ -298,6 +425,100 @@ impl SqliteConnectOptions {
         self
     }
 
+    /// Add a scalar SQL function implemented in Rust.
+    ///
+    /// The function takes `num_args` arguments, or any number of them if `-1`. If a function
+    /// with the same name and number of arguments already exists, it will be replaced.
+    /// An error returned by the function fails the query with its message.
+    ///
+    /// Functions are registered as non-deterministic, so they can't be used in indexes,
+    /// `CHECK` constraints or generated columns.
+    ///
+    /// See [`sqlite3_create_function()`](https://www.sqlite.org/c3ref/create_function.html)
+    /// for details.
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::sqlite::SqliteConnectOptions;
+    /// use sqlx::Value;
+    ///
+    /// // `x REGEXP y` calls `regexp(y, x)`; this one only matches prefixes
+    /// let options = SqliteConnectOptions::new().scalar_function("regexp", 2, |args| {
+    ///     let prefix = args[0].try_decode::<String>()?;
+    ///     let text = args[1].try_decode::<String>()?;
+    ///
+    ///     Ok(text.starts_with(&prefix))
+    /// });
+    /// ```
+    pub fn scalar_function<N, F, R>(mut self, name: N, num_args: i32, f: F) -> Self
+    where
+        N: Into<Arc<str>>,
+        F: Fn(&[SqliteValue]) -> Result<R, BoxDynError> + Send + Sync + 'static,
+        R: Encode<'static, Sqlite>,
+    {
+        self.functions.push(Function::scalar(name, num_args, f));
+        self
+    }
+
+    /// Add an aggregate SQL function implemented in Rust.
+    ///
+    /// `init` creates the state of the aggregate for each group of rows. See
+    /// [`SqliteAggregate`] for an example, and [`scalar_function()`][Self::scalar_function]
+    /// for details.
+    pub fn aggregate_function<N, F, A>(mut self, name: N, num_args: i32, init: F) -> Self
+    where
+        N: Into<Arc<str>>,
+        F: Fn() -> A + Send + Sync + 'static,
+        A: SqliteAggregate,
+    {
+        self.functions
+            .push(Function::aggregate(name, num_args, init));
+        self
+    }
+
+    /// Load an [extension](https://www.sqlite.org/loadext.html) when the connection is
+    /// established, using its default entry point.
+    ///
+    /// The extension is found as described by
+    /// [`sqlite3_load_extension()`](https://www.sqlite.org/c3ref/load_extension.html): as given,
+    /// or with the shared library suffix of the platform (e.g. `mod_spatialite` loads
+    /// `mod_spatialite.so` on Linux) from the library search path.
+    ///
+    /// Extensions are loaded in the order they are added, on every connection. Loading
+    /// extensions is enabled only while they are loaded, so SQL can't call `load_extension()`.
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::sqlite::SqliteConnectOptions;
+    /// let options = SqliteConnectOptions::new()
+    ///     .filename("data.db")
+    ///     .extension("mod_spatialite");
+    /// ```
+    pub fn extension(mut self, extension_name: impl Into<Cow<'static, str>>) -> Self {
+        self.extensions.insert(extension_name.into(), None);
+        self
+    }
+
+    /// Load an [extension](https://www.sqlite.org/loadext.html) when the connection is
+    /// established, using the given entry point.
+    ///
+    /// See [`extension()`][Self::extension] for details.
+    pub fn extension_with_entrypoint(
+        mut self,
+        extension_name: impl Into<Cow<'static, str>>,
+        entry_point: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.extensions
+            .insert(extension_name.into(), Some(entry_point.into()));
+        self
+    }
+
+    /// Send the changes to rows committed by every connection to the given listener.
+    ///
+    /// See [`SqliteChangeListener`] for the changes which are reported.
+    pub fn change_listener(mut self, listener: &SqliteChangeListener) -> Self {
+        self.change_listener = Some(listener.sender());
+        self
+    }
+
     /// Set to `true` to signal to SQLite that the database file is on read-only media.
     ///
     /// If enabled, SQLite assumes the database file _cannot_ be modified, even by higher
@@ -1,15 +1,13 @@
 use crate::error::Error;
+use crate::sqlite::options::unique_in_memory_name;
 use crate::sqlite::SqliteConnectOptions;
 use percent_encoding::percent_decode_str;
 use std::borrow::Cow;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::sync::atomic::{AtomicUsize, Ordering};
 
 // https://www.sqlite.org/uri.html
 
-static IN_MEMORY_DB_SEQ: AtomicUsize = AtomicUsize::new(0);
-
 impl FromStr for SqliteConnectOptions {
     type Err = Error;
 
@@ -26,10 +24,7 @@ impl FromStr for SqliteConnectOptions {
         let database = database_and_params.next().unwrap_or_default();
 
         if database == ":memory:" {
-            options.in_memory = true;
-            options.shared_cache = true;
-            let seqno = IN_MEMORY_DB_SEQ.fetch_add(1, Ordering::Relaxed);
-            options.filename = Cow::Owned(PathBuf::from(format!("file:sqlx-in-memory-{}", seqno)));
+            options = options.shared_memory(unique_in_memory_name());
         } else {
             // % decode to allow for `?` or `#` in the filename
             options.filename = Cow::Owned(
@@ -121,6 +116,12 @@ impl FromStr for SqliteConnectOptions {
             }
         }
 
+        // an unnamed in-memory database would be private to each connection
+        if options.in_memory && options.filename.as_os_str().is_empty() {
+            options.filename =
+                Cow::Owned(PathBuf::from(format!("file:{}", unique_in_memory_name())));
+        }
+
         Ok(options)
     }
 }
@@ -143,6 +144,24 @@ fn test_parse_in_memory() -> Result<(), Error> {
     assert!(options.in_memory);
     assert!(!options.shared_cache);
 
+    // each unnamed in-memory database gets a name of its own
+    let a: SqliteConnectOptions = "sqlite::memory:".parse()?;
+    let b: SqliteConnectOptions = "sqlite://?mode=memory".parse()?;
+    assert!(a
+        .filename
+        .to_string_lossy()
+        .starts_with("file:sqlx-in-memory-"));
+    assert!(b
+        .filename
+        .to_string_lossy()
+        .starts_with("file:sqlx-in-memory-"));
+    assert_ne!(a.filename, b.filename);
+
+    let options: SqliteConnectOptions = "sqlite:file:cache?mode=memory&cache=shared".parse()?;
+    assert!(options.in_memory);
+    assert!(options.shared_cache);
+    assert_eq!(&*options.filename.to_string_lossy(), "file:cache");
+
     Ok(())
 }
 
@@ -79,13 +79,88 @@ impl ColumnIndex<SqliteRow> for &'_ str {
     }
 }
 
+#[cfg(feature = "any")]
+impl SqliteRow {
+    // the converted columns are shared with the other rows of the result set
+    pub(crate) fn into_any(
+        self,
+        columns: &mut crate::any::row::AnyColumnsCache,
+    ) -> crate::any::AnyRow {
+        crate::any::AnyRow {
+            columns: columns.get(&self.columns, |columns| {
+                columns.iter().map(|col| col.clone().into()).collect()
+            }),
+            kind: crate::any::row::AnyRowKind::Sqlite(self),
+        }
+    }
+}
+
 #[cfg(feature = "any")]
 impl From<SqliteRow> for crate::any::AnyRow {
     #[inline]
     fn from(row: SqliteRow) -> Self {
-        crate::any::AnyRow {
-            columns: row.columns.iter().map(|col| col.clone().into()).collect(),
-            kind: crate::any::row::AnyRowKind::Sqlite(row),
+        row.into_any(&mut Default::default())
+    }
+}
+
+/// Serializes the row as a map of its values by the names of their columns, e.g. as a JSON
+/// object.
+///
+/// The values are serialized by their storage class: integers, reals, text and blobs as
+/// themselves, except the integers of a column declared `BOOLEAN`, which are serialized as
+/// booleans.
+#[cfg(feature = "json")]
+impl serde::Serialize for SqliteRow {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        crate::row::serialize_row(self, serializer, to_json)
+    }
+}
+
+#[cfg(feature = "json")]
+fn to_json(
+    column: &SqliteColumn,
+    value: SqliteValueRef<'_>,
+) -> Result<serde_json::Value, crate::error::BoxDynError> {
+    use crate::decode::Decode;
+    use crate::sqlite::type_info::DataType;
+    use crate::value::ValueRef;
+
+    macro_rules! decode {
+        ($T:ty) => {
+            <$T as Decode<'_, Sqlite>>::decode(value)?
+        };
+    }
+
+    let json = match value.type_info().0 {
+        DataType::Int | DataType::Int64 if column.type_info.0 == DataType::Bool => {
+            decode!(bool).into()
+        }
+        DataType::Int | DataType::Int64 => decode!(i64).into(),
+        DataType::Float => decode!(f64).into(),
+        DataType::Blob => decode!(&[u8]).into(),
+        _ => decode!(&str).into(),
+    };
+
+    Ok(json)
+}
+
+/// The type of a column is its declared type, e.g. `INTEGER` for `Int64`, and `String` for an
+/// expression.
+#[cfg(feature = "parquet")]
+impl crate::export::parquet::ParquetDatabase for Sqlite {
+    fn parquet_type(ty: &crate::sqlite::SqliteTypeInfo) -> crate::export::parquet::ParquetType {
+        use crate::export::parquet::ParquetType;
+        use crate::sqlite::type_info::DataType;
+
+        match ty.0 {
+            DataType::Bool => ParquetType::Boolean,
+            DataType::Int | DataType::Int64 => ParquetType::Int64,
+            DataType::Float => ParquetType::Double,
+            DataType::Blob => ParquetType::Bytes,
+            _ => ParquetType::String,
         }
     }
 }
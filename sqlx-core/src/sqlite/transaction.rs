@@ -2,7 +2,7 @@ use futures_core::future::BoxFuture;
 
 use crate::error::Error;
 use crate::sqlite::{Sqlite, SqliteConnection};
-use crate::transaction::TransactionManager;
+use crate::transaction::{TransactionManager, TransactionOptions};
 
 /// Implementation of [`TransactionManager`] for SQLite.
 pub struct SqliteTransactionManager;
@@ -14,6 +14,26 @@ impl TransactionManager for SqliteTransactionManager {
         Box::pin(conn.worker.begin())
     }
 
+    fn begin_with(
+        conn: &mut SqliteConnection,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            // every transaction is serializable, and read-only connections are opened as such
+            if options.read_only == Some(true) || options.deferrable == Some(true) {
+                return Err(Error::Configuration(
+                    "SQLite does not support read-only or deferrable transactions".into(),
+                ));
+            }
+
+            if options.read_only == Some(false) {
+                conn.worker.begin_immediate().await
+            } else {
+                conn.worker.begin().await
+            }
+        })
+    }
+
     fn commit(conn: &mut SqliteConnection) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(conn.worker.commit())
     }
@@ -25,4 +45,8 @@ impl TransactionManager for SqliteTransactionManager {
     fn start_rollback(conn: &mut SqliteConnection) {
         conn.worker.start_rollback().ok();
     }
+
+    fn start_statement(conn: &mut SqliteConnection, sql: &str) {
+        conn.worker.start_statement(sql).ok();
+    }
 }
@@ -1,12 +1,17 @@
 use std::borrow::Cow;
 use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
 use std::ops::{Deref, DerefMut};
+use std::time::{Duration, Instant};
 
 use futures_core::future::BoxFuture;
 
+use crate::connection::Connection;
 use crate::database::Database;
 use crate::error::Error;
-use crate::pool::MaybePoolConnection;
+use crate::executor::Executor;
+use crate::observer::{self, TransactionEvent, TransactionEventKind};
+use crate::pool::{MaybePoolConnection, WatchedTransaction};
 
 /// Generic management of database transactions.
 ///
@@ -20,6 +25,31 @@ pub trait TransactionManager {
         conn: &mut <Self::Database as Database>::Connection,
     ) -> BoxFuture<'_, Result<(), Error>>;
 
+    /// Begin a new transaction with `options`, outside of a transaction, and with options other
+    /// than the default ones.
+    ///
+    /// Fails for the databases which don't support them, other than the
+    /// [timeout](TransactionOptions::timeout), which is enforced by the [`Transaction`].
+    fn begin_with(
+        conn: &mut <Self::Database as Database>::Connection,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        let options = TransactionOptions {
+            timeout: None,
+            ..options
+        };
+
+        if options == TransactionOptions::default() {
+            return Self::begin(conn);
+        }
+
+        Box::pin(async move {
+            Err(Error::Configuration(
+                "transaction options are not supported by this database".into(),
+            ))
+        })
+    }
+
     /// Commit the active transaction or release the most recent savepoint.
     fn commit(
         conn: &mut <Self::Database as Database>::Connection,
@@ -32,6 +62,31 @@ pub trait TransactionManager {
 
     /// Starts to abort the active transaction or restore from the most recent snapshot.
     fn start_rollback(conn: &mut <Self::Database as Database>::Connection);
+
+    /// Starts to execute `sql`, a statement managing the active transaction, e.g. rolling back
+    /// to a savepoint which was dropped.
+    fn start_statement(conn: &mut <Self::Database as Database>::Connection, sql: &str);
+
+    /// The statement creating the named savepoint `name`, rolling back to it, or releasing it;
+    /// `None` if the database doesn't release its savepoints.
+    fn savepoint_sql(
+        conn: &<Self::Database as Database>::Connection,
+        statement: SavepointStatement,
+        name: &str,
+    ) -> Option<String> {
+        let _ = conn;
+
+        Some(savepoint_ansi_sql(statement, name))
+    }
+}
+
+/// A statement of a named [`Savepoint`], for [`TransactionManager::savepoint_sql()`].
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SavepointStatement {
+    Create,
+    RollbackTo,
+    Release,
 }
 
 /// An in-progress database transaction or savepoint.
@@ -46,52 +101,343 @@ pub trait TransactionManager {
 /// executed after it was established to be rolled back, restoring the transaction state to
 /// what it was at the time of the savepoint.
 ///
+/// Closures registered with [`on_commit`] run once the transaction is committed, and those
+/// registered with [`on_rollback`] once it is rolled back or dropped, so that side effects,
+/// e.g. publishing events, happen only once the changes are durable, or discarded.
+///
 /// [`Connection::begin`]: crate::connection::Connection::begin()
 /// [`Pool::begin`]: crate::pool::Pool::begin()
 /// [`commit`]: Self::commit()
 /// [`rollback`]: Self::rollback()
+/// [`on_commit`]: Self::on_commit()
+/// [`on_rollback`]: Self::on_rollback()
 pub struct Transaction<'c, DB>
 where
     DB: Database,
 {
     connection: MaybePoolConnection<'c, DB>,
     open: bool,
+
+    callbacks: Callbacks,
+    // the callbacks of the transaction a savepoint was established in
+    parent: Option<&'c mut Callbacks>,
+
+    // set by `TransactionOptions::timeout`
+    deadline: Option<Instant>,
+    timed_out: bool,
+
+    // set by `PoolOptions::long_transaction_threshold`
+    watched: Option<WatchedTransaction>,
+
+    // for the observer and the metrics
+    started: Instant,
+    depth: usize,
+    statements: u64,
 }
 
 impl<'c, DB> Transaction<'c, DB>
 where
     DB: Database,
 {
-    pub(crate) fn begin(
+    pub(crate) fn start(
+        conn: impl Into<MaybePoolConnection<'c, DB>>,
+    ) -> BoxFuture<'c, Result<Self, Error>> {
+        Self::start_with(conn, TransactionOptions::default())
+    }
+
+    pub(crate) fn start_with(
         conn: impl Into<MaybePoolConnection<'c, DB>>,
+        options: TransactionOptions,
     ) -> BoxFuture<'c, Result<Self, Error>> {
         let mut conn = conn.into();
 
         Box::pin(async move {
-            DB::TransactionManager::begin(&mut conn).await?;
+            let started = Instant::now();
+
+            if options == TransactionOptions::default() {
+                DB::TransactionManager::begin(&mut conn).await?;
+            } else if conn.transaction_depth() > 0 {
+                return Err(Error::Configuration(
+                    "transaction options cannot be set for a savepoint".into(),
+                ));
+            } else {
+                DB::TransactionManager::begin_with(&mut conn, options).await?;
+            }
 
-            Ok(Self {
+            let watched = match &conn {
+                MaybePoolConnection::PoolConnection(conn) => conn.watch_transaction(),
+                MaybePoolConnection::Connection(_) => None,
+            };
+
+            let transaction = Self {
+                depth: conn.transaction_depth(),
+                statements: conn.statement_count(),
                 connection: conn,
                 open: true,
-            })
+                callbacks: Callbacks::default(),
+                parent: None,
+                deadline: options.timeout.map(|timeout| started + timeout),
+                timed_out: false,
+                watched,
+                started,
+            };
+
+            transaction.observe(TransactionEventKind::Begin, started.elapsed(), 0);
+
+            Ok(transaction)
         })
     }
 
+    /// Establishes a savepoint within this transaction, as [`Connection::begin`] does, whose
+    /// [`on_commit`] and [`on_rollback`] callbacks are handed to this transaction once it is
+    /// committed, and which times out with it.
+    ///
+    /// [`Connection::begin`]: crate::connection::Connection::begin()
+    /// [`on_commit`]: Self::on_commit()
+    /// [`on_rollback`]: Self::on_rollback()
+    pub fn begin(&mut self) -> BoxFuture<'_, Result<Transaction<'_, DB>, Error>> {
+        let Self {
+            connection,
+            callbacks,
+            deadline,
+            ..
+        } = self;
+
+        Box::pin(async move {
+            let mut savepoint =
+                Transaction::start(MaybePoolConnection::Connection(&mut **connection)).await?;
+            savepoint.parent = Some(callbacks);
+            savepoint.deadline = *deadline;
+
+            Ok(savepoint)
+        })
+    }
+
+    /// Registers `f` to run once this transaction is committed.
+    ///
+    /// The callbacks of a savepoint run once the transaction it was established in is committed,
+    /// and are discarded if the savepoint is rolled back.
+    pub fn on_commit(&mut self, f: impl FnOnce() + Send + 'static) {
+        self.callbacks.on_commit.push(Box::new(f));
+    }
+
+    /// Registers `f` to run once this transaction is rolled back, or dropped without being
+    /// committed.
+    ///
+    /// The callbacks of a savepoint run once it is rolled back, or once the transaction it was
+    /// established in is.
+    pub fn on_rollback(&mut self, f: impl FnOnce() + Send + 'static) {
+        self.callbacks.on_rollback.push(Box::new(f));
+    }
+
     /// Commits this transaction or savepoint.
+    ///
+    /// Fails with [`Error::TransactionTimedOut`] once the transaction has timed out, and was
+    /// rolled back.
     pub async fn commit(mut self) -> Result<(), Error> {
+        self.before_statement()?;
+
+        let statements = self.statements();
+
         DB::TransactionManager::commit(&mut self.connection).await?;
         self.open = false;
 
+        let callbacks = std::mem::take(&mut self.callbacks);
+
+        match self.parent.as_deref_mut() {
+            Some(parent) => parent.append(callbacks),
+            None => callbacks.commit(),
+        }
+
+        self.observe(
+            TransactionEventKind::Commit,
+            self.started.elapsed(),
+            statements,
+        );
+
         Ok(())
     }
 
     /// Aborts this transaction or savepoint.
     pub async fn rollback(mut self) -> Result<(), Error> {
+        if self.timed_out {
+            // already rolled back
+            return Ok(());
+        }
+
+        let statements = self.statements();
+
         DB::TransactionManager::rollback(&mut self.connection).await?;
         self.open = false;
 
+        std::mem::take(&mut self.callbacks).rollback();
+
+        self.observe(
+            TransactionEventKind::Rollback,
+            self.started.elapsed(),
+            statements,
+        );
+
         Ok(())
     }
+
+    /// Creates the savepoint `name` in this transaction, which can be rolled back to any number
+    /// of times, and then released, unlike a nested transaction.
+    ///
+    /// The name is an identifier of letters, digits and underscores. A savepoint which is
+    /// dropped without being released is rolled back to, and released.
+    ///
+    /// ```rust,ignore
+    /// let mut savepoint = tx.savepoint("before_import").await?;
+    ///
+    /// for row in rows {
+    ///     if let Err(e) = insert(&mut *savepoint, row).await {
+    ///         // discard the rows inserted so far, and go on without them
+    ///         savepoint.rollback_to().await?;
+    ///         break;
+    ///     }
+    /// }
+    ///
+    /// savepoint.release().await?;
+    /// ```
+    pub async fn savepoint(&mut self, name: &str) -> Result<Savepoint<'_, DB>, Error>
+    where
+        for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+    {
+        let is_identifier = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+        if !is_identifier {
+            return Err(Error::Configuration(
+                format!("invalid savepoint name {:?}", name).into(),
+            ));
+        }
+
+        let connection = &mut **self;
+
+        if let Some(sql) =
+            DB::TransactionManager::savepoint_sql(connection, SavepointStatement::Create, name)
+        {
+            connection.execute(&*sql).await?;
+        }
+
+        Ok(Savepoint {
+            connection,
+            name: name.to_owned(),
+            active: true,
+        })
+    }
+
+    // called before each statement executed on the transaction, returns the deadline of the
+    // statement, and fails once the transaction has timed out
+    pub(crate) fn before_statement(&mut self) -> Result<Option<Instant>, Error> {
+        if self.timed_out {
+            return Err(Error::TransactionTimedOut);
+        }
+
+        if let Some(watched) = &self.watched {
+            watched.set_statements(self.statements() + 1);
+        }
+
+        match self.deadline {
+            Some(deadline) if Instant::now() >= deadline => Err(self.time_out()),
+            deadline => Ok(deadline),
+        }
+    }
+
+    // rolls back the transaction once its deadline is passed
+    pub(crate) fn time_out(&mut self) -> Error {
+        if self.open {
+            let statements = self.statements();
+
+            DB::TransactionManager::start_rollback(&mut self.connection);
+            self.open = false;
+            self.timed_out = true;
+
+            self.observe(
+                TransactionEventKind::Rollback,
+                self.started.elapsed(),
+                statements,
+            );
+
+            std::mem::take(&mut self.callbacks).rollback();
+        }
+
+        Error::TransactionTimedOut
+    }
+
+    // ends the transaction without committing or rolling it back, e.g. once it's prepared for a
+    // two-phase commit, and discards its callbacks
+    #[cfg(feature = "postgres")]
+    pub(crate) fn detach(mut self) {
+        self.open = false;
+    }
+
+    // the statements executed since the transaction began
+    fn statements(&self) -> u64 {
+        self.connection
+            .statement_count()
+            .saturating_sub(self.statements)
+    }
+
+    fn observe(&self, kind: TransactionEventKind, elapsed: Duration, statements: u64) {
+        // savepoints are accounted for by their transaction
+        #[cfg(feature = "metrics")]
+        if self.depth <= 1 {
+            crate::metrics::record_transaction(kind, elapsed, statements);
+        }
+
+        if observer::is_transaction_observed() {
+            let tags = observer::current_tags();
+
+            observer::notify_transaction(&TransactionEvent {
+                kind,
+                depth: self.depth,
+                elapsed,
+                statements,
+                tags: tags.as_deref().unwrap_or_default(),
+            });
+        }
+    }
+}
+
+// waits for `f` until `deadline`, if any
+pub(crate) async fn until<F: Future>(deadline: Option<Instant>, f: F) -> Option<F::Output> {
+    match deadline {
+        Some(deadline) => {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+
+            crate::runtime::timeout(timeout, f).await.ok()
+        }
+        None => Some(f.await),
+    }
+}
+
+type Callback = Box<dyn FnOnce() + Send + 'static>;
+
+#[derive(Default)]
+struct Callbacks {
+    on_commit: Vec<Callback>,
+    on_rollback: Vec<Callback>,
+}
+
+impl Callbacks {
+    fn append(&mut self, mut other: Callbacks) {
+        self.on_commit.append(&mut other.on_commit);
+        self.on_rollback.append(&mut other.on_rollback);
+    }
+
+    fn commit(self) {
+        for f in self.on_commit {
+            f();
+        }
+    }
+
+    fn rollback(self) {
+        for f in self.on_rollback {
+            f();
+        }
+    }
 }
 
 // NOTE: required due to lack of lazy normalization
@@ -103,6 +449,26 @@ macro_rules! impl_executor_for_transaction {
         {
             type Database = $DB;
 
+            fn execute<'e, 'q: 'e, E: 'q>(
+                self,
+                query: E,
+            ) -> futures_core::future::BoxFuture<
+                'e,
+                Result<<$DB as crate::database::Database>::QueryResult, crate::error::Error>,
+            >
+            where
+                't: 'e,
+                E: crate::executor::Execute<'q, Self::Database>,
+            {
+                Box::pin(async move {
+                    let deadline = self.before_statement()?;
+
+                    crate::transaction::until(deadline, (&mut **self).execute(query))
+                        .await
+                        .unwrap_or_else(|| Err(self.time_out()))
+                })
+            }
+
             fn fetch_many<'e, 'q: 'e, E: 'q>(
                 self,
                 query: E,
@@ -117,7 +483,77 @@ macro_rules! impl_executor_for_transaction {
                 't: 'e,
                 E: crate::executor::Execute<'q, Self::Database>,
             {
-                (&mut **self).fetch_many(query)
+                Box::pin(try_stream! {
+                    let deadline = self.before_statement()?;
+                    let mut s = (&mut **self).fetch_many(query);
+
+                    loop {
+                        match crate::transaction::until(deadline, futures_util::TryStreamExt::try_next(&mut s)).await {
+                            Some(Ok(Some(v))) => r#yield!(v),
+                            Some(Ok(None)) => break,
+                            Some(Err(e)) => return Err(e),
+                            None => {
+                                drop(s);
+                                return Err(self.time_out());
+                            }
+                        }
+                    }
+
+                    Ok(())
+                })
+            }
+
+            fn fetch<'e, 'q: 'e, E: 'q>(
+                self,
+                query: E,
+            ) -> futures_core::stream::BoxStream<'e, Result<$Row, crate::error::Error>>
+            where
+                't: 'e,
+                E: crate::executor::Execute<'q, Self::Database>,
+            {
+                Box::pin(futures_util::TryStreamExt::try_filter_map(
+                    self.fetch_many(query),
+                    |step| async move {
+                        Ok(match step {
+                            either::Either::Left(_) => None,
+                            either::Either::Right(row) => Some(row),
+                        })
+                    },
+                ))
+            }
+
+            fn fetch_all<'e, 'q: 'e, E: 'q>(
+                self,
+                query: E,
+            ) -> futures_core::future::BoxFuture<'e, Result<Vec<$Row>, crate::error::Error>>
+            where
+                't: 'e,
+                E: crate::executor::Execute<'q, Self::Database>,
+            {
+                Box::pin(async move {
+                    let deadline = self.before_statement()?;
+
+                    crate::transaction::until(deadline, (&mut **self).fetch_all(query))
+                        .await
+                        .unwrap_or_else(|| Err(self.time_out()))
+                })
+            }
+
+            fn fetch_one<'e, 'q: 'e, E: 'q>(
+                self,
+                query: E,
+            ) -> futures_core::future::BoxFuture<'e, Result<$Row, crate::error::Error>>
+            where
+                't: 'e,
+                E: crate::executor::Execute<'q, Self::Database>,
+            {
+                Box::pin(async move {
+                    let deadline = self.before_statement()?;
+
+                    crate::transaction::until(deadline, (&mut **self).fetch_one(query))
+                        .await
+                        .unwrap_or_else(|| Err(self.time_out()))
+                })
             }
 
             fn fetch_optional<'e, 'q: 'e, E: 'q>(
@@ -128,7 +564,13 @@ macro_rules! impl_executor_for_transaction {
                 't: 'e,
                 E: crate::executor::Execute<'q, Self::Database>,
             {
-                (&mut **self).fetch_optional(query)
+                Box::pin(async move {
+                    let deadline = self.before_statement()?;
+
+                    crate::transaction::until(deadline, (&mut **self).fetch_optional(query))
+                        .await
+                        .unwrap_or_else(|| Err(self.time_out()))
+                })
             }
 
             fn prepare_with<'e, 'q: 'e>(
@@ -145,7 +587,13 @@ macro_rules! impl_executor_for_transaction {
             where
                 't: 'e,
             {
-                (&mut **self).prepare_with(sql, parameters)
+                Box::pin(async move {
+                    let deadline = self.before_statement()?;
+
+                    crate::transaction::until(deadline, (&mut **self).prepare_with(sql, parameters))
+                        .await
+                        .unwrap_or_else(|| Err(self.time_out()))
+                })
             }
 
             #[doc(hidden)]
@@ -159,7 +607,13 @@ macro_rules! impl_executor_for_transaction {
             where
                 't: 'e,
             {
-                (&mut **self).describe(query)
+                Box::pin(async move {
+                    let deadline = self.before_statement()?;
+
+                    crate::transaction::until(deadline, (&mut **self).describe(query))
+                        .await
+                        .unwrap_or_else(|| Err(self.time_out()))
+                })
             }
         }
     };
@@ -203,6 +657,12 @@ where
 {
     fn drop(&mut self) {
         if self.open {
+            self.observe(
+                TransactionEventKind::Dropped,
+                self.started.elapsed(),
+                self.statements(),
+            );
+
             // starts a rollback operation
 
             // what this does depends on the database but generally this means we queue a rollback
@@ -210,10 +670,219 @@ where
             // connection (including if the connection is returned to a pool)
 
             DB::TransactionManager::start_rollback(&mut self.connection);
+
+            std::mem::take(&mut self.callbacks).rollback();
+        }
+    }
+}
+
+/// The options of a transaction, for [`Connection::begin_with()`] and [`Pool::begin_with()`].
+///
+/// ```rust,ignore
+/// let mut tx = conn
+///     .begin_with(
+///         TransactionOptions::new()
+///             .isolation(IsolationLevel::Serializable)
+///             .read_only(true)
+///             .deferrable(true),
+///     )
+///     .await?;
+/// ```
+///
+/// The options which aren't set are those of the session. They're supported by Postgres and
+/// MySQL, except for [`deferrable(true)`](Self::deferrable) on MySQL; SQLite runs every
+/// transaction at the serializable level, and only supports `read_only(false)`, which begins the
+/// transaction with `BEGIN IMMEDIATE` to take the write lock up front. The other databases only
+/// support the default options.
+///
+/// [`Connection::begin_with()`]: crate::connection::Connection::begin_with()
+/// [`Pool::begin_with()`]: crate::pool::Pool::begin_with()
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransactionOptions {
+    pub(crate) isolation: Option<IsolationLevel>,
+    pub(crate) read_only: Option<bool>,
+    pub(crate) deferrable: Option<bool>,
+    pub(crate) timeout: Option<Duration>,
+}
+
+impl TransactionOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn isolation(mut self, isolation: IsolationLevel) -> Self {
+        self.isolation = Some(isolation);
+        self
+    }
+
+    /// Whether the transaction can't write, e.g. `READ ONLY` or `READ WRITE` for Postgres.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = Some(read_only);
+        self
+    }
+
+    /// Whether a serializable, read-only transaction of Postgres waits to take a snapshot which
+    /// can't fail with a serialization failure, rather than taking it right away.
+    ///
+    /// Only supported by Postgres; beginning a deferrable transaction on another database fails.
+    pub fn deferrable(mut self, deferrable: bool) -> Self {
+        self.deferrable = Some(deferrable);
+        self
+    }
+
+    /// The time the transaction can take, from when it begins, after which it's rolled back,
+    /// so that a stuck transaction doesn't hold its locks indefinitely.
+    ///
+    /// The statements executed on the [`Transaction`] itself, e.g. with `&mut tx`, fail with
+    /// [`Error::TransactionTimedOut`] once it's passed, as well as its `commit()`; a statement
+    /// still running at that time is abandoned.
+    ///
+    /// The timeout is only enforced on the client: the timeouts of the server, such as
+    /// `idle_in_transaction_session_timeout` and `transaction_timeout` of Postgres, aren't
+    /// changed, as exceeding them terminates the session rather than just rolling back the
+    /// transaction. The statements executed on its connection, e.g. with `&mut *tx`, aren't
+    /// bounded by it.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+/// The isolation level of a transaction, from the weakest to the strongest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IsolationLevel {
+    ReadUncommitted,
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+}
+
+impl IsolationLevel {
+    pub(crate) fn as_sql(self) -> &'static str {
+        match self {
+            IsolationLevel::ReadUncommitted => "READ UNCOMMITTED",
+            IsolationLevel::ReadCommitted => "READ COMMITTED",
+            IsolationLevel::RepeatableRead => "REPEATABLE READ",
+            IsolationLevel::Serializable => "SERIALIZABLE",
+        }
+    }
+}
+
+/// A named savepoint of a transaction, created by [`Transaction::savepoint()`].
+///
+/// The statements of the transaction are executed on it while it's alive, e.g. with
+/// `&mut *savepoint`.
+pub struct Savepoint<'t, DB>
+where
+    DB: Database,
+{
+    connection: &'t mut DB::Connection,
+    name: String,
+    active: bool,
+}
+
+impl<'t, DB> Savepoint<'t, DB>
+where
+    DB: Database,
+{
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Rolls back the statements executed since the savepoint was created, or since it was last
+    /// rolled back to; the savepoint is kept, so it can be rolled back to again.
+    pub async fn rollback_to(&mut self) -> Result<(), Error>
+    where
+        for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+    {
+        self.execute(SavepointStatement::RollbackTo).await
+    }
+
+    /// Releases the savepoint, keeping the statements executed since it was created in the
+    /// transaction.
+    pub async fn release(mut self) -> Result<(), Error>
+    where
+        for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+    {
+        self.execute(SavepointStatement::Release).await?;
+        self.active = false;
+
+        Ok(())
+    }
+
+    async fn execute(&mut self, statement: SavepointStatement) -> Result<(), Error>
+    where
+        for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+    {
+        if let Some(sql) =
+            DB::TransactionManager::savepoint_sql(self.connection, statement, &self.name)
+        {
+            self.connection.execute(&*sql).await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'t, DB> Debug for Savepoint<'t, DB>
+where
+    DB: Database,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Savepoint")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl<'t, DB> Deref for Savepoint<'t, DB>
+where
+    DB: Database,
+{
+    type Target = DB::Connection;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.connection
+    }
+}
+
+impl<'t, DB> DerefMut for Savepoint<'t, DB>
+where
+    DB: Database,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.connection
+    }
+}
+
+impl<'t, DB> Drop for Savepoint<'t, DB>
+where
+    DB: Database,
+{
+    fn drop(&mut self) {
+        if self.active {
+            // discards the statements of the savepoint, like a transaction dropped while open
+            for statement in [SavepointStatement::RollbackTo, SavepointStatement::Release] {
+                if let Some(sql) =
+                    DB::TransactionManager::savepoint_sql(self.connection, statement, &self.name)
+                {
+                    DB::TransactionManager::start_statement(self.connection, &sql);
+                }
+            }
         }
     }
 }
 
+pub(crate) fn savepoint_ansi_sql(statement: SavepointStatement, name: &str) -> String {
+    match statement {
+        SavepointStatement::Create => format!("SAVEPOINT {}", name),
+        SavepointStatement::RollbackTo => format!("ROLLBACK TO SAVEPOINT {}", name),
+        SavepointStatement::Release => format!("RELEASE SAVEPOINT {}", name),
+    }
+}
+
 #[allow(dead_code)]
 pub(crate) fn begin_ansi_transaction_sql(depth: usize) -> Cow<'static, str> {
     if depth == 0 {
@@ -148,6 +148,29 @@ pub use json::{Json, JsonRawValue, JsonValue};
 /// enum Color { Red, Green, Blue }
 /// ```
 ///
+/// Any integer type can be used with `#[repr(_)]` as long as it implements [`Type`] for the
+/// database, e.g. `#[repr(i16)]` for a `SMALLINT` column.
+///
+/// ##### Attributes
+///
+/// * `#[sqlx(rename = "<name>")]` on a variant (without `#[repr(_)]` only): use `<name>` instead
+///   of the variant name.
+/// * `#[sqlx(other)]` on a unit variant: decode any value that doesn't match another variant to
+///   this one instead of returning an error, e.g. so an application keeps working after a new
+///   value is added to an enum in the database. Encoding this variant writes its own
+///   discriminant or name, as for any other variant.
+///
+/// ```rust,ignore
+/// #[derive(sqlx::Type)]
+/// #[repr(i16)]
+/// enum Status {
+///     Active = 1,
+///     Suspended = 2,
+///     #[sqlx(other)]
+///     Unknown = -1,
+/// }
+/// ```
+///
 /// ### Records
 ///
 /// User-defined composite types are supported through deriving a `struct`.
@@ -164,6 +187,23 @@ pub use json::{Json, JsonRawValue, JsonValue};
 /// }
 /// ```
 ///
+/// ### Read-only and write-only types
+///
+/// For a type that should only be read from the database, add `#[sqlx(decode_only)]` to generate
+/// [`Decode`](crate::decode::Decode) but not [`Encode`](crate::encode::Encode), and for one that
+/// should only be written, add `#[sqlx(encode_only)]`. This works with any of the kinds of types
+/// above.
+///
+/// ```rust,ignore
+/// #[derive(sqlx::Type)]
+/// #[sqlx(type_name = "audit_action", rename_all = "lowercase", decode_only)]
+/// enum AuditAction { Insert, Update, Delete }
+/// ```
+///
+/// `Encode` and `Decode` can also be derived on their own, with `#[derive(sqlx::Encode)]` and
+/// `#[derive(sqlx::Decode)]`, for a type that implements `Type` by hand. They take the same
+/// attributes as `#[derive(sqlx::Type)]`.
+///
 pub trait Type<DB: Database> {
     /// Returns the canonical SQL type for this Rust type.
     ///
@@ -1,7 +1,19 @@
+use once_cell::sync::Lazy;
 use proc_macro2::Span;
+use sqlx_core::config::Config;
 use std::env;
 use std::path::{Path, PathBuf};
 
+/// The configuration of the crate being compiled, from the closest `sqlx.toml`, if any.
+pub(crate) static CONFIG: Lazy<Config> = Lazy::new(|| {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").expect("`CARGO_MANIFEST_DIR` must be set");
+
+    match Config::find(Path::new(&manifest_dir)) {
+        Ok(config) => config.unwrap_or_default(),
+        Err(e) => panic!("{}", e),
+    }
+});
+
 pub(crate) fn resolve_path(path: &str, err_span: Span) -> syn::Result<PathBuf> {
     let path = Path::new(path);
 
@@ -35,3 +47,12 @@ pub(crate) fn resolve_path(path: &str, err_span: Span) -> syn::Result<PathBuf> {
 
     Ok(base_dir_path.join(path))
 }
+
+/// Hash a column name to the key used by `sqlx::from_columns::ColumnValue`.
+///
+/// This is 64-bit FNV-1a, which is simple enough to keep stable across versions of the macros.
+pub(crate) fn column_name_hash(name: &str) -> u64 {
+    name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
@@ -7,6 +7,17 @@ pub enum ParamChecking {
     Weak,
 }
 
+/// How `{in: ...}` list arguments are passed to the database.
+#[derive(PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum InListStyle {
+    /// Rewrite `IN ($N)` to `= ANY($N)` and bind the list as an array.
+    AnyArray,
+    /// Expand the placeholder to one placeholder per element at runtime.
+    Placeholders,
+    Unsupported,
+}
+
 pub trait DatabaseExt: Database {
     const DATABASE_PATH: &'static str;
     const ROW_PATH: &'static str;
@@ -14,6 +25,8 @@ pub trait DatabaseExt: Database {
 
     const PARAM_CHECKING: ParamChecking;
 
+    const IN_LIST_STYLE: InListStyle;
+
     fn db_path() -> syn::Path {
         syn::parse_str(Self::DATABASE_PATH).unwrap()
     }
@@ -35,6 +48,7 @@ macro_rules! impl_database_ext {
             $($(#[$meta:meta])? $ty:ty $(| $input:ty)?),*$(,)?
         },
         ParamChecking::$param_checking:ident,
+        InListStyle::$in_list_style:ident,
         feature-types: $ty_info:ident => $get_gate:expr,
         row = $row:path,
         name = $db_name:literal
@@ -43,6 +57,7 @@ macro_rules! impl_database_ext {
             const DATABASE_PATH: &'static str = stringify!($database);
             const ROW_PATH: &'static str = stringify!($row);
             const PARAM_CHECKING: $crate::database::ParamChecking = $crate::database::ParamChecking::$param_checking;
+            const IN_LIST_STYLE: $crate::database::InListStyle = $crate::database::InListStyle::$in_list_style;
             const NAME: &'static str = $db_name;
 
             fn param_type_for_id(info: &Self::TypeInfo) -> Option<&'static str> {
@@ -12,6 +12,7 @@ impl_database_ext! {
         String,
     },
     ParamChecking::Weak,
+    InListStyle::Unsupported,
     feature-types: _info => None,
     row = sqlx::mssql::MssqlRow,
     name = "MSSQL"
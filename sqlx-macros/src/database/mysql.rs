@@ -54,6 +54,7 @@ impl_database_ext! {
         sqlx::types::JsonValue,
     },
     ParamChecking::Weak,
+    InListStyle::Placeholders,
     feature-types: info => info.__type_feature_gate(),
     row = sqlx::mysql::MySqlRow,
     name = "MySQL"
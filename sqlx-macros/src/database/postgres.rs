@@ -202,6 +202,7 @@ impl_database_ext! {
             &[sqlx::postgres::types::PgRange<sqlx::types::time::OffsetDateTime>],
     },
     ParamChecking::Strong,
+    InListStyle::AnyArray,
     feature-types: info => info.__type_feature_gate(),
     row = sqlx::postgres::PgRow,
     name = "PostgreSQL"
@@ -3,7 +3,7 @@ use quote::{quote, quote_spanned};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::token::Comma;
-use syn::{Attribute, DeriveInput, Field, Lit, Meta, MetaNameValue, NestedMeta, Variant};
+use syn::{Attribute, DeriveInput, Field, Fields, Lit, Meta, MetaNameValue, NestedMeta, Variant};
 
 macro_rules! assert_attribute {
     ($e:expr, $err:expr, $input:expr) => {
@@ -65,12 +65,26 @@ pub struct SqlxContainerAttributes {
     pub type_name: Option<TypeName>,
     pub rename_all: Option<RenameAll>,
     pub repr: Option<Ident>,
+    pub table: Option<String>,
+    /// `#[derive(Type)]` only generates `Decode`, not `Encode`.
+    pub decode_only: bool,
+    /// `#[derive(Type)]` only generates `Encode`, not `Decode`.
+    pub encode_only: bool,
 }
 
 pub struct SqlxChildAttributes {
     pub rename: Option<String>,
     pub default: bool,
+    /// The function giving the value of a missing column, from `#[sqlx(default = "...")]`.
+    pub default_with: Option<syn::Path>,
+    /// The function converting the value of the column, from `#[sqlx(decode_with = "...")]`,
+    /// and the type the column is decoded as to pass it to the function.
+    pub decode_with: Option<(syn::Path, syn::Type)>,
     pub flatten: bool,
+    pub prefix: Option<String>,
+    pub json: bool,
+    pub other: bool,
+    pub primary_key: bool,
 }
 
 pub fn parse_container_attributes(input: &[Attribute]) -> syn::Result<SqlxContainerAttributes> {
@@ -78,6 +92,9 @@ pub fn parse_container_attributes(input: &[Attribute]) -> syn::Result<SqlxContai
     let mut repr = None;
     let mut type_name = None;
     let mut rename_all = None;
+    let mut table = None;
+    let mut decode_only = None;
+    let mut encode_only = None;
 
     for attr in input
         .iter()
@@ -95,6 +112,14 @@ pub fn parse_container_attributes(input: &[Attribute]) -> syn::Result<SqlxContai
                                 try_set!(transparent, true, value)
                             }
 
+                            Meta::Path(p) if p.is_ident("decode_only") => {
+                                try_set!(decode_only, true, value)
+                            }
+
+                            Meta::Path(p) if p.is_ident("encode_only") => {
+                                try_set!(encode_only, true, value)
+                            }
+
                             Meta::NameValue(MetaNameValue {
                                 path,
                                 lit: Lit::Str(val),
@@ -146,6 +171,12 @@ pub fn parse_container_attributes(input: &[Attribute]) -> syn::Result<SqlxContai
                                 )
                             }
 
+                            Meta::NameValue(MetaNameValue {
+                                path,
+                                lit: Lit::Str(val),
+                                ..
+                            }) if path.is_ident("table") => try_set!(table, val.value(), value),
+
                             u => fail!(u, "unexpected attribute"),
                         },
                         u => fail!(u, "unexpected attribute"),
@@ -172,13 +203,23 @@ pub fn parse_container_attributes(input: &[Attribute]) -> syn::Result<SqlxContai
         repr,
         type_name,
         rename_all,
+        table,
+        decode_only: decode_only.unwrap_or(false),
+        encode_only: encode_only.unwrap_or(false),
     })
 }
 
 pub fn parse_child_attributes(input: &[Attribute]) -> syn::Result<SqlxChildAttributes> {
     let mut rename = None;
     let mut default = false;
+    let mut default_with = None;
+    let mut decode_with = None;
+    let mut decode_as = None;
     let mut flatten = false;
+    let mut prefix = None;
+    let mut json = false;
+    let mut other = false;
+    let mut primary_key = false;
 
     for attr in input.iter().filter(|a| a.path.is_ident("sqlx")) {
         let meta = attr
@@ -194,8 +235,38 @@ pub fn parse_child_attributes(input: &[Attribute]) -> syn::Result<SqlxChildAttri
                             lit: Lit::Str(val),
                             ..
                         }) if path.is_ident("rename") => try_set!(rename, val.value(), value),
+                        Meta::NameValue(MetaNameValue {
+                            path,
+                            lit: Lit::Str(val),
+                            ..
+                        }) if path.is_ident("prefix") => try_set!(prefix, val.value(), value),
+                        Meta::NameValue(MetaNameValue {
+                            path,
+                            lit: Lit::Str(val),
+                            ..
+                        }) if path.is_ident("default") => {
+                            try_set!(default_with, val.parse::<syn::Path>()?, value);
+                            default = true;
+                        }
+                        Meta::NameValue(MetaNameValue {
+                            path,
+                            lit: Lit::Str(val),
+                            ..
+                        }) if path.is_ident("decode_with") => {
+                            try_set!(decode_with, val.parse::<syn::Path>()?, value)
+                        }
+                        Meta::NameValue(MetaNameValue {
+                            path,
+                            lit: Lit::Str(val),
+                            ..
+                        }) if path.is_ident("decode_as") => {
+                            try_set!(decode_as, val.parse::<syn::Type>()?, value)
+                        }
                         Meta::Path(path) if path.is_ident("default") => default = true,
                         Meta::Path(path) if path.is_ident("flatten") => flatten = true,
+                        Meta::Path(path) if path.is_ident("json") => json = true,
+                        Meta::Path(path) if path.is_ident("other") => other = true,
+                        Meta::Path(path) if path.is_ident("primary_key") => primary_key = true,
                         u => fail!(u, "unexpected attribute"),
                     },
                     u => fail!(u, "unexpected attribute"),
@@ -204,10 +275,51 @@ pub fn parse_child_attributes(input: &[Attribute]) -> syn::Result<SqlxChildAttri
         }
     }
 
+    if json && flatten {
+        fail!(
+            input.first(),
+            "`json` and `flatten` attributes cannot be used together"
+        )
+    }
+
+    if prefix.is_some() && !flatten {
+        fail!(
+            input.first(),
+            "the `prefix` attribute can only be used together with `flatten`"
+        )
+    }
+
+    let decode_with = match (decode_with, decode_as) {
+        (Some(path), Some(ty)) => Some((path, ty)),
+        (None, None) => None,
+        (Some(_), None) => fail!(
+            input.first(),
+            "the `decode_with` attribute requires `decode_as = \"<type>\"`, \
+             the type the column is decoded as to pass it to the function"
+        ),
+        (None, Some(_)) => fail!(
+            input.first(),
+            "the `decode_as` attribute can only be used together with `decode_with`"
+        ),
+    };
+
+    if decode_with.is_some() && (json || flatten) {
+        fail!(
+            input.first(),
+            "the `decode_with` attribute cannot be used together with `json` or `flatten`"
+        )
+    }
+
     Ok(SqlxChildAttributes {
         rename,
         default,
+        default_with,
+        decode_with,
         flatten,
+        prefix,
+        json,
+        other,
+        primary_key,
     })
 }
 
@@ -270,20 +382,49 @@ pub fn check_weak_enum_attributes(
         );
     }
 
+    find_other_variant(variants)?;
+
     Ok(attributes)
 }
 
 pub fn check_strong_enum_attributes(
     input: &DeriveInput,
-    _variants: &Punctuated<Variant, Comma>,
+    variants: &Punctuated<Variant, Comma>,
 ) -> syn::Result<SqlxContainerAttributes> {
     let attributes = check_enum_attributes(input)?;
 
     assert_attribute!(attributes.repr.is_none(), "unexpected #[repr(..)]", input);
 
+    find_other_variant(variants)?;
+
     Ok(attributes)
 }
 
+/// Find the variant marked `#[sqlx(other)]`, which is decoded from any unknown value.
+pub fn find_other_variant(variants: &Punctuated<Variant, Comma>) -> syn::Result<Option<&Ident>> {
+    let mut other = None;
+
+    for variant in variants {
+        if !parse_child_attributes(&variant.attrs)?.other {
+            continue;
+        }
+
+        assert_attribute!(
+            matches!(variant.fields, Fields::Unit),
+            "#[sqlx(other)] must be on a unit variant",
+            variant
+        );
+
+        if other.is_some() {
+            fail!(variant, "only one variant can be marked #[sqlx(other)]")
+        }
+
+        other = Some(&variant.ident);
+    }
+
+    Ok(other)
+}
+
 pub fn check_struct_attributes<'a>(
     input: &'a DeriveInput,
     fields: &Punctuated<Field, Comma>,
@@ -1,6 +1,7 @@
 use super::attributes::{
     check_strong_enum_attributes, check_struct_attributes, check_transparent_attributes,
-    check_weak_enum_attributes, parse_child_attributes, parse_container_attributes,
+    check_weak_enum_attributes, find_other_variant, parse_child_attributes,
+    parse_container_attributes,
 };
 use super::rename_all;
 use proc_macro2::TokenStream;
@@ -111,6 +112,15 @@ fn expand_derive_decode_weak_enum(
         })
         .collect::<Vec<Arm>>();
 
+    let fallback = match find_other_variant(variants)? {
+        Some(other) => quote!(::std::result::Result::Ok(#ident::#other)),
+        None => quote!(::std::result::Result::Err(::std::boxed::Box::new(
+            ::sqlx::Error::Decode(
+                ::std::format!("invalid value {:?} for enum {}", value, #ident_s).into(),
+            )
+        ))),
+    };
+
     Ok(quote!(
         #[automatically_derived]
         impl<'r, DB: ::sqlx::Database> ::sqlx::decode::Decode<'r, DB> for #ident
@@ -129,9 +139,7 @@ fn expand_derive_decode_weak_enum(
 
                 match value {
                     #(#arms)*
-                    _ => ::std::result::Result::Err(::std::boxed::Box::new(::sqlx::Error::Decode(
-                        ::std::format!("invalid value {:?} for enum {}", value, #ident_s).into(),
-                    )))
+                    _ => #fallback
                 }
             }
         }
@@ -163,11 +171,18 @@ fn expand_derive_decode_strong_enum(
         }
     });
 
+    let fallback = match find_other_variant(variants)? {
+        Some(other) => quote!(::std::result::Result::Ok(#ident :: #other)),
+        None => quote!(Err(
+            format!("invalid value {:?} for enum {}", value, #ident_s).into()
+        )),
+    };
+
     let values = quote! {
         match value {
             #(#value_arms)*
 
-            _ => Err(format!("invalid value {:?} for enum {}", value, #ident_s).into())
+            _ => #fallback
         }
     };
 
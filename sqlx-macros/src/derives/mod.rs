@@ -2,22 +2,43 @@ mod attributes;
 mod decode;
 mod encode;
 mod row;
+mod table;
 mod r#type;
 
 pub(crate) use decode::expand_derive_decode;
 pub(crate) use encode::expand_derive_encode;
 pub(crate) use r#type::expand_derive_type;
 pub(crate) use row::expand_derive_from_row;
+pub(crate) use table::expand_derive_table;
 
-use self::attributes::RenameAll;
+use self::attributes::{parse_container_attributes, RenameAll};
 use heck::{ToKebabCase, ToLowerCamelCase, ToShoutySnakeCase, ToSnakeCase, ToUpperCamelCase};
 use proc_macro2::TokenStream;
 use std::iter::FromIterator;
 use syn::DeriveInput;
 
 pub(crate) fn expand_derive_type_encode_decode(input: &DeriveInput) -> syn::Result<TokenStream> {
-    let encode_tts = expand_derive_encode(input)?;
-    let decode_tts = expand_derive_decode(input)?;
+    let attrs = parse_container_attributes(&input.attrs)?;
+
+    if attrs.decode_only && attrs.encode_only {
+        return Err(syn::Error::new_spanned(
+            input,
+            "#[sqlx(decode_only)] and #[sqlx(encode_only)] are mutually exclusive",
+        ));
+    }
+
+    let encode_tts = if attrs.decode_only {
+        TokenStream::new()
+    } else {
+        expand_derive_encode(input)?
+    };
+
+    let decode_tts = if attrs.encode_only {
+        TokenStream::new()
+    } else {
+        expand_derive_decode(input)?
+    };
+
     let type_tts = expand_derive_type(input)?;
 
     let combined = TokenStream::from_iter(encode_tts.into_iter().chain(decode_tts).chain(type_tts));
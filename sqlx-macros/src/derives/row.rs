@@ -1,12 +1,18 @@
 use proc_macro2::{Span, TokenStream};
-use quote::quote;
+use quote::{quote, ToTokens};
 use syn::{
     parse_quote, punctuated::Punctuated, token::Comma, Data, DataStruct, DeriveInput, Expr, Field,
-    Fields, FieldsNamed, FieldsUnnamed, Lifetime, Stmt,
+    Fields, FieldsNamed, FieldsUnnamed, GenericArgument, Ident, Lifetime, PathArguments, Stmt,
+    Type, WherePredicate,
 };
 
+use crate::common::column_name_hash;
+
 use super::{
-    attributes::{parse_child_attributes, parse_container_attributes},
+    attributes::{
+        parse_child_attributes, parse_container_attributes, SqlxChildAttributes,
+        SqlxContainerAttributes,
+    },
     rename_all,
 };
 
@@ -59,41 +65,262 @@ fn expand_derive_from_row_struct(
         generics.params.insert(0, parse_quote!(#lifetime));
     }
 
-    let predicates = &mut generics.make_where_clause().predicates;
+    let container_attributes = parse_container_attributes(&input.attrs)?;
+
+    // report invalid field attributes as errors; they're unwrapped below
+    for field in fields {
+        parse_child_attributes(&field.attrs)?;
+    }
+
+    let mut prefixed_generics = generics.clone();
 
+    let predicates = &mut generics.make_where_clause().predicates;
     predicates.push(parse_quote!(&#lifetime ::std::primitive::str: ::sqlx::ColumnIndex<R>));
+    let reads = expand_reads(fields, &container_attributes, &lifetime, predicates, false);
 
-    let container_attributes = parse_container_attributes(&input.attrs)?;
+    // column names are built at runtime when reading with a prefix, so they don't live for `'a`
+    let prefixed_predicates = &mut prefixed_generics.make_where_clause().predicates;
+    prefixed_predicates.push(parse_quote!(
+        for<'__name> &'__name ::std::primitive::str: ::sqlx::ColumnIndex<R>
+    ));
+    let prefixed_reads = expand_reads(
+        fields,
+        &container_attributes,
+        &lifetime,
+        prefixed_predicates,
+        true,
+    );
+
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (prefixed_impl_generics, _, prefixed_where_clause) = prefixed_generics.split_for_impl();
+
+    let names = fields.iter().map(|field| &field.ident);
+    let prefixed_names = names.clone();
+
+    let from_columns = expand_from_columns(input, fields, &container_attributes);
+
+    Ok(quote!(
+        #[automatically_derived]
+        impl #impl_generics ::sqlx::FromRow<#lifetime, R> for #ident #ty_generics #where_clause {
+            fn from_row(row: &#lifetime R) -> ::sqlx::Result<Self> {
+                #(#reads)*
+
+                ::std::result::Result::Ok(#ident {
+                    #(#names),*
+                })
+            }
+        }
+
+        #[automatically_derived]
+        impl #prefixed_impl_generics ::sqlx::FromRowPrefixed<#lifetime, R> for #ident #ty_generics #prefixed_where_clause {
+            fn from_row_prefixed(
+                row: &#lifetime R,
+                prefix: &::std::primitive::str,
+            ) -> ::sqlx::Result<Self> {
+                #(#prefixed_reads)*
+
+                ::std::result::Result::Ok(#ident {
+                    #(#prefixed_names),*
+                })
+            }
+        }
+
+        #from_columns
+    ))
+}
+
+/// The name of the column a field is read from.
+pub(super) fn column_name(
+    id: &Ident,
+    rename: Option<String>,
+    container_attributes: &SqlxContainerAttributes,
+) -> String {
+    let name = rename.unwrap_or_else(|| id.to_string().trim_start_matches("r#").to_owned());
+
+    match container_attributes.rename_all {
+        Some(pattern) => rename_all(&name, pattern),
+        None => name,
+    }
+}
+
+/// Implement `FromColumns` so `query_as!()` can build the struct by column name.
+///
+/// A field flattened with a prefix adds an unsatisfiable bound, as its column names are only
+/// known at runtime; using the struct with `query_as!()` is then an error saying so.
+fn expand_from_columns(
+    input: &DeriveInput,
+    fields: &Punctuated<Field, Comma>,
+    container_attributes: &SqlxContainerAttributes,
+) -> TokenStream {
+    let ident = &input.ident;
+
+    let mut generics = input.generics.clone();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    generics.params.push(parse_quote!(__C));
+
+    let predicates = &mut generics.make_where_clause().predicates;
+
+    let mut values = Vec::with_capacity(fields.len());
+
+    for field in fields {
+        let id = match &field.ident {
+            Some(id) => id,
+            None => continue,
+        };
+        let attributes = parse_child_attributes(&field.attrs).unwrap();
+        let ty = &field.ty;
+
+        if attributes.flatten {
+            if attributes.prefix.is_some() {
+                predicates.push(parse_quote!(__C: ::sqlx::from_columns::FlattenWithPrefix));
+            }
+
+            predicates.push(parse_quote!(#ty: ::sqlx::from_columns::FromColumns<__C>));
+            values.push(quote!(
+                #id: <#ty as ::sqlx::from_columns::FromColumns<__C>>::from_columns(columns)?
+            ));
+
+            continue;
+        }
+
+        let column = column_name(id, attributes.rename.clone(), container_attributes);
+        let name = column_name_hash(&column);
+
+        // the type the column is decoded as, and the tokens turning the `Result` of decoding it
+        // into that of the field
+        let (decode_as, convert): (Type, TokenStream) = if attributes.json {
+            let (decode_as, unwrap) = json_decode_as(ty);
+
+            (decode_as, quote!(.map(|json| json #unwrap)))
+        } else if let Some((decode_with, decode_as)) = &attributes.decode_with {
+            (decode_as.clone(), quote_decode_with(decode_with, &column))
+        } else {
+            (ty.clone(), quote!())
+        };
+
+        let value = if attributes.default {
+            let default = quote_default(&attributes, ty, predicates);
+
+            predicates.push(parse_quote!(
+                __C: ::sqlx::from_columns::OptionalColumnValue<#name, #decode_as>
+            ));
+            quote!(
+                match ::sqlx::from_columns::OptionalColumnValue::<#name, #decode_as>::optional_column_value(columns) {
+                    ::std::option::Option::Some(value) => value #convert?,
+                    ::std::option::Option::None => #default,
+                }
+            )
+        } else {
+            predicates.push(parse_quote!(
+                __C: ::sqlx::from_columns::ColumnValue<#name, #decode_as>
+            ));
+            quote!(
+                ::sqlx::from_columns::ColumnValue::<#name, #decode_as>::column_value(columns)
+                    #convert?
+            )
+        };
+
+        values.push(quote!(#id: #value));
+    }
+
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    quote!(
+        #[automatically_derived]
+        impl #impl_generics ::sqlx::from_columns::FromColumns<__C> for #ident #ty_generics #where_clause {
+            fn from_columns(columns: &__C) -> ::sqlx::Result<Self> {
+                ::std::result::Result::Ok(#ident {
+                    #(#values),*
+                })
+            }
+        }
+    )
+}
 
-    let reads: Vec<Stmt> = fields
+/// Generate a `let` statement reading each field from `row`, adding the required bounds to
+/// `predicates`.
+///
+/// If `prefixed` is set, the column names (and the prefixes of flattened fields) are prepended
+/// with the `prefix` argument of `FromRowPrefixed::from_row_prefixed()`.
+fn expand_reads(
+    fields: &Punctuated<Field, Comma>,
+    container_attributes: &SqlxContainerAttributes,
+    lifetime: &Lifetime,
+    predicates: &mut Punctuated<WherePredicate, Comma>,
+    prefixed: bool,
+) -> Vec<Stmt> {
+    fields
         .iter()
         .filter_map(|field| -> Option<Stmt> {
             let id = &field.ident.as_ref()?;
             let attributes = parse_child_attributes(&field.attrs).unwrap();
             let ty = &field.ty;
 
+            let id_s = column_name(id, attributes.rename.clone(), container_attributes);
+
+            let column: Expr = if prefixed {
+                parse_quote!(&*::std::format!("{}{}", prefix, #id_s))
+            } else {
+                parse_quote!(#id_s)
+            };
+
             let expr: Expr = if attributes.flatten {
-                predicates.push(parse_quote!(#ty: ::sqlx::FromRow<#lifetime, R>));
-                parse_quote!(#ty::from_row(row))
+                let field_prefix: Option<Expr> = match (&attributes.prefix, prefixed) {
+                    (Some(field_prefix), true) => {
+                        Some(parse_quote!(&::std::format!("{}{}", prefix, #field_prefix)))
+                    }
+                    (Some(field_prefix), false) => Some(parse_quote!(#field_prefix)),
+                    (None, true) => Some(parse_quote!(prefix)),
+                    (None, false) => None,
+                };
+
+                match field_prefix {
+                    Some(field_prefix) => {
+                        predicates.push(parse_quote!(#ty: ::sqlx::FromRowPrefixed<#lifetime, R>));
+                        parse_quote!(
+                            <#ty as ::sqlx::FromRowPrefixed<#lifetime, R>>::from_row_prefixed(
+                                row,
+                                #field_prefix,
+                            )
+                        )
+                    }
+                    None => {
+                        predicates.push(parse_quote!(#ty: ::sqlx::FromRow<#lifetime, R>));
+                        parse_quote!(#ty::from_row(row))
+                    }
+                }
+            } else if attributes.json {
+                let (decode_as, unwrap) = json_decode_as(ty);
+
+                predicates.push(parse_quote!(
+                    #decode_as: ::sqlx::decode::Decode<#lifetime, R::Database>
+                ));
+                predicates.push(parse_quote!(#decode_as: ::sqlx::types::Type<R::Database>));
+
+                parse_quote!(row.try_get::<#decode_as, _>(#column).map(|json| json #unwrap))
+            } else if let Some((decode_with, decode_as)) = &attributes.decode_with {
+                predicates.push(parse_quote!(
+                    #decode_as: ::sqlx::decode::Decode<#lifetime, R::Database>
+                ));
+                predicates.push(parse_quote!(#decode_as: ::sqlx::types::Type<R::Database>));
+
+                let decode = quote_decode_with(decode_with, &column);
+
+                parse_quote!(row.try_get::<#decode_as, _>(#column) #decode)
             } else {
                 predicates.push(parse_quote!(#ty: ::sqlx::decode::Decode<#lifetime, R::Database>));
                 predicates.push(parse_quote!(#ty: ::sqlx::types::Type<R::Database>));
 
-                let id_s = attributes
-                    .rename
-                    .or_else(|| Some(id.to_string().trim_start_matches("r#").to_owned()))
-                    .map(|s| match container_attributes.rename_all {
-                        Some(pattern) => rename_all(&s, pattern),
-                        None => s,
-                    })
-                    .unwrap();
-                parse_quote!(row.try_get(#id_s))
+                parse_quote!(row.try_get(#column))
             };
 
             if attributes.default {
+                let default = quote_default(&attributes, ty, predicates);
+
                 Some(parse_quote!(let #id: #ty = #expr.or_else(|e| match e {
                 ::sqlx::Error::ColumnNotFound(_) => {
-                    ::std::result::Result::Ok(Default::default())
+                    ::std::result::Result::Ok(#default)
                 },
                 e => ::std::result::Result::Err(e)
             })?;))
@@ -103,24 +330,71 @@ fn expand_derive_from_row_struct(
                 ))
             }
         })
-        .collect();
+        .collect()
+}
 
-    let (impl_generics, _, where_clause) = generics.split_for_impl();
+/// The type to decode a field marked `#[sqlx(json)]` as, and the tokens unwrapping its value.
+///
+/// An `Option<T>` field is decoded as `Option<Json<T>>` rather than `Json<Option<T>>`, so that
+/// a `NULL` column is read as `None` rather than failing to decode as JSON.
+fn json_decode_as(ty: &Type) -> (Type, TokenStream) {
+    match option_inner(ty) {
+        Some(inner) => (
+            parse_quote!(::std::option::Option<::sqlx::types::Json<#inner>>),
+            quote!(.map(|json| json.0)),
+        ),
+        None => (parse_quote!(::sqlx::types::Json<#ty>), quote!(.0)),
+    }
+}
 
-    let names = fields.iter().map(|field| &field.ident);
+/// The `T` of a field of type `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let path = match ty {
+        Type::Path(path) if path.qself.is_none() => &path.path,
+        Type::Group(group) => return option_inner(&group.elem),
+        _ => return None,
+    };
 
-    Ok(quote!(
-        #[automatically_derived]
-        impl #impl_generics ::sqlx::FromRow<#lifetime, R> for #ident #ty_generics #where_clause {
-            fn from_row(row: &#lifetime R) -> ::sqlx::Result<Self> {
-                #(#reads)*
+    let segment = path.segments.last()?;
 
-                ::std::result::Result::Ok(#ident {
-                    #(#names),*
-                })
-            }
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Generate a method call converting the `Result` of reading the column `index` with the function
+/// of `#[sqlx(decode_with = "...")]`, reporting its errors as `ColumnDecode` errors.
+fn quote_decode_with(decode_with: &syn::Path, index: &dyn ToTokens) -> TokenStream {
+    quote!(
+        .and_then(|value| #decode_with(value).map_err(|e| ::sqlx::Error::ColumnDecode {
+            index: ::std::format!("{:?}", #index),
+            source: ::std::convert::Into::into(e),
+        }))
+    )
+}
+
+/// Generate the value of a field marked `#[sqlx(default)]` when its column is missing, adding
+/// the required bounds to `predicates`.
+fn quote_default(
+    attributes: &SqlxChildAttributes,
+    ty: &Type,
+    predicates: &mut Punctuated<WherePredicate, Comma>,
+) -> TokenStream {
+    match &attributes.default_with {
+        Some(default_with) => quote!(#default_with()),
+        None => {
+            predicates.push(parse_quote!(#ty: ::std::default::Default));
+            quote!(Default::default())
         }
-    ))
+    }
 }
 
 fn expand_derive_from_row_struct_unnamed(
@@ -152,20 +426,62 @@ fn expand_derive_from_row_struct_unnamed(
         ::std::primitive::usize: ::sqlx::ColumnIndex<R>
     ));
 
-    for field in fields {
+    let mut gets = Vec::with_capacity(fields.len());
+
+    for (idx, field) in fields.iter().enumerate() {
+        let attributes = parse_child_attributes(&field.attrs)?;
         let ty = &field.ty;
 
-        predicates.push(parse_quote!(#ty: ::sqlx::decode::Decode<#lifetime, R::Database>));
-        predicates.push(parse_quote!(#ty: ::sqlx::types::Type<R::Database>));
+        // fields of tuple structs are read by their position, so there is no name to change and
+        // no way to tell how many columns a flattened field would take
+        if attributes.rename.is_some() || attributes.flatten {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`rename` and `flatten` are not supported for fields of tuple structs",
+            ));
+        }
+
+        let expr: Expr = if attributes.json {
+            let (decode_as, unwrap) = json_decode_as(ty);
+
+            predicates.push(parse_quote!(
+                #decode_as: ::sqlx::decode::Decode<#lifetime, R::Database>
+            ));
+            predicates.push(parse_quote!(#decode_as: ::sqlx::types::Type<R::Database>));
+
+            parse_quote!(row.try_get::<#decode_as, _>(#idx).map(|json| json #unwrap))
+        } else if let Some((decode_with, decode_as)) = &attributes.decode_with {
+            predicates.push(parse_quote!(
+                #decode_as: ::sqlx::decode::Decode<#lifetime, R::Database>
+            ));
+            predicates.push(parse_quote!(#decode_as: ::sqlx::types::Type<R::Database>));
+
+            let decode = quote_decode_with(decode_with, &idx);
+
+            parse_quote!(row.try_get::<#decode_as, _>(#idx) #decode)
+        } else {
+            predicates.push(parse_quote!(#ty: ::sqlx::decode::Decode<#lifetime, R::Database>));
+            predicates.push(parse_quote!(#ty: ::sqlx::types::Type<R::Database>));
+
+            parse_quote!(row.try_get(#idx))
+        };
+
+        gets.push(if attributes.default {
+            let default = quote_default(&attributes, ty, predicates);
+
+            quote!(#expr.or_else(|e| match e {
+                ::sqlx::Error::ColumnIndexOutOfBounds { .. } => {
+                    ::std::result::Result::Ok(#default)
+                },
+                e => ::std::result::Result::Err(e)
+            })?)
+        } else {
+            quote!(#expr?)
+        });
     }
 
     let (impl_generics, _, where_clause) = generics.split_for_impl();
 
-    let gets = fields
-        .iter()
-        .enumerate()
-        .map(|(idx, _)| quote!(row.try_get(#idx)?));
-
     Ok(quote!(
         #[automatically_derived]
         impl #impl_generics ::sqlx::FromRow<#lifetime, R> for #ident #ty_generics #where_clause {
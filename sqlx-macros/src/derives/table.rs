@@ -0,0 +1,242 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    punctuated::Punctuated, token::Comma, Data, DataStruct, DeriveInput, Field, Fields, FieldsNamed,
+};
+
+use crate::database::DatabaseExt;
+
+use super::{
+    attributes::{parse_child_attributes, parse_container_attributes},
+    row::column_name,
+};
+
+/// How queries are written for a database.
+struct Dialect {
+    /// The name of the database, as in [`DatabaseExt::NAME`].
+    name: &'static str,
+    db_path: syn::Path,
+    /// The characters an identifier is quoted with.
+    quotes: (char, char),
+    /// Write the placeholder of the bind parameter with the given (1-based) number.
+    placeholder: fn(usize) -> String,
+}
+
+impl Dialect {
+    fn new<DB: DatabaseExt>(quotes: (char, char), placeholder: fn(usize) -> String) -> Self {
+        Dialect {
+            name: DB::NAME,
+            db_path: DB::db_path(),
+            quotes,
+            placeholder,
+        }
+    }
+
+    fn quote(&self, ident: &str) -> String {
+        format!("{}{}{}", self.quotes.0, ident, self.quotes.1)
+    }
+}
+
+fn dialects() -> Vec<Dialect> {
+    vec![
+        #[cfg(feature = "postgres")]
+        Dialect::new::<sqlx_core::postgres::Postgres>(('"', '"'), |n| format!("${}", n)),
+        #[cfg(feature = "mysql")]
+        Dialect::new::<sqlx_core::mysql::MySql>(('`', '`'), |_| "?".to_owned()),
+        #[cfg(feature = "sqlite")]
+        Dialect::new::<sqlx_core::sqlite::Sqlite>(('"', '"'), |n| format!("${}", n)),
+        #[cfg(feature = "mssql")]
+        Dialect::new::<sqlx_core::mssql::Mssql>(('[', ']'), |n| format!("@p{}", n)),
+    ]
+}
+
+/// A field of the struct and the column it is stored in.
+struct Column {
+    name: String,
+    primary_key: bool,
+    /// The expression to bind for the column, in the context of `&self`.
+    value: TokenStream,
+}
+
+pub fn expand_derive_table(input: &DeriveInput) -> syn::Result<TokenStream> {
+    match &input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(FieldsNamed { named, .. }),
+            ..
+        }) => expand_derive_table_struct(input, named),
+
+        Data::Struct(_) => Err(syn::Error::new_spanned(
+            input,
+            "only structs with named fields are supported",
+        )),
+
+        Data::Enum(_) => Err(syn::Error::new_spanned(input, "enums are not supported")),
+
+        Data::Union(_) => Err(syn::Error::new_spanned(input, "unions are not supported")),
+    }
+}
+
+fn expand_derive_table_struct(
+    input: &DeriveInput,
+    fields: &Punctuated<Field, Comma>,
+) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+
+    let container_attributes = parse_container_attributes(&input.attrs)?;
+
+    let table = container_attributes.table.clone().ok_or_else(|| {
+        syn::Error::new_spanned(input, "expected #[sqlx(table = \"<table name>\")]")
+    })?;
+
+    let mut columns = Vec::new();
+
+    for field in fields {
+        let id = field.ident.as_ref().expect("named field");
+        let attributes = parse_child_attributes(&field.attrs)?;
+
+        if attributes.flatten {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`flatten` is not supported by `#[derive(Table)]`",
+            ));
+        }
+
+        let value = if attributes.json {
+            quote!(::sqlx::types::Json(&self.#id) as _)
+        } else {
+            quote!(self.#id)
+        };
+
+        columns.push(Column {
+            name: column_name(id, attributes.rename, &container_attributes),
+            primary_key: attributes.primary_key,
+            value,
+        });
+    }
+
+    // without a field marked as the primary key, use the one named `id`
+    if !columns.iter().any(|column| column.primary_key) {
+        if let Some(column) = columns.iter_mut().find(|column| column.name == "id") {
+            column.primary_key = true;
+        }
+    }
+
+    let (keys, values): (Vec<&Column>, Vec<&Column>) =
+        columns.iter().partition(|column| column.primary_key);
+
+    if keys.is_empty() {
+        return Err(syn::Error::new_spanned(
+            input,
+            "expected a field named `id` or marked #[sqlx(primary_key)]",
+        ));
+    }
+
+    if values.is_empty() {
+        return Err(syn::Error::new_spanned(
+            input,
+            "expected a field that is not part of the primary key",
+        ));
+    }
+
+    let insert_sql = |dialect: &Dialect| {
+        let names = columns.iter().map(|column| dialect.quote(&column.name));
+        let placeholders = (1..=columns.len()).map(dialect.placeholder);
+
+        format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            table,
+            names.collect::<Vec<_>>().join(", "),
+            placeholders.collect::<Vec<_>>().join(", "),
+        )
+    };
+
+    // the values are bound first, followed by the primary key
+    let update_sql = |dialect: &Dialect| {
+        let assignment = |column: &Column, i: usize| {
+            format!(
+                "{} = {}",
+                dialect.quote(&column.name),
+                (dialect.placeholder)(i)
+            )
+        };
+
+        let set = values
+            .iter()
+            .enumerate()
+            .map(|(i, column)| assignment(column, i + 1));
+
+        let filter = keys
+            .iter()
+            .enumerate()
+            .map(|(i, column)| assignment(column, values.len() + i + 1));
+
+        format!(
+            "UPDATE {} SET {} WHERE {}",
+            table,
+            set.collect::<Vec<_>>().join(", "),
+            filter.collect::<Vec<_>>().join(" AND "),
+        )
+    };
+
+    let dialects = dialects();
+
+    // the statements are checked by `query!()`, so they're written for the database it will use
+    let candidates: Vec<_> = dialects
+        .iter()
+        .map(|dialect| (dialect.name, insert_sql(dialect)))
+        .collect();
+
+    let name = crate::query::resolve_database(&candidates)
+        .map_err(|e| syn::Error::new_spanned(input, e))?;
+
+    let dialect = dialects
+        .iter()
+        .find(|dialect| dialect.name == name)
+        .expect("resolved database is one of the candidates");
+
+    let db_path = &dialect.db_path;
+    let insert_sql = insert_sql(dialect);
+    let update_sql = update_sql(dialect);
+
+    let insert_args = columns.iter().map(|column| &column.value);
+    let update_args = values.iter().chain(&keys).map(|column| &column.value);
+
+    let insert_doc = format!("Insert `self` as a new row of `{}`.", table);
+    let update_doc = format!(
+        "Update the row of `{}` with the primary key of `self` to the values of `self`.",
+        table
+    );
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote!(
+        #[automatically_derived]
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #[doc = #insert_doc]
+            pub async fn insert<'c, E>(
+                &self,
+                executor: E,
+            ) -> ::sqlx::Result<<#db_path as ::sqlx::Database>::QueryResult>
+            where
+                E: ::sqlx::Executor<'c, Database = #db_path>,
+            {
+                ::sqlx::sqlx_macros::expand_query!(source = #insert_sql, args = [#(#insert_args),*])
+                    .execute(executor)
+                    .await
+            }
+
+            #[doc = #update_doc]
+            pub async fn update_by_pk<'c, E>(
+                &self,
+                executor: E,
+            ) -> ::sqlx::Result<<#db_path as ::sqlx::Database>::QueryResult>
+            where
+                E: ::sqlx::Executor<'c, Database = #db_path>,
+            {
+                ::sqlx::sqlx_macros::expand_query!(source = #update_sql, args = [#(#update_args),*])
+                    .execute(executor)
+                    .await
+            }
+        }
+    ))
+}
@@ -78,13 +78,62 @@ pub fn derive_from_row(input: TokenStream) -> TokenStream {
     }
 }
 
+/// Generate `insert()` and `update_by_pk()` methods for a struct stored as a row of a table.
+///
+/// The table is named with `#[sqlx(table = "<name>")]`, and each field is stored in the column of
+/// the same name, following `#[sqlx(rename = "...")]` and `#[sqlx(rename_all = "...")]` as with
+/// `FromRow`. The primary key is the field named `id`, or else the fields marked
+/// `#[sqlx(primary_key)]`:
+///
+/// ```rust,ignore
+/// #[derive(sqlx::FromRow, sqlx::Table)]
+/// #[sqlx(table = "users")]
+/// struct User {
+///     id: i64,
+///     name: String,
+///     #[sqlx(json)]
+///     settings: Settings,
+/// }
+///
+/// let user = User { id: 1, name: "Alice".into(), settings: Settings::default() };
+///
+/// // INSERT INTO users ("id", "name", "settings") VALUES ($1, $2, $3)
+/// user.insert(&mut conn).await?;
+///
+/// // UPDATE users SET "name" = $1, "settings" = $2 WHERE "id" = $3
+/// user.update_by_pk(&mut conn).await?;
+/// ```
+///
+/// Both statements are checked with `query!()`, against the database at `DATABASE_URL` or the
+/// offline data, so the same requirements apply and a field whose type doesn't match its column
+/// fails to compile. The methods are written for that database, and take any executor for it.
+/// Fields with `#[sqlx(json)]` are bound as [`Json`][sqlx::types::Json], without checking their
+/// type.
+///
+/// The table name is inserted as-is, so it may include a schema or be quoted.
+#[proc_macro_derive(Table, attributes(sqlx))]
+pub fn derive_table(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+
+    match derives::expand_derive_table(&input) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
 #[cfg(feature = "migrate")]
 #[proc_macro]
 pub fn migrate(input: TokenStream) -> TokenStream {
-    use syn::LitStr;
+    use syn::parse::Parser;
+    use syn::punctuated::Punctuated;
+    use syn::{LitStr, Token};
 
-    let input = syn::parse_macro_input!(input as LitStr);
-    match migrate::expand_migrator_from_dir(input) {
+    let dirs = match Punctuated::<LitStr, Token![,]>::parse_terminated.parse(input) {
+        Ok(dirs) => dirs.into_iter().collect::<Vec<_>>(),
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    match migrate::expand_migrator_from_dirs(&dirs) {
         Ok(ts) => ts.into(),
         Err(e) => {
             if let Some(parse_err) = e.downcast_ref::<syn::Error>() {
@@ -97,21 +146,75 @@ pub fn migrate(input: TokenStream) -> TokenStream {
     }
 }
 
+/// Run an `async fn` as a test.
+///
+/// With `#[sqlx_macros::test(skip_if_no_db)]`, the test returns early instead of running if
+/// `DATABASE_URL` is not set (after loading `.env`, so the test crate must depend on `dotenv`),
+/// printing a message saying it was skipped.
 #[doc(hidden)]
 #[proc_macro_attribute]
-pub fn test(_attr: TokenStream, input: TokenStream) -> TokenStream {
+pub fn test(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let args = syn::parse_macro_input!(attr as syn::AttributeArgs);
     let input = syn::parse_macro_input!(input as syn::ItemFn);
 
+    let mut skip_if_no_db = false;
+
+    for arg in &args {
+        match arg {
+            syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("skip_if_no_db") => {
+                skip_if_no_db = true
+            }
+            arg => {
+                return syn::Error::new_spanned(arg, "expected `skip_if_no_db`")
+                    .to_compile_error()
+                    .into()
+            }
+        }
+    }
+
     let ret = &input.sig.output;
     let name = &input.sig.ident;
     let body = &input.block;
     let attrs = &input.attrs;
 
+    let skip = if skip_if_no_db {
+        let skipped = match ret {
+            syn::ReturnType::Default => quote!(()),
+            syn::ReturnType::Type(..) => {
+                quote!(::std::result::Result::Ok(::std::default::Default::default()))
+            }
+        };
+
+        quote! {
+            let _ = ::dotenv::dotenv();
+
+            if ::std::env::var_os("DATABASE_URL").is_none() {
+                // written directly to stderr as the output of `eprintln!()` is captured by the
+                // test harness and only shown for failing tests
+                let _ = ::std::io::Write::write_all(
+                    &mut ::std::io::stderr(),
+                    ::std::format!(
+                        "test {}::{} skipped: `DATABASE_URL` is not set\n",
+                        ::std::module_path!(),
+                        ::std::stringify!(#name),
+                    )
+                    .as_bytes(),
+                );
+
+                return #skipped;
+            }
+        }
+    } else {
+        quote!()
+    };
+
     let result = if cfg!(feature = "_rt-tokio") {
         quote! {
             #[test]
             #(#attrs)*
             fn #name() #ret {
+                #skip
+
                 ::sqlx_rt::tokio::runtime::Builder::new_multi_thread()
                     .enable_io()
                     .enable_time()
@@ -125,6 +228,8 @@ pub fn test(_attr: TokenStream, input: TokenStream) -> TokenStream {
             #[test]
             #(#attrs)*
             fn #name() #ret {
+                #skip
+
                 ::sqlx_rt::async_std::task::block_on(async { #body })
             }
         }
@@ -133,6 +238,8 @@ pub fn test(_attr: TokenStream, input: TokenStream) -> TokenStream {
             #[test]
             #(#attrs)*
             fn #name() #ret {
+                #skip
+
                 ::sqlx_rt::actix_rt::System::new()
                     .block_on(async { #body })
             }
@@ -1,10 +1,13 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::{quote, ToTokens, TokenStreamExt};
 use sha2::{Digest, Sha384};
-use sqlx_core::migrate::MigrationType;
+use sqlx_core::migrate::{MigrateError, MigrationHeaders, MigrationType};
 use std::fs;
+use std::path::Path;
 use syn::LitStr;
 
+use crate::common::CONFIG;
+
 pub struct QuotedMigrationType(MigrationType);
 
 impl ToTokens for QuotedMigrationType {
@@ -48,6 +51,7 @@ impl ToTokens for QuotedMigration {
                 checksum: ::std::borrow::Cow::Borrowed(&[
                     #(#checksum),*
                 ]),
+                code: ::std::option::Option::None,
             }
         };
 
@@ -55,9 +59,70 @@ impl ToTokens for QuotedMigration {
     }
 }
 
+/// Expand to a `Migrator` with the migrations of all of `dirs`, merged in order of version, or
+/// those of the directory set in `sqlx.toml` (`migrations` by default) if there are none.
+pub(crate) fn expand_migrator_from_dirs(dirs: &[LitStr]) -> crate::Result<TokenStream> {
+    let mut migrations: Vec<QuotedMigration> = Vec::new();
+
+    if dirs.is_empty() {
+        migrations = match &CONFIG.migrate.dir {
+            Some(dir) => read_migrations_in(&CONFIG.resolve(dir))?,
+            None => read_migrations(&LitStr::new("./migrations", Span::call_site()))?,
+        };
+    }
+
+    for dir in dirs {
+        let dir_migrations = read_migrations(dir)?;
+
+        for migration in &dir_migrations {
+            if migrations.iter().any(|m| m.version == migration.version) {
+                return Err(syn::Error::new(
+                    dir.span(),
+                    format!(
+                        "migration {} is defined in more than one of the migration directories",
+                        migration.version
+                    ),
+                )
+                .into());
+            }
+        }
+
+        migrations.extend(dir_migrations);
+    }
+
+    // ensure that we are sorted by `VERSION ASC`
+    migrations.sort_by_key(|m| m.version);
+
+    let table_name = match &CONFIG.migrate.table_name {
+        Some(table_name) => quote!(#table_name),
+        None => quote!(::sqlx::migrate::DEFAULT_TABLE_NAME),
+    };
+
+    Ok(quote! {
+        ::sqlx::migrate::Migrator {
+            migrations: ::std::borrow::Cow::Borrowed(&[
+                #(#migrations),*
+            ]),
+            ignore_missing: false,
+            allow_checksum_mismatch: ::std::borrow::Cow::Borrowed(&[]),
+            out_of_order: ::sqlx::migrate::OutOfOrder::ApplyMissing,
+            table_name: ::std::borrow::Cow::Borrowed(#table_name),
+            schema_dump: ::std::option::Option::None,
+            locking: true,
+            lock: ::sqlx::migrate::MigrationLock::new(),
+            hooks: ::sqlx::migrate::MigrationHooks::new(),
+        }
+    })
+}
+
 // mostly copied from sqlx-core/src/migrate/source.rs
-pub(crate) fn expand_migrator_from_dir(dir: LitStr) -> crate::Result<TokenStream> {
+fn read_migrations(dir: &LitStr) -> crate::Result<Vec<QuotedMigration>> {
     let path = crate::common::resolve_path(&dir.value(), dir.span())?;
+
+    read_migrations_in(&path)
+}
+
+fn read_migrations_in(path: &Path) -> crate::Result<Vec<QuotedMigration>> {
     let mut migrations = Vec::new();
 
     for entry in fs::read_dir(&path)? {
@@ -88,6 +153,9 @@ pub(crate) fn expand_migrator_from_dir(dir: LitStr) -> crate::Result<TokenStream
 
         let sql = fs::read_to_string(&entry.path())?;
 
+        // report invalid headers at compile time
+        MigrationHeaders::parse(&sql).map_err(|e| MigrateError::InvalidHeader(version, e))?;
+
         let checksum = Vec::from(Sha384::digest(sql.as_bytes()).as_slice());
 
         // canonicalize the path so we can pass it to `include_str!()`
@@ -111,9 +179,6 @@ pub(crate) fn expand_migrator_from_dir(dir: LitStr) -> crate::Result<TokenStream
         })
     }
 
-    // ensure that we are sorted by `VERSION ASC`
-    migrations.sort_by_key(|m| m.version);
-
     #[cfg(any(sqlx_macros_unstable, procmacro2_semver_exempt))]
     {
         let path = path.canonicalize()?;
@@ -127,12 +192,5 @@ pub(crate) fn expand_migrator_from_dir(dir: LitStr) -> crate::Result<TokenStream
         proc_macro::tracked_path::path(path);
     }
 
-    Ok(quote! {
-        ::sqlx::migrate::Migrator {
-            migrations: ::std::borrow::Cow::Borrowed(&[
-                #(#migrations),*
-            ]),
-            ignore_missing: false,
-        }
-    })
+    Ok(migrations)
 }
@@ -1,4 +1,4 @@
-use crate::database::DatabaseExt;
+use crate::database::{DatabaseExt, InListStyle};
 use crate::query::QueryMacroInput;
 use either::Either;
 use proc_macro2::TokenStream;
@@ -25,11 +25,24 @@ pub fn quote_args<DB: DatabaseExt>(
         .map(|i| format_ident!("arg{}", i))
         .collect::<Vec<_>>();
 
-    let arg_name = &arg_names;
-    let arg_expr = input.arg_exprs.iter().cloned().map(strip_wildcard);
+    let arg_bindings = input
+        .arg_exprs
+        .iter()
+        .cloned()
+        .map(strip_wildcard)
+        .zip(arg_names.iter())
+        .enumerate()
+        .map(|(i, (expr, name))| {
+            if input.in_list_args.contains(&i) {
+                // accept anything that can be sliced, e.g. arrays and `Vec`s
+                quote!(let #name = &(#expr)[..];)
+            } else {
+                quote!(let #name = &(#expr);)
+            }
+        });
 
     let arg_bindings = quote! {
-        #(let #arg_name = &(#arg_expr);)*
+        #(#arg_bindings)*
     };
 
     let args_check = match info.parameters() {
@@ -73,13 +86,20 @@ pub fn quote_args<DB: DatabaseExt>(
                         }
                     };
 
+                    // for lists, the parameter type is the type of an element
+                    let value = if input.in_list_args.contains(&i) {
+                        quote!(&#name[0])
+                    } else {
+                        quote!(#name)
+                    };
+
                     Ok(quote_spanned!(expr.span() =>
                         // this shouldn't actually run
                         if false {
                             use ::sqlx::ty_match::{WrapSameExt as _, MatchBorrowExt as _};
 
                             // evaluate the expression only once in case it contains moves
-                            let expr = ::sqlx::ty_match::dupe_value(#name);
+                            let expr = ::sqlx::ty_match::dupe_value(#value);
 
                             // if `expr` is `Option<T>`, get `Option<$ty>`, otherwise `$ty`
                             let ty_check = ::sqlx::ty_match::WrapSame::<#param_ty, _>::new(&expr).wrap_same();
@@ -100,6 +120,36 @@ pub fn quote_args<DB: DatabaseExt>(
 
     let args_count = input.arg_exprs.len();
 
+    // with `InListStyle::Placeholders`, each element of a list is bound as its own argument
+    let expand_list = |i: usize| {
+        input.in_list_args.contains(&i) && DB::IN_LIST_STYLE == InListStyle::Placeholders
+    };
+
+    let size_hints = arg_names.iter().enumerate().map(|(i, name)| {
+        if expand_list(i) {
+            quote! {
+                #name.iter().map(::sqlx::encode::Encode::<#db_path>::size_hint).sum::<usize>()
+            }
+        } else if input.in_list_args.contains(&i) {
+            // the list is bound as a slice
+            quote!(::sqlx::encode::Encode::<#db_path>::size_hint(&#name))
+        } else {
+            quote!(::sqlx::encode::Encode::<#db_path>::size_hint(#name))
+        }
+    });
+
+    let add_args = arg_names.iter().enumerate().map(|(i, name)| {
+        if expand_list(i) {
+            quote! {
+                for value in ::sqlx::in_list::padded(#name) {
+                    query_args.add(value);
+                }
+            }
+        } else {
+            quote!(query_args.add(#name);)
+        }
+    });
+
     Ok(quote! {
         #arg_bindings
 
@@ -108,9 +158,9 @@ pub fn quote_args<DB: DatabaseExt>(
         let mut query_args = <#db_path as ::sqlx::database::HasArguments>::Arguments::default();
         query_args.reserve(
             #args_count,
-            0 #(+ ::sqlx::encode::Encode::<#db_path>::size_hint(#arg_name))*
+            0 #(+ #size_hints)*
         );
-        #(query_args.add(#arg_name);)*
+        #(#add_args)*
     })
 }
 
@@ -0,0 +1,69 @@
+//! Support for `query_batch!()`, which takes a script of several statements separated by
+//! semicolons.
+//!
+//! Each statement is checked against the database (or found in the offline data) on its own, as
+//! databases can only describe one statement at a time. The script is then executed as a whole,
+//! without bind parameters, which lets the database run it in a single round-trip.
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use sqlx_core::query_batch::split_statements;
+
+use crate::query::input::RecordType;
+use crate::query::QueryMacroInput;
+
+/// Check each statement of the batch with `expand_statement()`, which returns the path of the
+/// database type the statement was checked against.
+pub fn expand_batch(
+    input: QueryMacroInput,
+    mut expand_statement: impl FnMut(QueryMacroInput) -> crate::Result<TokenStream>,
+) -> crate::Result<TokenStream> {
+    let statements = split_statements(&input.sql);
+
+    let mut db_path = None;
+
+    for (i, statement) in statements.iter().enumerate() {
+        let statement_input = QueryMacroInput {
+            sql: (*statement).to_owned(),
+            src_span: input.src_span,
+            record_type: RecordType::Batch,
+            arg_exprs: Vec::new(),
+            in_list_args: Vec::new(),
+            checked: input.checked,
+            file_path: None,
+            const_refs: Vec::new(),
+            db: input.db.clone(),
+        };
+
+        let path = expand_statement(statement_input)
+            .map_err(|e| format!("in statement #{} of the batch: {}", i + 1, e))?;
+
+        db_path = Some(path);
+    }
+
+    let db_path = db_path.ok_or("the batch does not contain any statements")?;
+
+    // if the batch came from a file, use `include_str!()` to tell the compiler where it came from
+    let sql = if let Some(ref path) = input.file_path {
+        quote_spanned! { input.src_span => include_str!(#path) }
+    } else {
+        let sql = &input.sql;
+        quote! { #sql }
+    };
+
+    let const_refs = &input.const_refs;
+    let lint_tokens = super::lint::quote_lints(&input)?;
+
+    Ok(quote! {
+        {
+            #[allow(clippy::all)]
+            {
+                #(let _: &str = #const_refs;)*
+
+                #lint_tokens
+
+                ::sqlx::query_batch::<#db_path>(#sql)
+            }
+        }
+    })
+}
@@ -0,0 +1,636 @@
+//! Information about the crate being compiled that proc macros can't otherwise get, found by
+//! parsing its sources.
+//!
+//! This is used for:
+//!
+//! * Evaluating query strings assembled from constants, e.g.
+//!   `query!(concat!(SELECT_BASE, " WHERE id = $1"), id)`. Proc macros are expanded before
+//!   constants can be evaluated, so to resolve a `const` we look for its definition. Only `const`
+//!   items (including associated constants) defined in this crate are found, and they must
+//!   themselves be built from string literals, `concat!()`/`concatcp!()` and other such
+//!   constants.
+//! * Checking whether the output struct of `query_as!()` can be built from a struct literal with
+//!   the columns of the query as fields.
+//! * Finding where a query is written, for the query manifest, as proc macros can't tell the line
+//!   of their input on stable.
+
+#[cfg(feature = "offline")]
+use std::cmp;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use once_cell::sync::Lazy;
+use quote::ToTokens;
+use syn::punctuated::Punctuated;
+use syn::{
+    Attribute, BinOp, Expr, ExprBinary, ExprLit, ExprPath, ImplItem, Item, ItemStruct, Lit, Token,
+    Type,
+};
+
+#[derive(Default)]
+struct CrateItems {
+    /// The definitions of `const` items, by name.
+    ///
+    /// Stored as strings because `syn` types can't be shared between threads.
+    consts: HashMap<String, Vec<String>>,
+    /// For each definition of a struct, by name, the names of its fields (empty for tuple and
+    /// unit structs), or `None` if it or its fields have `#[sqlx(...)]` attributes.
+    structs: HashMap<String, Vec<Option<Vec<String>>>>,
+}
+
+impl CrateItems {
+    fn extend(&mut self, other: &CrateItems) {
+        for (name, defs) in &other.consts {
+            self.consts
+                .entry(name.clone())
+                .or_default()
+                .extend(defs.iter().cloned());
+        }
+
+        for (name, defs) in &other.structs {
+            self.structs
+                .entry(name.clone())
+                .or_default()
+                .extend(defs.iter().cloned());
+        }
+    }
+}
+
+/// What was read from each source file, along with the time the file was modified when it was
+/// read, so it can be read again when it changes.
+type FileCache<T> = Mutex<HashMap<PathBuf, (SystemTime, Arc<T>)>>;
+
+/// The items defined in each source file.
+static ITEMS_CACHE: Lazy<FileCache<CrateItems>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The string literals in a source file of the crate with the line they are on.
+#[cfg(feature = "offline")]
+type FileLiterals = Vec<(String, usize)>;
+
+#[cfg(feature = "offline")]
+static LITERALS_CACHE: Lazy<FileCache<FileLiterals>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Sanity limit for constants referring to other constants.
+const MAX_DEPTH: usize = 32;
+
+/// The directories searched for items, relative to `CARGO_MANIFEST_DIR`.
+const SOURCE_DIRS: &[&str] = &["src", "tests", "examples", "benches"];
+
+/// Evaluate the expression given as the query source to a string.
+///
+/// Also returns the paths of the constants it refers to directly, so the expansion can reference
+/// them; this lets the compiler check that they resolve and keeps them from being reported as
+/// unused.
+pub fn eval_query_source(expr: &Expr) -> syn::Result<(String, Vec<ExprPath>)> {
+    let mut const_refs = Vec::new();
+    let value = eval(expr, 0, &mut const_refs)?;
+
+    Ok((value, const_refs))
+}
+
+fn eval(expr: &Expr, depth: usize, const_refs: &mut Vec<ExprPath>) -> syn::Result<String> {
+    if depth > MAX_DEPTH {
+        return Err(syn::Error::new_spanned(
+            expr,
+            "recursion limit reached while evaluating the query string",
+        ));
+    }
+
+    match expr {
+        Expr::Lit(ExprLit { lit, .. }) => eval_lit(lit),
+
+        Expr::Group(group) => eval(&group.expr, depth, const_refs),
+
+        Expr::Paren(paren) => eval(&paren.expr, depth, const_refs),
+
+        // `"SELECT ..." + "FROM ..."` is accepted for backwards compatibility
+        Expr::Binary(ExprBinary {
+            left,
+            op: BinOp::Add(_),
+            right,
+            ..
+        }) => Ok(eval(left, depth, const_refs)? + &eval(right, depth, const_refs)?),
+
+        Expr::Macro(mac) if is_concat(&mac.mac.path) => {
+            let parts = mac
+                .mac
+                .parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)?;
+
+            parts
+                .iter()
+                .map(|part| eval(part, depth, const_refs))
+                .collect()
+        }
+
+        Expr::Path(path) if path.qself.is_none() => {
+            let name = &path
+                .path
+                .segments
+                .last()
+                .expect("BUG: path without segments")
+                .ident;
+
+            if depth == 0 {
+                const_refs.push(path.clone());
+            }
+
+            eval_const(expr, &name.to_string(), depth)
+        }
+
+        _ => Err(syn::Error::new_spanned(
+            expr,
+            "expected a string literal, `concat!()`, or a `const` string defined in this crate",
+        )),
+    }
+}
+
+fn eval_lit(lit: &Lit) -> syn::Result<String> {
+    match lit {
+        Lit::Str(s) => Ok(s.value()),
+        // the same as what `concat!()` accepts
+        Lit::Char(c) => Ok(c.value().to_string()),
+        Lit::Int(i) => Ok(i.base10_digits().to_owned()),
+        Lit::Float(f) => Ok(f.base10_digits().to_owned()),
+        Lit::Bool(b) => Ok(b.value.to_string()),
+        _ => Err(syn::Error::new_spanned(lit, "expected a string literal")),
+    }
+}
+
+fn is_concat(path: &syn::Path) -> bool {
+    path.segments.last().map_or(false, |seg| {
+        seg.ident == "concat" || seg.ident == "concatcp"
+    })
+}
+
+/// Check if a struct named `name` in this crate has exactly the fields `fields`, in any order,
+/// and no `#[sqlx(...)]` attributes changing how they're read.
+///
+/// Returns `true` if there is no such struct, e.g. because it is defined in another crate, as
+/// there's no telling.
+pub fn may_have_fields(name: &str, fields: &[String]) -> crate::Result<bool> {
+    let items = load_items()?;
+
+    let defs = match items.structs.get(name) {
+        Some(defs) => defs,
+        None => return Ok(true),
+    };
+
+    let mut fields: Vec<&str> = fields.iter().map(|field| unraw(field)).collect();
+    fields.sort_unstable();
+
+    Ok(defs.iter().flatten().any(|def| {
+        let mut def: Vec<&str> = def.iter().map(|field| unraw(field)).collect();
+        def.sort_unstable();
+
+        def == fields
+    }))
+}
+
+fn unraw(ident: &str) -> &str {
+    ident.trim_start_matches("r#")
+}
+
+/// Find the file (relative to `CARGO_MANIFEST_DIR`) and line of the string literal in this crate
+/// with the value `value`.
+///
+/// Returns `None` if there is no such literal, e.g. because the query is built from constants, or
+/// if there are several, as we can't tell which one is being expanded.
+#[cfg(feature = "offline")]
+pub fn locate_literal(value: &str) -> crate::Result<Option<(PathBuf, usize)>> {
+    let manifest_dir = &super::METADATA.manifest_dir;
+
+    let mut cache = LITERALS_CACHE
+        .lock()
+        // Just reset the cache on error
+        .unwrap_or_else(|poison_err| {
+            let mut guard = poison_err.into_inner();
+            *guard = HashMap::new();
+            guard
+        });
+
+    let mut locations = Vec::new();
+
+    for dir in SOURCE_DIRS {
+        for path in source_files(&manifest_dir.join(dir))? {
+            let modified = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+
+            let literals = match cache.get(&path) {
+                Some((scanned, literals)) if *scanned == modified => literals.clone(),
+                _ => {
+                    let source = fs::read_to_string(&path)
+                        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+
+                    let literals = Arc::new(string_literals(&source));
+                    cache.insert(path.clone(), (modified, literals.clone()));
+                    literals
+                }
+            };
+
+            let relative = path.strip_prefix(manifest_dir).unwrap_or(&path);
+
+            locations.extend(
+                literals
+                    .iter()
+                    .filter(|(literal, _)| literal == value)
+                    .map(|(_, line)| (relative.to_owned(), *line)),
+            );
+        }
+    }
+
+    Ok(match locations.as_slice() {
+        [location] => Some(location.clone()),
+        _ => None,
+    })
+}
+
+fn eval_const(expr: &Expr, name: &str, depth: usize) -> syn::Result<String> {
+    let items = load_items().map_err(|e| syn::Error::new_spanned(expr, e))?;
+
+    let defs = items.consts.get(name).ok_or_else(|| {
+        syn::Error::new_spanned(
+            expr,
+            format!(
+                "could not find a definition of `const {}` in this crate; \
+                 only constants defined in the same crate can be used in query strings",
+                name
+            ),
+        )
+    })?;
+
+    let mut value: Option<String> = None;
+
+    for def in defs {
+        let def_value = eval(&syn::parse_str(def)?, depth + 1, &mut Vec::new())?;
+
+        match value {
+            Some(ref value) if *value != def_value => {
+                return Err(syn::Error::new_spanned(
+                    expr,
+                    format!(
+                        "found several definitions of `const {}` with different values",
+                        name
+                    ),
+                ))
+            }
+            _ => value = Some(def_value),
+        }
+    }
+
+    Ok(value.expect("BUG: const without definitions"))
+}
+
+fn load_items() -> crate::Result<CrateItems> {
+    let manifest_dir = &super::METADATA.manifest_dir;
+
+    let mut cache = ITEMS_CACHE
+        .lock()
+        // Just reset the cache on error
+        .unwrap_or_else(|poison_err| {
+            let mut guard = poison_err.into_inner();
+            *guard = HashMap::new();
+            guard
+        });
+
+    let mut items = CrateItems::default();
+
+    for dir in SOURCE_DIRS {
+        for path in source_files(&manifest_dir.join(dir))? {
+            let modified = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+
+            let file_items = match cache.get(&path) {
+                Some((parsed, file_items)) if *parsed == modified => file_items.clone(),
+                _ => {
+                    let file_items = Arc::new(parse_items(&path)?);
+                    cache.insert(path.clone(), (modified, file_items.clone()));
+                    file_items
+                }
+            };
+
+            items.extend(&file_items);
+        }
+    }
+
+    Ok(items)
+}
+
+fn parse_items(path: &Path) -> crate::Result<CrateItems> {
+    let source = fs::read_to_string(path)
+        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+
+    let mut items = CrateItems::default();
+
+    // files that don't parse can't be compiled either, so they can't define our items
+    if let Ok(file) = syn::parse_file(&source) {
+        collect_items(&file.items, &mut items);
+    }
+
+    Ok(items)
+}
+
+/// The Rust source files in `dir` and its subdirectories.
+fn source_files(dir: &Path) -> crate::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    if !dir.is_dir() {
+        return Ok(files);
+    }
+
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("failed to read {}: {}", dir.display(), e))?;
+
+    for entry in entries {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            files.extend(source_files(&path)?);
+        } else if path.extension().map_or(false, |ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+
+    Ok(files)
+}
+
+/// The values of the string literals in Rust source code, with the (1-based) line each starts on.
+///
+/// This is a scan of the tokens rather than a parse, as `syn` can't give the lines of its spans
+/// inside a proc macro on stable. Byte strings are included, and anything unexpected is skipped.
+#[cfg(feature = "offline")]
+fn string_literals(source: &str) -> Vec<(String, usize)> {
+    let bytes = source.as_bytes();
+    let mut literals = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+
+    // the index of the closing quote of a raw string whose opening quote is at `start`, with
+    // `hashes` hashes
+    let raw_end = |start: usize, hashes: usize| {
+        let closing = format!("\"{}", "#".repeat(hashes));
+        source[start + 1..]
+            .find(&closing)
+            .map(|len| start + 1 + len)
+    };
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\n' => {
+                line += 1;
+                i += 1;
+            }
+
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = source[i..].find('\n').map_or(bytes.len(), |len| i + len);
+            }
+
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let end = source[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |len| i + 2 + len + 2);
+                line += source[i..end].matches('\n').count();
+                i = end;
+            }
+
+            // a char literal, or else a lifetime
+            b'\'' => {
+                let end = if bytes.get(i + 1) == Some(&b'\\') {
+                    source[i + 2..].find('\'').map(|len| i + 2 + len + 1)
+                } else {
+                    source[i + 1..]
+                        .chars()
+                        .next()
+                        .map(|c| i + 1 + c.len_utf8())
+                        .filter(|&end| bytes.get(end) == Some(&b'\''))
+                        .map(|end| end + 1)
+                };
+
+                i = end.unwrap_or(i + 1);
+            }
+
+            // an identifier, or the prefix of a raw or byte string
+            b if is_ident_byte(b) => {
+                let start = i;
+
+                while i < bytes.len() && is_ident_byte(bytes[i]) {
+                    i += 1;
+                }
+
+                let prefix = &source[start..i];
+                let hashes = bytes[i..].iter().take_while(|&&b| b == b'#').count();
+
+                if (prefix == "r" || prefix == "br") && bytes.get(i + hashes) == Some(&b'"') {
+                    let start = i + hashes;
+                    let end = raw_end(start, hashes).unwrap_or(bytes.len());
+
+                    literals.push((source[start + 1..end].to_owned(), line));
+                    line += source[start..end].matches('\n').count();
+                    i = cmp::min(end + 1 + hashes, bytes.len());
+                }
+            }
+
+            b'"' => {
+                let start_line = line;
+                let mut value = String::new();
+                let mut chars = source[i + 1..].char_indices();
+                let mut end = bytes.len();
+
+                while let Some((offset, c)) = chars.next() {
+                    match c {
+                        '"' => {
+                            end = i + 1 + offset + 1;
+                            break;
+                        }
+                        '\\' => match chars.next() {
+                            Some((_, 'n')) => value.push('\n'),
+                            Some((_, 'r')) => value.push('\r'),
+                            Some((_, 't')) => value.push('\t'),
+                            Some((_, '0')) => value.push('\0'),
+                            Some((_, 'x')) => {
+                                let hex: String = chars.by_ref().take(2).map(|(_, c)| c).collect();
+                                value.extend(u8::from_str_radix(&hex, 16).ok().map(char::from));
+                            }
+                            Some((_, 'u')) => {
+                                let hex: String = chars
+                                    .by_ref()
+                                    .map(|(_, c)| c)
+                                    .take_while(|&c| c != '}')
+                                    .filter(|&c| c != '{')
+                                    .collect();
+                                value.extend(
+                                    u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32),
+                                );
+                            }
+                            // a line continuation skips the newline and any leading whitespace
+                            Some((_, '\n')) => {
+                                line += 1;
+
+                                let mut rest = chars.clone();
+                                while let Some((_, c)) = rest.next() {
+                                    if !c.is_whitespace() {
+                                        break;
+                                    }
+
+                                    if c == '\n' {
+                                        line += 1;
+                                    }
+
+                                    chars = rest.clone();
+                                }
+                            }
+                            Some((_, c)) => value.push(c),
+                            None => {}
+                        },
+                        '\n' => {
+                            line += 1;
+                            value.push(c);
+                        }
+                        c => value.push(c),
+                    }
+                }
+
+                literals.push((value, start_line));
+                i = end;
+            }
+
+            _ => i += 1,
+        }
+    }
+
+    literals
+}
+
+#[cfg(feature = "offline")]
+fn is_ident_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+fn collect_items(items: &[Item], crate_items: &mut CrateItems) {
+    for item in items {
+        match item {
+            Item::Const(item) if is_str_ref(&item.ty) => {
+                crate_items
+                    .consts
+                    .entry(item.ident.to_string())
+                    .or_default()
+                    .push(item.expr.to_token_stream().to_string());
+            }
+            Item::Struct(item) => {
+                crate_items
+                    .structs
+                    .entry(item.ident.to_string())
+                    .or_default()
+                    .push(struct_fields(item));
+            }
+            Item::Mod(item) => {
+                if let Some((_, items)) = &item.content {
+                    collect_items(items, crate_items);
+                }
+            }
+            Item::Impl(item) => {
+                for item in &item.items {
+                    match item {
+                        ImplItem::Const(item) if is_str_ref(&item.ty) => crate_items
+                            .consts
+                            .entry(item.ident.to_string())
+                            .or_default()
+                            .push(item.expr.to_token_stream().to_string()),
+                        _ => (),
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// The names of the fields of a struct, or `None` if it or its fields have `#[sqlx(...)]`
+/// attributes.
+fn struct_fields(item: &ItemStruct) -> Option<Vec<String>> {
+    let is_sqlx = |attr: &Attribute| attr.path.is_ident("sqlx");
+
+    if item.attrs.iter().any(is_sqlx)
+        || item
+            .fields
+            .iter()
+            .any(|field| field.attrs.iter().any(is_sqlx))
+    {
+        return None;
+    }
+
+    Some(
+        item.fields
+            .iter()
+            .filter_map(|field| field.ident.as_ref())
+            .map(|ident| ident.to_string())
+            .collect(),
+    )
+}
+
+/// Check if the type of a `const` is `&str` (with any lifetime).
+fn is_str_ref(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => {
+            matches!(&*reference.elem, Type::Path(path) if path.path.is_ident("str"))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_evaluates_literals() {
+        let eval_str = |s| eval_query_source(&syn::parse_str(s).unwrap()).unwrap().0;
+
+        assert_eq!(eval_str(r#""SELECT 1""#), "SELECT 1");
+        assert_eq!(eval_str(r#""SELECT " + "1""#), "SELECT 1");
+        assert_eq!(
+            eval_str(r#"concat!("SELECT ", 1, concat!(" AS ", "one"))"#),
+            "SELECT 1 AS one"
+        );
+        assert_eq!(
+            eval_str(r#"const_format::concatcp!("SELECT ", 1)"#),
+            "SELECT 1"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "offline")]
+    fn it_finds_string_literals() {
+        let source = r##"
+            // "a comment"
+            fn main() {
+                let c = '"';
+                let s: &'static str = "SELECT 1 \
+                    FROM t\n";
+                let r = r#"SELECT "id"
+                           FROM t"#;
+            }
+        "##;
+
+        assert_eq!(
+            string_literals(source),
+            [
+                ("SELECT 1 FROM t\n".to_owned(), 5),
+                (
+                    "SELECT \"id\"\n                           FROM t".to_owned(),
+                    7
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_rejects_other_expressions() {
+        let expr = syn::parse_str(r#"format!("SELECT {}", 1)"#).unwrap();
+        assert!(eval_query_source(&expr).is_err());
+    }
+}
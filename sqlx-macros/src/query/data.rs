@@ -50,6 +50,7 @@ pub mod offline {
 
     use once_cell::sync::Lazy;
     use proc_macro2::Span;
+    use sqlx_build::query_data;
     use sqlx_core::describe::Describe;
 
     static OFFLINE_DATA_CACHE: Lazy<Mutex<BTreeMap<PathBuf, OfflineData>>> =
@@ -81,17 +82,65 @@ pub mod offline {
 
     #[derive(serde::Deserialize)]
     pub struct DynQueryData {
-        #[serde(skip)]
         pub db_name: String,
         pub query: String,
         pub describe: serde_json::Value,
-        #[serde(skip)]
         pub hash: String,
     }
 
     impl DynQueryData {
+        /// Find and deserialize the data for this query from its own file in a `.sqlx` directory,
+        /// as written by `cargo sqlx prepare`. The file is named after the SHA-256 hash of the
+        /// query in hex.
+        pub fn from_query_dir(dir: impl AsRef<Path>, query: &str) -> crate::Result<Self> {
+            let path = query_data::query_file(dir.as_ref(), &hash_string(query));
+
+            let contents = match fs::read_to_string(&path) {
+                Ok(contents) => contents,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    return Err(format!(
+                        "failed to find data for query {} in {}; \
+                         `cargo sqlx prepare` may need to be rerun",
+                        query,
+                        dir.as_ref().display()
+                    )
+                    .into())
+                }
+                Err(e) => {
+                    return Err(format!("failed to read path {}: {}", path.display(), e).into())
+                }
+            };
+
+            let query_data: DynQueryData = serde_json::from_str(&contents)
+                .map_err(|e| format!("failed to parse {}: {}", path.display(), e))?;
+
+            if query != query_data.query {
+                return Err(format!(
+                    "hash collision for stored queries:\n{:?}\n{:?}",
+                    query, query_data.query
+                )
+                .into());
+            }
+
+            #[cfg(procmacro2_semver_exempt)]
+            {
+                let path = path.canonicalize()?;
+                let path = path.to_str().ok_or_else(|| {
+                    format!(
+                        "query data path cannot be represented as a string: {:?}",
+                        path
+                    )
+                })?;
+
+                proc_macro::tracked_path::path(path);
+            }
+
+            Ok(query_data)
+        }
+
         /// Find and deserialize the data table for this query from a shared `sqlx-data.json`
-        /// file. The expected structure is a JSON map keyed by the SHA-256 hash of queries in hex.
+        /// file, as written by older versions of `cargo sqlx prepare`. The expected structure is
+        /// a JSON map keyed by the SHA-256 hash of queries in hex.
         pub fn from_data_file(path: impl AsRef<Path>, query: &str) -> crate::Result<Self> {
             let path = path.as_ref();
 
@@ -132,7 +181,7 @@ pub mod offline {
                 query_data
             };
 
-            #[cfg(procmacr2_semver_exempt)]
+            #[cfg(procmacro2_semver_exempt)]
             {
                 let path = path.as_ref().canonicalize()?;
                 let path = path.to_str().ok_or_else(|| {
@@ -175,19 +224,24 @@ pub mod offline {
         }
 
         pub fn save_in(&self, dir: impl AsRef<Path>, input_span: Span) -> crate::Result<()> {
-            // we save under the hash of the span representation because that should be unique
-            // per invocation
-            let path = dir.as_ref().join(format!(
-                "query-{}.json",
-                hash_string(&format!("{:?}", input_span))
-            ));
+            // we save under the hash of the span representation and the query because that should
+            // be unique per invocation, and per statement of a batch
+            let path = query_data::query_file(
+                dir.as_ref(),
+                &hash_string(&format!("{:?}{}", input_span, self.query)),
+            );
+
+            // `cargo sqlx prepare` copies this to `.sqlx` as-is, so it needs to say which database
+            // it's for
+            let mut data = serde_json::to_value(self)?;
+            data["db_name"] = DB::NAME.into();
 
             serde_json::to_writer_pretty(
                 BufWriter::new(
                     File::create(&path)
                         .map_err(|e| format!("failed to open path {}: {}", path.display(), e))?,
                 ),
-                self,
+                &data,
             )
             .map_err(Into::into)
         }
@@ -0,0 +1,206 @@
+//! Opt-in check of the plan of the queries in `query!()` and friends, enabled by setting
+//! `SQLX_EXPLAIN_LINT=1`.
+//!
+//! During expansion the query is explained with the database at `DATABASE_URL`, and a warning is
+//! emitted for each sequential scan over a table with more rows than `SQLX_EXPLAIN_LINT_ROWS`
+//! (1000 by default), as estimated from the statistics of the planner. Like the lints in
+//! [`super::lint`], these are reported as deprecation warnings on the query string.
+//!
+//! Only Postgres is supported. The generic plan of the query is explained, the same one used when
+//! a prepared statement is executed with arbitrary parameters, so the plan doesn't depend on the
+//! values bound.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote_spanned};
+use sqlx_core::executor::Executor;
+use sqlx_core::postgres::PgConnection;
+use sqlx_core::row::Row;
+
+use crate::query::QueryMacroInput;
+
+const DEFAULT_MAX_ROWS: f64 = 1000.0;
+
+/// A sequential scan found in a query plan.
+#[derive(Debug, PartialEq)]
+struct SeqScan {
+    /// The name of the table, qualified with its schema.
+    table: String,
+    /// The number of rows the planner estimates the scan to return.
+    rows: f64,
+}
+
+/// Returns the tokens emitting a warning for each sequential scan over a large table in the plan
+/// of the query, if enabled. Errors from the database are ignored, as not every statement can be
+/// explained.
+pub async fn quote_explain_lint(
+    conn: &mut PgConnection,
+    input: &QueryMacroInput,
+    num_params: usize,
+) -> crate::Result<TokenStream> {
+    let enabled = super::env("SQLX_EXPLAIN_LINT")
+        .map(|s| s.eq_ignore_ascii_case("true") || s == "1")
+        .unwrap_or(false);
+
+    if !enabled {
+        return Ok(TokenStream::new());
+    }
+
+    let max_rows = match super::env("SQLX_EXPLAIN_LINT_ROWS") {
+        Ok(rows) => rows.trim().parse::<f64>().map_err(|_| {
+            format!(
+                "expected a number of rows in `SQLX_EXPLAIN_LINT_ROWS`, got {:?}",
+                rows
+            )
+        })?,
+        Err(_) => DEFAULT_MAX_ROWS,
+    };
+
+    let scans = match explain(conn, &input.sql, num_params).await {
+        Ok(scans) => scans,
+        Err(_) => return Ok(TokenStream::new()),
+    };
+
+    let span = input.src_span;
+    let mut tokens = TokenStream::new();
+
+    for scan in scans {
+        // the estimate of the planner is only as good as the statistics of the table
+        let rows = match table_rows(conn, &scan.table).await {
+            Ok(rows) if rows >= 0.0 => rows,
+            _ => scan.rows,
+        };
+
+        if rows <= max_rows {
+            continue;
+        }
+
+        let name = format_ident!("sqlx_explain_seq_scan", span = span);
+        let note = format!(
+            "the query plan has a sequential scan over `{}` (about {} rows); \
+             consider adding an index (`SQLX_EXPLAIN_LINT`)",
+            scan.table, rows as u64
+        );
+
+        tokens.extend(quote_spanned! { span =>
+            {
+                #[deprecated(note = #note)]
+                #[allow(non_upper_case_globals)]
+                const #name: () = ();
+                let _ = #name;
+            }
+        });
+    }
+
+    Ok(tokens)
+}
+
+/// Explain the generic plan of `sql` and find the sequential scans in it.
+async fn explain(
+    conn: &mut PgConnection,
+    sql: &str,
+    num_params: usize,
+) -> crate::Result<Vec<SeqScan>> {
+    (&mut *conn)
+        .execute(&*format!("PREPARE _sqlx_explain_lint AS {}", sql))
+        .await?;
+
+    let params = vec!["NULL"; num_params].join(", ");
+    let explain = if params.is_empty() {
+        "EXPLAIN (VERBOSE) EXECUTE _sqlx_explain_lint".to_owned()
+    } else {
+        format!("EXPLAIN (VERBOSE) EXECUTE _sqlx_explain_lint({})", params)
+    };
+
+    // the connection is cached, so the setting has to be restored afterwards
+    let plan = async {
+        (&mut *conn)
+            .execute("SET plan_cache_mode = force_generic_plan")
+            .await?;
+
+        // sent as a simple query so it isn't prepared itself
+        let rows = (&mut *conn).fetch_all(&*explain).await?;
+
+        rows.iter()
+            .map(|row| row.try_get::<String, _>(0))
+            .collect::<Result<Vec<_>, _>>()
+    }
+    .await;
+
+    (&mut *conn)
+        .execute("RESET plan_cache_mode; DEALLOCATE _sqlx_explain_lint")
+        .await?;
+
+    Ok(plan?
+        .iter()
+        .filter_map(|line| parse_seq_scan(line))
+        .collect())
+}
+
+/// The number of rows in `table` according to its statistics, or `-1` if it has never been
+/// analyzed.
+async fn table_rows(conn: &mut PgConnection, table: &str) -> crate::Result<f64> {
+    let rows = sqlx_core::query_scalar::query_scalar::<_, f64>(
+        "SELECT reltuples::float8 FROM pg_class WHERE oid = to_regclass($1)",
+    )
+    .bind(table)
+    .fetch_optional(conn)
+    .await?;
+
+    Ok(rows.unwrap_or(-1.0))
+}
+
+/// Parse a line of a plan in the text format of `EXPLAIN (VERBOSE)`, e.g.
+/// `  ->  Seq Scan on public.users u  (cost=0.00..35.50 rows=2550 width=36)`.
+fn parse_seq_scan(line: &str) -> Option<SeqScan> {
+    let line = line.trim_start().trim_start_matches("->").trim_start();
+
+    let rest = line
+        .strip_prefix("Seq Scan on ")
+        .or_else(|| line.strip_prefix("Parallel Seq Scan on "))?;
+
+    let (target, estimate) = rest.split_once("  (")?;
+    let table = target.split(' ').next()?;
+
+    let rows = estimate
+        .split(' ')
+        .find_map(|part| part.strip_prefix("rows="))?
+        .parse()
+        .ok()?;
+
+    Some(SeqScan {
+        table: table.to_owned(),
+        rows,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_seq_scans() {
+        assert_eq!(
+            parse_seq_scan("Seq Scan on public.tweet  (cost=0.00..22.70 rows=1270 width=56)"),
+            Some(SeqScan {
+                table: "public.tweet".into(),
+                rows: 1270.0
+            })
+        );
+
+        assert_eq!(
+            parse_seq_scan(
+                "        ->  Parallel Seq Scan on public.users u  (cost=0.00..8.50 rows=5 width=4)"
+            ),
+            Some(SeqScan {
+                table: "public.users".into(),
+                rows: 5.0
+            })
+        );
+
+        assert_eq!(
+            parse_seq_scan("  ->  Index Scan using tweet_pkey on public.tweet  (cost=0.15..8.17 rows=1 width=56)"),
+            None
+        );
+        assert_eq!(parse_seq_scan("        Output: id, text"), None);
+    }
+}
@@ -0,0 +1,309 @@
+//! Support for `{in: <expr>}` list arguments, e.g.
+//! `query!("SELECT * FROM users WHERE id IN (?)", {in: ids})`.
+//!
+//! The query is always described as written, with a single placeholder standing in for the list,
+//! so the database tells us the type of an *element* and we typecheck against that.
+//!
+//! What is actually sent to the database depends on the backend (see [`InListStyle`]):
+//!
+//! * Postgres: `[NOT] IN ($N)` is rewritten to `= ANY($N)` (`<> ALL($N)`) at compile time and the
+//!   list is bound as a single array.
+//! * MySQL/SQLite: the placeholder is expanded at runtime to one `?` per element. The element count
+//!   is rounded up to the next power of two (by repeating the last element) so the number of
+//!   distinct statements stays small.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::database::{DatabaseExt, InListStyle};
+use crate::query::QueryMacroInput;
+
+/// A bind parameter found in the query string.
+struct Placeholder {
+    /// Byte range of the placeholder in the query string.
+    start: usize,
+    end: usize,
+    /// The 1-based parameter number, if the placeholder is numbered (`$N`, `?N`).
+    number: Option<usize>,
+}
+
+/// Returns the tokens for the SQL string to pass to `query_with()`.
+///
+/// If the query has list arguments that need to be expanded at runtime, this refers to the
+/// `query_sql` binding created by [`quote_expanded_sql()`].
+pub fn quote_sql<DB: DatabaseExt>(input: &QueryMacroInput) -> crate::Result<TokenStream> {
+    if input.in_list_args.is_empty() {
+        // if this query came from a file, use `include_str!()` to tell the compiler where it came from
+        return Ok(if let Some(ref path) = &input.file_path {
+            quote::quote_spanned! { input.src_span => include_str!(#path) }
+        } else {
+            let sql = &input.sql;
+            quote! { #sql }
+        });
+    }
+
+    match DB::IN_LIST_STYLE {
+        InListStyle::AnyArray => {
+            let sql = rewrite_any_array(&input.sql, &input.in_list_args)?;
+            Ok(quote! { #sql })
+        }
+        InListStyle::Placeholders => Ok(quote! { query_sql }),
+        InListStyle::Unsupported => Err(format!(
+            "`{{in: ...}}` list arguments are not supported for {}",
+            DB::NAME
+        )
+        .into()),
+    }
+}
+
+/// Returns a tokenstream that binds the SQL, expanded for the current lengths of the list
+/// arguments, to the ident `query_sql`. Empty if no expansion is necessary.
+///
+/// Expects the arguments to already be bound to `arg0..argN` by `quote_args()`.
+pub fn quote_expanded_sql<DB: DatabaseExt>(input: &QueryMacroInput) -> crate::Result<TokenStream> {
+    if input.in_list_args.is_empty() || DB::IN_LIST_STYLE != InListStyle::Placeholders {
+        return Ok(TokenStream::new());
+    }
+
+    let parts = split_at_list_args(&input.sql, input.arg_exprs.len(), &input.in_list_args)?;
+    let list_names = input.in_list_args.iter().map(|i| format_ident!("arg{}", i));
+
+    Ok(quote! {
+        let query_sql = {
+            static QUERY_SQL: ::sqlx::in_list::InListSql =
+                ::sqlx::in_list::InListSql::new(&[#(#parts),*]);
+
+            QUERY_SQL.sql(&[#(#list_names.len()),*])
+        };
+    })
+}
+
+/// Rewrite each `[NOT] IN ($N)` where `$N` is a list argument to `= ANY($N)` (`<> ALL($N)`).
+fn rewrite_any_array(sql: &str, in_list_args: &[usize]) -> crate::Result<String> {
+    let mut rewritten = String::with_capacity(sql.len());
+    let mut copied_to = 0;
+
+    for placeholder in find_placeholders(sql) {
+        let number = match placeholder.number {
+            Some(number)
+                if sql[placeholder.start..].starts_with('$')
+                    && in_list_args.contains(&number.wrapping_sub(1)) =>
+            {
+                number
+            }
+            _ => continue,
+        };
+
+        // `$N` must be the only thing within the parentheses of an `IN` clause
+        let before = sql[..placeholder.start].trim_end();
+        let after = sql[placeholder.end..].trim_start();
+
+        let in_clause_start = before
+            .strip_suffix('(')
+            .map(str::trim_end)
+            .and_then(|before| strip_keyword_suffix(before, "IN"))
+            .filter(|_| after.starts_with(')'));
+
+        let in_clause_start = match in_clause_start {
+            Some(in_clause_start) => in_clause_start,
+            None => {
+                return Err(format!(
+                    "list argument ${0} must appear in the query as `IN (${0})`",
+                    number
+                )
+                .into())
+            }
+        };
+
+        let (in_clause_start, op) = match strip_keyword_suffix(in_clause_start.trim_end(), "NOT") {
+            Some(not_start) => (not_start, "<> ALL"),
+            None => (in_clause_start, "= ANY"),
+        };
+
+        let in_clause_end = sql.len() - after.len() + 1;
+
+        rewritten.push_str(&sql[copied_to..in_clause_start.len()]);
+        // keep the whitespace between the left-hand side and `[NOT] IN`
+        if in_clause_start.ends_with(char::is_whitespace) || in_clause_start.is_empty() {
+            rewritten.push_str(op);
+        } else {
+            rewritten.push(' ');
+            rewritten.push_str(op);
+        }
+        rewritten.push_str(&format!("(${})", number));
+
+        copied_to = in_clause_end;
+    }
+
+    rewritten.push_str(&sql[copied_to..]);
+
+    Ok(rewritten)
+}
+
+/// Split the query string at the placeholders of the list arguments, dropping the placeholders.
+fn split_at_list_args(
+    sql: &str,
+    num_args: usize,
+    in_list_args: &[usize],
+) -> crate::Result<Vec<String>> {
+    let placeholders = find_placeholders(sql);
+
+    if placeholders.iter().any(|p| p.number.is_some()) || placeholders.len() != num_args {
+        return Err(
+            "queries with `{in: ...}` list arguments may only use `?` bind parameters, \
+             one for each argument"
+                .into(),
+        );
+    }
+
+    let mut parts = Vec::with_capacity(in_list_args.len() + 1);
+    let mut part_start = 0;
+
+    for (i, placeholder) in placeholders.iter().enumerate() {
+        if in_list_args.contains(&i) {
+            parts.push(sql[part_start..placeholder.start].to_owned());
+            part_start = placeholder.end;
+        }
+    }
+
+    parts.push(sql[part_start..].to_owned());
+
+    Ok(parts)
+}
+
+/// If `s` ends with `keyword` (case-insensitive) as a whole word, return what precedes it.
+fn strip_keyword_suffix<'a>(s: &'a str, keyword: &str) -> Option<&'a str> {
+    let split = s.len().checked_sub(keyword.len())?;
+
+    if !s.is_char_boundary(split) || !s[split..].eq_ignore_ascii_case(keyword) {
+        return None;
+    }
+
+    let before = &s[..split];
+
+    if before.ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+        return None;
+    }
+
+    Some(before)
+}
+
+/// Find all bind parameters in the query string, skipping over quoted strings, quoted
+/// identifiers and comments.
+fn find_placeholders(sql: &str) -> Vec<Placeholder> {
+    let bytes = sql.as_bytes();
+    let mut placeholders = Vec::new();
+    let mut i = 0;
+
+    let scan_number = |start: usize| {
+        let end = bytes[start..]
+            .iter()
+            .position(|b| !b.is_ascii_digit())
+            .map_or(bytes.len(), |len| start + len);
+
+        (end, sql[start..end].parse::<usize>().ok())
+    };
+
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'\'' | b'"' | b'`') => {
+                // doubled quotes are escapes, which this handles as two adjacent quoted strings
+                i = bytes[i + 1..]
+                    .iter()
+                    .position(|&b| b == quote)
+                    .map_or(bytes.len(), |len| i + len + 2);
+            }
+
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                i = bytes[i..]
+                    .iter()
+                    .position(|&b| b == b'\n')
+                    .map_or(bytes.len(), |len| i + len + 1);
+            }
+
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = sql[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |len| i + len + 4);
+            }
+
+            b'?' => {
+                let (end, number) = scan_number(i + 1);
+                placeholders.push(Placeholder {
+                    start: i,
+                    end,
+                    number,
+                });
+                i = end;
+            }
+
+            b'$' if bytes.get(i + 1).map_or(false, u8::is_ascii_digit) => {
+                let (end, number) = scan_number(i + 1);
+                placeholders.push(Placeholder {
+                    start: i,
+                    end,
+                    number,
+                });
+                i = end;
+            }
+
+            _ => i += 1,
+        }
+    }
+
+    placeholders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_rewrites_in_to_any() {
+        assert_eq!(
+            rewrite_any_array("SELECT * FROM foo WHERE id IN ($1)", &[0]).unwrap(),
+            "SELECT * FROM foo WHERE id = ANY($1)"
+        );
+
+        assert_eq!(
+            rewrite_any_array(
+                "SELECT * FROM foo WHERE name = $1 AND id not in ( $2 ) AND x IN ($3)",
+                &[1]
+            )
+            .unwrap(),
+            "SELECT * FROM foo WHERE name = $1 AND id <> ALL($2) AND x IN ($3)"
+        );
+
+        assert_eq!(
+            rewrite_any_array("SELECT 'IN ($1)', id FROM foo WHERE id IN($1)", &[0]).unwrap(),
+            "SELECT 'IN ($1)', id FROM foo WHERE id = ANY($1)"
+        );
+    }
+
+    #[test]
+    fn it_rejects_list_args_outside_in() {
+        assert!(rewrite_any_array("SELECT * FROM foo WHERE id = $1", &[0]).is_err());
+        assert!(rewrite_any_array("SELECT * FROM foo WHERE id IN ($1, $2)", &[0]).is_err());
+        assert!(rewrite_any_array("SELECT * FROM foo WHERE id JOIN ($1)", &[0]).is_err());
+    }
+
+    #[test]
+    fn it_splits_at_list_args() {
+        assert_eq!(
+            split_at_list_args(
+                "SELECT * FROM foo WHERE a = ? AND b IN (?) -- ?\n AND c = '?' AND d IN (?)",
+                3,
+                &[1, 2]
+            )
+            .unwrap(),
+            [
+                "SELECT * FROM foo WHERE a = ? AND b IN (",
+                ") -- ?\n AND c = '?' AND d IN (",
+                ")"
+            ]
+        );
+
+        assert!(split_at_list_args("SELECT * FROM foo WHERE a IN (?1)", 1, &[0]).is_err());
+        assert!(split_at_list_args("SELECT * FROM foo WHERE a IN (?)", 2, &[0]).is_err());
+    }
+}
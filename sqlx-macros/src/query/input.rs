@@ -2,9 +2,8 @@ use std::fs;
 
 use proc_macro2::{Ident, Span};
 use syn::parse::{Parse, ParseStream};
-use syn::punctuated::Punctuated;
-use syn::{Expr, LitBool, LitStr, Token};
-use syn::{ExprArray, Type};
+use syn::{token, Type};
+use syn::{Expr, ExprPath, LitBool, LitStr, Token};
 
 /// Macro input shared by `query!()` and `query_file!()`
 pub struct QueryMacroInput {
@@ -17,9 +16,18 @@ pub struct QueryMacroInput {
 
     pub(super) arg_exprs: Vec<Expr>,
 
+    /// Indices of the arguments passed as `{in: <expr>}` lists.
+    pub(super) in_list_args: Vec<usize>,
+
     pub(super) checked: bool,
 
     pub(super) file_path: Option<String>,
+
+    /// Constants referenced by the query source, e.g. `concat!(SELECT_BASE, " WHERE ...")`.
+    pub(super) const_refs: Vec<ExprPath>,
+
+    /// The name of the database to check the query against, given as `db = "<name>"`.
+    pub(super) db: Option<LitStr>,
 }
 
 enum QuerySrc {
@@ -31,14 +39,18 @@ pub enum RecordType {
     Given(Type),
     Scalar,
     Generated,
+    /// The query is a script of statements, executed without output (`batch = true`).
+    Batch,
 }
 
 impl Parse for QueryMacroInput {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let mut query_src: Option<(QuerySrc, Span)> = None;
-        let mut args: Option<Vec<Expr>> = None;
+        let mut args: Option<(Vec<Expr>, Vec<usize>)> = None;
         let mut record_type = RecordType::Generated;
         let mut checked = true;
+        let mut const_refs = Vec::new();
+        let mut db = None;
 
         let mut expect_comma = false;
 
@@ -53,17 +65,14 @@ impl Parse for QueryMacroInput {
 
             if key == "source" {
                 let span = input.span();
-                let query_str = Punctuated::<LitStr, Token![+]>::parse_separated_nonempty(input)?
-                    .iter()
-                    .map(LitStr::value)
-                    .collect();
+                let (query_str, refs) = super::crate_source::eval_query_source(&input.parse()?)?;
                 query_src = Some((QuerySrc::String(query_str), span));
+                const_refs = refs;
             } else if key == "source_file" {
                 let lit_str = input.parse::<LitStr>()?;
                 query_src = Some((QuerySrc::File(lit_str.value()), lit_str.span()));
             } else if key == "args" {
-                let exprs = input.parse::<ExprArray>()?;
-                args = Some(exprs.elems.into_iter().collect())
+                args = Some(parse_args(input)?);
             } else if key == "record" {
                 if !matches!(record_type, RecordType::Generated) {
                     return Err(input.error("colliding `scalar` or `record` key"));
@@ -80,9 +89,25 @@ impl Parse for QueryMacroInput {
                 // of the column in SQL
                 input.parse::<syn::Token![_]>()?;
                 record_type = RecordType::Scalar;
+            } else if key == "batch" {
+                if !matches!(record_type, RecordType::Generated) {
+                    return Err(input.error("colliding `batch`, `scalar` or `record` key"));
+                }
+
+                if input.parse::<LitBool>()?.value {
+                    record_type = RecordType::Batch;
+                }
             } else if key == "checked" {
                 let lit_bool = input.parse::<LitBool>()?;
                 checked = lit_bool.value;
+            } else if key == "db" {
+                let lit_str = input.parse::<LitStr>()?;
+
+                if lit_str.value().is_empty() {
+                    return Err(syn::Error::new_spanned(lit_str, "expected a database name"));
+                }
+
+                db = Some(lit_str);
             } else {
                 let message = format!("unexpected input key: {}", key);
                 return Err(syn::Error::new_spanned(key, message));
@@ -94,7 +119,14 @@ impl Parse for QueryMacroInput {
         let (src, src_span) =
             query_src.ok_or_else(|| input.error("expected `source` or `source_file` key"))?;
 
-        let arg_exprs = args.unwrap_or_default();
+        let (arg_exprs, in_list_args) = args.unwrap_or_default();
+
+        if matches!(record_type, RecordType::Batch) && !arg_exprs.is_empty() {
+            return Err(syn::Error::new(
+                src_span,
+                "bind parameters cannot be used in a batch",
+            ));
+        }
 
         let file_path = src.file_path(src_span)?;
 
@@ -103,12 +135,64 @@ impl Parse for QueryMacroInput {
             src_span,
             record_type,
             arg_exprs,
+            in_list_args,
             checked,
             file_path,
+            const_refs,
+            db,
         })
     }
 }
 
+/// Parse the bracketed list of bind arguments, where each argument is either an expression or
+/// `{in: <expr>}` to pass a list for an `IN (...)` clause.
+fn parse_args(input: ParseStream) -> syn::Result<(Vec<Expr>, Vec<usize>)> {
+    let content;
+    syn::bracketed!(content in input);
+
+    let mut exprs = Vec::new();
+    let mut in_list_args = Vec::new();
+
+    while !content.is_empty() {
+        if content.peek(token::Brace) && peek_in_list(&content) {
+            let list;
+            syn::braced!(list in content);
+            list.parse::<Token![in]>()?;
+            list.parse::<Token![:]>()?;
+
+            in_list_args.push(exprs.len());
+            exprs.push(list.parse()?);
+
+            if !list.is_empty() {
+                return Err(list.error("expected `}` after list argument"));
+            }
+        } else {
+            exprs.push(content.parse()?);
+        }
+
+        if content.is_empty() {
+            break;
+        }
+
+        content.parse::<Token![,]>()?;
+    }
+
+    Ok((exprs, in_list_args))
+}
+
+/// Check if the next argument is `{in: ...}`, which is never a valid block expression.
+fn peek_in_list(input: ParseStream) -> bool {
+    let fork = input.fork();
+
+    let parse_in = || -> syn::Result<bool> {
+        let list;
+        syn::braced!(list in fork);
+        Ok(list.peek(Token![in]))
+    };
+
+    parse_in().unwrap_or(false)
+}
+
 impl QuerySrc {
     /// If the query source is a file, read it to a string. Otherwise return the query string.
     fn resolve(self, source_span: Span) -> syn::Result<String> {
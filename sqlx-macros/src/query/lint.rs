@@ -0,0 +1,446 @@
+//! Opt-in lints for the SQL of `query!()` and friends, enabled by listing them in `SQLX_LINTS`,
+//! e.g. `SQLX_LINTS=select_star,missing_where` (or `SQLX_LINTS=all`).
+//!
+//! The query is only tokenized, not parsed, so the lints are heuristics that err on the side of
+//! not warning. They are reported as deprecation warnings on the query string, as there is no
+//! other way to emit a warning from a proc macro on stable.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote_spanned};
+
+use crate::query::QueryMacroInput;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Lint {
+    SelectStar,
+    MissingWhere,
+    CrossJoin,
+    Literals,
+}
+
+impl Lint {
+    const ALL: [Lint; 4] = [
+        Lint::SelectStar,
+        Lint::MissingWhere,
+        Lint::CrossJoin,
+        Lint::Literals,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Lint::SelectStar => "select_star",
+            Lint::MissingWhere => "missing_where",
+            Lint::CrossJoin => "cross_join",
+            Lint::Literals => "literals",
+        }
+    }
+
+    fn message(self) -> &'static str {
+        match self {
+            Lint::SelectStar => {
+                "`*` in the select list makes the output depend on the table definition; \
+                 list the columns explicitly"
+            }
+            Lint::MissingWhere => {
+                "`UPDATE` or `DELETE` without a `WHERE` clause affects every row of the table"
+            }
+            Lint::CrossJoin => {
+                "tables separated by commas in `FROM` are cross joined; \
+                 use `JOIN ... ON` or an explicit `CROSS JOIN`"
+            }
+            Lint::Literals => {
+                "literal value in a comparison; consider passing it as a bind parameter"
+            }
+        }
+    }
+}
+
+/// Returns the tokens emitting a warning for each enabled lint the query violates.
+pub fn quote_lints(input: &QueryMacroInput) -> crate::Result<TokenStream> {
+    let enabled = match super::env("SQLX_LINTS") {
+        Ok(lints) => parse_lints(&lints)?,
+        Err(_) => return Ok(TokenStream::new()),
+    };
+
+    let span = input.src_span;
+
+    Ok(check(&input.sql, &enabled)
+        .into_iter()
+        .map(|lint| {
+            let name = format_ident!("sqlx_lint_{}", lint.name(), span = span);
+            let note = format!("{} (`{}` in `SQLX_LINTS`)", lint.message(), lint.name());
+
+            quote_spanned! { span =>
+                {
+                    #[deprecated(note = #note)]
+                    #[allow(non_upper_case_globals)]
+                    const #name: () = ();
+                    let _ = #name;
+                }
+            }
+        })
+        .collect())
+}
+
+/// Parse a comma-separated list of lint names, or `all`.
+fn parse_lints(s: &str) -> crate::Result<Vec<Lint>> {
+    let mut lints = Vec::new();
+
+    for name in s.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+        let name = name.to_ascii_lowercase().replace('-', "_");
+
+        if name == "all" {
+            return Ok(Lint::ALL.to_vec());
+        }
+
+        match Lint::ALL.iter().find(|lint| lint.name() == name) {
+            Some(lint) => lints.push(*lint),
+            None => {
+                return Err(format!(
+                    "unknown lint in `SQLX_LINTS`: {:?}; expected one of: all, {}",
+                    name,
+                    Lint::ALL.map(Lint::name).join(", ")
+                )
+                .into())
+            }
+        }
+    }
+
+    Ok(lints)
+}
+
+/// Returns the enabled lints violated by `sql`, each at most once.
+fn check(sql: &str, enabled: &[Lint]) -> Vec<Lint> {
+    let tokens = tokenize(sql);
+    let mut violated = Vec::new();
+
+    for statement in tokens.split(|token| *token == Token::Symbol(";")) {
+        for &lint in enabled {
+            let found = match lint {
+                Lint::SelectStar => has_select_star(statement),
+                Lint::MissingWhere => has_missing_where(statement),
+                Lint::CrossJoin => has_cross_join(statement),
+                Lint::Literals => has_literal_comparison(statement),
+            };
+
+            if found && !violated.contains(&lint) {
+                violated.push(lint);
+            }
+        }
+    }
+
+    // report in a stable order
+    violated.sort_by_key(|lint| Lint::ALL.iter().position(|l| l == lint));
+    violated
+}
+
+fn has_select_star(tokens: &[Token<'_>]) -> bool {
+    tokens.windows(2).any(|pair| {
+        pair[1] == Token::Symbol("*")
+            && match pair[0] {
+                Token::Word(word) => ["SELECT", "DISTINCT", "ALL"]
+                    .iter()
+                    .any(|kw| word.eq_ignore_ascii_case(kw)),
+                Token::Symbol(symbol) => symbol == "," || symbol == ".",
+                _ => false,
+            }
+    })
+}
+
+fn has_missing_where(tokens: &[Token<'_>]) -> bool {
+    const VERBS: &[&str] = &[
+        "SELECT", "INSERT", "UPDATE", "DELETE", "REPLACE", "VALUES", "MERGE",
+    ];
+
+    let mut depth = 0usize;
+    let mut verb = None;
+
+    for token in tokens {
+        match *token {
+            Token::Symbol("(") => depth += 1,
+            Token::Symbol(")") => depth = depth.saturating_sub(1),
+            Token::Word(word) if depth == 0 => match verb {
+                None => verb = VERBS.iter().find(|kw| word.eq_ignore_ascii_case(kw)),
+                Some(_) if word.eq_ignore_ascii_case("WHERE") => return false,
+                Some(_) => (),
+            },
+            _ => (),
+        }
+    }
+
+    matches!(verb, Some(&"UPDATE") | Some(&"DELETE"))
+}
+
+fn has_cross_join(tokens: &[Token<'_>]) -> bool {
+    // keywords ending the `FROM` clause, or a function call like `extract(... FROM ...)`
+    const END_OF_FROM: &[&str] = &[
+        "WHERE",
+        "GROUP",
+        "HAVING",
+        "WINDOW",
+        "ORDER",
+        "LIMIT",
+        "OFFSET",
+        "FETCH",
+        "FOR",
+        "UNION",
+        "INTERSECT",
+        "EXCEPT",
+        "RETURNING",
+        "USING",
+        "SELECT",
+        "SET",
+        "VALUES",
+    ];
+
+    // whether we're in a `FROM` clause, for each level of parentheses
+    let mut in_from = vec![false];
+
+    for (i, token) in tokens.iter().enumerate() {
+        match *token {
+            Token::Symbol("(") => in_from.push(false),
+            Token::Symbol(")") => {
+                if in_from.len() > 1 {
+                    in_from.pop();
+                }
+            }
+            Token::Word(word) => {
+                let in_from = in_from.last_mut().unwrap();
+
+                if word.eq_ignore_ascii_case("FROM") {
+                    *in_from = true;
+                } else if END_OF_FROM.iter().any(|kw| word.eq_ignore_ascii_case(kw)) {
+                    *in_from = false;
+                }
+            }
+            Token::Symbol(",") if *in_from.last().unwrap() => {
+                // `FROM foo, LATERAL (...)` is correlated with `foo`, so not a cross join
+                let lateral = matches!(
+                    tokens.get(i + 1),
+                    Some(Token::Word(word)) if word.eq_ignore_ascii_case("LATERAL")
+                );
+
+                if !lateral {
+                    return true;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    false
+}
+
+fn has_literal_comparison(tokens: &[Token<'_>]) -> bool {
+    let is_comparison = |token: &Token<'_>| match *token {
+        Token::Symbol(symbol) => ["=", "<>", "!=", "<", ">", "<=", ">="].contains(&symbol),
+        Token::Word(word) => {
+            word.eq_ignore_ascii_case("LIKE") || word.eq_ignore_ascii_case("ILIKE")
+        }
+        _ => false,
+    };
+
+    tokens.windows(2).any(|pair| {
+        (pair[0] == Token::Literal && is_comparison(&pair[1]))
+            || (is_comparison(&pair[0]) && pair[1] == Token::Literal)
+    })
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(super) enum Token<'a> {
+    /// A keyword or identifier, including quoted identifiers (with their quotes).
+    Word(&'a str),
+    /// A string or numeric literal.
+    Literal,
+    /// A bind parameter.
+    Placeholder,
+    /// An operator or punctuation.
+    Symbol(&'a str),
+}
+
+/// Split the query into tokens, dropping whitespace and comments.
+pub(super) fn tokenize(sql: &str) -> Vec<Token<'_>> {
+    const OPERATORS: &[&str] = &["<>", "!=", "<=", ">=", "::", "||"];
+
+    let bytes = sql.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    let is_word_byte = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b >= 0x80;
+    let scan_while = |start: usize, f: &dyn Fn(u8) -> bool| {
+        bytes[start..]
+            .iter()
+            .position(|&b| !f(b))
+            .map_or(bytes.len(), |len| start + len)
+    };
+
+    while i < bytes.len() {
+        let b = bytes[i];
+
+        if b.is_ascii_whitespace() {
+            i += 1;
+        } else if sql[i..].starts_with("--") {
+            i = sql[i..].find('\n').map_or(bytes.len(), |len| i + len + 1);
+        } else if sql[i..].starts_with("/*") {
+            i = sql[i + 2..]
+                .find("*/")
+                .map_or(bytes.len(), |len| i + len + 4);
+        } else if b == b'\'' || b == b'"' || b == b'`' || b == b'[' {
+            let close = if b == b'[' { b']' } else { b };
+            // doubled quotes are escapes, which this handles as two adjacent quoted strings
+            let end = bytes[i + 1..]
+                .iter()
+                .position(|&c| c == close)
+                .map_or(bytes.len(), |len| i + len + 2);
+
+            let token = if b == b'\'' {
+                Token::Literal
+            } else {
+                Token::Word(&sql[i..end])
+            };
+
+            if b != b'\'' || tokens.last() != Some(&Token::Literal) {
+                tokens.push(token);
+            }
+
+            i = end;
+        } else if b == b'$' && bytes.get(i + 1).map_or(false, u8::is_ascii_digit) {
+            i = scan_while(i + 1, &|b| b.is_ascii_digit());
+            tokens.push(Token::Placeholder);
+        } else if b == b'$' {
+            // Postgres dollar-quoted string, e.g. `$$foo$$` or `$tag$foo$tag$`
+            let tag_end = scan_while(i + 1, &is_word_byte);
+
+            if bytes.get(tag_end) == Some(&b'$') {
+                let tag = &sql[i..=tag_end];
+                i = sql[tag_end + 1..]
+                    .find(tag)
+                    .map_or(bytes.len(), |len| tag_end + 1 + len + tag.len());
+                tokens.push(Token::Literal);
+            } else {
+                tokens.push(Token::Symbol(&sql[i..i + 1]));
+                i += 1;
+            }
+        } else if b == b'?' || (b == b'@' && bytes.get(i + 1).map_or(false, |&b| is_word_byte(b))) {
+            i = scan_while(i + 1, &is_word_byte);
+            tokens.push(Token::Placeholder);
+        } else if b.is_ascii_digit()
+            || (b == b'.' && bytes.get(i + 1).map_or(false, u8::is_ascii_digit))
+        {
+            i = scan_while(i, &|b| is_word_byte(b) || b == b'.');
+            tokens.push(Token::Literal);
+        } else if is_word_byte(b) {
+            let end = scan_while(i, &is_word_byte);
+            tokens.push(Token::Word(&sql[i..end]));
+            i = end;
+        } else {
+            let len = match OPERATORS.iter().find(|op| sql[i..].starts_with(*op)) {
+                Some(op) => op.len(),
+                None => sql[i..].chars().next().map_or(1, char::len_utf8),
+            };
+
+            tokens.push(Token::Symbol(&sql[i..i + len]));
+            i += len;
+        }
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lints(sql: &str) -> Vec<&'static str> {
+        check(sql, &Lint::ALL).into_iter().map(Lint::name).collect()
+    }
+
+    #[test]
+    fn it_parses_lint_names() {
+        assert_eq!(
+            parse_lints("select_star, missing-where").unwrap(),
+            [Lint::SelectStar, Lint::MissingWhere]
+        );
+        assert_eq!(parse_lints("ALL").unwrap(), Lint::ALL);
+        assert!(parse_lints("").unwrap().is_empty());
+        assert!(parse_lints("select_star,nope").is_err());
+    }
+
+    #[test]
+    fn it_passes_clean_queries() {
+        assert!(lints("SELECT id, name FROM users WHERE id = $1").is_empty());
+        assert!(lints("SELECT count(*), 2 * price FROM items WHERE a = ? -- = 'x'").is_empty());
+        assert!(lints("DELETE FROM users WHERE id = $1").is_empty());
+        assert!(lints(
+            "INSERT INTO users (id) VALUES ($1) ON CONFLICT (id) DO UPDATE SET id = excluded.id"
+        )
+        .is_empty());
+        assert!(lints("SELECT extract(year FROM created_at), a FROM t WHERE b = $1").is_empty());
+        assert!(lints("SELECT u.id FROM users u, LATERAL (SELECT 1) x WHERE u.id = $1").is_empty());
+        assert!(lints("SELECT 'a, b' FROM t WHERE id = $1").is_empty());
+    }
+
+    #[test]
+    fn it_finds_select_star() {
+        assert_eq!(lints("SELECT * FROM users WHERE id = $1"), ["select_star"]);
+        assert_eq!(
+            lints("select u.* from users u where id = ?"),
+            ["select_star"]
+        );
+        assert_eq!(
+            lints("SELECT DISTINCT * FROM (SELECT id FROM t) x"),
+            ["select_star"]
+        );
+    }
+
+    #[test]
+    fn it_finds_missing_where() {
+        assert_eq!(lints("DELETE FROM users"), ["missing_where"]);
+        assert_eq!(lints("UPDATE users SET name = $1"), ["missing_where"]);
+        assert_eq!(
+            lints("UPDATE users SET name = (SELECT name FROM t WHERE id = $1)"),
+            ["missing_where"]
+        );
+        assert_eq!(
+            lints("WITH x AS (SELECT id FROM t WHERE id = $1) DELETE FROM users"),
+            ["missing_where"]
+        );
+        assert_eq!(
+            lints("SELECT id FROM t WHERE id = $1; DELETE FROM users"),
+            ["missing_where"]
+        );
+    }
+
+    #[test]
+    fn it_finds_cross_joins() {
+        assert_eq!(
+            lints("SELECT a.id FROM a, b WHERE a.id = b.id"),
+            ["cross_join"]
+        );
+        assert_eq!(
+            lints("SELECT id FROM (SELECT a.id FROM a, b) x WHERE id = $1"),
+            ["cross_join"]
+        );
+        assert!(lints("SELECT a.id, b.id FROM a CROSS JOIN b").is_empty());
+    }
+
+    #[test]
+    fn it_finds_literals() {
+        assert_eq!(lints("SELECT id FROM t WHERE kind = 'admin'"), ["literals"]);
+        assert_eq!(lints("SELECT id FROM t WHERE 10 < age"), ["literals"]);
+        assert_eq!(lints("SELECT id FROM t WHERE name LIKE 'a%'"), ["literals"]);
+        assert_eq!(
+            lints("SELECT id FROM t WHERE body = $$it's$$"),
+            ["literals"]
+        );
+        assert!(lints("SELECT 'x = y' AS s, 1 AS n FROM t WHERE id = $1 LIMIT 10").is_empty());
+    }
+
+    #[test]
+    fn it_reports_each_lint_once() {
+        assert_eq!(
+            lints("SELECT * FROM a, b WHERE x = 1 AND y = 2; DELETE FROM c"),
+            ["select_star", "missing_where", "cross_join", "literals"]
+        );
+    }
+}
@@ -0,0 +1,310 @@
+//! Opt-in manifest of the queries checked by `query!()` and friends, for external tooling, enabled
+//! by setting `SQLX_QUERY_MANIFEST=1`.
+//!
+//! For each query expanded, a JSON file is written to `target/sqlx-manifest` with the SQL, where
+//! it is written, the tables it references and the types of its parameters and output columns.
+//! Tools can read the whole directory to build an inventory of the queries of a project.
+
+use std::fs::{self, File};
+use std::io::BufWriter;
+
+use either::Either;
+use sqlx_core::column::Column;
+use sqlx_core::describe::Describe;
+use sqlx_core::type_info::TypeInfo;
+
+use crate::database::DatabaseExt;
+use crate::query::data::offline::hash_string;
+use crate::query::lint::{tokenize, Token};
+use crate::query::QueryMacroInput;
+
+#[derive(serde::Serialize)]
+struct QueryManifest<'a> {
+    #[serde(rename = "crate")]
+    crate_name: Option<String>,
+    db_name: &'static str,
+    query: &'a str,
+    /// The hash the offline data of the query is saved under, as `.sqlx/query-<hash>.json`.
+    hash: String,
+    /// The file the query is written in, relative to the crate, and its line, if found.
+    file: Option<String>,
+    line: Option<usize>,
+    tables: Vec<String>,
+    /// The type of each bind parameter, if known.
+    parameters: Vec<Option<String>>,
+    columns: Vec<ManifestColumn>,
+}
+
+#[derive(serde::Serialize)]
+struct ManifestColumn {
+    name: String,
+    #[serde(rename = "type")]
+    type_name: String,
+    nullable: Option<bool>,
+}
+
+/// Write the manifest of the query to the target directory, if enabled.
+pub fn write_manifest<DB: DatabaseExt>(
+    input: &QueryMacroInput,
+    describe: &Describe<DB>,
+) -> crate::Result<()> {
+    let enabled = super::env("SQLX_QUERY_MANIFEST")
+        .map(|s| s.eq_ignore_ascii_case("true") || s == "1")
+        .unwrap_or(false);
+
+    if !enabled {
+        return Ok(());
+    }
+
+    let (file, line) = match &input.file_path {
+        Some(path) => {
+            let path = std::path::Path::new(path);
+            let relative = path
+                .strip_prefix(&super::METADATA.manifest_dir)
+                .unwrap_or(path);
+
+            (Some(relative.to_owned()), Some(1))
+        }
+        None => match super::crate_source::locate_literal(&input.sql)? {
+            Some((file, line)) => (Some(file), Some(line)),
+            None => (None, None),
+        },
+    };
+
+    let parameters = match describe.parameters() {
+        Some(Either::Left(params)) => params
+            .iter()
+            .map(|param| Some(param.name().to_owned()))
+            .collect(),
+        Some(Either::Right(num)) => vec![None; num],
+        None => Vec::new(),
+    };
+
+    let columns = describe
+        .columns()
+        .iter()
+        .enumerate()
+        .map(|(i, column)| ManifestColumn {
+            name: column.name().to_owned(),
+            type_name: column.type_info().name().to_owned(),
+            nullable: describe.nullable(i),
+        })
+        .collect();
+
+    let manifest = QueryManifest {
+        crate_name: super::env("CARGO_PKG_NAME").ok(),
+        db_name: DB::NAME,
+        query: &input.sql,
+        hash: hash_string(&input.sql),
+        file: file.map(|file| file.to_string_lossy().replace('\\', "/")),
+        line,
+        tables: referenced_tables(&input.sql),
+        parameters,
+        columns,
+    };
+
+    let dir = super::METADATA.target_dir.join("sqlx-manifest");
+    fs::create_dir_all(&dir)?;
+
+    // named like the offline data, which is unique per invocation and per statement of a batch
+    let path = dir.join(format!(
+        "query-{}.json",
+        hash_string(&format!("{:?}{}", input.src_span, input.sql))
+    ));
+
+    serde_json::to_writer_pretty(
+        BufWriter::new(
+            File::create(&path)
+                .map_err(|e| format!("failed to open path {}: {}", path.display(), e))?,
+        ),
+        &manifest,
+    )
+    .map_err(Into::into)
+}
+
+/// The tables named in the `FROM`, `JOIN`, `INTO` and `UPDATE` clauses of `sql`, in order of first
+/// appearance, excluding common table expressions.
+///
+/// Like the lints, this only tokenizes the query, so it may miss tables in unusual syntax.
+fn referenced_tables(sql: &str) -> Vec<String> {
+    let tokens = tokenize(sql);
+    let mut tables: Vec<String> = Vec::new();
+    let mut ctes: Vec<String> = Vec::new();
+
+    let is_keyword = |token: Option<&Token<'_>>, keyword: &str| match token {
+        Some(Token::Word(word)) => word.eq_ignore_ascii_case(keyword),
+        _ => false,
+    };
+
+    // whether each level of parentheses is a (sub)query, as opposed to e.g. a function call like
+    // `extract(year FROM ...)`, and whether it is in a `FROM` list
+    let mut levels = vec![(true, false)];
+
+    for (i, token) in tokens.iter().enumerate() {
+        let (is_query, in_from) = *levels.last().unwrap();
+
+        let expect_table = match *token {
+            Token::Symbol("(") => {
+                let is_subquery = ["SELECT", "WITH", "VALUES"]
+                    .iter()
+                    .any(|keyword| is_keyword(tokens.get(i + 1), keyword));
+
+                levels.push((is_subquery, false));
+                false
+            }
+            Token::Symbol(")") => {
+                if levels.len() > 1 {
+                    levels.pop();
+                }
+
+                false
+            }
+            Token::Symbol(",") => in_from,
+            Token::Word(word) => {
+                let upper = word.to_ascii_uppercase();
+
+                // `WITH name AS (...)`, `name AS MATERIALIZED (...)`
+                if is_keyword(tokens.get(i + 1), "AS")
+                    && (tokens.get(i + 2) == Some(&Token::Symbol("("))
+                        || is_keyword(tokens.get(i + 2), "MATERIALIZED")
+                        || is_keyword(tokens.get(i + 2), "NOT"))
+                {
+                    ctes.push(unquote(word));
+                }
+
+                let from = upper == "FROM"
+                    && is_query
+                    && !(i > 0 && is_keyword(tokens.get(i - 1), "DISTINCT"));
+
+                if from {
+                    levels.last_mut().unwrap().1 = true;
+                } else if [
+                    "WHERE",
+                    "GROUP",
+                    "HAVING",
+                    "WINDOW",
+                    "ORDER",
+                    "LIMIT",
+                    "OFFSET",
+                    "FETCH",
+                    "FOR",
+                    "UNION",
+                    "INTERSECT",
+                    "EXCEPT",
+                    "RETURNING",
+                    "ON",
+                    "SET",
+                    "SELECT",
+                    "VALUES",
+                ]
+                .contains(&upper.as_str())
+                {
+                    levels.last_mut().unwrap().1 = false;
+                }
+
+                // not `ON DUPLICATE KEY UPDATE`, `DO UPDATE` or `FOR UPDATE`
+                let update = upper == "UPDATE"
+                    && !["KEY", "DO", "FOR"]
+                        .iter()
+                        .any(|keyword| i > 0 && is_keyword(tokens.get(i - 1), keyword));
+
+                from || update || upper == "JOIN" || upper == "INTO"
+            }
+            _ => false,
+        };
+
+        if !expect_table {
+            continue;
+        }
+
+        // skip `ONLY` and `LATERAL`
+        let mut j = i + 1;
+
+        while ["ONLY", "LATERAL"]
+            .iter()
+            .any(|keyword| is_keyword(tokens.get(j), keyword))
+        {
+            j += 1;
+        }
+
+        let mut name = match tokens.get(j) {
+            Some(Token::Word(word)) => unquote(word),
+            _ => continue,
+        };
+
+        // a schema-qualified name
+        while tokens.get(j + 1) == Some(&Token::Symbol(".")) {
+            match tokens.get(j + 2) {
+                Some(Token::Word(word)) => {
+                    name.push('.');
+                    name.push_str(&unquote(word));
+                    j += 2;
+                }
+                _ => break,
+            }
+        }
+
+        // a function in `FROM`, e.g. `unnest($1)`, rather than `INTO table (columns)`
+        if tokens.get(j + 1) == Some(&Token::Symbol("(")) && !is_keyword(Some(token), "INTO") {
+            continue;
+        }
+
+        if !tables.contains(&name) {
+            tables.push(name);
+        }
+    }
+
+    tables.retain(|table| !ctes.contains(table));
+    tables
+}
+
+/// Strip the quotes of a quoted identifier.
+fn unquote(word: &str) -> String {
+    match word.as_bytes().first() {
+        Some(b'"') | Some(b'`') | Some(b'[') => word[1..word.len() - 1].to_owned(),
+        _ => word.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_referenced_tables() {
+        assert_eq!(
+            referenced_tables("SELECT id FROM users WHERE id = $1"),
+            ["users"]
+        );
+        assert_eq!(
+            referenced_tables(
+                "SELECT u.id, p.title FROM public.users u \
+                 LEFT JOIN \"posts\" p ON p.user_id = u.id, LATERAL unnest(u.tags) t \
+                 WHERE u.id IN (SELECT user_id FROM bans) AND extract(year FROM u.created) = 1"
+            ),
+            ["public.users", "posts", "bans"]
+        );
+        assert_eq!(
+            referenced_tables(
+                "WITH recent AS (SELECT id FROM tweet WHERE id > ?) \
+                 INSERT INTO `archive` (id) SELECT id FROM recent \
+                 ON DUPLICATE KEY UPDATE id = id"
+            ),
+            ["tweet", "archive"]
+        );
+        assert_eq!(
+            referenced_tables(
+                "INSERT INTO users (id) VALUES ($1) ON CONFLICT (id) DO UPDATE SET id = $1"
+            ),
+            ["users"]
+        );
+        assert_eq!(
+            referenced_tables("UPDATE accounts SET balance = 0 FROM ledger WHERE a = b"),
+            ["accounts", "ledger"]
+        );
+        assert_eq!(
+            referenced_tables("SELECT a IS DISTINCT FROM b FROM t1, t2"),
+            ["t1", "t2"]
+        );
+    }
+}
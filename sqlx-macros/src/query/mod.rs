@@ -10,20 +10,29 @@ use syn::Type;
 use url::Url;
 
 pub use input::QueryMacroInput;
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, ToTokens};
 use sqlx_core::connection::Connection;
 use sqlx_core::database::Database;
 use sqlx_core::{column::Column, describe::Describe, type_info::TypeInfo};
 use sqlx_rt::{block_on, AsyncMutex};
 
+use crate::common::CONFIG;
 use crate::database::DatabaseExt;
 use crate::query::data::QueryData;
 use crate::query::input::RecordType;
 use either::Either;
 
 mod args;
+mod batch;
+mod crate_source;
 mod data;
+#[cfg(feature = "postgres")]
+mod explain;
+mod in_list;
 mod input;
+mod lint;
+#[cfg(feature = "offline")]
+mod manifest;
 mod output;
 
 struct Metadata {
@@ -104,7 +113,7 @@ static METADATA: Lazy<Metadata> = Lazy::new(|| {
         .map(|s| s.eq_ignore_ascii_case("true") || s == "1")
         .unwrap_or(false);
 
-    let database_url = env("DATABASE_URL").ok();
+    let database_url = env(CONFIG.database_url_var()).ok();
 
     Metadata {
         manifest_dir,
@@ -118,18 +127,47 @@ static METADATA: Lazy<Metadata> = Lazy::new(|| {
 });
 
 pub fn expand_input(input: QueryMacroInput) -> crate::Result<TokenStream> {
-    match &*METADATA {
+    // `db = "<name>"` selects the database URL from `SQLX_DATABASE_URL_<NAME>` instead
+    let (url_var, database_url) = match &input.db {
+        Some(db) => {
+            // make sure `.env` has been loaded
+            Lazy::force(&METADATA);
+
+            let url_var = database_url_var(&db.value());
+            let database_url = env(&url_var).ok();
+
+            (url_var, database_url)
+        }
+        None => (
+            CONFIG.database_url_var().to_owned(),
+            METADATA.database_url.clone(),
+        ),
+    };
+
+    if let RecordType::Batch = input.record_type {
+        return batch::expand_batch(input, |statement| {
+            expand_statement(statement, &url_var, database_url.clone())
+        });
+    }
+
+    expand_statement(input, &url_var, database_url)
+}
+
+/// Expand a single statement, checking it against the database at `database_url` or else using
+/// the offline data.
+fn expand_statement(
+    input: QueryMacroInput,
+    url_var: &str,
+    database_url: Option<String>,
+) -> crate::Result<TokenStream> {
+    match (METADATA.offline, database_url) {
         #[cfg(not(any(
             feature = "postgres",
             feature = "mysql",
             feature = "mssql",
             feature = "sqlite"
         )))]
-        Metadata {
-            offline: false,
-            database_url: Some(db_url),
-            ..
-        } => Err(
+        (false, Some(_)) => Err(
             "At least one of the features ['postgres', 'mysql', 'mssql', 'sqlite'] must be enabled \
             to get information directly from a database"
             .into(),
@@ -141,43 +179,141 @@ pub fn expand_input(input: QueryMacroInput) -> crate::Result<TokenStream> {
             feature = "mssql",
             feature = "sqlite"
         ))]
-        Metadata {
-            offline: false,
-            database_url: Some(db_url),
-            ..
-        } => expand_from_db(input, &db_url),
+        (false, Some(db_url)) => expand_from_db(input, &db_url),
 
         #[cfg(feature = "offline")]
         _ => {
-            let data_file_path = METADATA.manifest_dir.join("sqlx-data.json");
+            // a directory set in `sqlx.toml` is the only one looked in
+            if let Some(query_dir) = CONFIG.offline_dir() {
+                let query_data = data::offline::DynQueryData::from_query_dir(query_dir, &input.sql)?;
+                return expand_from_offline_data(input, query_data);
+            }
 
-            if data_file_path.exists() {
-                expand_from_file(input, data_file_path)
-            } else {
-                let workspace_data_file_path = METADATA.workspace_root().join("sqlx-data.json");
-                if workspace_data_file_path.exists() {
-                    expand_from_file(input, workspace_data_file_path)
-                } else {
-                    Err(
-                        "`DATABASE_URL` must be set, or `cargo sqlx prepare` must have been run \
-                     and sqlx-data.json must exist, to use query macros"
-                            .into(),
-                    )
-                }
+            // look in the crate directory first, and only then ask Cargo for the workspace root
+            if let Some(query_data) = find_offline_data(&METADATA.manifest_dir, &input.sql)? {
+                return expand_from_offline_data(input, query_data);
             }
+
+            if let Some(query_data) = find_offline_data(&METADATA.workspace_root(), &input.sql)? {
+                return expand_from_offline_data(input, query_data);
+            }
+
+            Err(format!(
+                "`{}` must be set, or `cargo sqlx prepare` must have been run \
+                 and the `.sqlx` directory must exist, to use query macros",
+                url_var
+            )
+            .into())
         }
 
         #[cfg(not(feature = "offline"))]
-        Metadata { offline: true, .. } => {
+        (true, _) => {
             Err("The cargo feature `offline` has to be enabled to use `SQLX_OFFLINE`".into())
         }
 
         #[cfg(not(feature = "offline"))]
-        Metadata {
-            offline: false,
-            database_url: None,
-            ..
-        } => Err("`DATABASE_URL` must be set to use query macros".into()),
+        (false, None) => Err(format!("`{}` must be set to use query macros", url_var).into()),
+    }
+}
+
+/// The environment variable holding the URL of the database named in `db = "<name>"`.
+fn database_url_var(db: &str) -> String {
+    let name: String = db
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    format!("SQLX_DATABASE_URL_{}", name)
+}
+
+/// Find the database that query macros generated by a derive will be checked against, out of
+/// `candidates`, which pair the name of each database with the query as written for it.
+///
+/// This is the database at `DATABASE_URL` or, in offline mode, the one the saved data for the
+/// query is for.
+pub(crate) fn resolve_database(
+    candidates: &[(&'static str, String)],
+) -> crate::Result<&'static str> {
+    let find = |name: &str| {
+        candidates
+            .iter()
+            .map(|(candidate, _)| *candidate)
+            .find(|candidate| *candidate == name)
+    };
+
+    match (METADATA.offline, &METADATA.database_url) {
+        (false, Some(db_url)) => {
+            use sqlx_core::any::AnyKind;
+
+            let name = match AnyKind::from_str(db_url)? {
+                #[cfg(feature = "postgres")]
+                AnyKind::Postgres => sqlx_core::postgres::Postgres::NAME,
+                #[cfg(feature = "mysql")]
+                AnyKind::MySql => sqlx_core::mysql::MySql::NAME,
+                #[cfg(feature = "sqlite")]
+                AnyKind::Sqlite => sqlx_core::sqlite::Sqlite::NAME,
+                #[cfg(feature = "mssql")]
+                AnyKind::Mssql => sqlx_core::mssql::Mssql::NAME,
+                // Variants depend on feature flags
+                #[allow(unreachable_patterns)]
+                _ => return Err("the database at `DATABASE_URL` is not supported".into()),
+            };
+
+            find(name).ok_or_else(|| format!("{} is not supported", name).into())
+        }
+
+        #[cfg(feature = "offline")]
+        _ => {
+            let database_url_missing = || {
+                format!(
+                    "`{}` must be set, or `cargo sqlx prepare` must have been run \
+                     and the `.sqlx` directory must exist, to use query macros",
+                    CONFIG.database_url_var()
+                )
+                .into()
+            };
+
+            if let Some(query_dir) = CONFIG.offline_dir() {
+                for (_, sql) in candidates {
+                    if let Ok(query_data) =
+                        data::offline::DynQueryData::from_query_dir(&query_dir, sql)
+                    {
+                        return find(&query_data.db_name).ok_or_else(|| {
+                            format!("{} is not supported", query_data.db_name).into()
+                        });
+                    }
+                }
+
+                return Err(database_url_missing());
+            }
+
+            for (_, sql) in candidates {
+                for dir in [METADATA.manifest_dir.clone(), METADATA.workspace_root()] {
+                    // a missing query is an error if `.sqlx` exists, but another candidate may
+                    // still be found
+                    if let Ok(Some(query_data)) = find_offline_data(&dir, sql) {
+                        return find(&query_data.db_name).ok_or_else(|| {
+                            format!("{} is not supported", query_data.db_name).into()
+                        });
+                    }
+                }
+            }
+
+            Err(database_url_missing())
+        }
+
+        #[cfg(not(feature = "offline"))]
+        _ => Err(format!(
+            "`{}` must be set to use query macros",
+            CONFIG.database_url_var()
+        )
+        .into()),
     }
 }
 
@@ -223,8 +359,26 @@ fn expand_from_db(input: QueryMacroInput, db_url: &str) -> crate::Result<TokenSt
         match conn_item.private_get_mut() {
             #[cfg(feature = "postgres")]
             sqlx_core::any::AnyConnectionKind::Postgres(conn) => {
-                let data = QueryData::from_db(conn, &input.sql).await?;
-                expand_with_data(input, data, false)
+                let data = QueryData::from_db(&mut *conn, &input.sql).await?;
+
+                // a statement of a batch expands to the database type, not an expression
+                if let RecordType::Batch = input.record_type {
+                    return expand_with_data(input, data, false);
+                }
+
+                let num_params = match data.describe.parameters() {
+                    Some(Either::Left(params)) => params.len(),
+                    _ => 0,
+                };
+
+                let explain_tokens = explain::quote_explain_lint(conn, &input, num_params).await?;
+                let tokens = expand_with_data(input, data, false)?;
+
+                if explain_tokens.is_empty() {
+                    Ok(tokens)
+                } else {
+                    Ok(quote! {{ #explain_tokens #tokens }})
+                }
             }
             #[cfg(feature = "mssql")]
             sqlx_core::any::AnyConnectionKind::Mssql(conn) => {
@@ -245,11 +399,35 @@ fn expand_from_db(input: QueryMacroInput, db_url: &str) -> crate::Result<TokenSt
     })
 }
 
+/// Find the data for a query in `dir`, looking for a `.sqlx` directory with a file per query, or
+/// else a `sqlx-data.json` written by older versions of `cargo sqlx prepare`.
 #[cfg(feature = "offline")]
-pub fn expand_from_file(input: QueryMacroInput, file: PathBuf) -> crate::Result<TokenStream> {
+fn find_offline_data(
+    dir: &std::path::Path,
+    query: &str,
+) -> crate::Result<Option<data::offline::DynQueryData>> {
     use data::offline::DynQueryData;
 
-    let query_data = DynQueryData::from_data_file(file, &input.sql)?;
+    let query_dir = dir.join(sqlx_build::query_data::QUERY_DIR);
+
+    if query_dir.is_dir() {
+        return DynQueryData::from_query_dir(query_dir, query).map(Some);
+    }
+
+    let data_file_path = dir.join("sqlx-data.json");
+
+    if data_file_path.exists() {
+        return DynQueryData::from_data_file(data_file_path, query).map(Some);
+    }
+
+    Ok(None)
+}
+
+#[cfg(feature = "offline")]
+fn expand_from_offline_data(
+    input: QueryMacroInput,
+    query_data: data::offline::DynQueryData,
+) -> crate::Result<TokenStream> {
     assert!(!query_data.db_name.is_empty());
 
     match &*query_data.db_name {
@@ -303,6 +481,31 @@ fn expand_with_data<DB: DatabaseExt>(
 where
     Describe<DB>: DescribeExt,
 {
+    if let RecordType::Batch = input.record_type {
+        if data
+            .describe
+            .parameters()
+            .map_or(false, |params| match params {
+                Either::Left(params) => !params.is_empty(),
+                Either::Right(num) => num > 0,
+            })
+        {
+            return Err("bind parameters cannot be used in a batch".into());
+        }
+
+        #[cfg(feature = "offline")]
+        {
+            if !offline {
+                save_query_data(&data, &input)?;
+            }
+
+            manifest::write_manifest(&input, &data.describe)?;
+        }
+
+        // only the database is needed, to build the `QueryBatch` for the whole script
+        return Ok(DB::db_path().into_token_stream());
+    }
+
     // validate at the minimum that our args match the query's input parameters
     let num_parameters = match data.describe.parameters() {
         Some(Either::Left(params)) => Some(params.len()),
@@ -321,6 +524,9 @@ where
 
     let args_tokens = args::quote_args(&input, &data.describe)?;
 
+    let sql_tokens = in_list::quote_expanded_sql::<DB>(&input)?;
+    let sql = in_list::quote_sql::<DB>(&input)?;
+
     let query_args = format_ident!("query_args");
 
     let output = if data
@@ -330,7 +536,6 @@ where
         .all(|it| it.type_info().is_void())
     {
         let db_path = DB::db_path();
-        let sql = &input.sql;
 
         quote! {
             ::sqlx::query_with::<#db_path, _>(#sql, #query_args)
@@ -338,7 +543,7 @@ where
     } else {
         match input.record_type {
             RecordType::Generated => {
-                let columns = output::columns_to_rust::<DB>(&data.describe)?;
+                let columns = output::columns_to_rust::<DB>(&data.describe, false)?;
 
                 let record_name: Type = syn::parse_str("Record").unwrap();
 
@@ -370,6 +575,7 @@ where
                 record_tokens.extend(output::quote_query_as::<DB>(
                     &input,
                     &record_name,
+                    &sql,
                     &query_args,
                     &columns,
                 ));
@@ -377,24 +583,43 @@ where
                 record_tokens
             }
             RecordType::Given(ref out_ty) => {
-                let columns = output::columns_to_rust::<DB>(&data.describe)?;
+                let columns = output::columns_to_rust::<DB>(&data.describe, true)?;
 
-                output::quote_query_as::<DB>(&input, out_ty, &query_args, &columns)
+                let literal = may_build_literal(out_ty, &columns)?;
+
+                output::quote_query_as_from_columns::<DB>(
+                    &input,
+                    out_ty,
+                    &sql,
+                    &query_args,
+                    &columns,
+                    literal,
+                )
             }
             RecordType::Scalar => {
-                output::quote_query_scalar::<DB>(&input, &query_args, &data.describe)?
+                output::quote_query_scalar::<DB>(&input, &sql, &query_args, &data.describe)?
             }
+            RecordType::Batch => unreachable!("batches are handled above"),
         }
     };
 
+    let const_refs = &input.const_refs;
+    let lint_tokens = lint::quote_lints(&input)?;
+
     let ret_tokens = quote! {
         {
             #[allow(clippy::all)]
             {
                 use ::sqlx::Arguments as _;
 
+                #(let _: &str = #const_refs;)*
+
+                #lint_tokens
+
                 #args_tokens
 
+                #sql_tokens
+
                 #output
             }
         }
@@ -403,15 +628,55 @@ where
     // Store query metadata only if offline support is enabled but the current build is online.
     // If the build is offline, the cache is our input so it's pointless to also write data for it.
     #[cfg(feature = "offline")]
-    if !offline {
-        let save_dir = METADATA.target_dir.join("sqlx");
-        std::fs::create_dir_all(&save_dir)?;
-        data.save_in(save_dir, input.src_span)?;
+    {
+        if !offline {
+            save_query_data(&data, &input)?;
+        }
+
+        manifest::write_manifest(&input, &data.describe)?;
     }
 
     Ok(ret_tokens)
 }
 
+#[cfg(feature = "offline")]
+fn save_query_data<DB: DatabaseExt>(
+    data: &QueryData<DB>,
+    input: &QueryMacroInput,
+) -> crate::Result<()>
+where
+    Describe<DB>: DescribeExt,
+{
+    // saved per package, for `cargo sqlx prepare --workspace` to tell the queries of each apart
+    // when the checks of members rebuild the members they depend on
+    let mut save_dir = METADATA.target_dir.join("sqlx");
+    if let Ok(package) = env("CARGO_PKG_NAME") {
+        save_dir.push(package);
+    }
+
+    std::fs::create_dir_all(&save_dir)?;
+    data.save_in(save_dir, input.src_span)
+}
+
+/// Check if the output type of `query_as!()` may be built from a struct literal with the columns
+/// as fields; not if it's a struct in this crate with other fields, or with `#[sqlx(...)]`
+/// attributes for its `FromRow` derive, as the literal could then fail to compile.
+fn may_build_literal(out_ty: &Type, columns: &[output::RustColumn]) -> crate::Result<bool> {
+    match out_ty {
+        // `macro_rules!` fragments are passed to us wrapped in invisible groups
+        Type::Group(group) => may_build_literal(&group.elem, columns),
+        Type::Path(path) if path.qself.is_none() => match path.path.segments.last() {
+            Some(segment) => {
+                let fields: Vec<String> = columns.iter().map(|col| col.ident.to_string()).collect();
+
+                crate_source::may_have_fields(&segment.ident.to_string(), &fields)
+            }
+            None => Ok(true),
+        },
+        _ => Ok(true),
+    }
+}
+
 /// Get the value of an environment variable, telling the compiler about it if applicable.
 fn env(name: &str) -> Result<String, std::env::VarError> {
     #[cfg(procmacro2_semver_exempt)]
@@ -424,3 +689,17 @@ fn env(name: &str) -> Result<String, std::env::VarError> {
         std::env::var(name)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_names_database_url_vars() {
+        assert_eq!(database_url_var("analytics"), "SQLX_DATABASE_URL_ANALYTICS");
+        assert_eq!(
+            database_url_var("read-replica"),
+            "SQLX_DATABASE_URL_READ_REPLICA"
+        );
+    }
+}
@@ -5,6 +5,7 @@ use syn::Type;
 use sqlx_core::column::Column;
 use sqlx_core::describe::Describe;
 
+use crate::common::column_name_hash;
 use crate::database::DatabaseExt;
 
 use crate::query::QueryMacroInput;
@@ -75,13 +76,24 @@ impl Display for DisplayColumn<'_> {
     }
 }
 
-pub fn columns_to_rust<DB: DatabaseExt>(describe: &Describe<DB>) -> crate::Result<Vec<RustColumn>> {
+/// If `infer_unsupported` is set, columns of types we don't know the Rust equivalent of are
+/// treated as if they had a wildcard override (`"foo: _"`) rather than being an error, so their
+/// type is inferred from the fields of the output struct of `query_as!()`. This is the only way
+/// to use such columns with `SELECT *`, where they cannot be given an override.
+pub fn columns_to_rust<DB: DatabaseExt>(
+    describe: &Describe<DB>,
+    infer_unsupported: bool,
+) -> crate::Result<Vec<RustColumn>> {
     (0..describe.columns().len())
-        .map(|i| column_to_rust(describe, i))
+        .map(|i| column_to_rust(describe, i, infer_unsupported))
         .collect::<crate::Result<Vec<_>>>()
 }
 
-fn column_to_rust<DB: DatabaseExt>(describe: &Describe<DB>, i: usize) -> crate::Result<RustColumn> {
+fn column_to_rust<DB: DatabaseExt>(
+    describe: &Describe<DB>,
+    i: usize,
+    infer_unsupported: bool,
+) -> crate::Result<RustColumn> {
     let column = &describe.columns()[i];
 
     // add raw prefix to all identifiers
@@ -104,14 +116,17 @@ fn column_to_rust<DB: DatabaseExt>(describe: &Describe<DB>, i: usize) -> crate::
         (ColumnTypeOverride::Wildcard, false) => ColumnType::Wildcard,
         (ColumnTypeOverride::Wildcard, true) => ColumnType::OptWildcard,
 
-        (ColumnTypeOverride::None, _) => {
-            let type_ = get_column_type::<DB>(i, column);
-            if !nullable {
-                ColumnType::Exact(type_)
-            } else {
-                ColumnType::Exact(quote! { ::std::option::Option<#type_> })
+        (ColumnTypeOverride::None, _) => match try_column_type::<DB>(i, column) {
+            Ok(type_) if !nullable => ColumnType::Exact(type_),
+            Ok(type_) => ColumnType::Exact(quote! { ::std::option::Option<#type_> }),
+
+            Err(_) if infer_unsupported && !nullable => ColumnType::Wildcard,
+            Err(_) if infer_unsupported => ColumnType::OptWildcard,
+
+            Err(message) => {
+                ColumnType::Exact(syn::Error::new(Span::call_site(), message).to_compile_error())
             }
-        }
+        },
     };
 
     Ok(RustColumn {
@@ -126,11 +141,36 @@ fn column_to_rust<DB: DatabaseExt>(describe: &Describe<DB>, i: usize) -> crate::
 pub fn quote_query_as<DB: DatabaseExt>(
     input: &QueryMacroInput,
     out_ty: &Type,
+    sql: &TokenStream,
     bind_args: &Ident,
     columns: &[RustColumn],
 ) -> TokenStream {
-    let instantiations = columns.iter().enumerate().map(
-        |(
+    let instantiations = quote_instantiations(input, columns);
+
+    let ident = columns.iter().map(|col| &col.ident);
+    let var_name = columns.iter().map(|col| &col.var_name);
+
+    let db_path = DB::db_path();
+    let row_path = DB::row_path();
+
+    quote! {
+        ::sqlx::query_with::<#db_path, _>(#sql, #bind_args).try_map(|row: #row_path| {
+            use ::sqlx::Row as _;
+
+            #(#instantiations)*
+
+            Ok(#out_ty { #(#ident: #var_name),* })
+        })
+    }
+}
+
+/// Generate a `let` statement reading each column from `row` into its variable.
+fn quote_instantiations<'a>(
+    input: &'a QueryMacroInput,
+    columns: &'a [RustColumn],
+) -> impl Iterator<Item = TokenStream> + 'a {
+    columns.iter().enumerate().map(
+        move |(
             i,
             &RustColumn {
                 ref var_name,
@@ -155,35 +195,163 @@ pub fn quote_query_as<DB: DatabaseExt>(
                 (false, _) => quote!( let #var_name = row.try_get_unchecked(#i)?; ),
             }
         },
-    );
-
-    let ident = columns.iter().map(|col| &col.ident);
-    let var_name = columns.iter().map(|col| &col.var_name);
+    )
+}
 
+/// Like [`quote_query_as()`], but builds the struct through its `FromColumns` impl if it has one,
+/// as generated by `#[derive(FromRow)]`, which maps columns to fields according to its field
+/// attributes.
+///
+/// Whether it has one is decided by the trait bound, with autoref specialization: structs
+/// without it are built from a struct literal as in [`quote_query_as()`], unless `literal` isn't
+/// set because the struct is known not to have the fields for it.
+pub fn quote_query_as_from_columns<DB: DatabaseExt>(
+    input: &QueryMacroInput,
+    out_ty: &Type,
+    sql: &TokenStream,
+    bind_args: &Ident,
+    columns: &[RustColumn],
+    literal: bool,
+) -> TokenStream {
     let db_path = DB::db_path();
     let row_path = DB::row_path();
 
-    // if this query came from a file, use `include_str!()` to tell the compiler where it came from
-    let sql = if let Some(ref path) = &input.file_path {
-        quote::quote_spanned! { input.src_span => include_str!(#path) }
+    let impls = columns.iter().enumerate().map(|(i, RustColumn { ident, type_, .. })| {
+        let name = column_name_hash(ident.to_string().trim_start_matches("r#"));
+
+        let (get, generics, value_ty) = match (input.checked, type_) {
+            // we guarantee the type is valid so we can skip the runtime check
+            (true, ColumnType::Exact(type_)) => (
+                quote!(try_get_unchecked::<#type_, _>),
+                quote!(<'r>),
+                type_.clone(),
+            ),
+            // type was overridden to be a wildcard so we fallback to the runtime check
+            (true, ColumnType::Wildcard) => (quote!(try_get), quote!(<'r, T>), quote!(T)),
+            (true, ColumnType::OptWildcard) => (
+                quote!(try_get),
+                quote!(<'r, T>),
+                quote!(::std::option::Option<T>),
+            ),
+            // macro is the `_unchecked!()` variant so this will die in decoding if it's wrong
+            (false, _) => (quote!(try_get_unchecked), quote!(<'r, T>), quote!(T)),
+        };
+
+        let where_clause = if matches!(type_, ColumnType::Exact(_)) && input.checked {
+            quote!()
+        } else {
+            quote!(where #value_ty: ::sqlx::decode::Decode<'r, #db_path> + ::sqlx::types::Type<#db_path>)
+        };
+
+        quote! {
+            impl #generics ::sqlx::from_columns::ColumnValue<#name, #value_ty>
+                for SqlxQueryAsColumns<'r> #where_clause
+            {
+                fn column_value(&self) -> ::sqlx::Result<#value_ty> {
+                    self.0.#get(#i)
+                }
+            }
+        }
+    });
+
+    // the columns of fields marked `#[sqlx(default)]`, whose types are checked when decoding
+    let optional_get = if input.checked {
+        quote!(try_get)
     } else {
-        let sql = &input.sql;
-        quote! { #sql }
+        quote!(try_get_unchecked)
+    };
+    let optional_arms = columns
+        .iter()
+        .enumerate()
+        .map(|(i, RustColumn { ident, .. })| {
+            let name = column_name_hash(ident.to_string().trim_start_matches("r#"));
+
+            quote!(#name => ::std::option::Option::Some(self.0.#optional_get(#i)),)
+        });
+
+    let from_columns = quote! {
+        <#out_ty as ::sqlx::from_columns::FromColumns<SqlxQueryAsColumns<'_>>>::from_columns(
+            &SqlxQueryAsColumns(&row),
+        )
+    };
+
+    let build = if literal {
+        let instantiations = quote_instantiations(input, columns);
+        let ident = columns.iter().map(|col| &col.ident);
+        let var_name = columns.iter().map(|col| &col.var_name);
+
+        quote! {
+            struct SqlxQueryAsOutput<T>(::std::marker::PhantomData<T>);
+
+            trait SqlxFromColumns<'r> {
+                type Output;
+
+                fn sqlx_build(&self, row: &'r #row_path) -> ::sqlx::Result<Self::Output>;
+            }
+
+            impl<'r, T> SqlxFromColumns<'r> for SqlxQueryAsOutput<T>
+            where
+                T: ::sqlx::from_columns::FromColumns<SqlxQueryAsColumns<'r>>,
+            {
+                type Output = T;
+
+                fn sqlx_build(&self, row: &'r #row_path) -> ::sqlx::Result<T> {
+                    T::from_columns(&SqlxQueryAsColumns(row))
+                }
+            }
+
+            trait SqlxFromLiteral<'r> {
+                type Output;
+
+                fn sqlx_build(&self, row: &'r #row_path) -> ::sqlx::Result<Self::Output>;
+            }
+
+            impl<'r> SqlxFromLiteral<'r> for &SqlxQueryAsOutput<#out_ty> {
+                type Output = #out_ty;
+
+                fn sqlx_build(&self, row: &'r #row_path) -> ::sqlx::Result<#out_ty> {
+                    #(#instantiations)*
+
+                    Ok(#out_ty { #(#ident: #var_name),* })
+                }
+            }
+
+            (&SqlxQueryAsOutput::<#out_ty>(::std::marker::PhantomData)).sqlx_build(&row)
+        }
+    } else {
+        from_columns
     };
 
     quote! {
         ::sqlx::query_with::<#db_path, _>(#sql, #bind_args).try_map(|row: #row_path| {
             use ::sqlx::Row as _;
 
-            #(#instantiations)*
+            struct SqlxQueryAsColumns<'r>(&'r #row_path);
+
+            #(#impls)*
+
+            impl<'r, const NAME: u64, T> ::sqlx::from_columns::OptionalColumnValue<NAME, T>
+                for SqlxQueryAsColumns<'r>
+            where
+                T: ::sqlx::decode::Decode<'r, #db_path> + ::sqlx::types::Type<#db_path>,
+            {
+                #[allow(unreachable_patterns)]
+                fn optional_column_value(&self) -> ::std::option::Option<::sqlx::Result<T>> {
+                    match NAME {
+                        #(#optional_arms)*
+                        _ => ::std::option::Option::None,
+                    }
+                }
+            }
 
-            Ok(#out_ty { #(#ident: #var_name),* })
+            #build
         })
     }
 }
 
 pub fn quote_query_scalar<DB: DatabaseExt>(
     input: &QueryMacroInput,
+    sql: &TokenStream,
     bind_args: &Ident,
     describe: &Describe<DB>,
 ) -> crate::Result<TokenStream> {
@@ -198,7 +366,7 @@ pub fn quote_query_scalar<DB: DatabaseExt>(
     }
 
     // attempt to parse a column override, otherwise fall back to the inferred type of the column
-    let ty = if let Ok(rust_col) = column_to_rust(describe, 0) {
+    let ty = if let Ok(rust_col) = column_to_rust(describe, 0, false) {
         rust_col.type_.to_token_stream()
     } else if input.checked {
         let ty = get_column_type::<DB>(0, &columns[0]);
@@ -212,43 +380,46 @@ pub fn quote_query_scalar<DB: DatabaseExt>(
     };
 
     let db = DB::db_path();
-    let query = &input.sql;
 
     Ok(quote! {
-        ::sqlx::query_scalar_with::<#db, #ty, _>(#query, #bind_args)
+        ::sqlx::query_scalar_with::<#db, #ty, _>(#sql, #bind_args)
     })
 }
 
 fn get_column_type<DB: DatabaseExt>(i: usize, column: &DB::Column) -> TokenStream {
+    try_column_type::<DB>(i, column)
+        .unwrap_or_else(|message| syn::Error::new(Span::call_site(), message).to_compile_error())
+}
+
+/// Get the Rust type for a column, or an error message if its type isn't supported.
+fn try_column_type<DB: DatabaseExt>(i: usize, column: &DB::Column) -> Result<TokenStream, String> {
     let type_info = &*column.type_info();
 
-    <DB as DatabaseExt>::return_type_for_id(&type_info).map_or_else(
-        || {
-            let message =
-                if let Some(feature_gate) = <DB as DatabaseExt>::get_feature_gate(&type_info) {
-                    format!(
-                        "optional feature `{feat}` required for type {ty} of {col}",
-                        ty = &type_info,
-                        feat = feature_gate,
-                        col = DisplayColumn {
-                            idx: i,
-                            name: &*column.name()
-                        }
-                    )
-                } else {
-                    format!(
-                        "unsupported type {ty} of {col}",
-                        ty = type_info,
-                        col = DisplayColumn {
-                            idx: i,
-                            name: &*column.name()
-                        }
-                    )
-                };
-            syn::Error::new(Span::call_site(), message).to_compile_error()
-        },
-        |t| t.parse().unwrap(),
-    )
+    match <DB as DatabaseExt>::return_type_for_id(&type_info) {
+        Some(t) => Ok(t.parse().unwrap()),
+        None => Err(
+            if let Some(feature_gate) = <DB as DatabaseExt>::get_feature_gate(&type_info) {
+                format!(
+                    "optional feature `{feat}` required for type {ty} of {col}",
+                    ty = &type_info,
+                    feat = feature_gate,
+                    col = DisplayColumn {
+                        idx: i,
+                        name: &*column.name()
+                    }
+                )
+            } else {
+                format!(
+                    "unsupported type {ty} of {col}",
+                    ty = type_info,
+                    col = DisplayColumn {
+                        idx: i,
+                        name: &*column.name()
+                    }
+                )
+            },
+        ),
+    }
 }
 
 impl ColumnDecl {
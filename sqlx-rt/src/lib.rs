@@ -48,6 +48,15 @@ pub use tokio::{
 ))]
 pub use tokio::net::UnixStream;
 
+#[cfg(all(
+    windows,
+    any(feature = "_rt-tokio", feature = "_rt-actix"),
+    not(feature = "_rt-async-std"),
+))]
+pub use tokio::net::windows::named_pipe::{
+    ClientOptions as NamedPipeClientOptions, NamedPipeClient,
+};
+
 #[cfg(all(
     any(feature = "_rt-tokio", feature = "_rt-actix"),
     not(feature = "_rt-async-std"),
@@ -0,0 +1,34 @@
+// These traits allow `query_as!()` to build a struct that derives `FromRow` while honoring its
+// `#[sqlx(rename = "...")]` and `#[sqlx(rename_all = "...")]` attributes, which the macro
+// can't see.
+
+// The derive implements `FromColumns<C>` with a bound `C: ColumnValue<HASH, T>` for each field,
+// where `HASH` is a hash of the name of the column the field is read from and `T` is the type
+// of the field. `query_as!()` declares a type wrapping the row and implements `ColumnValue` for
+// each column of the query, so a missing column or a mismatched type is a compile error.
+
+/// Get the value of the column whose name hashes to `NAME`, decoded as `T`.
+pub trait ColumnValue<const NAME: u64, T> {
+    fn column_value(&self) -> crate::Result<T>;
+}
+
+/// Build `Self` from the column values provided by `C`.
+pub trait FromColumns<C>: Sized {
+    fn from_columns(columns: &C) -> crate::Result<Self>;
+}
+
+/// Like [`ColumnValue`], but `None` if the query has no column whose name hashes to `NAME`, for
+/// fields marked `#[sqlx(default)]`; the type is then only checked when decoding.
+pub trait OptionalColumnValue<const NAME: u64, T> {
+    fn optional_column_value(&self) -> Option<crate::Result<T>>;
+}
+
+/// Not implemented for any type; the derive adds it as a bound of `FromColumns` if a field is
+/// marked `#[sqlx(flatten, prefix = "...")]`, as the names of the columns it reads are only known
+/// at runtime, so using the struct with `query_as!()` is an error explaining this.
+#[diagnostic::on_unimplemented(
+    message = "`query_as!()` can't build a struct with a field marked `#[sqlx(flatten, prefix = \"...\")]`",
+    label = "the column names of a prefixed field are only known at runtime",
+    note = "use `query_as()` with the `FromRow` implementation of the struct instead"
+)]
+pub trait FlattenWithPrefix {}
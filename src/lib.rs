@@ -13,21 +13,30 @@ compile_error!(
 
 pub use sqlx_core::acquire::Acquire;
 pub use sqlx_core::arguments::{Arguments, IntoArguments};
+pub use sqlx_core::buffer_pool;
 pub use sqlx_core::column::Column;
 pub use sqlx_core::column::ColumnIndex;
-pub use sqlx_core::connection::{ConnectOptions, Connection};
+pub use sqlx_core::connection::{ConnectOptions, Connection, ServerCapabilities, ServerVersion};
 pub use sqlx_core::database::{self, Database};
 pub use sqlx_core::describe::Describe;
 pub use sqlx_core::executor::{Execute, Executor};
-pub use sqlx_core::from_row::FromRow;
+pub use sqlx_core::from_row::{FromRow, FromRowPrefixed};
+pub use sqlx_core::observer;
 pub use sqlx_core::pool::{self, Pool};
 pub use sqlx_core::query::{query, query_with};
 pub use sqlx_core::query_as::{query_as, query_as_with};
+pub use sqlx_core::query_batch::query_batch;
 pub use sqlx_core::query_builder::{self, QueryBuilder};
+pub use sqlx_core::query_log;
 pub use sqlx_core::query_scalar::{query_scalar, query_scalar_with};
+pub use sqlx_core::reconnect::ReconnectingConnection;
 pub use sqlx_core::row::Row;
+pub use sqlx_core::runtime;
+pub use sqlx_core::service;
 pub use sqlx_core::statement::Statement;
-pub use sqlx_core::transaction::{Transaction, TransactionManager};
+pub use sqlx_core::transaction::{
+    IsolationLevel, Savepoint, Transaction, TransactionManager, TransactionOptions,
+};
 pub use sqlx_core::type_info::TypeInfo;
 pub use sqlx_core::types::Type;
 pub use sqlx_core::value::{Value, ValueRef};
@@ -39,17 +48,42 @@ pub use sqlx_core::error::{self, Error, Result};
 #[cfg(feature = "migrate")]
 pub use sqlx_core::migrate;
 
+#[cfg(feature = "config")]
+pub use sqlx_core::config;
+
+#[cfg(feature = "json")]
+pub use sqlx_core::export;
+
+#[cfg(feature = "metrics")]
+pub use sqlx_core::metrics;
+
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+pub use sqlx_core::sqlcommenter;
+
 #[cfg(all(
     any(
         feature = "mysql",
         feature = "sqlite",
         feature = "postgres",
-        feature = "mssql"
+        feature = "mssql",
+        feature = "duckdb"
     ),
     feature = "any"
 ))]
 pub use sqlx_core::any::{self, Any, AnyConnection, AnyExecutor, AnyPool};
 
+#[cfg(all(
+    any(
+        feature = "mysql",
+        feature = "sqlite",
+        feature = "postgres",
+        feature = "mssql",
+        feature = "duckdb"
+    ),
+    feature = "mock"
+))]
+pub use sqlx_core::mock;
+
 #[cfg(feature = "mysql")]
 #[cfg_attr(docsrs, doc(cfg(feature = "mysql")))]
 pub use sqlx_core::mysql::{self, MySql, MySqlConnection, MySqlExecutor, MySqlPool};
@@ -66,6 +100,10 @@ pub use sqlx_core::postgres::{self, PgConnection, PgExecutor, PgPool, Postgres};
 #[cfg_attr(docsrs, doc(cfg(feature = "sqlite")))]
 pub use sqlx_core::sqlite::{self, Sqlite, SqliteConnection, SqliteExecutor, SqlitePool};
 
+#[cfg(feature = "duckdb")]
+#[cfg_attr(docsrs, doc(cfg(feature = "duckdb")))]
+pub use sqlx_core::duckdb::{self, DuckDb, DuckDbConnection, DuckDbExecutor, DuckDbPool};
+
 #[cfg(feature = "macros")]
 #[doc(hidden)]
 pub extern crate sqlx_macros;
@@ -75,6 +113,9 @@ pub extern crate sqlx_macros;
 #[doc(hidden)]
 pub use sqlx_macros::{FromRow, Type};
 
+#[cfg(feature = "macros")]
+pub use sqlx_macros::Table;
+
 #[cfg(feature = "macros")]
 mod macros;
 
@@ -83,6 +124,14 @@ mod macros;
 #[doc(hidden)]
 pub mod ty_match;
 
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub mod from_columns;
+
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub use sqlx_core::in_list;
+
 /// Conversions between Rust and SQL types.
 ///
 /// To see how each SQL type maps to a Rust type, see the corresponding `types` module for each
@@ -92,6 +141,7 @@ pub mod ty_match;
 ///  * MySQL: [mysql::types]
 ///  * SQLite: [sqlite::types]
 ///  * MSSQL: [mssql::types]
+///  * DuckDB: [duckdb::types]
 ///
 /// Any external types that have had [`Type`] implemented for, are re-exported in this module
 /// for convenience as downstream users need to use a compatible version of the external crate
@@ -132,6 +182,7 @@ pub use self::decode::Decode;
 pub mod query {
     pub use sqlx_core::query::{Map, Query};
     pub use sqlx_core::query_as::QueryAs;
+    pub use sqlx_core::query_batch::QueryBatch;
     pub use sqlx_core::query_scalar::QueryScalar;
 }
 
@@ -45,12 +45,16 @@
 /// server with the schema that the query string will be checked against. All variants of `query!()`
 /// use [dotenv] so this can be in a `.env` file instead.
 ///
-///     * Or, `sqlx-data.json` must exist at the workspace root. See [Offline Mode](#offline-mode-requires-the-offline-feature)
+///     * Or, the `.sqlx` directory must exist in the crate directory or at the workspace root.
+///       See [Offline Mode](#offline-mode-requires-the-offline-feature)
 ///       below.
 ///
 /// * The query must be a string literal, or concatenation of string literals using `+` (useful
-/// for queries generated by macro), or else it cannot be introspected (and thus cannot be dynamic
-/// or the result of another macro).
+/// for queries generated by macro) or `concat!()`, or else it cannot be introspected (and thus
+/// cannot be dynamic or the result of another macro).
+///
+///     * `concat!()` may also refer to `const` strings defined in the same crate, so fragments
+///       such as column lists can be shared between queries (see [below](#query-strings-from-constants)).
 ///
 /// * The `QueryAs` instance will be bound to the same database type as `query!()` was compiled
 /// against (e.g. you cannot build against a Postgres database and then run the query against
@@ -60,6 +64,32 @@
 ///       determine the database type.
 ///
 /// [dotenv]: https://crates.io/crates/dotenv
+///
+/// ## Query Strings from Constants
+/// The query can be assembled with `concat!()` from string literals and `const` strings:
+///
+/// ```rust,ignore
+/// const ACCOUNT_COLUMNS: &str = "id, name, is_active";
+///
+/// let account = sqlx::query!(
+///     concat!("select ", ACCOUNT_COLUMNS, " from accounts where id = ?"),
+///     1i32
+/// )
+/// .fetch_one(&mut conn)
+/// .await?;
+/// ```
+///
+/// Since proc macros cannot evaluate constants, their definitions are found by parsing the Rust
+/// sources under `src/`, `tests/`, `examples/` and `benches/` of the crate being compiled, so:
+///
+/// * Only `const` items of type `&str` (including associated constants) defined in the same crate
+///   can be used; constants from dependencies cannot.
+/// * Constants are looked up by name only, so a name defined more than once must have the same
+///   value everywhere.
+/// * A constant's value must itself be a string literal, `concat!()` (or
+///   [`const_format::concatcp!()`](https://docs.rs/const_format)) of literals and other such
+///   constants.
+///
 /// ## Query Arguments
 /// Like `println!()` and the other formatting macros, you can add bind parameters to your SQL
 /// and this macro will typecheck passed arguments and error on missing ones:
@@ -96,6 +126,38 @@
 /// * Postgres: `$N` where `N` is the 1-based positional argument index
 /// * MySQL/SQLite: `?` which matches arguments in order that it appears in the query
 ///
+/// ## Query Arguments: Lists
+/// To bind a list of values for an `IN (...)` clause, write a single bind parameter inside the
+/// parentheses and pass the list as `{in: <expr>}`, where `<expr>` is anything that can be sliced
+/// (an array, `Vec`, slice, etc.):
+///
+/// ```rust,ignore
+/// let ids: Vec<i64> = vec![1, 2, 3];
+///
+/// // MySQL/SQLite
+/// let accounts = sqlx::query!("select * from accounts where id in (?)", {in: ids})
+///     .fetch_all(&mut conn)
+///     .await?;
+///
+/// // Postgres
+/// let accounts = sqlx::query!("select * from accounts where id in ($1)", {in: ids})
+///     .fetch_all(&mut conn)
+///     .await?;
+/// ```
+///
+/// The query is checked with the single bind parameter, so the elements of the list are
+/// typechecked like any other argument.
+///
+/// * Postgres: `[NOT] IN ($N)` is rewritten to `= ANY($N)` (`<> ALL($N)`) and the list is bound
+///   as an array, so the element type must support arrays.
+/// * MySQL/SQLite: the bind parameter is expanded to one `?` per element when the query is
+///   created. To limit the number of distinct statements, the length of the list is rounded up to
+///   the next power of two by repeating the last element. An empty list is expanded to an empty
+///   subquery, so `IN (?)` matches no rows and `NOT IN (?)` matches all rows, the same as on
+///   Postgres. List arguments cannot be combined with
+///   numbered bind parameters (`?NNN`).
+/// * MSSQL: not supported.
+///
 /// ## Nullability: Bind Parameters
 /// For a given expected type `T`, both `T` and `Option<T>` are allowed (as well as either
 /// behind references). `Option::None` will be bound as `NULL`, so if binding a type behind `Option`
@@ -113,10 +175,13 @@
 /// In most cases, the database engine can tell us whether or not a column may be `NULL`, and
 /// the `query!()` macro adjusts the field types of the returned struct accordingly.
 ///
-/// For Postgres, this only works for columns which come directly from actual tables,
-/// as the implementation will need to query the table metadata to find if a given column
-/// has a `NOT NULL` constraint. Columns that do not have a `NOT NULL` constraint or are the result
-/// of an expression are assumed to be nullable and so `Option<T>` is used instead of `T`.
+/// For Postgres, columns which come directly from actual tables are looked up in the table
+/// metadata to find if a given column has a `NOT NULL` constraint. The nullability of expressions
+/// is then inferred from the query plan, as far as the implementation understands them: literals
+/// and functions like `count()` are never `NULL`, `COALESCE()` is not `NULL` if one of its
+/// arguments isn't, and operators and functions like `lower()` are only `NULL` if one of their
+/// arguments is. Columns that do not have a `NOT NULL` constraint or are the result of any other
+/// expression are assumed to be nullable and so `Option<T>` is used instead of `T`.
 ///
 /// For MySQL, the implementation looks at [the `NOT_NULL` flag](https://dev.mysql.com/doc/dev/mysql-server/8.0.12/group__group__cs__column__definition__flags.html#ga50377f5ca5b3e92f3931a81fe7b44043)
 /// of [the `ColumnDefinition` structure in `COM_QUERY_OK`](https://dev.mysql.com/doc/internals/en/com-query-response.html#column-definition):
@@ -164,13 +229,13 @@
 /// # async fn main() {
 /// # let mut conn = panic!();
 /// // Postgres: using a raw query string lets us use unescaped double-quotes
-/// // Note that this query wouldn't work in SQLite as we still don't know the exact type of `id`
-/// let record = sqlx::query!(r#"select 1 as "id!""#) // MySQL: use "select 1 as `id!`" instead
+/// // MySQL: use "select max(id) as `id!` from tweet" instead
+/// let record = sqlx::query!(r#"select max(id) as "id!" from tweet"#)
 ///     .fetch_one(&mut conn)
 ///     .await?;
 ///
-/// // For Postgres this would have been inferred to be Option<i32> instead
-/// assert_eq!(record.id, 1i32);
+/// // For Postgres this would have been inferred to be Option<i64> instead
+/// assert_eq!(record.id, 1i64);
 /// # }
 ///
 /// ```
@@ -188,8 +253,7 @@
 ///     .fetch_one(&mut conn)
 ///     .await?;
 ///
-/// // For Postgres this would have been inferred to be Option<i32> anyway
-/// // but this is just a basic example
+/// // This would have been inferred to be `i32`
 /// assert_eq!(record.id, Some(1i32));
 /// # }
 /// ```
@@ -206,9 +270,10 @@
 ///
 /// In Postgres, we patch up this inference by analyzing `EXPLAIN VERBOSE` output (which is not
 /// well documented, is highly dependent on the query plan that Postgres generates, and may differ
-/// between releases) to find columns that are the result of left/right/full outer joins. This
-/// analysis errs on the side of producing false positives (marking columns nullable that are not
-/// in practice) but there are likely edge cases that it does not cover yet.
+/// between releases) to find columns that are the result of left/right/full outer joins, wherever
+/// the joins are in the plan, and to infer the nullability of expressions. This analysis errs on
+/// the side of producing false positives (marking columns nullable that are not in practice) but
+/// there are likely edge cases that it does not cover yet.
 ///
 /// Using `?` as an override we can fix this for columns we know to be nullable in practice:
 ///
@@ -255,9 +320,9 @@
 ///     .fetch_one(&mut conn)
 ///     .await?;
 ///
-/// // For Postgres this would have been inferred to be `Option<i32>`, MySQL/SQLite `i32`
-/// // Note that while using `id: MyInt4` (without the `!`) would work the same for MySQL/SQLite,
-/// // Postgres would expect `Some(MyInt4(1))` and the code wouldn't compile
+/// // This would have been inferred to be `i32`
+/// // Using `id: MyInt4` (without the `!`) would work the same here, as `1` is known to not be
+/// // `NULL`, but for an expression of unknown nullability `Some(MyInt4(1))` would be expected
 /// assert_eq!(record.id, MyInt4(1));
 /// # }
 /// ```
@@ -272,6 +337,39 @@
 /// | `foo!: T` | Forced not-null | Overridden |
 /// | `foo?: T` | Forced nullable | Overridden |
 ///
+/// With [query_as!], `_` may be used in place of `T` to take the type from the output struct
+/// (e.g. `foo!: _`).
+///
+/// ##### Expressions, Casts and Joins
+/// An override applies to the output column whatever it is computed from, so expressions and casts
+/// only need an alias carrying the override:
+///
+/// ```rust,ignore
+/// // Postgres
+/// let record = sqlx::query!(
+///     r#"select count(*) as "count!", id::text as "id!: MyString", lower(name) as "name: Name"
+///        from users group by id"#
+/// )
+/// ```
+///
+/// Likewise, `foo as "foo!"` forces a column brought in by an outer join to be treated as not-null,
+/// for queries where the join condition is known to always match.
+///
+/// How much has to be overridden depends on the database:
+///
+/// | Database | Type of expressions                    | Nullability of expressions                       |
+/// | -------- | -------------------------------------- | ------------------------------------------------ |
+/// | Postgres | Inferred                               | Inferred where understood; otherwise use `!`     |
+/// | MySQL    | Inferred                               | Inferred                                         |
+/// | SQLite   | Often unknown; use `: T` to specify it | Inferred by analyzing the query plan             |
+///
+/// ##### Wildcard Selects
+/// Columns selected with `*` cannot carry overrides. With [query_as!] (and its variants), a column
+/// of a type the macros don't know the Rust equivalent of, such as a custom enum, is therefore
+/// treated as if it had the `foo: _` override, taking its type from the output struct with the
+/// usual runtime type check. Its inferred nullability still applies; to override that, select the
+/// column explicitly.
+///
 /// ## Offline Mode (requires the `offline` feature)
 /// The macros can be configured to not require a live database connection for compilation,
 /// but it requires a couple extra steps:
@@ -279,19 +377,108 @@
 /// * Run `cargo install sqlx-cli`.
 /// * In your project with `DATABASE_URL` set (or in a `.env` file) and the database server running,
 ///   run `cargo sqlx prepare`.
-/// * Check the generated `sqlx-data.json` file into version control.
+/// * Check the generated `.sqlx` directory into version control. It holds one file per query,
+///   named after the hash of the query, so changes to different queries don't conflict.
 /// * Don't have `DATABASE_URL` set during compilation.
 ///
 /// Your project can now be built without a database connection (you must omit `DATABASE_URL` or
-/// else it will still try to connect). To update the generated file simply run `cargo sqlx prepare`
+/// else it will still try to connect). To update the generated files simply run `cargo sqlx prepare`
 /// again.
 ///
-/// To ensure that your `sqlx-data.json` file is kept up-to-date, both with the queries in your
+/// A `sqlx-data.json` file generated by older versions of `cargo sqlx prepare` is still used if
+/// there is no `.sqlx` directory.
+///
+/// To ensure that your `.sqlx` directory is kept up-to-date, both with the queries in your
 /// project and your database schema itself, run
 /// `cargo install sqlx-cli && cargo sqlx prepare --check` in your Continuous Integration script.
 ///
 /// See [the README for `sqlx-cli`](https://crates.io/crates/sqlx-cli) for more information.
 ///
+/// ## Multiple Databases
+/// A crate talking to more than one database can name the database each query is checked against
+/// by passing `db = "<name>"` as the first argument to any of the query macros:
+///
+/// ```rust,ignore
+/// let events = sqlx::query!(db = "analytics", "select id, kind from events where id = $1", id)
+///     .fetch_all(&analytics_pool)
+///     .await?;
+///
+/// let user = sqlx::query_as!(db = "users", User, "select * from users where id = ?", user_id)
+///     .fetch_one(&users_pool)
+///     .await?;
+/// ```
+///
+/// The URL of a named database is read from `SQLX_DATABASE_URL_<NAME>` instead of `DATABASE_URL`,
+/// where `<NAME>` is the name in uppercase with any character other than ASCII letters and digits
+/// replaced by `_` (e.g. `db = "read-replica"` reads `SQLX_DATABASE_URL_READ_REPLICA`). As with
+/// `DATABASE_URL`, these may be set in a `.env` file, and each database may be of a different kind.
+///
+/// In offline mode, queries are looked up by their hash as usual. `cargo sqlx prepare` passes the
+/// environment through to the build, so the `SQLX_DATABASE_URL_<NAME>` variables just need to be
+/// set when running it.
+///
+/// ## Lints
+/// The macros can warn about common mistakes in queries. The lints are off by default and are
+/// enabled by listing them, comma-separated, in the `SQLX_LINTS` environment variable (which may
+/// also be set in `.env`), or with `SQLX_LINTS=all`:
+///
+/// | Lint            | Warns on                                                              |
+/// | --------------- | --------------------------------------------------------------------- |
+/// | `select_star`   | `*` or `table.*` in a select list                                     |
+/// | `missing_where` | `UPDATE` or `DELETE` statements without a `WHERE` clause              |
+/// | `cross_join`    | tables separated by commas in `FROM` (except `LATERAL` subqueries)    |
+/// | `literals`      | string or numeric literals compared with `=`, `<>`, `<`, `LIKE`, etc. |
+///
+/// The query is only tokenized, not fully parsed, so these are best-effort checks.
+///
+/// Lints are reported as deprecation warnings on the macro invocation, so they can be turned
+/// into errors with `#![deny(deprecated)]`, or silenced for a single query with
+/// `#[allow(deprecated)]` on the enclosing item.
+///
+/// #### Query Plans (Postgres only)
+/// With `SQLX_EXPLAIN_LINT=1`, the macros also explain each query with the database at
+/// `DATABASE_URL`, and warn if the plan has a sequential scan over a table with more than
+/// `SQLX_EXPLAIN_LINT_ROWS` rows (1000 by default), which usually means the query can't use an
+/// index. The number of rows comes from the statistics of the table, so it is only as accurate as
+/// the last `ANALYZE`, and the plan is the generic one used for any values of the bind parameters.
+///
+/// Nothing is checked when building offline.
+///
+/// ## Query Manifest (requires the `offline` feature)
+/// With `SQLX_QUERY_MANIFEST=1`, the macros write a description of each query they check to
+/// `target/sqlx-manifest` (or under `CARGO_TARGET_DIR`), as one JSON file per query, for tools
+/// building an inventory of the queries of a project or checking the impact of schema changes:
+///
+/// ```json
+/// {
+///   "crate": "my-app",
+///   "db_name": "PostgreSQL",
+///   "query": "SELECT id, name FROM users WHERE id = $1",
+///   "hash": "5c3c1b...",
+///   "file": "src/users.rs",
+///   "line": 42,
+///   "tables": ["users"],
+///   "parameters": ["INT8"],
+///   "columns": [
+///     { "name": "id", "type": "INT8", "nullable": false },
+///     { "name": "name", "type": "TEXT", "nullable": true }
+///   ]
+/// }
+/// ```
+///
+/// * `hash` is the one the query is saved under in `.sqlx`.
+/// * `file` and `line` locate the string literal the query is written as, relative to the crate,
+///   and are `null` if it can't be found (e.g. for queries built from constants, or written the
+///   same way more than once). For [query_file!] and friends they give the query file.
+/// * `tables` are the tables named in `FROM`, `JOIN`, `INTO` and `UPDATE` clauses. As with the
+///   lints, the query is only tokenized, so this is best-effort.
+/// * `parameters` holds the type of each bind parameter, or `null` where the database doesn't
+///   tell (MySQL).
+///
+/// The files are written whenever a query is expanded, online or offline, and are not removed
+/// when a query is, so run `cargo clean` (or delete the directory) before a full build to get
+/// only the current queries.
+///
 /// ## See Also
 /// * [query_as!] if you want to use a struct you can name,
 /// * [query_file!] if you want to define the SQL query out-of-line,
@@ -299,6 +486,12 @@
 #[macro_export]
 #[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
 macro_rules! query (
+    (db = $db:literal, $query:expr) => ({
+        $crate::sqlx_macros::expand_query!(db = $db, source = $query)
+    });
+    (db = $db:literal, $query:expr, $($args:tt)*) => ({
+        $crate::sqlx_macros::expand_query!(db = $db, source = $query, args = [$($args)*])
+    });
     // in Rust 1.45 we can now invoke proc macros in expression position
     ($query:expr) => ({
         $crate::sqlx_macros::expand_query!(source = $query)
@@ -321,6 +514,12 @@ macro_rules! query (
 #[macro_export]
 #[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
 macro_rules! query_unchecked (
+    (db = $db:literal, $query:expr) => ({
+        $crate::sqlx_macros::expand_query!(db = $db, source = $query, checked = false)
+    });
+    (db = $db:literal, $query:expr, $($args:tt)*) => ({
+        $crate::sqlx_macros::expand_query!(db = $db, source = $query, args = [$($args)*], checked = false)
+    });
     ($query:expr) => ({
         $crate::sqlx_macros::expand_query!(source = $query, checked = false)
     });
@@ -374,6 +573,12 @@ macro_rules! query_unchecked (
 #[macro_export]
 #[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
 macro_rules! query_file (
+    (db = $db:literal, $path:literal) => ({
+        $crate::sqlx_macros::expand_query!(db = $db, source_file = $path)
+    });
+    (db = $db:literal, $path:literal, $($args:tt)*) => ({
+        $crate::sqlx_macros::expand_query!(db = $db, source_file = $path, args = [$($args)*])
+    });
     ($path:literal) => ({
         $crate::sqlx_macros::expand_query!(source_file = $path)
     });
@@ -387,6 +592,12 @@ macro_rules! query_file (
 #[macro_export]
 #[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
 macro_rules! query_file_unchecked (
+    (db = $db:literal, $path:literal) => ({
+        $crate::sqlx_macros::expand_query!(db = $db, source_file = $path, checked = false)
+    });
+    (db = $db:literal, $path:literal, $($args:tt)*) => ({
+        $crate::sqlx_macros::expand_query!(db = $db, source_file = $path, args = [$($args)*], checked = false)
+    });
     ($path:literal) => ({
         $crate::sqlx_macros::expand_query!(source_file = $path, checked = false)
     });
@@ -409,7 +620,9 @@ macro_rules! query_file_unchecked (
 ///
 /// This enforces a few things:
 /// * The query must output at least one column.
-/// * The column names of the query must match the field names of the struct.
+/// * The column names of the query must match the field names of the struct, or the names given
+/// by its `#[sqlx(rename)]`/`#[sqlx(rename_all)]` attributes if it derives [`FromRow`][crate::FromRow]
+/// (see below).
 /// * The field types must be the Rust equivalent of their SQL counterparts; see the corresponding
 /// module for your database for mappings:
 ///     * Postgres: [crate::postgres::types]
@@ -497,6 +710,43 @@ macro_rules! query_file_unchecked (
 /// assert_eq!(record.id, MyInt4(1));
 /// ```
 ///
+/// ### Structs Deriving `FromRow`: Renamed Fields
+/// If the given struct derives [`FromRow`][crate::FromRow], which is told by the trait
+/// implementations the derive generates, columns are matched to fields the same way as in
+/// `FromRow`, so the struct's `#[sqlx(rename = "...")]` and `#[sqlx(rename_all = "...")]`
+/// attributes are honored:
+///
+/// ```rust,ignore
+/// #[derive(sqlx::FromRow)]
+/// #[sqlx(rename_all = "camelCase")]
+/// struct Account {
+///     account_id: i32,
+///     #[sqlx(rename = "name")]
+///     display_name: String,
+/// }
+///
+/// // Postgres
+/// let account = sqlx::query_as!(
+///     Account,
+///     r#"SELECT id AS "accountId", name FROM accounts WHERE id = $1"#,
+///     1i32
+/// )
+///     .fetch_one(&mut conn)
+///     .await?;
+/// ```
+///
+/// Fields marked `#[sqlx(flatten)]` are read from the same columns as their own fields, and
+/// `#[sqlx(json)]` fields expect the column to be overridden to `Json<_>`
+/// (e.g. `settings as "settings: Json<Settings>"`). Fields marked `#[sqlx(default)]` take their
+/// default value if the query has no column for them; their type is then only checked when
+/// decoding. Flattening with a `prefix` is a compile error, as the names of the columns are only
+/// known at runtime.
+///
+/// Unlike with other structs, the query may return columns that aren't used by the struct.
+/// A missing column or a field whose type doesn't match its column is reported as an unsatisfied
+/// trait bound such as ``SqlxQueryAsColumns<'_>: ColumnValue<{hash}, i32>``, where `i32` is the
+/// type of the field.
+///
 /// ### Troubleshooting: "error: mismatched types"
 /// If you get a "mismatched types" error from an invocation of this macro and the error
 /// isn't pointing specifically at a parameter.
@@ -550,6 +800,12 @@ macro_rules! query_file_unchecked (
 #[macro_export]
 #[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
 macro_rules! query_as (
+    (db = $db:literal, $out_struct:path, $query:expr) => ( {
+        $crate::sqlx_macros::expand_query!(db = $db, record = $out_struct, source = $query)
+    });
+    (db = $db:literal, $out_struct:path, $query:expr, $($args:tt)*) => ( {
+        $crate::sqlx_macros::expand_query!(db = $db, record = $out_struct, source = $query, args = [$($args)*])
+    });
     ($out_struct:path, $query:expr) => ( {
         $crate::sqlx_macros::expand_query!(record = $out_struct, source = $query)
     });
@@ -594,6 +850,12 @@ macro_rules! query_as (
 #[macro_export]
 #[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
 macro_rules! query_file_as (
+    (db = $db:literal, $out_struct:path, $path:literal) => ( {
+        $crate::sqlx_macros::expand_query!(db = $db, record = $out_struct, source_file = $path)
+    });
+    (db = $db:literal, $out_struct:path, $path:literal, $($args:tt)*) => ( {
+        $crate::sqlx_macros::expand_query!(db = $db, record = $out_struct, source_file = $path, args = [$($args)*])
+    });
     ($out_struct:path, $path:literal) => ( {
         $crate::sqlx_macros::expand_query!(record = $out_struct, source_file = $path)
     });
@@ -607,6 +869,12 @@ macro_rules! query_file_as (
 #[macro_export]
 #[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
 macro_rules! query_as_unchecked (
+    (db = $db:literal, $out_struct:path, $query:expr) => ( {
+        $crate::sqlx_macros::expand_query!(db = $db, record = $out_struct, source = $query, checked = false)
+    });
+    (db = $db:literal, $out_struct:path, $query:expr, $($args:tt)*) => ( {
+        $crate::sqlx_macros::expand_query!(db = $db, record = $out_struct, source = $query, args = [$($args)*], checked = false)
+    });
     ($out_struct:path, $query:expr) => ( {
         $crate::sqlx_macros::expand_query!(record = $out_struct, source = $query, checked = false)
     });
@@ -622,6 +890,12 @@ macro_rules! query_as_unchecked (
 #[macro_export]
 #[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
 macro_rules! query_file_as_unchecked (
+    (db = $db:literal, $out_struct:path, $path:literal) => ( {
+        $crate::sqlx_macros::expand_query!(db = $db, record = $out_struct, source_file = $path, checked = false)
+    });
+    (db = $db:literal, $out_struct:path, $path:literal, $($args:tt)*) => ( {
+        $crate::sqlx_macros::expand_query!(db = $db, record = $out_struct, source_file = $path, args = [$($args)*], checked = false)
+    });
     ($out_struct:path, $path:literal) => ( {
         $crate::sqlx_macros::expand_query!(record = $out_struct, source_file = $path, checked = false)
     });
@@ -648,6 +922,12 @@ macro_rules! query_file_as_unchecked (
 #[macro_export]
 #[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
 macro_rules! query_scalar (
+    (db = $db:literal, $query:expr) => (
+        $crate::sqlx_macros::expand_query!(db = $db, scalar = _, source = $query)
+    );
+    (db = $db:literal, $query:expr, $($args:tt)*) => (
+        $crate::sqlx_macros::expand_query!(db = $db, scalar = _, source = $query, args = [$($args)*])
+    );
     ($query:expr) => (
         $crate::sqlx_macros::expand_query!(scalar = _, source = $query)
     );
@@ -660,6 +940,12 @@ macro_rules! query_scalar (
 #[macro_export]
 #[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
 macro_rules! query_file_scalar (
+    (db = $db:literal, $path:literal) => (
+        $crate::sqlx_macros::expand_query!(db = $db, scalar = _, source_file = $path)
+    );
+    (db = $db:literal, $path:literal, $($args:tt)*) => (
+        $crate::sqlx_macros::expand_query!(db = $db, scalar = _, source_file = $path, args = [$($args)*])
+    );
     ($path:literal) => (
         $crate::sqlx_macros::expand_query!(scalar = _, source_file = $path)
     );
@@ -677,6 +963,12 @@ macro_rules! query_file_scalar (
 #[macro_export]
 #[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
 macro_rules! query_scalar_unchecked (
+    (db = $db:literal, $query:expr) => (
+        $crate::sqlx_macros::expand_query!(db = $db, scalar = _, source = $query, checked = false)
+    );
+    (db = $db:literal, $query:expr, $($args:tt)*) => (
+        $crate::sqlx_macros::expand_query!(db = $db, scalar = _, source = $query, args = [$($args)*], checked = false)
+    );
     ($query:expr) => (
         $crate::sqlx_macros::expand_query!(scalar = _, source = $query, checked = false)
     );
@@ -694,6 +986,12 @@ macro_rules! query_scalar_unchecked (
 #[macro_export]
 #[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
 macro_rules! query_file_scalar_unchecked (
+    (db = $db:literal, $path:literal) => (
+        $crate::sqlx_macros::expand_query!(db = $db, scalar = _, source_file = $path, checked = false)
+    );
+    (db = $db:literal, $path:literal, $($args:tt)*) => (
+        $crate::sqlx_macros::expand_query!(db = $db, scalar = _, source_file = $path, args = [$($args)*], checked = false)
+    );
     ($path:literal) => (
         $crate::sqlx_macros::expand_query!(scalar = _, source_file = $path, checked = false)
     );
@@ -702,6 +1000,64 @@ macro_rules! query_file_scalar_unchecked (
     )
 );
 
+/// A statically checked batch of SQL statements separated by semicolons, executed in a single
+/// round-trip where the database supports it.
+///
+/// Each statement is checked against the database on its own, with the same requirements as
+/// [query!]. This expands to a [`QueryBatch`][crate::query::QueryBatch], whose `.execute()`
+/// returns the rows affected by all statements together and `.execute_many()` those affected
+/// by each statement:
+///
+/// ```rust,ignore
+/// let result = sqlx::query_batch!(
+///     "UPDATE accounts SET balance = balance - 100 WHERE id = 1;
+///      UPDATE accounts SET balance = balance + 100 WHERE id = 2;
+///      INSERT INTO transfers (from_id, to_id, amount) VALUES (1, 2, 100);"
+/// )
+/// .execute(&mut tx)
+/// .await?;
+///
+/// assert_eq!(result.rows_affected(), 3);
+/// ```
+///
+/// The batch is sent without bind parameters, using the "simple" query protocol in Postgres and
+/// a multi-statement `COM_QUERY` in MySQL; so bind parameters cannot be used, and any rows
+/// returned by the statements are discarded. SQLite executes the statements one after the other.
+///
+/// Some limitations apply, as the statements are only checked, not executed, at compile time:
+///
+/// * A statement can't refer to tables or other objects created by an earlier statement of the
+///   same batch, unless they already exist in the database used for checking.
+/// * The batch is split at semicolons outside of strings, quoted identifiers and comments, so
+///   statements containing other semicolons (e.g. `BEGIN ... END` blocks of SQLite triggers)
+///   are not supported. In Postgres, use dollar-quoting for function bodies.
+///
+/// The batch is not run in a transaction unless it contains `BEGIN` and `COMMIT` statements, or
+/// is executed on a [`Transaction`][crate::Transaction].
+#[macro_export]
+#[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
+macro_rules! query_batch (
+    (db = $db:literal, $query:expr) => ({
+        $crate::sqlx_macros::expand_query!(db = $db, batch = true, source = $query)
+    });
+    ($query:expr) => ({
+        $crate::sqlx_macros::expand_query!(batch = true, source = $query)
+    })
+);
+
+/// A variant of [query_batch!] where the SQL script is stored in a separate file, like
+/// [query_file!].
+#[macro_export]
+#[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
+macro_rules! query_file_batch (
+    (db = $db:literal, $path:literal) => ({
+        $crate::sqlx_macros::expand_query!(db = $db, batch = true, source_file = $path)
+    });
+    ($path:literal) => ({
+        $crate::sqlx_macros::expand_query!(batch = true, source_file = $path)
+    })
+);
+
 /// Embeds migrations into the binary by expanding to a static instance of [Migrator][crate::migrate::Migrator].
 ///
 /// ```rust,ignore
@@ -716,12 +1072,32 @@ macro_rules! query_file_scalar_unchecked (
 /// static MIGRATOR: Migrator = sqlx::migrate!(); // defaults to "./migrations"
 /// ```
 ///
+/// Without a directory, the one set as `dir` in the `[migrate]` table of `sqlx.toml` is used if
+/// there is one, as is its `table-name` for the table recording applied migrations; see the
+/// `sqlx::config` module, enabled by the `config` feature.
+///
 /// The directory must be relative to the project root (the directory containing `Cargo.toml`),
 /// unlike `include_str!()` which uses compiler internals to get the path of the file where it
 /// was invoked.
 ///
 /// See [MigrationSource][crate::migrate::MigrationSource] for details on structure of the ./migrations directory.
 ///
+/// Migration files may start with comment headers configuring how they are run, such as
+/// `-- sqlx:no-transaction`; see [MigrationHeaders][crate::migrate::MigrationHeaders]. Invalid
+/// headers are reported at compile time. Migrations implemented in Rust can be added to a clone of
+/// the migrator with [`Migrator::add_migration()`][crate::migrate::Migrator::add_migration].
+///
+/// ## Merging Migration Directories
+/// Several directories may be given, e.g. for a workspace whose crates each have their own
+/// migrations, to embed all of them in one `Migrator` applying them as a single history in order
+/// of version. A version defined in more than one of the directories is a compile error.
+///
+/// ```rust,ignore
+/// static MIGRATOR: Migrator = sqlx::migrate!("../users/migrations", "../billing/migrations");
+/// ```
+///
+/// To merge `Migrator`s at runtime instead, see [`Migrator::merge()`][crate::migrate::Migrator::merge].
+///
 /// ## Triggering Recompilation on Migration Changes
 /// In some cases when making changes to embedded migrations, such as adding a new migration without
 /// changing any Rust source files, you might find that `cargo build` doesn't actually do anything,
@@ -786,11 +1162,11 @@ macro_rules! query_file_scalar_unchecked (
 #[cfg(feature = "migrate")]
 #[macro_export]
 macro_rules! migrate {
-    ($dir:literal) => {{
-        $crate::sqlx_macros::migrate!($dir)
+    ($($dir:literal),+ $(,)?) => {{
+        $crate::sqlx_macros::migrate!($($dir),+)
     }};
 
     () => {{
-        $crate::sqlx_macros::migrate!("./migrations")
+        $crate::sqlx_macros::migrate!()
     }};
 }
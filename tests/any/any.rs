@@ -27,6 +27,23 @@ async fn it_pings() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_shares_columns_between_rows() -> anyhow::Result<()> {
+    let mut conn = new::<Any>().await?;
+
+    let rows = conn
+        .fetch_all("SELECT 1 AS a, 2 AS b UNION ALL SELECT 3, 4")
+        .await?;
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].columns().as_ptr(), rows[1].columns().as_ptr());
+
+    assert_eq!(rows[0].try_get::<i32, _>("b")?, 2);
+    assert_eq!(rows[1].try_get::<i32, _>("b")?, 4);
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_executes_with_pool() -> anyhow::Result<()> {
     let pool = sqlx_test::pool::<Any>().await?;
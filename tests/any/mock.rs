@@ -0,0 +1,136 @@
+use sqlx::any::{AnyPoolOptions, AnyRow};
+use sqlx::mock::{MockDatabase, MockStatement};
+use sqlx::{Acquire, Connection, Row};
+
+async fn rename_user(pool: &sqlx::AnyPool, id: i64, name: &str) -> sqlx::Result<bool> {
+    let mut tx = pool.begin().await?;
+
+    let exists = sqlx::query("SELECT 1 FROM users WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut tx)
+        .await?
+        .is_some();
+
+    if exists {
+        sqlx::query("UPDATE users SET name = ? WHERE id = ?")
+            .bind(name.to_owned())
+            .bind(id)
+            .execute(&mut tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(exists)
+}
+
+#[sqlx_macros::test]
+async fn it_runs_a_script() -> anyhow::Result<()> {
+    let db = MockDatabase::new();
+
+    db.expect(MockStatement::new("BEGIN"))
+        .expect(
+            MockStatement::new("SELECT 1 FROM users WHERE id = ?")
+                .bind(7_i64)
+                .columns(["1"])
+                .row((1_i32,)),
+        )
+        .expect(
+            MockStatement::new("UPDATE users SET name = ? WHERE id = ?")
+                .bind("Bob")
+                .bind(7_i64)
+                .rows_affected(1),
+        )
+        .expect(MockStatement::new("COMMIT"));
+
+    let pool = AnyPoolOptions::new()
+        .max_connections(1)
+        .connect(&db.url())
+        .await?;
+
+    assert!(rename_user(&pool, 7, "Bob").await?);
+
+    db.verify();
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_returns_rows() -> anyhow::Result<()> {
+    let db = MockDatabase::new();
+
+    db.expect(
+        MockStatement::new("SELECT id, name FROM users")
+            .no_binds()
+            .columns(["id", "name"])
+            .row((1_i64, "Alice"))
+            .row((2_i64, Option::<String>::None)),
+    );
+
+    let mut conn = db.connect().await?;
+
+    let rows: Vec<AnyRow> = sqlx::query("SELECT id, name FROM users")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].try_get::<String, _>("name")?, "Alice");
+    assert_eq!(rows[1].try_get::<i64, _>("id")?, 2);
+    assert_eq!(rows[1].try_get::<Option<String>, _>("name")?, None);
+
+    db.verify();
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_fails_unexpected_statements() -> anyhow::Result<()> {
+    let db = MockDatabase::new();
+
+    db.expect(MockStatement::new("DELETE FROM users WHERE id = ?").bind(1_i64));
+
+    let mut conn = db.connect().await?;
+
+    let err = sqlx::query("DELETE FROM users WHERE id = ?")
+        .bind(2_i64)
+        .execute(&mut conn)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, sqlx::Error::Protocol(_)), "{:?}", err);
+
+    let verified = std::panic::catch_unwind(|| db.verify());
+
+    assert!(verified.is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_returns_database_errors() -> anyhow::Result<()> {
+    let db = MockDatabase::new();
+
+    db.expect(MockStatement::new("BEGIN"))
+        .expect(
+            MockStatement::new("INSERT INTO users (name) VALUES (?)")
+                .fails("23505", "duplicate key value violates unique constraint"),
+        )
+        .expect(MockStatement::new("ROLLBACK"));
+
+    let mut conn = db.connect().await?;
+    let mut tx = Connection::begin(&mut conn).await?;
+
+    let err = sqlx::query("INSERT INTO users (name) VALUES (?)")
+        .bind("Alice")
+        .execute(tx.acquire().await?)
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.as_database_error().unwrap().code().unwrap(), "23505");
+
+    tx.rollback().await?;
+
+    db.verify();
+
+    Ok(())
+}
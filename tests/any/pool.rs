@@ -210,3 +210,27 @@ async fn test_pool_callbacks() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn pool_should_hand_out_trusted_connections_without_waiting() -> anyhow::Result<()> {
+    use futures::FutureExt;
+
+    let pool = AnyPoolOptions::new()
+        .max_connections(1)
+        .test_before_acquire_trust_window(Duration::from_secs(60))
+        .connect(&dotenv::var("DATABASE_URL")?)
+        .await?;
+
+    drop(pool.acquire().await?);
+
+    // connections are released asynchronously
+    while pool.num_idle() == 0 {
+        sqlx_rt::sleep(Duration::from_millis(10)).await;
+    }
+
+    // the connection was released within the trust window, so it's handed out right away
+    let conn = pool.acquire().now_or_never();
+    assert!(matches!(conn, Some(Ok(_))));
+
+    Ok(())
+}
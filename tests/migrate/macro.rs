@@ -1,4 +1,4 @@
-use sqlx::migrate::Migrator;
+use sqlx::migrate::{MigrateError, Migrator};
 use std::path::Path;
 
 static EMBEDDED: Migrator = sqlx::migrate!("tests/migrate/migrations");
@@ -18,3 +18,34 @@ async fn same_output() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+static MERGED: Migrator = sqlx::migrate!(
+    "tests/migrate/migrations",
+    "tests/migrate/migrations_reversible"
+);
+
+#[sqlx_macros::test]
+async fn merged_output() -> anyhow::Result<()> {
+    let simple = Migrator::new(Path::new("tests/migrate/migrations")).await?;
+    let reversible = Migrator::new(Path::new("tests/migrate/migrations_reversible")).await?;
+    let runtime = Migrator::merge(&[&reversible, &simple])?;
+
+    assert_eq!(runtime.migrations.len(), MERGED.migrations.len());
+
+    for (e, r) in MERGED.iter().zip(runtime.iter()) {
+        assert_eq!(e.version, r.version);
+        assert_eq!(e.checksum, r.checksum);
+    }
+
+    assert!(MERGED
+        .iter()
+        .zip(MERGED.iter().skip(1))
+        .all(|(a, b)| a.version <= b.version));
+
+    assert!(matches!(
+        Migrator::merge(&[&simple, &reversible, &simple]),
+        Err(MigrateError::VersionCollision(20200723212833))
+    ));
+
+    Ok(())
+}
@@ -33,6 +33,38 @@ async fn macro_select_from_cte_bind() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn macro_select_bind_in_list() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let ids = vec![3i32, 1, 2];
+
+    let accounts = sqlx::query!(
+        "select * from (select (1) as id, 'Herp Derpinson' as name union select 4, 'Bob') accounts \
+         where id in (?) and name <> ?",
+        {in: ids},
+        "Bob"
+    )
+    .fetch_all(&mut conn)
+    .await?;
+
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].id, 1);
+
+    let ids: [i32; 0] = [];
+
+    let count = sqlx::query_scalar!(
+        "select count(*) from (select (1) as id union select 4) accounts where id not in (?)",
+        {in: ids}
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(count, 2);
+
+    Ok(())
+}
+
 #[derive(Debug)]
 struct RawAccount {
     r#type: i32,
@@ -1,5 +1,8 @@
 use futures::TryStreamExt;
-use sqlx::mysql::{MySql, MySqlConnection, MySqlPool, MySqlPoolOptions, MySqlRow};
+use sqlx::mysql::{
+    MySql, MySqlCompression, MySqlConnectOptions, MySqlConnection, MySqlPool, MySqlPoolOptions,
+    MySqlRow,
+};
 use sqlx::{Column, Connection, Executor, Row, Statement, TypeInfo};
 use sqlx_test::{new, setup_if_needed};
 use std::env;
@@ -118,6 +121,45 @@ async fn it_works_with_cache_disabled() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_executes_a_batch() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let results = conn
+        .execute_batch(
+            "CREATE TEMPORARY TABLE batch (id INTEGER PRIMARY KEY AUTO_INCREMENT); \
+             INSERT INTO batch () VALUES (), (); \
+             SELECT id FROM batch ORDER BY id",
+        )
+        .await?;
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[1].rows_affected(), 2);
+
+    let ids: Vec<i32> = results[2].rows().iter().map(|row| row.get(0)).collect();
+    assert_eq!(ids, [1, 2]);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_rejects_a_batch_without_multi_statements() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    let mut url = url::Url::parse(&env::var("DATABASE_URL")?)?;
+    url.query_pairs_mut()
+        .append_pair("multi-statements", "false");
+
+    let mut conn = MySqlConnection::connect(url.as_ref()).await?;
+
+    assert!(conn.execute_batch("SELECT 1; SELECT 2").await.is_err());
+
+    // the server rejects the second statement
+    assert!(conn.execute("SELECT 1; SELECT 2").await.is_err());
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_drops_results_in_affected_rows() -> anyhow::Result<()> {
     let mut conn = new::<MySql>().await?;
@@ -237,6 +279,60 @@ async fn it_caches_statements() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_closes_statements_over_max_prepared_statements() -> anyhow::Result<()> {
+    let options: MySqlConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let mut conn = MySqlConnection::connect_with(
+        &options
+            .statement_cache_capacity(2)
+            .max_prepared_statements(3),
+    )
+    .await?;
+
+    for i in 0..10 {
+        let val: i64 = sqlx::query_scalar(&format!("SELECT CAST(? AS SIGNED) + {}", i))
+            .bind(1_i32)
+            .persistent(i % 2 == 0)
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(val, i + 1);
+    }
+
+    assert_eq!(2, conn.cached_statements_size());
+
+    conn.clear_cached_statements().await?;
+    assert_eq!(0, conn.cached_statements_size());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_skips_packets_over_max_message_size() -> anyhow::Result<()> {
+    let options: MySqlConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let mut conn = MySqlConnection::connect_with(&options.max_message_size(1024)).await?;
+
+    let res = sqlx::query("SELECT REPEAT('x', ?)")
+        .bind(100_000_i32)
+        .fetch_one(&mut conn)
+        .await;
+
+    assert!(matches!(
+        res,
+        Err(sqlx::Error::MessageTooLarge { max: 1024, .. })
+    ));
+
+    // the connection is still usable
+    let val: String = sqlx::query_scalar("SELECT REPEAT('x', ?)")
+        .bind(10_i32)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(val, "x".repeat(10));
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_can_bind_null_and_non_null_issue_540() -> anyhow::Result<()> {
     let mut conn = new::<MySql>().await?;
@@ -446,3 +542,143 @@ async fn it_can_work_with_transactions() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_begins_transactions_with_options() -> anyhow::Result<()> {
+    use sqlx::{IsolationLevel, TransactionOptions};
+
+    let mut conn = new::<MySql>().await?;
+
+    let mut tx = conn
+        .begin_with(
+            TransactionOptions::new()
+                .isolation(IsolationLevel::Serializable)
+                .read_only(true),
+        )
+        .await?;
+
+    let _: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut tx).await?;
+
+    tx.rollback().await?;
+
+    assert!(conn
+        .begin_with(TransactionOptions::new().deferrable(true))
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_cancels_the_running_statement() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let token = conn.cancel_token();
+    let id: u64 = sqlx::query_scalar("SELECT CONNECTION_ID()")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(u64::from(token.connection_id()), id);
+
+    let canceller = sqlx_rt::spawn(async move {
+        sqlx_rt::sleep(std::time::Duration::from_millis(500)).await;
+        token.cancel().await.unwrap();
+    });
+
+    // an interrupted `SLEEP()` returns 1 instead of failing
+    let interrupted: i64 = sqlx::query_scalar("SELECT SLEEP(30)")
+        .fetch_one(&mut conn)
+        .await?;
+    let _ = canceller.await;
+
+    assert_eq!(interrupted, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reports_the_server_version() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let version: String = sqlx::query_scalar("SELECT VERSION()")
+        .fetch_one(&mut conn)
+        .await?;
+    let server_version = conn.server_version().unwrap();
+
+    assert!(version.contains(&server_version.to_string()));
+    assert_eq!(conn.is_mariadb(), version.contains("MariaDB"));
+    assert!(conn.server_capabilities().json());
+
+    Ok(())
+}
+
+// polls `future` until it waited `suspensions` times, then drops it, as a timeout would;
+// returns its output if it completed before
+async fn cancel_after<F: std::future::Future>(future: F, suspensions: usize) -> Option<F::Output> {
+    futures::pin_mut!(future);
+    let mut remaining = suspensions;
+
+    futures::future::poll_fn(|cx| match future.as_mut().poll(cx) {
+        std::task::Poll::Ready(output) => std::task::Poll::Ready(Some(output)),
+        std::task::Poll::Pending if remaining == 0 => std::task::Poll::Ready(None),
+        std::task::Poll::Pending => {
+            remaining -= 1;
+            std::task::Poll::Pending
+        }
+    })
+    .await
+}
+
+#[sqlx_macros::test]
+async fn it_recovers_from_queries_cancelled_at_every_stage() -> anyhow::Result<()> {
+    let options: MySqlConnectOptions = env::var("DATABASE_URL")?.parse()?;
+
+    // larger than the socket buffers, so that it's sent and received in several steps
+    let value = "x".repeat(1024 * 1024);
+
+    for compression in [MySqlCompression::Disabled, MySqlCompression::Zlib] {
+        let mut conn =
+            MySqlConnection::connect_with(&options.clone().compression(compression)).await?;
+
+        // each stage is a point where the query waits for the server
+        for stage in 0.. {
+            // a new statement each time, prepared before the query
+            let sql = format!(
+                "SELECT ? AS value, {} AS stage UNION ALL SELECT ?, 0",
+                stage
+            );
+
+            let query = sqlx::query(&sql)
+                .bind(&value)
+                .bind(&value)
+                .fetch_all(&mut conn);
+
+            let completed = cancel_after(query, stage).await.transpose()?;
+
+            // the next query isn't affected by the one cancelled before
+            let echo: i64 = sqlx::query_scalar("SELECT ?")
+                .bind(stage as i64)
+                .fetch_one(&mut conn)
+                .await?;
+
+            assert_eq!(echo, stage as i64);
+
+            if let Some(rows) = completed {
+                assert_eq!(rows.len(), 2);
+                break;
+            }
+        }
+
+        // the same for a ping
+        for stage in 0.. {
+            let completed = cancel_after(conn.ping(), stage).await.transpose()?;
+
+            conn.ping().await?;
+
+            if completed.is_some() {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -33,6 +33,26 @@ enum Weak {
     Three = 4,
 }
 
+// Unknown values of either kind of enum can decode to a fallback variant
+#[derive(PartialEq, Copy, Clone, Debug, sqlx::Type)]
+#[repr(i16)]
+enum WeakWithOther {
+    One = 1,
+    Two = 2,
+    #[sqlx(other)]
+    Unknown = -1,
+}
+
+#[derive(PartialEq, Debug, sqlx::Type)]
+#[sqlx(type_name = "text")]
+#[sqlx(rename_all = "lowercase")]
+enum StrongWithOther {
+    One,
+    Two,
+    #[sqlx(other)]
+    Unknown,
+}
+
 // "Strong" enums can map to TEXT (25)
 #[derive(PartialEq, Debug, sqlx::Type)]
 #[sqlx(type_name = "text")]
@@ -151,6 +171,73 @@ test_type!(strong_enum<Strong>(Postgres,
     "'four'::text" == Strong::Three
 ));
 
+test_type!(weak_enum_with_other<WeakWithOther>(Postgres,
+    "1::int2" == WeakWithOther::One,
+    "2::int2" == WeakWithOther::Two,
+    "-1::int2" == WeakWithOther::Unknown
+));
+
+test_type!(strong_enum_with_other<StrongWithOther>(Postgres,
+    "'one'::text" == StrongWithOther::One,
+    "'two'::text" == StrongWithOther::Two,
+    "'unknown'::text" == StrongWithOther::Unknown
+));
+
+#[sqlx_macros::test]
+async fn test_enum_other() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let (weak, strong): (WeakWithOther, StrongWithOther) =
+        sqlx::query_as("SELECT 3::int2, 'three'::text")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(weak, WeakWithOther::Unknown);
+    assert_eq!(strong, StrongWithOther::Unknown);
+
+    Ok(())
+}
+
+// Types can derive only one of `Decode` and `Encode`
+#[derive(PartialEq, Debug, sqlx::Type)]
+#[sqlx(type_name = "status", rename_all = "lowercase", decode_only)]
+enum ReadOnlyStatus {
+    New,
+    Open,
+    Closed,
+}
+
+#[derive(sqlx::Type)]
+#[sqlx(transparent, encode_only)]
+struct WriteOnlyId(i32);
+
+// `Decode` can be derived on its own for a type implementing `Type` by hand
+#[derive(PartialEq, Debug, sqlx::Decode)]
+struct Label(String);
+
+impl sqlx::Type<Postgres> for Label {
+    fn type_info() -> sqlx::postgres::PgTypeInfo {
+        <String as sqlx::Type<Postgres>>::type_info()
+    }
+}
+
+#[sqlx_macros::test]
+async fn test_decode_only_encode_only() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let (status, label, id): (ReadOnlyStatus, Label, i32) =
+        sqlx::query_as("SELECT 'open'::status, 'fragile'::text, $1")
+            .bind(WriteOnlyId(7))
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(status, ReadOnlyStatus::Open);
+    assert_eq!(label, Label("fragile".to_owned()));
+    assert_eq!(id, 7);
+
+    Ok(())
+}
+
 test_type!(floatrange<FloatRange>(Postgres,
     "'[1.234, 5.678]'::float_range" == FloatRange(PgRange::from((Bound::Included(1.234), Bound::Included(5.678)))),
 ));
@@ -551,6 +638,73 @@ async fn test_from_row_tuple() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[cfg(feature = "macros")]
+#[sqlx_macros::test]
+async fn test_from_row_tuple_attributes() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    #[derive(Debug, sqlx::FromRow)]
+    struct Account(
+        i32,
+        #[sqlx(json)] Vec<String>,
+        #[sqlx(default)] Option<String>,
+    );
+
+    let account: Account = sqlx::query_as(r#"SELECT 1, '["a", "b"]'::jsonb"#)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(account.0, 1);
+    assert_eq!(account.1, ["a", "b"]);
+    assert_eq!(account.2, None);
+
+    Ok(())
+}
+
+#[cfg(feature = "macros")]
+#[sqlx_macros::test]
+async fn test_from_row_generic() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    #[derive(Debug, sqlx::FromRow)]
+    struct Account {
+        id: i32,
+        name: String,
+    }
+
+    #[derive(Debug, sqlx::FromRow)]
+    struct Tagged<T, U> {
+        tag: U,
+        #[sqlx(flatten)]
+        item: T,
+        #[sqlx(default)]
+        extra: U,
+    }
+
+    let tagged: Tagged<Account, String> =
+        sqlx::query_as("SELECT 'admin' AS tag, 1 AS id, 'Herp Derpinson' AS name")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(tagged.tag, "admin");
+    assert_eq!(tagged.item.id, 1);
+    assert_eq!(tagged.item.name, "Herp Derpinson");
+    assert_eq!(tagged.extra, "");
+
+    #[derive(Debug, sqlx::FromRow)]
+    struct Pair<'a, T>(T, &'a str);
+
+    let mut cursor = sqlx::query("SELECT 1::int8, 'Herp Derpinson'").fetch(&mut conn);
+
+    let row = cursor.try_next().await?.unwrap();
+    let pair = Pair::<i64>::from_row(&row)?;
+
+    assert_eq!(pair.0, 1);
+    assert_eq!(pair.1, "Herp Derpinson");
+
+    Ok(())
+}
+
 #[cfg(feature = "macros")]
 #[sqlx_macros::test]
 async fn test_default() -> anyhow::Result<()> {
@@ -614,3 +768,233 @@ async fn test_flatten() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg(feature = "macros")]
+#[sqlx_macros::test]
+async fn test_flatten_prefix() -> anyhow::Result<()> {
+    #[derive(Debug, sqlx::FromRow)]
+    struct Country {
+        name: String,
+    }
+
+    #[derive(Debug, sqlx::FromRow)]
+    #[sqlx(rename_all = "camelCase")]
+    struct Author {
+        id: i32,
+        display_name: String,
+        #[sqlx(flatten, prefix = "country_")]
+        country: Country,
+    }
+
+    #[derive(Debug, sqlx::FromRow)]
+    struct Post {
+        id: i32,
+        title: String,
+        #[sqlx(flatten, prefix = "author_")]
+        author: Author,
+    }
+
+    let mut conn = new::<Postgres>().await?;
+
+    let post: Post = sqlx::query_as(
+        r#"SELECT * from (VALUES (1, 'Hello', 2, 'foo', 'Norway')) posts("id", "title", "author_id", "author_displayName", "author_country_name")"#,
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(1, post.id);
+    assert_eq!("Hello", post.title);
+    assert_eq!(2, post.author.id);
+    assert_eq!("foo", post.author.display_name);
+    assert_eq!("Norway", post.author.country.name);
+
+    Ok(())
+}
+
+#[cfg(all(feature = "macros", feature = "json"))]
+#[sqlx_macros::test]
+async fn test_json_field() -> anyhow::Result<()> {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Settings {
+        theme: String,
+        notifications: bool,
+    }
+
+    #[derive(Debug, sqlx::FromRow)]
+    struct Account {
+        id: i32,
+        #[sqlx(json)]
+        settings: Settings,
+        #[sqlx(json, rename = "tags")]
+        labels: Vec<String>,
+        #[sqlx(json, default)]
+        extra: Option<Settings>,
+    }
+
+    let mut conn = new::<Postgres>().await?;
+
+    let account: Account = sqlx::query_as(
+        r#"SELECT * from (VALUES (1, '{"theme": "dark", "notifications": true}'::jsonb, '["a", "b"]'::json)) accounts("id", "settings", "tags")"#,
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(1, account.id);
+    assert_eq!(
+        Settings {
+            theme: "dark".into(),
+            notifications: true
+        },
+        account.settings
+    );
+    assert_eq!(vec!["a", "b"], account.labels);
+    assert_eq!(None, account.extra);
+
+    Ok(())
+}
+
+#[cfg(all(feature = "macros", feature = "json"))]
+#[sqlx_macros::test]
+async fn test_json_field_null() -> anyhow::Result<()> {
+    #[derive(Debug, sqlx::FromRow)]
+    struct Account {
+        id: i32,
+        #[sqlx(json)]
+        tags: Option<Vec<String>>,
+    }
+
+    #[derive(Debug, sqlx::FromRow)]
+    struct TupleAccount(i32, #[sqlx(json)] Option<Vec<String>>);
+
+    let mut conn = new::<Postgres>().await?;
+
+    let accounts: Vec<Account> = sqlx::query_as(
+        r#"SELECT * from (VALUES (1, NULL::jsonb), (2, '["a"]'::jsonb)) accounts("id", "tags")"#,
+    )
+    .fetch_all(&mut conn)
+    .await?;
+
+    assert_eq!(accounts[0].id, 1);
+    assert_eq!(accounts[0].tags, None);
+    assert_eq!(accounts[1].tags, Some(vec!["a".to_owned()]));
+
+    let account: TupleAccount = sqlx::query_as("SELECT 1, NULL::jsonb")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(account.1, None);
+
+    Ok(())
+}
+
+#[cfg(feature = "macros")]
+#[sqlx_macros::test]
+async fn test_decode_with() -> anyhow::Result<()> {
+    #[derive(Debug, PartialEq)]
+    struct Version(u32, u32);
+
+    fn parse_version(s: String) -> Result<Version, String> {
+        let (major, minor) = s
+            .split_once('.')
+            .ok_or_else(|| format!("invalid version: {:?}", s))?;
+
+        Ok(Version(
+            major.parse().map_err(|_| "invalid major version")?,
+            minor.parse().map_err(|_| "invalid minor version")?,
+        ))
+    }
+
+    fn unknown_version() -> Version {
+        Version(0, 0)
+    }
+
+    #[derive(Debug, sqlx::FromRow)]
+    struct Package {
+        name: String,
+        #[sqlx(decode_with = "parse_version", decode_as = "String")]
+        version: Version,
+        #[sqlx(
+            rename = "previous",
+            decode_with = "parse_version",
+            decode_as = "String",
+            default = "unknown_version"
+        )]
+        previous_version: Version,
+    }
+
+    let mut conn = new::<Postgres>().await?;
+
+    let package: Package = sqlx::query_as("SELECT 'sqlx' AS name, '0.6' AS version")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!("sqlx", package.name);
+    assert_eq!(Version(0, 6), package.version);
+    assert_eq!(Version(0, 0), package.previous_version);
+
+    let err = sqlx::query_as::<_, Package>("SELECT 'sqlx' AS name, 'latest' AS version")
+        .fetch_one(&mut conn)
+        .await
+        .unwrap_err();
+
+    match err {
+        sqlx::Error::ColumnDecode { index, source } => {
+            assert_eq!(index, "\"version\"");
+            assert_eq!(source.to_string(), "invalid version: \"latest\"");
+        }
+        err => panic!("expected `ColumnDecode`, got {}", err),
+    }
+
+    #[derive(Debug, sqlx::FromRow)]
+    struct Pair(
+        String,
+        #[sqlx(decode_with = "parse_version", decode_as = "String")] Version,
+    );
+
+    let pair: Pair = sqlx::query_as("SELECT 'sqlx', '1.2'")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(Version(1, 2), pair.1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn test_table_insert_update() -> anyhow::Result<()> {
+    #[derive(Debug, PartialEq, sqlx::FromRow, sqlx::Table)]
+    #[sqlx(table = "tweet")]
+    struct Tweet {
+        id: i64,
+        #[sqlx(rename = "text")]
+        body: String,
+        owner_id: Option<i64>,
+    }
+
+    let mut conn = new::<Postgres>().await?;
+    let mut tx = conn.begin().await?;
+
+    let mut tweet = Tweet {
+        id: 1_000_000,
+        body: "Hello".into(),
+        owner_id: None,
+    };
+
+    assert_eq!(1, tweet.insert(&mut tx).await?.rows_affected());
+
+    tweet.body = "Hello, world".into();
+    tweet.owner_id = Some(5);
+
+    assert_eq!(1, tweet.update_by_pk(&mut tx).await?.rows_affected());
+
+    let saved: Tweet = sqlx::query_as("SELECT id, text, owner_id FROM tweet WHERE id = $1")
+        .bind(tweet.id)
+        .fetch_one(&mut tx)
+        .await?;
+
+    assert_eq!(tweet, saved);
+
+    tx.rollback().await?;
+
+    Ok(())
+}
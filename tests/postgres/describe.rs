@@ -34,14 +34,79 @@ async fn it_describes_expression() -> anyhow::Result<()> {
     // ?column? will cause the macro to emit an error ad ask the user to explicitly name the type
     assert_eq!(d.columns()[0].name(), "?column?");
 
-    // postgres cannot infer nullability from an expression
-    // this will cause the macro to emit `Option<_>`
-    assert_eq!(d.nullable(0), None);
+    // the nullability of the expression is inferred from its operands
+    assert_eq!(d.nullable(0), Some(false));
     assert_eq!(d.columns()[0].type_info().name(), "INT8");
 
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_describes_nullability_of_expressions() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let d = conn
+        .describe(
+            "SELECT COALESCE(owner_id, 0), owner_id + 1, lower(text), count(*) OVER (), \
+                    CASE WHEN id > 1 THEN text END \
+             FROM tweet",
+        )
+        .await?;
+
+    assert_eq!(d.nullable(0), Some(false));
+    assert_eq!(d.nullable(1), Some(true));
+    assert_eq!(d.nullable(2), Some(false));
+    assert_eq!(d.nullable(3), Some(false));
+    assert_eq!(d.nullable(4), Some(true));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_describes_nullability_of_outer_joins() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    // the join is under a sort, and the right side of a left join is null-extended
+    let d = conn
+        .describe(
+            "SELECT t1.id, t2.id, t2.text \
+             FROM tweet t1 LEFT JOIN tweet t2 ON t2.owner_id = t1.id \
+             ORDER BY t1.created_at",
+        )
+        .await?;
+
+    assert_eq!(d.nullable(0), Some(false));
+    assert_eq!(d.nullable(1), Some(true));
+    assert_eq!(d.nullable(2), Some(true));
+
+    // the left side of a right join is null-extended, whichever side the planner puts it on
+    let d = conn
+        .describe(
+            "SELECT t1.id, t2.id \
+             FROM tweet t1 RIGHT JOIN tweet t2 ON t2.owner_id = t1.id",
+        )
+        .await?;
+
+    assert_eq!(d.nullable(0), Some(true));
+    assert_eq!(d.nullable(1), Some(false));
+
+    // through an aggregate and a subquery
+    let d = conn
+        .describe(
+            "SELECT t1.id, count(x.id), max(x.text), COALESCE(x.text, '') \
+             FROM tweet t1 \
+             LEFT JOIN (SELECT id, text, owner_id FROM tweet) x ON x.owner_id = t1.id \
+             GROUP BY t1.id, x.text",
+        )
+        .await?;
+
+    assert_eq!(d.nullable(0), Some(false));
+    assert_eq!(d.nullable(1), Some(false));
+    assert_eq!(d.nullable(3), Some(false));
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_describes_enum() -> anyhow::Result<()> {
     let mut conn = new::<Postgres>().await?;
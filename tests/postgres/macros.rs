@@ -14,8 +14,49 @@ async fn test_query() -> anyhow::Result<()> {
     .fetch_one(&mut conn)
     .await?;
 
-    assert_eq!(account.id, Some(1));
-    assert_eq!(account.name.as_deref(), Some("Herp Derpinson"));
+    assert_eq!(account.id, 1);
+    assert_eq!(account.name, "Herp Derpinson");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn test_query_bind_in_list() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let ids = vec![3i32, 1, 2];
+
+    let accounts = sqlx::query!(
+        "SELECT * from (VALUES (1, 'Herp Derpinson'), (4, 'Bob')) accounts(id, name) \
+         where id IN ($1) AND name <> $2",
+        {in: ids},
+        "Bob"
+    )
+    .fetch_all(&mut conn)
+    .await?;
+
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].id, Some(1));
+
+    let count = sqlx::query_scalar!(
+        "SELECT count(*) from (VALUES (1), (4)) accounts(id) where id NOT IN ($1)",
+        {in: [1i32, 2, 3]}
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(count, 1);
+
+    let ids: Vec<i32> = Vec::new();
+
+    let count = sqlx::query_scalar!(
+        "SELECT count(*) from (VALUES (1), (4)) accounts(id) where id NOT IN ($1)",
+        {in: ids}
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(count, 2);
 
     Ok(())
 }
@@ -54,17 +95,37 @@ async fn test_no_result() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn test_query_batch() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+    let mut tx = conn.begin().await?;
+
+    let result = sqlx::query_batch!(
+        "INSERT INTO tweet (text) VALUES ('batch'), ('batch');
+         UPDATE tweet SET owner_id = 1 WHERE text = 'batch';
+         -- a comment; with a semicolon
+         DELETE FROM tweet WHERE text = 'batch' AND owner_id = 1;"
+    )
+    .execute(&mut tx)
+    .await?;
+
+    assert_eq!(result.rows_affected(), 6);
+
+    // let the transaction rollback so we don't actually insert the tweets
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn test_text_var_char_char_n() -> anyhow::Result<()> {
     let mut conn = new::<Postgres>().await?;
 
     // TEXT
-    // we cannot infer nullability from an expression
     let rec = sqlx::query!("SELECT 'Hello'::text as greeting")
         .fetch_one(&mut conn)
         .await?;
 
-    assert_eq!(rec.greeting.as_deref(), Some("Hello"));
+    assert_eq!(rec.greeting, "Hello");
 
     // VARCHAR(N)
 
@@ -72,7 +133,7 @@ async fn test_text_var_char_char_n() -> anyhow::Result<()> {
         .fetch_one(&mut conn)
         .await?;
 
-    assert_eq!(rec.greeting.as_deref(), Some("Hello"));
+    assert_eq!(rec.greeting, "Hello");
 
     // CHAR(N)
 
@@ -80,7 +141,7 @@ async fn test_text_var_char_char_n() -> anyhow::Result<()> {
         .fetch_one(&mut conn)
         .await?;
 
-    assert_eq!(rec.greeting.as_deref(), Some("Hello"));
+    assert_eq!(rec.greeting, "Hello");
 
     Ok(())
 }
@@ -163,6 +224,166 @@ async fn test_query_as_raw() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[derive(Debug, sqlx::FromRow)]
+#[sqlx(rename_all = "camelCase")]
+struct RenamedAccount {
+    account_id: i32,
+    #[sqlx(rename = "name")]
+    display_name: String,
+    created_by: MyInt4,
+}
+
+#[sqlx_macros::test]
+async fn test_query_as_renamed() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let account = sqlx::query_as!(
+        RenamedAccount,
+        r#"SELECT id "accountId!", name, creator "createdBy!: _", 'unused' AS extra
+        from (VALUES (1, 'Herp Derpinson', 2)) accounts(id, name, creator)"#
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(1, account.account_id);
+    assert_eq!("Herp Derpinson", account.display_name);
+    assert_eq!(MyInt4(2), account.created_by);
+
+    Ok(())
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct AccountWithDefaults {
+    id: i32,
+    #[sqlx(default)]
+    nickname: Option<String>,
+    #[sqlx(default)]
+    logins: i64,
+}
+
+#[sqlx_macros::test]
+async fn test_query_as_default() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let account = sqlx::query_as!(AccountWithDefaults, r#"SELECT 1 AS "id!""#)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(1, account.id);
+    assert_eq!(None, account.nickname);
+    assert_eq!(0, account.logins);
+
+    let account = sqlx::query_as!(
+        AccountWithDefaults,
+        r#"SELECT 2 AS "id!", 'herp' AS nickname, 3::int8 AS "logins!""#
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(2, account.id);
+    assert_eq!(Some("herp"), account.nickname.as_deref());
+    assert_eq!(3, account.logins);
+
+    Ok(())
+}
+
+// structs with the same name, of which only one derives `FromRow`
+mod derived {
+    #[derive(Debug, sqlx::FromRow)]
+    #[sqlx(rename_all = "camelCase")]
+    pub struct Member {
+        pub member_id: i32,
+    }
+}
+
+mod plain {
+    #[derive(Debug)]
+    pub struct Member {
+        pub id: i32,
+    }
+}
+
+#[sqlx_macros::test]
+async fn test_query_as_same_name() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let member = sqlx::query_as!(derived::Member, r#"SELECT 1 AS "memberId!""#)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(1, member.member_id);
+
+    let member = sqlx::query_as!(plain::Member, r#"SELECT 2 AS "id!""#)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(2, member.id);
+
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+struct Version(u32, u32);
+
+fn parse_version(s: String) -> Result<Version, std::num::ParseIntError> {
+    let (major, minor) = s.split_once('.').unwrap_or((&s, "0"));
+
+    Ok(Version(major.parse()?, minor.parse()?))
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct Package {
+    name: String,
+    #[sqlx(decode_with = "parse_version", decode_as = "String")]
+    version: Version,
+}
+
+#[sqlx_macros::test]
+async fn test_query_as_decode_with() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let package = sqlx::query_as!(Package, "SELECT 'sqlx' AS name, '0.6' AS version")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!("sqlx", package.name);
+    assert_eq!(Version(0, 6), package.version);
+
+    Ok(())
+}
+
+#[derive(PartialEq, Debug, sqlx::Type)]
+#[sqlx(type_name = "status", rename_all = "lowercase")]
+enum Status {
+    New,
+    Open,
+    Closed,
+}
+
+#[derive(Debug)]
+struct Ticket {
+    id: i32,
+    status: Status,
+}
+
+#[sqlx_macros::test]
+async fn test_query_as_wildcard_custom_type() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    // `status` can't be given an override with `*`, so its type is taken from `Ticket`
+    let ticket = sqlx::query_as!(
+        Ticket,
+        "SELECT * from (VALUES (1, 'open'::status)) tickets(id, status)"
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(1, ticket.id);
+    assert_eq!(Status::Open, ticket.status);
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn test_query_file_as() -> anyhow::Result<()> {
     let mut conn = new::<Postgres>().await?;
@@ -181,14 +402,13 @@ async fn test_query_scalar() -> anyhow::Result<()> {
     let mut conn = new::<Postgres>().await?;
 
     let id = sqlx::query_scalar!("select 1").fetch_one(&mut conn).await?;
-    // nullability inference can't handle expressions
-    assert_eq!(id, Some(1i32));
+    assert_eq!(id, 1i32);
 
     // invalid column names are ignored
     let id = sqlx::query_scalar!(r#"select 1 as "&foo""#)
         .fetch_one(&mut conn)
         .await?;
-    assert_eq!(id, Some(1i32));
+    assert_eq!(id, 1i32);
 
     let id = sqlx::query_scalar!(r#"select 1 as "foo!""#)
         .fetch_one(&mut conn)
@@ -205,7 +425,7 @@ async fn test_query_scalar() -> anyhow::Result<()> {
         .fetch_one(&mut conn)
         .await?;
 
-    assert_eq!(id, Some(MyInt4(1i32)));
+    assert_eq!(id, MyInt4(1i32));
 
     let id = sqlx::query_scalar!(r#"select 1 as "foo?: MyInt4""#)
         .fetch_one(&mut conn)
@@ -221,9 +441,7 @@ async fn test_query_scalar() -> anyhow::Result<()> {
 
     let id: MyInt4 = sqlx::query_scalar!(r#"select 1 as "foo: _""#)
         .fetch_one(&mut conn)
-        .await?
-        // don't hint that it should be `Option<MyInt4>`
-        .unwrap();
+        .await?;
 
     assert_eq!(id, MyInt4(1i32));
 
@@ -265,7 +483,7 @@ async fn query_by_string() -> anyhow::Result<()> {
     .fetch_one(&mut conn)
     .await?;
 
-    assert_eq!(result.string, Some(string));
+    assert_eq!(result.string, string);
 
     Ok(())
 }
@@ -295,7 +513,7 @@ async fn query_by_bigdecimal() -> anyhow::Result<()> {
     .fetch_one(&mut conn)
     .await?;
 
-    assert_eq!(result.decimal, Some(decimal));
+    assert_eq!(result.decimal, decimal);
 
     Ok(())
 }
@@ -366,7 +584,7 @@ async fn test_array_from_slice() -> anyhow::Result<()> {
         .fetch_one(&mut conn)
         .await?;
 
-    assert_eq!(account.my_array, Some(vec![4, 3, 2, 1]));
+    assert_eq!(account.my_array, vec![4, 3, 2, 1]);
 
     println!("account ID: {:?}", account.my_array);
 
@@ -557,7 +775,7 @@ async fn test_bind_arg_override_exact() -> anyhow::Result<()> {
     .fetch_one(&mut conn)
     .await?;
 
-    assert_eq!(record.id, Some(1i32));
+    assert_eq!(record.id, 1i32);
 
     // test that we're actually emitting the typecast by requiring the bound type to be the same
     let record = sqlx::query!("select $1::int8 as id", 1i32 as i64)
@@ -576,7 +794,7 @@ async fn test_bind_arg_override_exact() -> anyhow::Result<()> {
     .fetch_one(&mut conn)
     .await?;
 
-    assert_eq!(record.id, Some(1i32));
+    assert_eq!(record.id, 1i32);
 
     Ok(())
 }
@@ -594,7 +812,7 @@ async fn test_bind_arg_override_wildcard() -> anyhow::Result<()> {
     .fetch_one(&mut conn)
     .await?;
 
-    assert_eq!(record.id, Some(1i32));
+    assert_eq!(record.id, 1i32);
 
     Ok(())
 }
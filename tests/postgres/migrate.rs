@@ -0,0 +1,306 @@
+use sqlx::migrate::{
+    BatchedQuery, Migrate, MigrateError, Migration, MigrationFiles, MigrationLock, MigrationType,
+    Migrator, DEFAULT_TABLE_NAME,
+};
+use sqlx::postgres::{PgConnection, Postgres};
+use sqlx::Executor;
+use sqlx_test::new;
+use std::borrow::Cow;
+use std::time::Duration;
+
+const VERSION: i64 = 20220801000000;
+
+async fn reset(conn: &mut PgConnection, version: i64) -> anyhow::Result<()> {
+    conn.ensure_migrations_table(DEFAULT_TABLE_NAME).await?;
+
+    sqlx::query("DELETE FROM _sqlx_migrations WHERE version = $1")
+        .bind(version)
+        .execute(&mut *conn)
+        .await?;
+
+    conn.execute("DROP INDEX IF EXISTS tweet_text_headers")
+        .await?;
+
+    Ok(())
+}
+
+async fn index_exists(conn: &mut PgConnection) -> anyhow::Result<bool> {
+    Ok(sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM pg_indexes WHERE indexname = 'tweet_text_headers')",
+    )
+    .fetch_one(&mut *conn)
+    .await?)
+}
+
+#[sqlx_macros::test]
+async fn it_applies_migrations_without_transaction() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+    reset(&mut conn, VERSION).await?;
+    conn.execute("DROP INDEX IF EXISTS tweet_text_headers")
+        .await?;
+
+    let up = Migration::new(
+        VERSION,
+        Cow::Borrowed("index tweet text"),
+        MigrationType::ReversibleUp,
+        Cow::Borrowed(
+            "-- sqlx:no-transaction\n\
+             -- sqlx:lock-timeout=5s\n\
+             -- sqlx:require-db=postgres\n\
+             CREATE INDEX CONCURRENTLY tweet_text_headers ON tweet (text);",
+        ),
+    );
+
+    let down = Migration::new(
+        VERSION,
+        Cow::Borrowed("index tweet text"),
+        MigrationType::ReversibleDown,
+        Cow::Borrowed(
+            "-- sqlx:no-transaction\n\
+             DROP INDEX CONCURRENTLY tweet_text_headers;",
+        ),
+    );
+
+    conn.apply(DEFAULT_TABLE_NAME, &up).await?;
+    assert!(index_exists(&mut conn).await?);
+
+    // the lock timeout only applies to the migration
+    let lock_timeout: String = sqlx::query_scalar("SHOW lock_timeout")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(lock_timeout, "0");
+
+    conn.revert(DEFAULT_TABLE_NAME, &down).await?;
+    assert!(!index_exists(&mut conn).await?);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_checks_migration_headers() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+    reset(&mut conn, VERSION + 1).await?;
+
+    let migration = |sql: &'static str| {
+        Migration::new(
+            VERSION + 1,
+            Cow::Borrowed("headers"),
+            MigrationType::Simple,
+            Cow::Borrowed(sql),
+        )
+    };
+
+    assert!(matches!(
+        conn.apply(DEFAULT_TABLE_NAME, &migration("-- sqlx:require-db=sqlite\nSELECT 1")).await,
+        Err(MigrateError::RequiresDatabase(_, db)) if db == "sqlite"
+    ));
+
+    assert!(matches!(
+        conn.apply(
+            DEFAULT_TABLE_NAME,
+            &migration("-- sqlx:no-transactions\nSELECT 1")
+        )
+        .await,
+        Err(MigrateError::InvalidHeader(_, _))
+    ));
+
+    // in a transaction, the lock timeout is local to it
+    conn.apply(
+        DEFAULT_TABLE_NAME,
+        &migration("-- sqlx:lock-timeout=100ms\nSELECT 1"),
+    )
+    .await?;
+
+    let lock_timeout: String = sqlx::query_scalar("SHOW lock_timeout")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(lock_timeout, "0");
+
+    reset(&mut conn, VERSION + 1).await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_dumps_schema() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute(
+        r#"
+DROP SCHEMA IF EXISTS dump_schema_test CASCADE;
+CREATE SCHEMA dump_schema_test;
+SET search_path TO dump_schema_test;
+
+CREATE TYPE mood AS ENUM ('sad', 'happy');
+CREATE TABLE author (id BIGSERIAL PRIMARY KEY, name TEXT NOT NULL UNIQUE, mood mood);
+CREATE TABLE book (
+    id BIGINT PRIMARY KEY,
+    author_id BIGINT NOT NULL REFERENCES author (id),
+    title TEXT NOT NULL DEFAULT ''
+);
+CREATE INDEX book_title ON book (title);
+CREATE VIEW happy_author AS SELECT name FROM author WHERE mood = 'happy';
+        "#,
+    )
+    .await?;
+
+    // the migrations table is left out
+    conn.ensure_migrations_table(DEFAULT_TABLE_NAME).await?;
+
+    let schema = conn.dump_schema(DEFAULT_TABLE_NAME).await?;
+
+    conn.execute("DROP SCHEMA dump_schema_test CASCADE").await?;
+
+    let (tables, views) = schema.split_at(schema.find("CREATE VIEW").unwrap_or(schema.len()));
+
+    assert_eq!(
+        tables,
+        r#"CREATE TYPE mood AS ENUM ('sad', 'happy');
+
+CREATE TABLE author (
+    id bigint NOT NULL DEFAULT nextval('author_id_seq'::regclass),
+    name text NOT NULL,
+    mood mood,
+    CONSTRAINT author_name_key UNIQUE (name),
+    CONSTRAINT author_pkey PRIMARY KEY (id)
+);
+
+CREATE TABLE book (
+    id bigint NOT NULL,
+    author_id bigint NOT NULL,
+    title text NOT NULL DEFAULT ''::text,
+    CONSTRAINT book_author_id_fkey FOREIGN KEY (author_id) REFERENCES author(id),
+    CONSTRAINT book_pkey PRIMARY KEY (id)
+);
+
+CREATE INDEX book_title ON dump_schema_test.book USING btree (title);
+
+"#
+    );
+
+    // the formatting of the query depends on the version of Postgres
+    assert!(views.starts_with("CREATE VIEW happy_author AS\n"));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_waits_for_the_migration_lock() -> anyhow::Result<()> {
+    let mut holder = new::<Postgres>().await?;
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute("DROP TABLE IF EXISTS _lock_test_migrations")
+        .await?;
+
+    let mut migrator = Migrator::new(MigrationFiles::new([("1_noop.sql", "SELECT 1")])).await?;
+    migrator.set_table_name("_lock_test_migrations");
+    migrator.set_lock_key(VERSION);
+    migrator.set_lock_timeout(Duration::from_millis(200));
+
+    let lock = MigrationLock {
+        key: Some(VERSION),
+        timeout: None,
+    };
+
+    holder.lock(lock).await?;
+
+    assert!(matches!(
+        migrator.run(&mut conn).await,
+        Err(MigrateError::LockTimeout)
+    ));
+
+    holder.unlock(lock).await?;
+    migrator.run(&mut conn).await?;
+
+    // the lock is released after migrating
+    holder.lock(lock).await?;
+    holder.unlock(lock).await?;
+
+    conn.execute("DROP TABLE _lock_test_migrations").await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_runs_batched_queries() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute(
+        "CREATE TEMPORARY TABLE batched (id SERIAL PRIMARY KEY, value INT NOT NULL); \
+         INSERT INTO batched (value) SELECT generate_series(1, 95)",
+    )
+    .await?;
+
+    let mut batches = 0;
+
+    let rows = BatchedQuery::<i32>::new(
+        "batched",
+        "id",
+        "UPDATE batched SET value = -value WHERE id BETWEEN {start} AND {end}",
+    )
+    .batch_size(10)
+    .on_progress(|progress| batches = progress.batches)
+    .execute(&mut conn)
+    .await?;
+
+    assert_eq!(rows, 95);
+    assert_eq!(batches, 10);
+
+    let positive: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM batched WHERE value > 0")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(positive, 0);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_resumes_failed_migrations() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute("DROP TABLE IF EXISTS _resume_test_migrations; DROP TABLE IF EXISTS resumed")
+        .await?;
+
+    let migrator = |sql: &'static str| async move {
+        let mut migrator = Migrator::new(MigrationFiles::new([("1_resume.sql", sql)])).await?;
+        migrator.set_table_name("_resume_test_migrations");
+        Ok::<_, MigrateError>(migrator)
+    };
+
+    let failing = migrator(
+        "-- sqlx:resumable\n\
+         CREATE TABLE resumed (id INT);\n\
+         CREATE INDEX CONCURRENTLY resumed_id ON resumed (id);\n\
+         INSERT INTO resumed VALUES (1 / 0);\n\
+         INSERT INTO resumed VALUES (2);",
+    )
+    .await?;
+
+    assert!(failing.run(&mut conn).await.is_err());
+    assert_eq!(
+        conn.dirty_version("_resume_test_migrations").await?,
+        Some(1)
+    );
+
+    let fixed = migrator(
+        "-- sqlx:resumable\n\
+         CREATE TABLE resumed (id INT);\n\
+         CREATE INDEX CONCURRENTLY resumed_id ON resumed (id);\n\
+         INSERT INTO resumed VALUES (1);\n\
+         INSERT INTO resumed VALUES (2);",
+    )
+    .await?;
+
+    fixed.run(&mut conn).await?;
+
+    let ids: Vec<i32> = sqlx::query_scalar("SELECT id FROM resumed ORDER BY id")
+        .fetch_all(&mut conn)
+        .await?;
+    assert_eq!(ids, [1, 2]);
+    assert_eq!(conn.dirty_version("_resume_test_migrations").await?, None);
+
+    conn.execute("DROP TABLE _resume_test_migrations; DROP TABLE resumed")
+        .await?;
+
+    Ok(())
+}
@@ -1,13 +1,13 @@
 use futures::{StreamExt, TryStreamExt};
 use sqlx::postgres::types::Oid;
 use sqlx::postgres::{
-    PgAdvisoryLock, PgConnectOptions, PgConnection, PgDatabaseError, PgErrorPosition, PgListener,
-    PgPoolOptions, PgRow, PgSeverity, Postgres,
+    PgAdvisoryLock, PgConnectOptions, PgConnection, PgDatabaseError, PgErrorPosition,
+    PgFlushPolicy, PgListener, PgPoolOptions, PgRow, PgSeverity, Postgres,
 };
 use sqlx::{Column, Connection, Executor, Row, Statement, TypeInfo};
 use sqlx_test::{new, pool, setup_if_needed};
 use std::env;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 #[sqlx_macros::test]
@@ -154,6 +154,69 @@ async fn it_can_inspect_constraint_errors() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_maps_error_kinds() -> anyhow::Result<()> {
+    use sqlx::error::ErrorKind;
+
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute(
+        r#"
+CREATE TEMPORARY TABLE parents (id INT PRIMARY KEY);
+CREATE TEMPORARY TABLE children (
+    id INT PRIMARY KEY,
+    parent_id INT NOT NULL REFERENCES parents (id),
+    age INT CHECK (age > 0)
+);
+INSERT INTO parents VALUES (1);
+        "#,
+    )
+    .await?;
+
+    let cases = [
+        (
+            "INSERT INTO parents VALUES (1)",
+            ErrorKind::UniqueViolation,
+            Some("parents_pkey"),
+            None,
+        ),
+        (
+            "INSERT INTO children VALUES (1, 2, 1)",
+            ErrorKind::ForeignKeyViolation,
+            Some("children_parent_id_fkey"),
+            None,
+        ),
+        (
+            "INSERT INTO children VALUES (1, NULL, 1)",
+            ErrorKind::NotNullViolation,
+            None,
+            Some("parent_id"),
+        ),
+        (
+            "INSERT INTO children VALUES (1, 1, 0)",
+            ErrorKind::CheckViolation,
+            Some("children_age_check"),
+            None,
+        ),
+    ];
+
+    for (sql, kind, constraint, column) in cases {
+        let err = conn.execute(sql).await.unwrap_err();
+        let err = err.as_database_error().unwrap();
+
+        assert_eq!(err.kind(), kind, "{}", sql);
+        assert_eq!(err.constraint(), constraint, "{}", sql);
+        assert_eq!(err.column(), column, "{}", sql);
+        assert!(err.table().is_some(), "{}", sql);
+    }
+
+    let err = conn.execute("SELECT f").await.unwrap_err();
+
+    assert_eq!(err.as_database_error().unwrap().kind(), ErrorKind::Other);
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_executes() -> anyhow::Result<()> {
     let mut conn = new::<Postgres>().await?;
@@ -535,6 +598,286 @@ async fn it_can_drop_multiple_transactions() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_can_work_with_named_savepoints() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE saved (id INT4 NOT NULL)")
+        .await?;
+
+    let mut tx = conn.begin().await?;
+
+    tx.execute("INSERT INTO saved VALUES (1)").await?;
+
+    let mut savepoint = tx.savepoint("import").await?;
+
+    savepoint.execute("INSERT INTO saved VALUES (2)").await?;
+    savepoint.rollback_to().await?;
+
+    // the savepoint is kept once rolled back to
+    savepoint.execute("INSERT INTO saved VALUES (3)").await?;
+    savepoint.release().await?;
+
+    {
+        let mut savepoint = tx.savepoint("dropped").await?;
+
+        savepoint.execute("INSERT INTO saved VALUES (4)").await?;
+    }
+
+    tx.execute("INSERT INTO saved VALUES (5)").await?;
+
+    assert!(tx.savepoint("not a name; COMMIT").await.is_err());
+
+    tx.commit().await?;
+
+    let saved: Vec<i32> = sqlx::query_scalar("SELECT id FROM saved ORDER BY id")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(saved, vec![1, 3, 5]);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_runs_transaction_callbacks() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    let record = |event: &'static str| {
+        let events = events.clone();
+        move || events.lock().unwrap().push(event)
+    };
+
+    let mut tx = conn.begin().await?;
+    tx.on_commit(record("committed"));
+    tx.on_rollback(record("rolled back"));
+    tx.commit().await?;
+
+    let mut tx = conn.begin().await?;
+    tx.on_commit(record("not committed"));
+    tx.on_rollback(record("rolled back"));
+    tx.rollback().await?;
+
+    {
+        let mut tx = conn.begin().await?;
+        tx.on_rollback(record("dropped"));
+    }
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec!["committed", "rolled back", "dropped"]
+    );
+    events.lock().unwrap().clear();
+
+    let mut tx = conn.begin().await?;
+
+    let mut savepoint = tx.begin().await?;
+    savepoint.on_commit(record("savepoint committed"));
+    savepoint.on_rollback(record("savepoint rolled back"));
+    savepoint.rollback().await?;
+
+    let mut savepoint = tx.begin().await?;
+    savepoint.on_commit(record("savepoint released"));
+    savepoint.commit().await?;
+
+    // the callbacks of a released savepoint wait for its transaction
+    assert_eq!(*events.lock().unwrap(), vec!["savepoint rolled back"]);
+
+    tx.on_commit(record("committed"));
+    tx.commit().await?;
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec!["savepoint rolled back", "savepoint released", "committed"]
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_commits_prepared_transactions() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let max: String = sqlx::query_scalar("SHOW max_prepared_transactions")
+        .fetch_one(&mut conn)
+        .await?;
+
+    if max == "0" {
+        // the server doesn't allow prepared transactions
+        return Ok(());
+    }
+
+    conn.execute("CREATE TABLE IF NOT EXISTS two_phase (id TEXT NOT NULL)")
+        .await?;
+    conn.execute("DELETE FROM two_phase").await?;
+
+    for id in ["sqlx-two-phase-commit", "sqlx-two-phase-rollback"] {
+        let mut tx = conn.begin().await?;
+        sqlx::query("INSERT INTO two_phase VALUES ($1)")
+            .bind(id)
+            .execute(&mut tx)
+            .await?;
+        tx.prepare_transaction(id).await?;
+    }
+
+    // the session is no longer in a transaction
+    assert!(conn
+        .begin()
+        .await?
+        .prepare_transaction("sqlx-empty")
+        .await
+        .is_ok());
+    conn.rollback_prepared("sqlx-empty").await?;
+
+    let prepared: Vec<String> = conn
+        .prepared_transactions()
+        .await?
+        .into_iter()
+        .map(|tx| tx.id)
+        .filter(|id| id.starts_with("sqlx-two-phase"))
+        .collect();
+
+    assert_eq!(
+        prepared,
+        vec!["sqlx-two-phase-commit", "sqlx-two-phase-rollback"]
+    );
+
+    // a prepared transaction is resolved from any connection
+    let mut other = new::<Postgres>().await?;
+    other.commit_prepared("sqlx-two-phase-commit").await?;
+    other.rollback_prepared("sqlx-two-phase-rollback").await?;
+
+    let ids: Vec<String> = sqlx::query_scalar("SELECT id FROM two_phase")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(ids, vec!["sqlx-two-phase-commit"]);
+    assert!(conn.prepared_transactions().await?.is_empty());
+
+    let mut tx = conn.begin().await?;
+    assert!(tx
+        .begin()
+        .await?
+        .prepare_transaction("sqlx-savepoint")
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_times_out_transactions() -> anyhow::Result<()> {
+    use sqlx::TransactionOptions;
+
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE timed (id INT4 NOT NULL)")
+        .await?;
+
+    let mut tx = conn
+        .begin_with(TransactionOptions::new().timeout(Duration::from_secs(5)))
+        .await?;
+
+    // the timeouts of the server, which terminate the session, are left alone
+    let timeout: String = sqlx::query_scalar("SHOW idle_in_transaction_session_timeout")
+        .fetch_one(&mut tx)
+        .await?;
+    assert_eq!(timeout, "0");
+
+    tx.commit().await?;
+
+    // a statement still running once the timeout is passed is abandoned
+    let mut tx = conn
+        .begin_with(TransactionOptions::new().timeout(Duration::from_millis(200)))
+        .await?;
+
+    tx.execute("INSERT INTO timed VALUES (1)").await?;
+
+    let err = tx.execute("SELECT pg_sleep(1)").await.unwrap_err();
+    assert!(matches!(err, sqlx::Error::TransactionTimedOut));
+
+    assert!(matches!(
+        tx.execute("INSERT INTO timed VALUES (2)").await,
+        Err(sqlx::Error::TransactionTimedOut)
+    ));
+    assert!(matches!(
+        tx.commit().await,
+        Err(sqlx::Error::TransactionTimedOut)
+    ));
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM timed")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 0);
+
+    // as is a transaction committed once the timeout is passed
+    let mut tx = conn
+        .begin_with(TransactionOptions::new().timeout(Duration::from_millis(100)))
+        .await?;
+
+    tx.execute("INSERT INTO timed VALUES (3)").await?;
+    sqlx_rt::sleep(Duration::from_millis(150)).await;
+
+    assert!(matches!(
+        tx.commit().await,
+        Err(sqlx::Error::TransactionTimedOut)
+    ));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_begins_transactions_with_options() -> anyhow::Result<()> {
+    use sqlx::{IsolationLevel, TransactionOptions};
+
+    let mut conn = new::<Postgres>().await?;
+
+    let mut tx = conn
+        .begin_with(
+            TransactionOptions::new()
+                .isolation(IsolationLevel::Serializable)
+                .read_only(true)
+                .deferrable(true),
+        )
+        .await?;
+
+    for (setting, expected) in [
+        ("transaction_isolation", "serializable"),
+        ("transaction_read_only", "on"),
+        ("transaction_deferrable", "on"),
+    ] {
+        let value: String = sqlx::query_scalar(&format!("SHOW {}", setting))
+            .fetch_one(&mut tx)
+            .await?;
+
+        assert_eq!(value, expected);
+    }
+
+    // a savepoint has the options of its transaction
+    assert!(tx
+        .begin_with(TransactionOptions::new().read_only(false))
+        .await
+        .is_err());
+
+    tx.begin().await?.rollback().await?;
+    tx.commit().await?;
+
+    let pool = pool::<Postgres>().await?;
+    let mut tx = pool
+        .begin_with(TransactionOptions::new().isolation(IsolationLevel::RepeatableRead))
+        .await?;
+
+    let isolation: String = sqlx::query_scalar("SHOW transaction_isolation")
+        .fetch_one(&mut tx)
+        .await?;
+
+    assert_eq!(isolation, "repeatable read");
+
+    Ok(())
+}
+
 // run with `cargo test --features postgres -- --ignored --nocapture pool_smoke_test`
 #[ignore]
 #[sqlx_macros::test]
@@ -754,92 +1097,329 @@ async fn it_closes_statement_from_cache_issue_470() -> anyhow::Result<()> {
 }
 
 #[sqlx_macros::test]
-async fn it_sets_application_name() -> anyhow::Result<()> {
-    sqlx_test::setup_if_needed();
+async fn it_prepares_statements_with_each_flush_policy() -> anyhow::Result<()> {
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+
+    for policy in [PgFlushPolicy::Coalesce, PgFlushPolicy::Eager] {
+        let mut conn = PgConnection::connect_with(
+            &options
+                .clone()
+                .statement_cache_capacity(1)
+                .flush_policy(policy),
+        )
+        .await?;
 
-    let mut options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
-    options = options.application_name("some-name");
+        // the type of the first column is fetched after the statement is executed
+        let row = sqlx::query("SELECT 'open'::status AS status, $1::int4 AS val")
+            .bind(1_i32)
+            .fetch_one(&mut conn)
+            .await?;
 
-    let mut conn = PgConnection::connect_with(&options).await?;
+        assert_eq!(row.column(0).type_info().name(), "status");
+        assert_eq!(row.get::<i32, _>("val"), 1);
 
-    let row = sqlx::query("select current_setting('application_name') as app_name")
-        .fetch_one(&mut conn)
-        .await?;
+        for i in 0..5 {
+            let val: i32 = sqlx::query_scalar(&format!("SELECT $1::int4 + {}", i))
+                .bind(1_i32)
+                .fetch_one(&mut conn)
+                .await?;
 
-    let val: String = row.get("app_name");
+            assert_eq!(val, i + 1);
+        }
 
-    assert_eq!("some-name", &val);
+        assert_eq!(1, conn.cached_statements_size());
+
+        // closes the cached statement, and the evicted ones not closed yet
+        conn.clear_cached_statements().await?;
+
+        let prepared: i64 = conn
+            .fetch_one("SELECT COUNT(*) FROM pg_prepared_statements")
+            .await?
+            .get(0);
+
+        assert_eq!(prepared, 0);
+    }
 
     Ok(())
 }
 
 #[sqlx_macros::test]
-async fn it_can_handle_parameter_status_message_issue_484() -> anyhow::Result<()> {
-    new::<Postgres>().await?.execute("SET NAMES 'UTF8'").await?;
+async fn it_closes_statements_over_max_prepared_statements() -> anyhow::Result<()> {
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+
+    for policy in [PgFlushPolicy::Coalesce, PgFlushPolicy::Eager] {
+        let mut conn = PgConnection::connect_with(
+            &options
+                .clone()
+                .statement_cache_capacity(2)
+                .max_prepared_statements(3)
+                .flush_policy(policy),
+        )
+        .await?;
+
+        for i in 0..10 {
+            let val: i32 = sqlx::query_scalar(&format!("SELECT $1::int4 + {}", i))
+                .bind(1_i32)
+                .persistent(i % 2 == 0)
+                .fetch_one(&mut conn)
+                .await?;
+
+            assert_eq!(val, i + 1);
+
+            let prepared: i64 = conn
+                .fetch_one("SELECT COUNT(*) FROM pg_prepared_statements")
+                .await?
+                .get(0);
+
+            assert!(prepared <= 3, "{} statements are prepared", prepared);
+        }
+
+        assert_eq!(2, conn.cached_statements_size());
+
+        // closes the statements which aren't cached as well
+        conn.clear_cached_statements().await?;
+
+        let prepared: i64 = conn
+            .fetch_one("SELECT COUNT(*) FROM pg_prepared_statements")
+            .await?
+            .get(0);
+
+        assert_eq!(prepared, 0);
+    }
+
     Ok(())
 }
 
 #[sqlx_macros::test]
-async fn it_can_prepare_then_execute() -> anyhow::Result<()> {
-    let mut conn = new::<Postgres>().await?;
-    let mut tx = conn.begin().await?;
+async fn it_skips_messages_over_max_message_size() -> anyhow::Result<()> {
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+    let mut conn = PgConnection::connect_with(&options.max_message_size(1024)).await?;
 
-    let tweet_id: i64 =
-        sqlx::query_scalar("INSERT INTO tweet ( text ) VALUES ( 'Hello, World' ) RETURNING id")
-            .fetch_one(&mut tx)
-            .await?;
+    // a prepared query, then a simple query
+    let res = sqlx::query("SELECT repeat('x', $1)")
+        .bind(100_000_i32)
+        .fetch_one(&mut conn)
+        .await;
 
-    let statement = tx.prepare("SELECT * FROM tweet WHERE id = $1").await?;
+    assert!(matches!(
+        res,
+        Err(sqlx::Error::MessageTooLarge { max: 1024, .. })
+    ));
 
-    assert_eq!(statement.column(0).name(), "id");
-    assert_eq!(statement.column(1).name(), "created_at");
-    assert_eq!(statement.column(2).name(), "text");
-    assert_eq!(statement.column(3).name(), "owner_id");
+    let res = conn.fetch_one("SELECT repeat('x', 100000)").await;
 
-    assert_eq!(statement.column(0).type_info().name(), "INT8");
-    assert_eq!(statement.column(1).type_info().name(), "TIMESTAMPTZ");
-    assert_eq!(statement.column(2).type_info().name(), "TEXT");
-    assert_eq!(statement.column(3).type_info().name(), "INT8");
+    assert!(matches!(
+        res,
+        Err(sqlx::Error::MessageTooLarge { max: 1024, .. })
+    ));
 
-    let row = statement.query().bind(tweet_id).fetch_one(&mut tx).await?;
-    let tweet_text: &str = row.try_get("text")?;
+    // the connection is still usable
+    let val: String = sqlx::query_scalar("SELECT repeat('x', $1)")
+        .bind(10_i32)
+        .fetch_one(&mut conn)
+        .await?;
 
-    assert_eq!(tweet_text, "Hello, World");
+    assert_eq!(val, "x".repeat(10));
 
     Ok(())
 }
 
-// repro is more reliable with the basic scheduler used by `#[tokio::test]`
-#[cfg(feature = "_rt-tokio")]
-#[tokio::test]
-async fn test_issue_622() -> anyhow::Result<()> {
-    use std::time::Instant;
+#[sqlx_macros::test]
+async fn it_streams_rows_with_a_bounded_buffer() -> anyhow::Result<()> {
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+    let mut conn =
+        PgConnection::connect_with(&options.row_buffer_size(16).row_buffer_bytes(1024)).await?;
 
-    setup_if_needed();
+    // the first execution of the statement, then with the statement cached
+    for _ in 0..2 {
+        let mut rows =
+            sqlx::query_scalar::<_, String>("SELECT repeat('x', n) FROM generate_series(1, $1) n")
+                .bind(200_i32)
+                .fetch(&mut conn);
 
-    let pool = PgPoolOptions::new()
-        .max_connections(1) // also fails with higher counts, e.g. 5
-        .connect(&std::env::var("DATABASE_URL").unwrap())
-        .await?;
+        let mut n = 0;
 
-    println!("pool state: {:?}", pool);
+        while let Some(val) = rows.try_next().await? {
+            n += 1;
+            assert_eq!(val.len(), n);
+        }
 
-    let mut handles = vec![];
+        assert_eq!(n, 200);
+    }
 
-    // given repro spawned 100 tasks but I found it reliably reproduced with 3
-    for i in 0..3 {
-        let pool = pool.clone();
+    Ok(())
+}
 
-        handles.push(sqlx_rt::spawn(async move {
-            {
-                let mut conn = pool.acquire().await.unwrap();
+#[sqlx_macros::test]
+async fn it_shares_statement_metadata_between_connections() -> anyhow::Result<()> {
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+    let options = options.shared_statement_cache_capacity(10);
 
-                let _ = sqlx::query("SELECT 1").fetch_one(&mut conn).await.unwrap();
+    for policy in [PgFlushPolicy::Coalesce, PgFlushPolicy::Eager] {
+        let options = options.clone().flush_policy(policy);
 
-                // conn gets dropped here and should be returned to the pool
-            }
+        // the first connection describes the statement, the second reuses its metadata
+        for _ in 0..2 {
+            let mut conn = PgConnection::connect_with(&options).await?;
 
-            // (do some other work here without holding on to a connection)
+            let row = sqlx::query("SELECT 'open'::status AS status, $1::int4 AS val")
+                .bind(1_i32)
+                .fetch_one(&mut conn)
+                .await?;
+
+            assert_eq!(row.column(0).type_info().name(), "status");
+            assert_eq!(row.get::<i32, _>("val"), 1);
+
+            conn.close().await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_sets_application_name() -> anyhow::Result<()> {
+    sqlx_test::setup_if_needed();
+
+    let mut options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+    options = options.application_name("some-name");
+
+    let mut conn = PgConnection::connect_with(&options).await?;
+
+    let row = sqlx::query("select current_setting('application_name') as app_name")
+        .fetch_one(&mut conn)
+        .await?;
+
+    let val: String = row.get("app_name");
+
+    assert_eq!("some-name", &val);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_handle_parameter_status_message_issue_484() -> anyhow::Result<()> {
+    new::<Postgres>().await?.execute("SET NAMES 'UTF8'").await?;
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reports_parameter_statuses() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    assert!(conn.parameter_status("server_version").is_some());
+
+    conn.execute("SET application_name = 'reported-name'")
+        .await?;
+
+    assert_eq!(
+        Some("reported-name"),
+        conn.parameter_status("application_name")
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_handles_and_collects_notices() -> anyhow::Result<()> {
+    sqlx_test::setup_if_needed();
+
+    let handled = Arc::new(Mutex::new(Vec::new()));
+    let handled_ = handled.clone();
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+    let options = options.collect_notices(true).notice_handler(move |notice| {
+        handled_.lock().unwrap().push(notice.message().to_owned());
+    });
+
+    let mut conn = PgConnection::connect_with(&options).await?;
+
+    conn.execute(
+        r#"
+DO $$
+BEGIN
+    RAISE NOTICE 'hello %', 'world';
+    RAISE WARNING 'careful' USING HINT = 'be careful';
+END $$
+        "#,
+    )
+    .await?;
+
+    let notices = conn.take_notices();
+
+    assert_eq!(2, notices.len());
+    assert_eq!(PgSeverity::Notice, notices[0].severity());
+    assert_eq!("hello world", notices[0].message());
+    assert_eq!(PgSeverity::Warning, notices[1].severity());
+    assert_eq!(Some("be careful"), notices[1].hint());
+
+    assert!(conn.take_notices().is_empty());
+    assert_eq!(vec!["hello world", "careful"], *handled.lock().unwrap());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_prepare_then_execute() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+    let mut tx = conn.begin().await?;
+
+    let tweet_id: i64 =
+        sqlx::query_scalar("INSERT INTO tweet ( text ) VALUES ( 'Hello, World' ) RETURNING id")
+            .fetch_one(&mut tx)
+            .await?;
+
+    let statement = tx.prepare("SELECT * FROM tweet WHERE id = $1").await?;
+
+    assert_eq!(statement.column(0).name(), "id");
+    assert_eq!(statement.column(1).name(), "created_at");
+    assert_eq!(statement.column(2).name(), "text");
+    assert_eq!(statement.column(3).name(), "owner_id");
+
+    assert_eq!(statement.column(0).type_info().name(), "INT8");
+    assert_eq!(statement.column(1).type_info().name(), "TIMESTAMPTZ");
+    assert_eq!(statement.column(2).type_info().name(), "TEXT");
+    assert_eq!(statement.column(3).type_info().name(), "INT8");
+
+    let row = statement.query().bind(tweet_id).fetch_one(&mut tx).await?;
+    let tweet_text: &str = row.try_get("text")?;
+
+    assert_eq!(tweet_text, "Hello, World");
+
+    Ok(())
+}
+
+// repro is more reliable with the basic scheduler used by `#[tokio::test]`
+#[cfg(feature = "_rt-tokio")]
+#[tokio::test]
+async fn test_issue_622() -> anyhow::Result<()> {
+    use std::time::Instant;
+
+    setup_if_needed();
+
+    let pool = PgPoolOptions::new()
+        .max_connections(1) // also fails with higher counts, e.g. 5
+        .connect(&std::env::var("DATABASE_URL").unwrap())
+        .await?;
+
+    println!("pool state: {:?}", pool);
+
+    let mut handles = vec![];
+
+    // given repro spawned 100 tasks but I found it reliably reproduced with 3
+    for i in 0..3 {
+        let pool = pool.clone();
+
+        handles.push(sqlx_rt::spawn(async move {
+            {
+                let mut conn = pool.acquire().await.unwrap();
+
+                let _ = sqlx::query("SELECT 1").fetch_one(&mut conn).await.unwrap();
+
+                // conn gets dropped here and should be returned to the pool
+            }
+
+            // (do some other work here without holding on to a connection)
             // this actually fixes the issue, depending on the timeout used
             // sqlx_rt::sleep(Duration::from_millis(500)).await;
 
@@ -1714,6 +2294,48 @@ async fn test_advisory_locks() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn test_advisory_lock_guards() -> anyhow::Result<()> {
+    let pool = PgPoolOptions::new()
+        .max_connections(3)
+        .connect(&dotenv::var("DATABASE_URL")?)
+        .await?;
+
+    let lock = PgAdvisoryLock::new("sqlx-postgres-tests-guards");
+
+    let guard = lock.acquire_from_pool(&pool).await?;
+
+    // the lock is held by the connection of the guard
+    assert!(lock.try_acquire_from_pool(&pool).await?.is_none());
+
+    let mut tx = pool.begin().await?;
+    assert!(!lock.try_acquire_xact(&mut tx).await?);
+    tx.rollback().await?;
+
+    guard.release_now().await?;
+
+    // a transaction-scoped lock is held until the transaction ends
+    let mut tx = pool.begin().await?;
+    lock.acquire_xact(&mut tx).await?;
+
+    assert!(lock.try_acquire_from_pool(&pool).await?.is_none());
+
+    tx.commit().await?;
+
+    let guard = lock.try_acquire_from_pool(&pool).await?;
+    assert!(guard.is_some());
+    drop(guard);
+
+    // the lock is released once the connection of the dropped guard is returned to the pool
+    let mut tx = pool.begin().await?;
+    lock.acquire_xact(&mut tx).await?;
+    tx.commit().await?;
+
+    pool.close().await;
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn test_postgres_bytea_hex_deserialization_errors() -> anyhow::Result<()> {
     let mut conn = new::<Postgres>().await?;
@@ -1726,3 +2348,846 @@ async fn test_postgres_bytea_hex_deserialization_errors() -> anyhow::Result<()>
     }
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_cancels_the_running_statement() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let token = conn.cancel_token();
+    let pid: i32 = conn.fetch_one("SELECT pg_backend_pid()").await?.get(0);
+    assert_eq!(token.process_id(), pid as u32);
+
+    let canceller = sqlx_rt::spawn(async move {
+        sqlx_rt::sleep(Duration::from_millis(500)).await;
+        token.cancel().await.unwrap();
+    });
+
+    let err = conn.execute("SELECT pg_sleep(30)").await.unwrap_err();
+    let _ = canceller.await;
+
+    let code = err.as_database_error().and_then(|e| e.code());
+    assert_eq!(code.as_deref(), Some("57014"));
+
+    // the connection is still usable
+    let value: i32 = conn.fetch_one("SELECT 1").await?.get(0);
+    assert_eq!(value, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_sends_startup_params() -> anyhow::Result<()> {
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+    let mut conn = PgConnection::connect_with(
+        &options
+            .startup_param("statement_timeout", "12s")
+            .startup_param("search_path", "pg_catalog, public")
+            .options([("lock_timeout", "3s")]),
+    )
+    .await?;
+
+    let statement_timeout: String = conn.fetch_one("SHOW statement_timeout").await?.get(0);
+    let search_path: String = conn.fetch_one("SHOW search_path").await?.get(0);
+    let lock_timeout: String = conn.fetch_one("SHOW lock_timeout").await?.get(0);
+
+    assert_eq!(statement_timeout, "12s");
+    assert_eq!(search_path, "pg_catalog, public");
+    assert_eq!(lock_timeout, "3s");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reports_the_server_version() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let num: String = conn.fetch_one("SHOW server_version_num").await?.get(0);
+    let num: u32 = num.parse()?;
+    let version = conn.server_version().unwrap();
+
+    assert_eq!(version.major(), num / 10000);
+    assert_eq!(
+        conn.server_capabilities().multiranges(),
+        version.major() >= 14
+    );
+    assert!(conn.server_capabilities().returning());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_retries_transactions_on_serialization_failures() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    assert!(!conn.is_cockroachdb());
+    assert_eq!(conn.cockroachdb_version(), None);
+
+    conn.execute("CREATE TEMPORARY TABLE retried (attempt INT4 NOT NULL)")
+        .await?;
+
+    let mut attempts = 0;
+
+    // fails the first 2 attempts like a conflicting transaction of CockroachDB
+    let attempt = conn
+        .transaction_with_retry(3, |tx| {
+            attempts += 1;
+            let attempt = attempts;
+
+            Box::pin(async move {
+                sqlx::query("INSERT INTO retried (attempt) VALUES ($1)")
+                    .bind(attempt)
+                    .execute(&mut **tx)
+                    .await?;
+
+                if attempt < 3 {
+                    tx.execute("DO $$ BEGIN RAISE EXCEPTION 'restart transaction' USING ERRCODE = '40001'; END $$")
+                        .await?;
+                }
+
+                Ok(attempt)
+            })
+        })
+        .await?;
+
+    assert_eq!(attempt, 3);
+
+    // only the last attempt was committed
+    let committed: Vec<i32> = sqlx::query_scalar("SELECT attempt FROM retried")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(committed, vec![3]);
+
+    // the error is returned once the retries are exhausted
+    let err = conn
+        .transaction_with_retry(1, |tx| {
+            Box::pin(async move {
+                tx.execute("DO $$ BEGIN RAISE EXCEPTION 'restart transaction' USING ERRCODE = '40001'; END $$")
+                    .await?;
+
+                Ok(())
+            })
+        })
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        err.as_database_error().unwrap().code().as_deref(),
+        Some("40001")
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_retries_pool_transactions_on_conflicts() -> anyhow::Result<()> {
+    use sqlx::pool::RetryPolicy;
+    use sqlx::{IsolationLevel, TransactionOptions};
+
+    let pool = pool::<Postgres>().await?;
+
+    let policy = RetryPolicy::new()
+        .base_delay(Duration::from_millis(1))
+        .transaction_options(TransactionOptions::new().isolation(IsolationLevel::Serializable));
+
+    let mut attempts = 0;
+
+    // a deadlock, then a serialization failure
+    let isolation = pool
+        .transaction_with_retry(&policy, |tx| {
+            attempts += 1;
+            let attempt = attempts;
+
+            Box::pin(async move {
+                match attempt {
+                    1 => tx.execute("DO $$ BEGIN RAISE EXCEPTION 'deadlock' USING ERRCODE = '40P01'; END $$").await?,
+                    2 => tx.execute("DO $$ BEGIN RAISE EXCEPTION 'conflict' USING ERRCODE = '40001'; END $$").await?,
+                    _ => Default::default(),
+                };
+
+                sqlx::query_scalar::<_, String>("SHOW transaction_isolation")
+                    .fetch_one(&mut **tx)
+                    .await
+            })
+        })
+        .await?;
+
+    assert_eq!(attempts, 3);
+    assert_eq!(isolation, "serializable");
+
+    // the other errors aren't retried
+    let mut attempts = 0;
+
+    let err = pool
+        .transaction_with_retry(&policy, |tx| {
+            attempts += 1;
+
+            Box::pin(async move {
+                tx.execute("SELECT * FROM not_a_table").await?;
+
+                Ok(())
+            })
+        })
+        .await
+        .unwrap_err();
+
+    assert_eq!(attempts, 1);
+    assert_eq!(
+        err.as_database_error().unwrap().code().as_deref(),
+        Some("42P01")
+    );
+
+    // nor the last conflict
+    let mut attempts = 0;
+
+    let err = pool
+        .transaction_with_retry(&policy.clone().max_attempts(2), |tx| {
+            attempts += 1;
+
+            Box::pin(async move {
+                tx.execute(
+                    "DO $$ BEGIN RAISE EXCEPTION 'conflict' USING ERRCODE = '40001'; END $$",
+                )
+                .await?;
+
+                Ok(())
+            })
+        })
+        .await
+        .unwrap_err();
+
+    assert_eq!(attempts, 2);
+    assert!(err.as_database_error().unwrap().is_transaction_conflict());
+
+    Ok(())
+}
+
+// polls `future` until it waited `suspensions` times, then drops it, as a timeout would;
+// returns its output if it completed before
+async fn cancel_after<F: std::future::Future>(future: F, suspensions: usize) -> Option<F::Output> {
+    futures::pin_mut!(future);
+    let mut remaining = suspensions;
+
+    futures::future::poll_fn(|cx| match future.as_mut().poll(cx) {
+        std::task::Poll::Ready(output) => std::task::Poll::Ready(Some(output)),
+        std::task::Poll::Pending if remaining == 0 => std::task::Poll::Ready(None),
+        std::task::Poll::Pending => {
+            remaining -= 1;
+            std::task::Poll::Pending
+        }
+    })
+    .await
+}
+
+#[sqlx_macros::test]
+async fn it_recovers_from_queries_cancelled_at_every_stage() -> anyhow::Result<()> {
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+
+    // larger than the socket buffers, so that it's sent and received in several steps
+    let value = "x".repeat(1024 * 1024);
+
+    for policy in [PgFlushPolicy::Coalesce, PgFlushPolicy::Eager] {
+        let mut conn = PgConnection::connect_with(&options.clone().flush_policy(policy)).await?;
+
+        // each stage is a point where the query waits for the server
+        for stage in 0.. {
+            // a new statement each time, prepared along with the query
+            let sql = format!(
+                "SELECT $1::text AS value, i + {} AS i FROM generate_series(1, 3) AS i",
+                stage
+            );
+
+            let query = sqlx::query(&sql).bind(&value).fetch_all(&mut conn);
+            let completed = cancel_after(query, stage).await.transpose()?;
+
+            // the next query isn't affected by the one cancelled before
+            let echo: i32 = sqlx::query_scalar("SELECT $1::int4")
+                .bind(stage as i32)
+                .fetch_one(&mut conn)
+                .await?;
+
+            assert_eq!(echo, stage as i32);
+
+            if let Some(rows) = completed {
+                assert_eq!(rows.len(), 3);
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_recovers_from_simple_queries_cancelled_at_every_stage() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let sql = format!(
+        "SELECT '{}' AS value FROM generate_series(1, 3)",
+        "x".repeat(1024 * 1024)
+    );
+
+    for stage in 0.. {
+        let completed = cancel_after(conn.fetch_all(&*sql), stage)
+            .await
+            .transpose()?;
+
+        let echo: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+
+        assert_eq!(echo, 1);
+
+        if completed.is_some() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "metrics")]
+#[sqlx_macros::test]
+async fn it_exports_metrics() -> anyhow::Result<()> {
+    let pool = sqlx_test::pool::<Postgres>().await?;
+
+    let _ = pool.fetch_one("SELECT 1").await?;
+    let _ = pool.execute("SELECT * FROM not_a_table").await;
+
+    let metrics = sqlx::metrics::render();
+
+    assert!(metrics.contains("# TYPE sqlx_pool_acquire_duration_seconds histogram\n"));
+    assert!(metrics.contains("sqlx_query_duration_seconds_count{family=\"select\"} "));
+    assert!(!metrics.contains("sqlx_query_duration_seconds_count{family=\"select\"} 0\n"));
+    assert!(metrics.contains("sqlx_query_errors_total{code=\"42P01\"} "));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_appends_sql_comments() -> anyhow::Result<()> {
+    use sqlx::sqlcommenter::SqlCommenter;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let span = Arc::new(AtomicUsize::new(0));
+    let current = Arc::clone(&span);
+
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+    let mut conn = PgConnection::connect_with(
+        &options.sql_commenter(
+            SqlCommenter::new()
+                .tag("application", "sqlx tests")
+                .traceparent(move || match current.load(Ordering::SeqCst) {
+                    0 => None,
+                    span => Some(format!(
+                        "00-0af7651916cd43dd8448eb211c80319c-{:016x}-01",
+                        span
+                    )),
+                }),
+        ),
+    )
+    .await?;
+
+    let query: String = sqlx::query_scalar("SELECT current_query()")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(
+        query,
+        "SELECT current_query() /*application='sqlx%20tests'*/"
+    );
+
+    // a statement executed within a trace is prepared with the context of each execution
+    for i in 1..=2 {
+        span.store(i, Ordering::SeqCst);
+
+        let query: String = sqlx::query_scalar("SELECT current_query()")
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(
+            query,
+            format!(
+                "SELECT current_query() /*application='sqlx%20tests',\
+                 traceparent='00-0af7651916cd43dd8448eb211c80319c-{:016x}-01'*/",
+                i
+            )
+        );
+    }
+
+    assert_eq!(1, conn.cached_statements_size());
+
+    let query: String = conn.fetch_one("SELECT current_query()").await?.get(0);
+
+    assert_eq!(
+        query,
+        "SELECT current_query() /*application='sqlx%20tests',\
+         traceparent='00-0af7651916cd43dd8448eb211c80319c-0000000000000002-01'*/"
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_logs_query_records() -> anyhow::Result<()> {
+    use sqlx::query_log::{QueryLog, QueryRecord};
+    use sqlx::ConnectOptions;
+
+    #[derive(Default)]
+    struct Records(Mutex<Vec<(String, u64, Option<u64>)>>);
+
+    impl QueryLog for Records {
+        fn log(&self, record: &QueryRecord<'_>) {
+            self.0.lock().unwrap().push((
+                record.sql().to_owned(),
+                record.rows_returned(),
+                record.connection_id(),
+            ));
+        }
+    }
+
+    let records = Arc::new(Records::default());
+
+    let mut options: PgConnectOptions = env::var("DATABASE_URL")?.parse().unwrap();
+    options.query_log(records.clone());
+
+    let mut conn = PgConnection::connect_with(&options).await?;
+
+    let pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+        .fetch_one(&mut conn)
+        .await?;
+
+    let records = records.0.lock().unwrap();
+
+    assert_eq!(
+        records.last(),
+        Some(&("SELECT pg_backend_pid()".to_owned(), 1, Some(pid as u64)))
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_observes_statements() -> anyhow::Result<()> {
+    use sqlx::observer::{self, StatementEvent};
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let observed = Arc::clone(&events);
+
+    // the tests run concurrently, so only the statements of this one are kept
+    observer::set_statement_observer(move |event: &StatementEvent<'_>| {
+        if event.tag("test") == Some("it_observes_statements") {
+            observed.lock().unwrap().push((
+                event.sql().to_owned(),
+                event.rows_returned(),
+                event.error_code().map(ToOwned::to_owned),
+                event.tag("tenant").map(ToOwned::to_owned),
+            ));
+        }
+    });
+
+    let mut conn = new::<Postgres>().await?;
+
+    observer::with_tags([("test", "it_observes_statements")], async {
+        let _: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+
+        observer::with_tags([("tenant", "acme")], async {
+            let _ = conn.execute("SELECT * FROM not_a_table").await;
+        })
+        .await;
+
+        anyhow::Ok(())
+    })
+    .await?;
+
+    // outside of the scope
+    let _: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+
+    observer::clear_statement_observer();
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![
+            ("SELECT 1".to_owned(), 1, None, None),
+            (
+                "SELECT * FROM not_a_table".to_owned(),
+                0,
+                Some("42P01".to_owned()),
+                Some("acme".to_owned())
+            ),
+        ]
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_serves_queries() -> anyhow::Result<()> {
+    use sqlx::service::{QueryRequest, QueryService};
+
+    let service = QueryService::new(pool::<Postgres>().await?);
+
+    // the futures own their request, so they outlive the borrow of the service
+    let futures: Vec<_> = (1..=3_i64)
+        .map(|i| {
+            service.call(
+                QueryRequest::new("SELECT $1::int8 * 2 AS doubled, $2::text AS name")
+                    .bind(i)
+                    .bind(format!("row {}", i)),
+            )
+        })
+        .collect();
+
+    drop(service);
+
+    let responses = futures::future::try_join_all(futures).await?;
+
+    let rows: Vec<(i64, String)> = responses
+        .iter()
+        .map(|response| {
+            let row = &response.rows()[0];
+
+            Ok((row.try_get("doubled")?, row.try_get("name")?))
+        })
+        .collect::<sqlx::Result<_>>()?;
+
+    assert_eq!(
+        rows,
+        vec![
+            (2, "row 1".to_owned()),
+            (4, "row 2".to_owned()),
+            (6, "row 3".to_owned())
+        ]
+    );
+
+    let response = QueryService::new(pool::<Postgres>().await?)
+        .call(QueryRequest::new("SELECT * FROM not_a_table"))
+        .await;
+
+    assert!(response.is_err());
+
+    Ok(())
+}
+
+#[cfg(all(feature = "tower", feature = "_rt-tokio"))]
+#[sqlx_macros::test]
+async fn it_serves_queries_through_tower_layers() -> anyhow::Result<()> {
+    use std::time::Duration;
+
+    use sqlx::service::{QueryRequest, QueryService};
+    use tower::{Service, ServiceBuilder, ServiceExt};
+
+    let mut service = ServiceBuilder::new()
+        .concurrency_limit(2)
+        .timeout(Duration::from_millis(500))
+        .service(QueryService::new(pool::<Postgres>().await?));
+
+    let response = service
+        .ready()
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?
+        .call(QueryRequest::new("SELECT $1::int8 AS id").bind(7_i64))
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let id: i64 = response.rows()[0].try_get("id")?;
+    assert_eq!(id, 7);
+
+    let error = service
+        .ready()
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?
+        .call(QueryRequest::new("SELECT pg_sleep(5)"))
+        .await
+        .unwrap_err();
+
+    assert!(error.is::<tower::timeout::error::Elapsed>());
+
+    Ok(())
+}
+
+#[cfg(feature = "json")]
+#[sqlx_macros::test]
+async fn it_serializes_rows() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let row = sqlx::query(
+        "SELECT 1::int4 AS int, 'x'::text AS text, NULL::int8 AS null, true AS bool, \
+         '{\"k\": [1]}'::jsonb AS json, ARRAY['a', 'b'] AS array, '\\x0102'::bytea AS bytes, \
+         'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11'::uuid AS uuid",
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(
+        serde_json::to_value(&row)?,
+        serde_json::json!({
+            "int": 1,
+            "text": "x",
+            "null": null,
+            "bool": true,
+            "json": { "k": [1] },
+            "array": ["a", "b"],
+            "bytes": [1, 2],
+            "uuid": "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11",
+        })
+    );
+
+    #[cfg(feature = "chrono")]
+    {
+        let row = sqlx::query(
+            "SELECT '2020-01-02 03:04:05.5'::timestamp AS timestamp, \
+             '2020-01-02 03:04:05+00'::timestamptz AS timestamptz, '2020-01-02'::date AS date",
+        )
+        .fetch_one(&mut conn)
+        .await?;
+
+        assert_eq!(
+            serde_json::to_value(&row)?,
+            serde_json::json!({
+                "timestamp": "2020-01-02T03:04:05.500",
+                "timestamptz": "2020-01-02T03:04:05+00:00",
+                "date": "2020-01-02",
+            })
+        );
+    }
+
+    let row = sqlx::query("SELECT point(1, 2) AS point")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert!(serde_json::to_value(&row).is_err());
+
+    Ok(())
+}
+
+#[cfg(feature = "json")]
+#[sqlx_macros::test]
+async fn it_exports_rows() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let mut csv = Vec::new();
+
+    let rows = sqlx::query(
+        "SELECT * FROM (VALUES (1, 'a,b', true), (2, NULL, false), (3, '', NULL)) \
+         AS t (id, name, active)",
+    )
+    .fetch(&mut conn);
+
+    let count = sqlx::export::write_csv(rows, &mut csv).await?;
+
+    assert_eq!(count, 3);
+    assert_eq!(
+        String::from_utf8(csv)?,
+        "id,name,active\r\n1,\"a,b\",true\r\n2,,false\r\n3,\"\",\r\n"
+    );
+
+    let mut ndjson = Vec::new();
+
+    let rows = sqlx::query("SELECT generate_series(1, 2) AS n").fetch(&mut conn);
+
+    sqlx::export::write_ndjson(rows, &mut ndjson).await?;
+
+    assert_eq!(ndjson, b"{\"n\":1}\n{\"n\":2}\n");
+
+    Ok(())
+}
+
+#[cfg(feature = "parquet")]
+#[sqlx_macros::test]
+async fn it_exports_rows_as_parquet() -> anyhow::Result<()> {
+    use parquet_::basic::{ConvertedType, Type as PhysicalType};
+    use parquet_::data_type::ByteArray;
+    use parquet_::file::reader::{FileReader, SerializedFileReader};
+    use parquet_::record::Field;
+    use sqlx::export::parquet::ParquetWriter;
+
+    let mut conn = new::<Postgres>().await?;
+
+    // the last row is all `NULL`s
+    let rows = sqlx::query(
+        r#"SELECT t.* FROM generate_series(1, 5) AS i LEFT JOIN LATERAL (
+            SELECT i % 2 = 0 AS bool, chr(96 + i)::"char" AS char, i::int2 AS int2,
+                i::int4 AS int4, i::int8 * 1000000000000 AS int8, (i / 2.0)::float4 AS float4,
+                (i / 2.0)::float8 AS float8, i::text AS text, jsonb_build_object('i', i) AS json,
+                decode(lpad(i::text, 2, '0'), 'hex') AS bytes
+        ) t ON i < 5"#,
+    )
+    .fetch(&mut conn);
+
+    let mut parquet = Vec::new();
+
+    let count = ParquetWriter::new()
+        .row_group_size(2)
+        .write(rows, &mut parquet)
+        .await?;
+
+    assert_eq!(count, 5);
+
+    let path = std::env::temp_dir().join(format!("sqlx-export-{}.parquet", std::process::id()));
+    std::fs::write(&path, &parquet)?;
+
+    let reader = SerializedFileReader::new(std::fs::File::open(&path)?)?;
+    std::fs::remove_file(&path)?;
+
+    let metadata = reader.metadata().file_metadata();
+
+    assert_eq!(metadata.num_rows(), 5);
+    assert_eq!(reader.metadata().num_row_groups(), 3);
+
+    let schema: Vec<_> = metadata
+        .schema_descr()
+        .columns()
+        .iter()
+        .map(|column| {
+            (
+                column.name(),
+                column.physical_type(),
+                column.converted_type(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        schema,
+        [
+            ("bool", PhysicalType::BOOLEAN, ConvertedType::NONE),
+            ("char", PhysicalType::INT32, ConvertedType::INT_8),
+            ("int2", PhysicalType::INT32, ConvertedType::INT_16),
+            ("int4", PhysicalType::INT32, ConvertedType::NONE),
+            ("int8", PhysicalType::INT64, ConvertedType::NONE),
+            ("float4", PhysicalType::FLOAT, ConvertedType::NONE),
+            ("float8", PhysicalType::DOUBLE, ConvertedType::NONE),
+            ("text", PhysicalType::BYTE_ARRAY, ConvertedType::UTF8),
+            ("json", PhysicalType::BYTE_ARRAY, ConvertedType::JSON),
+            ("bytes", PhysicalType::BYTE_ARRAY, ConvertedType::NONE),
+        ]
+    );
+
+    let rows = reader
+        .get_row_iter(None)?
+        .map(|row| {
+            Ok(row?
+                .get_column_iter()
+                .map(|(_, field)| field.clone())
+                .collect::<Vec<_>>())
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let expected: Vec<Vec<Field>> = (1..=4)
+        .map(|i: i32| {
+            vec![
+                Field::Bool(i % 2 == 0),
+                Field::Byte(96 + i as i8),
+                Field::Short(i as i16),
+                Field::Int(i),
+                Field::Long(i as i64 * 1_000_000_000_000),
+                Field::Float(i as f32 / 2.0),
+                Field::Double(i as f64 / 2.0),
+                Field::Str(i.to_string()),
+                Field::Str(format!(r#"{{"i":{}}}"#, i)),
+                Field::Bytes(ByteArray::from(vec![i as u8])),
+            ]
+        })
+        .chain(Some(vec![Field::Null; 10]))
+        .collect();
+
+    assert_eq!(rows, expected);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_checks_pool_health() -> anyhow::Result<()> {
+    let pool = PgPoolOptions::new()
+        .max_connections(2)
+        .connect(&dotenv::var("DATABASE_URL")?)
+        .await?;
+
+    let health = pool.health_check(Duration::from_secs(5)).await;
+
+    assert!(health.healthy, "{:?}", health);
+    assert_eq!(health.max_connections, 2);
+    assert!(health.error.is_none());
+    assert!(health.last_error.is_none());
+
+    let pool = PgPoolOptions::new()
+        .health_check_query("SELECT pg_sleep(1)".to_owned())
+        .connect(&dotenv::var("DATABASE_URL")?)
+        .await?;
+
+    let health = pool.health_check(Duration::from_millis(100)).await;
+
+    assert!(!health.healthy);
+    assert!(health.error.as_deref().unwrap().starts_with("timed out"));
+    assert_eq!(health.last_error.map(|error| error.message), health.error);
+
+    let pool = PgPoolOptions::new()
+        .health_check_query("SELECT * FROM missing_table".to_owned())
+        .connect(&dotenv::var("DATABASE_URL")?)
+        .await?;
+
+    let health = pool.health_check(Duration::from_secs(5)).await;
+
+    assert!(!health.healthy);
+    assert!(health.error.unwrap().contains("missing_table"));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_observes_transactions() -> anyhow::Result<()> {
+    use sqlx::observer::{self, TransactionEvent, TransactionEventKind};
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let observed = Arc::clone(&events);
+
+    // the tests run concurrently, so only the transactions of this one are kept
+    observer::set_transaction_observer(move |event: &TransactionEvent<'_>| {
+        if event.tag("test") == Some("it_observes_transactions") {
+            observed
+                .lock()
+                .unwrap()
+                .push((event.kind(), event.depth(), event.statements()));
+        }
+    });
+
+    let mut conn = new::<Postgres>().await?;
+
+    observer::with_tags([("test", "it_observes_transactions")], async {
+        let mut tx = conn.begin().await?;
+
+        tx.execute("SELECT 1").await?;
+
+        {
+            let mut savepoint = tx.begin().await?;
+
+            savepoint.execute("SELECT 2").await?;
+            savepoint.rollback().await?;
+        }
+
+        tx.execute("SELECT 3").await?;
+        tx.commit().await?;
+
+        let tx = conn.begin().await?;
+        drop(tx);
+
+        anyhow::Ok(())
+    })
+    .await?;
+
+    observer::clear_transaction_observer();
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![
+            (TransactionEventKind::Begin, 1, 0),
+            (TransactionEventKind::Begin, 2, 0),
+            (TransactionEventKind::Rollback, 2, 1),
+            // the statements of the savepoint, along with its SAVEPOINT and ROLLBACK TO
+            (TransactionEventKind::Commit, 1, 5),
+            (TransactionEventKind::Begin, 1, 0),
+            (TransactionEventKind::Dropped, 1, 0),
+        ]
+    );
+
+    Ok(())
+}
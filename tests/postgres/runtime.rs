@@ -0,0 +1,90 @@
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use sqlx::postgres::{PgPoolOptions, Postgres};
+use sqlx::runtime::{Runtime, RuntimeSocket};
+use sqlx::{Connection, Executor};
+use sqlx_test::new;
+
+// delegates to the runtime of the crate features, counting what SQLx asks of it
+#[derive(Default)]
+struct CountingRuntime {
+    spawned: Arc<AtomicUsize>,
+    connected: Arc<AtomicUsize>,
+}
+
+impl Runtime for CountingRuntime {
+    fn spawn(&self, task: BoxFuture<'static, ()>) {
+        self.spawned.fetch_add(1, Ordering::SeqCst);
+        sqlx_rt::spawn(task);
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(sqlx_rt::sleep(duration))
+    }
+
+    fn connect_tcp<'a>(
+        &'a self,
+        host: &'a str,
+        port: u16,
+    ) -> BoxFuture<'a, io::Result<Box<dyn RuntimeSocket>>> {
+        Box::pin(async move {
+            let stream = sqlx_rt::TcpStream::connect((host, port)).await?;
+            self.connected.fetch_add(1, Ordering::SeqCst);
+
+            Ok(Box::new(stream) as Box<dyn RuntimeSocket>)
+        })
+    }
+
+    #[cfg(unix)]
+    fn connect_uds<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> BoxFuture<'a, io::Result<Box<dyn RuntimeSocket>>> {
+        Box::pin(async move {
+            let stream = sqlx_rt::UnixStream::connect(path).await?;
+            self.connected.fetch_add(1, Ordering::SeqCst);
+
+            Ok(Box::new(stream) as Box<dyn RuntimeSocket>)
+        })
+    }
+}
+
+// the runtime is installed once for the whole test binary, so it's a single test
+#[sqlx_macros::test]
+async fn it_runs_on_an_installed_runtime() -> anyhow::Result<()> {
+    let runtime = CountingRuntime::default();
+    let spawned = Arc::clone(&runtime.spawned);
+    let connected = Arc::clone(&runtime.connected);
+
+    sqlx::runtime::install(runtime)?;
+    assert!(sqlx::runtime::install(CountingRuntime::default()).is_err());
+
+    let mut conn = new::<Postgres>().await?;
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+
+    assert_eq!(value, 1);
+    assert_eq!(connected.load(Ordering::SeqCst), 1);
+
+    conn.close().await?;
+
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connect(&dotenv::var("DATABASE_URL")?)
+        .await?;
+
+    // the connection is returned to the pool by a task spawned on the runtime
+    pool.acquire().await?.execute("SELECT 1").await?;
+    pool.acquire().await?.execute("SELECT 1").await?;
+
+    assert_eq!(connected.load(Ordering::SeqCst), 2);
+    assert!(spawned.load(Ordering::SeqCst) >= 1);
+
+    pool.close().await;
+
+    Ok(())
+}
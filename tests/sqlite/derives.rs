@@ -12,3 +12,44 @@ test_type!(origin_enum<Origin>(Sqlite,
     "1" == Origin::Foo,
     "2" == Origin::Bar,
 ));
+
+#[sqlx_macros::test]
+async fn test_table_insert_update() -> anyhow::Result<()> {
+    use sqlx::Connection;
+    use sqlx_test::new;
+
+    #[derive(Debug, PartialEq, sqlx::FromRow, sqlx::Table)]
+    #[sqlx(table = "accounts")]
+    struct Account {
+        #[sqlx(primary_key)]
+        id: i64,
+        name: String,
+        is_active: Option<bool>,
+    }
+
+    let mut conn = new::<Sqlite>().await?;
+    let mut tx = conn.begin().await?;
+
+    let mut account = Account {
+        id: 1_000,
+        name: "Alice".into(),
+        is_active: None,
+    };
+
+    assert_eq!(1, account.insert(&mut tx).await?.rows_affected());
+
+    account.is_active = Some(true);
+
+    assert_eq!(1, account.update_by_pk(&mut tx).await?.rows_affected());
+
+    let saved: Account = sqlx::query_as("SELECT * FROM accounts WHERE id = ?")
+        .bind(account.id)
+        .fetch_one(&mut tx)
+        .await?;
+
+    assert_eq!(account, saved);
+
+    tx.rollback().await?;
+
+    Ok(())
+}
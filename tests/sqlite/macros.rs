@@ -1,4 +1,4 @@
-use sqlx::Sqlite;
+use sqlx::{Connection, Sqlite};
 use sqlx_test::new;
 
 #[sqlx_macros::test]
@@ -16,6 +16,26 @@ async fn macro_select() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn macro_query_batch() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+    let mut tx = conn.begin().await?;
+
+    let result = sqlx::query_batch!(
+        "insert into tweet (id, text) values (100, 'batch; one'), (101, 'batch; two');
+         update tweet set owner_id = 1 where id >= 100;
+         delete from tweet where id = 101;"
+    )
+    .execute(&mut tx)
+    .await?;
+
+    assert_eq!(result.rows_affected(), 5);
+
+    // let the transaction rollback so the tweets aren't actually inserted
+
+    Ok(())
+}
+
 macro_rules! gen_macro_select_concats {
     ($param:literal) => {
         #[sqlx_macros::test]
@@ -37,6 +57,36 @@ macro_rules! gen_macro_select_concats {
 
 gen_macro_select_concats!("id, name, is_active");
 
+const ACCOUNT_COLUMNS: &str = "id, name, is_active";
+
+mod queries {
+    pub const SELECT_ACCOUNTS: &'static str = concat!("select ", "id, name, is_active");
+}
+
+#[sqlx_macros::test]
+async fn macro_select_from_consts() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let account = sqlx::query!(
+        concat!("select ", ACCOUNT_COLUMNS, " from accounts where id = ?"),
+        1i32
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(1, account.id);
+    assert_eq!("Herp Derpinson", account.name);
+    assert_eq!(account.is_active, Some(true));
+
+    let account = sqlx::query!(concat!(queries::SELECT_ACCOUNTS, " from accounts"))
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(1, account.id);
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn macro_select_expression() -> anyhow::Result<()> {
     let mut conn = new::<Sqlite>().await?;
@@ -88,6 +138,49 @@ async fn macro_select_bind() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn macro_select_bind_in_list() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let ids = vec![3i32, 1, 2];
+
+    let accounts = sqlx::query!(
+        "select id, name from accounts where id in (?) and name <> ?",
+        {in: ids},
+        "Bob"
+    )
+    .fetch_all(&mut conn)
+    .await?;
+
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(1, accounts[0].id);
+
+    let ids: [i32; 0] = [];
+
+    let accounts = sqlx::query!("select id from accounts where id in (?)", {in: ids})
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert!(accounts.is_empty());
+
+    let count = sqlx::query_scalar!(
+        "select count(*) from accounts where id not in (?)",
+        {in: &[2i32, 3, 4, 5, 6][..]}
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(count, 1);
+
+    let count = sqlx::query_scalar!("select count(*) from accounts where id not in (?)", {in: ids})
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 1);
+
+    Ok(())
+}
+
 #[derive(Debug)]
 struct RawAccount {
     id: i64,
@@ -0,0 +1,777 @@
+use sqlx::migrate::{
+    BatchProgress, BatchedQuery, Migrate, MigrateError, Migration, MigrationFiles, MigrationHooks,
+    MigrationLock, MigrationType, Migrator, OutOfOrder, DEFAULT_TABLE_NAME,
+};
+use sqlx::{Connection, Executor, Sqlite, SqliteConnection};
+use std::borrow::Cow;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+async fn payload(conn: &mut SqliteConnection) -> anyhow::Result<Option<i64>> {
+    let exists: bool = sqlx::query_scalar(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE name = 'migrations_reversible_test'",
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if !exists {
+        return Ok(None);
+    }
+
+    let payload = sqlx::query_scalar("SELECT some_payload FROM migrations_reversible_test")
+        .fetch_one(&mut *conn)
+        .await?;
+
+    Ok(Some(payload))
+}
+
+#[sqlx_macros::test]
+async fn run_to_and_undo_to() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+    let migrator = Migrator::new(Path::new("tests/migrate/migrations_reversible")).await?;
+
+    migrator.run_to(&mut conn, 20220721124650).await?;
+    assert_eq!(payload(&mut conn).await?, Some(100));
+
+    migrator.run(&mut conn).await?;
+    assert_eq!(payload(&mut conn).await?, Some(101));
+
+    migrator.undo_to(&mut conn, 20220721124650).await?;
+    assert_eq!(payload(&mut conn).await?, Some(100));
+
+    migrator.undo_to(&mut conn, 0).await?;
+    assert_eq!(payload(&mut conn).await?, None);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn redo() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+    let migrator = Migrator::new(Path::new("tests/migrate/migrations_reversible")).await?;
+
+    // nothing to redo
+    migrator.redo(&mut conn).await?;
+    assert_eq!(payload(&mut conn).await?, None);
+
+    migrator.run(&mut conn).await?;
+    migrator.redo(&mut conn).await?;
+    assert_eq!(payload(&mut conn).await?, Some(101));
+
+    let simple = Migrator::new(Path::new("tests/migrate/migrations")).await?;
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+    simple.run(&mut conn).await?;
+
+    assert!(matches!(
+        simple.redo(&mut conn).await,
+        Err(MigrateError::NotReversible(20200723212841))
+    ));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn repair_and_allow_checksum_mismatch() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+    let mut migrator = Migrator::new(Path::new("tests/migrate/migrations_reversible")).await?;
+
+    migrator.run(&mut conn).await?;
+
+    // as if the migration file was edited after it was applied
+    sqlx::query("UPDATE _sqlx_migrations SET checksum = x'00' WHERE version = 20220721124650")
+        .execute(&mut conn)
+        .await?;
+
+    assert!(matches!(
+        migrator.run(&mut conn).await,
+        Err(MigrateError::VersionMismatch(20220721124650))
+    ));
+
+    migrator.set_allow_checksum_mismatch([20220721124650]);
+    migrator.run(&mut conn).await?;
+
+    migrator.set_allow_checksum_mismatch([]);
+    assert_eq!(migrator.repair(&mut conn).await?, [20220721124650]);
+    assert_eq!(migrator.repair(&mut conn).await?, Vec::<i64>::new());
+
+    migrator.run(&mut conn).await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn migration_headers() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    let migration = |version: i64, sql: &'static str| {
+        Migration::new(
+            version,
+            Cow::Borrowed("headers"),
+            MigrationType::Simple,
+            Cow::Borrowed(sql),
+        )
+    };
+
+    let migrator = Migrator {
+        migrations: Cow::Owned(vec![
+            migration(
+                1,
+                "-- sqlx:lock-timeout=250ms\nCREATE TABLE headers_test (id INTEGER);",
+            ),
+            // `VACUUM` cannot run in a transaction
+            migration(2, "-- sqlx:no-transaction\nVACUUM;"),
+        ]),
+        ignore_missing: false,
+        allow_checksum_mismatch: Cow::Borrowed(&[]),
+        out_of_order: OutOfOrder::ApplyMissing,
+        table_name: Cow::Borrowed(DEFAULT_TABLE_NAME),
+        schema_dump: None,
+        locking: true,
+        lock: MigrationLock::new(),
+        hooks: MigrationHooks::new(),
+    };
+
+    let busy_timeout: i64 = sqlx::query_scalar("PRAGMA busy_timeout")
+        .fetch_one(&mut conn)
+        .await?;
+
+    migrator.run(&mut conn).await?;
+
+    // the lock timeout only applies to the migration
+    assert_eq!(
+        sqlx::query_scalar::<_, i64>("PRAGMA busy_timeout")
+            .fetch_one(&mut conn)
+            .await?,
+        busy_timeout
+    );
+
+    assert!(matches!(
+        conn.apply(DEFAULT_TABLE_NAME, &migration(3, "-- sqlx:require-db=postgres\nSELECT 1"))
+            .await,
+        Err(MigrateError::RequiresDatabase(3, db)) if db == "postgres"
+    ));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn plan() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+    let migrator = Migrator::new(Path::new("tests/migrate/migrations_reversible")).await?;
+
+    let versions = |plan: Vec<&Migration>| plan.iter().map(|m| m.version).collect::<Vec<_>>();
+
+    assert_eq!(
+        versions(migrator.plan(&mut conn).await?),
+        [20220721124650, 20220721125033]
+    );
+
+    // nothing is applied
+    assert_eq!(payload(&mut conn).await?, None);
+
+    migrator.run_to(&mut conn, 20220721124650).await?;
+    assert_eq!(versions(migrator.plan(&mut conn).await?), [20220721125033]);
+
+    migrator.run(&mut conn).await?;
+    assert!(migrator.plan(&mut conn).await?.is_empty());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn hooks() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+    let mut migrator = Migrator::new(Path::new("tests/migrate/migrations_reversible")).await?;
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    let before = events.clone();
+    migrator.before_each(move |migration| {
+        before.lock().unwrap().push(format!(
+            "before {} {}",
+            migration.version,
+            migration.migration_type.label()
+        ));
+
+        Box::pin(async { Ok(()) })
+    });
+
+    let after = events.clone();
+    migrator.after_each(move |migration| {
+        assert!(migration.elapsed.is_some());
+        after
+            .lock()
+            .unwrap()
+            .push(format!("after {}", migration.version));
+
+        Box::pin(async { Ok(()) })
+    });
+
+    migrator.run_to(&mut conn, 20220721124650).await?;
+    migrator.undo_to(&mut conn, 0).await?;
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        [
+            "before 20220721124650 migrate",
+            "after 20220721124650",
+            "before 20220721124650 revert",
+            "after 20220721124650",
+        ]
+    );
+
+    // a failing hook stops the migration
+    migrator.before_each(|_| Box::pin(async { Err("workers are still running".into()) }));
+
+    assert!(matches!(
+        migrator.run(&mut conn).await,
+        Err(MigrateError::Hook(20220721124650, _))
+    ));
+    assert_eq!(payload(&mut conn).await?, None);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn rust_migrations() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+    let mut migrator = Migrator::new(Path::new("tests/migrate/migrations_reversible")).await?;
+
+    // between the two SQL migrations
+    migrator.add_migration(Migration::from_fn::<Sqlite, _>(
+        20220721125000,
+        Cow::Borrowed("double payload"),
+        MigrationType::ReversibleUp,
+        |conn| {
+            Box::pin(async move {
+                let payload: i64 =
+                    sqlx::query_scalar("SELECT some_payload FROM migrations_reversible_test")
+                        .fetch_one(&mut *conn)
+                        .await?;
+
+                sqlx::query("UPDATE migrations_reversible_test SET some_payload = ?")
+                    .bind(payload.checked_mul(2).ok_or("overflow")?)
+                    .execute(&mut *conn)
+                    .await?;
+
+                Ok(())
+            })
+        },
+    ))?;
+
+    assert!(matches!(
+        migrator.add_migration(Migration::from_fn::<Sqlite, _>(
+            20220721125000,
+            Cow::Borrowed("duplicate"),
+            MigrationType::ReversibleUp,
+            |_| Box::pin(async { Ok(()) }),
+        )),
+        Err(MigrateError::VersionCollision(20220721125000))
+    ));
+
+    migrator.run(&mut conn).await?;
+    assert_eq!(payload(&mut conn).await?, Some(201));
+
+    // a failing migration is rolled back
+    migrator.add_migration(Migration::from_fn::<Sqlite, _>(
+        20220721130000,
+        Cow::Borrowed("fail"),
+        MigrationType::ReversibleUp,
+        |conn| {
+            Box::pin(async move {
+                sqlx::query("UPDATE migrations_reversible_test SET some_payload = 0")
+                    .execute(&mut *conn)
+                    .await?;
+
+                Err("application logic failed".into())
+            })
+        },
+    ))?;
+
+    assert!(matches!(
+        migrator.run(&mut conn).await,
+        Err(MigrateError::Code(20220721130000, _))
+    ));
+    assert_eq!(payload(&mut conn).await?, Some(201));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn baseline() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+    let migrator = Migrator::new(Path::new("tests/migrate/migrations_reversible")).await?;
+
+    // an existing database whose schema matches the first migration
+    sqlx::query(
+        "CREATE TABLE migrations_reversible_test (some_id BIGINT NOT NULL PRIMARY KEY, some_payload BIGINT NOT NULL); \
+         INSERT INTO migrations_reversible_test (some_id, some_payload) VALUES (1, 100);",
+    )
+    .execute(&mut conn)
+    .await?;
+
+    assert_eq!(
+        migrator.baseline(&mut conn, 20220721124650).await?,
+        [20220721124650]
+    );
+    assert_eq!(
+        migrator.baseline(&mut conn, 20220721124650).await?,
+        Vec::<i64>::new()
+    );
+
+    migrator.run(&mut conn).await?;
+    assert_eq!(payload(&mut conn).await?, Some(101));
+
+    Ok(())
+}
+
+async fn squash_test_versions(conn: &mut SqliteConnection) -> anyhow::Result<Vec<i64>> {
+    // the schema is the same either way
+    sqlx::query("INSERT INTO squash_test (id, name, email) VALUES (1, 'a', 'b')")
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(
+        sqlx::query_scalar("SELECT version FROM _sqlx_migrations ORDER BY version")
+            .fetch_all(&mut *conn)
+            .await?,
+    )
+}
+
+#[sqlx_macros::test]
+async fn squash() -> anyhow::Result<()> {
+    let migration = |version: i64, sql: &'static str| {
+        Migration::new(
+            version,
+            Cow::Borrowed("squash"),
+            MigrationType::Simple,
+            Cow::Borrowed(sql),
+        )
+    };
+
+    let migrator = |migrations: Vec<Migration>| Migrator {
+        migrations: Cow::Owned(migrations),
+        ignore_missing: false,
+        allow_checksum_mismatch: Cow::Borrowed(&[]),
+        out_of_order: OutOfOrder::ApplyMissing,
+        table_name: Cow::Borrowed(DEFAULT_TABLE_NAME),
+        schema_dump: None,
+        locking: true,
+        lock: MigrationLock::new(),
+        hooks: MigrationHooks::new(),
+    };
+
+    let history = migrator(vec![
+        migration(1, "CREATE TABLE squash_test (id INTEGER);"),
+        migration(2, "ALTER TABLE squash_test ADD COLUMN name TEXT;"),
+        migration(3, "ALTER TABLE squash_test ADD COLUMN email TEXT;"),
+    ]);
+
+    let squashed = migrator(vec![
+        migration(
+            2,
+            "-- sqlx:squash\nCREATE TABLE squash_test (id INTEGER, name TEXT);",
+        ),
+        migration(3, "ALTER TABLE squash_test ADD COLUMN email TEXT;"),
+    ]);
+
+    // a database which applied the old history
+    let mut old = SqliteConnection::connect("sqlite::memory:").await?;
+    history.run_to(&mut old, 2).await?;
+    squashed.run(&mut old).await?;
+
+    // a new database
+    let mut new = SqliteConnection::connect("sqlite::memory:").await?;
+    squashed.run(&mut new).await?;
+
+    assert_eq!(squash_test_versions(&mut old).await?, [1, 2, 3]);
+    assert_eq!(squash_test_versions(&mut new).await?, [2, 3]);
+
+    // a database which applied only part of the squashed migrations
+    let mut partial = SqliteConnection::connect("sqlite::memory:").await?;
+    history.run_to(&mut partial, 1).await?;
+
+    assert!(matches!(
+        squashed.run(&mut partial).await,
+        Err(MigrateError::PartiallySquashed(2))
+    ));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn out_of_order() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    let migration = |version: i64| {
+        Migration::new(
+            version,
+            Cow::Borrowed("out of order"),
+            MigrationType::Simple,
+            Cow::Owned(format!(
+                "CREATE TABLE out_of_order_{} (id INTEGER);",
+                version
+            )),
+        )
+    };
+
+    let migrator = |versions: &[i64]| Migrator {
+        migrations: Cow::Owned(versions.iter().copied().map(migration).collect()),
+        ignore_missing: false,
+        allow_checksum_mismatch: Cow::Borrowed(&[]),
+        out_of_order: OutOfOrder::Error,
+        table_name: Cow::Borrowed(DEFAULT_TABLE_NAME),
+        schema_dump: None,
+        locking: true,
+        lock: MigrationLock::new(),
+        hooks: MigrationHooks::new(),
+    };
+
+    migrator(&[1, 3]).run(&mut conn).await?;
+
+    // migration 2 was merged after migration 3 was applied
+    let mut merged = migrator(&[1, 2, 3]);
+
+    assert!(matches!(
+        merged.run(&mut conn).await,
+        Err(MigrateError::OutOfOrder(2))
+    ));
+
+    merged.set_out_of_order(OutOfOrder::Warn);
+    merged.run(&mut conn).await?;
+    assert_eq!(merged.plan(&mut conn).await?.len(), 0);
+
+    merged.set_out_of_order(OutOfOrder::ApplyMissing);
+    merged.run(&mut conn).await?;
+    sqlx::query("SELECT * FROM out_of_order_2")
+        .execute(&mut conn)
+        .await?;
+
+    assert_eq!("apply-missing".parse(), Ok(OutOfOrder::ApplyMissing));
+    assert!("ignore".parse::<OutOfOrder>().is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn custom_table_name() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    let mut reversible = Migrator::new(Path::new("tests/migrate/migrations_reversible")).await?;
+    reversible.set_table_name("main._reversible_migrations");
+
+    let mut simple = Migrator::new(Path::new("tests/migrate/migrations")).await?;
+    simple.set_table_name("_simple_migrations");
+
+    // two services sharing the database don't see each other's migrations as missing
+    reversible.run(&mut conn).await?;
+    simple.run(&mut conn).await?;
+    reversible.run(&mut conn).await?;
+
+    assert_eq!(payload(&mut conn).await?, Some(101));
+
+    let count = |table: &str| format!("SELECT COUNT(*) FROM {}", table);
+
+    assert_eq!(
+        sqlx::query_scalar::<_, i64>(&count("_reversible_migrations"))
+            .fetch_one(&mut conn)
+            .await?,
+        2
+    );
+    assert_eq!(
+        sqlx::query_scalar::<_, i64>(&count("_simple_migrations"))
+            .fetch_one(&mut conn)
+            .await?,
+        simple.iter().count() as i64
+    );
+    assert_eq!(
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM sqlite_master WHERE name = '_sqlx_migrations'"
+        )
+        .fetch_one(&mut conn)
+        .await?,
+        0
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn migration_files() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    let migrator = Migrator::new(MigrationFiles::new([
+        (
+            "migrations/20220721125033_modify_column.up.sql",
+            "UPDATE migrations_reversible_test SET some_payload = some_payload + 1;",
+        ),
+        (
+            "migrations/20220721124650_add_table.up.sql",
+            "CREATE TABLE migrations_reversible_test (some_id BIGINT NOT NULL PRIMARY KEY, some_payload BIGINT NOT NULL); \
+             INSERT INTO migrations_reversible_test (some_id, some_payload) VALUES (1, 100);",
+        ),
+        ("migrations/README.md", "not a migration"),
+    ]))
+    .await?;
+
+    assert_eq!(
+        migrator.iter().map(|m| m.version).collect::<Vec<_>>(),
+        [20220721124650, 20220721125033]
+    );
+
+    migrator.run(&mut conn).await?;
+    assert_eq!(payload(&mut conn).await?, Some(101));
+
+    Ok(())
+}
+
+// the up and down migrations of a version may come in any order
+#[cfg(any(feature = "migrate-tar", feature = "migrate-zip"))]
+fn checksums(migrator: &Migrator) -> Vec<&[u8]> {
+    let mut checksums: Vec<_> = migrator.iter().map(|m| &*m.checksum).collect();
+    checksums.sort();
+    checksums
+}
+
+#[cfg(feature = "migrate-tar")]
+#[sqlx_macros::test]
+async fn tar_archive() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+    let directory = Migrator::new(Path::new("tests/migrate/migrations_reversible")).await?;
+    let migrator = Migrator::new(sqlx::migrate::TarArchive::open(
+        "tests/migrate/migrations_reversible.tar.gz",
+    ))
+    .await?;
+
+    // the same migrations as the directory
+    assert_eq!(checksums(&migrator), checksums(&directory));
+
+    migrator.run(&mut conn).await?;
+    assert_eq!(payload(&mut conn).await?, Some(101));
+
+    Ok(())
+}
+
+#[cfg(feature = "migrate-zip")]
+#[sqlx_macros::test]
+async fn zip_archive() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+    let directory = Migrator::new(Path::new("tests/migrate/migrations_reversible")).await?;
+    let bytes = std::fs::read("tests/migrate/migrations_reversible.zip")?;
+    let migrator = Migrator::new(sqlx::migrate::ZipArchive::from_bytes(bytes)).await?;
+
+    assert_eq!(checksums(&migrator), checksums(&directory));
+
+    migrator.run(&mut conn).await?;
+    assert_eq!(payload(&mut conn).await?, Some(101));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn schema_dump() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+    let mut migrator = Migrator::new(Path::new("tests/migrate/migrations_reversible")).await?;
+
+    let path = std::env::temp_dir().join(format!("sqlx-schema-dump-{}.sql", std::process::id()));
+    migrator.set_schema_dump(&path);
+
+    migrator.run(&mut conn).await?;
+
+    let schema = std::fs::read_to_string(&path)?;
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(schema, migrator.dump_schema(&mut conn).await?);
+    assert!(schema.starts_with("CREATE TABLE migrations_reversible_test ("));
+    assert!(!schema.contains("_sqlx_migrations"));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn locking() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+    let mut migrator = Migrator::new(Path::new("tests/migrate/migrations_reversible")).await?;
+    migrator.set_lock_timeout(std::time::Duration::from_millis(200));
+
+    // held by another migrator
+    conn.lock(MigrationLock::new()).await?;
+
+    assert!(matches!(
+        migrator.run(&mut conn).await,
+        Err(MigrateError::LockTimeout)
+    ));
+
+    // unless it uses another key
+    let mut other = Migrator::new(MigrationFiles::new([(
+        "1_other.sql",
+        "CREATE TABLE other (id INTEGER);",
+    )]))
+    .await?;
+    other.set_table_name("_other_migrations");
+    other.set_lock_key(1);
+    other.run(&mut conn).await?;
+
+    conn.unlock(MigrationLock::new()).await?;
+    migrator.run(&mut conn).await?;
+
+    // the lock is released even if a migration fails
+    let failing = Migrator::new(MigrationFiles::new([(
+        "20220801000000_failing.up.sql",
+        "SELECT * FROM missing_table;",
+    )]))
+    .await?;
+    assert!(failing.run(&mut conn).await.is_err());
+
+    let locks: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM _sqlx_migrations_lock")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(locks, 0);
+
+    // or not taken at all
+    conn.lock(MigrationLock::new()).await?;
+    migrator.set_locking(false);
+    migrator.run(&mut conn).await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn batched_query() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    conn.execute("CREATE TABLE batched (id INTEGER PRIMARY KEY, value INTEGER NOT NULL)")
+        .await?;
+
+    for id in 1..=25 {
+        sqlx::query("INSERT INTO batched (id, value) VALUES (?, ?)")
+            .bind(id * 2)
+            .bind(id)
+            .execute(&mut conn)
+            .await?;
+    }
+
+    let mut progress = Vec::new();
+
+    let rows = BatchedQuery::new(
+        "batched",
+        "id",
+        "UPDATE batched SET value = value * 10 WHERE id BETWEEN {start} AND {end}",
+    )
+    .batch_size(10)
+    .on_progress(|p: &BatchProgress<i64>| progress.push((p.batches, p.rows, p.key, p.max_key)))
+    .execute(&mut conn)
+    .await?;
+
+    assert_eq!(rows, 25);
+    assert_eq!(
+        progress,
+        [(1, 10, 20, 50), (2, 20, 40, 50), (3, 25, 50, 50)]
+    );
+
+    let sum: i64 = sqlx::query_scalar("SELECT SUM(value) FROM batched")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(sum, (1..=25).sum::<i64>() * 10);
+
+    // in a migration, committing each batch
+    let migration = Migration::from_fn::<Sqlite, _>(
+        1,
+        Cow::Borrowed("delete even values"),
+        MigrationType::Simple,
+        |conn| {
+            Box::pin(async move {
+                BatchedQuery::<i64>::new(
+                    "batched",
+                    "id",
+                    "DELETE FROM batched WHERE id BETWEEN {start} AND {end} AND value % 20 = 0",
+                )
+                .batch_size(7)
+                .execute(&mut *conn)
+                .await?;
+
+                Ok(())
+            })
+        },
+    )
+    .no_transaction();
+
+    assert!(migration.headers()?.no_transaction);
+
+    let mut migrator = Migrator::new(MigrationFiles::new(Vec::<(&str, &str)>::new())).await?;
+    migrator.add_migration(migration)?;
+    migrator.run(&mut conn).await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM batched")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, 13);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn resumable_migrations() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    let migrator = |sql: &'static str| Migrator::new(MigrationFiles::new([("1_resume.sql", sql)]));
+
+    let failing = migrator(
+        "-- sqlx:resumable\n\
+         CREATE TABLE resumed (id INTEGER);\n\
+         INSERT INTO resumed VALUES (1);\n\
+         INSERT INTO missing_table VALUES (2);\n\
+         INSERT INTO resumed VALUES (3);",
+    )
+    .await?;
+
+    assert!(failing.run(&mut conn).await.is_err());
+    assert_eq!(conn.dirty_version(DEFAULT_TABLE_NAME).await?, Some(1));
+
+    let applied: i64 = sqlx::query_scalar("SELECT statements_applied FROM _sqlx_migrations")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(applied, 2);
+
+    // once fixed, the migration is resumed from the statement that failed
+    let fixed = migrator(
+        "-- sqlx:resumable\n\
+         CREATE TABLE resumed (id INTEGER);\n\
+         INSERT INTO resumed VALUES (1);\n\
+         INSERT INTO resumed VALUES (2);\n\
+         INSERT INTO resumed VALUES (3);",
+    )
+    .await?;
+
+    let plan: Vec<_> = fixed
+        .plan(&mut conn)
+        .await?
+        .iter()
+        .map(|m| m.version)
+        .collect();
+    assert_eq!(plan, [1]);
+
+    fixed.run(&mut conn).await?;
+
+    let ids: Vec<i64> = sqlx::query_scalar("SELECT id FROM resumed ORDER BY id")
+        .fetch_all(&mut conn)
+        .await?;
+    assert_eq!(ids, [1, 2, 3]);
+
+    assert_eq!(conn.dirty_version(DEFAULT_TABLE_NAME).await?, None);
+    fixed.run(&mut conn).await?;
+
+    // a migration which can't be resumed stays dirty
+    conn.execute("UPDATE _sqlx_migrations SET success = FALSE")
+        .await?;
+
+    assert!(matches!(
+        migrator("CREATE TABLE resumed (id INTEGER);")
+            .await?
+            .run(&mut conn)
+            .await,
+        Err(MigrateError::Dirty(1))
+    ));
+
+    Ok(())
+}
@@ -13,6 +13,11 @@ async fn it_connects() -> anyhow::Result<()> {
     Ok(new::<Sqlite>().await?.ping().await?)
 }
 
+#[sqlx_macros::test(skip_if_no_db)]
+async fn it_connects_or_skips_without_database_url() -> anyhow::Result<()> {
+    Ok(new::<Sqlite>().await?.ping().await?)
+}
+
 #[sqlx_macros::test]
 async fn it_fetches_and_inflates_row() -> anyhow::Result<()> {
     let mut conn = new::<Sqlite>().await?;
@@ -719,3 +724,687 @@ async fn concurrent_read_and_write() {
         write.await;
     }
 }
+
+#[sqlx_macros::test]
+async fn it_fails_to_load_a_missing_extension() -> anyhow::Result<()> {
+    let res = SqliteConnectOptions::new()
+        .filename(":memory:")
+        .extension("sqlx_no_such_extension")
+        .connect()
+        .await;
+
+    let err = res.unwrap_err();
+    let err = err.into_database_error().unwrap();
+
+    assert!(err.message().contains("sqlx_no_such_extension"));
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct Product(Option<i64>);
+
+impl sqlx::sqlite::SqliteAggregate for Product {
+    type Output = Option<i64>;
+
+    fn step(&mut self, args: &[sqlx::sqlite::SqliteValue]) -> Result<(), sqlx::error::BoxDynError> {
+        use sqlx::Value;
+
+        let value: i64 = args[0].try_decode()?;
+
+        if value < 0 {
+            return Err("negative value".into());
+        }
+
+        self.0 = Some(self.0.unwrap_or(1) * value);
+
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Option<i64>, sqlx::error::BoxDynError> {
+        Ok(self.0)
+    }
+}
+
+#[sqlx_macros::test]
+async fn it_calls_rust_functions() -> anyhow::Result<()> {
+    use sqlx::Value;
+
+    let mut conn = SqliteConnectOptions::new()
+        .filename(":memory:")
+        .scalar_function("regexp", 2, |args| {
+            let prefix = args[0].try_decode::<String>()?;
+            let text = args[1].try_decode::<Option<String>>()?;
+
+            Ok(text.map(|text| text.starts_with(&prefix)))
+        })
+        .aggregate_function("product", 1, Product::default)
+        .connect()
+        .await?;
+
+    conn.create_scalar_function("concat_all", -1, |args| {
+        let mut s = String::new();
+
+        for arg in args {
+            s += &arg.try_decode::<String>()?;
+        }
+
+        Ok(s)
+    })
+    .await?;
+
+    conn.execute("CREATE TABLE t (g INTEGER, v INTEGER, s TEXT)")
+        .await?;
+    conn.execute(
+        "INSERT INTO t VALUES (1, 2, 'sqlx'), (1, 3, 'sqlite'), (2, 5, 'rust'), (3, -1, NULL)",
+    )
+    .await?;
+
+    let rows: Vec<(i64, bool)> =
+        sqlx::query_as("SELECT v, s REGEXP 'sql' FROM t WHERE s IS NOT NULL ORDER BY v")
+            .fetch_all(&mut conn)
+            .await?;
+
+    assert_eq!(rows, vec![(2, true), (3, true), (5, false)]);
+
+    let nulls: (Option<bool>,) = sqlx::query_as("SELECT regexp('x', NULL)")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(nulls, (None,));
+
+    let products: Vec<(i64, i64)> =
+        sqlx::query_as("SELECT g, product(v) FROM t WHERE g < 3 GROUP BY g ORDER BY g")
+            .fetch_all(&mut conn)
+            .await?;
+
+    assert_eq!(products, vec![(1, 6), (2, 5)]);
+
+    // an empty group
+    let empty: (Option<i64>,) = sqlx::query_as("SELECT product(v) FROM t WHERE g > 3")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(empty, (None,));
+
+    let concat: (String,) = sqlx::query_as("SELECT concat_all('a', 'b', 'c')")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(concat.0, "abc");
+
+    let err = sqlx::query("SELECT product(v) FROM t")
+        .execute(&mut conn)
+        .await
+        .unwrap_err();
+
+    assert!(err.to_string().contains("negative value"));
+
+    let err = sqlx::query("SELECT concat_all(1, x'ff')")
+        .execute(&mut conn)
+        .await
+        .unwrap_err();
+
+    assert!(err.into_database_error().is_some());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_opens_a_schema_with_custom_collations() -> anyhow::Result<()> {
+    let filename = std::env::temp_dir().join("sqlx-custom-collations.db");
+    let _ = std::fs::remove_file(&filename);
+
+    let options = SqliteConnectOptions::new()
+        .filename(&filename)
+        .collation("reverse", |a, b| b.cmp(a));
+
+    let mut conn = options.clone().create_if_missing(true).connect().await?;
+
+    conn.execute(
+        r#"
+CREATE TABLE words (word TEXT NOT NULL COLLATE reverse);
+CREATE INDEX words_word ON words (word);
+INSERT INTO words VALUES ('a'), ('c'), ('b');
+    "#,
+    )
+    .await?;
+
+    conn.close().await?;
+
+    let mut conn = options.clone().connect().await?;
+
+    let words: Vec<String> = sqlx::query_scalar("SELECT word FROM words ORDER BY word")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(words, ["c", "b", "a"]);
+
+    conn.close().await?;
+
+    // the macros don't know the collations of the application
+    let describe = sqlx::sqlite::describe_blocking(
+        &SqliteConnectOptions::new().filename(&filename),
+        "SELECT word FROM words WHERE word > ?1 ORDER BY word",
+    )?;
+
+    assert_eq!(describe.columns()[0].name(), "word");
+
+    std::fs::remove_file(&filename)?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_backs_up_a_database() -> anyhow::Result<()> {
+    let filename = std::env::temp_dir().join("sqlx-backup.db");
+    let _ = std::fs::remove_file(&filename);
+
+    let mut conn = SqliteConnectOptions::new()
+        .filename(":memory:")
+        .page_size(1024)
+        .connect()
+        .await?;
+
+    conn.execute("CREATE TABLE t (id INTEGER PRIMARY KEY, data BLOB)")
+        .await?;
+
+    for _ in 0..20 {
+        conn.execute("INSERT INTO t (data) VALUES (randomblob(1000))")
+            .await?;
+    }
+
+    // to a file, a few pages at a time
+    let mut steps = Vec::new();
+
+    conn.backup_to(&*filename)
+        .await?
+        .run(5, std::time::Duration::ZERO, |progress| {
+            steps.push(progress)
+        })
+        .await?;
+
+    assert!(steps.len() > 1);
+    assert_eq!(steps.last().unwrap().remaining(), 0);
+    assert!(steps.iter().all(|step| step.page_count() > 20));
+
+    let mut copy = SqliteConnectOptions::new()
+        .filename(&filename)
+        .connect()
+        .await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM t")
+        .fetch_one(&mut copy)
+        .await?;
+
+    assert_eq!(count, 20);
+
+    // to another connection, stepping it manually
+    let mut other = SqliteConnectOptions::new()
+        .filename(":memory:")
+        .connect()
+        .await?;
+
+    let mut backup = copy.backup_to(&mut other).await?;
+
+    while !backup.step(-1)? {}
+
+    backup.finish()?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM t")
+        .fetch_one(&mut other)
+        .await?;
+
+    assert_eq!(count, 20);
+
+    copy.close().await?;
+    std::fs::remove_file(&filename)?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_listens_for_committed_changes() -> anyhow::Result<()> {
+    use sqlx::sqlite::{SqliteChangeListener, SqliteOperation};
+
+    let mut listener = SqliteChangeListener::new();
+
+    let mut conn = SqliteConnectOptions::new()
+        .filename(":memory:")
+        .change_listener(&listener)
+        .connect()
+        .await?;
+
+    conn.execute("CREATE TABLE t (x INTEGER)").await?;
+    conn.execute("INSERT INTO t (x) VALUES (1), (2)").await?;
+    conn.execute("UPDATE t SET x = 3 WHERE x = 2").await?;
+    conn.execute("DELETE FROM t WHERE x = 1").await?;
+
+    // not reported
+    let mut tx = conn.begin().await?;
+    tx.execute("INSERT INTO t (x) VALUES (4)").await?;
+    tx.rollback().await?;
+
+    let mut tx = conn.begin().await?;
+    tx.execute("INSERT INTO t (x) VALUES (5)").await?;
+    tx.commit().await?;
+
+    let mut changes = Vec::new();
+
+    while let Some(change) = listener.try_recv() {
+        assert_eq!(change.database(), "main");
+        assert_eq!(change.table(), "t");
+
+        changes.push((change.operation(), change.rowid()));
+    }
+
+    assert_eq!(
+        changes,
+        [
+            (SqliteOperation::Insert, 1),
+            (SqliteOperation::Insert, 2),
+            (SqliteOperation::Update, 2),
+            (SqliteOperation::Delete, 1),
+            (SqliteOperation::Insert, 3),
+        ]
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_checkpoints_the_wal() -> anyhow::Result<()> {
+    use sqlx::sqlite::{SqliteCheckpointMode, SqliteJournalMode};
+
+    let filename = std::env::temp_dir().join("sqlx-wal-checkpoint.db");
+    let _ = std::fs::remove_file(&filename);
+
+    let mut conn = SqliteConnectOptions::new()
+        .filename(&filename)
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .wal_autocheckpoint(0)
+        .journal_size_limit(0)
+        .connect()
+        .await?;
+
+    let pages: i64 = sqlx::query_scalar("PRAGMA wal_autocheckpoint")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(pages, 0);
+
+    conn.execute("CREATE TABLE t (x INTEGER)").await?;
+    conn.execute("INSERT INTO t (x) VALUES (1), (2), (3)")
+        .await?;
+
+    let checkpoint = conn.wal_checkpoint(SqliteCheckpointMode::Passive).await?;
+
+    assert!(!checkpoint.is_busy());
+    assert!(checkpoint.log_frames().unwrap() > 0);
+    assert_eq!(checkpoint.checkpointed_frames(), checkpoint.log_frames());
+
+    let checkpoint = conn.wal_checkpoint(SqliteCheckpointMode::Truncate).await?;
+
+    assert!(!checkpoint.is_busy());
+    assert_eq!(checkpoint.log_frames(), Some(0));
+
+    conn.close().await?;
+    std::fs::remove_file(&filename)?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_calls_the_busy_handler() -> anyhow::Result<()> {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    let filename = std::env::temp_dir().join("sqlx-busy-handler.db");
+    let _ = std::fs::remove_file(&filename);
+
+    let options = SqliteConnectOptions::new()
+        .filename(&filename)
+        .create_if_missing(true);
+
+    let mut locker = options.clone().connect().await?;
+
+    locker.execute("CREATE TABLE t (x INTEGER)").await?;
+    locker.execute("BEGIN IMMEDIATE").await?;
+
+    let calls = Arc::new(AtomicU32::new(0));
+    let handler_calls = Arc::clone(&calls);
+
+    let mut conn = options
+        .busy_handler(move |count| {
+            handler_calls.fetch_add(1, Ordering::SeqCst);
+            count < 2
+        })
+        .connect()
+        .await?;
+
+    let err = conn
+        .execute("INSERT INTO t (x) VALUES (1)")
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        err.into_database_error().unwrap().code().as_deref(),
+        Some("5")
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+    locker.execute("COMMIT").await?;
+    conn.execute("INSERT INTO t (x) VALUES (1)").await?;
+
+    conn.close().await?;
+    locker.close().await?;
+    std::fs::remove_file(&filename)?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_shares_an_in_memory_database_with_a_pool() -> anyhow::Result<()> {
+    let pool = SqlitePoolOptions::new()
+        .min_connections(2)
+        .max_connections(2)
+        .connect_with(SqliteConnectOptions::new().filename(":memory:"))
+        .await?;
+
+    let mut a = pool.acquire().await?;
+    let mut b = pool.acquire().await?;
+
+    a.execute("CREATE TABLE t (x INTEGER); INSERT INTO t (x) VALUES (1)")
+        .await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM t")
+        .fetch_one(&mut b)
+        .await?;
+
+    assert_eq!(count, 1);
+
+    // the database outlives the connections which used it
+    let options = SqliteConnectOptions::new().shared_memory("sqlx-outlives-connections");
+
+    let mut conn = options.connect().await?;
+    conn.execute("CREATE TABLE t (x INTEGER)").await?;
+    conn.close().await?;
+
+    let mut conn = options.clone().connect().await?;
+    conn.execute("SELECT * FROM t").await?;
+    conn.close().await?;
+
+    // but isn't shared with other options
+    let mut other = SqliteConnectOptions::new()
+        .filename(":memory:")
+        .connect()
+        .await?;
+
+    assert!(other.execute("SELECT * FROM t").await.is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reconnects_with_the_session_setup() -> anyhow::Result<()> {
+    use sqlx::ReconnectingConnection;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let connects = Arc::new(AtomicU32::new(0));
+    let counter = Arc::clone(&connects);
+
+    let mut conn =
+        ReconnectingConnection::<Sqlite>::new(SqliteConnectOptions::new().filename(":memory:"))
+            .ping_after_idle(Duration::ZERO)
+            .after_connect(move |conn| {
+                counter.fetch_add(1, Ordering::SeqCst);
+
+                Box::pin(async move {
+                    conn.execute("CREATE TEMP TABLE session (x INTEGER)")
+                        .await?;
+
+                    Ok(())
+                })
+            });
+
+    assert!(!conn.is_connected());
+
+    conn.execute("INSERT INTO session (x) VALUES (1)").await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM session")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 1);
+    assert_eq!(connects.load(Ordering::SeqCst), 1);
+
+    // an error from the database doesn't mean the connection was lost
+    assert!(conn.execute("SELECT * FROM missing").await.is_err());
+    assert!(conn.is_connected());
+
+    // a new connection gets a new session
+    conn.reset();
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM session")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 0);
+    assert_eq!(connects.load(Ordering::SeqCst), 2);
+
+    let mut tx = conn.begin().await?;
+    tx.execute("INSERT INTO session (x) VALUES (2)").await?;
+    tx.commit().await?;
+
+    let mut rows = conn.fetch("SELECT x FROM session");
+    assert!(rows.try_next().await?.is_some());
+    drop(rows);
+
+    conn.close().await?;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reports_the_server_version() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let version: String = sqlx::query_scalar("SELECT sqlite_version()")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(conn.server_version().unwrap().to_string(), version);
+
+    // the bundled SQLite is recent enough
+    let capabilities = conn.server_capabilities();
+    assert!(capabilities.returning());
+    assert!(capabilities.common_table_expressions());
+    assert!(!capabilities.multiranges());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_borrows_values_from_the_row() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let row = conn
+        .fetch_one("SELECT 'hello' AS text, x'0102' AS blob")
+        .await?;
+
+    assert_eq!(row.get_borrowed::<str, _>("text"), "hello");
+    assert_eq!(row.try_get_borrowed::<[u8], _>(1)?, &[1u8, 2][..]);
+    assert!(row.try_get_borrowed::<str, _>("missing").is_err());
+
+    let mut lens = Vec::new();
+
+    sqlx::query("SELECT text FROM tweet ORDER BY id")
+        .fetch_for_each(&mut conn, |row| {
+            lens.push(row.try_get_borrowed::<str, _>(0)?.len());
+            Ok(())
+        })
+        .await?;
+
+    let expected: Vec<String> = sqlx::query_scalar("SELECT text FROM tweet ORDER BY id")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(lens, expected.iter().map(String::len).collect::<Vec<_>>());
+
+    Ok(())
+}
+
+#[cfg(feature = "json")]
+#[sqlx_macros::test]
+async fn it_serializes_rows() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE flags (id INTEGER PRIMARY KEY, name TEXT, enabled BOOLEAN, weight REAL, data BLOB)")
+        .await?;
+    conn.execute(
+        "INSERT INTO flags VALUES (1, 'dark mode', 1, 0.5, x'0102'), (2, NULL, 0, 1, NULL)",
+    )
+    .await?;
+
+    let rows = sqlx::query("SELECT * FROM flags ORDER BY id")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(
+        serde_json::to_value(&rows)?,
+        serde_json::json!([
+            { "id": 1, "name": "dark mode", "enabled": true, "weight": 0.5, "data": [1, 2] },
+            { "id": 2, "name": null, "enabled": false, "weight": 1.0, "data": null },
+        ])
+    );
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_maps_error_kinds() -> anyhow::Result<()> {
+    use sqlx::error::ErrorKind;
+
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    conn.execute(
+        r#"
+CREATE TABLE parents (id INTEGER PRIMARY KEY, email TEXT UNIQUE);
+CREATE TABLE children (
+    id INTEGER PRIMARY KEY,
+    parent_id INTEGER NOT NULL REFERENCES parents (id),
+    age INTEGER CONSTRAINT age_positive CHECK (age > 0)
+);
+INSERT INTO parents VALUES (1, 'a@b.c');
+        "#,
+    )
+    .await?;
+
+    let cases = [
+        (
+            "INSERT INTO parents VALUES (2, 'a@b.c')",
+            ErrorKind::UniqueViolation,
+            None,
+            Some("parents"),
+            Some("email"),
+        ),
+        (
+            "INSERT INTO children VALUES (1, 2, 1)",
+            ErrorKind::ForeignKeyViolation,
+            None,
+            None,
+            None,
+        ),
+        (
+            "INSERT INTO children VALUES (1, NULL, 1)",
+            ErrorKind::NotNullViolation,
+            None,
+            Some("children"),
+            Some("parent_id"),
+        ),
+        (
+            "INSERT INTO children VALUES (1, 1, 0)",
+            ErrorKind::CheckViolation,
+            Some("age_positive"),
+            None,
+            None,
+        ),
+    ];
+
+    for (sql, kind, constraint, table, column) in cases {
+        let err = conn.execute(sql).await.unwrap_err();
+        let err = err.as_database_error().unwrap();
+
+        assert_eq!(err.kind(), kind, "{}", sql);
+        assert_eq!(err.constraint(), constraint, "{}", sql);
+        assert_eq!(err.table(), table, "{}", sql);
+        assert_eq!(err.column(), column, "{}", sql);
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_begins_transactions_with_options() -> anyhow::Result<()> {
+    use sqlx::{IsolationLevel, TransactionOptions};
+
+    let mut conn = new::<Sqlite>().await?;
+
+    let mut tx = conn
+        .begin_with(
+            TransactionOptions::new()
+                .isolation(IsolationLevel::Serializable)
+                .read_only(false),
+        )
+        .await?;
+
+    let _: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut tx).await?;
+
+    tx.rollback().await?;
+
+    assert!(conn
+        .begin_with(TransactionOptions::new().read_only(true))
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_work_with_named_savepoints() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+    conn.execute("CREATE TABLE saved (id INTEGER NOT NULL)")
+        .await?;
+
+    let mut tx = conn.begin().await?;
+
+    tx.execute("INSERT INTO saved VALUES (1)").await?;
+
+    let mut savepoint = tx.savepoint("import").await?;
+
+    savepoint.execute("INSERT INTO saved VALUES (2)").await?;
+    savepoint.rollback_to().await?;
+    savepoint.execute("INSERT INTO saved VALUES (3)").await?;
+    savepoint.release().await?;
+
+    {
+        let mut savepoint = tx.savepoint("dropped").await?;
+
+        savepoint.execute("INSERT INTO saved VALUES (4)").await?;
+    }
+
+    tx.execute("INSERT INTO saved VALUES (5)").await?;
+    tx.commit().await?;
+
+    let saved: Vec<i32> = sqlx::query_scalar("SELECT id FROM saved ORDER BY id")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(saved, vec![1, 3, 5]);
+
+    Ok(())
+}